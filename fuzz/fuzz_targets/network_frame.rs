@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shinkai_message_primitives::schemas::shinkai_network::{
+    NetworkFrame, NetworkFrameLengths, MAX_NETWORK_FRAME_LENGTH, MAX_NETWORK_IDENTITY_LENGTH,
+};
+
+// Frame headers come straight from the TCP socket in Node::listen and in the relayer
+fuzz_target!(|data: &[u8]| {
+    if let Ok(frame) = NetworkFrame::parse(data) {
+        assert!(frame.identity.len() <= MAX_NETWORK_IDENTITY_LENGTH);
+        assert!(8 + frame.identity.len() + 1 + frame.payload.len() <= data.len());
+    }
+    if data.len() >= 8 {
+        let prefix = |start: usize| -> [u8; 4] { data[start..start + 4].try_into().unwrap() };
+        if let Ok(lengths) = NetworkFrameLengths::parse(prefix(0), prefix(4)) {
+            assert!(lengths.identity_length + lengths.payload_length <= MAX_NETWORK_FRAME_LENGTH);
+        }
+    }
+});
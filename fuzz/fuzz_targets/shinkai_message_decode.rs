@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
+
+// Bytes received from peers are decoded as is, so any input must give a message or an error
+fuzz_target!(|data: &[u8]| {
+    if let Ok(message) = ShinkaiMessage::decode_message_result(data.to_vec()) {
+        // A decoded message encodes back into one that decodes to the same encoding
+        let encoded = message.encode_message().expect("decoded message failed to encode");
+        let decoded = ShinkaiMessage::decode_message_result(encoded.clone()).expect("encoded message failed to decode");
+        assert_eq!(decoded.encode_message().unwrap(), encoded);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shinkai_vector_resources::vector_resource::VRPack;
+
+// VRPacks are uploaded through the API and received from other nodes
+fuzz_target!(|data: &[u8]| {
+    if let Ok(vrpack) = VRPack::from_bytes(data) {
+        let _ = vrpack.unpack_all_vrkais();
+    }
+});
@@ -14,12 +14,14 @@ export STARTING_NUM_QR_PROFILES="1"
 export STARTING_NUM_QR_DEVICES="1"
 export FIRST_DEVICE_NEEDS_REGISTRATION_CODE="false"
 export LOG_SIMPLE="true"
+# export LOG_FORMAT="json"
 export NO_SECRET_FILE="true"
 export EMBEDDINGS_SERVER_URL="http://localhost:11434"
 export UNSTRUCTURED_SERVER_URL="https://public.shinkai.com/x-un"
 
 # export TELEMETRY_ENDPOINT="https://apm-node-b1.shinkai.com/api/default"
 # export TELEMETRY_AUTH_HEADER="Basic xxx"
+# export TELEMETRY_SAMPLING_RATIO="0.1"
 
 export STATIC_SERVER_PORT="9554"
 export STATIC_SERVER_IP="0.0.0.0"
@@ -21,6 +21,7 @@ export UNSTRUCTURED_SERVER_URL="https://public.shinkai.com/x-un"
 
 # export TELEMETRY_ENDPOINT="https://apm-node-b1.shinkai.com/api/default"
 # export TELEMETRY_AUTH_HEADER="Basic xxx"
+# export TELEMETRY_SAMPLING_RATIO="0.1"
 
 export STATIC_SERVER_PORT="9954"
 export STATIC_SERVER_IP="0.0.0.0"
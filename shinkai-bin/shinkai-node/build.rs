@@ -0,0 +1,8 @@
+fn main() {
+    // The gRPC API is optional, so are protoc and the generated code
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto");
+        tonic_build::compile_protos("proto/shinkai_node/v1/node.proto").expect("Failed to compile the gRPC protos");
+    }
+}
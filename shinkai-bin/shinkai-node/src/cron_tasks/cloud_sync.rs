@@ -0,0 +1,674 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use shinkai_vector_resources::embedding_generator::RemoteEmbeddingGenerator;
+use shinkai_vector_resources::file_parser::file_parser::FileParser;
+use shinkai_vector_resources::source::{DistributionInfo, SourceFileType};
+use shinkai_vector_resources::vector_resource::VRPath;
+
+use crate::cron_tasks::cron_manager::CronManager;
+use crate::db::ShinkaiDB;
+use crate::llm_provider::parsing_helper::{IngestionSettings, ParsingHelper};
+use crate::schemas::calendar_account::OAuthToken;
+use crate::schemas::cloud_connector::{CloudConnector, CloudProvider};
+use crate::vector_fs::vector_fs::VectorFS;
+
+const CLOUD_SYNC_TIMEOUT_SECS: u64 = 60;
+const GOOGLE_DRIVE_API: &str = "https://www.googleapis.com/drive/v3";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const GOOGLE_FOLDER_MIME_TYPE: &str = "application/vnd.google-apps.folder";
+const DROPBOX_API: &str = "https://api.dropboxapi.com/2";
+const DROPBOX_CONTENT_API: &str = "https://content.dropboxapi.com/2";
+const DROPBOX_TOKEN_URL: &str = "https://api.dropboxapi.com/oauth2/token";
+/// Google Drive folders are nested at most this deep below the synced one
+const MAX_DRIVE_DEPTH: usize = 32;
+
+/// A change of the synced folder since the last sync
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteChange {
+    /// A file that was added or modified. Its key identifies it at the provider: the Google Drive file id or the
+    /// lowercase Dropbox path.
+    Upserted {
+        key: String,
+        relative_path: String,
+        /// What the file is downloaded from: its Google Drive download URL or its Dropbox path
+        source: String,
+        modified: Option<DateTime<Utc>>,
+    },
+    /// A file that was removed or moved out of the synced folder, or a Dropbox folder with all its files
+    Removed { key: String },
+}
+
+/// Periodically syncs the remote folders of the cloud connectors into the VectorFS of their profiles. Only what
+/// changed since the last sync is fetched, through the delta APIs of the providers.
+pub struct CloudSync;
+
+impl CloudSync {
+    /// Spawns the sync loop. It checks every CRON_INTERVAL_TIME seconds which connectors are due.
+    pub fn start(
+        db: Weak<ShinkaiDB>,
+        vector_fs: Weak<VectorFS>,
+        generator: RemoteEmbeddingGenerator,
+    ) -> tokio::task::JoinHandle<()> {
+        let interval = CronManager::cron_interval_time();
+
+        tokio::spawn(async move {
+            loop {
+                let (Some(db), Some(vector_fs)) = (db.upgrade(), vector_fs.upgrade()) else {
+                    return;
+                };
+
+                for connector in db.get_all_cloud_connectors().unwrap_or_default() {
+                    if !CronManager::is_cron_due(&connector.sync_cron, interval) {
+                        continue;
+                    }
+                    if let Err(e) = Self::sync_connector(&db, &vector_fs, &generator, &connector).await {
+                        shinkai_log(
+                            ShinkaiLogOption::CronExecution,
+                            ShinkaiLogLevel::Error,
+                            &format!("Failed to sync cloud connector {}: {}", connector.id, e),
+                        );
+                    }
+                }
+
+                drop(db);
+                drop(vector_fs);
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+            }
+        })
+    }
+
+    /// Applies the changes of the remote folder since the last sync and returns how many were applied. The
+    /// first sync lists the whole folder.
+    pub async fn sync_connector(
+        db: &Arc<ShinkaiDB>,
+        vector_fs: &Arc<VectorFS>,
+        generator: &RemoteEmbeddingGenerator,
+        connector: &CloudConnector,
+    ) -> Result<usize, String> {
+        let profile = ShinkaiName::new(connector.profile.clone()).map_err(|e| e.to_string())?;
+        let mut state = db.get_cloud_sync_state(&connector.id).map_err(|e| e.to_string())?;
+        let mut client = CloudClient::new(db.clone(), connector.clone())?;
+        let ingestion = ParsingHelper::ingestion_settings(db, &profile);
+        let (changes, cursor) = client.list_changes(state.cursor.clone()).await?;
+        let mut synced = 0;
+
+        for change in changes {
+            match change {
+                RemoteChange::Upserted {
+                    key,
+                    relative_path,
+                    source,
+                    modified,
+                } => {
+                    let file_name = relative_path.rsplit('/').next().unwrap_or_default();
+                    if SourceFileType::detect_file_type(file_name).is_err() {
+                        continue;
+                    }
+                    // A renamed or moved file is removed from where it was synced to
+                    if let Some(previous_path) = state.files.get(&key).filter(|path| **path != relative_path) {
+                        Self::delete_item(vector_fs, &profile, connector, previous_path).await?;
+                    }
+                    let content = client.download(&source).await?;
+                    Self::ingest_file(
+                        vector_fs,
+                        generator,
+                        &ingestion,
+                        &profile,
+                        connector,
+                        &relative_path,
+                        content,
+                        modified,
+                    )
+                    .await?;
+                    state.files.insert(key, relative_path);
+                }
+                RemoteChange::Removed { key } => {
+                    let removed: Vec<String> = state
+                        .files
+                        .keys()
+                        .filter(|tracked| **tracked == key || tracked.starts_with(&format!("{}/", key)))
+                        .cloned()
+                        .collect();
+                    for tracked in removed {
+                        if let Some(relative_path) = state.files.remove(&tracked) {
+                            Self::delete_item(vector_fs, &profile, connector, &relative_path).await?;
+                        }
+                    }
+                }
+            }
+            // Saved after each change so a failed sync doesn't lose track of the files already synced
+            db.set_cloud_sync_state(&connector.id, &state)
+                .map_err(|e| e.to_string())?;
+            synced += 1;
+        }
+
+        state.cursor = Some(cursor);
+        db.set_cloud_sync_state(&connector.id, &state)
+            .map_err(|e| e.to_string())?;
+
+        shinkai_log(
+            ShinkaiLogOption::CronExecution,
+            ShinkaiLogLevel::Info,
+            &format!("Synced {} changes of cloud connector {}", synced, connector.id),
+        );
+        Ok(synced)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn ingest_file(
+        vector_fs: &Arc<VectorFS>,
+        generator: &RemoteEmbeddingGenerator,
+        ingestion: &IngestionSettings,
+        profile: &ShinkaiName,
+        connector: &CloudConnector,
+        relative_path: &str,
+        content: Vec<u8>,
+        modified: Option<DateTime<Utc>>,
+    ) -> Result<(), String> {
+        let (folder_path, file_name) = connector.vector_fs_location(relative_path).map_err(|e| e.to_string())?;
+        let root_writer = vector_fs
+            .new_writer(profile.clone(), VRPath::root(), profile.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        vector_fs
+            .create_new_folder_auto(&root_writer, folder_path.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let (generator, ingestion) = vector_fs
+            .folder_embedding_settings(profile, &folder_path, generator, ingestion)
+            .await
+            .map_err(|e| e.to_string())?;
+        let distribution_info = DistributionInfo::new_auto(&file_name, modified);
+        let processed = ParsingHelper::process_files_into_vrkai(
+            vec![(file_name, content, distribution_info)],
+            &*generator,
+            None,
+            FileParser::Local,
+            &ingestion,
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let writer = vector_fs
+            .new_writer(profile.clone(), folder_path, profile.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        for (_, vrkai) in processed {
+            vector_fs
+                .save_vrkai_in_folder(&writer, vrkai)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    async fn delete_item(
+        vector_fs: &Arc<VectorFS>,
+        profile: &ShinkaiName,
+        connector: &CloudConnector,
+        relative_path: &str,
+    ) -> Result<(), String> {
+        let item_path = connector
+            .vector_fs_item_path(relative_path)
+            .map_err(|e| e.to_string())?;
+        let writer = vector_fs
+            .new_writer(profile.clone(), item_path, profile.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        // The item may already have been deleted from the VectorFS
+        let _ = vector_fs.delete_item(&writer).await;
+        Ok(())
+    }
+}
+
+/// Talks to the cloud storage of a connector. Refreshed tokens are saved back to the DB.
+struct CloudClient {
+    connector: CloudConnector,
+    db: Arc<ShinkaiDB>,
+    client: reqwest::Client,
+}
+
+impl CloudClient {
+    fn new(db: Arc<ShinkaiDB>, connector: CloudConnector) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(CLOUD_SYNC_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { connector, db, client })
+    }
+
+    /// Changes since the cursor, or every file of the folder without one, with the cursor to continue from
+    async fn list_changes(&mut self, cursor: Option<String>) -> Result<(Vec<RemoteChange>, String), String> {
+        match self.connector.provider.clone() {
+            CloudProvider::GoogleDrive { folder_id, .. } => match cursor {
+                Some(page_token) => self.drive_changes(&folder_id, page_token).await,
+                None => {
+                    // Taken before listing, so what changes meanwhile is picked up by the next sync
+                    let body = self
+                        .get_json(&format!("{}/changes/startPageToken", GOOGLE_DRIVE_API), &[])
+                        .await?;
+                    let page_token = Self::json_str(&body, "startPageToken")?;
+                    Ok((self.drive_list_folder(&folder_id).await?, page_token))
+                }
+            },
+            CloudProvider::Dropbox { folder_path, .. } => self.dropbox_changes(&folder_path, cursor).await,
+        }
+    }
+
+    async fn download(&mut self, source: &str) -> Result<Vec<u8>, String> {
+        let access_token = self.access_token().await?;
+        let request = match &self.connector.provider {
+            CloudProvider::GoogleDrive { .. } => self.client.get(source),
+            CloudProvider::Dropbox { .. } => self
+                .client
+                .post(format!("{}/files/download", DROPBOX_CONTENT_API))
+                .header("Dropbox-API-Arg", json!({ "path": source }).to_string()),
+        };
+        let response = request
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Downloading {} failed with status {}",
+                source,
+                response.status()
+            ));
+        }
+        Ok(response.bytes().await.map_err(|e| e.to_string())?.to_vec())
+    }
+
+    /// Every file below the folder, walking its subfolders
+    async fn drive_list_folder(&mut self, folder_id: &str) -> Result<Vec<RemoteChange>, String> {
+        let mut changes = Vec::new();
+        let mut folders = vec![(folder_id.to_string(), String::new())];
+
+        while let Some((id, prefix)) = folders.pop() {
+            let mut page_token: Option<String> = None;
+            loop {
+                let query = format!("'{}' in parents and trashed = false", id);
+                let mut params = vec![
+                    ("q", query),
+                    (
+                        "fields",
+                        "nextPageToken, files(id, name, mimeType, modifiedTime)".to_string(),
+                    ),
+                    ("pageSize", "1000".to_string()),
+                ];
+                if let Some(token) = &page_token {
+                    params.push(("pageToken", token.clone()));
+                }
+                let body = self.get_json(&format!("{}/files", GOOGLE_DRIVE_API), &params).await?;
+
+                for file in body
+                    .get("files")
+                    .and_then(|files| files.as_array())
+                    .into_iter()
+                    .flatten()
+                {
+                    let name = Self::json_str(file, "name")?;
+                    if file.get("mimeType").and_then(|v| v.as_str()) == Some(GOOGLE_FOLDER_MIME_TYPE) {
+                        if prefix.matches('/').count() < MAX_DRIVE_DEPTH {
+                            folders.push((Self::json_str(file, "id")?, format!("{}{}/", prefix, name)));
+                        }
+                    } else if let Some(change) = Self::drive_upserted(file, format!("{}{}", prefix, name)) {
+                        changes.push(change);
+                    }
+                }
+
+                page_token = body
+                    .get("nextPageToken")
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.to_string());
+                if page_token.is_none() {
+                    break;
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    async fn drive_changes(
+        &mut self,
+        folder_id: &str,
+        mut page_token: String,
+    ) -> Result<(Vec<RemoteChange>, String), String> {
+        let mut changes = Vec::new();
+        // Names and parents of the folders looked up to find the path of the changed files
+        let mut folders: HashMap<String, (String, Vec<String>)> = HashMap::new();
+
+        loop {
+            let params = [
+                ("pageToken", page_token.clone()),
+                (
+                    "fields",
+                    "nextPageToken, newStartPageToken, changes(fileId, removed, file(id, name, mimeType, parents, \
+                     trashed, modifiedTime))"
+                        .to_string(),
+                ),
+                ("pageSize", "1000".to_string()),
+            ];
+            let body = self.get_json(&format!("{}/changes", GOOGLE_DRIVE_API), &params).await?;
+
+            for change in body
+                .get("changes")
+                .and_then(|changes| changes.as_array())
+                .into_iter()
+                .flatten()
+            {
+                let key = Self::json_str(change, "fileId")?;
+                let file = change
+                    .get("file")
+                    .filter(|_| change.get("removed") != Some(&Value::Bool(true)));
+                let Some(file) = file.filter(|file| file.get("trashed") != Some(&Value::Bool(true))) else {
+                    changes.push(RemoteChange::Removed { key });
+                    continue;
+                };
+                if file.get("mimeType").and_then(|v| v.as_str()) == Some(GOOGLE_FOLDER_MIME_TYPE) {
+                    continue;
+                }
+                let relative_path = self.drive_relative_path(folder_id, file, &mut folders).await?;
+                match relative_path.and_then(|path| Self::drive_upserted(file, path)) {
+                    Some(upserted) => changes.push(upserted),
+                    None => changes.push(RemoteChange::Removed { key }),
+                }
+            }
+
+            if let Some(new_start) = body.get("newStartPageToken").and_then(|v| v.as_str()) {
+                return Ok((changes, new_start.to_string()));
+            }
+            page_token = Self::json_str(&body, "nextPageToken")?;
+        }
+    }
+
+    /// Path of the file relative to the synced folder, or None if it isn't below it
+    async fn drive_relative_path(
+        &mut self,
+        folder_id: &str,
+        file: &Value,
+        folders: &mut HashMap<String, (String, Vec<String>)>,
+    ) -> Result<Option<String>, String> {
+        let mut segments = vec![Self::json_str(file, "name")?];
+        let mut parents = Self::json_parents(file);
+
+        for _ in 0..MAX_DRIVE_DEPTH {
+            let Some(parent) = parents.first().cloned() else {
+                return Ok(None);
+            };
+            if parent == folder_id {
+                segments.reverse();
+                return Ok(Some(segments.join("/")));
+            }
+            if !folders.contains_key(&parent) {
+                let body = self
+                    .get_json(
+                        &format!("{}/files/{}", GOOGLE_DRIVE_API, parent),
+                        &[("fields", "name, parents".to_string())],
+                    )
+                    .await?;
+                folders.insert(
+                    parent.clone(),
+                    (Self::json_str(&body, "name")?, Self::json_parents(&body)),
+                );
+            }
+            let (name, grandparents) = folders[&parent].clone();
+            segments.push(name);
+            parents = grandparents;
+        }
+        Ok(None)
+    }
+
+    /// Google Docs, Sheets and Slides are exported to a format that can be ingested, other Google files skipped
+    fn drive_upserted(file: &Value, relative_path: String) -> Option<RemoteChange> {
+        let id = file.get("id")?.as_str()?;
+        let mime_type = file.get("mimeType").and_then(|v| v.as_str()).unwrap_or_default();
+        let export = match mime_type {
+            "application/vnd.google-apps.document" => Some(("text/plain", "txt")),
+            "application/vnd.google-apps.spreadsheet" => Some(("text/csv", "csv")),
+            "application/vnd.google-apps.presentation" => Some(("application/pdf", "pdf")),
+            _ if mime_type.starts_with("application/vnd.google-apps.") => return None,
+            _ => None,
+        };
+        let (relative_path, source) = match export {
+            Some((export_mime_type, extension)) => (
+                format!("{}.{}", relative_path, extension),
+                format!(
+                    "{}/files/{}/export?mimeType={}",
+                    GOOGLE_DRIVE_API,
+                    id,
+                    urlencoding::encode(export_mime_type)
+                ),
+            ),
+            None => (relative_path, format!("{}/files/{}?alt=media", GOOGLE_DRIVE_API, id)),
+        };
+        Some(RemoteChange::Upserted {
+            key: id.to_string(),
+            relative_path,
+            source,
+            modified: file
+                .get("modifiedTime")
+                .and_then(|v| v.as_str())
+                .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+                .map(|v| v.with_timezone(&Utc)),
+        })
+    }
+
+    async fn dropbox_changes(
+        &mut self,
+        folder_path: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<RemoteChange>, String), String> {
+        let mut changes = Vec::new();
+        let mut body = match cursor {
+            Some(cursor) => {
+                self.post_json(
+                    &format!("{}/files/list_folder/continue", DROPBOX_API),
+                    json!({ "cursor": cursor }),
+                )
+                .await?
+            }
+            None => {
+                self.post_json(
+                    &format!("{}/files/list_folder", DROPBOX_API),
+                    json!({ "path": folder_path, "recursive": true }),
+                )
+                .await?
+            }
+        };
+
+        loop {
+            for entry in body
+                .get("entries")
+                .and_then(|entries| entries.as_array())
+                .into_iter()
+                .flatten()
+            {
+                let key = Self::json_str(entry, "path_lower")?;
+                match entry.get(".tag").and_then(|v| v.as_str()) {
+                    Some("file") => {
+                        let path_display = Self::json_str(entry, "path_display")?;
+                        let relative_path: String = path_display.chars().skip(folder_path.chars().count()).collect();
+                        changes.push(RemoteChange::Upserted {
+                            relative_path: relative_path.trim_start_matches('/').to_string(),
+                            source: key.clone(),
+                            key,
+                            modified: entry
+                                .get("server_modified")
+                                .and_then(|v| v.as_str())
+                                .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+                                .map(|v| v.with_timezone(&Utc)),
+                        });
+                    }
+                    Some("deleted") => changes.push(RemoteChange::Removed { key }),
+                    _ => {}
+                }
+            }
+
+            let cursor = Self::json_str(&body, "cursor")?;
+            if body.get("has_more") != Some(&Value::Bool(true)) {
+                return Ok((changes, cursor));
+            }
+            body = self
+                .post_json(
+                    &format!("{}/files/list_folder/continue", DROPBOX_API),
+                    json!({ "cursor": cursor }),
+                )
+                .await?;
+        }
+    }
+
+    async fn get_json(&mut self, url: &str, params: &[(&str, String)]) -> Result<Value, String> {
+        let access_token = self.access_token().await?;
+        let response = self
+            .client
+            .get(url)
+            .query(params)
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Request to {} failed with status {}", url, response.status()));
+        }
+        response.json().await.map_err(|e| e.to_string())
+    }
+
+    async fn post_json(&mut self, url: &str, payload: Value) -> Result<Value, String> {
+        let access_token = self.access_token().await?;
+        let response = self
+            .client
+            .post(url)
+            .json(&payload)
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Request to {} failed with status {}", url, response.status()));
+        }
+        response.json().await.map_err(|e| e.to_string())
+    }
+
+    /// Access token of the connector, refreshing it first if it expired
+    async fn access_token(&mut self) -> Result<String, String> {
+        let oauth = self.connector.provider.oauth().clone();
+        if !oauth.is_expired() {
+            return Ok(oauth.access_token);
+        }
+        let Some(refresh_token) = oauth.refresh_token else {
+            return Err("The token expired and there is no refresh token".to_string());
+        };
+
+        let (token_url, env_prefix) = match self.connector.provider {
+            CloudProvider::GoogleDrive { .. } => (GOOGLE_TOKEN_URL, "GOOGLE"),
+            CloudProvider::Dropbox { .. } => (DROPBOX_TOKEN_URL, "DROPBOX"),
+        };
+        let env_var = |name: &str| {
+            let key = format!("{}_OAUTH_{}", env_prefix, name);
+            std::env::var(&key).map_err(|_| format!("{} is not set", key))
+        };
+        let client_id = env_var("CLIENT_ID")?;
+        let client_secret = env_var("CLIENT_SECRET")?;
+        let response = self
+            .client
+            .post(token_url)
+            .form(&[
+                ("client_id", client_id.as_str()),
+                ("client_secret", client_secret.as_str()),
+                ("refresh_token", refresh_token.as_str()),
+                ("grant_type", "refresh_token"),
+            ])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Refreshing the token failed with status {}", response.status()));
+        }
+        let body: Value = response.json().await.map_err(|e| e.to_string())?;
+        let access_token = Self::json_str(&body, "access_token")?;
+        let expires_in = body.get("expires_in").and_then(|v| v.as_i64()).unwrap_or(3600);
+
+        *self.connector.provider.oauth_mut() = OAuthToken {
+            access_token: access_token.clone(),
+            // Providers only return a new refresh token when they rotate it
+            refresh_token: body
+                .get("refresh_token")
+                .and_then(|v| v.as_str())
+                .map(|v| v.to_string())
+                .or(Some(refresh_token)),
+            expires_at: Some(Utc::now() + chrono::Duration::seconds(expires_in)),
+        };
+        // Not saved if the connector was removed while syncing
+        if self.db.get_cloud_connector(&self.connector.id).is_ok() {
+            self.db
+                .set_cloud_connector(&self.connector)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(access_token)
+    }
+
+    fn json_str(value: &Value, field: &str) -> Result<String, String> {
+        value
+            .get(field)
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string())
+            .ok_or_else(|| format!("Missing {} in the response", field))
+    }
+
+    fn json_parents(value: &Value) -> Vec<String> {
+        value
+            .get("parents")
+            .and_then(|parents| parents.as_array())
+            .map(|parents| {
+                parents
+                    .iter()
+                    .filter_map(|v| v.as_str().map(|v| v.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drive_upserted() {
+        let doc = json!({
+            "id": "doc1",
+            "name": "Roadmap",
+            "mimeType": "application/vnd.google-apps.document",
+            "modifiedTime": "2024-05-01T10:00:00Z",
+        });
+        let Some(RemoteChange::Upserted {
+            key,
+            relative_path,
+            source,
+            modified,
+        }) = CloudClient::drive_upserted(&doc, "Plans/Roadmap".to_string())
+        else {
+            panic!("expected an upserted file");
+        };
+        assert_eq!(key, "doc1");
+        assert_eq!(relative_path, "Plans/Roadmap.txt");
+        assert_eq!(
+            source,
+            format!("{}/files/doc1/export?mimeType=text%2Fplain", GOOGLE_DRIVE_API)
+        );
+        assert!(modified.is_some());
+
+        let form = json!({ "id": "form1", "name": "Survey", "mimeType": "application/vnd.google-apps.form" });
+        assert_eq!(CloudClient::drive_upserted(&form, "Survey".to_string()), None);
+    }
+}
@@ -138,7 +138,7 @@ impl CronManager {
         }
     }
 
-    fn cron_interval_time() -> u64 {
+    pub fn cron_interval_time() -> u64 {
         std::env::var("CRON_INTERVAL_TIME")
             .unwrap_or_else(|_| "60".to_string())
             .parse()
@@ -291,6 +291,7 @@ impl CronManager {
         let job_creation = JobCreationInfo {
             scope: JobScope::new_default(),
             is_hidden: Some(false),
+            retrieval: None,
         };
 
         // Create Job
@@ -364,6 +365,7 @@ impl CronManager {
             workflow_name: None,
             callback: None,
             sheet_job_data: None,
+            retrieval: None,
         };
 
         job_manager
@@ -376,19 +378,24 @@ impl CronManager {
     }
 
     pub fn should_execute_cron_task(cron_task: &CronTask, cron_time_interval: u64) -> bool {
+        Self::is_cron_due(&cron_task.cron, cron_time_interval)
+    }
+
+    /// Checks if the cron expression fires between now and now + cron_time_interval
+    pub fn is_cron_due(cron: &str, cron_time_interval: u64) -> bool {
         // Calculate the current time and the end of the interval
         let now = Utc::now();
         let now_rounded = now.with_second(0).unwrap().with_nanosecond(0).unwrap();
         let end_of_interval = now_rounded + chrono::Duration::seconds(cron_time_interval as i64);
 
         // Parse the cron expression
-        let next_execution_time = match cron_parser::parse(&cron_task.cron, &now_rounded) {
+        let next_execution_time = match cron_parser::parse(cron, &now_rounded) {
             Ok(datetime) => datetime,
             Err(_) => {
                 shinkai_log(
                     ShinkaiLogOption::CronExecution,
                     ShinkaiLogLevel::Error,
-                    format!("Invalid cron expression: {}", &cron_task.cron).as_str(),
+                    format!("Invalid cron expression: {}", cron).as_str(),
                 );
                 return false;
             }
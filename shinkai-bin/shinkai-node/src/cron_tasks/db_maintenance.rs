@@ -0,0 +1,77 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use chrono::Utc;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+
+use crate::cron_tasks::cron_manager::CronManager;
+use crate::db::ShinkaiDB;
+use crate::schemas::db_maintenance::DbMaintenanceReport;
+use crate::vector_fs::vector_fs::VectorFS;
+
+/// Compacts the main and VectorFS databases on the schedule set through the API. Long-running nodes accumulate
+/// the tombstones of deleted keys, which slow reads down until RocksDB compacts them away.
+pub struct DbMaintenance;
+
+impl DbMaintenance {
+    /// Spawns the compaction loop. It checks every CRON_INTERVAL_TIME seconds whether a compaction is due.
+    pub fn start(db: Weak<ShinkaiDB>, vector_fs: Weak<VectorFS>) -> tokio::task::JoinHandle<()> {
+        let interval = CronManager::cron_interval_time();
+
+        tokio::spawn(async move {
+            loop {
+                let (Some(db), Some(vector_fs)) = (db.upgrade(), vector_fs.upgrade()) else {
+                    return;
+                };
+
+                let due = db
+                    .get_db_compaction_schedule()
+                    .is_ok_and(|schedule| schedule.is_due(Utc::now()));
+                if due {
+                    if let Err(e) = Self::compact(&db, &vector_fs).await {
+                        shinkai_log(
+                            ShinkaiLogOption::CronExecution,
+                            ShinkaiLogLevel::Error,
+                            &format!("Failed to compact the databases: {}", e),
+                        );
+                    }
+                }
+
+                drop(db);
+                drop(vector_fs);
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+            }
+        })
+    }
+
+    /// Compacts both databases and records when, so the schedule restarts from now
+    pub async fn compact(db: &Arc<ShinkaiDB>, vector_fs: &Arc<VectorFS>) -> Result<(), String> {
+        let (db_clone, vector_fs_clone) = (db.clone(), vector_fs.clone());
+        tokio::task::spawn_blocking(move || -> Result<(), String> {
+            db_clone.compact().map_err(|e| e.to_string())?;
+            vector_fs_clone.db.compact().map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+        let mut schedule = db.get_db_compaction_schedule().map_err(|e| e.to_string())?;
+        schedule.last_compaction = Some(Utc::now());
+        db.update_db_compaction_schedule(&schedule).map_err(|e| e.to_string())?;
+        shinkai_log(
+            ShinkaiLogOption::CronExecution,
+            ShinkaiLogLevel::Info,
+            "Compacted the main and VectorFS databases",
+        );
+        Ok(())
+    }
+
+    /// Sizes of the column families of both databases, along with their compaction schedule
+    pub fn report(db: &ShinkaiDB, vector_fs: &VectorFS, compacted: bool) -> Result<DbMaintenanceReport, String> {
+        Ok(DbMaintenanceReport {
+            main_db: db.column_family_sizes().map_err(|e| e.to_string())?,
+            vector_fs_db: vector_fs.db.column_family_sizes().map_err(|e| e.to_string())?,
+            compacted,
+            schedule: db.get_db_compaction_schedule().map_err(|e| e.to_string())?,
+        })
+    }
+}
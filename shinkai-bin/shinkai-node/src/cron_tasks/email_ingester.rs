@@ -0,0 +1,318 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use shinkai_vector_resources::embedding_generator::RemoteEmbeddingGenerator;
+use shinkai_vector_resources::file_parser::file_parser::FileParser;
+use shinkai_vector_resources::source::DistributionInfo;
+use shinkai_vector_resources::vector_resource::VRPath;
+
+use crate::cron_tasks::cron_manager::CronManager;
+use crate::db::ShinkaiDB;
+use crate::llm_provider::parsing_helper::{IngestionSettings, ParsingHelper};
+use crate::schemas::email_account::EmailAccountConfig;
+use crate::vector_fs::vector_fs::VectorFS;
+
+/// An email fetched from IMAP, already parsed
+#[derive(Debug, Clone)]
+pub struct FetchedEmail {
+    pub uid: u32,
+    pub subject: String,
+    pub from: String,
+    pub to: String,
+    pub date: Option<DateTime<Utc>>,
+    pub body: String,
+}
+
+impl FetchedEmail {
+    pub fn parse(uid: u32, raw: &[u8]) -> Result<Self, String> {
+        let parsed = mailparse::parse_mail(raw).map_err(|e| e.to_string())?;
+        let header = |name: &str| parsed.headers.get_first_value(name).unwrap_or_default();
+        let date = mailparse::dateparse(&header("Date"))
+            .ok()
+            .and_then(|timestamp| DateTime::<Utc>::from_timestamp(timestamp, 0));
+
+        Ok(FetchedEmail {
+            uid,
+            subject: header("Subject"),
+            from: header("From"),
+            to: header("To"),
+            date,
+            body: Self::text_body(&parsed).unwrap_or_default(),
+        })
+    }
+
+    /// The text/plain part of the email, falling back to the text of the HTML part
+    fn text_body(mail: &mailparse::ParsedMail) -> Option<String> {
+        if mail.subparts.is_empty() {
+            let body = mail.get_body().ok()?;
+            return match mail.ctype.mimetype.as_str() {
+                "text/plain" => Some(body),
+                "text/html" => Some(html2md::parse_html(&body)),
+                _ => None,
+            };
+        }
+        let parts: Vec<String> = mail.subparts.iter().filter_map(Self::text_body).collect();
+        match mail.ctype.mimetype.as_str() {
+            // Both parts have the same content, the first one is the plain text one
+            "multipart/alternative" => parts.into_iter().next(),
+            _ => Some(parts.join("\n\n")).filter(|body| !body.is_empty()),
+        }
+    }
+
+    /// File name the email is saved with in the VectorFS
+    pub fn file_name(&self) -> String {
+        let subject: String = self
+            .subject
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == ' ' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(80)
+            .collect();
+        let subject = if subject.trim().is_empty() {
+            "No subject"
+        } else {
+            subject.trim()
+        };
+        format!("{} - {}.txt", self.uid, subject)
+    }
+
+    pub fn to_text(&self) -> String {
+        format!(
+            "Subject: {}\nFrom: {}\nTo: {}\nDate: {}\n\n{}",
+            self.subject,
+            self.from,
+            self.to,
+            self.date.map(|d| d.to_rfc3339()).unwrap_or_default(),
+            self.body
+        )
+    }
+}
+
+/// Periodically fetches the configured IMAP folders of every email account and saves new emails into the
+/// profile's VectorFS, so they can be searched and summarized by jobs.
+pub struct EmailIngester;
+
+impl EmailIngester {
+    /// Spawns the ingestion loop. It checks every CRON_INTERVAL_TIME seconds which accounts are due.
+    pub fn start(
+        db: Weak<ShinkaiDB>,
+        vector_fs: Weak<VectorFS>,
+        generator: RemoteEmbeddingGenerator,
+    ) -> tokio::task::JoinHandle<()> {
+        let interval = CronManager::cron_interval_time();
+
+        tokio::spawn(async move {
+            loop {
+                let (Some(db), Some(vector_fs)) = (db.upgrade(), vector_fs.upgrade()) else {
+                    return;
+                };
+
+                for account in db.get_all_email_accounts().unwrap_or_default() {
+                    if account.ingest_folders.is_empty() || !CronManager::is_cron_due(&account.ingest_cron, interval) {
+                        continue;
+                    }
+                    if let Err(e) = Self::ingest_account(&db, &vector_fs, &generator, &account).await {
+                        shinkai_log(
+                            ShinkaiLogOption::CronExecution,
+                            ShinkaiLogLevel::Error,
+                            &format!("Failed to ingest emails of {}: {}", account.profile, e),
+                        );
+                    }
+                }
+
+                drop(db);
+                drop(vector_fs);
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+            }
+        })
+    }
+
+    pub async fn ingest_account(
+        db: &Arc<ShinkaiDB>,
+        vector_fs: &Arc<VectorFS>,
+        generator: &RemoteEmbeddingGenerator,
+        account: &EmailAccountConfig,
+    ) -> Result<usize, String> {
+        let profile = ShinkaiName::new(account.profile.clone()).map_err(|e| e.to_string())?;
+        let ingestion = ParsingHelper::ingestion_settings(db, &profile);
+        let mut ingested = 0;
+
+        for folder in &account.ingest_folders {
+            let last_uid = db
+                .get_email_ingest_last_uid(&account.profile, folder)
+                .map_err(|e| e.to_string())?;
+
+            let fetch_account = account.clone();
+            let fetch_folder = folder.clone();
+            let emails =
+                tokio::task::spawn_blocking(move || Self::fetch_new_emails(&fetch_account, &fetch_folder, last_uid))
+                    .await
+                    .map_err(|e| e.to_string())??;
+
+            for email in emails {
+                Self::save_email(
+                    vector_fs,
+                    generator,
+                    &ingestion,
+                    &profile,
+                    &account.vector_fs_path,
+                    folder,
+                    &email,
+                )
+                .await?;
+                db.set_email_ingest_last_uid(&account.profile, folder, email.uid)
+                    .map_err(|e| e.to_string())?;
+                ingested += 1;
+            }
+        }
+
+        shinkai_log(
+            ShinkaiLogOption::CronExecution,
+            ShinkaiLogLevel::Info,
+            &format!("Ingested {} emails for {}", ingested, account.profile),
+        );
+        Ok(ingested)
+    }
+
+    /// Fetches the emails with a UID greater than `last_uid`, oldest first, up to EMAIL_INGEST_MAX_PER_RUN (default 50)
+    fn fetch_new_emails(
+        account: &EmailAccountConfig,
+        folder: &str,
+        last_uid: u32,
+    ) -> Result<Vec<FetchedEmail>, String> {
+        let max_per_run: usize = std::env::var("EMAIL_INGEST_MAX_PER_RUN")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(50);
+
+        let tls = native_tls::TlsConnector::builder().build().map_err(|e| e.to_string())?;
+        let client = imap::connect(
+            (account.imap_host.as_str(), account.imap_port),
+            account.imap_host.as_str(),
+            &tls,
+        )
+        .map_err(|e| e.to_string())?;
+        let mut session = client
+            .login(&account.username, account.password()?)
+            .map_err(|(e, _)| e.to_string())?;
+        session.select(folder).map_err(|e| e.to_string())?;
+
+        // `n:*` always matches the last email, even when its UID is lower than n
+        let mut uids: Vec<u32> = session
+            .uid_search(format!("UID {}:*", last_uid + 1))
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|uid| *uid > last_uid)
+            .collect();
+        uids.sort_unstable();
+        uids.truncate(max_per_run);
+
+        let mut emails = Vec::new();
+        if !uids.is_empty() {
+            let uid_set = uids.iter().map(|uid| uid.to_string()).collect::<Vec<_>>().join(",");
+            let fetches = session.uid_fetch(uid_set, "(UID RFC822)").map_err(|e| e.to_string())?;
+            for fetch in fetches.iter() {
+                if let (Some(uid), Some(body)) = (fetch.uid, fetch.body()) {
+                    emails.push(FetchedEmail::parse(uid, body)?);
+                }
+            }
+        }
+        let _ = session.logout();
+
+        emails.sort_by_key(|email| email.uid);
+        Ok(emails)
+    }
+
+    async fn save_email(
+        vector_fs: &Arc<VectorFS>,
+        generator: &RemoteEmbeddingGenerator,
+        ingestion: &IngestionSettings,
+        profile: &ShinkaiName,
+        vector_fs_path: &str,
+        folder: &str,
+        email: &FetchedEmail,
+    ) -> Result<(), String> {
+        let folder_path = VRPath::from_string(vector_fs_path)
+            .map_err(|e| e.to_string())?
+            .push_cloned(folder.to_string());
+        let root_writer = vector_fs
+            .new_writer(profile.clone(), VRPath::root(), profile.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        vector_fs
+            .create_new_folder_auto(&root_writer, folder_path.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let (generator, ingestion) = vector_fs
+            .folder_embedding_settings(profile, &folder_path, generator, ingestion)
+            .await
+            .map_err(|e| e.to_string())?;
+        let file_name = email.file_name();
+        let distribution_info = DistributionInfo::new_auto(&file_name, email.date);
+        let processed = ParsingHelper::process_files_into_vrkai(
+            vec![(file_name, email.to_text().into_bytes(), distribution_info)],
+            &*generator,
+            None,
+            FileParser::Local,
+            &ingestion,
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let writer = vector_fs
+            .new_writer(profile.clone(), folder_path, profile.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        for (_, vrkai) in processed {
+            vector_fs
+                .save_vrkai_in_folder(&writer, vrkai)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multipart_email() {
+        let raw = concat!(
+            "From: Bob <bob@example.com>\r\n",
+            "To: alice@example.com\r\n",
+            "Subject: Weekly report\r\n",
+            "Date: Mon, 14 Oct 2024 09:30:00 +0000\r\n",
+            "Content-Type: multipart/alternative; boundary=\"b\"\r\n",
+            "\r\n",
+            "--b\r\n",
+            "Content-Type: text/plain\r\n",
+            "\r\n",
+            "Sales are up.\r\n",
+            "--b\r\n",
+            "Content-Type: text/html\r\n",
+            "\r\n",
+            "<p>Sales are <b>up</b>.</p>\r\n",
+            "--b--\r\n",
+        );
+
+        let email = FetchedEmail::parse(42, raw.as_bytes()).unwrap();
+        assert_eq!(email.subject, "Weekly report");
+        assert_eq!(email.from, "Bob <bob@example.com>");
+        assert_eq!(email.body.trim(), "Sales are up.");
+        assert_eq!(email.date.unwrap().to_rfc3339(), "2024-10-14T09:30:00+00:00");
+        assert_eq!(email.file_name(), "42 - Weekly report.txt");
+    }
+}
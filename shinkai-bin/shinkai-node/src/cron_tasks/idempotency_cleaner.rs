@@ -0,0 +1,42 @@
+use std::sync::Weak;
+use std::time::Duration;
+
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+
+use crate::db::ShinkaiDB;
+
+/// Periodically removes the expired idempotency keys of the v2 API, so requests don't pay for the cleanup.
+/// Runs every IDEMPOTENCY_CLEANUP_INTERVAL_SECS (default an hour) seconds.
+pub struct IdempotencyCleaner;
+
+impl IdempotencyCleaner {
+    pub fn start(db: Weak<ShinkaiDB>) -> tokio::task::JoinHandle<()> {
+        let interval = std::env::var("IDEMPOTENCY_CLEANUP_INTERVAL_SECS")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(3600);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+                let Some(db) = db.upgrade() else {
+                    return;
+                };
+
+                match db.remove_expired_idempotency_keys() {
+                    Ok(removed) if removed > 0 => shinkai_log(
+                        ShinkaiLogOption::CronExecution,
+                        ShinkaiLogLevel::Info,
+                        &format!("Removed {} expired idempotency keys", removed),
+                    ),
+                    Ok(_) => {}
+                    Err(e) => shinkai_log(
+                        ShinkaiLogOption::CronExecution,
+                        ShinkaiLogLevel::Error,
+                        &format!("Failed to remove expired idempotency keys: {}", e),
+                    ),
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,344 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use chrono::Utc;
+use ed25519_dalek::SigningKey;
+use shinkai_message_primitives::schemas::inbox_name::InboxName;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::JobMessage;
+use shinkai_message_primitives::shinkai_utils::job_scope::JobScope;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use shinkai_message_primitives::shinkai_utils::shinkai_message_builder::ShinkaiMessageBuilder;
+use shinkai_message_primitives::shinkai_utils::signatures::clone_signature_secret_key;
+use shinkai_vector_resources::shinkai_time::ShinkaiStringTime;
+use tokio::sync::Mutex;
+
+use crate::db::db_errors::ShinkaiDBError;
+use crate::db::ShinkaiDB;
+use crate::llm_provider::execution::prompts::prompts::JobPromptGenerator;
+use crate::llm_provider::job_manager::JobManager;
+use crate::network::ws_manager::WSUpdateHandler;
+use crate::schemas::identity::StandardIdentity;
+use crate::schemas::inbox_digest::{InboxDigestSchedule, InboxDigestSettings};
+use crate::schemas::inbox_permission::InboxPermission;
+
+/// Name of the inbox the digests are posted to, and subject of the emails they're sent in
+const DIGEST_INBOX_NAME: &str = "Inbox digest";
+/// Unread messages of each conversation that make it into a digest
+const MAX_UNREAD_MESSAGES_PER_INBOX: usize = 20;
+/// Unread messages and answers of jobs are cut to this many characters in the prompt
+const ACTIVITY_ITEM_MAX_CHARS: usize = 1000;
+
+/// What happened in the inboxes of a profile since its last digest
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InboxActivity {
+    /// Unread messages of the conversations, as (conversation, sender, content)
+    pub unread_messages: Vec<(String, String, String)>,
+    /// Jobs the agent answered, as (job, answer)
+    pub completed_jobs: Vec<(String, String)>,
+}
+
+impl InboxActivity {
+    pub fn is_empty(&self) -> bool {
+        self.unread_messages.is_empty() && self.completed_jobs.is_empty()
+    }
+
+    fn to_prompt_text(&self) -> String {
+        let mut text = String::new();
+        if !self.unread_messages.is_empty() {
+            text.push_str("Unread messages:\n");
+            for (conversation, sender, content) in &self.unread_messages {
+                text.push_str(&format!("- [{}] {}: {}\n", conversation, sender, content));
+            }
+        }
+        if !self.completed_jobs.is_empty() {
+            text.push_str("Finished jobs:\n");
+            for (job, answer) in &self.completed_jobs {
+                text.push_str(&format!("- [{}] {}\n", job, answer));
+            }
+        }
+        text
+    }
+}
+
+/// Posts digests of the unread messages and answered jobs of the profiles that asked for them, daily or weekly,
+/// written by the agent they chose. Digests go to an inbox of their own and, if the profile set recipients, are
+/// emailed from its email account. Profiles are checked every INBOX_DIGEST_INTERVAL_SECS (default 300) seconds.
+/// Nothing is posted when there was no activity, and failed digests are retried on the next check.
+pub struct InboxDigester;
+
+impl InboxDigester {
+    pub fn start(
+        db: Weak<ShinkaiDB>,
+        node_name: ShinkaiName,
+        signing_key: SigningKey,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let interval = std::env::var("INBOX_DIGEST_INTERVAL_SECS")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(300);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+                let Some(db) = db.upgrade() else {
+                    return;
+                };
+
+                let profiles = match db.get_all_profiles(node_name.clone()) {
+                    Ok(profiles) => profiles,
+                    Err(e) => {
+                        shinkai_log(
+                            ShinkaiLogOption::CronExecution,
+                            ShinkaiLogLevel::Error,
+                            &format!("Failed to get the profiles to send digests to: {}", e),
+                        );
+                        continue;
+                    }
+                };
+                for profile in profiles {
+                    let is_due = matches!(
+                        db.get_inbox_digest_schedule(&profile.full_identity_name),
+                        Ok(Some(schedule)) if schedule.is_due(Utc::now())
+                    );
+                    if !is_due {
+                        continue;
+                    }
+                    if let Err(e) = Self::send_digest(&db, &profile, &node_name, &signing_key, ws_manager.clone()).await
+                    {
+                        shinkai_log(
+                            ShinkaiLogOption::CronExecution,
+                            ShinkaiLogLevel::Error,
+                            &format!("Failed to send the digest of {}: {}", profile.full_identity_name, e),
+                        );
+                    }
+                }
+            }
+        })
+    }
+
+    /// Makes the digest of the activity of the profile since its last one and posts it. Returns the digest, or
+    /// None if the profile doesn't get digests or there was no activity to summarize.
+    pub async fn send_digest(
+        db: &Arc<ShinkaiDB>,
+        profile: &StandardIdentity,
+        node_name: &ShinkaiName,
+        signing_key: &SigningKey,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) -> Result<Option<String>, String> {
+        let profile_name = &profile.full_identity_name;
+        let Some(mut schedule) = db.get_inbox_digest_schedule(profile_name).map_err(|e| e.to_string())? else {
+            return Ok(None);
+        };
+        let now = ShinkaiStringTime::generate_time_now();
+
+        let activity = Self::collect_activity(
+            db,
+            profile.clone(),
+            schedule.inbox_name.as_deref(),
+            &schedule.last_digest_at,
+        )
+        .map_err(|e| e.to_string())?;
+        let digest = if activity.is_empty() {
+            None
+        } else {
+            let llm_provider = db
+                .get_llm_provider(&schedule.settings.llm_provider_id, profile_name)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Agent {} not found", schedule.settings.llm_provider_id))?;
+            let prompt = JobPromptGenerator::inbox_digest(activity.to_prompt_text());
+            let response = JobManager::inference_with_llm_provider(llm_provider, prompt, None, None)
+                .await
+                .map_err(|e| e.to_string())?;
+            let digest = response.response_string.trim().to_string();
+
+            Self::post_digest(
+                db,
+                &mut schedule,
+                profile_name,
+                &digest,
+                node_name,
+                signing_key,
+                ws_manager,
+            )
+            .await?;
+            if !schedule.settings.email_to.is_empty() {
+                if let Err(e) = Self::email_digest(db, &schedule.settings, profile_name, &digest).await {
+                    shinkai_log(
+                        ShinkaiLogOption::CronExecution,
+                        ShinkaiLogLevel::Error,
+                        &format!("Failed to email the digest of {}: {}", profile_name, e),
+                    );
+                }
+            }
+            Some(digest)
+        };
+
+        // The settings may have changed while the digest was written
+        if let Some(mut saved) = db.get_inbox_digest_schedule(profile_name).map_err(|e| e.to_string())? {
+            saved.inbox_name = schedule.inbox_name;
+            saved.last_digest_at = now;
+            db.set_inbox_digest_schedule(profile_name, &saved)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(digest)
+    }
+
+    /// Unread messages received by the profile after `since` and the jobs answered after it. Archived and muted
+    /// inboxes, message requests and the inbox of the digests are left out.
+    pub fn collect_activity(
+        db: &ShinkaiDB,
+        profile: StandardIdentity,
+        digest_inbox: Option<&str>,
+        since: &str,
+    ) -> Result<InboxActivity, ShinkaiDBError> {
+        let mut activity = InboxActivity::default();
+        let truncate = |content: String| content.chars().take(ACTIVITY_ITEM_MAX_CHARS).collect::<String>();
+
+        for inbox in db.get_all_smart_inboxes_for_profile(profile.clone(), false)? {
+            if inbox.is_muted || inbox.is_message_request || Some(inbox.inbox_id.as_str()) == digest_inbox {
+                continue;
+            }
+
+            match InboxName::new(inbox.inbox_id.clone()) {
+                Ok(InboxName::JobInbox { .. }) => {
+                    // Answers of the agent are sent by the node without a subidentity
+                    let Some(answer) = inbox.last_message.filter(|message| {
+                        message.external_metadata.scheduled_time.as_str() > since
+                            && message.get_sender_subidentity().unwrap_or_default().is_empty()
+                    }) else {
+                        continue;
+                    };
+                    activity
+                        .completed_jobs
+                        .push((inbox.custom_name, truncate(Self::job_message_content(&answer))));
+                }
+                Ok(InboxName::RegularInbox { .. }) => {
+                    let unread_messages = db.get_last_unread_messages_from_inbox(
+                        inbox.inbox_id.clone(),
+                        MAX_UNREAD_MESSAGES_PER_INBOX,
+                        None,
+                    )?;
+                    for message in unread_messages {
+                        if message.external_metadata.scheduled_time.as_str() <= since {
+                            continue;
+                        }
+                        let Ok(sender) = ShinkaiName::from_shinkai_message_using_sender_subidentity(&message) else {
+                            continue;
+                        };
+                        if sender.full_name == profile.full_identity_name.full_name {
+                            continue;
+                        }
+                        let Ok(content) = message.get_message_content() else {
+                            continue;
+                        };
+                        activity
+                            .unread_messages
+                            .push((inbox.custom_name.clone(), sender.full_name, truncate(content)));
+                    }
+                }
+                Err(_) => continue,
+            }
+        }
+
+        Ok(activity)
+    }
+
+    fn job_message_content(message: &ShinkaiMessage) -> String {
+        let content = message.get_message_content().unwrap_or_default();
+        match serde_json::from_str::<JobMessage>(&content) {
+            Ok(job_message) => job_message.content,
+            Err(_) => content,
+        }
+    }
+
+    /// Posts the digest to the inbox of the digests of the profile, creating it if it doesn't exist yet
+    async fn post_digest(
+        db: &Arc<ShinkaiDB>,
+        schedule: &mut InboxDigestSchedule,
+        profile: &ShinkaiName,
+        digest: &str,
+        node_name: &ShinkaiName,
+        signing_key: &SigningKey,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) -> Result<(), String> {
+        let existing_job_id = match schedule.inbox_name.clone().map(InboxName::new) {
+            Some(Ok(InboxName::JobInbox { unique_id, .. })) if db.get_job(&unique_id).is_ok() => Some(unique_id),
+            _ => None,
+        };
+        let job_id = match existing_job_id {
+            Some(job_id) => job_id,
+            None => {
+                let job_id = format!("jobid_{}", uuid::Uuid::new_v4());
+                db.create_new_job(
+                    job_id.clone(),
+                    schedule.settings.llm_provider_id.clone(),
+                    JobScope::new_default(),
+                    false,
+                )
+                .map_err(|e| e.to_string())?;
+                let inbox_name = InboxName::get_job_inbox_name_from_params(job_id.clone())
+                    .map_err(|e| e.to_string())?
+                    .to_string();
+                db.add_permission_with_profile(&inbox_name, profile.clone(), InboxPermission::Admin)
+                    .map_err(|e| e.to_string())?;
+                db.update_smart_inbox_name(&inbox_name, DIGEST_INBOX_NAME)
+                    .map_err(|e| e.to_string())?;
+                schedule.inbox_name = Some(inbox_name);
+                job_id
+            }
+        };
+
+        let message = ShinkaiMessageBuilder::job_message_from_llm_provider(
+            job_id.clone(),
+            digest.to_string(),
+            "".to_string(),
+            clone_signature_secret_key(signing_key),
+            node_name.node_name.clone(),
+            node_name.node_name.clone(),
+        )?;
+        db.add_message_to_job_inbox(&job_id, &message, None, ws_manager)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Emails the digest with the send email tool, from the email account of the profile
+    #[cfg(feature = "email")]
+    async fn email_digest(
+        db: &Arc<ShinkaiDB>,
+        settings: &InboxDigestSettings,
+        profile: &ShinkaiName,
+        digest: &str,
+    ) -> Result<(), String> {
+        use crate::tools::email_send_tool::SendEmailTool;
+        use crate::tools::native_tool::{NativeTool, NativeToolContext};
+
+        let mut args = serde_json::Map::new();
+        args.insert("to".to_string(), serde_json::json!(settings.email_to));
+        args.insert("subject".to_string(), serde_json::json!(DIGEST_INBOX_NAME));
+        args.insert("body".to_string(), serde_json::json!(digest));
+        let context = NativeToolContext {
+            profile: Some(profile.clone()),
+            db: Some(db.clone()),
+            ..Default::default()
+        };
+
+        SendEmailTool
+            .run_with_context(args, context)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(feature = "email"))]
+    async fn email_digest(
+        _db: &Arc<ShinkaiDB>,
+        _settings: &InboxDigestSettings,
+        _profile: &ShinkaiName,
+        _digest: &str,
+    ) -> Result<(), String> {
+        Err("the node was built without the email feature".to_string())
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use shinkai_message_primitives::schemas::inbox_name::InboxName;
+use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::JobMessage;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+
+use crate::db::ShinkaiDB;
+use crate::llm_provider::execution::prompts::prompts::JobPromptGenerator;
+use crate::llm_provider::job_manager::JobManager;
+
+/// How many of the first messages of an inbox are looked at to find its first exchange
+const FIRST_EXCHANGE_MESSAGES: usize = 10;
+/// Characters of the first message and of its answer that the title is generated from
+const TITLE_SOURCE_MAX_CHARS: usize = 2000;
+/// Generated titles are cut to this many characters
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Names the new job inboxes from their first exchange, replacing the truncated first message they are named
+/// after until then. Titles are generated by the summarization LLM provider if one is set, as it's meant to be a
+/// cheap model, or else by the LLM provider of the job. Runs every INBOX_TITLING_INTERVAL_SECS (default 30)
+/// seconds. Inboxes the user renames before they get a title keep the user's name.
+pub struct InboxTitler;
+
+impl InboxTitler {
+    pub fn start(db: Weak<ShinkaiDB>) -> tokio::task::JoinHandle<()> {
+        let interval = std::env::var("INBOX_TITLING_INTERVAL_SECS")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(30);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+                let Some(db) = db.upgrade() else {
+                    return;
+                };
+
+                let pending = match db.get_inboxes_pending_title() {
+                    Ok(pending) => pending,
+                    Err(e) => {
+                        shinkai_log(
+                            ShinkaiLogOption::CronExecution,
+                            ShinkaiLogLevel::Error,
+                            &format!("Failed to get the inboxes waiting for a title: {}", e),
+                        );
+                        continue;
+                    }
+                };
+                for (inbox_name, profile) in pending {
+                    if let Err(e) = Self::title_inbox(&db, &inbox_name, &profile).await {
+                        shinkai_log(
+                            ShinkaiLogOption::CronExecution,
+                            ShinkaiLogLevel::Error,
+                            &format!("Failed to generate the title of inbox {}: {}", inbox_name, e),
+                        );
+                    }
+                }
+            }
+        })
+    }
+
+    /// Generates the title of the inbox once its first message is answered, and renames the inbox with it.
+    /// Returns the title, or None if the inbox isn't answered yet, there's no LLM provider to generate it with or
+    /// the user renamed the inbox in the meantime. Failed generations aren't retried, the inbox keeps its name.
+    pub async fn title_inbox(
+        db: &Arc<ShinkaiDB>,
+        inbox_name: &str,
+        profile: &ShinkaiName,
+    ) -> Result<Option<String>, String> {
+        let Some((message, answer)) = Self::first_exchange(db, inbox_name)? else {
+            return Ok(None);
+        };
+        let title = Self::generate_title(db, inbox_name, profile, message, answer).await;
+
+        // Renaming the inbox removes it from the pending ones, and the name of the user wins
+        if !db.has_inbox_pending_title(inbox_name).map_err(|e| e.to_string())? {
+            return Ok(None);
+        }
+        match title {
+            Ok(Some(title)) => {
+                db.update_smart_inbox_name(inbox_name, &title)
+                    .map_err(|e| e.to_string())?;
+                Ok(Some(title))
+            }
+            other => {
+                db.remove_inbox_pending_title(inbox_name).map_err(|e| e.to_string())?;
+                other
+            }
+        }
+    }
+
+    async fn generate_title(
+        db: &ShinkaiDB,
+        inbox_name: &str,
+        profile: &ShinkaiName,
+        message: String,
+        answer: String,
+    ) -> Result<Option<String>, String> {
+        let Some(llm_provider) = Self::titling_llm_provider(db, inbox_name, profile)? else {
+            return Ok(None);
+        };
+
+        let prompt = JobPromptGenerator::inbox_title(message, answer);
+        let response = JobManager::inference_with_llm_provider(llm_provider, prompt, None, None)
+            .await
+            .map_err(|e| e.to_string())?;
+        let title = Self::clean_title(&response.response_string);
+        Ok(Some(title).filter(|title| !title.is_empty()))
+    }
+
+    /// The first message of the user in the inbox and the first answer to it, if it's been answered
+    fn first_exchange(db: &ShinkaiDB, inbox_name: &str) -> Result<Option<(String, String)>, String> {
+        let messages = db
+            .get_last_messages_from_inbox(inbox_name.to_string(), FIRST_EXCHANGE_MESSAGES, None)
+            .map_err(|e| e.to_string())?;
+
+        let mut message = None;
+        for branch in messages {
+            let Some(shinkai_message) = branch.first() else {
+                continue;
+            };
+            let Some(job_message) = shinkai_message
+                .get_message_content()
+                .ok()
+                .and_then(|content| serde_json::from_str::<JobMessage>(&content).ok())
+            else {
+                continue;
+            };
+            let content: String = job_message.content.chars().take(TITLE_SOURCE_MAX_CHARS).collect();
+
+            // Answers of the agent are sent by the node without a subidentity
+            let is_answer = shinkai_message.get_sender_subidentity().unwrap_or_default().is_empty();
+            if !is_answer {
+                message.get_or_insert(content);
+            } else if let Some(message) = message.take() {
+                return Ok(Some((message, content)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The summarization LLM provider when one is set, or else the LLM provider of the job of the inbox
+    fn titling_llm_provider(
+        db: &ShinkaiDB,
+        inbox_name: &str,
+        profile: &ShinkaiName,
+    ) -> Result<Option<SerializedLLMProvider>, String> {
+        if let Ok(Some(llm_provider_id)) = db.get_summarization_llm_provider() {
+            if let Ok(Some(llm_provider)) = db.get_llm_provider(&llm_provider_id, profile) {
+                return Ok(Some(llm_provider));
+            }
+        }
+
+        let InboxName::JobInbox { unique_id, .. } =
+            InboxName::new(inbox_name.to_string()).map_err(|e| e.to_string())?
+        else {
+            return Ok(None);
+        };
+        let job = db.get_job(&unique_id).map_err(|e| e.to_string())?;
+        db.get_llm_provider(&job.parent_llm_provider_id, profile)
+            .map_err(|e| e.to_string())
+    }
+
+    /// First line of the answer of the LLM, without the quotes and final punctuation models tend to add
+    pub fn clean_title(response: &str) -> String {
+        let line = response.trim().lines().next().unwrap_or_default();
+        let line = line.strip_prefix("Title:").unwrap_or(line);
+        line.trim()
+            .trim_matches(|c: char| c == '"' || c == '\'' || c == '*' || c == '#')
+            .trim_end_matches(['.', '!'])
+            .trim()
+            .chars()
+            .take(MAX_TITLE_CHARS)
+            .collect()
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use shinkai_vector_resources::vector_resource::VRPath;
+
+use crate::db::ShinkaiDB;
+use crate::schemas::notification::NotificationKind;
+use crate::vector_fs::vector_fs::VectorFS;
+use crate::vector_fs::vector_fs_integrity::ItemIntegrityReport;
+
+/// At most this many items are listed in a notification, the rest are counted
+const MAX_NOTIFIED_ITEMS: usize = 10;
+
+/// Periodically re-validates the hashes of the data stored in the VectorFS of every profile, and notifies the
+/// profiles whose items are corrupted or missing data. Runs every VECTOR_FS_INTEGRITY_CHECK_SECS (default a day)
+/// seconds. With VECTOR_FS_INTEGRITY_AUTO_REPAIR set to true, broken items of subscribed folders are deleted so
+/// that the next sync of the subscription downloads them again.
+pub struct IntegrityChecker;
+
+impl IntegrityChecker {
+    pub fn start(db: Weak<ShinkaiDB>, vector_fs: Weak<VectorFS>) -> tokio::task::JoinHandle<()> {
+        let interval = std::env::var("VECTOR_FS_INTEGRITY_CHECK_SECS")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(86400);
+        let auto_repair = std::env::var("VECTOR_FS_INTEGRITY_AUTO_REPAIR").is_ok_and(|value| value == "true");
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+                let (Some(db), Some(vector_fs)) = (db.upgrade(), vector_fs.upgrade()) else {
+                    return;
+                };
+
+                let profiles: Vec<ShinkaiName> = vector_fs.internals_map.read().await.keys().cloned().collect();
+                for profile in profiles {
+                    if let Err(e) = Self::check_profile(&db, &vector_fs, &profile, auto_repair).await {
+                        shinkai_log(
+                            ShinkaiLogOption::CronExecution,
+                            ShinkaiLogLevel::Error,
+                            &format!("Failed to check the VectorFS integrity of {}: {}", profile, e),
+                        );
+                    }
+                }
+            }
+        })
+    }
+
+    /// Verifies the items of the profile, repairs the ones that can be and notifies the profile of the problems.
+    /// Returns the reports of the broken items.
+    pub async fn check_profile(
+        db: &Arc<ShinkaiDB>,
+        vector_fs: &Arc<VectorFS>,
+        profile: &ShinkaiName,
+        auto_repair: bool,
+    ) -> Result<Vec<ItemIntegrityReport>, String> {
+        let reports = vector_fs
+            .verify_profile_integrity(profile)
+            .await
+            .map_err(|e| e.to_string())?;
+        if reports.is_empty() {
+            return Ok(reports);
+        }
+
+        let mut repaired = Vec::new();
+        if auto_repair {
+            let subscribed_folders = Self::subscribed_folders(db, profile);
+            for report in &reports {
+                if !subscribed_folders
+                    .iter()
+                    .any(|folder| report.path.starts_with(&format!("{}/", folder)))
+                {
+                    continue;
+                }
+                match Self::repair_item(vector_fs, profile, &report.path).await {
+                    Ok(()) => repaired.push(report.path.clone()),
+                    Err(e) => shinkai_log(
+                        ShinkaiLogOption::CronExecution,
+                        ShinkaiLogLevel::Error,
+                        &format!("Failed to repair {}: {}", report.path, e),
+                    ),
+                }
+            }
+        }
+
+        let mut lines: Vec<String> = reports
+            .iter()
+            .take(MAX_NOTIFIED_ITEMS)
+            .map(|report| {
+                let problems: Vec<String> = report.problems.iter().map(|problem| problem.to_string()).collect();
+                let repair = match repaired.contains(&report.path) {
+                    true => " (will be downloaded again from its subscription)",
+                    false => "",
+                };
+                format!("{}: {}{}", report.path, problems.join(", "), repair)
+            })
+            .collect();
+        if reports.len() > MAX_NOTIFIED_ITEMS {
+            lines.push(format!("and {} more", reports.len() - MAX_NOTIFIED_ITEMS));
+        }
+        let message = format!(
+            "The integrity check of the VectorFS found {} broken items:\n{}",
+            reports.len(),
+            lines.join("\n")
+        );
+        db.write_notification_of_kind(profile.clone(), NotificationKind::Integrity, message)
+            .map_err(|e| e.to_string())?;
+        Ok(reports)
+    }
+
+    /// Paths of the folders the profile's subscriptions are synced into
+    fn subscribed_folders(db: &ShinkaiDB, profile: &ShinkaiName) -> Vec<String> {
+        db.list_all_my_subscriptions()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|subscription| {
+                subscription
+                    .get_subscriber_with_profile()
+                    .is_ok_and(|subscriber| subscriber == *profile)
+            })
+            .map(|subscription| format!("/My Subscriptions{}", subscription.shared_folder.trim_end_matches('/')))
+            .collect()
+    }
+
+    /// Deletes the broken item. The subscription sync downloads whatever is missing from the subscribed folder.
+    async fn repair_item(vector_fs: &Arc<VectorFS>, profile: &ShinkaiName, path: &str) -> Result<(), String> {
+        let path = VRPath::from_string(path).map_err(|e| e.to_string())?;
+        let writer = vector_fs
+            .new_writer(profile.clone(), path, profile.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        vector_fs.delete_item(&writer).await.map_err(|e| e.to_string())
+    }
+}
@@ -1,2 +1,12 @@
+pub mod cloud_sync;
 pub mod cron_manager;
-pub mod web_scrapper;
\ No newline at end of file
+pub mod db_maintenance;
+pub mod idempotency_cleaner;
+pub mod inbox_digester;
+pub mod inbox_titler;
+pub mod integrity_checker;
+pub mod tool_usage_billing;
+pub mod web_scrapper;
+pub mod workspace_sync;
+#[cfg(feature = "email")]
+pub mod email_ingester;
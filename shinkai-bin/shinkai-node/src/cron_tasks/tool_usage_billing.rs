@@ -0,0 +1,44 @@
+use std::sync::Weak;
+use std::time::Duration;
+
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+
+use crate::db::ShinkaiDB;
+use crate::tools::tool_pricing::ToolUsageMeter;
+
+/// Periodically turns the recorded invocations of priced toolkits into invoices for the profiles that used them.
+/// Runs every TOOL_USAGE_BILLING_INTERVAL_SECS (default a day) seconds.
+pub struct ToolUsageBilling;
+
+impl ToolUsageBilling {
+    pub fn start(db: Weak<ShinkaiDB>, node_name: ShinkaiName) -> tokio::task::JoinHandle<()> {
+        let interval = std::env::var("TOOL_USAGE_BILLING_INTERVAL_SECS")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(86400);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+                let Some(db) = db.upgrade() else {
+                    return;
+                };
+
+                match ToolUsageMeter::bill(&db, &node_name) {
+                    Ok(invoices) if !invoices.is_empty() => shinkai_log(
+                        ShinkaiLogOption::CronExecution,
+                        ShinkaiLogLevel::Info,
+                        &format!("Billed the tool usage in {} invoices", invoices.len()),
+                    ),
+                    Ok(_) => {}
+                    Err(e) => shinkai_log(
+                        ShinkaiLogOption::CronExecution,
+                        ShinkaiLogLevel::Error,
+                        &format!("Failed to bill the tool usage: {}", e),
+                    ),
+                }
+            }
+        })
+    }
+}
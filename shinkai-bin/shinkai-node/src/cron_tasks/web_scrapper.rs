@@ -10,6 +10,8 @@ use shinkai_vector_resources::file_parser::unstructured_api::UnstructuredAPI;
 use uuid::Uuid;
 
 use crate::db::db_cron_task::CronTask;
+use crate::network::outbound_proxy::OUTBOUND_PROXY;
+use crate::schemas::outbound_proxy::DestinationClass;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CronTaskRequest {
@@ -67,7 +69,8 @@ impl WebScraper {
 
         // Download the content
         // eprintln!("Downloading: {}", &url);
-        let client = reqwest::Client::builder()
+        let client = OUTBOUND_PROXY
+            .client_builder(DestinationClass::WebTools, reqwest::Client::builder())
             .timeout(std::time::Duration::from_secs(60))
             .redirect(reqwest::redirect::Policy::limited(20))
             .build()?;
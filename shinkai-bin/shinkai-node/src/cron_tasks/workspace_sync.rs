@@ -0,0 +1,69 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use ed25519_dalek::SigningKey;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use tokio::sync::Mutex;
+use x25519_dalek::StaticSecret as EncryptionStaticKey;
+
+use crate::db::ShinkaiDB;
+use crate::managers::IdentityManager;
+use crate::network::node::ProxyConnectionInfo;
+use crate::network::workspace_manager::WorkspaceManager;
+use crate::network::ws_manager::WSUpdateHandler;
+
+/// Sends the messages written on this node to the workspace inboxes to the other members of the workspaces.
+/// Runs every WORKSPACE_SYNC_INTERVAL_SECS (default 30) seconds.
+pub struct WorkspaceSync;
+
+impl WorkspaceSync {
+    #[allow(clippy::too_many_arguments)]
+    pub fn start(
+        db: Weak<ShinkaiDB>,
+        node_name: ShinkaiName,
+        encryption_secret_key: EncryptionStaticKey,
+        signing_key: SigningKey,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let interval = std::env::var("WORKSPACE_SYNC_INTERVAL_SECS")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(30);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+                let Some(db) = db.upgrade() else {
+                    return;
+                };
+
+                match WorkspaceManager::sync_inbox_messages(
+                    &node_name,
+                    &encryption_secret_key,
+                    &signing_key,
+                    db,
+                    identity_manager.clone(),
+                    proxy_connection_info.clone(),
+                    ws_manager.clone(),
+                )
+                .await
+                {
+                    Ok(sent) if sent > 0 => shinkai_log(
+                        ShinkaiLogOption::CronExecution,
+                        ShinkaiLogLevel::Debug,
+                        &format!("Sent {} workspace messages to the other members", sent),
+                    ),
+                    Ok(_) => {}
+                    Err(e) => shinkai_log(
+                        ShinkaiLogOption::CronExecution,
+                        ShinkaiLogLevel::Error,
+                        &format!("Failed to sync the workspace inboxes: {}", e),
+                    ),
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use crate::schemas::calendar_account::CalendarAccountConfig;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the calendar account keys. It's padded to the 47 bytes of the NodeAndUsers prefix extractor.
+const CALENDAR_ACCOUNT_PREFIX: &str = "calendar_account_placeholder_value_to_match_pre";
+
+impl ShinkaiDB {
+    fn calendar_account_key(profile: &str) -> String {
+        format!("{}{}", CALENDAR_ACCOUNT_PREFIX, profile.to_lowercase())
+    }
+
+    /// Saves the calendar account of a profile, replacing the previous one
+    pub fn set_calendar_account(&self, account: &CalendarAccountConfig) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::calendar_account_key(&account.profile);
+        let value = serde_json::to_vec(account)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn get_calendar_account(&self, profile: &str) -> Result<CalendarAccountConfig, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::calendar_account_key(profile);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => {
+                let account: CalendarAccountConfig = serde_json::from_slice(&value)?;
+                Ok(account)
+            }
+            None => Err(ShinkaiDBError::DataNotFound),
+        }
+    }
+
+    pub fn remove_calendar_account(&self, profile: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::calendar_account_key(profile);
+
+        self.db.delete_cf(cf, key.as_bytes())?;
+        Ok(())
+    }
+}
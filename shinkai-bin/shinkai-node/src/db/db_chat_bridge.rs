@@ -0,0 +1,34 @@
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+impl ShinkaiDB {
+    fn chat_bridge_job_key(platform: &str, chat_id: &str) -> String {
+        format!("chat_bridge_job_{}_{}", platform, chat_id)
+    }
+
+    /// Job the messages of a Telegram/Discord chat are sent to
+    pub fn get_chat_bridge_job(&self, platform: &str, chat_id: &str) -> Result<Option<String>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::chat_bridge_job_key(platform, chat_id);
+
+        Ok(self
+            .db
+            .get_cf(cf, key.as_bytes())?
+            .map(|value| String::from_utf8_lossy(&value).to_string()))
+    }
+
+    pub fn set_chat_bridge_job(&self, platform: &str, chat_id: &str, job_id: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::chat_bridge_job_key(platform, chat_id);
+
+        self.db.put_cf(cf, key.as_bytes(), job_id.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn remove_chat_bridge_job(&self, platform: &str, chat_id: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::chat_bridge_job_key(platform, chat_id);
+
+        self.db.delete_cf(cf, key.as_bytes())?;
+        Ok(())
+    }
+}
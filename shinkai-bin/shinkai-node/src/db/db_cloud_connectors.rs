@@ -0,0 +1,89 @@
+use crate::schemas::cloud_connector::{CloudConnector, CloudSyncState};
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the cloud connector keys. It's padded to the 47 bytes of the NodeAndUsers prefix extractor.
+const CLOUD_CONNECTOR_PREFIX: &str = "cloud_connector_placeholder_value_to_match_pref";
+
+impl ShinkaiDB {
+    fn cloud_connector_key(id: &str) -> String {
+        format!("{}{}", CLOUD_CONNECTOR_PREFIX, id)
+    }
+
+    fn cloud_sync_state_key(id: &str) -> String {
+        format!("cloud_sync_state_{}", id)
+    }
+
+    /// Saves the connector, replacing the one with the same id
+    pub fn set_cloud_connector(&self, connector: &CloudConnector) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::cloud_connector_key(&connector.id);
+        let value = serde_json::to_vec(connector)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn get_cloud_connector(&self, id: &str) -> Result<CloudConnector, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::cloud_connector_key(id);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Err(ShinkaiDBError::DataNotFound),
+        }
+    }
+
+    pub fn get_all_cloud_connectors(&self) -> Result<Vec<CloudConnector>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, CLOUD_CONNECTOR_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(CLOUD_CONNECTOR_PREFIX.as_bytes()) {
+                break;
+            }
+            let connector: CloudConnector = serde_json::from_slice(&value)?;
+            result.push(connector);
+        }
+
+        Ok(result)
+    }
+
+    pub fn get_cloud_connectors_for_profile(&self, profile: &str) -> Result<Vec<CloudConnector>, ShinkaiDBError> {
+        Ok(self
+            .get_all_cloud_connectors()?
+            .into_iter()
+            .filter(|connector| connector.profile.eq_ignore_ascii_case(profile))
+            .collect())
+    }
+
+    /// Removes the connector and where its sync left off. The items its files were ingested as are kept.
+    pub fn remove_cloud_connector(&self, id: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        self.db.delete_cf(cf, Self::cloud_connector_key(id).as_bytes())?;
+        self.db.delete_cf(cf, Self::cloud_sync_state_key(id).as_bytes())?;
+        Ok(())
+    }
+
+    pub fn get_cloud_sync_state(&self, id: &str) -> Result<CloudSyncState, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::cloud_sync_state_key(id);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(CloudSyncState::default()),
+        }
+    }
+
+    pub fn set_cloud_sync_state(&self, id: &str, state: &CloudSyncState) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::cloud_sync_state_key(id);
+        let value = serde_json::to_vec(state)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use shinkai_message_primitives::schemas::contact::{Contact, ContactBook};
+use shinkai_message_primitives::schemas::inbox_name::InboxName;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+impl ShinkaiDB {
+    fn contact_book_key(profile: &ShinkaiName) -> String {
+        format!("contact_book_{}", profile.full_name)
+    }
+
+    /// Contact book of the profile. Profiles without contacts get an empty one, not in allowlist mode.
+    pub fn get_contact_book(&self, profile: &ShinkaiName) -> Result<ContactBook, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self.db.get_cf(cf, Self::contact_book_key(profile).as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(ContactBook::default()),
+        }
+    }
+
+    fn save_contact_book(&self, profile: &ShinkaiName, contact_book: &ContactBook) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let value = serde_json::to_vec(contact_book)?;
+
+        self.db.put_cf(cf, Self::contact_book_key(profile).as_bytes(), value)?;
+        Ok(())
+    }
+
+    /// Adds the contact to the contact book of the profile, or replaces the one with the same identity
+    pub fn set_contact(&self, profile: &ShinkaiName, contact: Contact) -> Result<(), ShinkaiDBError> {
+        let mut contact_book = self.get_contact_book(profile)?;
+        contact_book.upsert(contact);
+        self.save_contact_book(profile, &contact_book)
+    }
+
+    pub fn remove_contact(&self, profile: &ShinkaiName, identity: &str) -> Result<(), ShinkaiDBError> {
+        let mut contact_book = self.get_contact_book(profile)?;
+        if !contact_book.remove(identity) {
+            return Err(ShinkaiDBError::DataNotFound);
+        }
+        self.save_contact_book(profile, &contact_book)
+    }
+
+    /// Removes the whole contact book of the profile
+    pub fn remove_contact_book(&self, profile: &ShinkaiName) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        self.db.delete_cf(cf, Self::contact_book_key(profile).as_bytes())?;
+        Ok(())
+    }
+
+    pub fn set_contacts_allowlist_mode(&self, profile: &ShinkaiName, enabled: bool) -> Result<(), ShinkaiDBError> {
+        let mut contact_book = self.get_contact_book(profile)?;
+        contact_book.allowlist_mode = enabled;
+        self.save_contact_book(profile, &contact_book)
+    }
+
+    /// Flags the inbox of a received message as a message request if the message starts a conversation with a
+    /// profile in allowlist mode, and its sender isn't allowed by the contact book of the profile. Must be called
+    /// before the message is inserted. Returns whether the inbox was flagged.
+    pub fn flag_message_request(&self, message: &ShinkaiMessage) -> Result<bool, ShinkaiDBError> {
+        let inbox_name = message.get_message_inbox()?;
+        if !matches!(InboxName::new(inbox_name.clone())?, InboxName::RegularInbox { .. })
+            || self.does_inbox_exists(&inbox_name)?
+        {
+            return Ok(false);
+        }
+
+        let Ok(profile) = ShinkaiName::from_shinkai_message_using_recipient_subidentity(message)?.extract_profile()
+        else {
+            return Ok(false);
+        };
+        let sender = ShinkaiName::from_shinkai_message_using_sender_subidentity(message)?;
+        if self.get_contact_book(&profile)?.allows(&sender) {
+            return Ok(false);
+        }
+
+        self.set_inbox_message_request(&inbox_name, true)?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,83 @@
+use crate::schemas::email_account::EmailAccountConfig;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the email account keys. It's padded to the 47 bytes of the NodeAndUsers prefix extractor.
+const EMAIL_ACCOUNT_PREFIX: &str = "email_account_placeholder_value_to_match_prefix";
+
+impl ShinkaiDB {
+    fn email_account_key(profile: &str) -> String {
+        format!("{}{}", EMAIL_ACCOUNT_PREFIX, profile.to_lowercase())
+    }
+
+    fn email_ingest_uid_key(profile: &str, folder: &str) -> String {
+        format!("email_ingest_last_uid_{}_{}", profile.to_lowercase(), folder)
+    }
+
+    /// Saves the email account of a profile, replacing the previous one
+    pub fn set_email_account(&self, account: &EmailAccountConfig) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::email_account_key(&account.profile);
+        let value = serde_json::to_vec(account)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn get_email_account(&self, profile: &str) -> Result<EmailAccountConfig, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::email_account_key(profile);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => {
+                let account: EmailAccountConfig = serde_json::from_slice(&value)?;
+                Ok(account)
+            }
+            None => Err(ShinkaiDBError::DataNotFound),
+        }
+    }
+
+    pub fn get_all_email_accounts(&self) -> Result<Vec<EmailAccountConfig>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, EMAIL_ACCOUNT_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(EMAIL_ACCOUNT_PREFIX.as_bytes()) {
+                break;
+            }
+            let account: EmailAccountConfig = serde_json::from_slice(&value)?;
+            result.push(account);
+        }
+
+        Ok(result)
+    }
+
+    pub fn remove_email_account(&self, profile: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::email_account_key(profile);
+
+        self.db.delete_cf(cf, key.as_bytes())?;
+        Ok(())
+    }
+
+    /// UID of the last email ingested from the folder, or 0 if none was
+    pub fn get_email_ingest_last_uid(&self, profile: &str, folder: &str) -> Result<u32, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::email_ingest_uid_key(profile, folder);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(String::from_utf8_lossy(&value).parse().unwrap_or(0)),
+            None => Ok(0),
+        }
+    }
+
+    pub fn set_email_ingest_last_uid(&self, profile: &str, folder: &str, uid: u32) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::email_ingest_uid_key(profile, folder);
+
+        self.db.put_cf(cf, key.as_bytes(), uid.to_string().as_bytes())?;
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{tools::error::ToolError, vector_fs::vector_fs_error::VectorFSError};
+use crate::{network::error_code::ErrorCode, tools::error::ToolError, vector_fs::vector_fs_error::VectorFSError};
 use core::fmt;
 use shinkai_message_primitives::{
     schemas::{inbox_name::InboxNameError, shinkai_name::ShinkaiNameError},
@@ -18,6 +18,8 @@ pub enum ShinkaiDBError {
     MessageNotFound,
     CodeAlreadyUsed,
     CodeNonExistent,
+    CodeExpired,
+    CodeRevoked,
     ProfileNameAlreadyExists,
     SomeError(String),
     ProfileNameNonExistent(String),
@@ -44,6 +46,7 @@ pub enum ShinkaiDBError {
     InvalidProfileName(String),
     InvalidIdentityName(String),
     DeviceNameNonExistent(String),
+    DeviceKeyRevoked(String),
     ShinkaiNameLacksProfile,
     ToolError(ToolError),
     MessageEncodingError(String),
@@ -69,6 +72,8 @@ impl fmt::Display for ShinkaiDBError {
                 write!(f, "Registration code has already been used")
             }
             ShinkaiDBError::CodeNonExistent => write!(f, "Registration code does not exist"),
+            ShinkaiDBError::CodeExpired => write!(f, "Registration code has expired"),
+            ShinkaiDBError::CodeRevoked => write!(f, "Registration code has been revoked"),
             ShinkaiDBError::ProfileNameAlreadyExists => {
                 write!(f, "Profile name already exists")
             }
@@ -113,6 +118,7 @@ impl fmt::Display for ShinkaiDBError {
             ShinkaiDBError::ProfileNotFound(e) => write!(f, "Profile not found: {}", e),
             ShinkaiDBError::DeviceIdentityAlreadyExists(e) => write!(f, "Device identity already exists: {}", e),
             ShinkaiDBError::DeviceNameNonExistent(e) => write!(f, "Device name does not exist: {}", e),
+            ShinkaiDBError::DeviceKeyRevoked(e) => write!(f, "The keys of the device were revoked: {}", e),
             ShinkaiDBError::MessageEncodingError(e) => write!(f, "Message encoding error: {}", e),
             ShinkaiDBError::ShinkaiMessageError(e) => write!(f, "ShinkaiMessage error: {}", e),
             ShinkaiDBError::JobAlreadyExists(e) => write!(f, "Job attempted to be created, but already exists: {}", e),
@@ -130,6 +136,50 @@ impl fmt::Display for ShinkaiDBError {
     }
 }
 
+impl ShinkaiDBError {
+    /// Stable code of the error for API clients
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            ShinkaiDBError::PermissionNotFound(_)
+            | ShinkaiDBError::InvalidInboxPermission(_)
+            | ShinkaiDBError::InvalidPermissionType(_)
+            | ShinkaiDBError::InvalidPermissionsType
+            | ShinkaiDBError::DeviceKeyRevoked(_) => ErrorCode::PermissionDenied,
+            ShinkaiDBError::CodeAlreadyUsed
+            | ShinkaiDBError::CodeNonExistent
+            | ShinkaiDBError::CodeExpired
+            | ShinkaiDBError::CodeRevoked => ErrorCode::InvalidRegistrationCode,
+            ShinkaiDBError::ProfileNameAlreadyExists
+            | ShinkaiDBError::DeviceIdentityAlreadyExists(_)
+            | ShinkaiDBError::JobAlreadyExists(_) => ErrorCode::Conflict,
+            ShinkaiDBError::ProfileNameNonExistent(_) | ShinkaiDBError::ProfileNotFound(_) => {
+                ErrorCode::ProfileNotFound
+            }
+            ShinkaiDBError::InboxNotFound(_) => ErrorCode::InboxNotFound,
+            ShinkaiDBError::IdentityNotFound(_) | ShinkaiDBError::DeviceNameNonExistent(_) => {
+                ErrorCode::IdentityNotFound
+            }
+            ShinkaiDBError::MessageNotFound
+            | ShinkaiDBError::DataNotFound
+            | ShinkaiDBError::CronTaskNotFound(_)
+            | ShinkaiDBError::SheetNotFound(_) => ErrorCode::NotFound,
+            ShinkaiDBError::ToolNotFound(_) => ErrorCode::ToolNotFound,
+            ShinkaiDBError::ToolkitNotFound(_) => ErrorCode::ToolkitNotFound,
+            ShinkaiDBError::WorkflowNotFound(_) => ErrorCode::WorkflowNotFound,
+            ShinkaiDBError::ToolError(_) | ShinkaiDBError::InvalidToolType(_) => ErrorCode::ToolError,
+            ShinkaiDBError::VectorFSError(_) => ErrorCode::VecfsError,
+            ShinkaiDBError::InvalidIdentityType(_)
+            | ShinkaiDBError::InvalidProfileName(_)
+            | ShinkaiDBError::InvalidIdentityName(_)
+            | ShinkaiDBError::InvalidAttributeName(_)
+            | ShinkaiDBError::ShinkaiNameError(_)
+            | ShinkaiDBError::ShinkaiNameLacksProfile
+            | ShinkaiDBError::InboxNameError(_) => ErrorCode::InvalidInput,
+            _ => ErrorCode::DatabaseError,
+        }
+    }
+}
+
 impl std::error::Error for ShinkaiDBError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -150,6 +200,8 @@ impl PartialEq for ShinkaiDBError {
             (ShinkaiDBError::MessageNotFound, ShinkaiDBError::MessageNotFound) => true,
             (ShinkaiDBError::CodeAlreadyUsed, ShinkaiDBError::CodeAlreadyUsed) => true,
             (ShinkaiDBError::CodeNonExistent, ShinkaiDBError::CodeNonExistent) => true,
+            (ShinkaiDBError::CodeExpired, ShinkaiDBError::CodeExpired) => true,
+            (ShinkaiDBError::CodeRevoked, ShinkaiDBError::CodeRevoked) => true,
             (ShinkaiDBError::ProfileNameAlreadyExists, ShinkaiDBError::ProfileNameAlreadyExists) => true,
             (ShinkaiDBError::EncryptionKeyNonExistent, ShinkaiDBError::EncryptionKeyNonExistent) => true,
             (ShinkaiDBError::PublicKeyParseError, ShinkaiDBError::PublicKeyParseError) => true,
@@ -188,6 +240,7 @@ impl PartialEq for ShinkaiDBError {
                 msg1 == msg2
             }
             (ShinkaiDBError::DeviceNameNonExistent(msg1), ShinkaiDBError::DeviceNameNonExistent(msg2)) => msg1 == msg2,
+            (ShinkaiDBError::DeviceKeyRevoked(msg1), ShinkaiDBError::DeviceKeyRevoked(msg2)) => msg1 == msg2,
             _ => false,
         }
     }
@@ -0,0 +1,62 @@
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::FolderAgentBinding;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the folder agent keys. It's padded to the 47 bytes of the NodeAndUsers prefix extractor.
+const FOLDER_AGENT_PREFIX: &str = "folder_agent_placeholder_value_to_match_prefix_";
+
+impl ShinkaiDB {
+    fn folder_agent_key(path: &str) -> String {
+        format!("{}{}", FOLDER_AGENT_PREFIX, path)
+    }
+
+    /// Binds the default agent of the jobs created for the folder, replacing the previous one
+    pub fn set_folder_agent(&self, path: &str, agent: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let binding = FolderAgentBinding {
+            path: path.to_string(),
+            agent: agent.to_string(),
+        };
+        let value = serde_json::to_vec(&binding)?;
+
+        self.db.put_cf(cf, Self::folder_agent_key(path).as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn get_folder_agent(&self, path: &str) -> Result<Option<String>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self.db.get_cf(cf, Self::folder_agent_key(path).as_bytes())? {
+            Some(value) => {
+                let binding: FolderAgentBinding = serde_json::from_slice(&value)?;
+                Ok(Some(binding.agent))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_all_folder_agents(&self) -> Result<Vec<FolderAgentBinding>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, FOLDER_AGENT_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(FOLDER_AGENT_PREFIX.as_bytes()) {
+                break;
+            }
+            let binding: FolderAgentBinding = serde_json::from_slice(&value)?;
+            result.push(binding);
+        }
+        result.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(result)
+    }
+
+    pub fn remove_folder_agent(&self, path: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        self.db.delete_cf(cf, Self::folder_agent_key(path).as_bytes())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use shinkai_message_primitives::schemas::folder_publication::FolderPublication;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the folder publication keys. It's padded to the 47 bytes of the NodeAndUsers prefix extractor.
+const FOLDER_PUBLICATION_PREFIX: &str = "folder_publication_placeholder_value_match_abcd";
+
+impl ShinkaiDB {
+    fn folder_publication_key(publication_id: &str) -> String {
+        format!("{}{}", FOLDER_PUBLICATION_PREFIX, publication_id)
+    }
+
+    pub fn set_folder_publication(&self, publication: &FolderPublication) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let value = serde_json::to_vec(publication)?;
+
+        self.db.put_cf(
+            cf,
+            Self::folder_publication_key(&publication.publication_id).as_bytes(),
+            value,
+        )?;
+        Ok(())
+    }
+
+    pub fn get_folder_publication(&self, publication_id: &str) -> Result<FolderPublication, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self
+            .db
+            .get_cf(cf, Self::folder_publication_key(publication_id).as_bytes())?
+        {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Err(ShinkaiDBError::DataNotFound),
+        }
+    }
+
+    pub fn get_all_folder_publications(&self) -> Result<Vec<FolderPublication>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, FOLDER_PUBLICATION_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(FOLDER_PUBLICATION_PREFIX.as_bytes()) {
+                break;
+            }
+            let publication: FolderPublication = serde_json::from_slice(&value)?;
+            result.push(publication);
+        }
+        result.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+        Ok(result)
+    }
+
+    pub fn remove_folder_publication(&self, publication_id: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        self.db
+            .delete_cf(cf, Self::folder_publication_key(publication_id).as_bytes())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Keys are kept for a day, which covers any sensible retry policy
+const IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+/// A request that didn't finish in this time was interrupted (e.g. the node restarted) and can be retried
+const IN_PROGRESS_TIMEOUT_MINUTES: i64 = 10;
+
+/// Serializes the check-and-reserve of keys, so two retries arriving together don't both run
+static IDEMPOTENCY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IdempotencyRecord {
+    /// Hash of the request that used the key, so the key can't be reused for a different request
+    pub request_hash: String,
+    /// Response of the request, `None` while it's running
+    pub response: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdempotencyStatus {
+    /// First time the key is used, the request was reserved and should run
+    New,
+    /// A request with the same key is still running
+    InProgress,
+    /// The request already ran, with this response
+    Completed(Value),
+    /// The key was already used for a different request
+    Mismatch,
+}
+
+impl ShinkaiDB {
+    fn idempotency_db_key(key: &str) -> String {
+        format!("idempotency_key_{}", key)
+    }
+
+    /// Reserves the idempotency key for the request, unless it was already used.
+    pub fn start_idempotent_request(&self, key: &str, request_hash: &str) -> Result<IdempotencyStatus, ShinkaiDBError> {
+        let _lock = IDEMPOTENCY_LOCK
+            .lock()
+            .map_err(|e| ShinkaiDBError::SomeError(e.to_string()))?;
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let db_key = Self::idempotency_db_key(key);
+        let now = Utc::now();
+
+        if let Some(value) = self.db.get_cf(cf, db_key.as_bytes())? {
+            let record: IdempotencyRecord = serde_json::from_slice(&value)?;
+            let age = now - record.created_at;
+            if age < Duration::hours(IDEMPOTENCY_KEY_TTL_HOURS) {
+                if record.request_hash != request_hash {
+                    return Ok(IdempotencyStatus::Mismatch);
+                }
+                match record.response {
+                    Some(response) => return Ok(IdempotencyStatus::Completed(response)),
+                    None if age < Duration::minutes(IN_PROGRESS_TIMEOUT_MINUTES) => {
+                        return Ok(IdempotencyStatus::InProgress)
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        let record = IdempotencyRecord {
+            request_hash: request_hash.to_string(),
+            response: None,
+            created_at: now,
+        };
+        self.db.put_cf(cf, db_key.as_bytes(), serde_json::to_vec(&record)?)?;
+        Ok(IdempotencyStatus::New)
+    }
+
+    /// Stores the response of a request started with `start_idempotent_request`.
+    /// Without a response (the request failed) the key is released, so the client can retry it.
+    pub fn finish_idempotent_request(&self, key: &str, response: Option<Value>) -> Result<(), ShinkaiDBError> {
+        let _lock = IDEMPOTENCY_LOCK
+            .lock()
+            .map_err(|e| ShinkaiDBError::SomeError(e.to_string()))?;
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let db_key = Self::idempotency_db_key(key);
+
+        let Some(response) = response else {
+            self.db.delete_cf(cf, db_key.as_bytes())?;
+            return Ok(());
+        };
+        let value = self
+            .db
+            .get_cf(cf, db_key.as_bytes())?
+            .ok_or(ShinkaiDBError::DataNotFound)?;
+        let mut record: IdempotencyRecord = serde_json::from_slice(&value)?;
+        record.response = Some(response);
+        self.db.put_cf(cf, db_key.as_bytes(), serde_json::to_vec(&record)?)?;
+        Ok(())
+    }
+
+    /// Removes the keys older than a day. Returns how many were removed.
+    pub fn remove_expired_idempotency_keys(&self) -> Result<usize, ShinkaiDBError> {
+        let _lock = IDEMPOTENCY_LOCK
+            .lock()
+            .map_err(|e| ShinkaiDBError::SomeError(e.to_string()))?;
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let prefix = Self::idempotency_db_key("");
+        let expiration = Utc::now() - Duration::hours(IDEMPOTENCY_KEY_TTL_HOURS);
+
+        let mut removed = 0;
+        for item in self.db.prefix_iterator_cf(cf, prefix.as_bytes()) {
+            let (key, value) = item?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            let record: IdempotencyRecord = serde_json::from_slice(&value)?;
+            if record.created_at < expiration {
+                self.db.delete_cf(cf, &key)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use shinkai_vector_resources::utils::hash_string;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_idempotent_request_lifecycle() {
+        let db_path = format!("db_tests/{}", hash_string("idempotency"));
+        let _ = fs::remove_dir_all(Path::new(&db_path));
+        let db = ShinkaiDB::new(&db_path).unwrap();
+
+        let status = db.start_idempotent_request("create_job:key1", "hash1").unwrap();
+        assert_eq!(status, IdempotencyStatus::New);
+        let status = db.start_idempotent_request("create_job:key1", "hash1").unwrap();
+        assert_eq!(status, IdempotencyStatus::InProgress);
+
+        db.finish_idempotent_request("create_job:key1", Some(json!("jobid_123")))
+            .unwrap();
+        let status = db.start_idempotent_request("create_job:key1", "hash1").unwrap();
+        assert_eq!(status, IdempotencyStatus::Completed(json!("jobid_123")));
+        let status = db.start_idempotent_request("create_job:key1", "hash2").unwrap();
+        assert_eq!(status, IdempotencyStatus::Mismatch);
+
+        // Failed requests release the key
+        db.start_idempotent_request("create_job:key2", "hash1").unwrap();
+        db.finish_idempotent_request("create_job:key2", None).unwrap();
+        let status = db.start_idempotent_request("create_job:key2", "hash1").unwrap();
+        assert_eq!(status, IdempotencyStatus::New);
+
+        assert_eq!(db.remove_expired_idempotency_keys().unwrap(), 0);
+    }
+}
@@ -1,5 +1,6 @@
 use super::{db_errors::ShinkaiDBError, db_main::Topic, ShinkaiDB};
-use crate::schemas::identity::{DeviceIdentity, Identity, StandardIdentity, StandardIdentityType};
+use crate::schemas::identity::{DeviceIdentity, DeviceInfo, Identity, StandardIdentity, StandardIdentityType};
+use chrono::Utc;
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
 use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::IdentityPermissions;
 use shinkai_message_primitives::shinkai_utils::encryption::{
@@ -463,13 +464,25 @@ impl ShinkaiDB {
             ));
         }
 
-        // Start write batch for atomic operation
-        let mut batch = rocksdb::WriteBatch::default();
-
         // Convert the public keys to strings
         let device_signature_public_key = signature_public_key_to_string_ref(&device.device_signature_public_key);
         let device_encryption_public_key = encryption_public_key_to_string_ref(&device.device_encryption_public_key);
 
+        // Revoked keys can't come back with a new registration code
+        if self
+            .db
+            .get_cf(
+                cf_node_and_users,
+                format!("revoked_device_key_of_{}", device_signature_public_key).as_bytes(),
+            )?
+            .is_some()
+        {
+            return Err(ShinkaiDBError::DeviceKeyRevoked(device.full_identity_name.to_string()));
+        }
+
+        // Start write batch for atomic operation
+        let mut batch = rocksdb::WriteBatch::default();
+
         // Add the device information to the batch using specific prefixes
         batch.put_cf(
             cf_node_and_users,
@@ -720,6 +733,122 @@ impl ShinkaiDB {
         })
     }
 
+    /// Devices of a profile, with the name the user gave them and when they were last seen
+    pub fn get_profile_devices(&self, profile: &ShinkaiName) -> Result<Vec<DeviceInfo>, ShinkaiDBError> {
+        let profile_name = profile
+            .get_profile_name_string()
+            .ok_or(ShinkaiDBError::InvalidIdentityName(profile.to_string()))?;
+        let cf_node_and_users = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let device_key_prefix = format!("device_identity_key_of_{}/device/", profile_name);
+
+        let mut devices = Vec::new();
+        for item in self.db.iterator_cf(cf_node_and_users, rocksdb::IteratorMode::Start) {
+            let (key, _) = item?;
+            let key_str = String::from_utf8(key.to_vec()).map_err(|_| ShinkaiDBError::Utf8ConversionError)?;
+            if !key_str.starts_with(&device_key_prefix) {
+                continue;
+            }
+
+            let device_name = key_str.trim_start_matches("device_identity_key_of_");
+            let full_identity_name = format!("{}/{}", profile.get_node_name_string(), device_name);
+            let device = self.get_device(ShinkaiName::new(full_identity_name.clone())?)?;
+            devices.push(DeviceInfo {
+                full_identity_name,
+                display_name: self.get_device_string_value("device_display_name_of_", device_name)?,
+                permission_type: device.permission_type,
+                last_seen: self.get_device_string_value("device_last_seen_of_", device_name)?,
+            });
+        }
+
+        Ok(devices)
+    }
+
+    fn get_device_string_value(&self, key_prefix: &str, device_name: &str) -> Result<Option<String>, ShinkaiDBError> {
+        let cf_node_and_users = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        match self
+            .db
+            .get_cf(cf_node_and_users, format!("{}{}", key_prefix, device_name).as_bytes())?
+        {
+            Some(value) => Ok(Some(
+                String::from_utf8(value.to_vec()).map_err(|_| ShinkaiDBError::Utf8ConversionError)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Device name without the node name, erroring if the device isn't registered
+    fn registered_device_name(&self, device: &ShinkaiName) -> Result<String, ShinkaiDBError> {
+        let device_name = device
+            .get_fullname_string_without_node_name()
+            .filter(|_| device.has_device())
+            .ok_or(ShinkaiDBError::InvalidIdentityName(device.to_string()))?;
+
+        let cf_node_and_users = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        if self
+            .db
+            .get_cf(
+                cf_node_and_users,
+                format!("device_identity_key_of_{}", device_name).as_bytes(),
+            )?
+            .is_none()
+        {
+            return Err(ShinkaiDBError::DeviceNameNonExistent(device_name));
+        }
+        Ok(device_name)
+    }
+
+    /// Sets the name shown for the device. The identity name of the device doesn't change.
+    pub fn set_device_display_name(&self, device: &ShinkaiName, display_name: &str) -> Result<(), ShinkaiDBError> {
+        let device_name = self.registered_device_name(device)?;
+        let cf_node_and_users = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        self.db.put_cf(
+            cf_node_and_users,
+            format!("device_display_name_of_{}", device_name).as_bytes(),
+            display_name.as_bytes(),
+        )?;
+        Ok(())
+    }
+
+    pub fn update_device_last_seen(&self, device: &ShinkaiName) -> Result<(), ShinkaiDBError> {
+        let device_name = self.registered_device_name(device)?;
+        let cf_node_and_users = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        self.db.put_cf(
+            cf_node_and_users,
+            format!("device_last_seen_of_{}", device_name).as_bytes(),
+            Utc::now().to_rfc3339().as_bytes(),
+        )?;
+        Ok(())
+    }
+
+    /// Removes the device and marks its signature key as revoked, so it can't be registered again
+    pub fn revoke_device(&self, device: &ShinkaiName) -> Result<(), ShinkaiDBError> {
+        let device_name = self.registered_device_name(device)?;
+        let cf_node_and_users = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let device_signature_public_key = self
+            .get_device_string_value("device_identity_key_of_", &device_name)?
+            .unwrap_or_default();
+
+        // Start write batch for atomic operation
+        let mut batch = rocksdb::WriteBatch::default();
+        for key_prefix in [
+            "device_identity_key_of_",
+            "device_encryption_key_of_",
+            "device_permissions_of_",
+            "device_display_name_of_",
+            "device_last_seen_of_",
+        ] {
+            batch.delete_cf(cf_node_and_users, format!("{}{}", key_prefix, device_name).as_bytes());
+        }
+        batch.put_cf(
+            cf_node_and_users,
+            format!("revoked_device_key_of_{}", device_signature_public_key).as_bytes(),
+            Utc::now().to_rfc3339().as_bytes(),
+        );
+        self.db.write(batch)?;
+
+        Ok(())
+    }
+
     pub fn get_subidentity_encryption_public_key(
         &self,
         full_identity_name: ShinkaiName,
@@ -1,7 +1,9 @@
 use super::{db_errors::ShinkaiDBError, db_main::Topic, ShinkaiDB};
 use crate::schemas::identity::{DeviceIdentity, StandardIdentity, StandardIdentityType};
+use chrono::{DateTime, Duration, Utc};
 use ed25519_dalek::VerifyingKey;
 use rand::RngCore;
+use serde::{Deserialize, Serialize};
 use shinkai_message_primitives::schemas::shinkai_name::{ShinkaiName, ShinkaiSubidentityType};
 use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{IdentityPermissions, RegistrationCodeType};
 use shinkai_message_primitives::shinkai_utils::encryption::{
@@ -12,16 +14,19 @@ use shinkai_message_primitives::shinkai_utils::signatures::{
 };
 use x25519_dalek::PublicKey as EncryptionPublicKey;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum RegistrationCodeStatus {
     Unused,
     Used,
+    Revoked,
 }
 
 impl RegistrationCodeStatus {
     pub fn from_slice(slice: &[u8]) -> Self {
         match slice {
             b"unused" => Self::Unused,
+            b"revoked" => Self::Revoked,
             _ => Self::Used,
         }
     }
@@ -30,19 +35,35 @@ impl RegistrationCodeStatus {
         match self {
             Self::Unused => b"unused",
             Self::Used => b"used",
+            Self::Revoked => b"revoked",
         }
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
 pub struct RegistrationCodeInfo {
     pub status: RegistrationCodeStatus,
     pub permission: IdentityPermissions,
     pub code_type: RegistrationCodeType,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// The code can't be used after this time
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    /// Times the code can be used, unlimited if not set
+    #[serde(default)]
+    pub max_uses: Option<u32>,
+    #[serde(default)]
+    pub uses: u32,
 }
 
 impl RegistrationCodeInfo {
     pub fn from_slice(slice: &[u8]) -> Self {
+        if let Ok(code_info) = serde_json::from_slice(slice) {
+            return code_info;
+        }
+
+        // Codes created before the limits existed were stored as `status:permission:type[:profile]`
         let s = std::str::from_utf8(slice).unwrap();
         let parts: Vec<&str> = s.split(':').collect();
         let status = match parts.first() {
@@ -62,41 +83,22 @@ impl RegistrationCodeInfo {
             status,
             permission,
             code_type,
+            created_at: None,
+            expires_at: None,
+            max_uses: None,
+            uses: 0,
         }
     }
 
     pub fn as_bytes(&self) -> Vec<u8> {
-        match &self.code_type {
-            RegistrationCodeType::Device(device_name) => format!(
-                "{}:{}:{}:{}",
-                match self.status {
-                    RegistrationCodeStatus::Unused => "unused",
-                    RegistrationCodeStatus::Used => "used",
-                },
-                match self.permission {
-                    IdentityPermissions::Admin => "admin",
-                    IdentityPermissions::Standard => "standard",
-                    IdentityPermissions::None => "none",
-                },
-                "Device",
-                device_name
-            )
-            .into_bytes(),
-            RegistrationCodeType::Profile => format!(
-                "{}:{}:{}",
-                match self.status {
-                    RegistrationCodeStatus::Unused => "unused",
-                    RegistrationCodeStatus::Used => "used",
-                },
-                match self.permission {
-                    IdentityPermissions::Admin => "admin",
-                    IdentityPermissions::Standard => "standard",
-                    IdentityPermissions::None => "none",
-                },
-                "Profile"
-            )
-            .into_bytes(),
-        }
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .as_ref()
+            .and_then(|expires_at| DateTime::parse_from_rfc3339(expires_at).ok())
+            .is_some_and(|expires_at| expires_at < Utc::now())
     }
 }
 
@@ -105,6 +107,18 @@ impl ShinkaiDB {
         &self,
         permissions: IdentityPermissions,
         code_type: RegistrationCodeType,
+    ) -> Result<String, ShinkaiDBError> {
+        self.generate_registration_new_code_with_limits(permissions, code_type, None, None)
+    }
+
+    /// Generates a code that expires `ttl_secs` seconds from now and can be used `max_uses` times.
+    /// Without them the code can be used forever.
+    pub fn generate_registration_new_code_with_limits(
+        &self,
+        permissions: IdentityPermissions,
+        code_type: RegistrationCodeType,
+        ttl_secs: Option<u64>,
+        max_uses: Option<u32>,
     ) -> Result<String, ShinkaiDBError> {
         let mut rng = rand::thread_rng();
         let mut random_bytes = [0u8; 64];
@@ -118,10 +132,15 @@ impl ShinkaiDB {
                 "Column family NodeAndUsers not found".to_string(),
             ))?;
 
+        let now = Utc::now();
         let code_info = RegistrationCodeInfo {
             status: RegistrationCodeStatus::Unused,
             permission: permissions,
             code_type,
+            created_at: Some(now.to_rfc3339()),
+            expires_at: ttl_secs.map(|ttl_secs| (now + Duration::seconds(ttl_secs as i64)).to_rfc3339()),
+            max_uses,
+            uses: 0,
         };
 
         let prefixed_new_code = format!("registration_code_{}", new_code);
@@ -172,8 +191,11 @@ impl ShinkaiDB {
             None => return Err(ShinkaiDBError::CodeNonExistent),
         };
 
-        if code_info.status != RegistrationCodeStatus::Unused {
-            return Err(ShinkaiDBError::CodeAlreadyUsed);
+        match code_info.status {
+            RegistrationCodeStatus::Unused if code_info.is_expired() => return Err(ShinkaiDBError::CodeExpired),
+            RegistrationCodeStatus::Unused => {}
+            RegistrationCodeStatus::Used => return Err(ShinkaiDBError::CodeAlreadyUsed),
+            RegistrationCodeStatus::Revoked => return Err(ShinkaiDBError::CodeRevoked),
         }
 
         if !new_name.chars().all(|c| c.is_alphanumeric() || c == '_') {
@@ -364,6 +386,51 @@ impl ShinkaiDB {
             }
         }
 
+        self.record_registration_code_use(registration_code)
+    }
+
+    /// Counts a use of the code, marking it as used once it reached its max uses
+    fn record_registration_code_use(&self, registration_code: &str) -> Result<(), ShinkaiDBError> {
+        let cf_codes = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let prefixed_registration_code = format!("registration_code_{}", registration_code);
+        let mut code_info = self.get_registration_code_info(registration_code)?;
+
+        code_info.uses += 1;
+        if code_info.max_uses.is_some_and(|max_uses| code_info.uses >= max_uses) {
+            code_info.status = RegistrationCodeStatus::Used;
+        }
+        self.db
+            .put_cf(cf_codes, prefixed_registration_code.as_bytes(), code_info.as_bytes())?;
+        Ok(())
+    }
+
+    /// Codes that can still be used, i.e. not used up, expired or revoked
+    pub fn get_outstanding_registration_codes(&self) -> Result<Vec<(String, RegistrationCodeInfo)>, ShinkaiDBError> {
+        let cf_codes = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        let mut codes = Vec::new();
+        for item in self.db.iterator_cf(cf_codes, rocksdb::IteratorMode::Start) {
+            let (key, value) = item?;
+            let Some(code) = key.strip_prefix(b"registration_code_".as_slice()) else {
+                continue;
+            };
+            let code_info = RegistrationCodeInfo::from_slice(&value);
+            if code_info.status == RegistrationCodeStatus::Unused && !code_info.is_expired() {
+                codes.push((String::from_utf8_lossy(code).to_string(), code_info));
+            }
+        }
+
+        Ok(codes)
+    }
+
+    pub fn revoke_registration_code(&self, registration_code: &str) -> Result<(), ShinkaiDBError> {
+        let cf_codes = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let prefixed_registration_code = format!("registration_code_{}", registration_code);
+        let mut code_info = self.get_registration_code_info(registration_code)?;
+
+        code_info.status = RegistrationCodeStatus::Revoked;
+        self.db
+            .put_cf(cf_codes, prefixed_registration_code.as_bytes(), code_info.as_bytes())?;
         Ok(())
     }
 
@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+
+use crate::managers::identity_registry_cache::CachedRegistryIdentity;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+const IDENTITY_REGISTRY_CACHE_KEY: &str = "settings_identity_registry_cache";
+
+impl ShinkaiDB {
+    /// Saves the identities looked up in the registry, by identity name
+    pub fn set_identity_registry_cache(
+        &self,
+        entries: &HashMap<String, CachedRegistryIdentity>,
+    ) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let value = serde_json::to_vec(entries)?;
+
+        self.db.put_cf(cf, IDENTITY_REGISTRY_CACHE_KEY.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn get_identity_registry_cache(&self) -> Result<HashMap<String, CachedRegistryIdentity>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self.db.get_cf(cf, IDENTITY_REGISTRY_CACHE_KEY.as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(HashMap::new()),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+
+use crate::schemas::inbound_message_policy::InboundMessagePolicy;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+impl ShinkaiDB {
+    fn inbound_message_policy_key(profile: &ShinkaiName) -> String {
+        format!("inbound_message_policy_{}", profile.full_name)
+    }
+
+    /// Inbound message policy of the profile. Profiles without one don't filter the messages they receive.
+    pub fn get_inbound_message_policy(&self, profile: &ShinkaiName) -> Result<InboundMessagePolicy, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self
+            .db
+            .get_cf(cf, Self::inbound_message_policy_key(profile).as_bytes())?
+        {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(InboundMessagePolicy::default()),
+        }
+    }
+
+    pub fn set_inbound_message_policy(
+        &self,
+        profile: &ShinkaiName,
+        policy: &InboundMessagePolicy,
+    ) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let value = serde_json::to_vec(policy)?;
+
+        self.db
+            .put_cf(cf, Self::inbound_message_policy_key(profile).as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn remove_inbound_message_policy(&self, profile: &ShinkaiName) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        self.db
+            .delete_cf(cf, Self::inbound_message_policy_key(profile).as_bytes())?;
+        Ok(())
+    }
+}
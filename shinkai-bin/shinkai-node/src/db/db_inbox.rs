@@ -4,7 +4,7 @@ use chrono::DateTime;
 use chrono::Utc;
 use rocksdb::{Error, WriteBatch};
 
-use serde_json::Value;
+use serde_json::{json, Value};
 use shinkai_message_primitives::shinkai_message::shinkai_message::NodeApiData;
 use shinkai_message_primitives::{
     schemas::{inbox_name::InboxName, shinkai_name::ShinkaiName, shinkai_time::ShinkaiStringTime},
@@ -13,13 +13,18 @@ use shinkai_message_primitives::{
 };
 use tokio::sync::Mutex;
 
+use crate::network::node_events::NodeEventType;
 use crate::network::ws_manager::WSMessageType;
 use crate::network::ws_manager::WSUpdateHandler;
+use crate::network::Node;
 use crate::schemas::smart_inbox::LLMProviderSubset;
 use crate::schemas::{identity::StandardIdentity, inbox_permission::InboxPermission, smart_inbox::SmartInbox};
 
 use super::{db_main::Topic, db_errors::ShinkaiDBError, ShinkaiDB};
 
+/// Unread messages of an inbox are counted up to this many
+const MAX_UNREAD_COUNT: usize = 100;
+
 impl ShinkaiDB {
     pub fn create_empty_inbox(&self, inbox_name: String) -> Result<(), Error> {
         shinkai_log(
@@ -192,6 +197,14 @@ impl ShinkaiDB {
         }
 
         self.db.write(batch)?;
+
+        let v2_message = Node::convert_shinkai_message_to_v2_chat_message(updated_message)
+            .ok()
+            .and_then(|message| serde_json::to_value(message).ok());
+        self.events.publish(
+            NodeEventType::Message,
+            json!({ "inbox": inbox_name, "message_hash": hash_key, "message": v2_message }),
+        );
         Ok(())
     }
 
@@ -481,15 +494,32 @@ impl ShinkaiDB {
         Ok(inboxes)
     }
 
+    /// Smart inboxes of the profile, sorted by their last message. Archived inboxes are left out unless
+    /// `include_archived` is set. Muted inboxes always have an unread count of 0. Regular inboxes come with the
+    /// contact of the other participant, if the profile has one.
     pub fn get_all_smart_inboxes_for_profile(
         &self,
         profile_name_identity: StandardIdentity,
+        include_archived: bool,
     ) -> Result<Vec<SmartInbox>, ShinkaiDBError> {
         let inboxes = self.get_inboxes_for_profile(profile_name_identity.clone())?;
+        let contact_book = self.get_contact_book(&profile_name_identity.full_identity_name)?;
 
         let mut smart_inboxes = Vec::new();
 
         for inbox_id in inboxes {
+            let is_archived = self.is_inbox_archived(&inbox_id)?;
+            if is_archived && !include_archived {
+                continue;
+            }
+            let is_muted = self.is_inbox_muted(&inbox_id)?;
+            let unread_count = if is_muted {
+                0
+            } else {
+                self.get_last_unread_messages_from_inbox(inbox_id.clone(), MAX_UNREAD_COUNT, None)?
+                    .len()
+            };
+
             let last_message = self
                 .get_last_messages_from_inbox(inbox_id.clone(), 1, None)?
                 .into_iter()
@@ -547,6 +577,17 @@ impl ShinkaiDB {
                 }
             };
 
+            let organization = self.get_inbox_organization(&inbox_id)?;
+            let is_message_request = self.is_inbox_message_request(&inbox_id)?;
+            let contact = match InboxName::new(inbox_id.clone())? {
+                InboxName::RegularInbox { identities, .. } => identities
+                    .iter()
+                    .find(|identity| identity.full_name != profile_name_identity.full_identity_name.full_name)
+                    .and_then(|identity| contact_book.find(identity))
+                    .cloned(),
+                InboxName::JobInbox { .. } => None,
+            };
+
             let smart_inbox = SmartInbox {
                 inbox_id: inbox_id.clone(),
                 custom_name,
@@ -555,6 +596,13 @@ impl ShinkaiDB {
                 is_finished,
                 job_scope: job_scope_value,
                 agent: agent_subset,
+                folder: organization.folder,
+                labels: organization.labels,
+                is_archived,
+                is_muted,
+                unread_count,
+                is_message_request,
+                contact,
             };
 
             smart_inboxes.push(smart_inbox);
@@ -575,6 +623,54 @@ impl ShinkaiDB {
         Ok(smart_inboxes)
     }
 
+    /// Archived inboxes are hidden from the smart inboxes list unless requested
+    pub fn set_inbox_archived(&self, inbox_id: &str, archived: bool) -> Result<(), ShinkaiDBError> {
+        self.set_inbox_flag(inbox_id, "archived", archived)
+    }
+
+    pub fn is_inbox_archived(&self, inbox_id: &str) -> Result<bool, ShinkaiDBError> {
+        self.get_inbox_flag(inbox_id, "archived")
+    }
+
+    /// Muted inboxes don't count their unread messages
+    pub fn set_inbox_muted(&self, inbox_id: &str, muted: bool) -> Result<(), ShinkaiDBError> {
+        self.set_inbox_flag(inbox_id, "muted", muted)
+    }
+
+    pub fn is_inbox_muted(&self, inbox_id: &str) -> Result<bool, ShinkaiDBError> {
+        self.get_inbox_flag(inbox_id, "muted")
+    }
+
+    /// Message requests are conversations started by identities the profile doesn't allow yet
+    pub fn set_inbox_message_request(&self, inbox_id: &str, message_request: bool) -> Result<(), ShinkaiDBError> {
+        self.set_inbox_flag(inbox_id, "message_request", message_request)
+    }
+
+    pub fn is_inbox_message_request(&self, inbox_id: &str) -> Result<bool, ShinkaiDBError> {
+        self.get_inbox_flag(inbox_id, "message_request")
+    }
+
+    fn set_inbox_flag(&self, inbox_id: &str, flag: &str, value: bool) -> Result<(), ShinkaiDBError> {
+        let cf_inbox = self.get_cf_handle(Topic::Inbox).unwrap();
+        let inbox_flag_key = format!("{}_{}", inbox_id, flag);
+
+        // Only the set flags are stored
+        if value {
+            self.db.put_cf(cf_inbox, inbox_flag_key.as_bytes(), b"true")?;
+        } else {
+            self.db.delete_cf(cf_inbox, inbox_flag_key.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn get_inbox_flag(&self, inbox_id: &str, flag: &str) -> Result<bool, ShinkaiDBError> {
+        let cf_inbox = self.get_cf_handle(Topic::Inbox).unwrap();
+        let inbox_flag_key = format!("{}_{}", inbox_id, flag);
+
+        Ok(self.db.get_cf(cf_inbox, inbox_flag_key.as_bytes())?.is_some())
+    }
+
+    /// Renames the inbox. A pending generated title doesn't replace the new name anymore.
     pub fn update_smart_inbox_name(&self, inbox_id: &str, new_name: &str) -> Result<(), ShinkaiDBError> {
         // Fetch the column family for the Inbox topic
         let cf_inbox = self.get_cf_handle(Topic::Inbox).unwrap();
@@ -585,6 +681,7 @@ impl ShinkaiDB {
         // Update the name in the column family
         self.db
             .put_cf(cf_inbox, inbox_smart_inbox_name_key.as_bytes(), new_name.as_bytes())?;
+        self.remove_inbox_pending_title(inbox_id)?;
 
         Ok(())
     }
@@ -0,0 +1,49 @@
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+
+use crate::schemas::inbox_digest::InboxDigestSchedule;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+impl ShinkaiDB {
+    fn inbox_digest_schedule_key(profile: &ShinkaiName) -> String {
+        format!("inbox_digest_schedule_{}", profile.full_name)
+    }
+
+    /// Digest schedule of the profile, if it gets digests
+    pub fn get_inbox_digest_schedule(
+        &self,
+        profile: &ShinkaiName,
+    ) -> Result<Option<InboxDigestSchedule>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self
+            .db
+            .get_cf(cf, Self::inbox_digest_schedule_key(profile).as_bytes())?
+        {
+            Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_inbox_digest_schedule(
+        &self,
+        profile: &ShinkaiName,
+        schedule: &InboxDigestSchedule,
+    ) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let value = serde_json::to_vec(schedule)?;
+
+        self.db
+            .put_cf(cf, Self::inbox_digest_schedule_key(profile).as_bytes(), value)?;
+        Ok(())
+    }
+
+    /// Stops the digests of the profile. The inbox of the past digests is kept.
+    pub fn remove_inbox_digest_schedule(&self, profile: &ShinkaiName) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        self.db
+            .delete_cf(cf, Self::inbox_digest_schedule_key(profile).as_bytes())?;
+        Ok(())
+    }
+}
@@ -180,7 +180,7 @@ impl ShinkaiDB {
             match self.fetch_message_and_hash(&hash_key) {
                 Ok((message, added_message_hash)) => {
                     added_message_hash_tmp = Some(added_message_hash);
-                    path.push(message.clone());
+                    path.push(self.with_inbox_message_changes(&inbox_hash, message)?);
                 }
                 Err(e) => return Err(e),
             }
@@ -214,7 +214,7 @@ impl ShinkaiDB {
                             if let Ok((child_message, _)) = self.fetch_message_and_hash(&child_key) {
                                 if Some(child_message.calculate_message_hash_for_pagination()) != added_message_hash_tmp
                                 {
-                                    path.push(child_message);
+                                    path.push(self.with_inbox_message_changes(&inbox_hash, child_message)?);
                                 }
                             }
                         }
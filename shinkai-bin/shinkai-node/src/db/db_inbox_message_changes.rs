@@ -0,0 +1,102 @@
+use shinkai_message_primitives::schemas::inbox_name::InboxName;
+use shinkai_message_primitives::shinkai_message::shinkai_message::{MessageBody, ShinkaiMessage};
+
+use crate::schemas::inbox_message_change::{InboxMessageChange, MessageChangeHistory, MessageEdit};
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+impl ShinkaiDB {
+    fn inbox_message_changes_key(inbox_hash: &str, message_hash: &str) -> String {
+        format!("inbox_{}_changes_{}", inbox_hash, message_hash)
+    }
+
+    /// Edits the message of the inbox, or deletes it if the change has no new content. The stored message isn't
+    /// touched, as the hashes of the branch depend on it: the change is recorded on the side and applied when the
+    /// message is returned. Deleted messages can't be edited anymore.
+    pub fn apply_inbox_message_change(
+        &self,
+        change: &InboxMessageChange,
+    ) -> Result<MessageChangeHistory, ShinkaiDBError> {
+        let (message, _) = self.fetch_message_and_hash(&change.message_hash)?;
+        if message.get_message_inbox().ok().as_deref() != Some(change.inbox_name.as_str()) {
+            return Err(ShinkaiDBError::MessageNotFound);
+        }
+
+        let mut history = self.get_inbox_message_changes(&change.inbox_name, &change.message_hash)?;
+        if history.deleted_at.is_some() {
+            return Err(ShinkaiDBError::SomeError(format!(
+                "Message {} was deleted",
+                change.message_hash
+            )));
+        }
+        match &change.new_content {
+            Some(content) => history.edits.push(MessageEdit {
+                content: content.clone(),
+                edited_at: change.changed_at.clone(),
+            }),
+            None => {
+                history.edits.clear();
+                history.deleted_at = Some(change.changed_at.clone());
+            }
+        }
+
+        let cf = self.cf_handle(Topic::Inbox.as_str())?;
+        let inbox_hash = InboxName::new(change.inbox_name.clone())?.hash_value_first_half();
+        self.db.put_cf(
+            cf,
+            Self::inbox_message_changes_key(&inbox_hash, &change.message_hash).as_bytes(),
+            serde_json::to_vec(&history)?,
+        )?;
+        Ok(history)
+    }
+
+    /// Edits and deletion of the message of the inbox. Messages that were never changed have an empty history.
+    pub fn get_inbox_message_changes(
+        &self,
+        inbox_name: &str,
+        message_hash: &str,
+    ) -> Result<MessageChangeHistory, ShinkaiDBError> {
+        let inbox_hash = InboxName::new(inbox_name.to_string())?.hash_value_first_half();
+        self.get_inbox_message_changes_by_hash(&inbox_hash, message_hash)
+    }
+
+    fn get_inbox_message_changes_by_hash(
+        &self,
+        inbox_hash: &str,
+        message_hash: &str,
+    ) -> Result<MessageChangeHistory, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::Inbox.as_str())?;
+
+        match self
+            .db
+            .get_cf(cf, Self::inbox_message_changes_key(inbox_hash, message_hash).as_bytes())?
+        {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(MessageChangeHistory::default()),
+        }
+    }
+
+    /// Sets the last edit or the deletion of the message in its node api data, so the ones returning it can show
+    /// the change
+    pub(crate) fn with_inbox_message_changes(
+        &self,
+        inbox_hash: &str,
+        mut message: ShinkaiMessage,
+    ) -> Result<ShinkaiMessage, ShinkaiDBError> {
+        let message_hash = message.calculate_message_hash_for_pagination();
+        let history = self.get_inbox_message_changes_by_hash(inbox_hash, &message_hash)?;
+        if history == MessageChangeHistory::default() {
+            return Ok(message);
+        }
+
+        if let MessageBody::Unencrypted(body) = &mut message.body {
+            if let Some(node_api_data) = body.internal_metadata.node_api_data.as_mut() {
+                let last_edit = history.edits.last();
+                node_api_data.edited_content = last_edit.map(|edit| edit.content.clone());
+                node_api_data.edited_at = last_edit.map(|edit| edit.edited_at.clone());
+                node_api_data.deleted_at = history.deleted_at;
+            }
+        }
+        Ok(message)
+    }
+}
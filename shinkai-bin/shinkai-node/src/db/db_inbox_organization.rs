@@ -0,0 +1,138 @@
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+
+use crate::schemas::identity::StandardIdentity;
+use crate::schemas::smart_inbox::{InboxFolders, InboxOrganization};
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the keys of the job inboxes waiting for a generated title. It's padded to the 47 bytes of the Inbox
+/// prefix extractor.
+const PENDING_INBOX_TITLE_PREFIX: &str = "pending_inbox_title_placeholder_value_to_match_";
+
+impl ShinkaiDB {
+    fn pending_inbox_title_key(inbox_name: &str) -> String {
+        format!("{}{}", PENDING_INBOX_TITLE_PREFIX, inbox_name)
+    }
+
+    fn inbox_organization_key(inbox_name: &str) -> String {
+        format!("{}_organization", inbox_name)
+    }
+
+    /// Queues the job inbox of the profile to be titled from its first exchange by the inbox titler
+    pub fn add_inbox_pending_title(&self, inbox_name: &str, profile: &ShinkaiName) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::Inbox.as_str())?;
+
+        self.db.put_cf(
+            cf,
+            Self::pending_inbox_title_key(inbox_name).as_bytes(),
+            profile.to_string().as_bytes(),
+        )?;
+        Ok(())
+    }
+
+    /// Job inboxes waiting for a generated title, with the profile each one belongs to
+    pub fn get_inboxes_pending_title(&self) -> Result<Vec<(String, ShinkaiName)>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::Inbox.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, PENDING_INBOX_TITLE_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(PENDING_INBOX_TITLE_PREFIX.as_bytes()) {
+                break;
+            }
+            let inbox_name = String::from_utf8(key[PENDING_INBOX_TITLE_PREFIX.len()..].to_vec())?;
+            let profile = ShinkaiName::new(String::from_utf8(value.to_vec())?)
+                .map_err(|e| ShinkaiDBError::SomeError(e.to_string()))?;
+            result.push((inbox_name, profile));
+        }
+
+        Ok(result)
+    }
+
+    pub fn has_inbox_pending_title(&self, inbox_name: &str) -> Result<bool, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::Inbox.as_str())?;
+
+        Ok(self
+            .db
+            .get_cf(cf, Self::pending_inbox_title_key(inbox_name).as_bytes())?
+            .is_some())
+    }
+
+    pub fn remove_inbox_pending_title(&self, inbox_name: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::Inbox.as_str())?;
+
+        self.db
+            .delete_cf(cf, Self::pending_inbox_title_key(inbox_name).as_bytes())?;
+        Ok(())
+    }
+
+    /// Folder and labels of the inbox. Inboxes that were never organized are unfiled and without labels.
+    pub fn get_inbox_organization(&self, inbox_name: &str) -> Result<InboxOrganization, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::Inbox.as_str())?;
+
+        match self
+            .db
+            .get_cf(cf, Self::inbox_organization_key(inbox_name).as_bytes())?
+        {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(InboxOrganization::default()),
+        }
+    }
+
+    /// Moves the inbox into the folder, or out of any folder if None or blank. Its labels are kept.
+    pub fn set_inbox_folder(&self, inbox_name: &str, folder: Option<String>) -> Result<(), ShinkaiDBError> {
+        let mut organization = self.get_inbox_organization(inbox_name)?;
+        organization.folder = folder
+            .map(|folder| folder.trim().to_string())
+            .filter(|folder| !folder.is_empty());
+        self.save_inbox_organization(inbox_name, &organization)
+    }
+
+    /// Replaces the labels of the inbox. Blank and repeated labels are dropped.
+    pub fn set_inbox_labels(&self, inbox_name: &str, labels: Vec<String>) -> Result<(), ShinkaiDBError> {
+        let mut organization = self.get_inbox_organization(inbox_name)?;
+        organization.labels.clear();
+        for label in labels {
+            let label = label.trim().to_string();
+            if !label.is_empty() && !organization.labels.contains(&label) {
+                organization.labels.push(label);
+            }
+        }
+        self.save_inbox_organization(inbox_name, &organization)
+    }
+
+    fn save_inbox_organization(
+        &self,
+        inbox_name: &str,
+        organization: &InboxOrganization,
+    ) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::Inbox.as_str())?;
+        let value = serde_json::to_vec(organization)?;
+
+        self.db
+            .put_cf(cf, Self::inbox_organization_key(inbox_name).as_bytes(), value)?;
+        Ok(())
+    }
+
+    /// Counts the inboxes of the profile in each folder and tagged with each label
+    pub fn get_inbox_folders_for_profile(
+        &self,
+        profile_name_identity: StandardIdentity,
+    ) -> Result<InboxFolders, ShinkaiDBError> {
+        let mut inbox_folders = InboxFolders::default();
+
+        for inbox_name in self.get_inboxes_for_profile(profile_name_identity)? {
+            let organization = self.get_inbox_organization(&inbox_name)?;
+            match organization.folder {
+                Some(folder) => *inbox_folders.folders.entry(folder).or_default() += 1,
+                None => inbox_folders.unfiled += 1,
+            }
+            for label in organization.labels {
+                *inbox_folders.labels.entry(label).or_default() += 1;
+            }
+        }
+
+        Ok(inbox_folders)
+    }
+}
@@ -0,0 +1,50 @@
+use shinkai_message_primitives::schemas::payment_invoice::Invoice;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the invoice keys. It's padded to the 47 bytes of the NodeAndUsers prefix extractor.
+const INVOICE_PREFIX: &str = "payment_invoice_placeholder_value_to_match_pref";
+
+impl ShinkaiDB {
+    fn invoice_key(invoice_id: &str) -> String {
+        format!("{}{}", INVOICE_PREFIX, invoice_id)
+    }
+
+    /// Saves the invoice, replacing the one with the same id
+    pub fn set_invoice(&self, invoice: &Invoice) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let value = serde_json::to_vec(invoice)?;
+
+        self.db
+            .put_cf(cf, Self::invoice_key(&invoice.invoice_id).as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn get_invoice(&self, invoice_id: &str) -> Result<Invoice, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self.db.get_cf(cf, Self::invoice_key(invoice_id).as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Err(ShinkaiDBError::DataNotFound),
+        }
+    }
+
+    /// Invoices issued by this node and invoices it was sent, the most recent first
+    pub fn get_all_invoices(&self) -> Result<Vec<Invoice>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, INVOICE_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(INVOICE_PREFIX.as_bytes()) {
+                break;
+            }
+            let invoice: Invoice = serde_json::from_slice(&value)?;
+            result.push(invoice);
+        }
+        result.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,53 @@
+use crate::planner::task_plan::TaskPlan;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+impl ShinkaiDB {
+    fn job_plan_key(job_id: &str) -> String {
+        format!("job_plan_{}", job_id)
+    }
+
+    fn job_planning_enabled_key(job_id: &str) -> String {
+        format!("job_planning_enabled_{}", job_id)
+    }
+
+    /// The latest plan of the job
+    pub fn get_job_plan(&self, job_id: &str) -> Result<TaskPlan, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::job_plan_key(job_id);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Err(ShinkaiDBError::DataNotFound),
+        }
+    }
+
+    pub fn set_job_plan(&self, plan: &TaskPlan) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::job_plan_key(&plan.job_id);
+        let value = serde_json::to_vec(plan)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    /// Whether the messages of the job go through the planner chain
+    pub fn is_job_planning_enabled(&self, job_id: &str) -> Result<bool, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::job_planning_enabled_key(job_id);
+
+        Ok(self.db.get_cf(cf, key.as_bytes())?.is_some())
+    }
+
+    pub fn set_job_planning_enabled(&self, job_id: &str, enabled: bool) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::job_planning_enabled_key(job_id);
+
+        if enabled {
+            self.db.put_cf(cf, key.as_bytes(), b"true")?;
+        } else {
+            self.db.delete_cf(cf, key.as_bytes())?;
+        }
+        Ok(())
+    }
+}
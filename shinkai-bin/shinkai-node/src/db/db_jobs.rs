@@ -9,6 +9,8 @@ use crate::llm_provider::job::{Job, JobLike, JobStepResult};
 use crate::network::ws_manager::WSUpdateHandler;
 
 use rocksdb::{IteratorMode, WriteBatch};
+use shinkai_message_primitives::schemas::job_citation::JobCitation;
+use shinkai_message_primitives::schemas::retrieval_config::RetrievalConfig;
 use shinkai_message_primitives::schemas::{inbox_name::InboxName, shinkai_time::ShinkaiStringTime};
 use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
 use shinkai_message_primitives::shinkai_utils::job_scope::JobScope;
@@ -350,6 +352,53 @@ impl ShinkaiDB {
         Ok(())
     }
 
+    /// Replaces the retrieval parameters of a job given it's id
+    pub fn set_job_retrieval_config(&self, job_id: &str, config: &RetrievalConfig) -> Result<(), ShinkaiDBError> {
+        let cf_jobs = self.get_cf_handle(Topic::Inbox).unwrap();
+        let job_retrieval_config_key = format!("jobinbox_{}_retrieval_config", job_id);
+        self.db
+            .put_cf(cf_jobs, job_retrieval_config_key.as_bytes(), serde_json::to_vec(config)?)?;
+
+        Ok(())
+    }
+
+    /// Retrieval parameters of a job, with all fields unset if they were never changed
+    pub fn get_job_retrieval_config(&self, job_id: &str) -> Result<RetrievalConfig, ShinkaiDBError> {
+        let cf_jobs = self.get_cf_handle(Topic::Inbox).unwrap();
+        let job_retrieval_config_key = format!("jobinbox_{}_retrieval_config", job_id);
+
+        match self.db.get_cf(cf_jobs, job_retrieval_config_key.as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(RetrievalConfig::default()),
+        }
+    }
+
+    /// Saves the source content a reply of the job was based on, given the reply's message hash
+    pub fn set_job_message_citations(
+        &self,
+        job_id: &str,
+        message_hash: &str,
+        citations: &Vec<JobCitation>,
+    ) -> Result<(), ShinkaiDBError> {
+        let cf_jobs = self.get_cf_handle(Topic::Inbox).unwrap();
+        let job_citations_key = format!("jobinbox_{}_citations_{}", job_id, message_hash);
+        self.db
+            .put_cf(cf_jobs, job_citations_key.as_bytes(), serde_json::to_vec(citations)?)?;
+
+        Ok(())
+    }
+
+    /// Citations of a reply of the job. Empty if the reply wasn't based on any content of the job scope.
+    pub fn get_job_message_citations(&self, job_id: &str, message_hash: &str) -> Result<Vec<JobCitation>, ShinkaiDBError> {
+        let cf_jobs = self.get_cf_handle(Topic::Inbox).unwrap();
+        let job_citations_key = format!("jobinbox_{}_citations_{}", job_id, message_hash);
+
+        match self.db.get_cf(cf_jobs, job_citations_key.as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Fetches all jobs under a specific Agent
     pub fn get_agent_jobs(&self, agent_id: String) -> Result<Vec<Box<dyn JobLike>>, ShinkaiDBError> {
         let cf_jobs = self.get_cf_handle(Topic::Inbox).unwrap();
@@ -0,0 +1,36 @@
+use crate::tools::knowledge_base_import_tool::KnowledgeBaseImportState;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+impl ShinkaiDB {
+    fn knowledge_base_import_key(import_key: &str) -> String {
+        format!("knowledge_base_import_{}", import_key)
+    }
+
+    /// What the previous imports with the key ingested. Empty if it was never imported.
+    pub fn get_knowledge_base_import_state(
+        &self,
+        import_key: &str,
+    ) -> Result<KnowledgeBaseImportState, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::knowledge_base_import_key(import_key);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(KnowledgeBaseImportState::default()),
+        }
+    }
+
+    pub fn set_knowledge_base_import_state(
+        &self,
+        import_key: &str,
+        state: &KnowledgeBaseImportState,
+    ) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::knowledge_base_import_key(import_key);
+        let value = serde_json::to_vec(state)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+}
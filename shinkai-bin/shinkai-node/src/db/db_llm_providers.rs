@@ -113,13 +113,27 @@ impl ShinkaiDB {
         let llm_provider_key = format!("agent_placeholder_value_to_match_prefix_abcdef_{}", llm_provider_id_for_db);
     
         // Check if the llm provider exists
-        let llm_provider_exists = self.db.get_cf(cf_node_and_users, llm_provider_key.as_bytes())?.is_some();
-        if !llm_provider_exists {
-            return Err(ShinkaiDBError::DataNotFound);
-        }
+        let llm_provider_bytes = match self.db.get_cf(cf_node_and_users, llm_provider_key.as_bytes())? {
+            Some(bytes) => bytes,
+            None => return Err(ShinkaiDBError::DataNotFound),
+        };
+        let removed_model = from_slice::<SerializedLLMProvider>(&llm_provider_bytes)
+            .ok()
+            .map(|llm_provider| (llm_provider.model, llm_provider.external_url));
     
         // Delete the specific llm provider key
         self.db.delete_cf(cf_node_and_users, llm_provider_key.as_bytes())?;
+
+        // Forget the probed capabilities of the model once no other provider uses it
+        if let Some((model, external_url)) = removed_model {
+            let model_still_used = self
+                .get_all_llm_providers()?
+                .iter()
+                .any(|llm_provider| llm_provider.model == model && llm_provider.external_url == external_url);
+            if !model_still_used {
+                self.remove_probed_model_capabilities(&model, external_url.as_deref())?;
+            }
+        }
     
         Ok(())
     }
@@ -1,6 +1,8 @@
 use super::db_errors::ShinkaiDBError;
+use crate::network::node_events::NodeEventBus;
+use crate::utils::environment::is_ephemeral_node;
 use chrono::{DateTime, Utc};
-use rocksdb::{ColumnFamilyDescriptor, Error, IteratorMode, LogLevel, Options, DB};
+use rocksdb::{ColumnFamilyDescriptor, Env, Error, IteratorMode, LogLevel, Options, DB};
 use shinkai_message_primitives::{
     schemas::{shinkai_name::ShinkaiName, shinkai_time::ShinkaiStringTime},
     shinkai_message::shinkai_message::ShinkaiMessage,
@@ -50,14 +52,28 @@ impl fmt::Debug for ShinkaiDB {
 pub struct ShinkaiDB {
     pub db: DB,
     pub path: String,
+    /// Events published as data changes, for the API listeners
+    pub events: NodeEventBus,
 }
 
 impl ShinkaiDB {
     pub fn new(db_path: &str) -> Result<Self, Error> {
+        Self::open(db_path, is_ephemeral_node())
+    }
+
+    /// Opens a database kept in memory, gone once dropped. Nothing is written at the path, which only names it.
+    pub fn new_in_memory(db_path: &str) -> Result<Self, Error> {
+        Self::open(db_path, true)
+    }
+
+    fn open(db_path: &str, in_memory: bool) -> Result<Self, Error> {
         let start = Instant::now();
-        let db_opts = Self::create_cf_options(None);
+        let mut db_opts = Self::create_cf_options(None);
+        if in_memory {
+            db_opts.set_env(&Env::mem_env()?);
+        }
 
-        let cf_names = if Path::new(db_path).exists() {
+        let cf_names = if !in_memory && Path::new(db_path).exists() {
             // If the database file exists, get the list of column families from the database
             DB::list_cf(&db_opts, db_path)?
         } else {
@@ -107,6 +123,7 @@ impl ShinkaiDB {
         let shinkai_db = ShinkaiDB {
             db,
             path: db_path.to_string(),
+            events: NodeEventBus::new(),
         };
 
         Ok(shinkai_db)
@@ -0,0 +1,82 @@
+use rocksdb::DB;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+use crate::schemas::db_maintenance::{ColumnFamilySize, DbCompactionSchedule};
+
+impl ShinkaiDB {
+    /// Sizes of every column family, as estimated by RocksDB
+    pub fn column_family_sizes(&self) -> Result<Vec<ColumnFamilySize>, ShinkaiDBError> {
+        let cf_names = DB::list_cf(&Self::create_cf_options(None), &self.path)?;
+
+        let mut sizes = Vec::new();
+        for cf_name in cf_names {
+            let Some(cf) = self.db.cf_handle(&cf_name) else {
+                continue;
+            };
+            sizes.push(ColumnFamilySize::read(&cf_name, |property| {
+                self.db.property_int_value_cf(cf, property)
+            })?);
+        }
+        Ok(sizes)
+    }
+
+    /// Compacts every column family, which drops the tombstones of deleted keys and the overwritten values.
+    /// Blocks until done, so it's run off the async runtime.
+    pub fn compact(&self) -> Result<(), ShinkaiDBError> {
+        let cf_names = DB::list_cf(&Self::create_cf_options(None), &self.path)?;
+        for cf_name in cf_names {
+            if let Some(cf) = self.db.cf_handle(&cf_name) {
+                self.db.compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets when the databases are compacted on their own. By default they are only compacted on demand.
+    pub fn get_db_compaction_schedule(&self) -> Result<DbCompactionSchedule, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = b"settings_db_compaction_schedule";
+
+        match self.db.get_cf(cf, key)? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(DbCompactionSchedule::default()),
+        }
+    }
+
+    /// Updates when the databases are compacted on their own
+    pub fn update_db_compaction_schedule(&self, schedule: &DbCompactionSchedule) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = b"settings_db_compaction_schedule";
+        let value = serde_json::to_vec(schedule)?;
+
+        self.db.put_cf(cf, key, value)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shinkai_vector_resources::utils::hash_string;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_compact_and_column_family_sizes() {
+        let db_path = format!("db_tests/{}", hash_string("maintenance"));
+        let _ = fs::remove_dir_all(Path::new(&db_path));
+        let db = ShinkaiDB::new(&db_path).unwrap();
+
+        let cf = db.db.cf_handle(Topic::NodeAndUsers.as_str()).unwrap();
+        db.db.put_cf(cf, b"maintenance_test_key", b"value").unwrap();
+        db.db.delete_cf(cf, b"maintenance_test_key").unwrap();
+        db.compact().unwrap();
+
+        let sizes = db.column_family_sizes().unwrap();
+        assert!(sizes.iter().any(|size| size.name == Topic::NodeAndUsers.as_str()));
+        assert_eq!(
+            db.get_db_compaction_schedule().unwrap(),
+            DbCompactionSchedule::default()
+        );
+    }
+}
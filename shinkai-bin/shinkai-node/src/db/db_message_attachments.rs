@@ -0,0 +1,87 @@
+use std::sync::Mutex;
+
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::MessageAttachment;
+
+use crate::schemas::message_attachment::AttachmentAnnouncement;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Serializes the check-and-put of chunks and announcements, so the first one stored is the one kept
+static ATTACHMENTS_LOCK: Mutex<()> = Mutex::new(());
+
+impl ShinkaiDB {
+    fn attachment_chunk_key(attachment_id: &str, index: u32) -> String {
+        format!("attachment_{}_chunk_{}", attachment_id, index)
+    }
+
+    fn attachment_announcement_key(attachment_id: &str) -> String {
+        format!("attachment_{}_announcement", attachment_id)
+    }
+
+    /// Stores an encrypted chunk of an attachment. Chunks already stored are kept, so they can't be replaced once
+    /// received. Returns false if there was one already.
+    pub fn add_attachment_chunk(&self, attachment_id: &str, index: u32, chunk: &[u8]) -> Result<bool, ShinkaiDBError> {
+        let _lock = ATTACHMENTS_LOCK
+            .lock()
+            .map_err(|e| ShinkaiDBError::SomeError(e.to_string()))?;
+        let cf = self.cf_handle(Topic::MessageBoxSymmetricKeys.as_str())?;
+        let key = Self::attachment_chunk_key(attachment_id, index);
+
+        if self.db.get_cf(cf, key.as_bytes())?.is_some() {
+            return Ok(false);
+        }
+        self.db.put_cf(cf, key.as_bytes(), chunk)?;
+        Ok(true)
+    }
+
+    /// Records an attachment of a message received from another node. The first announcement of an attachment is
+    /// kept, so another message can't take over its chunks. Returns false if there was one already.
+    pub fn add_attachment_announcement(
+        &self,
+        attachment_id: &str,
+        announcement: &AttachmentAnnouncement,
+    ) -> Result<bool, ShinkaiDBError> {
+        let _lock = ATTACHMENTS_LOCK
+            .lock()
+            .map_err(|e| ShinkaiDBError::SomeError(e.to_string()))?;
+        let cf = self.cf_handle(Topic::MessageBoxSymmetricKeys.as_str())?;
+        let key = Self::attachment_announcement_key(attachment_id);
+
+        if self.db.get_cf(cf, key.as_bytes())?.is_some() {
+            return Ok(false);
+        }
+        self.db.put_cf(cf, key.as_bytes(), serde_json::to_vec(announcement)?)?;
+        Ok(true)
+    }
+
+    pub fn get_attachment_announcement(
+        &self,
+        attachment_id: &str,
+    ) -> Result<Option<AttachmentAnnouncement>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::MessageBoxSymmetricKeys.as_str())?;
+        let key = Self::attachment_announcement_key(attachment_id);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Encrypted chunks of the attachment in order, or None if some of them weren't received yet
+    pub fn get_attachment_chunks(
+        &self,
+        attachment: &MessageAttachment,
+    ) -> Result<Option<Vec<Vec<u8>>>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::MessageBoxSymmetricKeys.as_str())?;
+
+        let mut chunks = Vec::new();
+        for index in 0..attachment.chunk_count {
+            let key = Self::attachment_chunk_key(&attachment.attachment_id, index);
+            match self.db.get_cf(cf, key.as_bytes())? {
+                Some(chunk) => chunks.push(chunk),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(chunks))
+    }
+}
@@ -0,0 +1,70 @@
+use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::LLMProviderInterface;
+
+use crate::managers::model_capabilities_prober::ProbedModelCapabilities;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+impl ShinkaiDB {
+    /// Generates the key used to store the probed capabilities of a model.
+    /// Probed results are shared by every provider pointing to the same model interface on the same host,
+    /// as the same model name can be served with different context lengths by different hosts.
+    fn probed_model_capabilities_key(
+        model: &LLMProviderInterface,
+        external_url: Option<&str>,
+    ) -> Result<String, ShinkaiDBError> {
+        let model_str = serde_json::to_string(model)?;
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(model_str.as_bytes());
+        hasher.update(b":::");
+        hasher.update(external_url.unwrap_or_default().as_bytes());
+        let model_hash = hasher.finalize().to_hex().to_string();
+        Ok(format!("model_capabilities_probe_{}", model_hash))
+    }
+
+    /// Saves the probed capabilities of a model.
+    pub fn set_probed_model_capabilities(
+        &self,
+        model: &LLMProviderInterface,
+        external_url: Option<&str>,
+        capabilities: &ProbedModelCapabilities,
+    ) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::probed_model_capabilities_key(model, external_url)?;
+        let value = serde_json::to_vec(capabilities)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    /// Gets the probed capabilities of a model.
+    /// Returns `DataNotFound` if the model has never been probed.
+    pub fn get_probed_model_capabilities(
+        &self,
+        model: &LLMProviderInterface,
+        external_url: Option<&str>,
+    ) -> Result<ProbedModelCapabilities, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::probed_model_capabilities_key(model, external_url)?;
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => {
+                let capabilities: ProbedModelCapabilities = serde_json::from_slice(&value)?;
+                Ok(capabilities)
+            }
+            None => Err(ShinkaiDBError::DataNotFound),
+        }
+    }
+
+    /// Removes the probed capabilities of a model so it gets probed again on the next registration.
+    pub fn remove_probed_model_capabilities(
+        &self,
+        model: &LLMProviderInterface,
+        external_url: Option<&str>,
+    ) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::probed_model_capabilities_key(model, external_url)?;
+
+        self.db.delete_cf(cf, key.as_bytes())?;
+        Ok(())
+    }
+}
@@ -1,20 +1,41 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
 
+use crate::network::node_events::NodeEventType;
+use crate::schemas::notification::{NotificationKind, NotificationPreferences};
+
 use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UserNetworkNotification {
     pub message: String,
     pub datetime: DateTime<Utc>,
+    #[serde(default)]
+    pub kind: NotificationKind,
 }
 
 impl ShinkaiDB {
-    /// Writes a notification to the Inbox with a specific prefix
+    /// Writes a subscription notification to the Inbox with a specific prefix
     pub fn write_notification(&self, user_profile: ShinkaiName, message: String) -> Result<(), ShinkaiDBError> {
+        self.write_notification_of_kind(user_profile, NotificationKind::Subscription, message)
+    }
+
+    /// Writes a notification to the Inbox and pushes it to the listeners of the profile, unless the profile
+    /// disabled or silenced that kind of notifications
+    pub fn write_notification_of_kind(
+        &self,
+        user_profile: ShinkaiName,
+        kind: NotificationKind,
+        message: String,
+    ) -> Result<(), ShinkaiDBError> {
         // Get the profile name string
         let profile_name = Self::get_profile_name_string(&user_profile)?;
+        let preferences = self.get_notification_preferences(&profile_name)?;
+        if preferences.disabled_kinds.contains(&kind) {
+            return Ok(());
+        }
 
         // Calculate the half hash of the profile name
         let half_hash = Self::hex_blake3_to_half_hash(&profile_name);
@@ -32,7 +53,11 @@ impl ShinkaiDB {
         let composite_key = format!("network_notif_{}_{}", half_hash, reverse_time_key);
 
         // Create the notification struct
-        let notification = UserNetworkNotification { message, datetime };
+        let notification = UserNetworkNotification {
+            message,
+            datetime,
+            kind,
+        };
 
         // Serialize the notification
         let serialized_notification = serde_json::to_vec(&notification)?;
@@ -43,6 +68,114 @@ impl ShinkaiDB {
         // Insert the serialized notification into the "Inbox" column family using the composite key
         self.db.put_cf(inbox_cf, composite_key, serialized_notification)?;
 
+        if !preferences.silent_kinds.contains(&kind) {
+            self.events.publish(
+                NodeEventType::Notification,
+                json!({
+                    "profile": profile_name,
+                    "notification": notification,
+                    "unread_count": self.get_unread_notifications_count(&user_profile)?,
+                }),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Notifications of the profile written after it last marked them as read
+    pub fn get_unread_notifications_count(&self, user_profile: &ShinkaiName) -> Result<usize, ShinkaiDBError> {
+        let profile_name = Self::get_profile_name_string(user_profile)?;
+        let half_hash = Self::hex_blake3_to_half_hash(&profile_name);
+        let inbox_cf = self.get_cf_handle(Topic::Inbox).unwrap();
+
+        let last_read = match self.db.get_cf(inbox_cf, format!("network_notif_read_{}", half_hash))? {
+            Some(value) => Some(DateTime::parse_from_rfc3339(&String::from_utf8(value.to_vec())?)?),
+            None => None,
+        };
+
+        // Notifications are sorted from the newest
+        let prefix = format!("network_notif_{}_", half_hash);
+        let mut count = 0;
+        for item in self.db.prefix_iterator_cf(inbox_cf, prefix.as_bytes()) {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            let notification: UserNetworkNotification = serde_json::from_slice(&value)?;
+            if last_read.is_some_and(|last_read| notification.datetime <= last_read) {
+                break;
+            }
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Marks every notification of the profile written until now as read
+    pub fn mark_notifications_as_read(&self, user_profile: &ShinkaiName) -> Result<(), ShinkaiDBError> {
+        let profile_name = Self::get_profile_name_string(user_profile)?;
+        let half_hash = Self::hex_blake3_to_half_hash(&profile_name);
+        let inbox_cf = self.get_cf_handle(Topic::Inbox).unwrap();
+
+        self.db.put_cf(
+            inbox_cf,
+            format!("network_notif_read_{}", half_hash),
+            Utc::now().to_rfc3339(),
+        )?;
+
+        // Lets other clients of the profile clear their badges
+        self.events.publish(
+            NodeEventType::Notification,
+            json!({ "profile": profile_name, "unread_count": 0 }),
+        );
+        Ok(())
+    }
+
+    pub fn get_notification_preferences(&self, profile_name: &str) -> Result<NotificationPreferences, ShinkaiDBError> {
+        let half_hash = Self::hex_blake3_to_half_hash(profile_name);
+        let inbox_cf = self.get_cf_handle(Topic::Inbox).unwrap();
+
+        match self.db.get_cf(inbox_cf, format!("network_notif_prefs_{}", half_hash))? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(NotificationPreferences::default()),
+        }
+    }
+
+    pub fn set_notification_preferences(
+        &self,
+        profile_name: &str,
+        preferences: &NotificationPreferences,
+    ) -> Result<(), ShinkaiDBError> {
+        let half_hash = Self::hex_blake3_to_half_hash(profile_name);
+        let inbox_cf = self.get_cf_handle(Topic::Inbox).unwrap();
+
+        self.db.put_cf(
+            inbox_cf,
+            format!("network_notif_prefs_{}", half_hash),
+            serde_json::to_vec(preferences)?,
+        )?;
+        Ok(())
+    }
+
+    /// Removes the notifications, read marker and preferences of the profile
+    pub fn remove_notifications_for_profile(&self, user_profile: &ShinkaiName) -> Result<(), ShinkaiDBError> {
+        let profile_name = Self::get_profile_name_string(user_profile)?;
+        let half_hash = Self::hex_blake3_to_half_hash(&profile_name);
+        let inbox_cf = self.get_cf_handle(Topic::Inbox).unwrap();
+
+        let prefix = format!("network_notif_{}_", half_hash);
+        let mut batch = rocksdb::WriteBatch::default();
+        for item in self.db.prefix_iterator_cf(inbox_cf, prefix.as_bytes()) {
+            let (key, _) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            batch.delete_cf(inbox_cf, key);
+        }
+        batch.delete_cf(inbox_cf, format!("network_notif_read_{}", half_hash));
+        batch.delete_cf(inbox_cf, format!("network_notif_prefs_{}", half_hash));
+
+        self.db.write(batch)?;
         Ok(())
     }
 
@@ -76,6 +209,10 @@ impl ShinkaiDB {
         let mut notifications = Vec::new();
         for item in iter {
             let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            // Notifications of other profiles can share the prefix used by the column family
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
             let key_str = String::from_utf8(key.to_vec()).unwrap();
             if let Some(ref ts) = timestamp {
                 let ts_datetime = DateTime::parse_from_rfc3339(ts).unwrap().with_timezone(&Utc);
@@ -287,4 +424,44 @@ mod tests {
         assert_eq!(notifications[0].message, "Test message 1");
         assert_eq!(notifications[1].message, "Test message 2");
     }
+
+    #[test]
+    fn test_unread_count_and_preferences() {
+        let db = setup();
+        let user_profile = test_user();
+
+        db.write_notification(user_profile.clone(), "Test message 1".to_string())
+            .unwrap();
+        db.write_notification_of_kind(user_profile.clone(), NotificationKind::JobDone, "Job done".to_string())
+            .unwrap();
+        assert_eq!(db.get_unread_notifications_count(&user_profile).unwrap(), 2);
+
+        sleep(std::time::Duration::from_millis(1));
+        db.mark_notifications_as_read(&user_profile).unwrap();
+        assert_eq!(db.get_unread_notifications_count(&user_profile).unwrap(), 0);
+
+        let preferences = NotificationPreferences {
+            disabled_kinds: vec![NotificationKind::JobDone],
+            silent_kinds: vec![],
+        };
+        db.set_notification_preferences("main", &preferences).unwrap();
+        assert_eq!(db.get_notification_preferences("main").unwrap(), preferences);
+
+        sleep(std::time::Duration::from_millis(1));
+        db.write_notification_of_kind(user_profile.clone(), NotificationKind::JobDone, "Job done".to_string())
+            .unwrap();
+        db.write_notification_of_kind(user_profile.clone(), NotificationKind::Error, "Job failed".to_string())
+            .unwrap();
+        assert_eq!(db.get_unread_notifications_count(&user_profile).unwrap(), 1);
+
+        let notifications = db.get_last_notifications(user_profile.clone(), 1, None).unwrap();
+        assert_eq!(notifications[0].kind, NotificationKind::Error);
+
+        db.remove_notifications_for_profile(&user_profile).unwrap();
+        assert!(db.get_last_notifications(user_profile, 10, None).unwrap().is_empty());
+        assert_eq!(
+            db.get_notification_preferences("main").unwrap(),
+            NotificationPreferences::default()
+        );
+    }
 }
@@ -0,0 +1,71 @@
+use std::sync::Mutex;
+
+use chrono::Utc;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Serializes the check-and-record of tokens, so the same token sent twice at once is only accepted once
+static OIDC_TOKENS_LOCK: Mutex<()> = Mutex::new(());
+
+impl ShinkaiDB {
+    fn consumed_oidc_token_key(token_id: &str) -> String {
+        format!("oidc_consumed_token_{}", token_id)
+    }
+
+    /// Records that an ID token was used. Returns false if it was already used.
+    /// Tokens are remembered until they expire (`expires_at` is a unix timestamp), since expired tokens are
+    /// rejected anyway, so the records of the expired ones are removed along the way.
+    pub fn consume_oidc_token(&self, token_id: &str, expires_at: i64) -> Result<bool, ShinkaiDBError> {
+        let _lock = OIDC_TOKENS_LOCK
+            .lock()
+            .map_err(|e| ShinkaiDBError::SomeError(e.to_string()))?;
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let db_key = Self::consumed_oidc_token_key(token_id);
+        if self.db.get_cf(cf, db_key.as_bytes())?.is_some() {
+            return Ok(false);
+        }
+
+        let prefix = Self::consumed_oidc_token_key("");
+        let now = Utc::now().timestamp();
+        for item in self.db.prefix_iterator_cf(cf, prefix.as_bytes()) {
+            let (key, value) = item?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            let token_expires_at: i64 = serde_json::from_slice(&value)?;
+            if token_expires_at < now {
+                self.db.delete_cf(cf, &key)?;
+            }
+        }
+
+        self.db
+            .put_cf(cf, db_key.as_bytes(), serde_json::to_vec(&expires_at)?)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shinkai_vector_resources::utils::hash_string;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_consume_oidc_token() {
+        let db_path = format!("db_tests/{}", hash_string("oidc_tokens"));
+        let _ = fs::remove_dir_all(Path::new(&db_path));
+        let db = ShinkaiDB::new(&db_path).unwrap();
+        let expires_at = Utc::now().timestamp() + 300;
+
+        assert!(db.consume_oidc_token("token1", expires_at).unwrap());
+        assert!(!db.consume_oidc_token("token1", expires_at).unwrap());
+        assert!(db.consume_oidc_token("token2", expires_at).unwrap());
+
+        // Records of expired tokens are dropped
+        assert!(db.consume_oidc_token("token3", Utc::now().timestamp() - 1).unwrap());
+        assert!(db.consume_oidc_token("token4", expires_at).unwrap());
+        assert!(db.consume_oidc_token("token3", expires_at).unwrap());
+        assert!(!db.consume_oidc_token("token2", expires_at).unwrap());
+    }
+}
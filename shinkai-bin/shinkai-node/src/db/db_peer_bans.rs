@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+
+use crate::network::peer_reputation::PeerBanOverride;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+const PEER_BAN_OVERRIDES_KEY: &str = "settings_peer_ban_overrides";
+
+impl ShinkaiDB {
+    /// Saves the bans and allows set by an admin, by peer IP
+    pub fn set_peer_ban_overrides(&self, overrides: &HashMap<String, PeerBanOverride>) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let value = serde_json::to_vec(overrides)?;
+
+        self.db.put_cf(cf, PEER_BAN_OVERRIDES_KEY.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn get_peer_ban_overrides(&self) -> Result<HashMap<String, PeerBanOverride>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self.db.get_cf(cf, PEER_BAN_OVERRIDES_KEY.as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(HashMap::new()),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use shinkai_message_primitives::schemas::shinkai_network::PeerProtocol;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+impl ShinkaiDB {
+    fn peer_protocol_key(node_name: &str) -> String {
+        format!("peer_protocol_{}", node_name.to_lowercase())
+    }
+
+    /// Saves what a peer announced in its last protocol hello
+    pub fn set_peer_protocol(&self, node_name: &str, protocol: &PeerProtocol) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let value = serde_json::to_vec(protocol)?;
+
+        self.db
+            .put_cf(cf, Self::peer_protocol_key(node_name).as_bytes(), value)?;
+        Ok(())
+    }
+
+    /// What the peer announced, None if it never sent a protocol hello
+    pub fn get_peer_protocol(&self, node_name: &str) -> Result<Option<PeerProtocol>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self.db.get_cf(cf, Self::peer_protocol_key(node_name).as_bytes())? {
+            Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+}
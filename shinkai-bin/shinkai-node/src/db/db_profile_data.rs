@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+
+use rocksdb::WriteBatch;
+use shinkai_message_primitives::schemas::inbox_name::InboxName;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+impl ShinkaiDB {
+    /// Deletes inboxes with their messages and permissions. The jobs of job inboxes are deleted with them.
+    pub fn remove_inboxes(&self, inbox_names: &[String]) -> Result<(), ShinkaiDBError> {
+        let mut key_prefixes = Vec::new();
+        let mut inbox_keys = HashSet::new();
+        let mut job_ids = HashSet::new();
+        for inbox_name in inbox_names {
+            let inbox = InboxName::new(inbox_name.clone())?;
+            // Messages, children and parents of the messages
+            key_prefixes.push(format!("inbox_{}_", inbox.hash_value_first_half()));
+            // Read list, smart inbox name and permissions
+            key_prefixes.push(format!("{}_", inbox_name));
+            inbox_keys.insert(format!("inbox_placeholder_value_to_match_prefix_abcdef_{}", inbox_name));
+            if let InboxName::JobInbox { unique_id, .. } = inbox {
+                job_ids.insert(unique_id);
+            }
+        }
+
+        let mut job_hashes = Vec::new();
+        for job_id in &job_ids {
+            let job_hash = Self::job_id_to_hash(job_id);
+            key_prefixes.push(format!("jobinbox_{}_", job_id));
+            key_prefixes.push(format!("{}_smart_inbox_name", job_id));
+            key_prefixes.push(format!("jobinbox_{}_ctxt_", job_hash));
+            key_prefixes.push(format!("job_unprocess_{}_", job_hash));
+            job_hashes.push(job_hash);
+        }
+
+        let cf_inbox = self.cf_handle(Topic::Inbox.as_str())?;
+        let mut message_hashes = HashSet::new();
+        let mut batch = WriteBatch::default();
+        for item in self.db.iterator_cf(cf_inbox, rocksdb::IteratorMode::Start) {
+            let (key, value) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+
+            let is_job_key = if let Some(rest) = key_str.strip_prefix("jobinbox_agent_") {
+                job_ids.iter().any(|job_id| rest.ends_with(&format!("_{}", job_id)))
+            } else if key_str.starts_with("all_jobs_time_keyed_placeholder_to_fit_prefix__") {
+                job_ids.contains(String::from_utf8_lossy(&value).as_ref())
+            } else if let Some(rest) = key_str.strip_prefix("step_history__") {
+                job_hashes.iter().any(|job_hash| rest.contains(job_hash.as_str()))
+            } else {
+                false
+            };
+
+            if is_job_key
+                || inbox_keys.contains(key_str.as_ref())
+                || key_prefixes.iter().any(|prefix| key_str.starts_with(prefix.as_str()))
+            {
+                if key_str.starts_with("inbox_") && key_str.contains("_message_") {
+                    message_hashes.insert(value.to_vec());
+                }
+                batch.delete_cf(cf_inbox, &key);
+            }
+        }
+
+        // The messages are also keyed by hash and by time in AllMessages
+        let cf_all_messages = self.cf_handle(Topic::AllMessages.as_str())?;
+        for item in self.db.iterator_cf(cf_all_messages, rocksdb::IteratorMode::Start) {
+            let (key, value) = item?;
+            if message_hashes.contains(key.as_ref()) || message_hashes.contains(value.as_ref()) {
+                batch.delete_cf(cf_all_messages, &key);
+            }
+        }
+
+        self.db.write(batch)?;
+        Ok(())
+    }
+}
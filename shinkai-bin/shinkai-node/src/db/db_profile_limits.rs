@@ -0,0 +1,134 @@
+use crate::schemas::profile_limits::ProfileLimits;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the profile limits keys. It's padded to the 47 bytes of the NodeAndUsers prefix extractor.
+const PROFILE_LIMITS_PREFIX: &str = "profile_limits_placeholder_values_match_prefix_";
+
+impl ShinkaiDB {
+    fn profile_limits_key(profile: &str) -> String {
+        format!("{}{}", PROFILE_LIMITS_PREFIX, profile.to_lowercase())
+    }
+
+    fn profile_llm_tokens_key(profile: &str, month: &str) -> String {
+        format!("profile_llm_tokens_{}_{}", profile.to_lowercase(), month)
+    }
+
+    /// Saves the limits of a profile, replacing the previous ones
+    pub fn set_profile_limits(&self, limits: &ProfileLimits) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::profile_limits_key(&limits.profile);
+        let value = serde_json::to_vec(limits)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    /// Limits of a profile. Profiles without limits are unlimited.
+    pub fn get_profile_limits(&self, profile: &str) -> Result<ProfileLimits, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::profile_limits_key(profile);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(ProfileLimits {
+                profile: profile.to_string(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn get_all_profile_limits(&self) -> Result<Vec<ProfileLimits>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, PROFILE_LIMITS_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(PROFILE_LIMITS_PREFIX.as_bytes()) {
+                break;
+            }
+            result.push(serde_json::from_slice(&value)?);
+        }
+
+        Ok(result)
+    }
+
+    /// LLM tokens used by the profile in the month, e.g. `2024-09`
+    pub fn get_profile_llm_tokens(&self, profile: &str, month: &str) -> Result<u64, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::profile_llm_tokens_key(profile, month);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(String::from_utf8_lossy(&value).parse().unwrap_or(0)),
+            None => Ok(0),
+        }
+    }
+
+    /// Adds tokens to the usage of the profile in the month and returns the new total
+    pub fn add_profile_llm_tokens(&self, profile: &str, month: &str, tokens: u64) -> Result<u64, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::profile_llm_tokens_key(profile, month);
+
+        let total = self.get_profile_llm_tokens(profile, month)? + tokens;
+        self.db.put_cf(cf, key.as_bytes(), total.to_string().as_bytes())?;
+        Ok(total)
+    }
+
+    /// Removes the limits of a profile and its LLM token usage of every month
+    pub fn remove_profile_limits(&self, profile: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let usage_prefix = Self::profile_llm_tokens_key(profile, "");
+
+        let mut batch = rocksdb::WriteBatch::default();
+        batch.delete_cf(cf, Self::profile_limits_key(profile).as_bytes());
+        for item in self.db.iterator_cf(cf, rocksdb::IteratorMode::Start) {
+            let (key, _) = item?;
+            // The month can't have underscores, unlike the names of other profiles starting with this one
+            let is_usage_key = key
+                .strip_prefix(usage_prefix.as_bytes())
+                .is_some_and(|month| !month.contains(&b'_'));
+            if is_usage_key {
+                batch.delete_cf(cf, &key);
+            }
+        }
+        self.db.write(batch)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shinkai_vector_resources::utils::hash_string;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_profile_limits_and_usage() {
+        let db_path = format!("db_tests/{}", hash_string("profile_limits"));
+        let _ = fs::remove_dir_all(Path::new(&db_path));
+        let db = ShinkaiDB::new(&db_path).unwrap();
+
+        assert_eq!(db.get_profile_limits("main").unwrap().max_concurrent_jobs, None);
+        let limits = ProfileLimits {
+            profile: "main".to_string(),
+            max_concurrent_jobs: Some(2),
+            monthly_llm_token_budget: Some(1000),
+            ..Default::default()
+        };
+        db.set_profile_limits(&limits).unwrap();
+        assert_eq!(db.get_profile_limits("Main").unwrap(), limits);
+        assert_eq!(db.get_all_profile_limits().unwrap(), vec![limits]);
+
+        assert_eq!(db.add_profile_llm_tokens("main", "2024-09", 300).unwrap(), 300);
+        assert_eq!(db.add_profile_llm_tokens("main", "2024-09", 200).unwrap(), 500);
+        assert_eq!(db.get_profile_llm_tokens("main", "2024-10").unwrap(), 0);
+
+        db.add_profile_llm_tokens("main_2", "2024-09", 100).unwrap();
+        db.remove_profile_limits("main").unwrap();
+        assert_eq!(db.get_all_profile_limits().unwrap(), vec![]);
+        assert_eq!(db.get_profile_llm_tokens("main", "2024-09").unwrap(), 0);
+        assert_eq!(db.get_profile_llm_tokens("main_2", "2024-09").unwrap(), 100);
+    }
+}
@@ -1,5 +1,7 @@
 use shinkai_vector_resources::model_type::EmbeddingModelType;
 
+use crate::schemas::outbound_proxy::OutboundProxySettings;
+
 use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
 
 impl ShinkaiDB {
@@ -28,6 +30,56 @@ impl ShinkaiDB {
         Ok(())
     }
 
+    /// Gets whether the text chunks of ingested files are tagged with the people, organizations and dates they
+    /// mention. If the setting does not exist, it returns false by default.
+    pub fn get_entity_tagging_preference(&self) -> Result<bool, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = b"settings_entity_tagging_preference";
+
+        match self.db.get_cf(cf, key)? {
+            Some(value) => {
+                let preference: bool = serde_json::from_slice(&value)?;
+                Ok(preference)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Updates the entity tagging preference setting.
+    pub fn update_entity_tagging_preference(&self, preference: bool) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = b"settings_entity_tagging_preference";
+        let value = serde_json::to_vec(&preference)?;
+
+        self.db.put_cf(cf, key, value)?;
+        Ok(())
+    }
+
+    /// Gets the id of the LLM provider that summarizes each file ingested into the VectorFS, looked up among the
+    /// ones of the profile the file is ingested for. If the setting does not exist, files aren't summarized.
+    pub fn get_summarization_llm_provider(&self) -> Result<Option<String>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = b"settings_summarization_llm_provider";
+
+        match self.db.get_cf(cf, key)? {
+            Some(value) => {
+                let llm_provider_id: Option<String> = serde_json::from_slice(&value)?;
+                Ok(llm_provider_id)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Updates the summarization LLM provider setting. None turns summarization off.
+    pub fn update_summarization_llm_provider(&self, llm_provider_id: Option<String>) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = b"settings_summarization_llm_provider";
+        let value = serde_json::to_vec(&llm_provider_id)?;
+
+        self.db.put_cf(cf, key, value)?;
+        Ok(())
+    }
+
     /// Gets the default embedding model.
     pub fn get_default_embedding_model(&self) -> Result<EmbeddingModelType, ShinkaiDBError> {
         let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
@@ -75,4 +127,25 @@ impl ShinkaiDB {
         self.db.put_cf(cf, key, value)?;
         Ok(())
     }
+
+    /// Gets the proxies of the outbound connections. If the setting does not exist, every connection is direct.
+    pub fn get_outbound_proxy_settings(&self) -> Result<OutboundProxySettings, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = b"settings_outbound_proxy";
+
+        match self.db.get_cf(cf, key)? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(OutboundProxySettings::default()),
+        }
+    }
+
+    /// Updates the proxies of the outbound connections.
+    pub fn update_outbound_proxy_settings(&self, settings: &OutboundProxySettings) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = b"settings_outbound_proxy";
+        let value = serde_json::to_vec(settings)?;
+
+        self.db.put_cf(cf, key, value)?;
+        Ok(())
+    }
 }
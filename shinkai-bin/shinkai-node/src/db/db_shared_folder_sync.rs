@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+impl ShinkaiDB {
+    /// Keyed like the shared folders, since profiles can share folders with the same path
+    fn shared_folder_writers_key(streamer_profile: &str, path: &str) -> String {
+        format!("shared_folder_writers_{}:::{}", streamer_profile, path)
+    }
+
+    fn shared_folder_sync_bases_key(subscription_id: &str) -> String {
+        format!("shared_folder_sync_bases_{}", subscription_id)
+    }
+
+    /// Sets the nodes or profiles allowed to push changes to the folder the profile shares. An empty list turns
+    /// two-way sync off.
+    pub fn set_shared_folder_writers(
+        &self,
+        streamer_profile: &str,
+        path: &str,
+        writers: &[String],
+    ) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::shared_folder_writers_key(streamer_profile, path);
+
+        if writers.is_empty() {
+            self.db.delete_cf(cf, key.as_bytes())?;
+        } else {
+            self.db.put_cf(cf, key.as_bytes(), serde_json::to_vec(writers)?)?;
+        }
+        Ok(())
+    }
+
+    pub fn get_shared_folder_writers(&self, streamer_profile: &str, path: &str) -> Result<Vec<String>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self
+            .db
+            .get_cf(cf, Self::shared_folder_writers_key(streamer_profile, path).as_bytes())?
+        {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Last modification of the items of a subscription as last synced from the node sharing the folder,
+    /// used as the base of the changes pushed back to it
+    pub fn get_shared_folder_sync_bases(
+        &self,
+        subscription_id: &str,
+    ) -> Result<HashMap<String, DateTime<Utc>>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self
+            .db
+            .get_cf(cf, Self::shared_folder_sync_bases_key(subscription_id).as_bytes())?
+        {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    pub fn set_shared_folder_sync_bases(
+        &self,
+        subscription_id: &str,
+        bases: &HashMap<String, DateTime<Utc>>,
+    ) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let value = serde_json::to_vec(bases)?;
+
+        self.db.put_cf(
+            cf,
+            Self::shared_folder_sync_bases_key(subscription_id).as_bytes(),
+            value,
+        )?;
+        Ok(())
+    }
+}
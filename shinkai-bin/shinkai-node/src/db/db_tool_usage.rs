@@ -0,0 +1,64 @@
+use crate::tools::tool_pricing::{ToolUsageAllowance, ToolUsageRecord};
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the tool usage record keys. It's padded to the 47 bytes of the NodeAndUsers prefix extractor.
+const TOOL_USAGE_RECORD_PREFIX: &str = "tool_usage_record_placeholder_value_to_match_pr";
+
+impl ShinkaiDB {
+    fn tool_usage_allowance_key(consumer: &str, asset: &str) -> String {
+        format!("tool_usage_allowance_{}_{}", consumer, asset.to_uppercase())
+    }
+
+    /// Saves the usage record, replacing the one with the same id
+    pub fn set_tool_usage_record(&self, record: &ToolUsageRecord) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = format!("{}{}", TOOL_USAGE_RECORD_PREFIX, record.usage_id);
+        let value = serde_json::to_vec(record)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    /// Usage of the priced toolkits, the most recent first
+    pub fn get_all_tool_usage_records(&self) -> Result<Vec<ToolUsageRecord>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, TOOL_USAGE_RECORD_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(TOOL_USAGE_RECORD_PREFIX.as_bytes()) {
+                break;
+            }
+            let record: ToolUsageRecord = serde_json::from_slice(&value)?;
+            result.push(record);
+        }
+        result.sort_by(|a, b| b.invoked_at.cmp(&a.invoked_at));
+
+        Ok(result)
+    }
+
+    pub fn get_tool_usage_allowance(
+        &self,
+        consumer: &str,
+        asset: &str,
+    ) -> Result<Option<ToolUsageAllowance>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::tool_usage_allowance_key(consumer, asset);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_tool_usage_allowance(&self, allowance: &ToolUsageAllowance) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::tool_usage_allowance_key(&allowance.consumer, &allowance.asset);
+        let value = serde_json::to_vec(allowance)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use crate::tools::js_toolkit_headers::ToolkitCapabilityGrants;
+use crate::tools::tool_execution_limits::ToolExecutionLimits;
+use crate::tools::tool_pricing::ToolPricing;
+use crate::tools::toolkit_package::ToolkitProvenance;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the toolkit provenance keys. It's padded to the 47 bytes of the NodeAndUsers prefix extractor.
+const TOOLKIT_PROVENANCE_PREFIX: &str = "toolkit_provenance_placeholder_to_match_prefix_";
+
+impl ShinkaiDB {
+    fn toolkit_provenance_key(toolkit_name: &str) -> String {
+        format!("{}{}", TOOLKIT_PROVENANCE_PREFIX, toolkit_name.to_lowercase())
+    }
+
+    /// Saves where an installed toolkit came from. Reinstalling a toolkit overwrites its previous record.
+    pub fn set_toolkit_provenance(&self, provenance: &ToolkitProvenance) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::toolkit_provenance_key(&provenance.toolkit_name);
+        let value = serde_json::to_vec(provenance)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn get_toolkit_provenance(&self, toolkit_name: &str) -> Result<ToolkitProvenance, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::toolkit_provenance_key(toolkit_name);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => {
+                let provenance: ToolkitProvenance = serde_json::from_slice(&value)?;
+                Ok(provenance)
+            }
+            None => Err(ShinkaiDBError::ToolkitNotFound(toolkit_name.to_string())),
+        }
+    }
+
+    pub fn get_all_toolkit_provenances(&self) -> Result<Vec<ToolkitProvenance>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, TOOLKIT_PROVENANCE_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(TOOLKIT_PROVENANCE_PREFIX.as_bytes()) {
+                break;
+            }
+            let provenance: ToolkitProvenance = serde_json::from_slice(&value)?;
+            result.push(provenance);
+        }
+
+        Ok(result)
+    }
+
+    pub fn remove_toolkit_provenance(&self, toolkit_name: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::toolkit_provenance_key(toolkit_name);
+
+        self.db.delete_cf(cf, key.as_bytes())?;
+        Ok(())
+    }
+
+    fn toolkit_capabilities_key(toolkit_name: &str) -> String {
+        format!("toolkit_capabilities_{}", toolkit_name.to_lowercase())
+    }
+
+    /// Gets the capabilities granted to a toolkit. Toolkits without grants get an empty list.
+    pub fn get_toolkit_capability_grants(&self, toolkit_name: &str) -> Result<ToolkitCapabilityGrants, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::toolkit_capabilities_key(toolkit_name);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => {
+                let grants: ToolkitCapabilityGrants = serde_json::from_slice(&value)?;
+                Ok(grants)
+            }
+            None => Ok(ToolkitCapabilityGrants {
+                toolkit_name: toolkit_name.to_string(),
+                granted: vec![],
+            }),
+        }
+    }
+
+    pub fn set_toolkit_capability_grants(&self, grants: &ToolkitCapabilityGrants) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::toolkit_capabilities_key(&grants.toolkit_name);
+        let value = serde_json::to_vec(grants)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    fn toolkit_execution_limits_key(toolkit_name: &str) -> String {
+        format!("toolkit_execution_limits_{}", toolkit_name.to_lowercase())
+    }
+
+    /// Gets the execution limits of a toolkit, or the node defaults if none were set
+    pub fn get_toolkit_execution_limits(&self, toolkit_name: &str) -> Result<ToolExecutionLimits, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::toolkit_execution_limits_key(toolkit_name);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => {
+                let limits: ToolExecutionLimits = serde_json::from_slice(&value)?;
+                Ok(limits)
+            }
+            None => Ok(ToolExecutionLimits::default()),
+        }
+    }
+
+    pub fn set_toolkit_execution_limits(
+        &self,
+        toolkit_name: &str,
+        limits: &ToolExecutionLimits,
+    ) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::toolkit_execution_limits_key(toolkit_name);
+        let value = serde_json::to_vec(limits)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    fn toolkit_pricing_key(toolkit_name: &str) -> String {
+        format!("toolkit_pricing_{}", toolkit_name.to_lowercase())
+    }
+
+    /// The price of the invocations of a toolkit, `None` if its tools are free
+    pub fn get_toolkit_pricing(&self, toolkit_name: &str) -> Result<Option<ToolPricing>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::toolkit_pricing_key(toolkit_name);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_toolkit_pricing(&self, toolkit_name: &str, pricing: &ToolPricing) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::toolkit_pricing_key(toolkit_name);
+        let value = serde_json::to_vec(pricing)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn remove_toolkit_pricing(&self, toolkit_name: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::toolkit_pricing_key(toolkit_name);
+
+        self.db.delete_cf(cf, key.as_bytes())?;
+        Ok(())
+    }
+}
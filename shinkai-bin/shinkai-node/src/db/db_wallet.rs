@@ -0,0 +1,76 @@
+use crate::schemas::wallet::{WalletConfig, WalletTransaction};
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the wallet transaction keys. It's padded to the 47 bytes of the NodeAndUsers prefix extractor.
+const WALLET_TRANSACTION_PREFIX: &str = "wallet_transaction_placeholder_value_to_match_p";
+
+impl ShinkaiDB {
+    fn wallet_transaction_key(transaction_id: &str) -> String {
+        format!("{}{}", WALLET_TRANSACTION_PREFIX, transaction_id)
+    }
+
+    /// The wallet of the node, if one was set up
+    pub fn get_wallet_config(&self) -> Result<Option<WalletConfig>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = b"settings_wallet";
+
+        match self.db.get_cf(cf, key)? {
+            Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_wallet_config(&self, config: &WalletConfig) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = b"settings_wallet";
+        let value = serde_json::to_vec(config)?;
+
+        self.db.put_cf(cf, key, value)?;
+        Ok(())
+    }
+
+    /// Saves the transaction, replacing the one with the same id
+    pub fn set_wallet_transaction(&self, transaction: &WalletTransaction) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let value = serde_json::to_vec(transaction)?;
+
+        self.db.put_cf(
+            cf,
+            Self::wallet_transaction_key(&transaction.transaction_id).as_bytes(),
+            value,
+        )?;
+        Ok(())
+    }
+
+    pub fn get_wallet_transaction(&self, transaction_id: &str) -> Result<WalletTransaction, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self
+            .db
+            .get_cf(cf, Self::wallet_transaction_key(transaction_id).as_bytes())?
+        {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Err(ShinkaiDBError::DataNotFound),
+        }
+    }
+
+    /// Transactions made by the wallet, the most recent first
+    pub fn get_all_wallet_transactions(&self) -> Result<Vec<WalletTransaction>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, WALLET_TRANSACTION_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(WALLET_TRANSACTION_PREFIX.as_bytes()) {
+                break;
+            }
+            let transaction: WalletTransaction = serde_json::from_slice(&value)?;
+            result.push(transaction);
+        }
+        result.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use crate::schemas::watched_folder::WatchedFolder;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the watched folder keys. It's padded to the 47 bytes of the NodeAndUsers prefix extractor.
+const WATCHED_FOLDER_PREFIX: &str = "watched_folder_placeholder_value_to_match_prefx";
+
+impl ShinkaiDB {
+    fn watched_folder_key(id: &str) -> String {
+        format!("{}{}", WATCHED_FOLDER_PREFIX, id)
+    }
+
+    fn watched_folder_files_key(id: &str) -> String {
+        format!("watched_folder_files_{}", id)
+    }
+
+    pub fn add_watched_folder(&self, folder: &WatchedFolder) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::watched_folder_key(&folder.id);
+        let value = serde_json::to_vec(folder)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn get_all_watched_folders(&self) -> Result<Vec<WatchedFolder>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, WATCHED_FOLDER_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(WATCHED_FOLDER_PREFIX.as_bytes()) {
+                break;
+            }
+            let folder: WatchedFolder = serde_json::from_slice(&value)?;
+            result.push(folder);
+        }
+
+        Ok(result)
+    }
+
+    pub fn get_watched_folders_for_profile(&self, profile: &str) -> Result<Vec<WatchedFolder>, ShinkaiDBError> {
+        Ok(self
+            .get_all_watched_folders()?
+            .into_iter()
+            .filter(|folder| folder.profile.eq_ignore_ascii_case(profile))
+            .collect())
+    }
+
+    /// Removes the watched folder and what's known of its synced files. The items they were ingested as are kept.
+    pub fn remove_watched_folder(&self, id: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        self.db.delete_cf(cf, Self::watched_folder_key(id).as_bytes())?;
+        self.db.delete_cf(cf, Self::watched_folder_files_key(id).as_bytes())?;
+        Ok(())
+    }
+
+    /// Content hashes of the files synced from the watched folder, by path relative to its local directory
+    pub fn get_watched_folder_files(&self, id: &str) -> Result<HashMap<String, String>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::watched_folder_files_key(id);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    pub fn set_watched_folder_files(&self, id: &str, files: &HashMap<String, String>) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::watched_folder_files_key(id);
+        let value = serde_json::to_vec(files)?;
+
+        self.db.put_cf(cf, key.as_bytes(), value)?;
+        Ok(())
+    }
+}
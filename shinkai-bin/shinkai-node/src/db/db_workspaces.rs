@@ -0,0 +1,153 @@
+use shinkai_message_primitives::schemas::workspace::Workspace;
+
+use super::{db_errors::ShinkaiDBError, ShinkaiDB, Topic};
+
+/// Prefix of the workspace keys. It's padded to the 47 bytes of the NodeAndUsers prefix extractor.
+const WORKSPACE_PREFIX: &str = "workspace_placeholder_value_to_match_prefix_abc";
+/// Prefix of the workspaces sent by other nodes and not accepted yet, padded like `WORKSPACE_PREFIX`
+const WORKSPACE_INVITATION_PREFIX: &str = "workspace_invitation_placeholder_to_match_abcde";
+
+impl ShinkaiDB {
+    fn workspace_key(workspace_id: &str) -> String {
+        format!("{}{}", WORKSPACE_PREFIX, workspace_id)
+    }
+
+    fn workspace_invitation_key(workspace_id: &str) -> String {
+        format!("{}{}", WORKSPACE_INVITATION_PREFIX, workspace_id)
+    }
+
+    fn workspace_sync_cursor_key(workspace_id: &str, inbox_name: &str) -> String {
+        format!("workspace_sync_cursor_{}_{}", workspace_id, inbox_name)
+    }
+
+    /// Saves the workspace, replacing the one with the same id
+    pub fn set_workspace(&self, workspace: &Workspace) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let value = serde_json::to_vec(workspace)?;
+
+        self.db
+            .put_cf(cf, Self::workspace_key(&workspace.workspace_id).as_bytes(), value)?;
+        Ok(())
+    }
+
+    pub fn get_workspace(&self, workspace_id: &str) -> Result<Workspace, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        match self.db.get_cf(cf, Self::workspace_key(workspace_id).as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Err(ShinkaiDBError::DataNotFound),
+        }
+    }
+
+    /// Workspaces created by this node and the ones it's a member of
+    pub fn get_all_workspaces(&self) -> Result<Vec<Workspace>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, WORKSPACE_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(WORKSPACE_PREFIX.as_bytes()) {
+                break;
+            }
+            let workspace: Workspace = serde_json::from_slice(&value)?;
+            result.push(workspace);
+        }
+        result.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(result)
+    }
+
+    pub fn remove_workspace(&self, workspace_id: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        self.db.delete_cf(cf, Self::workspace_key(workspace_id).as_bytes())?;
+        Ok(())
+    }
+
+    /// Saves a workspace this node was invited to, replacing the previous version of the invitation
+    pub fn set_workspace_invitation(&self, workspace: &Workspace) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let value = serde_json::to_vec(workspace)?;
+
+        self.db.put_cf(
+            cf,
+            Self::workspace_invitation_key(&workspace.workspace_id).as_bytes(),
+            value,
+        )?;
+        Ok(())
+    }
+
+    pub fn get_workspace_invitation(&self, workspace_id: &str) -> Result<Workspace, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::workspace_invitation_key(workspace_id);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Err(ShinkaiDBError::DataNotFound),
+        }
+    }
+
+    /// Workspaces this node was invited to and hasn't accepted yet
+    pub fn get_all_workspace_invitations(&self) -> Result<Vec<Workspace>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let mut result = Vec::new();
+
+        let iter = self.db.prefix_iterator_cf(cf, WORKSPACE_INVITATION_PREFIX.as_bytes());
+        for item in iter {
+            let (key, value) = item.map_err(ShinkaiDBError::RocksDBError)?;
+            if !key.starts_with(WORKSPACE_INVITATION_PREFIX.as_bytes()) {
+                break;
+            }
+            let workspace: Workspace = serde_json::from_slice(&value)?;
+            result.push(workspace);
+        }
+        result.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(result)
+    }
+
+    pub fn remove_workspace_invitation(&self, workspace_id: &str) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+
+        self.db
+            .delete_cf(cf, Self::workspace_invitation_key(workspace_id).as_bytes())?;
+        Ok(())
+    }
+
+    /// Workspace sharing the folder of the profile, if any
+    pub fn get_workspace_for_folder(&self, owner: &str, path: &str) -> Result<Option<Workspace>, ShinkaiDBError> {
+        Ok(self
+            .get_all_workspaces()?
+            .into_iter()
+            .find(|workspace| workspace.owner == owner && workspace.folders.iter().any(|folder| folder == path)))
+    }
+
+    /// Time of the last message of the inbox sent to the other members
+    pub fn get_workspace_sync_cursor(
+        &self,
+        workspace_id: &str,
+        inbox_name: &str,
+    ) -> Result<Option<String>, ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::workspace_sync_cursor_key(workspace_id, inbox_name);
+
+        match self.db.get_cf(cf, key.as_bytes())? {
+            Some(value) => Ok(Some(String::from_utf8(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_workspace_sync_cursor(
+        &self,
+        workspace_id: &str,
+        inbox_name: &str,
+        scheduled_time: &str,
+    ) -> Result<(), ShinkaiDBError> {
+        let cf = self.cf_handle(Topic::NodeAndUsers.as_str())?;
+        let key = Self::workspace_sync_cursor_key(workspace_id, inbox_name);
+
+        self.db.put_cf(cf, key.as_bytes(), scheduled_time.as_bytes())?;
+        Ok(())
+    }
+}
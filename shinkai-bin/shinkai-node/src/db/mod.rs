@@ -2,6 +2,7 @@ pub mod db_main;
 pub use db_main::ShinkaiDB;
 pub use db_main::Topic;
 pub mod db_llm_providers;
+pub mod db_model_capabilities;
 pub mod db_cron_task;
 pub mod db_errors;
 pub mod db_files_transmission;
@@ -9,15 +10,45 @@ pub mod db_identity;
 pub mod db_identity_registration;
 pub mod db_inbox;
 pub mod db_inbox_get_messages;
+pub mod db_inbox_message_changes;
+pub mod db_message_attachments;
+pub mod db_inbox_organization;
 pub mod db_job_queue;
 pub mod db_jobs;
 pub mod db_profile_bound;
+pub mod db_profile_data;
+pub mod db_profile_limits;
 pub mod db_retry;
 pub mod db_utils;
 pub mod db_shared_folder_req;
+pub mod db_shared_folder_sync;
+pub mod db_folder_publications;
+pub mod db_folder_agents;
 pub mod db_subscribers;
 pub mod db_my_subscriptions;
 pub mod db_settings;
 pub mod db_network_notifications;
+pub mod db_oidc_tokens;
 pub mod db_uploaded_files_links;
 pub mod db_sheet;
+pub mod db_toolkits;
+pub mod db_email;
+pub mod db_calendar;
+pub mod db_cloud_connectors;
+pub mod db_chat_bridge;
+pub mod db_idempotency;
+pub mod db_knowledge_base_imports;
+pub mod db_integrity;
+pub mod db_watched_folders;
+pub mod db_maintenance;
+pub mod db_peer_bans;
+pub mod db_peer_protocols;
+pub mod db_identity_registry_cache;
+pub mod db_invoices;
+pub mod db_wallet;
+pub mod db_tool_usage;
+pub mod db_job_plans;
+pub mod db_workspaces;
+pub mod db_contacts;
+pub mod db_inbound_message_policies;
+pub mod db_inbox_digests;
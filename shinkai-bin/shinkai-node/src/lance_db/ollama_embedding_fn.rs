@@ -5,6 +5,7 @@ use lancedb::embeddings::EmbeddingFunction;
 use lancedb::Result;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use shinkai_vector_resources::embedding_generator::{DeterministicEmbeddingGenerator, DETERMINISTIC_EMBEDDINGS_URL};
 use shinkai_vector_resources::model_type::EmbeddingModelType;
 use std::borrow::Cow;
 use std::sync::Arc;
@@ -26,6 +27,9 @@ impl OllamaEmbeddingFunction {
     }
 
     pub async fn request_embeddings(&self, prompt: &str) -> Result<Vec<f32>> {
+        if self.api_url == DETERMINISTIC_EMBEDDINGS_URL {
+            return Ok(DeterministicEmbeddingGenerator::new(self.model_type.clone()).vector_for(prompt));
+        }
         let model_str = match &self.model_type {
             EmbeddingModelType::OllamaTextEmbeddingsInference(model) => model.to_string(),
             _ => {
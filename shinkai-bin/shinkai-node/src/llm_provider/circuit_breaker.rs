@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use utoipa::ToSchema;
+
+use crate::network::error_code::ErrorCode;
+
+use super::error::LLMProviderError;
+
+lazy_static! {
+    /// Circuit breakers of every LLM provider the node sends inferences to
+    pub static ref LLM_PROVIDER_BREAKERS: LLMProviderCircuitBreakers = LLMProviderCircuitBreakers::new_from_env();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Inferences go to the provider
+    Closed,
+    /// The provider failed too many times in a row, inferences fail right away
+    Open,
+    /// The provider was open long enough, the next inference probes it
+    HalfOpen,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CircuitBreakerStatus {
+    pub llm_provider_id: String,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub opened_at: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    consecutive_failures: u32,
+    /// When the circuit opened, or when the last probe went through
+    opened_at: Option<(Instant, DateTime<Utc>)>,
+    last_error: Option<String>,
+}
+
+/// Stops sending inferences to a provider after `failure_threshold` failures in a row, so jobs fail fast instead
+/// of piling up on a provider that hangs. Once open for `open_duration`, one inference goes through as a probe:
+/// its success closes the circuit and its failure keeps it open for another `open_duration`.
+pub struct LLMProviderCircuitBreakers {
+    breakers: Mutex<HashMap<String, CircuitBreaker>>,
+    failure_threshold: u32,
+    open_duration: Duration,
+    default_timeout: Duration,
+    /// Timeouts of the providers that don't use the default one, by provider id
+    timeouts: HashMap<String, Duration>,
+}
+
+impl LLMProviderCircuitBreakers {
+    pub fn new(
+        failure_threshold: u32,
+        open_duration: Duration,
+        default_timeout: Duration,
+        timeouts: HashMap<String, Duration>,
+    ) -> Self {
+        Self {
+            breakers: Mutex::new(HashMap::new()),
+            failure_threshold: std::cmp::max(1, failure_threshold),
+            open_duration,
+            default_timeout,
+            timeouts,
+        }
+    }
+
+    /// Reads LLM_PROVIDER_FAILURE_THRESHOLD, LLM_PROVIDER_OPEN_SECS and LLM_PROVIDER_TIMEOUT_SECS, and the timeouts
+    /// of single providers from LLM_PROVIDER_TIMEOUTS, e.g. `my_gpt=60,my_llama=600`
+    pub fn new_from_env() -> Self {
+        let read_env = |key: &str, default: u64| -> u64 {
+            std::env::var(key)
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(default)
+        };
+        let timeouts = std::env::var("LLM_PROVIDER_TIMEOUTS")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|timeout| {
+                let (id, secs) = timeout.split_once('=')?;
+                Some((id.trim().to_string(), Duration::from_secs(secs.trim().parse().ok()?)))
+            })
+            .collect();
+
+        Self::new(
+            read_env("LLM_PROVIDER_FAILURE_THRESHOLD", 5) as u32,
+            Duration::from_secs(read_env("LLM_PROVIDER_OPEN_SECS", 60)),
+            Duration::from_secs(read_env("LLM_PROVIDER_TIMEOUT_SECS", 300)),
+            timeouts,
+        )
+    }
+
+    pub fn timeout(&self, llm_provider_id: &str) -> Duration {
+        self.timeouts
+            .get(llm_provider_id)
+            .copied()
+            .unwrap_or(self.default_timeout)
+    }
+
+    /// Fails while the circuit of the provider is open, letting a probe through once it's half-open
+    pub fn try_acquire(&self, llm_provider_id: &str) -> Result<(), LLMProviderError> {
+        let mut breakers = self.breakers.lock().unwrap();
+        let Some(breaker) = breakers.get_mut(llm_provider_id) else {
+            return Ok(());
+        };
+        match self.state(breaker) {
+            CircuitState::Closed => Ok(()),
+            CircuitState::HalfOpen => {
+                // Other inferences wait for the result of this one
+                breaker.opened_at = Some((Instant::now(), Utc::now()));
+                Ok(())
+            }
+            CircuitState::Open => Err(LLMProviderError::LLMProviderUnavailable(format!(
+                "{} failed {} times in a row, last with: {}",
+                llm_provider_id,
+                breaker.consecutive_failures,
+                breaker.last_error.clone().unwrap_or_default()
+            ))),
+        }
+    }
+
+    pub fn record_result<T>(&self, llm_provider_id: &str, result: &Result<T, LLMProviderError>) {
+        let mut breakers = self.breakers.lock().unwrap();
+        match result {
+            Ok(_) => {
+                breakers.remove(llm_provider_id);
+            }
+            Err(e) if Self::is_provider_failure(e) => {
+                let breaker = breakers.entry(llm_provider_id.to_string()).or_default();
+                breaker.consecutive_failures += 1;
+                breaker.last_error = Some(e.to_string());
+                if breaker.consecutive_failures >= self.failure_threshold {
+                    if breaker.opened_at.is_none() {
+                        shinkai_log(
+                            ShinkaiLogOption::JobExecution,
+                            ShinkaiLogLevel::Error,
+                            &format!("Circuit of LLM provider {} opened: {}", llm_provider_id, e),
+                        );
+                    }
+                    breaker.opened_at = Some((Instant::now(), Utc::now()));
+                }
+            }
+            // Errors of the request, e.g. a prompt over the token limit, say nothing about the provider
+            Err(_) => {}
+        }
+    }
+
+    /// Providers that failed since their last success
+    pub fn statuses(&self) -> Vec<CircuitBreakerStatus> {
+        let breakers = self.breakers.lock().unwrap();
+        let mut statuses: Vec<CircuitBreakerStatus> = breakers
+            .iter()
+            .map(|(llm_provider_id, breaker)| CircuitBreakerStatus {
+                llm_provider_id: llm_provider_id.clone(),
+                state: self.state(breaker),
+                consecutive_failures: breaker.consecutive_failures,
+                last_error: breaker.last_error.clone(),
+                opened_at: breaker.opened_at.map(|(_, opened_at)| opened_at.to_rfc3339()),
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.llm_provider_id.cmp(&b.llm_provider_id));
+        statuses
+    }
+
+    fn state(&self, breaker: &CircuitBreaker) -> CircuitState {
+        match breaker.opened_at {
+            None => CircuitState::Closed,
+            Some((opened_at, _)) if opened_at.elapsed() < self.open_duration => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    fn is_provider_failure(error: &LLMProviderError) -> bool {
+        matches!(error.error_code(), ErrorCode::LlmProviderError | ErrorCode::Timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_opens_and_probes() {
+        let breakers =
+            LLMProviderCircuitBreakers::new(2, Duration::from_millis(50), Duration::from_secs(1), HashMap::new());
+        let failure: Result<(), LLMProviderError> = Err(LLMProviderError::InferenceFailed);
+
+        breakers.record_result("test_provider", &failure);
+        assert!(breakers.try_acquire("test_provider").is_ok());
+        breakers.record_result("test_provider", &failure);
+        assert!(breakers.try_acquire("test_provider").is_err());
+        assert_eq!(breakers.statuses()[0].state, CircuitState::Open);
+
+        // A single probe goes through once the circuit is half-open
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(breakers.try_acquire("test_provider").is_ok());
+        assert!(breakers.try_acquire("test_provider").is_err());
+        breakers.record_result("test_provider", &Ok(()));
+        assert!(breakers.try_acquire("test_provider").is_ok());
+        assert!(breakers.statuses().is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{db::db_errors::ShinkaiDBError, managers::model_capabilities_manager::ModelCapabilitiesManagerError, tools::error::ToolError, vector_fs::vector_fs_error::VectorFSError, workflows::sm_executor::WorkflowError};
+use crate::{db::db_errors::ShinkaiDBError, managers::model_capabilities_manager::ModelCapabilitiesManagerError, network::error_code::ErrorCode, tools::error::ToolError, vector_fs::vector_fs_error::VectorFSError, workflows::sm_executor::WorkflowError};
 use anyhow::Error as AnyhowError;
 use shinkai_message_primitives::{
     schemas::{inbox_name::InboxNameError, shinkai_name::ShinkaiNameError},
@@ -78,7 +78,14 @@ pub enum LLMProviderError {
     CallbackManagerNotFound,
     SheetManagerError(String),
     InputProcessingError(String),
-    ToolRouterNotFound
+    ToolRouterNotFound,
+    ToolTimedOut(String),
+    ToolOutputTooLarge(String),
+    ProfileLimitExceeded(String),
+    LLMProviderTimedOut(String),
+    LLMProviderUnavailable(String),
+    OperationCancelled(String),
+    PlanningFailed(String),
 }
 
 impl fmt::Display for LLMProviderError {
@@ -164,6 +171,13 @@ impl fmt::Display for LLMProviderError {
             LLMProviderError::SheetManagerError(s) => write!(f, "{}", s),
             LLMProviderError::InputProcessingError(s) => write!(f, "{}", s),
             LLMProviderError::ToolRouterNotFound => write!(f, "Tool Router not found"),
+            LLMProviderError::ToolTimedOut(s) => write!(f, "Tool timed out: {}", s),
+            LLMProviderError::ToolOutputTooLarge(s) => write!(f, "Tool output too large: {}", s),
+            LLMProviderError::ProfileLimitExceeded(s) => write!(f, "Profile limit exceeded: {}", s),
+            LLMProviderError::LLMProviderTimedOut(s) => write!(f, "LLM provider timed out: {}", s),
+            LLMProviderError::LLMProviderUnavailable(s) => write!(f, "LLM provider unavailable: {}", s),
+            LLMProviderError::OperationCancelled(s) => write!(f, "Operation cancelled: {}", s),
+            LLMProviderError::PlanningFailed(s) => write!(f, "Planning failed: {}", s),
         }
     }
 }
@@ -239,15 +253,56 @@ impl LLMProviderError {
             LLMProviderError::SheetManagerError(_) => "SheetManagerError",
             LLMProviderError::InputProcessingError(_) => "InputProcessingError",
             LLMProviderError::ToolRouterNotFound => "ToolRouterNotFound",
+            LLMProviderError::ToolTimedOut(_) => "ToolTimedOut",
+            LLMProviderError::ToolOutputTooLarge(_) => "ToolOutputTooLarge",
+            LLMProviderError::ProfileLimitExceeded(_) => "ProfileLimitExceeded",
+            LLMProviderError::LLMProviderTimedOut(_) => "LLMProviderTimedOut",
+            LLMProviderError::LLMProviderUnavailable(_) => "LLMProviderUnavailable",
+            LLMProviderError::OperationCancelled(_) => "OperationCancelled",
+            LLMProviderError::PlanningFailed(_) => "PlanningFailed",
         };
 
         let error_message = format!("{}", self);
 
         serde_json::json!({
             "error": error_name,
+            "error_code": self.error_code(),
             "error_message": error_message
         }).to_string()
     }
+
+    /// Stable code of the error for API clients
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            LLMProviderError::JobNotFound => ErrorCode::JobNotFound,
+            LLMProviderError::LLMProviderNotFound => ErrorCode::JobAgentMissing,
+            LLMProviderError::LLMProviderMissingCapabilities(_) => ErrorCode::LlmProviderMissingCapabilities,
+            LLMProviderError::ShinkaiBackendInferenceLimitReached(_)
+            | LLMProviderError::LLMServiceInferenceLimitReached(_)
+            | LLMProviderError::TokenLimit(_) => ErrorCode::LlmProviderLimitReached,
+            LLMProviderError::ProfileLimitExceeded(_) => ErrorCode::ProfileLimitExceeded,
+            LLMProviderError::LLMProviderTimedOut(_) => ErrorCode::Timeout,
+            LLMProviderError::OperationCancelled(_) => ErrorCode::OperationCancelled,
+            LLMProviderError::NoUserProfileFound => ErrorCode::ProfileNotFound,
+            LLMProviderError::InvalidSubidentity(_)
+            | LLMProviderError::InvalidProfileSubidentity(_)
+            | LLMProviderError::NotAJobMessage
+            | LLMProviderError::JobCreationDeserializationFailed
+            | LLMProviderError::JobMessageDeserializationFailed
+            | LLMProviderError::MessageTypeParseFailed
+            | LLMProviderError::InvalidModelType(_) => ErrorCode::InvalidInput,
+            LLMProviderError::FunctionNotFound(_) => ErrorCode::ToolNotFound,
+            LLMProviderError::FunctionExecutionError(_)
+            | LLMProviderError::InvalidFunctionArguments(_)
+            | LLMProviderError::InvalidFunctionResult(_)
+            | LLMProviderError::ToolRouterError(_)
+            | LLMProviderError::ToolTimedOut(_)
+            | LLMProviderError::ToolOutputTooLarge(_) => ErrorCode::ToolError,
+            LLMProviderError::ShinkaiDB(err) => err.error_code(),
+            LLMProviderError::VectorFS(err) => err.error_code(),
+            _ => ErrorCode::LlmProviderError,
+        }
+    }
 }
 
 
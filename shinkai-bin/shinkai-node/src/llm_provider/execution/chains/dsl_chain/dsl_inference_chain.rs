@@ -6,7 +6,11 @@ use crate::{
         providers::shared::openai::FunctionCall,
     },
     managers::model_capabilities_manager::ModelCapabilitiesManager,
-    tools::{shinkai_tool::ShinkaiTool, workflow_tool::WorkflowTool},
+    tools::{
+        native_tool::{NativeToolContext, NATIVE_TOOL_REGISTRY},
+        shinkai_tool::ShinkaiTool, tool_pricing::ToolUsageMeter, wasm_tools::WasmToolHostContext,
+        workflow_tool::WorkflowTool,
+    },
     workflows::sm_executor::{AsyncFunction, FunctionMap, WorkflowEngine, WorkflowError},
 };
 use async_trait::async_trait;
@@ -370,6 +374,8 @@ impl AsyncFunction for OpinionatedInferenceFunction {
                 generator.clone(),
                 20,
                 max_tokens_in_prompt,
+                self.context.scope_pruning(),
+                self.context.retrieval_config(),
             )
             .await
             .map_err(|e| WorkflowError::ExecutionError(e.to_string()))?;
@@ -543,12 +549,27 @@ impl AsyncFunction for ShinkaiToolFunction {
             arguments: serde_json::Value::Object(params),
         };
 
+        self.tool
+            .check_capabilities(&self.context.db())
+            .map_err(|e| WorkflowError::ExecutionError(e.to_string()))?;
+        let consumer = self.context.user_profile().to_string();
+        let reservation = ToolUsageMeter::authorize(&self.context.db(), &consumer, &self.tool)
+            .map_err(|e| WorkflowError::ExecutionError(e.to_string()))?;
+
         let result = match &self.tool {
             ShinkaiTool::JS(js_tool, _) => {
                 let function_config = self.tool.get_config_from_env();
+                let limits = self
+                    .tool
+                    .execution_limits(&self.context.db())
+                    .map_err(|e| WorkflowError::ExecutionError(e.to_string()))?;
                 let result = js_tool
-                    .run(function_call.arguments, function_config)
+                    .run(function_call.arguments, function_config, &limits)
                     .map_err(|e| WorkflowError::ExecutionError(e.to_string()))?;
+                if let Some(reservation) = reservation {
+                    ToolUsageMeter::record(&self.context.db(), &self.tool, reservation)
+                        .map_err(|e| WorkflowError::ExecutionError(e.to_string()))?;
+                }
                 let data = &result.data;
 
                 // Check if the result has only one main type
@@ -570,10 +591,56 @@ impl AsyncFunction for ShinkaiToolFunction {
                         .map_err(|e| WorkflowError::ExecutionError(format!("Failed to stringify result: {}", e)))?,
                 }
             }
-            ShinkaiTool::Rust(_, _) => {
-                return Err(WorkflowError::ExecutionError(
-                    "Rust tools are not supported in this context".to_string(),
-                ));
+            ShinkaiTool::Rust(rust_tool, _) => {
+                if !NATIVE_TOOL_REGISTRY.contains(&rust_tool.name) {
+                    return Err(WorkflowError::ExecutionError(
+                        "Rust tools are not supported in this context".to_string(),
+                    ));
+                }
+                let native_context = NativeToolContext {
+                    profile: Some(self.context.user_profile().clone()),
+                    db: Some(self.context.db()),
+                    vector_fs: Some(self.context.vector_fs()),
+                    generator: Some(self.context.generator().clone()),
+                };
+                let result = NATIVE_TOOL_REGISTRY
+                    .call(&rust_tool.name, function_call.arguments, native_context)
+                    .await
+                    .map_err(|e| WorkflowError::ExecutionError(e.to_string()))?;
+                if let Some(reservation) = reservation {
+                    ToolUsageMeter::record(&self.context.db(), &self.tool, reservation)
+                        .map_err(|e| WorkflowError::ExecutionError(e.to_string()))?;
+                }
+
+                match result {
+                    serde_json::Value::String(s) => s,
+                    _ => serde_json::to_string(&result)
+                        .map_err(|e| WorkflowError::ExecutionError(format!("Failed to stringify result: {}", e)))?,
+                }
+            }
+            ShinkaiTool::Wasm(wasm_tool, _) => {
+                let host_context = WasmToolHostContext {
+                    vector_fs: Some(self.context.vector_fs()),
+                    profile: Some(self.context.user_profile().clone()),
+                };
+                let limits = self
+                    .tool
+                    .execution_limits(&self.context.db())
+                    .map_err(|e| WorkflowError::ExecutionError(e.to_string()))?;
+                let result = wasm_tool
+                    .run(function_call.arguments, host_context, &limits)
+                    .await
+                    .map_err(|e| WorkflowError::ExecutionError(e.to_string()))?;
+                if let Some(reservation) = reservation {
+                    ToolUsageMeter::record(&self.context.db(), &self.tool, reservation)
+                        .map_err(|e| WorkflowError::ExecutionError(e.to_string()))?;
+                }
+
+                match result {
+                    serde_json::Value::String(s) => s,
+                    _ => serde_json::to_string(&result)
+                        .map_err(|e| WorkflowError::ExecutionError(format!("Failed to stringify result: {}", e)))?,
+                }
             }
             ShinkaiTool::Workflow(_, _) => {
                 // TODO: we should allow for a workflow to call another workflow
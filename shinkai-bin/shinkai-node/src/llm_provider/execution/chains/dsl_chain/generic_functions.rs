@@ -9,6 +9,8 @@ use crate::{llm_provider::{
     execution::{chains::inference_chain_trait::InferenceChainContextTrait, prompts::subprompts::SubPrompt},
     job_manager::JobManager,
 }, workflows::sm_executor::WorkflowError};
+use crate::network::outbound_proxy::OUTBOUND_PROXY;
+use crate::schemas::outbound_proxy::DestinationClass;
 
 use super::split_text_for_llm::split_text_for_llm;
 
@@ -109,7 +111,8 @@ pub fn download_webpage(
     let result = tokio::runtime::Runtime::new()
         .map_err(|e| WorkflowError::ExecutionError(e.to_string()))?
         .block_on(async {
-            let client = reqwest::Client::builder()
+            let client = OUTBOUND_PROXY
+                .client_builder(DestinationClass::WebTools, reqwest::Client::builder())
                 .timeout(std::time::Duration::from_secs(60))
                 .redirect(reqwest::redirect::Policy::limited(20))
                 .build()
@@ -454,6 +457,8 @@ pub fn search_embeddings_in_job_scope(
                     generator.clone(),
                     num_of_top_results,
                     max_tokens_in_prompt,
+                    context.scope_pruning(),
+                    context.retrieval_config(),
                 )
                 .await;
 
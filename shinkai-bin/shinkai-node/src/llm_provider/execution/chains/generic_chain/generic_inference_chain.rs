@@ -3,29 +3,40 @@ use crate::llm_provider::error::LLMProviderError;
 use crate::llm_provider::execution::chains::inference_chain_trait::{
     InferenceChain, InferenceChainContext, InferenceChainContextTrait, InferenceChainResult,
 };
-use crate::llm_provider::execution::prompts::prompts::JobPromptGenerator;
+use crate::llm_provider::execution::prompts::prompts::{JobPromptGenerator, Prompt};
+use crate::llm_provider::execution::prompts::subprompts::{SubPromptAssetType, SubPromptType};
+use crate::llm_provider::execution::scope_pruning::ScopePruningStrategy;
 use crate::llm_provider::execution::user_message_parser::ParsedUserMessage;
 use crate::llm_provider::job::{Job, JobLike};
 use crate::llm_provider::job_manager::JobManager;
+use crate::managers::model_capabilities_manager::{ModelCapabilitiesManager, ModelCapability};
 use crate::network::ws_manager::WSUpdateHandler;
 use crate::tools::tool_router::ToolRouter;
+use crate::utils::environment::image_embedding_generator;
 use crate::vector_fs::vector_fs::VectorFS;
 use async_recursion::async_recursion;
 use async_trait::async_trait;
 use shinkai_message_primitives::schemas::inbox_name::InboxName;
+use shinkai_message_primitives::schemas::job_citation::JobCitation;
 use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::{
     LLMProviderInterface, SerializedLLMProvider,
 };
+use shinkai_message_primitives::schemas::retrieval_config::RetrievalConfig;
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
 use shinkai_vector_resources::embedding_generator::RemoteEmbeddingGenerator;
-use shinkai_vector_resources::vector_resource::RetrievedNode;
+use shinkai_vector_resources::file_parser::file_parser::ShinkaiFileParser;
+use shinkai_vector_resources::source::StandardSourceFile;
+use shinkai_vector_resources::vector_resource::{RetrievedNode, SourceFileType};
 use std::fmt;
 use std::result::Result::Ok;
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::Mutex;
 use tracing::instrument;
 
+/// How many of the job scope's images are given to LLMs that support image analysis
+const MAX_SCOPE_IMAGES: u64 = 2;
+
 #[derive(Clone)]
 pub struct GenericInferenceChain {
     pub context: InferenceChainContext,
@@ -57,7 +68,7 @@ impl InferenceChain for GenericInferenceChain {
     }
 
     async fn run_chain(&mut self) -> Result<InferenceChainResult, LLMProviderError> {
-        let response = GenericInferenceChain::start_chain(
+        let (response, citations) = GenericInferenceChain::start_chain(
             self.context.db.clone(),
             self.context.vector_fs.clone(),
             self.context.full_job.clone(),
@@ -70,10 +81,12 @@ impl InferenceChain for GenericInferenceChain {
             self.context.max_tokens_in_prompt,
             self.ws_manager_trait.clone(),
             self.context.tool_router.clone(),
+            self.context.scope_pruning.clone(),
+            self.context.retrieval_config.clone(),
         )
         .await?;
         let job_execution_context = self.context.execution_context.clone();
-        Ok(InferenceChainResult::new(response, job_execution_context))
+        Ok(InferenceChainResult::new(response, job_execution_context).with_citations(citations))
     }
 }
 
@@ -89,7 +102,7 @@ impl GenericInferenceChain {
     }
 
     #[async_recursion]
-    #[instrument(skip(generator, vector_fs, db, ws_manager_trait, tool_router))]
+    #[instrument(skip(generator, vector_fs, db, ws_manager_trait, tool_router, scope_pruning))]
     #[allow(clippy::too_many_arguments)]
     pub async fn start_chain(
         db: Arc<ShinkaiDB>,
@@ -104,7 +117,9 @@ impl GenericInferenceChain {
         max_tokens_in_prompt: usize,
         ws_manager_trait: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
         tool_router: Option<Arc<Mutex<ToolRouter>>>,
-    ) -> Result<String, LLMProviderError> {
+        scope_pruning: Arc<dyn ScopePruningStrategy>,
+        retrieval_config: RetrievalConfig,
+    ) -> Result<(String, Vec<JobCitation>), LLMProviderError> {
         shinkai_log(
             ShinkaiLogOption::JobExecution,
             ShinkaiLogLevel::Info,
@@ -141,10 +156,59 @@ impl GenericInferenceChain {
                 generator.clone(),
                 20,
                 max_tokens_in_prompt,
+                scope_pruning.clone(),
+                &retrieval_config,
             )
             .await?;
             ret_nodes = ret;
             summary_node_text = summary;
+
+            // Messages in another language than the documents found are also searched translated into it
+            if retrieval_config.translate_query == Some(true) {
+                if let Some(translated_message) =
+                    Self::translate_query(&user_message, &ret_nodes, llm_provider.clone()).await
+                {
+                    let (translated_ret, _) = JobManager::keyword_chained_job_scope_vector_search(
+                        db.clone(),
+                        vector_fs.clone(),
+                        full_job.scope(),
+                        translated_message,
+                        &user_profile,
+                        generator.clone(),
+                        20,
+                        max_tokens_in_prompt,
+                        scope_pruning,
+                        &retrieval_config,
+                    )
+                    .await?;
+                    ret_nodes = Self::merge_translated_results(translated_ret, ret_nodes);
+                }
+            }
+        }
+
+        // Images in the scope relevant to the message are given to LLMs that can see them
+        let mut scope_images = vec![];
+        let supports_images = ModelCapabilitiesManager::get_llm_provider_capabilities(&llm_provider.model)
+            .contains(&ModelCapability::ImageAnalysis);
+        let image_generator = image_embedding_generator().filter(|_| !scope_is_empty && supports_images);
+        if let Some(image_generator) = image_generator {
+            match JobManager::job_scope_image_search(
+                vector_fs.clone(),
+                full_job.scope(),
+                user_message.clone(),
+                &user_profile,
+                &image_generator,
+                MAX_SCOPE_IMAGES,
+            )
+            .await
+            {
+                Ok(images) => scope_images = images.into_iter().map(|(_, image)| image).collect(),
+                Err(e) => shinkai_log(
+                    ShinkaiLogOption::JobExecution,
+                    ShinkaiLogLevel::Error,
+                    &format!("Failed to search the job scope images: {}", e),
+                ),
+            }
         }
 
         // 2) Vector search for tooling / workflows if the workflow / tooling scope isn't empty
@@ -184,6 +248,7 @@ impl GenericInferenceChain {
             tools.clone(),
             None,
         );
+        Self::add_scope_images(&mut filled_prompt, &scope_images, &llm_provider);
 
         let mut iteration_count = 0;
         loop {
@@ -272,13 +337,89 @@ impl GenericInferenceChain {
                     tools.clone(),
                     Some(function_response),
                 );
+                Self::add_scope_images(&mut filled_prompt, &scope_images, &llm_provider);
             } else {
-                // No more function calls required, return the final response
-                return Ok(response.response_string);
+                // No more function calls required, return the final response with the content it was based on
+                return Ok((response.response_string, JobCitation::from_retrieved_nodes(&ret_nodes)));
             }
 
             // Increment the iteration count
             iteration_count += 1;
         }
     }
+
+    /// Translates the user message into the language most of the retrieved nodes are in. None if the nodes aren't
+    /// tagged with a language, the message is already in it, or the translation fails.
+    async fn translate_query(
+        user_message: &str,
+        ret_nodes: &[RetrievedNode],
+        llm_provider: SerializedLLMProvider,
+    ) -> Option<String> {
+        let language_key = ShinkaiFileParser::language_metadata_key();
+        let documents_language = ShinkaiFileParser::most_common_language(
+            ret_nodes
+                .iter()
+                .filter_map(|node| node.node.metadata.as_ref()?.get(&language_key).map(String::as_str)),
+        )?;
+        if ShinkaiFileParser::detect_language(user_message).as_deref() == Some(documents_language.as_str()) {
+            return None;
+        }
+        let language_name = ShinkaiFileParser::language_name(&documents_language)?;
+        let prompt = JobPromptGenerator::query_translation(user_message.to_string(), language_name);
+        match JobManager::inference_with_llm_provider(llm_provider, prompt, None, None).await {
+            Ok(response) => Some(response.response_string.trim().to_string()).filter(|query| !query.is_empty()),
+            Err(e) => {
+                shinkai_log(
+                    ShinkaiLogOption::JobExecution,
+                    ShinkaiLogLevel::Error,
+                    &format!("Failed to translate the query: {}", e),
+                );
+                None
+            }
+        }
+    }
+
+    /// Puts the results of the translated query first, followed by the ones of the original query not found by it.
+    /// As many results are kept as the larger of both searches returned, so the prompt doesn't grow.
+    fn merge_translated_results(
+        translated_nodes: Vec<RetrievedNode>,
+        original_nodes: Vec<RetrievedNode>,
+    ) -> Vec<RetrievedNode> {
+        let num_of_results = translated_nodes.len().max(original_nodes.len());
+        let mut merged = translated_nodes;
+        for node in original_nodes {
+            if !merged
+                .iter()
+                .any(|merged_node| merged_node.node.content == node.node.content)
+            {
+                merged.push(node);
+            }
+        }
+        merged.truncate(num_of_results);
+        merged
+    }
+
+    /// Adds the images to the prompt after the user message, encoded the way the LLM provider expects them
+    fn add_scope_images(prompt: &mut Prompt, images: &[StandardSourceFile], llm_provider: &SerializedLLMProvider) {
+        for image in images {
+            let content = match (&llm_provider.model, &image.file_type) {
+                (
+                    LLMProviderInterface::OpenAI(_) | LLMProviderInterface::ShinkaiBackend(_),
+                    SourceFileType::Image(image_type),
+                ) => format!(
+                    "data:image/{};base64,{}",
+                    image_type,
+                    base64::encode(&image.file_content)
+                ),
+                _ => base64::encode(&image.file_content),
+            };
+            prompt.add_asset(
+                SubPromptAssetType::Image,
+                content,
+                String::from("auto"),
+                SubPromptType::User,
+                96,
+            );
+        }
+    }
 }
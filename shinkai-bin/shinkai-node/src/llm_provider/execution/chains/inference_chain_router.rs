@@ -1,5 +1,6 @@
 use super::generic_chain::generic_inference_chain::GenericInferenceChain;
 use super::inference_chain_trait::{InferenceChain, InferenceChainContext, InferenceChainResult};
+use super::planner_chain::planner_chain::PlannerInferenceChain;
 use crate::db::ShinkaiDB;
 use crate::llm_provider::error::LLMProviderError;
 use crate::llm_provider::execution::user_message_parser::ParsedUserMessage;
@@ -37,11 +38,13 @@ impl JobManager {
     ) -> Result<InferenceChainResult, LLMProviderError> {
         // Initializations
         let llm_provider = llm_provider_found.ok_or(LLMProviderError::LLMProviderNotFound)?;
-        let max_tokens_in_prompt = ModelCapabilitiesManager::get_max_input_tokens(&llm_provider.model);
+        let max_tokens_in_prompt = ModelCapabilitiesManager::get_max_input_tokens_with_probe(&db, &llm_provider);
         let parsed_user_message = ParsedUserMessage::new(job_message.content.to_string());
+        let planning_enabled = db.is_job_planning_enabled(&full_job.job_id)?;
+        let retrieval_config = JobManager::job_retrieval_config(&db, &job_message)?;
 
         // Create the inference chain context
-        let chain_context = InferenceChainContext::new(
+        let mut chain_context = InferenceChainContext::new(
             db,
             vector_fs,
             full_job,
@@ -56,6 +59,12 @@ impl JobManager {
             ws_manager_trait.clone(),
             tool_router.clone(),
         );
+        chain_context.update_retrieval_config(retrieval_config);
+
+        if planning_enabled {
+            let mut planner_chain = PlannerInferenceChain::new(chain_context, ws_manager_trait);
+            return planner_chain.run_chain().await;
+        }
 
         let mut generic_chain = GenericInferenceChain::new(chain_context, ws_manager_trait);
         generic_chain.run_chain().await
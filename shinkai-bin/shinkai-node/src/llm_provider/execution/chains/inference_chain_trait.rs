@@ -1,4 +1,5 @@
 use crate::db::ShinkaiDB;
+use crate::llm_provider::execution::scope_pruning::{default_scope_pruning, NoScopePruning, ScopePruningStrategy};
 use crate::llm_provider::execution::user_message_parser::ParsedUserMessage;
 use crate::llm_provider::providers::shared::openai::FunctionCall;
 use crate::llm_provider::{error::LLMProviderError, job::Job};
@@ -8,7 +9,9 @@ use crate::vector_fs;
 use crate::vector_fs::vector_fs::VectorFS;
 use async_trait::async_trait;
 use serde_json::Value as JsonValue;
+use shinkai_message_primitives::schemas::job_citation::JobCitation;
 use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider;
+use shinkai_message_primitives::schemas::retrieval_config::RetrievalConfig;
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
 use shinkai_vector_resources::embedding_generator::RemoteEmbeddingGenerator;
 use std::fmt;
@@ -65,6 +68,8 @@ pub trait InferenceChainContextTrait: Send + Sync {
     fn raw_files(&self) -> &RawFiles;
     fn ws_manager_trait(&self) -> Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>;
     fn tool_router(&self) -> Option<Arc<Mutex<ToolRouter>>>;
+    fn scope_pruning(&self) -> Arc<dyn ScopePruningStrategy>;
+    fn retrieval_config(&self) -> &RetrievalConfig;
 
     fn clone_box(&self) -> Box<dyn InferenceChainContextTrait>;
 }
@@ -148,6 +153,14 @@ impl InferenceChainContextTrait for InferenceChainContext {
         self.tool_router.clone()
     }
 
+    fn scope_pruning(&self) -> Arc<dyn ScopePruningStrategy> {
+        self.scope_pruning.clone()
+    }
+
+    fn retrieval_config(&self) -> &RetrievalConfig {
+        &self.retrieval_config
+    }
+
     fn clone_box(&self) -> Box<dyn InferenceChainContextTrait> {
         Box::new(self.clone())
     }
@@ -173,6 +186,10 @@ pub struct InferenceChainContext {
     pub raw_files: RawFiles,
     pub ws_manager_trait: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
     pub tool_router: Option<Arc<Mutex<ToolRouter>>>,
+    /// Picks the parts of the job scope deep searched for each query
+    pub scope_pruning: Arc<dyn ScopePruningStrategy>,
+    /// Retrieval parameters of the job, with the overrides of the message being processed
+    pub retrieval_config: RetrievalConfig,
 }
 
 impl InferenceChainContext {
@@ -208,6 +225,8 @@ impl InferenceChainContext {
             raw_files: None,
             ws_manager_trait,
             tool_router,
+            scope_pruning: default_scope_pruning(),
+            retrieval_config: RetrievalConfig::default(),
         }
     }
 
@@ -220,6 +239,16 @@ impl InferenceChainContext {
     pub fn update_raw_files(&mut self, new_raw_files: RawFiles) {
         self.raw_files = new_raw_files;
     }
+
+    /// Updates the strategy pruning the job scope before vector searches
+    pub fn update_scope_pruning(&mut self, new_scope_pruning: Arc<dyn ScopePruningStrategy>) {
+        self.scope_pruning = new_scope_pruning;
+    }
+
+    /// Updates the retrieval parameters used by the vector searches
+    pub fn update_retrieval_config(&mut self, new_retrieval_config: RetrievalConfig) {
+        self.retrieval_config = new_retrieval_config;
+    }
 }
 
 impl fmt::Debug for InferenceChainContext {
@@ -240,6 +269,8 @@ impl fmt::Debug for InferenceChainContext {
             .field("raw_files", &self.raw_files)
             .field("ws_manager_trait", &self.ws_manager_trait.is_some())
             .field("tool_router", &self.tool_router.is_some())
+            .field("scope_pruning", &self.scope_pruning.strategy_id())
+            .field("retrieval_config", &self.retrieval_config)
             .finish()
     }
 }
@@ -248,6 +279,8 @@ impl fmt::Debug for InferenceChainContext {
 pub struct InferenceChainResult {
     pub response: String,
     pub new_job_execution_context: HashMap<String, String>,
+    /// Source content the response was based on
+    pub citations: Vec<JobCitation>,
 }
 
 impl InferenceChainResult {
@@ -255,9 +288,15 @@ impl InferenceChainResult {
         Self {
             response,
             new_job_execution_context,
+            citations: Vec::new(),
         }
     }
 
+    pub fn with_citations(mut self, citations: Vec<JobCitation>) -> Self {
+        self.citations = citations;
+        self
+    }
+
     pub fn new_empty_execution_context(response: String) -> Self {
         Self::new(response, HashMap::new())
     }
@@ -373,6 +412,14 @@ impl InferenceChainContextTrait for Box<dyn InferenceChainContextTrait> {
         (**self).tool_router()
     }
 
+    fn scope_pruning(&self) -> Arc<dyn ScopePruningStrategy> {
+        (**self).scope_pruning()
+    }
+
+    fn retrieval_config(&self) -> &RetrievalConfig {
+        (**self).retrieval_config()
+    }
+
     fn clone_box(&self) -> Box<dyn InferenceChainContextTrait> {
         (**self).clone_box()
     }
@@ -390,6 +437,7 @@ pub struct MockInferenceChainContext {
     pub raw_files: RawFiles,
     pub db: Option<Arc<ShinkaiDB>>,
     pub vector_fs: Option<Arc<VectorFS>>,
+    pub retrieval_config: RetrievalConfig,
 }
 
 impl MockInferenceChainContext {
@@ -418,6 +466,7 @@ impl MockInferenceChainContext {
             raw_files,
             db,
             vector_fs,
+            retrieval_config: RetrievalConfig::default(),
         }
     }
 }
@@ -440,6 +489,7 @@ impl Default for MockInferenceChainContext {
             raw_files: None,
             db: None,
             vector_fs: None,
+            retrieval_config: RetrievalConfig::default(),
         }
     }
 }
@@ -517,6 +567,14 @@ impl InferenceChainContextTrait for MockInferenceChainContext {
         unimplemented!()
     }
 
+    fn scope_pruning(&self) -> Arc<dyn ScopePruningStrategy> {
+        Arc::new(NoScopePruning)
+    }
+
+    fn retrieval_config(&self) -> &RetrievalConfig {
+        &self.retrieval_config
+    }
+
     fn clone_box(&self) -> Box<dyn InferenceChainContextTrait> {
         Box::new(self.clone())
     }
@@ -535,6 +593,7 @@ impl Clone for MockInferenceChainContext {
             raw_files: self.raw_files.clone(),
             db: self.db.clone(),
             vector_fs: self.vector_fs.clone(),
+            retrieval_config: self.retrieval_config.clone(),
         }
     }
 }
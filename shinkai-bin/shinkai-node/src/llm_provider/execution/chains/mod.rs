@@ -3,3 +3,4 @@ pub mod inference_chain_router;
 pub mod inference_chain_trait;
 pub mod dsl_chain;
 pub mod generic_chain;
+pub mod planner_chain;
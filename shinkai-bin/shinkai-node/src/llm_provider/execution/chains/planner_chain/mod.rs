@@ -0,0 +1,2 @@
+pub mod planner_chain;
+pub mod planner_prompts;
@@ -0,0 +1,240 @@
+use crate::llm_provider::error::LLMProviderError;
+use crate::llm_provider::execution::chains::generic_chain::generic_inference_chain::GenericInferenceChain;
+use crate::llm_provider::execution::chains::inference_chain_trait::{
+    InferenceChain, InferenceChainContext, InferenceChainContextTrait, InferenceChainResult,
+};
+use crate::llm_provider::execution::prompts::prompts::{JobPromptGenerator, Prompt};
+use crate::llm_provider::job_manager::JobManager;
+use crate::network::ws_manager::{WSMessageType, WSMetadata, WSUpdateHandler};
+use crate::planner::task_plan::{PlanStepStatus, ProposedPlan, TaskPlan, TaskPlanStatus};
+use crate::tools::shinkai_tool::ShinkaiToolHeader;
+use async_trait::async_trait;
+use shinkai_message_primitives::schemas::inbox_name::InboxName;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::WSTopic;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use std::env;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Plans can't have more steps than this, counting the ones added when re-planning
+const MAX_PLAN_STEPS: usize = 20;
+
+/// Tools offered to the planner, found by searching the enabled tools with the goal
+const PLANNER_TOOLS_SEARCH_RESULTS: u64 = 10;
+
+/// Decomposes the goal of the user into a DAG of steps, runs each step through the generic chain once the ones it
+/// depends on are done, and answers with their results. When a step fails the remaining steps are planned again, up
+/// to PLANNER_MAX_REPLANS (default 2) times. The plan is saved so it can be fetched while it runs, and its progress
+/// is sent to the job inbox through the WebSocket.
+#[derive(Clone)]
+pub struct PlannerInferenceChain {
+    pub context: InferenceChainContext,
+    pub ws_manager_trait: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+}
+
+impl fmt::Debug for PlannerInferenceChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PlannerInferenceChain")
+            .field("context", &self.context)
+            .field("ws_manager_trait", &self.ws_manager_trait.is_some())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl InferenceChain for PlannerInferenceChain {
+    fn chain_id() -> String {
+        "planner_inference_chain".to_string()
+    }
+
+    fn chain_context(&mut self) -> &mut dyn InferenceChainContextTrait {
+        &mut self.context
+    }
+
+    async fn run_chain(&mut self) -> Result<InferenceChainResult, LLMProviderError> {
+        let goal = self.context.user_message.original_user_message_string.to_string();
+        let tools = self.available_tools(&goal).await;
+
+        let prompt = JobPromptGenerator::planner_create_plan_prompt(
+            goal.clone(),
+            &tools,
+            Some(self.context.full_job.step_history.clone()),
+        );
+        let proposed = self.propose_plan(prompt).await?;
+        let mut plan = TaskPlan::new(self.context.full_job.job_id.clone(), goal, proposed)
+            .map_err(LLMProviderError::PlanningFailed)?;
+        Self::check_plan_size(&plan)?;
+        self.save_progress(&plan, false).await?;
+
+        let max_replans = Self::max_replans();
+        loop {
+            let ready = plan.ready_steps();
+            if ready.is_empty() {
+                break;
+            }
+
+            for step_id in ready {
+                plan.update_step(&step_id, PlanStepStatus::Running, None, None);
+                self.save_progress(&plan, false).await?;
+
+                match self.execute_step(&plan, &step_id).await {
+                    Ok(output) => {
+                        plan.update_step(&step_id, PlanStepStatus::Done, Some(output), None);
+                        self.save_progress(&plan, false).await?;
+                    }
+                    Err(e) => {
+                        shinkai_log(
+                            ShinkaiLogOption::JobExecution,
+                            ShinkaiLogLevel::Error,
+                            &format!("Plan {} step {} failed: {}", plan.plan_id, step_id, e),
+                        );
+                        plan.update_step(&step_id, PlanStepStatus::Failed, None, Some(e.to_string()));
+                        if plan.replans >= max_replans {
+                            plan.status = TaskPlanStatus::Failed;
+                            self.save_progress(&plan, true).await?;
+                            return Err(LLMProviderError::PlanningFailed(format!(
+                                "Step {} failed after {} re-plans: {}",
+                                step_id, plan.replans, e
+                            )));
+                        }
+
+                        let prompt = JobPromptGenerator::planner_replan_prompt(&plan, &step_id, &tools);
+                        let proposed = self.propose_plan(prompt).await?;
+                        plan.replace_pending_steps(proposed)
+                            .map_err(LLMProviderError::PlanningFailed)?;
+                        Self::check_plan_size(&plan)?;
+                        plan.replans += 1;
+                        self.save_progress(&plan, false).await?;
+                        // The ready steps changed with the new plan
+                        break;
+                    }
+                }
+            }
+        }
+
+        if !plan.is_complete() {
+            plan.status = TaskPlanStatus::Failed;
+            self.save_progress(&plan, true).await?;
+            return Err(LLMProviderError::PlanningFailed(
+                "The plan has steps that can't run".to_string(),
+            ));
+        }
+        plan.status = TaskPlanStatus::Completed;
+        self.save_progress(&plan, true).await?;
+
+        // The final answer is streamed to the job inbox like the generic chain does
+        let inbox_name = InboxName::get_job_inbox_name_from_params(self.context.full_job.job_id.clone()).ok();
+        let response = JobManager::inference_with_llm_provider(
+            self.context.llm_provider.clone(),
+            JobPromptGenerator::planner_final_answer_prompt(&plan),
+            inbox_name,
+            self.ws_manager_trait.clone(),
+        )
+        .await?;
+
+        let mut execution_context = self.context.execution_context.clone();
+        execution_context.insert("plan_id".to_string(), plan.plan_id.clone());
+        Ok(InferenceChainResult::new(response.response_string, execution_context))
+    }
+}
+
+impl PlannerInferenceChain {
+    pub fn new(
+        context: InferenceChainContext,
+        ws_manager_trait: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) -> Self {
+        Self {
+            context,
+            ws_manager_trait,
+        }
+    }
+
+    fn max_replans() -> u32 {
+        env::var("PLANNER_MAX_REPLANS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(2)
+    }
+
+    fn check_plan_size(plan: &TaskPlan) -> Result<(), LLMProviderError> {
+        if plan.steps.len() > MAX_PLAN_STEPS {
+            return Err(LLMProviderError::PlanningFailed(format!(
+                "The plan has {} steps, the maximum is {}",
+                plan.steps.len(),
+                MAX_PLAN_STEPS
+            )));
+        }
+        Ok(())
+    }
+
+    async fn available_tools(&self, goal: &str) -> Vec<ShinkaiToolHeader> {
+        let Some(tool_router) = &self.context.tool_router else {
+            return vec![];
+        };
+        tool_router
+            .lock()
+            .await
+            .vector_search_enabled_tools(goal, PLANNER_TOOLS_SEARCH_RESULTS)
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Inferences the plan. The raw JSON isn't streamed to the job inbox.
+    async fn propose_plan(&self, prompt: Prompt) -> Result<ProposedPlan, LLMProviderError> {
+        let response =
+            JobManager::inference_with_llm_provider(self.context.llm_provider.clone(), prompt, None, None).await?;
+        ProposedPlan::from_llm_response(&response.response_string).map_err(LLMProviderError::PlanningFailed)
+    }
+
+    /// Runs the step through the generic chain, which finds and calls the tools it needs
+    async fn execute_step(&self, plan: &TaskPlan, step_id: &str) -> Result<String, LLMProviderError> {
+        let message = JobPromptGenerator::planner_step_message(plan, step_id);
+        GenericInferenceChain::start_chain(
+            self.context.db.clone(),
+            self.context.vector_fs.clone(),
+            self.context.full_job.clone(),
+            message,
+            self.context.llm_provider.clone(),
+            self.context.execution_context.clone(),
+            self.context.generator.clone(),
+            self.context.user_profile.clone(),
+            self.context.max_iterations,
+            self.context.max_tokens_in_prompt,
+            None,
+            self.context.tool_router.clone(),
+            self.context.scope_pruning.clone(),
+            self.context.retrieval_config.clone(),
+        )
+        .await
+        .map(|(response, _citations)| response)
+    }
+
+    /// Saves the plan and sends its progress to the job inbox
+    async fn save_progress(&self, plan: &TaskPlan, is_done: bool) -> Result<(), LLMProviderError> {
+        self.context.db.set_job_plan(plan)?;
+
+        if let Some(ws_manager) = &self.ws_manager_trait {
+            let inbox_name = InboxName::get_job_inbox_name_from_params(plan.job_id.clone())?;
+            let metadata = WSMetadata {
+                id: Some(plan.plan_id.clone()),
+                is_done,
+                done_reason: is_done.then(|| format!("{:?}", plan.status)),
+                total_duration: None,
+                eval_count: None,
+            };
+            ws_manager
+                .lock()
+                .await
+                .queue_message(
+                    WSTopic::Inbox,
+                    inbox_name.to_string(),
+                    plan.progress_text(),
+                    WSMessageType::Metadata(metadata),
+                    false,
+                )
+                .await;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use super::super::super::prompts::prompts::{JobPromptGenerator, Prompt};
+use crate::llm_provider::{execution::prompts::subprompts::SubPromptType, job::JobStepResult};
+use crate::planner::task_plan::{PlanStepStatus, TaskPlan};
+use crate::tools::shinkai_tool::ShinkaiToolHeader;
+
+const PLAN_FORMAT: &str = r#"Answer only with a JSON object in this format: {"steps": [{"id": "1", "description": "what the step does", "tool": "name of the tool to use or null", "depends_on": ["ids of the steps whose results it needs"]}]}. Keep the plan short, steps that don't depend on each other can run in any order."#;
+
+impl JobPromptGenerator {
+    /// Asks the LLM to decompose the goal into a DAG of steps using the available tools
+    pub fn planner_create_plan_prompt(
+        goal: String,
+        tools: &[ShinkaiToolHeader],
+        job_step_history: Option<Vec<JobStepResult>>,
+    ) -> Prompt {
+        let mut prompt = Prompt::new();
+        prompt.add_content(
+            "You are a planner. You split the goal of the user into the steps an assistant with the listed tools needs to take to achieve it.".to_string(),
+            SubPromptType::System,
+            99,
+        );
+        if let Some(step_history) = job_step_history {
+            prompt.add_step_history(step_history, 97);
+        }
+        prompt.add_content(Self::planner_tools_list(tools), SubPromptType::ExtraContext, 98);
+        prompt.add_content(format!("Goal: {}\n{}", goal, PLAN_FORMAT), SubPromptType::User, 100);
+        prompt
+    }
+
+    /// Asks the LLM for new steps replacing the ones that aren't done after a step failed
+    pub fn planner_replan_prompt(plan: &TaskPlan, failed_step_id: &str, tools: &[ShinkaiToolHeader]) -> Prompt {
+        let mut prompt = Prompt::new();
+        prompt.add_content(
+            "You are a planner. A step of the plan failed, plan the rest of the work again to still achieve the goal."
+                .to_string(),
+            SubPromptType::System,
+            99,
+        );
+        prompt.add_content(Self::planner_tools_list(tools), SubPromptType::ExtraContext, 98);
+
+        let mut content = format!("Goal: {}\nSteps already done:\n", plan.goal);
+        for step in plan.steps.iter().filter(|step| step.status == PlanStepStatus::Done) {
+            content.push_str(&format!(
+                "- {} ({}): {}\n",
+                step.step_id,
+                step.description,
+                step.output.clone().unwrap_or_default()
+            ));
+        }
+        if let Some(step) = plan.step(failed_step_id) {
+            content.push_str(&format!(
+                "Failed step {} ({}): {}\n",
+                step.step_id,
+                step.description,
+                step.error.clone().unwrap_or_default()
+            ));
+        }
+        content.push_str("New steps can depend on the ones already done, don't repeat them and use new ids. ");
+        content.push_str(PLAN_FORMAT);
+        prompt.add_content(content, SubPromptType::User, 100);
+        prompt
+    }
+
+    /// Message a step of the plan runs with, including the results of the steps it depends on
+    pub fn planner_step_message(plan: &TaskPlan, step_id: &str) -> String {
+        let Some(step) = plan.step(step_id) else {
+            return String::new();
+        };
+        let mut message = format!(
+            "As part of the goal \"{}\", do this step: {}",
+            plan.goal, step.description
+        );
+        if let Some(tool) = &step.tool {
+            message.push_str(&format!("\nUse the tool {}.", tool));
+        }
+        for dependency in step.depends_on.iter().filter_map(|id| plan.step(id)) {
+            message.push_str(&format!(
+                "\nResult of \"{}\": {}",
+                dependency.description,
+                dependency.output.clone().unwrap_or_default()
+            ));
+        }
+        message
+    }
+
+    /// Asks the LLM to answer the goal with the results of every step
+    pub fn planner_final_answer_prompt(plan: &TaskPlan) -> Prompt {
+        let mut prompt = Prompt::new();
+        prompt.add_content(
+            "You are a very helpful assistant. Answer the user with the results of the steps taken to achieve their goal."
+                .to_string(),
+            SubPromptType::System,
+            99,
+        );
+        for step in &plan.steps {
+            prompt.add_content(
+                format!(
+                    "Result of \"{}\": {}",
+                    step.description,
+                    step.output.clone().unwrap_or_default()
+                ),
+                SubPromptType::ExtraContext,
+                97,
+            );
+        }
+        prompt.add_content(plan.goal.clone(), SubPromptType::User, 100);
+        prompt
+    }
+
+    fn planner_tools_list(tools: &[ShinkaiToolHeader]) -> String {
+        if tools.is_empty() {
+            return "There are no tools available, the steps can only use the knowledge of the assistant.".to_string();
+        }
+        let mut content = "Available tools:\n".to_string();
+        for tool in tools {
+            content.push_str(&format!("- {}: {}\n", tool.name, tool.description));
+        }
+        content
+    }
+}
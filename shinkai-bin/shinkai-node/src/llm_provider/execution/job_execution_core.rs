@@ -7,6 +7,7 @@ use crate::llm_provider::job_manager::JobManager;
 use crate::llm_provider::parsing_helper::ParsingHelper;
 use crate::llm_provider::queue::job_queue_manager::{JobForProcessing, JobQueueManager};
 use crate::managers::model_capabilities_manager::{ModelCapabilitiesManager, ModelCapability};
+use crate::managers::profile_limits_manager::ProfileLimitsManager;
 use crate::managers::sheet_manager::SheetManager;
 use crate::network::ws_manager::WSUpdateHandler;
 use crate::tools::tool_router::ToolRouter;
@@ -109,6 +110,15 @@ impl JobManager {
         )
         .unwrap();
 
+        // Refuse to run more inferences once the profile used its monthly LLM token budget
+        let budget_check = db
+            .get_profile_limits(&user_profile.get_profile_name_string().unwrap_or_default())
+            .map_err(LLMProviderError::from)
+            .and_then(|limits| ProfileLimitsManager::check_llm_budget(&db, &limits));
+        if let Err(e) = budget_check {
+            return Self::handle_error(&db, Some(user_profile), &job_id, &identity_secret_key, e, ws_manager).await;
+        }
+
         // Note: remove later on. This code is for the meantime only while we add embeddings to tools so they can get added at the first Shinkai start
         {
             if let Some(tool_router) = tool_router.clone() {
@@ -311,6 +321,7 @@ impl JobManager {
         .await?;
         let inference_response_content = inference_response.response;
         let new_execution_context = inference_response.new_job_execution_context;
+        let citations = inference_response.citations;
 
         let duration = start.elapsed();
         shinkai_log(
@@ -344,6 +355,13 @@ impl JobManager {
             inference_response_content.to_string(),
             None,
         )?;
+        if !citations.is_empty() {
+            db.set_job_message_citations(
+                &job_message.job_id,
+                &shinkai_message.calculate_message_hash_for_pagination(),
+                &citations,
+            )?;
+        }
         db.add_message_to_job_inbox(&job_message.job_id.clone(), &shinkai_message, None, ws_manager)
             .await?;
         db.set_job_execution_context(job_message.job_id.clone(), new_execution_context, None)?;
@@ -470,7 +488,7 @@ impl JobManager {
         workflow: Workflow,
     ) -> Result<InferenceChainResult, LLMProviderError> {
         let llm_provider = llm_provider_found.ok_or(LLMProviderError::LLMProviderNotFound)?;
-        let max_tokens_in_prompt = ModelCapabilitiesManager::get_max_input_tokens(&llm_provider.model);
+        let max_tokens_in_prompt = ModelCapabilitiesManager::get_max_input_tokens_with_probe(&db, &llm_provider);
         let parsed_user_message = ParsedUserMessage::new(message_content);
         let full_execution_context = full_job.execution_context.clone();
 
@@ -489,6 +507,7 @@ impl JobManager {
             ws_manager.clone(),
             tool_router.clone(),
         );
+        chain_context.update_retrieval_config(JobManager::job_retrieval_config(&db, job_message)?);
 
         // Process files
         {
@@ -894,7 +913,7 @@ impl JobManager {
         vector_fs: Arc<VectorFS>,
         agent: Option<SerializedLLMProvider>,
         files_inbox: String,
-        _profile: ShinkaiName,
+        profile: ShinkaiName,
         save_to_vector_fs_folder: Option<VRPath>,
         generator: RemoteEmbeddingGenerator,
         unstructured_api: UnstructuredAPI,
@@ -943,8 +962,16 @@ impl JobManager {
             false => FileParser::Unstructured(unstructured_api),
         };
 
-        let processed_vrkais =
-            ParsingHelper::process_files_into_vrkai(dist_files, &generator, agent.clone(), file_parser).await?;
+        let processed_vrkais = ParsingHelper::process_files_into_vrkai(
+            dist_files,
+            &generator,
+            agent.clone(),
+            file_parser,
+            &ParsingHelper::ingestion_settings(&db, &profile),
+            Some(&db.events),
+            None,
+        )
+        .await?;
 
         // Save the vrkai into scope (and potentially VectorFS)
         for (filename, vrkai) in processed_vrkais {
@@ -9,7 +9,9 @@ use crate::llm_provider::llm_provider::LLMProvider;
 use crate::network::ws_manager::WSUpdateHandler;
 use shinkai_message_primitives::schemas::inbox_name::InboxName;
 use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider;
+use shinkai_message_primitives::schemas::retrieval_config::RetrievalConfig;
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::JobMessage;
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
 use tokio::sync::Mutex;
 use std::result::Result::Ok;
@@ -72,4 +74,14 @@ impl JobManager {
     pub async fn get_all_llm_providers(db: Arc<ShinkaiDB>) -> Result<Vec<SerializedLLMProvider>, ShinkaiDBError> {
         db.get_all_llm_providers()
     }
+
+    /// Retrieval parameters for processing the job message: the ones persisted in the job, overridden by the message's
+    pub fn job_retrieval_config(db: &ShinkaiDB, job_message: &JobMessage) -> Result<RetrievalConfig, ShinkaiDBError> {
+        let job_config = db.get_job_retrieval_config(&job_message.job_id)?;
+
+        Ok(match &job_message.retrieval {
+            Some(overrides) => job_config.merged_with(overrides),
+            None => job_config,
+        })
+    }
 }
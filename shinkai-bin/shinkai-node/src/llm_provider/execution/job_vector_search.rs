@@ -1,16 +1,21 @@
 use crate::db::db_errors::ShinkaiDBError;
 use crate::db::ShinkaiDB;
+use crate::llm_provider::execution::scope_pruning::ScopePruningStrategy;
 use crate::llm_provider::job_manager::JobManager;
+use crate::managers::model_capabilities_manager::ModelCapabilitiesManager;
 use crate::vector_fs::vector_fs::VectorFS;
+use crate::vector_fs::vector_fs_types::FSItem;
 use keyphrases::KeyPhraseExtractor;
+use shinkai_message_primitives::schemas::retrieval_config::RetrievalConfig;
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
 use shinkai_message_primitives::shinkai_utils::job_scope::JobScope;
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
 use shinkai_vector_resources::embedding_generator::{EmbeddingGenerator, RemoteEmbeddingGenerator};
 use shinkai_vector_resources::embeddings::Embedding;
+use shinkai_vector_resources::source::{SourceFile, StandardSourceFile};
 use shinkai_vector_resources::vector_resource::{
     deep_search_scores_average_out, BaseVectorResource, Node, ResultsMode, RetrievedNode, ScoringMode, TraversalMethod,
-    TraversalOption,
+    TraversalOption, VRPath,
 };
 use std::collections::HashMap;
 use std::result::Result::Ok;
@@ -20,6 +25,8 @@ impl JobManager {
     /// Performs multiple proximity vector searches within the job scope based on extracting keywords from the query text.
     /// Attempts to take at least 1 proximity group per keyword that is from a VR different than the highest scored node, to encourage wider diversity in results.
     /// Returns the search results and the description/summary text of the VR the highest scored retrieved node is from.
+    /// The scope is first pruned by `scope_pruning` using the whole query text, and the keyword searches reuse it.
+    /// The fields set in `retrieval` replace `num_of_top_results` and the default traversal, and filter the results.
     #[allow(clippy::too_many_arguments)]
     pub async fn keyword_chained_job_scope_vector_search(
         db: Arc<ShinkaiDB>,
@@ -30,10 +37,16 @@ impl JobManager {
         generator: RemoteEmbeddingGenerator,
         num_of_top_results: u64,
         max_tokens_in_prompt: usize,
+        scope_pruning: Arc<dyn ScopePruningStrategy>,
+        retrieval: &RetrievalConfig,
     ) -> Result<(Vec<RetrievedNode>, Option<String>), ShinkaiDBError> {
+        let num_of_top_results = retrieval.top_k.unwrap_or(num_of_top_results);
         let mut master_intro_hashmap: HashMap<String, Vec<RetrievedNode>> = HashMap::new();
         // First perform a standard job scope vector search using the whole query text
         let query = generator.generate_embedding_default(&query_text).await?;
+        let job_scope = &scope_pruning
+            .prune_scope(&vector_fs, user_profile, job_scope, &query)
+            .await;
         let (mut ret_groups, intro_hashmap) = JobManager::internal_job_scope_vector_search_groups(
             db.clone(),
             vector_fs.clone(),
@@ -45,6 +58,7 @@ impl JobManager {
             true,
             generator.clone(),
             max_tokens_in_prompt,
+            retrieval,
         )
         .await?;
         // Insert the contents of intro_hashmap into master_intro_hashmap
@@ -78,6 +92,7 @@ impl JobManager {
                     true,
                     generator.clone(),
                     max_tokens_in_prompt,
+                    retrieval,
                 )
                 .await?;
 
@@ -126,6 +141,14 @@ impl JobManager {
             }
         }
 
+        // Drop the results scored below the minimum, and the groups left empty
+        if let Some(min_score) = retrieval.min_score {
+            for group in ret_groups.iter_mut() {
+                group.retain(|node| node.score >= min_score);
+            }
+            ret_groups.retain(|group| !group.is_empty());
+        }
+
         // For the top N groups, fetch their VRs' intros and include them at the front of the list
         // We do this by iterating in reverse order (ex. 5th, 4th, 3rd, 2nd, 1st), so highest scored VR intro will be at the top.
         let num_groups = Self::determine_num_groups_for_intro_fetch(max_tokens_in_prompt);
@@ -170,6 +193,20 @@ impl JobManager {
             }
         }
 
+        // Keep the highest ranked nodes that fit in the context tokens
+        if let Some(max_context_tokens) = retrieval.max_context_tokens {
+            let mut context_tokens = 0;
+            final_nodes = final_nodes
+                .into_iter()
+                .take_while(|node| {
+                    context_tokens += ModelCapabilitiesManager::count_tokens_from_message_llama3(
+                        node.node.get_text_content().unwrap_or_default(),
+                    );
+                    context_tokens <= max_context_tokens
+                })
+                .collect();
+        }
+
         // println!(
         //     "\n\n\nDone Vector Searching: {}\n------------------------------------------------",
         //     query_text
@@ -220,6 +257,7 @@ impl JobManager {
         include_description: bool,
         generator: RemoteEmbeddingGenerator,
         max_tokens_in_prompt: usize,
+        retrieval: &RetrievalConfig,
     ) -> Result<Vec<RetrievedNode>, ShinkaiDBError> {
         let results = Self::internal_job_scope_vector_search_groups(
             db,
@@ -232,6 +270,7 @@ impl JobManager {
             include_description,
             generator,
             max_tokens_in_prompt,
+            retrieval,
         )
         .await?;
 
@@ -257,6 +296,7 @@ impl JobManager {
         _include_description: bool,
         generator: RemoteEmbeddingGenerator,
         max_tokens_in_prompt: usize,
+        retrieval: &RetrievalConfig,
     ) -> Result<(Vec<Vec<RetrievedNode>>, HashMap<String, Vec<RetrievedNode>>), ShinkaiDBError> {
         let average_out_deep_search_scores = true;
         let proximity_window_size = Self::determine_proximity_window_size(max_tokens_in_prompt);
@@ -265,10 +305,12 @@ impl JobManager {
         let mut intro_hashmap: HashMap<String, Vec<RetrievedNode>> = HashMap::new();
 
         // Setup vars used across searches
-        let deep_traversal_options = vec![
+        let mut deep_traversal_options = vec![
             TraversalOption::SetScoringMode(ScoringMode::HierarchicalAverageScoring),
             TraversalOption::SetResultsMode(ResultsMode::ProximitySearch(proximity_window_size, num_of_top_results)),
         ];
+        deep_traversal_options.extend(retrieval.traversal_options());
+        let traversal_method = retrieval.traversal_method(TraversalMethod::Exhaustive);
         let num_of_resources_to_search_into = 50;
         let mut retrieved_node_groups = Vec::new();
 
@@ -282,7 +324,7 @@ impl JobManager {
                     &vec![],
                     None,
                     total_num_of_results,
-                    TraversalMethod::Exhaustive,
+                    traversal_method.clone(),
                     &deep_traversal_options,
                     generator.clone(),
                     average_out_deep_search_scores,
@@ -363,7 +405,7 @@ impl JobManager {
             let mut results = resource.as_trait_object().vector_search_customized(
                 query.clone(),
                 total_num_of_results,
-                TraversalMethod::Exhaustive,
+                traversal_method.clone(),
                 &deep_traversal_options,
                 None,
             );
@@ -410,6 +452,62 @@ impl JobManager {
         Ok((sorted_retrieved_node_groups, intro_hashmap))
     }
 
+    /// Finds the images in the VectorFS folders and items of the job scope most similar to the query text, by the
+    /// image embeddings generated when they were ingested. Returns the images' FSItems with their source files.
+    pub async fn job_scope_image_search(
+        vector_fs: Arc<VectorFS>,
+        job_scope: &JobScope,
+        query_text: String,
+        profile: &ShinkaiName,
+        image_generator: &RemoteEmbeddingGenerator,
+        num_of_results: u64,
+    ) -> Result<Vec<(FSItem, StandardSourceFile)>, ShinkaiDBError> {
+        let query = image_generator.generate_embedding_default(&query_text).await?;
+        let image_model = image_generator.model_type();
+
+        // Items are searched from their parent folder, keeping only the item itself
+        let mut search_paths: Vec<(VRPath, Option<VRPath>)> = job_scope
+            .vector_fs_folders
+            .iter()
+            .map(|folder| (folder.path.clone(), None))
+            .collect();
+        search_paths.extend(
+            job_scope
+                .vector_fs_items
+                .iter()
+                .map(|item| (item.path.parent_path(), Some(item.path.clone()))),
+        );
+
+        let mut images_with_scores: Vec<(FSItem, f32)> = vec![];
+        for (path, item_path) in search_paths {
+            let reader = vector_fs.new_reader(profile.clone(), path, profile.clone()).await?;
+            let results = vector_fs
+                .vector_search_images_with_score(&reader, query.clone(), &image_model, num_of_results)
+                .await?;
+            for (item, score) in results {
+                let in_scope = item_path.as_ref().map_or(true, |item_path| &item.path == item_path);
+                if in_scope && !images_with_scores.iter().any(|(found, _)| found.path == item.path) {
+                    images_with_scores.push((item, score));
+                }
+            }
+        }
+        images_with_scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        images_with_scores.truncate(num_of_results as usize);
+
+        let mut images = vec![];
+        for (item, _) in images_with_scores {
+            let reader = vector_fs
+                .new_reader(profile.clone(), item.path.clone(), profile.clone())
+                .await?;
+            let source_file_map = vector_fs.retrieve_source_file_map(&reader).await?;
+            if let Some(SourceFile::Standard(image)) = source_file_map.get_source_file(VRPath::root()) {
+                images.push((item, image.clone()));
+            }
+        }
+
+        Ok(images)
+    }
+
     /// Determines the proximity window size based on the max tokens supported by the model
     fn determine_proximity_window_size(max_tokens_in_prompt: usize) -> u64 {
         if max_tokens_in_prompt < 5000 {
@@ -5,4 +5,5 @@ pub mod job_execution_helpers;
 pub mod job_scope_helpers;
 pub mod job_vector_search;
 pub mod prompts;
+pub mod scope_pruning;
 pub mod user_message_parser;
@@ -123,6 +123,52 @@ impl JobPromptGenerator {
         prompt
     }
 
+    /// Prompt for having a search query translated into the language of the documents searched
+    pub fn query_translation(query: String, language: String) -> Prompt {
+        let mut prompt = Prompt::new();
+        prompt.add_content(
+            "You are a translator. Only answer with the translation, without quotes, notes or explanations."
+                .to_string(),
+            SubPromptType::System,
+            99,
+        );
+        prompt.add_content(format!("Translate this search query into {}:", language), SubPromptType::User, 100);
+        prompt.add_content(query, SubPromptType::User, 100);
+
+        prompt
+    }
+
+    /// Prompt for naming a conversation from its first message and the answer to it
+    pub fn inbox_title(message: String, answer: String) -> Prompt {
+        let mut prompt = Prompt::new();
+        prompt.add_content(
+            "You name conversations. Only answer with a short title of at most 6 words that describes the topic of the conversation, without quotes or punctuation at the end."
+                .to_string(),
+            SubPromptType::System,
+            99,
+        );
+        prompt.add_content(format!("First message: {}", message), SubPromptType::User, 100);
+        prompt.add_content(format!("Answer: {}", answer), SubPromptType::User, 98);
+        prompt.add_content("Title of the conversation:".to_string(), SubPromptType::User, 100);
+
+        prompt
+    }
+
+    /// Prompt for summarizing the activity of the inboxes of a user since their last digest
+    pub fn inbox_digest(activity: String) -> Prompt {
+        let mut prompt = Prompt::new();
+        prompt.add_content(
+            "You write digests of the activity of the user's inboxes. Summarize the unread messages by conversation and the results of the finished jobs in a few short bullet points each, highlighting what needs the user's attention. Don't make up anything that isn't in the activity."
+                .to_string(),
+            SubPromptType::System,
+            99,
+        );
+        prompt.add_content(format!("Activity:\n{}", activity), SubPromptType::User, 100);
+        prompt.add_content("Digest:".to_string(), SubPromptType::User, 100);
+
+        prompt
+    }
+
     /// Prompt for having the description of a cron translated to a cron expression
     pub fn image_to_text_analysis(description: String, image: String) -> Prompt {
         let mut prompt = Prompt::new();
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_utils::job_scope::{JobScope, VectorFSFolderScopeEntry};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use shinkai_vector_resources::embeddings::Embedding;
+
+use crate::vector_fs::vector_fs::VectorFS;
+
+/// Number of VectorFS folders of a job scope deep searched per query when JOB_SCOPE_PRUNING_MAX_FOLDERS isn't set
+const DEFAULT_MAX_FOLDERS: usize = 5;
+
+/// Decides which parts of a job scope are worth deep searching for a query, before the vector search runs.
+/// The inference chain context holds the strategy, so chains and tests can swap it.
+#[async_trait]
+pub trait ScopePruningStrategy: Send + Sync {
+    /// Returns a hardcoded String that uniquely identifies the strategy
+    fn strategy_id(&self) -> String;
+
+    /// Returns the scope to search for the query. Never fails: whatever can't be scored is kept.
+    async fn prune_scope(
+        &self,
+        vector_fs: &VectorFS,
+        profile: &ShinkaiName,
+        job_scope: &JobScope,
+        query: &Embedding,
+    ) -> JobScope;
+}
+
+/// Searches the whole scope
+pub struct NoScopePruning;
+
+#[async_trait]
+impl ScopePruningStrategy for NoScopePruning {
+    fn strategy_id(&self) -> String {
+        "none".to_string()
+    }
+
+    async fn prune_scope(
+        &self,
+        _vector_fs: &VectorFS,
+        _profile: &ShinkaiName,
+        job_scope: &JobScope,
+        _query: &Embedding,
+    ) -> JobScope {
+        job_scope.clone()
+    }
+}
+
+/// Deep searches only the `max_folders` VectorFS folders whose embeddings centroid is the most similar to the
+/// query. Scopes with fewer folders are searched whole, and items and local resources are never pruned.
+pub struct CentroidScopePruning {
+    pub max_folders: usize,
+}
+
+impl CentroidScopePruning {
+    /// Keeps the `max_folders` best scored folders, in their order in the scope
+    fn most_relevant(
+        folders: &[VectorFSFolderScopeEntry],
+        scores: &[f32],
+        max_folders: usize,
+    ) -> Vec<VectorFSFolderScopeEntry> {
+        let mut ranked: Vec<usize> = (0..folders.len()).collect();
+        ranked.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+        ranked.truncate(max_folders);
+        ranked.sort();
+
+        ranked.into_iter().map(|index| folders[index].clone()).collect()
+    }
+}
+
+#[async_trait]
+impl ScopePruningStrategy for CentroidScopePruning {
+    fn strategy_id(&self) -> String {
+        "centroid".to_string()
+    }
+
+    async fn prune_scope(
+        &self,
+        vector_fs: &VectorFS,
+        profile: &ShinkaiName,
+        job_scope: &JobScope,
+        query: &Embedding,
+    ) -> JobScope {
+        let folders = &job_scope.vector_fs_folders;
+        if folders.len() <= self.max_folders {
+            return job_scope.clone();
+        }
+
+        let mut scores = Vec::with_capacity(folders.len());
+        for folder in folders {
+            let score = match vector_fs.folder_embeddings_centroid(folder.path.clone(), profile).await {
+                Ok(Some(centroid)) => query.cosine_similarity(&centroid),
+                // Empty folders have nothing to find
+                Ok(None) => f32::MIN,
+                Err(e) => {
+                    shinkai_log(
+                        ShinkaiLogOption::JobExecution,
+                        ShinkaiLogLevel::Error,
+                        &format!("Failed to score folder {} of the job scope: {}", folder.path, e),
+                    );
+                    f32::MAX
+                }
+            };
+            scores.push(score);
+        }
+
+        let mut pruned = job_scope.clone();
+        pruned.vector_fs_folders = Self::most_relevant(folders, &scores, self.max_folders);
+        shinkai_log(
+            ShinkaiLogOption::JobExecution,
+            ShinkaiLogLevel::Info,
+            &format!(
+                "Pruned the job scope to {} of {} folders",
+                pruned.vector_fs_folders.len(),
+                folders.len()
+            ),
+        );
+        pruned
+    }
+}
+
+/// Strategy of new inference chain contexts. JOB_SCOPE_PRUNING_MAX_FOLDERS sets how many folders are deep
+/// searched per query (default 5), and 0 turns pruning off.
+pub fn default_scope_pruning() -> Arc<dyn ScopePruningStrategy> {
+    let max_folders = std::env::var("JOB_SCOPE_PRUNING_MAX_FOLDERS")
+        .ok()
+        .and_then(|max| max.parse().ok())
+        .unwrap_or(DEFAULT_MAX_FOLDERS);
+
+    if max_folders == 0 {
+        Arc::new(NoScopePruning)
+    } else {
+        Arc::new(CentroidScopePruning { max_folders })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shinkai_vector_resources::vector_resource::VRPath;
+
+    fn folder(name: &str) -> VectorFSFolderScopeEntry {
+        VectorFSFolderScopeEntry {
+            name: name.to_string(),
+            path: VRPath::root().push_cloned(name.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_most_relevant_keeps_best_scored_in_scope_order() {
+        let folders = vec![folder("a"), folder("b"), folder("c"), folder("d")];
+        let scores = vec![0.2, 0.9, f32::MIN, 0.5];
+
+        let kept = CentroidScopePruning::most_relevant(&folders, &scores, 2);
+
+        assert_eq!(kept, vec![folder("b"), folder("d")]);
+    }
+}
@@ -1,16 +1,23 @@
 use super::error::LLMProviderError;
 use super::job_callback_manager::JobCallbackManager;
+use super::job_status::{JobState, JobStatusTracker};
 use super::queue::job_queue_manager::{JobForProcessing, JobQueueManager};
 use crate::db::{ShinkaiDB, Topic};
 use crate::llm_provider::job::JobLike;
 use crate::llm_provider::llm_provider::LLMProvider;
+use crate::managers::profile_limits_manager::ProfileLimitsManager;
 use crate::managers::sheet_manager::SheetManager;
 use crate::managers::IdentityManager;
+use crate::network::node_events::NodeEventType;
+use crate::network::panic_isolation::catch_panic;
+use crate::network::request_id::with_optional_request_id;
 use crate::network::ws_manager::WSUpdateHandler;
+use crate::schemas::notification::NotificationKind;
 use crate::tools::tool_router::ToolRouter;
 use crate::vector_fs::vector_fs::VectorFS;
 use ed25519_dalek::SigningKey;
 use futures::Future;
+use serde_json::json;
 use shinkai_message_primitives::schemas::inbox_name::InboxName;
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
 use shinkai_message_primitives::{
@@ -23,7 +30,6 @@ use shinkai_message_primitives::{
 };
 use shinkai_vector_resources::embedding_generator::RemoteEmbeddingGenerator;
 use shinkai_vector_resources::file_parser::unstructured_api::UnstructuredAPI;
-use std::collections::HashSet;
 use std::env;
 use std::pin::Pin;
 use std::result::Result::Ok;
@@ -210,7 +216,8 @@ impl JobManager {
         let identity_sk = clone_signature_secret_key(&identity_sk);
         let job_processing_fn = Arc::new(job_processing_fn);
 
-        let processing_jobs = Arc::new(Mutex::new(HashSet::new()));
+        // Jobs being processed, with the profile they belong to
+        let processing_jobs = Arc::new(Mutex::new(HashMap::<String, String>::new()));
         let semaphore = Arc::new(Semaphore::new(max_parallel_jobs));
 
         return tokio::spawn(async move {
@@ -234,22 +241,39 @@ impl JobManager {
                         .unwrap_or(Vec::new());
                     std::mem::drop(job_queue_manager_lock);
 
-                    let filtered_jobs = all_jobs
+                    // Jobs of a profile over its max_concurrent_jobs wait for the next iteration
+                    let db = db_clone.upgrade();
+                    let mut profile_limits: HashMap<String, Option<usize>> = HashMap::new();
+                    let queued_jobs = all_jobs
                         .into_iter()
-                        .filter_map(|job| {
-                            let job_id = job.job_message.job_id.clone().to_string();
-                            if !processing_jobs_lock.contains(&job_id) {
-                                processing_jobs_lock.insert(job_id.clone());
-                                Some(job_id)
-                            } else {
-                                None
-                            }
+                        .map(|job| {
+                            (
+                                job.job_message.job_id.clone(),
+                                job.profile.get_profile_name_string().unwrap_or_default(),
+                            )
                         })
-                        .take(max_parallel_jobs)
-                        .collect::<Vec<_>>();
+                        .collect();
+                    let (filtered_jobs, deferred_jobs) = Self::select_jobs_to_process(
+                        queued_jobs,
+                        &processing_jobs_lock,
+                        max_parallel_jobs,
+                        |profile_name| {
+                            *profile_limits.entry(profile_name.to_string()).or_insert_with(|| {
+                                db.as_ref()
+                                    .and_then(|db| db.get_profile_limits(profile_name).ok())
+                                    .and_then(|limits| limits.max_concurrent_jobs)
+                            })
+                        },
+                    );
+                    for (job_id, profile_name) in &filtered_jobs {
+                        processing_jobs_lock.insert(job_id.clone(), profile_name.clone());
+                    }
+                    let filtered_jobs: Vec<String> = filtered_jobs.into_iter().map(|(job_id, _)| job_id).collect();
 
-                    // Check if the number of jobs to process is equal to max_parallel_jobs
-                    continue_immediately = filtered_jobs.len() == max_parallel_jobs;
+                    // Check if the number of jobs to process is equal to max_parallel_jobs, or if jobs are
+                    // waiting for their profile to finish others
+                    continue_immediately =
+                        filtered_jobs.len() == max_parallel_jobs || (deferred_jobs && !filtered_jobs.is_empty());
 
                     std::mem::drop(processing_jobs_lock);
                     filtered_jobs
@@ -284,9 +308,26 @@ impl JobManager {
 
                         match job {
                             Ok(Some(job)) => {
+                                JobStatusTracker::set_state(&job_id, JobState::Running, None);
+                                let events = db_clone_2.upgrade().map(|db| db.events.clone());
+                                if let Some(events) = &events {
+                                    events.publish(
+                                        NodeEventType::JobStatus,
+                                        json!({ "job_id": job_id, "status": "processing" }),
+                                    );
+                                }
+
+                                let job_profile = job.profile.clone();
+                                let db_weak = db_clone_2.clone();
+
                                 // Acquire the lock, process the job, and immediately release the lock
+                                #[cfg(feature = "telemetry")]
+                                let start_time = std::time::Instant::now();
                                 let result = {
-                                    let result = job_processing_fn(
+                                    let request_id = job.request_id.clone();
+                                    let profile_name = job.profile.get_profile_name_string().unwrap_or_default();
+                                    let db = db_clone_2.upgrade();
+                                    let job_processing = job_processing_fn(
                                         job,
                                         db_clone_2,
                                         vector_fs_clone_2,
@@ -299,8 +340,15 @@ impl JobManager {
                                         sheet_manager,
                                         callback_manager,
                                         job_queue_manager.clone(),
-                                    )
-                                    .await;
+                                    );
+                                    // A panic fails the job instead of leaving it in processing forever
+                                    let processing = catch_panic(
+                                        "Job processing",
+                                        ProfileLimitsManager::track_llm_usage(db, profile_name, job_processing),
+                                    );
+                                    let result = with_optional_request_id(request_id, processing)
+                                        .await
+                                        .unwrap_or_else(|e| Err(LLMProviderError::TaskJoinError(e.message)));
                                     if let Ok(Some(_)) = job_queue_manager.lock().await.dequeue(&job_id.clone()).await {
                                         result
                                     } else {
@@ -308,6 +356,11 @@ impl JobManager {
                                     }
                                 };
 
+                                #[cfg(feature = "telemetry")]
+                                crate::utils::open_telemetry::record_job_execution(
+                                    start_time.elapsed(),
+                                    result.is_ok(),
+                                );
                                 if result.is_ok() {
                                     shinkai_log(
                                         ShinkaiLogOption::JobExecution,
@@ -315,6 +368,32 @@ impl JobManager {
                                         "Job processed successfully",
                                     );
                                 }
+                                match &result {
+                                    Ok(_) => JobStatusTracker::set_state(&job_id, JobState::Done, None),
+                                    Err(e) => {
+                                        JobStatusTracker::set_state(&job_id, JobState::Error, Some(e.to_string()))
+                                    }
+                                }
+                                if let Some(events) = &events {
+                                    let status = match &result {
+                                        Ok(_) => json!({ "job_id": job_id, "status": "done" }),
+                                        Err(e) => json!({ "job_id": job_id, "status": "failed", "error": e.to_string() }),
+                                    };
+                                    events.publish(NodeEventType::JobStatus, status);
+                                }
+                                if let Some(db) = db_weak.upgrade() {
+                                    let (kind, message) = match &result {
+                                        Ok(_) => (NotificationKind::JobDone, format!("Job {} finished.", job_id)),
+                                        Err(e) => (NotificationKind::Error, format!("Job {} failed: {}", job_id, e)),
+                                    };
+                                    if let Err(e) = db.write_notification_of_kind(job_profile, kind, message) {
+                                        shinkai_log(
+                                            ShinkaiLogOption::JobExecution,
+                                            ShinkaiLogLevel::Error,
+                                            &format!("Failed to write the notification of job {}: {}", job_id, e),
+                                        );
+                                    }
+                                }
                             }
                             Ok(None) => {}
                             Err(_) => {
@@ -349,9 +428,50 @@ impl JobManager {
         });
     }
 
+    /// Picks up to `max_jobs` of the queued (job id, profile name) pairs to start. Jobs already running are skipped,
+    /// and count towards the max_concurrent_jobs of their profile along with the picked ones. Also returns whether
+    /// jobs were left waiting because their profile reached its limit.
+    pub fn select_jobs_to_process(
+        queued_jobs: Vec<(String, String)>,
+        running_jobs: &HashMap<String, String>,
+        max_jobs: usize,
+        mut max_concurrent_jobs: impl FnMut(&str) -> Option<usize>,
+    ) -> (Vec<(String, String)>, bool) {
+        let mut jobs_per_profile: HashMap<String, usize> = HashMap::new();
+        for profile_name in running_jobs.values() {
+            *jobs_per_profile.entry(profile_name.clone()).or_insert(0) += 1;
+        }
+
+        let mut selected_jobs = Vec::new();
+        let mut deferred_jobs = false;
+        for (job_id, profile_name) in queued_jobs {
+            if selected_jobs.len() >= max_jobs {
+                break;
+            }
+            if running_jobs.contains_key(&job_id) {
+                continue;
+            }
+
+            let max_concurrent_jobs = max_concurrent_jobs(&profile_name);
+            let profile_jobs = jobs_per_profile.entry(profile_name.clone()).or_insert(0);
+            if max_concurrent_jobs.is_some_and(|max| *profile_jobs >= max) {
+                deferred_jobs = true;
+                continue;
+            }
+
+            *profile_jobs += 1;
+            selected_jobs.push((job_id, profile_name));
+        }
+        (selected_jobs, deferred_jobs)
+    }
+
     pub async fn process_job_message(&mut self, message: ShinkaiMessage) -> Result<String, LLMProviderError> {
         let profile = ShinkaiName::from_shinkai_message_using_recipient_subidentity(&message)?;
 
+        if let (Some(db), Some(profile_name)) = (self.db.upgrade(), profile.get_profile_name_string()) {
+            ProfileLimitsManager::check_rate_limit(&db.get_profile_limits(&profile_name)?)?;
+        }
+
         if self.is_job_message(message.clone()) {
             match message.clone().body {
                 MessageBody::Unencrypted(body) => {
@@ -421,6 +541,9 @@ impl JobManager {
                 Ok(_) => (),
                 Err(err) => return Err(LLMProviderError::ShinkaiDB(err)),
             };
+            if let Some(retrieval) = &job_creation.retrieval {
+                db_arc.set_job_retrieval_config(&job_id, retrieval)?;
+            }
 
             match db_arc.get_job(&job_id) {
                 Ok(job) => {
@@ -488,6 +611,8 @@ impl JobManager {
             }
             let inbox_name = InboxName::get_job_inbox_name_from_params(job_message.job_id.to_string())?.to_string();
             db_arc.update_smart_inbox_name(&inbox_name.to_string(), &content)?;
+            // The truncated message is replaced by a title once the first answer arrives
+            db_arc.add_inbox_pending_title(&inbox_name, &profile)?;
         }
 
         db_arc
@@ -518,3 +643,52 @@ impl JobManager {
         Ok(job_message.job_id.clone().to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(jobs: &[(&str, &str)]) -> Vec<(String, String)> {
+        jobs.iter()
+            .map(|(job_id, profile)| (job_id.to_string(), profile.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_select_jobs_counts_running_jobs() {
+        // Alice can run 2 jobs at a time and one is still running from the previous loop
+        let running_jobs = HashMap::from([("alice_1".to_string(), "alice".to_string())]);
+        let limits = |profile: &str| if profile == "alice" { Some(2) } else { None };
+
+        let (selected, deferred) = JobManager::select_jobs_to_process(
+            queued(&[("alice_1", "alice"), ("alice_2", "alice"), ("alice_3", "alice"), ("bob_1", "bob")]),
+            &running_jobs,
+            10,
+            limits,
+        );
+        assert_eq!(selected, queued(&[("alice_2", "alice"), ("bob_1", "bob")]));
+        assert!(deferred);
+
+        // Once both of Alice's jobs are running, none of hers is started
+        let running_jobs = HashMap::from([
+            ("alice_1".to_string(), "alice".to_string()),
+            ("alice_2".to_string(), "alice".to_string()),
+        ]);
+        let (selected, deferred) =
+            JobManager::select_jobs_to_process(queued(&[("alice_3", "alice")]), &running_jobs, 10, limits);
+        assert!(selected.is_empty());
+        assert!(deferred);
+    }
+
+    #[test]
+    fn test_select_jobs_without_limits() {
+        let (selected, deferred) = JobManager::select_jobs_to_process(
+            queued(&[("job_1", "alice"), ("job_2", "alice"), ("job_3", "bob")]),
+            &HashMap::new(),
+            2,
+            |_| None,
+        );
+        assert_eq!(selected, queued(&[("job_1", "alice"), ("job_2", "alice")]));
+        assert!(!deferred);
+    }
+}
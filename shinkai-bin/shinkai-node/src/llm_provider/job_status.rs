@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+lazy_static! {
+    /// Latest state of the jobs processed since the node started, set by the job queue
+    static ref JOB_STATES: Mutex<HashMap<String, (JobState, Option<String>)>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    /// Messages of the job are waiting for a free worker
+    Queued,
+    Running,
+    Done,
+    /// Processing the latest message failed
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct JobStatus {
+    pub job_id: String,
+    pub state: JobState,
+    /// Messages waiting to be processed, including the one being processed
+    pub queued_messages: usize,
+    /// Steps in the history of the job, which grows as it answers
+    pub steps: usize,
+    /// Why processing the latest message failed
+    pub error: Option<String>,
+    /// Latest answer of the agent in the job inbox
+    pub latest_output: Option<String>,
+}
+
+pub struct JobStatusTracker {}
+
+impl JobStatusTracker {
+    pub fn set_state(job_id: &str, state: JobState, error: Option<String>) {
+        JOB_STATES.lock().unwrap().insert(job_id.to_string(), (state, error));
+    }
+
+    /// State of a job from what the queue last reported and its messages waiting in the queue. Jobs that weren't
+    /// processed since the node started are done once their queue is empty.
+    pub fn current_state(job_id: &str, queued_messages: usize) -> (JobState, Option<String>) {
+        let tracked = JOB_STATES.lock().unwrap().get(job_id).cloned();
+        Self::resolve_state(tracked, queued_messages)
+    }
+
+    fn resolve_state(
+        tracked: Option<(JobState, Option<String>)>,
+        queued_messages: usize,
+    ) -> (JobState, Option<String>) {
+        match tracked {
+            Some((JobState::Running, _)) => (JobState::Running, None),
+            _ if queued_messages > 0 => (JobState::Queued, None),
+            Some((state, error)) => (state, error),
+            None => (JobState::Done, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_state() {
+        assert_eq!(JobStatusTracker::resolve_state(None, 0), (JobState::Done, None));
+        assert_eq!(JobStatusTracker::resolve_state(None, 2), (JobState::Queued, None));
+        assert_eq!(
+            JobStatusTracker::resolve_state(Some((JobState::Running, None)), 1),
+            (JobState::Running, None)
+        );
+        // A new message after a failure queues the job again
+        let failed = Some((JobState::Error, Some("timeout".to_string())));
+        assert_eq!(
+            JobStatusTracker::resolve_state(failed.clone(), 1),
+            (JobState::Queued, None)
+        );
+        assert_eq!(
+            JobStatusTracker::resolve_state(failed, 0),
+            (JobState::Error, Some("timeout".to_string()))
+        );
+    }
+}
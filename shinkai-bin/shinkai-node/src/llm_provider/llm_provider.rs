@@ -1,8 +1,14 @@
 use std::sync::Arc;
 
+use crate::managers::model_capabilities_manager::ModelCapabilitiesManager;
+use crate::managers::profile_limits_manager::ProfileLimitsManager;
+use crate::network::outbound_proxy::OUTBOUND_PROXY;
 use crate::network::ws_manager::WSUpdateHandler;
+use crate::schemas::outbound_proxy::DestinationClass;
 
+use super::circuit_breaker::LLM_PROVIDER_BREAKERS;
 use super::error::LLMProviderError;
+use super::local_inference_scheduler::LOCAL_INFERENCE_SCHEDULER;
 use super::execution::chains::inference_chain_trait::LLMInferenceResponse;
 use super::execution::prompts::prompts::Prompt;
 use super::providers::LLMService;
@@ -42,7 +48,8 @@ impl LLMProvider {
         storage_bucket_permissions: Vec<String>,
         allowed_message_senders: Vec<String>,
     ) -> Self {
-        let client = Client::builder()
+        let client = OUTBOUND_PROXY
+            .client_builder(DestinationClass::LlmProvider, Client::builder())
             .timeout(std::time::Duration::from_secs(120))
             .build()
             .unwrap();
@@ -85,7 +92,39 @@ impl LLMProvider {
         inbox_name: Option<InboxName>,
         ws_manager_trait: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
     ) -> Result<LLMInferenceResponse, LLMProviderError> {
-        let response = match &self.model {
+        LLM_PROVIDER_BREAKERS.try_acquire(&self.id)?;
+        let timeout = LLM_PROVIDER_BREAKERS.timeout(&self.id);
+        let result = tokio::time::timeout(timeout, self.call_provider(&prompt, inbox_name, ws_manager_trait))
+            .await
+            .unwrap_or_else(|_| {
+                Err(LLMProviderError::LLMProviderTimedOut(format!(
+                    "{} didn't answer in {} seconds",
+                    self.id,
+                    timeout.as_secs()
+                )))
+            });
+        LLM_PROVIDER_BREAKERS.record_result(&self.id, &result);
+        let response = result?;
+
+        // Estimated with the llama3 tokenizer, since providers don't all report usage
+        let tokens = prompt
+            .sub_prompts
+            .iter()
+            .map(|sub_prompt| sub_prompt.count_tokens_as_completion_message())
+            .sum::<usize>()
+            + ModelCapabilitiesManager::count_tokens_from_message_llama3(&response.response_string);
+        ProfileLimitsManager::record_llm_tokens(tokens as u64);
+
+        Ok(response)
+    }
+
+    async fn call_provider(
+        &self,
+        prompt: &Prompt,
+        inbox_name: Option<InboxName>,
+        ws_manager_trait: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) -> Result<LLMInferenceResponse, LLMProviderError> {
+        match &self.model {
             LLMProviderInterface::OpenAI(openai) => {
                 openai
                     .call_api(
@@ -113,6 +152,12 @@ impl LLMProvider {
                     .await
             }
             LLMProviderInterface::Ollama(ollama) => {
+                // Local models share the same GPU so we let the scheduler decide when it's our turn
+                let host = self.external_url.as_deref().unwrap_or_default();
+                if !host.is_empty() && !LOCAL_INFERENCE_SCHEDULER.is_loaded(host, &ollama.model_type) {
+                    LOCAL_INFERENCE_SCHEDULER.refresh_from_ollama(&self.client, host).await;
+                }
+                let _permit = LOCAL_INFERENCE_SCHEDULER.acquire(host, &ollama.model_type).await;
                 ollama
                     .call_api(
                         &self.client,
@@ -178,8 +223,7 @@ impl LLMProvider {
             LLMProviderInterface::LocalLLM(_local_llm) => {
                 self.inference_locally(prompt.generate_single_output_string()?).await
             }
-        }?;
-        Ok(response)
+        }
     }
 }
 
@@ -0,0 +1,428 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use tokio::sync::Notify;
+
+lazy_static! {
+    /// Shared scheduler for every job that targets a local (Ollama) model
+    pub static ref LOCAL_INFERENCE_SCHEDULER: Arc<LocalInferenceScheduler> =
+        Arc::new(LocalInferenceScheduler::new_from_env());
+}
+
+/// Metrics collected by the scheduler, exposed through the API
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocalInferenceMetrics {
+    pub total_requests: u64,
+    pub total_wait_ms: u64,
+    pub max_wait_ms: u64,
+    pub model_load_events: u64,
+    pub model_unload_events: u64,
+    pub vram_usage_bytes: u64,
+    pub loaded_models: Vec<String>,
+    pub active_requests: HashMap<String, usize>,
+    pub waiting_requests: usize,
+}
+
+#[derive(Debug, Default)]
+struct HostState {
+    /// Models we consider loaded in memory, least recently used first
+    loaded_models: Vec<String>,
+    /// Number of in-flight inferences per model
+    active: HashMap<String, usize>,
+    /// Waiting requests per model with the time the oldest one started waiting
+    waiting: HashMap<String, (usize, Instant)>,
+    /// VRAM used by the host as last reported by Ollama
+    vram_usage_bytes: u64,
+}
+
+#[derive(Debug, Default)]
+struct SchedulerState {
+    /// Bookkeeping per Ollama host, as every host loads models into its own memory
+    hosts: HashMap<String, HostState>,
+    metrics: LocalInferenceMetrics,
+}
+
+/// Serializes jobs that target local models so we don't keep swapping models in and out of VRAM.
+/// Models are tracked per host, so two providers pointing to different Ollama hosts never wait for each other.
+///
+/// Requests for a model that is already loaded go straight through (up to `max_concurrent_per_model`).
+/// Requests for a model that isn't loaded wait until there is room for it, either because fewer than
+/// `max_loaded_models` are loaded or because a loaded model became idle and can be evicted.
+/// To avoid starvation, once a request for another model has waited longer than `max_swap_wait`,
+/// new requests for the loaded models are held back so the idle model can be swapped out.
+pub struct LocalInferenceScheduler {
+    state: Mutex<SchedulerState>,
+    notify: Notify,
+    max_loaded_models: usize,
+    max_concurrent_per_model: usize,
+    max_swap_wait: Duration,
+}
+
+/// Held while an inference is running. Releasing it lets the next waiting job through.
+pub struct LocalInferencePermit {
+    scheduler: Arc<LocalInferenceScheduler>,
+    host: String,
+    model: String,
+}
+
+impl Drop for LocalInferencePermit {
+    fn drop(&mut self) {
+        self.scheduler.release(&self.host, &self.model);
+    }
+}
+
+impl LocalInferenceScheduler {
+    pub fn new(max_loaded_models: usize, max_concurrent_per_model: usize, max_swap_wait: Duration) -> Self {
+        Self {
+            state: Mutex::new(SchedulerState::default()),
+            notify: Notify::new(),
+            max_loaded_models: std::cmp::max(1, max_loaded_models),
+            max_concurrent_per_model: std::cmp::max(1, max_concurrent_per_model),
+            max_swap_wait,
+        }
+    }
+
+    /// Reads the limits from LOCAL_INFERENCE_MAX_LOADED_MODELS, LOCAL_INFERENCE_MAX_CONCURRENT_PER_MODEL
+    /// and LOCAL_INFERENCE_MAX_SWAP_WAIT_SECS. Defaults match Ollama's defaults (one model, a few parallel requests).
+    pub fn new_from_env() -> Self {
+        let read_env = |key: &str, default: u64| -> u64 {
+            std::env::var(key)
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(default)
+        };
+
+        Self::new(
+            read_env("LOCAL_INFERENCE_MAX_LOADED_MODELS", 1) as usize,
+            read_env("LOCAL_INFERENCE_MAX_CONCURRENT_PER_MODEL", 4) as usize,
+            Duration::from_secs(read_env("LOCAL_INFERENCE_MAX_SWAP_WAIT_SECS", 30)),
+        )
+    }
+
+    /// Ollama names models without an explicit tag as `<name>:latest`
+    fn normalize_model_name(model: &str) -> String {
+        if model.contains(':') {
+            model.to_string()
+        } else {
+            format!("{}:latest", model)
+        }
+    }
+
+    /// The same host can be written with or without a trailing slash
+    fn normalize_host(host: &str) -> String {
+        host.trim_end_matches('/').to_string()
+    }
+
+    /// Name used for a model of a host in the metrics
+    fn metrics_name(host: &str, model: &str) -> String {
+        if host.is_empty() {
+            model.to_string()
+        } else {
+            format!("{}@{}", model, host)
+        }
+    }
+
+    pub fn is_loaded(&self, host: &str, model: &str) -> bool {
+        let host = Self::normalize_host(host);
+        let model = Self::normalize_model_name(model);
+        self.state
+            .lock()
+            .unwrap()
+            .hosts
+            .get(&host)
+            .map_or(false, |host_state| host_state.loaded_models.contains(&model))
+    }
+
+    /// Waits until the model can be used on the host and returns a permit that must be held for the whole inference.
+    pub async fn acquire(self: &Arc<Self>, host: &str, model: &str) -> LocalInferencePermit {
+        let host = Self::normalize_host(host);
+        let model = Self::normalize_model_name(model);
+        let model = model.as_str();
+        let start = Instant::now();
+        let mut is_waiting = false;
+
+        loop {
+            // Register for notifications before checking so we don't miss a release in between
+            let notified = self.notify.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                let SchedulerState { hosts, metrics } = &mut *state;
+                let host_state = hosts.entry(host.clone()).or_default();
+                if self.try_admit(host_state, metrics, &host, model, start) {
+                    if is_waiting {
+                        Self::remove_waiter(host_state, model);
+                    }
+                    let waited_ms = start.elapsed().as_millis() as u64;
+                    metrics.total_requests += 1;
+                    metrics.total_wait_ms += waited_ms;
+                    metrics.max_wait_ms = std::cmp::max(metrics.max_wait_ms, waited_ms);
+
+                    if waited_ms > 0 && is_waiting {
+                        shinkai_log(
+                            ShinkaiLogOption::JobExecution,
+                            ShinkaiLogLevel::Debug,
+                            &format!(
+                                "Local inference for {} waited {} ms for the model",
+                                Self::metrics_name(&host, model),
+                                waited_ms
+                            ),
+                        );
+                    }
+
+                    return LocalInferencePermit {
+                        scheduler: self.clone(),
+                        host: host.clone(),
+                        model: model.to_string(),
+                    };
+                }
+
+                if !is_waiting {
+                    let entry = host_state.waiting.entry(model.to_string()).or_insert((0, start));
+                    entry.0 += 1;
+                    is_waiting = true;
+                }
+            }
+            notified.await;
+        }
+    }
+
+    fn try_admit(
+        &self,
+        state: &mut HostState,
+        metrics: &mut LocalInferenceMetrics,
+        host: &str,
+        model: &str,
+        requested_at: Instant,
+    ) -> bool {
+        if let Some(position) = state.loaded_models.iter().position(|m| m == model) {
+            let active = state.active.get(model).copied().unwrap_or(0);
+            if active >= self.max_concurrent_per_model {
+                return false;
+            }
+
+            // Hold back new requests if another model has been starving for too long
+            let starving_other = state.waiting.iter().any(|(waiting_model, (count, since))| {
+                waiting_model != model && *count > 0 && since.elapsed() > self.max_swap_wait && *since < requested_at
+            });
+            if starving_other {
+                return false;
+            }
+
+            let loaded = state.loaded_models.remove(position);
+            state.loaded_models.push(loaded);
+            *state.active.entry(model.to_string()).or_insert(0) += 1;
+            return true;
+        }
+
+        if state.loaded_models.len() >= self.max_loaded_models {
+            // Evict the least recently used idle model, if any
+            let idle_position = state
+                .loaded_models
+                .iter()
+                .position(|m| state.active.get(m).copied().unwrap_or(0) == 0);
+            match idle_position {
+                Some(position) => {
+                    let evicted = state.loaded_models.remove(position);
+                    state.active.remove(&evicted);
+                    metrics.model_unload_events += 1;
+                    shinkai_log(
+                        ShinkaiLogOption::JobExecution,
+                        ShinkaiLogLevel::Info,
+                        &format!(
+                            "Local inference scheduler swapping model {} for {}",
+                            Self::metrics_name(host, &evicted),
+                            model
+                        ),
+                    );
+                }
+                None => return false,
+            }
+        }
+
+        state.loaded_models.push(model.to_string());
+        state.active.insert(model.to_string(), 1);
+        metrics.model_load_events += 1;
+        true
+    }
+
+    fn remove_waiter(state: &mut HostState, model: &str) {
+        if let Some(entry) = state.waiting.get_mut(model) {
+            entry.0 = entry.0.saturating_sub(1);
+            if entry.0 == 0 {
+                state.waiting.remove(model);
+            } else {
+                // We don't track every waiter's start time, so reset the oldest one to now
+                entry.1 = Instant::now();
+            }
+        }
+    }
+
+    fn release(&self, host: &str, model: &str) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if let Some(active) = state
+                .hosts
+                .get_mut(host)
+                .and_then(|host_state| host_state.active.get_mut(model))
+            {
+                *active = active.saturating_sub(1);
+            }
+        }
+        self.notify.notify_waiters();
+    }
+
+    /// Syncs the loaded models of a host with what the runtime reports (e.g. Ollama's /api/ps), as models can be
+    /// unloaded by the runtime itself after its keep-alive expires.
+    pub fn sync_loaded_models(&self, host: &str, loaded_models: Vec<String>) {
+        {
+            let mut state = self.state.lock().unwrap();
+            let state = state.hosts.entry(Self::normalize_host(host)).or_default();
+            let active = state.active.clone();
+            state
+                .loaded_models
+                .retain(|m| loaded_models.contains(m) || active.get(m).copied().unwrap_or(0) > 0);
+            for model in loaded_models {
+                if !state.loaded_models.contains(&model) && state.loaded_models.len() < self.max_loaded_models {
+                    state.loaded_models.push(model);
+                }
+            }
+        }
+        self.notify.notify_waiters();
+    }
+
+    /// Refreshes the loaded models and VRAM usage from Ollama's /api/ps endpoint.
+    /// Errors are ignored as the scheduler can keep working with its own bookkeeping.
+    pub async fn refresh_from_ollama(&self, client: &Client, base_url: &str) {
+        let url = format!("{}/api/ps", base_url);
+        let body = match client.get(url).send().await {
+            Ok(response) => match response.json::<serde_json::Value>().await {
+                Ok(body) => body,
+                Err(_) => return,
+            },
+            Err(_) => return,
+        };
+
+        let models = body
+            .get("models")
+            .and_then(|models| models.as_array())
+            .cloned()
+            .unwrap_or_default();
+        let loaded_models = models
+            .iter()
+            .filter_map(|m| m.get("name").and_then(|name| name.as_str()))
+            .map(Self::normalize_model_name)
+            .collect();
+        let vram_usage_bytes = models
+            .iter()
+            .filter_map(|m| m.get("size_vram").and_then(|size| size.as_u64()))
+            .sum();
+
+        self.state
+            .lock()
+            .unwrap()
+            .hosts
+            .entry(Self::normalize_host(base_url))
+            .or_default()
+            .vram_usage_bytes = vram_usage_bytes;
+        self.sync_loaded_models(base_url, loaded_models);
+    }
+
+    pub fn metrics(&self) -> LocalInferenceMetrics {
+        let state = self.state.lock().unwrap();
+        let mut metrics = state.metrics.clone();
+        for (host, host_state) in state.hosts.iter() {
+            metrics.vram_usage_bytes += host_state.vram_usage_bytes;
+            metrics.loaded_models.extend(
+                host_state
+                    .loaded_models
+                    .iter()
+                    .map(|model| Self::metrics_name(host, model)),
+            );
+            metrics.active_requests.extend(
+                host_state
+                    .active
+                    .iter()
+                    .filter(|(_, c)| **c > 0)
+                    .map(|(m, c)| (Self::metrics_name(host, m), *c)),
+            );
+            metrics.waiting_requests += host_state.waiting.values().map(|(count, _)| *count).sum::<usize>();
+        }
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOST: &str = "http://localhost:11434";
+
+    #[tokio::test]
+    async fn test_same_model_runs_concurrently() {
+        let scheduler = Arc::new(LocalInferenceScheduler::new(1, 2, Duration::from_secs(30)));
+        let _a = scheduler.acquire(HOST, "llama3.1:8b").await;
+        let _b = scheduler.acquire(HOST, "llama3.1:8b").await;
+        assert!(scheduler.is_loaded(HOST, "llama3.1:8b"));
+
+        let metrics = scheduler.metrics();
+        assert_eq!(metrics.model_load_events, 1);
+        assert_eq!(
+            metrics.active_requests.get("llama3.1:8b@http://localhost:11434"),
+            Some(&2)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_other_model_waits_until_idle() {
+        let scheduler = Arc::new(LocalInferenceScheduler::new(1, 2, Duration::from_secs(30)));
+        let permit = scheduler.acquire(HOST, "llama3.1:8b").await;
+
+        let scheduler_clone = scheduler.clone();
+        let handle = tokio::spawn(async move {
+            let _permit = scheduler_clone.acquire(HOST, "llava:7b").await;
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!handle.is_finished());
+        assert_eq!(scheduler.metrics().waiting_requests, 1);
+
+        drop(permit);
+        handle.await.unwrap();
+
+        let metrics = scheduler.metrics();
+        assert_eq!(metrics.model_load_events, 2);
+        assert_eq!(metrics.model_unload_events, 1);
+        assert_eq!(
+            metrics.loaded_models,
+            vec!["llava:7b@http://localhost:11434".to_string()]
+        );
+        assert!(!scheduler.is_loaded(HOST, "llama3.1:8b"));
+        assert!(metrics.max_wait_ms >= 50);
+    }
+
+    #[tokio::test]
+    async fn test_same_model_on_other_host_does_not_wait() {
+        let scheduler = Arc::new(LocalInferenceScheduler::new(1, 1, Duration::from_secs(30)));
+        let _local = scheduler.acquire(HOST, "llama3.1:8b").await;
+
+        // Another host has its own memory, so neither its models nor its slots are shared
+        let remote = tokio::time::timeout(
+            Duration::from_millis(200),
+            scheduler.acquire("http://gpu-box:11434/", "llama3.1:8b"),
+        )
+        .await;
+        assert!(remote.is_ok());
+        assert!(scheduler.is_loaded("http://gpu-box:11434", "llama3.1:8b"));
+        assert!(!scheduler.is_loaded("http://other:11434", "llama3.1:8b"));
+
+        let metrics = scheduler.metrics();
+        assert_eq!(metrics.model_load_events, 2);
+        assert_eq!(metrics.model_unload_events, 0);
+        assert_eq!(metrics.loaded_models.len(), 2);
+    }
+}
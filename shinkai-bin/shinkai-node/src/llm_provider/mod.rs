@@ -1,10 +1,13 @@
 #[allow(clippy::module_inception)]
 pub mod llm_provider;
 pub mod llm_provider_to_serialization;
+pub mod circuit_breaker;
 pub mod error;
 pub mod execution;
 pub mod job;
 pub mod job_manager;
+pub mod job_status;
+pub mod local_inference_scheduler;
 pub mod parsing_helper;
 pub mod providers;
 pub mod queue;
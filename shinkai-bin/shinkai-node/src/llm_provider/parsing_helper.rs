@@ -2,16 +2,57 @@ use super::error::LLMProviderError;
 use super::execution::prompts::prompts::JobPromptGenerator;
 use super::execution::user_message_parser::{JobTaskElement, ParsedUserMessage};
 use super::job_manager::JobManager;
+use crate::db::ShinkaiDB;
+use crate::managers::operation_registry::OperationHandle;
+use crate::network::node_events::{NodeEventBus, NodeEventType};
+use crate::utils::environment::{image_embedding_generator, multilingual_embedding_generator};
+use crate::vector_fs::vector_fs_markdown_bundle::MarkdownBundleDocument;
+use crate::vector_fs::vector_fs_types::FSItem;
+use futures::stream::{self, StreamExt};
 use regex::Regex;
+use serde_json::json;
 use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
-use shinkai_vector_resources::embedding_generator::EmbeddingGenerator;
+use shinkai_vector_resources::embedding_generator::{EmbeddingGenerator, RemoteEmbeddingGenerator};
 use shinkai_vector_resources::file_parser::file_parser::{FileParser, ShinkaiFileParser};
 use shinkai_vector_resources::file_parser::file_parser_types::TextGroup;
 use shinkai_vector_resources::source::{DistributionInfo, SourceFile, SourceFileMap, TextChunkingStrategy};
 use shinkai_vector_resources::vector_resource::{BaseVectorResource, SourceFileType, VRKai, VRPath};
 use shinkai_vector_resources::{data_tags::DataTag, source::VRSourceReference};
 use std::collections::HashMap;
+use std::future::Future;
+
+/// How many files are converted into Vector Resources at the same time
+const FILE_PROCESSING_WORKERS: usize = 4;
+/// How much of a document's text is given to the LLM summarizing it
+const SUMMARY_MAX_TEXT_CHARS: usize = 10000;
+
+/// Node settings applied to the files ingested into the VectorFS
+#[derive(Debug, Clone, Default)]
+pub struct IngestionSettings {
+    /// The text chunks are tagged with the ones they match
+    pub parsing_tags: Vec<DataTag>,
+    /// LLM provider that summarizes each file, if summarization is on
+    pub summary_llm_provider: Option<SerializedLLMProvider>,
+    /// Generator of the embeddings images are found by, if an image embedding model is configured
+    pub image_embedding_generator: Option<RemoteEmbeddingGenerator>,
+    /// Generator the documents in other languages than English are embedded with, if a multilingual embedding model
+    /// is configured
+    pub multilingual_embedding_generator: Option<RemoteEmbeddingGenerator>,
+}
+
+impl IngestionSettings {
+    /// Settings for the files saved in a folder. Folders which declare their own embedding model keep it for all
+    /// their files, whatever their language.
+    pub fn for_folder(&self, folder_generator: &Option<RemoteEmbeddingGenerator>) -> IngestionSettings {
+        let mut settings = self.clone();
+        if folder_generator.is_some() {
+            settings.multilingual_embedding_generator = None;
+        }
+        settings
+    }
+}
 
 pub struct ParsingHelper {}
 
@@ -58,11 +99,13 @@ impl ParsingHelper {
     ///  Processes the file buffer through Unstructured, our hierarchical structuring algo,
     ///  generates all embeddings, uses LLM to generate desc and improve overall structure quality,
     ///  and returns a finalized BaseVectorResource. If no agent is provided, description defaults to first text in elements.
+    /// If a multilingual generator is provided, documents in other languages than English are embedded with it.
     /// Note: Requires file_name to include the extension ie. `*.pdf` or url `http://...`
     #[allow(clippy::too_many_arguments)]
     pub async fn process_file_into_resource_gen_desc(
         file_buffer: Vec<u8>,
         generator: &dyn EmbeddingGenerator,
+        multilingual_generator: Option<&RemoteEmbeddingGenerator>,
         file_name: String,
         parsing_tags: &Vec<DataTag>,
         agent: Option<SerializedLLMProvider>,
@@ -81,6 +124,20 @@ impl ParsingHelper {
         )
         .await?;
 
+        // The text groups are tagged with their language while parsed
+        let language_key = ShinkaiFileParser::language_metadata_key();
+        let document_language = ShinkaiFileParser::most_common_language(
+            text_groups
+                .iter()
+                .filter_map(|group| group.metadata.get(&language_key).map(String::as_str)),
+        );
+        let generator: &dyn EmbeddingGenerator = match (multilingual_generator, document_language) {
+            (Some(multilingual_generator), Some(language)) if !ShinkaiFileParser::is_english_language(&language) => {
+                multilingual_generator
+            }
+            _ => generator,
+        };
+
         let mut desc = None;
         if let Some(actual_agent) = agent {
             desc = Some(Self::generate_description(&text_groups, actual_agent, max_node_text_size).await?);
@@ -108,13 +165,58 @@ impl ParsingHelper {
         .await?)
     }
 
+    /// Settings the files ingested for the profile are processed with. The chunks are tagged with the entity tags
+    /// for people, organizations and dates when the node's entity tagging preference is on, and the files are
+    /// summarized when the summarization LLM provider is one of the profile's. Images are embedded with the
+    /// image embedding model when one is configured, and documents in other languages than English with the
+    /// multilingual embedding model when one is configured.
+    pub fn ingestion_settings(db: &ShinkaiDB, profile: &ShinkaiName) -> IngestionSettings {
+        let parsing_tags = match db.get_entity_tagging_preference() {
+            Ok(true) => DataTag::entity_tags(),
+            _ => vec![],
+        };
+        let summary_llm_provider = match db.get_summarization_llm_provider() {
+            Ok(Some(llm_provider_id)) => db.get_llm_provider(&llm_provider_id, profile).ok().flatten(),
+            _ => None,
+        };
+        IngestionSettings {
+            parsing_tags,
+            summary_llm_provider,
+            image_embedding_generator: image_embedding_generator(),
+            multilingual_embedding_generator: multilingual_embedding_generator(),
+        }
+    }
+
+    /// Generates a summary of the text of the Vector Resource using the LLM provider
+    pub async fn generate_summary(
+        resource: &BaseVectorResource,
+        llm_provider: SerializedLLMProvider,
+    ) -> Result<String, LLMProviderError> {
+        let text: String = MarkdownBundleDocument::from_resource(resource)
+            .body
+            .chars()
+            .take(SUMMARY_MAX_TEXT_CHARS)
+            .collect();
+        let prompt = JobPromptGenerator::simple_doc_description(vec![text]);
+        let response = JobManager::inference_with_llm_provider(llm_provider, prompt, None, None).await?;
+        Ok(response.response_string.trim().to_string())
+    }
+
     /// Processes the list of files into VRKai structs ready to be used/saved/etc.
     /// Supports both `.vrkai` files, and standard doc/html/etc which get generated into VRs.
+    /// Up to `FILE_PROCESSING_WORKERS` files are processed at once, so one file can be embedded while the next
+    /// is parsed. If an event bus is given, a `file_processing` event is published as each file is done or fails.
+    /// If an operation is given, each generated file is a step of it, and its cancellation stops the processing.
+    /// The files are processed with the ingestion settings: their text chunks are tagged with the parsing tags
+    /// they match, and if a summary LLM provider is set, the summary of each file is in its VRKai's metadata.
     pub async fn process_files_into_vrkai(
         files: Vec<(String, Vec<u8>, DistributionInfo)>,
         generator: &dyn EmbeddingGenerator,
         agent: Option<SerializedLLMProvider>,
         file_parser: FileParser,
+        ingestion: &IngestionSettings,
+        events: Option<&NodeEventBus>,
+        operation: Option<&OperationHandle>,
     ) -> Result<Vec<(String, VRKai)>, LLMProviderError> {
         #[allow(clippy::type_complexity)]
         let (vrkai_files, other_files): (
@@ -138,35 +240,227 @@ impl ParsingHelper {
         }
 
         // Parse the other files by generating a Vector Resource from scratch
-        for file in other_files {
-            let filename = file.0.clone();
-            shinkai_log(
-                ShinkaiLogOption::JobExecution,
-                ShinkaiLogLevel::Debug,
-                &format!("Processing file: {}", filename),
-            );
+        let other_files = other_files
+            .into_iter()
+            .map(|(filename, buffer, distribution_info)| (filename, (buffer, distribution_info)))
+            .collect();
+        let generated_vrkais = Self::process_with_workers(
+            other_files,
+            events,
+            operation,
+            |filename, (buffer, distribution_info)| {
+                let agent = agent.clone();
+                let file_parser = file_parser.clone();
+                async move {
+                    shinkai_log(
+                        ShinkaiLogOption::JobExecution,
+                        ShinkaiLogLevel::Debug,
+                        &format!("Processing file: {}", filename),
+                    );
+                    Self::process_file_into_vrkai(
+                        filename,
+                        buffer,
+                        distribution_info,
+                        generator,
+                        agent,
+                        file_parser,
+                        ingestion,
+                    )
+                    .await
+                }
+            },
+        )
+        .await?;
+        processed_vrkais.extend(generated_vrkais);
+
+        Ok(processed_vrkais)
+    }
 
-            let resource = ParsingHelper::process_file_into_resource_gen_desc(
-                file.1.clone(),
-                generator,
-                filename.clone(),
-                &vec![],
-                agent.clone(),
-                (generator.model_type().max_input_token_count() - 20) as u64,
-                file_parser.clone(),
-                file.2.clone(),
-            )
-            .await?;
-
-            let file_type = SourceFileType::detect_file_type(&file.0)?;
-            let source = SourceFile::new_standard_source_file(file.0, file_type, file.1, None);
-            let mut source_map = SourceFileMap::new(HashMap::new());
-            source_map.add_source_file(VRPath::root(), source);
-
-            processed_vrkais.push((filename, VRKai::new(resource, Some(source_map))))
+    /// Runs `process` on up to `FILE_PROCESSING_WORKERS` files at once and returns the results in the order the
+    /// files were given in. It stops at the first file that fails.
+    async fn process_with_workers<I, T, F, Fut>(
+        files: Vec<(String, I)>,
+        events: Option<&NodeEventBus>,
+        operation: Option<&OperationHandle>,
+        process: F,
+    ) -> Result<Vec<(String, T)>, LLMProviderError>
+    where
+        F: Fn(String, I) -> Fut,
+        Fut: Future<Output = Result<T, LLMProviderError>>,
+    {
+        let total_files = files.len();
+        if let Some(operation) = operation {
+            operation.set_total_steps(total_files as u64);
         }
+        let publish_progress = |filename: &str, status: &str, processed: usize| {
+            if let Some(events) = events {
+                events.publish(
+                    NodeEventType::FileProcessing,
+                    json!({ "file_name": filename, "status": status, "processed": processed, "total": total_files }),
+                );
+            }
+        };
+        let mut results = stream::iter(files.into_iter().enumerate())
+            .map(|(index, (filename, file))| {
+                let result = process(filename.clone(), file);
+                async move { (index, filename, result.await) }
+            })
+            .buffer_unordered(FILE_PROCESSING_WORKERS);
 
-        Ok(processed_vrkais)
+        let mut processed_files = vec![];
+        while let Some((index, filename, result)) = results.next().await {
+            match result {
+                Ok(result) => {
+                    publish_progress(&filename, "done", processed_files.len() + 1);
+                    processed_files.push((index, filename, result));
+                }
+                Err(e) => {
+                    publish_progress(&filename, "error", processed_files.len());
+                    return Err(e);
+                }
+            }
+            if let Some(operation) = operation {
+                operation.advance();
+                // Dropping the stream stops the files still being processed
+                if operation.is_cancelled() {
+                    return Err(LLMProviderError::OperationCancelled(
+                        operation.operation_id().to_string(),
+                    ));
+                }
+            }
+        }
+
+        // Keep the order the files were given in
+        processed_files.sort_by_key(|(index, _, _)| *index);
+        Ok(processed_files
+            .into_iter()
+            .map(|(_, filename, result)| (filename, result))
+            .collect())
+    }
+
+    /// Generates the Vector Resource of a single file and bundles it with the file as its source
+    async fn process_file_into_vrkai(
+        filename: String,
+        file_buffer: Vec<u8>,
+        distribution_info: DistributionInfo,
+        generator: &dyn EmbeddingGenerator,
+        agent: Option<SerializedLLMProvider>,
+        file_parser: FileParser,
+        ingestion: &IngestionSettings,
+    ) -> Result<VRKai, LLMProviderError> {
+        if let SourceFileType::Image(_) = SourceFileType::detect_file_type(&filename)? {
+            return Self::process_image_into_vrkai(filename, file_buffer, distribution_info, generator, ingestion)
+                .await;
+        }
+
+        let resource = ParsingHelper::process_file_into_resource_gen_desc(
+            file_buffer.clone(),
+            generator,
+            ingestion.multilingual_embedding_generator.as_ref(),
+            filename.clone(),
+            &ingestion.parsing_tags,
+            agent,
+            (generator.model_type().max_input_token_count() - 20) as u64,
+            file_parser,
+            distribution_info,
+        )
+        .await?;
+
+        let file_type = SourceFileType::detect_file_type(&filename)?;
+        let source = SourceFile::new_standard_source_file(filename, file_type, file_buffer, None);
+        let mut source_map = SourceFileMap::new(HashMap::new());
+        source_map.add_source_file(VRPath::root(), source);
+
+        // A file that can't be summarized is still ingested, just without a summary
+        let summary = match &ingestion.summary_llm_provider {
+            Some(llm_provider) => match Self::generate_summary(&resource, llm_provider.clone()).await {
+                Ok(summary) => Some(summary),
+                Err(e) => {
+                    shinkai_log(
+                        ShinkaiLogOption::JobExecution,
+                        ShinkaiLogLevel::Error,
+                        &format!("Failed to summarize {}: {}", resource.as_trait_object().name(), e),
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let mut vrkai = VRKai::new(resource, Some(source_map));
+        if let Some(summary) = summary.filter(|summary| !summary.is_empty()) {
+            vrkai.metadata.insert(FSItem::vr_summary_metadata_key(), summary);
+        }
+        Ok(vrkai)
+    }
+
+    /// Images have no text to parse, so their Vector Resource holds a single node with their name, and the image
+    /// itself is its source file. If an image embedding generator is set, the image embedding is in the VRKai's
+    /// metadata, so the image can be found by text queries once saved in the VectorFS.
+    async fn process_image_into_vrkai(
+        filename: String,
+        file_buffer: Vec<u8>,
+        distribution_info: DistributionInfo,
+        generator: &dyn EmbeddingGenerator,
+        ingestion: &IngestionSettings,
+    ) -> Result<VRKai, LLMProviderError> {
+        let cleaned_name = ShinkaiFileParser::clean_name(&filename);
+        let source = VRSourceReference::from_file(&filename, TextChunkingStrategy::V1)?;
+        let text_groups = vec![TextGroup::new(
+            format!("Image: {}", cleaned_name),
+            HashMap::new(),
+            vec![],
+            None,
+        )];
+        let resource = ShinkaiFileParser::process_groups_into_resource(
+            text_groups,
+            generator,
+            cleaned_name.clone(),
+            None,
+            source,
+            &ingestion.parsing_tags,
+            (generator.model_type().max_input_token_count() - 20) as u64,
+            distribution_info,
+        )
+        .await?;
+
+        let image_embedding = match &ingestion.image_embedding_generator {
+            Some(image_generator) => {
+                match image_generator
+                    .generate_image_embedding(&file_buffer, &cleaned_name)
+                    .await
+                {
+                    Ok(embedding) => Some((embedding, image_generator.model_type())),
+                    // The image is still ingested, it just can't be found by its content
+                    Err(e) => {
+                        shinkai_log(
+                            ShinkaiLogOption::JobExecution,
+                            ShinkaiLogLevel::Error,
+                            &format!("Failed to embed image {}: {}", cleaned_name, e),
+                        );
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let file_type = SourceFileType::detect_file_type(&filename)?;
+        let source = SourceFile::new_standard_source_file(filename, file_type, file_buffer, None);
+        let mut source_map = SourceFileMap::new(HashMap::new());
+        source_map.add_source_file(VRPath::root(), source);
+
+        let mut vrkai = VRKai::new(resource, Some(source_map));
+        if let Some((embedding, model)) = image_embedding {
+            vrkai.metadata.insert(
+                FSItem::vr_image_embedding_metadata_key(),
+                serde_json::to_string(&embedding.vector).unwrap_or_default(),
+            );
+            vrkai
+                .metadata
+                .insert(FSItem::vr_image_embedding_model_metadata_key(), model.to_string());
+        }
+        Ok(vrkai)
     }
 
     /// Cleans the value string from a parsed markdown response from common LLM issues.
@@ -230,3 +524,62 @@ impl ParsingHelper {
         re_references.replace_all(string, "").to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_process_with_workers_keeps_file_order() {
+        let events = NodeEventBus::new();
+        let mut receiver = events.subscribe();
+        let files = vec![
+            ("a.txt".to_string(), 30),
+            ("b.txt".to_string(), 10),
+            ("c.txt".to_string(), 20),
+        ];
+
+        let processed =
+            ParsingHelper::process_with_workers(files, Some(&events), None, |filename, delay_ms| async move {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                Ok(filename.to_uppercase())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            processed,
+            vec![
+                ("a.txt".to_string(), "A.TXT".to_string()),
+                ("b.txt".to_string(), "B.TXT".to_string()),
+                ("c.txt".to_string(), "C.TXT".to_string()),
+            ]
+        );
+        // Progress is published in the order the files finished
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(event.event_type, NodeEventType::FileProcessing);
+        assert_eq!(event.data["file_name"], "b.txt");
+        assert_eq!(event.data["status"], "done");
+        assert_eq!(event.data["processed"], 1);
+        assert_eq!(event.data["total"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_process_with_workers_stops_at_failed_file() {
+        let events = NodeEventBus::new();
+        let mut receiver = events.subscribe();
+        let files = vec![("broken.pdf".to_string(), ())];
+
+        let result = ParsingHelper::process_with_workers(files, Some(&events), None, |filename, _| async move {
+            Err::<String, _>(LLMProviderError::IO(format!("Failed to parse {}", filename)))
+        })
+        .await;
+
+        assert!(matches!(result, Err(LLMProviderError::IO(_))));
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(event.data["file_name"], "broken.pdf");
+        assert_eq!(event.data["status"], "error");
+        assert_eq!(event.data["processed"], 0);
+    }
+}
@@ -82,14 +82,18 @@ fn from_chat_completion_messages(
         if let Some(content) = message.content {
             let mut images = None;
 
+            // The image messages following a user message are its images
             if message.role.clone().unwrap_or_default() == "user" {
-                if let Some(next_message) = iter.peek() {
-                    if next_message.role.clone().unwrap_or_default() == "user" && next_message.name.as_deref() == Some("image") {
-                        if let Some(image_content) = &next_message.content {
-                            images = Some(vec![image_content.clone()]);
-                            iter.next(); // Consume the next message
-                        }
+                while let Some(next_message) = iter.peek() {
+                    if next_message.role.clone().unwrap_or_default() != "user"
+                        || next_message.name.as_deref() != Some("image")
+                    {
+                        break;
                     }
+                    if let Some(image_content) = &next_message.content {
+                        images.get_or_insert_with(Vec::new).push(image_content.clone());
+                    }
+                    iter.next(); // Consume the image message
                 }
             }
 
@@ -86,13 +86,11 @@ pub struct ApiPayload {
 pub fn openai_prepare_messages(model: &LLMProviderInterface, prompt: Prompt) -> Result<PromptResult, LLMProviderError> {
     let max_input_tokens = ModelCapabilitiesManager::get_max_input_tokens(model);
 
-    // Generate the messages and filter out images
+    // Generate the messages and separate the images, which are sent as image parts of a user message
     let chat_completion_messages = prompt.generate_openai_messages(Some(max_input_tokens))?;
-    let filtered_chat_completion_messages: Vec<_> = chat_completion_messages
-        .clone()
+    let (image_messages, filtered_chat_completion_messages): (Vec<_>, Vec<_>) = chat_completion_messages
         .into_iter()
-        .filter(|message| message.name.as_deref() != Some("image"))
-        .collect();
+        .partition(|message| message.name.as_deref() == Some("image"));
 
     // Get a more accurate estimate of the number of used tokens
     let used_tokens = ModelCapabilitiesManager::num_tokens_from_messages(&filtered_chat_completion_messages);
@@ -109,10 +107,18 @@ pub fn openai_prepare_messages(model: &LLMProviderInterface, prompt: Prompt) ->
     let tools_json = serde_json::to_value(tools)?;
 
     // Convert messages_json and tools_json to Vec<serde_json::Value>
-    let messages_vec = match messages_json {
+    let mut messages_vec = match messages_json {
         serde_json::Value::Array(arr) => arr,
         _ => vec![],
     };
+    let image_parts: Vec<JsonValue> = image_messages
+        .into_iter()
+        .filter_map(|message| message.content)
+        .map(|url| serde_json::json!({ "type": "image_url", "image_url": { "url": url } }))
+        .collect();
+    if !image_parts.is_empty() {
+        messages_vec.push(serde_json::json!({ "role": "user", "content": image_parts }));
+    }
 
     // Flatten the tools array to extract functions directly
     let tools_vec = match tools_json {
@@ -1,5 +1,6 @@
 use crate::db::db_errors::ShinkaiDBError;
 use crate::db::ShinkaiDB;
+use crate::network::request_id::current_request_id;
 use chrono::Utc;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -20,6 +21,9 @@ pub struct JobForProcessing {
     pub job_message: JobMessage,
     pub profile: ShinkaiName,
     pub date_created: String,
+    /// Correlation ID of the API request that created the job message, so the job logs can be traced back to it
+    #[serde(default)]
+    pub request_id: Option<String>,
     // TODO: add a new optional field for callbacks
 }
 
@@ -29,6 +33,7 @@ impl JobForProcessing {
             job_message,
             profile,
             date_created: Utc::now().to_rfc3339(),
+            request_id: current_request_id(),
         }
     }
 }
@@ -195,6 +200,15 @@ impl<T: Clone + Send + 'static + DeserializeOwned + Serialize + Ord + Debug> Job
         Ok(None)
     }
 
+    /// Number of elements waiting in the queue of the key
+    pub async fn queue_len(&self, key: &str) -> usize {
+        let queues = self.queues.lock().await;
+        match queues.get(key) {
+            Some(queue) => queue.lock().await.len(),
+            None => 0,
+        }
+    }
+
     pub async fn get_all_elements_interleave(&self) -> Result<Vec<T>, ShinkaiDBError> {
         let db_arc = self.db.upgrade().ok_or("Failed to upgrade shinkai_db").unwrap();
         let mut db_queues: HashMap<_, _> = db_arc.get_all_queues::<T>(&self.cf_name, self.prefix.clone())?;
@@ -322,6 +336,7 @@ mod tests {
                 workflow_name: None,
                 sheet_job_data: None,
                 callback: None,
+                retrieval: None,
             },
             ShinkaiName::new("@@node1.shinkai/main".to_string()).unwrap(),
         );
@@ -355,6 +370,7 @@ mod tests {
                 workflow_name: None,
                 sheet_job_data: None,
                 callback: None,
+                retrieval: None,
             },
             ShinkaiName::new("@@node1.shinkai/main".to_string()).unwrap(),
         );
@@ -368,6 +384,7 @@ mod tests {
                 workflow_name: None,
                 sheet_job_data: None,
                 callback: None,
+                retrieval: None,
             },
             ShinkaiName::new("@@node1.shinkai/main".to_string()).unwrap(),
         );
@@ -471,6 +488,7 @@ mod tests {
                 workflow_name: None,
                 sheet_job_data: None,
                 callback: None,
+                retrieval: None,
             },
             ShinkaiName::new("@@node1.shinkai/main".to_string()).unwrap(),
         );
@@ -484,6 +502,7 @@ mod tests {
                 workflow_name: None,
                 sheet_job_data: None,
                 callback: None,
+                retrieval: None,
             },
             ShinkaiName::new("@@node1.shinkai/main".to_string()).unwrap(),
         );
@@ -497,6 +516,7 @@ mod tests {
                 workflow_name: None,
                 sheet_job_data: None,
                 callback: None,
+                retrieval: None,
             },
             ShinkaiName::new("@@node1.shinkai/main".to_string()).unwrap(),
         );
@@ -511,6 +531,7 @@ mod tests {
                 workflow_name: None,
                 sheet_job_data: None,
                 callback: None,
+                retrieval: None,
             },
             ShinkaiName::new("@@node1.shinkai/main".to_string()).unwrap(),
         );
@@ -525,6 +546,7 @@ mod tests {
                 workflow_name: None,
                 sheet_job_data: None,
                 callback: None,
+                retrieval: None,
             },
             ShinkaiName::new("@@node1.shinkai/main".to_string()).unwrap(),
         );
@@ -539,6 +561,7 @@ mod tests {
                 workflow_name: None,
                 sheet_job_data: None,
                 callback: None,
+                retrieval: None,
             },
             ShinkaiName::new("@@node1.shinkai/main".to_string()).unwrap(),
         );
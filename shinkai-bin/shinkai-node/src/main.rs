@@ -16,7 +16,7 @@ mod welcome_files;
 mod workflows;
 mod lance_db;
 
-use runner::{initialize_node, run_node_tasks};
+use runner::{exit_code, initialize_node, run_node_tasks};
 
 #[cfg(feature = "console")]
 use console_subscriber;
@@ -29,6 +29,15 @@ pub async fn main() {
         eprintln!("> tokio-console is enabled");
     }
 
-    let result = initialize_node().await.unwrap();
-    let _ = run_node_tasks(result.1, result.2, result.3).await;
+    let (_, api_server, node_task, node) = match initialize_node().await {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Failed to start the node: {}", e);
+            std::process::exit(exit_code(e.as_ref()));
+        }
+    };
+    if let Err(e) = run_node_tasks(api_server, node_task, node).await {
+        eprintln!("The node stopped with an error: {}", e);
+        std::process::exit(exit_code(e.as_ref()));
+    }
 }
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use notify::event::{AccessKind, AccessMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use shinkai_vector_resources::embedding_generator::RemoteEmbeddingGenerator;
+use shinkai_vector_resources::file_parser::file_parser::FileParser;
+use shinkai_vector_resources::source::DistributionInfo;
+use shinkai_vector_resources::vector_resource::VRPath;
+use tokio::sync::mpsc;
+
+use crate::db::ShinkaiDB;
+use crate::llm_provider::parsing_helper::ParsingHelper;
+use crate::schemas::watched_folder::WatchedFolder;
+use crate::vector_fs::vector_fs::VectorFS;
+
+/// Changes to a file are synced once it wasn't written to for this long, so files being copied are read whole
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Keeps the local directories of the watched folders in sync with their VectorFS folders. Watched folders added
+/// or removed through the API are picked up every FOLDER_WATCHER_RELOAD_SECS (default 10) seconds, and each one
+/// is rescanned when it starts being watched, so files changed while the node was down get synced too.
+pub struct FolderWatcher {
+    db: Weak<ShinkaiDB>,
+    vector_fs: Weak<VectorFS>,
+    generator: RemoteEmbeddingGenerator,
+    /// File watchers of the watched folders, by id
+    watchers: HashMap<String, (WatchedFolder, RecommendedWatcher)>,
+}
+
+impl FolderWatcher {
+    pub fn start(
+        db: Weak<ShinkaiDB>,
+        vector_fs: Weak<VectorFS>,
+        generator: RemoteEmbeddingGenerator,
+    ) -> tokio::task::JoinHandle<()> {
+        let reload_interval = Duration::from_secs(
+            std::env::var("FOLDER_WATCHER_RELOAD_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .unwrap_or(10),
+        );
+
+        tokio::spawn(async move {
+            let mut folder_watcher = FolderWatcher {
+                db,
+                vector_fs,
+                generator,
+                watchers: HashMap::new(),
+            };
+            let (events_sender, mut events_receiver) = mpsc::unbounded_channel::<(String, PathBuf)>();
+            let mut pending: HashMap<PathBuf, String> = HashMap::new();
+
+            loop {
+                if !folder_watcher.reload(&events_sender).await {
+                    return;
+                }
+
+                // Gathers the changed paths until no file changed for DEBOUNCE, then syncs them
+                let reload_at = tokio::time::Instant::now() + reload_interval;
+                loop {
+                    let wait = match pending.is_empty() {
+                        true => reload_at.saturating_duration_since(tokio::time::Instant::now()),
+                        false => DEBOUNCE,
+                    };
+                    match tokio::time::timeout(wait, events_receiver.recv()).await {
+                        Ok(Some((folder_id, path))) => {
+                            pending.insert(path, folder_id);
+                        }
+                        Ok(None) => return,
+                        Err(_) if pending.is_empty() => break,
+                        Err(_) => {
+                            for (path, folder_id) in std::mem::take(&mut pending) {
+                                folder_watcher.sync_changed_path(&folder_id, &path).await;
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Watches the folders added since the last reload and stops watching the removed ones. Returns false once
+    /// the node stopped.
+    async fn reload(&mut self, events_sender: &mpsc::UnboundedSender<(String, PathBuf)>) -> bool {
+        let Some(db) = self.db.upgrade() else {
+            return false;
+        };
+        let folders = match db.get_all_watched_folders() {
+            Ok(folders) => folders,
+            Err(e) => {
+                Self::log_error(&format!("Failed to read the watched folders: {}", e));
+                return true;
+            }
+        };
+
+        self.watchers
+            .retain(|id, (watched, _)| folders.iter().any(|folder| &folder.id == id && folder == watched));
+        for folder in folders {
+            if self.watchers.contains_key(&folder.id) {
+                continue;
+            }
+            let folder_id = folder.id.clone();
+            let sender = events_sender.clone();
+            let watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+                let Ok(event) = result else {
+                    return;
+                };
+                // Reads don't change anything, the end of a write does
+                if matches!(event.kind, EventKind::Access(kind) if kind != AccessKind::Close(AccessMode::Write)) {
+                    return;
+                }
+                for path in event.paths {
+                    let _ = sender.send((folder_id.clone(), path));
+                }
+            });
+            let recursive_mode = match folder.recursive {
+                true => RecursiveMode::Recursive,
+                false => RecursiveMode::NonRecursive,
+            };
+            let watcher = watcher.and_then(|mut watcher| {
+                watcher.watch(Path::new(&folder.local_path), recursive_mode)?;
+                Ok(watcher)
+            });
+            match watcher {
+                Ok(watcher) => {
+                    self.rescan(&folder).await;
+                    self.watchers.insert(folder.id.clone(), (folder, watcher));
+                }
+                Err(e) => Self::log_error(&format!("Failed to watch {}: {}", folder.local_path, e)),
+            }
+        }
+        true
+    }
+
+    /// Syncs every file of the local directory, and removes the items of the files deleted since the last sync
+    async fn rescan(&self, folder: &WatchedFolder) {
+        let local_path = PathBuf::from(&folder.local_path);
+        let recursive = folder.recursive;
+        let files = tokio::task::spawn_blocking(move || Self::list_files(&local_path, recursive))
+            .await
+            .unwrap_or_default();
+        let relative_paths: Vec<String> = files
+            .iter()
+            .filter_map(|path| Self::relative_path(folder, path))
+            .collect();
+
+        if let Err(e) = self.sync_paths(folder, &relative_paths, true).await {
+            Self::log_error(&format!("Failed to sync {}: {}", folder.local_path, e));
+        }
+    }
+
+    async fn sync_changed_path(&self, folder_id: &str, path: &Path) {
+        let Some((folder, _)) = self.watchers.get(folder_id) else {
+            return;
+        };
+        let Some(relative_path) = Self::relative_path(folder, path) else {
+            return;
+        };
+
+        // A directory moved into the local one has all its files synced at once
+        let relative_paths = match path.is_dir() {
+            true => {
+                let dir = path.to_path_buf();
+                let recursive = folder.recursive;
+                tokio::task::spawn_blocking(move || Self::list_files(&dir, recursive))
+                    .await
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|file| Self::relative_path(folder, file))
+                    .collect()
+            }
+            false => vec![relative_path],
+        };
+        if let Err(e) = self.sync_paths(folder, &relative_paths, false).await {
+            Self::log_error(&format!("Failed to sync {}: {}", path.display(), e));
+        }
+    }
+
+    /// Ingests the files at the paths whose content changed since their last sync, and removes the items of the
+    /// synced files that no longer exist, either at the paths or, when `whole_folder`, anywhere in the folder
+    async fn sync_paths(
+        &self,
+        folder: &WatchedFolder,
+        relative_paths: &[String],
+        whole_folder: bool,
+    ) -> Result<(), String> {
+        let (Some(db), Some(vector_fs)) = (self.db.upgrade(), self.vector_fs.upgrade()) else {
+            return Ok(());
+        };
+        let profile = ShinkaiName::new(folder.profile.clone()).map_err(|e| e.to_string())?;
+        let mut synced_files = db.get_watched_folder_files(&folder.id).map_err(|e| e.to_string())?;
+
+        for relative_path in relative_paths {
+            let path = Path::new(&folder.local_path).join(relative_path);
+            if !folder.should_sync(relative_path) || !path.is_file() {
+                continue;
+            }
+            // A file that fails is skipped, and retried on its next change or on the next full scan
+            let content = match tokio::fs::read(&path).await {
+                Ok(content) => content,
+                Err(e) => {
+                    Self::log_error(&format!("Failed to read {}: {}", path.display(), e));
+                    continue;
+                }
+            };
+            let hash = blake3::hash(&content).to_hex().to_string();
+            if synced_files.get(relative_path) == Some(&hash) {
+                continue;
+            }
+            let modified = tokio::fs::metadata(&path)
+                .await
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .map(DateTime::<Utc>::from);
+
+            if let Err(e) = self
+                .ingest_file(&vector_fs, &profile, folder, relative_path, content, modified)
+                .await
+            {
+                Self::log_error(&format!("Failed to ingest {}: {}", path.display(), e));
+                continue;
+            }
+            synced_files.insert(relative_path.clone(), hash);
+            db.set_watched_folder_files(&folder.id, &synced_files)
+                .map_err(|e| e.to_string())?;
+        }
+
+        // Files that are gone, including the ones of deleted directories
+        let removed: Vec<String> = synced_files
+            .keys()
+            .filter(|synced| {
+                whole_folder
+                    || relative_paths
+                        .iter()
+                        .any(|path| *synced == path || synced.starts_with(&format!("{}/", path)))
+            })
+            .filter(|synced| !Path::new(&folder.local_path).join(synced).is_file())
+            .cloned()
+            .collect();
+        for relative_path in removed {
+            let item_path = folder.vector_fs_item_path(&relative_path).map_err(|e| e.to_string())?;
+            // The item may already have been deleted from the VectorFS, and an item that fails to be deleted is
+            // kept as synced so the deletion is retried on the next full scan
+            if let Ok(writer) = vector_fs.new_writer(profile.clone(), item_path, profile.clone()).await {
+                if let Err(e) = vector_fs.delete_item(&writer).await {
+                    Self::log_error(&format!("Failed to remove the item of {}: {}", relative_path, e));
+                    continue;
+                }
+            }
+            synced_files.remove(&relative_path);
+            db.set_watched_folder_files(&folder.id, &synced_files)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    async fn ingest_file(
+        &self,
+        vector_fs: &Arc<VectorFS>,
+        profile: &ShinkaiName,
+        folder: &WatchedFolder,
+        relative_path: &str,
+        content: Vec<u8>,
+        modified: Option<DateTime<Utc>>,
+    ) -> Result<(), String> {
+        let (folder_path, file_name) = folder.vector_fs_location(relative_path).map_err(|e| e.to_string())?;
+        let root_writer = vector_fs
+            .new_writer(profile.clone(), VRPath::root(), profile.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        vector_fs
+            .create_new_folder_auto(&root_writer, folder_path.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let ingestion = self
+            .db
+            .upgrade()
+            .map(|db| ParsingHelper::ingestion_settings(&db, profile))
+            .unwrap_or_default();
+        let (generator, ingestion) = vector_fs
+            .folder_embedding_settings(profile, &folder_path, &self.generator, &ingestion)
+            .await
+            .map_err(|e| e.to_string())?;
+        let distribution_info = DistributionInfo::new_auto(&file_name, modified);
+        let processed = ParsingHelper::process_files_into_vrkai(
+            vec![(file_name, content, distribution_info)],
+            &*generator,
+            None,
+            FileParser::Local,
+            &ingestion,
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        // Saving over the item of the previous content keeps that one as a version
+        let writer = vector_fs
+            .new_writer(profile.clone(), folder_path, profile.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        for (_, vrkai) in processed {
+            vector_fs
+                .save_vrkai_in_folder(&writer, vrkai)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        shinkai_log(
+            ShinkaiLogOption::Node,
+            ShinkaiLogLevel::Info,
+            &format!("Ingested {} from {}", relative_path, folder.local_path),
+        );
+        Ok(())
+    }
+
+    fn relative_path(folder: &WatchedFolder, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&folder.local_path).ok()?;
+        let segments: Vec<String> = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect();
+        match segments.is_empty() {
+            true => None,
+            false => Some(segments.join("/")),
+        }
+    }
+
+    fn list_files(dir: &Path, recursive: bool) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut files = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if recursive {
+                    files.extend(Self::list_files(&path, recursive));
+                }
+            } else {
+                files.push(path);
+            }
+        }
+        files
+    }
+
+    fn log_error(message: &str) {
+        shinkai_log(ShinkaiLogOption::Node, ShinkaiLogLevel::Error, message);
+    }
+}
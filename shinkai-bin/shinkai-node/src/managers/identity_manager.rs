@@ -1,11 +1,12 @@
-use super::identity_network_manager::IdentityNetworkManager;
+use super::identity_network_manager::{IdentityNetworkManager, RegistryIdentity};
 use crate::db::db_errors::ShinkaiDBError;
 use crate::db::ShinkaiDB;
 use crate::network::network_manager::network_handlers::verify_message_signature;
+use crate::network::error_code::ErrorCode;
 use crate::network::node_error::NodeError;
 use crate::schemas::identity::{DeviceIdentity, Identity, StandardIdentity, StandardIdentityType};
 use async_trait::async_trait;
-use shinkai_crypto_identities::ShinkaiRegistryError;
+use shinkai_crypto_identities::{OnchainIdentity, ShinkaiRegistryError};
 use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider;
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
 use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
@@ -29,6 +30,8 @@ pub trait IdentityManagerTrait {
     fn find_by_identity_name(&self, full_profile_name: ShinkaiName) -> Option<&Identity>;
     async fn search_identity(&self, full_identity_name: &str) -> Option<Identity>;
     fn clone_box(&self) -> Box<dyn IdentityManagerTrait + Send>;
+    /// Called when a device sent a valid message
+    fn record_device_seen(&self, _device_name: &ShinkaiName) {}
 }
 
 impl Clone for Box<dyn IdentityManagerTrait + Send> {
@@ -207,6 +210,24 @@ impl IdentityManager {
         Ok(())
     }
 
+    /// Removes a revoked device, so its messages are rejected from now on
+    pub fn remove_device_subidentity(&mut self, device_name: &ShinkaiName) {
+        self.local_identities.retain(|identity| match identity {
+            Identity::Device(device) => &device.full_identity_name != device_name,
+            _ => true,
+        });
+    }
+
+    /// Removes a deleted profile together with its devices
+    pub fn remove_profile_subidentity(&mut self, profile: &ShinkaiName) {
+        let profile_name = profile.get_profile_name_string();
+        self.local_identities.retain(|identity| match identity {
+            Identity::Standard(standard) => standard.full_identity_name.get_profile_name_string() != profile_name,
+            Identity::Device(device) => device.full_identity_name.get_profile_name_string() != profile_name,
+            _ => true,
+        });
+    }
+
     pub fn has_profile_identity(&self) -> bool {
         self.local_identities.iter().any(|identity| {
             matches!(identity, Identity::Standard(standard_identity) if standard_identity.identity_type == StandardIdentityType::Profile)
@@ -288,6 +309,21 @@ impl IdentityManager {
             .as_str(),
         );
 
+        // ENS names and DIDs are resolved to the Shinkai identity they point to
+        let resolved_name = {
+            let external_im = self.external_identity_manager.lock().await;
+            match external_im.is_resolvable_name(full_profile_name) {
+                true => Some(
+                    external_im
+                        .resolve_identity_name(full_profile_name)
+                        .await
+                        .map(|identity| format!("@@{}", identity.record.shinkai_identity))?,
+                ),
+                false => None,
+            }
+        };
+        let full_profile_name = resolved_name.as_deref().unwrap_or(full_profile_name);
+
         let full_identity_name = match ShinkaiName::new(full_profile_name.to_string().clone()) {
             Ok(name) => name,
             Err(_) => {
@@ -309,6 +345,7 @@ impl IdentityManager {
         match external_im
             .external_identity_to_profile_data(node_name.to_string())
             .await
+            .map(Self::registry_record)
         {
             Ok(identity_network_manager) => match identity_network_manager.first_address().await {
                 Ok(first_address) => {
@@ -366,6 +403,7 @@ impl IdentityManagerTrait for IdentityManager {
             match external_im
                 .external_identity_to_profile_data(full_identity_name.to_string())
                 .await
+                .map(Self::registry_record)
             {
                 Ok(identity_network_manager) => match identity_network_manager.first_address().await {
                     Ok(first_address) => {
@@ -399,9 +437,36 @@ impl IdentityManagerTrait for IdentityManager {
     fn clone_box(&self) -> Box<dyn IdentityManagerTrait + Send> {
         Box::new(self.clone())
     }
+
+    fn record_device_seen(&self, device_name: &ShinkaiName) {
+        if let Some(db) = self.db.upgrade() {
+            if let Err(e) = db.update_device_last_seen(device_name) {
+                shinkai_log(
+                    ShinkaiLogOption::Identity,
+                    ShinkaiLogLevel::Error,
+                    format!("Failed to update the last seen time of {}: {}", device_name, e).as_str(),
+                );
+            }
+        }
+    }
 }
 
 impl IdentityManager {
+    fn registry_record(identity: RegistryIdentity) -> OnchainIdentity {
+        if identity.is_stale {
+            shinkai_log(
+                ShinkaiLogOption::Identity,
+                ShinkaiLogLevel::Info,
+                format!(
+                    "Using the stale cached registry record of {}",
+                    identity.record.shinkai_identity
+                )
+                .as_str(),
+            );
+        }
+        identity.record
+    }
+
     pub fn get_full_identity_name(identity: &Identity) -> Option<String> {
         match identity {
             Identity::Standard(std_identity) => Some(std_identity.full_identity_name.clone().to_string()),
@@ -431,6 +496,7 @@ impl IdentityManager {
                     "Subidentity not found for profile name: {}",
                     decrypted_message.external_metadata.clone().sender
                 ),
+                error_code: ErrorCode::ProfileNotFound,
             });
         }
         // If we reach this point, it means that subidentity exists, so it's safe to unwrap
@@ -464,6 +530,7 @@ impl IdentityManager {
             return Err(NodeError {
                 message: "Failed to verify message signature. Signature public key doesn't exist for identity"
                     .to_string(),
+                error_code: ErrorCode::IdentityNotFound,
             });
         }
 
@@ -477,6 +544,7 @@ impl IdentityManager {
                 );
                 Err(NodeError {
                     message: format!("Failed to verify message signature: {}", e),
+                    error_code: ErrorCode::InternalError,
                 })
             }
         }
@@ -1,47 +1,153 @@
-use shinkai_crypto_identities::{OnchainIdentity, ShinkaiRegistry};
+use super::identity_registry_cache::IdentityRegistryCache;
+use crate::db::ShinkaiDB;
+use ed25519_dalek::SigningKey;
+use shinkai_crypto_identities::{
+    DidWebResolver, EnsResolver, IdentityNameResolver, IdentityRegistryBackend, IdentityRegistryConfig, OnchainIdentity,
+};
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use std::sync::Weak;
 use std::{env, sync::Arc};
 use tokio::sync::Mutex;
 
 pub struct IdentityNetworkManager {
-    registry: Arc<Mutex<ShinkaiRegistry>>,
+    registry: Arc<dyn IdentityRegistryBackend>,
+    local_cache: Option<Mutex<IdentityRegistryCache>>,
+    /// Map ENS names and DIDs to Shinkai identities
+    name_resolvers: Vec<Arc<dyn IdentityNameResolver>>,
+}
+
+/// An identity found in the registry or in the local cache
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegistryIdentity {
+    pub record: OnchainIdentity,
+    /// Served from the local cache past its TTL, because the registry couldn't be reached or the node is offline
+    pub is_stale: bool,
 }
 
 impl IdentityNetworkManager {
     pub async fn new() -> Self {
-        // TODO: Update with mainnet values (eventually)
-        let rpc_url =
-            env::var("RPC_URL").unwrap_or("https://public.stackup.sh/api/v1/node/arbitrum-sepolia".to_string());
-        let contract_address =
-            env::var("CONTRACT_ADDRESS").unwrap_or("0x1d2D57F78Bc3B878aF68c411a03AcF327c85e0D6".to_string());
-        let abi_path = env::var("ABI_PATH").ok();
+        let config = Self::registry_config_from_env();
         shinkai_log(
             ShinkaiLogOption::IdentityNetwork,
             ShinkaiLogLevel::Info,
-            &format!("Identity Network Manager initialized with ABI path: {:?}", abi_path),
+            &format!("Identity Network Manager initialized with registry: {:?}", config),
         );
 
-        let registry = ShinkaiRegistry::new(&rpc_url, &contract_address, abi_path)
+        let registry = config.build().await.unwrap();
+
+        let mut name_resolvers: Vec<Arc<dyn IdentityNameResolver>> = vec![Arc::new(DidWebResolver::new())];
+        let ens_rpc_url = env::var("ENS_RPC_URL").unwrap_or("https://cloudflare-eth.com".to_string());
+        match EnsResolver::new(&ens_rpc_url) {
+            Ok(ens_resolver) => name_resolvers.push(Arc::new(ens_resolver)),
+            Err(e) => shinkai_log(
+                ShinkaiLogOption::IdentityNetwork,
+                ShinkaiLogLevel::Error,
+                &format!("ENS names won't be resolved: {}", e),
+            ),
+        }
+
+        IdentityNetworkManager {
+            registry,
+            local_cache: None,
+            name_resolvers,
+        }
+    }
+
+    /// Whether the name is an ENS name or a DID rather than a Shinkai identity
+    pub fn is_resolvable_name(&self, name: &str) -> bool {
+        self.name_resolvers.iter().any(|resolver| resolver.can_resolve(name))
+    }
+
+    /// Resolves the ENS name or DID to its Shinkai identity, then checks the registry record of the identity has the
+    /// signature key the name vouches for
+    pub async fn resolve_identity_name(&self, name: &str) -> Result<RegistryIdentity, String> {
+        let resolver = self
+            .name_resolvers
+            .iter()
+            .find(|resolver| resolver.can_resolve(name))
+            .ok_or_else(|| format!("No resolver for {}", name))?;
+        let resolved = resolver.resolve(name).await.map_err(|e| e.to_string())?;
+        let identity = self
+            .external_identity_to_profile_data(resolved.shinkai_identity.clone())
             .await
-            .unwrap();
+            .map_err(|e| format!("{} resolves to {}: {}", name, resolved.shinkai_identity, e))?;
+        resolved.verify(&identity.record).map_err(|e| e.to_string())?;
+        Ok(identity)
+    }
+
+    /// The registry is picked with IDENTITY_REGISTRY_BACKEND: `onchain` (default), `file` (IDENTITY_REGISTRY_FILE) or
+    /// `https` (IDENTITY_REGISTRY_URL)
+    pub fn registry_config_from_env() -> IdentityRegistryConfig {
+        match env::var("IDENTITY_REGISTRY_BACKEND").unwrap_or_default().as_str() {
+            "file" => IdentityRegistryConfig::StaticFile {
+                path: env::var("IDENTITY_REGISTRY_FILE").unwrap_or("identity_registry.json".to_string()),
+            },
+            "https" => IdentityRegistryConfig::HttpsDirectory {
+                base_url: env::var("IDENTITY_REGISTRY_URL")
+                    .expect("IDENTITY_REGISTRY_URL is required by the https registry"),
+            },
+            _ => IdentityRegistryConfig::Onchain {
+                // TODO: Update with mainnet values (eventually)
+                rpc_url: env::var("RPC_URL")
+                    .unwrap_or("https://public.stackup.sh/api/v1/node/arbitrum-sepolia".to_string()),
+                contract_address: env::var("CONTRACT_ADDRESS")
+                    .unwrap_or("0x1d2D57F78Bc3B878aF68c411a03AcF327c85e0D6".to_string()),
+                abi_path: env::var("ABI_PATH").ok(),
+            },
+        }
+    }
 
-        let registry = Arc::new(Mutex::new(registry));
+    /// Keeps the identities looked up in a local cache saved in the db and signed by the node identity key
+    pub fn enable_local_cache(&mut self, db: Weak<ShinkaiDB>, signing_key: SigningKey) {
+        self.local_cache = Some(Mutex::new(IdentityRegistryCache::new_from_env(db, signing_key)));
+    }
 
-        IdentityNetworkManager { registry }
+    /// Looks up the identity in the local cache first, then in the registry. The cached record is served, flagged as
+    /// stale, if it's past its TTL and the registry fails or the node is offline.
+    async fn get_identity_record(&self, identity: &str) -> Result<(OnchainIdentity, bool), &'static str> {
+        let cached = match &self.local_cache {
+            Some(local_cache) => {
+                let local_cache = local_cache.lock().await;
+                match (local_cache.get(identity), local_cache.is_offline()) {
+                    (Some((record, false)), _) => return Ok((record, false)),
+                    (Some((record, true)), true) => return Ok((record, true)),
+                    (None, true) => return Err("Unknown identity while the identity registry is offline"),
+                    (cached, false) => cached,
+                }
+            }
+            None => None,
+        };
+
+        let fetched = self.registry.get_identity_record(identity.to_string()).await;
+        match (fetched, cached) {
+            (Ok(record), _) => {
+                if let Some(local_cache) = &self.local_cache {
+                    local_cache.lock().await.insert(identity, record.clone());
+                }
+                Ok((record, false))
+            }
+            (Err(e), Some((record, _))) => {
+                shinkai_log(
+                    ShinkaiLogOption::IdentityNetwork,
+                    ShinkaiLogLevel::Info,
+                    &format!(
+                        "Serving the stale cached record of {} as the registry failed: {}",
+                        identity, e
+                    ),
+                );
+                Ok((record, true))
+            }
+            (Err(_), None) => Err("Unrecognized global identity"),
+        }
     }
 
     pub async fn external_identity_to_profile_data(
         &self,
         global_identity: String,
-    ) -> Result<OnchainIdentity, &'static str> {
-        let record = {
-            let identity = global_identity.trim_start_matches("@@");
-            let registry = self.registry.lock().await;
-            match registry.get_identity_record(identity.to_string()).await {
-                Ok(record) => record,
-                Err(_) => return Err("Unrecognized global identity"),
-            }
-        };
+    ) -> Result<RegistryIdentity, &'static str> {
+        let (record, is_stale) = self
+            .get_identity_record(global_identity.trim_start_matches("@@"))
+            .await?;
 
         // Check if any of the address_or_proxy_nodes ends with .sepolia-shinkai
         if record.address_or_proxy_nodes.iter().any(|node| {
@@ -49,12 +155,9 @@ impl IdentityNetworkManager {
         }) {
             // Call the proxy node to get the actual data
             let proxy_identity = record.address_or_proxy_nodes.clone();
-            let proxy_record = {
-                let registry = self.registry.lock().await;
-                match registry.get_identity_record(proxy_identity.join(",")).await {
-                    Ok(record) => record,
-                    Err(_) => return Err("Failed to fetch proxy node data"),
-                }
+            let (proxy_record, is_proxy_stale) = match self.get_identity_record(&proxy_identity.join(",")).await {
+                Ok(proxy_record) => proxy_record,
+                Err(_) => return Err("Failed to fetch proxy node data"),
             };
 
             // Return the same record but with the updated address_or_proxy_nodes field
@@ -67,10 +170,13 @@ impl IdentityNetworkManager {
                 updated_record
             );
 
-            return Ok(updated_record);
+            return Ok(RegistryIdentity {
+                record: updated_record,
+                is_stale: is_stale || is_proxy_stale,
+            });
         }
 
         eprintln!("external_identity_to_profile_data> Found record: {:?}", record);
-        Ok(record)
+        Ok(RegistryIdentity { record, is_stale })
     }
 }
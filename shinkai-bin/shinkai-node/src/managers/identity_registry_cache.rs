@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::Weak;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use shinkai_crypto_identities::OnchainIdentity;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+
+use crate::db::ShinkaiDB;
+
+/// Identity registry record kept by the node, signed by its identity key so a tampered cache isn't trusted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedRegistryIdentity {
+    pub record: OnchainIdentity,
+    pub fetched_at: DateTime<Utc>,
+    pub signature: String,
+}
+
+impl CachedRegistryIdentity {
+    pub fn new_signed(record: OnchainIdentity, signing_key: &SigningKey) -> Self {
+        let mut cached = CachedRegistryIdentity {
+            record,
+            fetched_at: Utc::now(),
+            signature: String::new(),
+        };
+        cached.signature = hex::encode(signing_key.sign(cached.signed_content().as_bytes()).to_bytes());
+        cached
+    }
+
+    fn signed_content(&self) -> String {
+        format!(
+            "{}:{}",
+            serde_json::to_string(&self.record).unwrap_or_default(),
+            self.fetched_at.to_rfc3339()
+        )
+    }
+
+    pub fn verify(&self, verifying_key: &VerifyingKey) -> bool {
+        let signature = hex::decode(&self.signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok());
+        match signature {
+            Some(signature) => verifying_key
+                .verify(self.signed_content().as_bytes(), &signature)
+                .is_ok(),
+            None => false,
+        }
+    }
+
+    pub fn is_expired(&self, ttl: Duration) -> bool {
+        let age = Utc::now().signed_duration_since(self.fetched_at);
+        age.to_std().map_or(false, |age| age >= ttl)
+    }
+}
+
+/// Local copy of the identities looked up in the registry, so known peers can still be reached when the registry
+/// can't. Entries younger than the TTL are served without asking the registry.
+pub struct IdentityRegistryCache {
+    entries: HashMap<String, CachedRegistryIdentity>,
+    ttl: Duration,
+    /// Never asks the registry, identities are only served from the cache
+    offline: bool,
+    signing_key: SigningKey,
+    db: Weak<ShinkaiDB>,
+}
+
+impl IdentityRegistryCache {
+    /// Loads the entries saved in the db, dropping the ones not signed by the key
+    pub fn new(db: Weak<ShinkaiDB>, signing_key: SigningKey, ttl: Duration, offline: bool) -> Self {
+        let verifying_key = signing_key.verifying_key();
+        let entries = match db.upgrade().map(|db| db.get_identity_registry_cache()) {
+            Some(Ok(entries)) => entries,
+            Some(Err(e)) => {
+                shinkai_log(
+                    ShinkaiLogOption::IdentityNetwork,
+                    ShinkaiLogLevel::Error,
+                    &format!("Failed to load the identity registry cache: {}", e),
+                );
+                HashMap::new()
+            }
+            None => HashMap::new(),
+        };
+        let entries = entries
+            .into_iter()
+            .filter(|(identity, cached)| {
+                let is_valid = cached.verify(&verifying_key);
+                if !is_valid {
+                    shinkai_log(
+                        ShinkaiLogOption::IdentityNetwork,
+                        ShinkaiLogLevel::Error,
+                        &format!("Dropping cached identity {} with an invalid signature", identity),
+                    );
+                }
+                is_valid
+            })
+            .collect();
+
+        IdentityRegistryCache {
+            entries,
+            ttl,
+            offline,
+            signing_key,
+            db,
+        }
+    }
+
+    /// Reads the TTL from IDENTITY_CACHE_TTL_SECS (6 hours by default) and the offline mode from
+    /// IDENTITY_REGISTRY_OFFLINE
+    pub fn new_from_env(db: Weak<ShinkaiDB>, signing_key: SigningKey) -> Self {
+        let ttl = env::var("IDENTITY_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(6 * 60 * 60);
+        let offline = env::var("IDENTITY_REGISTRY_OFFLINE")
+            .map(|value| value == "true")
+            .unwrap_or(false);
+        Self::new(db, signing_key, Duration::from_secs(ttl), offline)
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// The cached record, and whether it's older than the TTL
+    pub fn get(&self, identity: &str) -> Option<(OnchainIdentity, bool)> {
+        self.entries
+            .get(identity)
+            .map(|cached| (cached.record.clone(), cached.is_expired(self.ttl)))
+    }
+
+    /// Caches a record just fetched from the registry
+    pub fn insert(&mut self, identity: &str, record: OnchainIdentity) {
+        let cached = CachedRegistryIdentity::new_signed(record, &self.signing_key);
+        self.entries.insert(identity.to_string(), cached);
+
+        if let Some(db) = self.db.upgrade() {
+            if let Err(e) = db.set_identity_registry_cache(&self.entries) {
+                shinkai_log(
+                    ShinkaiLogOption::IdentityNetwork,
+                    ShinkaiLogLevel::Error,
+                    &format!("Failed to save the identity registry cache: {}", e),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::U256;
+    use shinkai_message_primitives::shinkai_utils::signatures::unsafe_deterministic_signature_keypair;
+
+    #[test]
+    fn test_cached_identity_signature_and_expiration() {
+        let (signing_key, verifying_key) = unsafe_deterministic_signature_keypair(0);
+        let record = OnchainIdentity {
+            shinkai_identity: "node1.sepolia-shinkai".to_string(),
+            bound_nft: U256::from(1),
+            staked_tokens: U256::from(1000),
+            encryption_key: "encryption_key".to_string(),
+            signature_key: "signature_key".to_string(),
+            routing: false,
+            address_or_proxy_nodes: vec!["127.0.0.1:9552".to_string()],
+            delegated_tokens: U256::from(0),
+            last_updated: Utc::now(),
+        };
+
+        let mut cache = IdentityRegistryCache::new(Weak::new(), signing_key.clone(), Duration::from_secs(60), false);
+        cache.insert("node1.sepolia-shinkai", record.clone());
+        assert_eq!(cache.get("node1.sepolia-shinkai"), Some((record.clone(), false)));
+        assert_eq!(cache.get("node2.sepolia-shinkai"), None);
+
+        let mut cached = CachedRegistryIdentity::new_signed(record, &signing_key);
+        assert!(cached.verify(&verifying_key));
+        assert!(cached.is_expired(Duration::from_secs(0)));
+
+        cached.record.address_or_proxy_nodes = vec!["203.0.113.7:9552".to_string()];
+        assert!(!cached.verify(&verifying_key));
+    }
+}
@@ -1,5 +1,17 @@
+#[cfg(feature = "folder-watcher")]
+pub mod folder_watcher;
 pub mod identity_manager;
 pub use identity_manager::IdentityManager;
 pub mod identity_network_manager;
+pub mod identity_registry_cache;
 pub mod model_capabilities_manager;
+pub mod model_capabilities_prober;
+pub mod node_diagnostics;
+pub mod node_health;
+pub mod node_metrics;
+pub mod node_onboarding;
+pub mod oidc_onboarding;
+pub mod operation_registry;
+pub mod profile_data_manager;
+pub mod profile_limits_manager;
 pub mod sheet_manager;
\ No newline at end of file
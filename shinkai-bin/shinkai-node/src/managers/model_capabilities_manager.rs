@@ -14,6 +14,8 @@ use shinkai_message_primitives::schemas::{
     llm_providers::serialized_llm_provider::{LLMProviderInterface, SerializedLLMProvider},
     shinkai_name::ShinkaiName,
 };
+
+use super::model_capabilities_prober::ProbedModelCapabilities;
 use std::{
     fmt,
     sync::{Arc, Weak},
@@ -243,13 +245,50 @@ impl ModelCapabilitiesManager {
         }
     }
 
+    // Same as get_capability but also takes into account the capabilities probed at registration time (if any)
+    pub fn get_capability_with_probe(
+        db: &ShinkaiDB,
+        agent: &SerializedLLMProvider,
+    ) -> (Vec<ModelCapability>, ModelCost, ModelPrivacy) {
+        let (mut capabilities, cost, privacy) = Self::get_capability(agent);
+
+        if let Ok(probed) = db.get_probed_model_capabilities(&agent.model, agent.external_url.as_deref()) {
+            if probed.vision && !capabilities.contains(&ModelCapability::ImageAnalysis) {
+                capabilities.push(ModelCapability::ImageAnalysis);
+            }
+            let answered_any_probe = probed.vision || probed.json_mode || probed.tool_calling;
+            if answered_any_probe && !capabilities.contains(&ModelCapability::TextInference) {
+                capabilities.push(ModelCapability::TextInference);
+            }
+        }
+
+        (capabilities, cost, privacy)
+    }
+
+    /// Returns the maximum number of tokens for the model, preferring the context length probed from the provider
+    pub fn get_max_tokens_with_probe(db: &ShinkaiDB, llm_provider: &SerializedLLMProvider) -> usize {
+        match db.get_probed_model_capabilities(&llm_provider.model, llm_provider.external_url.as_deref()) {
+            Ok(ProbedModelCapabilities {
+                context_length: Some(context_length),
+                ..
+            }) => context_length,
+            _ => Self::get_max_tokens(&llm_provider.model),
+        }
+    }
+
     // Function to check capabilities
     pub async fn check_capabilities(&self) -> Vec<(Vec<ModelCapability>, ModelCost, ModelPrivacy)> {
         let llm_providers = self.llm_providers.clone();
-        llm_providers
-            .into_iter()
-            .map(|llm_provider| Self::get_capability(&llm_provider))
-            .collect()
+        match self.db.upgrade() {
+            Some(db) => llm_providers
+                .into_iter()
+                .map(|llm_provider| Self::get_capability_with_probe(&db, &llm_provider))
+                .collect(),
+            None => llm_providers
+                .into_iter()
+                .map(|llm_provider| Self::get_capability(&llm_provider))
+                .collect(),
+        }
     }
 
     // Function to check if a specific capability is available
@@ -443,7 +482,15 @@ impl ModelCapabilitiesManager {
 
     /// Returns the maximum number of input tokens allowed for the given model, leaving room for output tokens.
     pub fn get_max_input_tokens(model: &LLMProviderInterface) -> usize {
-        let max_tokens = Self::get_max_tokens(model);
+        Self::max_input_tokens(model, Self::get_max_tokens(model))
+    }
+
+    /// Same as get_max_input_tokens but preferring the context length probed from the provider (if any)
+    pub fn get_max_input_tokens_with_probe(db: &ShinkaiDB, llm_provider: &SerializedLLMProvider) -> usize {
+        Self::max_input_tokens(&llm_provider.model, Self::get_max_tokens_with_probe(db, llm_provider))
+    }
+
+    fn max_input_tokens(model: &LLMProviderInterface, max_tokens: usize) -> usize {
         let max_output_tokens = Self::get_max_output_tokens(model) / 2;
         if max_tokens > max_output_tokens {
             max_tokens - max_output_tokens
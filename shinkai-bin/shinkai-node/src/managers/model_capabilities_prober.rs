@@ -0,0 +1,294 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shinkai_message_primitives::{
+    schemas::llm_providers::serialized_llm_provider::{LLMProviderInterface, SerializedLLMProvider},
+    shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption},
+};
+
+use crate::{
+    db::ShinkaiDB,
+    llm_provider::{
+        execution::{
+            chains::inference_chain_trait::LLMInferenceResponse,
+            prompts::{
+                prompts::Prompt,
+                subprompts::{SubPromptAssetType, SubPromptType},
+            },
+        },
+        llm_provider::LLMProvider,
+    },
+};
+
+use super::model_capabilities_manager::ModelCapabilitiesManagerError;
+
+/// A 1x1 red PNG used to check whether a model can actually see images.
+const RED_PIXEL_PNG_BASE64: &str =
+    "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mP8z8DwHwAFBQIAX8jx0gAAAABJRU5ErkJggg==";
+
+/// Max time we wait for a single probe inference before considering the capability unsupported
+const PROBE_TIMEOUT_SECS: u64 = 60;
+
+/// Capabilities detected by actively testing a model instead of relying on static knowledge
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProbedModelCapabilities {
+    pub vision: bool,
+    pub context_length: Option<usize>,
+    pub json_mode: bool,
+    pub tool_calling: bool,
+    pub probed_at: String,
+}
+
+pub struct ModelCapabilitiesProber {}
+
+impl ModelCapabilitiesProber {
+    /// Returns true if the static tables in ModelCapabilitiesManager don't explicitly cover the model,
+    /// in which case we should probe it to find out what it supports.
+    pub fn needs_probing(model: &LLMProviderInterface) -> bool {
+        match model {
+            LLMProviderInterface::OpenAI(openai) => !openai.model_type.starts_with("gpt-"),
+            LLMProviderInterface::GenericAPI(genericapi) => {
+                let model_type = genericapi.model_type.as_str();
+                !(model_type.starts_with("togethercomputer/llama-2") || model_type == "yorickvp/llava-13b")
+            }
+            LLMProviderInterface::Ollama(ollama) => !Self::is_known_open_model(&ollama.model_type),
+            LLMProviderInterface::Exo(exo) => !Self::is_known_open_model(&exo.model_type),
+            LLMProviderInterface::Groq(groq) => !Self::is_known_open_model(&groq.model_type),
+            LLMProviderInterface::ShinkaiBackend(_) => false,
+            LLMProviderInterface::Gemini(_) => false,
+            LLMProviderInterface::LocalLLM(_) => false,
+        }
+    }
+
+    fn is_known_open_model(model_type: &str) -> bool {
+        ["llama3", "llava", "bakllava", "moondream"]
+            .iter()
+            .any(|prefix| model_type.starts_with(prefix))
+            || model_type.contains("minicpm_llama3")
+    }
+
+    /// Probes the provider for every capability we care about and stores the results in the DB
+    pub async fn probe_and_store(
+        db: Arc<ShinkaiDB>,
+        llm_provider: SerializedLLMProvider,
+    ) -> Result<ProbedModelCapabilities, ModelCapabilitiesManagerError> {
+        let capabilities = Self::probe(llm_provider.clone()).await;
+        db.set_probed_model_capabilities(&llm_provider.model, llm_provider.external_url.as_deref(), &capabilities)
+            .map_err(|e| ModelCapabilitiesManagerError::GeneralError(e.to_string()))?;
+
+        shinkai_log(
+            ShinkaiLogOption::JobExecution,
+            ShinkaiLogLevel::Info,
+            &format!(
+                "Probed capabilities for LLM provider {}: {:?}",
+                llm_provider.id, capabilities
+            ),
+        );
+        Ok(capabilities)
+    }
+
+    /// Runs all the probes against the provider. A probe that errors or times out counts as unsupported.
+    pub async fn probe(llm_provider: SerializedLLMProvider) -> ProbedModelCapabilities {
+        let external_url = llm_provider.external_url.clone();
+        let model = llm_provider.model.clone();
+        let provider = LLMProvider::from_serialized_llm_provider(llm_provider);
+
+        let vision = Self::probe_vision(&provider).await;
+        let json_mode = Self::probe_json_mode(&provider).await;
+        let tool_calling = Self::probe_tool_calling(&provider).await;
+        let context_length = Self::probe_context_length(&provider, &model, external_url).await;
+
+        ProbedModelCapabilities {
+            vision,
+            context_length,
+            json_mode,
+            tool_calling,
+            probed_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    async fn run_probe(provider: &LLMProvider, prompt: Prompt) -> Option<LLMInferenceResponse> {
+        match tokio::time::timeout(
+            Duration::from_secs(PROBE_TIMEOUT_SECS),
+            provider.inference(prompt, None, None),
+        )
+        .await
+        {
+            Ok(Ok(response)) => Some(response),
+            Ok(Err(e)) => {
+                shinkai_log(
+                    ShinkaiLogOption::JobExecution,
+                    ShinkaiLogLevel::Debug,
+                    &format!("Capability probe failed for {}: {}", provider.id, e),
+                );
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    async fn probe_vision(provider: &LLMProvider) -> bool {
+        let mut prompt = Prompt::new();
+        prompt.add_asset(
+            SubPromptAssetType::Image,
+            RED_PIXEL_PNG_BASE64.to_string(),
+            String::from("auto"),
+            SubPromptType::User,
+            100,
+        );
+        prompt.add_content(
+            "What is the color of this image? Answer with a single word.".to_string(),
+            SubPromptType::User,
+            100,
+        );
+
+        match Self::run_probe(provider, prompt).await {
+            Some(response) => response.response_string.to_lowercase().contains("red"),
+            None => false,
+        }
+    }
+
+    async fn probe_json_mode(provider: &LLMProvider) -> bool {
+        let mut prompt = Prompt::new();
+        prompt.add_content(
+            "Respond only with a JSON object with a single key \"answer\" holding the result of 2 + 2. Do not add any other text."
+                .to_string(),
+            SubPromptType::User,
+            100,
+        );
+
+        match Self::run_probe(provider, prompt).await {
+            Some(response) => Self::is_json_answer(&response.response_string),
+            None => false,
+        }
+    }
+
+    /// Checks that the response is a JSON object (optionally wrapped in a markdown code block) with an `answer` key
+    fn is_json_answer(response: &str) -> bool {
+        let trimmed = response
+            .trim()
+            .trim_start_matches("```json")
+            .trim_start_matches("```")
+            .trim_end_matches("```")
+            .trim();
+        match serde_json::from_str::<serde_json::Value>(trimmed) {
+            Ok(value) => value.get("answer").is_some(),
+            Err(_) => false,
+        }
+    }
+
+    async fn probe_tool_calling(provider: &LLMProvider) -> bool {
+        let mut prompt = Prompt::new();
+        prompt.add_tool(
+            json!({
+                "type": "function",
+                "function": {
+                    "name": "get_current_weather",
+                    "description": "Get the current weather for a city",
+                    "parameters": {
+                        "type": "object",
+                        "properties": {
+                            "city": {
+                                "type": "string",
+                                "description": "The name of the city",
+                            },
+                        },
+                        "required": ["city"],
+                    },
+                },
+            }),
+            SubPromptType::AvailableTool,
+            100,
+        );
+        prompt.add_content(
+            "What is the weather like in Paris right now?".to_string(),
+            SubPromptType::User,
+            100,
+        );
+
+        match Self::run_probe(provider, prompt).await {
+            Some(response) => response.function_call.is_some(),
+            None => false,
+        }
+    }
+
+    /// Only Ollama exposes the context length of a model through its API. For other providers we return None
+    /// and keep relying on the static values from ModelCapabilitiesManager.
+    async fn probe_context_length(
+        provider: &LLMProvider,
+        model: &LLMProviderInterface,
+        external_url: Option<String>,
+    ) -> Option<usize> {
+        let (LLMProviderInterface::Ollama(ollama), Some(base_url)) = (model, external_url) else {
+            return None;
+        };
+
+        let url = format!("{}/api/show", base_url);
+        let response = provider
+            .client
+            .post(url)
+            .json(&json!({ "name": ollama.model_type }))
+            .send()
+            .await
+            .ok()?;
+        let body: serde_json::Value = response.json().await.ok()?;
+        Self::extract_ollama_context_length(&body)
+    }
+
+    /// Ollama reports the context length as `<architecture>.context_length` inside `model_info`
+    fn extract_ollama_context_length(body: &serde_json::Value) -> Option<usize> {
+        body.get("model_info")?
+            .as_object()?
+            .iter()
+            .find(|(key, _)| key.ends_with(".context_length"))
+            .and_then(|(_, value)| value.as_u64())
+            .map(|value| value as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::{Ollama, OpenAI};
+
+    #[test]
+    fn test_needs_probing() {
+        let known = LLMProviderInterface::Ollama(Ollama {
+            model_type: "llama3.1:8b".to_string(),
+        });
+        let unknown = LLMProviderInterface::Ollama(Ollama {
+            model_type: "some-new-model:7b".to_string(),
+        });
+        let openai = LLMProviderInterface::OpenAI(OpenAI {
+            model_type: "gpt-4o".to_string(),
+        });
+
+        assert!(!ModelCapabilitiesProber::needs_probing(&known));
+        assert!(ModelCapabilitiesProber::needs_probing(&unknown));
+        assert!(!ModelCapabilitiesProber::needs_probing(&openai));
+    }
+
+    #[test]
+    fn test_is_json_answer() {
+        assert!(ModelCapabilitiesProber::is_json_answer("{\"answer\": 4}"));
+        assert!(ModelCapabilitiesProber::is_json_answer("```json\n{\"answer\": 4}\n```"));
+        assert!(!ModelCapabilitiesProber::is_json_answer("The answer is 4"));
+    }
+
+    #[test]
+    fn test_extract_ollama_context_length() {
+        let body = json!({
+            "model_info": {
+                "general.architecture": "llama",
+                "llama.context_length": 131072,
+            }
+        });
+        assert_eq!(
+            ModelCapabilitiesProber::extract_ollama_context_length(&body),
+            Some(131072)
+        );
+        assert_eq!(ModelCapabilitiesProber::extract_ollama_context_length(&json!({})), None);
+    }
+}
@@ -0,0 +1,267 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use tokio::net::TcpStream;
+
+use crate::db::ShinkaiDB;
+
+/// Max time we wait for a service to answer before reporting it as unreachable
+const REACHABILITY_TIMEOUT_SECS: u64 = 10;
+/// Server whose clock is used as reference, overridable with DIAGNOSTICS_TIME_URL
+const DEFAULT_TIME_URL: &str = "https://www.google.com";
+/// Messages are signed with timestamps, so a node whose clock is off gets them rejected by other nodes
+const CLOCK_SKEW_WARNING_SECS: i64 = 30;
+const CLOCK_SKEW_ERROR_SECS: i64 = 300;
+const DISK_SPACE_WARNING_BYTES: u64 = 1024 * 1024 * 1024; // 1GB
+const DISK_SPACE_ERROR_BYTES: u64 = 100 * 1024 * 1024; // 100MB
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticCheck {
+    pub name: String,
+    pub status: DiagnosticStatus,
+    pub message: String,
+    pub duration_ms: u64,
+}
+
+/// Result of every check, with `status` being the worst of them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticsReport {
+    pub status: DiagnosticStatus,
+    pub checks: Vec<DiagnosticCheck>,
+    pub ran_at: String,
+}
+
+impl DiagnosticsReport {
+    pub fn new(checks: Vec<DiagnosticCheck>) -> Self {
+        let status = checks
+            .iter()
+            .map(|check| check.status)
+            .max()
+            .unwrap_or(DiagnosticStatus::Ok);
+        DiagnosticsReport {
+            status,
+            checks,
+            ran_at: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// What the diagnostics need to know about the node
+pub struct DiagnosticsTargets {
+    pub embedding_api_url: String,
+    pub unstructured_api_url: String,
+    /// Addresses the node should be listening on, e.g. the node and websocket ones
+    pub listen_addresses: Vec<(String, SocketAddr)>,
+}
+
+pub struct NodeDiagnostics {}
+
+impl NodeDiagnostics {
+    /// Runs every check concurrently. Checks never fail, problems are reported in the result.
+    pub async fn run(db: Arc<ShinkaiDB>, targets: DiagnosticsTargets) -> DiagnosticsReport {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REACHABILITY_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        let mut service_checks = vec![
+            ("embedding_server".to_string(), targets.embedding_api_url),
+            ("unstructured_api".to_string(), targets.unstructured_api_url),
+        ];
+        match db.get_all_llm_providers() {
+            Ok(llm_providers) => {
+                service_checks.extend(llm_providers.into_iter().filter_map(|llm_provider| {
+                    llm_provider
+                        .external_url
+                        .map(|url| (format!("llm_provider:{}", llm_provider.id), url))
+                }));
+            }
+            Err(e) => shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Error,
+                &format!("Failed to read the LLM providers to diagnose: {}", e),
+            ),
+        }
+
+        let service_futures = service_checks
+            .into_iter()
+            .map(|(name, url)| Self::timed(name, Self::check_reachable(client.clone(), url)));
+        let port_futures = targets
+            .listen_addresses
+            .into_iter()
+            .map(|(name, address)| Self::timed(format!("listen_port:{}", name), Self::check_listening(address)));
+
+        let (mut checks, port_checks, database_check, disk_space_check, clock_skew_check) = tokio::join!(
+            join_all(service_futures),
+            join_all(port_futures),
+            Self::timed("database".to_string(), Self::check_database(db.clone())),
+            Self::timed("disk_space".to_string(), Self::check_disk_space(db.path.clone())),
+            Self::timed("clock_skew".to_string(), Self::check_clock_skew(client)),
+        );
+        checks.extend(port_checks);
+        checks.push(database_check);
+        checks.push(disk_space_check);
+        checks.push(clock_skew_check);
+
+        DiagnosticsReport::new(checks)
+    }
+
+    async fn timed(
+        name: String,
+        check: impl std::future::Future<Output = (DiagnosticStatus, String)>,
+    ) -> DiagnosticCheck {
+        let start = Instant::now();
+        let (status, message) = check.await;
+        DiagnosticCheck {
+            name,
+            status,
+            message,
+            duration_ms: start.elapsed().as_millis() as u64,
+        }
+    }
+
+    /// Any HTTP answer means the service is up, since we don't know which routes (or keys) it expects
+    pub(crate) async fn check_reachable(client: reqwest::Client, url: String) -> (DiagnosticStatus, String) {
+        match client.get(&url).send().await {
+            Ok(response) if response.status().is_server_error() => (
+                DiagnosticStatus::Warning,
+                format!("{} is reachable but answered with {}", url, response.status()),
+            ),
+            Ok(_) => (DiagnosticStatus::Ok, format!("{} is reachable", url)),
+            Err(e) => (DiagnosticStatus::Error, format!("{} is unreachable: {}", url, e)),
+        }
+    }
+
+    async fn check_listening(address: SocketAddr) -> (DiagnosticStatus, String) {
+        // A node listening on every interface is reached through the loopback one
+        let mut connect_address = address;
+        if address.ip().is_unspecified() {
+            connect_address.set_ip(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        }
+
+        let timeout = Duration::from_secs(REACHABILITY_TIMEOUT_SECS);
+        match tokio::time::timeout(timeout, TcpStream::connect(connect_address)).await {
+            Ok(Ok(_)) => (DiagnosticStatus::Ok, format!("Listening on {}", address)),
+            Ok(Err(e)) => (
+                DiagnosticStatus::Error,
+                format!("Nothing is listening on {}: {}", address, e),
+            ),
+            Err(_) => (DiagnosticStatus::Error, format!("Timed out connecting to {}", address)),
+        }
+    }
+
+    async fn check_database(db: Arc<ShinkaiDB>) -> (DiagnosticStatus, String) {
+        match tokio::task::spawn_blocking(move || db.verify_integrity()).await {
+            Ok(Ok(keys_checked)) => (
+                DiagnosticStatus::Ok,
+                format!("Verified the checksums of {} keys", keys_checked),
+            ),
+            Ok(Err(e)) => (DiagnosticStatus::Error, format!("The database is corrupted: {}", e)),
+            Err(e) => (DiagnosticStatus::Error, format!("Failed to verify the database: {}", e)),
+        }
+    }
+
+    async fn check_disk_space(path: String) -> (DiagnosticStatus, String) {
+        match fs2::available_space(&path) {
+            Ok(available) => {
+                let message = format!("{} MB available for {}", available / (1024 * 1024), path);
+                (Self::disk_space_status(available), message)
+            }
+            Err(e) => (
+                DiagnosticStatus::Warning,
+                format!("Failed to read the available space for {}: {}", path, e),
+            ),
+        }
+    }
+
+    fn disk_space_status(available_bytes: u64) -> DiagnosticStatus {
+        if available_bytes < DISK_SPACE_ERROR_BYTES {
+            DiagnosticStatus::Error
+        } else if available_bytes < DISK_SPACE_WARNING_BYTES {
+            DiagnosticStatus::Warning
+        } else {
+            DiagnosticStatus::Ok
+        }
+    }
+
+    /// Compares the local clock with the `Date` header of a well known server
+    async fn check_clock_skew(client: reqwest::Client) -> (DiagnosticStatus, String) {
+        let time_url = std::env::var("DIAGNOSTICS_TIME_URL").unwrap_or_else(|_| DEFAULT_TIME_URL.to_string());
+        let response = match client.head(&time_url).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                return (
+                    DiagnosticStatus::Warning,
+                    format!("Couldn't reach {} to compare clocks: {}", time_url, e),
+                )
+            }
+        };
+        let server_time = response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| DateTime::parse_from_rfc2822(date).ok());
+        let Some(server_time) = server_time else {
+            return (
+                DiagnosticStatus::Warning,
+                format!("{} didn't send a valid Date header", time_url),
+            );
+        };
+
+        let skew_secs = (Utc::now() - server_time.with_timezone(&Utc)).num_seconds();
+        (
+            Self::clock_skew_status(skew_secs),
+            format!("The local clock is {}s off from {}", skew_secs, time_url),
+        )
+    }
+
+    fn clock_skew_status(skew_secs: i64) -> DiagnosticStatus {
+        match skew_secs.abs() {
+            skew if skew > CLOCK_SKEW_ERROR_SECS => DiagnosticStatus::Error,
+            skew if skew > CLOCK_SKEW_WARNING_SECS => DiagnosticStatus::Warning,
+            _ => DiagnosticStatus::Ok,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_diagnostic_statuses() {
+        assert_eq!(NodeDiagnostics::clock_skew_status(-5), DiagnosticStatus::Ok);
+        assert_eq!(NodeDiagnostics::clock_skew_status(-120), DiagnosticStatus::Warning);
+        assert_eq!(NodeDiagnostics::clock_skew_status(3600), DiagnosticStatus::Error);
+        assert_eq!(
+            NodeDiagnostics::disk_space_status(10 * 1024 * 1024),
+            DiagnosticStatus::Error
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (status, _) = NodeDiagnostics::check_listening(listener.local_addr().unwrap()).await;
+        assert_eq!(status, DiagnosticStatus::Ok);
+
+        let check = |status| DiagnosticCheck {
+            name: "check".to_string(),
+            status,
+            message: String::new(),
+            duration_ms: 0,
+        };
+        let report = DiagnosticsReport::new(vec![check(DiagnosticStatus::Ok), check(DiagnosticStatus::Warning)]);
+        assert_eq!(report.status, DiagnosticStatus::Warning);
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use futures::future::join_all;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::db::ShinkaiDB;
+use crate::llm_provider::circuit_breaker::{CircuitState, LLM_PROVIDER_BREAKERS};
+use crate::vector_fs::vector_fs::VectorFS;
+
+use super::node_diagnostics::{DiagnosticStatus, NodeDiagnostics};
+
+/// Load balancers poll the health endpoint, so remote services are checked at most this often
+const REMOTE_CHECKS_CACHE_SECS: u64 = 30;
+/// Health checks have to answer quickly, unlike the diagnostics
+const REMOTE_CHECK_TIMEOUT_SECS: u64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyHealth {
+    pub name: String,
+    pub status: HealthStatus,
+    /// The node can't work without it, so it being unhealthy makes the node unhealthy
+    pub critical: bool,
+    pub checked_at: String,
+    /// Last failure, kept after the dependency recovers
+    pub last_error: Option<String>,
+    pub last_error_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeHealth {
+    pub status: HealthStatus,
+    pub version: String,
+    pub node_name: String,
+    pub is_pristine: bool,
+    pub dependencies: Vec<DependencyHealth>,
+}
+
+/// Remote services the node depends on
+pub struct HealthTargets {
+    /// Name the API reports for the node
+    pub node_name: String,
+    pub embedding_api_url: String,
+    /// Relay the node connects through, and whether it's currently connected to it
+    pub relay: Option<(String, bool)>,
+}
+
+#[derive(Clone)]
+struct CheckResult {
+    name: String,
+    critical: bool,
+    error: Option<String>,
+}
+
+lazy_static! {
+    static ref LAST_ERRORS: Mutex<HashMap<String, (String, String)>> = Mutex::new(HashMap::new());
+    static ref REMOTE_CHECKS_CACHE: Mutex<Option<(Instant, Vec<CheckResult>)>> = Mutex::new(None);
+}
+
+pub struct NodeHealthChecker {}
+
+impl NodeHealthChecker {
+    pub async fn check(db: Arc<ShinkaiDB>, vector_fs: Arc<VectorFS>, targets: HealthTargets) -> NodeHealth {
+        let has_any_profile = db.has_any_profile();
+        let mut results = vec![
+            CheckResult {
+                name: "db".to_string(),
+                critical: true,
+                error: has_any_profile.as_ref().err().map(|e| e.to_string()),
+            },
+            CheckResult {
+                name: "vector_fs".to_string(),
+                critical: true,
+                error: vector_fs.db.db.get(b"health_check").err().map(|e| e.to_string()),
+            },
+        ];
+        if let Some((relay, connected)) = &targets.relay {
+            results.push(CheckResult {
+                name: "relay".to_string(),
+                critical: false,
+                error: (!connected).then(|| format!("Not connected to the relay {}", relay)),
+            });
+        }
+        results.extend(Self::remote_checks(&db, targets.embedding_api_url).await);
+        results.extend(LLM_PROVIDER_BREAKERS.statuses().into_iter().map(|status| CheckResult {
+            name: format!("llm_provider_circuit:{}", status.llm_provider_id),
+            critical: false,
+            error: (status.state != CircuitState::Closed).then(|| {
+                format!(
+                    "Circuit open after {} failures: {}",
+                    status.consecutive_failures,
+                    status.last_error.unwrap_or_default()
+                )
+            }),
+        }));
+
+        Self::report(results, targets.node_name, !has_any_profile.unwrap_or(false))
+    }
+
+    /// Checks the embedding server and the LLM providers, reusing the results of the last
+    /// REMOTE_CHECKS_CACHE_SECS seconds
+    async fn remote_checks(db: &ShinkaiDB, embedding_api_url: String) -> Vec<CheckResult> {
+        if let Some((checked_at, results)) = REMOTE_CHECKS_CACHE.lock().unwrap().as_ref() {
+            if checked_at.elapsed() < Duration::from_secs(REMOTE_CHECKS_CACHE_SECS) {
+                return results.clone();
+            }
+        }
+
+        let mut services = vec![("embedding_server".to_string(), embedding_api_url)];
+        if let Ok(llm_providers) = db.get_all_llm_providers() {
+            services.extend(llm_providers.into_iter().filter_map(|llm_provider| {
+                llm_provider
+                    .external_url
+                    .map(|url| (format!("llm_provider:{}", llm_provider.id), url))
+            }));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REMOTE_CHECK_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+        let results: Vec<CheckResult> = join_all(services.into_iter().map(|(name, url)| {
+            let client = client.clone();
+            async move {
+                let (status, message) = NodeDiagnostics::check_reachable(client, url).await;
+                CheckResult {
+                    name,
+                    critical: false,
+                    error: (status != DiagnosticStatus::Ok).then_some(message),
+                }
+            }
+        }))
+        .await;
+
+        *REMOTE_CHECKS_CACHE.lock().unwrap() = Some((Instant::now(), results.clone()));
+        results
+    }
+
+    fn report(results: Vec<CheckResult>, node_name: String, is_pristine: bool) -> NodeHealth {
+        let now = Utc::now().to_rfc3339();
+        let mut last_errors = LAST_ERRORS.lock().unwrap();
+        let dependencies: Vec<DependencyHealth> = results
+            .into_iter()
+            .map(|result| {
+                if let Some(error) = &result.error {
+                    last_errors.insert(result.name.clone(), (error.clone(), now.clone()));
+                }
+                let last_error = last_errors.get(&result.name).cloned();
+                DependencyHealth {
+                    status: if result.error.is_some() {
+                        HealthStatus::Unhealthy
+                    } else {
+                        HealthStatus::Healthy
+                    },
+                    critical: result.critical,
+                    checked_at: now.clone(),
+                    last_error: last_error.as_ref().map(|(error, _)| error.clone()),
+                    last_error_at: last_error.map(|(_, at)| at),
+                    name: result.name,
+                }
+            })
+            .collect();
+
+        NodeHealth {
+            status: Self::overall_status(&dependencies),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            node_name,
+            is_pristine,
+            dependencies,
+        }
+    }
+
+    /// Unhealthy if a critical dependency is, degraded if any other one isn't healthy
+    fn overall_status(dependencies: &[DependencyHealth]) -> HealthStatus {
+        dependencies
+            .iter()
+            .map(|dependency| match dependency.status {
+                HealthStatus::Unhealthy if !dependency.critical => HealthStatus::Degraded,
+                status => status,
+            })
+            .max()
+            .unwrap_or(HealthStatus::Healthy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overall_health_status() {
+        let result = |name: &str, critical, error: Option<&str>| CheckResult {
+            name: name.to_string(),
+            critical,
+            error: error.map(|e| e.to_string()),
+        };
+
+        let health = NodeHealthChecker::report(
+            vec![
+                result("test_db", true, None),
+                result("test_relay", false, Some("disconnected")),
+            ],
+            "@@node.shinkai".to_string(),
+            false,
+        );
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert_eq!(health.dependencies[1].last_error, Some("disconnected".to_string()));
+
+        // The last error is kept once the dependency recovers
+        let health = NodeHealthChecker::report(
+            vec![
+                result("test_db", true, Some("corrupted")),
+                result("test_relay", false, None),
+            ],
+            "@@node.shinkai".to_string(),
+            false,
+        );
+        assert_eq!(health.status, HealthStatus::Unhealthy);
+        assert_eq!(health.dependencies[1].status, HealthStatus::Healthy);
+        assert_eq!(health.dependencies[1].last_error, Some("disconnected".to_string()));
+    }
+}
@@ -0,0 +1,110 @@
+use std::fs;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::llm_provider::circuit_breaker::CircuitBreakerStatus;
+
+/// Clock ticks per second of the CPU times in /proc, which is 100 on every Linux platform we run on
+const CLOCK_TICKS_PER_SEC: f64 = 100.0;
+
+lazy_static! {
+    pub static ref NODE_STARTED_AT: Instant = Instant::now();
+    /// CPU ticks used by the node at the previous call, to compute the usage in between
+    static ref LAST_CPU_SAMPLE: Mutex<Option<(Instant, u64)>> = Mutex::new(None);
+}
+
+/// Resource usage and load of the node, for the desktop app to show
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct NodeMetrics {
+    pub version: String,
+    pub uptime_secs: u64,
+    /// CPU used since the previous call, 100 being a whole core. Not known on the first call and outside Linux.
+    pub cpu_usage_percent: Option<f64>,
+    /// Resident memory of the node process
+    pub memory_bytes: Option<u64>,
+    pub threads: Option<u64>,
+    /// Job messages waiting to be processed, including the ones being processed
+    pub queued_jobs: usize,
+    /// Inferences waiting for a local model
+    pub waiting_local_inferences: usize,
+    /// LLM providers that failed since their last success, with the state of their circuit breaker
+    pub llm_provider_circuits: Vec<CircuitBreakerStatus>,
+}
+
+pub struct NodeMetricsCollector {}
+
+impl NodeMetricsCollector {
+    pub fn collect(
+        queued_jobs: usize,
+        waiting_local_inferences: usize,
+        llm_provider_circuits: Vec<CircuitBreakerStatus>,
+    ) -> NodeMetrics {
+        let status = fs::read_to_string("/proc/self/status").unwrap_or_default();
+        let cpu_ticks = fs::read_to_string("/proc/self/stat")
+            .ok()
+            .and_then(|stat| Self::parse_cpu_ticks(&stat));
+
+        NodeMetrics {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime_secs: NODE_STARTED_AT.elapsed().as_secs(),
+            cpu_usage_percent: cpu_ticks.and_then(Self::cpu_usage_percent),
+            memory_bytes: Self::parse_status_value(&status, "VmRSS:").map(|kb| kb * 1024),
+            threads: Self::parse_status_value(&status, "Threads:"),
+            queued_jobs,
+            waiting_local_inferences,
+            llm_provider_circuits,
+        }
+    }
+
+    fn cpu_usage_percent(cpu_ticks: u64) -> Option<f64> {
+        let now = Instant::now();
+        let previous = LAST_CPU_SAMPLE.lock().unwrap().replace((now, cpu_ticks))?;
+        let elapsed_secs = now.duration_since(previous.0).as_secs_f64();
+        if elapsed_secs <= 0.0 {
+            return None;
+        }
+        let used_secs = cpu_ticks.saturating_sub(previous.1) as f64 / CLOCK_TICKS_PER_SEC;
+        Some(used_secs / elapsed_secs * 100.0)
+    }
+
+    /// User and system CPU ticks of a /proc/[pid]/stat line
+    fn parse_cpu_ticks(stat: &str) -> Option<u64> {
+        // The process name can have spaces, the fields are counted from the state after it
+        let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+        let utime: u64 = fields.get(11)?.parse().ok()?;
+        let stime: u64 = fields.get(12)?.parse().ok()?;
+        Some(utime + stime)
+    }
+
+    /// Number of a /proc/[pid]/status line, e.g. `VmRSS:     1024 kB`
+    fn parse_status_value(status: &str, key: &str) -> Option<u64> {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(key))?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_files() {
+        let stat = "4242 (shinkai node) S 1 4242 4242 0 -1 4194560 1200 0 0 0 350 75 0 0 20 0 12 0 100 0";
+        assert_eq!(NodeMetricsCollector::parse_cpu_ticks(stat), Some(425));
+        assert_eq!(NodeMetricsCollector::parse_cpu_ticks("4242 (shinkai"), None);
+
+        let status = "Name:\tshinkai_node\nVmRSS:\t  20480 kB\nThreads:\t12\n";
+        assert_eq!(NodeMetricsCollector::parse_status_value(status, "VmRSS:"), Some(20480));
+        assert_eq!(NodeMetricsCollector::parse_status_value(status, "Threads:"), Some(12));
+        assert_eq!(NodeMetricsCollector::parse_status_value(status, "VmSwap:"), None);
+    }
+}
@@ -0,0 +1,102 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider;
+use shinkai_message_primitives::shinkai_utils::encryption::{
+    encryption_public_key_to_string, encryption_secret_key_to_string, ephemeral_encryption_keys,
+};
+use shinkai_message_primitives::shinkai_utils::signatures::{
+    ephemeral_signature_keypair, signature_public_key_to_string, signature_secret_key_to_string,
+};
+
+use crate::llm_provider::execution::prompts::prompts::Prompt;
+use crate::llm_provider::execution::prompts::subprompts::SubPromptType;
+use crate::llm_provider::llm_provider::LLMProvider;
+
+/// The wizard waits for the answer, so slow providers are reported as failing
+const LLM_PROVIDER_TEST_TIMEOUT_SECS: u64 = 30;
+
+/// Keys of the first profile, generated for clients that can't generate them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingKeys {
+    pub profile_encryption_sk: String,
+    pub profile_encryption_pk: String,
+    pub profile_identity_sk: String,
+    pub profile_identity_pk: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LLMProviderTestResult {
+    pub success: bool,
+    /// Answer of the model, or why it couldn't answer
+    pub message: String,
+    pub latency_ms: u64,
+}
+
+pub struct NodeOnboarding {}
+
+impl NodeOnboarding {
+    pub fn generate_keys() -> OnboardingKeys {
+        let (encryption_sk, encryption_pk) = ephemeral_encryption_keys();
+        let (identity_sk, identity_pk) = ephemeral_signature_keypair();
+
+        OnboardingKeys {
+            profile_encryption_sk: encryption_secret_key_to_string(encryption_sk),
+            profile_encryption_pk: encryption_public_key_to_string(encryption_pk),
+            profile_identity_sk: signature_secret_key_to_string(identity_sk),
+            profile_identity_pk: signature_public_key_to_string(identity_pk),
+        }
+    }
+
+    /// Sends a short prompt to the provider, checking its URL, credentials and model before they're saved
+    pub async fn test_llm_provider(llm_provider: SerializedLLMProvider) -> LLMProviderTestResult {
+        let provider = LLMProvider::from_serialized_llm_provider(llm_provider);
+        let mut prompt = Prompt::new();
+        prompt.add_content("Answer with a single word: ready".to_string(), SubPromptType::User, 100);
+
+        let started_at = Instant::now();
+        let result = tokio::time::timeout(
+            Duration::from_secs(LLM_PROVIDER_TEST_TIMEOUT_SECS),
+            provider.inference(prompt, None, None),
+        )
+        .await;
+        let latency_ms = started_at.elapsed().as_millis() as u64;
+
+        let (success, message) = match result {
+            Ok(Ok(response)) => (true, response.response_string),
+            Ok(Err(e)) => (false, e.to_string()),
+            Err(_) => (
+                false,
+                format!("No answer after {} seconds", LLM_PROVIDER_TEST_TIMEOUT_SECS),
+            ),
+        };
+        LLMProviderTestResult {
+            success,
+            message,
+            latency_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shinkai_message_primitives::shinkai_utils::encryption::string_to_encryption_public_key;
+    use shinkai_message_primitives::shinkai_utils::signatures::{
+        string_to_signature_public_key, string_to_signature_secret_key,
+    };
+
+    #[test]
+    fn test_generate_onboarding_keys() {
+        let keys = NodeOnboarding::generate_keys();
+
+        let identity_sk = string_to_signature_secret_key(&keys.profile_identity_sk).unwrap();
+        let identity_pk = string_to_signature_public_key(&keys.profile_identity_pk).unwrap();
+        assert_eq!(identity_sk.verifying_key(), identity_pk);
+        assert!(string_to_encryption_public_key(&keys.profile_encryption_pk).is_ok());
+        assert_ne!(
+            keys.profile_identity_pk,
+            NodeOnboarding::generate_keys().profile_identity_pk
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use serde_json::Value;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::IdentityPermissions;
+
+/// Keys of the identity provider are fetched again after this, or when a token is signed with an unknown key
+const JWKS_CACHE_SECS: u64 = 3600;
+
+lazy_static! {
+    /// Keys of the identity provider, with the URL they were fetched from
+    static ref JWKS_CACHE: Mutex<Option<(String, Instant, JwkSet)>> = Mutex::new(None);
+}
+
+/// Identity provider devices can authenticate against instead of using a registration code.
+/// It's disabled unless `OIDC_ISSUER_URL` and `OIDC_CLIENT_ID` are set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OidcConfig {
+    pub issuer_url: String,
+    /// Audience the ID tokens have to be issued for
+    pub client_id: String,
+    /// Claim with the groups or roles of the user, e.g. `groups`
+    pub permissions_claim: String,
+    /// Values of the permissions claim that give admin permissions
+    pub admin_values: Vec<String>,
+    /// Values of the permissions claim that give standard permissions
+    pub standard_values: Vec<String>,
+    /// Claim naming the profile the device is added to, e.g. `preferred_username`. Devices are added to
+    /// the main profile if not set.
+    pub profile_claim: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OidcDiscovery {
+    jwks_uri: String,
+}
+
+impl OidcConfig {
+    pub fn from_env() -> Option<Self> {
+        let list = |name: &str| -> Vec<String> {
+            env::var(name)
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        };
+
+        Some(Self {
+            issuer_url: env::var("OIDC_ISSUER_URL").ok()?.trim_end_matches('/').to_string(),
+            client_id: env::var("OIDC_CLIENT_ID").ok()?,
+            permissions_claim: env::var("OIDC_PERMISSIONS_CLAIM").unwrap_or_else(|_| "groups".to_string()),
+            admin_values: list("OIDC_ADMIN_VALUES"),
+            standard_values: list("OIDC_STANDARD_VALUES"),
+            profile_claim: env::var("OIDC_PROFILE_CLAIM").ok(),
+        })
+    }
+
+    /// Checks the signature, issuer, audience and expiry of an ID token and returns its claims
+    pub async fn verify_id_token(&self, id_token: &str) -> Result<Value, String> {
+        let header = decode_header(id_token).map_err(|e| format!("Invalid ID token: {}", e))?;
+        // Tokens signed with a shared secret can't be checked against the keys of the provider
+        if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+            return Err(format!("Unsupported ID token algorithm {:?}", header.alg));
+        }
+        let kid = header.kid.ok_or("The ID token doesn't name its signing key")?;
+
+        let mut jwks = self.jwks(false).await?;
+        if jwks.find(&kid).is_none() {
+            // The provider may have rotated its keys
+            jwks = self.jwks(true).await?;
+        }
+        let jwk = jwks.find(&kid).ok_or(format!("Unknown ID token signing key {}", kid))?;
+        let decoding_key = DecodingKey::from_jwk(jwk).map_err(|e| format!("Invalid signing key {}: {}", kid, e))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.issuer_url]);
+        validation.set_audience(&[&self.client_id]);
+        let token =
+            decode::<Value>(id_token, &decoding_key, &validation).map_err(|e| format!("Invalid ID token: {}", e))?;
+
+        Ok(token.claims)
+    }
+
+    /// Identifier of an ID token to detect its reuse, from its `jti` claim or the whole token if it doesn't have one
+    pub fn token_id(id_token: &str, claims: &Value) -> String {
+        let id = claims.get("jti").and_then(|jti| jti.as_str()).unwrap_or(id_token);
+        blake3::hash(id.as_bytes()).to_hex().to_string()
+    }
+
+    /// Permissions a user gets from the claims of its ID token, `None` if it's not allowed to register devices
+    pub fn permission_from_claims(&self, claims: &Value) -> Option<IdentityPermissions> {
+        let values: Vec<&str> = match claims.get(&self.permissions_claim) {
+            Some(Value::String(value)) => vec![value.as_str()],
+            Some(Value::Array(values)) => values.iter().filter_map(|value| value.as_str()).collect(),
+            _ => vec![],
+        };
+
+        if values
+            .iter()
+            .any(|value| self.admin_values.iter().any(|admin| admin == value))
+        {
+            Some(IdentityPermissions::Admin)
+        } else if values
+            .iter()
+            .any(|value| self.standard_values.iter().any(|standard| standard == value))
+        {
+            Some(IdentityPermissions::Standard)
+        } else {
+            None
+        }
+    }
+
+    /// Profile a device of the user is added to
+    pub fn profile_from_claims(&self, claims: &Value) -> Result<String, String> {
+        let Some(profile_claim) = &self.profile_claim else {
+            return Ok("main".to_string());
+        };
+
+        let profile = claims
+            .get(profile_claim)
+            .and_then(|value| value.as_str())
+            .ok_or(format!("The ID token doesn't have the {} claim", profile_claim))?;
+        // Profile names can only have alphanumeric characters and underscores
+        let profile: String = profile
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        Ok(profile)
+    }
+
+    async fn jwks(&self, refresh: bool) -> Result<JwkSet, String> {
+        if !refresh {
+            if let Some((issuer_url, fetched_at, jwks)) = JWKS_CACHE.lock().unwrap().as_ref() {
+                if issuer_url == &self.issuer_url && fetched_at.elapsed() < Duration::from_secs(JWKS_CACHE_SECS) {
+                    return Ok(jwks.clone());
+                }
+            }
+        }
+
+        let discovery_url = format!("{}/.well-known/openid-configuration", self.issuer_url);
+        let discovery: OidcDiscovery = Self::get_json(&discovery_url).await?;
+        let jwks: JwkSet = Self::get_json(&discovery.jwks_uri).await?;
+
+        *JWKS_CACHE.lock().unwrap() = Some((self.issuer_url.clone(), Instant::now(), jwks.clone()));
+        Ok(jwks)
+    }
+
+    async fn get_json<T: for<'de> Deserialize<'de>>(url: &str) -> Result<T, String> {
+        reqwest::get(url)
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Failed to reach the identity provider at {}: {}", url, e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid response from the identity provider at {}: {}", url, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_oidc_claims_mapping() {
+        let config = OidcConfig {
+            issuer_url: "https://idp.example.com".to_string(),
+            client_id: "shinkai".to_string(),
+            permissions_claim: "groups".to_string(),
+            admin_values: vec!["shinkai-admins".to_string()],
+            standard_values: vec!["shinkai-users".to_string()],
+            profile_claim: Some("preferred_username".to_string()),
+        };
+
+        let claims = json!({ "groups": ["everyone", "shinkai-users"], "preferred_username": "Jane.Doe" });
+        assert_eq!(
+            config.permission_from_claims(&claims),
+            Some(IdentityPermissions::Standard)
+        );
+        assert_eq!(config.profile_from_claims(&claims), Ok("jane_doe".to_string()));
+
+        let claims = json!({ "groups": "shinkai-admins" });
+        assert_eq!(config.permission_from_claims(&claims), Some(IdentityPermissions::Admin));
+        assert!(config.profile_from_claims(&claims).is_err());
+
+        assert_eq!(config.permission_from_claims(&json!({ "groups": ["everyone"] })), None);
+    }
+
+    #[test]
+    fn test_oidc_token_id() {
+        let claims = json!({ "jti": "token-1" });
+        assert_eq!(
+            OidcConfig::token_id("header.payload.signature", &claims),
+            OidcConfig::token_id("other.payload.signature", &claims)
+        );
+        assert_ne!(
+            OidcConfig::token_id("header.payload.signature", &claims),
+            OidcConfig::token_id("header.payload.signature", &json!({ "jti": "token-2" }))
+        );
+
+        // Without a jti, the token itself identifies it
+        assert_ne!(
+            OidcConfig::token_id("header.payload.signature", &json!({})),
+            OidcConfig::token_id("other.payload.signature", &json!({}))
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+lazy_static! {
+    /// Long-running VectorFS and subscription operations of the node
+    pub static ref OPERATIONS: OperationRegistry = OperationRegistry::new();
+}
+
+/// Finished operations kept for their status to be read, the oldest ones are dropped first
+const MAX_FINISHED_OPERATIONS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    CopyFolder,
+    ConvertFiles,
+    SubscriptionSync,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationState {
+    Running,
+    Done,
+    Error,
+    /// Cancellation was requested, the operation stops at its next step
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OperationStatus {
+    pub operation_id: String,
+    pub kind: OperationKind,
+    pub description: String,
+    pub state: OperationState,
+    pub completed_steps: u64,
+    /// Zero while the operation doesn't know how many steps it has
+    pub total_steps: u64,
+    pub progress_percent: u8,
+    pub error: Option<String>,
+    pub started_at: String,
+    /// Set once the operation stopped, which for cancelled ones can be a while after the cancellation
+    pub finished_at: Option<String>,
+}
+
+struct Operation {
+    status: OperationStatus,
+    /// Operations of the same subscription, folder, etc. share a key so they're only tracked once
+    key: Option<String>,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Registry of the operations that take long enough for clients to want their progress, e.g. copying a large
+/// folder. Operations report their progress through an `OperationHandle` and check it for cancellation between
+/// steps.
+#[derive(Clone, Default)]
+pub struct OperationRegistry {
+    operations: Arc<Mutex<HashMap<String, Operation>>>,
+}
+
+impl OperationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start(&self, kind: OperationKind, description: String) -> OperationHandle {
+        self.insert(kind, description, None)
+    }
+
+    /// Starts an operation, or returns the one with the same key if it didn't stop yet
+    pub fn start_keyed(&self, kind: OperationKind, key: &str, description: String) -> OperationHandle {
+        {
+            let operations = self.operations.lock().unwrap();
+            let running = operations
+                .values()
+                .find(|operation| operation.key.as_deref() == Some(key) && operation.status.finished_at.is_none());
+            if let Some(operation) = running {
+                return OperationHandle {
+                    operation_id: operation.status.operation_id.clone(),
+                    cancelled: operation.cancelled.clone(),
+                    registry: self.clone(),
+                };
+            }
+        }
+        self.insert(kind, description, Some(key.to_string()))
+    }
+
+    fn insert(&self, kind: OperationKind, description: String, key: Option<String>) -> OperationHandle {
+        let operation_id = Uuid::new_v4().to_string();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let status = OperationStatus {
+            operation_id: operation_id.clone(),
+            kind,
+            description,
+            state: OperationState::Running,
+            completed_steps: 0,
+            total_steps: 0,
+            progress_percent: 0,
+            error: None,
+            started_at: Utc::now().to_rfc3339(),
+            finished_at: None,
+        };
+
+        let mut operations = self.operations.lock().unwrap();
+        operations.insert(
+            operation_id.clone(),
+            Operation {
+                status,
+                key,
+                cancelled: cancelled.clone(),
+            },
+        );
+        Self::prune_finished(&mut operations);
+
+        OperationHandle {
+            operation_id,
+            cancelled,
+            registry: self.clone(),
+        }
+    }
+
+    pub fn status(&self, operation_id: &str) -> Option<OperationStatus> {
+        let operations = self.operations.lock().unwrap();
+        operations.get(operation_id).map(|operation| operation.status.clone())
+    }
+
+    /// Operations that didn't stop yet, oldest first
+    pub fn running(&self) -> Vec<OperationStatus> {
+        let operations = self.operations.lock().unwrap();
+        let mut statuses: Vec<OperationStatus> = operations
+            .values()
+            .filter(|operation| operation.status.finished_at.is_none())
+            .map(|operation| operation.status.clone())
+            .collect();
+        statuses.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+        statuses
+    }
+
+    /// Asks an operation to stop. Returns None if there is no such operation, and its status otherwise, which
+    /// is left unchanged if it already stopped.
+    pub fn cancel(&self, operation_id: &str) -> Option<OperationStatus> {
+        let mut operations = self.operations.lock().unwrap();
+        let operation = operations.get_mut(operation_id)?;
+        if operation.status.finished_at.is_none() {
+            operation.cancelled.store(true, Ordering::SeqCst);
+            operation.status.state = OperationState::Cancelled;
+        }
+        Some(operation.status.clone())
+    }
+
+    fn update(&self, operation_id: &str, update: impl FnOnce(&mut OperationStatus)) {
+        let mut operations = self.operations.lock().unwrap();
+        if let Some(operation) = operations.get_mut(operation_id) {
+            update(&mut operation.status);
+        }
+    }
+
+    fn prune_finished(operations: &mut HashMap<String, Operation>) {
+        let mut finished: Vec<(String, String)> = operations
+            .iter()
+            .filter_map(|(id, operation)| Some((operation.status.finished_at.clone()?, id.clone())))
+            .collect();
+        if finished.len() <= MAX_FINISHED_OPERATIONS {
+            return;
+        }
+        finished.sort();
+        for (_, id) in finished.iter().take(finished.len() - MAX_FINISHED_OPERATIONS) {
+            operations.remove(id);
+        }
+    }
+}
+
+/// Reports the progress of a single operation
+#[derive(Clone)]
+pub struct OperationHandle {
+    operation_id: String,
+    cancelled: Arc<AtomicBool>,
+    registry: OperationRegistry,
+}
+
+impl OperationHandle {
+    pub fn operation_id(&self) -> &str {
+        &self.operation_id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn set_total_steps(&self, total_steps: u64) {
+        self.registry.update(&self.operation_id, |status| {
+            status.total_steps = total_steps;
+            status.progress_percent = progress_percent(status.completed_steps, total_steps);
+        });
+    }
+
+    pub fn set_progress(&self, completed_steps: u64, total_steps: u64) {
+        self.registry.update(&self.operation_id, |status| {
+            status.completed_steps = completed_steps;
+            status.total_steps = total_steps;
+            status.progress_percent = progress_percent(completed_steps, total_steps);
+        });
+    }
+
+    pub fn advance(&self) {
+        self.registry.update(&self.operation_id, |status| {
+            status.completed_steps += 1;
+            status.progress_percent = progress_percent(status.completed_steps, status.total_steps);
+        });
+    }
+
+    /// Marks the operation as stopped with the given result. Cancelled operations stay cancelled.
+    pub fn finish<T, E: Display>(&self, result: &Result<T, E>) {
+        self.registry.update(&self.operation_id, |status| {
+            match (status.state, result) {
+                (OperationState::Cancelled, _) => {}
+                (_, Ok(_)) => {
+                    status.state = OperationState::Done;
+                    status.completed_steps = status.total_steps;
+                    status.progress_percent = 100;
+                }
+                (_, Err(e)) => {
+                    status.state = OperationState::Error;
+                    status.error = Some(e.to_string());
+                }
+            }
+            status.finished_at = Some(Utc::now().to_rfc3339());
+        });
+    }
+}
+
+fn progress_percent(completed_steps: u64, total_steps: u64) -> u8 {
+    if total_steps == 0 {
+        return 0;
+    }
+    (completed_steps.min(total_steps) * 100 / total_steps) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_progress_and_cancellation() {
+        let registry = OperationRegistry::new();
+        let copy = registry.start(OperationKind::CopyFolder, "Copying /docs".to_string());
+        copy.set_total_steps(4);
+        copy.advance();
+        let status = registry.status(copy.operation_id()).unwrap();
+        assert_eq!((status.state, status.progress_percent), (OperationState::Running, 25));
+
+        // Operations of the same key are only tracked once
+        let sync = registry.start_keyed(OperationKind::SubscriptionSync, "sub_1", "Syncing".to_string());
+        let same_sync = registry.start_keyed(OperationKind::SubscriptionSync, "sub_1", "Syncing".to_string());
+        assert_eq!(sync.operation_id(), same_sync.operation_id());
+        assert_eq!(registry.running().len(), 2);
+
+        let cancelled = registry.cancel(sync.operation_id()).unwrap();
+        assert_eq!(cancelled.state, OperationState::Cancelled);
+        assert!(same_sync.is_cancelled());
+        sync.finish::<(), String>(&Ok(()));
+        assert_eq!(
+            registry.status(sync.operation_id()).unwrap().state,
+            OperationState::Cancelled
+        );
+
+        copy.finish::<(), String>(&Err("disk full".to_string()));
+        let status = registry.status(copy.operation_id()).unwrap();
+        assert_eq!(status.error, Some("disk full".to_string()));
+        assert!(registry.running().is_empty());
+        assert!(registry.cancel("unknown").is_none());
+    }
+}
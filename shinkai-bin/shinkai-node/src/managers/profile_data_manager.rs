@@ -0,0 +1,305 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{json, Value};
+use shinkai_message_primitives::schemas::contact::ContactBook;
+use shinkai_message_primitives::schemas::inbox_name::InboxName;
+use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
+use shinkai_vector_resources::vector_resource::VRPath;
+use tokio::sync::Mutex;
+
+use crate::db::db_cron_task::CronTask;
+use crate::db::db_errors::ShinkaiDBError;
+use crate::db::db_network_notifications::UserNetworkNotification;
+use crate::db::ShinkaiDB;
+use crate::schemas::calendar_account::CalendarAccountConfig;
+use crate::schemas::cloud_connector::CloudConnector;
+use crate::schemas::email_account::EmailAccountConfig;
+use crate::schemas::identity::{DeviceInfo, StandardIdentity};
+use crate::schemas::inbound_message_policy::InboundMessagePolicy;
+use crate::schemas::inbox_digest::InboxDigestSchedule;
+use crate::schemas::inbox_permission::InboxPermission;
+use crate::schemas::notification::NotificationPreferences;
+use crate::schemas::profile_limits::ProfileLimits;
+use crate::schemas::watched_folder::WatchedFolder;
+use crate::vector_fs::vector_fs::VectorFS;
+
+use super::IdentityManager;
+
+/// Everything the node stores for a profile, without the data of other profiles
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileDataExport {
+    pub profile: String,
+    pub exported_at: String,
+    pub identity: StandardIdentity,
+    pub devices: Vec<DeviceInfo>,
+    pub inboxes: Vec<InboxExport>,
+    pub llm_providers: Vec<SerializedLLMProvider>,
+    pub limits: ProfileLimits,
+    pub cron_tasks: Vec<CronTask>,
+    pub email_account: Option<EmailAccountConfig>,
+    /// Without its OAuth tokens
+    pub calendar_account: Option<CalendarAccountConfig>,
+    pub watched_folders: Vec<WatchedFolder>,
+    /// Without their OAuth tokens
+    pub cloud_connectors: Vec<CloudConnector>,
+    pub contact_book: ContactBook,
+    pub inbound_message_policy: InboundMessagePolicy,
+    pub inbox_digest_schedule: Option<InboxDigestSchedule>,
+    pub notification_preferences: NotificationPreferences,
+    /// Notifications from the newest
+    pub notifications: Vec<UserNetworkNotification>,
+    /// VectorFS of the profile as a base64 encoded VRPack, if it has one
+    pub vector_fs: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InboxExport {
+    pub inbox_name: String,
+    /// Messages from the oldest, with their branches
+    pub messages: Vec<Vec<ShinkaiMessage>>,
+    /// Job of the inbox, for job inboxes
+    pub job: Option<Value>,
+}
+
+pub struct ProfileDataManager {}
+
+impl ProfileDataManager {
+    pub async fn export_profile_data(
+        db: &ShinkaiDB,
+        vector_fs: &VectorFS,
+        profile: &ShinkaiName,
+    ) -> Result<ProfileDataExport, ShinkaiDBError> {
+        let profile_name = profile
+            .get_profile_name_string()
+            .ok_or(ShinkaiDBError::InvalidIdentityName(profile.to_string()))?;
+        let identity = db
+            .get_profile(profile.clone())?
+            .ok_or(ShinkaiDBError::ProfileNotFound(profile.to_string()))?;
+
+        let mut inboxes = Vec::new();
+        for inbox_name in db.get_inboxes_for_profile(identity.clone())? {
+            let job = match InboxName::new(inbox_name.clone())? {
+                InboxName::JobInbox { unique_id, .. } => db.get_job(&unique_id).ok().map(|job| {
+                    json!({
+                        "job_id": job.job_id,
+                        "is_hidden": job.is_hidden,
+                        "datetime_created": job.datetime_created,
+                        "is_finished": job.is_finished,
+                        "parent_llm_provider_id": job.parent_llm_provider_id,
+                        "scope": job.scope,
+                        "step_history": job.step_history,
+                        "execution_context": job.execution_context,
+                    })
+                }),
+                _ => None,
+            };
+            let messages = db.get_last_messages_from_inbox(inbox_name.clone(), usize::MAX, None)?;
+            inboxes.push(InboxExport {
+                inbox_name,
+                messages,
+                job,
+            });
+        }
+
+        let vector_fs_data = match vector_fs
+            .new_reader(profile.clone(), VRPath::root(), profile.clone())
+            .await
+        {
+            Ok(reader) => Some(vector_fs.retrieve_vrpack(&reader).await?.encode_as_base64()?),
+            // Profiles that never used the VectorFS don't have one
+            Err(_) => None,
+        };
+
+        Ok(ProfileDataExport {
+            profile: profile.full_name.clone(),
+            exported_at: Utc::now().to_rfc3339(),
+            devices: db.get_profile_devices(profile)?,
+            inboxes,
+            llm_providers: db.get_llm_providers_for_profile(profile.clone())?,
+            limits: db.get_profile_limits(&profile_name)?,
+            cron_tasks: db
+                .get_all_cron_tasks_for_profile(profile.clone())?
+                .into_values()
+                .collect(),
+            email_account: db.get_email_account(&profile.full_name).ok(),
+            calendar_account: db
+                .get_calendar_account(&profile.full_name)
+                .ok()
+                .map(|account| account.redacted()),
+            watched_folders: db.get_watched_folders_for_profile(&profile.full_name)?,
+            cloud_connectors: db
+                .get_cloud_connectors_for_profile(&profile.full_name)?
+                .iter()
+                .map(|connector| connector.redacted())
+                .collect(),
+            contact_book: db.get_contact_book(profile)?,
+            inbound_message_policy: db.get_inbound_message_policy(profile)?,
+            inbox_digest_schedule: db.get_inbox_digest_schedule(profile)?,
+            notification_preferences: db.get_notification_preferences(&profile_name)?,
+            notifications: db.get_last_notifications(profile.clone(), usize::MAX, None)?,
+            vector_fs: vector_fs_data,
+            identity,
+        })
+    }
+
+    /// Deletes a profile with its devices, inboxes, jobs, LLM providers, settings and VectorFS.
+    /// Inboxes other profiles of the node take part in are kept, only losing the access of this profile.
+    pub async fn delete_profile(
+        db: &ShinkaiDB,
+        vector_fs: &VectorFS,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        node_name: &ShinkaiName,
+        profile: &ShinkaiName,
+    ) -> Result<(), ShinkaiDBError> {
+        let profile_name = profile
+            .get_profile_name_string()
+            .ok_or(ShinkaiDBError::InvalidIdentityName(profile.to_string()))?;
+        let identity = db
+            .get_profile(profile.clone())?
+            .ok_or(ShinkaiDBError::ProfileNotFound(profile.to_string()))?;
+        let other_profiles: Vec<StandardIdentity> = db
+            .get_all_profiles(node_name.clone())?
+            .into_iter()
+            .filter(|other| other.full_identity_name.get_profile_name_string() != Some(profile_name.clone()))
+            .collect();
+
+        let mut removed_inboxes = Vec::new();
+        for inbox_name in db.get_inboxes_for_profile(identity.clone())? {
+            let is_shared = other_profiles.iter().any(|other| {
+                Self::is_inbox_participant(&inbox_name, &other.full_identity_name)
+                    || db
+                        .has_permission(&inbox_name, other, InboxPermission::Read)
+                        .unwrap_or(false)
+            });
+            if is_shared {
+                db.remove_permission(&inbox_name, &identity)?;
+            } else {
+                removed_inboxes.push(inbox_name);
+            }
+        }
+        db.remove_inboxes(&removed_inboxes)?;
+
+        for device in db.get_profile_devices(profile)? {
+            db.revoke_device(&ShinkaiName::new(device.full_identity_name)?)?;
+        }
+
+        let mut removed_llm_providers = Vec::new();
+        for llm_provider in db.get_llm_providers_for_profile(profile.clone())? {
+            if llm_provider.full_identity_name.get_profile_name_string() == Some(profile_name.clone()) {
+                db.remove_llm_provider(&llm_provider.id, profile)?;
+                removed_llm_providers.push(llm_provider.id);
+            } else {
+                db.remove_profile_from_llm_provider_access(
+                    &llm_provider.id,
+                    &profile_name,
+                    &llm_provider.full_identity_name,
+                )?;
+            }
+        }
+
+        for task_id in db.get_all_cron_tasks_for_profile(profile.clone())?.into_keys() {
+            db.remove_cron_task(profile.clone(), task_id)?;
+        }
+        db.remove_email_account(&profile.full_name)?;
+        db.remove_calendar_account(&profile.full_name)?;
+        for folder in db.get_watched_folders_for_profile(&profile.full_name)? {
+            db.remove_watched_folder(&folder.id)?;
+        }
+        for connector in db.get_cloud_connectors_for_profile(&profile.full_name)? {
+            db.remove_cloud_connector(&connector.id)?;
+        }
+        db.remove_profile_limits(&profile_name)?;
+        db.remove_notifications_for_profile(profile)?;
+        db.remove_contact_book(profile)?;
+        db.remove_inbound_message_policy(profile)?;
+        db.remove_inbox_digest_schedule(profile)?;
+
+        vector_fs.remove_profile(node_name, profile).await?;
+        db.remove_profile(&profile_name)?;
+
+        let mut identity_manager = identity_manager.lock().await;
+        identity_manager.remove_profile_subidentity(profile);
+        for llm_provider_id in removed_llm_providers {
+            let _ = identity_manager.remove_agent_subidentity(&llm_provider_id).await;
+        }
+        Ok(())
+    }
+
+    /// Whether the profile (or one of its devices or agents) is one of the identities of a regular inbox
+    fn is_inbox_participant(inbox_name: &str, profile: &ShinkaiName) -> bool {
+        match InboxName::new(inbox_name.to_string()) {
+            Ok(InboxName::RegularInbox { identities, .. }) => identities.iter().any(|identity| {
+                identity.node_name == profile.node_name && identity.profile_name == profile.profile_name
+            }),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_provider::job::JobLike;
+    use shinkai_message_primitives::shinkai_utils::job_scope::JobScope;
+    use shinkai_vector_resources::utils::hash_string;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_remove_inboxes_keeps_other_inboxes() {
+        let db_path = format!("db_tests/{}", hash_string("profile_data"));
+        let _ = fs::remove_dir_all(Path::new(&db_path));
+        let db = ShinkaiDB::new(&db_path).unwrap();
+
+        let scope = JobScope::new_default();
+        db.create_new_job("removed_job".to_string(), "agent".to_string(), scope.clone(), false)
+            .unwrap();
+        db.create_new_job("kept_job".to_string(), "agent".to_string(), scope, false)
+            .unwrap();
+
+        let removed_inbox = InboxName::get_job_inbox_name_from_params("removed_job".to_string())
+            .unwrap()
+            .to_string();
+        db.remove_inboxes(&[removed_inbox]).unwrap();
+
+        assert!(db.get_job("removed_job").is_err());
+        assert!(db.get_job("kept_job").is_ok());
+        let job_ids: Vec<String> = db
+            .get_all_jobs()
+            .unwrap()
+            .iter()
+            .map(|job| job.job_id().to_string())
+            .collect();
+        assert_eq!(job_ids, vec!["kept_job".to_string()]);
+    }
+
+    #[test]
+    fn test_inbox_participants_match_whole_identities() {
+        let inbox_name = InboxName::get_regular_inbox_name_from_params(
+            "@@node.shinkai".to_string(),
+            "main_profile".to_string(),
+            "@@node.shinkai".to_string(),
+            "bob/device/phone".to_string(),
+            false,
+        )
+        .unwrap()
+        .to_string();
+
+        let bob = ShinkaiName::new("@@node.shinkai/bob".to_string()).unwrap();
+        let main = ShinkaiName::new("@@node.shinkai/main".to_string()).unwrap();
+        let main_profile = ShinkaiName::new("@@node.shinkai/main_profile".to_string()).unwrap();
+        assert!(ProfileDataManager::is_inbox_participant(&inbox_name, &bob));
+        assert!(ProfileDataManager::is_inbox_participant(&inbox_name, &main_profile));
+        // A profile whose name is only a substring of a participant is not part of the inbox
+        assert!(!ProfileDataManager::is_inbox_participant(&inbox_name, &main));
+
+        let job_inbox = InboxName::get_job_inbox_name_from_params("job_1".to_string())
+            .unwrap()
+            .to_string();
+        assert!(!ProfileDataManager::is_inbox_participant(&job_inbox, &bob));
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use futures::Future;
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use lazy_static::lazy_static;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+
+use crate::db::ShinkaiDB;
+use crate::llm_provider::error::LLMProviderError;
+use crate::schemas::profile_limits::ProfileLimits;
+
+lazy_static! {
+    /// Rate limiter of each profile, with the limit it was created for so it's rebuilt when the limit changes
+    static ref PROFILE_RATE_LIMITERS: Mutex<HashMap<String, (u32, Arc<DefaultDirectRateLimiter>)>> =
+        Mutex::new(HashMap::new());
+}
+
+tokio::task_local! {
+    /// LLM tokens used by the job being processed in the current task
+    static LLM_TOKENS_USED: Arc<AtomicU64>;
+}
+
+pub struct ProfileLimitsManager {}
+
+impl ProfileLimitsManager {
+    /// Month the LLM token budgets are counted in, e.g. `2024-09`
+    pub fn current_month() -> String {
+        Utc::now().format("%Y-%m").to_string()
+    }
+
+    /// Errors if the profile already sent `max_requests_per_minute` requests in the last minute
+    pub fn check_rate_limit(limits: &ProfileLimits) -> Result<(), LLMProviderError> {
+        let Some(max_requests) = limits.max_requests_per_minute.and_then(NonZeroU32::new) else {
+            return Ok(());
+        };
+
+        let rate_limiter = {
+            let mut rate_limiters = PROFILE_RATE_LIMITERS.lock().unwrap();
+            let entry = rate_limiters
+                .entry(limits.profile.to_lowercase())
+                .or_insert_with(|| (0, Arc::new(RateLimiter::direct(Quota::per_minute(max_requests)))));
+            if entry.0 != max_requests.get() {
+                *entry = (
+                    max_requests.get(),
+                    Arc::new(RateLimiter::direct(Quota::per_minute(max_requests))),
+                );
+            }
+            entry.1.clone()
+        };
+
+        rate_limiter.check().map_err(|_| {
+            LLMProviderError::ProfileLimitExceeded(format!(
+                "Profile {} can't send more than {} requests per minute",
+                limits.profile, max_requests
+            ))
+        })
+    }
+
+    /// Errors if the profile already used its LLM token budget this month
+    pub fn check_llm_budget(db: &ShinkaiDB, limits: &ProfileLimits) -> Result<(), LLMProviderError> {
+        let Some(budget) = limits.monthly_llm_token_budget else {
+            return Ok(());
+        };
+
+        let used = db.get_profile_llm_tokens(&limits.profile, &Self::current_month())?;
+        if used >= budget {
+            return Err(LLMProviderError::ProfileLimitExceeded(format!(
+                "Profile {} used its monthly budget of {} LLM tokens",
+                limits.profile, budget
+            )));
+        }
+        Ok(())
+    }
+
+    /// Adds tokens to the job being tracked by `track_llm_usage`, if any
+    pub fn record_llm_tokens(tokens: u64) {
+        let _ = LLM_TOKENS_USED.try_with(|used| used.fetch_add(tokens, Ordering::Relaxed));
+    }
+
+    /// Runs the processing of a job and adds the LLM tokens it used to the usage of the profile
+    pub async fn track_llm_usage<F: Future>(db: Option<Arc<ShinkaiDB>>, profile: String, fut: F) -> F::Output {
+        let used = Arc::new(AtomicU64::new(0));
+        let output = LLM_TOKENS_USED.scope(used.clone(), fut).await;
+
+        let tokens = used.load(Ordering::Relaxed);
+        if let (Some(db), true) = (db, tokens > 0) {
+            if let Err(e) = db.add_profile_llm_tokens(&profile, &Self::current_month(), tokens) {
+                shinkai_log(
+                    ShinkaiLogOption::JobExecution,
+                    ShinkaiLogLevel::Error,
+                    &format!("Failed to record the LLM tokens used by {}: {}", profile, e),
+                );
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_profile_rate_limit_and_token_tracking() {
+        let limits = ProfileLimits {
+            profile: "rate_limited".to_string(),
+            max_requests_per_minute: Some(2),
+            ..Default::default()
+        };
+        assert!(ProfileLimitsManager::check_rate_limit(&limits).is_ok());
+        assert!(ProfileLimitsManager::check_rate_limit(&limits).is_ok());
+        assert!(ProfileLimitsManager::check_rate_limit(&limits).is_err());
+
+        // Outside of a tracked job the tokens are ignored
+        ProfileLimitsManager::record_llm_tokens(10);
+        let used = ProfileLimitsManager::track_llm_usage(None, "main".to_string(), async {
+            ProfileLimitsManager::record_llm_tokens(10);
+            ProfileLimitsManager::record_llm_tokens(5);
+            LLM_TOKENS_USED.with(|used| used.load(Ordering::Relaxed))
+        })
+        .await;
+        assert_eq!(used, 15);
+    }
+}
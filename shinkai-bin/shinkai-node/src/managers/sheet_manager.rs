@@ -214,6 +214,7 @@ impl SheetManager {
             let job_creation_info = JobCreationInfo {
                 scope: JobScope::new_default(),
                 is_hidden: Some(true),
+                retrieval: None,
             };
 
             let mut job_manager = job_manager.lock().await;
@@ -232,6 +233,7 @@ impl SheetManager {
                 workflow_name: None, // it could be in the sheet_job_data
                 sheet_job_data: Some(serde_json::to_string(&job_data).unwrap()),
                 callback: None,
+                retrieval: None,
             };
 
             job_messages.push((job_message, job_data));
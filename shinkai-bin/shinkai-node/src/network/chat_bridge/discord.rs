@@ -0,0 +1,163 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use tokio_tungstenite::tungstenite::Message;
+
+use super::{ChatBridge, ChatConnector, ChatPlatform, IncomingChatMessage};
+
+const GATEWAY_URL: &str = "wss://gateway.discord.gg/?v=10&encoding=json";
+const API_URL: &str = "https://discord.com/api/v10";
+const RECONNECT_DELAY_SECS: u64 = 5;
+/// GUILD_MESSAGES | DIRECT_MESSAGES | MESSAGE_CONTENT
+const GATEWAY_INTENTS: u64 = (1 << 9) | (1 << 12) | (1 << 15);
+
+/// Talks to Discord through the gateway. The bot answers direct messages, and messages that mention it in servers.
+pub struct DiscordConnector {
+    token: String,
+    client: reqwest::Client,
+}
+
+impl DiscordConnector {
+    pub fn new(token: String) -> Self {
+        DiscordConnector {
+            token,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// The message if it's meant for the bot, without the mention
+    pub fn parse_message_create(data: &Value, bot_user_id: &str) -> Option<IncomingChatMessage> {
+        if data.pointer("/author/bot").and_then(|v| v.as_bool()) == Some(true) {
+            return None;
+        }
+        let content = data.get("content")?.as_str()?;
+        let is_direct_message = data.get("guild_id").map_or(true, |guild_id| guild_id.is_null());
+        let mentions = [format!("<@{}>", bot_user_id), format!("<@!{}>", bot_user_id)];
+        if !is_direct_message && !mentions.iter().any(|mention| content.contains(mention.as_str())) {
+            return None;
+        }
+
+        let text = mentions
+            .iter()
+            .fold(content.to_string(), |text, mention| text.replace(mention.as_str(), ""));
+        Some(IncomingChatMessage {
+            chat_id: data.get("channel_id")?.as_str()?.to_string(),
+            user_id: data.pointer("/author/id")?.as_str()?.to_string(),
+            text: text.trim().to_string(),
+        })
+    }
+
+    /// Keeps a gateway connection open until the node shuts down, reconnecting when Discord drops it
+    pub async fn run(self: Arc<Self>, bridge: ChatBridge) {
+        loop {
+            if let Err(e) = self.clone().run_session(&bridge).await {
+                shinkai_log(
+                    ShinkaiLogOption::Node,
+                    ShinkaiLogLevel::Error,
+                    &format!("Discord gateway disconnected: {}", e),
+                );
+            }
+            tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+        }
+    }
+
+    async fn run_session(self: Arc<Self>, bridge: &ChatBridge) -> Result<(), String> {
+        let (socket, _) = tokio_tungstenite::connect_async(GATEWAY_URL)
+            .await
+            .map_err(|e| e.to_string())?;
+        let (mut writer, mut reader) = socket.split();
+
+        let mut heartbeat = tokio::time::interval(Duration::from_secs(45));
+        let mut sequence = Value::Null;
+        let mut bot_user_id = String::new();
+
+        loop {
+            tokio::select! {
+                _ = heartbeat.tick() => {
+                    let payload = json!({ "op": 1, "d": sequence }).to_string();
+                    writer.send(Message::Text(payload)).await.map_err(|e| e.to_string())?;
+                }
+                frame = reader.next() => {
+                    let text = match frame {
+                        Some(Ok(Message::Text(text))) => text,
+                        Some(Ok(Message::Close(frame))) => return Err(format!("closed: {:?}", frame)),
+                        Some(Ok(_)) => continue,
+                        Some(Err(e)) => return Err(e.to_string()),
+                        None => return Err("connection ended".to_string()),
+                    };
+                    let payload: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+                    if let Some(s) = payload.get("s").filter(|s| !s.is_null()) {
+                        sequence = s.clone();
+                    }
+
+                    match payload.get("op").and_then(|v| v.as_u64()) {
+                        // Hello: start heartbeating and identify
+                        Some(10) => {
+                            let interval_ms = payload.pointer("/d/heartbeat_interval").and_then(|v| v.as_u64()).unwrap_or(45_000);
+                            heartbeat = tokio::time::interval(Duration::from_millis(interval_ms));
+                            let identify = json!({
+                                "op": 2,
+                                "d": {
+                                    "token": self.token,
+                                    "intents": GATEWAY_INTENTS,
+                                    "properties": { "os": std::env::consts::OS, "browser": "shinkai-node", "device": "shinkai-node" },
+                                },
+                            });
+                            writer.send(Message::Text(identify.to_string())).await.map_err(|e| e.to_string())?;
+                        }
+                        // Heartbeat requested by Discord
+                        Some(1) => {
+                            let payload = json!({ "op": 1, "d": sequence }).to_string();
+                            writer.send(Message::Text(payload)).await.map_err(|e| e.to_string())?;
+                        }
+                        // Reconnect or invalid session
+                        Some(7) | Some(9) => return Err("Discord asked to reconnect".to_string()),
+                        Some(0) => match payload.get("t").and_then(|v| v.as_str()) {
+                            Some("READY") => {
+                                bot_user_id = payload.pointer("/d/user/id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                            }
+                            Some("MESSAGE_CREATE") => {
+                                if let Some(message) = payload.get("d").and_then(|d| Self::parse_message_create(d, &bot_user_id)) {
+                                    let bridge = bridge.clone();
+                                    let connector: Arc<dyn ChatConnector> = self.clone();
+                                    tokio::spawn(async move { bridge.handle_incoming(connector, message).await });
+                                }
+                            }
+                            _ => {}
+                        },
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ChatConnector for DiscordConnector {
+    fn platform(&self) -> ChatPlatform {
+        ChatPlatform::Discord
+    }
+
+    async fn send_message(&self, chat_id: &str, text: &str) -> Result<(), String> {
+        let response = self
+            .client
+            .post(format!("{}/channels/{}/messages", API_URL, chat_id))
+            .header("Authorization", format!("Bot {}", self.token))
+            .json(&json!({ "content": text }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Discord create message failed with status {}",
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+}
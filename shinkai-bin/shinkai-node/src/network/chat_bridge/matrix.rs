@@ -0,0 +1,189 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use matrix_sdk::config::SyncSettings;
+use matrix_sdk::matrix_auth::MatrixSession;
+use matrix_sdk::ruma::events::room::member::StrippedRoomMemberEvent;
+use matrix_sdk::ruma::events::room::message::{MessageType, OriginalSyncRoomMessageEvent, RoomMessageEventContent};
+use matrix_sdk::ruma::RoomId;
+use matrix_sdk::{Client, Room, RoomState};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+
+use super::{ChatBridge, ChatConnector, ChatPlatform, IncomingChatMessage, MatrixConfig};
+
+const RECONNECT_DELAY_SECS: u64 = 10;
+const SESSION_FILE: &str = "session.json";
+
+/// Matrix account of the node. Each room it's in is a chat of the bridge, so it gets its own job.
+/// Encrypted rooms work as long as the store is kept between restarts.
+pub struct MatrixConnector {
+    client: Client,
+}
+
+impl MatrixConnector {
+    /// Logs in, restoring the previous session from the store if there is one
+    async fn login(config: &MatrixConfig) -> Result<Client, String> {
+        let store_path = Path::new(&config.store_path);
+        std::fs::create_dir_all(store_path).map_err(|e| e.to_string())?;
+
+        let client = Client::builder()
+            .homeserver_url(&config.homeserver_url)
+            .sqlite_store(store_path, None)
+            .build()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let session_path = store_path.join(SESSION_FILE);
+        let saved_session = std::fs::read_to_string(&session_path)
+            .ok()
+            .and_then(|session| serde_json::from_str::<MatrixSession>(&session).ok());
+        match saved_session {
+            Some(session) => {
+                if let Err(e) = client.restore_session(session).await {
+                    // Log in again on the next attempt
+                    let _ = std::fs::remove_file(&session_path);
+                    return Err(e.to_string());
+                }
+            }
+            None => {
+                client
+                    .matrix_auth()
+                    .login_username(&config.user_id, &config.password)
+                    .initial_device_display_name("Shinkai Node")
+                    .await
+                    .map_err(|e| e.to_string())?;
+                if let Some(session) = client.matrix_auth().session() {
+                    let session = serde_json::to_string(&session).map_err(|e| e.to_string())?;
+                    std::fs::write(&session_path, session).map_err(|e| e.to_string())?;
+                }
+            }
+        }
+        Ok(client)
+    }
+
+    /// Parses the text messages of a room, skipping the ones sent by the node itself
+    fn parse_event(
+        event: &OriginalSyncRoomMessageEvent,
+        room: &Room,
+        own_user_id: &str,
+    ) -> Option<IncomingChatMessage> {
+        if event.sender.as_str() == own_user_id {
+            return None;
+        }
+        let MessageType::Text(text) = &event.content.msgtype else {
+            return None;
+        };
+        Some(IncomingChatMessage {
+            chat_id: room.room_id().to_string(),
+            user_id: event.sender.to_string(),
+            text: text.body.clone(),
+        })
+    }
+
+    pub async fn run(config: MatrixConfig, bridge: ChatBridge) {
+        loop {
+            if let Err(e) = Self::run_session(&config, &bridge).await {
+                shinkai_log(
+                    ShinkaiLogOption::Node,
+                    ShinkaiLogLevel::Error,
+                    &format!("Matrix sync stopped: {}", e),
+                );
+            }
+            tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+        }
+    }
+
+    async fn run_session(config: &MatrixConfig, bridge: &ChatBridge) -> Result<(), String> {
+        let client = Self::login(config).await?;
+        let own_user_id = client.user_id().map(|id| id.to_string()).unwrap_or_default();
+        let connector = Arc::new(MatrixConnector { client: client.clone() });
+
+        // Skip the messages sent while the node was offline, they were never answered in the inbox
+        let sync_settings = SyncSettings::default();
+        let response = client
+            .sync_once(sync_settings.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // Join the rooms allowed users invite the node to
+        let allowed_user_ids = config.allowed_user_ids.clone();
+        client.add_event_handler(move |event: StrippedRoomMemberEvent, room: Room, client: Client| {
+            let allowed_user_ids = allowed_user_ids.clone();
+            async move {
+                let is_own_invite = client.user_id().map_or(false, |id| id == event.state_key);
+                if is_own_invite && allowed_user_ids.contains(&event.sender.to_string()) {
+                    let _ = room.join().await;
+                }
+            }
+        });
+
+        let handler_bridge = bridge.clone();
+        client.add_event_handler(move |event: OriginalSyncRoomMessageEvent, room: Room| {
+            let bridge = handler_bridge.clone();
+            let connector: Arc<dyn ChatConnector> = connector.clone();
+            let own_user_id = own_user_id.clone();
+            async move {
+                if room.state() != RoomState::Joined {
+                    return;
+                }
+                if let Some(message) = Self::parse_event(&event, &room, &own_user_id) {
+                    tokio::spawn(async move { bridge.handle_incoming(connector, message).await });
+                }
+            }
+        });
+
+        client
+            .sync(sync_settings.token(response.next_batch))
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl ChatConnector for MatrixConnector {
+    fn platform(&self) -> ChatPlatform {
+        ChatPlatform::Matrix
+    }
+
+    /// Messages to encrypted rooms are encrypted by the client
+    async fn send_message(&self, chat_id: &str, text: &str) -> Result<(), String> {
+        let room_id = RoomId::parse(chat_id).map_err(|e| e.to_string())?;
+        let room = self
+            .client
+            .get_room(&room_id)
+            .ok_or_else(|| format!("The node isn't in room {}", chat_id))?;
+        room.send(RoomMessageEventContent::text_plain(text))
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(homeserver_url: &str, store_path: &Path) -> MatrixConfig {
+        MatrixConfig {
+            homeserver_url: homeserver_url.to_string(),
+            user_id: "@shinkai:example.org".to_string(),
+            password: "password".to_string(),
+            store_path: store_path.to_string_lossy().to_string(),
+            allowed_user_ids: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_login_fails_with_invalid_homeserver() {
+        let store = tempfile::tempdir().unwrap();
+        let store_path = store.path().join("matrix_store");
+
+        let result = MatrixConnector::login(&config("not a url", &store_path)).await;
+        assert!(result.is_err());
+        // The store is still created so the next attempt can reuse it
+        assert!(store_path.is_dir());
+        assert!(!store_path.join(SESSION_FILE).exists());
+    }
+}
@@ -0,0 +1,496 @@
+#[cfg(feature = "discord-bridge")]
+pub mod discord;
+#[cfg(feature = "matrix-bridge")]
+pub mod matrix;
+pub mod telegram;
+
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ed25519_dalek::SigningKey;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{JobCreationInfo, JobMessage};
+use shinkai_message_primitives::shinkai_utils::job_scope::JobScope;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use tokio::sync::Mutex;
+use x25519_dalek::{PublicKey as EncryptionPublicKey, StaticSecret as EncryptionStaticKey};
+
+use crate::db::ShinkaiDB;
+use crate::llm_provider::job_manager::JobManager;
+use crate::managers::IdentityManager;
+use crate::network::node_api_router::SendResponseBodyData;
+use crate::network::Node;
+
+/// Messaging apps the bridge can connect to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatPlatform {
+    Telegram,
+    Discord,
+    Matrix,
+}
+
+impl ChatPlatform {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChatPlatform::Telegram => "telegram",
+            ChatPlatform::Discord => "discord",
+            ChatPlatform::Matrix => "matrix",
+        }
+    }
+
+    /// Longest message the platform accepts
+    pub fn max_message_length(&self) -> usize {
+        match self {
+            ChatPlatform::Telegram => 4096,
+            ChatPlatform::Discord => 2000,
+            // Matrix limits events to 64KB, this leaves room for the rest of the event
+            ChatPlatform::Matrix => 16000,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IncomingChatMessage {
+    pub chat_id: String,
+    pub user_id: String,
+    pub text: String,
+}
+
+/// Sends messages back to a messaging app
+#[async_trait]
+pub trait ChatConnector: Send + Sync {
+    fn platform(&self) -> ChatPlatform;
+
+    async fn send_message(&self, chat_id: &str, text: &str) -> Result<(), String>;
+}
+
+/// Configured with:
+/// - CHAT_BRIDGE_LLM_PROVIDER: the agent new chats are assigned to
+/// - TELEGRAM_BOT_TOKEN and TELEGRAM_ALLOWED_CHAT_IDS
+/// - DISCORD_BOT_TOKEN and DISCORD_ALLOWED_USER_IDS (needs the discord-bridge feature)
+/// - MATRIX_HOMESERVER_URL, MATRIX_USER_ID, MATRIX_PASSWORD, MATRIX_ALLOWED_USER_IDS and
+///   MATRIX_STORE_PATH (needs the matrix-bridge feature)
+/// - CHAT_BRIDGE_REPLY_TIMEOUT_SECS: how long to wait for the agent to answer (default 300)
+///
+/// The allowlists are comma separated. Messages from anyone else are ignored.
+/// API_V2_KEY must be set, since the bridge goes through the same checks as the v2 API.
+#[derive(Debug, Clone)]
+pub struct ChatBridgeConfig {
+    pub bearer: String,
+    pub llm_provider: String,
+    pub reply_timeout: Duration,
+    pub telegram_token: Option<String>,
+    pub telegram_allowed_chat_ids: Vec<String>,
+    pub discord_token: Option<String>,
+    pub discord_allowed_user_ids: Vec<String>,
+    pub matrix: Option<MatrixConfig>,
+}
+
+/// Account the node logs into Matrix with. The store keeps the session and the encryption keys,
+/// so the node shows up as the same device after restarts and can read encrypted rooms.
+#[derive(Debug, Clone)]
+pub struct MatrixConfig {
+    pub homeserver_url: String,
+    pub user_id: String,
+    pub password: String,
+    pub store_path: String,
+    pub allowed_user_ids: Vec<String>,
+}
+
+impl ChatBridgeConfig {
+    /// Returns None if no messaging app is configured
+    pub fn from_env() -> Option<Self> {
+        let list = |name: &str| -> Vec<String> {
+            env::var(name)
+                .unwrap_or_default()
+                .split(',')
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .collect()
+        };
+        let telegram_token = env::var("TELEGRAM_BOT_TOKEN").ok().filter(|token| !token.is_empty());
+        let discord_token = env::var("DISCORD_BOT_TOKEN").ok().filter(|token| !token.is_empty());
+        let matrix = match (
+            env::var("MATRIX_HOMESERVER_URL"),
+            env::var("MATRIX_USER_ID"),
+            env::var("MATRIX_PASSWORD"),
+        ) {
+            (Ok(homeserver_url), Ok(user_id), Ok(password)) => Some(MatrixConfig {
+                homeserver_url,
+                user_id,
+                password,
+                store_path: env::var("MATRIX_STORE_PATH").unwrap_or_else(|_| "matrix_store".to_string()),
+                allowed_user_ids: list("MATRIX_ALLOWED_USER_IDS"),
+            }),
+            _ => None,
+        };
+        if telegram_token.is_none() && discord_token.is_none() && matrix.is_none() {
+            return None;
+        }
+
+        let (Ok(bearer), Ok(llm_provider)) = (env::var("API_V2_KEY"), env::var("CHAT_BRIDGE_LLM_PROVIDER")) else {
+            shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Error,
+                "The chat bridge needs API_V2_KEY and CHAT_BRIDGE_LLM_PROVIDER to be set",
+            );
+            return None;
+        };
+        let reply_timeout_secs = env::var("CHAT_BRIDGE_REPLY_TIMEOUT_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(300);
+
+        Some(ChatBridgeConfig {
+            bearer,
+            llm_provider,
+            reply_timeout: Duration::from_secs(reply_timeout_secs),
+            telegram_token,
+            telegram_allowed_chat_ids: list("TELEGRAM_ALLOWED_CHAT_IDS"),
+            discord_token,
+            discord_allowed_user_ids: list("DISCORD_ALLOWED_USER_IDS"),
+            matrix,
+        })
+    }
+
+    pub fn is_allowed(&self, platform: ChatPlatform, message: &IncomingChatMessage) -> bool {
+        match platform {
+            ChatPlatform::Telegram => self.telegram_allowed_chat_ids.contains(&message.chat_id),
+            ChatPlatform::Discord => self.discord_allowed_user_ids.contains(&message.user_id),
+            ChatPlatform::Matrix => self
+                .matrix
+                .as_ref()
+                .map_or(false, |matrix| matrix.allowed_user_ids.contains(&message.user_id)),
+        }
+    }
+}
+
+/// Relays messages between chats (Telegram, Discord or Matrix rooms) and jobs. Every chat gets its own job,
+/// `/new` starts a new one and `/link <job_id>` connects the chat to an existing job. The job inbox stays
+/// the source of truth, chats only get the messages relayed.
+#[derive(Clone)]
+pub struct ChatBridge {
+    config: Arc<ChatBridgeConfig>,
+    db: Arc<ShinkaiDB>,
+    node_name: ShinkaiName,
+    identity_manager: Arc<Mutex<IdentityManager>>,
+    job_manager: Arc<Mutex<JobManager>>,
+    encryption_secret_key: EncryptionStaticKey,
+    encryption_public_key: EncryptionPublicKey,
+    identity_secret_key: SigningKey,
+}
+
+impl ChatBridge {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        config: ChatBridgeConfig,
+        db: Arc<ShinkaiDB>,
+        node_name: ShinkaiName,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        job_manager: Arc<Mutex<JobManager>>,
+        encryption_secret_key: EncryptionStaticKey,
+        encryption_public_key: EncryptionPublicKey,
+        identity_secret_key: SigningKey,
+    ) -> Self {
+        ChatBridge {
+            config: Arc::new(config),
+            db,
+            node_name,
+            identity_manager,
+            job_manager,
+            encryption_secret_key,
+            encryption_public_key,
+            identity_secret_key,
+        }
+    }
+
+    /// Starts listening on the configured messaging apps
+    pub fn start(self) -> Vec<tokio::task::JoinHandle<()>> {
+        let mut tasks = Vec::new();
+
+        if let Some(token) = self.config.telegram_token.clone() {
+            let connector = Arc::new(telegram::TelegramConnector::new(token));
+            tasks.push(tokio::spawn(connector.run(self.clone())));
+        }
+
+        #[cfg(feature = "discord-bridge")]
+        if let Some(token) = self.config.discord_token.clone() {
+            let connector = Arc::new(discord::DiscordConnector::new(token));
+            tasks.push(tokio::spawn(connector.run(self.clone())));
+        }
+        #[cfg(not(feature = "discord-bridge"))]
+        if self.config.discord_token.is_some() {
+            shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Error,
+                "DISCORD_BOT_TOKEN is set but the node was built without the discord-bridge feature",
+            );
+        }
+
+        #[cfg(feature = "matrix-bridge")]
+        if let Some(matrix_config) = self.config.matrix.clone() {
+            tasks.push(tokio::spawn(matrix::MatrixConnector::run(matrix_config, self.clone())));
+        }
+        #[cfg(not(feature = "matrix-bridge"))]
+        if self.config.matrix.is_some() {
+            shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Error,
+                "MATRIX_HOMESERVER_URL is set but the node was built without the matrix-bridge feature",
+            );
+        }
+
+        tasks
+    }
+
+    /// Forwards a message from a chat to its job, and relays the answer once the agent replies
+    pub async fn handle_incoming(&self, connector: Arc<dyn ChatConnector>, message: IncomingChatMessage) {
+        let platform = connector.platform();
+        if !self.config.is_allowed(platform, &message) {
+            shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Info,
+                &format!(
+                    "Ignoring {} message from chat {} (user {}), it isn't in the allowlist",
+                    platform.as_str(),
+                    message.chat_id,
+                    message.user_id
+                ),
+            );
+            return;
+        }
+
+        let text = message.text.trim();
+        if text == "/new" || text == "/start" {
+            let _ = self.db.remove_chat_bridge_job(platform.as_str(), &message.chat_id);
+            let _ = connector
+                .send_message(&message.chat_id, "Started a new conversation.")
+                .await;
+            return;
+        }
+        if let Some(job_id) = text.strip_prefix("/link ") {
+            let job_id = job_id.trim();
+            let reply = match self.db.get_job(job_id) {
+                Ok(_) => match self.db.set_chat_bridge_job(platform.as_str(), &message.chat_id, job_id) {
+                    Ok(_) => format!("This chat is now linked to job {}.", job_id),
+                    Err(e) => format!("Couldn't link the job: {}", e),
+                },
+                Err(_) => format!("Job {} doesn't exist.", job_id),
+            };
+            let _ = connector.send_message(&message.chat_id, &reply).await;
+            return;
+        }
+
+        let result = match self.job_for_chat(platform, &message.chat_id).await {
+            Ok(job_id) => self.send_job_message(&job_id, text).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(sent) => {
+                let bridge = self.clone();
+                let chat_id = message.chat_id.clone();
+                tokio::spawn(async move {
+                    bridge.relay_reply(connector, &chat_id, sent).await;
+                });
+            }
+            Err(e) => {
+                shinkai_log(
+                    ShinkaiLogOption::Node,
+                    ShinkaiLogLevel::Error,
+                    &format!("Failed to forward {} message: {}", platform.as_str(), e),
+                );
+                let _ = connector
+                    .send_message(
+                        &message.chat_id,
+                        &format!("Couldn't send the message to the node: {}", e),
+                    )
+                    .await;
+            }
+        }
+    }
+
+    async fn job_for_chat(&self, platform: ChatPlatform, chat_id: &str) -> Result<String, String> {
+        if let Some(job_id) = self
+            .db
+            .get_chat_bridge_job(platform.as_str(), chat_id)
+            .map_err(|e| e.to_string())?
+        {
+            return Ok(job_id);
+        }
+
+        let (res_sender, res_receiver) = async_channel::bounded(1);
+        let _ = Node::v2_create_new_job(
+            self.db.clone(),
+            self.node_name.clone(),
+            self.identity_manager.clone(),
+            self.job_manager.clone(),
+            self.config.bearer.clone(),
+            JobCreationInfo {
+                scope: JobScope::new_default(),
+                is_hidden: Some(false),
+                retrieval: None,
+            },
+            self.config.llm_provider.clone(),
+            self.encryption_secret_key.clone(),
+            self.encryption_public_key,
+            self.identity_secret_key.clone(),
+            res_sender,
+        )
+        .await;
+        let job_id = res_receiver
+            .recv()
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.message)?;
+
+        self.db
+            .set_chat_bridge_job(platform.as_str(), chat_id, &job_id)
+            .map_err(|e| e.to_string())?;
+        Ok(job_id)
+    }
+
+    async fn send_job_message(&self, job_id: &str, content: &str) -> Result<SendResponseBodyData, String> {
+        let job_message = JobMessage {
+            job_id: job_id.to_string(),
+            content: content.to_string(),
+            files_inbox: "".to_string(),
+            parent: None,
+            workflow_code: None,
+            workflow_name: None,
+            sheet_job_data: None,
+            callback: None,
+            retrieval: None,
+        };
+
+        let (res_sender, res_receiver) = async_channel::bounded(1);
+        let _ = Node::v2_job_message(
+            self.db.clone(),
+            self.node_name.clone(),
+            self.identity_manager.clone(),
+            self.job_manager.clone(),
+            self.config.bearer.clone(),
+            job_message,
+            self.encryption_secret_key.clone(),
+            self.encryption_public_key,
+            self.identity_secret_key.clone(),
+            res_sender,
+        )
+        .await;
+        res_receiver
+            .recv()
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.message)
+    }
+
+    /// Waits for the agent to answer the sent message and relays the answer to the chat
+    async fn relay_reply(&self, connector: Arc<dyn ChatConnector>, chat_id: &str, sent: SendResponseBodyData) {
+        let waiting = Node::v2_wait_for_job_reply(
+            self.db.clone(),
+            self.config.bearer.clone(),
+            sent.inbox.clone(),
+            sent.message_id.clone(),
+        );
+        let text = match tokio::time::timeout(self.config.reply_timeout, waiting).await {
+            Ok(reply) => reply,
+            Err(_) => "The agent didn't answer in time.".to_string(),
+        };
+        for chunk in split_message(&text, connector.platform().max_message_length()) {
+            if let Err(e) = connector.send_message(chat_id, &chunk).await {
+                shinkai_log(
+                    ShinkaiLogOption::Node,
+                    ShinkaiLogLevel::Error,
+                    &format!("Failed to relay reply to {}: {}", connector.platform().as_str(), e),
+                );
+                return;
+            }
+        }
+    }
+}
+
+/// Splits text into chunks of at most `max_length` characters, on line breaks when possible
+pub fn split_message(text: &str, max_length: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in text.split_inclusive('\n') {
+        if current.chars().count() + line.chars().count() > max_length && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if line.chars().count() > max_length {
+            let chars: Vec<char> = line.chars().collect();
+            for piece in chars.chunks(max_length) {
+                chunks.push(piece.iter().collect());
+            }
+        } else {
+            current.push_str(line);
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_message() {
+        assert_eq!(split_message("hello", 10), vec!["hello".to_string()]);
+        assert_eq!(
+            split_message("first line\nsecond line\nthird", 12),
+            vec![
+                "first line\n".to_string(),
+                "second line\n".to_string(),
+                "third".to_string()
+            ]
+        );
+        assert_eq!(
+            split_message("abcdefghij", 4),
+            vec!["abcd".to_string(), "efgh".to_string(), "ij".to_string()]
+        );
+        assert!(split_message("", 10).is_empty());
+    }
+
+    fn config_with_matrix(matrix: Option<MatrixConfig>) -> ChatBridgeConfig {
+        ChatBridgeConfig {
+            bearer: "bearer".to_string(),
+            llm_provider: "agent".to_string(),
+            reply_timeout: Duration::from_secs(300),
+            telegram_token: None,
+            telegram_allowed_chat_ids: vec![],
+            discord_token: None,
+            discord_allowed_user_ids: vec![],
+            matrix,
+        }
+    }
+
+    fn matrix_message(user_id: &str) -> IncomingChatMessage {
+        IncomingChatMessage {
+            chat_id: "!room:example.org".to_string(),
+            user_id: user_id.to_string(),
+            text: "hello".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_matrix_allowlist() {
+        let config = config_with_matrix(Some(MatrixConfig {
+            homeserver_url: "https://matrix.example.org".to_string(),
+            user_id: "@shinkai:example.org".to_string(),
+            password: "password".to_string(),
+            store_path: "matrix_store".to_string(),
+            allowed_user_ids: vec!["@alice:example.org".to_string()],
+        }));
+        assert!(config.is_allowed(ChatPlatform::Matrix, &matrix_message("@alice:example.org")));
+        assert!(!config.is_allowed(ChatPlatform::Matrix, &matrix_message("@mallory:example.org")));
+
+        // Room ids don't grant access, and without a Matrix account nobody is allowed
+        assert!(!config.is_allowed(ChatPlatform::Matrix, &matrix_message("!room:example.org")));
+        let config = config_with_matrix(None);
+        assert!(!config.is_allowed(ChatPlatform::Matrix, &matrix_message("@alice:example.org")));
+    }
+}
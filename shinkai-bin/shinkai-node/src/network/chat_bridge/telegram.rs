@@ -0,0 +1,114 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+
+use super::{ChatBridge, ChatConnector, ChatPlatform, IncomingChatMessage};
+
+const LONG_POLL_TIMEOUT_SECS: u64 = 30;
+const RETRY_DELAY_SECS: u64 = 5;
+
+/// Talks to the Telegram Bot API with long polling, so the node doesn't need a public webhook
+pub struct TelegramConnector {
+    token: String,
+    client: reqwest::Client,
+}
+
+impl TelegramConnector {
+    pub fn new(token: String) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(LONG_POLL_TIMEOUT_SECS + 10))
+            .build()
+            .unwrap_or_default();
+        TelegramConnector { token, client }
+    }
+
+    fn url(&self, method: &str) -> String {
+        format!("https://api.telegram.org/bot{}/{}", self.token, method)
+    }
+
+    async fn get_updates(&self, offset: i64) -> Result<Vec<Value>, String> {
+        let response: Value = self
+            .client
+            .get(self.url("getUpdates"))
+            .query(&[
+                ("offset", offset.to_string()),
+                ("timeout", LONG_POLL_TIMEOUT_SECS.to_string()),
+                ("allowed_updates", "[\"message\"]".to_string()),
+            ])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.get("ok").and_then(|v| v.as_bool()) != Some(true) {
+            return Err(format!("Telegram getUpdates failed: {}", response));
+        }
+        Ok(response
+            .get("result")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// Text messages of the update, ignoring anything else (stickers, joins, edits...)
+    pub fn parse_update(update: &Value) -> Option<IncomingChatMessage> {
+        let message = update.get("message")?;
+        Some(IncomingChatMessage {
+            chat_id: message.pointer("/chat/id")?.as_i64()?.to_string(),
+            user_id: message.pointer("/from/id")?.as_i64()?.to_string(),
+            text: message.get("text")?.as_str()?.to_string(),
+        })
+    }
+
+    /// Polls for new messages until the node shuts down
+    pub async fn run(self: Arc<Self>, bridge: ChatBridge) {
+        let mut offset = 0;
+        loop {
+            let updates = match self.get_updates(offset).await {
+                Ok(updates) => updates,
+                Err(e) => {
+                    shinkai_log(ShinkaiLogOption::Node, ShinkaiLogLevel::Error, &e);
+                    tokio::time::sleep(Duration::from_secs(RETRY_DELAY_SECS)).await;
+                    continue;
+                }
+            };
+
+            for update in updates {
+                if let Some(update_id) = update.get("update_id").and_then(|v| v.as_i64()) {
+                    offset = offset.max(update_id + 1);
+                }
+                if let Some(message) = Self::parse_update(&update) {
+                    let bridge = bridge.clone();
+                    let connector: Arc<dyn ChatConnector> = self.clone();
+                    tokio::spawn(async move { bridge.handle_incoming(connector, message).await });
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ChatConnector for TelegramConnector {
+    fn platform(&self) -> ChatPlatform {
+        ChatPlatform::Telegram
+    }
+
+    async fn send_message(&self, chat_id: &str, text: &str) -> Result<(), String> {
+        let response = self
+            .client
+            .post(self.url("sendMessage"))
+            .json(&json!({ "chat_id": chat_id, "text": text }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Telegram sendMessage failed with status {}", response.status()));
+        }
+        Ok(())
+    }
+}
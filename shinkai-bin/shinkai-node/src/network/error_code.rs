@@ -0,0 +1,111 @@
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Stable code of an API error, so clients can branch on errors without parsing their message.
+/// Codes are only added, never renamed.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    BadRequest,
+    InvalidInput,
+    Unauthorized,
+    PermissionDenied,
+    NotFound,
+    Conflict,
+    Timeout,
+    InternalError,
+    DatabaseError,
+    IdentityNotFound,
+    ProfileNotFound,
+    InboxNotFound,
+    InvalidRegistrationCode,
+    JobNotFound,
+    JobAgentMissing,
+    LlmProviderNotFound,
+    LlmProviderMissingCapabilities,
+    LlmProviderLimitReached,
+    LlmProviderError,
+    VecfsPathNotFound,
+    VecfsPathAlreadyExists,
+    VecfsError,
+    ToolNotFound,
+    ToolkitNotFound,
+    WorkflowNotFound,
+    ToolError,
+    IdempotencyKeyInUse,
+    IdempotencyKeyMismatch,
+    ProfileLimitExceeded,
+    OperationCancelled,
+}
+
+impl ErrorCode {
+    /// Generic code of an HTTP status, for errors that don't have a more specific one
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            400 => ErrorCode::BadRequest,
+            401 => ErrorCode::Unauthorized,
+            403 => ErrorCode::PermissionDenied,
+            404 => ErrorCode::NotFound,
+            409 => ErrorCode::Conflict,
+            422 => ErrorCode::InvalidInput,
+            504 => ErrorCode::Timeout,
+            _ => ErrorCode::InternalError,
+        }
+    }
+
+    /// HTTP status the API replies with for the code
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ErrorCode::BadRequest | ErrorCode::InvalidInput | ErrorCode::InvalidRegistrationCode => {
+                StatusCode::BAD_REQUEST
+            }
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::PermissionDenied => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound
+            | ErrorCode::IdentityNotFound
+            | ErrorCode::ProfileNotFound
+            | ErrorCode::InboxNotFound
+            | ErrorCode::JobNotFound
+            | ErrorCode::JobAgentMissing
+            | ErrorCode::LlmProviderNotFound
+            | ErrorCode::VecfsPathNotFound
+            | ErrorCode::ToolNotFound
+            | ErrorCode::ToolkitNotFound
+            | ErrorCode::WorkflowNotFound => StatusCode::NOT_FOUND,
+            ErrorCode::Conflict
+            | ErrorCode::VecfsPathAlreadyExists
+            | ErrorCode::IdempotencyKeyInUse
+            | ErrorCode::OperationCancelled => StatusCode::CONFLICT,
+            ErrorCode::IdempotencyKeyMismatch | ErrorCode::LlmProviderMissingCapabilities => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ErrorCode::LlmProviderLimitReached | ErrorCode::ProfileLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            ErrorCode::InternalError
+            | ErrorCode::DatabaseError
+            | ErrorCode::LlmProviderError
+            | ErrorCode::VecfsError
+            | ErrorCode::ToolError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code_serialization() {
+        assert_eq!(
+            serde_json::to_string(&ErrorCode::VecfsPathNotFound).unwrap(),
+            "\"VECFS_PATH_NOT_FOUND\""
+        );
+        assert_eq!(
+            serde_json::to_string(&ErrorCode::JobAgentMissing).unwrap(),
+            "\"JOB_AGENT_MISSING\""
+        );
+        assert_eq!(ErrorCode::from_status(403), ErrorCode::PermissionDenied);
+        assert_eq!(ErrorCode::from_status(418), ErrorCode::InternalError);
+    }
+}
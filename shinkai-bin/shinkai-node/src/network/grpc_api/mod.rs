@@ -0,0 +1,33 @@
+pub mod node_service;
+
+use std::net::SocketAddr;
+
+use async_channel::Sender;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+
+use super::node_commands::NodeCommand;
+
+/// Code generated from proto/shinkai_node/v1/node.proto
+pub mod proto {
+    tonic::include_proto!("shinkai_node.v1");
+}
+
+/// Serves the gRPC API. Like the v2 HTTP API, it's only usable when API_V2_KEY is set.
+pub async fn run_grpc_api(
+    node_commands_sender: Sender<NodeCommand>,
+    address: SocketAddr,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    shinkai_log(
+        ShinkaiLogOption::Api,
+        ShinkaiLogLevel::Info,
+        &format!("Starting Node gRPC API server at: {}", &address),
+    );
+
+    let service = node_service::NodeGrpcService::new(node_commands_sender);
+    tonic::transport::Server::builder()
+        .add_service(proto::shinkai_node_server::ShinkaiNodeServer::new(service))
+        .serve(address)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,387 @@
+use async_channel::Sender;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
+    APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsDeleteFolder, APIVecFsDeleteItem,
+    APIVecFsMoveFolder, APIVecFsMoveItem, APIVecFsRetrievePathSimplifiedJson,
+    APIVecFsRetrieveVectorSearchSimplifiedJson, JobCreationInfo, JobMessage, V2ChatMessage,
+};
+use shinkai_message_primitives::shinkai_utils::job_scope::JobScope;
+use tonic::{Request, Response, Status};
+
+use crate::network::{error_code::ErrorCode, node_api_router::APIError, node_commands::NodeCommand};
+
+use super::proto::{self, shinkai_node_server::ShinkaiNode};
+
+/// Implements the gRPC service on top of the same node commands as the v2 HTTP API
+pub struct NodeGrpcService {
+    node_commands_sender: Sender<NodeCommand>,
+}
+
+impl NodeGrpcService {
+    pub fn new(node_commands_sender: Sender<NodeCommand>) -> Self {
+        NodeGrpcService { node_commands_sender }
+    }
+
+    /// Sends a node command with the caller's bearer and waits for its result
+    async fn run_command<T, R>(
+        &self,
+        request: &Request<R>,
+        command: impl FnOnce(String, Sender<Result<T, APIError>>) -> NodeCommand,
+    ) -> Result<T, Status> {
+        let (res_sender, res_receiver) = async_channel::bounded(1);
+        self.node_commands_sender
+            .send(command(bearer(request), res_sender))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        res_receiver
+            .recv()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .map_err(api_error_to_status)
+    }
+}
+
+/// API key sent as `authorization: Bearer <key>` metadata
+fn bearer<T>(request: &Request<T>) -> String {
+    request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Proto3 strings can't be null, empty ones stand for unset values
+fn non_empty(value: String) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn non_zero(value: u32) -> Option<usize> {
+    if value == 0 {
+        None
+    } else {
+        Some(value as usize)
+    }
+}
+
+pub fn api_error_to_status(error: APIError) -> Status {
+    let message = format!("{}: {}", error.error, error.message);
+    match error.code {
+        400 => Status::invalid_argument(message),
+        401 => Status::unauthenticated(message),
+        403 => Status::permission_denied(message),
+        404 => Status::not_found(message),
+        409 => Status::already_exists(message),
+        504 => Status::deadline_exceeded(message),
+        _ => Status::internal(message),
+    }
+}
+
+fn to_proto_message(message: V2ChatMessage) -> proto::ChatMessage {
+    proto::ChatMessage {
+        message_hash: message.node_api_data.node_message_hash,
+        parent_hash: message.node_api_data.parent_hash,
+        timestamp: message.node_api_data.node_timestamp,
+        inbox: message.inbox,
+        job_id: message.job_message.job_id,
+        content: message.job_message.content,
+        sender: message.sender,
+        sender_subidentity: message.sender_subidentity,
+    }
+}
+
+#[tonic::async_trait]
+impl ShinkaiNode for NodeGrpcService {
+    async fn create_job(
+        &self,
+        request: Request<proto::CreateJobRequest>,
+    ) -> Result<Response<proto::CreateJobResponse>, Status> {
+        let job_creation_info = JobCreationInfo {
+            scope: JobScope::new_default(),
+            is_hidden: Some(request.get_ref().is_hidden),
+            retrieval: None,
+        };
+        let llm_provider = request.get_ref().llm_provider.clone();
+        let job_id = self
+            .run_command(&request, |bearer, res| NodeCommand::V2ApiCreateJob {
+                bearer,
+                job_creation_info,
+                llm_provider,
+                res,
+            })
+            .await?;
+        Ok(Response::new(proto::CreateJobResponse { job_id }))
+    }
+
+    async fn send_job_message(
+        &self,
+        request: Request<proto::SendJobMessageRequest>,
+    ) -> Result<Response<proto::SendJobMessageResponse>, Status> {
+        let payload = request.get_ref().clone();
+        let job_message = JobMessage {
+            job_id: payload.job_id,
+            content: payload.content,
+            files_inbox: payload.files_inbox,
+            parent: non_empty(payload.parent),
+            workflow_code: None,
+            workflow_name: None,
+            sheet_job_data: None,
+            callback: None,
+            retrieval: None,
+        };
+        let sent = self
+            .run_command(&request, |bearer, res| NodeCommand::V2ApiJobMessage {
+                bearer,
+                job_message,
+                res,
+            })
+            .await?;
+        Ok(Response::new(proto::SendJobMessageResponse {
+            message_id: sent.message_id,
+            parent_message_id: sent.parent_message_id.unwrap_or_default(),
+            inbox: sent.inbox,
+            scheduled_time: sent.scheduled_time,
+        }))
+    }
+
+    async fn get_last_messages(
+        &self,
+        request: Request<proto::GetLastMessagesRequest>,
+    ) -> Result<Response<proto::GetLastMessagesResponse>, Status> {
+        let payload = request.get_ref().clone();
+        let messages = self
+            .run_command(&request, |bearer, res| NodeCommand::V2ApiGetLastMessagesFromInbox {
+                bearer,
+                inbox_name: payload.inbox_name,
+                limit: non_zero(payload.limit).unwrap_or(10),
+                offset_key: non_empty(payload.offset_key),
+                res,
+            })
+            .await?;
+        Ok(Response::new(proto::GetLastMessagesResponse {
+            messages: messages.into_iter().map(to_proto_message).collect(),
+        }))
+    }
+
+    async fn vector_search(
+        &self,
+        request: Request<proto::VectorSearchRequest>,
+    ) -> Result<Response<proto::VectorSearchResponse>, Status> {
+        let payload = request.get_ref().clone();
+        let payload = APIVecFsRetrieveVectorSearchSimplifiedJson {
+            search: payload.search,
+            path: non_empty(payload.path),
+            max_results: non_zero(payload.max_results),
+            max_files_to_scan: non_zero(payload.max_files_to_scan),
+        };
+        let results = self
+            .run_command(&request, |bearer, res| NodeCommand::V2ApiVecFSVectorSearch {
+                bearer,
+                payload,
+                res,
+            })
+            .await?;
+        Ok(Response::new(proto::VectorSearchResponse {
+            results: results
+                .into_iter()
+                .map(|(content, path_ids, score)| proto::VectorSearchResult {
+                    content,
+                    path_ids,
+                    score,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn retrieve_path(
+        &self,
+        request: Request<proto::RetrievePathRequest>,
+    ) -> Result<Response<proto::JsonResponse>, Status> {
+        let payload = APIVecFsRetrievePathSimplifiedJson {
+            path: request.get_ref().path.clone(),
+        };
+        let json = self
+            .run_command(&request, |bearer, res| {
+                NodeCommand::V2ApiVecFSRetrievePathSimplifiedJson { bearer, payload, res }
+            })
+            .await?;
+        Ok(Response::new(proto::JsonResponse { json: json.to_string() }))
+    }
+
+    async fn create_folder(
+        &self,
+        request: Request<proto::CreateFolderRequest>,
+    ) -> Result<Response<proto::StatusResponse>, Status> {
+        let payload = APIVecFsCreateFolder {
+            path: request.get_ref().path.clone(),
+            folder_name: request.get_ref().folder_name.clone(),
+        };
+        let message = self
+            .run_command(&request, |bearer, res| NodeCommand::V2ApiVecFSCreateFolder {
+                bearer,
+                payload,
+                res,
+            })
+            .await?;
+        Ok(Response::new(proto::StatusResponse { message }))
+    }
+
+    async fn upload_file(
+        &self,
+        request: Request<proto::UploadFileRequest>,
+    ) -> Result<Response<proto::JsonResponse>, Status> {
+        let payload = request.get_ref().clone();
+        let json = self
+            .run_command(&request, |bearer, res| NodeCommand::V2ApiUploadFileToFolder {
+                bearer,
+                filename: payload.filename,
+                file: payload.file,
+                path: payload.path,
+                file_datetime: None,
+                res,
+            })
+            .await?;
+        Ok(Response::new(proto::JsonResponse { json: json.to_string() }))
+    }
+
+    async fn move_item(&self, request: Request<proto::MoveRequest>) -> Result<Response<proto::StatusResponse>, Status> {
+        let payload = APIVecFsMoveItem {
+            origin_path: request.get_ref().origin_path.clone(),
+            destination_path: request.get_ref().destination_path.clone(),
+        };
+        let message = self
+            .run_command(&request, |bearer, res| NodeCommand::V2ApiMoveItem {
+                bearer,
+                payload,
+                res,
+            })
+            .await?;
+        Ok(Response::new(proto::StatusResponse { message }))
+    }
+
+    async fn copy_item(&self, request: Request<proto::MoveRequest>) -> Result<Response<proto::StatusResponse>, Status> {
+        let payload = APIVecFsCopyItem {
+            origin_path: request.get_ref().origin_path.clone(),
+            destination_path: request.get_ref().destination_path.clone(),
+        };
+        let message = self
+            .run_command(&request, |bearer, res| NodeCommand::V2ApiCopyItem {
+                bearer,
+                payload,
+                res,
+            })
+            .await?;
+        Ok(Response::new(proto::StatusResponse { message }))
+    }
+
+    async fn move_folder(
+        &self,
+        request: Request<proto::MoveRequest>,
+    ) -> Result<Response<proto::StatusResponse>, Status> {
+        let payload = APIVecFsMoveFolder {
+            origin_path: request.get_ref().origin_path.clone(),
+            destination_path: request.get_ref().destination_path.clone(),
+        };
+        let message = self
+            .run_command(&request, |bearer, res| NodeCommand::V2ApiMoveFolder {
+                bearer,
+                payload,
+                res,
+            })
+            .await?;
+        Ok(Response::new(proto::StatusResponse { message }))
+    }
+
+    async fn copy_folder(
+        &self,
+        request: Request<proto::MoveRequest>,
+    ) -> Result<Response<proto::StatusResponse>, Status> {
+        let payload = APIVecFsCopyFolder {
+            origin_path: request.get_ref().origin_path.clone(),
+            destination_path: request.get_ref().destination_path.clone(),
+        };
+        let message = self
+            .run_command(&request, |bearer, res| NodeCommand::V2ApiCopyFolder {
+                bearer,
+                payload,
+                res,
+            })
+            .await?;
+        Ok(Response::new(proto::StatusResponse { message }))
+    }
+
+    async fn delete_item(
+        &self,
+        request: Request<proto::PathRequest>,
+    ) -> Result<Response<proto::StatusResponse>, Status> {
+        let payload = APIVecFsDeleteItem {
+            path: request.get_ref().path.clone(),
+        };
+        let message = self
+            .run_command(&request, |bearer, res| NodeCommand::V2ApiDeleteItem {
+                bearer,
+                payload,
+                res,
+            })
+            .await?;
+        Ok(Response::new(proto::StatusResponse { message }))
+    }
+
+    async fn delete_folder(
+        &self,
+        request: Request<proto::PathRequest>,
+    ) -> Result<Response<proto::StatusResponse>, Status> {
+        let payload = APIVecFsDeleteFolder {
+            path: request.get_ref().path.clone(),
+        };
+        let message = self
+            .run_command(&request, |bearer, res| NodeCommand::V2ApiDeleteFolder {
+                bearer,
+                payload,
+                res,
+            })
+            .await?;
+        Ok(Response::new(proto::StatusResponse { message }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::Code;
+
+    #[test]
+    fn test_api_error_to_status() {
+        let error = APIError {
+            code: 401,
+            error_code: ErrorCode::Unauthorized,
+            error: "Unauthorized".to_string(),
+            message: "Invalid bearer token".to_string(),
+        };
+        let status = api_error_to_status(error);
+        assert_eq!(status.code(), Code::Unauthenticated);
+        assert_eq!(status.message(), "Unauthorized: Invalid bearer token");
+
+        let error = APIError {
+            code: 500,
+            error_code: ErrorCode::InternalError,
+            error: "Internal Server Error".to_string(),
+            message: "".to_string(),
+        };
+        assert_eq!(api_error_to_status(error).code(), Code::Internal);
+    }
+
+    #[test]
+    fn test_bearer_from_metadata() {
+        let mut request = Request::new(());
+        assert_eq!(bearer(&request), "");
+        request
+            .metadata_mut()
+            .insert("authorization", "Bearer my_api_key".parse().unwrap());
+        assert_eq!(bearer(&request), "my_api_key");
+    }
+}
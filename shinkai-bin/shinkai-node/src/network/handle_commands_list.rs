@@ -1,11 +1,14 @@
 use std::sync::Arc;
 
-use crate::network::{node_commands::NodeCommand, Node};
+use crate::managers::node_diagnostics::DiagnosticsTargets;
+use crate::managers::node_health::HealthTargets;
+use crate::network::{node_commands::NodeCommand, panic_isolation::spawn_command_handler, Node};
 
 impl Node {
     pub async fn handle_command(&self, command: NodeCommand) {
         match command {
-            // Spawn a new task for each command to handle it concurrently
+            // Spawn a new task for each command to handle it concurrently. A panic in one is logged and doesn't stop
+            // the node.
 
             // NodeCommand::Shutdown => {
             //     shinkai_log(ShinkaiLogOption::Node, ShinkaiLogLevel::Info, "Shutdown command received. Stopping the node.");
@@ -21,7 +24,7 @@ impl Node {
                 let listen_address_clone = self.listen_address;
                 let proxy_connection_info = self.proxy_connection_info.clone();
                 let ws_manager_trait = self.ws_manager_trait.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Self::ping_all(
                         node_name_clone,
                         encryption_secret_key_clone,
@@ -36,16 +39,23 @@ impl Node {
                     .await;
                 });
             }
+            NodeCommand::GetPeers(sender) => {
+                let peers_clone = self.peers.clone();
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Self::send_peers(peers_clone, db_clone, sender).await;
+                });
+            }
             NodeCommand::GetPublicKeys(sender) => {
                 let identity_public_key = self.identity_public_key;
                 let encryption_public_key = self.encryption_public_key;
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::send_public_keys(identity_public_key, encryption_public_key, sender).await;
                 });
             }
             NodeCommand::IdentityNameToExternalProfileData { name, res } => {
                 let identity_manager_clone = Arc::clone(&self.identity_manager);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Self::handle_external_profile_data(identity_manager_clone, name, res).await;
                 });
             }
@@ -57,7 +67,7 @@ impl Node {
                 let identity_secret_key_clone = self.identity_secret_key.clone();
                 let proxy_connection_info = self.proxy_connection_info.clone();
                 let ws_manager_trait = self.ws_manager_trait.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_handle_send_onionized_message(
                         db_clone,
                         node_name_clone,
@@ -74,13 +84,13 @@ impl Node {
             }
             NodeCommand::FetchLastMessages { limit, res } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::fetch_and_send_last_messages(db_clone, limit, res).await;
                 });
             }
             NodeCommand::GetAllSubidentitiesDevicesAndLLMProviders(res) => {
                 let identity_manager_clone = Arc::clone(&self.identity_manager);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ =
                         Node::local_get_all_subidentities_devices_and_llm_providers(identity_manager_clone, res).await;
                 });
@@ -88,11 +98,21 @@ impl Node {
             NodeCommand::LocalCreateRegistrationCode {
                 permissions,
                 code_type,
+                ttl_secs,
+                max_uses,
                 res,
             } => {
                 let db = self.db.clone();
-                tokio::spawn(async move {
-                    let _ = Node::local_create_and_send_registration_code(db, permissions, code_type, res).await;
+                spawn_command_handler(async move {
+                    let _ = Node::local_create_and_send_registration_code(
+                        db,
+                        permissions,
+                        code_type,
+                        ttl_secs,
+                        max_uses,
+                        res,
+                    )
+                    .await;
                 });
             }
             NodeCommand::GetLastMessagesFromInbox {
@@ -102,7 +122,7 @@ impl Node {
                 res,
             } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ =
                         Node::local_get_last_messages_from_inbox(db_clone, inbox_name, limit, offset_key, res).await;
                 });
@@ -113,7 +133,7 @@ impl Node {
                 res,
             } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::local_mark_as_read_up_to(db_clone, inbox_name, up_to_time, res).await;
                 });
             }
@@ -124,7 +144,7 @@ impl Node {
                 res,
             } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ =
                         Node::local_get_last_unread_messages_from_inbox(db_clone, inbox_name, limit, offset, res).await;
                 });
@@ -137,7 +157,7 @@ impl Node {
             } => {
                 let identity_manager_clone = Arc::clone(&self.identity_manager);
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::local_add_inbox_permission(
                         identity_manager_clone,
                         db_clone,
@@ -157,7 +177,7 @@ impl Node {
             } => {
                 let identity_manager_clone = Arc::clone(&self.identity_manager);
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::local_remove_inbox_permission(
                         db_clone,
                         identity_manager_clone,
@@ -177,7 +197,7 @@ impl Node {
             } => {
                 let identity_manager_clone = self.identity_manager.clone();
                 let db_clone = self.db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::has_inbox_permission(
                         identity_manager_clone,
                         db_clone,
@@ -193,7 +213,7 @@ impl Node {
                 let job_manager_clone = self.job_manager.clone().unwrap();
                 let db_clone = self.db.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::local_create_new_job(
                         db_clone,
                         identity_manager_clone,
@@ -206,7 +226,7 @@ impl Node {
             }
             NodeCommand::JobMessage { shinkai_message, res } => {
                 let job_manager_clone = self.job_manager.clone().unwrap();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::local_job_message(job_manager_clone, shinkai_message, res).await;
                 });
             }
@@ -216,7 +236,7 @@ impl Node {
                 let db_clone = self.db.clone();
                 let identity_secret_key_clone = self.identity_secret_key.clone();
                 let ws_manager_trait = self.ws_manager_trait.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::local_add_llm_provider(
                         db_clone,
                         identity_manager_clone,
@@ -233,13 +253,13 @@ impl Node {
             NodeCommand::AvailableLLMProviders { full_profile_name, res } => {
                 let db_clone = self.db.clone();
                 let node_name_clone = self.node_name.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ =
                         Node::local_available_llm_providers(db_clone, &node_name_clone, full_profile_name, res).await;
                 });
             }
             NodeCommand::LocalScanOllamaModels { res } => {
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::local_scan_ollama_models(res).await;
                 });
             }
@@ -253,7 +273,7 @@ impl Node {
                 let job_manager_clone = self.job_manager.clone().unwrap();
                 let identity_secret_key_clone = self.identity_secret_key.clone();
                 let ws_manager_trait = self.ws_manager_trait.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::local_add_ollama_models(
                         db_clone,
                         identity_manager_clone,
@@ -272,7 +292,7 @@ impl Node {
                 let db_clone = Arc::clone(&self.db);
                 let identity_manager_clone = self.identity_manager.clone();
                 let node_name_clone = self.node_name.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_create_and_send_registration_code(
                         encryption_secret_key_clone,
                         db_clone,
@@ -299,7 +319,7 @@ impl Node {
                 let job_manager = self.job_manager.clone().unwrap();
                 let ws_manager_trait = self.ws_manager_trait.clone();
                 let support_embedding_models = self.supported_embedding_models.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_handle_registration_code_usage(
                         db_clone,
                         vec_fs_clone,
@@ -323,7 +343,7 @@ impl Node {
             }
             NodeCommand::APIGetAllSubidentities { res } => {
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_all_profiles(identity_manager_clone, res).await;
                 });
             }
@@ -332,7 +352,7 @@ impl Node {
                 let db_clone = Arc::clone(&self.db);
                 let identity_manager_clone = self.identity_manager.clone();
                 let node_name_clone = self.node_name.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_last_messages_from_inbox(
                         encryption_secret_key_clone,
                         db_clone,
@@ -349,7 +369,7 @@ impl Node {
                 let db_clone = Arc::clone(&self.db);
                 let identity_manager_clone = self.identity_manager.clone();
                 let node_name_clone = self.node_name.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_last_unread_messages_from_inbox(
                         encryption_secret_key_clone,
                         db_clone,
@@ -366,7 +386,7 @@ impl Node {
                 let db_clone = Arc::clone(&self.db);
                 let identity_manager_clone = self.identity_manager.clone();
                 let node_name_clone = self.node_name.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_mark_as_read_up_to(
                         encryption_secret_key_clone,
                         db_clone,
@@ -384,7 +404,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let job_manager_clone = self.job_manager.clone().unwrap();
                 let node_name_clone = self.node_name.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_create_new_job(
                         encryption_secret_key_clone,
                         db_clone,
@@ -403,7 +423,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let node_name_clone = self.node_name.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_all_inboxes_for_profile(
                         db_clone,
                         identity_manager_clone,
@@ -424,7 +444,7 @@ impl Node {
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let identity_secret_key_clone = self.identity_secret_key.clone();
                 let ws_manager_trait = self.ws_manager_trait.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_add_agent(
                         db_clone,
                         node_name_clone,
@@ -445,7 +465,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let node_name_clone = self.node_name.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_remove_agent(
                         db_clone,
                         node_name_clone,
@@ -463,7 +483,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let node_name_clone = self.node_name.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_modify_agent(
                         db_clone,
                         node_name_clone,
@@ -481,7 +501,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let job_manager_clone = self.job_manager.clone().unwrap();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_job_message(
                         db_clone,
                         node_name_clone,
@@ -500,7 +520,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let node_name_clone = self.node_name.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_change_job_agent(
                         db_clone,
                         node_name_clone,
@@ -518,7 +538,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let node_name_clone = self.node_name.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_available_llm_providers(
                         db_clone,
                         node_name_clone,
@@ -537,7 +557,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let encryption_public_key_clone = self.encryption_public_key;
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_create_files_inbox_with_symmetric_key(
                         db_clone,
                         node_name_clone,
@@ -558,7 +578,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let encryption_public_key_clone = self.encryption_public_key;
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_filenames_in_inbox(
                         db_clone,
                         vector_fs_clone,
@@ -582,7 +602,7 @@ impl Node {
             } => {
                 let db_clone = Arc::clone(&self.db);
                 let vector_fs_clone = self.vector_fs.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_add_file_to_inbox_with_symmetric_key(
                         db_clone,
                         vector_fs_clone,
@@ -601,7 +621,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let node_name_clone = self.node_name.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_all_smart_inboxes_for_profile(
                         db_clone,
                         identity_manager_clone,
@@ -619,7 +639,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let node_name_clone = self.node_name.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_update_smart_inbox_name(
                         encryption_secret_key_clone,
                         db_clone,
@@ -637,7 +657,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let node_name_clone = self.node_name.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_update_job_to_finished(
                         db_clone,
                         node_name_clone,
@@ -653,7 +673,7 @@ impl Node {
             NodeCommand::APIPrivateDevopsCronList { res } => {
                 let db_clone = Arc::clone(&self.db);
                 let node_name_clone = self.node_name.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_private_devops_cron_list(db_clone, node_name_clone, res).await;
                 });
             }
@@ -664,7 +684,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_add_toolkit(
                         lance_db,
                         vector_fs_clone,
@@ -677,12 +697,31 @@ impl Node {
                     .await;
                 });
             }
+            NodeCommand::APIInstallToolkitFromURL { msg, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let lance_db = self.lance_db.clone();
+                let node_name_clone = self.node_name.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let encryption_secret_key_clone = self.encryption_secret_key.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::api_install_toolkit_from_url(
+                        db_clone,
+                        lance_db,
+                        node_name_clone,
+                        identity_manager_clone,
+                        encryption_secret_key_clone,
+                        msg,
+                        res,
+                    )
+                    .await;
+                });
+            }
             NodeCommand::APIListAllShinkaiTools { msg, res } => {
                 let lance_db = self.lance_db.clone();
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_list_all_shinkai_tools(
                         lance_db,
                         node_name_clone,
@@ -699,7 +738,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_set_shinkai_tool(
                         lance_db,
                         node_name_clone,
@@ -717,7 +756,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_shinkai_tool(
                         lance_db,
                         node_name_clone,
@@ -735,7 +774,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_remove_toolkit(
                         lance_db,
                         node_name_clone,
@@ -753,7 +792,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_list_toolkits(
                         lance_db,
                         node_name_clone,
@@ -771,7 +810,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let sheet_manager = self.sheet_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_set_column(
                         sheet_manager,
                         node_name_clone,
@@ -789,7 +828,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let sheet_manager = self.sheet_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_remove_column(
                         sheet_manager,
                         node_name_clone,
@@ -807,7 +846,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let sheet_manager = self.sheet_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_add_rows(
                         sheet_manager,
                         node_name_clone,
@@ -825,7 +864,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let sheet_manager = self.sheet_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_remove_rows(
                         sheet_manager,
                         node_name_clone,
@@ -843,7 +882,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let sheet_manager = self.sheet_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_user_sheets(
                         sheet_manager,
                         node_name_clone,
@@ -861,7 +900,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let sheet_manager = self.sheet_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_create_empty_sheet(
                         sheet_manager,
                         node_name_clone,
@@ -879,7 +918,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let sheet_manager = self.sheet_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_remove_sheet(
                         sheet_manager,
                         node_name_clone,
@@ -897,7 +936,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let sheet_manager = self.sheet_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_set_cell_value(
                         sheet_manager,
                         node_name_clone,
@@ -915,7 +954,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let sheet_manager = self.sheet_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_sheet(
                         sheet_manager,
                         node_name_clone,
@@ -932,7 +971,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_scan_ollama_models(
                         node_name_clone,
                         identity_manager_clone,
@@ -952,7 +991,7 @@ impl Node {
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let identity_secret_key_clone = self.identity_secret_key.clone();
                 let ws_manager_trait = self.ws_manager_trait.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_add_ollama_models(
                         db_clone,
                         node_name_clone,
@@ -975,7 +1014,7 @@ impl Node {
                 let encryption_public_key_clone = self.encryption_public_key;
                 let identity_public_key_clone = self.identity_public_key;
                 let secret_file_path = self.secrets_file_path.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_change_nodes_name(
                         secret_file_path.as_str(),
                         node_name_clone,
@@ -992,21 +1031,41 @@ impl Node {
             // NodeCommand::APIIsPristine { res } => self.api_is_pristine(res).await,
             NodeCommand::APIIsPristine { res } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Self::api_is_pristine(db_clone, res).await;
                 });
             }
+            NodeCommand::APINodeHealth { node_name, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                let proxy_connection_info = self.proxy_connection_info.clone();
+                let embedding_api_url = self.embedding_generator.api_url.clone();
+                spawn_command_handler(async move {
+                    let relay = proxy_connection_info.lock().await.as_ref().map(|proxy_info| {
+                        (
+                            proxy_info.proxy_identity.get_node_name_string(),
+                            proxy_info.tcp_connection.is_some(),
+                        )
+                    });
+                    let targets = HealthTargets {
+                        node_name,
+                        embedding_api_url,
+                        relay,
+                    };
+                    let _ = Self::api_node_health(db_clone, vector_fs_clone, targets, res).await;
+                });
+            }
             // NodeCommand::IsPristine { res } => self.local_is_pristine(res).await,
             NodeCommand::IsPristine { res } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Self::local_is_pristine(db_clone, res).await;
                 });
             }
             // NodeCommand::GetNodeName { res: Sender<String> },
             NodeCommand::GetNodeName { res } => {
                 let node_name = self.node_name.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = res.send(node_name.node_name).await;
                 });
             }
@@ -1016,7 +1075,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_last_messages_from_inbox_with_branches(
                         encryption_secret_key_clone,
                         db_clone,
@@ -1036,7 +1095,7 @@ impl Node {
                 res,
             } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::local_get_last_messages_from_inbox_with_branches(
                         db_clone, inbox_name, limit, offset_key, res,
                     )
@@ -1051,7 +1110,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_vec_fs_retrieve_path_simplified_json(
                         db_clone,
                         vector_fs_clone,
@@ -1073,7 +1132,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_vec_fs_retrieve_path_minimal_json(
                         db_clone,
                         vector_fs_clone,
@@ -1097,7 +1156,7 @@ impl Node {
                 let embedding_generator_clone = self.embedding_generator.clone();
                 let unstructured_api_clone = self.unstructured_api.clone();
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_convert_files_and_save_to_folder(
                         db_clone,
                         vector_fs_clone,
@@ -1120,7 +1179,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_vec_fs_retrieve_vector_search_simplified_json(
                         db_clone,
                         vector_fs_clone,
@@ -1140,7 +1199,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_vec_fs_search_items(
                         db_clone,
                         vector_fs_clone,
@@ -1160,7 +1219,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_vec_fs_create_folder(
                         db_clone,
                         vector_fs_clone,
@@ -1180,7 +1239,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_vec_fs_move_item(
                         db_clone,
                         vector_fs_clone,
@@ -1200,7 +1259,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_vec_fs_copy_item(
                         db_clone,
                         vector_fs_clone,
@@ -1220,7 +1279,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_vec_fs_move_folder(
                         db_clone,
                         vector_fs_clone,
@@ -1240,7 +1299,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_vec_fs_copy_folder(
                         db_clone,
                         vector_fs_clone,
@@ -1260,7 +1319,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_vec_fs_retrieve_vector_resource(
                         db_clone,
                         vector_fs_clone,
@@ -1280,7 +1339,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_vec_fs_delete_folder(
                         db_clone,
                         vector_fs_clone,
@@ -1300,7 +1359,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_vec_fs_delete_item(
                         db_clone,
                         vector_fs_clone,
@@ -1322,7 +1381,7 @@ impl Node {
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
                 let my_subscription_manager_clone = self.my_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_subscription_available_shared_items(
                         db_clone,
                         vector_fs_clone,
@@ -1341,7 +1400,7 @@ impl Node {
             NodeCommand::APIAvailableSharedItemsOpen { msg, res } => {
                 let node_name_clone = self.node_name.clone();
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_subscription_available_shared_items_open(
                         node_name_clone,
                         ext_subscription_manager_clone,
@@ -1359,7 +1418,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_subscription_create_shareable_folder(
                         db_clone,
                         vector_fs_clone,
@@ -1381,7 +1440,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_subscription_update_shareable_folder(
                         db_clone,
                         vector_fs_clone,
@@ -1403,7 +1462,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_subscription_unshare_folder(
                         db_clone,
                         vector_fs_clone,
@@ -1425,7 +1484,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let my_subscription_manager_clone = self.my_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_subscription_subscribe_to_shared_folder(
                         db_clone,
                         vector_fs_clone,
@@ -1446,7 +1505,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_subscription_my_subscriptions(
                         db_clone,
                         vector_fs_clone,
@@ -1465,7 +1524,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let my_subscription_manager_clone = self.my_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_unsubscribe_my_subscriptions(
                         node_name_clone,
                         identity_manager_clone,
@@ -1483,7 +1542,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_my_subscribers(
                         node_name_clone,
                         identity_manager_clone,
@@ -1503,7 +1562,7 @@ impl Node {
                 let db_clone = Arc::clone(&self.db);
                 let node_name_clone = self.node_name.clone();
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_http_free_subscription_links(
                         db_clone,
                         node_name_clone,
@@ -1521,7 +1580,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::retrieve_vr_kai(
                         db_clone,
                         vector_fs_clone,
@@ -1541,7 +1600,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::retrieve_vr_pack(
                         db_clone,
                         vector_fs_clone,
@@ -1557,7 +1616,7 @@ impl Node {
             // NodeCommand::LocalExtManagerProcessSubscriptionUpdates { res } => self.local_ext_manager_process_subscription_updates(res).await,
             NodeCommand::LocalExtManagerProcessSubscriptionUpdates { res } => {
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ =
                         Node::local_ext_manager_process_subscription_updates(ext_subscription_manager_clone, res).await;
                 });
@@ -1565,7 +1624,7 @@ impl Node {
             // NodeCommand::LocalHttpUploaderProcessSubscriptionUpdates { res } => self.local_http_uploader_process_subscription_updates(res).await,
             NodeCommand::LocalHttpUploaderProcessSubscriptionUpdates { res } => {
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::local_http_uploader_process_subscription_updates(ext_subscription_manager_clone, res)
                         .await;
                 });
@@ -1573,7 +1632,7 @@ impl Node {
             // NodeCommand:: { res } => self.local_mysubscription_manager_process_download_updates(res).await,
             NodeCommand::LocalMySubscriptionCallJobMessageProcessing { res } => {
                 let my_subscription_manager_clone = self.my_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ =
                         Node::local_mysubscription_manager_process_download_updates(my_subscription_manager_clone, res)
                             .await;
@@ -1582,7 +1641,7 @@ impl Node {
             // NodeCommand:: { res } => self.local_mysubscription_trigger_http_download(res).await,
             NodeCommand::LocalMySubscriptionTriggerHttpDownload { res } => {
                 let my_subscription_manager_clone = self.my_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::local_mysubscription_trigger_http_download(my_subscription_manager_clone, res).await;
                 });
             }
@@ -1592,7 +1651,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_last_notifications(
                         db_clone,
                         node_name_clone,
@@ -1610,7 +1669,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_notifications_before_timestamp(
                         db_clone,
                         node_name_clone,
@@ -1628,7 +1687,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_local_processing_preference(
                         db_clone,
                         node_name_clone,
@@ -1645,7 +1704,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_update_local_processing_preference(
                         db_clone,
                         node_name_clone,
@@ -1665,7 +1724,7 @@ impl Node {
                 let tool_router_clone = self.tool_router.clone();
                 let embedding_generator_clone = Arc::new(self.embedding_generator.clone());
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_search_workflows(
                         db_clone,
                         node_name_clone,
@@ -1686,7 +1745,7 @@ impl Node {
                 let tool_router_clone = self.tool_router.clone();
                 let embedding_generator_clone = Arc::new(self.embedding_generator.clone());
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_search_shinkai_tool(
                         db_clone,
                         node_name_clone,
@@ -1706,7 +1765,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_add_workflow(
                         lance_db,
                         node_name_clone,
@@ -1724,7 +1783,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     // Note: yes it's the same as above
                     let _ = Node::api_add_workflow(
                         lance_db,
@@ -1743,7 +1802,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_remove_workflow(
                         lance_db,
                         node_name_clone,
@@ -1761,7 +1820,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_get_workflow_info(
                         lance_db,
                         node_name_clone,
@@ -1779,7 +1838,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_list_all_workflows(
                         lance_db,
                         node_name_clone,
@@ -1797,7 +1856,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_update_default_embedding_model(
                         db,
                         node_name_clone,
@@ -1816,7 +1875,7 @@ impl Node {
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::api_update_supported_embedding_models(
                         db,
                         vector_fs,
@@ -1835,7 +1894,7 @@ impl Node {
             NodeCommand::V2ApiGetPublicKeys { res: sender } => {
                 let identity_public_key = self.identity_public_key;
                 let encryption_public_key = self.encryption_public_key;
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_send_public_keys(identity_public_key, encryption_public_key, sender).await;
                 });
             }
@@ -1854,7 +1913,7 @@ impl Node {
                 let ws_manager_trait = self.ws_manager_trait.clone();
                 let supported_embedding_models = self.supported_embedding_models.clone();
 
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_handle_initial_registration(
                         db_clone,
                         identity_manager_clone,
@@ -1875,6 +1934,90 @@ impl Node {
                     .await;
                 });
             }
+            NodeCommand::V2ApiGenerateOnboardingKeys { res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_generate_onboarding_keys(db_clone, res).await;
+                });
+            }
+            NodeCommand::V2ApiTestLLMProvider { llm_provider, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_test_llm_provider(db_clone, llm_provider, res).await;
+                });
+            }
+            NodeCommand::V2ApiInitializeNodeInteractive { payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let node_name_clone = self.node_name.clone();
+                let first_device_needs_registration_code = self.first_device_needs_registration_code;
+                let embedding_generator_clone = self.embedding_generator.clone();
+                let encryption_public_key_clone = self.encryption_public_key;
+                let identity_public_key_clone = self.identity_public_key;
+                let identity_secret_key_clone = self.identity_secret_key.clone();
+                let initial_llm_providers_clone = self.initial_llm_providers.clone();
+                let job_manager = self.job_manager.clone().unwrap();
+                let ws_manager_trait = self.ws_manager_trait.clone();
+                let default_embedding_model = self.default_embedding_model.clone();
+                let supported_embedding_models = self.supported_embedding_models.clone();
+
+                spawn_command_handler(async move {
+                    Node::v2_handle_initialize_node_interactive(
+                        db_clone,
+                        identity_manager_clone,
+                        node_name_clone,
+                        payload,
+                        res,
+                        vector_fs_clone,
+                        first_device_needs_registration_code,
+                        embedding_generator_clone,
+                        job_manager,
+                        encryption_public_key_clone,
+                        identity_public_key_clone,
+                        identity_secret_key_clone,
+                        initial_llm_providers_clone,
+                        ws_manager_trait,
+                        default_embedding_model,
+                        supported_embedding_models,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiOidcRegistration { payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let node_name_clone = self.node_name.clone();
+                let embedding_generator_clone = Arc::new(self.embedding_generator.clone());
+                let encryption_public_key_clone = self.encryption_public_key;
+                let identity_public_key_clone = self.identity_public_key;
+                let identity_secret_key_clone = self.identity_secret_key.clone();
+                let initial_llm_providers_clone = self.initial_llm_providers.clone();
+                let job_manager = self.job_manager.clone().unwrap();
+                let ws_manager_trait = self.ws_manager_trait.clone();
+                let supported_embedding_models = self.supported_embedding_models.clone();
+
+                spawn_command_handler(async move {
+                    Node::v2_handle_oidc_registration(
+                        db_clone,
+                        identity_manager_clone,
+                        node_name_clone,
+                        payload,
+                        res,
+                        vector_fs_clone,
+                        embedding_generator_clone,
+                        job_manager,
+                        encryption_public_key_clone,
+                        identity_public_key_clone,
+                        identity_secret_key_clone,
+                        initial_llm_providers_clone,
+                        ws_manager_trait,
+                        supported_embedding_models,
+                    )
+                    .await;
+                });
+            }
             NodeCommand::V2ApiCreateJob {
                 bearer,
                 job_creation_info,
@@ -1888,7 +2031,7 @@ impl Node {
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let encryption_public_key_clone = self.encryption_public_key;
                 let signing_secret_key_clone = self.identity_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_create_new_job(
                         db_clone,
                         node_name_clone,
@@ -1905,6 +2048,54 @@ impl Node {
                     .await;
                 });
             }
+            NodeCommand::V2ApiCreateJobForPath { bearer, payload, res } => {
+                let job_manager_clone = self.job_manager.clone().unwrap();
+                let node_name_clone = self.node_name.clone();
+                let db_clone = self.db.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let vector_fs_clone = self.vector_fs.clone();
+                let encryption_secret_key_clone = self.encryption_secret_key.clone();
+                let encryption_public_key_clone = self.encryption_public_key;
+                let signing_secret_key_clone = self.identity_secret_key.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_create_job_for_path(
+                        db_clone,
+                        node_name_clone,
+                        identity_manager_clone,
+                        job_manager_clone,
+                        vector_fs_clone,
+                        bearer,
+                        payload,
+                        encryption_secret_key_clone,
+                        encryption_public_key_clone,
+                        signing_secret_key_clone,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiSetFolderAgent { bearer, payload, res } => {
+                let db_clone = self.db.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let vector_fs_clone = self.vector_fs.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_folder_agent(
+                        db_clone,
+                        identity_manager_clone,
+                        vector_fs_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiListFolderAgents { bearer, res } => {
+                let db_clone = self.db.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_list_folder_agents(db_clone, bearer, res).await;
+                });
+            }
             NodeCommand::V2ApiJobMessage {
                 bearer,
                 job_message,
@@ -1917,7 +2108,7 @@ impl Node {
                 let encryption_secret_key_clone = self.encryption_secret_key.clone();
                 let encryption_public_key_clone = self.encryption_public_key;
                 let signing_secret_key_clone = self.identity_secret_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_job_message(
                         db_clone,
                         node_name_clone,
@@ -1941,22 +2132,39 @@ impl Node {
                 res,
             } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_get_last_messages_from_inbox(db_clone, bearer, inbox_name, limit, offset_key, res)
                         .await;
                 });
             }
-            NodeCommand::V2ApiGetAllSmartInboxes { bearer, res } => {
+            NodeCommand::V2ApiGetAllSmartInboxes {
+                bearer,
+                folder,
+                label,
+                include_archived,
+                message_requests,
+                res,
+            } => {
                 let db_clone = Arc::clone(&self.db);
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_get_all_smart_inboxes(db_clone, identity_manager_clone, bearer, res).await;
+                spawn_command_handler(async move {
+                    let _ = Node::v2_get_all_smart_inboxes(
+                        db_clone,
+                        identity_manager_clone,
+                        bearer,
+                        folder,
+                        label,
+                        include_archived,
+                        message_requests,
+                        res,
+                    )
+                    .await;
                 });
             }
             NodeCommand::V2ApiAvailableLLMProviders { bearer, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let node_name_clone = self.node_name.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_get_available_llm_providers(db_clone, node_name_clone, bearer, res).await;
                 });
             }
@@ -1965,7 +2173,7 @@ impl Node {
                 let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_vec_fs_retrieve_path_simplified_json(
                         db_clone,
                         vector_fs_clone,
@@ -1985,7 +2193,7 @@ impl Node {
                 let embedding_generator_clone = self.embedding_generator.clone();
                 let unstructured_api_clone = self.unstructured_api.clone();
                 let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_convert_files_and_save_to_folder(
                         db_clone,
                         vector_fs_clone,
@@ -2004,7 +2212,7 @@ impl Node {
                 let db_clone = Arc::clone(&self.db);
                 let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ =
                         Node::v2_create_folder(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
                             .await;
@@ -2014,7 +2222,7 @@ impl Node {
                 let db_clone = Arc::clone(&self.db);
                 let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_move_item(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
                         .await;
                 });
@@ -2024,7 +2232,7 @@ impl Node {
                 let db_clone = Arc::clone(&self.db);
                 let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_copy_item(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
                         .await;
                 });
@@ -2034,7 +2242,7 @@ impl Node {
                 let db_clone = Arc::clone(&self.db);
                 let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ =
                         Node::v2_move_folder(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
                             .await;
@@ -2045,313 +2253,736 @@ impl Node {
                 let db_clone = Arc::clone(&self.db);
                 let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ =
                         Node::v2_copy_folder(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
                             .await;
                 });
             }
 
-            NodeCommand::V2ApiDeleteFolder { bearer, payload, res } => {
+            NodeCommand::V2ApiVecFSGetFolderStats { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
-                    let _ =
-                        Node::v2_delete_folder(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
-                            .await;
+                spawn_command_handler(async move {
+                    let _ = Node::v2_get_folder_stats(
+                        db_clone,
+                        vector_fs_clone,
+                        identity_manager_clone,
+                        payload,
+                        bearer,
+                        res,
+                    )
+                    .await;
                 });
             }
 
-            NodeCommand::V2ApiDeleteItem { bearer, payload, res } => {
+            NodeCommand::V2ApiVecFSExportFolderAsVRPack { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
-                    let _ =
-                        Node::v2_delete_item(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
-                            .await;
+                spawn_command_handler(async move {
+                    let _ = Node::v2_export_folder_as_vrpack(
+                        db_clone,
+                        vector_fs_clone,
+                        identity_manager_clone,
+                        payload,
+                        bearer,
+                        res,
+                    )
+                    .await;
                 });
             }
 
-            NodeCommand::V2ApiSearchItems { bearer, payload, res } => {
+            NodeCommand::V2ApiVecFSExportMarkdownBundle { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
-                    let _ =
-                        Node::v2_search_items(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
-                            .await;
+                spawn_command_handler(async move {
+                    let _ = Node::v2_export_markdown_bundle(
+                        db_clone,
+                        vector_fs_clone,
+                        identity_manager_clone,
+                        payload,
+                        bearer,
+                        res,
+                    )
+                    .await;
                 });
             }
-            NodeCommand::V2ApiVecFSRetrieveVectorResource { bearer, path, res } => {
+
+            NodeCommand::V2ApiVecFSImportMarkdownBundle { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_retrieve_vector_resource(
+                let embedding_generator_clone = self.embedding_generator.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_import_markdown_bundle(
                         db_clone,
                         vector_fs_clone,
                         identity_manager_clone,
-                        path,
+                        Arc::new(embedding_generator_clone),
+                        payload,
                         bearer,
                         res,
                     )
                     .await;
                 });
             }
-            NodeCommand::V2ApiUpdateSmartInboxName {
-                bearer,
-                inbox_name,
-                custom_name,
-                res,
-            } => {
-                let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
-                    let _ = Node::v2_update_smart_inbox_name(db_clone, bearer, inbox_name, custom_name, res).await;
-                });
-            }
-            NodeCommand::V2ApiCreateFilesInbox { bearer, res } => {
-                let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
-                    let _ = Node::v2_create_files_inbox(db_clone, bearer, res).await;
-                });
-            }
-            NodeCommand::V2ApiAddFileToInbox {
-                file_inbox_name,
-                filename,
-                file,
-                bearer,
-                res,
-            } => {
+
+            NodeCommand::V2ApiVecFSGetItemVersions { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let vector_fs_clone = self.vector_fs.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_add_file_to_inbox(
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_get_item_versions(
                         db_clone,
                         vector_fs_clone,
-                        file_inbox_name,
-                        filename,
-                        file,
+                        identity_manager_clone,
+                        payload,
                         bearer,
                         res,
                     )
                     .await;
                 });
             }
-            NodeCommand::V2ApiUploadFileToFolder {
-                bearer,
-                filename,
-                file,
-                path,
-                file_datetime,
-                res,
-            } => {
+
+            NodeCommand::V2ApiVecFSDiffItemVersion { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                let embedding_generator_clone = self.embedding_generator.clone();
-                let unstructured_api_clone = self.unstructured_api.clone();
-                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_upload_file_to_folder(
+                spawn_command_handler(async move {
+                    let _ = Node::v2_diff_item_version(
                         db_clone,
                         vector_fs_clone,
                         identity_manager_clone,
-                        Arc::new(embedding_generator_clone),
-                        Arc::new(unstructured_api_clone),
-                        ext_subscription_manager_clone,
+                        payload,
                         bearer,
-                        filename,
-                        file,
-                        path,
-                        file_datetime,
                         res,
                     )
                     .await;
                 });
             }
-            // New Code
-            NodeCommand::V2ApiAvailableSharedItems { bearer, payload, res } => {
+
+            NodeCommand::V2ApiVecFSRestoreItemVersion { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
-                let node_name_clone = self.node_name.clone();
+                let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                let my_subscription_manager_clone = self.my_subscription_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_api_available_shared_items(
+                spawn_command_handler(async move {
+                    let _ = Node::v2_restore_item_version(
                         db_clone,
-                        node_name_clone,
+                        vector_fs_clone,
                         identity_manager_clone,
-                        ext_subscription_manager_clone,
-                        my_subscription_manager_clone,
-                        bearer,
                         payload,
+                        bearer,
                         res,
                     )
                     .await;
                 });
             }
-            NodeCommand::V2ApiAvailableSharedItemsOpen { bearer, payload, res } => {
+
+            NodeCommand::V2ApiVecFSSetFolderEmbeddingModel { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
-                let node_name_clone = self.node_name.clone();
-                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_api_available_shared_items_open(
+                let vector_fs_clone = self.vector_fs.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_set_folder_embedding_model(
                         db_clone,
-                        node_name_clone,
-                        ext_subscription_manager_clone,
-                        bearer,
+                        vector_fs_clone,
+                        identity_manager_clone,
                         payload,
+                        bearer,
                         res,
                     )
                     .await;
                 });
             }
-            NodeCommand::V2ApiCreateShareableFolder { bearer, payload, res } => {
+
+            NodeCommand::V2ApiVecFSCreateLink { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_api_create_shareable_folder(
-                        db_clone,
-                        identity_manager_clone,
-                        ext_subscription_manager_clone,
-                        bearer,
-                        payload,
-                        res,
-                    )
-                    .await;
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_create_link(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
+                            .await;
                 });
             }
-            NodeCommand::V2ApiUpdateShareableFolder { bearer, payload, res } => {
+
+            NodeCommand::V2ApiGetOperationStatus { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_get_operation_status(db_clone, payload, bearer, res).await;
+                });
+            }
+
+            NodeCommand::V2ApiCancelOperation { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_cancel_operation(db_clone, payload, bearer, res).await;
+                });
+            }
+
+            NodeCommand::V2ApiDeleteFolder { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_api_update_shareable_folder(
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_delete_folder(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
+                            .await;
+                });
+            }
+
+            NodeCommand::V2ApiDeleteItem { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_delete_item(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
+                            .await;
+                });
+            }
+
+            NodeCommand::V2ApiSearchItems { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_search_items(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
+                            .await;
+                });
+            }
+            NodeCommand::V2ApiVecFSVectorSearch { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_vector_search(db_clone, vector_fs_clone, identity_manager_clone, payload, bearer, res)
+                            .await;
+                });
+            }
+            NodeCommand::V2ApiVecFSRetrieveVectorResource { bearer, path, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_retrieve_vector_resource(
                         db_clone,
+                        vector_fs_clone,
                         identity_manager_clone,
-                        ext_subscription_manager_clone,
+                        path,
                         bearer,
-                        payload,
                         res,
                     )
                     .await;
                 });
             }
-            NodeCommand::V2ApiUnshareFolder { bearer, payload, res } => {
+            NodeCommand::V2ApiVecFSRetrieveSourceFileMap { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_api_unshare_folder(
+                spawn_command_handler(async move {
+                    let _ = Node::v2_retrieve_source_file_map(
                         db_clone,
+                        vector_fs_clone,
                         identity_manager_clone,
-                        ext_subscription_manager_clone,
-                        bearer,
                         payload,
+                        bearer,
                         res,
                     )
                     .await;
                 });
             }
-            NodeCommand::V2ApiSubscribeToSharedFolder { bearer, payload, res } => {
+            NodeCommand::V2ApiUpdateSmartInboxName {
+                bearer,
+                inbox_name,
+                custom_name,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_update_smart_inbox_name(db_clone, bearer, inbox_name, custom_name, res).await;
+                });
+            }
+            NodeCommand::V2ApiMoveInboxesToFolder {
+                bearer,
+                inbox_names,
+                folder,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_move_inboxes_to_folder(db_clone, bearer, inbox_names, folder, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetInboxLabels {
+                bearer,
+                inbox_name,
+                labels,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_set_inbox_labels(db_clone, bearer, inbox_name, labels, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetInboxFolders { bearer, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let identity_manager_clone = self.identity_manager.clone();
-                let my_subscription_manager_clone = self.my_subscription_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_api_subscribe_to_shared_folder(
+                spawn_command_handler(async move {
+                    let _ = Node::v2_get_inbox_folders(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetInboxArchived {
+                bearer,
+                inbox_name,
+                archived,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_set_inbox_archived(db_clone, bearer, inbox_name, archived, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetInboxMuted {
+                bearer,
+                inbox_name,
+                muted,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_set_inbox_muted(db_clone, bearer, inbox_name, muted, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetInboxDigest { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_get_inbox_digest(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetInboxDigest { bearer, settings, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_set_inbox_digest(db_clone, identity_manager_clone, bearer, settings, res).await;
+                });
+            }
+            NodeCommand::V2ApiRemoveInboxDigest { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_remove_inbox_digest(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiChangeInboxMessage {
+                bearer,
+                inbox_name,
+                message_hash,
+                new_content,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let encryption_secret_key_clone = self.encryption_secret_key.clone();
+                let identity_secret_key_clone = self.identity_secret_key.clone();
+                let proxy_connection_info = self.proxy_connection_info.clone();
+                let ws_manager_trait = self.ws_manager_trait.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_change_inbox_message(
                         db_clone,
+                        node_name_clone,
                         identity_manager_clone,
-                        my_subscription_manager_clone,
+                        encryption_secret_key_clone,
+                        identity_secret_key_clone,
+                        proxy_connection_info,
+                        ws_manager_trait,
                         bearer,
-                        payload,
+                        inbox_name,
+                        message_hash,
+                        new_content,
                         res,
                     )
                     .await;
                 });
             }
-            NodeCommand::V2ApiUnsubscribe { bearer, payload, res } => {
+            NodeCommand::V2ApiGetInboxMessageHistory {
+                bearer,
+                inbox_name,
+                message_hash,
+                res,
+            } => {
                 let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_get_inbox_message_history(db_clone, bearer, inbox_name, message_hash, res).await;
+                });
+            }
+            NodeCommand::V2ApiSendMessageWithAttachments {
+                bearer,
+                inbox_name,
+                content,
+                files_inbox,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                let my_subscription_manager_clone = self.my_subscription_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_api_unsubscribe(
+                let encryption_secret_key_clone = self.encryption_secret_key.clone();
+                let identity_secret_key_clone = self.identity_secret_key.clone();
+                let proxy_connection_info = self.proxy_connection_info.clone();
+                let ws_manager_trait = self.ws_manager_trait.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_send_message_with_attachments(
                         db_clone,
+                        vector_fs_clone,
                         node_name_clone,
                         identity_manager_clone,
-                        my_subscription_manager_clone,
+                        encryption_secret_key_clone,
+                        identity_secret_key_clone,
+                        proxy_connection_info,
+                        ws_manager_trait,
                         bearer,
-                        payload,
+                        inbox_name,
+                        content,
+                        files_inbox,
                         res,
                     )
                     .await;
                 });
             }
-            NodeCommand::V2ApiMySubscriptions { bearer, res } => {
+            NodeCommand::V2ApiDownloadAttachment {
+                bearer,
+                inbox_name,
+                message_hash,
+                attachment_id,
+                res,
+            } => {
                 let db_clone = Arc::clone(&self.db);
-                let node_name_clone = self.node_name.clone();
-                let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ =
-                        Node::v2_api_my_subscriptions(db_clone, node_name_clone, identity_manager_clone, bearer, res)
+                        Node::v2_download_attachment(db_clone, bearer, inbox_name, message_hash, attachment_id, res)
                             .await;
                 });
             }
-            NodeCommand::V2ApiGetMySubscribers { bearer, payload, res } => {
+            NodeCommand::V2ApiCreateFilesInbox { bearer, res } => {
                 let db_clone = Arc::clone(&self.db);
-                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
-                    let _ =
-                        Node::v2_api_get_my_subscribers(db_clone, ext_subscription_manager_clone, bearer, payload, res)
-                            .await;
+                spawn_command_handler(async move {
+                    let _ = Node::v2_create_files_inbox(db_clone, bearer, res).await;
                 });
             }
-            NodeCommand::V2ApiGetHttpFreeSubscriptionLinks {
+            NodeCommand::V2ApiAddFileToInbox {
+                file_inbox_name,
+                filename,
+                file,
                 bearer,
-                subscription_profile_path,
                 res,
             } => {
                 let db_clone = Arc::clone(&self.db);
-                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_api_get_http_free_subscription_links(
+                let vector_fs_clone = self.vector_fs.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_add_file_to_inbox(
                         db_clone,
-                        ext_subscription_manager_clone,
+                        vector_fs_clone,
+                        file_inbox_name,
+                        filename,
+                        file,
                         bearer,
-                        subscription_profile_path,
                         res,
                     )
                     .await;
                 });
             }
-            NodeCommand::V2ApiGetLastNotifications { bearer, payload, res } => {
+            NodeCommand::V2ApiUploadFileToFolder {
+                bearer,
+                filename,
+                file,
+                path,
+                file_datetime,
+                allow_duplicates,
+                res,
+            } => {
                 let db_clone = Arc::clone(&self.db);
-                let node_name_clone = self.node_name.clone();
+                let vector_fs_clone = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_api_get_last_notifications(
+                let embedding_generator_clone = self.embedding_generator.clone();
+                let unstructured_api_clone = self.unstructured_api.clone();
+                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_upload_file_to_folder(
                         db_clone,
-                        node_name_clone,
+                        vector_fs_clone,
                         identity_manager_clone,
+                        Arc::new(embedding_generator_clone),
+                        Arc::new(unstructured_api_clone),
+                        ext_subscription_manager_clone,
                         bearer,
-                        payload,
+                        filename,
+                        file,
+                        path,
+                        file_datetime,
+                        allow_duplicates,
                         res,
                     )
                     .await;
                 });
             }
-            NodeCommand::V2ApiGetNotificationsBeforeTimestamp { bearer, payload, res } => {
+            // New Code
+            NodeCommand::V2ApiAvailableSharedItems { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let node_name_clone = self.node_name.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_api_get_notifications_before_timestamp(
+                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
+                let my_subscription_manager_clone = self.my_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_available_shared_items(
                         db_clone,
                         node_name_clone,
                         identity_manager_clone,
+                        ext_subscription_manager_clone,
+                        my_subscription_manager_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiAvailableSharedItemsOpen { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_available_shared_items_open(
+                        db_clone,
+                        node_name_clone,
+                        ext_subscription_manager_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiCreateShareableFolder { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_create_shareable_folder(
+                        db_clone,
+                        identity_manager_clone,
+                        ext_subscription_manager_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiUpdateShareableFolder { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_update_shareable_folder(
+                        db_clone,
+                        identity_manager_clone,
+                        ext_subscription_manager_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiUnshareFolder { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_unshare_folder(
+                        db_clone,
+                        identity_manager_clone,
+                        ext_subscription_manager_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiSubscribeToSharedFolder { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                let my_subscription_manager_clone = self.my_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_subscribe_to_shared_folder(
+                        db_clone,
+                        identity_manager_clone,
+                        my_subscription_manager_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiUnsubscribe { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let my_subscription_manager_clone = self.my_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_unsubscribe(
+                        db_clone,
+                        node_name_clone,
+                        identity_manager_clone,
+                        my_subscription_manager_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiSetSharedFolderWriters { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_shared_folder_writers(
+                        db_clone,
+                        identity_manager_clone,
+                        ext_subscription_manager_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiPushSharedFolderChanges { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                let my_subscription_manager_clone = self.my_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_push_shared_folder_changes(
+                        db_clone,
+                        identity_manager_clone,
+                        my_subscription_manager_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiMySubscriptions { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_api_my_subscriptions(db_clone, node_name_clone, identity_manager_clone, bearer, res)
+                            .await;
+                });
+            }
+            NodeCommand::V2ApiGetMySubscribers { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_api_get_my_subscribers(db_clone, ext_subscription_manager_clone, bearer, payload, res)
+                            .await;
+                });
+            }
+            NodeCommand::V2ApiGetHttpFreeSubscriptionLinks {
+                bearer,
+                subscription_profile_path,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_http_free_subscription_links(
+                        db_clone,
+                        ext_subscription_manager_clone,
+                        bearer,
+                        subscription_profile_path,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiGetLastNotifications { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_last_notifications(
+                        db_clone,
+                        node_name_clone,
+                        identity_manager_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiGetNotificationsBeforeTimestamp { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_notifications_before_timestamp(
+                        db_clone,
+                        node_name_clone,
+                        identity_manager_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiGetUnreadNotificationsCount { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_unread_notifications_count(db_clone, identity_manager_clone, bearer, res)
+                        .await;
+                });
+            }
+            NodeCommand::V2ApiMarkNotificationsRead { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_mark_notifications_read(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetNotificationPreferences { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_api_get_notification_preferences(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetNotificationPreferences { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_notification_preferences(
+                        db_clone,
+                        identity_manager_clone,
                         bearer,
                         payload,
                         res,
@@ -2361,7 +2992,7 @@ impl Node {
             }
             NodeCommand::V2ApiGetLocalProcessingPreference { bearer, res } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_get_local_processing_preference(
                         db_clone,
                         bearer,
@@ -2372,7 +3003,7 @@ impl Node {
             }
             NodeCommand::V2ApiUpdateLocalProcessingPreference { bearer, preference, res } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_update_local_processing_preference(
                         db_clone,
                         bearer,
@@ -2382,10 +3013,43 @@ impl Node {
                     .await;
                 });
             }
+            NodeCommand::V2ApiGetEntityTaggingPreference { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_entity_tagging_preference(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiUpdateEntityTaggingPreference {
+                bearer,
+                preference,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_update_entity_tagging_preference(db_clone, bearer, preference, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetSummarizationLLMProvider { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_summarization_llm_provider(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiUpdateSummarizationLLMProvider {
+                bearer,
+                llm_provider_id,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_api_update_summarization_llm_provider(db_clone, bearer, llm_provider_id, res).await;
+                });
+            }
             NodeCommand::V2ApiSearchWorkflows { bearer, query, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_search_workflows(
                         db_clone,
                         lance_db,
@@ -2399,7 +3063,7 @@ impl Node {
             NodeCommand::V2ApiSearchShinkaiTool { bearer, query, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_search_shinkai_tool(
                         db_clone,
                         lance_db,
@@ -2413,7 +3077,7 @@ impl Node {
             NodeCommand::V2ApiSetWorkflow { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_set_workflow(
                         db_clone,
                         lance_db,
@@ -2427,7 +3091,7 @@ impl Node {
             NodeCommand::V2ApiRemoveWorkflow { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_remove_workflow(
                         db_clone,
                         lance_db,
@@ -2441,7 +3105,7 @@ impl Node {
             NodeCommand::V2ApiGetWorkflowInfo { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_get_workflow_info(
                         db_clone,
                         lance_db,
@@ -2455,7 +3119,7 @@ impl Node {
             NodeCommand::V2ApiListAllWorkflows { bearer, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_list_all_workflows(
                         db_clone,
                         lance_db,
@@ -2467,7 +3131,7 @@ impl Node {
             }
             NodeCommand::V2ApiGetDefaultEmbeddingModel { bearer, res } => {
                 let db = self.db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_get_default_embedding_model(
                         db,
                         bearer,
@@ -2478,7 +3142,7 @@ impl Node {
             }
             NodeCommand::V2ApiGetSupportedEmbeddingModels { bearer, res } => {
                 let db = self.db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_get_supported_embedding_models(
                         db,
                         bearer,
@@ -2489,7 +3153,7 @@ impl Node {
             }
             NodeCommand::V2ApiUpdateDefaultEmbeddingModel { bearer, model_name, res } => {
                 let db = self.db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_update_default_embedding_model(
                         db,
                         bearer,
@@ -2503,7 +3167,7 @@ impl Node {
                 let db = self.db.clone();
                 let vector_fs = self.vector_fs.clone();
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_update_supported_embedding_models(
                         db,
                         vector_fs,
@@ -2521,7 +3185,7 @@ impl Node {
                 let job_manager_clone = self.job_manager.clone();
                 let identity_secret_key_clone = self.identity_secret_key.clone();
                 let ws_manager_trait = self.ws_manager_trait.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_add_llm_provider(
                         db_clone,
                         identity_manager_clone,
@@ -2537,7 +3201,7 @@ impl Node {
             }
             NodeCommand::V2ApiChangeJobLlmProvider { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_change_job_llm_provider(
                         db_clone,
                         bearer,
@@ -2550,7 +3214,7 @@ impl Node {
             NodeCommand::V2ApiRemoveLlmProvider { bearer, llm_provider_id, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_remove_llm_provider(
                         db_clone,
                         identity_manager_clone,
@@ -2564,7 +3228,7 @@ impl Node {
             NodeCommand::V2ApiModifyLlmProvider { bearer, agent, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let identity_manager_clone = self.identity_manager.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_modify_llm_provider(
                         db_clone,
                         identity_manager_clone,
@@ -2581,7 +3245,7 @@ impl Node {
                 let identity_manager_clone = self.identity_manager.clone();
                 let encryption_public_key_clone = self.encryption_public_key.clone();
                 let identity_public_key_clone = self.identity_public_key.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_change_nodes_name(
                         bearer,
                         db_clone,
@@ -2597,13 +3261,13 @@ impl Node {
             }
             NodeCommand::V2ApiIsPristine { bearer, res } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_is_pristine(bearer, db_clone, res).await;
                 });
             }
             NodeCommand::V2ApiScanOllamaModels { bearer, res } => {
                 let db_clone = Arc::clone(&self.db);
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_scan_ollama_models(
                         db_clone,
                         bearer,
@@ -2615,7 +3279,7 @@ impl Node {
             NodeCommand::V2ApiListAllShinkaiTools { bearer, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_list_all_shinkai_tools(
                         db_clone,
                         lance_db,
@@ -2628,7 +3292,7 @@ impl Node {
             NodeCommand::V2ApiSetShinkaiTool { bearer, tool_key, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
+                spawn_command_handler(async move {
                     let _ = Node::v2_api_set_shinkai_tool(
                         db_clone,
                         lance_db,
@@ -2640,38 +3304,931 @@ impl Node {
                     .await;
                 });
             }
-            NodeCommand::V2ApiGetShinkaiTool { bearer, payload, res } => {
+            NodeCommand::V2ApiAddWasmTool { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let lance_db = self.lance_db.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_api_get_shinkai_tool(
-                        db_clone,
-                        lance_db,
-                        bearer,
-                        payload,
-                        res,
-                    )
-                    .await;
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_add_wasm_tool(db_clone, lance_db, bearer, payload, res).await;
                 });
             }
-            NodeCommand::V2ApiAddOllamaModels { bearer, payload, res } => {
+            NodeCommand::V2ApiGetToolkitCapabilities {
+                bearer,
+                toolkit_name,
+                res,
+            } => {
                 let db_clone = Arc::clone(&self.db);
-                let identity_manager_clone = self.identity_manager.clone();
-                let job_manager_clone = self.job_manager.clone();
-                let identity_secret_key_clone = self.identity_secret_key.clone();
-                let ws_manager_trait = self.ws_manager_trait.clone();
-                tokio::spawn(async move {
-                    let _ = Node::v2_api_add_ollama_models(
-                        db_clone,
-                        identity_manager_clone,
-                        job_manager_clone,
-                        identity_secret_key_clone,
-                        bearer,
-                        payload,
-                        ws_manager_trait,
-                        res,
-                    )
-                    .await;
+                let lance_db = self.lance_db.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_toolkit_capabilities(db_clone, lance_db, bearer, toolkit_name, res).await;
+                });
+            }
+            NodeCommand::V2ApiGrantToolkitCapabilities { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_update_toolkit_capabilities(db_clone, bearer, payload, true, res).await;
+                });
+            }
+            NodeCommand::V2ApiRevokeToolkitCapabilities { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_update_toolkit_capabilities(db_clone, bearer, payload, false, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetToolkitExecutionLimits {
+                bearer,
+                toolkit_name,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_toolkit_execution_limits(db_clone, bearer, toolkit_name, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetToolkitExecutionLimits {
+                bearer,
+                toolkit_name,
+                payload,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_api_set_toolkit_execution_limits(db_clone, bearer, toolkit_name, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetToolkitPricing {
+                bearer,
+                toolkit_name,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_toolkit_pricing(db_clone, bearer, toolkit_name, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetToolkitPricing {
+                bearer,
+                toolkit_name,
+                payload,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_toolkit_pricing(db_clone, bearer, toolkit_name, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetToolUsage {
+                bearer,
+                consumer,
+                asset,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_tool_usage(db_clone, bearer, consumer, asset, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetToolUsageAllowance { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_tool_usage_allowance(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetShinkaiTool { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let lance_db = self.lance_db.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_shinkai_tool(
+                        db_clone,
+                        lance_db,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiAddOllamaModels { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                let job_manager_clone = self.job_manager.clone();
+                let identity_secret_key_clone = self.identity_secret_key.clone();
+                let ws_manager_trait = self.ws_manager_trait.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_add_ollama_models(
+                        db_clone,
+                        identity_manager_clone,
+                        job_manager_clone,
+                        identity_secret_key_clone,
+                        bearer,
+                        payload,
+                        ws_manager_trait,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiGetLocalInferenceMetrics { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_local_inference_metrics(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetLogLevels { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_log_levels(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetLogLevels {
+                bearer,
+                log_levels,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_log_levels(db_clone, bearer, log_levels, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetRecentLogs { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_recent_logs(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiDownloadLogs { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_download_logs(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiRunDiagnostics { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let targets = DiagnosticsTargets {
+                    embedding_api_url: self.embedding_generator.api_url.clone(),
+                    unstructured_api_url: self.unstructured_api.endpoint_url(),
+                    listen_addresses: std::iter::once(("node".to_string(), self.listen_address))
+                        .chain(self.ws_address.map(|ws_address| ("websocket".to_string(), ws_address)))
+                        .collect(),
+                };
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_run_diagnostics(db_clone, targets, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiRunDbMaintenance { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_run_db_maintenance(db_clone, vector_fs_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiRelocateStorage { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_relocate_storage(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetNodeMetrics { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let job_manager_clone = self.job_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_node_metrics(db_clone, job_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetPeerReputations { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_peer_reputations(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetPeerBan { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_peer_ban(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetOutboundProxySettings { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_outbound_proxy_settings(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetOutboundProxySettings { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_outbound_proxy_settings(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiResolveIdentityName { bearer, name, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_api_resolve_identity_name(db_clone, identity_manager_clone, bearer, name, res).await;
+                });
+            }
+            NodeCommand::V2ApiCreateInvoice { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let encryption_secret_key_clone = self.encryption_secret_key.clone();
+                let identity_secret_key_clone = self.identity_secret_key.clone();
+                let proxy_connection_info = self.proxy_connection_info.clone();
+                let ws_manager_trait = self.ws_manager_trait.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_create_invoice(
+                        db_clone,
+                        node_name_clone,
+                        identity_manager_clone,
+                        encryption_secret_key_clone,
+                        identity_secret_key_clone,
+                        proxy_connection_info,
+                        ws_manager_trait,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiListInvoices { bearer, status, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_list_invoices(db_clone, bearer, status, res).await;
+                });
+            }
+            NodeCommand::V2ApiMarkInvoicePaid { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let encryption_secret_key_clone = self.encryption_secret_key.clone();
+                let identity_secret_key_clone = self.identity_secret_key.clone();
+                let proxy_connection_info = self.proxy_connection_info.clone();
+                let ws_manager_trait = self.ws_manager_trait.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_mark_invoice_paid(
+                        db_clone,
+                        node_name_clone,
+                        identity_manager_clone,
+                        encryption_secret_key_clone,
+                        identity_secret_key_clone,
+                        proxy_connection_info,
+                        ws_manager_trait,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiSetWallet { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_wallet(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetWallet { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_wallet(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiPayInvoice { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_pay_invoice(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiListWalletTransactions { bearer, status, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_list_wallet_transactions(db_clone, bearer, status, res).await;
+                });
+            }
+            NodeCommand::V2ApiCompleteWalletTransaction { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_complete_wallet_transaction(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiCreateWorkspace { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
+                let encryption_secret_key_clone = self.encryption_secret_key.clone();
+                let identity_secret_key_clone = self.identity_secret_key.clone();
+                let proxy_connection_info = self.proxy_connection_info.clone();
+                let ws_manager_trait = self.ws_manager_trait.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_create_workspace(
+                        db_clone,
+                        node_name_clone,
+                        identity_manager_clone,
+                        ext_subscription_manager_clone,
+                        encryption_secret_key_clone,
+                        identity_secret_key_clone,
+                        proxy_connection_info,
+                        ws_manager_trait,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiUpdateWorkspace { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
+                let encryption_secret_key_clone = self.encryption_secret_key.clone();
+                let identity_secret_key_clone = self.identity_secret_key.clone();
+                let proxy_connection_info = self.proxy_connection_info.clone();
+                let ws_manager_trait = self.ws_manager_trait.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_update_workspace(
+                        db_clone,
+                        node_name_clone,
+                        identity_manager_clone,
+                        ext_subscription_manager_clone,
+                        encryption_secret_key_clone,
+                        identity_secret_key_clone,
+                        proxy_connection_info,
+                        ws_manager_trait,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiSetWorkspaceMember { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let ext_subscription_manager_clone = self.ext_subscription_manager.clone();
+                let encryption_secret_key_clone = self.encryption_secret_key.clone();
+                let identity_secret_key_clone = self.identity_secret_key.clone();
+                let proxy_connection_info = self.proxy_connection_info.clone();
+                let ws_manager_trait = self.ws_manager_trait.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_workspace_member(
+                        db_clone,
+                        node_name_clone,
+                        identity_manager_clone,
+                        ext_subscription_manager_clone,
+                        encryption_secret_key_clone,
+                        identity_secret_key_clone,
+                        proxy_connection_info,
+                        ws_manager_trait,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiListWorkspaces { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_list_workspaces(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiListWorkspaceInvitations { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_list_workspace_invitations(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiAcceptWorkspaceInvitation { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let my_subscription_manager_clone = self.my_subscription_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_accept_workspace_invitation(
+                        db_clone,
+                        node_name_clone,
+                        identity_manager_clone,
+                        my_subscription_manager_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiDeclineWorkspaceInvitation { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_decline_workspace_invitation(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetContacts { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_contacts(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetContact { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_contact(db_clone, identity_manager_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiRemoveContact { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_remove_contact(db_clone, identity_manager_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetContactsAllowlistMode { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_contacts_allowlist_mode(db_clone, identity_manager_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiAcceptMessageRequest { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_accept_message_request(db_clone, identity_manager_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetInboundMessagePolicy { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_inbound_message_policy(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetInboundMessagePolicy { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_inbound_message_policy(db_clone, identity_manager_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiPublishFolder { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_publish_folder(db_clone, identity_manager_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiUnpublishFolder { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_unpublish_folder(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiListPublications { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_list_publications(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::GetPublishedContent {
+                publication_id,
+                token,
+                path,
+                download,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::get_published_content(
+                        db_clone,
+                        vector_fs_clone,
+                        publication_id,
+                        token,
+                        path,
+                        download,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiStopNode { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_stop_node(db_clone, bearer, false, res).await;
+                });
+            }
+            NodeCommand::V2ApiRestartNode { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_stop_node(db_clone, bearer, true, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetProfileLimits { bearer, profile, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                let node_name_clone = self.node_name.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_profile_limits(
+                        db_clone,
+                        vector_fs_clone,
+                        node_name_clone,
+                        bearer,
+                        profile,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiSetProfileLimits { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                let node_name_clone = self.node_name.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_profile_limits(
+                        db_clone,
+                        vector_fs_clone,
+                        node_name_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiExportProfileData { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                let node_name_clone = self.node_name.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_export_profile_data(
+                        db_clone,
+                        vector_fs_clone,
+                        node_name_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiDeleteProfile { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let vector_fs_clone = self.vector_fs.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let node_name_clone = self.node_name.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_delete_profile(
+                        db_clone,
+                        vector_fs_clone,
+                        identity_manager_clone,
+                        node_name_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiSetEmailAccount { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_email_account(db_clone, identity_manager_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetEmailAccount { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_email_account(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiRemoveEmailAccount { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_remove_email_account(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetCalendarAccount { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_api_set_calendar_account(db_clone, identity_manager_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetCalendarAccount { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_calendar_account(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiRemoveCalendarAccount { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_remove_calendar_account(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiAddWatchedFolder { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_api_add_watched_folder(db_clone, identity_manager_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiListWatchedFolders { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_list_watched_folders(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiRemoveWatchedFolder { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_remove_watched_folder(db_clone, identity_manager_clone, bearer, payload, res)
+                        .await;
+                });
+            }
+            NodeCommand::V2ApiAddCloudConnector { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_api_add_cloud_connector(db_clone, identity_manager_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiListCloudConnectors { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_list_cloud_connectors(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiUpdateCloudConnector { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_update_cloud_connector(db_clone, identity_manager_clone, bearer, payload, res)
+                        .await;
+                });
+            }
+            NodeCommand::V2ApiRemoveCloudConnector { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_remove_cloud_connector(db_clone, identity_manager_clone, bearer, payload, res)
+                        .await;
+                });
+            }
+            NodeCommand::V2ApiOpenAIChatCompletion { bearer, request, res } => {
+                let job_manager_clone = self.job_manager.clone().unwrap();
+                let node_name_clone = self.node_name.clone();
+                let db_clone = self.db.clone();
+                let identity_manager_clone = self.identity_manager.clone();
+                let encryption_secret_key_clone = self.encryption_secret_key.clone();
+                let encryption_public_key_clone = self.encryption_public_key;
+                let signing_secret_key_clone = self.identity_secret_key.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_openai_chat_completion(
+                        db_clone,
+                        node_name_clone,
+                        identity_manager_clone,
+                        job_manager_clone,
+                        bearer,
+                        request,
+                        encryption_secret_key_clone,
+                        encryption_public_key_clone,
+                        signing_secret_key_clone,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiSubscribeToEvents { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_subscribe_to_events(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiCheckBearer { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_check_bearer(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiStartIdempotentRequest {
+                bearer,
+                idempotency_key,
+                request_hash,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ =
+                        Node::v2_api_start_idempotent_request(db_clone, bearer, idempotency_key, request_hash, res)
+                            .await;
+                });
+            }
+            NodeCommand::V2ApiFinishIdempotentRequest {
+                bearer,
+                idempotency_key,
+                response,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_finish_idempotent_request(db_clone, bearer, idempotency_key, response, res)
+                        .await;
+                });
+            }
+            NodeCommand::V2ApiCreateRegistrationCode { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_create_registration_code(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetAllProfiles { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_all_profiles(db_clone, identity_manager_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetDevices { bearer, profile, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_devices(db_clone, node_name_clone, bearer, profile, res).await;
+                });
+            }
+            NodeCommand::V2ApiRenameDevice { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name_clone = self.node_name.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_rename_device(db_clone, node_name_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiRevokeDevice { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                let node_name_clone = self.node_name.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_revoke_device(
+                        db_clone,
+                        identity_manager_clone,
+                        node_name_clone,
+                        bearer,
+                        payload,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiGetRegistrationCodes { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_registration_codes(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiRevokeRegistrationCode { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_revoke_registration_code(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiUpdateJobToFinished { bearer, job_id, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_update_job_to_finished(db_clone, bearer, job_id, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetJobStatus { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let job_manager_clone = self.job_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_job_status(db_clone, job_manager_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetJobPlanning { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_job_planning(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetJobPlan { bearer, job_id, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_job_plan(db_clone, bearer, job_id, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetJobRetrievalConfig { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_set_job_retrieval_config(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetJobRetrievalConfig { bearer, job_id, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_job_retrieval_config(db_clone, bearer, job_id, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetJobMessageCitations {
+                bearer,
+                job_id,
+                message_hash,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_get_job_message_citations(db_clone, bearer, job_id, message_hash, res).await;
+                });
+            }
+            NodeCommand::V2ApiMarkAsReadUpTo {
+                bearer,
+                inbox_name,
+                up_to_time,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_mark_as_read_up_to(db_clone, bearer, inbox_name, up_to_time, res).await;
+                });
+            }
+            NodeCommand::V2ApiAddInboxPermission {
+                bearer,
+                inbox_name,
+                profile,
+                permission,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_add_inbox_permission(
+                        db_clone,
+                        identity_manager_clone,
+                        bearer,
+                        inbox_name,
+                        profile,
+                        permission,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiRemoveInboxPermission {
+                bearer,
+                inbox_name,
+                profile,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                let identity_manager_clone = self.identity_manager.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_remove_inbox_permission(
+                        db_clone,
+                        identity_manager_clone,
+                        bearer,
+                        inbox_name,
+                        profile,
+                        res,
+                    )
+                    .await;
+                });
+            }
+            NodeCommand::V2ApiListToolkits { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_list_toolkits(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiInstallToolkitFromURL { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let lance_db = self.lance_db.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_install_toolkit_from_url(db_clone, lance_db, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiRemoveToolkit {
+                bearer,
+                toolkit_name,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                let lance_db = self.lance_db.clone();
+                spawn_command_handler(async move {
+                    let _ = Node::v2_api_remove_toolkit(db_clone, lance_db, bearer, toolkit_name, res).await;
                 });
             }
             _ => (),
@@ -0,0 +1,254 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use shinkai_message_primitives::schemas::contact::ContactTrustLevel;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+
+use crate::db::db_errors::ShinkaiDBError;
+use crate::db::ShinkaiDB;
+
+lazy_static! {
+    /// Filter of the messages other nodes send to the profiles of the node
+    pub static ref INBOUND_MESSAGE_FILTER: InboundMessageFilter = InboundMessageFilter::new_from_env();
+}
+
+/// Window of the per sender rate limits of the inbound message policies
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+/// The rate limit windows of senders that stopped sending are dropped once there are this many
+const MAX_RATE_LIMIT_WINDOWS: usize = 4096;
+/// Senders that failed checks without being blocked are forgotten once this many are tracked, so a peer cycling
+/// through claimed node names can't grow the map without bounds
+const MAX_FAILED_CHECKS_SENDERS: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InboundMessageVerdict {
+    Accept,
+    /// Accepted, but the inbox waits in the message requests of the profile until the sender is approved
+    Request,
+    /// Dropped without being saved, for the given reason
+    Drop(String),
+}
+
+#[derive(Debug, Default)]
+struct FailedChecks {
+    count: u32,
+    blocked_until: Option<Instant>,
+}
+
+/// Applies the inbound message policies of the profiles to the messages received from other nodes, and drops
+/// everything coming from a sender whose messages failed the identity or signature checks `max_failed_checks`
+/// times in a row, for `block_duration`.
+///
+/// Failed checks are counted by claimed sender node and IP, so a peer spoofing a node can't get the real one
+/// blocked.
+pub struct InboundMessageFilter {
+    rate_limit_windows: Mutex<HashMap<(String, String), VecDeque<Instant>>>,
+    failed_checks: Mutex<HashMap<(String, String), FailedChecks>>,
+    max_failed_checks: u32,
+    block_duration: Duration,
+}
+
+impl InboundMessageFilter {
+    pub fn new(max_failed_checks: u32, block_duration: Duration) -> Self {
+        Self {
+            rate_limit_windows: Mutex::new(HashMap::new()),
+            failed_checks: Mutex::new(HashMap::new()),
+            max_failed_checks: max_failed_checks.max(1),
+            block_duration,
+        }
+    }
+
+    /// Reads INBOUND_MAX_FAILED_CHECKS and INBOUND_FAILED_CHECKS_BLOCK_SECS
+    pub fn new_from_env() -> Self {
+        let read_env = |key: &str, default: u64| -> u64 {
+            std::env::var(key)
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(default)
+        };
+        Self::new(
+            read_env("INBOUND_MAX_FAILED_CHECKS", 5) as u32,
+            Duration::from_secs(read_env("INBOUND_FAILED_CHECKS_BLOCK_SECS", 3600)),
+        )
+    }
+
+    /// Counts a message of the sender node that failed the identity or signature checks
+    pub fn record_failed_check(&self, sender_node: &str, ip: &str) {
+        let mut failed_checks = self.failed_checks.lock().unwrap();
+        let key = (sender_node.to_string(), ip.to_string());
+        if failed_checks.len() >= MAX_FAILED_CHECKS_SENDERS && !failed_checks.contains_key(&key) {
+            let now = Instant::now();
+            failed_checks.retain(|_, sender| sender.blocked_until.is_some_and(|until| now < until));
+            // Every tracked sender is blocked, the block that ends first makes room
+            if failed_checks.len() >= MAX_FAILED_CHECKS_SENDERS {
+                let ending_first = failed_checks
+                    .iter()
+                    .min_by_key(|(_, sender)| sender.blocked_until)
+                    .map(|(key, _)| key.clone());
+                if let Some(ending_first) = ending_first {
+                    failed_checks.remove(&ending_first);
+                }
+            }
+        }
+
+        let sender = failed_checks.entry(key).or_default();
+        sender.count += 1;
+        if sender.count >= self.max_failed_checks {
+            sender.count = 0;
+            sender.blocked_until = Some(Instant::now() + self.block_duration);
+            shinkai_log(
+                ShinkaiLogOption::Network,
+                ShinkaiLogLevel::Error,
+                &format!(
+                    "Dropping the messages of {} from {} for {:?} after {} failed checks",
+                    sender_node, ip, self.block_duration, self.max_failed_checks
+                ),
+            );
+        }
+    }
+
+    /// A message of the sender node passed the checks, its failed ones are forgotten
+    pub fn record_passed_check(&self, sender_node: &str, ip: &str) {
+        let mut failed_checks = self.failed_checks.lock().unwrap();
+        let key = (sender_node.to_string(), ip.to_string());
+        if failed_checks
+            .get(&key)
+            .is_some_and(|sender| sender.blocked_until.is_none())
+        {
+            failed_checks.remove(&key);
+        }
+    }
+
+    pub fn is_blocked(&self, sender_node: &str, ip: &str) -> bool {
+        let mut failed_checks = self.failed_checks.lock().unwrap();
+        let key = (sender_node.to_string(), ip.to_string());
+        match failed_checks.get(&key).and_then(|sender| sender.blocked_until) {
+            Some(until) if Instant::now() < until => true,
+            Some(_) => {
+                failed_checks.remove(&key);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Applies the inbound message policy of the recipient profile to the message, before it's saved. Contacts of
+    /// the profile skip the policy if it exempts them, and conversations started by senders the contact book
+    /// doesn't allow are flagged as message requests.
+    pub fn check(&self, db: &ShinkaiDB, message: &ShinkaiMessage) -> Result<InboundMessageVerdict, ShinkaiDBError> {
+        let Ok(profile) = ShinkaiName::from_shinkai_message_using_recipient_subidentity(message)?.extract_profile()
+        else {
+            return Ok(InboundMessageVerdict::Accept);
+        };
+        let sender = ShinkaiName::from_shinkai_message_using_sender_subidentity(message)?;
+
+        let policy = db.get_inbound_message_policy(&profile)?;
+        let exempt = policy.exempt_contacts
+            && db
+                .get_contact_book(&profile)?
+                .find(&sender)
+                .is_some_and(|contact| contact.trust_level != ContactTrustLevel::Untrusted);
+        if !exempt {
+            if let Some(reason) = policy.filter(message) {
+                return Ok(InboundMessageVerdict::Drop(reason));
+            }
+            if let Some(max_messages_per_minute) = policy.max_messages_per_minute {
+                if !self.take_rate_limit_slot(&profile, &sender, max_messages_per_minute) {
+                    return Ok(InboundMessageVerdict::Drop(format!(
+                        "{} is over the limit of {} messages per minute",
+                        sender, max_messages_per_minute
+                    )));
+                }
+            }
+        }
+
+        match db.flag_message_request(message)? {
+            true => Ok(InboundMessageVerdict::Request),
+            false => Ok(InboundMessageVerdict::Accept),
+        }
+    }
+
+    /// Counts a message of the sender to the profile, unless it already sent the maximum in the last minute
+    fn take_rate_limit_slot(&self, profile: &ShinkaiName, sender: &ShinkaiName, max_messages: u32) -> bool {
+        let mut windows = self.rate_limit_windows.lock().unwrap();
+        if windows.len() >= MAX_RATE_LIMIT_WINDOWS {
+            windows.retain(|_, window| window.back().is_some_and(|last| last.elapsed() < RATE_LIMIT_WINDOW));
+        }
+
+        let window = windows
+            .entry((profile.full_name.clone(), sender.full_name.clone()))
+            .or_default();
+        while window.front().is_some_and(|first| first.elapsed() >= RATE_LIMIT_WINDOW) {
+            window.pop_front();
+        }
+        if window.len() >= max_messages as usize {
+            return false;
+        }
+        window.push_back(Instant::now());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sender_is_blocked_after_failed_checks() {
+        let filter = InboundMessageFilter::new(3, Duration::from_millis(50));
+        let (node, ip) = ("@@node1.shinkai", "10.0.0.1");
+
+        filter.record_failed_check(node, ip);
+        filter.record_failed_check(node, ip);
+        filter.record_passed_check(node, ip);
+        filter.record_failed_check(node, ip);
+        filter.record_failed_check(node, ip);
+        assert!(!filter.is_blocked(node, ip));
+
+        filter.record_failed_check(node, ip);
+        assert!(filter.is_blocked(node, ip));
+        // The real node, connecting from somewhere else, isn't blocked
+        assert!(!filter.is_blocked(node, "10.0.0.2"));
+        // Neither a message passing the checks nor another failed one change the block
+        filter.record_passed_check(node, ip);
+        assert!(filter.is_blocked(node, ip));
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(!filter.is_blocked(node, ip));
+    }
+
+    #[test]
+    fn test_failed_checks_are_capped() {
+        let filter = InboundMessageFilter::new(2, Duration::from_secs(60));
+        let ip = "10.0.0.1";
+
+        filter.record_failed_check("@@blocked.shinkai", ip);
+        filter.record_failed_check("@@blocked.shinkai", ip);
+        for i in 0..MAX_FAILED_CHECKS_SENDERS * 2 {
+            filter.record_failed_check(&format!("@@node{}.shinkai", i), ip);
+        }
+
+        assert!(filter.failed_checks.lock().unwrap().len() <= MAX_FAILED_CHECKS_SENDERS);
+        // Blocked senders are kept over the ones that only failed once
+        assert!(filter.is_blocked("@@blocked.shinkai", ip));
+    }
+
+    #[test]
+    fn test_rate_limit_is_per_profile_and_sender() {
+        let filter = InboundMessageFilter::new(5, Duration::from_secs(60));
+        let profile = ShinkaiName::new("@@node1.shinkai/main".to_string()).unwrap();
+        let other_profile = ShinkaiName::new("@@node1.shinkai/other".to_string()).unwrap();
+        let sender = ShinkaiName::new("@@node2.shinkai/main".to_string()).unwrap();
+        let other_sender = ShinkaiName::new("@@node3.shinkai/main".to_string()).unwrap();
+
+        assert!(filter.take_rate_limit_slot(&profile, &sender, 2));
+        assert!(filter.take_rate_limit_slot(&profile, &sender, 2));
+        assert!(!filter.take_rate_limit_slot(&profile, &sender, 2));
+        assert!(filter.take_rate_limit_slot(&other_profile, &sender, 2));
+        assert!(filter.take_rate_limit_slot(&profile, &other_sender, 2));
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use ed25519_dalek::SigningKey;
+use shinkai_message_primitives::schemas::inbox_name::InboxName;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_utils::encryption::clone_static_secret_key;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use shinkai_message_primitives::shinkai_utils::shinkai_message_builder::ShinkaiMessageBuilder;
+use shinkai_message_primitives::shinkai_utils::signatures::clone_signature_secret_key;
+use shinkai_vector_resources::shinkai_time::ShinkaiStringTime;
+use tokio::sync::Mutex;
+use x25519_dalek::StaticSecret as EncryptionStaticKey;
+
+use crate::db::db_errors::ShinkaiDBError;
+use crate::db::ShinkaiDB;
+use crate::managers::IdentityManager;
+use crate::network::node::ProxyConnectionInfo;
+use crate::network::ws_manager::WSUpdateHandler;
+use crate::network::Node;
+use crate::schemas::inbox_message_change::{InboxMessageChange, MessageChangeHistory};
+
+#[derive(Debug)]
+pub enum InboxMessageChangeError {
+    InvalidInput(String),
+    NotFound(String),
+    /// Only the sender of a message can change it
+    NotAllowed(String),
+    DatabaseError(ShinkaiDBError),
+    NetworkError(String),
+}
+
+impl fmt::Display for InboxMessageChangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InboxMessageChangeError::InvalidInput(msg) => write!(f, "Invalid change: {}", msg),
+            InboxMessageChangeError::NotFound(message_hash) => write!(f, "Message {} not found", message_hash),
+            InboxMessageChangeError::NotAllowed(msg) => write!(f, "{}", msg),
+            InboxMessageChangeError::DatabaseError(err) => write!(f, "Database error: {}", err),
+            InboxMessageChangeError::NetworkError(msg) => write!(f, "Failed to reach the participant: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for InboxMessageChangeError {}
+
+impl From<ShinkaiDBError> for InboxMessageChangeError {
+    fn from(err: ShinkaiDBError) -> Self {
+        InboxMessageChangeError::DatabaseError(err)
+    }
+}
+
+/// Edits and deletions of the messages of an inbox.
+///
+/// Users can only change the messages they sent. The change is recorded next to the message, which is kept as it
+/// was so the branch it's part of stays valid, and the node of the sender sends it to the nodes of the other
+/// participants of regular inboxes. Those only accept changes coming from the node the message was sent from.
+pub struct InboxMessageChanges;
+
+impl InboxMessageChanges {
+    /// Edits the message the author sent to the inbox, or deletes it if there's no new content
+    pub fn change_own_message(
+        db: &ShinkaiDB,
+        author: &ShinkaiName,
+        inbox_name: &str,
+        message_hash: &str,
+        new_content: Option<String>,
+    ) -> Result<InboxMessageChange, InboxMessageChangeError> {
+        if new_content.as_ref().is_some_and(|content| content.trim().is_empty()) {
+            return Err(InboxMessageChangeError::InvalidInput(
+                "The new content is empty, delete the message instead".to_string(),
+            ));
+        }
+
+        let (message, _) = db
+            .fetch_message_and_hash(message_hash)
+            .map_err(|_| InboxMessageChangeError::NotFound(message_hash.to_string()))?;
+        if message.get_message_inbox().ok().as_deref() != Some(inbox_name) {
+            return Err(InboxMessageChangeError::NotFound(message_hash.to_string()));
+        }
+        let sender = ShinkaiName::from_shinkai_message_using_sender_subidentity(&message)
+            .map_err(|e| InboxMessageChangeError::InvalidInput(e.to_string()))?;
+        if sender.full_name != author.full_name {
+            return Err(InboxMessageChangeError::NotAllowed(format!(
+                "{} can't change messages sent by {}",
+                author, sender
+            )));
+        }
+        if db
+            .get_inbox_message_changes(inbox_name, message_hash)?
+            .deleted_at
+            .is_some()
+        {
+            return Err(InboxMessageChangeError::InvalidInput(format!(
+                "Message {} was deleted",
+                message_hash
+            )));
+        }
+
+        let change = InboxMessageChange {
+            inbox_name: inbox_name.to_string(),
+            message_hash: message_hash.to_string(),
+            new_content,
+            changed_at: ShinkaiStringTime::generate_time_now(),
+        };
+        db.apply_inbox_message_change(&change)?;
+        Ok(change)
+    }
+
+    /// Applies the change sent by the node of another participant of the inbox, with the checks of
+    /// `change_own_message`
+    pub fn receive_change(
+        db: &ShinkaiDB,
+        sender: &ShinkaiName,
+        change: InboxMessageChange,
+    ) -> Result<MessageChangeHistory, InboxMessageChangeError> {
+        let inbox_name = InboxName::new(change.inbox_name.clone())
+            .map_err(|e| InboxMessageChangeError::InvalidInput(e.to_string()))?;
+        if !matches!(inbox_name, InboxName::RegularInbox { .. }) {
+            return Err(InboxMessageChangeError::NotAllowed(format!(
+                "Messages of {} can't be changed remotely",
+                change.inbox_name
+            )));
+        }
+        if change
+            .new_content
+            .as_ref()
+            .is_some_and(|content| content.trim().is_empty())
+        {
+            return Err(InboxMessageChangeError::InvalidInput(
+                "The new content is empty, delete the message instead".to_string(),
+            ));
+        }
+
+        let (message, _) = db
+            .fetch_message_and_hash(&change.message_hash)
+            .map_err(|_| InboxMessageChangeError::NotFound(change.message_hash.clone()))?;
+        // Else the change would be recorded under an inbox the message isn't part of
+        if message.get_message_inbox().ok().as_deref() != Some(change.inbox_name.as_str()) {
+            return Err(InboxMessageChangeError::NotFound(change.message_hash.clone()));
+        }
+        let author = ShinkaiName::from_shinkai_message_only_using_sender_node_name(&message)
+            .map_err(|e| InboxMessageChangeError::InvalidInput(e.to_string()))?;
+        if author.extract_node() != sender.extract_node() {
+            return Err(InboxMessageChangeError::NotAllowed(format!(
+                "{} can't change messages sent from {}",
+                sender, author
+            )));
+        }
+        if db
+            .get_inbox_message_changes(&change.inbox_name, &change.message_hash)?
+            .deleted_at
+            .is_some()
+        {
+            return Err(InboxMessageChangeError::InvalidInput(format!(
+                "Message {} was deleted",
+                change.message_hash
+            )));
+        }
+
+        Ok(db.apply_inbox_message_change(&change)?)
+    }
+
+    /// Sends the change to the nodes of the other participants of the inbox. Job inboxes stay on this node.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_to_participants(
+        change: &InboxMessageChange,
+        node_name: &ShinkaiName,
+        encryption_secret_key: &EncryptionStaticKey,
+        signing_key: &SigningKey,
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) {
+        let Ok(InboxName::RegularInbox { identities, .. }) = InboxName::new(change.inbox_name.clone()) else {
+            return;
+        };
+
+        let mut sent = HashSet::new();
+        for participant in identities {
+            let participant_node = participant.extract_node();
+            if participant_node == node_name.extract_node() || !sent.insert(participant_node.full_name.clone()) {
+                continue;
+            }
+            if let Err(e) = Self::send_to_node(
+                change,
+                &participant_node,
+                node_name,
+                encryption_secret_key,
+                signing_key,
+                db.clone(),
+                identity_manager.clone(),
+                proxy_connection_info.clone(),
+                ws_manager.clone(),
+            )
+            .await
+            {
+                shinkai_log(
+                    ShinkaiLogOption::Network,
+                    ShinkaiLogLevel::Error,
+                    &format!(
+                        "Failed to send the change of message {} to {}: {}",
+                        change.message_hash, participant_node, e
+                    ),
+                );
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn send_to_node(
+        change: &InboxMessageChange,
+        recipient_node: &ShinkaiName,
+        node_name: &ShinkaiName,
+        encryption_secret_key: &EncryptionStaticKey,
+        signing_key: &SigningKey,
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) -> Result<(), InboxMessageChangeError> {
+        let recipient_identity = identity_manager
+            .lock()
+            .await
+            .external_profile_to_global_identity(&recipient_node.get_node_name_string())
+            .await
+            .map_err(InboxMessageChangeError::NetworkError)?;
+        let address = recipient_identity
+            .addr
+            .ok_or_else(|| InboxMessageChangeError::NetworkError(format!("{} has no address", recipient_node)))?;
+
+        let message = ShinkaiMessageBuilder::p2p_inbox_message_change(
+            change,
+            clone_static_secret_key(encryption_secret_key),
+            clone_signature_secret_key(signing_key),
+            recipient_identity.node_encryption_public_key,
+            node_name.get_node_name_string(),
+            recipient_node.get_node_name_string(),
+        )
+        .map_err(|e| InboxMessageChangeError::NetworkError(e.to_string()))?;
+
+        Node::send(
+            message,
+            Arc::new(clone_static_secret_key(encryption_secret_key)),
+            (address, recipient_node.get_node_name_string()),
+            proxy_connection_info,
+            db,
+            identity_manager,
+            ws_manager,
+            false,
+            None,
+        );
+        Ok(())
+    }
+}
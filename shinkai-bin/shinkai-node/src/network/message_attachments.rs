@@ -0,0 +1,502 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use ed25519_dalek::SigningKey;
+use shinkai_message_primitives::schemas::inbox_name::InboxName;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
+    MessageAttachment, MessageSchemaType, MessageWithAttachments,
+};
+use shinkai_message_primitives::shinkai_utils::encryption::{clone_static_secret_key, EncryptionMethod};
+use shinkai_message_primitives::shinkai_utils::file_encryption::{
+    aes_encryption_key_to_string, decrypt_file_chunks, encrypt_file_chunks, random_aes_encryption_key,
+    ENCRYPTED_ATTACHMENT_CHUNK_SIZE,
+};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use shinkai_message_primitives::shinkai_utils::shinkai_message_builder::ShinkaiMessageBuilder;
+use shinkai_message_primitives::shinkai_utils::signatures::clone_signature_secret_key;
+use shinkai_vector_resources::shinkai_time::ShinkaiStringTime;
+use tokio::sync::Mutex;
+use x25519_dalek::StaticSecret as EncryptionStaticKey;
+
+use crate::db::db_errors::ShinkaiDBError;
+use crate::db::ShinkaiDB;
+use crate::managers::IdentityManager;
+use crate::network::node::ProxyConnectionInfo;
+use crate::network::ws_manager::WSUpdateHandler;
+use crate::network::Node;
+use crate::schemas::message_attachment::{AttachmentAnnouncement, AttachmentChunk};
+
+/// Attachments of messages from other nodes with more chunks are refused, that's 1 GiB
+const MAX_ATTACHMENT_CHUNKS: u32 = 4096;
+
+#[derive(Debug)]
+pub enum MessageAttachmentError {
+    InvalidInput(String),
+    NotFound(String),
+    NotAllowed(String),
+    /// Some chunks of the attachment weren't received yet
+    NotReceived(String),
+    DatabaseError(ShinkaiDBError),
+    NetworkError(String),
+}
+
+impl fmt::Display for MessageAttachmentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MessageAttachmentError::InvalidInput(msg) => write!(f, "Invalid attachment: {}", msg),
+            MessageAttachmentError::NotFound(msg) => write!(f, "{} not found", msg),
+            MessageAttachmentError::NotAllowed(msg) => write!(f, "{}", msg),
+            MessageAttachmentError::NotReceived(attachment_id) => {
+                write!(f, "Attachment {} is still being received", attachment_id)
+            }
+            MessageAttachmentError::DatabaseError(err) => write!(f, "Database error: {}", err),
+            MessageAttachmentError::NetworkError(msg) => write!(f, "Failed to reach the participant: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MessageAttachmentError {}
+
+impl From<ShinkaiDBError> for MessageAttachmentError {
+    fn from(err: ShinkaiDBError) -> Self {
+        MessageAttachmentError::DatabaseError(err)
+    }
+}
+
+/// Files attached to the messages of regular inboxes.
+///
+/// The files of a message are encrypted in chunks with a key generated for the message, which travels inside the
+/// message so only the participants can read them. The node of the sender keeps the encrypted chunks and sends
+/// them to the nodes of the other participants apart from the message, which is why a recipient may get the
+/// message before its attachments.
+pub struct MessageAttachments;
+
+impl MessageAttachments {
+    /// Sends a message with the files to the regular inbox, as the sender. Returns the message as it's saved on
+    /// this node.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_message(
+        sender: &ShinkaiName,
+        inbox_name: &str,
+        content: String,
+        files: Vec<(String, Vec<u8>)>,
+        node_name: &ShinkaiName,
+        encryption_secret_key: &EncryptionStaticKey,
+        signing_key: &SigningKey,
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) -> Result<ShinkaiMessage, MessageAttachmentError> {
+        let Ok(InboxName::RegularInbox { identities, .. }) = InboxName::new(inbox_name.to_string()) else {
+            return Err(MessageAttachmentError::InvalidInput(format!(
+                "{} isn't a regular inbox",
+                inbox_name
+            )));
+        };
+        if !identities.iter().any(|identity| identity.full_name == sender.full_name) {
+            return Err(MessageAttachmentError::NotAllowed(format!(
+                "{} isn't a participant of inbox {}",
+                sender, inbox_name
+            )));
+        }
+        if files.is_empty() {
+            return Err(MessageAttachmentError::InvalidInput(
+                "There are no files to attach".to_string(),
+            ));
+        }
+
+        let (payload, chunks) = Self::encrypt_files(&db, content, files)?;
+        let receiver = identities
+            .iter()
+            .find(|identity| identity.full_name != sender.full_name)
+            .unwrap_or(sender);
+        let receiver_node = receiver.extract_node();
+        let receiver_identity = if receiver_node == node_name.extract_node() {
+            None
+        } else {
+            Some(
+                identity_manager
+                    .lock()
+                    .await
+                    .external_profile_to_global_identity(&receiver_node.get_node_name_string())
+                    .await
+                    .map_err(MessageAttachmentError::NetworkError)?,
+            )
+        };
+        let receiver_public_key = receiver_identity
+            .as_ref()
+            .map(|identity| identity.node_encryption_public_key)
+            .unwrap_or_else(|| x25519_dalek::PublicKey::from(encryption_secret_key));
+
+        let payload =
+            serde_json::to_string(&payload).map_err(|e| MessageAttachmentError::InvalidInput(e.to_string()))?;
+        let message = ShinkaiMessageBuilder::new(
+            clone_static_secret_key(encryption_secret_key),
+            clone_signature_secret_key(signing_key),
+            receiver_public_key,
+        )
+        .message_raw_content(payload)
+        .body_encryption(EncryptionMethod::None)
+        .message_schema_type(MessageSchemaType::MessageWithAttachments)
+        .internal_metadata_with_inbox(
+            sender.get_fullname_string_without_node_name().unwrap_or_default(),
+            receiver.get_fullname_string_without_node_name().unwrap_or_default(),
+            inbox_name.to_string(),
+            EncryptionMethod::None,
+            None,
+        )
+        .external_metadata_with_schedule(
+            receiver_node.get_node_name_string(),
+            node_name.get_node_name_string(),
+            ShinkaiStringTime::generate_time_now(),
+        )
+        .build()
+        .map_err(|e| MessageAttachmentError::InvalidInput(e.to_string()))?;
+
+        db.unsafe_insert_inbox_message(&message, None, ws_manager.clone())
+            .await?;
+
+        if let Some(receiver_identity) = receiver_identity {
+            let address = receiver_identity
+                .addr
+                .ok_or_else(|| MessageAttachmentError::NetworkError(format!("{} has no address", receiver_node)))?;
+            let mut outgoing = message.clone();
+            outgoing.encryption = EncryptionMethod::DiffieHellmanChaChaPoly1305;
+            let outgoing = outgoing
+                .encrypt_outer_layer(encryption_secret_key, &receiver_identity.node_encryption_public_key)
+                .and_then(|outgoing| outgoing.sign_outer_layer(signing_key))
+                .map_err(|e| MessageAttachmentError::NetworkError(e.to_string()))?;
+            Node::send(
+                outgoing,
+                Arc::new(clone_static_secret_key(encryption_secret_key)),
+                (address, receiver_node.get_node_name_string()),
+                proxy_connection_info.clone(),
+                db.clone(),
+                identity_manager.clone(),
+                ws_manager.clone(),
+                false,
+                None,
+            );
+        }
+
+        Self::send_chunks_to_participants(
+            inbox_name,
+            &identities,
+            &chunks,
+            node_name,
+            encryption_secret_key,
+            signing_key,
+            db,
+            identity_manager,
+            proxy_connection_info,
+            ws_manager,
+        )
+        .await;
+
+        Ok(message)
+    }
+
+    /// Encrypts the files with a new key and stores their chunks. Returns the content of the message referencing
+    /// them, and the chunks of each attachment.
+    pub fn encrypt_files(
+        db: &ShinkaiDB,
+        content: String,
+        files: Vec<(String, Vec<u8>)>,
+    ) -> Result<(MessageWithAttachments, Vec<(MessageAttachment, Vec<Vec<u8>>)>), MessageAttachmentError> {
+        let key = random_aes_encryption_key();
+
+        let mut attachments = Vec::new();
+        for (file_name, file) in files {
+            let chunks = encrypt_file_chunks(&key, &file)
+                .map_err(|_| MessageAttachmentError::InvalidInput(format!("Failed to encrypt {}", file_name)))?;
+            let attachment = MessageAttachment {
+                attachment_id: uuid::Uuid::new_v4().to_string(),
+                file_name,
+                size: file.len() as u64,
+                chunk_count: chunks.len() as u32,
+                content_hash: blake3::hash(&file).to_hex().to_string(),
+            };
+            for (index, chunk) in chunks.iter().enumerate() {
+                db.add_attachment_chunk(&attachment.attachment_id, index as u32, chunk)?;
+            }
+            attachments.push((attachment, chunks));
+        }
+
+        let message = MessageWithAttachments {
+            content,
+            attachments: attachments.iter().map(|(attachment, _)| attachment.clone()).collect(),
+            key: aes_encryption_key_to_string(key),
+        };
+        Ok((message, attachments))
+    }
+
+    /// Records the attachments of a message saved from the node of another participant of the inbox, so their
+    /// chunks are accepted from that node
+    pub fn receive_announcement(
+        db: &ShinkaiDB,
+        node_name: &ShinkaiName,
+        message: &ShinkaiMessage,
+    ) -> Result<(), MessageAttachmentError> {
+        let sender = ShinkaiName::from_shinkai_message_only_using_sender_node_name(message)
+            .map_err(|e| MessageAttachmentError::InvalidInput(e.to_string()))?;
+        let inbox_name = message
+            .get_message_inbox()
+            .map_err(|e| MessageAttachmentError::InvalidInput(e.to_string()))?;
+        let inbox_name = Self::check_participants(&inbox_name, node_name, &sender)?;
+
+        let content = message
+            .get_message_content()
+            .map_err(|e| MessageAttachmentError::InvalidInput(e.to_string()))?;
+        let payload: MessageWithAttachments =
+            serde_json::from_str(&content).map_err(|e| MessageAttachmentError::InvalidInput(e.to_string()))?;
+        for attachment in payload.attachments {
+            if uuid::Uuid::parse_str(&attachment.attachment_id).is_err() {
+                return Err(MessageAttachmentError::InvalidInput(format!(
+                    "Invalid attachment id {}",
+                    attachment.attachment_id
+                )));
+            }
+            if attachment.chunk_count == 0 || attachment.chunk_count > MAX_ATTACHMENT_CHUNKS {
+                return Err(MessageAttachmentError::InvalidInput(format!(
+                    "{} has {} chunks, up to {} are accepted",
+                    attachment.attachment_id, attachment.chunk_count, MAX_ATTACHMENT_CHUNKS
+                )));
+            }
+            let announcement = AttachmentAnnouncement {
+                inbox_name: inbox_name.clone(),
+                sender_node: sender.get_node_name_string(),
+                chunk_count: attachment.chunk_count,
+            };
+            if !db.add_attachment_announcement(&attachment.attachment_id, &announcement)? {
+                shinkai_log(
+                    ShinkaiLogOption::Network,
+                    ShinkaiLogLevel::Error,
+                    &format!(
+                        "Attachment {} from {} was already announced, keeping the first announcement",
+                        attachment.attachment_id, sender
+                    ),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores the chunk sent by the node of another participant of the inbox, if it belongs to an attachment of a
+    /// message this node saved from that participant. Chunks already received are kept.
+    pub fn receive_chunk(
+        db: &ShinkaiDB,
+        node_name: &ShinkaiName,
+        sender: &ShinkaiName,
+        chunk: AttachmentChunk,
+    ) -> Result<(), MessageAttachmentError> {
+        let inbox_name = Self::check_participants(&chunk.inbox_name, node_name, sender)?;
+        let announcement = db
+            .get_attachment_announcement(&chunk.attachment_id)?
+            .filter(|announcement| {
+                announcement.sender_node == sender.get_node_name_string() && announcement.inbox_name == inbox_name
+            })
+            .ok_or_else(|| {
+                MessageAttachmentError::NotAllowed(format!(
+                    "{} didn't send a message with attachment {} to inbox {}",
+                    sender, chunk.attachment_id, chunk.inbox_name
+                ))
+            })?;
+        if chunk.chunk_count != announcement.chunk_count || chunk.index >= announcement.chunk_count {
+            return Err(MessageAttachmentError::InvalidInput(format!(
+                "Chunk {} of {} is out of range, the attachment has {} chunks",
+                chunk.index, chunk.chunk_count, announcement.chunk_count
+            )));
+        }
+
+        let data = base64::decode(&chunk.data).map_err(|e| MessageAttachmentError::InvalidInput(e.to_string()))?;
+        if data.len() > ENCRYPTED_ATTACHMENT_CHUNK_SIZE {
+            return Err(MessageAttachmentError::InvalidInput(format!(
+                "Chunk {} of {} has {} bytes, up to {} are accepted",
+                chunk.index,
+                chunk.attachment_id,
+                data.len(),
+                ENCRYPTED_ATTACHMENT_CHUNK_SIZE
+            )));
+        }
+        if !db.add_attachment_chunk(&chunk.attachment_id, chunk.index, &data)? {
+            return Err(MessageAttachmentError::InvalidInput(format!(
+                "Chunk {} of {} was already received",
+                chunk.index, chunk.attachment_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Errors unless the inbox is a regular inbox of both nodes. Returns the normalized inbox name.
+    fn check_participants(
+        inbox_name: &str,
+        node_name: &ShinkaiName,
+        sender: &ShinkaiName,
+    ) -> Result<String, MessageAttachmentError> {
+        let Ok(InboxName::RegularInbox { value, identities, .. }) = InboxName::new(inbox_name.to_string()) else {
+            return Err(MessageAttachmentError::InvalidInput(format!(
+                "{} isn't a regular inbox",
+                inbox_name
+            )));
+        };
+        let is_participant = |node: &ShinkaiName| {
+            identities
+                .iter()
+                .any(|identity| identity.extract_node() == node.extract_node())
+        };
+        if !is_participant(sender) || !is_participant(node_name) {
+            return Err(MessageAttachmentError::NotAllowed(format!(
+                "{} can't send attachments to inbox {}",
+                sender, inbox_name
+            )));
+        }
+        Ok(value)
+    }
+
+    /// Decrypts the attachment of the message of the inbox. Returns its file name and content.
+    pub fn read_attachment(
+        db: &ShinkaiDB,
+        inbox_name: &str,
+        message_hash: &str,
+        attachment_id: &str,
+    ) -> Result<(String, Vec<u8>), MessageAttachmentError> {
+        let (message, _) = db
+            .fetch_message_and_hash(message_hash)
+            .map_err(|_| MessageAttachmentError::NotFound(format!("Message {}", message_hash)))?;
+        if message.get_message_inbox().ok().as_deref() != Some(inbox_name) {
+            return Err(MessageAttachmentError::NotFound(format!("Message {}", message_hash)));
+        }
+        if db
+            .get_inbox_message_changes(inbox_name, message_hash)?
+            .deleted_at
+            .is_some()
+        {
+            return Err(MessageAttachmentError::NotFound(format!("Message {}", message_hash)));
+        }
+
+        let content = message
+            .get_message_content()
+            .map_err(|e| MessageAttachmentError::InvalidInput(e.to_string()))?;
+        let payload: MessageWithAttachments = serde_json::from_str(&content)
+            .map_err(|_| MessageAttachmentError::NotFound(format!("Attachment {}", attachment_id)))?;
+        let attachment = payload
+            .attachments
+            .iter()
+            .find(|attachment| attachment.attachment_id == attachment_id)
+            .ok_or_else(|| MessageAttachmentError::NotFound(format!("Attachment {}", attachment_id)))?;
+
+        let chunks = db
+            .get_attachment_chunks(attachment)?
+            .ok_or_else(|| MessageAttachmentError::NotReceived(attachment_id.to_string()))?;
+        let key: [u8; 32] = hex::decode(&payload.key)
+            .ok()
+            .and_then(|key| key.try_into().ok())
+            .ok_or_else(|| MessageAttachmentError::InvalidInput("The key of the message is malformed".to_string()))?;
+        let file = decrypt_file_chunks(&key, &chunks)
+            .map_err(|_| MessageAttachmentError::InvalidInput(format!("Failed to decrypt {}", attachment_id)))?;
+        if blake3::hash(&file).to_hex().to_string() != attachment.content_hash {
+            return Err(MessageAttachmentError::InvalidInput(format!(
+                "The content of {} doesn't match its hash",
+                attachment_id
+            )));
+        }
+
+        Ok((attachment.file_name.clone(), file))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn send_chunks_to_participants(
+        inbox_name: &str,
+        identities: &[ShinkaiName],
+        attachments: &[(MessageAttachment, Vec<Vec<u8>>)],
+        node_name: &ShinkaiName,
+        encryption_secret_key: &EncryptionStaticKey,
+        signing_key: &SigningKey,
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) {
+        let mut sent = HashSet::new();
+        for participant in identities {
+            let participant_node = participant.extract_node();
+            if participant_node == node_name.extract_node() || !sent.insert(participant_node.full_name.clone()) {
+                continue;
+            }
+
+            let recipient_identity = match identity_manager
+                .lock()
+                .await
+                .external_profile_to_global_identity(&participant_node.get_node_name_string())
+                .await
+            {
+                Ok(identity) => identity,
+                Err(e) => {
+                    shinkai_log(
+                        ShinkaiLogOption::Network,
+                        ShinkaiLogLevel::Error,
+                        &format!("Failed to send the attachments to {}: {}", participant_node, e),
+                    );
+                    continue;
+                }
+            };
+            let Some(address) = recipient_identity.addr else {
+                shinkai_log(
+                    ShinkaiLogOption::Network,
+                    ShinkaiLogLevel::Error,
+                    &format!(
+                        "Failed to send the attachments to {}: it has no address",
+                        participant_node
+                    ),
+                );
+                continue;
+            };
+
+            for (attachment, chunks) in attachments {
+                for (index, chunk) in chunks.iter().enumerate() {
+                    let payload = AttachmentChunk {
+                        inbox_name: inbox_name.to_string(),
+                        attachment_id: attachment.attachment_id.clone(),
+                        index: index as u32,
+                        chunk_count: attachment.chunk_count,
+                        data: base64::encode(chunk),
+                    };
+                    let message = match ShinkaiMessageBuilder::p2p_attachment_chunk(
+                        payload,
+                        clone_static_secret_key(encryption_secret_key),
+                        clone_signature_secret_key(signing_key),
+                        recipient_identity.node_encryption_public_key,
+                        node_name.get_node_name_string(),
+                        participant_node.get_node_name_string(),
+                    ) {
+                        Ok(message) => message,
+                        Err(e) => {
+                            shinkai_log(
+                                ShinkaiLogOption::Network,
+                                ShinkaiLogLevel::Error,
+                                &format!("Failed to build chunk {} of {}: {}", index, attachment.attachment_id, e),
+                            );
+                            continue;
+                        }
+                    };
+
+                    Node::send(
+                        message,
+                        Arc::new(clone_static_secret_key(encryption_secret_key)),
+                        (address, participant_node.get_node_name_string()),
+                        proxy_connection_info.clone(),
+                        db.clone(),
+                        identity_manager.clone(),
+                        ws_manager.clone(),
+                        false,
+                        None,
+                    );
+                }
+            }
+        }
+    }
+}
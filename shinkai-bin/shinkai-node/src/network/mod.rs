@@ -2,13 +2,27 @@ pub mod node;
 pub use node::Node;
 pub mod node_api_router;
 pub mod node_error;
+pub mod error_code;
+pub mod request_id;
+pub mod panic_isolation;
+pub mod node_events;
 pub mod ws_manager;
 pub mod ws_routes;
 pub mod node_shareable_logic;
 pub mod network_limiter;
+pub mod outbound_proxy;
+pub mod peer_reputation;
+pub mod inbound_message_filter;
 pub mod subscription_manager;
 pub mod network_manager;
 pub mod handle_commands_list;
 pub mod v1_api;
 pub mod v2_api;
-pub mod node_commands;
\ No newline at end of file
+pub mod node_commands;
+pub mod chat_bridge;
+pub mod workspace_manager;
+pub mod inbox_message_changes;
+pub mod message_attachments;
+#[cfg(feature = "grpc")]
+pub mod grpc_api;pub mod webdav;
+pub mod publishing;
@@ -5,6 +5,8 @@ use std::collections::HashMap;
 use governor::clock::DefaultClock;
 use governor::state::keyed::DefaultKeyedStateStore;
 
+use super::peer_reputation::{PeerOffense, PEER_REPUTATIONS};
+
 // Define a struct to hold your rate limiter and connection tracking.
 pub struct ConnectionLimiter {
     pub rate_limiter: RateLimiter<String, DefaultKeyedStateStore<String>, DefaultClock>,
@@ -24,15 +26,29 @@ impl ConnectionLimiter {
         }
     }
 
+    /// Peers banned for their offenses or by an admin
+    pub fn is_banned(&self, ip: &str) -> bool {
+        PEER_REPUTATIONS.is_banned(ip)
+    }
+
     pub async fn check_rate_limit(&self, ip: &str) -> bool {
         // Check the rate limit for a specific key (IP address)
-        self.rate_limiter.check_key(&ip.to_string()).is_ok()
+        let allowed = self.rate_limiter.check_key(&ip.to_string()).is_ok();
+        if !allowed {
+            PEER_REPUTATIONS.record(ip, None, PeerOffense::RateLimited);
+        }
+        allowed
     }
 
     pub async fn increment_connection(&self, ip: &str) -> bool {
+        // Deprioritized peers get a single connection at a time
+        let max_connections = match PEER_REPUTATIONS.is_deprioritized(ip) {
+            true => 1,
+            false => self.max_connections_per_ip,
+        };
         let mut connections = self.connections.lock().await;
         let entry = connections.entry(ip.to_string()).or_insert(0);
-        if *entry < self.max_connections_per_ip {
+        if *entry < max_connections {
             *entry += 1;
             true
         } else {
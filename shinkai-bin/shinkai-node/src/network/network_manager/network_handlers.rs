@@ -2,21 +2,34 @@ use crate::{
     db::ShinkaiDB,
     managers::IdentityManager,
     network::{
+        inbox_message_changes::{InboxMessageChangeError, InboxMessageChanges},
+        message_attachments::{MessageAttachmentError, MessageAttachments},
         node::ProxyConnectionInfo,
+        peer_reputation::{PeerOffense, PEER_REPUTATIONS},
         subscription_manager::{
             external_subscriber_manager::{ExternalSubscriberManager, SharedFolderInfo},
             fs_entry_tree::FSEntryTree,
             my_subscription_manager::MySubscriptionsManager,
+            subscriber_manager_error::SubscriberManagerError,
         },
+        workspace_manager::{WorkspaceError, WorkspaceManager},
         ws_manager::WSUpdateHandler,
         Node,
     },
+    payments::{
+        invoices::{InvoiceError, InvoiceManager},
+        wallet::WalletManager,
+    },
+    schemas::{inbox_message_change::InboxMessageChange, message_attachment::AttachmentChunk},
 };
 use ed25519_dalek::{SigningKey, VerifyingKey};
 use shinkai_message_primitives::{
     schemas::{
+        payment_invoice::{Invoice, PaymentReceipt},
+        shared_folder_sync::{SharedFolderPush, SharedFolderPushResult},
         shinkai_name::{ShinkaiName, ShinkaiNameError},
         shinkai_subscription::SubscriptionId,
+        workspace::{Workspace, WorkspaceInboxMessages},
     },
     shinkai_message::{
         shinkai_message::{MessageBody, MessageData, ShinkaiMessage},
@@ -37,6 +50,7 @@ use shinkai_message_primitives::{
 use std::sync::Arc;
 use std::{io, net::SocketAddr};
 use tokio::sync::Mutex;
+use tracing::instrument;
 use x25519_dalek::{PublicKey as EncryptionPublicKey, StaticSecret as EncryptionStaticKey};
 
 use super::network_job_manager_error::NetworkJobQueueError;
@@ -46,6 +60,7 @@ pub enum PingPong {
     Pong,
 }
 
+#[instrument(skip_all, fields(sender = %sender_profile_name))]
 #[allow(clippy::too_many_arguments)]
 pub async fn handle_based_on_message_content_and_encryption(
     message: ShinkaiMessage,
@@ -357,6 +372,12 @@ pub async fn handle_default_encryption(
             eprintln!("Failed to decrypt message: {:?}", e);
             eprintln!("Message: {:?}", message);
             println!("handle_default_encryption > Failed to decrypt message.");
+            // The signature of the sender was verified, so the offense is its own even if it came through a relay
+            PEER_REPUTATIONS.record(
+                &unsafe_sender_address.ip().to_string(),
+                Some(&sender_profile_name),
+                PeerOffense::InvalidMessage,
+            );
             // TODO: send error back?
             Ok(())
         }
@@ -416,6 +437,7 @@ pub async fn handle_network_message_cases(
         schema_result,
         Ok(MessageSchemaType::TextContent)
             | Ok(MessageSchemaType::JobMessageSchema)
+            | Ok(MessageSchemaType::MessageWithAttachments)
             | Ok(MessageSchemaType::SubscribeToSharedFolderResponse)
     ) || matches!(
         schema_result,
@@ -1021,6 +1043,290 @@ pub async fn handle_network_message_cases(
                         }
                     }
                 }
+                MessageSchemaType::PaymentInvoice => {
+                    let content = message.get_message_content().unwrap_or("".to_string());
+                    let result = match serde_json::from_str::<Invoice>(&content) {
+                        Ok(invoice) => {
+                            let sender = ShinkaiName::from_shinkai_message_only_using_sender_node_name(&message)
+                                .map_err(|e| ShinkaiNameError::InvalidNameFormat(e.to_string()))?;
+                            let my_node_name = ShinkaiName::new(my_node_full_name.to_string())
+                                .map_err(|e| ShinkaiNameError::InvalidNameFormat(e.to_string()))?;
+                            InvoiceManager::receive_invoice(&maybe_db, &my_node_name, &sender, invoice)
+                        }
+                        Err(e) => Err(InvoiceError::InvalidInput(e.to_string())),
+                    };
+                    match result {
+                        Ok(invoice) => {
+                            // Paid in its own task so the network queue isn't held up until the transaction confirms
+                            let db = maybe_db.clone();
+                            tokio::spawn(async move {
+                                match WalletManager::auto_pay_invoice(&db, &invoice).await {
+                                    Ok(Some(transaction)) => shinkai_log(
+                                        ShinkaiLogOption::Network,
+                                        ShinkaiLogLevel::Info,
+                                        &format!(
+                                            "PaymentInvoice Paid invoice {} automatically with transaction {}",
+                                            invoice.invoice_id, transaction.transaction_id
+                                        ),
+                                    ),
+                                    Ok(None) => {}
+                                    Err(e) => shinkai_log(
+                                        ShinkaiLogOption::Network,
+                                        ShinkaiLogLevel::Info,
+                                        &format!(
+                                            "PaymentInvoice Invoice {} wasn't paid automatically: {}",
+                                            invoice.invoice_id, e
+                                        ),
+                                    ),
+                                }
+                            });
+                        }
+                        Err(e) => {
+                            shinkai_log(
+                                ShinkaiLogOption::Network,
+                                ShinkaiLogLevel::Error,
+                                &format!("PaymentInvoice Failed to save the invoice: {}", e),
+                            );
+                        }
+                    }
+                    return Ok(());
+                }
+                MessageSchemaType::PaymentReceipt => {
+                    let content = message.get_message_content().unwrap_or("".to_string());
+                    let sender = ShinkaiName::from_shinkai_message_only_using_sender_node_name(&message)
+                        .map_err(|e| ShinkaiNameError::InvalidNameFormat(e.to_string()))?;
+                    let result = match serde_json::from_str::<PaymentReceipt>(&content) {
+                        Ok(receipt) => {
+                            // The receipt is checked against the key the payee has in the registry
+                            let sender_identity = maybe_identity_manager
+                                .lock()
+                                .await
+                                .external_profile_to_global_identity(&sender.get_node_name_string())
+                                .await
+                                .map_err(InvoiceError::NetworkError);
+                            sender_identity.and_then(|identity| {
+                                InvoiceManager::receive_receipt(
+                                    &maybe_db,
+                                    &sender,
+                                    receipt,
+                                    &identity.node_signature_public_key,
+                                )
+                            })
+                        }
+                        Err(e) => Err(InvoiceError::InvalidReceipt(e.to_string())),
+                    };
+                    if let Err(e) = result {
+                        shinkai_log(
+                            ShinkaiLogOption::Network,
+                            ShinkaiLogLevel::Error,
+                            &format!("PaymentReceipt Failed to save the receipt from {}: {}", sender, e),
+                        );
+                    }
+                    return Ok(());
+                }
+                MessageSchemaType::WorkspaceUpdate => {
+                    let content = message.get_message_content().unwrap_or("".to_string());
+                    let sender = ShinkaiName::from_shinkai_message_only_using_sender_node_name(&message)
+                        .map_err(|e| ShinkaiNameError::InvalidNameFormat(e.to_string()))?;
+                    let my_node_name = ShinkaiName::new(my_node_full_name.to_string())
+                        .map_err(|e| ShinkaiNameError::InvalidNameFormat(e.to_string()))?;
+                    let result = match serde_json::from_str::<Workspace>(&content) {
+                        Ok(workspace) => {
+                            WorkspaceManager::receive_update(
+                                &maybe_db,
+                                my_subscription_manager.clone(),
+                                &my_node_name,
+                                &sender,
+                                workspace,
+                            )
+                            .await
+                        }
+                        Err(e) => Err(WorkspaceError::InvalidInput(e.to_string())),
+                    };
+                    if let Err(e) = result {
+                        shinkai_log(
+                            ShinkaiLogOption::Network,
+                            ShinkaiLogLevel::Error,
+                            &format!("WorkspaceUpdate Failed to save the workspace from {}: {}", sender, e),
+                        );
+                    }
+                    return Ok(());
+                }
+                MessageSchemaType::WorkspaceInboxMessages => {
+                    let content = message.get_message_content().unwrap_or("".to_string());
+                    let sender = ShinkaiName::from_shinkai_message_only_using_sender_node_name(&message)
+                        .map_err(|e| ShinkaiNameError::InvalidNameFormat(e.to_string()))?;
+                    let my_node_name = ShinkaiName::new(my_node_full_name.to_string())
+                        .map_err(|e| ShinkaiNameError::InvalidNameFormat(e.to_string()))?;
+                    let result = match serde_json::from_str::<WorkspaceInboxMessages>(&content) {
+                        Ok(payload) => {
+                            WorkspaceManager::receive_inbox_messages(
+                                &maybe_db,
+                                &my_node_name,
+                                &sender,
+                                payload,
+                                ws_manager.clone(),
+                            )
+                            .await
+                        }
+                        Err(e) => Err(WorkspaceError::InvalidInput(e.to_string())),
+                    };
+                    if let Err(e) = result {
+                        shinkai_log(
+                            ShinkaiLogOption::Network,
+                            ShinkaiLogLevel::Error,
+                            &format!(
+                                "WorkspaceInboxMessages Failed to save the messages from {}: {}",
+                                sender, e
+                            ),
+                        );
+                    }
+                    return Ok(());
+                }
+                MessageSchemaType::SharedFolderPush => {
+                    let streamer_node_with_profile =
+                        ShinkaiName::from_shinkai_message_using_recipient_subidentity(&message)?;
+                    let requester_node_with_profile =
+                        ShinkaiName::from_shinkai_message_using_sender_subidentity(&message)?;
+                    let content = message.get_message_content().unwrap_or("".to_string());
+                    let push = match serde_json::from_str::<SharedFolderPush>(&content) {
+                        Ok(push) => push,
+                        Err(e) => {
+                            shinkai_log(
+                                ShinkaiLogOption::Network,
+                                ShinkaiLogLevel::Error,
+                                &format!("SharedFolderPush Failed to deserialize the push: {}", e),
+                            );
+                            return Ok(());
+                        }
+                    };
+                    let (push_id, shared_folder) = (push.push_id.clone(), push.shared_folder.clone());
+
+                    let result = external_subscription_manager
+                        .lock()
+                        .await
+                        .apply_shared_folder_push(
+                            requester_node_with_profile.clone(),
+                            streamer_node_with_profile.clone(),
+                            push,
+                        )
+                        .await
+                        .unwrap_or_else(|e| SharedFolderPushResult {
+                            push_id,
+                            shared_folder,
+                            accepted: Vec::new(),
+                            conflicts: Vec::new(),
+                            error: Some(e.to_string()),
+                        });
+
+                    let msg = ShinkaiMessageBuilder::p2p_shared_folder_sync_message(
+                        result,
+                        MessageSchemaType::SharedFolderPushResult,
+                        clone_static_secret_key(my_encryption_secret_key),
+                        clone_signature_secret_key(my_signature_secret_key),
+                        sender_encryption_pk,
+                        my_node_full_name.to_string(),
+                        streamer_node_with_profile
+                            .get_profile_name_string()
+                            .unwrap_or("".to_string()),
+                        requester_node_with_profile.get_node_name_string(),
+                        requester_node_with_profile
+                            .get_profile_name_string()
+                            .unwrap_or("".to_string()),
+                        None,
+                    )
+                    .map_err(|e| NetworkJobQueueError::Other(e.to_string()))?;
+
+                    Node::send(
+                        msg,
+                        Arc::new(clone_static_secret_key(my_encryption_secret_key)),
+                        (sender_address, requester_node_with_profile.get_node_name_string()),
+                        proxy_connection_info.clone(),
+                        maybe_db.clone(),
+                        maybe_identity_manager.clone(),
+                        ws_manager.clone(),
+                        false,
+                        None,
+                    );
+                    return Ok(());
+                }
+                MessageSchemaType::SharedFolderPushResult => {
+                    let my_profile = ShinkaiName::from_shinkai_message_using_recipient_subidentity(&message)?;
+                    let streamer_node_with_profile =
+                        ShinkaiName::from_shinkai_message_using_sender_subidentity(&message)?;
+                    let content = message.get_message_content().unwrap_or("".to_string());
+                    let result = match serde_json::from_str::<SharedFolderPushResult>(&content) {
+                        Ok(result) => MySubscriptionsManager::receive_shared_folder_push_result(
+                            &maybe_db,
+                            my_profile,
+                            &streamer_node_with_profile,
+                            result,
+                        ),
+                        Err(e) => Err(SubscriberManagerError::SerializationError(e.to_string())),
+                    };
+                    if let Err(e) = result {
+                        shinkai_log(
+                            ShinkaiLogOption::Network,
+                            ShinkaiLogLevel::Error,
+                            &format!(
+                                "SharedFolderPushResult Failed to save the result from {}: {}",
+                                streamer_node_with_profile, e
+                            ),
+                        );
+                    }
+                    return Ok(());
+                }
+                MessageSchemaType::InboxMessageChange => {
+                    let content = message.get_message_content().unwrap_or("".to_string());
+                    let sender = ShinkaiName::from_shinkai_message_only_using_sender_node_name(&message)
+                        .map_err(|e| ShinkaiNameError::InvalidNameFormat(e.to_string()))?;
+                    let result = match serde_json::from_str::<InboxMessageChange>(&content) {
+                        Ok(change) => InboxMessageChanges::receive_change(&maybe_db, &sender, change),
+                        Err(e) => Err(InboxMessageChangeError::InvalidInput(e.to_string())),
+                    };
+                    if let Err(e) = result {
+                        shinkai_log(
+                            ShinkaiLogOption::Network,
+                            ShinkaiLogLevel::Error,
+                            &format!("InboxMessageChange Failed to apply the change from {}: {}", sender, e),
+                        );
+                    }
+                    return Ok(());
+                }
+                MessageSchemaType::MessageWithAttachments => {
+                    // Saved above like any message, its chunks are only accepted once it's known
+                    let my_node_name = ShinkaiName::new(my_node_full_name.to_string())
+                        .map_err(|e| ShinkaiNameError::InvalidNameFormat(e.to_string()))?;
+                    if let Err(e) = MessageAttachments::receive_announcement(&maybe_db, &my_node_name, &message) {
+                        shinkai_log(
+                            ShinkaiLogOption::Network,
+                            ShinkaiLogLevel::Error,
+                            &format!(
+                                "MessageWithAttachments Failed to record the attachments from {}: {}",
+                                sender_profile_name, e
+                            ),
+                        );
+                    }
+                }
+                MessageSchemaType::AttachmentChunk => {
+                    let content = message.get_message_content().unwrap_or("".to_string());
+                    let sender = ShinkaiName::from_shinkai_message_only_using_sender_node_name(&message)
+                        .map_err(|e| ShinkaiNameError::InvalidNameFormat(e.to_string()))?;
+                    let my_node_name = ShinkaiName::new(my_node_full_name.to_string())
+                        .map_err(|e| ShinkaiNameError::InvalidNameFormat(e.to_string()))?;
+                    let result = match serde_json::from_str::<AttachmentChunk>(&content) {
+                        Ok(chunk) => MessageAttachments::receive_chunk(&maybe_db, &my_node_name, &sender, chunk),
+                        Err(e) => Err(MessageAttachmentError::InvalidInput(e.to_string())),
+                    };
+                    if let Err(e) = result {
+                        shinkai_log(
+                            ShinkaiLogOption::Network,
+                            ShinkaiLogLevel::Error,
+                            &format!("AttachmentChunk Failed to save the chunk from {}: {}", sender, e),
+                        );
+                    }
+                    return Ok(());
+                }
                 _ => {
                     // Ignore other schemas
                     shinkai_log(
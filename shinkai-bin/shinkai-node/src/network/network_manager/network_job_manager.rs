@@ -1,12 +1,15 @@
 use crate::db::{ShinkaiDB, Topic};
 use crate::llm_provider::queue::job_queue_manager::JobQueueManager;
 use crate::managers::IdentityManager;
+use crate::network::inbound_message_filter::INBOUND_MESSAGE_FILTER;
 use crate::network::node::ProxyConnectionInfo;
+use crate::network::peer_reputation::{PeerOffense, PEER_REPUTATIONS};
 use crate::network::subscription_manager::external_subscriber_manager::ExternalSubscriberManager;
 use crate::network::subscription_manager::fs_entry_tree::FSEntryTree;
 use crate::network::subscription_manager::fs_entry_tree_generator::FSEntryTreeGenerator;
 use crate::network::subscription_manager::my_subscription_manager::MySubscriptionsManager;
 use crate::network::ws_manager::{self, WSUpdateHandler};
+use crate::network::Node;
 use crate::vector_fs::vector_fs::VectorFS;
 use aes_gcm::aead::generic_array::GenericArray;
 use aes_gcm::aead::Aead;
@@ -17,10 +20,11 @@ use ed25519_dalek::SigningKey;
 use futures::Future;
 use serde::{Deserialize, Serialize};
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
-use shinkai_message_primitives::schemas::shinkai_network::NetworkMessageType;
+use shinkai_message_primitives::schemas::shinkai_network::{NetworkMessageType, PeerProtocol, ProtocolHello};
 use shinkai_message_primitives::schemas::shinkai_subscription::SubscriptionId;
 use shinkai_message_primitives::shinkai_utils::encryption::clone_static_secret_key;
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use tracing::instrument;
 use shinkai_message_primitives::shinkai_utils::signatures::clone_signature_secret_key;
 use shinkai_vector_resources::vector_resource::{VRPack, VRPath};
 use std::cmp::Ordering;
@@ -448,6 +452,30 @@ impl NetworkJobManager {
                 )
                 .await;
             }
+            NetworkMessageType::ProtocolHello => {
+                let proxy_connection_info = proxy_connection_info
+                    .upgrade()
+                    .ok_or(NetworkJobQueueError::ProxyConnectionInfoUpgradeFailed)?;
+                let hello: ProtocolHello = serde_json::from_slice(&job.content).map_err(|e| {
+                    PEER_REPUTATIONS.record(
+                        &job.unsafe_sender_address.ip().to_string(),
+                        None,
+                        PeerOffense::InvalidMessage,
+                    );
+                    NetworkJobQueueError::DeserializationFailed(e.to_string())
+                })?;
+
+                Self::handle_protocol_hello(
+                    hello,
+                    job.unsafe_sender_address,
+                    my_node_profile_name.get_node_name_string(),
+                    my_signature_secret_key,
+                    db.clone(),
+                    identity_manager.clone(),
+                    proxy_connection_info,
+                )
+                .await?;
+            }
             NetworkMessageType::VRKaiPathPair => {
                 shinkai_log(
                     ShinkaiLogOption::Network,
@@ -555,6 +583,22 @@ impl NetworkJobManager {
         let vr_pack_plus_changes: VRPackPlusChanges = bincode::deserialize(&decrypted_data)
             .map_err(|_| NetworkJobQueueError::DeserializationFailed("Failed to deserialize VRPack".to_string()))?;
 
+        // Keep the versions received so local changes can be pushed back to the folder
+        {
+            let maybe_db = db.upgrade().ok_or(NetworkJobQueueError::ShinkaDBUpgradeFailed)?;
+            if let Err(e) = MySubscriptionsManager::record_shared_folder_sync_bases(
+                &maybe_db,
+                &network_vr_pack.subscription_id,
+                &vr_pack_plus_changes.diff,
+            ) {
+                shinkai_log(
+                    ShinkaiLogOption::Network,
+                    ShinkaiLogLevel::Error,
+                    &format!("Failed to save the shared folder sync versions: {}", e),
+                );
+            }
+        }
+
         // Find destination path from my_subscripton
         let destination_path = {
             let path = if subscription.subscriber_destination_path.is_none() {
@@ -758,6 +802,7 @@ impl NetworkJobManager {
         Ok(())
     }
 
+    #[instrument(skip_all, fields(sender = %unsafe_sender_address))]
     #[allow(clippy::too_many_arguments)]
     pub async fn handle_message_internode(
         receiver_address: SocketAddr,
@@ -787,7 +832,11 @@ impl NetworkJobManager {
         );
 
         // Extract and validate the message
-        let message = extract_message(bytes, receiver_address)?;
+        let sender_ip = unsafe_sender_address.ip().to_string();
+        let message = extract_message(bytes, receiver_address).map_err(|e| {
+            PEER_REPUTATIONS.record(&sender_ip, None, PeerOffense::InvalidMessage);
+            e
+        })?;
         shinkai_log(
             ShinkaiLogOption::Node,
             ShinkaiLogLevel::Debug,
@@ -798,6 +847,17 @@ impl NetworkJobManager {
         let sender_profile_name_string = ShinkaiName::from_shinkai_message_only_using_sender_node_name(&message)
             .unwrap()
             .get_node_name_string();
+        if INBOUND_MESSAGE_FILTER.is_blocked(&sender_profile_name_string, &sender_ip) {
+            shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Info,
+                &format!(
+                    "{} > Dropped message of {} from {}, blocked after repeated failed checks",
+                    receiver_address, sender_profile_name_string, sender_ip
+                ),
+            );
+            return Ok(());
+        }
         let sender_identity = identity_manager
             .lock()
             .await
@@ -805,6 +865,7 @@ impl NetworkJobManager {
             .await;
 
         if let Err(e) = sender_identity {
+            INBOUND_MESSAGE_FILTER.record_failed_check(&sender_profile_name_string, &sender_ip);
             shinkai_log(
                 ShinkaiLogOption::Node,
                 ShinkaiLogLevel::Error,
@@ -818,7 +879,23 @@ impl NetworkJobManager {
 
         let sender_identity = sender_identity.unwrap();
 
-        verify_message_signature(sender_identity.node_signature_public_key, &message)?;
+        verify_message_signature(sender_identity.node_signature_public_key, &message).map_err(|e| {
+            PEER_REPUTATIONS.record(&sender_ip, None, PeerOffense::InvalidSignature);
+            INBOUND_MESSAGE_FILTER.record_failed_check(&sender_profile_name_string, &sender_ip);
+            e
+        })?;
+        INBOUND_MESSAGE_FILTER.record_passed_check(&sender_profile_name_string, &sender_ip);
+        if PEER_REPUTATIONS.is_banned(&sender_profile_name_string) {
+            shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Info,
+                &format!(
+                    "{} > Dropped message of banned peer {} from {}",
+                    receiver_address, sender_profile_name_string, sender_ip
+                ),
+            );
+            return Ok(());
+        }
 
         shinkai_log(
             ShinkaiLogOption::Node,
@@ -864,4 +941,80 @@ impl NetworkJobManager {
         )
         .await
     }
+
+    /// Saves the protocol a peer announced and answers with the one of this node, unless the hello was an answer
+    pub async fn handle_protocol_hello(
+        hello: ProtocolHello,
+        unsafe_sender_address: SocketAddr,
+        my_node_profile_name: String,
+        my_signature_secret_key: SigningKey,
+        db: Weak<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+    ) -> Result<(), NetworkJobQueueError> {
+        let db = db.upgrade().ok_or(NetworkJobQueueError::ShinkaDBUpgradeFailed)?;
+        let sender_node_name = ShinkaiName::new(hello.node_name.clone())
+            .map_err(|e| NetworkJobQueueError::Other(e.to_string()))?
+            .get_node_name_string();
+        let sender_identity = identity_manager
+            .lock()
+            .await
+            .external_profile_to_global_identity(&sender_node_name)
+            .await
+            .map_err(NetworkJobQueueError::Other)?;
+
+        // Anyone can send a hello, only the ones signed by the node they name are kept
+        if !hello.verify(&sender_identity.node_signature_public_key) {
+            shinkai_log(
+                ShinkaiLogOption::Network,
+                ShinkaiLogLevel::Error,
+                &format!("Protocol hello of {} has an invalid signature", sender_node_name),
+            );
+            PEER_REPUTATIONS.record(
+                &unsafe_sender_address.ip().to_string(),
+                None,
+                PeerOffense::InvalidSignature,
+            );
+            return Ok(());
+        }
+        // A signed hello can still be captured and sent again, so only recent ones newer than the last kept count
+        let last_protocol = db
+            .get_peer_protocol(&sender_node_name)
+            .map_err(|e| NetworkJobQueueError::DatabaseError(e.to_string()))?;
+        let is_replayed = last_protocol.map_or(false, |last| hello.timestamp <= last.announced_at);
+        if !hello.is_fresh(Utc::now()) || is_replayed {
+            shinkai_log(
+                ShinkaiLogOption::Network,
+                ShinkaiLogLevel::Error,
+                &format!(
+                    "Protocol hello of {} sent at {} is stale, ignoring it",
+                    sender_node_name, hello.timestamp
+                ),
+            );
+            return Ok(());
+        }
+        db.set_peer_protocol(&sender_node_name, &PeerProtocol::from_hello(&hello))
+            .map_err(|e| NetworkJobQueueError::DatabaseError(e.to_string()))?;
+        shinkai_log(
+            ShinkaiLogOption::Network,
+            ShinkaiLogLevel::Info,
+            &format!(
+                "{} speaks protocol version {} with frames up to version {}",
+                sender_node_name, hello.protocol_version, hello.max_frame_version
+            ),
+        );
+
+        if !hello.is_reply {
+            if let Some(sender_address) = sender_identity.addr {
+                Node::send_protocol_hello(
+                    ProtocolHello::new_signed(my_node_profile_name, true, &my_signature_secret_key),
+                    sender_address,
+                    sender_node_name,
+                    proxy_connection_info,
+                    identity_manager,
+                );
+            }
+        }
+        Ok(())
+    }
 }
@@ -1,3 +1,4 @@
+use super::chat_bridge::{ChatBridge, ChatBridgeConfig};
 use super::network_manager::network_job_manager::{
     NetworkJobManager, NetworkJobQueue, NetworkVRKai, VRPackPlusChanges,
 };
@@ -16,10 +17,17 @@ use crate::llm_provider::job_manager::JobManager;
 use crate::managers::identity_manager::IdentityManagerTrait;
 use crate::managers::sheet_manager::SheetManager;
 use crate::managers::IdentityManager;
+use crate::network::inbound_message_filter::{InboundMessageVerdict, INBOUND_MESSAGE_FILTER};
 use crate::network::network_limiter::ConnectionLimiter;
+use crate::network::outbound_proxy::OUTBOUND_PROXY;
+use crate::network::panic_isolation::{catch_panic, spawn_supervised};
+use crate::network::peer_reputation::{PeerOffense, PEER_REPUTATIONS};
+use crate::network::request_id::{spawn_with_request_id, with_request_id};
 use crate::network::ws_manager::WSUpdateHandler;
 use crate::network::ws_routes::run_ws_api;
+use crate::schemas::outbound_proxy::DestinationClass;
 use crate::tools::tool_router::ToolRouter;
+use crate::utils::environment::multilingual_embedding_generator;
 use crate::vector_fs::vector_fs::VectorFS;
 use aes_gcm::aead::generic_array::GenericArray;
 use aes_gcm::aead::Aead;
@@ -34,7 +42,10 @@ use futures::{future::FutureExt, pin_mut, prelude::*, select};
 use rand::Rng;
 use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider;
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
-use shinkai_message_primitives::schemas::shinkai_network::NetworkMessageType;
+use shinkai_message_primitives::schemas::shinkai_network::{
+    check_network_frame_version, encode_network_frame, NetworkFrameLengths, NetworkFrameLimits, NetworkMessageType,
+    ProtocolHello, NETWORK_FRAME_VERSION_1, NETWORK_FRAME_VERSION_MARKER,
+};
 use shinkai_message_primitives::schemas::shinkai_subscription::SubscriptionId;
 use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
 use shinkai_message_primitives::shinkai_utils::encryption::{
@@ -190,6 +201,11 @@ impl Node {
         // Setup Identity Manager
         let db_weak = Arc::downgrade(&db_arc);
         let subidentity_manager = IdentityManager::new(db_weak.clone(), node_name.clone()).await.unwrap();
+        subidentity_manager
+            .external_identity_manager
+            .lock()
+            .await
+            .enable_local_cache(db_weak.clone(), clone_signature_secret_key(&identity_secret_key));
         let identity_manager = Arc::new(Mutex::new(subidentity_manager));
 
         // Initialize default UnstructuredAPI/RemoteEmbeddingGenerator if none provided
@@ -205,10 +221,15 @@ impl Node {
             };
         }
 
-        // Initialize/setup the VectorFS.
+        // Initialize/setup the VectorFS. Documents in other languages than English are embedded with the
+        // multilingual model, if one is configured.
+        let mut vector_fs_embedding_models = vec![embedding_generator.model_type.clone()];
+        if let Some(multilingual_generator) = multilingual_embedding_generator() {
+            vector_fs_embedding_models.push(multilingual_generator.model_type());
+        }
         let vector_fs = VectorFS::new(
             embedding_generator.clone(),
-            vec![embedding_generator.model_type.clone()],
+            vector_fs_embedding_models,
             profile_list,
             &vector_fs_db_path,
             node_name.clone(),
@@ -218,6 +239,12 @@ impl Node {
             eprintln!("Error: {:?}", e);
             panic!("Failed to load VectorFS from database: {}", vector_fs_db_path)
         });
+        // Load the storage quotas of the profiles
+        for limits in db_arc.get_all_profile_limits().unwrap_or_default() {
+            vector_fs
+                .set_profile_quota(&limits.profile, limits.vector_fs_quota_bytes)
+                .await;
+        }
         let vector_fs_arc = Arc::new(vector_fs);
 
         let max_connections: u32 = std::env::var("MAX_CONNECTIONS")
@@ -421,6 +448,84 @@ impl Node {
         );
         let db_weak = Arc::downgrade(&self.db);
 
+        match self.db.get_peer_ban_overrides() {
+            Ok(overrides) => PEER_REPUTATIONS.load_overrides(overrides),
+            Err(e) => shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Error,
+                &format!("Failed to load the peer ban overrides: {}", e),
+            ),
+        }
+        match self.db.get_outbound_proxy_settings() {
+            Ok(settings) => OUTBOUND_PROXY.set_settings(settings),
+            Err(e) => shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Error,
+                &format!("Failed to load the outbound proxy settings: {}", e),
+            ),
+        }
+
+        if let Some(chat_bridge_config) = ChatBridgeConfig::from_env() {
+            ChatBridge::new(
+                chat_bridge_config,
+                self.db.clone(),
+                self.node_name.clone(),
+                self.identity_manager.clone(),
+                job_manager.clone(),
+                self.encryption_secret_key.clone(),
+                self.encryption_public_key,
+                clone_signature_secret_key(&self.identity_secret_key),
+            )
+            .start();
+        }
+
+        #[cfg(feature = "email")]
+        crate::cron_tasks::email_ingester::EmailIngester::start(
+            db_weak.clone(),
+            vector_fs_weak.clone(),
+            self.embedding_generator.clone(),
+        );
+
+        crate::cron_tasks::cloud_sync::CloudSync::start(
+            db_weak.clone(),
+            vector_fs_weak.clone(),
+            self.embedding_generator.clone(),
+        );
+
+        crate::cron_tasks::integrity_checker::IntegrityChecker::start(db_weak.clone(), vector_fs_weak.clone());
+
+        crate::cron_tasks::db_maintenance::DbMaintenance::start(db_weak.clone(), vector_fs_weak.clone());
+
+        crate::cron_tasks::inbox_titler::InboxTitler::start(db_weak.clone());
+
+        crate::cron_tasks::inbox_digester::InboxDigester::start(
+            db_weak.clone(),
+            self.node_name.clone(),
+            clone_signature_secret_key(&self.identity_secret_key),
+            self.ws_manager_trait.clone(),
+        );
+
+        crate::cron_tasks::tool_usage_billing::ToolUsageBilling::start(db_weak.clone(), self.node_name.clone());
+
+        crate::cron_tasks::idempotency_cleaner::IdempotencyCleaner::start(db_weak.clone());
+
+        crate::cron_tasks::workspace_sync::WorkspaceSync::start(
+            db_weak.clone(),
+            self.node_name.clone(),
+            self.encryption_secret_key.clone(),
+            clone_signature_secret_key(&self.identity_secret_key),
+            self.identity_manager.clone(),
+            self.proxy_connection_info.clone(),
+            self.ws_manager_trait.clone(),
+        );
+
+        #[cfg(feature = "folder-watcher")]
+        crate::managers::folder_watcher::FolderWatcher::start(
+            db_weak.clone(),
+            vector_fs_weak.clone(),
+            self.embedding_generator.clone(),
+        );
+
         let cron_manager_result = CronManager::new(
             db_weak.clone(),
             vector_fs_weak,
@@ -446,9 +551,8 @@ impl Node {
             // Starting the WebSocket server
             if let (Some(ws_manager), Some(ws_address)) = (&self.ws_manager, self.ws_address) {
                 let ws_manager = Arc::clone(ws_manager);
-                let ws_server = tokio::spawn(async move {
-                    run_ws_api(ws_address, ws_manager).await;
-                });
+                let ws_server =
+                    spawn_supervised("WebSocket server", move || run_ws_api(ws_address, ws_manager.clone()));
                 self.ws_server = Some(ws_server);
             }
         }
@@ -550,8 +654,16 @@ impl Node {
                     },
                     // check_peers = check_peers_future => self.connect_new_peers().await,
                     command = commands_future => {
-                        if let Some(command) = command {
-                            self.handle_command(command).await;
+                        match command {
+                            // A panic while dispatching a command must not stop the loop
+                            Some(NodeCommand::WithRequestId { request_id, command }) => {
+                                let handled = catch_panic("Node command", self.handle_command(*command));
+                                let _ = with_request_id(request_id, handled).await;
+                            }
+                            Some(command) => {
+                                let _ = catch_panic("Node command", self.handle_command(command)).await;
+                            }
+                            None => {}
                         }
                     }
             };
@@ -638,6 +750,11 @@ impl Node {
                             identity_manager.clone(),
                         )
                         .await;
+
+                        // The connection was lost, so it isn't used to send messages until it's reestablished
+                        if let Some(ref mut proxy_info) = *proxy_connection_info.lock().await {
+                            proxy_info.tcp_connection = None;
+                        }
                     }
                     Ok(None) | Err(_) => {
                         // Increment retry count and determine sleep duration
@@ -702,10 +819,13 @@ impl Node {
             }
         };
 
+        // Everything forwarded by the proxy comes from its IP, so offenses are never held against it
+        PEER_REPUTATIONS.add_relay(&proxy_addr.ip().to_string());
+
         let node_name = node_name.clone();
         let signing_sk = identity_secret_key.clone();
 
-        match TcpStream::connect(proxy_addr).await {
+        match OUTBOUND_PROXY.connect(DestinationClass::Node, proxy_addr).await {
             Ok(proxy_stream) => {
                 shinkai_log(
                     ShinkaiLogOption::Node,
@@ -767,6 +887,7 @@ impl Node {
         }
 
         // Handle the connection
+        let frame_limits = NetworkFrameLimits::new_from_env();
         loop {
             let reader_clone = Arc::clone(&reader);
             let network_job_manager_clone = Arc::clone(&network_job_manager);
@@ -788,7 +909,7 @@ impl Node {
                         return Err(io::Error::new(io::ErrorKind::Other, e));
                     }
                 };
-                Self::handle_connection(reader_clone, proxy_addr, network_job_manager_clone)
+                Self::handle_connection(reader_clone, proxy_addr, network_job_manager_clone, frame_limits, None)
                     .await
                     .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{:?}", e)))?;
                 Ok::<(), std::io::Error>(())
@@ -819,6 +940,7 @@ impl Node {
         _node_name: ShinkaiName,
     ) -> io::Result<()> {
         let listener = TcpListener::bind(&listen_address).await?;
+        let frame_limits = NetworkFrameLimits::new_from_env();
 
         shinkai_log(
             ShinkaiLogOption::Node,
@@ -834,6 +956,15 @@ impl Node {
             let ip = addr.ip().to_string();
             let conn_limiter_clone = conn_limiter.clone();
 
+            if conn_limiter_clone.is_banned(&ip) {
+                shinkai_log(
+                    ShinkaiLogOption::Node,
+                    ShinkaiLogLevel::Info,
+                    &format!("Refused connection from banned IP: {}", ip),
+                );
+                continue;
+            }
+
             if !conn_limiter_clone.check_rate_limit(&ip).await {
                 shinkai_log(
                     ShinkaiLogOption::Node,
@@ -864,7 +995,14 @@ impl Node {
             tokio::spawn(async move {
                 let (reader, _writer) = tokio::io::split(socket);
                 let reader = Arc::new(Mutex::new(reader));
-                let _ = Self::handle_connection(reader, addr, network_job_manager).await;
+                let _ = Self::handle_connection(
+                    reader,
+                    addr,
+                    network_job_manager,
+                    frame_limits,
+                    Some(frame_limits.read_timeout),
+                )
+                .await;
                 conn_limiter_clone.decrement_connection(&ip).await;
             });
         }
@@ -895,63 +1033,61 @@ impl Node {
         reader: Arc<Mutex<ReadHalf<TcpStream>>>,
         addr: SocketAddr,
         network_job_manager: Arc<Mutex<NetworkJobManager>>,
+        frame_limits: NetworkFrameLimits,
+        // Direct connections are dropped if no frame starts in time, the connection to the proxy waits for frames
+        idle_timeout: Option<Duration>,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let start_time = Utc::now();
-        let mut length_bytes = [0u8; 4];
+        let mut first_bytes = [0u8; 4];
         {
             let mut reader = reader.lock().await;
-            reader.read_exact(&mut length_bytes).await?;
-            let total_length = u32::from_be_bytes(length_bytes) as usize;
-
-            // Read the identity length
-            let mut identity_length_bytes = [0u8; 4];
-            reader.read_exact(&mut identity_length_bytes).await?;
-            let identity_length = u32::from_be_bytes(identity_length_bytes) as usize;
-
-            // Read the identity bytes
-            let mut identity_bytes = vec![0u8; identity_length];
-            reader.read_exact(&mut identity_bytes).await?;
-
-            // Calculate the message length excluding the identity length and the identity itself
-            let msg_length = total_length - 1 - 4 - identity_length; // Subtract 1 for the header and 4 for the identity length bytes
-
-            // Read the header byte to determine the message type
-            let mut header_byte = [0u8; 1];
-            reader.read_exact(&mut header_byte).await?;
-            let message_type = match header_byte[0] {
-                0x01 => NetworkMessageType::ShinkaiMessage,
-                0x02 => NetworkMessageType::VRKaiPathPair,
-                0x03 => NetworkMessageType::ProxyMessage,
-                _ => {
-                    shinkai_log(
-                        ShinkaiLogOption::Node,
-                        ShinkaiLogLevel::Error,
-                        "Received message with unknown type identifier",
-                    );
-                    return Err("Unknown message type".into());
-                }
+            match idle_timeout {
+                Some(idle_timeout) => tokio::time::timeout(idle_timeout, reader.read_exact(&mut first_bytes)).await??,
+                None => reader.read_exact(&mut first_bytes).await?,
             };
 
-            if msg_length == 0 {
+            // The rest of the frame must arrive in time, else a peer could hold the connection sending nothing
+            let read_frame = Self::read_network_frame(&mut reader, first_bytes, frame_limits.max_frame_length);
+            let (frame_version, message_type, buffer) =
+                match tokio::time::timeout(frame_limits.read_timeout, read_frame).await {
+                    Ok(Ok(frame)) => frame,
+                    Ok(Err(e)) => {
+                        shinkai_log(
+                            ShinkaiLogOption::Node,
+                            ShinkaiLogLevel::Error,
+                            &format!("Received invalid message from {:?}: {}", addr, e),
+                        );
+                        PEER_REPUTATIONS.record(&addr.ip().to_string(), None, PeerOffense::InvalidMessage);
+                        return Err(e);
+                    }
+                    Err(e) => {
+                        shinkai_log(
+                            ShinkaiLogOption::Node,
+                            ShinkaiLogLevel::Error,
+                            &format!("Timed out reading message from {:?}", addr),
+                        );
+                        PEER_REPUTATIONS.record(&addr.ip().to_string(), None, PeerOffense::InvalidMessage);
+                        return Err(e.into());
+                    }
+                };
+            if buffer.is_empty() {
                 return Ok(()); // Exit, unless there is a message_type without body
             }
 
-            // Initialize buffer to fit the message
-            let mut buffer = vec![0u8; msg_length];
-
-            // Read the rest of the message into the buffer
-            reader.read_exact(&mut buffer).await?;
             shinkai_log(
                 ShinkaiLogOption::Node,
                 ShinkaiLogLevel::Info,
-                &format!("Received message of type {:?} from: {:?}", message_type, addr),
+                &format!(
+                    "Received message of type {:?} (frame version {}) from: {:?}",
+                    message_type, frame_version, addr
+                ),
             );
 
             let network_job = NetworkJobQueue {
                 receiver_address: addr, // TODO: this should be my socketaddr!
                 unsafe_sender_address: addr,
                 message_type,
-                content: buffer.clone(), // Now buffer does not include the header
+                content: buffer, // Now buffer does not include the header
                 date_created: Utc::now(),
             };
 
@@ -970,6 +1106,47 @@ impl Node {
         Ok(())
     }
 
+    /// Reads the rest of a frame whose first 4 bytes were read, which are either the total length of a version 1
+    /// frame or the marker of a versioned frame. Returns the frame version, the message type and the payload.
+    async fn read_network_frame(
+        reader: &mut ReadHalf<TcpStream>,
+        first_bytes: [u8; 4],
+        max_frame_length: usize,
+    ) -> Result<(u8, NetworkMessageType, Vec<u8>), Box<dyn std::error::Error>> {
+        let (frame_version, length_bytes) = match first_bytes {
+            NETWORK_FRAME_VERSION_MARKER => {
+                let mut version_byte = [0u8; 1];
+                reader.read_exact(&mut version_byte).await?;
+                let frame_version = check_network_frame_version(version_byte[0])?;
+                let mut length_bytes = [0u8; 4];
+                reader.read_exact(&mut length_bytes).await?;
+                (frame_version, length_bytes)
+            }
+            length_bytes => (NETWORK_FRAME_VERSION_1, length_bytes),
+        };
+
+        // Read the identity length
+        let mut identity_length_bytes = [0u8; 4];
+        reader.read_exact(&mut identity_length_bytes).await?;
+
+        // Check the lengths before allocating anything, the peer could announce any of them
+        let lengths = NetworkFrameLengths::parse_with_limit(length_bytes, identity_length_bytes, max_frame_length)?;
+
+        // Read the identity bytes
+        let mut identity_bytes = vec![0u8; lengths.identity_length];
+        reader.read_exact(&mut identity_bytes).await?;
+
+        // Read the header byte to determine the message type
+        let mut header_byte = [0u8; 1];
+        reader.read_exact(&mut header_byte).await?;
+        let message_type = NetworkMessageType::from_header_byte(header_byte[0])?;
+
+        // Read the rest of the message, without the header
+        let mut buffer = vec![0u8; lengths.payload_length];
+        reader.read_exact(&mut buffer).await?;
+        Ok((frame_version, message_type, buffer))
+    }
+
     async fn retry_messages(
         db: Arc<ShinkaiDB>,
         encryption_secret_key: EncryptionStaticKey,
@@ -1048,8 +1225,11 @@ impl Node {
         let address = peer.0;
         let message = Arc::new(message);
 
-        tokio::spawn(async move {
+        // Keeps the correlation ID of the request that sent the message in the logs of the delivery
+        spawn_with_request_id(async move {
             let start_time = Utc::now();
+            let frame_version =
+                Node::frame_version_for(&db, &proxy_connection_info, &message.external_metadata.recipient).await;
             let writer_start_time = Utc::now();
             let writer = Node::get_writer(address, proxy_connection_info, maybe_identity_manager.clone()).await;
             let writer_end_time = Utc::now(); // End time for get_writer
@@ -1063,19 +1243,12 @@ impl Node {
             if let Some(writer) = writer {
                 let encoded_msg = message.encode_message().unwrap();
                 let identity = &message.external_metadata.recipient;
-                let identity_bytes = identity.as_bytes();
-                let identity_length = (identity_bytes.len() as u32).to_be_bytes();
-
-                // Prepare the message with a length prefix and identity length
-                let total_length = (encoded_msg.len() as u32 + 1 + identity_bytes.len() as u32 + 4).to_be_bytes(); // Convert the total length to bytes, adding 1 for the header and 4 for the identity length
-
-                let mut data_to_send = Vec::new();
-                let header_data_to_send = vec![0x01]; // Message type identifier for ShinkaiMessage
-                data_to_send.extend_from_slice(&total_length);
-                data_to_send.extend_from_slice(&identity_length);
-                data_to_send.extend(identity_bytes);
-                data_to_send.extend(header_data_to_send);
-                data_to_send.extend_from_slice(&encoded_msg);
+                let data_to_send = encode_network_frame(
+                    frame_version,
+                    identity,
+                    &NetworkMessageType::ShinkaiMessage,
+                    &encoded_msg,
+                );
 
                 {
                     let mut writer = writer.lock().await;
@@ -1134,7 +1307,8 @@ impl Node {
                 None
             }
         } else {
-            match tokio::time::timeout(Duration::from_secs(4), TcpStream::connect(address)).await {
+            let connect = OUTBOUND_PROXY.connect(DestinationClass::Node, address);
+            match tokio::time::timeout(Duration::from_secs(4), connect).await {
                 Ok(Ok(stream)) => {
                     let (_, writer) = tokio::io::split(stream);
                     Some(Arc::new(Mutex::new(writer)))
@@ -1159,6 +1333,58 @@ impl Node {
         }
     }
 
+    /// Frame version to send to a peer, the latest both nodes read. Frames through a proxy stay in version 1 since
+    /// the proxy may not read later ones.
+    async fn frame_version_for(
+        db: &ShinkaiDB,
+        proxy_connection_info: &Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        recipient: &str,
+    ) -> u8 {
+        if proxy_connection_info.lock().await.is_some() {
+            return NETWORK_FRAME_VERSION_1;
+        }
+        let node_name = match ShinkaiName::new(recipient.to_string()) {
+            Ok(name) => name.get_node_name_string(),
+            Err(_) => return NETWORK_FRAME_VERSION_1,
+        };
+        match db.get_peer_protocol(&node_name) {
+            Ok(Some(protocol)) => protocol.frame_version(),
+            _ => NETWORK_FRAME_VERSION_1,
+        }
+    }
+
+    /// Sends the protocol hello of this node to a peer, on a connection of its own since nodes which don't know
+    /// hellos drop the connection they got one on. Hellos aren't sent through a proxy, which may not know them either.
+    pub fn send_protocol_hello(
+        hello: ProtocolHello,
+        peer: SocketAddr,
+        recipient: String,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        maybe_identity_manager: Arc<Mutex<IdentityManager>>,
+    ) {
+        spawn_with_request_id(async move {
+            if proxy_connection_info.lock().await.is_some() {
+                return;
+            }
+            let payload = match serde_json::to_vec(&hello) {
+                Ok(payload) => payload,
+                Err(_) => return,
+            };
+            let data_to_send = encode_network_frame(
+                NETWORK_FRAME_VERSION_1,
+                &recipient,
+                &NetworkMessageType::ProtocolHello,
+                &payload,
+            );
+
+            if let Some(writer) = Node::get_writer(peer, proxy_connection_info, maybe_identity_manager).await {
+                let mut writer = writer.lock().await;
+                let _ = writer.write_all(&data_to_send).await;
+                let _ = writer.flush().await;
+            }
+        });
+    }
+
     pub async fn send_encrypted_vrpack(
         vr_pack_plus_changes: VRPackPlusChanges,
         subscription_id: SubscriptionId,
@@ -1168,7 +1394,7 @@ impl Node {
         maybe_identity_manager: Arc<Mutex<IdentityManager>>,
         recipient: ShinkaiName,
     ) {
-        tokio::spawn(async move {
+        spawn_with_request_id(async move {
             // Serialize only the VRKaiPath pairs
             let serialized_data = bincode::serialize(&vr_pack_plus_changes).unwrap();
             let encryption_key = hex::decode(encryption_key_hex.clone()).unwrap();
@@ -1201,19 +1427,12 @@ impl Node {
             let vr_kai_serialized = bincode::serialize(&vr_kai).unwrap();
 
             let identity = recipient.get_node_name_string();
-            let identity_bytes = identity.as_bytes();
-            let identity_length = (identity_bytes.len() as u32).to_be_bytes();
-
-            // Prepare the message with a length prefix, identity length, and identity
-            let total_length = (vr_kai_serialized.len() as u32 + 1 + identity_bytes.len() as u32 + 4).to_be_bytes(); // Convert the total length to bytes, adding 1 for the header and 4 for the identity length
-
-            let mut data_to_send = Vec::new();
-            let header_data_to_send = vec![0x02]; // Network Message type identifier for VRKaiPathPair
-            data_to_send.extend_from_slice(&total_length);
-            data_to_send.extend_from_slice(&identity_length);
-            data_to_send.extend(identity_bytes);
-            data_to_send.extend(header_data_to_send);
-            data_to_send.extend_from_slice(&vr_kai_serialized);
+            let data_to_send = encode_network_frame(
+                NETWORK_FRAME_VERSION_1,
+                &identity,
+                &NetworkMessageType::VRKaiPathPair,
+                &vr_kai_serialized,
+            );
 
             // Get the stream using the get_stream function
             let writer = Node::get_writer(peer, proxy_connection_info, maybe_identity_manager).await;
@@ -1318,6 +1537,30 @@ impl Node {
             ShinkaiLogLevel::Info,
             &format!("save_to_db> message_to_save: {:?}", message_to_save.clone()),
         );
+        if !am_i_sender {
+            match INBOUND_MESSAGE_FILTER.check(&db, &message_to_save) {
+                Ok(InboundMessageVerdict::Drop(reason)) => {
+                    shinkai_log(
+                        ShinkaiLogOption::Node,
+                        ShinkaiLogLevel::Info,
+                        &format!(
+                            "save_to_db> Dropped message {} by the inbound message policy: {}",
+                            message_to_save.calculate_message_hash_for_pagination(),
+                            reason
+                        ),
+                    );
+                    return Ok(());
+                }
+                Ok(_) => (),
+                Err(e) => {
+                    shinkai_log(
+                        ShinkaiLogOption::Node,
+                        ShinkaiLogLevel::Error,
+                        &format!("Failed to apply the inbound message policy: {}", e),
+                    );
+                }
+            }
+        }
         let db_result = db.unsafe_insert_inbox_message(&message_to_save, None, ws_manager).await;
         match db_result {
             Ok(_) => (),
@@ -1337,25 +1580,8 @@ impl Node {
 
     async fn send_network_message(writer: Arc<Mutex<WriteHalf<TcpStream>>>, msg: &NetworkMessage) {
         eprintln!("send_network_message> Sending message: {:?}", msg);
-        let encoded_msg = msg.payload.clone();
-        let identity = &msg.identity;
-        let identity_bytes = identity.as_bytes();
-        let identity_length = (identity_bytes.len() as u32).to_be_bytes();
-
-        // Prepare the message with a length prefix and identity length
-        let total_length = (encoded_msg.len() as u32 + 1 + identity_bytes.len() as u32 + 4).to_be_bytes();
-
-        let mut data_to_send = Vec::new();
-        let header_data_to_send = vec![match msg.message_type {
-            NetworkMessageType::ShinkaiMessage => 0x01,
-            NetworkMessageType::VRKaiPathPair => 0x02,
-            NetworkMessageType::ProxyMessage => 0x03,
-        }];
-        data_to_send.extend_from_slice(&total_length);
-        data_to_send.extend_from_slice(&identity_length);
-        data_to_send.extend(identity_bytes);
-        data_to_send.extend(header_data_to_send);
-        data_to_send.extend_from_slice(&encoded_msg);
+        let data_to_send =
+            encode_network_frame(NETWORK_FRAME_VERSION_1, &msg.identity, &msg.message_type, &msg.payload);
 
         // Print the name and length of each component
         let mut writer = writer.lock().await;
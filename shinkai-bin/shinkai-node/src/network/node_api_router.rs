@@ -1,5 +1,9 @@
+use super::error_code::ErrorCode;
 use super::node_commands::NodeCommand;
+use super::node_error::NodeError;
+use super::request_id::{request_id_or_new, with_request_id, REQUEST_ID_HEADER};
 use super::v1_api::api_v1_router::v1_routes;
+use super::v2_api::api_v2_handlers_openai::openai_routes;
 use super::v2_api::api_v2_router::v2_routes;
 use async_channel::Sender;
 use reqwest::StatusCode;
@@ -8,9 +12,13 @@ use serde_json::json;
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::shinkai_log;
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::ShinkaiLogLevel;
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::ShinkaiLogOption;
+use std::convert::Infallible;
 use std::env;
 use std::net::SocketAddr;
 use utoipa::ToSchema;
+use warp::http::HeaderValue;
+use warp::hyper::service::{make_service_fn, service_fn, Service};
+use warp::hyper::{Body, Request, Server};
 use warp::Filter;
 
 #[derive(serde::Serialize, ToSchema, Debug, Clone)]
@@ -37,6 +45,8 @@ pub struct GetPublicKeysResponse {
 #[derive(Serialize, ToSchema, Debug, Clone)]
 pub struct APIError {
     pub code: u16,
+    /// Stable code to branch on, the message is only meant for humans
+    pub error_code: ErrorCode,
     pub error: String,
     pub message: String,
 }
@@ -45,16 +55,35 @@ impl APIError {
     pub fn new(code: StatusCode, error: &str, message: &str) -> Self {
         Self {
             code: code.as_u16(),
+            error_code: ErrorCode::from_status(code.as_u16()),
             error: error.to_string(),
             message: message.to_string(),
         }
     }
+
+    /// Error with the HTTP status of the error code
+    pub fn from_code(error_code: ErrorCode, message: &str) -> Self {
+        let status = error_code.status();
+        Self {
+            code: status.as_u16(),
+            error_code,
+            error: status.canonical_reason().unwrap_or_default().to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<NodeError> for APIError {
+    fn from(error: NodeError) -> Self {
+        APIError::from_code(error.error_code, &error.message)
+    }
 }
 
 impl From<&str> for APIError {
     fn from(error: &str) -> Self {
         APIError {
             code: StatusCode::BAD_REQUEST.as_u16(),
+            error_code: ErrorCode::BadRequest,
             error: "Bad Request".to_string(),
             message: error.to_string(),
         }
@@ -65,6 +94,7 @@ impl From<async_channel::SendError<NodeCommand>> for APIError {
     fn from(error: async_channel::SendError<NodeCommand>) -> Self {
         APIError {
             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            error_code: ErrorCode::InternalError,
             error: "Internal Server Error".to_string(),
             message: format!("Failed with error: {}", error),
         }
@@ -75,6 +105,7 @@ impl From<String> for APIError {
     fn from(error: String) -> Self {
         APIError {
             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            error_code: ErrorCode::InternalError,
             error: "Internal Server Error".to_string(),
             message: error,
         }
@@ -99,8 +130,7 @@ pub async fn run_api(
             ShinkaiLogOption::Api,
             ShinkaiLogLevel::Debug,
             &format!(
-                "ip: {:?}, method: {:?}, path: {:?}, status: {:?}, elapsed: {:?}",
-                info.remote_addr(),
+                "method: {:?}, path: {:?}, status: {:?}, elapsed: {:?}",
                 info.method(),
                 info.path(),
                 info.status(),
@@ -112,7 +142,13 @@ pub async fn run_api(
     let cors = warp::cors()
         .allow_any_origin()
         .allow_methods(vec!["GET", "POST", "OPTIONS"])
-        .allow_headers(vec!["Content-Type", "Authorization"]);
+        .allow_headers(vec![
+            "Content-Type",
+            "Authorization",
+            "Idempotency-Key",
+            REQUEST_ID_HEADER,
+        ])
+        .expose_headers(vec![REQUEST_ID_HEADER]);
 
     let v1_routes = warp::path("v1").and(
         v1_routes(node_commands_sender.clone(), node_name.clone())
@@ -130,20 +166,60 @@ pub async fn run_api(
                 .with(cors.clone()),
         );
 
+        // The OpenAI compatible routes share the /v1 prefix, so they go before the v1 routes, which recover
+        // every rejection under it
+        let openai_routes = openai_routes(node_commands_sender.clone());
+
         // Combine all routes
-        let routes = v1_routes.or(v2_routes).with(log).with(cors);
+        let routes = openai_routes.or(v1_routes).or(v2_routes).with(log).with(cors);
 
-        warp::serve(routes).run(address).await;
+        serve_with_request_ids(routes, address).await?;
     } else {
         // Combine all routes
         let routes = v1_routes.with(log).with(cors);
 
-        warp::serve(routes).run(address).await;
+        serve_with_request_ids(routes, address).await?;
     }
 
     Ok(())
 }
 
+/// Serves the routes, handling each request with its correlation ID: the one in the `x-request-id` header or a new
+/// one. The ID is returned in the same header of the response.
+async fn serve_with_request_ids<F>(
+    routes: F,
+    address: SocketAddr,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+where
+    F: Filter<Error = warp::Rejection> + Clone + Send + Sync + 'static,
+    F::Extract: warp::Reply,
+{
+    let service = warp::service(routes);
+    let make_service = make_service_fn(move |_| {
+        let service = service.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let mut service = service.clone();
+                let request_id = request_id_or_new(
+                    request
+                        .headers()
+                        .get(REQUEST_ID_HEADER)
+                        .and_then(|value| value.to_str().ok()),
+                );
+                async move {
+                    let mut response = with_request_id(request_id.clone(), service.call(request)).await?;
+                    if let Ok(value) = HeaderValue::from_str(&request_id) {
+                        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+                    }
+                    Ok::<_, Infallible>(response)
+                }
+            }))
+        }
+    });
+    Server::try_bind(&address)?.serve(make_service).await?;
+    Ok(())
+}
+
 pub async fn handle_node_command<T, U, V>(
     node_commands_sender: Sender<NodeCommand>,
     message: V,
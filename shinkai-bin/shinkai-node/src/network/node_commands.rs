@@ -1,4 +1,4 @@
-use std::{collections::HashMap, net::SocketAddr};
+use std::collections::HashMap;
 
 use async_channel::Sender;
 use chrono::{DateTime, Utc};
@@ -6,31 +6,62 @@ use ed25519_dalek::VerifyingKey;
 use serde_json::Value;
 use shinkai_message_primitives::{
     schemas::{
-        llm_providers::serialized_llm_provider::SerializedLLMProvider, shinkai_name::ShinkaiName,
+        contact::{Contact, ContactBook},
+        folder_publication::FolderPublication,
+        job_citation::JobCitation,
+        llm_providers::serialized_llm_provider::SerializedLLMProvider,
+        payment_invoice::{Invoice, InvoiceStatus},
+        retrieval_config::RetrievalConfig,
+        shinkai_name::ShinkaiName,
         shinkai_subscription::ShinkaiSubscription,
+        workspace::Workspace,
     },
+    shinkai_utils::shinkai_logging::{LogEntry, LogLevelSetting},
     shinkai_message::{
         shinkai_message::ShinkaiMessage,
         shinkai_message_schemas::{
-            APIAddOllamaModels, APIAvailableSharedItems, APIChangeJobAgentRequest, APIConvertFilesAndSaveToFolder, APICreateShareableFolder, APIGetLastNotifications, APIGetMySubscribers, APIGetNotificationsBeforeTimestamp, APISetWorkflow, APISubscribeToSharedFolder, APIUnshareFolder, APIUnsubscribeToSharedFolder, APIUpdateShareableFolder, APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsDeleteFolder, APIVecFsDeleteItem, APIVecFsMoveFolder, APIVecFsMoveItem, APIVecFsRetrievePathSimplifiedJson, APIVecFsSearchItems, APIWorkflowKeyname, IdentityPermissions, JobCreationInfo, JobMessage, RegistrationCodeType, V2ChatMessage
+            APIAcceptMessageRequest, APIAddOllamaModels, APIAvailableSharedItems, APICancelOperation, APIChangeJobAgentRequest, APICompleteWalletTransaction, APIConvertFilesAndSaveToFolder, APICreateInvoice, APICreateJobForPath, APICreateShareableFolder, APICreateWorkspace, APIDeleteProfile, APIExportProfileData, APIGetJobStatus, APISetJobPlanning, APISetJobRetrievalConfig, APISetFolderAgent, FolderAgentBinding, APIGetLastNotifications, APIGetMySubscribers, APIGetOperationStatus, APIGetRecentLogs, APIGetNotificationsBeforeTimestamp, APIInitializeNodeInteractive, APIInstallToolkitFromURL, APIMarkInvoicePaid, APIPayInvoice, APIPublishFolder, APIPushSharedFolderChanges, APIRelocateStorage, APIRemoveCloudConnector, APIRemoveContact, APIRemoveWatchedFolder, APIRenameDevice, APIRevokeDevice, APIRevokeRegistrationCode, APIRunDbMaintenance, APISetContact, APISetContactsAllowlistMode, APISetPeerBan, APISetSharedFolderWriters, APISetWorkflow, APISetWorkspaceMember, APISubscribeToSharedFolder, APIUnpublishFolder, APIUnshareFolder, APIUnsubscribeToSharedFolder, APIUpdateShareableFolder, APIUpdateWorkspace, APIVecFSDiffItemVersion, APIVecFSExportFolderAsVRPack, APIVecFSExportMarkdownBundle, APIVecFSGetFolderStats, APIVecFSGetItemVersions, APIVecFSImportMarkdownBundle, APIVecFSRestoreItemVersion, APIVecFSSetFolderEmbeddingModel, APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsCreateLink, APIVecFsDeleteFolder, APIVecFsDeleteItem, APIVecFsMoveFolder, APIVecFsMoveItem, APIVecFsRetrievePathSimplifiedJson, APIVecFsRetrieveSourceFileMap, APIVecFsRetrieveVectorSearchSimplifiedJson, APIVecFsSearchItems, APIWorkflowKeyname, APIWorkspaceInvitation, IdentityPermissions, JobCreationInfo, JobMessage, RegistrationCodeRequest, RegistrationCodeType, V2ChatMessage
         },
     },
 };
 
-use crate::{schemas::{
-    identity::{Identity, StandardIdentity},
-    smart_inbox::{SmartInbox, V2SmartInbox},
+use crate::{llm_provider::{job_status::JobStatus, local_inference_scheduler::LocalInferenceMetrics}, managers::{node_diagnostics::DiagnosticsReport, node_health::NodeHealth, node_metrics::NodeMetrics, node_onboarding::{LLMProviderTestResult, OnboardingKeys}, operation_registry::OperationStatus}, vector_fs::{vector_fs_stats::FolderStats, vector_fs_types::{FSItemMetadataChange, FSItemVersion}}, schemas::{
+    db_maintenance::DbMaintenanceReport,
+    identity::{DeviceInfo, Identity, StandardIdentity},
+    inbound_message_policy::InboundMessagePolicy,
+    inbox_digest::{InboxDigestSchedule, InboxDigestSettings},
+    inbox_message_change::{InboxMessageChange, MessageChangeHistory},
+    network_peer::NetworkPeer,
+    notification::NotificationPreferences,
+    outbound_proxy::OutboundProxySettings,
+    profile_limits::{ProfileLimits, ProfileUsage},
+    smart_inbox::{InboxFolders, SmartInbox, V2SmartInbox},
+    wallet::{WalletConfig, WalletInfo, WalletTransaction, WalletTransactionStatus},
 }, tools::shinkai_tool::ShinkaiTool};
+use shinkai_vector_resources::source::SourceFileMap;
+use crate::tools::tool_pricing::ToolUsageAllowance;
+use tokio::sync::broadcast;
 use x25519_dalek::PublicKey as EncryptionPublicKey;
 
 use super::{
     node_api_router::{APIError, GetPublicKeysResponse, SendResponseBodyData},
+    node_events::NodeEvent,
+    peer_reputation::PeerReputationStatus,
+    publishing::publication_manager::PublishedContent,
     v1_api::api_v1_handlers::APIUseRegistrationCodeSuccessResponse,
-    v2_api::api_v2_handlers_general::InitialRegistrationRequest,
+    v2_api::{
+        api_v2_commands_openai::{OpenAIChatCompletion, OpenAIChatCompletionRequest},
+        api_v2_handlers_general::{InitialRegistrationRequest, OidcRegistrationRequest},
+    },
 };
 
 pub enum NodeCommand {
     Shutdown,
+    // Command sent by an API request, handled with the request correlation ID
+    WithRequestId {
+        request_id: String,
+        command: Box<NodeCommand>,
+    },
     // Command to make the node ping all the other nodes it knows about.
     PingAll,
     // Command to request the node's public keys for signing and encryption. The sender will receive the keys.
@@ -43,8 +74,8 @@ pub enum NodeCommand {
     GetNodeName {
         res: Sender<String>,
     },
-    // Command to request the addresses of all nodes this node is aware of. The sender will receive the list of addresses.
-    GetPeers(Sender<Vec<SocketAddr>>),
+    // Command to request all nodes this node is aware of, with the protocol versions they announced. The sender will receive the list of peers.
+    GetPeers(Sender<Vec<NetworkPeer>>),
     // Command to make the node create a registration code through the API. The sender will receive the code.
     APICreateRegistrationCode {
         msg: ShinkaiMessage,
@@ -54,6 +85,10 @@ pub enum NodeCommand {
     LocalCreateRegistrationCode {
         permissions: IdentityPermissions,
         code_type: RegistrationCodeType,
+        // Seconds until the code expires, it never does if not set
+        ttl_secs: Option<u64>,
+        // Times the code can be used, unlimited if not set
+        max_uses: Option<u32>,
         res: Sender<String>,
     },
     // Command to make the node use a registration code encapsulated in a `ShinkaiMessage`. The sender will receive the result.
@@ -252,6 +287,10 @@ pub enum NodeCommand {
         msg: ShinkaiMessage,
         res: Sender<Result<String, APIError>>,
     },
+    APIInstallToolkitFromURL {
+        msg: ShinkaiMessage,
+        res: Sender<Result<Value, APIError>>,
+    },
     APIRemoveToolkit {
         msg: ShinkaiMessage,
         res: Sender<Result<String, APIError>>,
@@ -267,6 +306,10 @@ pub enum NodeCommand {
     APIIsPristine {
         res: Sender<Result<bool, APIError>>,
     },
+    APINodeHealth {
+        node_name: String,
+        res: Sender<NodeHealth>,
+    },
     IsPristine {
         res: Sender<bool>,
     },
@@ -499,12 +542,80 @@ pub enum NodeCommand {
         payload: InitialRegistrationRequest,
         res: Sender<Result<APIUseRegistrationCodeSuccessResponse, APIError>>,
     },
+    V2ApiOidcRegistration {
+        payload: OidcRegistrationRequest,
+        res: Sender<Result<APIUseRegistrationCodeSuccessResponse, APIError>>,
+    },
+    V2ApiGenerateOnboardingKeys {
+        res: Sender<Result<OnboardingKeys, APIError>>,
+    },
+    V2ApiTestLLMProvider {
+        llm_provider: SerializedLLMProvider,
+        res: Sender<Result<LLMProviderTestResult, APIError>>,
+    },
+    V2ApiInitializeNodeInteractive {
+        payload: APIInitializeNodeInteractive,
+        res: Sender<Result<APIUseRegistrationCodeSuccessResponse, APIError>>,
+    },
+    V2ApiCheckBearer {
+        bearer: String,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiStartIdempotentRequest {
+        bearer: String,
+        idempotency_key: String,
+        request_hash: String,
+        res: Sender<Result<Option<Value>, APIError>>,
+    },
+    V2ApiFinishIdempotentRequest {
+        bearer: String,
+        idempotency_key: String,
+        response: Option<Value>,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiCreateRegistrationCode {
+        bearer: String,
+        payload: RegistrationCodeRequest,
+        res: Sender<Result<String, APIError>>,
+    },
+    V2ApiGetAllProfiles {
+        bearer: String,
+        res: Sender<Result<Vec<StandardIdentity>, APIError>>,
+    },
+    V2ApiGetDevices {
+        bearer: String,
+        profile: String,
+        res: Sender<Result<Vec<DeviceInfo>, APIError>>,
+    },
+    V2ApiRenameDevice {
+        bearer: String,
+        payload: APIRenameDevice,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiRevokeDevice {
+        bearer: String,
+        payload: APIRevokeDevice,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGetRegistrationCodes {
+        bearer: String,
+        res: Sender<Result<Vec<Value>, APIError>>,
+    },
+    V2ApiRevokeRegistrationCode {
+        bearer: String,
+        payload: APIRevokeRegistrationCode,
+        res: Sender<Result<Value, APIError>>,
+    },
     V2ApiAvailableLLMProviders {
         bearer: String,
         res: Sender<Result<Vec<SerializedLLMProvider>, APIError>>,
     },
     V2ApiGetAllSmartInboxes {
         bearer: String,
+        folder: Option<String>,
+        label: Option<String>,
+        include_archived: bool,
+        message_requests: bool,
         res: Sender<Result<Vec<V2SmartInbox>, APIError>>,
     },
     V2ApiUpdateSmartInboxName {
@@ -513,6 +624,75 @@ pub enum NodeCommand {
         custom_name: String,
         res: Sender<Result<(), APIError>>,
     },
+    V2ApiMoveInboxesToFolder {
+        bearer: String,
+        inbox_names: Vec<String>,
+        folder: Option<String>,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiSetInboxLabels {
+        bearer: String,
+        inbox_name: String,
+        labels: Vec<String>,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiGetInboxFolders {
+        bearer: String,
+        res: Sender<Result<InboxFolders, APIError>>,
+    },
+    V2ApiSetInboxArchived {
+        bearer: String,
+        inbox_name: String,
+        archived: bool,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiSetInboxMuted {
+        bearer: String,
+        inbox_name: String,
+        muted: bool,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiGetInboxDigest {
+        bearer: String,
+        res: Sender<Result<Option<InboxDigestSchedule>, APIError>>,
+    },
+    V2ApiSetInboxDigest {
+        bearer: String,
+        settings: InboxDigestSettings,
+        res: Sender<Result<InboxDigestSchedule, APIError>>,
+    },
+    V2ApiRemoveInboxDigest {
+        bearer: String,
+        res: Sender<Result<(), APIError>>,
+    },
+    /// Edits the message, or deletes it if there's no new content
+    V2ApiChangeInboxMessage {
+        bearer: String,
+        inbox_name: String,
+        message_hash: String,
+        new_content: Option<String>,
+        res: Sender<Result<InboxMessageChange, APIError>>,
+    },
+    V2ApiGetInboxMessageHistory {
+        bearer: String,
+        inbox_name: String,
+        message_hash: String,
+        res: Sender<Result<MessageChangeHistory, APIError>>,
+    },
+    V2ApiSendMessageWithAttachments {
+        bearer: String,
+        inbox_name: String,
+        content: String,
+        files_inbox: String,
+        res: Sender<Result<SendResponseBodyData, APIError>>,
+    },
+    V2ApiDownloadAttachment {
+        bearer: String,
+        inbox_name: String,
+        message_hash: String,
+        attachment_id: String,
+        res: Sender<Result<(String, Vec<u8>), APIError>>,
+    },
     V2ApiGetLastMessagesFromInbox {
         bearer: String,
         inbox_name: String,
@@ -526,11 +706,80 @@ pub enum NodeCommand {
         llm_provider: String,
         res: Sender<Result<String, APIError>>,
     },
+    V2ApiCreateJobForPath {
+        bearer: String,
+        payload: APICreateJobForPath,
+        res: Sender<Result<String, APIError>>,
+    },
+    V2ApiSetFolderAgent {
+        bearer: String,
+        payload: APISetFolderAgent,
+        res: Sender<Result<String, APIError>>,
+    },
+    V2ApiListFolderAgents {
+        bearer: String,
+        res: Sender<Result<Vec<FolderAgentBinding>, APIError>>,
+    },
     V2ApiJobMessage {
         bearer: String,
         job_message: JobMessage,
         res: Sender<Result<SendResponseBodyData, APIError>>,
     },
+    V2ApiUpdateJobToFinished {
+        bearer: String,
+        job_id: String,
+        res: Sender<Result<String, APIError>>,
+    },
+    V2ApiGetJobStatus {
+        bearer: String,
+        payload: APIGetJobStatus,
+        res: Sender<Result<JobStatus, APIError>>,
+    },
+    V2ApiSetJobPlanning {
+        bearer: String,
+        payload: APISetJobPlanning,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGetJobPlan {
+        bearer: String,
+        job_id: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiSetJobRetrievalConfig {
+        bearer: String,
+        payload: APISetJobRetrievalConfig,
+        res: Sender<Result<RetrievalConfig, APIError>>,
+    },
+    V2ApiGetJobRetrievalConfig {
+        bearer: String,
+        job_id: String,
+        res: Sender<Result<RetrievalConfig, APIError>>,
+    },
+    V2ApiGetJobMessageCitations {
+        bearer: String,
+        job_id: String,
+        message_hash: String,
+        res: Sender<Result<Vec<JobCitation>, APIError>>,
+    },
+    V2ApiMarkAsReadUpTo {
+        bearer: String,
+        inbox_name: String,
+        up_to_time: String,
+        res: Sender<Result<String, APIError>>,
+    },
+    V2ApiAddInboxPermission {
+        bearer: String,
+        inbox_name: String,
+        profile: String,
+        permission: String,
+        res: Sender<Result<String, APIError>>,
+    },
+    V2ApiRemoveInboxPermission {
+        bearer: String,
+        inbox_name: String,
+        profile: String,
+        res: Sender<Result<String, APIError>>,
+    },
     V2ApiVecFSRetrievePathSimplifiedJson {
         bearer: String,
         payload: APIVecFsRetrievePathSimplifiedJson,
@@ -541,6 +790,11 @@ pub enum NodeCommand {
         path: String,
         res: Sender<Result<Value, APIError>>,
     },
+    V2ApiVecFSRetrieveSourceFileMap {
+        bearer: String,
+        payload: APIVecFsRetrieveSourceFileMap,
+        res: Sender<Result<SourceFileMap, APIError>>,
+    },
     V2ApiConvertFilesAndSaveToFolder {
         bearer: String,
         payload: APIConvertFilesAndSaveToFolder,
@@ -571,6 +825,61 @@ pub enum NodeCommand {
         payload: APIVecFsCopyFolder,
         res: Sender<Result<String, APIError>>,
     },
+    V2ApiVecFSGetFolderStats {
+        bearer: String,
+        payload: APIVecFSGetFolderStats,
+        res: Sender<Result<FolderStats, APIError>>,
+    },
+    V2ApiVecFSExportFolderAsVRPack {
+        bearer: String,
+        payload: APIVecFSExportFolderAsVRPack,
+        res: Sender<Result<(String, Vec<u8>), APIError>>,
+    },
+    V2ApiVecFSExportMarkdownBundle {
+        bearer: String,
+        payload: APIVecFSExportMarkdownBundle,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiVecFSImportMarkdownBundle {
+        bearer: String,
+        payload: APIVecFSImportMarkdownBundle,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiVecFSGetItemVersions {
+        bearer: String,
+        payload: APIVecFSGetItemVersions,
+        res: Sender<Result<Vec<FSItemVersion>, APIError>>,
+    },
+    V2ApiVecFSDiffItemVersion {
+        bearer: String,
+        payload: APIVecFSDiffItemVersion,
+        res: Sender<Result<Vec<FSItemMetadataChange>, APIError>>,
+    },
+    V2ApiVecFSRestoreItemVersion {
+        bearer: String,
+        payload: APIVecFSRestoreItemVersion,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiVecFSSetFolderEmbeddingModel {
+        bearer: String,
+        payload: APIVecFSSetFolderEmbeddingModel,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiVecFSCreateLink {
+        bearer: String,
+        payload: APIVecFsCreateLink,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGetOperationStatus {
+        bearer: String,
+        payload: APIGetOperationStatus,
+        res: Sender<Result<Vec<OperationStatus>, APIError>>,
+    },
+    V2ApiCancelOperation {
+        bearer: String,
+        payload: APICancelOperation,
+        res: Sender<Result<OperationStatus, APIError>>,
+    },
     V2ApiDeleteFolder {
         bearer: String,
         payload: APIVecFsDeleteFolder,
@@ -586,6 +895,12 @@ pub enum NodeCommand {
         payload: APIVecFsSearchItems,
         res: Sender<Result<Vec<String>, APIError>>,
     },
+    V2ApiVecFSVectorSearch {
+        bearer: String,
+        payload: APIVecFsRetrieveVectorSearchSimplifiedJson,
+        #[allow(clippy::complexity)]
+        res: Sender<Result<Vec<(String, Vec<String>, f32)>, APIError>>,
+    },
     V2ApiCreateFilesInbox {
         bearer: String, //
         res: Sender<Result<String, APIError>>,
@@ -603,6 +918,7 @@ pub enum NodeCommand {
         file: Vec<u8>,
         path: String,
         file_datetime: Option<DateTime<Utc>>,
+        allow_duplicates: bool,
         res: Sender<Result<Value, APIError>>,
     },
     V2ApiAvailableSharedItems {
@@ -640,6 +956,16 @@ pub enum NodeCommand {
         payload: APIUnsubscribeToSharedFolder,
         res: Sender<Result<String, APIError>>,
     },
+    V2ApiSetSharedFolderWriters {
+        bearer: String,
+        payload: APISetSharedFolderWriters,
+        res: Sender<Result<String, APIError>>,
+    },
+    V2ApiPushSharedFolderChanges {
+        bearer: String,
+        payload: APIPushSharedFolderChanges,
+        res: Sender<Result<String, APIError>>,
+    },
     V2ApiMySubscriptions {
         bearer: String,
         res: Sender<Result<Value, APIError>>,
@@ -664,6 +990,23 @@ pub enum NodeCommand {
         payload: APIGetNotificationsBeforeTimestamp,
         res: Sender<Result<Value, APIError>>,
     },
+    V2ApiGetUnreadNotificationsCount {
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiMarkNotificationsRead {
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGetNotificationPreferences {
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiSetNotificationPreferences {
+        bearer: String,
+        payload: NotificationPreferences,
+        res: Sender<Result<Value, APIError>>,
+    },
     V2ApiSearchWorkflows {
         bearer: String,
         query: String,
@@ -708,6 +1051,73 @@ pub enum NodeCommand {
         payload: String,
         res: Sender<Result<Value, APIError>>,
     },
+    V2ApiAddWasmTool {
+        bearer: String,
+        payload: Value,
+        res: Sender<Result<ShinkaiTool, APIError>>,
+    },
+    V2ApiGetToolkitCapabilities {
+        bearer: String,
+        toolkit_name: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGrantToolkitCapabilities {
+        bearer: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiRevokeToolkitCapabilities {
+        bearer: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGetToolkitExecutionLimits {
+        bearer: String,
+        toolkit_name: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiSetToolkitExecutionLimits {
+        bearer: String,
+        toolkit_name: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGetToolkitPricing {
+        bearer: String,
+        toolkit_name: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiSetToolkitPricing {
+        bearer: String,
+        toolkit_name: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGetToolUsage {
+        bearer: String,
+        consumer: String,
+        asset: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiSetToolUsageAllowance {
+        bearer: String,
+        payload: ToolUsageAllowance,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiListToolkits {
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiInstallToolkitFromURL {
+        bearer: String,
+        payload: APIInstallToolkitFromURL,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiRemoveToolkit {
+        bearer: String,
+        toolkit_name: String,
+        res: Sender<Result<String, APIError>>,
+    },
     V2ApiGetLocalProcessingPreference {
         bearer: String,
         res: Sender<Result<bool, APIError>>,
@@ -717,6 +1127,24 @@ pub enum NodeCommand {
         preference: bool,
         res: Sender<Result<String, APIError>>,
     },
+    V2ApiGetEntityTaggingPreference {
+        bearer: String,
+        res: Sender<Result<bool, APIError>>,
+    },
+    V2ApiUpdateEntityTaggingPreference {
+        bearer: String,
+        preference: bool,
+        res: Sender<Result<String, APIError>>,
+    },
+    V2ApiGetSummarizationLLMProvider {
+        bearer: String,
+        res: Sender<Result<Option<String>, APIError>>,
+    },
+    V2ApiUpdateSummarizationLLMProvider {
+        bearer: String,
+        llm_provider_id: Option<String>,
+        res: Sender<Result<String, APIError>>,
+    },
     V2ApiGetDefaultEmbeddingModel {
         bearer: String,
         res: Sender<Result<String, APIError>>,
@@ -773,4 +1201,290 @@ pub enum NodeCommand {
         payload: APIAddOllamaModels,
         res: Sender<Result<(), APIError>>,
     },
+    V2ApiGetLocalInferenceMetrics {
+        bearer: String,
+        res: Sender<Result<LocalInferenceMetrics, APIError>>,
+    },
+    V2ApiGetLogLevels {
+        bearer: String,
+        res: Sender<Result<Vec<LogLevelSetting>, APIError>>,
+    },
+    V2ApiSetLogLevels {
+        bearer: String,
+        log_levels: Vec<LogLevelSetting>,
+        res: Sender<Result<Vec<LogLevelSetting>, APIError>>,
+    },
+    V2ApiGetRecentLogs {
+        bearer: String,
+        payload: APIGetRecentLogs,
+        res: Sender<Result<Vec<LogEntry>, APIError>>,
+    },
+    V2ApiDownloadLogs {
+        bearer: String,
+        res: Sender<Result<Vec<u8>, APIError>>,
+    },
+    V2ApiRunDiagnostics {
+        bearer: String,
+        res: Sender<Result<DiagnosticsReport, APIError>>,
+    },
+    V2ApiRunDbMaintenance {
+        bearer: String,
+        payload: APIRunDbMaintenance,
+        res: Sender<Result<DbMaintenanceReport, APIError>>,
+    },
+    V2ApiRelocateStorage {
+        bearer: String,
+        payload: APIRelocateStorage,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGetNodeMetrics {
+        bearer: String,
+        res: Sender<Result<NodeMetrics, APIError>>,
+    },
+    V2ApiGetPeerReputations {
+        bearer: String,
+        res: Sender<Result<Vec<PeerReputationStatus>, APIError>>,
+    },
+    V2ApiSetPeerBan {
+        bearer: String,
+        payload: APISetPeerBan,
+        res: Sender<Result<Vec<PeerReputationStatus>, APIError>>,
+    },
+    V2ApiGetOutboundProxySettings {
+        bearer: String,
+        res: Sender<Result<OutboundProxySettings, APIError>>,
+    },
+    V2ApiSetOutboundProxySettings {
+        bearer: String,
+        payload: OutboundProxySettings,
+        res: Sender<Result<OutboundProxySettings, APIError>>,
+    },
+    V2ApiResolveIdentityName {
+        bearer: String,
+        name: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiCreateInvoice {
+        bearer: String,
+        payload: APICreateInvoice,
+        res: Sender<Result<Invoice, APIError>>,
+    },
+    V2ApiListInvoices {
+        bearer: String,
+        status: Option<InvoiceStatus>,
+        res: Sender<Result<Vec<Invoice>, APIError>>,
+    },
+    V2ApiMarkInvoicePaid {
+        bearer: String,
+        payload: APIMarkInvoicePaid,
+        res: Sender<Result<Invoice, APIError>>,
+    },
+    V2ApiSetWallet {
+        bearer: String,
+        payload: WalletConfig,
+        res: Sender<Result<WalletInfo, APIError>>,
+    },
+    V2ApiGetWallet {
+        bearer: String,
+        res: Sender<Result<WalletInfo, APIError>>,
+    },
+    V2ApiPayInvoice {
+        bearer: String,
+        payload: APIPayInvoice,
+        res: Sender<Result<WalletTransaction, APIError>>,
+    },
+    V2ApiListWalletTransactions {
+        bearer: String,
+        status: Option<WalletTransactionStatus>,
+        res: Sender<Result<Vec<WalletTransaction>, APIError>>,
+    },
+    V2ApiCompleteWalletTransaction {
+        bearer: String,
+        payload: APICompleteWalletTransaction,
+        res: Sender<Result<WalletTransaction, APIError>>,
+    },
+    V2ApiCreateWorkspace {
+        bearer: String,
+        payload: APICreateWorkspace,
+        res: Sender<Result<Workspace, APIError>>,
+    },
+    V2ApiUpdateWorkspace {
+        bearer: String,
+        payload: APIUpdateWorkspace,
+        res: Sender<Result<Workspace, APIError>>,
+    },
+    V2ApiSetWorkspaceMember {
+        bearer: String,
+        payload: APISetWorkspaceMember,
+        res: Sender<Result<Workspace, APIError>>,
+    },
+    V2ApiListWorkspaces {
+        bearer: String,
+        res: Sender<Result<Vec<Workspace>, APIError>>,
+    },
+    V2ApiListWorkspaceInvitations {
+        bearer: String,
+        res: Sender<Result<Vec<Workspace>, APIError>>,
+    },
+    V2ApiAcceptWorkspaceInvitation {
+        bearer: String,
+        payload: APIWorkspaceInvitation,
+        res: Sender<Result<Workspace, APIError>>,
+    },
+    V2ApiDeclineWorkspaceInvitation {
+        bearer: String,
+        payload: APIWorkspaceInvitation,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiGetContacts {
+        bearer: String,
+        res: Sender<Result<ContactBook, APIError>>,
+    },
+    V2ApiSetContact {
+        bearer: String,
+        payload: APISetContact,
+        res: Sender<Result<Contact, APIError>>,
+    },
+    V2ApiRemoveContact {
+        bearer: String,
+        payload: APIRemoveContact,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiSetContactsAllowlistMode {
+        bearer: String,
+        payload: APISetContactsAllowlistMode,
+        res: Sender<Result<ContactBook, APIError>>,
+    },
+    V2ApiAcceptMessageRequest {
+        bearer: String,
+        payload: APIAcceptMessageRequest,
+        res: Sender<Result<Contact, APIError>>,
+    },
+    V2ApiGetInboundMessagePolicy {
+        bearer: String,
+        res: Sender<Result<InboundMessagePolicy, APIError>>,
+    },
+    V2ApiSetInboundMessagePolicy {
+        bearer: String,
+        payload: InboundMessagePolicy,
+        res: Sender<Result<InboundMessagePolicy, APIError>>,
+    },
+    V2ApiPublishFolder {
+        bearer: String,
+        payload: APIPublishFolder,
+        res: Sender<Result<FolderPublication, APIError>>,
+    },
+    V2ApiUnpublishFolder {
+        bearer: String,
+        payload: APIUnpublishFolder,
+        res: Sender<Result<String, APIError>>,
+    },
+    V2ApiListPublications {
+        bearer: String,
+        res: Sender<Result<Vec<FolderPublication>, APIError>>,
+    },
+    /// Sent by the publishing server, which has no API key
+    GetPublishedContent {
+        publication_id: String,
+        token: Option<String>,
+        path: Vec<String>,
+        download: bool,
+        res: Sender<Result<PublishedContent, APIError>>,
+    },
+    V2ApiStopNode {
+        bearer: String,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiRestartNode {
+        bearer: String,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiGetProfileLimits {
+        bearer: String,
+        profile: String,
+        res: Sender<Result<ProfileUsage, APIError>>,
+    },
+    V2ApiSetProfileLimits {
+        bearer: String,
+        payload: ProfileLimits,
+        res: Sender<Result<ProfileLimits, APIError>>,
+    },
+    V2ApiExportProfileData {
+        bearer: String,
+        payload: APIExportProfileData,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiDeleteProfile {
+        bearer: String,
+        payload: APIDeleteProfile,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiSetEmailAccount {
+        bearer: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGetEmailAccount {
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiRemoveEmailAccount {
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiSetCalendarAccount {
+        bearer: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGetCalendarAccount {
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiRemoveCalendarAccount {
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiAddWatchedFolder {
+        bearer: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiListWatchedFolders {
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiRemoveWatchedFolder {
+        bearer: String,
+        payload: APIRemoveWatchedFolder,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiAddCloudConnector {
+        bearer: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiListCloudConnectors {
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiUpdateCloudConnector {
+        bearer: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiRemoveCloudConnector {
+        bearer: String,
+        payload: APIRemoveCloudConnector,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiOpenAIChatCompletion {
+        bearer: String,
+        request: OpenAIChatCompletionRequest,
+        res: Sender<Result<OpenAIChatCompletion, APIError>>,
+    },
+    V2ApiSubscribeToEvents {
+        bearer: String,
+        res: Sender<Result<broadcast::Receiver<NodeEvent>, APIError>>,
+    },
 }
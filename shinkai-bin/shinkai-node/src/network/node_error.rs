@@ -1,4 +1,5 @@
 use crate::{
+    network::error_code::ErrorCode,
     llm_provider::error::LLMProviderError, db::db_errors::ShinkaiDBError, tools::error::ToolError,
     vector_fs::vector_fs_error::VectorFSError,
 };
@@ -11,6 +12,8 @@ use shinkai_vector_resources::resource_errors::VRError;
 #[derive(Debug)]
 pub struct NodeError {
     pub message: String,
+    /// Code of the underlying error, kept when the error is sent to API clients
+    pub error_code: ErrorCode,
 }
 
 impl std::fmt::Display for NodeError {
@@ -25,6 +28,7 @@ impl From<Box<dyn std::error::Error + Send + Sync>> for NodeError {
     fn from(err: Box<dyn std::error::Error + Send + Sync>) -> NodeError {
         NodeError {
             message: format!("{}", err),
+            error_code: ErrorCode::InternalError,
         }
     }
 }
@@ -33,6 +37,7 @@ impl From<Box<dyn std::error::Error + Send>> for NodeError {
     fn from(err: Box<dyn std::error::Error + Send>) -> NodeError {
         NodeError {
             message: format!("{}", err),
+            error_code: ErrorCode::InternalError,
         }
     }
 }
@@ -41,6 +46,7 @@ impl From<std::io::Error> for NodeError {
     fn from(err: std::io::Error) -> NodeError {
         NodeError {
             message: format!("{}", err),
+            error_code: ErrorCode::InternalError,
         }
     }
 }
@@ -49,6 +55,7 @@ impl From<VectorFSError> for NodeError {
     fn from(err: VectorFSError) -> NodeError {
         NodeError {
             message: format!("{}", err),
+            error_code: err.error_code(),
         }
     }
 }
@@ -57,6 +64,7 @@ impl From<VRError> for NodeError {
     fn from(err: VRError) -> NodeError {
         NodeError {
             message: format!("{}", err),
+            error_code: ErrorCode::InternalError,
         }
     }
 }
@@ -65,6 +73,7 @@ impl From<ShinkaiMessageError> for NodeError {
     fn from(err: ShinkaiMessageError) -> NodeError {
         NodeError {
             message: format!("{}", err),
+            error_code: ErrorCode::InternalError,
         }
     }
 }
@@ -73,6 +82,7 @@ impl From<LLMProviderError> for NodeError {
     fn from(error: LLMProviderError) -> Self {
         NodeError {
             message: format!("LLMProviderError occurred: {}", error),
+            error_code: error.error_code(),
         }
     }
 }
@@ -81,6 +91,7 @@ impl From<ShinkaiDBError> for NodeError {
     fn from(error: ShinkaiDBError) -> Self {
         NodeError {
             message: format!("Database error: {}", error),
+            error_code: error.error_code(),
         }
     }
 }
@@ -89,6 +100,7 @@ impl From<ToolError> for NodeError {
     fn from(error: ToolError) -> Self {
         NodeError {
             message: format!("{}", error),
+            error_code: ErrorCode::ToolError,
         }
     }
 }
@@ -97,6 +109,7 @@ impl From<InboxNameError> for NodeError {
     fn from(err: InboxNameError) -> NodeError {
         NodeError {
             message: format!("InboxNameError: {}", err),
+            error_code: ErrorCode::InvalidInput,
         }
     }
 }
@@ -105,12 +118,16 @@ impl From<ShinkaiNameError> for NodeError {
     fn from(error: ShinkaiNameError) -> Self {
         NodeError {
             message: format!("ShinkaiNameError: {}", error),
+            error_code: ErrorCode::InvalidInput,
         }
     }
 }
 
 impl From<String> for NodeError {
     fn from(error: String) -> Self {
-        NodeError { message: error }
+        NodeError {
+            message: error,
+            error_code: ErrorCode::InternalError,
+        }
     }
 }
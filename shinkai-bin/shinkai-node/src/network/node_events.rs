@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+/// Slow listeners that fall this many events behind skip the oldest ones
+const EVENT_BUS_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeEventType {
+    /// A message was added to an inbox
+    Message,
+    /// A job started or finished processing a message
+    JobStatus,
+    /// Files of a subscription were downloaded
+    SubscriptionSync,
+    /// A network notification was written for a profile
+    Notification,
+    /// An uploaded file started or finished converting into a Vector Resource
+    FileProcessing,
+}
+
+impl NodeEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeEventType::Message => "message",
+            NodeEventType::JobStatus => "job_status",
+            NodeEventType::SubscriptionSync => "subscription_sync",
+            NodeEventType::Notification => "notification",
+            NodeEventType::FileProcessing => "file_processing",
+        }
+    }
+
+    pub fn from_name(value: &str) -> Option<Self> {
+        match value {
+            "message" => Some(NodeEventType::Message),
+            "job_status" => Some(NodeEventType::JobStatus),
+            "subscription_sync" => Some(NodeEventType::SubscriptionSync),
+            "notification" => Some(NodeEventType::Notification),
+            "file_processing" => Some(NodeEventType::FileProcessing),
+            _ => None,
+        }
+    }
+
+    /// Parses a comma separated list of event types. An empty list means all of them.
+    pub fn parse_filter(value: &str) -> Result<Vec<Self>, String> {
+        value
+            .split(',')
+            .map(|event_type| event_type.trim())
+            .filter(|event_type| !event_type.is_empty())
+            .map(|event_type| Self::from_name(event_type).ok_or_else(|| format!("Unknown event type: {}", event_type)))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeEvent {
+    pub event_type: NodeEventType,
+    pub data: Value,
+}
+
+/// Fan out of node events to the API listeners (e.g. `/v2/events`). Publishing never blocks and is a no-op
+/// while nobody is listening.
+#[derive(Debug, Clone)]
+pub struct NodeEventBus {
+    sender: broadcast::Sender<NodeEvent>,
+}
+
+impl Default for NodeEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NodeEventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        NodeEventBus { sender }
+    }
+
+    pub fn publish(&self, event_type: NodeEventType, data: Value) {
+        // Only fails when there are no listeners
+        let _ = self.sender.send(NodeEvent { event_type, data });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEvent> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(
+            NodeEventType::parse_filter("message, job_status").unwrap(),
+            vec![NodeEventType::Message, NodeEventType::JobStatus]
+        );
+        assert!(NodeEventType::parse_filter("").unwrap().is_empty());
+        assert!(NodeEventType::parse_filter("message,unknown").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_publish_to_subscribers() {
+        let bus = NodeEventBus::new();
+        // Nobody is listening yet
+        bus.publish(NodeEventType::Notification, json!({}));
+
+        let mut receiver = bus.subscribe();
+        bus.publish(NodeEventType::Message, json!({ "inbox": "inbox_1" }));
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(event.event_type, NodeEventType::Message);
+        assert_eq!(event.data["inbox"], "inbox_1");
+    }
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+use super::error_code::ErrorCode;
 use super::node_api_router::APIError;
 use crate::managers::identity_manager::IdentityManagerTrait;
 use crate::{
@@ -47,6 +48,7 @@ pub async fn validate_message_main_logic(
                     Err(e) => {
                         return Err(APIError {
                             code: StatusCode::BAD_REQUEST.as_u16(),
+                            error_code: ErrorCode::InvalidInput,
                             error: "Bad Request".to_string(),
                             message: format!("Failed to decrypt message body: {}", e),
                         })
@@ -74,6 +76,7 @@ pub async fn validate_message_main_logic(
                         Identity::LLMProvider(_) => {
                             return Err(APIError {
                                 code: StatusCode::UNAUTHORIZED.as_u16(),
+                                error_code: ErrorCode::Unauthorized,
                                 error: "Unauthorized".to_string(),
                                 message:
                                     "Failed to get sender encryption pk from message: Agent identity not supported"
@@ -84,6 +87,7 @@ pub async fn validate_message_main_logic(
                     None => {
                         return Err(APIError {
                             code: StatusCode::UNAUTHORIZED.as_u16(),
+                            error_code: ErrorCode::Unauthorized,
                             error: "Unauthorized".to_string(),
                             message: "Failed to get sender encryption pk from message: Identity not found".to_string(),
                         })
@@ -97,6 +101,7 @@ pub async fn validate_message_main_logic(
                     Err(e) => {
                         return Err(APIError {
                             code: StatusCode::BAD_REQUEST.as_u16(),
+                            error_code: ErrorCode::InvalidInput,
                             error: "Bad Request".to_string(),
                             message: format!("Failed to decrypt message body: {}", e),
                         })
@@ -119,6 +124,7 @@ pub async fn validate_message_main_logic(
         if let Err(e) = msg.validate_message_schema(schema) {
             return Err(APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: format!("Invalid message schema: {}", e),
             });
@@ -131,6 +137,7 @@ pub async fn validate_message_main_logic(
         Err(e) => {
             return Err(APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: format!("Failed to get sender name from message: {}", e),
             })
@@ -141,6 +148,7 @@ pub async fn validate_message_main_logic(
     if sender_name.get_node_name_string() != node_profile_name.get_node_name_string() {
         return Err(APIError {
             code: StatusCode::BAD_REQUEST.as_u16(),
+            error_code: ErrorCode::BadRequest,
             error: "Bad Request".to_string(),
             message: "sender_name.node_name is not the same as self.node_name. It can't proxy through this node."
                 .to_string(),
@@ -163,6 +171,7 @@ pub async fn validate_message_main_logic(
         None => {
             return Err(APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::BadRequest,
                 error: "Bad Request".to_string(),
                 message: "Sender subidentity is None".to_string(),
             });
@@ -180,11 +189,19 @@ pub async fn validate_message_main_logic(
             error!("Failed to verify message signature: {}", e);
             return Err(APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: format!("Failed to verify message signature: {}", e),
             });
         }
     }
 
+    if let Identity::Device(device) = &sender_subidentity {
+        identity_manager
+            .lock()
+            .await
+            .record_device_seen(&device.full_identity_name);
+    }
+
     Ok((msg, sender_subidentity))
 }
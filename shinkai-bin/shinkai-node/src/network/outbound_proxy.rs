@@ -0,0 +1,221 @@
+use std::io;
+use std::net::SocketAddr;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+use reqwest::{ClientBuilder, NoProxy, Proxy, Url};
+use shinkai_vector_resources::embedding_generator::set_embeddings_proxy;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::schemas::outbound_proxy::{DestinationClass, OutboundProxySettings};
+
+lazy_static! {
+    /// Proxies of the outbound connections of the node, loaded from the settings when it starts
+    pub static ref OUTBOUND_PROXY: OutboundProxy = OutboundProxy::default();
+}
+
+/// The reply to an HTTP CONNECT is read up to this size
+const MAX_CONNECT_REPLY_LENGTH: usize = 8 * 1024;
+
+#[derive(Default)]
+pub struct OutboundProxy {
+    settings: RwLock<OutboundProxySettings>,
+}
+
+impl OutboundProxy {
+    pub fn settings(&self) -> OutboundProxySettings {
+        self.settings.read().unwrap().clone()
+    }
+
+    pub fn set_settings(&self, settings: OutboundProxySettings) {
+        set_embeddings_proxy(settings.proxy_url(DestinationClass::Embeddings));
+        *self.settings.write().unwrap() = settings;
+    }
+
+    pub fn proxy_url(&self, class: DestinationClass) -> Option<String> {
+        self.settings.read().unwrap().proxy_url(class)
+    }
+
+    /// Sends the requests of the client through the proxy of the class, if it has one
+    pub fn client_builder(&self, class: DestinationClass, builder: ClientBuilder) -> ClientBuilder {
+        match self.proxy_url(class).and_then(|proxy_url| Proxy::all(proxy_url).ok()) {
+            Some(proxy) => builder.proxy(proxy.no_proxy(NoProxy::from_string("localhost,127.0.0.1,::1"))),
+            None => builder,
+        }
+    }
+
+    /// Opens a TCP connection to the address, tunneled through the proxy of the class if it has one
+    pub async fn connect(&self, class: DestinationClass, address: SocketAddr) -> io::Result<TcpStream> {
+        let proxy_url = match self.proxy_url(class) {
+            Some(proxy_url) if !address.ip().is_loopback() => proxy_url,
+            _ => return TcpStream::connect(address).await,
+        };
+        let url = Url::parse(&proxy_url).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let host = url.host_str().unwrap_or_default();
+        let port = url.port_or_known_default().unwrap_or_default();
+        let mut stream = TcpStream::connect((host, port)).await?;
+        let credentials = match url.username() {
+            "" => None,
+            username => Some((username.to_string(), url.password().unwrap_or_default().to_string())),
+        };
+
+        match url.scheme() {
+            "socks5" | "socks5h" => Self::socks5_handshake(&mut stream, address, credentials).await?,
+            "http" => Self::http_connect(&mut stream, address, credentials).await?,
+            scheme => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Proxy scheme {} can't tunnel TCP connections", scheme),
+                ))
+            }
+        }
+        Ok(stream)
+    }
+
+    /// Asks a SOCKS5 proxy (RFC 1928) to connect the stream to the address
+    async fn socks5_handshake(
+        stream: &mut TcpStream,
+        address: SocketAddr,
+        credentials: Option<(String, String)>,
+    ) -> io::Result<()> {
+        match credentials {
+            Some(_) => stream.write_all(&[5, 2, 0, 2]).await?,
+            None => stream.write_all(&[5, 1, 0]).await?,
+        }
+        let mut method = [0u8; 2];
+        stream.read_exact(&mut method).await?;
+        match (method, credentials) {
+            ([5, 0], _) => {}
+            ([5, 2], Some((username, password))) => {
+                // Username and password authentication (RFC 1929)
+                let mut request = vec![1, username.len() as u8];
+                request.extend_from_slice(username.as_bytes());
+                request.push(password.len() as u8);
+                request.extend_from_slice(password.as_bytes());
+                stream.write_all(&request).await?;
+                let mut status = [0u8; 2];
+                stream.read_exact(&mut status).await?;
+                if status[1] != 0 {
+                    return Err(Self::proxy_error("The SOCKS5 proxy refused the credentials"));
+                }
+            }
+            _ => {
+                return Err(Self::proxy_error(
+                    "The SOCKS5 proxy accepts none of the offered authentications",
+                ))
+            }
+        }
+
+        let mut request = vec![5, 1, 0];
+        match address {
+            SocketAddr::V4(address) => {
+                request.push(1);
+                request.extend_from_slice(&address.ip().octets());
+            }
+            SocketAddr::V6(address) => {
+                request.push(4);
+                request.extend_from_slice(&address.ip().octets());
+            }
+        }
+        request.extend_from_slice(&address.port().to_be_bytes());
+        stream.write_all(&request).await?;
+
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply).await?;
+        if reply[1] != 0 {
+            return Err(Self::proxy_error(&format!(
+                "The SOCKS5 proxy failed to connect to {} (reply {})",
+                address, reply[1]
+            )));
+        }
+        // Skips the address the proxy bound, then the port
+        let bound_address_length = match reply[3] {
+            1 => 4,
+            4 => 16,
+            3 => stream.read_u8().await? as usize,
+            _ => return Err(Self::proxy_error("The SOCKS5 proxy sent an invalid reply")),
+        };
+        let mut bound_address = vec![0u8; bound_address_length + 2];
+        stream.read_exact(&mut bound_address).await?;
+        Ok(())
+    }
+
+    /// Asks an HTTP proxy to tunnel the stream to the address with a CONNECT request
+    async fn http_connect(
+        stream: &mut TcpStream,
+        address: SocketAddr,
+        credentials: Option<(String, String)>,
+    ) -> io::Result<()> {
+        let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", address);
+        if let Some((username, password)) = credentials {
+            let token = base64::encode(format!("{}:{}", username, password));
+            request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", token));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+
+        // Reads the reply byte by byte, so nothing sent after it by the peer is consumed
+        let mut reply = Vec::new();
+        while !reply.ends_with(b"\r\n\r\n") {
+            if reply.len() >= MAX_CONNECT_REPLY_LENGTH {
+                return Err(Self::proxy_error("The HTTP proxy sent a too long reply"));
+            }
+            reply.push(stream.read_u8().await?);
+        }
+        let reply = String::from_utf8_lossy(&reply);
+        let status_line = reply.lines().next().unwrap_or_default();
+        if status_line.split_whitespace().nth(1) != Some("200") {
+            return Err(Self::proxy_error(&format!(
+                "The HTTP proxy failed to connect to {}: {}",
+                address, status_line
+            )));
+        }
+        Ok(())
+    }
+
+    fn proxy_error(message: &str) -> io::Error {
+        io::Error::new(io::ErrorKind::ConnectionRefused, message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_connect_through_socks5_proxy() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_address = listener.local_addr().unwrap();
+        let target: SocketAddr = "203.0.113.7:9552".parse().unwrap();
+
+        let proxy = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 3];
+            socket.read_exact(&mut greeting).await.unwrap();
+            assert_eq!(greeting, [5, 1, 0]);
+            socket.write_all(&[5, 0]).await.unwrap();
+
+            let mut request = [0u8; 10];
+            socket.read_exact(&mut request).await.unwrap();
+            assert_eq!(request, [5, 1, 0, 1, 203, 0, 113, 7, 0x25, 0x50]);
+            socket.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).await.unwrap();
+            socket.write_all(b"hello").await.unwrap();
+        });
+
+        let outbound_proxy = OutboundProxy::default();
+        let mut settings = OutboundProxySettings::default();
+        settings
+            .proxies
+            .insert(DestinationClass::Node, format!("socks5://{}", proxy_address));
+        assert!(settings.validate().is_ok());
+        outbound_proxy.set_settings(settings);
+
+        let mut stream = outbound_proxy.connect(DestinationClass::Node, target).await.unwrap();
+        let mut greeting = [0u8; 5];
+        stream.read_exact(&mut greeting).await.unwrap();
+        assert_eq!(&greeting, b"hello");
+        proxy.await.unwrap();
+    }
+}
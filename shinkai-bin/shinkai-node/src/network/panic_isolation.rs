@@ -0,0 +1,99 @@
+use std::any::Any;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+
+use futures::FutureExt;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use tokio::task::JoinHandle;
+
+use super::error_code::ErrorCode;
+use super::node_error::NodeError;
+use super::request_id::spawn_with_request_id;
+
+/// Longest wait before restarting a subsystem that keeps panicking
+const MAX_RESTART_DELAY_SECS: u64 = 60;
+
+fn panic_message(panic: &Box<dyn Any + Send>) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Runs the future, turning a panic in it into a logged NodeError instead of unwinding into the caller
+pub async fn catch_panic<F: Future>(context: &str, future: F) -> Result<F::Output, NodeError> {
+    AssertUnwindSafe(future).catch_unwind().await.map_err(|panic| {
+        let message = format!("{} panicked: {}", context, panic_message(&panic));
+        shinkai_log(ShinkaiLogOption::Node, ShinkaiLogLevel::Error, &message);
+        NodeError {
+            message,
+            error_code: ErrorCode::InternalError,
+        }
+    })
+}
+
+/// Spawns the handler of a node command. A panic in it is logged, and the node keeps handling the other commands.
+pub fn spawn_command_handler<F>(future: F) -> JoinHandle<Result<F::Output, NodeError>>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    spawn_with_request_id(catch_panic("Node command handler", future))
+}
+
+/// Spawns a long running subsystem (e.g. a server) and starts it again if it panics, waiting longer after each
+/// restart. It isn't restarted once it finishes without panicking.
+pub fn spawn_supervised<F, Fut>(name: &'static str, start: F) -> JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut restart_delay_secs = 1;
+        while catch_panic(name, start()).await.is_err() {
+            shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Error,
+                &format!("Restarting {} in {} seconds", name, restart_delay_secs),
+            );
+            tokio::time::sleep(Duration::from_secs(restart_delay_secs)).await;
+            restart_delay_secs = (restart_delay_secs * 2).min(MAX_RESTART_DELAY_SECS);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_panics_are_isolated() {
+        let error = catch_panic("Test", async { panic!("boom") }).await.unwrap_err();
+        assert_eq!(error.message, "Test panicked: boom");
+        assert_eq!(catch_panic("Test", async { 1 }).await.unwrap(), 1);
+
+        let result = spawn_command_handler(async { panic!("handler failed") }).await.unwrap();
+        assert!(result.is_err());
+
+        // Panics the first time, then finishes
+        let runs = Arc::new(AtomicUsize::new(0));
+        let runs_clone = runs.clone();
+        spawn_supervised("test subsystem", move || {
+            let runs = runs_clone.clone();
+            async move {
+                if runs.fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("first run");
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+}
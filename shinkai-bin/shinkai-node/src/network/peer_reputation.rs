@@ -0,0 +1,323 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use utoipa::ToSchema;
+
+lazy_static! {
+    /// Reputation of the peers connecting to the node, by verified node identity or else by IP
+    pub static ref PEER_REPUTATIONS: PeerReputations = PeerReputations::new_from_env();
+}
+
+/// Bans double on every new ban of a peer, up to this many times the ban duration
+const MAX_BAN_DOUBLINGS: u32 = 6;
+
+/// Offenses of unverified peers coming through a relay are kept under this prefix, as they are not the relay's own
+const RELAY_PEER_PREFIX: &str = "relay:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerOffense {
+    /// A frame or a message that couldn't be read
+    InvalidMessage,
+    InvalidSignature,
+    RateLimited,
+}
+
+impl PeerOffense {
+    fn penalty(&self) -> f64 {
+        match self {
+            PeerOffense::InvalidMessage => 1.0,
+            PeerOffense::InvalidSignature => 5.0,
+            PeerOffense::RateLimited => 0.5,
+        }
+    }
+}
+
+/// Set by an admin, wins over the reputation of the peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerBanOverride {
+    /// Refused until the override is removed
+    Banned,
+    /// Never banned nor deprioritized
+    Allowed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PeerReputationStatus {
+    /// Node identity, IP, or `relay:<ip>` for the unverified peers coming through a relay
+    pub peer: String,
+    /// Sum of the penalties of the offenses, halved every half-life
+    pub score: f64,
+    pub invalid_messages: u64,
+    pub invalid_signatures: u64,
+    pub rate_limit_violations: u64,
+    /// Deprioritized peers get a single connection at a time
+    pub deprioritized: bool,
+    pub banned_until: Option<String>,
+    pub ban_override: Option<PeerBanOverride>,
+}
+
+#[derive(Debug, Default)]
+struct PeerReputation {
+    score: f64,
+    scored_at: Option<Instant>,
+    invalid_messages: u64,
+    invalid_signatures: u64,
+    rate_limit_violations: u64,
+    banned_until: Option<(Instant, DateTime<Utc>)>,
+    bans: u32,
+}
+
+/// Scores the offenses of the peers connecting to the node. A peer whose score reaches `deprioritize_score` gets a
+/// single connection at a time, and one reaching `ban_score` is refused for `ban_duration`, doubled on each new ban.
+/// Scores halve every `half_life`, so peers recover once they behave.
+///
+/// Offenses are scored against the node identity once its signature is verified, and against the IP before that.
+/// Relays forward the messages of many nodes from a single IP, so their IPs are never banned nor deprioritized.
+pub struct PeerReputations {
+    peers: Mutex<HashMap<String, PeerReputation>>,
+    overrides: Mutex<HashMap<String, PeerBanOverride>>,
+    relays: Mutex<HashSet<String>>,
+    deprioritize_score: f64,
+    ban_score: f64,
+    ban_duration: Duration,
+    half_life: Duration,
+}
+
+impl PeerReputations {
+    pub fn new(deprioritize_score: f64, ban_score: f64, ban_duration: Duration, half_life: Duration) -> Self {
+        Self {
+            peers: Mutex::new(HashMap::new()),
+            overrides: Mutex::new(HashMap::new()),
+            relays: Mutex::new(HashSet::new()),
+            deprioritize_score,
+            ban_score: ban_score.max(deprioritize_score),
+            ban_duration,
+            half_life: half_life.max(Duration::from_secs(1)),
+        }
+    }
+
+    /// Reads PEER_DEPRIORITIZE_SCORE, PEER_BAN_SCORE, PEER_BAN_SECS, PEER_SCORE_HALF_LIFE_SECS and the
+    /// comma-separated relay IPs of PEER_RELAY_IPS
+    pub fn new_from_env() -> Self {
+        let read_env = |key: &str, default: f64| -> f64 {
+            std::env::var(key)
+                .ok()
+                .and_then(|value| value.parse::<f64>().ok())
+                .unwrap_or(default)
+        };
+        let reputations = Self::new(
+            read_env("PEER_DEPRIORITIZE_SCORE", 10.0),
+            read_env("PEER_BAN_SCORE", 20.0),
+            Duration::from_secs(read_env("PEER_BAN_SECS", 600.0) as u64),
+            Duration::from_secs(read_env("PEER_SCORE_HALF_LIFE_SECS", 3600.0) as u64),
+        );
+        if let Ok(relay_ips) = std::env::var("PEER_RELAY_IPS") {
+            for ip in relay_ips.split(',').map(str::trim).filter(|ip| !ip.is_empty()) {
+                reputations.add_relay(ip);
+            }
+        }
+        reputations
+    }
+
+    /// Marks an IP as a relay, e.g. the proxy of this node
+    pub fn add_relay(&self, ip: &str) {
+        self.relays.lock().unwrap().insert(ip.to_string());
+    }
+
+    pub fn is_relay(&self, ip: &str) -> bool {
+        self.relays.lock().unwrap().contains(ip)
+    }
+
+    /// Records an offense of the peer at `ip`, against `verified_identity` if its signature was already verified
+    pub fn record(&self, ip: &str, verified_identity: Option<&str>, offense: PeerOffense) {
+        let (key, bannable) = match verified_identity {
+            Some(identity) => (identity.to_string(), true),
+            None if self.is_relay(ip) => (format!("{}{}", RELAY_PEER_PREFIX, ip), false),
+            None => (ip.to_string(), true),
+        };
+        let mut peers = self.peers.lock().unwrap();
+        let peer = peers.entry(key.clone()).or_default();
+        peer.score = self.current_score(peer) + offense.penalty();
+        peer.scored_at = Some(Instant::now());
+        match offense {
+            PeerOffense::InvalidMessage => peer.invalid_messages += 1,
+            PeerOffense::InvalidSignature => peer.invalid_signatures += 1,
+            PeerOffense::RateLimited => peer.rate_limit_violations += 1,
+        }
+
+        let banned = matches!(peer.banned_until, Some((until, _)) if Instant::now() < until);
+        if bannable && peer.score >= self.ban_score && !banned {
+            let duration = self.ban_duration * 2u32.pow(peer.bans.min(MAX_BAN_DOUBLINGS));
+            peer.bans += 1;
+            peer.banned_until = Some((
+                Instant::now() + duration,
+                Utc::now() + chrono::Duration::from_std(duration).unwrap_or_default(),
+            ));
+            // Deprioritized once the ban ends, until the score decays
+            peer.score = self.deprioritize_score;
+            shinkai_log(
+                ShinkaiLogOption::Network,
+                ShinkaiLogLevel::Error,
+                &format!("Banned peer {} for {:?} after {:?}", key, duration, offense),
+            );
+        }
+    }
+
+    /// Whether a peer, by IP or node identity, is banned. Relay IPs are only banned by an override.
+    pub fn is_banned(&self, peer: &str) -> bool {
+        match self.ban_override(peer) {
+            Some(PeerBanOverride::Banned) => true,
+            Some(PeerBanOverride::Allowed) => false,
+            None => self
+                .peers
+                .lock()
+                .unwrap()
+                .get(peer)
+                .and_then(|peer| peer.banned_until)
+                .map_or(false, |(until, _)| Instant::now() < until),
+        }
+    }
+
+    pub fn is_deprioritized(&self, peer: &str) -> bool {
+        if self.ban_override(peer) == Some(PeerBanOverride::Allowed) || self.is_relay(peer) {
+            return false;
+        }
+        self.peers
+            .lock()
+            .unwrap()
+            .get(peer)
+            .map_or(false, |peer| self.current_score(peer) >= self.deprioritize_score)
+    }
+
+    pub fn ban_override(&self, peer: &str) -> Option<PeerBanOverride> {
+        self.overrides.lock().unwrap().get(peer).copied()
+    }
+
+    /// Sets or removes the override of a peer. Removing it also forgets the offenses of the peer.
+    pub fn set_override(&self, peer: &str, ban_override: Option<PeerBanOverride>) {
+        let mut overrides = self.overrides.lock().unwrap();
+        match ban_override {
+            Some(ban_override) => {
+                overrides.insert(peer.to_string(), ban_override);
+            }
+            None => {
+                overrides.remove(peer);
+                self.peers.lock().unwrap().remove(peer);
+            }
+        }
+    }
+
+    pub fn overrides(&self) -> HashMap<String, PeerBanOverride> {
+        self.overrides.lock().unwrap().clone()
+    }
+
+    /// Restores the overrides saved in the database when the node starts
+    pub fn load_overrides(&self, overrides: HashMap<String, PeerBanOverride>) {
+        *self.overrides.lock().unwrap() = overrides;
+    }
+
+    /// Peers with offenses or an override
+    pub fn statuses(&self) -> Vec<PeerReputationStatus> {
+        let peers = self.peers.lock().unwrap();
+        let overrides = self.overrides.lock().unwrap();
+        let mut keys: Vec<&String> = peers.keys().chain(overrides.keys()).collect();
+        keys.sort();
+        keys.dedup();
+
+        keys.into_iter()
+            .map(|key| {
+                let peer = peers.get(key);
+                let ban_override = overrides.get(key).copied();
+                let score = peer.map_or(0.0, |peer| self.current_score(peer));
+                PeerReputationStatus {
+                    peer: key.clone(),
+                    score,
+                    invalid_messages: peer.map_or(0, |peer| peer.invalid_messages),
+                    invalid_signatures: peer.map_or(0, |peer| peer.invalid_signatures),
+                    rate_limit_violations: peer.map_or(0, |peer| peer.rate_limit_violations),
+                    deprioritized: ban_override != Some(PeerBanOverride::Allowed)
+                        && !key.starts_with(RELAY_PEER_PREFIX)
+                        && score >= self.deprioritize_score,
+                    banned_until: peer
+                        .and_then(|peer| peer.banned_until)
+                        .filter(|(until, _)| Instant::now() < *until)
+                        .map(|(_, until)| until.to_rfc3339()),
+                    ban_override,
+                }
+            })
+            .collect()
+    }
+
+    fn current_score(&self, peer: &PeerReputation) -> f64 {
+        match peer.scored_at {
+            Some(scored_at) => {
+                let half_lives = scored_at.elapsed().as_secs_f64() / self.half_life.as_secs_f64();
+                peer.score * 0.5f64.powf(half_lives)
+            }
+            None => peer.score,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_is_deprioritized_then_banned() {
+        let reputations = PeerReputations::new(2.0, 10.0, Duration::from_millis(50), Duration::from_secs(3600));
+        let ip = "10.0.0.1";
+
+        reputations.record(ip, None, PeerOffense::InvalidMessage);
+        assert!(!reputations.is_deprioritized(ip));
+        reputations.record(ip, None, PeerOffense::InvalidMessage);
+        assert!(reputations.is_deprioritized(ip));
+        assert!(!reputations.is_banned(ip));
+
+        reputations.record(ip, None, PeerOffense::InvalidSignature);
+        reputations.record(ip, None, PeerOffense::InvalidSignature);
+        assert!(reputations.is_banned(ip));
+        assert_eq!(reputations.statuses()[0].invalid_signatures, 2);
+
+        // The ban ends on its own, the peer stays deprioritized
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(!reputations.is_banned(ip));
+        assert!(reputations.is_deprioritized(ip));
+
+        reputations.set_override(ip, Some(PeerBanOverride::Allowed));
+        assert!(!reputations.is_deprioritized(ip));
+        reputations.set_override(ip, Some(PeerBanOverride::Banned));
+        assert!(reputations.is_banned(ip));
+        reputations.set_override(ip, None);
+        assert!(!reputations.is_banned(ip));
+        assert!(reputations.statuses().is_empty());
+    }
+
+    #[test]
+    fn test_relay_and_verified_peers_are_scored_apart() {
+        let reputations = PeerReputations::new(2.0, 5.0, Duration::from_secs(60), Duration::from_secs(3600));
+        let relay_ip = "10.0.0.2";
+        reputations.add_relay(relay_ip);
+
+        // Unverified peers behind the relay never get the relay banned
+        for _ in 0..3 {
+            reputations.record(relay_ip, None, PeerOffense::InvalidSignature);
+        }
+        assert!(!reputations.is_banned(relay_ip));
+        assert!(!reputations.is_deprioritized(relay_ip));
+        assert_eq!(reputations.statuses()[0].peer, "relay:10.0.0.2");
+
+        // A verified node is banned by its identity, not by the IP of the relay it comes through
+        let node = "@@mallory.sepolia-shinkai";
+        reputations.record(relay_ip, Some(node), PeerOffense::InvalidSignature);
+        assert!(reputations.is_banned(node));
+        assert!(!reputations.is_banned(relay_ip));
+    }
+}
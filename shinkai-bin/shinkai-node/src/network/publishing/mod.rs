@@ -0,0 +1,233 @@
+pub mod publication_manager;
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+use async_channel::Sender;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use warp::http::{Response, StatusCode};
+use warp::path::FullPath;
+use warp::Filter;
+
+use super::node_commands::NodeCommand;
+use super::webdav::path_segments;
+use publication_manager::PublishedContent;
+
+type PublishingResponse = Response<Vec<u8>>;
+
+/// Serves the published folders read-only over plain HTTP, as pages any browser can read: folders are listed,
+/// items are rendered from their text, and their source files are downloaded with `?download`. URLs start with the
+/// publication id, and publications that need a token take it as `?token=` or as a Bearer authorization.
+/// The server is meant to sit behind a reverse proxy that terminates HTTPS.
+pub async fn run_publishing_server(
+    node_commands_sender: Sender<NodeCommand>,
+    address: SocketAddr,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    shinkai_log(
+        ShinkaiLogOption::Api,
+        ShinkaiLogLevel::Info,
+        &format!("Starting Node publishing server at: {}", &address),
+    );
+
+    let routes = warp::get()
+        .and(warp::path::full())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(
+            move |path: FullPath, query: HashMap<String, String>, authorization: Option<String>| {
+                let node_commands_sender = node_commands_sender.clone();
+                async move { Ok::<_, warp::Rejection>(handle(node_commands_sender, path, query, authorization).await) }
+            },
+        );
+
+    let (_, serving) = warp::serve(routes).try_bind_ephemeral(address)?;
+    serving.await;
+    Ok(())
+}
+
+async fn handle(
+    node_commands_sender: Sender<NodeCommand>,
+    path: FullPath,
+    query: HashMap<String, String>,
+    authorization: Option<String>,
+) -> PublishingResponse {
+    let Some(mut segments) = path_segments(path.as_str()) else {
+        return error_page(StatusCode::BAD_REQUEST, "Invalid path");
+    };
+    if segments.is_empty() {
+        return error_page(StatusCode::NOT_FOUND, "Not found");
+    }
+    let publication_id = segments.remove(0);
+    let query_token = query.get("token").cloned();
+    let token = query_token.clone().or_else(|| {
+        authorization
+            .as_deref()
+            .and_then(|authorization| authorization.strip_prefix("Bearer "))
+            .map(|token| token.to_string())
+    });
+
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    let command = NodeCommand::GetPublishedContent {
+        publication_id: publication_id.clone(),
+        token,
+        path: segments.clone(),
+        download: query.contains_key("download"),
+        res: res_sender,
+    };
+    if node_commands_sender.send(command).await.is_err() {
+        return error_page(StatusCode::INTERNAL_SERVER_ERROR, "The node is not available");
+    }
+    let result = match res_receiver.recv().await {
+        Ok(result) => result,
+        Err(_) => return error_page(StatusCode::INTERNAL_SERVER_ERROR, "The node is not available"),
+    };
+
+    match result {
+        Ok(PublishedContent::Folder { name, folders, items }) => {
+            // Links keep the token so pages read with it can be browsed
+            let link_query = query_token
+                .map(|token| format!("token={}", urlencoding::encode(&token)))
+                .unwrap_or_default();
+            let base = format!(
+                "/{}",
+                std::iter::once(&publication_id)
+                    .chain(segments.iter())
+                    .map(|segment| urlencoding::encode(segment).into_owned())
+                    .collect::<Vec<String>>()
+                    .join("/")
+            );
+            let link = |name: &str, extra_query: &str| {
+                let query = [extra_query, link_query.as_str()]
+                    .into_iter()
+                    .filter(|part| !part.is_empty())
+                    .collect::<Vec<&str>>()
+                    .join("&");
+                let href = format!("{}/{}", base, urlencoding::encode(name));
+                match query.is_empty() {
+                    true => href,
+                    false => format!("{}?{}", href, query),
+                }
+            };
+
+            let mut body = format!("<h1>{}</h1>\n<ul>\n", escape_html(&name));
+            for folder in &folders {
+                body.push_str(&format!(
+                    "<li><a href=\"{}\">{}/</a></li>\n",
+                    escape_html(&link(folder, "")),
+                    escape_html(folder)
+                ));
+            }
+            for item in &items {
+                body.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a>",
+                    escape_html(&link(&item.name, "")),
+                    escape_html(&item.name)
+                ));
+                if let Some(file_name) = &item.file_name {
+                    body.push_str(&format!(
+                        " (<a href=\"{}\">{}</a>)",
+                        escape_html(&link(&item.name, "download")),
+                        escape_html(file_name)
+                    ));
+                }
+                body.push_str("</li>\n");
+            }
+            body.push_str("</ul>\n");
+            html_response(StatusCode::OK, &name, &body)
+        }
+        Ok(PublishedContent::Document {
+            name,
+            description,
+            markdown,
+        }) => {
+            let mut body = format!("<h1>{}</h1>\n", escape_html(&name));
+            if let Some(description) = description {
+                body.push_str(&format!("<p><em>{}</em></p>\n", escape_html(&description)));
+            }
+            body.push_str(&render_markdown(&markdown));
+            html_response(StatusCode::OK, &name, &body)
+        }
+        Ok(PublishedContent::File {
+            file_name,
+            content_type,
+            content,
+        }) => Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", content_type)
+            .header("Content-Length", content.len())
+            .header(
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", file_name.replace('"', "")),
+            )
+            .body(content)
+            .unwrap_or_default(),
+        Err(error) => error_page(error.error_code.status(), &error.message),
+    }
+}
+
+fn html_response(status: StatusCode, title: &str, body: &str) -> PublishingResponse {
+    let page = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        body
+    );
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "text/html; charset=utf-8")
+        .body(page.into_bytes())
+        .unwrap_or_default()
+}
+
+fn error_page(status: StatusCode, message: &str) -> PublishingResponse {
+    html_response(status, message, &format!("<p>{}</p>\n", escape_html(message)))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders the markdown of an item: headings, lists and paragraphs, with everything else kept as escaped text
+fn render_markdown(markdown: &str) -> String {
+    let mut html = String::new();
+    for block in markdown.split("\n\n").map(str::trim).filter(|block| !block.is_empty()) {
+        let level = block.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&level) && block[level..].starts_with(' ') && !block.contains('\n') {
+            html.push_str(&format!(
+                "<h{0}>{1}</h{0}>\n",
+                level,
+                escape_html(block[level..].trim())
+            ));
+        } else if block
+            .lines()
+            .all(|line| line.starts_with("- ") || line.starts_with("* "))
+        {
+            html.push_str("<ul>\n");
+            for line in block.lines() {
+                html.push_str(&format!("<li>{}</li>\n", escape_html(line[2..].trim())));
+            }
+            html.push_str("</ul>\n");
+        } else {
+            let lines: Vec<String> = block.lines().map(escape_html).collect();
+            html.push_str(&format!("<p>{}</p>\n", lines.join("<br>\n")));
+        }
+    }
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_markdown() {
+        let markdown = "## Results\n\nFirst line <b>\nsecond line\n\n- one\n- two & three";
+        assert_eq!(
+            render_markdown(markdown),
+            "<h2>Results</h2>\n<p>First line &lt;b&gt;<br>\nsecond line</p>\n<ul>\n<li>one</li>\n<li>two &amp; three</li>\n</ul>\n"
+        );
+        assert_eq!(render_markdown("#hashtag"), "<p>#hashtag</p>\n");
+    }
+}
@@ -0,0 +1,211 @@
+use std::fmt;
+
+use chrono::Utc;
+use rand::RngCore;
+use shinkai_message_primitives::schemas::folder_publication::{FolderPublication, PublicationAccess};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::APIPublishFolder;
+use shinkai_vector_resources::source::SourceFile;
+use shinkai_vector_resources::vector_resource::VRPath;
+use uuid::Uuid;
+
+use crate::db::db_errors::ShinkaiDBError;
+use crate::db::ShinkaiDB;
+use crate::network::webdav::dav_resource::DavResource;
+use crate::vector_fs::vector_fs::VectorFS;
+use crate::vector_fs::vector_fs_markdown_bundle::MarkdownBundleDocument;
+use crate::vector_fs::vector_fs_types::FSEntry;
+
+#[derive(Debug)]
+pub enum PublicationError {
+    InvalidInput(String),
+    NotFound(String),
+    /// The publication needs a token and it's missing or wrong
+    Unauthorized(String),
+    DatabaseError(ShinkaiDBError),
+    VectorFSError(String),
+}
+
+impl fmt::Display for PublicationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PublicationError::InvalidInput(msg) => write!(f, "Invalid publication: {}", msg),
+            PublicationError::NotFound(what) => write!(f, "{} not found", what),
+            PublicationError::Unauthorized(publication_id) => {
+                write!(f, "Publication {} needs a valid token", publication_id)
+            }
+            PublicationError::DatabaseError(err) => write!(f, "Database error: {}", err),
+            PublicationError::VectorFSError(msg) => write!(f, "Failed to read the folder: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PublicationError {}
+
+impl From<ShinkaiDBError> for PublicationError {
+    fn from(err: ShinkaiDBError) -> Self {
+        PublicationError::DatabaseError(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublishedItem {
+    pub name: String,
+    /// Name of the source file of the item, if it was kept
+    pub file_name: Option<String>,
+}
+
+/// What a path of a publication points to
+#[derive(Debug, Clone, PartialEq)]
+pub enum PublishedContent {
+    Folder {
+        name: String,
+        folders: Vec<String>,
+        items: Vec<PublishedItem>,
+    },
+    Document {
+        name: String,
+        description: Option<String>,
+        markdown: String,
+    },
+    File {
+        file_name: String,
+        content_type: String,
+        content: Vec<u8>,
+    },
+}
+
+/// Publications serve shared folders read-only over plain HTTP, for readers who don't run a node. Items are
+/// rendered from their text as markdown, and their source files can be downloaded. Unsharing the folder stops
+/// serving it.
+pub struct PublicationManager;
+
+impl PublicationManager {
+    pub fn publish_folder(
+        db: &ShinkaiDB,
+        profile: &ShinkaiName,
+        payload: APIPublishFolder,
+    ) -> Result<FolderPublication, PublicationError> {
+        if db.get_folder_requirements(&payload.path).is_err() {
+            return Err(PublicationError::InvalidInput(format!(
+                "{} must be shared before it's published",
+                payload.path
+            )));
+        }
+
+        let token = match payload.access {
+            PublicationAccess::Public => None,
+            PublicationAccess::Token => {
+                let mut random_bytes = [0u8; 32];
+                rand::thread_rng().fill_bytes(&mut random_bytes);
+                Some(hex::encode(random_bytes))
+            }
+        };
+        let publication = FolderPublication {
+            publication_id: Uuid::new_v4().to_string(),
+            path: payload.path,
+            profile: profile.to_string(),
+            access: payload.access,
+            token,
+            created_at: Utc::now(),
+        };
+        db.set_folder_publication(&publication)?;
+        Ok(publication)
+    }
+
+    pub fn unpublish_folder(db: &ShinkaiDB, publication_id: &str) -> Result<(), PublicationError> {
+        Self::get_publication(db, publication_id)?;
+        db.remove_folder_publication(publication_id)?;
+        Ok(())
+    }
+
+    fn get_publication(db: &ShinkaiDB, publication_id: &str) -> Result<FolderPublication, PublicationError> {
+        db.get_folder_publication(publication_id).map_err(|e| match e {
+            ShinkaiDBError::DataNotFound => PublicationError::NotFound(format!("Publication {}", publication_id)),
+            e => PublicationError::DatabaseError(e),
+        })
+    }
+
+    /// Reads what the path, relative to the published folder, points to. With `download`, items are read as
+    /// their source file instead of their text.
+    pub async fn get_content(
+        db: &ShinkaiDB,
+        vector_fs: &VectorFS,
+        publication_id: &str,
+        token: Option<&str>,
+        segments: &[String],
+        download: bool,
+    ) -> Result<PublishedContent, PublicationError> {
+        let publication = Self::get_publication(db, publication_id)?;
+        if !publication.allows(token) {
+            return Err(PublicationError::Unauthorized(publication_id.to_string()));
+        }
+        if db.get_folder_requirements(&publication.path).is_err() {
+            return Err(PublicationError::NotFound(format!("Publication {}", publication_id)));
+        }
+
+        let profile =
+            ShinkaiName::new(publication.profile.clone()).map_err(|e| PublicationError::InvalidInput(e.to_string()))?;
+        let mut path =
+            VRPath::from_string(&publication.path).map_err(|e| PublicationError::InvalidInput(e.to_string()))?;
+        for segment in segments {
+            path.push(segment.clone());
+        }
+        let not_found = || PublicationError::NotFound(format!("/{}", segments.join("/")));
+
+        let reader = vector_fs
+            .new_reader(profile.clone(), path, profile)
+            .await
+            .map_err(|_| not_found())?;
+        let entry = vector_fs.retrieve_fs_entry(&reader).await.map_err(|_| not_found())?;
+
+        match entry {
+            FSEntry::Folder(folder) => Ok(PublishedContent::Folder {
+                name: folder.name,
+                folders: folder.child_folders.into_iter().map(|folder| folder.name).collect(),
+                items: folder
+                    .child_items
+                    .iter()
+                    .map(|item| PublishedItem {
+                        name: item.name.clone(),
+                        file_name: DavResource::item_has_source_file(item).then(|| DavResource::item_file_name(item)),
+                    })
+                    .collect(),
+            }),
+            FSEntry::Item(item) if download => {
+                let source_file_map = vector_fs
+                    .retrieve_source_file_map(&reader)
+                    .await
+                    .map_err(|_| not_found())?;
+                // Items ingested from a single file keep it at the root of their map
+                let content = match source_file_map
+                    .get_source_file(VRPath::root())
+                    .or_else(|| source_file_map.map.values().next())
+                {
+                    Some(SourceFile::Standard(file)) => file.file_content.clone(),
+                    Some(SourceFile::TLSNotarized(file)) => file.file_content.clone(),
+                    None => return Err(not_found()),
+                };
+                let resource = DavResource::from_item(&item);
+                Ok(PublishedContent::File {
+                    file_name: DavResource::item_file_name(&item),
+                    content_type: resource.content_type().to_string(),
+                    content,
+                })
+            }
+            FSEntry::Item(_) => {
+                let resource = vector_fs
+                    .retrieve_vector_resource(&reader)
+                    .await
+                    .map_err(|e| PublicationError::VectorFSError(e.to_string()))?;
+                let document = MarkdownBundleDocument::from_resource(&resource);
+                Ok(PublishedContent::Document {
+                    name: document.name,
+                    description: document.description,
+                    markdown: document.body,
+                })
+            }
+            FSEntry::Root(_) => Err(not_found()),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::future::Future;
+
+use async_channel::Sender;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::set_request_id_provider;
+use tokio::task::JoinHandle;
+
+use super::node_commands::NodeCommand;
+
+/// Header clients can set to choose the correlation ID of their request. It's returned in every response.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const MAX_REQUEST_ID_LENGTH: usize = 64;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Adds the correlation ID of the request being handled to the node logs
+pub fn init_request_id_logging() {
+    set_request_id_provider(current_request_id);
+}
+
+pub fn new_request_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Uses the ID sent by the client if it's safe to log and echo back, otherwise generates a new one
+pub fn request_id_or_new(client_request_id: Option<&str>) -> String {
+    match client_request_id {
+        Some(request_id)
+            if !request_id.is_empty()
+                && request_id.len() <= MAX_REQUEST_ID_LENGTH
+                && request_id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+        {
+            request_id.to_string()
+        }
+        _ => new_request_id(),
+    }
+}
+
+/// Correlation ID of the request the current task is working on
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|request_id| request_id.clone()).ok()
+}
+
+/// Runs the future on behalf of the request
+pub async fn with_request_id<F: Future>(request_id: String, future: F) -> F::Output {
+    REQUEST_ID.scope(request_id, future).await
+}
+
+/// Runs the future on behalf of the request, if there's one
+pub async fn with_optional_request_id<F: Future>(request_id: Option<String>, future: F) -> F::Output {
+    match request_id {
+        Some(request_id) => REQUEST_ID.scope(request_id, future).await,
+        None => future.await,
+    }
+}
+
+/// `tokio::spawn` keeping the correlation ID of the current task, if any
+pub fn spawn_with_request_id<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    match current_request_id() {
+        Some(request_id) => tokio::spawn(REQUEST_ID.scope(request_id, future)),
+        None => tokio::spawn(future),
+    }
+}
+
+/// Sender for the API handlers of a request. The commands they send carry the request correlation ID, so the node
+/// handles them (and logs) on behalf of the request.
+pub fn request_scoped_sender(node_commands_sender: &Sender<NodeCommand>) -> Sender<NodeCommand> {
+    let Some(request_id) = current_request_id() else {
+        return node_commands_sender.clone();
+    };
+
+    let (sender, receiver) = async_channel::unbounded();
+    let node_commands_sender = node_commands_sender.clone();
+    // Stops once the handlers of the request drop their senders
+    tokio::spawn(async move {
+        while let Ok(command) = receiver.recv().await {
+            let command = NodeCommand::WithRequestId {
+                request_id: request_id.clone(),
+                command: Box::new(command),
+            };
+            if node_commands_sender.send(command).await.is_err() {
+                break;
+            }
+        }
+    });
+    sender
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_id_scope() {
+        assert_eq!(request_id_or_new(Some("req_123-abc")), "req_123-abc");
+        assert_ne!(request_id_or_new(Some("bad id\n")), "bad id\n");
+        assert_eq!(request_id_or_new(None).len(), 36);
+
+        assert_eq!(current_request_id(), None);
+        let request_id = with_request_id("req_1".to_string(), async {
+            spawn_with_request_id(async { current_request_id() }).await.unwrap()
+        })
+        .await;
+        assert_eq!(request_id, Some("req_1".to_string()));
+    }
+}
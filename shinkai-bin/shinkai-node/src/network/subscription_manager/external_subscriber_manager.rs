@@ -6,9 +6,11 @@ use crate::network::network_manager::network_job_manager::VRPackPlusChanges;
 use crate::network::node::ProxyConnectionInfo;
 use crate::network::subscription_manager::fs_entry_tree_generator::FSEntryTreeGenerator;
 use crate::network::subscription_manager::subscriber_manager_error::SubscriberManagerError;
+use crate::network::workspace_manager::WorkspaceManager;
 use crate::network::ws_manager::WSUpdateHandler;
 use crate::network::Node;
 use crate::schemas::identity::StandardIdentity;
+use crate::schemas::notification::NotificationKind;
 use crate::vector_fs::vector_fs::VectorFS;
 use crate::vector_fs::vector_fs_permissions::ReadPermission;
 use chrono::{DateTime, Utc};
@@ -16,6 +18,9 @@ use dashmap::DashMap;
 use ed25519_dalek::SigningKey;
 use futures::Future;
 use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::schemas::shared_folder_sync::{
+    SharedFolderConflict, SharedFolderPush, SharedFolderPushResult,
+};
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
 use shinkai_message_primitives::schemas::shinkai_subscription::{
     ShinkaiSubscription, ShinkaiSubscriptionStatus, SubscriptionId,
@@ -26,7 +31,7 @@ use shinkai_message_primitives::shinkai_utils::encryption::clone_static_secret_k
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
 use shinkai_message_primitives::shinkai_utils::shinkai_message_builder::ShinkaiMessageBuilder;
 use shinkai_message_primitives::shinkai_utils::signatures::clone_signature_secret_key;
-use shinkai_vector_resources::vector_resource::{VRPack, VRPath};
+use shinkai_vector_resources::vector_resource::{VRKai, VRPack, VRPath};
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::env;
@@ -1441,6 +1446,17 @@ impl ExternalSubscriberManager {
             "Database instance is not available".to_string(),
         ))?;
 
+        // Folders of a workspace are only replicated to its members
+        if let Ok(streamer_with_profile) = subscription_id.extract_streamer_node_with_profile() {
+            WorkspaceManager::check_folder_subscriber(
+                &db,
+                &streamer_with_profile,
+                &shared_folder,
+                &requester_shinkai_identity,
+            )
+            .map_err(|e| SubscriberManagerError::SubscriptionFailed(e.to_string()))?;
+        }
+
         match db.get_subscription_by_id(&subscription_id) {
             Ok(_) => {
                 // If subscription exists, let's allow the user to re-subscribe
@@ -1461,7 +1477,7 @@ impl ExternalSubscriberManager {
             requester_shinkai_identity.extract_node(),
             requester_profile,
             ShinkaiSubscriptionStatus::SubscriptionConfirmed,
-            Some(subscription_requirement),
+            Some(subscription_requirement.clone()),
             None,
             None,
         );
@@ -1471,6 +1487,21 @@ impl ExternalSubscriberManager {
         db.add_subscriber_subscription(subscription)
             .map_err(|e| SubscriberManagerError::DatabaseError(e.to_string()))?;
 
+        if let SubscriptionPayment::Payment(payment_details) = subscription_requirement {
+            let notification_message = format!(
+                "Received a payment of {} from user '{}' for shared folder '{}'.",
+                payment_details,
+                requester_shinkai_identity.get_node_name_string(),
+                shared_folder
+            );
+            db.write_notification_of_kind(
+                streamer_shinkai_identity.clone(),
+                NotificationKind::PaymentReceived,
+                notification_message,
+            )
+            .map_err(|e| SubscriberManagerError::DatabaseError(e.to_string()))?;
+        }
+
         shinkai_log(
             ShinkaiLogOption::ExtSubscriptions,
             ShinkaiLogLevel::Info,
@@ -1571,6 +1602,212 @@ impl ExternalSubscriberManager {
         }
     }
 
+    /// Sets the nodes or profiles allowed to push changes back to a folder shared by the requester.
+    /// Editors of the workspace the folder belongs to can always push changes.
+    pub async fn set_shared_folder_writers(
+        &self,
+        path: String,
+        requester_shinkai_identity: ShinkaiName,
+        writers: Vec<String>,
+    ) -> Result<bool, SubscriberManagerError> {
+        let requester_profile = requester_shinkai_identity.get_profile_name_string().ok_or(
+            SubscriberManagerError::IdentityProfileNotFound("Profile name not found for requester".to_string()),
+        )?;
+        let shared_folder_key = format!("{}:::{}", requester_profile, path);
+        if !self.shared_folders_trees.contains_key(&shared_folder_key) {
+            return Err(SubscriberManagerError::SharedFolderNotFound(path));
+        }
+        for writer in &writers {
+            ShinkaiName::new(writer.clone())
+                .map_err(|e| SubscriberManagerError::InvalidRequest(format!("Invalid writer {}: {}", writer, e)))?;
+        }
+
+        let db = self.db.upgrade().ok_or(SubscriberManagerError::DatabaseNotAvailable(
+            "Database instance is not available".to_string(),
+        ))?;
+        db.set_shared_folder_writers(&requester_profile, &path, &writers)
+            .map_err(|e| SubscriberManagerError::DatabaseError(e.to_string()))?;
+
+        Ok(true)
+    }
+
+    /// Applies the changes a subscriber pushed to a shared folder. Items changed since the version
+    /// the subscriber based its changes on are reported as conflicts and left as they are.
+    /// The accepted changes reach the other subscribers with the next sync of the folder.
+    pub async fn apply_shared_folder_push(
+        &mut self,
+        requester_shinkai_identity: ShinkaiName,
+        streamer_shinkai_identity: ShinkaiName,
+        push: SharedFolderPush,
+    ) -> Result<SharedFolderPushResult, SubscriberManagerError> {
+        shinkai_log(
+            ShinkaiLogOption::ExtSubscriptions,
+            ShinkaiLogLevel::Debug,
+            format!(
+                "apply_shared_folder_push> requester_shinkai_identity: {:?}, shared_folder: {:?}, items: {}",
+                requester_shinkai_identity,
+                push.shared_folder,
+                push.items.len()
+            )
+            .as_str(),
+        );
+        let requester_profile = requester_shinkai_identity.get_profile_name_string().ok_or(
+            SubscriberManagerError::IdentityProfileNotFound("Profile name not found for requester".to_string()),
+        )?;
+        let streamer_profile = streamer_shinkai_identity.get_profile_name_string().ok_or(
+            SubscriberManagerError::IdentityProfileNotFound("Profile name not found for streamer".to_string()),
+        )?;
+
+        let shared_folder = push.shared_folder.clone();
+        let shared_folder_key = format!("{}:::{}", streamer_profile, shared_folder);
+        if !self.shared_folders_trees.contains_key(&shared_folder_key) {
+            return Err(SubscriberManagerError::SharedFolderNotFound(shared_folder));
+        }
+
+        let db = self.db.upgrade().ok_or(SubscriberManagerError::DatabaseNotAvailable(
+            "Database instance is not available".to_string(),
+        ))?;
+
+        // Only subscribers can push changes, and only if the folder is open to them
+        let subscription_id = SubscriptionId::new(
+            streamer_shinkai_identity.extract_node(),
+            streamer_profile.clone(),
+            shared_folder.clone(),
+            requester_shinkai_identity.extract_node(),
+            requester_profile,
+        );
+        db.get_subscription_by_id(&subscription_id)
+            .map_err(|_| SubscriberManagerError::SubscriptionNotFound(subscription_id.get_unique_id().to_string()))?;
+
+        let writers = db.get_shared_folder_writers(&streamer_profile, &shared_folder)?;
+        let is_writer = writers.iter().any(|writer| {
+            *writer == requester_shinkai_identity.to_string()
+                || *writer == requester_shinkai_identity.get_node_name_string()
+        }) || WorkspaceManager::is_folder_writer(
+            &db,
+            &streamer_shinkai_identity,
+            &shared_folder,
+            &requester_shinkai_identity,
+        )
+        .map_err(|e| SubscriberManagerError::DatabaseError(e.to_string()))?;
+        if !is_writer {
+            return Err(SubscriberManagerError::InvalidSubscriber(format!(
+                "{} can't push changes to {}",
+                requester_shinkai_identity, shared_folder
+            )));
+        }
+
+        let vector_fs = self
+            .vector_fs
+            .upgrade()
+            .ok_or(SubscriberManagerError::VectorFSNotAvailable(
+                "VectorFS instance is not available".to_string(),
+            ))?;
+        let folder_prefix = format!("{}/", shared_folder.trim_end_matches('/'));
+
+        let mut accepted = Vec::new();
+        let mut conflicts = Vec::new();
+        for item in push.items {
+            if !item.path.starts_with(&folder_prefix) {
+                return Err(SubscriberManagerError::InvalidRequest(format!(
+                    "{} is not in {}",
+                    item.path, shared_folder
+                )));
+            }
+            let vr_path =
+                VRPath::from_string(&item.path).map_err(|e| SubscriberManagerError::InvalidRequest(e.to_string()))?;
+
+            let current_last_modified = match vector_fs
+                .new_reader(
+                    streamer_shinkai_identity.clone(),
+                    vr_path.clone(),
+                    streamer_shinkai_identity.clone(),
+                )
+                .await
+            {
+                Ok(reader) => Some(
+                    vector_fs
+                        .retrieve_fs_entry(&reader)
+                        .await?
+                        .as_item()?
+                        .last_written_datetime,
+                ),
+                Err(_) => None,
+            };
+            if let Some(conflict) =
+                SharedFolderConflict::check(&item.path, item.base_last_modified, current_last_modified)
+            {
+                conflicts.push(conflict);
+                continue;
+            }
+
+            match item.vrkai {
+                Some(encoded) => {
+                    let vrkai = VRKai::from_base64(&encoded)?;
+                    if vr_path.last_path_id().ok().as_deref() != Some(vrkai.resource.as_trait_object().name()) {
+                        return Err(SubscriberManagerError::InvalidRequest(format!(
+                            "The item pushed to {} has a different name",
+                            item.path
+                        )));
+                    }
+                    let folder_writer = vector_fs
+                        .new_writer(
+                            streamer_shinkai_identity.clone(),
+                            VRPath::root(),
+                            streamer_shinkai_identity.clone(),
+                        )
+                        .await?;
+                    vector_fs
+                        .create_new_folder_auto(&folder_writer, vr_path.parent_path())
+                        .await?;
+                    let writer = vector_fs
+                        .new_writer(
+                            streamer_shinkai_identity.clone(),
+                            vr_path.parent_path(),
+                            streamer_shinkai_identity.clone(),
+                        )
+                        .await?;
+                    vector_fs.save_vrkai_in_folder(&writer, vrkai).await?;
+                }
+                None if current_last_modified.is_some() => {
+                    let writer = vector_fs
+                        .new_writer(
+                            streamer_shinkai_identity.clone(),
+                            vr_path,
+                            streamer_shinkai_identity.clone(),
+                        )
+                        .await?;
+                    vector_fs.delete_item(&writer).await?;
+                }
+                None => {}
+            }
+            accepted.push(item.path);
+        }
+
+        if !accepted.is_empty() {
+            let _ = self.update_shared_folders().await;
+            db.write_notification_of_kind(
+                streamer_shinkai_identity.clone(),
+                NotificationKind::Subscription,
+                format!(
+                    "{} pushed changes to {} items of shared folder '{}'.",
+                    requester_shinkai_identity,
+                    accepted.len(),
+                    shared_folder
+                ),
+            )
+            .map_err(|e| SubscriberManagerError::DatabaseError(e.to_string()))?;
+        }
+
+        Ok(SharedFolderPushResult {
+            push_id: push.push_id,
+            shared_folder,
+            accepted,
+            conflicts,
+            error: None,
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn create_and_send_request_updated_state(
         subscription_id: SubscriptionId,
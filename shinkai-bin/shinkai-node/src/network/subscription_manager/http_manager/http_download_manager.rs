@@ -1,10 +1,13 @@
 use crate::db::Topic;
+use crate::managers::operation_registry::{OperationKind, OPERATIONS};
+use crate::network::node_events::NodeEventType;
 use crate::network::subscription_manager::fs_entry_tree::FSEntryTree;
 use crate::vector_fs::vector_fs::VectorFS;
 use crate::{db::ShinkaiDB, llm_provider::queue::job_queue_manager::JobQueueManager};
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT_ENCODING};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use shinkai_message_primitives::schemas::shinkai_subscription::ShinkaiSubscription;
 use shinkai_message_primitives::schemas::{shinkai_name::ShinkaiName, shinkai_subscription::SubscriptionId};
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
@@ -215,6 +218,30 @@ impl HttpDownloadManager {
 
             let handle = tokio::spawn(async move {
                 let _permit = semaphore_clone.acquire().await.unwrap();
+                let operation = OPERATIONS.start_keyed(
+                    OperationKind::SubscriptionSync,
+                    job_id.subscription_id.get_unique_id(),
+                    format!(
+                        "Downloading the files of subscription {}",
+                        job_id.subscription_id.get_unique_id()
+                    ),
+                );
+                if operation.is_cancelled() {
+                    // The remaining files of a cancelled sync are dropped without downloading them
+                    let mut job_queue = job_queue_manager.lock().await;
+                    let _ = job_queue.dequeue(job_id.subscription_id.get_unique_id()).await;
+                    let remaining_jobs = job_queue
+                        .get_all_elements_interleave()
+                        .await
+                        .unwrap_or(Vec::new())
+                        .into_iter()
+                        .any(|job| job.subscription_id == job_id.subscription_id);
+                    if !remaining_jobs {
+                        operation.finish::<(), String>(&Ok(()));
+                    }
+                    return;
+                }
+
                 // Call the new function to download and save the file
                 if let Err(e) = HttpDownloadManager::download_and_save_file(job_id.clone(), vector_fs_clone).await {
                     println!("Error processing job {:?}: {}", job_id, e);
@@ -235,15 +262,33 @@ impl HttpDownloadManager {
                         .await;
 
                     // Check if all jobs for this subscription ID are completed
-                    let remaining_jobs = {
+                    let remaining_job_count = {
                         let job_queue = job_queue_manager.lock().await;
                         job_queue
                             .get_all_elements_interleave()
                             .await
                             .unwrap_or(Vec::new())
                             .into_iter()
-                            .any(|job| job.subscription_id == job_id.subscription_id)
+                            .filter(|job| job.subscription_id == job_id.subscription_id)
+                            .count()
                     };
+                    let remaining_jobs = remaining_job_count > 0;
+                    operation.set_progress(*counter as u64, (*counter + remaining_job_count) as u64);
+                    if !remaining_jobs {
+                        operation.finish::<(), String>(&Ok(()));
+                    }
+
+                    if let Some(db_lock) = db_clone.upgrade() {
+                        db_lock.events.publish(
+                            NodeEventType::SubscriptionSync,
+                            json!({
+                                "subscription_id": job_id.subscription_id.get_unique_id(),
+                                "path": job_id.info.path,
+                                "downloaded_files": *counter,
+                                "is_done": !remaining_jobs,
+                            }),
+                        );
+                    }
 
                     if !remaining_jobs {
                         // Send notification
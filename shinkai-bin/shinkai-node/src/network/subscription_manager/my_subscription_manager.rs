@@ -9,12 +9,16 @@ use crate::network::subscription_manager::subscriber_manager_error::SubscriberMa
 use crate::network::ws_manager::WSUpdateHandler;
 use crate::network::Node;
 use crate::schemas::identity::StandardIdentity;
+use crate::schemas::notification::NotificationKind;
 use crate::vector_fs::vector_fs::VectorFS;
 use crate::vector_fs::vector_fs_types::FSEntry;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use ed25519_dalek::SigningKey;
 use futures::Future;
 use lru::LruCache;
+use shinkai_message_primitives::schemas::shared_folder_sync::{
+    SharedFolderPush, SharedFolderPushItem, SharedFolderPushResult,
+};
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
 use shinkai_message_primitives::schemas::shinkai_proxy_builder_info::ShinkaiProxyBuilderInfo;
 use shinkai_message_primitives::schemas::shinkai_subscription::{
@@ -40,6 +44,7 @@ use std::sync::Arc;
 use std::sync::Weak;
 use std::time::Duration;
 use tokio::sync::Mutex;
+use uuid::Uuid;
 
 use super::external_subscriber_manager::SharedFolderInfo;
 use super::fs_entry_tree::FSEntryTree;
@@ -410,6 +415,185 @@ impl MySubscriptionsManager {
         }
     }
 
+    /// Pushes the local changes to items of a subscribed folder back to the node sharing it.
+    /// Each item carries the version it was last synced at, so changes made on both sides are reported as conflicts.
+    pub async fn push_to_shared_folder(
+        &self,
+        streamer_node_name: ShinkaiName,
+        streamer_profile: String,
+        my_profile: String,
+        folder_name: String,
+        paths: Vec<String>,
+    ) -> Result<String, SubscriberManagerError> {
+        let db = self
+            .db
+            .upgrade()
+            .ok_or(SubscriberManagerError::DatabaseError("Unable to access DB".to_string()))?;
+        let vector_fs = self
+            .vector_fs
+            .upgrade()
+            .ok_or(SubscriberManagerError::VectorFSNotAvailable(
+                "Unable to access VectorFS".to_string(),
+            ))?;
+
+        let my_node_name = ShinkaiName::new(self.node_name.get_node_name_string())?;
+        let subscription_id = SubscriptionId::new(
+            streamer_node_name.clone(),
+            streamer_profile.clone(),
+            folder_name.clone(),
+            my_node_name,
+            my_profile.clone(),
+        );
+        match db.get_my_subscription(subscription_id.get_unique_id()) {
+            Ok(_) => {}
+            Err(ShinkaiDBError::DataNotFound) => {
+                return Err(SubscriberManagerError::SubscriptionNotFound(
+                    "Subscription does not exist.".to_string(),
+                ));
+            }
+            Err(e) => return Err(SubscriberManagerError::DatabaseError(e.to_string())),
+        }
+        let sync_bases = db.get_shared_folder_sync_bases(subscription_id.get_unique_id())?;
+
+        let local_subscriber =
+            ShinkaiName::from_node_and_profile_names(self.node_name.get_node_name_string(), my_profile.clone())?;
+        let folder_prefix = format!("{}/", folder_name.trim_end_matches('/'));
+        let mut items = Vec::new();
+        for path in paths {
+            if !path.starts_with(&folder_prefix) {
+                return Err(SubscriberManagerError::InvalidRequest(format!(
+                    "{} is not in {}",
+                    path, folder_name
+                )));
+            }
+            // Items are synced under /My Subscriptions, and the ones missing there are deleted
+            let local_path = VRPath::from_string(&format!("/My Subscriptions{}", path))
+                .map_err(|e| SubscriberManagerError::InvalidRequest(e.to_string()))?;
+            let vrkai = match vector_fs
+                .new_reader(local_subscriber.clone(), local_path, local_subscriber.clone())
+                .await
+            {
+                Ok(reader) => Some(vector_fs.retrieve_vrkai(&reader).await?.encode_as_base64()?),
+                Err(_) => None,
+            };
+            items.push(SharedFolderPushItem {
+                base_last_modified: sync_bases.get(&path).cloned(),
+                path,
+                vrkai,
+            });
+        }
+        if items.is_empty() {
+            return Err(SubscriberManagerError::InvalidRequest("No items to push".to_string()));
+        }
+
+        let identity_manager_arc = self
+            .identity_manager
+            .upgrade()
+            .ok_or(SubscriberManagerError::IdentityManagerUnavailable)?;
+        let standard_identity = identity_manager_arc
+            .lock()
+            .await
+            .external_profile_to_global_identity(&streamer_node_name.get_node_name_string())
+            .await?;
+        let receiver_public_key = standard_identity.node_encryption_public_key;
+        let proxy_builder_info = self.get_proxy_builder_info(identity_manager_arc).await;
+
+        let push = SharedFolderPush {
+            push_id: Uuid::new_v4().to_string(),
+            shared_folder: folder_name,
+            items,
+        };
+        let msg = ShinkaiMessageBuilder::p2p_shared_folder_sync_message(
+            &push,
+            MessageSchemaType::SharedFolderPush,
+            clone_static_secret_key(&self.my_encryption_secret_key),
+            clone_signature_secret_key(&self.my_signature_secret_key),
+            receiver_public_key,
+            self.node_name.get_node_name_string(),
+            my_profile,
+            streamer_node_name.get_node_name_string(),
+            streamer_profile,
+            proxy_builder_info,
+        )
+        .map_err(|e| SubscriberManagerError::MessageProcessingError(e.to_string()))?;
+
+        Self::send_message_to_peer(
+            msg,
+            self.db.clone(),
+            standard_identity,
+            self.my_encryption_secret_key.clone(),
+            self.identity_manager.clone(),
+            self.proxy_connection_info.clone(),
+            self.ws_manager.clone(),
+        )
+        .await?;
+
+        Ok(push.push_id)
+    }
+
+    /// Notifies the profile that pushed changes to a shared folder of the items accepted and the conflicts
+    pub fn receive_shared_folder_push_result(
+        db: &ShinkaiDB,
+        my_profile: ShinkaiName,
+        streamer: &ShinkaiName,
+        result: SharedFolderPushResult,
+    ) -> Result<(), SubscriberManagerError> {
+        let message = match result.error {
+            Some(error) => format!(
+                "Changes to shared folder '{}' of {} were rejected: {}",
+                result.shared_folder, streamer, error
+            ),
+            None if result.conflicts.is_empty() => format!(
+                "{} changes to shared folder '{}' of {} were accepted.",
+                result.accepted.len(),
+                result.shared_folder,
+                streamer
+            ),
+            None => format!(
+                "{} changes to shared folder '{}' of {} were accepted. These items were changed by {} too and weren't updated: {}",
+                result.accepted.len(),
+                result.shared_folder,
+                streamer,
+                streamer,
+                result
+                    .conflicts
+                    .iter()
+                    .map(|conflict| conflict.path.clone())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        };
+        db.write_notification_of_kind(my_profile, NotificationKind::Subscription, message)?;
+        Ok(())
+    }
+
+    /// Keeps the version of the items of the subscription received from the node sharing the folder,
+    /// so the changes pushed back to it can be checked for conflicts
+    pub fn record_shared_folder_sync_bases(
+        db: &ShinkaiDB,
+        subscription_id: &SubscriptionId,
+        diff: &FSEntryTree,
+    ) -> Result<(), SubscriberManagerError> {
+        fn collect(tree: &FSEntryTree, bases: &mut HashMap<String, DateTime<Utc>>) {
+            if tree.children.is_empty() {
+                // Deleted items come with an epoch timestamp
+                if tree.last_modified.timestamp() == 0 {
+                    bases.remove(&tree.path);
+                } else {
+                    bases.insert(tree.path.clone(), tree.last_modified);
+                }
+            }
+            for child in tree.children.values() {
+                collect(child, bases);
+            }
+        }
+
+        let mut bases = db.get_shared_folder_sync_bases(subscription_id.get_unique_id())?;
+        collect(diff, &mut bases);
+        db.set_shared_folder_sync_bases(subscription_id.get_unique_id(), &bases)?;
+        Ok(())
+    }
+
     // TODO: add new fn to create a scheduler for an HTTP API
     // it needs to be able to ping the API and check if the folder has been updated
     // probably we can expand the api endpoint to return some versioning (timestamp / merkle tree root hash)
@@ -682,7 +866,8 @@ impl MySubscriptionsManager {
                     };
                     if streamer_full_name == shared_folder_name {
                         // Count the number of files for the specific subscription folder
-                        let file_count = shared_folder_infos.iter()
+                        let file_count = shared_folder_infos
+                            .iter()
                             .filter(|info| info.path == subscription.shared_folder)
                             .map(|info| info.tree.count_files())
                             .sum::<usize>();
@@ -1397,7 +1582,9 @@ impl MySubscriptionsManager {
                 // Extracted the last 8 bytes of the merkle hash
                 if let Some(web_link) = &tree.web_link {
                     let last8_in_streamer = &web_link.file.last_8_hash;
-                    let last8_in_streamer = last8_in_streamer.get(last8_in_streamer.len().saturating_sub(8)..).unwrap_or("");
+                    let last8_in_streamer = last8_in_streamer
+                        .get(last8_in_streamer.len().saturating_sub(8)..)
+                        .unwrap_or("");
                     return last_8_bytes == last8_in_streamer;
                 }
             }
@@ -2,8 +2,12 @@ use crate::{
     db::db_errors::ShinkaiDBError,
     lance_db::shinkai_lance_db::LanceShinkaiDb,
     llm_provider::job_manager::JobManager,
-    managers::IdentityManager,
+    managers::{
+        node_health::{HealthTargets, NodeHealth, NodeHealthChecker},
+        IdentityManager,
+    },
     network::{
+        error_code::ErrorCode,
         node::ProxyConnectionInfo,
         node_api_router::{APIError, SendResponseBodyData},
         node_error::NodeError,
@@ -17,7 +21,14 @@ use crate::{
         inbox_permission::InboxPermission,
         smart_inbox::SmartInbox,
     },
-    tools::{js_toolkit::JSToolkit, shinkai_tool::ShinkaiTool, tool_router::ToolRouter, workflow_tool::WorkflowTool},
+    tools::{
+        js_toolkit::JSToolkit,
+        shinkai_tool::ShinkaiTool,
+        tool_router::ToolRouter,
+        toolkit_package::{SignedToolkitPackage, ToolkitProvenance, MAX_TOOLKIT_PACKAGE_SIZE},
+        wasm_tools::WasmToolPermissions,
+        workflow_tool::WorkflowTool,
+    },
     utils::update_global_identity::update_global_identity_name,
     vector_fs::vector_fs::VectorFS,
 };
@@ -27,6 +38,7 @@ use aes_gcm::Aes256Gcm;
 use aes_gcm::KeyInit;
 use async_channel::Sender;
 use blake3::Hasher;
+use chrono::Utc;
 use ed25519_dalek::{SigningKey, VerifyingKey};
 use log::error;
 use reqwest::StatusCode;
@@ -41,9 +53,9 @@ use shinkai_message_primitives::{
     shinkai_message::{
         shinkai_message::{MessageBody, MessageData, ShinkaiMessage},
         shinkai_message_schemas::{
-            APIAddAgentRequest, APIAddOllamaModels, APIChangeJobAgentRequest, APIGetMessagesFromInboxRequest,
-            APIReadUpToTimeRequest, APISetWorkflow, APIWorkflowKeyname, IdentityPermissions, MessageSchemaType,
-            RegistrationCodeRequest, RegistrationCodeType,
+            APIAddAgentRequest, APIAddOllamaModels, APIChangeJobAgentRequest, APIGetAllSmartInboxesForProfile,
+            APIGetMessagesFromInboxRequest, APIInstallToolkitFromURL, APIReadUpToTimeRequest, APISetWorkflow,
+            APIWorkflowKeyname, IdentityPermissions, MessageSchemaType, RegistrationCodeRequest, RegistrationCodeType,
         },
     },
     shinkai_utils::{
@@ -97,6 +109,7 @@ impl Node {
     ) -> Result<bool, NodeError> {
         let std_device = std_identity.clone().to_standard_identity().ok_or(NodeError {
             message: "Failed to convert to standard identity".to_string(),
+            error_code: ErrorCode::InternalError,
         })?;
         Self::has_standard_identity_access(db, inbox_name, &std_device).await
     }
@@ -123,6 +136,7 @@ impl Node {
                     "Invalid Identity type. You don't have enough permissions to access the inbox: {}",
                     inbox_name
                 ),
+                error_code: ErrorCode::PermissionDenied,
             }),
         }
     }
@@ -161,12 +175,14 @@ impl Node {
                 _ => {
                     return Err(NodeError {
                         message: "Message data is encrypted".into(),
+                        error_code: ErrorCode::InternalError,
                     })
                 }
             },
             _ => {
                 return Err(NodeError {
                     message: "Message body is encrypted".into(),
+                    error_code: ErrorCode::InternalError,
                 })
             }
         };
@@ -178,6 +194,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to parse GetLastMessagesFromInboxRequest: {}", e),
                 };
@@ -192,6 +209,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to parse InboxName: {}", e),
                 };
@@ -216,6 +234,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::FORBIDDEN.as_u16(),
+                            error_code: ErrorCode::PermissionDenied,
                             error: "Don't have access".to_string(),
                             message: format!(
                                 "Permission denied. You don't have enough permissions to access the inbox: {}",
@@ -231,6 +250,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!(
                             "Invalid identity type. Only StandardIdentity is allowed. Value: {:?}",
@@ -317,6 +337,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to parse GetLastMessagesFromInboxRequest: {}", e),
                 };
@@ -331,6 +352,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to parse InboxName: {}", e),
                 };
@@ -359,6 +381,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::FORBIDDEN.as_u16(),
+                            error_code: ErrorCode::PermissionDenied,
                             error: "Don't have access".to_string(),
                             message: format!(
                                 "Permission denied. You don't have enough permissions to access the inbox: {}",
@@ -374,6 +397,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!(
                             "Invalid identity type. Only StandardIdentity is allowed. Value: {:?}",
@@ -416,6 +440,7 @@ impl Node {
                 if std_identity.permission_type != IdentityPermissions::Admin {
                     return Err(NodeError {
                         message: "Permission denied. Only Admin can perform this operation.".to_string(),
+                        error_code: ErrorCode::PermissionDenied,
                     });
                 }
             }
@@ -423,6 +448,7 @@ impl Node {
                 if std_device.permission_type != IdentityPermissions::Admin {
                     return Err(NodeError {
                         message: "Permission denied. Only Admin can perform this operation.".to_string(),
+                        error_code: ErrorCode::PermissionDenied,
                     });
                 }
             }
@@ -430,6 +456,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!(
                             "Invalid identity type. Only StandardIdentity is allowed. Value: {:?}",
@@ -447,6 +474,7 @@ impl Node {
         let create_registration_code: RegistrationCodeRequest =
             serde_json::from_str(&content).map_err(|e| NodeError {
                 message: format!("Failed to parse CreateRegistrationCode: {}", e),
+                error_code: ErrorCode::InternalError,
             })?;
 
         let permissions = create_registration_code.permissions;
@@ -455,7 +483,12 @@ impl Node {
         // permissions: IdentityPermissions,
         // code_type: RegistrationCodeType,
 
-        match db.generate_registration_new_code(permissions, code_type) {
+        match db.generate_registration_new_code_with_limits(
+            permissions,
+            code_type,
+            create_registration_code.ttl_secs,
+            create_registration_code.max_uses,
+        ) {
             Ok(code) => {
                 let _ = res.send(Ok(code)).await.map_err(|_| ());
             }
@@ -463,6 +496,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidRegistrationCode,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to generate registration code: {}", err),
                     }))
@@ -508,6 +542,7 @@ impl Node {
                 // If there was an error, send the error message
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: err.error_code,
                     error: "Internal Server Error".to_string(),
                     message: format!("{}", err),
                 };
@@ -544,6 +579,7 @@ impl Node {
         let content = msg.get_message_content()?;
         let read_up_to_time: APIReadUpToTimeRequest = serde_json::from_str(&content).map_err(|e| NodeError {
             message: format!("Failed to parse APIReadUpToTimeRequest: {}", e),
+            error_code: ErrorCode::InternalError,
         })?;
 
         let inbox_name = read_up_to_time.inbox_name;
@@ -565,6 +601,7 @@ impl Node {
                             let _ = res
                                 .send(Err(APIError {
                                     code: StatusCode::BAD_REQUEST.as_u16(),
+                                    error_code: ErrorCode::InvalidInput,
                                     error: "Bad Request".to_string(),
                                     message: format!("Failed to mark as read up to time: {}", up_to_time),
                                 }))
@@ -575,6 +612,7 @@ impl Node {
                             let _ = res
                                 .send(Err(APIError {
                                     code: StatusCode::FORBIDDEN.as_u16(),
+                                    error_code: ErrorCode::PermissionDenied,
                                     error: "Don't have access".to_string(),
                                     message: format!(
                                         "Permission denied. You don't have enough permissions to access the inbox: {}",
@@ -589,6 +627,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::FORBIDDEN.as_u16(),
+                            error_code: ErrorCode::PermissionDenied,
                             error: "Don't have access".to_string(),
                             message: format!(
                                 "Permission denied. You don't have enough permissions to access the inbox: {}",
@@ -604,6 +643,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!(
                             "Invalid identity type. Only StandardIdentity is allowed. Value: {:?}",
@@ -646,6 +686,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!("Failed to parse encryption public key: {}", err),
                     }))
@@ -665,6 +706,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to decrypt message: {}", e),
                 };
@@ -681,6 +723,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!("Failed to get message content: {}", err),
                     }))
@@ -702,6 +745,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!("Failed to deserialize the content: {}", err),
                     }))
@@ -781,6 +825,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to check if main profile exists: {}", err),
                     }))
@@ -811,6 +856,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::BAD_REQUEST.as_u16(),
+                            error_code: ErrorCode::InvalidRegistrationCode,
                             error: "Internal Server Error".to_string(),
                             message: format!("Failed to generate registration code: {}", err),
                         }))
@@ -873,6 +919,7 @@ impl Node {
                             let _ = res
                                 .send(Err(APIError {
                                     code: StatusCode::BAD_REQUEST.as_u16(),
+                                    error_code: ErrorCode::InvalidInput,
                                     error: "Internal Server Error".to_string(),
                                     message: format!("Failed to add device subidentity: {}", err),
                                 }))
@@ -908,6 +955,7 @@ impl Node {
                                 let _ = res
                                     .send(Err(APIError {
                                         code: StatusCode::BAD_REQUEST.as_u16(),
+                                        error_code: ErrorCode::InvalidInput,
                                         error: "Internal Server Error".to_string(),
                                         message: format!("Failed to add device subidentity: {}", err),
                                     }))
@@ -1017,6 +1065,7 @@ impl Node {
                                 let _ = res
                                     .send(Err(APIError {
                                         code: StatusCode::BAD_REQUEST.as_u16(),
+                                        error_code: ErrorCode::InvalidInput,
                                         error: "Internal Server Error".to_string(),
                                         message: format!("Failed to add device subidentity: {}", err),
                                     }))
@@ -1034,6 +1083,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to add device subidentity: {}", err),
                     }))
@@ -1075,6 +1125,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: "Inbox name must be in an unencrypted message.".to_string(),
                     }))
@@ -1091,6 +1142,7 @@ impl Node {
                             if res.send(Ok(())).await.is_err() {
                                 let error = APIError {
                                     code: 500,
+                                    error_code: ErrorCode::InternalError,
                                     error: "ChannelSendError".to_string(),
                                     message: "Failed to send data through the channel".to_string(),
                                 };
@@ -1102,6 +1154,7 @@ impl Node {
                             let _ = res
                                 .send(Err(APIError {
                                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                                    error_code: ErrorCode::InternalError,
                                     error: "Failed to update inbox name".to_string(),
                                     message: e,
                                 }))
@@ -1119,6 +1172,7 @@ impl Node {
                                 if res.send(Ok(())).await.is_err() {
                                     let error = APIError {
                                         code: 500,
+                                        error_code: ErrorCode::InternalError,
                                         error: "ChannelSendError".to_string(),
                                         message: "Failed to send data through the channel".to_string(),
                                     };
@@ -1130,6 +1184,7 @@ impl Node {
                                 let _ = res
                                     .send(Err(APIError {
                                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                                        error_code: ErrorCode::InternalError,
                                         error: "Failed to update inbox name".to_string(),
                                         message: e,
                                     }))
@@ -1141,6 +1196,7 @@ impl Node {
                         let _ = res
                             .send(Err(APIError {
                                 code: StatusCode::FORBIDDEN.as_u16(),
+                                error_code: ErrorCode::PermissionDenied,
                                 error: "Don't have access".to_string(),
                                 message:
                                     "Permission denied. You don't have enough permissions to update this inbox name."
@@ -1155,6 +1211,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!(
                             "Invalid identity type. Only StandardIdentity is allowed. Value: {:?}",
@@ -1192,7 +1249,13 @@ impl Node {
             }
         };
 
-        let profile_requested: String = msg.get_message_content()?;
+        // The content is either the name of the profile or an APIGetAllSmartInboxesForProfile
+        let content = msg.get_message_content()?;
+        let (profile_requested, include_archived) =
+            match serde_json::from_str::<APIGetAllSmartInboxesForProfile>(&content) {
+                Ok(request) => (request.profile, request.include_archived),
+                Err(_) => (content, false),
+            };
 
         // Check that the message is coming from someone with the right permissions to do this action
         match sender {
@@ -1204,6 +1267,7 @@ impl Node {
                         let _ = res
                         .send(Err(APIError {
                             code: StatusCode::FORBIDDEN.as_u16(),
+                            error_code: ErrorCode::PermissionDenied,
                             error: "Don't have access".to_string(),
                             message: format!(
                                 "Permission denied. You don't have enough permissions to see this profile's inboxes list: {}",
@@ -1223,6 +1287,7 @@ impl Node {
                         db.clone(),
                         identity_manager.clone(),
                         profile_requested,
+                        include_archived,
                     )
                     .await;
 
@@ -1230,6 +1295,7 @@ impl Node {
                     if res.send(Ok(inboxes)).await.is_err() {
                         let error = APIError {
                             code: 500,
+                            error_code: ErrorCode::InternalError,
                             error: "ChannelSendError".to_string(),
                             message: "Failed to send data through the channel".to_string(),
                         };
@@ -1241,6 +1307,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::FORBIDDEN.as_u16(),
+                            error_code: ErrorCode::PermissionDenied,
                             error: "Don't have access".to_string(),
                             message: format!(
                                 "Permission denied. You don't have enough permissions to see this profile's inboxes list: {}",
@@ -1263,6 +1330,7 @@ impl Node {
                         db.clone(),
                         identity_manager.clone(),
                         profile_requested,
+                        include_archived,
                     )
                     .await;
 
@@ -1270,6 +1338,7 @@ impl Node {
                     if res.send(Ok(inboxes)).await.is_err() {
                         let error = APIError {
                             code: 500,
+                            error_code: ErrorCode::InternalError,
                             error: "ChannelSendError".to_string(),
                             message: "Failed to send data through the channel".to_string(),
                         };
@@ -1281,6 +1350,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::FORBIDDEN.as_u16(),
+                            error_code: ErrorCode::PermissionDenied,
                             error: "Don't have access".to_string(),
                             message: format!(
                                 "Permission denied. You don't have enough permissions to see this profile's inboxes list: {}",
@@ -1295,6 +1365,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!(
                             "Invalid identity type. Only StandardIdentity is allowed. Value: {:?}",
@@ -1350,6 +1421,7 @@ impl Node {
                         let _ = res
                         .send(Err(APIError {
                             code: StatusCode::FORBIDDEN.as_u16(),
+                            error_code: ErrorCode::PermissionDenied,
                             error: "Don't have access".to_string(),
                             message: format!(
                                 "Permission denied. You don't have enough permissions to see this profile's inboxes list: {}",
@@ -1376,6 +1448,7 @@ impl Node {
                     if res.send(Ok(inboxes)).await.is_err() {
                         let error = APIError {
                             code: 500,
+                            error_code: ErrorCode::InternalError,
                             error: "ChannelSendError".to_string(),
                             message: "Failed to send data through the channel".to_string(),
                         };
@@ -1387,6 +1460,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::FORBIDDEN.as_u16(),
+                            error_code: ErrorCode::PermissionDenied,
                             error: "Don't have access".to_string(),
                             message: format!(
                                 "Permission denied. You don't have enough permissions to see this profile's inboxes list: {}",
@@ -1416,6 +1490,7 @@ impl Node {
                     if res.send(Ok(inboxes)).await.is_err() {
                         let error = APIError {
                             code: 500,
+                            error_code: ErrorCode::InternalError,
                             error: "ChannelSendError".to_string(),
                             message: "Failed to send data through the channel".to_string(),
                         };
@@ -1427,6 +1502,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::FORBIDDEN.as_u16(),
+                            error_code: ErrorCode::PermissionDenied,
                             error: "Don't have access".to_string(),
                             message: format!(
                                 "Permission denied. You don't have enough permissions to see this profile's inboxes list: {}",
@@ -1441,6 +1517,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!(
                             "Invalid identity type. Only StandardIdentity is allowed. Value: {:?}",
@@ -1489,6 +1566,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::InternalError,
                             error: "Internal Server Error".to_string(),
                             message: format!("{}", err),
                         }))
@@ -1503,6 +1581,7 @@ impl Node {
         if definition_file.is_none() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Missing definition.json file".to_string(),
             };
@@ -1516,6 +1595,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!("Failed to parse definition file as UTF-8: {}", e),
                     }))
@@ -1530,6 +1610,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "User Error".to_string(),
                     message: format!("Failed to parse JSON: {}", err),
                 };
@@ -1542,6 +1623,7 @@ impl Node {
         if tool_definition.code.is_none() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Tool definition is missing the code field".to_string(),
             };
@@ -1559,6 +1641,7 @@ impl Node {
             if let Err(err) = lance_db.set_tool(&shinkai_tool).await {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to install toolkit: {}", err),
                 };
@@ -1571,6 +1654,187 @@ impl Node {
         Ok(())
     }
 
+    pub async fn api_install_toolkit_from_url(
+        db: Arc<ShinkaiDB>,
+        lance_db: Arc<Mutex<LanceShinkaiDb>>,
+        node_name: ShinkaiName,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        encryption_secret_key: EncryptionStaticKey,
+        potentially_encrypted_msg: ShinkaiMessage,
+        res: Sender<Result<JsonValue, APIError>>,
+    ) -> Result<(), NodeError> {
+        let (request, _) = match Self::validate_and_extract_payload::<APIInstallToolkitFromURL>(
+            node_name.clone(),
+            identity_manager.clone(),
+            encryption_secret_key,
+            potentially_encrypted_msg,
+            MessageSchemaType::APIInstallToolkitFromURL,
+        )
+        .await
+        {
+            Ok(data) => data,
+            Err(api_error) => {
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let result = Self::install_toolkit_from_url(db, lance_db, request).await;
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    /// Downloads, verifies and installs a signed toolkit package, granting it the permissions the user accepted
+    pub async fn install_toolkit_from_url(
+        db: Arc<ShinkaiDB>,
+        lance_db: Arc<Mutex<LanceShinkaiDb>>,
+        request: APIInstallToolkitFromURL,
+    ) -> Result<JsonValue, APIError> {
+        let bad_request = |message: String| APIError {
+            code: StatusCode::BAD_REQUEST.as_u16(),
+            error_code: ErrorCode::BadRequest,
+            error: "Bad Request".to_string(),
+            message,
+        };
+
+        // Download the package
+        let package_bytes = match Self::download_toolkit_package(&request.url).await {
+            Ok(bytes) => bytes,
+            Err(err) => return Err(bad_request(format!("Failed to download toolkit package: {}", err))),
+        };
+
+        // Verify the signature and the declared permissions
+        let package = match SignedToolkitPackage::from_bytes(&package_bytes) {
+            Ok(package) => package,
+            Err(err) => return Err(bad_request(format!("Invalid toolkit package: {}", err))),
+        };
+        let trusted_keys = SignedToolkitPackage::trusted_publisher_keys();
+        let manifest = match package.verify(&trusted_keys, request.expected_public_key.as_deref()) {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                return Err(APIError {
+                    code: StatusCode::FORBIDDEN.as_u16(),
+                    error_code: ErrorCode::PermissionDenied,
+                    error: "Forbidden".to_string(),
+                    message: format!("Toolkit package verification failed: {}", err),
+                });
+            }
+        };
+        let accepted_permissions = WasmToolPermissions {
+            network_allowed_hosts: request.accepted_network_hosts.clone(),
+            vector_fs_read: request.accepted_vector_fs_read.clone(),
+        };
+        if let Err(err) = manifest.check_permissions(&accepted_permissions) {
+            return Err(APIError {
+                code: StatusCode::FORBIDDEN.as_u16(),
+                error_code: ErrorCode::PermissionDenied,
+                error: "Forbidden".to_string(),
+                message: err.to_string(),
+            });
+        }
+
+        let toolkit_name = manifest.name.clone();
+        let version = manifest.version.clone();
+        let author = manifest.author.clone();
+        let permissions = manifest.permissions.clone();
+        let tools = match manifest.into_tools() {
+            Ok(tools) => tools,
+            Err(err) => return Err(bad_request(format!("Invalid toolkit: {}", err))),
+        };
+
+        // Install the tools
+        let mut tool_router_keys = Vec::new();
+        {
+            let lance_db = lance_db.lock().await;
+            for shinkai_tool in tools {
+                if let Err(err) = lance_db.set_tool(&shinkai_tool).await {
+                    let api_error = APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
+                        error: "Internal Server Error".to_string(),
+                        message: format!("Failed to install toolkit: {}", err),
+                    };
+                    return Err(api_error);
+                }
+                tool_router_keys.push(shinkai_tool.tool_router_key());
+            }
+        }
+
+        // The user accepted the declared permissions when installing, so they are granted right away
+        let mut grants = match db.get_toolkit_capability_grants(&toolkit_name) {
+            Ok(grants) => grants,
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to read toolkit capabilities: {}", err),
+                };
+                return Err(api_error);
+            }
+        };
+        grants.grant(permissions.to_capabilities());
+        if let Err(err) = db.set_toolkit_capability_grants(&grants) {
+            let api_error = APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error_code: ErrorCode::InternalError,
+                error: "Internal Server Error".to_string(),
+                message: format!("Failed to grant toolkit capabilities: {}", err),
+            };
+            return Err(api_error);
+        }
+
+        // Record where it came from
+        let provenance = ToolkitProvenance {
+            toolkit_name,
+            version,
+            author,
+            source_url: request.url,
+            publisher_public_key: package.public_key.to_lowercase(),
+            package_hash: blake3::hash(&package_bytes).to_hex().to_string(),
+            permissions,
+            tool_router_keys,
+            installed_at: Utc::now().to_rfc3339(),
+        };
+        if let Err(err) = db.set_toolkit_provenance(&provenance) {
+            let api_error = APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error_code: ErrorCode::InternalError,
+                error: "Internal Server Error".to_string(),
+                message: format!("Toolkit installed but failed to save its provenance: {}", err),
+            };
+            return Err(api_error);
+        }
+
+        Ok(json!(provenance))
+    }
+
+    async fn download_toolkit_package(url: &str) -> Result<Vec<u8>, String> {
+        let parsed_url = reqwest::Url::parse(url).map_err(|e| e.to_string())?;
+        if parsed_url.scheme() != "https" && parsed_url.scheme() != "http" {
+            return Err(format!("Unsupported URL scheme: {}", parsed_url.scheme()));
+        }
+
+        let mut response = reqwest::get(parsed_url).await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Server responded with {}", response.status()));
+        }
+        let too_big = || format!("Toolkit package is bigger than {} bytes", MAX_TOOLKIT_PACKAGE_SIZE);
+        if response.content_length().unwrap_or(0) as usize > MAX_TOOLKIT_PACKAGE_SIZE {
+            return Err(too_big());
+        }
+
+        // The Content-Length can be missing or wrong, so the size is also checked while streaming the body
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            if bytes.len() + chunk.len() > MAX_TOOLKIT_PACKAGE_SIZE {
+                return Err(too_big());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+
     pub async fn api_list_toolkits(
         db: Arc<Mutex<LanceShinkaiDb>>,
         node_name: ShinkaiName,
@@ -1599,6 +1863,7 @@ impl Node {
         if let Err(err) = profile {
             let api_error = APIError {
                 code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error_code: ErrorCode::InternalError,
                 error: "Internal Server Error".to_string(),
                 message: err.to_string(),
             };
@@ -1616,6 +1881,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to fetch toolkits: {}", err),
                     }))
@@ -1631,6 +1897,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to convert toolkits to JSON: {}", err),
                     }))
@@ -1671,6 +1938,7 @@ impl Node {
         if let Err(err) = profile {
             let api_error = APIError {
                 code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error_code: ErrorCode::InternalError,
                 error: "Internal Server Error".to_string(),
                 message: err.to_string(),
             };
@@ -1692,6 +1960,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to remove toolkit: {}", err),
                 };
@@ -1731,6 +2000,7 @@ impl Node {
             _ => {
                 let error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: "Failed to extract inbox name from the message".to_string(),
                 };
@@ -1744,6 +2014,7 @@ impl Node {
             _ => {
                 let error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: "Expected a JobInbox".to_string(),
                 };
@@ -1768,6 +2039,7 @@ impl Node {
                                     let _ = res
                                         .send(Err(APIError {
                                             code: StatusCode::BAD_REQUEST.as_u16(),
+                                            error_code: ErrorCode::InvalidInput,
                                             error: "Bad Request".to_string(),
                                             message: format!("{}", err),
                                         }))
@@ -1777,6 +2049,7 @@ impl Node {
                                     let _ = res
                                         .send(Err(APIError {
                                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                                            error_code: ErrorCode::InternalError,
                                             error: "Internal Server Error".to_string(),
                                             message: format!("{}", err),
                                         }))
@@ -1790,6 +2063,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::FORBIDDEN.as_u16(),
+                            error_code: ErrorCode::PermissionDenied,
                             error: "Don't have access".to_string(),
                             message: "Permission denied. You don't have enough permissions to update this job."
                                 .to_string(),
@@ -1802,6 +2076,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!(
                             "Invalid identity type. Only StandardIdentity is allowed. Value: {:?}",
@@ -1841,6 +2116,7 @@ impl Node {
         if res.send(Ok(subidentities)).await.is_err() {
             let error = APIError {
                 code: 500,
+                error_code: ErrorCode::InternalError,
                 error: "ChannelSendError".to_string(),
                 message: "Failed to send data through the channel".to_string(),
             };
@@ -1888,6 +2164,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: "Invalid sender identity name.".to_string(),
                     }))
@@ -1901,6 +2178,7 @@ impl Node {
             let _ = res
                 .send(Err(APIError {
                     code: StatusCode::FORBIDDEN.as_u16(),
+                    error_code: ErrorCode::PermissionDenied,
                     error: "Don't have access".to_string(),
                     message: "Permission denied. The sender identity does not belong to this node.".to_string(),
                 }))
@@ -1942,6 +2220,7 @@ impl Node {
                 // If there was an error, send the error message
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: err.error_code,
                     error: "Internal Server Error".to_string(),
                     message: format!("{}", err),
                 };
@@ -1979,6 +2258,7 @@ impl Node {
             .get_profile_name_string()
             .ok_or(NodeError {
                 message: "Profile name not found".to_string(),
+                error_code: ErrorCode::ProfileNotFound,
             })?;
 
         match Self::internal_get_llm_providers_for_profile(db.clone(), node_name.clone().node_name, profile).await {
@@ -1988,6 +2268,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("{}", err),
                 };
@@ -2038,6 +2319,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::UNAUTHORIZED.as_u16(),
+                        error_code: ErrorCode::Unauthorized,
                         error: "Unauthorized".to_string(),
                         message: "Sender identity must be a Profile type with admin privileges.".to_string(),
                     }))
@@ -2048,6 +2330,7 @@ impl Node {
             let _ = res
                 .send(Err(APIError {
                     code: StatusCode::UNAUTHORIZED.as_u16(),
+                    error_code: ErrorCode::Unauthorized,
                     error: "Unauthorized".to_string(),
                     message: "Sender identity is not supported or cannot be converted to a StandardIdentity."
                         .to_string(),
@@ -2064,6 +2347,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("{}", err),
                 };
@@ -2124,6 +2408,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::UNAUTHORIZED.as_u16(),
+                        error_code: ErrorCode::Unauthorized,
                         error: "Unauthorized".to_string(),
                         message: "Sender identity must be a Profile type with admin privileges.".to_string(),
                     }))
@@ -2134,6 +2419,7 @@ impl Node {
             let _ = res
                 .send(Err(APIError {
                     code: StatusCode::UNAUTHORIZED.as_u16(),
+                    error_code: ErrorCode::Unauthorized,
                     error: "Unauthorized".to_string(),
                     message: "Sender identity is not supported or cannot be converted to a StandardIdentity."
                         .to_string(),
@@ -2160,6 +2446,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to add model: {}", err),
                 };
@@ -2206,6 +2493,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to get message content: {}", e),
                 };
@@ -2221,6 +2509,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to parse APIAddAgentRequest: {}", e),
                 };
@@ -2239,6 +2528,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create profile: {}", err),
                 };
@@ -2267,6 +2557,7 @@ impl Node {
                 // If there was an error, send the error message
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("{}", err),
                 };
@@ -2307,6 +2598,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to get agent ID from message: {}", e),
                 };
@@ -2321,6 +2613,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create profile: {}", err),
                 };
@@ -2339,6 +2632,7 @@ impl Node {
                 Err(err) => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to remove agent from identity manager: {}", err),
                     };
@@ -2349,6 +2643,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to remove agent: {}", err),
                 };
@@ -2388,6 +2683,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to get profiles with access: {}", err),
                 };
@@ -2400,6 +2696,7 @@ impl Node {
             let _ = res
                 .send(Err(APIError {
                     code: StatusCode::FORBIDDEN.as_u16(),
+                    error_code: ErrorCode::PermissionDenied,
                     error: "Forbidden".to_string(),
                     message: "Profile does not have access to modify this agent".to_string(),
                 }))
@@ -2418,6 +2715,7 @@ impl Node {
                         Err(err) => {
                             let api_error = APIError {
                                 code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                                error_code: ErrorCode::InternalError,
                                 error: "Internal Server Error".to_string(),
                                 message: format!("Failed to update agent in identity manager: {}", err),
                             };
@@ -2429,6 +2727,7 @@ impl Node {
                 Err(err) => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to update agent: {}", err),
                     };
@@ -2470,6 +2769,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!("Failed to get message content: {}", e),
                     }))
@@ -2484,6 +2784,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!("Failed to parse APIChangeJobAgentRequest: {}", e),
                     }))
@@ -2498,6 +2799,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::FORBIDDEN.as_u16(),
+                        error_code: ErrorCode::PermissionDenied,
                         error: "Don't have access".to_string(),
                         message: "Permission denied. You don't have enough permissions to change this job agent."
                             .to_string(),
@@ -2520,6 +2822,7 @@ impl Node {
                         Err(err) => {
                             let api_error = APIError {
                                 code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                                error_code: ErrorCode::InternalError,
                                 error: "Internal Server Error".to_string(),
                                 message: format!("Failed to change job agent: {}", err),
                             };
@@ -2532,6 +2835,7 @@ impl Node {
                         .has_permission(&inbox_name, &std_identity, InboxPermission::Admin)
                         .map_err(|e| NodeError {
                             message: format!("Failed to check permissions: {}", e),
+                            error_code: ErrorCode::PermissionDenied,
                         })?;
                     if has_permission {
                         match db.change_job_llm_provider(&change_request.job_id, &change_request.new_agent_id) {
@@ -2542,6 +2846,7 @@ impl Node {
                             Err(err) => {
                                 let api_error = APIError {
                                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                                    error_code: ErrorCode::InternalError,
                                     error: "Internal Server Error".to_string(),
                                     message: format!("Failed to change job agent: {}", err),
                                 };
@@ -2553,6 +2858,7 @@ impl Node {
                         let _ = res
                             .send(Err(APIError {
                                 code: StatusCode::FORBIDDEN.as_u16(),
+                                error_code: ErrorCode::PermissionDenied,
                                 error: "Don't have access".to_string(),
                                 message:
                                     "Permission denied. You don't have enough permissions to change this job agent."
@@ -2567,6 +2873,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!(
                             "Invalid identity type. Only StandardIdentity is allowed. Value: {:?}",
@@ -2612,6 +2919,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to decrypt message: {}", err),
                 };
@@ -2626,6 +2934,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to extract message content: {}", err),
                 };
@@ -2654,6 +2963,7 @@ impl Node {
         // Convert the hex string to bytes
         let private_key_bytes = hex::decode(&content).map_err(|_| APIError {
             code: StatusCode::BAD_REQUEST.as_u16(),
+            error_code: ErrorCode::InvalidInput,
             error: "Bad Request".to_string(),
             message: "Invalid private key".to_string(),
         })?;
@@ -2661,6 +2971,7 @@ impl Node {
         // Convert the Vec<u8> to a [u8; 32]
         let private_key_array: [u8; 32] = private_key_bytes.try_into().map_err(|_| APIError {
             code: StatusCode::BAD_REQUEST.as_u16(),
+            error_code: ErrorCode::InvalidInput,
             error: "Bad Request".to_string(),
             message: "Failed to convert private key to array".to_string(),
         })?;
@@ -2677,6 +2988,7 @@ impl Node {
         db.write_symmetric_key(&hash_hex, &private_key_array)
             .map_err(|err| APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: format!("{}", err),
             })?;
@@ -2685,6 +2997,7 @@ impl Node {
         db.create_files_message_inbox(hash_hex.clone())
             .map_err(|err| APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: format!("Failed to create files message inbox: {}", err),
             })?;
@@ -2735,6 +3048,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("{}", err),
                     }))
@@ -2760,6 +3074,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::BAD_REQUEST.as_u16(),
+                            error_code: ErrorCode::InvalidInput,
                             error: "Bad Request".to_string(),
                             message: "Invalid public key".to_string(),
                         }))
@@ -2785,6 +3100,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::BadRequest,
                         error: "Bad Request".to_string(),
                         message: "Failed to decrypt the file.".to_string(),
                     }))
@@ -2817,6 +3133,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("{}", err),
                     }))
@@ -2832,6 +3149,17 @@ impl Node {
         Ok(())
     }
 
+    pub async fn api_node_health(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        targets: HealthTargets,
+        res: Sender<NodeHealth>,
+    ) -> Result<(), NodeError> {
+        let health = NodeHealthChecker::check(db, vector_fs, targets).await;
+        let _ = res.send(health).await;
+        Ok(())
+    }
+
     pub async fn api_get_local_processing_preference(
         db: Arc<ShinkaiDB>,
         node_name: ShinkaiName,
@@ -2863,6 +3191,7 @@ impl Node {
             let _ = res
                 .send(Err(APIError {
                     code: StatusCode::FORBIDDEN.as_u16(),
+                    error_code: ErrorCode::PermissionDenied,
                     error: "Forbidden".to_string(),
                     message: "You don't have permission to access this setting".to_string(),
                 }))
@@ -2879,6 +3208,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to get local processing preference: {}", err),
                     }))
@@ -2925,6 +3255,7 @@ impl Node {
             let _ = res
                 .send(Err(APIError {
                     code: StatusCode::NOT_FOUND.as_u16(),
+                    error_code: ErrorCode::IdentityNotFound,
                     error: "Not Found".to_string(),
                     message: "Sender identity not found".to_string(),
                 }))
@@ -2937,6 +3268,7 @@ impl Node {
             let _ = res
                 .send(Err(APIError {
                     code: StatusCode::FORBIDDEN.as_u16(),
+                    error_code: ErrorCode::PermissionDenied,
                     error: "Forbidden".to_string(),
                     message: "You don't have permission to update this setting".to_string(),
                 }))
@@ -2953,6 +3285,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to update local processing preference: {}", err),
                     }))
@@ -2995,6 +3328,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -3012,6 +3346,7 @@ impl Node {
                 Ok(workflows) => {
                     let workflows_json = serde_json::to_value(workflows).map_err(|err| NodeError {
                         message: format!("Failed to serialize workflows: {}", err),
+                        error_code: ErrorCode::InternalError,
                     })?;
                     // Log the elapsed time if LOG_ALL is set to 1
                     if std::env::var("LOG_ALL").unwrap_or_default() == "1" {
@@ -3026,6 +3361,7 @@ impl Node {
                 Err(err) => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to search workflows: {}", err),
                     };
@@ -3036,6 +3372,7 @@ impl Node {
         } else {
             let api_error = APIError {
                 code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error_code: ErrorCode::InternalError,
                 error: "Internal Server Error".to_string(),
                 message: "Tool router is not available".to_string(),
             };
@@ -3076,6 +3413,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -3093,6 +3431,7 @@ impl Node {
                 Ok(tools) => {
                     let tools_json = serde_json::to_value(tools).map_err(|err| NodeError {
                         message: format!("Failed to serialize tools: {}", err),
+                        error_code: ErrorCode::InternalError,
                     })?;
                     // Log the elapsed time if LOG_ALL is set to 1
                     if std::env::var("LOG_ALL").unwrap_or_default() == "1" {
@@ -3107,6 +3446,7 @@ impl Node {
                 Err(err) => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to search tools: {}", err),
                     };
@@ -3117,6 +3457,7 @@ impl Node {
         } else {
             let api_error = APIError {
                 code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error_code: ErrorCode::InternalError,
                 error: "Internal Server Error".to_string(),
                 message: "Tool router is not available".to_string(),
             };
@@ -3154,6 +3495,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -3167,6 +3509,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to create workflow: {}", err),
                 };
@@ -3185,6 +3528,7 @@ impl Node {
             if let Err(err) = lance_db.set_tool(&shinkai_tool).await {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to save workflow: {}", err),
                 };
@@ -3226,6 +3570,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -3242,6 +3587,7 @@ impl Node {
             if let Err(err) = lance_db.remove_tool(&workflow_key_str).await {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to remove workflow: {}", err),
                 };
@@ -3283,6 +3629,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -3301,6 +3648,7 @@ impl Node {
                 Ok(None) => {
                     let api_error = APIError {
                         code: StatusCode::NOT_FOUND.as_u16(),
+                        error_code: ErrorCode::WorkflowNotFound,
                         error: "Not Found".to_string(),
                         message: "Workflow not found".to_string(),
                     };
@@ -3310,6 +3658,7 @@ impl Node {
                 Err(err) => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to get workflow: {}", err),
                     };
@@ -3353,6 +3702,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -3368,6 +3718,7 @@ impl Node {
                 Err(err) => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to list workflows: {}", err),
                     };
@@ -3410,6 +3761,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -3425,6 +3777,7 @@ impl Node {
                 Err(err) => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to list tools: {}", err),
                     };
@@ -3468,6 +3821,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -3481,6 +3835,7 @@ impl Node {
             Ok(None) => {
                 let api_error = APIError {
                     code: StatusCode::NOT_FOUND.as_u16(),
+                    error_code: ErrorCode::ToolNotFound,
                     error: "Not Found".to_string(),
                     message: "Tool not found in LanceShinkaiDb".to_string(),
                 };
@@ -3490,6 +3845,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::DatabaseError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to fetch tool from LanceShinkaiDb: {}", err),
                 };
@@ -3504,6 +3860,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to convert existing tool to Value: {}", err),
                 };
@@ -3521,6 +3878,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert merged Value to ShinkaiTool: {}", err),
                 };
@@ -3552,6 +3910,7 @@ impl Node {
                     Ok(None) => {
                         let api_error = APIError {
                             code: StatusCode::NOT_FOUND.as_u16(),
+                            error_code: ErrorCode::ToolNotFound,
                             error: "Not Found".to_string(),
                             message: "Tool not found in LanceShinkaiDb".to_string(),
                         };
@@ -3561,6 +3920,7 @@ impl Node {
                     Err(err) => {
                         let api_error = APIError {
                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::DatabaseError,
                             error: "Internal Server Error".to_string(),
                             message: format!("Failed to fetch tool from LanceShinkaiDb: {}", err),
                         };
@@ -3572,6 +3932,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to set tool: {}", err),
                 };
@@ -3609,6 +3970,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -3622,6 +3984,7 @@ impl Node {
             Ok(None) => {
                 let api_error = APIError {
                     code: StatusCode::NOT_FOUND.as_u16(),
+                    error_code: ErrorCode::ToolNotFound,
                     error: "Not Found".to_string(),
                     message: "Tool not found".to_string(),
                 };
@@ -3631,6 +3994,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to fetch tool: {}", err),
                 };
@@ -3672,6 +4036,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -3685,6 +4050,7 @@ impl Node {
             Err(_) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Invalid embedding model provided".to_string(),
                 };
@@ -3704,6 +4070,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to update default embedding model: {}", err),
                 };
@@ -3742,6 +4109,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -3759,6 +4127,7 @@ impl Node {
         if let Err(err) = db.update_supported_embedding_models(new_supported_models.clone()) {
             let api_error = APIError {
                 code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error_code: ErrorCode::InternalError,
                 error: "Internal Server Error".to_string(),
                 message: format!("Failed to update supported embedding models: {}", err),
             };
@@ -3779,6 +4148,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to update supported embedding models: {}", err),
                 };
@@ -3828,6 +4198,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: "Invalid node name".to_string(),
                     }))
@@ -3850,6 +4221,7 @@ impl Node {
                         let _ = res
                             .send(Err(APIError {
                                 code: StatusCode::FORBIDDEN.as_u16(),
+                                error_code: ErrorCode::PermissionDenied,
                                 error: "Forbidden".to_string(),
                                 message: "The keys do not match with the current node".to_string(),
                             }))
@@ -3861,6 +4233,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::NOT_FOUND.as_u16(),
+                            error_code: ErrorCode::IdentityNotFound,
                             error: "Not Found".to_string(),
                             message: "The new node name does not exist in the blockchain".to_string(),
                         }))
@@ -3882,6 +4255,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("{}", err),
                     }))
@@ -3908,6 +4282,7 @@ impl Node {
             let _ = res
                 .send(Err(APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Invalid node name: @@localhost".to_string(),
                 }))
@@ -3929,6 +4304,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!("Error validating message: {}", api_error.message),
                     }))
@@ -3985,6 +4361,7 @@ impl Node {
                             let _ = res
                                 .send(Err(APIError {
                                     code: StatusCode::BAD_REQUEST.as_u16(),
+                                    error_code: ErrorCode::InvalidInput,
                                     error: "Bad Request".to_string(),
                                     message: format!("Error checking if sender has access to inbox: {}", e),
                                 }))
@@ -3998,6 +4375,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::BAD_REQUEST.as_u16(),
+                            error_code: ErrorCode::InvalidInput,
                             error: "Bad Request".to_string(),
                             message: format!("Error getting inbox from message: {}", e),
                         }))
@@ -4028,6 +4406,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Error".to_string(),
                         message: err,
                     }))
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::{db::ShinkaiDB, network::{node_api_router::APIError, node_error::NodeError, Node}};
+use crate::{db::ShinkaiDB, network::{error_code::ErrorCode, node_api_router::APIError, node_error::NodeError, Node}};
 
 use async_channel::Sender;
 use reqwest::StatusCode;
@@ -20,6 +20,7 @@ impl Node {
                 // If there was an error, send the error message
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("{}", err),
                 };
@@ -18,6 +18,7 @@ use shinkai_message_primitives::{
 use utoipa::ToSchema;
 use warp::Buf;
 
+use crate::managers::node_health::HealthStatus;
 use crate::network::node_api_router::handle_node_command;
 use crate::network::node_api_router::APIError;
 use crate::network::node_api_router::GetPublicKeysResponse;
@@ -137,6 +138,21 @@ pub async fn add_toolkit_handler(
     .await
 }
 
+pub async fn install_toolkit_from_url_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    message: ShinkaiMessage,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    handle_node_command(
+        node_commands_sender,
+        message,
+        |_node_commands_sender, message, res_sender| NodeCommand::APIInstallToolkitFromURL {
+            msg: message,
+            res: res_sender,
+        },
+    )
+    .await
+}
+
 pub async fn retrieve_vrkai_handler(
     node_commands_sender: Sender<NodeCommand>,
     message: ShinkaiMessage,
@@ -1298,29 +1314,22 @@ pub async fn shinkai_health_handler(
     node_commands_sender: Sender<NodeCommand>,
     node_name: String,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    let version = env!("CARGO_PKG_VERSION");
-
-    // Create a channel to receive the result
     let (res_sender, res_receiver) = async_channel::bounded(1);
-
-    // Send the command to the node
     node_commands_sender
-        .send(NodeCommand::APIIsPristine { res: res_sender })
+        .send(NodeCommand::APINodeHealth {
+            node_name,
+            res: res_sender,
+        })
         .await
         .map_err(|_| warp::reject::reject())?;
-
-    // Receive the result
-    let pristine_state = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
-
-    // Check if there was an error
-    if let Err(error) = pristine_state {
-        return Ok(warp::reply::json(&json!({ "status": "error", "error": error })));
-    }
-
-    // If there was no error, proceed as usual
-    Ok(warp::reply::json(
-        &json!({ "status": "ok", "version": version, "node_name": node_name, "is_pristine": pristine_state.unwrap() }),
-    ))
+    let health = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    // Load balancers stop routing to the node, a degraded node can still serve most requests
+    let status = match health.status {
+        HealthStatus::Unhealthy => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::OK,
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&health), status))
 }
 
 pub async fn get_all_subidentities_handler(
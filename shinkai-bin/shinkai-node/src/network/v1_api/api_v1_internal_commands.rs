@@ -1,15 +1,18 @@
 use crate::db::ShinkaiDB;
 use crate::llm_provider::job_manager::JobManager;
 use crate::managers::identity_manager::IdentityManagerTrait;
+use crate::managers::model_capabilities_prober::ModelCapabilitiesProber;
 use crate::managers::IdentityManager;
 use crate::network::network_manager::network_handlers::{ping_pong, PingPong};
 use crate::network::node::ProxyConnectionInfo;
+use crate::network::error_code::ErrorCode;
 use crate::network::node_error::NodeError;
 use crate::network::ws_manager::WSUpdateHandler;
 use crate::network::Node;
 use crate::schemas::{
     identity::{Identity, StandardIdentity},
     inbox_permission::InboxPermission,
+    network_peer::NetworkPeer,
     smart_inbox::SmartInbox,
 };
 use crate::welcome_files::welcome_message::WELCOME_MESSAGE;
@@ -27,6 +30,7 @@ use shinkai_message_primitives::{
         inbox_name::InboxName,
         llm_providers::serialized_llm_provider::{LLMProviderInterface, Ollama, SerializedLLMProvider},
         shinkai_name::ShinkaiName,
+        shinkai_network::ProtocolHello,
     },
     shinkai_message::shinkai_message::ShinkaiMessage,
     shinkai_utils::{
@@ -42,12 +46,22 @@ use tokio::sync::Mutex;
 use x25519_dalek::{PublicKey as EncryptionPublicKey, StaticSecret as EncryptionStaticKey};
 
 impl Node {
-    pub async fn send_peer_addresses(
+    pub async fn send_peers(
         peers: CHashMap<(SocketAddr, String), chrono::DateTime<chrono::Utc>>,
-        sender: Sender<Vec<SocketAddr>>,
+        db: Arc<ShinkaiDB>,
+        sender: Sender<Vec<NetworkPeer>>,
     ) -> Result<(), Error> {
-        let peer_addresses: Vec<SocketAddr> = peers.into_iter().map(|(k, _)| k.0).collect();
-        sender.send(peer_addresses).await.unwrap();
+        let network_peers: Vec<NetworkPeer> = peers
+            .into_iter()
+            .map(|((address, profile_name), last_seen)| {
+                let node_name = ShinkaiName::new(profile_name.clone())
+                    .map(|name| name.get_node_name_string())
+                    .unwrap_or_else(|_| profile_name.clone());
+                let protocol = db.get_peer_protocol(&node_name).ok().flatten();
+                NetworkPeer::new(address, profile_name, last_seen, protocol)
+            })
+            .collect();
+        sender.send(network_peers).await.unwrap();
         Ok(())
     }
 
@@ -163,6 +177,7 @@ impl Node {
         db: Arc<ShinkaiDB>,
         identity_manager: Arc<Mutex<IdentityManager>>,
         full_profile_name: String,
+        include_archived: bool,
     ) -> Vec<SmartInbox> {
         // Obtain the IdentityManager and ShinkaiDB locks
         let identity_manager = identity_manager.lock().await;
@@ -194,7 +209,7 @@ impl Node {
                 return Vec::new();
             }
         };
-        let result = match db.get_all_smart_inboxes_for_profile(standard_identity) {
+        let result = match db.get_all_smart_inboxes_for_profile(standard_identity, include_archived) {
             Ok(inboxes) => inboxes,
             Err(e) => {
                 shinkai_log(
@@ -262,7 +277,7 @@ impl Node {
         db.mark_as_read_up_to(inbox_name, up_to_time).map_err(|e| {
             let error_message = format!("Failed to mark messages as read: {}", e);
             error!("{}", &error_message);
-            NodeError { message: error_message }
+            NodeError { message: error_message , error_code: ErrorCode::InternalError}
         })?;
         Ok(true)
     }
@@ -338,6 +353,7 @@ impl Node {
                     _ => {
                         return Err(NodeError {
                             message: "Sender is not a StandardIdentity".to_string(),
+                            error_code: ErrorCode::InternalError,
                         })
                     }
                 };
@@ -361,6 +377,7 @@ impl Node {
             Err(e) => {
                 return Err(NodeError {
                     message: format!("Failed to create profile name: {}", e),
+                    error_code: ErrorCode::InternalError,
                 })
             }
         };
@@ -370,6 +387,7 @@ impl Node {
             Err(e) => {
                 return Err(NodeError {
                     message: format!("Failed to get llm providers for profile: {}", e),
+                    error_code: ErrorCode::InternalError,
                 })
             }
         };
@@ -386,6 +404,7 @@ impl Node {
             Ok(_) => Ok(()),
             Err(err) => Err(NodeError {
                 message: format!("Error with process job message: {}", err),
+                error_code: err.error_code(),
             }),
         }
     }
@@ -409,6 +428,30 @@ impl Node {
                     Ok(_) => {
                         drop(subidentity_manager);
 
+                        // Probe unknown models in the background so registration isn't blocked by slow providers
+                        if ModelCapabilitiesProber::needs_probing(&llm_provider.model)
+                            && db
+                                .get_probed_model_capabilities(
+                                    &llm_provider.model,
+                                    llm_provider.external_url.as_deref(),
+                                )
+                                .is_err()
+                        {
+                            let db_clone = db.clone();
+                            let llm_provider_clone = llm_provider.clone();
+                            tokio::spawn(async move {
+                                if let Err(e) =
+                                    ModelCapabilitiesProber::probe_and_store(db_clone, llm_provider_clone).await
+                                {
+                                    shinkai_log(
+                                        ShinkaiLogOption::Node,
+                                        ShinkaiLogLevel::Error,
+                                        &format!("Failed to probe model capabilities: {}", e),
+                                    );
+                                }
+                            });
+                        }
+
                         let (has_job_inbox, welcome_message) = if profile.has_agent() {
                             (false, false)
                         } else {
@@ -441,6 +484,7 @@ impl Node {
                             let job_creation = JobCreationInfo {
                                 scope: job_scope,
                                 is_hidden: Some(false),
+                                retrieval: None,
                             };
 
                             let mut job_manager_locked = job_manager.lock().await;
@@ -452,6 +496,7 @@ impl Node {
                                 Err(err) => {
                                     return Err(NodeError {
                                         message: format!("Failed to create job: {}", err),
+                                        error_code: ErrorCode::InternalError,
                                     })
                                 }
                             };
@@ -464,6 +509,7 @@ impl Node {
                                 _ => {
                                     return Err(NodeError {
                                         message: "Sender is not a StandardIdentity".to_string(),
+                                        error_code: ErrorCode::InternalError,
                                     })
                                 }
                             };
@@ -497,6 +543,7 @@ impl Node {
                         error!("Failed to add subidentity: {}", err);
                         Err(NodeError {
                             message: format!("Failed to add device subidentity: {}", err),
+                            error_code: ErrorCode::InternalError,
                         })
                     }
                 }
@@ -521,6 +568,7 @@ impl Node {
                         error!("Failed to remove subidentity: {}", err);
                         Err(NodeError {
                             message: format!("Failed to remove device subidentity: {}", err),
+                            error_code: ErrorCode::InternalError,
                         })
                     }
                 }
@@ -554,6 +602,15 @@ impl Node {
             let receiver = receiver_profile_identity.full_identity_name.get_node_name_string();
             let receiver_public_key = receiver_profile_identity.node_encryption_public_key;
 
+            // The peer learns the protocol of this node from its hello and answers with its own
+            Node::send_protocol_hello(
+                ProtocolHello::new_signed(sender.clone(), false, &identity_secret_key),
+                peer.0,
+                receiver.clone(),
+                proxy_connection_info.clone(),
+                identity_manager.clone(),
+            );
+
             // Important: the receiver doesn't really matter per se as long as it's valid because we are testing the connection
             let _ = ping_pong(
                 peer,
@@ -586,6 +643,7 @@ impl Node {
                     Ok(json) => {
                         let models = json["models"].as_array().ok_or_else(|| NodeError {
                             message: format!("Unexpected response format from {}", url),
+                            error_code: ErrorCode::InternalError,
                         })?;
 
                         let models_with_port: Vec<serde_json::Value> = models
@@ -615,6 +673,7 @@ impl Node {
         if all_models.is_empty() {
             Err(NodeError {
                 message: "No models could be retrieved from any source.".to_string(),
+                error_code: ErrorCode::InternalError,
             })
         } else {
             Ok(all_models)
@@ -7,7 +7,7 @@ use crate::network::subscription_manager::my_subscription_manager::MySubscriptio
 use crate::network::ws_manager::WSUpdateHandler;
 use crate::network::Node;
 use crate::{
-    network::node_api_router::APIError,
+    network::{error_code::ErrorCode, node_api_router::APIError},
     schemas::{identity::Identity, inbox_permission::InboxPermission},
 };
 use async_channel::Sender;
@@ -98,9 +98,11 @@ impl Node {
         db: Arc<ShinkaiDB>,
         permissions: IdentityPermissions,
         code_type: RegistrationCodeType,
+        ttl_secs: Option<u64>,
+        max_uses: Option<u32>,
         res: Sender<String>,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let code = match db.generate_registration_new_code(permissions, code_type) {
+        let code = match db.generate_registration_new_code_with_limits(permissions, code_type, ttl_secs, max_uses) {
             Ok(code) => code,
             Err(e) => {
                 error!("Failed to generate registration new code: {}", e);
@@ -125,6 +127,7 @@ impl Node {
             error!("Failed to send result: {}", e);
             let error = APIError {
                 code: 500,
+                error_code: ErrorCode::InternalError,
                 error: "ChannelSendError".to_string(),
                 message: "Failed to send data through the channel".to_string(),
             };
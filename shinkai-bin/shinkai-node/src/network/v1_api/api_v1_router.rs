@@ -5,10 +5,13 @@ use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage
 use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::APIAvailableSharedItems;
 use warp::Filter;
 
+use crate::network::request_id::request_scoped_sender;
+
 use super::api_v1_handlers::add_agent_handler;
 use super::api_v1_handlers::add_ollama_models_handler;
 use super::api_v1_handlers::add_row_handler;
 use super::api_v1_handlers::add_toolkit_handler;
+use super::api_v1_handlers::install_toolkit_from_url_handler;
 use super::api_v1_handlers::add_workflow_handler;
 use super::api_v1_handlers::api_convert_files_and_save_to_folder_handler;
 use super::api_v1_handlers::api_my_subscriptions_handler;
@@ -96,7 +99,7 @@ pub fn v1_routes(
         let node_commands_sender = node_commands_sender.clone();
         warp::path!("ping_all")
             .and(warp::post())
-            .and_then(move || ping_all_handler(node_commands_sender.clone()))
+            .and_then(move || ping_all_handler(request_scoped_sender(&node_commands_sender)))
     };
 
     let send_msg = {
@@ -104,7 +107,9 @@ pub fn v1_routes(
         warp::path!("send")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| send_msg_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                send_msg_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let identity_name_to_external_profile_data = {
@@ -113,7 +118,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json())
             .and_then(move |body: NameToExternalProfileData| {
-                identity_name_to_external_profile_data_handler(node_commands_sender.clone(), body)
+                identity_name_to_external_profile_data_handler(request_scoped_sender(&node_commands_sender), body)
             })
     };
 
@@ -121,7 +126,7 @@ pub fn v1_routes(
         let node_commands_sender = node_commands_sender.clone();
         warp::path!("get_public_keys")
             .and(warp::get())
-            .and_then(move || get_public_key_handler(node_commands_sender.clone()))
+            .and_then(move || get_public_key_handler(request_scoped_sender(&node_commands_sender)))
     };
 
     let add_toolkit = {
@@ -129,7 +134,19 @@ pub fn v1_routes(
         warp::path!("add_toolkit")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| add_toolkit_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                add_toolkit_handler(request_scoped_sender(&node_commands_sender), message)
+            })
+    };
+
+    let install_toolkit_from_url = {
+        let node_commands_sender = node_commands_sender.clone();
+        warp::path!("install_toolkit_from_url")
+            .and(warp::post())
+            .and(warp::body::json::<ShinkaiMessage>())
+            .and_then(move |message: ShinkaiMessage| {
+                install_toolkit_from_url_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let api_vec_fs_retrieve_path_simplified_json = {
@@ -138,7 +155,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_vec_fs_retrieve_path_simplified_json_handler(node_commands_sender.clone(), message)
+                api_vec_fs_retrieve_path_simplified_json_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -148,7 +165,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_vec_fs_retrieve_path_minimal_json_handler(node_commands_sender.clone(), message)
+                api_vec_fs_retrieve_path_minimal_json_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -158,7 +175,10 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_vec_fs_retrieve_vector_search_simplified_json_handler(node_commands_sender.clone(), message)
+                api_vec_fs_retrieve_vector_search_simplified_json_handler(
+                    request_scoped_sender(&node_commands_sender),
+                    message,
+                )
             })
     };
 
@@ -168,7 +188,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_vec_fs_search_item_handler(node_commands_sender.clone(), message)
+                api_vec_fs_search_item_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -178,7 +198,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_vec_fs_create_folder_handler(node_commands_sender.clone(), message)
+                api_vec_fs_create_folder_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -188,7 +208,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_vec_fs_move_folder_handler(node_commands_sender.clone(), message)
+                api_vec_fs_move_folder_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -198,7 +218,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_vec_fs_copy_folder_handler(node_commands_sender.clone(), message)
+                api_vec_fs_copy_folder_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -208,7 +228,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_vec_fs_remove_folder_handler(node_commands_sender.clone(), message)
+                api_vec_fs_remove_folder_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -218,7 +238,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_vec_fs_move_item_handler(node_commands_sender.clone(), message)
+                api_vec_fs_move_item_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -228,7 +248,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_vec_fs_copy_item_handler(node_commands_sender.clone(), message)
+                api_vec_fs_copy_item_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -238,7 +258,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_vec_fs_remove_item_handler(node_commands_sender.clone(), message)
+                api_vec_fs_remove_item_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -248,7 +268,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_convert_files_and_save_to_folder_handler(node_commands_sender.clone(), message)
+                api_convert_files_and_save_to_folder_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -258,7 +278,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_vec_fs_retrieve_vector_resource_handler(node_commands_sender.clone(), message)
+                api_vec_fs_retrieve_vector_resource_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -267,7 +287,7 @@ pub fn v1_routes(
         let node_commands_sender = node_commands_sender.clone();
         warp::path!("shinkai_health")
             .and(warp::get())
-            .and_then(move || shinkai_health_handler(node_commands_sender.clone(), node_name.clone()))
+            .and_then(move || shinkai_health_handler(request_scoped_sender(&node_commands_sender), node_name.clone()))
     };
 
     let available_llm_providers = {
@@ -276,7 +296,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                available_llm_providers_handler(node_commands_sender.clone(), message)
+                available_llm_providers_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -285,7 +305,9 @@ pub fn v1_routes(
         warp::path!("add_agent")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| add_agent_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                add_agent_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let modify_agent = {
@@ -293,7 +315,9 @@ pub fn v1_routes(
         warp::path!("modify_agent")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| modify_agent_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                modify_agent_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let remove_agent = {
@@ -301,7 +325,9 @@ pub fn v1_routes(
         warp::path!("remove_agent")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| remove_agent_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                remove_agent_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let get_last_messages_from_inbox = {
@@ -310,7 +336,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                get_last_messages_from_inbox_handler(node_commands_sender.clone(), message)
+                get_last_messages_from_inbox_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -320,7 +346,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                get_last_unread_messages_from_inbox_handler(node_commands_sender.clone(), message)
+                get_last_unread_messages_from_inbox_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -330,7 +356,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                get_all_inboxes_for_profile_handler(node_commands_sender.clone(), message)
+                get_all_inboxes_for_profile_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -340,7 +366,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                get_all_smart_inboxes_for_profile_handler(node_commands_sender.clone(), message)
+                get_all_smart_inboxes_for_profile_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -350,7 +376,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                update_smart_inbox_name_handler(node_commands_sender.clone(), message)
+                update_smart_inbox_name_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -359,7 +385,9 @@ pub fn v1_routes(
         warp::path!("create_job")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| create_job_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                create_job_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let job_message = {
@@ -367,7 +395,9 @@ pub fn v1_routes(
         warp::path!("job_message")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| job_message_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                job_message_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let get_filenames = {
@@ -376,7 +406,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                get_filenames_message_handler(node_commands_sender.clone(), message)
+                get_filenames_message_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -385,7 +415,9 @@ pub fn v1_routes(
         warp::path!("mark_as_read_up_to")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| mark_as_read_up_to_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                mark_as_read_up_to_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let create_registration_code = {
@@ -394,7 +426,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                create_registration_code_handler(node_commands_sender.clone(), message)
+                create_registration_code_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -404,7 +436,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                use_registration_code_handler(node_commands_sender.clone(), message)
+                use_registration_code_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -413,14 +445,16 @@ pub fn v1_routes(
         warp::path!("change_nodes_name")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| change_nodes_name_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                change_nodes_name_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let get_all_subidentities = {
         let node_commands_sender = node_commands_sender.clone();
         warp::path!("get_all_subidentities")
             .and(warp::get())
-            .and_then(move || get_all_subidentities_handler(node_commands_sender.clone()))
+            .and_then(move || get_all_subidentities_handler(request_scoped_sender(&node_commands_sender)))
     };
 
     let get_last_messages_from_inbox_with_branches = {
@@ -429,7 +463,10 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                get_last_messages_from_inbox_with_branches_handler(node_commands_sender.clone(), message)
+                get_last_messages_from_inbox_with_branches_handler(
+                    request_scoped_sender(&node_commands_sender),
+                    message,
+                )
             })
     };
 
@@ -439,7 +476,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                create_files_inbox_with_symmetric_key_handler(node_commands_sender.clone(), message)
+                create_files_inbox_with_symmetric_key_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -451,7 +488,7 @@ pub fn v1_routes(
             .and(warp::multipart::form().max_length(1024 * 1024 * 200))
             .and_then(
                 move |string1: String, string2: String, form: warp::multipart::FormData| {
-                    handle_file_upload(node_commands_sender.clone(), string1, string2, form)
+                    handle_file_upload(request_scoped_sender(&node_commands_sender), string1, string2, form)
                 },
             )
     };
@@ -462,7 +499,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                update_job_to_finished_handler(node_commands_sender.clone(), message)
+                update_job_to_finished_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -472,7 +509,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_subscription_available_shared_items_handler(node_commands_sender.clone(), message)
+                api_subscription_available_shared_items_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -482,7 +519,10 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<APIAvailableSharedItems>())
             .and_then(move |message: APIAvailableSharedItems| {
-                api_subscription_available_shared_items_open_handler(node_commands_sender.clone(), message)
+                api_subscription_available_shared_items_open_handler(
+                    request_scoped_sender(&node_commands_sender),
+                    message,
+                )
             })
     };
 
@@ -492,7 +532,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_my_subscriptions_handler(node_commands_sender.clone(), message)
+                api_my_subscriptions_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -502,7 +542,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_subscription_create_shareable_folder_handler(node_commands_sender.clone(), message)
+                api_subscription_create_shareable_folder_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -512,7 +552,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                subscribe_to_shared_folder_handler(node_commands_sender.clone(), message)
+                subscribe_to_shared_folder_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -522,7 +562,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_subscription_update_shareable_folder_handler(node_commands_sender.clone(), message)
+                api_subscription_update_shareable_folder_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -532,7 +572,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_subscription_unshare_folder_handler(node_commands_sender.clone(), message)
+                api_subscription_unshare_folder_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -541,7 +581,9 @@ pub fn v1_routes(
         warp::path!("unsubscribe")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| unsubscribe_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                unsubscribe_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let get_my_subscribers = {
@@ -549,7 +591,9 @@ pub fn v1_routes(
         warp::path!("my_subscribers")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| get_my_subscribers_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                get_my_subscribers_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let retrieve_vrkai = {
@@ -557,7 +601,9 @@ pub fn v1_routes(
         warp::path!("retrieve_vrkai")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| retrieve_vrkai_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                retrieve_vrkai_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let retrieve_vrpack = {
@@ -565,7 +611,9 @@ pub fn v1_routes(
         warp::path!("retrieve_vrpack")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| retrieve_vrpack_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                retrieve_vrpack_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let local_scan_ollama_models = {
@@ -573,7 +621,9 @@ pub fn v1_routes(
         warp::path!("scan_ollama_models")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| scan_ollama_models_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                scan_ollama_models_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let add_ollama_models = {
@@ -581,7 +631,9 @@ pub fn v1_routes(
         warp::path!("add_ollama_models")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| add_ollama_models_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                add_ollama_models_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let get_subscription_links = {
@@ -589,7 +641,7 @@ pub fn v1_routes(
         warp::path!("subscriptions" / String / "links")
             .and(warp::get())
             .and_then(move |subscription_id: String| {
-                get_subscription_links_handler(node_commands_sender.clone(), subscription_id)
+                get_subscription_links_handler(request_scoped_sender(&node_commands_sender), subscription_id)
             })
     };
 
@@ -598,7 +650,9 @@ pub fn v1_routes(
         warp::path!("change_job_agent")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| change_job_agent_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                change_job_agent_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let get_last_notifications = {
@@ -607,7 +661,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                get_last_notifications_handler(node_commands_sender.clone(), message)
+                get_last_notifications_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -617,7 +671,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                get_notifications_before_timestamp_handler(node_commands_sender.clone(), message)
+                get_notifications_before_timestamp_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -627,7 +681,7 @@ pub fn v1_routes(
             .and(warp::get())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                get_local_processing_preference_handler(node_commands_sender.clone(), message)
+                get_local_processing_preference_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -637,7 +691,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                update_local_processing_preference_handler(node_commands_sender.clone(), message)
+                update_local_processing_preference_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -646,7 +700,9 @@ pub fn v1_routes(
         warp::path!("search_workflows")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| search_workflows_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                search_workflows_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let search_shinkai_tool = {
@@ -654,7 +710,9 @@ pub fn v1_routes(
         warp::path!("search_shinkai_tool")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| search_shinkai_tool_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                search_shinkai_tool_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let add_workflow = {
@@ -662,7 +720,9 @@ pub fn v1_routes(
         warp::path!("add_workflow")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| add_workflow_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                add_workflow_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let update_workflow = {
@@ -670,7 +730,9 @@ pub fn v1_routes(
         warp::path!("update_workflow")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| update_workflow_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                update_workflow_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let delete_workflow = {
@@ -678,7 +740,9 @@ pub fn v1_routes(
         warp::path!("delete_workflow")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| delete_workflow_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                delete_workflow_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let get_workflow_info = {
@@ -686,7 +750,9 @@ pub fn v1_routes(
         warp::path!("get_workflow_info")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| get_workflow_info_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                get_workflow_info_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let list_all_workflows = {
@@ -694,7 +760,9 @@ pub fn v1_routes(
         warp::path!("list_all_workflows")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| list_all_workflows_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                list_all_workflows_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let set_column = {
@@ -702,7 +770,9 @@ pub fn v1_routes(
         warp::path!("set_column")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| set_column_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                set_column_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let remove_column = {
@@ -710,7 +780,9 @@ pub fn v1_routes(
         warp::path!("remove_column")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| remove_column_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                remove_column_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let add_row = {
@@ -718,7 +790,9 @@ pub fn v1_routes(
         warp::path!("add_rows")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| add_row_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                add_row_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let remove_row = {
@@ -726,7 +800,9 @@ pub fn v1_routes(
         warp::path!("remove_rows")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| remove_row_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                remove_row_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let user_sheets = {
@@ -734,7 +810,9 @@ pub fn v1_routes(
         warp::path!("user_sheets")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| user_sheets_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                user_sheets_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let create_sheet = {
@@ -742,7 +820,9 @@ pub fn v1_routes(
         warp::path!("create_sheet")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| create_sheet_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                create_sheet_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let remove_sheet = {
@@ -750,7 +830,9 @@ pub fn v1_routes(
         warp::path!("remove_sheet")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| remove_sheet_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                remove_sheet_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let get_sheet = {
@@ -758,7 +840,9 @@ pub fn v1_routes(
         warp::path!("get_sheet")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| get_sheet_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                get_sheet_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let set_cell_value = {
@@ -766,7 +850,9 @@ pub fn v1_routes(
         warp::path!("set_cell_value")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| set_cell_value_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                set_cell_value_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     let api_update_default_embedding_model = {
@@ -775,7 +861,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_update_default_embedding_model_handler(node_commands_sender.clone(), message)
+                api_update_default_embedding_model_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -785,7 +871,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                api_update_supported_embedding_models_handler(node_commands_sender.clone(), message)
+                api_update_supported_embedding_models_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -795,7 +881,7 @@ pub fn v1_routes(
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |message: ShinkaiMessage| {
-                list_all_shinkai_tools_handler(node_commands_sender.clone(), message)
+                list_all_shinkai_tools_handler(request_scoped_sender(&node_commands_sender), message)
             })
     };
 
@@ -806,7 +892,7 @@ pub fn v1_routes(
             .and(warp::query::<HashMap<String, String>>())
             .and(warp::body::json::<ShinkaiMessage>())
             .and_then(move |query_params: HashMap<String, String>, message: ShinkaiMessage| {
-                set_shinkai_tool_handler(node_commands_sender.clone(), query_params, message)
+                set_shinkai_tool_handler(request_scoped_sender(&node_commands_sender), query_params, message)
             })
     };
 
@@ -815,7 +901,9 @@ pub fn v1_routes(
         warp::path!("get_shinkai_tool")
             .and(warp::post())
             .and(warp::body::json::<ShinkaiMessage>())
-            .and_then(move |message: ShinkaiMessage| get_shinkai_tool_handler(node_commands_sender.clone(), message))
+            .and_then(move |message: ShinkaiMessage| {
+                get_shinkai_tool_handler(request_scoped_sender(&node_commands_sender), message)
+            })
     };
 
     ping_all
@@ -823,6 +911,7 @@ pub fn v1_routes(
         .or(identity_name_to_external_profile_data)
         .or(get_public_key)
         .or(add_toolkit)
+        .or(install_toolkit_from_url)
         .or(api_vec_fs_retrieve_path_simplified_json)
         .or(api_vec_fs_retrieve_path_minimal_json)
         .or(api_vec_fs_retrieve_vector_search_simplified_json)
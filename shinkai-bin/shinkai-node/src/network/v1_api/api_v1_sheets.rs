@@ -1,3 +1,4 @@
+use crate::network::error_code::ErrorCode;
 use crate::network::node_error::NodeError;
 use crate::network::Node;
 use crate::{managers::sheet_manager::SheetManager, network::node_api_router::APIError};
@@ -50,6 +51,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -69,6 +71,7 @@ impl Node {
             Err(err_msg) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert column: {}", err_msg),
                 };
@@ -86,6 +89,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to set column: {}", err),
                 };
@@ -123,6 +127,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -145,6 +150,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to remove column: {}", err),
                 };
@@ -182,6 +188,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -202,6 +209,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to get user sheets: {}", err),
                 };
@@ -239,6 +247,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -262,6 +271,7 @@ impl Node {
                     Err(err) => {
                         let api_error = APIError {
                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::InternalError,
                             error: "Internal Server Error".to_string(),
                             message: format!("Failed to update sheet name: {}", err),
                         };
@@ -273,6 +283,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create empty sheet: {}", err),
                 };
@@ -310,6 +321,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -329,6 +341,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to remove sheet: {}", err),
                 };
@@ -366,6 +379,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -389,6 +403,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to set cell value: {}", err),
                 };
@@ -426,6 +441,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -446,6 +462,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::NOT_FOUND.as_u16(),
+                    error_code: ErrorCode::NotFound,
                     error: "Not Found".to_string(),
                     message: format!("Failed to get sheet: {}", err),
                 };
@@ -483,6 +500,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -505,6 +523,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to remove rows: {}", err),
                 };
@@ -542,6 +561,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -560,6 +580,7 @@ impl Node {
                 Err(err) => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to add row: {}", err),
                     };
@@ -4,6 +4,7 @@ use crate::{
     db::ShinkaiDB,
     managers::IdentityManager,
     network::{
+        error_code::ErrorCode,
         node_api_router::APIError,
         node_error::NodeError,
         subscription_manager::{
@@ -61,6 +62,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -91,6 +93,7 @@ impl Node {
                     Err(e) => {
                         let api_error = APIError {
                             code: StatusCode::BAD_REQUEST.as_u16(),
+                            error_code: ErrorCode::InvalidInput,
                             error: "Bad Request".to_string(),
                             message: format!("Failed to convert path to VRPath: {}", e),
                         };
@@ -101,6 +104,7 @@ impl Node {
             Err(_) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Invalid node name provided".to_string(),
                 };
@@ -140,6 +144,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -159,6 +164,7 @@ impl Node {
                         // Handle serialization error
                         let api_error = APIError {
                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::InternalError,
                             error: "Internal Server Error".to_string(),
                             message: format!("Failed to serialize response: {}", e),
                         };
@@ -169,6 +175,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to retrieve subscriptions: {}", e),
                 };
@@ -211,6 +218,7 @@ impl Node {
             if !requester_name.has_profile() {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::BadRequest,
                     error: "Bad Request".to_string(),
                     message: "Requester name does not have a profile".to_string(),
                 };
@@ -225,6 +233,7 @@ impl Node {
             if streamer_full_name.is_err() {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Invalid origin node name or profile name provided".to_string(),
                 };
@@ -256,6 +265,7 @@ impl Node {
                             // Handle serialization error
                             let api_error = APIError {
                                 code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                                error_code: ErrorCode::InternalError,
                                 error: "Internal Server Error".to_string(),
                                 message: format!("Failed to serialize response: {}", e),
                             };
@@ -266,6 +276,7 @@ impl Node {
                 Err(e) => {
                     let api_error = APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!("Failed to convert path to VRPath: {}", e),
                     };
@@ -291,6 +302,7 @@ impl Node {
                                     // Handle serialization error
                                     let api_error = APIError {
                                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                                        error_code: ErrorCode::InternalError,
                                         error: "Internal Server Error".to_string(),
                                         message: format!("Failed to serialize response: {}", e),
                                     };
@@ -301,6 +313,7 @@ impl Node {
                         Err(e) => {
                             let api_error = APIError {
                                 code: StatusCode::BAD_REQUEST.as_u16(),
+                                error_code: ErrorCode::InvalidInput,
                                 error: "Bad Request".to_string(),
                                 message: format!("Failed to convert path to VRPath: {}", e),
                             };
@@ -311,6 +324,7 @@ impl Node {
                 Err(_) => {
                     let api_error = APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: "Invalid node name provided".to_string(),
                     };
@@ -342,6 +356,7 @@ impl Node {
                     // Handle serialization error
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to serialize response: {}", e),
                     };
@@ -351,6 +366,7 @@ impl Node {
         } else {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::BadRequest,
                 error: "Bad Request".to_string(),
                 message: "Streamer name doesn't match".to_string(),
             };
@@ -398,6 +414,7 @@ impl Node {
             Err(_) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Invalid node name provided".to_string(),
                 };
@@ -426,6 +443,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to subscribe to shared folder: {}", e),
                 };
@@ -465,6 +483,7 @@ impl Node {
         if !requester_name.has_profile() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::BadRequest,
                 error: "Bad Request".to_string(),
                 message: "Requester name does not have a profile".to_string(),
             };
@@ -492,6 +511,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to create shareable folder: {}", e),
                 };
@@ -543,6 +563,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to update shareable folder requirements: {}", e),
                 };
@@ -595,6 +616,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to unshare folder: {}", e),
                 };
@@ -640,6 +662,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to retrieve subscribers: {}", e),
                 };
@@ -662,6 +685,7 @@ impl Node {
         if parts.len() != 2 {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid subscription_id format. Expected format 'PROFILE:::PATH'.".to_string(),
             };
@@ -677,6 +701,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to retrieve folder requirements: {}", e),
                 };
@@ -702,6 +727,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to serialize response: {}", e),
                 };
@@ -739,6 +765,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -753,6 +780,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to get last notifications: {}", e),
                 };
@@ -791,6 +819,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -809,6 +838,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to get notifications before timestamp: {}", e),
                 };
@@ -3,14 +3,19 @@ use std::{env, fs, path::Path, sync::Arc};
 use crate::{
     db::ShinkaiDB,
     llm_provider::parsing_helper::ParsingHelper,
-    managers::IdentityManager,
+    managers::{
+        operation_registry::{OperationKind, OPERATIONS},
+        IdentityManager,
+    },
     network::{
+        error_code::ErrorCode,
         node_api_router::APIError,
         node_error::NodeError,
         subscription_manager::external_subscriber_manager::{ExternalSubscriberManager, SharedFolderInfo},
         Node,
     },
     schemas::identity::Identity,
+    utils::environment::image_embedding_generator,
     vector_fs::vector_fs::VectorFS,
 };
 use async_channel::Sender;
@@ -33,7 +38,7 @@ use shinkai_vector_resources::{
     embedding_generator::EmbeddingGenerator,
     file_parser::{file_parser::FileParser, unstructured_api::UnstructuredAPI},
     source::DistributionInfo,
-    vector_resource::{VRPack, VRPath},
+    vector_resource::{VRKai, VRPack, VRPath},
 };
 use tokio::sync::Mutex;
 use x25519_dalek::StaticSecret as EncryptionStaticKey;
@@ -61,12 +66,14 @@ impl Node {
 
         let content = msg.get_message_content().map_err(|e| APIError {
             code: StatusCode::BAD_REQUEST.as_u16(),
+            error_code: ErrorCode::InvalidInput,
             error: "Bad Request".to_string(),
             message: format!("Failed to get message content: {}", e),
         })?;
 
         let input_payload = serde_json::from_str::<T>(&content).map_err(|e| APIError {
             code: StatusCode::BAD_REQUEST.as_u16(),
+            error_code: ErrorCode::InvalidInput,
             error: "Bad Request".to_string(),
             message: format!("Failed to parse payload: {}", e),
         })?;
@@ -76,6 +83,7 @@ impl Node {
             _ => {
                 return Err(APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 })
@@ -174,6 +182,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert path to VRPath: {}", e),
                 };
@@ -190,6 +199,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create reader: {}", e),
                 };
@@ -255,6 +265,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to retrieve fs path json: {}", e),
                 };
@@ -295,6 +306,7 @@ impl Node {
                 Err(e) => {
                     let api_error = APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!("Failed to convert path to VRPath: {}", e),
                     };
@@ -312,6 +324,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create reader: {}", e),
                 };
@@ -323,12 +336,15 @@ impl Node {
         let max_resources_to_search = input_payload.max_files_to_scan.unwrap_or(100) as u64;
         let max_results = input_payload.max_results.unwrap_or(100) as u64;
 
-        let query_embedding = vector_fs
-            .generate_query_embedding_using_reader(input_payload.search, &reader)
-            .await
-            .unwrap();
+        // Images are also found by their image embeddings, if an image embedding model is set
+        let image_generator = image_embedding_generator();
         let search_results = vector_fs
-            .vector_search_fs_item(&reader, query_embedding, max_resources_to_search)
+            .search_fs_items_with_images(
+                &reader,
+                input_payload.search,
+                image_generator.as_ref(),
+                max_resources_to_search,
+            )
             .await
             .unwrap();
 
@@ -375,6 +391,7 @@ impl Node {
                 Err(e) => {
                     let api_error = APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!("Failed to convert path to VRPath: {}", e),
                     };
@@ -392,6 +409,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create reader: {}", e),
                 };
@@ -415,6 +433,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to perform deep vector search: {}", e),
                 };
@@ -473,6 +492,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert path to VRPath: {}", e),
                 };
@@ -489,6 +509,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer: {}", e),
                 };
@@ -506,6 +527,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create new folder: {}", e),
                 };
@@ -545,6 +567,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::VecfsError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to convert item path to VRPath: {}", e),
                 };
@@ -557,6 +580,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::VecfsError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to convert destination path to VRPath: {}", e),
                 };
@@ -573,6 +597,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer for original folder: {}", e),
                 };
@@ -590,6 +615,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to move folder: {}", e),
                 };
@@ -629,6 +655,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert folder path to VRPath: {}", e),
                 };
@@ -642,6 +669,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert destination path to VRPath: {}", e),
                 };
@@ -658,6 +686,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer for original folder: {}", e),
                 };
@@ -666,7 +695,18 @@ impl Node {
             }
         };
 
-        match vector_fs.copy_folder(&orig_writer, destination_path).await {
+        let operation = OPERATIONS.start(
+            OperationKind::CopyFolder,
+            format!(
+                "Copying {} to {}",
+                input_payload.origin_path, input_payload.destination_path
+            ),
+        );
+        let copy_result = vector_fs
+            .copy_folder_with_progress(&orig_writer, destination_path, &operation)
+            .await;
+        operation.finish(&copy_result);
+        match copy_result {
             Ok(_) => {
                 let success_message = format!("Folder copied successfully to {}", input_payload.destination_path);
                 let _ = res.send(Ok(success_message)).await.map_err(|_| ());
@@ -675,6 +715,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to copy folder: {}", e),
                 };
@@ -714,6 +755,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert item path to VRPath: {}", e),
                 };
@@ -730,6 +772,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer for item: {}", e),
                 };
@@ -747,6 +790,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to move item: {}", e),
                 };
@@ -786,6 +830,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert folder path to VRPath: {}", e),
                 };
@@ -802,6 +847,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer for item: {}", e),
                 };
@@ -819,6 +865,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to move item: {}", e),
                 };
@@ -858,6 +905,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert item path to VRPath: {}", e),
                 };
@@ -871,6 +919,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert destination path to VRPath: {}", e),
                 };
@@ -887,6 +936,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer for original item: {}", e),
                 };
@@ -904,6 +954,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to move item: {}", e),
                 };
@@ -943,6 +994,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::VecfsError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to convert item path to VRPath: {}", e),
                 };
@@ -955,6 +1007,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::VecfsError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to convert destination path to VRPath: {}", e),
                 };
@@ -971,6 +1024,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer for original item: {}", e),
                 };
@@ -988,6 +1042,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to copy item: {}", e),
                 };
@@ -1027,6 +1082,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert path to VRPath: {}", e),
                 };
@@ -1042,6 +1098,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create reader: {}", e),
                 };
@@ -1056,6 +1113,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to retrieve vector resource: {}", e),
                 };
@@ -1069,6 +1127,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::VecfsError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to convert vector resource to json: {}", e),
                 };
@@ -1138,6 +1197,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert path to VRPath: {}", e),
                 };
@@ -1153,6 +1213,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::VecfsError,
                             error: "Internal Server Error".to_string(),
                             message: format!("{}", err),
                         }))
@@ -1180,25 +1241,66 @@ impl Node {
             false => FileParser::Unstructured((*unstructured_api).clone()),
         };
 
-        // TODO: provide a default agent so that an LLM can be used to generate description of the VR for document files
-        let processed_vrkais =
-            ParsingHelper::process_files_into_vrkai(dist_files, &*embedding_generator, None, file_parser).await?;
+        let (embedding_generator, ingestion) = vector_fs
+            .folder_embedding_settings(
+                &requester_name,
+                &destination_path,
+                &*embedding_generator,
+                &ParsingHelper::ingestion_settings(&db, &requester_name),
+            )
+            .await?;
 
-        // Save the vrkais into VectorFS
-        let mut success_messages = Vec::new();
-        for (filename, vrkai) in processed_vrkais {
-            let folder_path = destination_path.clone();
-            let writer = vector_fs
-                .new_writer(requester_name.clone(), folder_path.clone(), requester_name.clone())
+        // TODO: provide a default agent so that an LLM can be used to generate description of the VR for document files
+        let operation = OPERATIONS.start(
+            OperationKind::ConvertFiles,
+            format!("Converting {} files into {}", dist_files.len(), input_payload.path),
+        );
+        let processed_vrkais = ParsingHelper::process_files_into_vrkai(
+            dist_files,
+            &*embedding_generator,
+            None,
+            file_parser,
+            &ingestion,
+            Some(&db.events),
+            Some(&operation),
+        )
+        .await;
+        operation.finish(&processed_vrkais);
+        let processed_vrkais = processed_vrkais?;
+
+        // Save the vrkais into VectorFS, in as few write batches as possible
+        let writer = vector_fs
+            .new_writer(requester_name.clone(), destination_path.clone(), requester_name.clone())
+            .await?;
+
+        // Files that look like duplicates of items of the folder are reported instead of saved, unless allowed
+        let mut duplicate_messages = Vec::new();
+        let mut vrkais_to_save = Vec::new();
+        if input_payload.allow_duplicates {
+            vrkais_to_save = processed_vrkais;
+        } else {
+            let reader = writer
+                .new_reader_copied_data(destination_path.clone(), &vector_fs)
                 .await?;
+            for (filename, vrkai) in processed_vrkais {
+                match vector_fs.find_possible_duplicate(&reader, &filename, &vrkai).await? {
+                    Some(duplicate) => duplicate_messages.push(serde_json::to_value(&duplicate).unwrap_or_default()),
+                    None => vrkais_to_save.push((filename, vrkai)),
+                }
+            }
+        }
+        let (filenames, vrkais): (Vec<String>, Vec<VRKai>) = vrkais_to_save.into_iter().unzip();
+        let save_results = vector_fs.save_vrkais_in_folder(&writer, vrkais).await?;
 
-            let save_result = vector_fs.save_vrkai_in_folder(&writer, vrkai).await;
+        let mut success_messages = Vec::new();
+        for (filename, save_result) in filenames.into_iter().zip(save_results) {
             let fs_item = match save_result {
                 Ok(fs_item) => fs_item,
                 Err(e) => {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::VecfsError,
                             error: "Internal Server Error".to_string(),
                             message: format!("Error saving '{}' in folder: {}", filename, e),
                         }))
@@ -1225,6 +1327,7 @@ impl Node {
                 Err(e) => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::VecfsError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to convert vector resource info to JSON: {}", e),
                     };
@@ -1248,6 +1351,7 @@ impl Node {
                 let _ = res
                     .send(Err(APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::VecfsError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Error extracting/saving '{}' into folder: {}", filename, e),
                     }))
@@ -1263,6 +1367,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::VecfsError,
                             error: "Internal Server Error".to_string(),
                             message: format!("{}", err),
                         }))
@@ -1277,6 +1382,7 @@ impl Node {
             let mut ext_manager = external_subscriber_manager.lock().await;
             let _ = ext_manager.update_shared_folders().await;
         }
+        success_messages.extend(duplicate_messages);
         let _ = res.send(Ok(success_messages)).await.map_err(|_| ());
         Ok(())
     }
@@ -1311,6 +1417,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert path to VRPath: {}", e),
                 };
@@ -1326,6 +1433,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create reader: {}", e),
                 };
@@ -1340,6 +1448,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to retrieve vector resource: {}", e),
                 };
@@ -1363,6 +1472,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::VecfsError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to convert vector resource to json: {}", e),
                 };
@@ -1404,6 +1514,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert path to VRPath: {}", e),
                 };
@@ -1419,6 +1530,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create reader: {}", e),
                 };
@@ -1433,6 +1545,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to retrieve vector resource: {}", e),
                 };
@@ -1446,6 +1559,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::VecfsError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to convert vector resource to json: {}", e),
                 };
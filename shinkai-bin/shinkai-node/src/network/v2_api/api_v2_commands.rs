@@ -1,4 +1,4 @@
-use std::{env, sync::Arc};
+use std::{env, net::IpAddr, path::Path, sync::Arc};
 
 use async_channel::Sender;
 use ed25519_dalek::{SigningKey, VerifyingKey};
@@ -10,41 +10,76 @@ use shinkai_message_primitives::{
     shinkai_message::{
         shinkai_message::{MessageBody, MessageData, ShinkaiMessage},
         shinkai_message_schemas::{
-            APIAddOllamaModels, APIChangeJobAgentRequest, IdentityPermissions, JobMessage, MessageSchemaType,
-            V2ChatMessage,
+            APIAddOllamaModels, APIChangeJobAgentRequest, APIDeleteProfile, APIExportProfileData, APIGetRecentLogs,
+            APIInitializeNodeInteractive, APIRelocateStorage, APIRemoveCloudConnector, APIRemoveWatchedFolder,
+            APIRenameDevice, APIRevokeDevice, APIRevokeRegistrationCode, APIRunDbMaintenance, APISetPeerBan,
+            IdentityPermissions, JobMessage, MessageSchemaType, MessageWithAttachments, PeerBanAction,
+            RegistrationCodeRequest, RegistrationCodeType, V2ChatMessage,
         },
     },
     shinkai_utils::{
         encryption::{encryption_public_key_to_string, EncryptionMethod},
+        shinkai_logging::{
+            log_file_path, log_levels, recent_logs, set_log_level, LogEntry, LogLevelSetting, RECENT_LOGS_CAPACITY,
+        },
         shinkai_message_builder::ShinkaiMessageBuilder,
         signatures::signature_public_key_to_string,
     },
 };
 use shinkai_vector_resources::{
     embedding_generator::RemoteEmbeddingGenerator, model_type::EmbeddingModelType, shinkai_time::ShinkaiStringTime,
+    vector_resource::VRPath,
 };
-use tokio::sync::Mutex;
+use serde_json::{json, Value};
+use tokio::sync::{broadcast, Mutex};
 use x25519_dalek::PublicKey as EncryptionPublicKey;
 
 use crate::{
-    db::ShinkaiDB,
-    llm_provider::job_manager::JobManager,
-    managers::IdentityManager,
+    cron_tasks::{cron_manager::CronManager, db_maintenance::DbMaintenance},
+    db::{db_errors::ShinkaiDBError, db_idempotency::IdempotencyStatus, ShinkaiDB},
+    llm_provider::{
+        circuit_breaker::LLM_PROVIDER_BREAKERS,
+        job_manager::JobManager,
+        local_inference_scheduler::{LocalInferenceMetrics, LOCAL_INFERENCE_SCHEDULER},
+    },
+    managers::{
+        node_diagnostics::{DiagnosticsReport, DiagnosticsTargets, NodeDiagnostics},
+        node_metrics::{NodeMetrics, NodeMetricsCollector},
+        node_onboarding::{LLMProviderTestResult, NodeOnboarding, OnboardingKeys},
+        oidc_onboarding::OidcConfig,
+        profile_data_manager::ProfileDataManager,
+        profile_limits_manager::ProfileLimitsManager,
+        IdentityManager,
+    },
     network::{
+        error_code::ErrorCode,
         node_api_router::{APIError, GetPublicKeysResponse},
         node_error::NodeError,
+        node_events::NodeEvent,
+        outbound_proxy::OUTBOUND_PROXY,
+        peer_reputation::{PeerBanOverride, PeerReputationStatus, PEER_REPUTATIONS},
         v1_api::api_v1_handlers::APIUseRegistrationCodeSuccessResponse,
         ws_manager::WSUpdateHandler,
         Node,
     },
-    schemas::identity::{Identity, IdentityType, RegistrationCode},
-    utils::update_global_identity::update_global_identity_name,
+    runner::{request_node_lifecycle, NodeLifecycleRequest},
+    schemas::{
+        calendar_account::CalendarAccountConfig,
+        cloud_connector::CloudConnector,
+        db_maintenance::DbMaintenanceReport,
+        email_account::EmailAccountConfig,
+        identity::{DeviceInfo, Identity, IdentityType, RegistrationCode, StandardIdentity},
+        outbound_proxy::OutboundProxySettings,
+        profile_limits::{ProfileLimits, ProfileUsage},
+        watched_folder::WatchedFolder,
+    },
+    utils::{data_dir::DataDir, update_global_identity::update_global_identity_name},
     vector_fs::vector_fs::VectorFS,
 };
 
 use x25519_dalek::StaticSecret as EncryptionStaticKey;
 
-use super::api_v2_handlers_general::InitialRegistrationRequest;
+use super::api_v2_handlers_general::{InitialRegistrationRequest, OidcRegistrationRequest};
 
 impl Node {
     pub async fn validate_bearer_token<T>(
@@ -58,6 +93,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::UNAUTHORIZED.as_u16(),
+                    error_code: ErrorCode::Unauthorized,
                     error: "Unauthorized".to_string(),
                     message: "Invalid bearer token".to_string(),
                 };
@@ -74,6 +110,7 @@ impl Node {
             MessageBody::Unencrypted(body) => Ok(&body.internal_metadata),
             _ => Err(NodeError {
                 message: "Missing internal metadata".to_string(),
+                error_code: ErrorCode::InternalError,
             }),
         }?;
 
@@ -82,24 +119,61 @@ impl Node {
                 MessageData::Unencrypted(data) => Ok(data),
                 _ => Err(NodeError {
                     message: "Missing message data".to_string(),
+                    error_code: ErrorCode::InternalError,
                 }),
             },
             _ => Err(NodeError {
                 message: "Missing message data".to_string(),
+                error_code: ErrorCode::InternalError,
             }),
         }?;
 
         let external_metadata = shinkai_message.external_metadata;
 
-        let job_message: JobMessage =
-            serde_json::from_str(&message_data.message_raw_content).map_err(|e| NodeError {
-                message: format!("Failed to parse job message content: {}", e),
-            })?;
+        let (mut job_message, mut attachments) = match message_data.message_content_schema {
+            MessageSchemaType::MessageWithAttachments => {
+                let message: MessageWithAttachments =
+                    serde_json::from_str(&message_data.message_raw_content).map_err(|e| NodeError {
+                        message: format!("Failed to parse message with attachments content: {}", e),
+                        error_code: ErrorCode::InternalError,
+                    })?;
+                let job_message = JobMessage {
+                    job_id: String::new(),
+                    content: message.content,
+                    files_inbox: String::new(),
+                    parent: None,
+                    workflow_code: None,
+                    workflow_name: None,
+                    sheet_job_data: None,
+                    callback: None,
+                    retrieval: None,
+                };
+                (job_message, message.attachments)
+            }
+            _ => {
+                let job_message: JobMessage =
+                    serde_json::from_str(&message_data.message_raw_content).map_err(|e| NodeError {
+                        message: format!("Failed to parse job message content: {}", e),
+                        error_code: ErrorCode::InternalError,
+                    })?;
+                (job_message, Vec::new())
+            }
+        };
 
         let node_api_data = internal_metadata.node_api_data.clone().ok_or(NodeError {
             message: "Missing node API data".to_string(),
+            error_code: ErrorCode::InternalError,
         })?;
 
+        // Edited and deleted messages are shown as they are now
+        if node_api_data.deleted_at.is_some() {
+            job_message.content = String::new();
+            job_message.files_inbox = String::new();
+            attachments.clear();
+        } else if let Some(edited_content) = &node_api_data.edited_content {
+            job_message.content = edited_content.clone();
+        }
+
         Ok(V2ChatMessage {
             job_message,
             sender: external_metadata.sender,
@@ -108,6 +182,7 @@ impl Node {
             receiver_subidentity: internal_metadata.recipient_subidentity.clone(),
             node_api_data,
             inbox: internal_metadata.inbox.clone(),
+            attachments,
         })
     }
 
@@ -239,6 +314,7 @@ impl Node {
             Err(err) => {
                 let error = APIError {
                     code: 500,
+                    error_code: ErrorCode::InvalidRegistrationCode,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to handle registration code usage: {}", err),
                 };
@@ -247,68 +323,325 @@ impl Node {
         }
     }
 
-    pub async fn v2_api_get_local_processing_preference(
+    /// Registers a device of a user authenticated by the configured identity provider, with the permissions
+    /// and profile its ID token maps to
+    #[allow(clippy::too_many_arguments)]
+    pub async fn v2_handle_oidc_registration(
         db: Arc<ShinkaiDB>,
-        bearer: String,
-        res: Sender<Result<bool, APIError>>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        node_name: ShinkaiName,
+        payload: OidcRegistrationRequest,
+        res: Sender<Result<APIUseRegistrationCodeSuccessResponse, APIError>>,
+        vector_fs: Arc<VectorFS>,
+        embedding_generator: Arc<RemoteEmbeddingGenerator>,
+        job_manager: Arc<Mutex<JobManager>>,
+        encryption_public_key: EncryptionPublicKey,
+        identity_public_key: VerifyingKey,
+        identity_secret_key: SigningKey,
+        initial_llm_providers: Vec<SerializedLLMProvider>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        supported_embedding_models: Arc<Mutex<Vec<EmbeddingModelType>>>,
+    ) {
+        let Some(oidc_config) = OidcConfig::from_env() else {
+            let api_error = APIError::from_code(ErrorCode::BadRequest, "OIDC login isn't configured on this node");
+            let _ = res.send(Err(api_error)).await;
+            return;
+        };
+
+        let claims = match oidc_config.verify_id_token(&payload.id_token).await {
+            Ok(claims) => claims,
+            Err(err) => {
+                let _ = res.send(Err(APIError::from_code(ErrorCode::Unauthorized, &err))).await;
+                return;
+            }
+        };
+        // An ID token can only register one device, so a leaked token can't be replayed
+        let token_id = OidcConfig::token_id(&payload.id_token, &claims);
+        let expires_at = claims.get("exp").and_then(|exp| exp.as_i64()).unwrap_or_default();
+        match db.consume_oidc_token(&token_id, expires_at) {
+            Ok(true) => {}
+            Ok(false) => {
+                let api_error = APIError::from_code(ErrorCode::Unauthorized, "The ID token was already used");
+                let _ = res.send(Err(api_error)).await;
+                return;
+            }
+            Err(err) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::DatabaseError,
+                    &format!("Failed to record the ID token: {}", err),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return;
+            }
+        }
+        let Some(permission) = oidc_config.permission_from_claims(&claims) else {
+            let api_error = APIError::from_code(
+                ErrorCode::PermissionDenied,
+                "The user isn't allowed to register devices on this node",
+            );
+            let _ = res.send(Err(api_error)).await;
+            return;
+        };
+        let profile = match oidc_config.profile_from_claims(&claims) {
+            Ok(profile) => profile,
+            Err(err) => {
+                let _ = res.send(Err(APIError::from_code(ErrorCode::InvalidInput, &err))).await;
+                return;
+            }
+        };
+
+        // The device is registered with a single use code minted for it
+        let code = match db.generate_registration_new_code_with_limits(
+            permission.clone(),
+            RegistrationCodeType::Device(profile),
+            Some(60),
+            Some(1),
+        ) {
+            Ok(code) => code,
+            Err(err) => {
+                let api_error = APIError::from_code(
+                    err.error_code(),
+                    &format!("Failed to generate registration code: {}", err),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return;
+            }
+        };
+
+        let registration_code = RegistrationCode {
+            code,
+            registration_name: payload.device_name,
+            profile_identity_pk: payload.profile_identity_pk,
+            profile_encryption_pk: payload.profile_encryption_pk,
+            device_identity_pk: payload.device_identity_pk,
+            device_encryption_pk: payload.device_encryption_pk,
+            identity_type: IdentityType::Device,
+            permission_type: permission,
+        };
+
+        // The minted code is always used, even for the first device of the node
+        if let Err(err) = Self::handle_registration_code_usage(
+            db,
+            vector_fs,
+            node_name,
+            true,
+            embedding_generator,
+            identity_manager,
+            job_manager,
+            encryption_public_key,
+            identity_public_key,
+            identity_secret_key,
+            initial_llm_providers,
+            registration_code,
+            ws_manager,
+            supported_embedding_models,
+            res.clone(),
+        )
+        .await
+        {
+            let api_error = APIError::from_code(
+                ErrorCode::InvalidRegistrationCode,
+                &format!("Failed to handle registration code usage: {}", err),
+            );
+            let _ = res.send(Err(api_error)).await;
+        }
+    }
+
+    /// Onboarding requests don't need a bearer token, so they're rejected once the node has a profile
+    async fn ensure_node_is_pristine<T>(db: &ShinkaiDB, res: &Sender<Result<T, APIError>>) -> Result<(), ()> {
+        let api_error = match db.has_any_profile() {
+            Ok(false) => return Ok(()),
+            Ok(true) => APIError::from_code(ErrorCode::Conflict, "The node is already initialized"),
+            Err(err) => APIError::from_code(err.error_code(), &format!("Failed to check the profiles: {}", err)),
+        };
+        let _ = res.send(Err(api_error)).await;
+        Err(())
+    }
+
+    pub async fn v2_api_generate_onboarding_keys(
+        db: Arc<ShinkaiDB>,
+        res: Sender<Result<OnboardingKeys, APIError>>,
     ) -> Result<(), NodeError> {
-        // Validate the bearer token
-        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+        if Self::ensure_node_is_pristine(&db, &res).await.is_err() {
             return Ok(());
         }
 
-        // Get the local processing preference
-        match db.get_local_processing_preference() {
-            Ok(preference) => {
-                let _ = res.send(Ok(preference)).await;
+        let _ = res.send(Ok(NodeOnboarding::generate_keys())).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_test_llm_provider(
+        db: Arc<ShinkaiDB>,
+        llm_provider: SerializedLLMProvider,
+        res: Sender<Result<LLMProviderTestResult, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::ensure_node_is_pristine(&db, &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = NodeOnboarding::test_llm_provider(llm_provider).await;
+        let _ = res.send(Ok(result)).await;
+        Ok(())
+    }
+
+    /// Sets the embedding model and registers the first profile and device with its LLM providers, replacing
+    /// the environment variables the node is usually set up with
+    #[allow(clippy::too_many_arguments)]
+    pub async fn v2_handle_initialize_node_interactive(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        node_name: ShinkaiName,
+        payload: APIInitializeNodeInteractive,
+        res: Sender<Result<APIUseRegistrationCodeSuccessResponse, APIError>>,
+        vector_fs: Arc<VectorFS>,
+        first_device_needs_registration_code: bool,
+        embedding_generator: RemoteEmbeddingGenerator,
+        job_manager: Arc<Mutex<JobManager>>,
+        encryption_public_key: EncryptionPublicKey,
+        identity_public_key: VerifyingKey,
+        identity_secret_key: SigningKey,
+        initial_llm_providers: Vec<SerializedLLMProvider>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        default_embedding_model: Arc<Mutex<EmbeddingModelType>>,
+        supported_embedding_models: Arc<Mutex<Vec<EmbeddingModelType>>>,
+    ) {
+        if Self::ensure_node_is_pristine(&db, &res).await.is_err() {
+            return;
+        }
+
+        let mut embedding_generator = embedding_generator;
+        if let Some(model_name) = &payload.embedding_model {
+            let Ok(model) = EmbeddingModelType::from_string(model_name) else {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Invalid embedding model: {}", model_name),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return;
+            };
+
+            let mut supported_models = supported_embedding_models.lock().await;
+            if !supported_models.contains(&model) {
+                supported_models.push(model.clone());
             }
-            Err(err) => {
-                let _ = res
-                    .send(Err(APIError {
-                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                        error: "Internal Server Error".to_string(),
-                        message: format!("Failed to get local processing preference: {}", err),
-                    }))
-                    .await;
+            let result = db
+                .update_default_embedding_model(model.clone())
+                .and_then(|_| db.update_supported_embedding_models(supported_models.clone()));
+            if let Err(err) = result {
+                let api_error =
+                    APIError::from_code(err.error_code(), &format!("Failed to set the embedding model: {}", err));
+                let _ = res.send(Err(api_error)).await;
+                return;
             }
+            *default_embedding_model.lock().await = model.clone();
+            embedding_generator.model_type = model;
+        }
+
+        let mut llm_providers = initial_llm_providers;
+        llm_providers.extend(payload.llm_providers);
+        let registration = InitialRegistrationRequest {
+            profile_encryption_pk: payload.profile_encryption_pk,
+            profile_identity_pk: payload.profile_identity_pk,
+        };
+
+        Self::v2_handle_initial_registration(
+            db,
+            identity_manager,
+            node_name,
+            registration,
+            res,
+            vector_fs,
+            first_device_needs_registration_code,
+            Arc::new(embedding_generator),
+            job_manager,
+            encryption_public_key,
+            identity_public_key,
+            identity_secret_key,
+            llm_providers,
+            ws_manager,
+            supported_embedding_models,
+        )
+        .await;
+    }
+
+    pub async fn v2_api_check_bearer(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
         }
 
+        let _ = res.send(Ok(())).await;
         Ok(())
     }
 
-    pub async fn v2_api_update_local_processing_preference(
+    pub async fn v2_api_start_idempotent_request(
         db: Arc<ShinkaiDB>,
         bearer: String,
-        preference: bool,
-        res: Sender<Result<String, APIError>>,
+        idempotency_key: String,
+        request_hash: String,
+        res: Sender<Result<Option<Value>, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
         if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        // Update the local processing preference
-        match db.update_local_processing_preference(preference) {
-            Ok(_) => {
-                let _ = res.send(Ok("Preference updated successfully".to_string())).await;
-            }
-            Err(err) => {
-                let _ = res
-                    .send(Err(APIError {
-                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                        error: "Internal Server Error".to_string(),
-                        message: format!("Failed to update local processing preference: {}", err),
-                    }))
-                    .await;
-            }
+        let result = match db.start_idempotent_request(&idempotency_key, &request_hash) {
+            Ok(IdempotencyStatus::New) => Ok(None),
+            Ok(IdempotencyStatus::Completed(response)) => Ok(Some(response)),
+            Ok(IdempotencyStatus::InProgress) => Err(APIError {
+                code: StatusCode::CONFLICT.as_u16(),
+                error_code: ErrorCode::IdempotencyKeyInUse,
+                error: "Conflict".to_string(),
+                message: "A request with this Idempotency-Key is still being processed".to_string(),
+            }),
+            Ok(IdempotencyStatus::Mismatch) => Err(APIError {
+                code: StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+                error_code: ErrorCode::IdempotencyKeyMismatch,
+                error: "Unprocessable Entity".to_string(),
+                message: "The Idempotency-Key was already used for a different request".to_string(),
+            }),
+            Err(err) => Err(APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error_code: ErrorCode::InternalError,
+                error: "Internal Server Error".to_string(),
+                message: format!("Failed to check the Idempotency-Key: {}", err),
+            }),
+        };
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_finish_idempotent_request(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        idempotency_key: String,
+        response: Option<Value>,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
         }
 
+        let result = db.finish_idempotent_request(&idempotency_key, response).map_err(|err| APIError {
+            code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            error_code: ErrorCode::InternalError,
+            error: "Internal Server Error".to_string(),
+            message: format!("Failed to store the response of the Idempotency-Key: {}", err),
+        });
+        let _ = res.send(result).await;
         Ok(())
     }
 
-    pub async fn v2_api_get_default_embedding_model(
+    pub async fn v2_api_create_registration_code(
         db: Arc<ShinkaiDB>,
         bearer: String,
+        payload: RegistrationCodeRequest,
         res: Sender<Result<String, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
@@ -316,16 +649,21 @@ impl Node {
             return Ok(());
         }
 
-        // Get the default embedding model from the database
-        match db.get_default_embedding_model() {
-            Ok(model) => {
-                let _ = res.send(Ok(model.to_string())).await;
+        match db.generate_registration_new_code_with_limits(
+            payload.permissions,
+            payload.code_type,
+            payload.ttl_secs,
+            payload.max_uses,
+        ) {
+            Ok(code) => {
+                let _ = res.send(Ok(code)).await;
             }
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InvalidRegistrationCode,
                     error: "Internal Server Error".to_string(),
-                    message: format!("Failed to get default embedding model: {}", err),
+                    message: format!("Failed to generate registration code: {}", err),
                 };
                 let _ = res.send(Err(api_error)).await;
             }
@@ -334,28 +672,38 @@ impl Node {
         Ok(())
     }
 
-    pub async fn v2_api_get_supported_embedding_models(
+    /// Registration codes that can still be used
+    pub async fn v2_api_get_registration_codes(
         db: Arc<ShinkaiDB>,
         bearer: String,
-        res: Sender<Result<Vec<String>, APIError>>,
+        res: Sender<Result<Vec<Value>, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
         if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        // Get the supported embedding models from the database
-        match db.get_supported_embedding_models() {
-            Ok(models) => {
-                let model_names: Vec<String> = models.into_iter().map(|model| model.to_string()).collect();
-                let _ = res.send(Ok(model_names)).await;
+        match db.get_outstanding_registration_codes() {
+            Ok(codes) => {
+                let codes = codes
+                    .into_iter()
+                    .map(|(code, info)| {
+                        json!({
+                            "code": code,
+                            "permission": info.permission,
+                            "code_type": info.code_type,
+                            "created_at": info.created_at,
+                            "expires_at": info.expires_at,
+                            "max_uses": info.max_uses,
+                            "uses": info.uses,
+                        })
+                    })
+                    .collect();
+                let _ = res.send(Ok(codes)).await;
             }
             Err(err) => {
-                let api_error = APIError {
-                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                    error: "Internal Server Error".to_string(),
-                    message: format!("Failed to get supported embedding models: {}", err),
-                };
+                let api_error =
+                    APIError::from_code(err.error_code(), &format!("Failed to get registration codes: {}", err));
                 let _ = res.send(Err(api_error)).await;
             }
         }
@@ -363,109 +711,1570 @@ impl Node {
         Ok(())
     }
 
-    pub async fn v2_api_update_default_embedding_model(
+    pub async fn v2_api_revoke_registration_code(
         db: Arc<ShinkaiDB>,
         bearer: String,
-        model_name: String,
-        res: Sender<Result<String, APIError>>,
+        payload: APIRevokeRegistrationCode,
+        res: Sender<Result<Value, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
         if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        // Convert the string to EmbeddingModelType
-        let new_default_model = match EmbeddingModelType::from_string(&model_name) {
-            Ok(model) => model,
-            Err(_) => {
-                let api_error = APIError {
-                    code: StatusCode::BAD_REQUEST.as_u16(),
-                    error: "Bad Request".to_string(),
-                    message: "Invalid embedding model provided".to_string(),
-                };
-                let _ = res.send(Err(api_error)).await;
-                return Ok(());
-            }
-        };
-
-        // Update the default embedding model in the database
-        match db.update_default_embedding_model(new_default_model) {
+        match db.revoke_registration_code(&payload.code) {
             Ok(_) => {
-                let _ = res
-                    .send(Ok("Default embedding model updated successfully".to_string()))
-                    .await;
-                Ok(())
+                let _ = res.send(Ok(json!({ "status": "success" }))).await;
             }
             Err(err) => {
-                let api_error = APIError {
-                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                    error: "Internal Server Error".to_string(),
-                    message: format!("Failed to update default embedding model: {}", err),
-                };
+                let api_error = APIError::from_code(
+                    err.error_code(),
+                    &format!("Failed to revoke registration code: {}", err),
+                );
                 let _ = res.send(Err(api_error)).await;
-                Ok(())
             }
         }
+
+        Ok(())
     }
 
-    pub async fn v2_api_update_supported_embedding_models(
+    pub async fn v2_api_get_all_profiles(
         db: Arc<ShinkaiDB>,
-        vector_fs: Arc<VectorFS>,
         identity_manager: Arc<Mutex<IdentityManager>>,
         bearer: String,
-        models: Vec<String>,
-        res: Sender<Result<String, APIError>>,
+        res: Sender<Result<Vec<StandardIdentity>, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
         if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        let requester_name = match identity_manager.lock().await.get_main_identity() {
-            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
-            _ => {
-                let api_error = APIError {
-                    code: StatusCode::BAD_REQUEST.as_u16(),
-                    error: "Bad Request".to_string(),
-                    message: "Wrong identity type. Expected Standard identity.".to_string(),
-                };
-                let _ = res.send(Err(api_error)).await;
-                return Ok(());
-            }
-        };
-
-        // Convert the strings to EmbeddingModelType
-        let new_supported_models: Vec<EmbeddingModelType> = models
+        let profiles = identity_manager
+            .lock()
+            .await
+            .get_all_subidentities()
             .into_iter()
-            .map(|s| EmbeddingModelType::from_string(&s).expect("Failed to parse embedding model"))
+            .filter_map(|identity| match identity {
+                Identity::Standard(std_identity) => Some(std_identity),
+                _ => None,
+            })
             .collect();
+        let _ = res.send(Ok(profiles)).await;
+
+        Ok(())
+    }
+
+    pub async fn v2_api_get_devices(
+        db: Arc<ShinkaiDB>,
+        node_name: ShinkaiName,
+        bearer: String,
+        profile: String,
+        res: Sender<Result<Vec<DeviceInfo>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(profile_name) = Self::existing_profile_name(&db, &node_name, &profile, &res).await else {
+            return Ok(());
+        };
+
+        match db.get_profile_devices(&profile_name) {
+            Ok(devices) => {
+                let _ = res.send(Ok(devices)).await;
+            }
+            Err(err) => {
+                let api_error = APIError::from_code(err.error_code(), &format!("Failed to get devices: {}", err));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_rename_device(
+        db: Arc<ShinkaiDB>,
+        node_name: ShinkaiName,
+        bearer: String,
+        payload: APIRenameDevice,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(device_name) = Self::local_device_name(&node_name, &payload.device_name, &res).await else {
+            return Ok(());
+        };
+
+        match db.set_device_display_name(&device_name, &payload.display_name) {
+            Ok(_) => {
+                let _ = res.send(Ok(json!({ "status": "success" }))).await;
+            }
+            Err(err) => {
+                let api_error = APIError::from_code(err.error_code(), &format!("Failed to rename device: {}", err));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_revoke_device(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        node_name: ShinkaiName,
+        bearer: String,
+        payload: APIRevokeDevice,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(device_name) = Self::local_device_name(&node_name, &payload.device_name, &res).await else {
+            return Ok(());
+        };
+
+        match db.revoke_device(&device_name) {
+            Ok(_) => {
+                identity_manager.lock().await.remove_device_subidentity(&device_name);
+                let _ = res.send(Ok(json!({ "status": "success" }))).await;
+            }
+            Err(err) => {
+                let api_error = APIError::from_code(err.error_code(), &format!("Failed to revoke device: {}", err));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parses the full name of a device of this node, sending an invalid input error otherwise
+    async fn local_device_name<T>(
+        node_name: &ShinkaiName,
+        device_name: &str,
+        res: &Sender<Result<T, APIError>>,
+    ) -> Option<ShinkaiName> {
+        match ShinkaiName::new(device_name.to_string()) {
+            Ok(name) if name.has_device() && name.node_name == node_name.node_name => Some(name),
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("{} isn't a device of this node", device_name),
+                );
+                let _ = res.send(Err(api_error)).await;
+                None
+            }
+        }
+    }
+
+    pub async fn v2_api_get_local_processing_preference(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<bool, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Get the local processing preference
+        match db.get_local_processing_preference() {
+            Ok(preference) => {
+                let _ = res.send(Ok(preference)).await;
+            }
+            Err(err) => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
+                        error: "Internal Server Error".to_string(),
+                        message: format!("Failed to get local processing preference: {}", err),
+                    }))
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_update_local_processing_preference(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        preference: bool,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Update the local processing preference
+        match db.update_local_processing_preference(preference) {
+            Ok(_) => {
+                let _ = res.send(Ok("Preference updated successfully".to_string())).await;
+            }
+            Err(err) => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
+                        error: "Internal Server Error".to_string(),
+                        message: format!("Failed to update local processing preference: {}", err),
+                    }))
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_get_entity_tagging_preference(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<bool, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match db.get_entity_tagging_preference() {
+            Ok(preference) => {
+                let _ = res.send(Ok(preference)).await;
+            }
+            Err(err) => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
+                        error: "Internal Server Error".to_string(),
+                        message: format!("Failed to get entity tagging preference: {}", err),
+                    }))
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Files ingested from then on have their text chunks tagged with the people, organizations and dates they
+    /// mention, or not. Already ingested files keep their tags.
+    pub async fn v2_api_update_entity_tagging_preference(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        preference: bool,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match db.update_entity_tagging_preference(preference) {
+            Ok(_) => {
+                let _ = res.send(Ok("Preference updated successfully".to_string())).await;
+            }
+            Err(err) => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
+                        error: "Internal Server Error".to_string(),
+                        message: format!("Failed to update entity tagging preference: {}", err),
+                    }))
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_get_summarization_llm_provider(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<Option<String>, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match db.get_summarization_llm_provider() {
+            Ok(llm_provider_id) => {
+                let _ = res.send(Ok(llm_provider_id)).await;
+            }
+            Err(err) => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
+                        error: "Internal Server Error".to_string(),
+                        message: format!("Failed to get summarization LLM provider: {}", err),
+                    }))
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Files ingested from then on are summarized by the LLM provider with the id, for the profiles that have one,
+    /// or not at all if None. Already ingested files keep their summaries.
+    pub async fn v2_api_update_summarization_llm_provider(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        llm_provider_id: Option<String>,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match db.update_summarization_llm_provider(llm_provider_id) {
+            Ok(_) => {
+                let _ = res
+                    .send(Ok("Summarization LLM provider updated successfully".to_string()))
+                    .await;
+            }
+            Err(err) => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
+                        error: "Internal Server Error".to_string(),
+                        message: format!("Failed to update summarization LLM provider: {}", err),
+                    }))
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_get_default_embedding_model(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Get the default embedding model from the database
+        match db.get_default_embedding_model() {
+            Ok(model) => {
+                let _ = res.send(Ok(model.to_string())).await;
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to get default embedding model: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_get_supported_embedding_models(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<Vec<String>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Get the supported embedding models from the database
+        match db.get_supported_embedding_models() {
+            Ok(models) => {
+                let model_names: Vec<String> = models.into_iter().map(|model| model.to_string()).collect();
+                let _ = res.send(Ok(model_names)).await;
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to get supported embedding models: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_update_default_embedding_model(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        model_name: String,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Convert the string to EmbeddingModelType
+        let new_default_model = match EmbeddingModelType::from_string(&model_name) {
+            Ok(model) => model,
+            Err(_) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: "Invalid embedding model provided".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        // Update the default embedding model in the database
+        match db.update_default_embedding_model(new_default_model) {
+            Ok(_) => {
+                let _ = res
+                    .send(Ok("Default embedding model updated successfully".to_string()))
+                    .await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to update default embedding model: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_update_supported_embedding_models(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        models: Vec<String>,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        // Convert the strings to EmbeddingModelType
+        let new_supported_models: Vec<EmbeddingModelType> = models
+            .into_iter()
+            .map(|s| EmbeddingModelType::from_string(&s).expect("Failed to parse embedding model"))
+            .collect();
+
+        // Update the supported embedding models in the database
+        if let Err(err) = db.update_supported_embedding_models(new_supported_models.clone()) {
+            let api_error = APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error_code: ErrorCode::InternalError,
+                error: "Internal Server Error".to_string(),
+                message: format!("Failed to update supported embedding models: {}", err),
+            };
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        match vector_fs
+            .set_profile_supported_models(&requester_name, &requester_name, new_supported_models)
+            .await
+        {
+            Ok(_) => {
+                let _ = res
+                    .send(Ok("Supported embedding models updated successfully".to_string()))
+                    .await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to update supported embedding models: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_add_llm_provider(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        job_manager: Option<Arc<Mutex<JobManager>>>,
+        identity_secret_key: SigningKey,
+        bearer: String,
+        agent: SerializedLLMProvider,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let job_manager = match job_manager {
+            Some(manager) => manager,
+            None => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: "JobManager is required".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let profile = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        match Self::internal_add_llm_provider(
+            db.clone(),
+            identity_manager.clone(),
+            job_manager,
+            identity_secret_key.clone(),
+            agent,
+            &profile,
+            ws_manager,
+        )
+        .await
+        {
+            Ok(_) => {
+                // If everything went well, send the success message
+                let _ = res.send(Ok("Agent added successfully".to_string())).await;
+                Ok(())
+            }
+            Err(err) => {
+                // If there was an error, send the error message
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("{}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_remove_llm_provider(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        llm_provider_id: String,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let mut identity_manager = identity_manager.lock().await;
+        match db.remove_llm_provider(&llm_provider_id, &requester_name) {
+            Ok(_) => match identity_manager.remove_agent_subidentity(&llm_provider_id).await {
+                Ok(_) => {
+                    let _ = res.send(Ok("Agent removed successfully".to_string())).await;
+                    Ok(())
+                }
+                Err(err) => {
+                    let api_error = APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
+                        error: "Internal Server Error".to_string(),
+                        message: format!("Failed to remove agent from identity manager: {}", err),
+                    };
+                    let _ = res.send(Err(api_error)).await;
+                    Ok(())
+                }
+            },
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to remove agent: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_modify_llm_provider(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        agent: SerializedLLMProvider,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        match db.update_llm_provider(agent.clone(), &requester_name) {
+            Ok(_) => {
+                let mut identity_manager = identity_manager.lock().await;
+                match identity_manager.modify_llm_provider_subidentity(agent).await {
+                    Ok(_) => {
+                        let _ = res.send(Ok("Agent modified successfully".to_string())).await;
+                        Ok(())
+                    }
+                    Err(err) => {
+                        let api_error = APIError {
+                            code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::InternalError,
+                            error: "Internal Server Error".to_string(),
+                            message: format!("Failed to update agent in identity manager: {}", err),
+                        };
+                        let _ = res.send(Err(api_error)).await;
+                        Ok(())
+                    }
+                }
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to update agent: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_change_nodes_name(
+        bearer: String,
+        db: Arc<ShinkaiDB>,
+        secret_file_path: &str,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        encryption_public_key: EncryptionPublicKey,
+        identity_public_key: VerifyingKey,
+        new_name: String,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Validate the new node name
+        let new_node_name = match ShinkaiName::from_node_name(new_name) {
+            Ok(name) => name,
+            Err(_) => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
+                        error: "Bad Request".to_string(),
+                        message: "Invalid node name".to_string(),
+                    }))
+                    .await;
+                return Ok(());
+            }
+        };
+
+        {
+            // Check if the new node name exists in the blockchain and the keys match
+            let identity_manager = identity_manager.lock().await;
+            match identity_manager
+                .external_profile_to_global_identity(new_node_name.get_node_name_string().as_str())
+                .await
+            {
+                Ok(standard_identity) => {
+                    if standard_identity.node_encryption_public_key != encryption_public_key
+                        || standard_identity.node_signature_public_key != identity_public_key
+                    {
+                        let _ = res
+                            .send(Err(APIError {
+                                code: StatusCode::FORBIDDEN.as_u16(),
+                                error_code: ErrorCode::PermissionDenied,
+                                error: "Forbidden".to_string(),
+                                message: "The keys do not match with the current node".to_string(),
+                            }))
+                            .await;
+                        return Ok(());
+                    }
+                }
+                Err(_) => {
+                    let _ = res
+                        .send(Err(APIError {
+                            code: StatusCode::NOT_FOUND.as_u16(),
+                            error_code: ErrorCode::IdentityNotFound,
+                            error: "Not Found".to_string(),
+                            message: "The new node name does not exist in the blockchain".to_string(),
+                        }))
+                        .await;
+                    return Ok(());
+                }
+            }
+        }
+
+        // Write to .secret file
+        match update_global_identity_name(secret_file_path, new_node_name.get_node_name_string().as_str()) {
+            Ok(_) => {
+                eprintln!("Node name changed successfully. Restarting server...");
+                let _ = res.send(Ok(())).await;
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                panic!("Node name changed successfully. Restarting server...");
+            }
+            Err(err) => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
+                        error: "Internal Server Error".to_string(),
+                        message: format!("{}", err),
+                    }))
+                    .await;
+            }
+        };
+        Ok(())
+    }
+
+    pub async fn v2_api_is_pristine(
+        bearer: String,
+        db: Arc<ShinkaiDB>,
+        res: Sender<Result<bool, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let has_any_profile = db.has_any_profile().unwrap_or(false);
+        let _ = res.send(Ok(!has_any_profile)).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_scan_ollama_models(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<Vec<serde_json::Value>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match Self::internal_scan_ollama_models().await {
+            Ok(response) => {
+                let _ = res.send(Ok(response)).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("{}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_add_ollama_models(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        job_manager: Option<Arc<Mutex<JobManager>>>,
+        identity_secret_key: SigningKey,
+        bearer: String,
+        payload: APIAddOllamaModels,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let job_manager = match job_manager {
+            Some(manager) => manager,
+            None => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: "JobManager is required".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        match Node::internal_add_ollama_models(
+            db,
+            identity_manager,
+            job_manager,
+            identity_secret_key,
+            payload.models,
+            requester_name,
+            ws_manager,
+        )
+        .await
+        {
+            Ok(_) => {
+                let _ = res.send(Ok::<(), APIError>(())).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to add model: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_get_local_inference_metrics(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<LocalInferenceMetrics, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let _ = res.send(Ok(LOCAL_INFERENCE_SCHEDULER.metrics())).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_get_log_levels(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<Vec<LogLevelSetting>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let _ = res.send(Ok(log_levels())).await;
+        Ok(())
+    }
+
+    /// Changes the level of the given log options until the node restarts
+    pub async fn v2_api_set_log_levels(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        log_levels_to_set: Vec<LogLevelSetting>,
+        res: Sender<Result<Vec<LogLevelSetting>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        for setting in log_levels_to_set {
+            set_log_level(setting.option, setting.level);
+        }
+        let _ = res.send(Ok(log_levels())).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_get_recent_logs(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: APIGetRecentLogs,
+        res: Sender<Result<Vec<LogEntry>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let limit = payload.limit.unwrap_or(200).min(RECENT_LOGS_CAPACITY);
+        let _ = res.send(Ok(recent_logs(payload.level, payload.module, limit))).await;
+        Ok(())
+    }
+
+    /// Contents of the log file, including the rotated one
+    pub async fn v2_api_download_logs(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<Vec<u8>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(path) = log_file_path() else {
+            let api_error = APIError::from_code(ErrorCode::NotFound, "The node doesn't write its logs to a file");
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        };
+
+        let mut rotated_path = path.clone().into_os_string();
+        rotated_path.push(".1");
+        let mut logs = tokio::fs::read(&rotated_path).await.unwrap_or_default();
+        match tokio::fs::read(&path).await {
+            Ok(current_logs) => {
+                logs.extend(current_logs);
+                let _ = res.send(Ok(logs)).await;
+            }
+            Err(e) => {
+                let api_error =
+                    APIError::from_code(ErrorCode::InternalError, &format!("Failed to read the log file: {}", e));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the services, database, disk, clock and ports the node depends on, so the UI can point out what's
+    /// wrong with a new install
+    pub async fn v2_api_run_diagnostics(
+        db: Arc<ShinkaiDB>,
+        targets: DiagnosticsTargets,
+        bearer: String,
+        res: Sender<Result<DiagnosticsReport, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let report = NodeDiagnostics::run(db, targets).await;
+        let _ = res.send(Ok(report)).await;
+        Ok(())
+    }
+
+    /// Reports the sizes of the column families of both databases, after compacting them if asked to. Also updates
+    /// how often they are compacted on their own.
+    pub async fn v2_api_run_db_maintenance(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        bearer: String,
+        payload: APIRunDbMaintenance,
+        res: Sender<Result<DbMaintenanceReport, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        if let Some(hours) = payload.compaction_interval_hours {
+            let updated = db.get_db_compaction_schedule().and_then(|mut schedule| {
+                schedule.interval_hours = (hours > 0).then_some(hours);
+                db.update_db_compaction_schedule(&schedule)
+            });
+            if let Err(e) = updated {
+                let api_error = APIError::from_code(
+                    ErrorCode::InternalError,
+                    &format!("Failed to update the compaction schedule: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        }
+
+        if payload.compact {
+            if let Err(e) = DbMaintenance::compact(&db, &vector_fs).await {
+                let api_error = APIError::from_code(
+                    ErrorCode::InternalError,
+                    &format!("Failed to compact the databases: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        }
+
+        match DbMaintenance::report(&db, &vector_fs, payload.compact) {
+            Ok(report) => {
+                let _ = res.send(Ok(report)).await;
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InternalError,
+                    &format!("Failed to measure the databases: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the databases, secrets and logs to a new data directory. The node restarts, copies the data while the
+    /// databases are closed, verifies the copy and switches to it. If anything fails it keeps the current one.
+    pub async fn v2_api_relocate_storage(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: APIRelocateStorage,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let data_dir = DataDir::new(env::var("NODE_STORAGE_PATH").unwrap_or_default());
+        let target = Path::new(&payload.new_data_dir);
+        if let Err(e) = data_dir.request_relocation(target, payload.remove_old_copy) {
+            let api_error = APIError::from_code(
+                ErrorCode::InvalidInput,
+                &format!("Can't move the data directory to {}: {}", payload.new_data_dir, e),
+            );
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        let _ = res
+            .send(Ok(json!({
+                "status": "restarting",
+                "data_size": data_dir.data_size(),
+                "new_data_dir": payload.new_data_dir,
+            })))
+            .await;
+        // Gives the API server the time to answer before it's stopped
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        request_node_lifecycle(NodeLifecycleRequest::Restart);
+        Ok(())
+    }
+
+    pub async fn v2_api_get_node_metrics(
+        db: Arc<ShinkaiDB>,
+        job_manager: Option<Arc<Mutex<JobManager>>>,
+        bearer: String,
+        res: Sender<Result<NodeMetrics, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let queued_jobs = match job_manager {
+            Some(job_manager) => {
+                let job_queue_manager = job_manager.lock().await.job_queue_manager.clone();
+                let queued_jobs = job_queue_manager.lock().await.get_all_elements_interleave().await;
+                queued_jobs.map(|jobs| jobs.len()).unwrap_or(0)
+            }
+            None => 0,
+        };
+        let waiting_local_inferences = LOCAL_INFERENCE_SCHEDULER.metrics().waiting_requests;
+
+        let metrics =
+            NodeMetricsCollector::collect(queued_jobs, waiting_local_inferences, LLM_PROVIDER_BREAKERS.statuses());
+        let _ = res.send(Ok(metrics)).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_get_peer_reputations(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<Vec<PeerReputationStatus>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let _ = res.send(Ok(PEER_REPUTATIONS.statuses())).await;
+        Ok(())
+    }
+
+    /// Bans or allows a peer whatever its reputation, the override is kept across restarts
+    pub async fn v2_api_set_peer_ban(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: APISetPeerBan,
+        res: Sender<Result<Vec<PeerReputationStatus>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Peers are kept by IP, by node identity once verified, or as `relay:<ip>` for the peers behind a relay
+        let ip = payload.peer.strip_prefix("relay:").unwrap_or(&payload.peer);
+        let peer = match ip.parse::<IpAddr>() {
+            Ok(_) => payload.peer.clone(),
+            Err(_) => match ShinkaiName::new(payload.peer.clone()) {
+                Ok(name) => name.get_node_name_string(),
+                Err(_) => {
+                    let api_error = APIError::from_code(
+                        ErrorCode::InvalidInput,
+                        &format!("Invalid peer, expected an IP address or a node name: {}", payload.peer),
+                    );
+                    let _ = res.send(Err(api_error)).await;
+                    return Ok(());
+                }
+            },
+        };
+        let ban_override = match payload.action {
+            PeerBanAction::Ban => Some(PeerBanOverride::Banned),
+            PeerBanAction::Allow => Some(PeerBanOverride::Allowed),
+            PeerBanAction::Clear => None,
+        };
+        PEER_REPUTATIONS.set_override(&peer, ban_override);
+
+        if let Err(e) = db.set_peer_ban_overrides(&PEER_REPUTATIONS.overrides()) {
+            let api_error = APIError::from_code(
+                ErrorCode::InternalError,
+                &format!("Failed to save the peer ban overrides: {}", e),
+            );
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+        let _ = res.send(Ok(PEER_REPUTATIONS.statuses())).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_get_outbound_proxy_settings(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<OutboundProxySettings, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let _ = res.send(Ok(OUTBOUND_PROXY.settings())).await;
+        Ok(())
+    }
+
+    /// Saves the proxies of the outbound connections, used by the connections opened from then on
+    pub async fn v2_api_set_outbound_proxy_settings(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: OutboundProxySettings,
+        res: Sender<Result<OutboundProxySettings, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        if let Err(e) = payload.validate() {
+            let _ = res.send(Err(APIError::from_code(ErrorCode::InvalidInput, &e))).await;
+            return Ok(());
+        }
+        if let Err(e) = db.update_outbound_proxy_settings(&payload) {
+            let api_error = APIError::from_code(
+                ErrorCode::InternalError,
+                &format!("Failed to save the outbound proxy settings: {}", e),
+            );
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+        OUTBOUND_PROXY.set_settings(payload.clone());
+        let _ = res.send(Ok(payload)).await;
+        Ok(())
+    }
+
+    /// Resolves an ENS name or a DID to the Shinkai identity it points to, with the keys and addresses of the
+    /// identity in the registry
+    pub async fn v2_api_resolve_identity_name(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        name: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let external_identity_manager = identity_manager.lock().await.external_identity_manager.clone();
+        let external_identity_manager = external_identity_manager.lock().await;
+        if !external_identity_manager.is_resolvable_name(&name) {
+            let api_error = APIError::from_code(
+                ErrorCode::InvalidInput,
+                &format!("{} is neither an ENS name nor a did:web DID", name),
+            );
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        match external_identity_manager.resolve_identity_name(&name).await {
+            Ok(identity) => {
+                let response = json!({
+                    "name": name,
+                    "shinkai_identity": format!("@@{}", identity.record.shinkai_identity),
+                    "encryption_key": identity.record.encryption_key,
+                    "signature_key": identity.record.signature_key,
+                    "address_or_proxy_nodes": identity.record.address_or_proxy_nodes,
+                    "is_stale": identity.is_stale,
+                });
+                let _ = res.send(Ok(response)).await;
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(ErrorCode::IdentityNotFound, &e);
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stops the node, or runs it again when `restart` is set, once the response is sent
+    pub async fn v2_api_stop_node(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        restart: bool,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let _ = res.send(Ok(())).await;
+        // Gives the API server the time to answer before it's stopped
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        request_node_lifecycle(if restart {
+            NodeLifecycleRequest::Restart
+        } else {
+            NodeLifecycleRequest::Stop
+        });
+        Ok(())
+    }
+
+    pub async fn v2_api_get_profile_limits(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        node_name: ShinkaiName,
+        bearer: String,
+        profile: String,
+        res: Sender<Result<ProfileUsage, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(profile_name) = Self::existing_profile_name(&db, &node_name, &profile, &res).await else {
+            return Ok(());
+        };
+
+        let month = ProfileLimitsManager::current_month();
+        let usage = db.get_profile_limits(&profile).and_then(|limits| {
+            Ok(ProfileUsage {
+                llm_tokens_used: db.get_profile_llm_tokens(&profile, &month)?,
+                limits,
+                month,
+                vector_fs_bytes_used: 0,
+            })
+        });
+        match usage {
+            Ok(mut usage) => {
+                usage.vector_fs_bytes_used = vector_fs.profile_storage_bytes(&profile_name).await.unwrap_or(0);
+                let _ = res.send(Ok(usage)).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to get profile limits: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_set_profile_limits(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        node_name: ShinkaiName,
+        bearer: String,
+        payload: ProfileLimits,
+        res: Sender<Result<ProfileLimits, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        if Self::existing_profile_name(&db, &node_name, &payload.profile, &res)
+            .await
+            .is_none()
+        {
+            return Ok(());
+        }
+
+        match db.set_profile_limits(&payload) {
+            Ok(_) => {
+                vector_fs
+                    .set_profile_quota(&payload.profile, payload.vector_fs_quota_bytes)
+                    .await;
+                let _ = res.send(Ok(payload)).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to set profile limits: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_export_profile_data(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        node_name: ShinkaiName,
+        bearer: String,
+        payload: APIExportProfileData,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(profile_name) = Self::existing_profile_name(&db, &node_name, &payload.profile, &res).await else {
+            return Ok(());
+        };
+
+        match ProfileDataManager::export_profile_data(&db, &vector_fs, &profile_name).await {
+            Ok(export) => {
+                let _ = res.send(Ok(json!(export))).await;
+            }
+            Err(err) => {
+                let api_error =
+                    APIError::from_code(err.error_code(), &format!("Failed to export profile data: {}", err));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_delete_profile(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        node_name: ShinkaiName,
+        bearer: String,
+        payload: APIDeleteProfile,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(profile_name) = Self::existing_profile_name(&db, &node_name, &payload.profile, &res).await else {
+            return Ok(());
+        };
+        let Some(main_profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+        if profile_name.full_name == main_profile {
+            let api_error = APIError::from_code(ErrorCode::BadRequest, "The main profile can't be deleted");
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        match ProfileDataManager::delete_profile(&db, &vector_fs, identity_manager, &node_name, &profile_name).await {
+            Ok(_) => {
+                let _ = res.send(Ok(json!({ "status": "success" }))).await;
+            }
+            Err(err) => {
+                let api_error = APIError::from_code(err.error_code(), &format!("Failed to delete profile: {}", err));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Full name of a profile of the node, sending a not found error if it doesn't exist
+    async fn existing_profile_name<T>(
+        db: &ShinkaiDB,
+        node_name: &ShinkaiName,
+        profile: &str,
+        res: &Sender<Result<T, APIError>>,
+    ) -> Option<ShinkaiName> {
+        let profile_name = ShinkaiName::from_node_and_profile_names(node_name.node_name.clone(), profile.to_string());
+        match profile_name {
+            Ok(profile_name) if db.does_identity_exists(&profile_name).unwrap_or(false) => Some(profile_name),
+            _ => {
+                let api_error = APIError {
+                    code: StatusCode::NOT_FOUND.as_u16(),
+                    error_code: ErrorCode::ProfileNotFound,
+                    error: "Not Found".to_string(),
+                    message: format!("Profile {} doesn't exist", profile),
+                };
+                let _ = res.send(Err(api_error)).await;
+                None
+            }
+        }
+    }
 
-        // Update the supported embedding models in the database
-        if let Err(err) = db.update_supported_embedding_models(new_supported_models.clone()) {
-            let api_error = APIError {
-                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                error: "Internal Server Error".to_string(),
-                message: format!("Failed to update supported embedding models: {}", err),
-            };
-            let _ = res.send(Err(api_error)).await;
+    async fn main_profile_name<T>(
+        identity_manager: &Arc<Mutex<IdentityManager>>,
+        res: &Sender<Result<T, APIError>>,
+    ) -> Option<String> {
+        match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => Some(std_identity.full_identity_name.full_name.clone()),
+            _ => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                None
+            }
+        }
+    }
+
+    pub async fn v2_api_set_email_account(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        match vector_fs
-            .set_profile_supported_models(&requester_name, &requester_name, new_supported_models)
-            .await
-        {
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let mut account = match serde_json::from_value::<EmailAccountConfig>(payload) {
+            Ok(account) if CronManager::is_valid_cron_expression(&account.ingest_cron) => account,
+            Ok(account) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("Invalid cron expression: {}", account.ingest_cron),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("Invalid email account: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+        account.profile = profile;
+
+        match db.set_email_account(&account) {
             Ok(_) => {
-                let _ = res
-                    .send(Ok("Supported embedding models updated successfully".to_string()))
-                    .await;
+                let _ = res.send(Ok(json!(account))).await;
                 Ok(())
             }
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
-                    message: format!("Failed to update supported embedding models: {}", err),
+                    message: format!("Failed to set email account: {}", err),
                 };
                 let _ = res.send(Err(api_error)).await;
                 Ok(())
@@ -473,69 +2282,294 @@ impl Node {
         }
     }
 
-    pub async fn v2_api_add_llm_provider(
+    pub async fn v2_api_get_email_account(
         db: Arc<ShinkaiDB>,
         identity_manager: Arc<Mutex<IdentityManager>>,
-        job_manager: Option<Arc<Mutex<JobManager>>>,
-        identity_secret_key: SigningKey,
         bearer: String,
-        agent: SerializedLLMProvider,
-        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
-        res: Sender<Result<String, APIError>>,
+        res: Sender<Result<Value, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
         if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        let job_manager = match job_manager {
-            Some(manager) => manager,
-            None => {
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        match db.get_email_account(&profile) {
+            Ok(account) => {
+                let _ = res.send(Ok(json!(account))).await;
+                Ok(())
+            }
+            Err(ShinkaiDBError::DataNotFound) => {
+                let api_error = APIError {
+                    code: StatusCode::NOT_FOUND.as_u16(),
+                    error_code: ErrorCode::ProfileNotFound,
+                    error: "Not Found".to_string(),
+                    message: format!("{} doesn't have an email account", profile),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+            Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
-                    message: "JobManager is required".to_string(),
+                    message: format!("Failed to get email account: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_remove_email_account(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        match db.remove_email_account(&profile) {
+            Ok(_) => {
+                let _ = res.send(Ok(json!({ "status": "success" }))).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to remove email account: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_set_calendar_account(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let mut account = match serde_json::from_value::<CalendarAccountConfig>(payload) {
+            Ok(account) => account,
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("Invalid calendar account: {}", err),
                 };
                 let _ = res.send(Err(api_error)).await;
                 return Ok(());
             }
         };
+        account.profile = profile;
 
-        let profile = match identity_manager.lock().await.get_main_identity() {
-            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
-            _ => {
+        match db.set_calendar_account(&account) {
+            Ok(_) => {
+                let _ = res.send(Ok(json!(account.redacted()))).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to set calendar account: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_get_calendar_account(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        match db.get_calendar_account(&profile) {
+            Ok(account) => {
+                let _ = res.send(Ok(json!(account.redacted()))).await;
+                Ok(())
+            }
+            Err(ShinkaiDBError::DataNotFound) => {
+                let api_error = APIError {
+                    code: StatusCode::NOT_FOUND.as_u16(),
+                    error_code: ErrorCode::ProfileNotFound,
+                    error: "Not Found".to_string(),
+                    message: format!("{} doesn't have a calendar account", profile),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to get calendar account: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_remove_calendar_account(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        match db.remove_calendar_account(&profile) {
+            Ok(_) => {
+                let _ = res.send(Ok(json!({ "status": "success" }))).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to remove calendar account: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_add_watched_folder(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let folder = serde_json::from_value::<WatchedFolder>(payload)
+            .map_err(|err| format!("Invalid watched folder: {}", err))
+            .and_then(|folder| {
+                let local_path = std::path::Path::new(&folder.local_path);
+                if !local_path.is_absolute() || !local_path.is_dir() {
+                    return Err(format!("{} isn't an existing absolute directory", folder.local_path));
+                }
+                VRPath::from_string(&folder.vector_fs_path)
+                    .map_err(|err| format!("Invalid VectorFS path {}: {}", folder.vector_fs_path, err))?;
+                Ok(folder)
+            });
+        let mut folder = match folder {
+            Ok(folder) => folder,
+            Err(message) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
-                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                    message,
                 };
                 let _ = res.send(Err(api_error)).await;
                 return Ok(());
             }
         };
+        folder.id = uuid::Uuid::new_v4().to_string();
+        folder.profile = profile;
 
-        match Self::internal_add_llm_provider(
-            db.clone(),
-            identity_manager.clone(),
-            job_manager,
-            identity_secret_key.clone(),
-            agent,
-            &profile,
-            ws_manager,
-        )
-        .await
-        {
+        match db.add_watched_folder(&folder) {
             Ok(_) => {
-                // If everything went well, send the success message
-                let _ = res.send(Ok("Agent added successfully".to_string())).await;
+                let _ = res.send(Ok(json!(folder))).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to add watched folder: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_list_watched_folders(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        match db.get_watched_folders_for_profile(&profile) {
+            Ok(folders) => {
+                let _ = res.send(Ok(json!(folders))).await;
                 Ok(())
             }
             Err(err) => {
-                // If there was an error, send the error message
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
-                    message: format!("{}", err),
+                    message: format!("Failed to list watched folders: {}", err),
                 };
                 let _ = res.send(Err(api_error)).await;
                 Ok(())
@@ -543,53 +2577,50 @@ impl Node {
         }
     }
 
-    pub async fn v2_api_remove_llm_provider(
+    /// Stops syncing the folder. What was already ingested from it stays in the VectorFS.
+    pub async fn v2_api_remove_watched_folder(
         db: Arc<ShinkaiDB>,
         identity_manager: Arc<Mutex<IdentityManager>>,
         bearer: String,
-        llm_provider_id: String,
-        res: Sender<Result<String, APIError>>,
+        payload: APIRemoveWatchedFolder,
+        res: Sender<Result<Value, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
         if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        let requester_name = match identity_manager.lock().await.get_main_identity() {
-            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
-            _ => {
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let result = db.get_watched_folders_for_profile(&profile).and_then(|folders| {
+            match folders.iter().any(|folder| folder.id == payload.id) {
+                true => db.remove_watched_folder(&payload.id).map(|_| true),
+                false => Ok(false),
+            }
+        });
+        match result {
+            Ok(true) => {
+                let _ = res.send(Ok(json!({ "status": "success" }))).await;
+                Ok(())
+            }
+            Ok(false) => {
                 let api_error = APIError {
-                    code: StatusCode::BAD_REQUEST.as_u16(),
-                    error: "Bad Request".to_string(),
-                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                    code: StatusCode::NOT_FOUND.as_u16(),
+                    error_code: ErrorCode::NotFound,
+                    error: "Not Found".to_string(),
+                    message: format!("{} doesn't have a watched folder with id {}", profile, payload.id),
                 };
                 let _ = res.send(Err(api_error)).await;
-                return Ok(());
+                Ok(())
             }
-        };
-
-        let mut identity_manager = identity_manager.lock().await;
-        match db.remove_llm_provider(&llm_provider_id, &requester_name) {
-            Ok(_) => match identity_manager.remove_agent_subidentity(&llm_provider_id).await {
-                Ok(_) => {
-                    let _ = res.send(Ok("Agent removed successfully".to_string())).await;
-                    Ok(())
-                }
-                Err(err) => {
-                    let api_error = APIError {
-                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                        error: "Internal Server Error".to_string(),
-                        message: format!("Failed to remove agent from identity manager: {}", err),
-                    };
-                    let _ = res.send(Err(api_error)).await;
-                    Ok(())
-                }
-            },
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
-                    message: format!("Failed to remove agent: {}", err),
+                    message: format!("Failed to remove watched folder: {}", err),
                 };
                 let _ = res.send(Err(api_error)).await;
                 Ok(())
@@ -597,55 +2628,61 @@ impl Node {
         }
     }
 
-    pub async fn v2_api_modify_llm_provider(
+    /// Checks the connector sent through the API, returning why it's invalid otherwise
+    fn validate_cloud_connector(payload: Value) -> Result<CloudConnector, String> {
+        let connector = serde_json::from_value::<CloudConnector>(payload)
+            .map_err(|err| format!("Invalid cloud connector: {}", err))?;
+        if !CronManager::is_valid_cron_expression(&connector.sync_cron) {
+            return Err(format!("Invalid cron expression: {}", connector.sync_cron));
+        }
+        VRPath::from_string(&connector.vector_fs_path)
+            .map_err(|err| format!("Invalid VectorFS path {}: {}", connector.vector_fs_path, err))?;
+        Ok(connector)
+    }
+
+    pub async fn v2_api_add_cloud_connector(
         db: Arc<ShinkaiDB>,
         identity_manager: Arc<Mutex<IdentityManager>>,
         bearer: String,
-        agent: SerializedLLMProvider,
-        res: Sender<Result<String, APIError>>,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
         if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        let requester_name = match identity_manager.lock().await.get_main_identity() {
-            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
-            _ => {
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let mut connector = match Self::validate_cloud_connector(payload) {
+            Ok(connector) => connector,
+            Err(message) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
-                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                    message,
                 };
                 let _ = res.send(Err(api_error)).await;
                 return Ok(());
             }
         };
+        connector.id = uuid::Uuid::new_v4().to_string();
+        connector.profile = profile;
 
-        match db.update_llm_provider(agent.clone(), &requester_name) {
+        match db.set_cloud_connector(&connector) {
             Ok(_) => {
-                let mut identity_manager = identity_manager.lock().await;
-                match identity_manager.modify_llm_provider_subidentity(agent).await {
-                    Ok(_) => {
-                        let _ = res.send(Ok("Agent modified successfully".to_string())).await;
-                        Ok(())
-                    }
-                    Err(err) => {
-                        let api_error = APIError {
-                            code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                            error: "Internal Server Error".to_string(),
-                            message: format!("Failed to update agent in identity manager: {}", err),
-                        };
-                        let _ = res.send(Err(api_error)).await;
-                        Ok(())
-                    }
-                }
+                let _ = res.send(Ok(json!(connector.redacted()))).await;
+                Ok(())
             }
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
-                    message: format!("Failed to update agent: {}", err),
+                    message: format!("Failed to add cloud connector: {}", err),
                 };
                 let _ = res.send(Err(api_error)).await;
                 Ok(())
@@ -653,126 +2690,33 @@ impl Node {
         }
     }
 
-    pub async fn v2_api_change_nodes_name(
-        bearer: String,
+    pub async fn v2_api_list_cloud_connectors(
         db: Arc<ShinkaiDB>,
-        secret_file_path: &str,
         identity_manager: Arc<Mutex<IdentityManager>>,
-        encryption_public_key: EncryptionPublicKey,
-        identity_public_key: VerifyingKey,
-        new_name: String,
-        res: Sender<Result<(), APIError>>,
-    ) -> Result<(), NodeError> {
-        // Validate the bearer token
-        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
-            return Ok(());
-        }
-
-        // Validate the new node name
-        let new_node_name = match ShinkaiName::from_node_name(new_name) {
-            Ok(name) => name,
-            Err(_) => {
-                let _ = res
-                    .send(Err(APIError {
-                        code: StatusCode::BAD_REQUEST.as_u16(),
-                        error: "Bad Request".to_string(),
-                        message: "Invalid node name".to_string(),
-                    }))
-                    .await;
-                return Ok(());
-            }
-        };
-
-        {
-            // Check if the new node name exists in the blockchain and the keys match
-            let identity_manager = identity_manager.lock().await;
-            match identity_manager
-                .external_profile_to_global_identity(new_node_name.get_node_name_string().as_str())
-                .await
-            {
-                Ok(standard_identity) => {
-                    if standard_identity.node_encryption_public_key != encryption_public_key
-                        || standard_identity.node_signature_public_key != identity_public_key
-                    {
-                        let _ = res
-                            .send(Err(APIError {
-                                code: StatusCode::FORBIDDEN.as_u16(),
-                                error: "Forbidden".to_string(),
-                                message: "The keys do not match with the current node".to_string(),
-                            }))
-                            .await;
-                        return Ok(());
-                    }
-                }
-                Err(_) => {
-                    let _ = res
-                        .send(Err(APIError {
-                            code: StatusCode::NOT_FOUND.as_u16(),
-                            error: "Not Found".to_string(),
-                            message: "The new node name does not exist in the blockchain".to_string(),
-                        }))
-                        .await;
-                    return Ok(());
-                }
-            }
-        }
-
-        // Write to .secret file
-        match update_global_identity_name(secret_file_path, new_node_name.get_node_name_string().as_str()) {
-            Ok(_) => {
-                eprintln!("Node name changed successfully. Restarting server...");
-                let _ = res.send(Ok(())).await;
-                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-                panic!("Node name changed successfully. Restarting server...");
-            }
-            Err(err) => {
-                let _ = res
-                    .send(Err(APIError {
-                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                        error: "Internal Server Error".to_string(),
-                        message: format!("{}", err),
-                    }))
-                    .await;
-            }
-        };
-        Ok(())
-    }
-
-    pub async fn v2_api_is_pristine(
         bearer: String,
-        db: Arc<ShinkaiDB>,
-        res: Sender<Result<bool, APIError>>,
+        res: Sender<Result<Value, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
         if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        let has_any_profile = db.has_any_profile().unwrap_or(false);
-        let _ = res.send(Ok(!has_any_profile)).await;
-        Ok(())
-    }
-
-    pub async fn v2_api_scan_ollama_models(
-        db: Arc<ShinkaiDB>,
-        bearer: String,
-        res: Sender<Result<Vec<serde_json::Value>, APIError>>,
-    ) -> Result<(), NodeError> {
-        // Validate the bearer token
-        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
             return Ok(());
-        }
+        };
 
-        match Self::internal_scan_ollama_models().await {
-            Ok(response) => {
-                let _ = res.send(Ok(response)).await;
+        match db.get_cloud_connectors_for_profile(&profile) {
+            Ok(connectors) => {
+                let connectors: Vec<CloudConnector> = connectors.iter().map(|connector| connector.redacted()).collect();
+                let _ = res.send(Ok(json!(connectors))).await;
                 Ok(())
             }
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
-                    message: format!("{}", err),
+                    message: format!("Failed to list cloud connectors: {}", err),
                 };
                 let _ = res.send(Err(api_error)).await;
                 Ok(())
@@ -780,71 +2724,148 @@ impl Node {
         }
     }
 
-    pub async fn v2_api_add_ollama_models(
+    /// Replaces the connector with the same id. Redacted tokens sent back keep the saved ones.
+    pub async fn v2_api_update_cloud_connector(
         db: Arc<ShinkaiDB>,
         identity_manager: Arc<Mutex<IdentityManager>>,
-        job_manager: Option<Arc<Mutex<JobManager>>>,
-        identity_secret_key: SigningKey,
         bearer: String,
-        payload: APIAddOllamaModels,
-        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
-        res: Sender<Result<(), APIError>>,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
         if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        let requester_name = match identity_manager.lock().await.get_main_identity() {
-            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
-            _ => {
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let mut connector = match Self::validate_cloud_connector(payload) {
+            Ok(connector) => connector,
+            Err(message) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
-                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                    message,
                 };
                 let _ = res.send(Err(api_error)).await;
                 return Ok(());
             }
         };
-
-        let job_manager = match job_manager {
-            Some(manager) => manager,
-            None => {
+        let saved = match db.get_cloud_connector(&connector.id) {
+            Ok(saved) if saved.profile.eq_ignore_ascii_case(&profile) => saved,
+            Ok(_) | Err(ShinkaiDBError::DataNotFound) => {
+                let api_error = APIError {
+                    code: StatusCode::NOT_FOUND.as_u16(),
+                    error_code: ErrorCode::NotFound,
+                    error: "Not Found".to_string(),
+                    message: format!("{} doesn't have a cloud connector with id {}", profile, connector.id),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+            Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
-                    message: "JobManager is required".to_string(),
+                    message: format!("Failed to get cloud connector: {}", err),
                 };
                 let _ = res.send(Err(api_error)).await;
                 return Ok(());
             }
         };
+        connector.keep_tokens_of(&saved);
+        connector.profile = saved.profile.clone();
 
-        match Node::internal_add_ollama_models(
-            db,
-            identity_manager,
-            job_manager,
-            identity_secret_key,
-            payload.models,
-            requester_name,
-            ws_manager,
-        )
-        .await
-        {
+        // The delta cursor and the synced files only apply to the remote folder they were taken from
+        let result = db.set_cloud_connector(&connector).and_then(|_| {
+            match connector.provider.same_remote_folder(&saved.provider) {
+                true => Ok(()),
+                false => db.set_cloud_sync_state(&connector.id, &Default::default()),
+            }
+        });
+        match result {
             Ok(_) => {
-                let _ = res.send(Ok::<(), APIError>(())).await;
+                let _ = res.send(Ok(json!(connector.redacted()))).await;
                 Ok(())
             }
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
-                    message: format!("Failed to add model: {}", err),
+                    message: format!("Failed to update cloud connector: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    /// Stops syncing the remote folder. What was already ingested from it stays in the VectorFS.
+    pub async fn v2_api_remove_cloud_connector(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        payload: APIRemoveCloudConnector,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(profile) = Self::main_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let result = match db.get_cloud_connector(&payload.id) {
+            Ok(saved) if saved.profile.eq_ignore_ascii_case(&profile) => db.remove_cloud_connector(&payload.id),
+            Ok(_) => Err(ShinkaiDBError::DataNotFound),
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(_) => {
+                let _ = res.send(Ok(json!({ "status": "success" }))).await;
+                Ok(())
+            }
+            Err(ShinkaiDBError::DataNotFound) => {
+                let api_error = APIError {
+                    code: StatusCode::NOT_FOUND.as_u16(),
+                    error_code: ErrorCode::NotFound,
+                    error: "Not Found".to_string(),
+                    message: format!("{} doesn't have a cloud connector with id {}", profile, payload.id),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to remove cloud connector: {}", err),
                 };
                 let _ = res.send(Err(api_error)).await;
                 Ok(())
             }
         }
     }
+
+    pub async fn v2_api_subscribe_to_events(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<broadcast::Receiver<NodeEvent>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let _ = res.send(Ok(db.events.subscribe())).await;
+        Ok(())
+    }
 }
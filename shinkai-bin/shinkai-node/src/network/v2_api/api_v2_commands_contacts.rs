@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use async_channel::Sender;
+use shinkai_message_primitives::{
+    schemas::{
+        contact::{Contact, ContactBook, ContactTrustLevel},
+        inbox_name::InboxName,
+        shinkai_name::ShinkaiName,
+    },
+    shinkai_message::shinkai_message_schemas::{
+        APIAcceptMessageRequest, APIRemoveContact, APISetContact, APISetContactsAllowlistMode,
+    },
+};
+use shinkai_vector_resources::shinkai_time::ShinkaiStringTime;
+use tokio::sync::Mutex;
+
+use crate::{
+    db::{db_errors::ShinkaiDBError, ShinkaiDB},
+    managers::IdentityManager,
+    network::{error_code::ErrorCode, node_api_router::APIError, node_error::NodeError, Node},
+    schemas::{identity::Identity, inbound_message_policy::InboundMessagePolicy},
+};
+
+fn contacts_db_api_error(error: ShinkaiDBError) -> APIError {
+    APIError::from_code(ErrorCode::DatabaseError, &format!("Contacts database error: {}", error))
+}
+
+impl Node {
+    /// The contacts are kept in the contact book of the main profile
+    async fn main_profile_or_send_error<T>(
+        identity_manager: &Arc<Mutex<IdentityManager>>,
+        res: &Sender<Result<T, APIError>>,
+    ) -> Option<ShinkaiName> {
+        match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => Some(std_identity.clone().full_identity_name),
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::IdentityNotFound,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                None
+            }
+        }
+    }
+
+    pub async fn v2_api_get_contacts(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        res: Sender<Result<ContactBook, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+        let Some(profile) = Self::main_profile_or_send_error(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let result = db.get_contact_book(&profile).map_err(contacts_db_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_set_contact(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        payload: APISetContact,
+        res: Sender<Result<Contact, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+        let Some(profile) = Self::main_profile_or_send_error(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        // Contacts are whole nodes or profiles, never devices or agents
+        let identity = match ShinkaiName::new(payload.identity.clone()) {
+            Ok(name) if name.subidentity_type.is_none() => name.full_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("{} isn't the name of a node or a profile", payload.identity),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+        let non_blank = |value: Option<String>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let contact = Contact {
+            identity,
+            nickname: non_blank(payload.nickname),
+            notes: non_blank(payload.notes),
+            trust_level: payload.trust_level,
+            pinned: payload.pinned,
+            added_at: ShinkaiStringTime::generate_time_now(),
+        };
+
+        let result = db
+            .set_contact(&profile, contact.clone())
+            .and_then(|_| db.get_contact_book(&profile))
+            .map(|contact_book| {
+                contact_book
+                    .contacts
+                    .into_iter()
+                    .find(|saved| saved.identity == contact.identity)
+                    .unwrap_or(contact)
+            })
+            .map_err(contacts_db_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_remove_contact(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        payload: APIRemoveContact,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+        let Some(profile) = Self::main_profile_or_send_error(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let identity = payload.identity.trim().to_lowercase();
+        let result = match db.remove_contact(&profile, &identity) {
+            Err(ShinkaiDBError::DataNotFound) => Err(APIError::from_code(
+                ErrorCode::NotFound,
+                &format!("{} isn't a contact", payload.identity),
+            )),
+            result => result.map_err(contacts_db_api_error),
+        };
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_set_contacts_allowlist_mode(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        payload: APISetContactsAllowlistMode,
+        res: Sender<Result<ContactBook, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+        let Some(profile) = Self::main_profile_or_send_error(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let result = db
+            .set_contacts_allowlist_mode(&profile, payload.enabled)
+            .and_then(|_| db.get_contact_book(&profile))
+            .map_err(contacts_db_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    /// Moves the message request to the inboxes of the main profile, adding its sender to the contacts if it
+    /// isn't one yet. Untrusted contacts are trusted as known ones from then on.
+    pub async fn v2_api_accept_message_request(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        payload: APIAcceptMessageRequest,
+        res: Sender<Result<Contact, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+        let Some(profile) = Self::main_profile_or_send_error(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let sender = match InboxName::new(payload.inbox_name.clone()) {
+            Ok(InboxName::RegularInbox { identities, .. })
+                if db.is_inbox_message_request(&payload.inbox_name).unwrap_or(false) =>
+            {
+                identities
+                    .into_iter()
+                    .find(|identity| identity.full_name != profile.full_name)
+            }
+            _ => None,
+        };
+        let Some(sender) = sender else {
+            let api_error = APIError::from_code(
+                ErrorCode::NotFound,
+                &format!("{} isn't a message request", payload.inbox_name),
+            );
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        };
+
+        let result = db.get_contact_book(&profile).and_then(|contact_book| {
+            let contact = match contact_book.find(&sender) {
+                Some(contact) if contact.trust_level == ContactTrustLevel::Untrusted => Contact {
+                    trust_level: ContactTrustLevel::Known,
+                    ..contact.clone()
+                },
+                Some(contact) => contact.clone(),
+                None => Contact {
+                    identity: sender.full_name.clone(),
+                    nickname: None,
+                    notes: None,
+                    trust_level: ContactTrustLevel::Known,
+                    pinned: false,
+                    added_at: ShinkaiStringTime::generate_time_now(),
+                },
+            };
+            db.set_contact(&profile, contact.clone())?;
+            db.set_inbox_message_request(&payload.inbox_name, false)?;
+            Ok(contact)
+        });
+        let _ = res.send(result.map_err(contacts_db_api_error)).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_get_inbound_message_policy(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        res: Sender<Result<InboundMessagePolicy, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+        let Some(profile) = Self::main_profile_or_send_error(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let result = db.get_inbound_message_policy(&profile).map_err(contacts_db_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    /// Replaces the inbound message policy of the main profile. Blank and repeated keywords are dropped.
+    pub async fn v2_api_set_inbound_message_policy(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        mut payload: InboundMessagePolicy,
+        res: Sender<Result<InboundMessagePolicy, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+        let Some(profile) = Self::main_profile_or_send_error(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let mut blocked_keywords: Vec<String> = Vec::new();
+        for keyword in payload.blocked_keywords {
+            let keyword = keyword.trim().to_string();
+            if !keyword.is_empty() && !blocked_keywords.contains(&keyword) {
+                blocked_keywords.push(keyword);
+            }
+        }
+        payload.blocked_keywords = blocked_keywords;
+
+        let result = db
+            .set_inbound_message_policy(&profile, &payload)
+            .map(|_| payload)
+            .map_err(contacts_db_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc, time::Duration};
 
 use async_channel::Sender;
 use ed25519_dalek::SigningKey;
@@ -7,32 +7,81 @@ use shinkai_message_primitives::{
     schemas::{
         inbox_name::InboxName,
         llm_providers::serialized_llm_provider::SerializedLLMProvider,
+        job_citation::JobCitation,
+        retrieval_config::RetrievalConfig,
         shinkai_name::{ShinkaiName, ShinkaiSubidentityType},
     },
-    shinkai_message::shinkai_message_schemas::{APIChangeJobAgentRequest, JobCreationInfo, JobMessage, MessageSchemaType, V2ChatMessage},
+    shinkai_message::shinkai_message_schemas::{
+        APIChangeJobAgentRequest, APICreateJobForPath, APIGetJobStatus, APISetFolderAgent, APISetJobPlanning,
+        APISetJobRetrievalConfig, FolderAgentBinding, JobCreationInfo, JobMessage, MessageSchemaType, V2ChatMessage,
+    },
+    shinkai_utils::job_scope::{JobScope, VectorFSFolderScopeEntry},
 };
+use shinkai_vector_resources::shinkai_time::ShinkaiStringTime;
+use shinkai_vector_resources::vector_resource::VRPath;
 
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::Mutex;
 use x25519_dalek::PublicKey as EncryptionPublicKey;
 
 use crate::{
-    db::ShinkaiDB,
-    llm_provider::job_manager::JobManager,
-    managers::IdentityManager,
+    db::{db_errors::ShinkaiDBError, ShinkaiDB},
+    llm_provider::{
+        job_manager::JobManager,
+        job_status::{JobState, JobStatus, JobStatusTracker},
+        queue::job_queue_manager::{JobForProcessing, JobQueueManager},
+    },
+    managers::{identity_manager::IdentityManagerTrait, IdentityManager},
     network::{
+        error_code::ErrorCode,
+        inbox_message_changes::{InboxMessageChangeError, InboxMessageChanges},
+        message_attachments::{MessageAttachmentError, MessageAttachments},
+        node::ProxyConnectionInfo,
         node_api_router::{APIError, SendResponseBodyData},
         node_error::NodeError,
+        node_events::NodeEventType,
+        ws_manager::WSUpdateHandler,
         Node,
     },
     schemas::{
-        identity::Identity,
-        smart_inbox::{SmartInbox, V2SmartInbox},
+        identity::{Identity, StandardIdentity},
+        inbox_digest::{InboxDigestSchedule, InboxDigestSettings},
+        inbox_message_change::{InboxMessageChange, MessageChangeHistory},
+        inbox_permission::InboxPermission,
+        smart_inbox::{InboxFolders, SmartInbox, V2SmartInbox},
     },
     vector_fs::vector_fs::VectorFS,
 };
 
 use x25519_dalek::StaticSecret as EncryptionStaticKey;
 
+/// Longest a job status request waits for the job to change, so it doesn't outlive proxies' timeouts
+const MAX_JOB_STATUS_WAIT_MS: u64 = 30_000;
+
+fn inbox_message_change_api_error(error: InboxMessageChangeError) -> APIError {
+    let code = match error {
+        InboxMessageChangeError::InvalidInput(_) => ErrorCode::InvalidInput,
+        InboxMessageChangeError::NotFound(_) => ErrorCode::NotFound,
+        InboxMessageChangeError::NotAllowed(_) => ErrorCode::PermissionDenied,
+        InboxMessageChangeError::DatabaseError(_) => ErrorCode::DatabaseError,
+        InboxMessageChangeError::NetworkError(_) => ErrorCode::InternalError,
+    };
+    APIError::from_code(code, &error.to_string())
+}
+
+fn message_attachment_api_error(error: MessageAttachmentError) -> APIError {
+    let code = match error {
+        MessageAttachmentError::InvalidInput(_) => ErrorCode::InvalidInput,
+        MessageAttachmentError::NotFound(_) => ErrorCode::NotFound,
+        MessageAttachmentError::NotAllowed(_) => ErrorCode::PermissionDenied,
+        MessageAttachmentError::NotReceived(_) => ErrorCode::Conflict,
+        MessageAttachmentError::DatabaseError(_) => ErrorCode::DatabaseError,
+        MessageAttachmentError::NetworkError(_) => ErrorCode::InternalError,
+    };
+    APIError::from_code(code, &error.to_string())
+}
+
 impl Node {
     pub fn convert_smart_inbox_to_v2_smart_inbox(smart_inbox: SmartInbox) -> Result<V2SmartInbox, NodeError> {
         let last_message = match smart_inbox.last_message {
@@ -48,6 +97,13 @@ impl Node {
             is_finished: smart_inbox.is_finished,
             job_scope: smart_inbox.job_scope,
             agent: smart_inbox.agent,
+            folder: smart_inbox.folder,
+            labels: smart_inbox.labels,
+            is_archived: smart_inbox.is_archived,
+            is_muted: smart_inbox.is_muted,
+            unread_count: smart_inbox.unread_count,
+            is_message_request: smart_inbox.is_message_request,
+            contact: smart_inbox.contact,
         })
     }
 
@@ -69,6 +125,12 @@ impl Node {
             return Ok(());
         }
 
+        if let Some(Err(err)) = job_creation_info.retrieval.as_ref().map(RetrievalConfig::validate) {
+            let api_error = APIError::from_code(ErrorCode::InvalidInput, &format!("Invalid retrieval config: {}", err));
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
         // Get the main identity from the identity manager
         let main_identity = {
             let identity_manager = identity_manager.lock().await;
@@ -77,6 +139,7 @@ impl Node {
                 None => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: "Failed to get main identity".to_string(),
                     };
@@ -92,6 +155,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create sender name: {}", err),
                 };
@@ -110,6 +174,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create recipient name: {}", err),
                 };
@@ -132,6 +197,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create Shinkai message: {}", err),
                 };
@@ -149,6 +215,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: err.error_code,
                     error: "Internal Server Error".to_string(),
                     message: format!("{}", err),
                 };
@@ -175,6 +242,12 @@ impl Node {
             return Ok(());
         }
 
+        if let Some(Err(err)) = job_message.retrieval.as_ref().map(RetrievalConfig::validate) {
+            let api_error = APIError::from_code(ErrorCode::InvalidInput, &format!("Invalid retrieval config: {}", err));
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
         // Get the main identity from the identity manager
         let main_identity = {
             let identity_manager = identity_manager.lock().await;
@@ -183,6 +256,7 @@ impl Node {
                 None => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: "Failed to get main identity".to_string(),
                     };
@@ -198,6 +272,10 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: match err {
+                        ShinkaiDBError::DataNotFound => ErrorCode::JobNotFound,
+                        _ => err.error_code(),
+                    },
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to retrieve job: {}", err),
                 };
@@ -212,6 +290,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create sender name: {}", err),
                 };
@@ -230,6 +309,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create recipient name: {}", err),
                 };
@@ -252,6 +332,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create Shinkai message: {}", err),
                 };
@@ -293,6 +374,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: err.error_code,
                     error: "Internal Server Error".to_string(),
                     message: format!("{}", err),
                 };
@@ -321,6 +403,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to retrieve messages: {}", err),
                 };
@@ -335,6 +418,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to convert messages: {}", err),
                 };
@@ -347,6 +431,7 @@ impl Node {
         if let Err(_) = res.send(Ok(v2_chat_messages)).await {
             let api_error = APIError {
                 code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error_code: ErrorCode::InternalError,
                 error: "Internal Server Error".to_string(),
                 message: "Failed to send messages".to_string(),
             };
@@ -356,10 +441,58 @@ impl Node {
         Ok(())
     }
 
+    /// Polls the inbox until the agent answers the message with the given hash and returns the answer.
+    /// It doesn't give up on its own, callers wrap it in a timeout.
+    pub async fn v2_wait_for_job_reply(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        inbox_name: String,
+        message_hash: String,
+    ) -> String {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+            let (res_sender, res_receiver) = async_channel::bounded(1);
+            let _ = Self::v2_get_last_messages_from_inbox(
+                db.clone(),
+                bearer.clone(),
+                inbox_name.clone(),
+                10,
+                None,
+                res_sender,
+            )
+            .await;
+            if let Ok(Ok(messages)) = res_receiver.recv().await {
+                if let Some(reply) = Self::agent_reply_after(&messages, &message_hash) {
+                    return reply;
+                }
+            }
+        }
+    }
+
+    /// Content of the first agent message after the message with the given hash. Agent messages are the
+    /// ones that aren't sent by a profile.
+    pub fn agent_reply_after(messages: &[V2ChatMessage], message_hash: &str) -> Option<String> {
+        let position = messages
+            .iter()
+            .position(|message| message.node_api_data.node_message_hash == message_hash)?;
+        messages[position + 1..]
+            .iter()
+            .find(|message| message.sender_subidentity.is_empty())
+            .map(|message| message.job_message.content.clone())
+    }
+
+    /// Lists the inboxes of the main profile, only the ones in the folder and tagged with the label if given.
+    /// Archived inboxes are only listed if `include_archived` is set. Message requests are listed instead of the
+    /// inboxes if `message_requests` is set.
+    #[allow(clippy::too_many_arguments)]
     pub async fn v2_get_all_smart_inboxes(
         db: Arc<ShinkaiDB>,
         identity_manager: Arc<Mutex<IdentityManager>>,
         bearer: String,
+        folder: Option<String>,
+        label: Option<String>,
+        include_archived: bool,
+        message_requests: bool,
         res: Sender<Result<Vec<V2SmartInbox>, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
@@ -375,6 +508,7 @@ impl Node {
                 _ => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: "Failed to get main identity".to_string(),
                     };
@@ -385,11 +519,19 @@ impl Node {
         };
 
         // Retrieve all smart inboxes for the profile
-        let smart_inboxes = match db.get_all_smart_inboxes_for_profile(main_identity) {
-            Ok(inboxes) => inboxes,
+        let smart_inboxes = match db.get_all_smart_inboxes_for_profile(main_identity, include_archived) {
+            Ok(mut inboxes) => {
+                inboxes.retain(|inbox| {
+                    inbox.is_message_request == message_requests
+                        && folder.iter().all(|folder| inbox.folder.as_ref() == Some(folder))
+                        && label.iter().all(|label| inbox.labels.contains(label))
+                });
+                inboxes
+            }
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to retrieve smart inboxes: {}", err),
                 };
@@ -411,6 +553,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to convert smart inboxes: {}", err),
                 };
@@ -440,6 +583,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to retrieve LLM providers: {}", err),
                 };
@@ -470,6 +614,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to update inbox name: {}", e),
                 };
@@ -480,105 +625,1224 @@ impl Node {
         Ok(())
     }
 
-    pub async fn v2_create_files_inbox(
+    /// Moves the inboxes into the folder, or out of their folder if None. Nothing is moved if an inbox doesn't exist.
+    pub async fn v2_move_inboxes_to_folder(
         db: Arc<ShinkaiDB>,
         bearer: String,
-        res: Sender<Result<String, APIError>>,
-    ) -> Result<(), APIError> {
+        inbox_names: Vec<String>,
+        folder: Option<String>,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
         // Validate the bearer token
         if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        let hash_hex = uuid::Uuid::new_v4().to_string();
+        for inbox_name in &inbox_names {
+            if !db.does_inbox_exists(inbox_name).unwrap_or(false) {
+                let api_error =
+                    APIError::from_code(ErrorCode::InboxNotFound, &format!("Inbox {} not found", inbox_name));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        }
 
-        match db.create_files_message_inbox(hash_hex.clone()) {
-            Ok(_) => {
-                if let Err(_) = res.send(Ok(hash_hex)).await {
-                    let api_error = APIError {
-                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                        error: "Internal Server Error".to_string(),
-                        message: "Failed to send response".to_string(),
-                    };
+        let result = inbox_names
+            .iter()
+            .try_for_each(|inbox_name| db.set_inbox_folder(inbox_name, folder.clone()))
+            .map_err(|err| {
+                APIError::from_code(
+                    ErrorCode::DatabaseError,
+                    &format!("Failed to move the inboxes: {}", err),
+                )
+            });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_set_inbox_labels(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        inbox_name: String,
+        labels: Vec<String>,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        if !db.does_inbox_exists(&inbox_name).unwrap_or(false) {
+            let api_error = APIError::from_code(ErrorCode::InboxNotFound, &format!("Inbox {} not found", inbox_name));
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        let result = db.set_inbox_labels(&inbox_name, labels).map_err(|err| {
+            APIError::from_code(
+                ErrorCode::DatabaseError,
+                &format!("Failed to set the labels of inbox {}: {}", inbox_name, err),
+            )
+        });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_get_inbox_folders(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        res: Sender<Result<InboxFolders, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Get the main identity from the identity manager
+        let main_identity = {
+            let identity_manager = identity_manager.lock().await;
+            match identity_manager.get_main_identity() {
+                Some(Identity::Standard(identity)) => identity.clone(),
+                _ => {
+                    let api_error = APIError::from_code(ErrorCode::InternalError, "Failed to get main identity");
                     let _ = res.send(Err(api_error)).await;
+                    return Ok(());
                 }
-                Ok(())
-            }
-            Err(err) => {
-                let api_error = APIError {
-                    code: StatusCode::BAD_REQUEST.as_u16(),
-                    error: "Bad Request".to_string(),
-                    message: format!("Failed to create files message inbox: {}", err),
-                };
-                let _ = res.send(Err(api_error)).await;
-                Ok(())
             }
+        };
+
+        let result = db.get_inbox_folders_for_profile(main_identity).map_err(|err| {
+            APIError::from_code(
+                ErrorCode::DatabaseError,
+                &format!("Failed to get the inbox folders: {}", err),
+            )
+        });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    /// Archives the inbox, hiding it from the inboxes list, or brings it back
+    pub async fn v2_set_inbox_archived(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        inbox_name: String,
+        archived: bool,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        if !db.does_inbox_exists(&inbox_name).unwrap_or(false) {
+            let api_error = APIError::from_code(ErrorCode::InboxNotFound, &format!("Inbox {} not found", inbox_name));
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
         }
+
+        let result = db.set_inbox_archived(&inbox_name, archived).map_err(|err| {
+            APIError::from_code(
+                ErrorCode::DatabaseError,
+                &format!("Failed to update inbox {}: {}", inbox_name, err),
+            )
+        });
+        let _ = res.send(result).await;
+        Ok(())
     }
 
-    pub async fn v2_add_file_to_inbox(
+    /// Mutes the inbox so that its unread messages aren't counted, or unmutes it
+    pub async fn v2_set_inbox_muted(
         db: Arc<ShinkaiDB>,
-        vector_fs: Arc<VectorFS>,
-        file_inbox_name: String,
-        filename: String,
-        file: Vec<u8>,
         bearer: String,
-        res: Sender<Result<String, APIError>>,
-    ) -> Result<(), APIError> {
+        inbox_name: String,
+        muted: bool,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
         // Validate the bearer token
         if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        match vector_fs
-            .db
-            .add_file_to_files_message_inbox(file_inbox_name, filename, file)
-        {
-            Ok(_) => {
-                let _ = res.send(Ok("File added successfully".to_string())).await;
-                Ok(())
-            }
-            Err(err) => {
-                let _ = res
-                    .send(Err(APIError {
-                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                        error: "Internal Server Error".to_string(),
-                        message: format!("Failed to add file to inbox: {}", err),
-                    }))
-                    .await;
-                Ok(())
-            }
+        if !db.does_inbox_exists(&inbox_name).unwrap_or(false) {
+            let api_error = APIError::from_code(ErrorCode::InboxNotFound, &format!("Inbox {} not found", inbox_name));
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
         }
+
+        let result = db.set_inbox_muted(&inbox_name, muted).map_err(|err| {
+            APIError::from_code(
+                ErrorCode::DatabaseError,
+                &format!("Failed to update inbox {}: {}", inbox_name, err),
+            )
+        });
+        let _ = res.send(result).await;
+        Ok(())
     }
 
-    pub async fn v2_api_change_job_llm_provider(
+    /// Digest schedule of the main profile, None if it doesn't get digests
+    pub async fn v2_get_inbox_digest(
         db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
         bearer: String,
-        payload: APIChangeJobAgentRequest,
-        res: Sender<Result<String, APIError>>,
+        res: Sender<Result<Option<InboxDigestSchedule>, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
         if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
             return Ok(());
         }
 
-        // Extract job ID and new agent ID from the payload
-        let change_request = payload;
+        // Get the main identity from the identity manager
+        let main_identity = {
+            let identity_manager = identity_manager.lock().await;
+            match identity_manager.get_main_identity() {
+                Some(Identity::Standard(identity)) => identity.clone(),
+                _ => {
+                    let api_error = APIError::from_code(ErrorCode::InternalError, "Failed to get main identity");
+                    let _ = res.send(Err(api_error)).await;
+                    return Ok(());
+                }
+            }
+        };
 
-        match db.change_job_llm_provider(&change_request.job_id, &change_request.new_agent_id) {
-            Ok(_) => {
-                let _ = res.send(Ok("Job agent changed successfully".to_string())).await;
-                Ok(())
+        let result = db
+            .get_inbox_digest_schedule(&main_identity.full_identity_name)
+            .map_err(|err| {
+                APIError::from_code(
+                    ErrorCode::DatabaseError,
+                    &format!("Failed to get the digest schedule: {}", err),
+                )
+            });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    /// Schedules the digests of the main profile, or changes their settings. The first digest of a new schedule
+    /// comes one period after it's set.
+    pub async fn v2_set_inbox_digest(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        mut settings: InboxDigestSettings,
+        res: Sender<Result<InboxDigestSchedule, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Get the main identity from the identity manager
+        let main_identity = {
+            let identity_manager = identity_manager.lock().await;
+            match identity_manager.get_main_identity() {
+                Some(Identity::Standard(identity)) => identity.clone(),
+                _ => {
+                    let api_error = APIError::from_code(ErrorCode::InternalError, "Failed to get main identity");
+                    let _ = res.send(Err(api_error)).await;
+                    return Ok(());
+                }
             }
-            Err(err) => {
-                let api_error = APIError {
-                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                    error: "Internal Server Error".to_string(),
-                    message: format!("Failed to change job agent: {}", err),
+        };
+        let profile = main_identity.full_identity_name;
+
+        if !matches!(db.get_llm_provider(&settings.llm_provider_id, &profile), Ok(Some(_))) {
+            let api_error = APIError::from_code(
+                ErrorCode::LlmProviderNotFound,
+                &format!("Agent {} not found", settings.llm_provider_id),
+            );
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+        settings.email_to = settings
+            .email_to
+            .iter()
+            .map(|address| address.trim().to_string())
+            .filter(|address| !address.is_empty())
+            .collect();
+        if !settings.email_to.is_empty() && !cfg!(feature = "email") {
+            let api_error = APIError::from_code(
+                ErrorCode::InvalidInput,
+                "This node can't email digests, it was built without the email feature",
+            );
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        let result = db
+            .get_inbox_digest_schedule(&profile)
+            .and_then(|existing| {
+                let schedule = match existing {
+                    Some(existing) => InboxDigestSchedule { settings, ..existing },
+                    None => InboxDigestSchedule {
+                        settings,
+                        inbox_name: None,
+                        last_digest_at: ShinkaiStringTime::generate_time_now(),
+                    },
                 };
+                db.set_inbox_digest_schedule(&profile, &schedule)?;
+                Ok(schedule)
+            })
+            .map_err(|err| {
+                APIError::from_code(
+                    ErrorCode::DatabaseError,
+                    &format!("Failed to set the digest schedule: {}", err),
+                )
+            });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    /// Stops the digests of the main profile. The inbox of the past digests is kept.
+    pub async fn v2_remove_inbox_digest(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Get the main identity from the identity manager
+        let main_identity = {
+            let identity_manager = identity_manager.lock().await;
+            match identity_manager.get_main_identity() {
+                Some(Identity::Standard(identity)) => identity.clone(),
+                _ => {
+                    let api_error = APIError::from_code(ErrorCode::InternalError, "Failed to get main identity");
+                    let _ = res.send(Err(api_error)).await;
+                    return Ok(());
+                }
+            }
+        };
+
+        let result = db
+            .remove_inbox_digest_schedule(&main_identity.full_identity_name)
+            .map_err(|err| {
+                APIError::from_code(
+                    ErrorCode::DatabaseError,
+                    &format!("Failed to remove the digest schedule: {}", err),
+                )
+            });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn v2_change_inbox_message(
+        db: Arc<ShinkaiDB>,
+        node_name: ShinkaiName,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        encryption_secret_key: EncryptionStaticKey,
+        identity_secret_key: SigningKey,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        bearer: String,
+        inbox_name: String,
+        message_hash: String,
+        new_content: Option<String>,
+        res: Sender<Result<InboxMessageChange, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Messages sent through the API are sent by the main profile of the node
+        let author = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::IdentityNotFound,
+                    "Wrong identity type. Expected Standard identity.",
+                );
                 let _ = res.send(Err(api_error)).await;
-                Ok(())
+                return Ok(());
             }
+        };
+
+        let change =
+            match InboxMessageChanges::change_own_message(&db, &author, &inbox_name, &message_hash, new_content) {
+                Ok(change) => change,
+                Err(e) => {
+                    let _ = res.send(Err(inbox_message_change_api_error(e))).await;
+                    return Ok(());
+                }
+            };
+
+        InboxMessageChanges::send_to_participants(
+            &change,
+            &node_name,
+            &encryption_secret_key,
+            &identity_secret_key,
+            db.clone(),
+            identity_manager,
+            proxy_connection_info,
+            ws_manager,
+        )
+        .await;
+
+        let _ = res.send(Ok(change)).await;
+        Ok(())
+    }
+
+    pub async fn v2_get_inbox_message_history(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        inbox_name: String,
+        message_hash: String,
+        res: Sender<Result<MessageChangeHistory, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
         }
+
+        if !db.does_inbox_exists(&inbox_name).unwrap_or(false) {
+            let api_error = APIError::from_code(ErrorCode::InboxNotFound, &format!("Inbox {} not found", inbox_name));
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        let result = db.get_inbox_message_changes(&inbox_name, &message_hash).map_err(|err| {
+            APIError::from_code(
+                ErrorCode::DatabaseError,
+                &format!("Failed to get the history of message {}: {}", message_hash, err),
+            )
+        });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn v2_send_message_with_attachments(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        node_name: ShinkaiName,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        encryption_secret_key: EncryptionStaticKey,
+        identity_secret_key: SigningKey,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        bearer: String,
+        inbox_name: String,
+        content: String,
+        files_inbox: String,
+        res: Sender<Result<SendResponseBodyData, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Messages sent through the API are sent by the main profile of the node
+        let sender = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::IdentityNotFound,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let files = match vector_fs.db.get_all_files_from_inbox(files_inbox.clone()) {
+            Ok(files) => files,
+            Err(err) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to get the files of inbox {}: {}", files_inbox, err),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let message = match MessageAttachments::send_message(
+            &sender,
+            &inbox_name,
+            content,
+            files,
+            &node_name,
+            &encryption_secret_key,
+            &identity_secret_key,
+            db.clone(),
+            identity_manager,
+            proxy_connection_info,
+            ws_manager,
+        )
+        .await
+        {
+            Ok(message) => message,
+            Err(e) => {
+                let _ = res.send(Err(message_attachment_api_error(e))).await;
+                return Ok(());
+            }
+        };
+
+        let message_hash = message.calculate_message_hash_for_pagination();
+        let parent_message_id = db.get_parent_message_hash(&inbox_name, &message_hash).unwrap_or(None);
+        let response = SendResponseBodyData {
+            message_id: message_hash,
+            parent_message_id,
+            inbox: inbox_name,
+            scheduled_time: message.external_metadata.scheduled_time,
+        };
+        let _ = res.send(Ok(response)).await;
+        Ok(())
+    }
+
+    pub async fn v2_download_attachment(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        inbox_name: String,
+        message_hash: String,
+        attachment_id: String,
+        res: Sender<Result<(String, Vec<u8>), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = MessageAttachments::read_attachment(&db, &inbox_name, &message_hash, &attachment_id)
+            .map_err(message_attachment_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_create_files_inbox(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), APIError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let hash_hex = uuid::Uuid::new_v4().to_string();
+
+        match db.create_files_message_inbox(hash_hex.clone()) {
+            Ok(_) => {
+                if let Err(_) = res.send(Ok(hash_hex)).await {
+                    let api_error = APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
+                        error: "Internal Server Error".to_string(),
+                        message: "Failed to send response".to_string(),
+                    };
+                    let _ = res.send(Err(api_error)).await;
+                }
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("Failed to create files message inbox: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_add_file_to_inbox(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        file_inbox_name: String,
+        filename: String,
+        file: Vec<u8>,
+        bearer: String,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), APIError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match vector_fs
+            .db
+            .add_file_to_files_message_inbox(file_inbox_name, filename, file)
+        {
+            Ok(_) => {
+                let _ = res.send(Ok("File added successfully".to_string())).await;
+                Ok(())
+            }
+            Err(err) => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
+                        error: "Internal Server Error".to_string(),
+                        message: format!("Failed to add file to inbox: {}", err),
+                    }))
+                    .await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_change_job_llm_provider(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: APIChangeJobAgentRequest,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Extract job ID and new agent ID from the payload
+        let change_request = payload;
+
+        match db.change_job_llm_provider(&change_request.job_id, &change_request.new_agent_id) {
+            Ok(_) => {
+                let _ = res.send(Ok("Job agent changed successfully".to_string())).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to change job agent: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_update_job_to_finished(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        job_id: String,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match db.update_job_to_finished(&job_id) {
+            Ok(_) => {
+                let _ = res.send(Ok("Job updated to finished".to_string())).await;
+            }
+            Err(err) => {
+                let api_error = match err {
+                    ShinkaiDBError::SomeError(_) => APIError {
+                        code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
+                        error: "Bad Request".to_string(),
+                        message: format!("{}", err),
+                    },
+                    _ => APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
+                        error: "Internal Server Error".to_string(),
+                        message: format!("{}", err),
+                    },
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_get_job_status(
+        db: Arc<ShinkaiDB>,
+        job_manager: Option<Arc<Mutex<JobManager>>>,
+        bearer: String,
+        payload: APIGetJobStatus,
+        res: Sender<Result<JobStatus, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        if db.get_job(&payload.job_id).is_err() {
+            let api_error = APIError::from_code(ErrorCode::JobNotFound, &format!("Job {} not found", payload.job_id));
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+        let job_queue_manager = match &job_manager {
+            Some(job_manager) => Some(job_manager.lock().await.job_queue_manager.clone()),
+            None => None,
+        };
+
+        // Subscribed before reading the status, so a change in between isn't missed
+        let mut events = db.events.subscribe();
+        let mut status = Self::get_job_status(&db, job_queue_manager.as_ref(), &payload.job_id).await;
+
+        let wait_ms = payload.wait_ms.unwrap_or(0).min(MAX_JOB_STATUS_WAIT_MS);
+        let is_pending = |status: &Result<JobStatus, ShinkaiDBError>| {
+            matches!(status, Ok(status) if matches!(status.state, JobState::Queued | JobState::Running))
+        };
+        if wait_ms > 0 && is_pending(&status) {
+            let deadline = tokio::time::sleep(Duration::from_millis(wait_ms));
+            tokio::pin!(deadline);
+            loop {
+                tokio::select! {
+                    _ = &mut deadline => break,
+                    event = events.recv() => match event {
+                        Ok(event) if event.event_type == NodeEventType::JobStatus
+                            && event.data["job_id"] == payload.job_id.as_str() => {}
+                        Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    },
+                }
+                let previous_state = status.as_ref().map(|status| status.state).ok();
+                status = Self::get_job_status(&db, job_queue_manager.as_ref(), &payload.job_id).await;
+                if status.as_ref().map(|status| status.state).ok() != previous_state || !is_pending(&status) {
+                    break;
+                }
+            }
+        }
+
+        let result = status.map_err(|err| {
+            APIError::from_code(
+                ErrorCode::DatabaseError,
+                &format!("Failed to get the status of job {}: {}", payload.job_id, err),
+            )
+        });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    async fn get_job_status(
+        db: &ShinkaiDB,
+        job_queue_manager: Option<&Arc<Mutex<JobQueueManager<JobForProcessing>>>>,
+        job_id: &str,
+    ) -> Result<JobStatus, ShinkaiDBError> {
+        let job = db.get_job(job_id)?;
+        let queued_messages = match job_queue_manager {
+            Some(job_queue_manager) => job_queue_manager.lock().await.queue_len(job_id).await,
+            None => 0,
+        };
+        let (state, error) = JobStatusTracker::current_state(job_id, queued_messages);
+
+        // Answers of the agent are sent by the node without a subidentity
+        let inbox_name = InboxName::get_job_inbox_name_from_params(job_id.to_string())?.to_string();
+        let latest_output = db
+            .get_last_messages_from_inbox(inbox_name, 1, None)?
+            .last()
+            .and_then(|branch| branch.first())
+            .filter(|message| message.get_sender_subidentity().unwrap_or_default().is_empty())
+            .and_then(|message| message.get_message_content().ok())
+            .and_then(|content| serde_json::from_str::<JobMessage>(&content).ok())
+            .map(|job_message| job_message.content);
+
+        Ok(JobStatus {
+            job_id: job_id.to_string(),
+            state,
+            queued_messages,
+            steps: job.step_history.len(),
+            error,
+            latest_output,
+        })
+    }
+
+    pub async fn v2_api_set_job_planning(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: APISetJobPlanning,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        if db.get_job(&payload.job_id).is_err() {
+            let api_error = APIError::from_code(ErrorCode::JobNotFound, &format!("Job {} not found", payload.job_id));
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        let result = db
+            .set_job_planning_enabled(&payload.job_id, payload.enabled)
+            .map(|_| json!({ "job_id": payload.job_id, "enabled": payload.enabled }))
+            .map_err(|err| {
+                APIError::from_code(
+                    ErrorCode::DatabaseError,
+                    &format!("Failed to set the planning of job {}: {}", payload.job_id, err),
+                )
+            });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    /// The latest plan of the job, with the status and output of its steps
+    pub async fn v2_api_get_job_plan(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        job_id: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = match db.get_job_plan(&job_id) {
+            Ok(plan) => Ok(json!(plan)),
+            Err(ShinkaiDBError::DataNotFound) => Err(APIError::from_code(
+                ErrorCode::NotFound,
+                &format!("Job {} has no plan", job_id),
+            )),
+            Err(err) => Err(APIError::from_code(
+                ErrorCode::DatabaseError,
+                &format!("Failed to get the plan of job {}: {}", job_id, err),
+            )),
+        };
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    /// Replaces the retrieval parameters persisted in the job. Messages can still override them one by one.
+    pub async fn v2_api_set_job_retrieval_config(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: APISetJobRetrievalConfig,
+        res: Sender<Result<RetrievalConfig, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        if db.get_job(&payload.job_id).is_err() {
+            let api_error = APIError::from_code(ErrorCode::JobNotFound, &format!("Job {} not found", payload.job_id));
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+        if let Err(err) = payload.config.validate() {
+            let api_error = APIError::from_code(ErrorCode::InvalidInput, &format!("Invalid retrieval config: {}", err));
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        let result = db
+            .set_job_retrieval_config(&payload.job_id, &payload.config)
+            .map(|_| payload.config)
+            .map_err(|err| {
+                APIError::from_code(
+                    ErrorCode::DatabaseError,
+                    &format!("Failed to set the retrieval config of job {}: {}", payload.job_id, err),
+                )
+            });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_get_job_retrieval_config(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        job_id: String,
+        res: Sender<Result<RetrievalConfig, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        if db.get_job(&job_id).is_err() {
+            let api_error = APIError::from_code(ErrorCode::JobNotFound, &format!("Job {} not found", job_id));
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        let result = db.get_job_retrieval_config(&job_id).map_err(|err| {
+            APIError::from_code(
+                ErrorCode::DatabaseError,
+                &format!("Failed to get the retrieval config of job {}: {}", job_id, err),
+            )
+        });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    /// Source content a reply of the job was based on, with the pages and offsets to jump to in the documents
+    pub async fn v2_api_get_job_message_citations(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        job_id: String,
+        message_hash: String,
+        res: Sender<Result<Vec<JobCitation>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        if db.get_job(&job_id).is_err() {
+            let api_error = APIError::from_code(ErrorCode::JobNotFound, &format!("Job {} not found", job_id));
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        let result = db.get_job_message_citations(&job_id, &message_hash).map_err(|err| {
+            APIError::from_code(
+                ErrorCode::DatabaseError,
+                &format!("Failed to get the citations of message {}: {}", message_hash, err),
+            )
+        });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_mark_as_read_up_to(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        inbox_name: String,
+        up_to_time: String,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match Self::internal_mark_as_read_up_to(db, inbox_name, up_to_time.clone()).await {
+            Ok(true) => {
+                let _ = res.send(Ok("true".to_string())).await;
+            }
+            Ok(false) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("Failed to mark as read up to time: {}", up_to_time),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to mark as read: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Profile identity an inbox permission is given to. Devices get the permissions of their profile.
+    async fn inbox_permission_identity<T>(
+        identity_manager: &Arc<Mutex<IdentityManager>>,
+        profile: &str,
+        res: &Sender<Result<T, APIError>>,
+    ) -> Option<StandardIdentity> {
+        let identity = identity_manager.lock().await.search_identity(profile).await;
+        let standard_identity = match identity {
+            Some(Identity::Standard(std_identity)) => Some(std_identity),
+            Some(Identity::Device(std_device)) => std_device.to_standard_identity(),
+            Some(Identity::LLMProvider(_)) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::PermissionDenied,
+                    error: "Bad Request".to_string(),
+                    message: "Agent identities cannot have inbox permissions".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return None;
+            }
+            None => None,
+        };
+
+        if standard_identity.is_none() {
+            let api_error = APIError {
+                code: StatusCode::NOT_FOUND.as_u16(),
+                error_code: ErrorCode::ProfileNotFound,
+                error: "Not Found".to_string(),
+                message: format!("No identity found with the name: {}", profile),
+            };
+            let _ = res.send(Err(api_error)).await;
+        }
+        standard_identity
+    }
+
+    pub async fn v2_api_add_inbox_permission(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        inbox_name: String,
+        profile: String,
+        permission: String,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let permission = match InboxPermission::from_str(&permission) {
+            Ok(permission) => permission,
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("{}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let Some(standard_identity) = Self::inbox_permission_identity(&identity_manager, &profile, &res).await else {
+            return Ok(());
+        };
+
+        match db.add_permission(&inbox_name, &standard_identity, permission) {
+            Ok(_) => {
+                let _ = res.send(Ok("Inbox permission added".to_string())).await;
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::PermissionDenied,
+                    error: "Bad Request".to_string(),
+                    message: format!("Failed to add inbox permission: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_remove_inbox_permission(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        inbox_name: String,
+        profile: String,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(standard_identity) = Self::inbox_permission_identity(&identity_manager, &profile, &res).await else {
+            return Ok(());
+        };
+
+        match db.remove_permission(&inbox_name, &standard_identity) {
+            Ok(_) => {
+                let _ = res.send(Ok("Inbox permission removed".to_string())).await;
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::PermissionDenied,
+                    error: "Bad Request".to_string(),
+                    message: format!("Failed to remove inbox permission: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the path is a folder of the main profile and returns it with the profile
+    async fn folder_for_job(
+        identity_manager: &Arc<Mutex<IdentityManager>>,
+        vector_fs: &Arc<VectorFS>,
+        path: &str,
+    ) -> Result<(ShinkaiName, VRPath), APIError> {
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                return Err(APIError::from_code(
+                    ErrorCode::IdentityNotFound,
+                    "Wrong identity type. Expected Standard identity.",
+                ))
+            }
+        };
+
+        let vr_path = VRPath::from_string(path).map_err(|e| {
+            APIError::from_code(
+                ErrorCode::InvalidInput,
+                &format!("Failed to convert path to VRPath: {}", e),
+            )
+        })?;
+        vector_fs
+            .validate_path_points_to_folder(vr_path.clone(), &requester_name)
+            .await
+            .map_err(|e| APIError::from_code(e.error_code(), &format!("Invalid folder: {}", e)))?;
+
+        Ok((requester_name, vr_path))
+    }
+
+    /// Creates a job whose scope is the folder. The folder is searched when the job runs,
+    /// so the job sees the items added to it or removed from it afterwards.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn v2_api_create_job_for_path(
+        db: Arc<ShinkaiDB>,
+        node_name: ShinkaiName,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        job_manager: Arc<Mutex<JobManager>>,
+        vector_fs: Arc<VectorFS>,
+        bearer: String,
+        payload: APICreateJobForPath,
+        node_encryption_sk: EncryptionStaticKey,
+        node_encryption_pk: EncryptionPublicKey,
+        node_signing_sk: SigningKey,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let vr_path = match Self::folder_for_job(&identity_manager, &vector_fs, &payload.path).await {
+            Ok((_, vr_path)) => vr_path,
+            Err(api_error) => {
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+        let path = vr_path.format_to_string();
+
+        // The agent of the request wins over the one bound to the folder
+        let agent = match payload.agent {
+            Some(agent) => agent,
+            None => match db.get_folder_agent(&path) {
+                Ok(Some(agent)) => agent,
+                Ok(None) => {
+                    let api_error = APIError::from_code(
+                        ErrorCode::JobAgentMissing,
+                        &format!("No agent was set and the folder {} has no default agent", path),
+                    );
+                    let _ = res.send(Err(api_error)).await;
+                    return Ok(());
+                }
+                Err(e) => {
+                    let api_error = APIError::from_code(
+                        ErrorCode::DatabaseError,
+                        &format!("Failed to get the agent of the folder: {}", e),
+                    );
+                    let _ = res.send(Err(api_error)).await;
+                    return Ok(());
+                }
+            },
+        };
+
+        let name = vr_path.last_path_id().unwrap_or_else(|_| "/".to_string());
+        let mut scope = JobScope::new_default();
+        scope
+            .vector_fs_folders
+            .push(VectorFSFolderScopeEntry { name, path: vr_path });
+        let job_creation_info = JobCreationInfo {
+            scope,
+            is_hidden: payload.is_hidden,
+            retrieval: None,
+        };
+
+        Self::v2_create_new_job(
+            db,
+            node_name,
+            identity_manager,
+            job_manager,
+            bearer,
+            job_creation_info,
+            agent,
+            node_encryption_sk,
+            node_encryption_pk,
+            node_signing_sk,
+            res,
+        )
+        .await
+    }
+
+    pub async fn v2_api_set_folder_agent(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        vector_fs: Arc<VectorFS>,
+        bearer: String,
+        payload: APISetFolderAgent,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Bindings of folders that were deleted can still be removed
+        let Some(agent) = payload.agent else {
+            let path = VRPath::from_string(&payload.path)
+                .map(|vr_path| vr_path.format_to_string())
+                .unwrap_or(payload.path);
+            let result = db
+                .remove_folder_agent(&path)
+                .map(|_| format!("Default agent of {} removed", path))
+                .map_err(|e| APIError::from_code(ErrorCode::DatabaseError, &e.to_string()));
+            let _ = res.send(result).await;
+            return Ok(());
+        };
+
+        let (requester_name, vr_path) = match Self::folder_for_job(&identity_manager, &vector_fs, &payload.path).await {
+            Ok(folder) => folder,
+            Err(api_error) => {
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        match db.get_llm_provider(&agent, &requester_name) {
+            Ok(Some(_)) => {}
+            Ok(None) | Err(ShinkaiDBError::DataNotFound) => {
+                let api_error =
+                    APIError::from_code(ErrorCode::LlmProviderNotFound, &format!("Agent {} not found", agent));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+            Err(e) => {
+                let _ = res
+                    .send(Err(APIError::from_code(ErrorCode::DatabaseError, &e.to_string())))
+                    .await;
+                return Ok(());
+            }
+        }
+
+        let path = vr_path.format_to_string();
+        let result = db
+            .set_folder_agent(&path, &agent)
+            .map(|_| format!("Default agent of {} set to {}", path, agent))
+            .map_err(|e| APIError::from_code(ErrorCode::DatabaseError, &e.to_string()));
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_list_folder_agents(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<Vec<FolderAgentBinding>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = db
+            .get_all_folder_agents()
+            .map_err(|e| APIError::from_code(ErrorCode::DatabaseError, &e.to_string()));
+        let _ = res.send(result).await;
+        Ok(())
     }
 }
@@ -0,0 +1,258 @@
+use std::{env, sync::Arc, time::Duration};
+
+use async_channel::Sender;
+use ed25519_dalek::SigningKey;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use shinkai_message_primitives::{
+    schemas::shinkai_name::ShinkaiName,
+    shinkai_message::shinkai_message_schemas::{JobCreationInfo, JobMessage},
+    shinkai_utils::job_scope::JobScope,
+};
+use tokio::sync::Mutex;
+use x25519_dalek::{PublicKey as EncryptionPublicKey, StaticSecret as EncryptionStaticKey};
+
+use crate::{
+    db::ShinkaiDB,
+    llm_provider::job_manager::JobManager,
+    managers::IdentityManager,
+    network::{error_code::ErrorCode, node_api_router::APIError, node_error::NodeError, Node},
+};
+
+/// Body of `POST /v1/chat/completions`, only the fields the node uses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIChatCompletionRequest {
+    /// Id of the agent (LLM provider) that answers
+    pub model: String,
+    pub messages: Vec<OpenAIChatMessage>,
+    #[serde(default)]
+    pub stream: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIChatMessage {
+    pub role: String,
+    /// Either a string or a list of content parts
+    #[serde(default)]
+    pub content: Value,
+}
+
+impl OpenAIChatMessage {
+    /// Text of the message. Non text parts (images, audio) are skipped.
+    pub fn text(&self) -> String {
+        match &self.content {
+            Value::String(text) => text.clone(),
+            Value::Array(parts) => parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(|text| text.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+}
+
+impl OpenAIChatCompletionRequest {
+    /// Content of the job message. OpenAI clients send the whole conversation on every request, while
+    /// each request gets a new job, so earlier turns are passed along as a transcript.
+    pub fn job_message_content(&self) -> String {
+        if let [message] = self.messages.as_slice() {
+            return message.text();
+        }
+        self.messages
+            .iter()
+            .map(|message| {
+                let role = match message.role.as_str() {
+                    "system" | "developer" => "System",
+                    "assistant" => "Assistant",
+                    "tool" => "Tool",
+                    _ => "User",
+                };
+                format!("{}: {}", role, message.text())
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Answer of the agent to a chat completion request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIChatCompletion {
+    pub job_id: String,
+    pub model: String,
+    pub content: String,
+}
+
+impl Node {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn v2_api_openai_chat_completion(
+        db: Arc<ShinkaiDB>,
+        node_name: ShinkaiName,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        job_manager: Arc<Mutex<JobManager>>,
+        bearer: String,
+        request: OpenAIChatCompletionRequest,
+        node_encryption_sk: EncryptionStaticKey,
+        node_encryption_pk: EncryptionPublicKey,
+        node_signing_sk: SigningKey,
+        res: Sender<Result<OpenAIChatCompletion, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        if request.messages.is_empty() {
+            let api_error = APIError {
+                code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
+                error: "Bad Request".to_string(),
+                message: "messages must not be empty".to_string(),
+            };
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        // Every request gets its own hidden job, so it doesn't show up next to the user's chats
+        let (job_sender, job_receiver) = async_channel::bounded(1);
+        let _ = Self::v2_create_new_job(
+            db.clone(),
+            node_name.clone(),
+            identity_manager.clone(),
+            job_manager.clone(),
+            bearer.clone(),
+            JobCreationInfo {
+                scope: JobScope::new_default(),
+                is_hidden: Some(true),
+                retrieval: None,
+            },
+            request.model.clone(),
+            node_encryption_sk.clone(),
+            node_encryption_pk,
+            node_signing_sk.clone(),
+            job_sender,
+        )
+        .await;
+        let job_id = match job_receiver.recv().await {
+            Ok(Ok(job_id)) => job_id,
+            Ok(Err(api_error)) => {
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to create job: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let job_message = JobMessage {
+            job_id: job_id.clone(),
+            content: request.job_message_content(),
+            files_inbox: "".to_string(),
+            parent: None,
+            workflow_code: None,
+            workflow_name: None,
+            sheet_job_data: None,
+            callback: None,
+            retrieval: None,
+        };
+        let (message_sender, message_receiver) = async_channel::bounded(1);
+        let _ = Self::v2_job_message(
+            db.clone(),
+            node_name,
+            identity_manager,
+            job_manager,
+            bearer.clone(),
+            job_message,
+            node_encryption_sk,
+            node_encryption_pk,
+            node_signing_sk,
+            message_sender,
+        )
+        .await;
+        let sent = match message_receiver.recv().await {
+            Ok(Ok(sent)) => sent,
+            Ok(Err(api_error)) => {
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to send job message: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let reply_timeout_secs = env::var("OPENAI_API_REPLY_TIMEOUT_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(300);
+        let waiting = Self::v2_wait_for_job_reply(db, bearer, sent.inbox, sent.message_id);
+        match tokio::time::timeout(Duration::from_secs(reply_timeout_secs), waiting).await {
+            Ok(content) => {
+                let completion = OpenAIChatCompletion {
+                    job_id,
+                    model: request.model,
+                    content,
+                };
+                let _ = res.send(Ok(completion)).await;
+            }
+            Err(_) => {
+                let api_error = APIError {
+                    code: StatusCode::GATEWAY_TIMEOUT.as_u16(),
+                    error_code: ErrorCode::Timeout,
+                    error: "Gateway Timeout".to_string(),
+                    message: format!("The agent didn't answer job {} in time", job_id),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_job_message_content() {
+        let request: OpenAIChatCompletionRequest = serde_json::from_value(json!({
+            "model": "my_agent",
+            "messages": [{ "role": "user", "content": "Hello" }]
+        }))
+        .unwrap();
+        assert_eq!(request.job_message_content(), "Hello");
+        assert!(!request.stream);
+
+        let request: OpenAIChatCompletionRequest = serde_json::from_value(json!({
+            "model": "my_agent",
+            "messages": [
+                { "role": "system", "content": "Be brief" },
+                { "role": "user", "content": [{ "type": "text", "text": "Hi" }, { "type": "image_url", "image_url": {} }] },
+                { "role": "assistant", "content": "Hello" },
+                { "role": "user", "content": "Bye" }
+            ],
+            "stream": true
+        }))
+        .unwrap();
+        assert_eq!(
+            request.job_message_content(),
+            "System: Be brief\n\nUser: Hi\n\nAssistant: Hello\n\nUser: Bye"
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use std::sync::Arc;
+
+use async_channel::Sender;
+use ed25519_dalek::SigningKey;
+use shinkai_message_primitives::{
+    schemas::{
+        payment_invoice::{Invoice, InvoiceStatus},
+        shinkai_name::ShinkaiName,
+    },
+    shinkai_message::shinkai_message_schemas::{
+        APICompleteWalletTransaction, APICreateInvoice, APIMarkInvoicePaid, APIPayInvoice, MessageSchemaType,
+    },
+    shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption},
+};
+use tokio::sync::Mutex;
+use x25519_dalek::StaticSecret as EncryptionStaticKey;
+
+use crate::{
+    db::ShinkaiDB,
+    managers::IdentityManager,
+    network::{
+        error_code::ErrorCode, node::ProxyConnectionInfo, node_api_router::APIError, node_error::NodeError,
+        ws_manager::WSUpdateHandler, Node,
+    },
+    payments::{
+        invoices::{InvoiceError, InvoiceManager},
+        wallet::{WalletError, WalletManager},
+    },
+    schemas::wallet::{WalletConfig, WalletInfo, WalletTransaction, WalletTransactionStatus},
+};
+
+fn invoice_api_error(error: InvoiceError) -> APIError {
+    let code = match error {
+        InvoiceError::InvalidInput(_) | InvoiceError::InvalidReceipt(_) => ErrorCode::InvalidInput,
+        InvoiceError::NotFound(_) => ErrorCode::NotFound,
+        InvoiceError::Closed(_) => ErrorCode::Conflict,
+        InvoiceError::DatabaseError(_) => ErrorCode::DatabaseError,
+        InvoiceError::NetworkError(_) => ErrorCode::InternalError,
+    };
+    APIError::from_code(code, &error.to_string())
+}
+
+fn wallet_api_error(error: WalletError) -> APIError {
+    let code = match error {
+        WalletError::NotConfigured | WalletError::InvalidInput(_) | WalletError::TransactionError(_) => {
+            ErrorCode::InvalidInput
+        }
+        WalletError::NotFound(_) => ErrorCode::NotFound,
+        WalletError::NotAllowed(_) => ErrorCode::Conflict,
+        WalletError::DatabaseError(_) => ErrorCode::DatabaseError,
+    };
+    APIError::from_code(code, &error.to_string())
+}
+
+impl Node {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn v2_api_create_invoice(
+        db: Arc<ShinkaiDB>,
+        node_name: ShinkaiName,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        encryption_secret_key: EncryptionStaticKey,
+        identity_secret_key: SigningKey,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        bearer: String,
+        payload: APICreateInvoice,
+        res: Sender<Result<Invoice, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let invoice = match InvoiceManager::create_invoice(&db, &node_name, payload) {
+            Ok(invoice) => invoice,
+            Err(e) => {
+                let _ = res.send(Err(invoice_api_error(e))).await;
+                return Ok(());
+            }
+        };
+
+        // The invoice is kept even if the payer can't be reached right now
+        if let Err(e) = InvoiceManager::send_to_counterparty(
+            &invoice,
+            MessageSchemaType::PaymentInvoice,
+            &invoice.payer,
+            &node_name,
+            &encryption_secret_key,
+            &identity_secret_key,
+            db.clone(),
+            identity_manager,
+            proxy_connection_info,
+            ws_manager,
+        )
+        .await
+        {
+            shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Error,
+                &format!(
+                    "Failed to send invoice {} to {}: {}",
+                    invoice.invoice_id, invoice.payer, e
+                ),
+            );
+        }
+
+        let _ = res.send(Ok(invoice)).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_list_invoices(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        status: Option<InvoiceStatus>,
+        res: Sender<Result<Vec<Invoice>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = InvoiceManager::list_invoices(&db, status).map_err(invoice_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn v2_api_mark_invoice_paid(
+        db: Arc<ShinkaiDB>,
+        node_name: ShinkaiName,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        encryption_secret_key: EncryptionStaticKey,
+        identity_secret_key: SigningKey,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        bearer: String,
+        payload: APIMarkInvoicePaid,
+        res: Sender<Result<Invoice, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let invoice = match InvoiceManager::mark_paid(
+            &db,
+            &node_name,
+            &payload.invoice_id,
+            payload.transaction_id,
+            &identity_secret_key,
+        ) {
+            Ok(invoice) => invoice,
+            Err(e) => {
+                let _ = res.send(Err(invoice_api_error(e))).await;
+                return Ok(());
+            }
+        };
+
+        if let Some(receipt) = &invoice.receipt {
+            if let Err(e) = InvoiceManager::send_to_counterparty(
+                receipt,
+                MessageSchemaType::PaymentReceipt,
+                &invoice.payer,
+                &node_name,
+                &encryption_secret_key,
+                &identity_secret_key,
+                db.clone(),
+                identity_manager,
+                proxy_connection_info,
+                ws_manager,
+            )
+            .await
+            {
+                shinkai_log(
+                    ShinkaiLogOption::Node,
+                    ShinkaiLogLevel::Error,
+                    &format!(
+                        "Failed to send the receipt of invoice {} to {}: {}",
+                        invoice.invoice_id, invoice.payer, e
+                    ),
+                );
+            }
+        }
+
+        let _ = res.send(Ok(invoice)).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_set_wallet(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: WalletConfig,
+        res: Sender<Result<WalletInfo, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = WalletManager::set_wallet(&db, payload).map_err(wallet_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_get_wallet(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<WalletInfo, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = WalletManager::get_wallet(&db).map_err(wallet_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_pay_invoice(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: APIPayInvoice,
+        res: Sender<Result<WalletTransaction, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = WalletManager::pay_invoice(&db, &payload.invoice_id)
+            .await
+            .map_err(wallet_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_list_wallet_transactions(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        status: Option<WalletTransactionStatus>,
+        res: Sender<Result<Vec<WalletTransaction>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = WalletManager::list_transactions(&db, status).map_err(wallet_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_complete_wallet_transaction(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: APICompleteWalletTransaction,
+        res: Sender<Result<WalletTransaction, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = WalletManager::complete_transaction(&db, &payload.transaction_id, payload.tx_hash, payload.error)
+            .map_err(wallet_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+}
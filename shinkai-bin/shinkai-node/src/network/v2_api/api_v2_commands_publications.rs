@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use async_channel::Sender;
+use shinkai_message_primitives::{
+    schemas::folder_publication::FolderPublication,
+    shinkai_message::shinkai_message_schemas::{APIPublishFolder, APIUnpublishFolder},
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    db::ShinkaiDB,
+    managers::IdentityManager,
+    network::{
+        error_code::ErrorCode,
+        node_api_router::APIError,
+        node_error::NodeError,
+        publishing::publication_manager::{PublicationError, PublicationManager, PublishedContent},
+        Node,
+    },
+    schemas::identity::Identity,
+    vector_fs::vector_fs::VectorFS,
+};
+
+fn publication_api_error(error: PublicationError) -> APIError {
+    let code = match error {
+        PublicationError::InvalidInput(_) => ErrorCode::InvalidInput,
+        PublicationError::NotFound(_) => ErrorCode::NotFound,
+        PublicationError::Unauthorized(_) => ErrorCode::Unauthorized,
+        PublicationError::DatabaseError(_) => ErrorCode::DatabaseError,
+        PublicationError::VectorFSError(_) => ErrorCode::VecfsError,
+    };
+    APIError::from_code(code, &error.to_string())
+}
+
+impl Node {
+    pub async fn v2_api_publish_folder(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        payload: APIPublishFolder,
+        res: Sender<Result<FolderPublication, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // The folder is read as the profile that shares it
+        let profile = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::IdentityNotFound,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let result = PublicationManager::publish_folder(&db, &profile, payload).map_err(publication_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_unpublish_folder(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: APIUnpublishFolder,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = PublicationManager::unpublish_folder(&db, &payload.publication_id)
+            .map(|_| "Folder unpublished successfully".to_string())
+            .map_err(publication_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_list_publications(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<Vec<FolderPublication>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = db
+            .get_all_folder_publications()
+            .map_err(|e| publication_api_error(PublicationError::DatabaseError(e)));
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    /// Reads a published folder for the publishing server. Access is checked against the publication, not an API key.
+    pub async fn get_published_content(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        publication_id: String,
+        token: Option<String>,
+        path: Vec<String>,
+        download: bool,
+        res: Sender<Result<PublishedContent, APIError>>,
+    ) -> Result<(), NodeError> {
+        let result =
+            PublicationManager::get_content(&db, &vector_fs, &publication_id, token.as_deref(), &path, download)
+                .await
+                .map_err(publication_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+}
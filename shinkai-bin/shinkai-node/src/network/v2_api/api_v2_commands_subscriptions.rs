@@ -2,11 +2,13 @@ use std::{collections::HashMap, sync::Arc};
 
 use async_channel::Sender;
 use reqwest::StatusCode;
-use serde_json::Value;
+use serde_json::{json, Value};
 use shinkai_message_primitives::{
     schemas::{shinkai_name::ShinkaiName, shinkai_subscription::ShinkaiSubscription},
     shinkai_message::shinkai_message_schemas::{
-        APIAvailableSharedItems, APICreateShareableFolder, APIGetLastNotifications, APIGetMySubscribers, APIGetNotificationsBeforeTimestamp, APISubscribeToSharedFolder, APIUnshareFolder, APIUnsubscribeToSharedFolder, APIUpdateShareableFolder
+        APIAvailableSharedItems, APICreateShareableFolder, APIGetLastNotifications, APIGetMySubscribers,
+        APIGetNotificationsBeforeTimestamp, APIPushSharedFolderChanges, APISetSharedFolderWriters,
+        APISubscribeToSharedFolder, APIUnshareFolder, APIUnsubscribeToSharedFolder, APIUpdateShareableFolder,
     },
 };
 
@@ -16,6 +18,7 @@ use crate::{
     db::ShinkaiDB,
     managers::IdentityManager,
     network::{
+        error_code::ErrorCode,
         node_api_router::APIError,
         node_error::NodeError,
         subscription_manager::{
@@ -25,7 +28,7 @@ use crate::{
         },
         Node,
     },
-    schemas::identity::Identity,
+    schemas::{identity::Identity, notification::NotificationPreferences},
 };
 
 impl Node {
@@ -49,6 +52,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -65,6 +69,7 @@ impl Node {
             if streamer_full_name.is_err() {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Invalid origin node name or profile name provided".to_string(),
                 };
@@ -93,6 +98,7 @@ impl Node {
                     Err(e) => {
                         let api_error = APIError {
                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::InternalError,
                             error: "Internal Server Error".to_string(),
                             message: format!("Failed to serialize response: {}", e),
                         };
@@ -102,6 +108,7 @@ impl Node {
                 Err(e) => {
                     let api_error = APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: format!("Failed to convert path to VRPath: {}", e),
                     };
@@ -125,6 +132,7 @@ impl Node {
                             Err(e) => {
                                 let api_error = APIError {
                                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                                    error_code: ErrorCode::InternalError,
                                     error: "Internal Server Error".to_string(),
                                     message: format!("Failed to serialize response: {}", e),
                                 };
@@ -134,6 +142,7 @@ impl Node {
                         Err(e) => {
                             let api_error = APIError {
                                 code: StatusCode::BAD_REQUEST.as_u16(),
+                                error_code: ErrorCode::InvalidInput,
                                 error: "Bad Request".to_string(),
                                 message: format!("Failed to convert path to VRPath: {}", e),
                             };
@@ -144,6 +153,7 @@ impl Node {
                 Err(_) => {
                     let api_error = APIError {
                         code: StatusCode::BAD_REQUEST.as_u16(),
+                        error_code: ErrorCode::InvalidInput,
                         error: "Bad Request".to_string(),
                         message: "Invalid node name provided".to_string(),
                     };
@@ -182,6 +192,7 @@ impl Node {
                     // Handle serialization error
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::InternalError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to serialize response: {}", e),
                     };
@@ -191,6 +202,7 @@ impl Node {
         } else {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::BadRequest,
                 error: "Bad Request".to_string(),
                 message: "Streamer name doesn't match".to_string(),
             };
@@ -219,6 +231,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -230,6 +243,7 @@ impl Node {
         if !requester_name.has_profile() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::BadRequest,
                 error: "Bad Request".to_string(),
                 message: "Requester name does not have a profile".to_string(),
             };
@@ -257,6 +271,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to create shareable folder: {}", e),
                 };
@@ -284,6 +299,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -307,6 +323,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to update shareable folder requirements: {}", e),
                 };
@@ -335,6 +352,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -356,6 +374,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to unshare folder: {}", e),
                 };
@@ -383,6 +402,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -401,6 +421,7 @@ impl Node {
             Err(_) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Invalid node name provided".to_string(),
                 };
@@ -429,6 +450,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to subscribe to shared folder: {}", e),
                 };
@@ -457,6 +479,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -469,6 +492,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -499,6 +523,7 @@ impl Node {
                     Err(e) => {
                         let api_error = APIError {
                             code: StatusCode::BAD_REQUEST.as_u16(),
+                            error_code: ErrorCode::InvalidInput,
                             error: "Bad Request".to_string(),
                             message: format!("Failed to convert path to VRPath: {}", e),
                         };
@@ -509,6 +534,7 @@ impl Node {
             Err(_) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Invalid node name provided".to_string(),
                 };
@@ -519,6 +545,132 @@ impl Node {
         Ok(())
     }
 
+    pub async fn v2_api_set_shared_folder_writers(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        ext_subscription_manager: Arc<Mutex<ExternalSubscriberManager>>,
+        bearer: String,
+        payload: APISetSharedFolderWriters,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let subscription_manager = ext_subscription_manager.lock().await;
+        let result = subscription_manager
+            .set_shared_folder_writers(payload.path, requester_name, payload.writers)
+            .await;
+
+        match result {
+            Ok(_) => {
+                let _ = res
+                    .send(Ok("Shared folder writers updated successfully".to_string()))
+                    .await
+                    .map_err(|_| ());
+            }
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("Failed to set the shared folder writers: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn v2_api_push_shared_folder_changes(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        my_subscription_manager: Arc<Mutex<MySubscriptionsManager>>,
+        bearer: String,
+        payload: APIPushSharedFolderChanges,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+        let requester_profile = requester_name.get_profile_name_string().unwrap_or("".to_string());
+
+        let streamer_full_name = match ShinkaiName::from_node_and_profile_names(
+            payload.streamer_node_name.clone(),
+            payload.streamer_profile_name.clone(),
+        ) {
+            Ok(shinkai_name) => shinkai_name,
+            Err(_) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: "Invalid node name provided".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let subscription_manager = my_subscription_manager.lock().await;
+        let result = subscription_manager
+            .push_to_shared_folder(
+                streamer_full_name.extract_node(),
+                payload.streamer_profile_name,
+                requester_profile,
+                payload.shared_folder,
+                payload.paths,
+            )
+            .await;
+
+        match result {
+            // The result of the push arrives later as a notification
+            Ok(push_id) => {
+                let _ = res.send(Ok(push_id)).await.map_err(|_| ());
+            }
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("Failed to push changes to the shared folder: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
     pub async fn v2_api_my_subscriptions(
         db: Arc<ShinkaiDB>,
         node_name: ShinkaiName,
@@ -536,6 +688,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -548,6 +701,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -567,6 +721,7 @@ impl Node {
                         // Handle serialization error
                         let api_error = APIError {
                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::InternalError,
                             error: "Internal Server Error".to_string(),
                             message: format!("Failed to serialize response: {}", e),
                         };
@@ -577,6 +732,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to retrieve subscriptions: {}", e),
                 };
@@ -609,6 +765,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to retrieve subscribers: {}", e),
                 };
@@ -636,6 +793,7 @@ impl Node {
         if parts.len() != 2 {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid subscription_profile_path format. Expected format 'PROFILE:::PATH'.".to_string(),
             };
@@ -651,6 +809,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to retrieve folder requirements: {}", e),
                 };
@@ -676,6 +835,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to serialize response: {}", e),
                 };
@@ -704,6 +864,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -715,6 +876,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -729,6 +891,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to get last notifications: {}", e),
                 };
@@ -757,6 +920,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -768,6 +932,7 @@ impl Node {
         if requester_name.get_node_name_string() != node_name.clone().get_node_name_string() {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: "Invalid node name provided".to_string(),
             };
@@ -782,6 +947,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to get notifications before timestamp: {}", e),
                 };
@@ -791,4 +957,151 @@ impl Node {
 
         Ok(())
     }
+
+    pub async fn v2_api_get_unread_notifications_count(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(requester_name) = Self::notifications_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        match db.get_unread_notifications_count(&requester_name) {
+            Ok(unread_count) => {
+                let _ = res.send(Ok(json!({ "unread_count": unread_count }))).await;
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InternalError,
+                    &format!("Failed to count unread notifications: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_mark_notifications_read(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(requester_name) = Self::notifications_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        match db.mark_notifications_as_read(&requester_name) {
+            Ok(_) => {
+                let _ = res.send(Ok(json!({ "unread_count": 0 }))).await;
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InternalError,
+                    &format!("Failed to mark notifications as read: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_get_notification_preferences(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(requester_name) = Self::notifications_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let profile_name = requester_name.get_profile_name_string().unwrap_or_default();
+        match db.get_notification_preferences(&profile_name) {
+            Ok(preferences) => {
+                let _ = res.send(Ok(json!(preferences))).await;
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InternalError,
+                    &format!("Failed to get notification preferences: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_api_set_notification_preferences(
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        bearer: String,
+        payload: NotificationPreferences,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(requester_name) = Self::notifications_profile_name(&identity_manager, &res).await else {
+            return Ok(());
+        };
+
+        let profile_name = requester_name.get_profile_name_string().unwrap_or_default();
+        match db.set_notification_preferences(&profile_name, &payload) {
+            Ok(_) => {
+                let _ = res.send(Ok(json!(payload))).await;
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InternalError,
+                    &format!("Failed to set notification preferences: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Profile the notifications of the API are for, the main one
+    async fn notifications_profile_name(
+        identity_manager: &Arc<Mutex<IdentityManager>>,
+        res: &Sender<Result<Value, APIError>>,
+    ) -> Option<ShinkaiName> {
+        match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => Some(std_identity.full_identity_name.clone()),
+            _ => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                None
+            }
+        }
+    }
 }
@@ -1,29 +1,48 @@
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use async_channel::Sender;
 use chrono::{DateTime, Utc};
 use reqwest::StatusCode;
-use serde_json::Value;
+use serde_json::{json, Value};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
 use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
-    APIConvertFilesAndSaveToFolder, APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsDeleteFolder,
-    APIVecFsDeleteItem, APIVecFsMoveFolder, APIVecFsMoveItem, APIVecFsRetrievePathSimplifiedJson, APIVecFsSearchItems,
+    APICancelOperation, APIConvertFilesAndSaveToFolder, APIGetOperationStatus, APIVecFSDiffItemVersion, APIVecFSExportFolderAsVRPack, APIVecFSExportMarkdownBundle, APIVecFSGetFolderStats, APIVecFSGetItemVersions, APIVecFSImportMarkdownBundle, APIVecFSRestoreItemVersion, APIVecFSSetFolderEmbeddingModel, APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsCreateLink, APIVecFsDeleteFolder,
+    APIVecFsDeleteItem, APIVecFsMoveFolder, APIVecFsMoveItem, APIVecFsRetrievePathSimplifiedJson, APIVecFsRetrieveSourceFileMap, APIVecFsRetrieveVectorSearchSimplifiedJson,
+    APIVecFsSearchItems,
 };
 use shinkai_vector_resources::{
-    embedding_generator::EmbeddingGenerator, file_parser::unstructured_api::UnstructuredAPI, vector_resource::VRPath,
+    embedding_generator::EmbeddingGenerator,
+    file_parser::{file_parser::FileParser, unstructured_api::UnstructuredAPI},
+    model_type::EmbeddingModelType,
+    source::{DistributionInfo, SourceFileMap},
+    vector_resource::VRPath,
 };
 use tokio::sync::Mutex;
 
 use crate::{
     db::ShinkaiDB,
-    managers::IdentityManager,
+    llm_provider::parsing_helper::{IngestionSettings, ParsingHelper},
+    managers::{
+        operation_registry::{OperationKind, OperationStatus, OPERATIONS},
+        IdentityManager,
+    },
     network::{
+        error_code::ErrorCode,
         node_api_router::APIError,
         node_error::NodeError,
         subscription_manager::external_subscriber_manager::{ExternalSubscriberManager, SharedFolderInfo},
         Node,
     },
     schemas::identity::Identity,
-    vector_fs::vector_fs::VectorFS,
+    utils::environment::image_embedding_generator,
+    vector_fs::{
+        vector_fs::VectorFS,
+        vector_fs_error::VectorFSError,
+        vector_fs_markdown_bundle::MarkdownBundleDocument,
+        vector_fs_stats::FolderStats,
+        vector_fs_types::{FSItemMetadataChange, FSItemVersion},
+    },
 };
 
 impl Node {
@@ -46,6 +65,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -59,6 +79,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert path to VRPath: {}", e),
                 };
@@ -75,6 +96,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create reader: {}", e),
                 };
@@ -134,6 +156,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to retrieve fs path json: {}", e),
                 };
@@ -164,6 +187,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -203,6 +227,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -216,6 +241,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert path to VRPath: {}", e),
                 };
@@ -232,6 +258,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer: {}", e),
                 };
@@ -249,6 +276,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create new folder: {}", e),
                 };
@@ -275,6 +303,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -288,6 +317,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert origin path to VRPath: {}", e),
                 };
@@ -301,6 +331,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert destination path to VRPath: {}", e),
                 };
@@ -317,6 +348,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer: {}", e),
                 };
@@ -334,6 +366,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to move item: {}", e),
                 };
@@ -360,6 +393,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -373,6 +407,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert origin path to VRPath: {}", e),
                 };
@@ -386,6 +421,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert destination path to VRPath: {}", e),
                 };
@@ -402,6 +438,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer: {}", e),
                 };
@@ -419,6 +456,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to copy item: {}", e),
                 };
@@ -445,6 +483,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -458,6 +497,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert origin path to VRPath: {}", e),
                 };
@@ -471,6 +511,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert destination path to VRPath: {}", e),
                 };
@@ -487,6 +528,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer: {}", e),
                 };
@@ -504,6 +546,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to move folder: {}", e),
                 };
@@ -530,6 +573,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -543,6 +587,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert origin path to VRPath: {}", e),
                 };
@@ -556,6 +601,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert destination path to VRPath: {}", e),
                 };
@@ -572,6 +618,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer: {}", e),
                 };
@@ -580,7 +627,18 @@ impl Node {
             }
         };
 
-        match vector_fs.copy_folder(&writer, destination_path).await {
+        let operation = OPERATIONS.start(
+            OperationKind::CopyFolder,
+            format!(
+                "Copying {} to {}",
+                input_payload.origin_path, input_payload.destination_path
+            ),
+        );
+        let copy_result = vector_fs
+            .copy_folder_with_progress(&writer, destination_path, &operation)
+            .await;
+        operation.finish(&copy_result);
+        match copy_result {
             Ok(_) => {
                 let success_message = format!("Folder copied successfully to {}", input_payload.destination_path);
                 let _ = res.send(Ok(success_message)).await.map_err(|_| ());
@@ -589,6 +647,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to copy folder: {}", e),
                 };
@@ -615,6 +674,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -628,6 +688,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert folder path to VRPath: {}", e),
                 };
@@ -644,6 +705,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer: {}", e),
                 };
@@ -661,6 +723,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to delete folder: {}", e),
                 };
@@ -687,6 +750,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -700,6 +764,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert item path to VRPath: {}", e),
                 };
@@ -716,6 +781,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create writer: {}", e),
                 };
@@ -733,6 +799,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to delete item: {}", e),
                 };
@@ -759,6 +826,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -773,6 +841,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert search path to VRPath: {}", e),
                 };
@@ -789,6 +858,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create reader: {}", e),
                 };
@@ -800,12 +870,15 @@ impl Node {
         let max_resources_to_search = input_payload.max_files_to_scan.unwrap_or(100) as u64;
         let max_results = input_payload.max_results.unwrap_or(100) as u64;
 
-        let query_embedding = vector_fs
-            .generate_query_embedding_using_reader(input_payload.search, &reader)
-            .await
-            .unwrap();
+        // Images are also found by their image embeddings, if an image embedding model is set
+        let image_generator = image_embedding_generator();
         let search_results = vector_fs
-            .vector_search_fs_item(&reader, query_embedding, max_resources_to_search)
+            .search_fs_items_with_images(
+                &reader,
+                input_payload.search,
+                image_generator.as_ref(),
+                max_resources_to_search,
+            )
             .await
             .unwrap();
 
@@ -819,6 +892,103 @@ impl Node {
         Ok(())
     }
 
+    /// Deep vector search: the closest chunks of the files under the path, as (content, path ids, score)
+    pub async fn v2_vector_search(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        input_payload: APIVecFsRetrieveVectorSearchSimplifiedJson,
+        bearer: String,
+        res: Sender<Result<Vec<(String, Vec<String>, f32)>, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: "Wrong identity type. Expected Standard identity.".to_string(),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let search_path_str = input_payload.path.as_deref().unwrap_or("/");
+        let search_path = match VRPath::from_string(search_path_str) {
+            Ok(path) => path,
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("Failed to convert search path to VRPath: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let reader = match vector_fs
+            .new_reader(requester_name.clone(), search_path, requester_name.clone())
+            .await
+        {
+            Ok(reader) => reader,
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to create reader: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let max_resources_to_search = input_payload.max_files_to_scan.unwrap_or(100) as u64;
+        let max_results = input_payload.max_results.unwrap_or(100) as u64;
+        let search_results = match vector_fs
+            .deep_vector_search(&reader, input_payload.search, max_resources_to_search, max_results)
+            .await
+        {
+            Ok(results) => results,
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to perform deep vector search: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let results: Vec<(String, Vec<String>, f32)> = search_results
+            .into_iter()
+            .map(|res| {
+                let content = res
+                    .resource_retrieved_node
+                    .node
+                    .get_text_content()
+                    .map(|text| text.to_string())
+                    .unwrap_or_default();
+                let path_ids = res.clone().fs_item_path().path_ids;
+                let score = res.resource_retrieved_node.score;
+                (content, path_ids, score)
+            })
+            .collect();
+
+        let _ = res.send(Ok(results)).await.map_err(|_| ());
+        Ok(())
+    }
+
     pub async fn v2_retrieve_vector_resource(
         db: Arc<ShinkaiDB>,
         vector_fs: Arc<VectorFS>,
@@ -837,6 +1007,7 @@ impl Node {
             _ => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: "Wrong identity type. Expected Standard identity.".to_string(),
                 };
@@ -850,6 +1021,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert path to VRPath: {}", e),
                 };
@@ -866,6 +1038,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to create reader: {}", e),
                 };
@@ -885,6 +1058,7 @@ impl Node {
                 Err(e) => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::VecfsError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to convert result to JSON: {}", e),
                     };
@@ -895,6 +1069,7 @@ impl Node {
             Err(e) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: e.error_code(),
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to retrieve vector resource: {}", e),
                 };
@@ -904,6 +1079,67 @@ impl Node {
         }
     }
 
+    pub async fn v2_retrieve_source_file_map(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        input_payload: APIVecFsRetrieveSourceFileMap,
+        bearer: String,
+        res: Sender<Result<SourceFileMap, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let vr_path = match VRPath::from_string(&input_payload.path) {
+            Ok(path) => path,
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to convert path to VRPath: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let reader = match vector_fs
+            .new_reader(requester_name.clone(), vr_path, requester_name.clone())
+            .await
+        {
+            Ok(reader) => reader,
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to create reader: {}", e));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        match vector_fs.retrieve_source_file_map(&reader).await {
+            Ok(source_file_map) => {
+                let _ = res.send(Ok(source_file_map)).await;
+            }
+            Err(e) => {
+                let api_error =
+                    APIError::from_code(e.error_code(), &format!("Failed to retrieve source file map: {}", e));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
     pub async fn v2_upload_file_to_folder(
         db: Arc<ShinkaiDB>,
         vector_fs: Arc<VectorFS>,
@@ -916,6 +1152,7 @@ impl Node {
         file: Vec<u8>,
         path: String,
         file_datetime: Option<DateTime<Utc>>,
+        allow_duplicates: bool,
         res: Sender<Result<Value, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
@@ -928,6 +1165,7 @@ impl Node {
         if let Err(err) = db.create_files_message_inbox(hash_hex.clone()) {
             let api_error = APIError {
                 code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
                 error: "Bad Request".to_string(),
                 message: format!("Failed to create files message inbox: {}", err),
             };
@@ -960,6 +1198,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::VecfsError,
                             error: "Internal Server Error".to_string(),
                             message: "Failed to receive add file result".to_string(),
                         }))
@@ -978,6 +1217,7 @@ impl Node {
             path,
             file_inbox: file_inbox_name,
             file_datetime,
+            allow_duplicates,
         };
 
         let (convert_res_sender, convert_res_receiver) = async_channel::bounded(1);
@@ -1002,6 +1242,7 @@ impl Node {
                         None => {
                             let api_error = APIError {
                                 code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                                error_code: ErrorCode::VecfsError,
                                 error: "Internal Server Error".to_string(),
                                 message: "Result array is empty".to_string(),
                             };
@@ -1018,6 +1259,7 @@ impl Node {
                     let _ = res
                         .send(Err(APIError {
                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::VecfsError,
                             error: "Internal Server Error".to_string(),
                             message: "Failed to receive conversion result".to_string(),
                         }))
@@ -1027,6 +1269,7 @@ impl Node {
             Err(node_error) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::VecfsError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to convert files and save to folder: {}", node_error),
                 };
@@ -1036,4 +1279,747 @@ impl Node {
 
         Ok(())
     }
+
+    pub async fn v2_get_folder_stats(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        input_payload: APIVecFSGetFolderStats,
+        bearer: String,
+        res: Sender<Result<FolderStats, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let vr_path = match VRPath::from_string(&input_payload.path) {
+            Ok(path) => path,
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to convert path to VRPath: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let reader = match vector_fs
+            .new_reader(requester_name.clone(), vr_path, requester_name.clone())
+            .await
+        {
+            Ok(reader) => reader,
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to create reader: {}", e));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        match vector_fs.get_folder_stats(&reader).await {
+            Ok(stats) => {
+                let _ = res.send(Ok(stats)).await;
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to get folder stats: {}", e));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Packs the folder, with the source files and the metadata of its items, into a VRPack. Sends back the file
+    /// name to download it as and the encoded VRPack.
+    pub async fn v2_export_folder_as_vrpack(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        input_payload: APIVecFSExportFolderAsVRPack,
+        bearer: String,
+        res: Sender<Result<(String, Vec<u8>), APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let vr_path = match VRPath::from_string(&input_payload.path) {
+            Ok(path) => path,
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to convert path to VRPath: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let reader = match vector_fs
+            .new_reader(requester_name.clone(), vr_path.clone(), requester_name.clone())
+            .await
+        {
+            Ok(reader) => reader,
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to create reader: {}", e));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let mut vrpack = match vector_fs.retrieve_vrpack(&reader).await {
+            Ok(vrpack) => vrpack,
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to pack the folder: {}", e));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+        vrpack.metadata_insert("exported_from_path".to_string(), vr_path.format_to_string());
+        vrpack.metadata_insert("exported_from_node".to_string(), requester_name.get_node_name_string());
+        vrpack.metadata_insert("exported_at".to_string(), Utc::now().to_rfc3339());
+
+        match vrpack.encode_as_bytes() {
+            Ok(bytes) => {
+                let file_name = format!("{}.vrpack", vrpack.name);
+                let _ = res.send(Ok((file_name, bytes))).await;
+            }
+            Err(e) => {
+                let api_error =
+                    APIError::from_code(ErrorCode::InternalError, &format!("Failed to encode the VRPack: {}", e));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the items of the folder as markdown documents with front matter into a directory on the node's machine
+    pub async fn v2_export_markdown_bundle(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        input_payload: APIVecFSExportMarkdownBundle,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let vr_path = match VRPath::from_string(&input_payload.path) {
+            Ok(path) => path,
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to convert path to VRPath: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let reader = match vector_fs
+            .new_reader(requester_name.clone(), vr_path, requester_name.clone())
+            .await
+        {
+            Ok(reader) => reader,
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to create reader: {}", e));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        match vector_fs
+            .export_markdown_bundle(&reader, Path::new(&input_payload.local_path))
+            .await
+        {
+            Ok(count) => {
+                let response = json!({ "local_path": input_payload.local_path, "document_count": count });
+                let _ = res.send(Ok(response)).await;
+            }
+            Err(e) => {
+                let api_error =
+                    APIError::from_code(e.error_code(), &format!("Failed to export the markdown bundle: {}", e));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Imports the markdown documents of a directory on the node's machine into the folder, with a subfolder per
+    /// subdirectory. Documents of items that already exist are saved as their new version.
+    pub async fn v2_import_markdown_bundle(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        embedding_generator: Arc<dyn EmbeddingGenerator>,
+        input_payload: APIVecFSImportMarkdownBundle,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let vr_path = match VRPath::from_string(&input_payload.path) {
+            Ok(path) => path,
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to convert path to VRPath: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let local_path = PathBuf::from(&input_payload.local_path);
+        let read_result = tokio::task::spawn_blocking(move || MarkdownBundleDocument::read_bundle(&local_path)).await;
+        let documents = match read_result {
+            Ok(Ok(documents)) => documents,
+            Ok(Err(e)) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to read the markdown bundle: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InternalError,
+                    &format!("Failed to read the markdown bundle: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let ingestion = ParsingHelper::ingestion_settings(&db, &requester_name);
+        let mut imported = Vec::new();
+        for (folders, document) in documents {
+            let mut folder_path = vr_path.clone();
+            for folder in folders {
+                folder_path.push(folder);
+            }
+            match Self::import_markdown_bundle_document(
+                &vector_fs,
+                &requester_name,
+                &*embedding_generator,
+                &ingestion,
+                folder_path,
+                &document,
+            )
+            .await
+            {
+                Ok(item_path) => imported.push(item_path.format_to_string()),
+                Err(e) => {
+                    let api_error = APIError::from_code(
+                        ErrorCode::VecfsError,
+                        &format!("Failed to import '{}': {}", document.name, e),
+                    );
+                    let _ = res.send(Err(api_error)).await;
+                    return Ok(());
+                }
+            }
+        }
+
+        let _ = res.send(Ok(json!({ "imported_items": imported }))).await;
+        Ok(())
+    }
+
+    async fn import_markdown_bundle_document(
+        vector_fs: &VectorFS,
+        profile: &ShinkaiName,
+        embedding_generator: &dyn EmbeddingGenerator,
+        ingestion: &IngestionSettings,
+        folder_path: VRPath,
+        document: &MarkdownBundleDocument,
+    ) -> Result<VRPath, String> {
+        let root_writer = vector_fs
+            .new_writer(profile.clone(), VRPath::root(), profile.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        vector_fs
+            .create_new_folder_auto(&root_writer, folder_path.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let (embedding_generator, ingestion) = vector_fs
+            .folder_embedding_settings(profile, &folder_path, embedding_generator, ingestion)
+            .await
+            .map_err(|e| e.to_string())?;
+        let file_name = MarkdownBundleDocument::file_name(&document.name);
+        let distribution_info = DistributionInfo::new_auto(&file_name, document.last_written_datetime);
+        let processed = ParsingHelper::process_files_into_vrkai(
+            vec![(file_name, document.body.clone().into_bytes(), distribution_info)],
+            &*embedding_generator,
+            None,
+            FileParser::Local,
+            &ingestion,
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let writer = vector_fs
+            .new_writer(profile.clone(), folder_path.clone(), profile.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut item_path = folder_path;
+        for (_, mut vrkai) in processed {
+            document.apply_to(&mut vrkai.resource);
+            let item = vector_fs
+                .save_vrkai_in_folder(&writer, vrkai)
+                .await
+                .map_err(|e| e.to_string())?;
+            item_path = item.path;
+        }
+        Ok(item_path)
+    }
+
+    pub async fn v2_get_item_versions(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        input_payload: APIVecFSGetItemVersions,
+        bearer: String,
+        res: Sender<Result<Vec<FSItemVersion>, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let vr_path = match VRPath::from_string(&input_payload.path) {
+            Ok(path) => path,
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to convert path to VRPath: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let reader = match vector_fs
+            .new_reader(requester_name.clone(), vr_path, requester_name.clone())
+            .await
+        {
+            Ok(reader) => reader,
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to create reader: {}", e));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        match vector_fs.retrieve_item_versions(&reader).await {
+            Ok(versions) => {
+                let _ = res.send(Ok(versions)).await;
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to get item versions: {}", e));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn v2_diff_item_version(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        input_payload: APIVecFSDiffItemVersion,
+        bearer: String,
+        res: Sender<Result<Vec<FSItemMetadataChange>, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let vr_path = match VRPath::from_string(&input_payload.path) {
+            Ok(path) => path,
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to convert path to VRPath: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let reader = match vector_fs
+            .new_reader(requester_name.clone(), vr_path, requester_name.clone())
+            .await
+        {
+            Ok(reader) => reader,
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to create reader: {}", e));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        match vector_fs
+            .diff_item_version_metadata(&reader, input_payload.version_id)
+            .await
+        {
+            Ok(changes) => {
+                let _ = res.send(Ok(changes)).await;
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to diff item version: {}", e));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn v2_restore_item_version(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        input_payload: APIVecFSRestoreItemVersion,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let vr_path = match VRPath::from_string(&input_payload.path) {
+            Ok(path) => path,
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to convert path to VRPath: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let writer = match vector_fs
+            .new_writer(requester_name.clone(), vr_path, requester_name.clone())
+            .await
+        {
+            Ok(writer) => writer,
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to create writer: {}", e));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let result = vector_fs
+            .restore_item_version(&writer, input_payload.version_id)
+            .await
+            .and_then(|item| item.to_json_minimal_value());
+        match result {
+            Ok(item) => {
+                let _ = res.send(Ok(item)).await;
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to restore item version: {}", e));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn v2_set_folder_embedding_model(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        input_payload: APIVecFSSetFolderEmbeddingModel,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let vr_path = match VRPath::from_string(&input_payload.path) {
+            Ok(path) => path,
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to convert path to VRPath: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let embedding_model = match input_payload
+            .embedding_model
+            .as_deref()
+            .map(EmbeddingModelType::from_string)
+        {
+            Some(Ok(model)) => Some(model),
+            Some(Err(e)) => {
+                let api_error =
+                    APIError::from_code(ErrorCode::InvalidInput, &format!("Unknown embedding model: {}", e));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+            None => None,
+        };
+
+        let writer = match vector_fs
+            .new_writer(requester_name.clone(), vr_path, requester_name.clone())
+            .await
+        {
+            Ok(writer) => writer,
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to create writer: {}", e));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let result = vector_fs
+            .set_folder_embedding_model(&writer, embedding_model)
+            .await
+            .and_then(|folder| serde_json::to_value(folder).map_err(VectorFSError::from));
+        match result {
+            Ok(folder) => {
+                let _ = res.send(Ok(folder)).await;
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    e.error_code(),
+                    &format!("Failed to set the folder embedding model: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn v2_create_link(
+        db: Arc<ShinkaiDB>,
+        vector_fs: Arc<VectorFS>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        input_payload: APIVecFsCreateLink,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let requester_name = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let vr_path = match VRPath::from_string(&input_payload.path) {
+            Ok(path) => path,
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to convert path to VRPath: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let writer = match vector_fs
+            .new_writer(requester_name.clone(), vr_path, requester_name.clone())
+            .await
+        {
+            Ok(writer) => writer,
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to create writer: {}", e));
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let target_path = match VRPath::from_string(&input_payload.target_path) {
+            Ok(path) => path,
+            Err(e) => {
+                let api_error = APIError::from_code(
+                    ErrorCode::InvalidInput,
+                    &format!("Failed to convert target path to VRPath: {}", e),
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let result = vector_fs
+            .create_link(&writer, &input_payload.link_name, target_path)
+            .await
+            .and_then(|link| link.to_json_minimal_value());
+        match result {
+            Ok(link) => {
+                let _ = res.send(Ok(link)).await;
+            }
+            Err(e) => {
+                let api_error = APIError::from_code(e.error_code(), &format!("Failed to create link: {}", e));
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn v2_get_operation_status(
+        db: Arc<ShinkaiDB>,
+        input_payload: APIGetOperationStatus,
+        bearer: String,
+        res: Sender<Result<Vec<OperationStatus>, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let statuses = match input_payload.operation_id {
+            Some(operation_id) => match OPERATIONS.status(&operation_id) {
+                Some(status) => vec![status],
+                None => {
+                    let api_error =
+                        APIError::from_code(ErrorCode::NotFound, &format!("Operation not found: {}", operation_id));
+                    let _ = res.send(Err(api_error)).await;
+                    return Ok(());
+                }
+            },
+            None => OPERATIONS.running(),
+        };
+        let _ = res.send(Ok(statuses)).await;
+        Ok(())
+    }
+
+    pub async fn v2_cancel_operation(
+        db: Arc<ShinkaiDB>,
+        input_payload: APICancelOperation,
+        bearer: String,
+        res: Sender<Result<OperationStatus, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match OPERATIONS.cancel(&input_payload.operation_id) {
+            Some(status) => {
+                let _ = res.send(Ok(status)).await;
+            }
+            None => {
+                let api_error = APIError::from_code(
+                    ErrorCode::NotFound,
+                    &format!("Operation not found: {}", input_payload.operation_id),
+                );
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
 }
@@ -3,9 +3,10 @@ use std::time::Instant;
 
 use async_channel::Sender;
 use reqwest::StatusCode;
+use rust_decimal::Decimal;
 use serde_json::{json, Value};
 use shinkai_dsl::dsl_schemas::Workflow;
-use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::APISetWorkflow;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{APIInstallToolkitFromURL, APISetWorkflow};
 
 use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::APIWorkflowKeyname;
 use tokio::sync::Mutex;
@@ -13,10 +14,24 @@ use tokio::sync::Mutex;
 use crate::lance_db::shinkai_lance_db::LanceShinkaiDb;
 use crate::{
     db::ShinkaiDB,
-    network::{node_api_router::APIError, node_error::NodeError, Node},
-    tools::{shinkai_tool::ShinkaiTool, workflow_tool::WorkflowTool},
+    network::{error_code::ErrorCode, node_api_router::APIError, node_error::NodeError, Node},
+    tools::{
+        js_toolkit_headers::ToolCapability,
+        shinkai_tool::{ShinkaiTool, ShinkaiToolHeader},
+        tool_execution_limits::ToolExecutionLimits,
+        tool_pricing::{ToolPricing, ToolUsageAllowance, ToolUsageMeter},
+        wasm_tools::WasmTool,
+        workflow_tool::WorkflowTool,
+    },
 };
 
+#[derive(Debug, Clone, serde::Deserialize)]
+struct ToolkitCapabilitiesPayload {
+    toolkit_name: String,
+    #[serde(default)]
+    capabilities: Vec<ToolCapability>,
+}
+
 impl Node {
     pub async fn v2_api_search_workflows(
         db: Arc<ShinkaiDB>,
@@ -38,6 +53,7 @@ impl Node {
             Ok(workflows) => {
                 let workflows_json = serde_json::to_value(workflows).map_err(|err| NodeError {
                     message: format!("Failed to serialize workflows: {}", err),
+                    error_code: ErrorCode::InternalError,
                 })?;
                 // Log the elapsed time if LOG_ALL is set to 1
                 if std::env::var("LOG_ALL").unwrap_or_default() == "1" {
@@ -52,6 +68,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to search workflows: {}", err),
                 };
@@ -81,6 +98,7 @@ impl Node {
             Ok(tools) => {
                 let tools_json = serde_json::to_value(tools).map_err(|err| NodeError {
                     message: format!("Failed to serialize tools: {}", err),
+                    error_code: ErrorCode::InternalError,
                 })?;
                 // Log the elapsed time if LOG_ALL is set to 1
                 if std::env::var("LOG_ALL").unwrap_or_default() == "1" {
@@ -95,6 +113,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to search tools: {}", err),
                 };
@@ -122,6 +141,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to create workflow: {}", err),
                 };
@@ -146,6 +166,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::DatabaseError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to add workflow to LanceShinkaiDb: {}", err),
                 };
@@ -180,6 +201,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::DatabaseError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to remove workflow from LanceShinkaiDb: {}", err),
                 };
@@ -214,6 +236,7 @@ impl Node {
             Ok(None) => {
                 let api_error = APIError {
                     code: StatusCode::NOT_FOUND.as_u16(),
+                    error_code: ErrorCode::WorkflowNotFound,
                     error: "Not Found".to_string(),
                     message: "Workflow not found".to_string(),
                 };
@@ -223,6 +246,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to get workflow: {}", err),
                 };
@@ -253,6 +277,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to list workflows: {}", err),
                 };
@@ -283,6 +308,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to list tools: {}", err),
                 };
@@ -313,6 +339,7 @@ impl Node {
                 Ok(None) => {
                     let api_error = APIError {
                         code: StatusCode::NOT_FOUND.as_u16(),
+                        error_code: ErrorCode::ToolNotFound,
                         error: "Not Found".to_string(),
                         message: "Tool not found in LanceShinkaiDb".to_string(),
                     };
@@ -322,6 +349,7 @@ impl Node {
                 Err(err) => {
                     let api_error = APIError {
                         code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error_code: ErrorCode::DatabaseError,
                         error: "Internal Server Error".to_string(),
                         message: format!("Failed to fetch tool from LanceShinkaiDb: {}", err),
                     };
@@ -337,6 +365,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to convert existing tool to Value: {}", err),
                 };
@@ -354,6 +383,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
                     error: "Bad Request".to_string(),
                     message: format!("Failed to convert merged Value to ShinkaiTool: {}", err),
                 };
@@ -384,6 +414,7 @@ impl Node {
                     Ok(None) => {
                         let api_error = APIError {
                             code: StatusCode::NOT_FOUND.as_u16(),
+                            error_code: ErrorCode::ToolNotFound,
                             error: "Not Found".to_string(),
                             message: "Tool not found in LanceShinkaiDb".to_string(),
                         };
@@ -393,6 +424,7 @@ impl Node {
                     Err(err) => {
                         let api_error = APIError {
                             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error_code: ErrorCode::DatabaseError,
                             error: "Internal Server Error".to_string(),
                             message: format!("Failed to fetch tool from LanceShinkaiDb: {}", err),
                         };
@@ -404,6 +436,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::DatabaseError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to add tool to LanceShinkaiDb: {}", err),
                 };
@@ -435,6 +468,7 @@ impl Node {
             Ok(None) => {
                 let api_error = APIError {
                     code: StatusCode::NOT_FOUND.as_u16(),
+                    error_code: ErrorCode::ToolNotFound,
                     error: "Not Found".to_string(),
                     message: "Tool not found".to_string(),
                 };
@@ -444,6 +478,7 @@ impl Node {
             Err(err) => {
                 let api_error = APIError {
                     code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
                     error: "Internal Server Error".to_string(),
                     message: format!("Failed to get tool: {}", err),
                 };
@@ -453,6 +488,82 @@ impl Node {
         }
     }
 
+    pub async fn v2_api_add_wasm_tool(
+        db: Arc<ShinkaiDB>,
+        lance_db: Arc<Mutex<LanceShinkaiDb>>,
+        bearer: String,
+        payload: Value,
+        res: Sender<Result<ShinkaiTool, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let wasm_tool: WasmTool = match serde_json::from_value(payload) {
+            Ok(tool) => tool,
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("Failed to parse WASM tool: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        // Make sure the module compiles before storing it
+        if let Err(err) = wasm_tool.validate() {
+            let api_error = APIError {
+                code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
+                error: "Bad Request".to_string(),
+                message: format!("Invalid WASM tool: {}", err),
+            };
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        // The uploader declares the permissions of the tool, so they are granted to its toolkit
+        let grants = db
+            .get_toolkit_capability_grants(&wasm_tool.toolkit_name)
+            .map(|mut grants| {
+                grants.grant(wasm_tool.permissions.to_capabilities());
+                grants
+            })
+            .and_then(|grants| db.set_toolkit_capability_grants(&grants));
+        if let Err(err) = grants {
+            let api_error = APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error_code: ErrorCode::InternalError,
+                error: "Internal Server Error".to_string(),
+                message: format!("Failed to grant WASM tool capabilities: {}", err),
+            };
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        let shinkai_tool = ShinkaiTool::Wasm(wasm_tool, true);
+        match lance_db.lock().await.set_tool(&shinkai_tool).await {
+            Ok(_) => {
+                let _ = res.send(Ok(shinkai_tool)).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to add WASM tool: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
     pub fn merge_json(existing: Value, input: Value) -> Value {
         match (existing, input) {
             (Value::Object(mut existing_map), Value::Object(input_map)) => {
@@ -475,6 +586,459 @@ impl Node {
             (_, input) => input,
         }
     }
+
+    pub async fn v2_api_get_toolkit_capabilities(
+        db: Arc<ShinkaiDB>,
+        lance_db: Arc<Mutex<LanceShinkaiDb>>,
+        bearer: String,
+        toolkit_name: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let tools = match lance_db.lock().await.get_all_tools().await {
+            Ok(tools) => tools,
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to list tools: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+        let key_prefix = ShinkaiTool::gen_router_key(String::new(), toolkit_name.clone());
+        let toolkit_tools: Vec<ShinkaiToolHeader> = tools
+            .into_iter()
+            .filter(|tool| tool.tool_router_key.starts_with(&key_prefix))
+            .collect();
+
+        let grants = match db.get_toolkit_capability_grants(&toolkit_name) {
+            Ok(grants) => grants,
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to get toolkit capabilities: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let mut requested: Vec<ToolCapability> = Vec::new();
+        for capability in toolkit_tools.iter().flat_map(|tool| tool.capabilities.iter()) {
+            if !requested.contains(capability) {
+                requested.push(capability.clone());
+            }
+        }
+        let missing = ToolCapability::missing(&requested, &grants.granted);
+        let tools: Vec<Value> = toolkit_tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "tool_router_key": tool.tool_router_key,
+                    "requested": tool.capabilities,
+                })
+            })
+            .collect();
+
+        let response = json!({
+            "toolkit_name": toolkit_name,
+            "requested": requested,
+            "granted": grants.granted,
+            "missing": missing,
+            "tools": tools,
+        });
+        let _ = res.send(Ok(response)).await;
+        Ok(())
+    }
+
+    /// Grants or revokes capabilities of a toolkit. Revoking an empty list revokes everything.
+    pub async fn v2_api_update_toolkit_capabilities(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: Value,
+        grant: bool,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let payload: ToolkitCapabilitiesPayload = match serde_json::from_value(payload) {
+            Ok(payload) => payload,
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("Invalid capabilities payload: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let result = db.get_toolkit_capability_grants(&payload.toolkit_name).and_then(|mut grants| {
+            if grant {
+                grants.grant(payload.capabilities);
+            } else {
+                grants.revoke(&payload.capabilities);
+            }
+            db.set_toolkit_capability_grants(&grants)?;
+            Ok(grants)
+        });
+
+        match result {
+            Ok(grants) => {
+                let _ = res.send(Ok(json!(grants))).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to update toolkit capabilities: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_get_toolkit_execution_limits(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        toolkit_name: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match db.get_toolkit_execution_limits(&toolkit_name) {
+            Ok(limits) => {
+                let _ = res.send(Ok(json!(limits))).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to get toolkit execution limits: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_set_toolkit_execution_limits(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        toolkit_name: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let limits = serde_json::from_value::<ToolExecutionLimits>(payload)
+            .map_err(|e| e.to_string())
+            .and_then(|limits| limits.validate().map(|_| limits).map_err(|e| e.to_string()));
+        let limits = match limits {
+            Ok(limits) => limits,
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("Invalid execution limits: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        match db.set_toolkit_execution_limits(&toolkit_name, &limits) {
+            Ok(_) => {
+                let _ = res.send(Ok(json!(limits))).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to set toolkit execution limits: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_get_toolkit_pricing(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        toolkit_name: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match db.get_toolkit_pricing(&toolkit_name) {
+            Ok(pricing) => {
+                let _ = res.send(Ok(json!(pricing))).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to get toolkit pricing: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    /// Sets the price of the invocations of the toolkit. A `null` payload makes its tools free again.
+    pub async fn v2_api_set_toolkit_pricing(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        toolkit_name: String,
+        payload: Value,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let pricing = serde_json::from_value::<Option<ToolPricing>>(payload)
+            .map_err(|e| e.to_string())
+            .and_then(|pricing| match pricing {
+                Some(pricing) => pricing.validate().map(|_| Some(pricing)).map_err(|e| e.to_string()),
+                None => Ok(None),
+            });
+        let pricing = match pricing {
+            Ok(pricing) => pricing,
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error_code: ErrorCode::InvalidInput,
+                    error: "Bad Request".to_string(),
+                    message: format!("Invalid toolkit pricing: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let result = match &pricing {
+            Some(pricing) => db.set_toolkit_pricing(&toolkit_name, pricing),
+            None => db.remove_toolkit_pricing(&toolkit_name),
+        };
+        match result {
+            Ok(_) => {
+                let _ = res.send(Ok(json!(pricing))).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to set toolkit pricing: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_get_tool_usage(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        consumer: String,
+        asset: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match ToolUsageMeter::summary(&db, &consumer, &asset) {
+            Ok(summary) => {
+                let _ = res.send(Ok(json!(summary))).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to get tool usage: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_set_tool_usage_allowance(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: ToolUsageAllowance,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        if payload.allowance < Decimal::ZERO || payload.asset.trim().is_empty() {
+            let api_error = APIError {
+                code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
+                error: "Bad Request".to_string(),
+                message: "An allowance needs an asset and can't be negative".to_string(),
+            };
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        match db.set_tool_usage_allowance(&payload) {
+            Ok(_) => {
+                let _ = res.send(Ok(json!(payload))).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to set tool usage allowance: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_list_toolkits(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match db.get_all_toolkit_provenances() {
+            Ok(provenances) => {
+                let _ = res.send(Ok(json!(provenances))).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to list toolkits: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn v2_api_install_toolkit_from_url(
+        db: Arc<ShinkaiDB>,
+        lance_db: Arc<Mutex<LanceShinkaiDb>>,
+        bearer: String,
+        payload: APIInstallToolkitFromURL,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = Self::install_toolkit_from_url(db, lance_db, payload).await;
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_remove_toolkit(
+        db: Arc<ShinkaiDB>,
+        lance_db: Arc<Mutex<LanceShinkaiDb>>,
+        bearer: String,
+        toolkit_name: String,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // Toolkits installed from a package know their tools, the rest are single tools named after the toolkit
+        let provenance = db.get_toolkit_provenance(&toolkit_name).ok();
+        let tool_keys = match &provenance {
+            Some(provenance) => provenance.tool_router_keys.clone(),
+            None => vec![toolkit_name.clone()],
+        };
+
+        let lance_db = lance_db.lock().await;
+        for tool_key in tool_keys {
+            if let Err(err) = lance_db.remove_tool(&tool_key).await {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to remove toolkit: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        }
+
+        if provenance.is_some() {
+            if let Err(err) = db.remove_toolkit_provenance(&toolkit_name) {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error_code: ErrorCode::InternalError,
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Toolkit removed but failed to remove its provenance: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        }
+
+        let _ = res.send(Ok("Toolkit removed successfully".to_string())).await;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -620,4 +1184,4 @@ mod tests {
         let merged_value = Node::merge_json(existing_tool_value, input_value);
         assert_eq!(merged_value, expected_merged_value);
     }
-}
\ No newline at end of file
+}
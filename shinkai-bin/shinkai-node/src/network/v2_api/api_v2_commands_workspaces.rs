@@ -0,0 +1,275 @@
+use std::sync::Arc;
+
+use async_channel::Sender;
+use ed25519_dalek::SigningKey;
+use shinkai_message_primitives::{
+    schemas::{shinkai_name::ShinkaiName, workspace::Workspace},
+    shinkai_message::shinkai_message_schemas::{
+        APICreateWorkspace, APISetWorkspaceMember, APIUpdateWorkspace, APIWorkspaceInvitation, MessageSchemaType,
+    },
+};
+use tokio::sync::Mutex;
+use x25519_dalek::StaticSecret as EncryptionStaticKey;
+
+use crate::{
+    db::ShinkaiDB,
+    managers::IdentityManager,
+    network::{
+        error_code::ErrorCode,
+        node::ProxyConnectionInfo,
+        node_api_router::APIError,
+        node_error::NodeError,
+        subscription_manager::{
+            external_subscriber_manager::ExternalSubscriberManager, my_subscription_manager::MySubscriptionsManager,
+        },
+        workspace_manager::{WorkspaceError, WorkspaceManager},
+        ws_manager::WSUpdateHandler,
+        Node,
+    },
+    schemas::identity::Identity,
+};
+
+fn workspace_api_error(error: WorkspaceError) -> APIError {
+    let code = match error {
+        WorkspaceError::InvalidInput(_) | WorkspaceError::SubscriptionError(_) => ErrorCode::InvalidInput,
+        WorkspaceError::NotFound(_) => ErrorCode::NotFound,
+        WorkspaceError::NotAllowed(_) => ErrorCode::PermissionDenied,
+        WorkspaceError::DatabaseError(_) => ErrorCode::DatabaseError,
+        WorkspaceError::NetworkError(_) => ErrorCode::InternalError,
+    };
+    APIError::from_code(code, &error.to_string())
+}
+
+impl Node {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn v2_api_create_workspace(
+        db: Arc<ShinkaiDB>,
+        node_name: ShinkaiName,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        ext_subscription_manager: Arc<Mutex<ExternalSubscriberManager>>,
+        encryption_secret_key: EncryptionStaticKey,
+        identity_secret_key: SigningKey,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        bearer: String,
+        payload: APICreateWorkspace,
+        res: Sender<Result<Workspace, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        // The workspace is owned by the main profile of the node
+        let owner = match identity_manager.lock().await.get_main_identity() {
+            Some(Identity::Standard(std_identity)) => std_identity.clone().full_identity_name,
+            _ => {
+                let api_error = APIError::from_code(
+                    ErrorCode::IdentityNotFound,
+                    "Wrong identity type. Expected Standard identity.",
+                );
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let workspace = match WorkspaceManager::create_workspace(&db, ext_subscription_manager, &owner, payload).await {
+            Ok(workspace) => workspace,
+            Err(e) => {
+                let _ = res.send(Err(workspace_api_error(e))).await;
+                return Ok(());
+            }
+        };
+
+        WorkspaceManager::send_to_members(
+            &workspace,
+            &workspace,
+            MessageSchemaType::WorkspaceUpdate,
+            &[],
+            &node_name,
+            &encryption_secret_key,
+            &identity_secret_key,
+            db.clone(),
+            identity_manager,
+            proxy_connection_info,
+            ws_manager,
+        )
+        .await;
+
+        let _ = res.send(Ok(workspace)).await;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn v2_api_update_workspace(
+        db: Arc<ShinkaiDB>,
+        node_name: ShinkaiName,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        ext_subscription_manager: Arc<Mutex<ExternalSubscriberManager>>,
+        encryption_secret_key: EncryptionStaticKey,
+        identity_secret_key: SigningKey,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        bearer: String,
+        payload: APIUpdateWorkspace,
+        res: Sender<Result<Workspace, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let workspace =
+            match WorkspaceManager::update_workspace(&db, ext_subscription_manager, &node_name, payload).await {
+                Ok(workspace) => workspace,
+                Err(e) => {
+                    let _ = res.send(Err(workspace_api_error(e))).await;
+                    return Ok(());
+                }
+            };
+
+        WorkspaceManager::send_to_members(
+            &workspace,
+            &workspace,
+            MessageSchemaType::WorkspaceUpdate,
+            &[],
+            &node_name,
+            &encryption_secret_key,
+            &identity_secret_key,
+            db.clone(),
+            identity_manager,
+            proxy_connection_info,
+            ws_manager,
+        )
+        .await;
+
+        let _ = res.send(Ok(workspace)).await;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn v2_api_set_workspace_member(
+        db: Arc<ShinkaiDB>,
+        node_name: ShinkaiName,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        ext_subscription_manager: Arc<Mutex<ExternalSubscriberManager>>,
+        encryption_secret_key: EncryptionStaticKey,
+        identity_secret_key: SigningKey,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        bearer: String,
+        payload: APISetWorkspaceMember,
+        res: Sender<Result<Workspace, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let (workspace, removed) =
+            match WorkspaceManager::set_member(&db, ext_subscription_manager, &node_name, payload).await {
+                Ok(result) => result,
+                Err(e) => {
+                    let _ = res.send(Err(workspace_api_error(e))).await;
+                    return Ok(());
+                }
+            };
+
+        // The removed member gets the workspace without it, so its node drops its copy
+        WorkspaceManager::send_to_members(
+            &workspace,
+            &workspace,
+            MessageSchemaType::WorkspaceUpdate,
+            &removed.into_iter().collect::<Vec<String>>(),
+            &node_name,
+            &encryption_secret_key,
+            &identity_secret_key,
+            db.clone(),
+            identity_manager,
+            proxy_connection_info,
+            ws_manager,
+        )
+        .await;
+
+        let _ = res.send(Ok(workspace)).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_list_workspaces(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<Vec<Workspace>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = db
+            .get_all_workspaces()
+            .map_err(|e| workspace_api_error(WorkspaceError::DatabaseError(e)));
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    /// Workspaces other nodes added this node to, waiting for a profile of this node to accept them
+    pub async fn v2_api_list_workspace_invitations(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        res: Sender<Result<Vec<Workspace>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = db
+            .get_all_workspace_invitations()
+            .map_err(|e| workspace_api_error(WorkspaceError::DatabaseError(e)));
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_accept_workspace_invitation(
+        db: Arc<ShinkaiDB>,
+        node_name: ShinkaiName,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        my_subscription_manager: Arc<Mutex<MySubscriptionsManager>>,
+        bearer: String,
+        payload: APIWorkspaceInvitation,
+        res: Sender<Result<Workspace, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = WorkspaceManager::accept_invitation(
+            &db,
+            identity_manager,
+            my_subscription_manager,
+            &node_name,
+            &payload.workspace_id,
+        )
+        .await
+        .map_err(workspace_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_decline_workspace_invitation(
+        db: Arc<ShinkaiDB>,
+        bearer: String,
+        payload: APIWorkspaceInvitation,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = WorkspaceManager::decline_invitation(&db, &payload.workspace_id).map_err(workspace_api_error);
+        let _ = res.send(result).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,382 @@
+use std::convert::Infallible;
+
+use async_channel::Sender;
+use async_graphql::{Context, EmptySubscription, Json, Object, Schema, SimpleObject};
+use async_graphql_warp::GraphQLResponse;
+use serde_json::Value;
+use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
+    APIVecFsCreateFolder, APIVecFsRetrievePathSimplifiedJson, APIVecFsSearchItems, JobCreationInfo, JobMessage,
+    V2ChatMessage,
+};
+use shinkai_message_primitives::shinkai_utils::job_scope::JobScope;
+use warp::Filter;
+
+use crate::network::{node_api_router::APIError, node_commands::NodeCommand};
+use crate::schemas::smart_inbox::V2SmartInbox;
+
+use super::api_v2_router::with_sender;
+
+pub type NodeSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// Per request data: resolvers run the same node commands as the REST handlers, with the caller's bearer
+struct GraphQLContext {
+    node_commands_sender: Sender<NodeCommand>,
+    bearer: String,
+}
+
+/// Sends a node command and waits for its result, turning API errors into GraphQL errors
+async fn run_command<T>(
+    ctx: &Context<'_>,
+    command: impl FnOnce(String, Sender<Result<T, APIError>>) -> NodeCommand,
+) -> async_graphql::Result<T> {
+    let context = ctx.data::<GraphQLContext>()?;
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    context
+        .node_commands_sender
+        .send(command(context.bearer.clone(), res_sender))
+        .await
+        .map_err(|e| async_graphql::Error::new(e.to_string()))?;
+    res_receiver
+        .recv()
+        .await
+        .map_err(|e| async_graphql::Error::new(e.to_string()))?
+        .map_err(|e| async_graphql::Error::new(format!("{}: {}", e.error, e.message)))
+}
+
+/// Job id of a job inbox (`job_inbox::<job_id>::false`)
+pub fn job_id_from_inbox(inbox_id: &str) -> Option<String> {
+    let mut parts = inbox_id.split("::");
+    match (parts.next(), parts.next()) {
+        (Some("job_inbox"), Some(job_id)) if !job_id.is_empty() => Some(job_id.to_string()),
+        _ => None,
+    }
+}
+
+pub struct Inbox(V2SmartInbox);
+
+#[Object]
+impl Inbox {
+    async fn id(&self) -> &str {
+        &self.0.inbox_id
+    }
+
+    async fn custom_name(&self) -> &str {
+        &self.0.custom_name
+    }
+
+    async fn datetime_created(&self) -> &str {
+        &self.0.datetime_created
+    }
+
+    /// Job of the inbox, if it's a job inbox
+    async fn job_id(&self) -> Option<String> {
+        job_id_from_inbox(&self.0.inbox_id)
+    }
+
+    async fn is_finished(&self) -> bool {
+        self.0.is_finished
+    }
+
+    async fn job_scope(&self) -> Option<Json<Value>> {
+        self.0.job_scope.clone().map(Json)
+    }
+
+    /// Id of the agent the job is assigned to
+    async fn llm_provider_id(&self) -> Option<&str> {
+        self.0.agent.as_ref().map(|agent| agent.id.as_str())
+    }
+
+    async fn last_message(&self) -> Option<ChatMessage> {
+        self.0.last_message.clone().map(ChatMessage)
+    }
+
+    async fn messages(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 10)] limit: usize,
+        offset_key: Option<String>,
+    ) -> async_graphql::Result<Vec<ChatMessage>> {
+        last_messages(ctx, self.0.inbox_id.clone(), limit, offset_key).await
+    }
+}
+
+pub struct ChatMessage(V2ChatMessage);
+
+#[Object]
+impl ChatMessage {
+    /// Hash of the message, used as its id and as the offset key for pagination
+    async fn hash(&self) -> &str {
+        &self.0.node_api_data.node_message_hash
+    }
+
+    async fn parent_hash(&self) -> &str {
+        &self.0.node_api_data.parent_hash
+    }
+
+    async fn timestamp(&self) -> &str {
+        &self.0.node_api_data.node_timestamp
+    }
+
+    async fn inbox(&self) -> &str {
+        &self.0.inbox
+    }
+
+    async fn content(&self) -> &str {
+        &self.0.job_message.content
+    }
+
+    async fn sender(&self) -> &str {
+        &self.0.sender
+    }
+
+    async fn sender_subidentity(&self) -> &str {
+        &self.0.sender_subidentity
+    }
+
+    /// Answers from agents aren't sent by a profile
+    async fn is_from_agent(&self) -> bool {
+        self.0.sender_subidentity.is_empty()
+    }
+}
+
+pub struct LLMProvider(SerializedLLMProvider);
+
+#[Object]
+impl LLMProvider {
+    async fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    async fn model(&self) -> Option<String> {
+        serde_json::to_value(&self.0.model)
+            .ok()
+            .and_then(|model| model.as_str().map(|model| model.to_string()))
+    }
+
+    async fn external_url(&self) -> Option<&str> {
+        self.0.external_url.as_deref()
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct SentJobMessage {
+    pub message_id: String,
+    pub parent_message_id: Option<String>,
+    pub inbox: String,
+    pub scheduled_time: String,
+}
+
+async fn last_messages(
+    ctx: &Context<'_>,
+    inbox_name: String,
+    limit: usize,
+    offset_key: Option<String>,
+) -> async_graphql::Result<Vec<ChatMessage>> {
+    let messages = run_command(ctx, |bearer, res| NodeCommand::V2ApiGetLastMessagesFromInbox {
+        bearer,
+        inbox_name,
+        limit,
+        offset_key,
+        res,
+    })
+    .await?;
+    Ok(messages.into_iter().map(ChatMessage).collect())
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn inboxes(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Inbox>> {
+        let inboxes = run_command(ctx, |bearer, res| NodeCommand::V2ApiGetAllSmartInboxes { bearer, res }).await?;
+        Ok(inboxes.into_iter().map(Inbox).collect())
+    }
+
+    async fn inbox(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<Inbox>> {
+        let inboxes = run_command(ctx, |bearer, res| NodeCommand::V2ApiGetAllSmartInboxes { bearer, res }).await?;
+        Ok(inboxes.into_iter().find(|inbox| inbox.inbox_id == id).map(Inbox))
+    }
+
+    async fn messages(
+        &self,
+        ctx: &Context<'_>,
+        inbox: String,
+        #[graphql(default = 10)] limit: usize,
+        offset_key: Option<String>,
+    ) -> async_graphql::Result<Vec<ChatMessage>> {
+        last_messages(ctx, inbox, limit, offset_key).await
+    }
+
+    async fn llm_providers(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<LLMProvider>> {
+        let llm_providers = run_command(ctx, |bearer, res| NodeCommand::V2ApiAvailableLLMProviders {
+            bearer,
+            res,
+        })
+        .await?;
+        Ok(llm_providers.into_iter().map(LLMProvider).collect())
+    }
+
+    /// Contents of a VectorFS folder, as returned by `/v2/retrieve_path_simplified`
+    async fn vecfs_path(&self, ctx: &Context<'_>, path: String) -> async_graphql::Result<Json<Value>> {
+        let payload = APIVecFsRetrievePathSimplifiedJson { path };
+        run_command(ctx, |bearer, res| NodeCommand::V2ApiVecFSRetrievePathSimplifiedJson {
+            bearer,
+            payload,
+            res,
+        })
+        .await
+        .map(Json)
+    }
+
+    /// Paths of the VectorFS items closest to the search, by vector similarity
+    async fn vecfs_search(
+        &self,
+        ctx: &Context<'_>,
+        search: String,
+        path: Option<String>,
+    ) -> async_graphql::Result<Vec<String>> {
+        let payload = APIVecFsSearchItems {
+            path,
+            search,
+            max_results: None,
+            max_files_to_scan: None,
+        };
+        run_command(ctx, |bearer, res| NodeCommand::V2ApiSearchItems {
+            bearer,
+            payload,
+            res,
+        })
+        .await
+    }
+
+    async fn my_subscriptions(&self, ctx: &Context<'_>) -> async_graphql::Result<Json<Value>> {
+        run_command(ctx, |bearer, res| NodeCommand::V2ApiMySubscriptions { bearer, res })
+            .await
+            .map(Json)
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Creates a job for the agent and returns its id
+    async fn create_job(
+        &self,
+        ctx: &Context<'_>,
+        llm_provider: String,
+        #[graphql(default = false)] is_hidden: bool,
+    ) -> async_graphql::Result<String> {
+        let job_creation_info = JobCreationInfo {
+            scope: JobScope::new_default(),
+            is_hidden: Some(is_hidden),
+            retrieval: None,
+        };
+        run_command(ctx, |bearer, res| NodeCommand::V2ApiCreateJob {
+            bearer,
+            job_creation_info,
+            llm_provider,
+            res,
+        })
+        .await
+    }
+
+    async fn send_job_message(
+        &self,
+        ctx: &Context<'_>,
+        job_id: String,
+        content: String,
+        #[graphql(default)] files_inbox: String,
+    ) -> async_graphql::Result<SentJobMessage> {
+        let job_message = JobMessage {
+            job_id,
+            content,
+            files_inbox,
+            parent: None,
+            workflow_code: None,
+            workflow_name: None,
+            sheet_job_data: None,
+            callback: None,
+            retrieval: None,
+        };
+        let sent = run_command(ctx, |bearer, res| NodeCommand::V2ApiJobMessage {
+            bearer,
+            job_message,
+            res,
+        })
+        .await?;
+        Ok(SentJobMessage {
+            message_id: sent.message_id,
+            parent_message_id: sent.parent_message_id,
+            inbox: sent.inbox,
+            scheduled_time: sent.scheduled_time,
+        })
+    }
+
+    async fn create_vecfs_folder(
+        &self,
+        ctx: &Context<'_>,
+        path: String,
+        folder_name: String,
+    ) -> async_graphql::Result<String> {
+        let payload = APIVecFsCreateFolder { path, folder_name };
+        run_command(ctx, |bearer, res| NodeCommand::V2ApiVecFSCreateFolder {
+            bearer,
+            payload,
+            res,
+        })
+        .await
+    }
+}
+
+/// `POST /v2/graphql`. The schema maps to the same node commands as the REST API, so clients can fetch
+/// nested data (e.g. inboxes with their last message and job status) in a single request.
+pub fn graphql_routes(
+    node_commands_sender: Sender<NodeCommand>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription).finish();
+
+    warp::path("graphql")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(with_sender(node_commands_sender))
+        .and(warp::header::<String>("authorization"))
+        .and(async_graphql_warp::graphql(schema))
+        .and_then(
+            |node_commands_sender: Sender<NodeCommand>,
+             authorization: String,
+             (schema, request): (NodeSchema, async_graphql::Request)| async move {
+                let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+                let request = request.data(GraphQLContext {
+                    node_commands_sender,
+                    bearer,
+                });
+                Ok::<_, Infallible>(GraphQLResponse::from(schema.execute(request).await))
+            },
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_id_from_inbox() {
+        assert_eq!(
+            job_id_from_inbox("job_inbox::jobid_1234::false"),
+            Some("jobid_1234".to_string())
+        );
+        assert_eq!(
+            job_id_from_inbox("inbox::@@node1.shinkai/main::@@node2.shinkai::false"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_schema_has_node_queries() {
+        let sdl = Schema::build(QueryRoot, MutationRoot, EmptySubscription).finish().sdl();
+        assert!(sdl.contains("inboxes: [Inbox!]!"));
+        assert!(sdl.contains("createJob("));
+    }
+}
@@ -0,0 +1,328 @@
+use async_channel::Sender;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
+    APICreateShareableFolder, APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsDeleteFolder,
+    APIVecFsDeleteItem, APIVecFsMoveFolder, APIVecFsMoveItem,
+};
+use utoipa::OpenApi;
+use warp::Filter;
+
+use crate::network::{error_code::ErrorCode, node_api_router::APIError, node_commands::NodeCommand};
+
+use super::api_v2_handlers_jobs::{CreateJobRequest, JobMessageRequest};
+use super::api_v2_router::with_sender;
+
+/// More than this and clients should split the batch
+const MAX_BATCH_OPERATIONS: usize = 50;
+
+pub fn batch_routes(
+    node_commands_sender: Sender<NodeCommand>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("batch")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(batch_handler)
+}
+
+#[derive(Deserialize)]
+pub struct BatchRequest {
+    pub operations: Vec<BatchOperation>,
+    /// Keep running the next operations after one fails. By default they are skipped, since later operations
+    /// usually depend on the earlier ones (e.g. uploading to a folder created in the same batch).
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+/// An API operation, e.g. `{ "op": "create_folder", "payload": { "path": "/", "folder_name": "docs" } }`.
+/// Payloads are the bodies of the matching v2 endpoints.
+#[derive(Deserialize)]
+#[serde(tag = "op", content = "payload", rename_all = "snake_case")]
+pub enum BatchOperation {
+    CreateFolder(APIVecFsCreateFolder),
+    MoveItem(APIVecFsMoveItem),
+    CopyItem(APIVecFsCopyItem),
+    MoveFolder(APIVecFsMoveFolder),
+    CopyFolder(APIVecFsCopyFolder),
+    RemoveItem(APIVecFsDeleteItem),
+    RemoveFolder(APIVecFsDeleteFolder),
+    UploadFileToFolder(BatchUploadFile),
+    CreateShareableFolder(APICreateShareableFolder),
+    CreateJob(CreateJobRequest),
+    JobMessage(JobMessageRequest),
+}
+
+#[derive(Deserialize)]
+pub struct BatchUploadFile {
+    pub path: String,
+    pub filename: String,
+    /// Base64 encoded content of the file
+    pub file: String,
+    #[serde(default)]
+    pub allow_duplicates: bool,
+}
+
+#[derive(Serialize)]
+pub struct BatchOperationResult {
+    pub index: usize,
+    /// HTTP status the operation would have had on its own endpoint. 424 if it was skipped.
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<APIError>,
+}
+
+/// Sends a node command and waits for its result
+async fn run_command<T: Serialize>(
+    node_commands_sender: &Sender<NodeCommand>,
+    command: impl FnOnce(Sender<Result<T, APIError>>) -> NodeCommand,
+) -> Result<Value, APIError> {
+    let internal_error = |message: String| APIError {
+        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+        error_code: ErrorCode::InternalError,
+        error: "Internal Server Error".to_string(),
+        message,
+    };
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(command(res_sender))
+        .await
+        .map_err(|e| internal_error(e.to_string()))?;
+    let result = res_receiver.recv().await.map_err(|e| internal_error(e.to_string()))??;
+    Ok(json!(result))
+}
+
+async fn run_operation(
+    node_commands_sender: &Sender<NodeCommand>,
+    bearer: String,
+    operation: BatchOperation,
+) -> Result<Value, APIError> {
+    match operation {
+        BatchOperation::CreateFolder(payload) => {
+            run_command(node_commands_sender, |res| NodeCommand::V2ApiVecFSCreateFolder {
+                bearer,
+                payload,
+                res,
+            })
+            .await
+        }
+        BatchOperation::MoveItem(payload) => {
+            run_command(node_commands_sender, |res| NodeCommand::V2ApiMoveItem {
+                bearer,
+                payload,
+                res,
+            })
+            .await
+        }
+        BatchOperation::CopyItem(payload) => {
+            run_command(node_commands_sender, |res| NodeCommand::V2ApiCopyItem {
+                bearer,
+                payload,
+                res,
+            })
+            .await
+        }
+        BatchOperation::MoveFolder(payload) => {
+            run_command(node_commands_sender, |res| NodeCommand::V2ApiMoveFolder {
+                bearer,
+                payload,
+                res,
+            })
+            .await
+        }
+        BatchOperation::CopyFolder(payload) => {
+            run_command(node_commands_sender, |res| NodeCommand::V2ApiCopyFolder {
+                bearer,
+                payload,
+                res,
+            })
+            .await
+        }
+        BatchOperation::RemoveItem(payload) => {
+            run_command(node_commands_sender, |res| NodeCommand::V2ApiDeleteItem {
+                bearer,
+                payload,
+                res,
+            })
+            .await
+        }
+        BatchOperation::RemoveFolder(payload) => {
+            run_command(node_commands_sender, |res| NodeCommand::V2ApiDeleteFolder {
+                bearer,
+                payload,
+                res,
+            })
+            .await
+        }
+        BatchOperation::UploadFileToFolder(payload) => {
+            let file = base64::decode(&payload.file).map_err(|e| APIError {
+                code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::InvalidInput,
+                error: "Bad Request".to_string(),
+                message: format!("Invalid base64 file content: {}", e),
+            })?;
+            run_command(node_commands_sender, |res| NodeCommand::V2ApiUploadFileToFolder {
+                bearer,
+                filename: payload.filename,
+                file,
+                path: payload.path,
+                file_datetime: None,
+                allow_duplicates: payload.allow_duplicates,
+                res,
+            })
+            .await
+        }
+        BatchOperation::CreateShareableFolder(payload) => {
+            run_command(node_commands_sender, |res| NodeCommand::V2ApiCreateShareableFolder {
+                bearer,
+                payload,
+                res,
+            })
+            .await
+        }
+        BatchOperation::CreateJob(payload) => {
+            run_command(node_commands_sender, |res| NodeCommand::V2ApiCreateJob {
+                bearer,
+                job_creation_info: payload.job_creation_info,
+                llm_provider: payload.llm_provider,
+                res,
+            })
+            .await
+        }
+        BatchOperation::JobMessage(payload) => {
+            run_command(node_commands_sender, |res| NodeCommand::V2ApiJobMessage {
+                bearer,
+                job_message: payload.job_message,
+                res,
+            })
+            .await
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/batch",
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "Results of the operations, in order, each with its own status", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn batch_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: BatchRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+
+    if payload.operations.len() > MAX_BATCH_OPERATIONS {
+        let error = APIError {
+            code: StatusCode::BAD_REQUEST.as_u16(),
+            error_code: ErrorCode::InvalidInput,
+            error: "Bad Request".to_string(),
+            message: format!("A batch can have at most {} operations", MAX_BATCH_OPERATIONS),
+        };
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    // Checked once for the whole batch, so a bad key fails the request instead of every operation
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiCheckBearer {
+            bearer: bearer.clone(),
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    if let Err(error) = res_receiver.recv().await.map_err(|_| warp::reject::reject())? {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        ));
+    }
+
+    let mut results = Vec::with_capacity(payload.operations.len());
+    let mut failed = false;
+    for (index, operation) in payload.operations.into_iter().enumerate() {
+        if failed && !payload.continue_on_error {
+            results.push(BatchOperationResult {
+                index,
+                status: StatusCode::FAILED_DEPENDENCY.as_u16(),
+                result: None,
+                error: None,
+            });
+            continue;
+        }
+
+        let result = match run_operation(&node_commands_sender, bearer.clone(), operation).await {
+            Ok(result) => BatchOperationResult {
+                index,
+                status: StatusCode::OK.as_u16(),
+                result: Some(result),
+                error: None,
+            },
+            Err(error) => {
+                failed = true;
+                BatchOperationResult {
+                    index,
+                    status: error.code,
+                    result: None,
+                    error: Some(error),
+                }
+            }
+        };
+        results.push(result);
+    }
+
+    Ok(warp::reply::with_status(
+        warp::reply::json(&json!({ "results": results })),
+        StatusCode::OK,
+    ))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        batch_handler,
+    ),
+    components(
+        schemas(APIError)
+    ),
+    tags(
+        (name = "batch", description = "Batch API endpoints")
+    )
+)]
+pub struct BatchApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_batch_request() {
+        let request: BatchRequest = serde_json::from_value(json!({
+            "operations": [
+                { "op": "create_folder", "payload": { "path": "/", "folder_name": "docs" } },
+                { "op": "upload_file_to_folder", "payload": { "path": "/docs", "filename": "a.txt", "file": "aGk=" } },
+                { "op": "remove_item", "payload": { "path": "/docs/old.txt" } }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(request.operations.len(), 3);
+        assert!(!request.continue_on_error);
+        assert!(matches!(
+            &request.operations[0],
+            BatchOperation::CreateFolder(folder) if folder.folder_name == "docs"
+        ));
+        assert!(matches!(&request.operations[1], BatchOperation::UploadFileToFolder(_)));
+    }
+}
@@ -0,0 +1,341 @@
+use async_channel::Sender;
+use reqwest::StatusCode;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
+    APIAcceptMessageRequest, APIRemoveContact, APISetContact, APISetContactsAllowlistMode,
+};
+use utoipa::OpenApi;
+use warp::Filter;
+
+use crate::network::{node_api_router::APIError, node_commands::NodeCommand};
+use crate::schemas::inbound_message_policy::InboundMessagePolicy;
+
+use super::api_v2_router::with_sender;
+
+pub fn contacts_routes(
+    node_commands_sender: Sender<NodeCommand>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let get_contacts_route = warp::path("contacts")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_contacts_handler);
+
+    let set_contact_route = warp::path("set_contact")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_contact_handler);
+
+    let remove_contact_route = warp::path("remove_contact")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(remove_contact_handler);
+
+    let set_contacts_allowlist_mode_route = warp::path("set_contacts_allowlist_mode")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_contacts_allowlist_mode_handler);
+
+    let accept_message_request_route = warp::path("accept_message_request")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(accept_message_request_handler);
+
+    let get_inbound_message_policy_route = warp::path("inbound_message_policy")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_inbound_message_policy_handler);
+
+    let set_inbound_message_policy_route = warp::path("set_inbound_message_policy")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_inbound_message_policy_handler);
+
+    get_contacts_route
+        .or(set_contact_route)
+        .or(remove_contact_route)
+        .or(set_contacts_allowlist_mode_route)
+        .or(accept_message_request_route)
+        .or(get_inbound_message_policy_route)
+        .or(set_inbound_message_policy_route)
+}
+
+/// Contact book of the main profile, with whether it's in allowlist mode
+#[utoipa::path(
+    get,
+    path = "/v2/contacts",
+    responses(
+        (status = 200, description = "The contact book", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn get_contacts_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetContacts {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Adds a contact to the contact book of the main profile, or replaces the one with the same identity
+#[utoipa::path(
+    post,
+    path = "/v2/set_contact",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The contact saved", body = Value),
+        (status = 400, description = "Invalid identity", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn set_contact_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APISetContact,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetContact {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Removes a contact from the contact book of the main profile
+#[utoipa::path(
+    post,
+    path = "/v2/remove_contact",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Contact removed", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError),
+        (status = 404, description = "Contact not found", body = APIError)
+    )
+)]
+pub async fn remove_contact_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIRemoveContact,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiRemoveContact {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Turns the allowlist mode of the main profile on or off. In allowlist mode, conversations started by identities
+/// that aren't contacts go to the message requests, listed with `/v2/all_inboxes?message_requests=true`.
+#[utoipa::path(
+    post,
+    path = "/v2/set_contacts_allowlist_mode",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The contact book", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn set_contacts_allowlist_mode_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APISetContactsAllowlistMode,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetContactsAllowlistMode {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Moves a message request to the inboxes, adding its sender to the contacts
+#[utoipa::path(
+    post,
+    path = "/v2/accept_message_request",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The contact of the sender", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError),
+        (status = 404, description = "The inbox isn't a message request", body = APIError)
+    )
+)]
+pub async fn accept_message_request_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIAcceptMessageRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiAcceptMessageRequest {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Inbound message policy of the main profile
+#[utoipa::path(
+    get,
+    path = "/v2/inbound_message_policy",
+    responses(
+        (status = 200, description = "The inbound message policy", body = InboundMessagePolicy),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn get_inbound_message_policy_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetInboundMessagePolicy {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Replaces the inbound message policy of the main profile: the per sender rate limit, the maximum size of the
+/// messages and the keywords they're dropped for. With the allowlist mode, first contacts wait in the message
+/// requests until they're accepted.
+#[utoipa::path(
+    post,
+    path = "/v2/set_inbound_message_policy",
+    request_body = InboundMessagePolicy,
+    responses(
+        (status = 200, description = "The inbound message policy saved", body = InboundMessagePolicy),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn set_inbound_message_policy_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: InboundMessagePolicy,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetInboundMessagePolicy {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_contacts_handler,
+        set_contact_handler,
+        remove_contact_handler,
+        set_contacts_allowlist_mode_handler,
+        accept_message_request_handler,
+        get_inbound_message_policy_handler,
+        set_inbound_message_policy_handler
+    ),
+    components(schemas(APIError, InboundMessagePolicy)),
+    tags(
+        (name = "contacts", description = "Contact book, message requests and inbound message policy API endpoints")
+    )
+)]
+pub struct ContactsApiDoc;
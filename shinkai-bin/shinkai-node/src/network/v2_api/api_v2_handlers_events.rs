@@ -0,0 +1,138 @@
+use std::convert::Infallible;
+
+use async_channel::Sender;
+use futures::stream;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use utoipa::OpenApi;
+use warp::reply::Response;
+use warp::sse::Event;
+use warp::{Filter, Reply};
+
+use crate::network::{
+    error_code::ErrorCode,
+    node_api_router::APIError,
+    node_commands::NodeCommand,
+    node_events::{NodeEvent, NodeEventType},
+};
+
+use super::api_v2_router::with_sender;
+
+pub fn events_routes(
+    node_commands_sender: Sender<NodeCommand>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("events")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender))
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::query::<EventsQuery>())
+        .and_then(events_handler)
+}
+
+#[derive(Deserialize)]
+pub struct EventsQuery {
+    /// Comma separated event types to receive (message, job_status, subscription_sync, notification,
+    /// file_processing).
+    /// All of them if it's not set.
+    pub types: Option<String>,
+    /// API key, for clients that can't set headers (e.g. the browser's EventSource)
+    pub token: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/events",
+    params(
+        ("types" = Option<String>, Query, description = "Comma separated event types to receive, all of them if not set"),
+        ("token" = Option<String>, Query, description = "API key, for clients that can't set the authorization header")
+    ),
+    responses(
+        (status = 200, description = "Stream of node events as Server-Sent Events"),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn events_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: Option<String>,
+    query: EventsQuery,
+) -> Result<Response, warp::Rejection> {
+    let bearer = match (authorization, query.token) {
+        (Some(authorization), _) => authorization.strip_prefix("Bearer ").unwrap_or("").to_string(),
+        (None, Some(token)) => token,
+        (None, None) => String::new(),
+    };
+    let event_types = match NodeEventType::parse_filter(query.types.as_deref().unwrap_or_default()) {
+        Ok(event_types) => event_types,
+        Err(e) => {
+            let error = APIError {
+                code: StatusCode::BAD_REQUEST.as_u16(),
+                error_code: ErrorCode::BadRequest,
+                error: "Bad Request".to_string(),
+                message: e,
+            };
+            return Ok(warp::reply::with_status(warp::reply::json(&error), StatusCode::BAD_REQUEST).into_response());
+        }
+    };
+
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiSubscribeToEvents {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let receiver = match res_receiver.recv().await.map_err(|_| warp::reject::reject())? {
+        Ok(receiver) => receiver,
+        Err(error) => {
+            return Ok(
+                warp::reply::with_status(warp::reply::json(&error), StatusCode::from_u16(error.code).unwrap())
+                    .into_response(),
+            )
+        }
+    };
+
+    let events = stream::unfold(receiver, move |mut receiver| {
+        let event_types = event_types.clone();
+        async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if event_types.is_empty() || event_types.contains(&event.event_type) => {
+                        return Some((Ok::<Event, Infallible>(to_sse_event(&event)), receiver));
+                    }
+                    Ok(_) => continue,
+                    // The client missed some events, tell it so it can refetch what it shows
+                    Err(RecvError::Lagged(skipped)) => {
+                        let event = Event::default().event("lagged").data(skipped.to_string());
+                        return Some((Ok(event), receiver));
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }
+    });
+
+    Ok(warp::sse::reply(warp::sse::keep_alive().stream(events)).into_response())
+}
+
+fn to_sse_event(event: &NodeEvent) -> Event {
+    Event::default()
+        .event(event.event_type.as_str())
+        .data(event.data.to_string())
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        events_handler,
+    ),
+    components(
+        schemas(APIError)
+    ),
+    tags(
+        (name = "events", description = "Node events API endpoints")
+    )
+)]
+pub struct EventsApiDoc;
@@ -1,15 +1,22 @@
 use async_channel::Sender;
 use reqwest::StatusCode;
 use serde::Deserialize;
-use serde_json::json;
-use shinkai_message_primitives::{schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider, shinkai_message::shinkai_message_schemas::APIAddOllamaModels};
+use serde_json::{json, Value};
+use shinkai_message_primitives::{schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider, shinkai_message::shinkai_message_schemas::{APIAddOllamaModels, APIDeleteProfile, APIExportProfileData, APIGetRecentLogs, APIInitializeNodeInteractive, APIRelocateStorage, APIRemoveCloudConnector, APIRemoveWatchedFolder, APIRenameDevice, APIRevokeDevice, APIRevokeRegistrationCode, APIRunDbMaintenance, APISetPeerBan, RegistrationCodeRequest}, shinkai_utils::shinkai_logging::LogLevelSetting};
 use utoipa::OpenApi;
 use warp::Filter;
 
+use crate::llm_provider::circuit_breaker::{CircuitBreakerStatus, CircuitState};
+use crate::managers::node_health::HealthStatus;
+use crate::managers::node_metrics::NodeMetrics;
 use crate::network::{
     node_api_router::{APIError, GetPublicKeysResponse},
     node_commands::NodeCommand,
+    peer_reputation::{PeerBanOverride, PeerReputationStatus},
 };
+use crate::schemas::db_maintenance::{ColumnFamilySize, DbCompactionSchedule, DbMaintenanceReport};
+use crate::schemas::outbound_proxy::{DestinationClass, OutboundProxySettings};
+use crate::schemas::profile_limits::{ProfileLimits, ProfileUsage};
 
 use super::api_v2_router::{create_success_response, with_node_name, with_sender};
 
@@ -34,6 +41,29 @@ pub fn general_routes(
         .and(warp::body::json())
         .and_then(initial_registration_handler);
 
+    let oidc_registration_route = warp::path("oidc_registration")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::body::json())
+        .and_then(oidc_registration_handler);
+
+    let generate_onboarding_keys_route = warp::path("generate_onboarding_keys")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and_then(generate_onboarding_keys_handler);
+
+    let test_llm_provider_route = warp::path("test_llm_provider")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::body::json())
+        .and_then(test_llm_provider_handler);
+
+    let initialize_node_route = warp::path("initialize_node")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::body::json())
+        .and_then(initialize_node_handler);
+
     let get_local_processing_preference_route = warp::path("local_processing_preference")
         .and(warp::get())
         .and(with_sender(node_commands_sender.clone()))
@@ -47,6 +77,32 @@ pub fn general_routes(
         .and(warp::body::json())
         .and_then(update_local_processing_preference_handler);
 
+    let get_entity_tagging_preference_route = warp::path("entity_tagging_preference")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_entity_tagging_preference_handler);
+
+    let update_entity_tagging_preference_route = warp::path("entity_tagging_preference")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(update_entity_tagging_preference_handler);
+
+    let get_summarization_llm_provider_route = warp::path("summarization_llm_provider")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_summarization_llm_provider_handler);
+
+    let update_summarization_llm_provider_route = warp::path("summarization_llm_provider")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(update_summarization_llm_provider_handler);
+
     let get_default_embedding_model_route = warp::path("default_embedding_model")
         .and(warp::get())
         .and(with_sender(node_commands_sender.clone()))
@@ -120,11 +176,282 @@ pub fn general_routes(
         .and(warp::body::json())
         .and_then(add_ollama_models_handler);
 
+    let local_inference_metrics_route = warp::path("local_inference_metrics")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(local_inference_metrics_handler);
+
+    let get_log_levels_route = warp::path("log_levels")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_log_levels_handler);
+
+    let set_log_levels_route = warp::path("set_log_levels")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_log_levels_handler);
+
+    let get_recent_logs_route = warp::path("recent_logs")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<APIGetRecentLogs>())
+        .and_then(get_recent_logs_handler);
+
+    let download_logs_route = warp::path("download_logs")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(download_logs_handler);
+
+    let run_diagnostics_route = warp::path("run_diagnostics")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(run_diagnostics_handler);
+
+    let run_db_maintenance_route = warp::path("run_db_maintenance")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(run_db_maintenance_handler);
+
+    let relocate_storage_route = warp::path("relocate_storage")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(relocate_storage_handler);
+
+    let node_metrics_route = warp::path("node_metrics")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(node_metrics_handler);
+
+    let peer_reputations_route = warp::path("peer_reputations")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_peer_reputations_handler);
+
+    let set_peer_ban_route = warp::path("set_peer_ban")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_peer_ban_handler);
+
+    let get_outbound_proxy_settings_route = warp::path("outbound_proxy_settings")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_outbound_proxy_settings_handler);
+
+    let set_outbound_proxy_settings_route = warp::path("outbound_proxy_settings")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_outbound_proxy_settings_handler);
+
+    let resolve_identity_name_route = warp::path("resolve_identity_name")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<ResolveIdentityNameRequest>())
+        .and_then(resolve_identity_name_handler);
+
+    let stop_node_route = warp::path("stop_node")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(stop_node_handler);
+
+    let restart_node_route = warp::path("restart_node")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(restart_node_handler);
+
+    let get_profile_limits_route = warp::path("profile_limits")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<GetProfileLimitsRequest>())
+        .and_then(get_profile_limits_handler);
+
+    let set_profile_limits_route = warp::path("set_profile_limits")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_profile_limits_handler);
+
+    let export_profile_data_route = warp::path("export_profile_data")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<APIExportProfileData>())
+        .and_then(export_profile_data_handler);
+
+    let delete_profile_route = warp::path("delete_profile")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(delete_profile_handler);
+
+    let set_email_account_route = warp::path("set_email_account")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_email_account_handler);
+
+    let get_email_account_route = warp::path("get_email_account")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_email_account_handler);
+
+    let remove_email_account_route = warp::path("remove_email_account")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(remove_email_account_handler);
+
+    let add_watched_folder_route = warp::path("add_watched_folder")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(add_watched_folder_handler);
+
+    let list_watched_folders_route = warp::path("list_watched_folders")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(list_watched_folders_handler);
+
+    let remove_watched_folder_route = warp::path("remove_watched_folder")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(remove_watched_folder_handler);
+
+    let add_cloud_connector_route = warp::path("add_cloud_connector")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(add_cloud_connector_handler);
+
+    let list_cloud_connectors_route = warp::path("list_cloud_connectors")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(list_cloud_connectors_handler);
+
+    let update_cloud_connector_route = warp::path("update_cloud_connector")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(update_cloud_connector_handler);
+
+    let remove_cloud_connector_route = warp::path("remove_cloud_connector")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(remove_cloud_connector_handler);
+
+    let set_calendar_account_route = warp::path("set_calendar_account")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_calendar_account_handler);
+
+    let get_calendar_account_route = warp::path("get_calendar_account")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_calendar_account_handler);
+
+    let remove_calendar_account_route = warp::path("remove_calendar_account")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(remove_calendar_account_handler);
+
+    let create_registration_code_route = warp::path("create_registration_code")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(create_registration_code_handler);
+
+    let get_all_profiles_route = warp::path("all_profiles")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_all_profiles_handler);
+
+    let get_devices_route = warp::path("devices")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<GetDevicesRequest>())
+        .and_then(get_devices_handler);
+
+    let rename_device_route = warp::path("rename_device")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(rename_device_handler);
+
+    let revoke_device_route = warp::path("revoke_device")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(revoke_device_handler);
+
+    let get_registration_codes_route = warp::path("registration_codes")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_registration_codes_handler);
+
+    let revoke_registration_code_route = warp::path("revoke_registration_code")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(revoke_registration_code_handler);
+
     public_keys_route
         .or(health_check_route)
         .or(initial_registration_route)
+        .or(oidc_registration_route)
+        .or(generate_onboarding_keys_route)
+        .or(test_llm_provider_route)
+        .or(initialize_node_route)
         .or(get_local_processing_preference_route)
         .or(update_local_processing_preference_route)
+        .or(get_entity_tagging_preference_route)
+        .or(update_entity_tagging_preference_route)
+        .or(get_summarization_llm_provider_route)
+        .or(update_summarization_llm_provider_route)
         .or(get_default_embedding_model_route)
         .or(get_supported_embedding_models_route)
         .or(update_default_embedding_model_route)
@@ -136,6 +463,46 @@ pub fn general_routes(
         .or(is_pristine_route)
         .or(scan_ollama_models_route)
         .or(add_ollama_models_route)
+        .or(local_inference_metrics_route)
+        .or(get_log_levels_route)
+        .or(set_log_levels_route)
+        .or(get_recent_logs_route)
+        .or(download_logs_route)
+        .or(run_diagnostics_route)
+        .or(run_db_maintenance_route)
+        .or(relocate_storage_route)
+        .or(node_metrics_route)
+        .or(peer_reputations_route)
+        .or(set_peer_ban_route)
+        .or(get_outbound_proxy_settings_route)
+        .or(set_outbound_proxy_settings_route)
+        .or(resolve_identity_name_route)
+        .or(stop_node_route)
+        .or(restart_node_route)
+        .or(get_profile_limits_route)
+        .or(set_profile_limits_route)
+        .or(export_profile_data_route)
+        .or(delete_profile_route)
+        .or(set_email_account_route)
+        .or(get_email_account_route)
+        .or(remove_email_account_route)
+        .or(add_watched_folder_route)
+        .or(list_watched_folders_route)
+        .or(remove_watched_folder_route)
+        .or(add_cloud_connector_route)
+        .or(list_cloud_connectors_route)
+        .or(update_cloud_connector_route)
+        .or(remove_cloud_connector_route)
+        .or(set_calendar_account_route)
+        .or(get_calendar_account_route)
+        .or(remove_calendar_account_route)
+        .or(create_registration_code_route)
+        .or(get_all_profiles_route)
+        .or(get_devices_route)
+        .or(rename_device_route)
+        .or(revoke_device_route)
+        .or(get_registration_codes_route)
+        .or(revoke_registration_code_route)
 }
 
 #[derive(Deserialize)]
@@ -144,6 +511,32 @@ pub struct InitialRegistrationRequest {
     pub profile_identity_pk: String,
 }
 
+#[derive(Deserialize)]
+pub struct OidcRegistrationRequest {
+    /// ID token issued to the user by the configured identity provider
+    pub id_token: String,
+    pub device_name: String,
+    pub device_encryption_pk: String,
+    pub device_identity_pk: String,
+    pub profile_encryption_pk: String,
+    pub profile_identity_pk: String,
+}
+
+#[derive(Deserialize)]
+pub struct GetProfileLimitsRequest {
+    pub profile: String,
+}
+
+#[derive(Deserialize)]
+pub struct GetDevicesRequest {
+    pub profile: String,
+}
+
+#[derive(Deserialize)]
+pub struct ResolveIdentityNameRequest {
+    pub name: String,
+}
+
 #[utoipa::path(
     get,
     path = "/v2/public_keys",
@@ -155,65 +548,1703 @@ pub struct InitialRegistrationRequest {
 pub async fn get_public_keys(sender: Sender<NodeCommand>) -> Result<impl warp::Reply, warp::Rejection> {
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiGetPublicKeys { res: res_sender })
+        .send(NodeCommand::V2ApiGetPublicKeys { res: res_sender })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/health_check",
+    responses(
+        (status = 200, description = "The node is healthy or degraded, with the status of each dependency", body = Value),
+        (status = 503, description = "A dependency the node can't work without is unhealthy", body = Value)
+    )
+)]
+pub async fn health_check(sender: Sender<NodeCommand>, node_name: String) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::APINodeHealth {
+            node_name,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let health = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    // Load balancers stop routing to the node, a degraded node can still serve most requests
+    let status = match health.status {
+        HealthStatus::Unhealthy => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::OK,
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&health), status))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/initial_registration",
+    request_body = ShinkaiMessage,
+    responses(
+        (status = 200, description = "Successfully used registration code", body = APIUseRegistrationCodeSuccessResponse),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn initial_registration_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    payload: InitialRegistrationRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let node_commands_sender = node_commands_sender.clone();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiInitialRegistration {
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Registers a device with an ID token of the identity provider configured with `OIDC_ISSUER_URL`,
+/// instead of a registration code
+#[utoipa::path(
+    post,
+    path = "/v2/oidc_registration",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully registered the device", body = APIUseRegistrationCodeSuccessResponse),
+        (status = 401, description = "Invalid ID token", body = APIError),
+        (status = 403, description = "The user isn't allowed to register devices", body = APIError)
+    )
+)]
+pub async fn oidc_registration_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    payload: OidcRegistrationRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiOidcRegistration {
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Keys for the first profile, for clients that can't generate them. Only available until the node is initialized.
+#[utoipa::path(
+    get,
+    path = "/v2/generate_onboarding_keys",
+    responses(
+        (status = 200, description = "New encryption and identity keys", body = Value),
+        (status = 409, description = "The node is already initialized", body = APIError)
+    )
+)]
+pub async fn generate_onboarding_keys_handler(
+    node_commands_sender: Sender<NodeCommand>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiGenerateOnboardingKeys { res: res_sender })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Sends a short prompt to an LLM provider before it's added, to check its URL, API key and model.
+/// A provider that fails is reported in the result, not as an error. Only available until the node is initialized.
+#[utoipa::path(
+    post,
+    path = "/v2/test_llm_provider",
+    request_body = SerializedLLMProvider,
+    responses(
+        (status = 200, description = "Whether the provider answered, with its answer or error", body = Value),
+        (status = 409, description = "The node is already initialized", body = APIError)
+    )
+)]
+pub async fn test_llm_provider_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    llm_provider: SerializedLLMProvider,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiTestLLMProvider {
+            llm_provider,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Sets up a new node in one call: its embedding model, the main profile with its first device and the
+/// LLM providers of the profile
+#[utoipa::path(
+    post,
+    path = "/v2/initialize_node",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully initialized the node", body = APIUseRegistrationCodeSuccessResponse),
+        (status = 400, description = "Invalid embedding model", body = APIError),
+        (status = 409, description = "The node is already initialized", body = APIError)
+    )
+)]
+pub async fn initialize_node_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    payload: APIInitializeNodeInteractive,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiInitializeNodeInteractive {
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/local_processing_preference",
+    responses(
+        (status = 200, description = "Successfully retrieved local processing preference", body = bool),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_local_processing_preference_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetLocalProcessingPreference {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/local_processing_preference",
+    request_body = bool,
+    responses(
+        (status = 200, description = "Successfully updated local processing preference", body = String),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn update_local_processing_preference_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+    preference: bool,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiUpdateLocalProcessingPreference {
+            bearer,
+            preference,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/entity_tagging_preference",
+    responses(
+        (status = 200, description = "Whether ingested files are tagged with the people, organizations and dates they mention", body = bool),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_entity_tagging_preference_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetEntityTaggingPreference {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/entity_tagging_preference",
+    request_body = bool,
+    responses(
+        (status = 200, description = "Successfully updated entity tagging preference", body = String),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn update_entity_tagging_preference_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    preference: bool,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiUpdateEntityTaggingPreference {
+            bearer,
+            preference,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/summarization_llm_provider",
+    responses(
+        (status = 200, description = "Id of the LLM provider that summarizes ingested files, null if they aren't summarized", body = Option<String>),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_summarization_llm_provider_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetSummarizationLLMProvider {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/summarization_llm_provider",
+    request_body = Option<String>,
+    responses(
+        (status = 200, description = "Successfully updated summarization LLM provider", body = String),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn update_summarization_llm_provider_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    llm_provider_id: Option<String>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiUpdateSummarizationLLMProvider {
+            bearer,
+            llm_provider_id,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/default_embedding_model",
+    responses(
+        (status = 200, description = "Successfully retrieved default embedding model", body = String),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_default_embedding_model_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetDefaultEmbeddingModel {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/supported_embedding_models",
+    responses(
+        (status = 200, description = "Successfully retrieved supported embedding models", body = Vec<String>),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_supported_embedding_models_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetSupportedEmbeddingModels {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/default_embedding_model",
+    request_body = String,
+    responses(
+        (status = 200, description = "Successfully updated default embedding model", body = String),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn update_default_embedding_model_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+    model_name: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiUpdateDefaultEmbeddingModel {
+            bearer,
+            model_name,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/supported_embedding_models",
+    request_body = Vec<String>,
+    responses(
+        (status = 200, description = "Successfully updated supported embedding models", body = String),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn update_supported_embedding_models_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+    models: Vec<String>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiUpdateSupportedEmbeddingModels {
+            bearer,
+            models,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/add_llm_provider",
+    request_body = SerializedLLMProvider,
+    responses(
+        (status = 200, description = "Successfully added LLM provider", body = String),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn add_llm_provider_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+    agent: SerializedLLMProvider,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiAddLlmProvider {
+            bearer,
+            agent,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/remove_llm_provider",
+    request_body = String,
+    responses(
+        (status = 200, description = "Successfully removed LLM provider", body = String),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn remove_llm_provider_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+    llm_provider_id: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiRemoveLlmProvider {
+            bearer,
+            llm_provider_id,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/modify_llm_provider",
+    request_body = SerializedLLMProvider,
+    responses(
+        (status = 200, description = "Successfully modified LLM provider", body = String),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn modify_llm_provider_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+    agent: SerializedLLMProvider,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiModifyLlmProvider {
+            bearer,
+            agent,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/change_node_name",
+    request_body = String,
+    responses(
+        (status = 200, description = "Successfully changed node name", body = String),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn change_node_name_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+    new_name: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiChangeNodesName {
+            bearer,
+            new_name,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(_) => Ok(warp::reply::json(&json!({"status": "success"}))),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/is_pristine",
+    responses(
+        (status = 200, description = "Successfully checked pristine state", body = bool),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn is_pristine_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiIsPristine {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/scan_ollama_models",
+    responses(
+        (status = 200, description = "Successfully scanned Ollama models", body = Vec<serde_json::Value>),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn scan_ollama_models_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiScanOllamaModels {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/add_ollama_models",
+    request_body = APIAddOllamaModels,
+    responses(
+        (status = 200, description = "Successfully added Ollama models", body = String),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn add_ollama_models_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+    payload: APIAddOllamaModels,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiAddOllamaModels {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(_) => Ok(warp::reply::json(&json!({"status": "success"}))),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/local_inference_metrics",
+    responses(
+        (status = 200, description = "Successfully retrieved local inference scheduler metrics", body = LocalInferenceMetrics),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn local_inference_metrics_handler(
+    sender: Sender<NodeCommand>,
+    bearer: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetLocalInferenceMetrics {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/log_levels",
+    responses(
+        (status = 200, description = "Level of every log option, null when it's off", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn get_log_levels_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetLogLevels {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Changes log levels without restarting the node, e.g. `[{ "option": "JobExecution", "level": "Debug" }]`.
+/// A null level turns the option off. The changes last until the node restarts.
+#[utoipa::path(
+    post,
+    path = "/v2/set_log_levels",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Level of every log option after the change", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn set_log_levels_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    log_levels: Vec<LogLevelSetting>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetLogLevels {
+            bearer,
+            log_levels,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/recent_logs",
+    params(
+        ("level" = Option<String>, Query, description = "Only entries of this level or more severe: Error, Info or Debug"),
+        ("module" = Option<String>, Query, description = "Only entries of this log option, e.g. JobExecution"),
+        ("limit" = Option<usize>, Query, description = "Number of entries, 200 by default")
+    ),
+    responses(
+        (status = 200, description = "Most recent log entries, oldest first", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn get_recent_logs_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    query: APIGetRecentLogs,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetRecentLogs {
+            bearer,
+            payload: query,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/download_logs",
+    responses(
+        (status = 200, description = "The node log file", body = String, content_type = "text/plain"),
+        (status = 404, description = "The node doesn't write its logs to a file", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn download_logs_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiDownloadLogs {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(logs) => Ok(Box::new(
+            warp::http::Response::builder()
+                .header("Content-Type", "text/plain; charset=utf-8")
+                .header("Content-Disposition", "attachment; filename=\"shinkai_node.log\"")
+                .body(logs),
+        )),
+        Err(error) => Ok(Box::new(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        ))),
+    }
+}
+
+/// Checks what the node depends on: the embedding server, the Unstructured API, the LLM providers, the database
+/// checksums, the disk space, the clock and the listening ports. Problems are reported in the checks, not as errors.
+#[utoipa::path(
+    get,
+    path = "/v2/run_diagnostics",
+    responses(
+        (status = 200, description = "Status of every check and the worst of them", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn run_diagnostics_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiRunDiagnostics {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Sizes of the column families of the main and VectorFS databases. Compacts them first if `compact` is set, and
+/// sets how often they are compacted on their own if `compaction_interval_hours` is.
+#[utoipa::path(
+    post,
+    path = "/v2/run_db_maintenance",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Sizes of the databases and their compaction schedule", body = DbMaintenanceReport),
+        (status = 500, description = "Internal server error", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn run_db_maintenance_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIRunDbMaintenance,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiRunDbMaintenance {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Moves the databases, secrets and logs of the node to a new data directory, e.g. on a bigger disk. The node
+/// restarts, copies the data, verifies the copy and switches to it, keeping the current data directory if anything
+/// fails.
+#[utoipa::path(
+    post,
+    path = "/v2/relocate_storage",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The node is restarting to move the data", body = Value),
+        (status = 400, description = "The new data directory isn't usable, e.g. not empty or too small", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn relocate_storage_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIRelocateStorage,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiRelocateStorage {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// CPU and memory usage of the node process, its uptime and how many jobs and local inferences are waiting.
+/// The CPU usage is measured since the previous call.
+#[utoipa::path(
+    get,
+    path = "/v2/node_metrics",
+    responses(
+        (status = 200, description = "Resource usage and load of the node", body = NodeMetrics),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn node_metrics_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetNodeMetrics {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Offenses of the peers connecting to the node, and whether they are deprioritized or banned for them
+#[utoipa::path(
+    get,
+    path = "/v2/peer_reputations",
+    responses(
+        (status = 200, description = "Reputation of the peers with offenses or an override", body = Vec<PeerReputationStatus>),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn get_peer_reputations_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetPeerReputations {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Bans (`ban`) or always allows (`allow`) a peer by IP or node name whatever its reputation, or removes the override
+/// and forgets the offenses of the peer (`clear`)
+#[utoipa::path(
+    post,
+    path = "/v2/set_peer_ban",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Reputation of the peers after the change", body = Vec<PeerReputationStatus>),
+        (status = 400, description = "Invalid IP address or node name", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn set_peer_ban_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APISetPeerBan,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetPeerBan {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Proxies the connections to other nodes, LLM providers, embeddings servers and the web go through
+#[utoipa::path(
+    get,
+    path = "/v2/outbound_proxy_settings",
+    responses(
+        (status = 200, description = "Proxy by destination class, and whether Tor is used", body = OutboundProxySettings),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn get_outbound_proxy_settings_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetOutboundProxySettings {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Sets the SOCKS5 or HTTP proxy of each destination class (`node`, `llm_provider`, `embeddings`, `web_tools`), or
+/// sends them all through Tor with `tor`. Connections opened from then on use the new proxies.
+#[utoipa::path(
+    post,
+    path = "/v2/outbound_proxy_settings",
+    request_body = OutboundProxySettings,
+    responses(
+        (status = 200, description = "The saved settings", body = OutboundProxySettings),
+        (status = 400, description = "Invalid proxy URL", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn set_outbound_proxy_settings_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: OutboundProxySettings,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetOutboundProxySettings {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Resolves an ENS name (`alice.eth`) or a did:web DID to its Shinkai identity. The identity is looked up in the
+/// registry and has to have the signature key the name vouches for, if it vouches for one.
+#[utoipa::path(
+    get,
+    path = "/v2/resolve_identity_name",
+    params(
+        ("name" = String, Query, description = "ENS name or DID, e.g. alice.eth or did:web:example.com")
+    ),
+    responses(
+        (status = 200, description = "Shinkai identity of the name, with its keys and addresses", body = Value),
+        (status = 400, description = "The name is neither an ENS name nor a DID", body = APIError),
+        (status = 404, description = "The name doesn't resolve to a valid identity", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn resolve_identity_name_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    query: ResolveIdentityNameRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiResolveIdentityName {
+            bearer,
+            name: query.name,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Stops the node once the response is sent. The process exits.
+#[utoipa::path(
+    post,
+    path = "/v2/stop_node",
+    responses(
+        (status = 200, description = "The node is stopping"),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn stop_node_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiStopNode {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(_) => Ok(warp::reply::with_status(
+            warp::reply::json(&json!({"status": "stopping"})),
+            StatusCode::OK,
+        )),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Runs the node again in the same process once the response is sent, e.g. after changing its environment.
+/// The API is unavailable until the node is back up.
+#[utoipa::path(
+    post,
+    path = "/v2/restart_node",
+    responses(
+        (status = 200, description = "The node is restarting"),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn restart_node_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiRestartNode {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(_) => Ok(warp::reply::with_status(
+            warp::reply::json(&json!({"status": "restarting"})),
+            StatusCode::OK,
+        )),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Limits of a profile, with its LLM token usage this month and the size of its VectorFS
+#[utoipa::path(
+    get,
+    path = "/v2/profile_limits",
+    params(
+        ("profile" = String, Query, description = "Name of the profile, e.g. main")
+    ),
+    responses(
+        (status = 200, description = "Limits and usage of the profile", body = ProfileUsage),
+        (status = 404, description = "The profile doesn't exist", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn get_profile_limits_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    query: GetProfileLimitsRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetProfileLimits {
+            bearer,
+            profile: query.profile,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Replaces the limits of a profile. Limits that aren't set are removed.
+#[utoipa::path(
+    post,
+    path = "/v2/set_profile_limits",
+    request_body = ProfileLimits,
+    responses(
+        (status = 200, description = "The new limits of the profile", body = ProfileLimits),
+        (status = 404, description = "The profile doesn't exist", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn set_profile_limits_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: ProfileLimits,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetProfileLimits {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Everything the node stores for a profile: identity, devices, inboxes with their jobs, LLM providers,
+/// settings and VectorFS
+#[utoipa::path(
+    get,
+    path = "/v2/export_profile_data",
+    params(
+        ("profile" = String, Query, description = "Name of the profile, e.g. main")
+    ),
+    responses(
+        (status = 200, description = "Data of the profile", body = Value),
+        (status = 404, description = "The profile doesn't exist", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn export_profile_data_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIExportProfileData,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiExportProfileData {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Deletes a profile and all its data. The main profile can't be deleted.
+#[utoipa::path(
+    post,
+    path = "/v2/delete_profile",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The profile was deleted", body = Value),
+        (status = 400, description = "The main profile can't be deleted", body = APIError),
+        (status = 404, description = "The profile doesn't exist", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn delete_profile_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIDeleteProfile,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiDeleteProfile {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/set_email_account",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully set the email account", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_email_account_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: Value,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetEmailAccount {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/get_email_account",
+    responses(
+        (status = 200, description = "Successfully retrieved the email account", body = Value),
+        (status = 404, description = "No email account set", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_email_account_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetEmailAccount {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/remove_email_account",
+    responses(
+        (status = 200, description = "Successfully removed the email account", body = Value),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn remove_email_account_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiRemoveEmailAccount {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/add_watched_folder",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully added the watched folder", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn add_watched_folder_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: Value,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiAddWatchedFolder {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/list_watched_folders",
+    responses(
+        (status = 200, description = "Successfully listed the watched folders", body = Value),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn list_watched_folders_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiListWatchedFolders {
+            bearer,
+            res: res_sender,
+        })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => Ok(warp::reply::json(&response)),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
 #[utoipa::path(
-    get,
-    path = "/v2/health_check",
+    post,
+    path = "/v2/remove_watched_folder",
+    request_body = Value,
     responses(
-        (status = 200, description = "Health check successful", body = Value),
+        (status = 200, description = "Successfully removed the watched folder", body = Value),
+        (status = 404, description = "Watched folder not found", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn health_check(sender: Sender<NodeCommand>, node_name: String) -> Result<impl warp::Reply, warp::Rejection> {
-    let version = env!("CARGO_PKG_VERSION");
-
+pub async fn remove_watched_folder_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIRemoveWatchedFolder,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
-
     sender
-        .send(NodeCommand::APIIsPristine { res: res_sender })
+        .send(NodeCommand::V2ApiRemoveWatchedFolder {
+            bearer,
+            payload,
+            res: res_sender,
+        })
         .await
         .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
-    let pristine_state = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
-
-    if let Err(error) = pristine_state {
-        return Ok(warp::reply::json(&json!({ "status": "error", "error": error })));
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
-
-    Ok(warp::reply::json(
-        &json!({ "status": "ok", "version": version, "node_name": node_name, "is_pristine": pristine_state.unwrap() }),
-    ))
 }
 
 #[utoipa::path(
     post,
-    path = "/v2/initial_registration",
-    request_body = ShinkaiMessage,
+    path = "/v2/add_cloud_connector",
+    request_body = Value,
     responses(
-        (status = 200, description = "Successfully used registration code", body = APIUseRegistrationCodeSuccessResponse),
+        (status = 200, description = "Successfully added the cloud connector", body = Value),
         (status = 400, description = "Bad request", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn initial_registration_handler(
-    node_commands_sender: Sender<NodeCommand>,
-    payload: InitialRegistrationRequest,
+pub async fn add_cloud_connector_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: Value,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    let node_commands_sender = node_commands_sender.clone();
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
-    node_commands_sender
-        .send(NodeCommand::V2ApiInitialRegistration {
+    sender
+        .send(NodeCommand::V2ApiAddCloudConnector {
+            bearer,
             payload,
             res: res_sender,
         })
@@ -222,10 +2253,7 @@ pub async fn initial_registration_handler(
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => {
-            let response = create_success_response(response);
-            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
-        }
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
         Err(error) => Ok(warp::reply::with_status(
             warp::reply::json(&error),
             StatusCode::from_u16(error.code).unwrap(),
@@ -235,402 +2263,459 @@ pub async fn initial_registration_handler(
 
 #[utoipa::path(
     get,
-    path = "/v2/local_processing_preference",
+    path = "/v2/list_cloud_connectors",
     responses(
-        (status = 200, description = "Successfully retrieved local processing preference", body = bool),
+        (status = 200, description = "Successfully listed the cloud connectors", body = Value),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn get_local_processing_preference_handler(
+pub async fn list_cloud_connectors_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
+    authorization: String,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiGetLocalProcessingPreference {
+        .send(NodeCommand::V2ApiListCloudConnectors {
             bearer,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => Ok(warp::reply::json(&response)),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
 #[utoipa::path(
     post,
-    path = "/v2/local_processing_preference",
-    request_body = bool,
+    path = "/v2/update_cloud_connector",
+    request_body = Value,
     responses(
-        (status = 200, description = "Successfully updated local processing preference", body = String),
+        (status = 200, description = "Successfully updated the cloud connector", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 404, description = "Cloud connector not found", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn update_local_processing_preference_handler(
+pub async fn update_cloud_connector_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
-    preference: bool,
+    authorization: String,
+    payload: Value,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiUpdateLocalProcessingPreference {
+        .send(NodeCommand::V2ApiUpdateCloudConnector {
             bearer,
-            preference,
+            payload,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => Ok(warp::reply::json(&response)),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
 #[utoipa::path(
-    get,
-    path = "/v2/default_embedding_model",
+    post,
+    path = "/v2/remove_cloud_connector",
+    request_body = Value,
     responses(
-        (status = 200, description = "Successfully retrieved default embedding model", body = String),
+        (status = 200, description = "Successfully removed the cloud connector", body = Value),
+        (status = 404, description = "Cloud connector not found", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn get_default_embedding_model_handler(
+pub async fn remove_cloud_connector_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
+    authorization: String,
+    payload: APIRemoveCloudConnector,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiGetDefaultEmbeddingModel {
+        .send(NodeCommand::V2ApiRemoveCloudConnector {
             bearer,
+            payload,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => Ok(warp::reply::json(&response)),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
 #[utoipa::path(
-    get,
-    path = "/v2/supported_embedding_models",
+    post,
+    path = "/v2/set_calendar_account",
+    request_body = Value,
     responses(
-        (status = 200, description = "Successfully retrieved supported embedding models", body = Vec<String>),
+        (status = 200, description = "Successfully set the calendar account", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn get_supported_embedding_models_handler(
+pub async fn set_calendar_account_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
+    authorization: String,
+    payload: Value,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiGetSupportedEmbeddingModels {
+        .send(NodeCommand::V2ApiSetCalendarAccount {
             bearer,
+            payload,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => Ok(warp::reply::json(&response)),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
 #[utoipa::path(
-    post,
-    path = "/v2/default_embedding_model",
-    request_body = String,
+    get,
+    path = "/v2/get_calendar_account",
     responses(
-        (status = 200, description = "Successfully updated default embedding model", body = String),
+        (status = 200, description = "Successfully retrieved the calendar account, without its tokens", body = Value),
+        (status = 404, description = "No calendar account set", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn update_default_embedding_model_handler(
+pub async fn get_calendar_account_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
-    model_name: String,
+    authorization: String,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiUpdateDefaultEmbeddingModel {
+        .send(NodeCommand::V2ApiGetCalendarAccount {
             bearer,
-            model_name,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => Ok(warp::reply::json(&response)),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
 #[utoipa::path(
     post,
-    path = "/v2/supported_embedding_models",
-    request_body = Vec<String>,
+    path = "/v2/remove_calendar_account",
     responses(
-        (status = 200, description = "Successfully updated supported embedding models", body = String),
+        (status = 200, description = "Successfully removed the calendar account", body = Value),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn update_supported_embedding_models_handler(
+pub async fn remove_calendar_account_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
-    models: Vec<String>,
+    authorization: String,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiUpdateSupportedEmbeddingModels {
+        .send(NodeCommand::V2ApiRemoveCalendarAccount {
             bearer,
-            models,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => Ok(warp::reply::json(&response)),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
 #[utoipa::path(
     post,
-    path = "/v2/add_llm_provider",
-    request_body = SerializedLLMProvider,
+    path = "/v2/create_registration_code",
+    request_body = RegistrationCodeRequest,
     responses(
-        (status = 200, description = "Successfully added LLM provider", body = String),
+        (status = 200, description = "Registration code for a new device or profile", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn add_llm_provider_handler(
+pub async fn create_registration_code_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
-    agent: SerializedLLMProvider,
+    authorization: String,
+    payload: RegistrationCodeRequest,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiAddLlmProvider {
+        .send(NodeCommand::V2ApiCreateRegistrationCode {
             bearer,
-            agent,
+            payload,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => Ok(warp::reply::json(&response)),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(code) => {
+            let response = create_success_response(json!({ "code": code }));
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
 #[utoipa::path(
-    post,
-    path = "/v2/remove_llm_provider",
-    request_body = String,
+    get,
+    path = "/v2/all_profiles",
     responses(
-        (status = 200, description = "Successfully removed LLM provider", body = String),
-        (status = 500, description = "Internal server error", body = APIError)
+        (status = 200, description = "Profiles of the node", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
     )
 )]
-pub async fn remove_llm_provider_handler(
+pub async fn get_all_profiles_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
-    llm_provider_id: String,
+    authorization: String,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiRemoveLlmProvider {
+        .send(NodeCommand::V2ApiGetAllProfiles {
             bearer,
-            llm_provider_id,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => Ok(warp::reply::json(&response)),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(profiles) => Ok(warp::reply::with_status(warp::reply::json(&profiles), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
+/// Devices registered to a profile, with their display names and when they were last seen
 #[utoipa::path(
-    post,
-    path = "/v2/modify_llm_provider",
-    request_body = SerializedLLMProvider,
+    get,
+    path = "/v2/devices",
+    params(
+        ("profile" = String, Query, description = "Name of the profile, e.g. main")
+    ),
     responses(
-        (status = 200, description = "Successfully modified LLM provider", body = String),
-        (status = 500, description = "Internal server error", body = APIError)
+        (status = 200, description = "Devices of the profile", body = Value),
+        (status = 404, description = "The profile doesn't exist", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
     )
 )]
-pub async fn modify_llm_provider_handler(
+pub async fn get_devices_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
-    agent: SerializedLLMProvider,
+    authorization: String,
+    query: GetDevicesRequest,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiModifyLlmProvider {
+        .send(NodeCommand::V2ApiGetDevices {
             bearer,
-            agent,
+            profile: query.profile,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => Ok(warp::reply::json(&response)),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(devices) => Ok(warp::reply::with_status(warp::reply::json(&devices), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
 #[utoipa::path(
     post,
-    path = "/v2/change_node_name",
-    request_body = String,
+    path = "/v2/rename_device",
+    request_body = Value,
     responses(
-        (status = 200, description = "Successfully changed node name", body = String),
-        (status = 500, description = "Internal server error", body = APIError)
+        (status = 200, description = "Successfully renamed the device", body = Value),
+        (status = 404, description = "The device doesn't exist", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
     )
 )]
-pub async fn change_node_name_handler(
+pub async fn rename_device_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
-    new_name: String,
+    authorization: String,
+    payload: APIRenameDevice,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiChangeNodesName {
+        .send(NodeCommand::V2ApiRenameDevice {
             bearer,
-            new_name,
+            payload,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(_) => Ok(warp::reply::json(&json!({"status": "success"}))),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
+/// Removes a device and revokes its keys. Its messages are rejected from then on.
 #[utoipa::path(
-    get,
-    path = "/v2/is_pristine",
+    post,
+    path = "/v2/revoke_device",
+    request_body = Value,
     responses(
-        (status = 200, description = "Successfully checked pristine state", body = bool),
-        (status = 500, description = "Internal server error", body = APIError)
+        (status = 200, description = "Successfully revoked the device", body = Value),
+        (status = 404, description = "The device doesn't exist", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
     )
 )]
-pub async fn is_pristine_handler(
+pub async fn revoke_device_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
+    authorization: String,
+    payload: APIRevokeDevice,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiIsPristine {
+        .send(NodeCommand::V2ApiRevokeDevice {
             bearer,
+            payload,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => Ok(warp::reply::json(&response)),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
+/// Registration codes that can still be used, i.e. not used up, expired or revoked
 #[utoipa::path(
     get,
-    path = "/v2/scan_ollama_models",
+    path = "/v2/registration_codes",
     responses(
-        (status = 200, description = "Successfully scanned Ollama models", body = Vec<serde_json::Value>),
-        (status = 500, description = "Internal server error", body = APIError)
+        (status = 200, description = "Outstanding registration codes", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
     )
 )]
-pub async fn scan_ollama_models_handler(
+pub async fn get_registration_codes_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
+    authorization: String,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiScanOllamaModels {
+        .send(NodeCommand::V2ApiGetRegistrationCodes {
             bearer,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => Ok(warp::reply::json(&response)),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(codes) => Ok(warp::reply::with_status(warp::reply::json(&codes), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
 #[utoipa::path(
     post,
-    path = "/v2/add_ollama_models",
-    request_body = APIAddOllamaModels,
+    path = "/v2/revoke_registration_code",
+    request_body = Value,
     responses(
-        (status = 200, description = "Successfully added Ollama models", body = String),
-        (status = 500, description = "Internal server error", body = APIError)
+        (status = 200, description = "Successfully revoked the registration code", body = Value),
+        (status = 400, description = "The registration code doesn't exist", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
     )
 )]
-pub async fn add_ollama_models_handler(
+pub async fn revoke_registration_code_handler(
     sender: Sender<NodeCommand>,
-    bearer: String,
-    payload: APIAddOllamaModels,
+    authorization: String,
+    payload: APIRevokeRegistrationCode,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     sender
-        .send(NodeCommand::V2ApiAddOllamaModels {
+        .send(NodeCommand::V2ApiRevokeRegistrationCode {
             bearer,
             payload,
             res: res_sender,
         })
         .await
         .map_err(|_| warp::reject::reject())?;
-
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(_) => Ok(warp::reply::json(&json!({"status": "success"}))),
-        Err(error) => Err(warp::reject::custom(error)),
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
     }
 }
 
@@ -640,8 +2725,16 @@ pub async fn add_ollama_models_handler(
         get_public_keys,
         health_check,
         initial_registration_handler,
+        oidc_registration_handler,
+        generate_onboarding_keys_handler,
+        test_llm_provider_handler,
+        initialize_node_handler,
         get_local_processing_preference_handler,
         update_local_processing_preference_handler,
+        get_entity_tagging_preference_handler,
+        update_entity_tagging_preference_handler,
+        get_summarization_llm_provider_handler,
+        update_summarization_llm_provider_handler,
         get_default_embedding_model_handler,
         get_supported_embedding_models_handler,
         update_default_embedding_model_handler,
@@ -653,9 +2746,49 @@ pub async fn add_ollama_models_handler(
         is_pristine_handler,
         scan_ollama_models_handler,
         add_ollama_models_handler,
+        local_inference_metrics_handler,
+        get_log_levels_handler,
+        set_log_levels_handler,
+        get_recent_logs_handler,
+        download_logs_handler,
+        run_diagnostics_handler,
+        run_db_maintenance_handler,
+        relocate_storage_handler,
+        node_metrics_handler,
+        get_peer_reputations_handler,
+        set_peer_ban_handler,
+        get_outbound_proxy_settings_handler,
+        set_outbound_proxy_settings_handler,
+        resolve_identity_name_handler,
+        stop_node_handler,
+        restart_node_handler,
+        get_profile_limits_handler,
+        set_profile_limits_handler,
+        export_profile_data_handler,
+        delete_profile_handler,
+        set_email_account_handler,
+        get_email_account_handler,
+        remove_email_account_handler,
+        add_watched_folder_handler,
+        list_watched_folders_handler,
+        remove_watched_folder_handler,
+        add_cloud_connector_handler,
+        list_cloud_connectors_handler,
+        update_cloud_connector_handler,
+        remove_cloud_connector_handler,
+        set_calendar_account_handler,
+        get_calendar_account_handler,
+        remove_calendar_account_handler,
+        create_registration_code_handler,
+        get_all_profiles_handler,
+        get_devices_handler,
+        rename_device_handler,
+        revoke_device_handler,
+        get_registration_codes_handler,
+        revoke_registration_code_handler,
     ),
     components(
-        schemas(GetPublicKeysResponse, APIError)
+        schemas(GetPublicKeysResponse, APIError, NodeMetrics, CircuitBreakerStatus, CircuitState, ProfileLimits, ProfileUsage, DbMaintenanceReport, DbCompactionSchedule, ColumnFamilySize, PeerReputationStatus, PeerBanOverride, OutboundProxySettings, DestinationClass)
     ),
     tags(
         (name = "general", description = "General API endpoints")
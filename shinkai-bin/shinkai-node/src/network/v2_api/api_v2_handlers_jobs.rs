@@ -2,18 +2,21 @@ use async_channel::Sender;
 use bytes::Buf;
 use futures::StreamExt;
 use reqwest::StatusCode;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{APIChangeJobAgentRequest, JobCreationInfo, JobMessage};
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{APIChangeJobAgentRequest, APICreateJobForPath, APIGetJobStatus, APISetFolderAgent, APISetJobPlanning, APISetJobRetrievalConfig, JobCreationInfo, JobMessage};
 use utoipa::OpenApi;
 use warp::multipart::FormData;
 use warp::Filter;
 
+use crate::llm_provider::job_status::{JobState, JobStatus};
 use crate::network::{
     node_api_router::{APIError, SendResponseBody, SendResponseBodyData},
     node_commands::NodeCommand,
 };
+use crate::schemas::inbox_digest::{InboxDigestFrequency, InboxDigestSchedule, InboxDigestSettings};
 
+use super::api_v2_idempotency::{run_idempotent, with_idempotency_key};
 use super::api_v2_router::{create_success_response, with_sender};
 
 pub fn job_routes(
@@ -24,13 +27,36 @@ pub fn job_routes(
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
         .and(warp::header::<String>("authorization"))
+        .and(with_idempotency_key())
         .and(warp::body::json())
         .and_then(create_job_handler);
 
+    let create_job_for_path_route = warp::path("create_job_for_path")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(with_idempotency_key())
+        .and(warp::body::json())
+        .and_then(create_job_for_path_handler);
+
+    let set_folder_agent_route = warp::path("set_folder_agent")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_folder_agent_handler);
+
+    let folder_agents_route = warp::path("folder_agents")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(folder_agents_handler);
+
     let job_message_route = warp::path("job_message")
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
         .and(warp::header::<String>("authorization"))
+        .and(with_idempotency_key())
         .and(warp::body::json())
         .and_then(job_message_handler);
 
@@ -45,6 +71,7 @@ pub fn job_routes(
         .and(warp::get())
         .and(with_sender(node_commands_sender.clone()))
         .and(warp::header::<String>("authorization"))
+        .and(warp::query::<SmartInboxesQuery>())
         .and_then(get_all_smart_inboxes_handler);
 
     let available_llm_providers_route = warp::path("available_models")
@@ -60,6 +87,94 @@ pub fn job_routes(
         .and(warp::body::json())
         .and_then(update_smart_inbox_name_handler);
 
+    let move_inboxes_to_folder_route = warp::path("move_inboxes_to_folder")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(move_inboxes_to_folder_handler);
+
+    let set_inbox_labels_route = warp::path("set_inbox_labels")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_inbox_labels_handler);
+
+    let inbox_folders_route = warp::path("inbox_folders")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(inbox_folders_handler);
+
+    let archive_inbox_route = warp::path("archive_inbox")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(archive_inbox_handler);
+
+    let mute_inbox_route = warp::path("mute_inbox")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(mute_inbox_handler);
+
+    let inbox_digest_route = warp::path("inbox_digest")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(inbox_digest_handler);
+
+    let set_inbox_digest_route = warp::path("set_inbox_digest")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_inbox_digest_handler);
+
+    let remove_inbox_digest_route = warp::path("remove_inbox_digest")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(remove_inbox_digest_handler);
+
+    let edit_message_route = warp::path("edit_message")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(edit_message_handler);
+
+    let delete_message_route = warp::path("delete_message")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(delete_message_handler);
+
+    let message_history_route = warp::path("message_history")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<MessageHistoryQuery>())
+        .and_then(message_history_handler);
+
+    let send_message_with_attachments_route = warp::path("send_message_with_attachments")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(send_message_with_attachments_handler);
+
+    let download_attachment_route = warp::path("download_attachment")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<DownloadAttachmentQuery>())
+        .and_then(download_attachment_handler);
+
     let create_files_inbox_route = warp::path("create_files_inbox")
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
@@ -80,24 +195,120 @@ pub fn job_routes(
         .and(warp::body::json())
         .and_then(change_job_llm_provider_handler);
 
+    let update_job_to_finished_route = warp::path("update_job_to_finished")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(update_job_to_finished_handler);
+
+    let job_status_route = warp::path("job_status")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<APIGetJobStatus>())
+        .and_then(job_status_handler);
+
+    let set_job_planning_route = warp::path("set_job_planning")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_job_planning_handler);
+
+    let job_plan_route = warp::path("job_plan")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<JobPlanQuery>())
+        .and_then(job_plan_handler);
+
+    let set_job_retrieval_config_route = warp::path("set_job_retrieval_config")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_job_retrieval_config_handler);
+
+    let job_retrieval_config_route = warp::path("job_retrieval_config")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<JobPlanQuery>())
+        .and_then(job_retrieval_config_handler);
+
+    let job_message_citations_route = warp::path("job_message_citations")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<JobMessageCitationsQuery>())
+        .and_then(job_message_citations_handler);
+
+    let mark_as_read_up_to_route = warp::path("mark_as_read_up_to")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(mark_as_read_up_to_handler);
+
+    let add_inbox_permission_route = warp::path("add_inbox_permission")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(add_inbox_permission_handler);
+
+    let remove_inbox_permission_route = warp::path("remove_inbox_permission")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(remove_inbox_permission_handler);
+
     create_job_route
+        .or(create_job_for_path_route)
+        .or(set_folder_agent_route)
+        .or(folder_agents_route)
         .or(job_message_route)
         .or(get_last_messages_route)
         .or(get_all_smart_inboxes_route)
         .or(available_llm_providers_route)
         .or(update_smart_inbox_name_route)
+        .or(move_inboxes_to_folder_route)
+        .or(set_inbox_labels_route)
+        .or(inbox_folders_route)
+        .or(archive_inbox_route)
+        .or(mute_inbox_route)
+        .or(inbox_digest_route)
+        .or(set_inbox_digest_route)
+        .or(remove_inbox_digest_route)
+        .or(edit_message_route)
+        .or(delete_message_route)
+        .or(message_history_route)
+        .or(send_message_with_attachments_route)
+        .or(download_attachment_route)
         .or(create_files_inbox_route)
         .or(add_file_to_inbox_route)
         .or(change_job_llm_provider_route)
+        .or(update_job_to_finished_route)
+        .or(job_status_route)
+        .or(set_job_planning_route)
+        .or(job_plan_route)
+        .or(set_job_retrieval_config_route)
+        .or(job_retrieval_config_route)
+        .or(job_message_citations_route)
+        .or(mark_as_read_up_to_route)
+        .or(add_inbox_permission_route)
+        .or(remove_inbox_permission_route)
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct CreateJobRequest {
     pub job_creation_info: JobCreationInfo,
     pub llm_provider: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct JobMessageRequest {
     pub job_message: JobMessage,
 }
@@ -115,38 +326,1284 @@ pub struct UpdateSmartInboxNameRequest {
     pub custom_name: String,
 }
 
-#[derive(Deserialize)]
-pub struct AddFileToInboxRequest {
-    pub file_inbox_name: String,
-    pub filename: String,
-    pub file: Vec<u8>,
+#[derive(Deserialize)]
+pub struct SmartInboxesQuery {
+    /// Only the inboxes in this folder
+    pub folder: Option<String>,
+    /// Only the inboxes tagged with this label
+    pub label: Option<String>,
+    /// Also list the archived inboxes
+    pub include_archived: Option<bool>,
+    /// List the message requests instead of the inboxes
+    pub message_requests: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct MoveInboxesToFolderRequest {
+    pub inbox_names: Vec<String>,
+    /// None moves the inboxes out of their folder
+    pub folder: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SetInboxLabelsRequest {
+    pub inbox_name: String,
+    pub labels: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ArchiveInboxRequest {
+    pub inbox_name: String,
+    pub archived: bool,
+}
+
+#[derive(Deserialize)]
+pub struct MuteInboxRequest {
+    pub inbox_name: String,
+    pub muted: bool,
+}
+
+#[derive(Deserialize)]
+pub struct EditMessageRequest {
+    pub inbox_name: String,
+    /// `node_message_hash` of the message
+    pub message_hash: String,
+    pub new_content: String,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteMessageRequest {
+    pub inbox_name: String,
+    /// `node_message_hash` of the message
+    pub message_hash: String,
+}
+
+#[derive(Deserialize)]
+pub struct MessageHistoryQuery {
+    pub inbox_name: String,
+    /// `node_message_hash` of the message
+    pub message_hash: String,
+}
+
+#[derive(Deserialize)]
+pub struct SendMessageWithAttachmentsRequest {
+    /// Regular inbox the message is sent to
+    pub inbox_name: String,
+    pub content: String,
+    /// Files inbox with the files to attach, from `/v2/create_files_inbox`
+    pub files_inbox: String,
+}
+
+#[derive(Deserialize)]
+pub struct DownloadAttachmentQuery {
+    pub inbox_name: String,
+    /// `node_message_hash` of the message
+    pub message_hash: String,
+    pub attachment_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct AddFileToInboxRequest {
+    pub file_inbox_name: String,
+    pub filename: String,
+    pub file: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateJobToFinishedRequest {
+    pub job_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct MarkAsReadUpToRequest {
+    pub inbox_name: String,
+    pub up_to_time: String,
+}
+
+#[derive(Deserialize)]
+pub struct InboxPermissionRequest {
+    pub inbox_name: String,
+    /// Full name of the profile, e.g. `@@node.shinkai/profile`
+    pub profile: String,
+    /// Read, Write or Admin. Only used when adding a permission.
+    #[serde(default = "default_inbox_permission")]
+    pub permission: String,
+}
+
+fn default_inbox_permission() -> String {
+    "Read".to_string()
+}
+
+// Code
+
+#[utoipa::path(
+    post,
+    path = "/v2/create_job",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")
+    ),
+    request_body = CreateJobRequest,
+    responses(
+        (status = 200, description = "Successfully created job", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn create_job_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    idempotency_key: Option<String>,
+    payload: CreateJobRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let request = serde_json::to_vec(&payload).unwrap_or_default();
+    let result = run_idempotent(
+        &node_commands_sender,
+        &bearer,
+        idempotency_key,
+        "create_job",
+        &request,
+        async {
+            let (res_sender, res_receiver) = async_channel::bounded(1);
+            node_commands_sender
+                .send(NodeCommand::V2ApiCreateJob {
+                    bearer: bearer.clone(),
+                    job_creation_info: payload.job_creation_info,
+                    llm_provider: payload.llm_provider,
+                    res: res_sender,
+                })
+                .await
+                .map_err(|_| warp::reject::reject())?;
+            res_receiver.recv().await.map_err(|_| warp::reject::reject())
+        },
+    )
+    .await?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(json!({ "job_id": response }));
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Creates a job that chats with a VectorFS folder, using the agent bound to the folder unless one is set
+#[utoipa::path(
+    post,
+    path = "/v2/create_job_for_path",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")
+    ),
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully created job", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 404, description = "Folder not found", body = APIError)
+    )
+)]
+pub async fn create_job_for_path_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    idempotency_key: Option<String>,
+    payload: APICreateJobForPath,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let request = serde_json::to_vec(&payload).unwrap_or_default();
+    let result = run_idempotent(
+        &node_commands_sender,
+        &bearer,
+        idempotency_key,
+        "create_job_for_path",
+        &request,
+        async {
+            let (res_sender, res_receiver) = async_channel::bounded(1);
+            node_commands_sender
+                .send(NodeCommand::V2ApiCreateJobForPath {
+                    bearer: bearer.clone(),
+                    payload,
+                    res: res_sender,
+                })
+                .await
+                .map_err(|_| warp::reject::reject())?;
+            res_receiver.recv().await.map_err(|_| warp::reject::reject())
+        },
+    )
+    .await?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(json!({ "job_id": response }));
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Binds the default agent of the jobs created for a folder, or removes the binding if no agent is set
+#[utoipa::path(
+    post,
+    path = "/v2/set_folder_agent",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Default agent of the folder updated", body = Value),
+        (status = 404, description = "Folder or agent not found", body = APIError)
+    )
+)]
+pub async fn set_folder_agent_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APISetFolderAgent,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiSetFolderAgent {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(json!({ "result": response }));
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/folder_agents",
+    responses(
+        (status = 200, description = "The default agents of the folders", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn folder_agents_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiListFolderAgents {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/job_message",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")
+    ),
+    request_body = JobMessageRequest,
+    responses(
+        (status = 200, description = "Successfully processed job message", body = SendResponseBody),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn job_message_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    idempotency_key: Option<String>,
+    payload: JobMessageRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let request = serde_json::to_vec(&payload).unwrap_or_default();
+    let result = run_idempotent(
+        &node_commands_sender,
+        &bearer,
+        idempotency_key,
+        "job_message",
+        &request,
+        async {
+            let (res_sender, res_receiver) = async_channel::bounded(1);
+            node_commands_sender
+                .send(NodeCommand::V2ApiJobMessage {
+                    bearer: bearer.clone(),
+                    job_message: payload.job_message,
+                    res: res_sender,
+                })
+                .await
+                .map_err(|_| warp::reject::reject())?;
+            res_receiver.recv().await.map_err(|_| warp::reject::reject())
+        },
+    )
+    .await?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/last_messages",
+    request_body = GetLastMessagesRequest,
+    responses(
+        (status = 200, description = "Successfully retrieved last messages", body = Vec<V2ChatMessage>),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_last_messages_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: GetLastMessagesRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let node_commands_sender = node_commands_sender.clone();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiGetLastMessagesFromInbox {
+            bearer,
+            inbox_name: payload.inbox_name,
+            limit: payload.limit,
+            offset_key: payload.offset_key,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/all_inboxes",
+    params(
+        ("folder" = Option<String>, Query, description = "Only the inboxes in this folder"),
+        ("label" = Option<String>, Query, description = "Only the inboxes tagged with this label"),
+        ("include_archived" = Option<bool>, Query, description = "Also list the archived inboxes"),
+        ("message_requests" = Option<bool>, Query, description = "List the message requests instead of the inboxes")
+    ),
+    responses(
+        (status = 200, description = "Successfully retrieved all smart inboxes", body = Vec<V2SmartInbox>),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_all_smart_inboxes_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    query: SmartInboxesQuery,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let node_commands_sender = node_commands_sender.clone();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiGetAllSmartInboxes {
+            bearer,
+            folder: query.folder,
+            label: query.label,
+            include_archived: query.include_archived.unwrap_or(false),
+            message_requests: query.message_requests.unwrap_or(false),
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/available_llm_providers",
+    responses(
+        (status = 200, description = "Successfully retrieved available LLM providers", body = Vec<SerializedLLMProvider>),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_available_llm_providers_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let node_commands_sender = node_commands_sender.clone();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiAvailableLLMProviders {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/update_smart_inbox_name",
+    request_body = UpdateSmartInboxNameRequest,
+    responses(
+        (status = 200, description = "Successfully updated smart inbox name", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn update_smart_inbox_name_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: UpdateSmartInboxNameRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiUpdateSmartInboxName {
+            bearer,
+            inbox_name: payload.inbox_name,
+            custom_name: payload.custom_name,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/move_inboxes_to_folder",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully moved the inboxes to the folder", body = Value),
+        (status = 404, description = "Inbox not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn move_inboxes_to_folder_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: MoveInboxesToFolderRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiMoveInboxesToFolder {
+            bearer,
+            inbox_names: payload.inbox_names,
+            folder: payload.folder,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/set_inbox_labels",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully replaced the labels of the inbox", body = Value),
+        (status = 404, description = "Inbox not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_inbox_labels_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: SetInboxLabelsRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiSetInboxLabels {
+            bearer,
+            inbox_name: payload.inbox_name,
+            labels: payload.labels,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/inbox_folders",
+    responses(
+        (status = 200, description = "Folders and labels of the inboxes with how many inboxes each one has", body = Value),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn inbox_folders_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiGetInboxFolders {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/archive_inbox",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully archived or unarchived the inbox", body = Value),
+        (status = 404, description = "Inbox not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn archive_inbox_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: ArchiveInboxRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiSetInboxArchived {
+            bearer,
+            inbox_name: payload.inbox_name,
+            archived: payload.archived,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/mute_inbox",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully muted or unmuted the inbox", body = Value),
+        (status = 404, description = "Inbox not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn mute_inbox_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: MuteInboxRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiSetInboxMuted {
+            bearer,
+            inbox_name: payload.inbox_name,
+            muted: payload.muted,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/inbox_digest",
+    responses(
+        (status = 200, description = "Digest schedule of the profile, null if it doesn't get digests", body = InboxDigestSchedule),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn inbox_digest_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiGetInboxDigest {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Schedules daily or weekly digests of the unread messages and finished jobs, written by the given agent and
+/// posted to an inbox of their own. Digests can also be emailed from the email account of the profile.
+#[utoipa::path(
+    post,
+    path = "/v2/set_inbox_digest",
+    request_body = InboxDigestSettings,
+    responses(
+        (status = 200, description = "The digest schedule", body = InboxDigestSchedule),
+        (status = 404, description = "Agent not found", body = APIError),
+        (status = 422, description = "Digests can't be emailed by this node", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_inbox_digest_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: InboxDigestSettings,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiSetInboxDigest {
+            bearer,
+            settings: payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/remove_inbox_digest",
+    responses(
+        (status = 200, description = "Successfully stopped the digests", body = Value),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn remove_inbox_digest_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiRemoveInboxDigest {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/edit_message",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully edited the message", body = Value),
+        (status = 403, description = "The message was sent by someone else", body = APIError),
+        (status = 404, description = "Message not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn edit_message_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: EditMessageRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiChangeInboxMessage {
+            bearer,
+            inbox_name: payload.inbox_name,
+            message_hash: payload.message_hash,
+            new_content: Some(payload.new_content),
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/delete_message",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully deleted the message", body = Value),
+        (status = 403, description = "The message was sent by someone else", body = APIError),
+        (status = 404, description = "Message not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn delete_message_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: DeleteMessageRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiChangeInboxMessage {
+            bearer,
+            inbox_name: payload.inbox_name,
+            message_hash: payload.message_hash,
+            new_content: None,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/message_history",
+    params(
+        ("inbox_name" = String, Query, description = "Inbox of the message"),
+        ("message_hash" = String, Query, description = "Node message hash of the message")
+    ),
+    responses(
+        (status = 200, description = "Edits of the message, oldest first, and when it was deleted", body = Value),
+        (status = 404, description = "Inbox not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn message_history_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    query: MessageHistoryQuery,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiGetInboxMessageHistory {
+            bearer,
+            inbox_name: query.inbox_name,
+            message_hash: query.message_hash,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/send_message_with_attachments",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully sent the message with its attachments", body = SendResponseBodyData),
+        (status = 400, description = "Invalid inbox or files", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn send_message_with_attachments_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: SendMessageWithAttachmentsRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiSendMessageWithAttachments {
+            bearer,
+            inbox_name: payload.inbox_name,
+            content: payload.content,
+            files_inbox: payload.files_inbox,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Size of the chunks the attachments are streamed in
+const ATTACHMENT_STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+#[utoipa::path(
+    get,
+    path = "/v2/download_attachment",
+    params(
+        ("inbox_name" = String, Query, description = "Inbox of the message"),
+        ("message_hash" = String, Query, description = "Node message hash of the message"),
+        ("attachment_id" = String, Query, description = "Attachment of the message")
+    ),
+    responses(
+        (status = 200, description = "The decrypted file", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 404, description = "Message or attachment not found", body = APIError),
+        (status = 409, description = "The attachment is still being received", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn download_attachment_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    query: DownloadAttachmentQuery,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiDownloadAttachment {
+            bearer,
+            inbox_name: query.inbox_name,
+            message_hash: query.message_hash,
+            attachment_id: query.attachment_id,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok((file_name, file)) => {
+            let chunks: Vec<Result<bytes::Bytes, std::io::Error>> = file
+                .chunks(ATTACHMENT_STREAM_CHUNK_SIZE)
+                .map(|chunk| Ok(bytes::Bytes::copy_from_slice(chunk)))
+                .collect();
+            Ok(Box::new(
+                warp::http::Response::builder()
+                    .header("Content-Type", "application/octet-stream")
+                    .header("Content-Disposition", format!("attachment; filename=\"{}\"", file_name))
+                    .body(warp::hyper::Body::wrap_stream(futures::stream::iter(chunks))),
+            ))
+        }
+        Err(error) => Ok(Box::new(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        ))),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/create_files_inbox",
+    responses(
+        (status = 200, description = "Successfully created files inbox", body = String),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn create_files_inbox_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiCreateFilesInbox {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/add_file_to_inbox",
+    request_body = AddFileToInboxRequest,
+    responses(
+        (status = 200, description = "Successfully added file to inbox", body = String),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn add_file_to_inbox_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    mut form: FormData,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let mut file_inbox_name = String::new();
+    let mut filename = String::new();
+    let mut file_data = Vec::new();
+
+    while let Some(part) = form.next().await {
+        let mut part = part.map_err(|e| {
+            eprintln!("Error collecting form data: {:?}", e);
+            warp::reject::custom(APIError::new(
+                StatusCode::BAD_REQUEST,
+                "Bad Request",
+                format!("Failed to collect form data: {:?}", e).as_str(),
+            ))
+        })?;
+        match part.name() {
+            "file_inbox_name" => {
+                let content = part.data().await.ok_or_else(|| {
+                    warp::reject::custom(APIError::new(
+                        StatusCode::BAD_REQUEST,
+                        "Bad Request",
+                        "Missing file_inbox_name",
+                    ))
+                })?;
+                let mut content = content.map_err(|e| {
+                    warp::reject::custom(APIError::new(
+                        StatusCode::BAD_REQUEST,
+                        "Bad Request",
+                        format!("Failed to read file_inbox_name: {:?}", e).as_str(),
+                    ))
+                })?;
+                file_inbox_name =
+                    String::from_utf8(content.copy_to_bytes(content.remaining()).to_vec()).map_err(|_| {
+                        warp::reject::custom(APIError::new(
+                            StatusCode::BAD_REQUEST,
+                            "Bad Request",
+                            "Invalid UTF-8 in file_inbox_name",
+                        ))
+                    })?;
+            }
+            "filename" => {
+                let content = part.data().await.ok_or_else(|| {
+                    warp::reject::custom(APIError::new(
+                        StatusCode::BAD_REQUEST,
+                        "Bad Request",
+                        "Missing filename",
+                    ))
+                })?;
+                let mut content = content.map_err(|_| {
+                    warp::reject::custom(APIError::new(
+                        StatusCode::BAD_REQUEST,
+                        "Bad Request",
+                        "Failed to read filename",
+                    ))
+                })?;
+                filename = String::from_utf8(content.copy_to_bytes(content.remaining()).to_vec()).map_err(|_| {
+                    warp::reject::custom(APIError::new(
+                        StatusCode::BAD_REQUEST,
+                        "Bad Request",
+                        "Invalid UTF-8 in filename",
+                    ))
+                })?;
+            }
+            "file_data" => {
+                while let Some(content) = part.data().await {
+                    let mut content = content.map_err(|_| {
+                        warp::reject::custom(APIError::new(
+                            StatusCode::BAD_REQUEST,
+                            "Bad Request",
+                            "Failed to read file data",
+                        ))
+                    })?;
+                    file_data.extend_from_slice(&content.copy_to_bytes(content.remaining()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if file_data.is_empty() {
+        return Err(warp::reject::custom(APIError::new(
+            StatusCode::BAD_REQUEST,
+            "Bad Request",
+            "No file data found. Check that the file is being uploaded correctly in the `field_data` field",
+        )));
+    }
+
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiAddFileToInbox {
+            bearer,
+            file_inbox_name,
+            filename,
+            file: file_data,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| {
+            warp::reject::custom(APIError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal Server Error",
+                "Failed to send command",
+            ))
+        })?;
+    let result = res_receiver.recv().await.map_err(|_| {
+        warp::reject::custom(APIError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Internal Server Error",
+            "Failed to receive response",
+        ))
+    })?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/change_job_llm_provider",
+    request_body = APIChangeJobAgentRequest,
+    responses(
+        (status = 200, description = "Successfully changed job LLM provider", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn change_job_llm_provider_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIChangeJobAgentRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiChangeJobLlmProvider {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(json!({ "result": response }));
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
 }
 
-// Code
-
 #[utoipa::path(
     post,
-    path = "/v2/create_job",
-    request_body = CreateJobRequest,
+    path = "/v2/update_job_to_finished",
+    request_body = UpdateJobToFinishedRequest,
     responses(
-        (status = 200, description = "Successfully created job", body = Value),
+        (status = 200, description = "Successfully marked the job as finished", body = Value),
         (status = 400, description = "Bad request", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn create_job_handler(
+pub async fn update_job_to_finished_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
-    payload: CreateJobRequest,
+    payload: UpdateJobToFinishedRequest,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
-    let node_commands_sender = node_commands_sender.clone();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     node_commands_sender
-        .send(NodeCommand::V2ApiCreateJob {
+        .send(NodeCommand::V2ApiUpdateJobToFinished {
             bearer,
-            job_creation_info: payload.job_creation_info,
-            llm_provider: payload.llm_provider,
+            job_id: payload.job_id,
             res: res_sender,
         })
         .await
@@ -155,7 +1612,7 @@ pub async fn create_job_handler(
 
     match result {
         Ok(response) => {
-            let response = create_success_response(json!({ "job_id": response }));
+            let response = create_success_response(json!({ "result": response }));
             Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
         }
         Err(error) => Ok(warp::reply::with_status(
@@ -166,27 +1623,29 @@ pub async fn create_job_handler(
 }
 
 #[utoipa::path(
-    post,
-    path = "/v2/job_message",
-    request_body = JobMessageRequest,
+    get,
+    path = "/v2/job_status",
+    params(
+        ("job_id" = String, Query, description = "Job to get the status of"),
+        ("wait_ms" = Option<u64>, Query, description = "Waits up to this long, at most 30 seconds, for a queued or running job to change state")
+    ),
     responses(
-        (status = 200, description = "Successfully processed job message", body = SendResponseBody),
-        (status = 400, description = "Bad request", body = APIError),
+        (status = 200, description = "State of the job and its latest output", body = JobStatus),
+        (status = 404, description = "Job not found", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn job_message_handler(
+pub async fn job_status_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
-    payload: JobMessageRequest,
+    query: APIGetJobStatus,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
-    let node_commands_sender = node_commands_sender.clone();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     node_commands_sender
-        .send(NodeCommand::V2ApiJobMessage {
+        .send(NodeCommand::V2ApiGetJobStatus {
             bearer,
-            job_message: payload.job_message,
+            payload: query,
             res: res_sender,
         })
         .await
@@ -194,10 +1653,7 @@ pub async fn job_message_handler(
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => {
-            let response = create_success_response(response);
-            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
-        }
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
         Err(error) => Ok(warp::reply::with_status(
             warp::reply::json(&error),
             StatusCode::from_u16(error.code).unwrap(),
@@ -205,30 +1661,32 @@ pub async fn job_message_handler(
     }
 }
 
+#[derive(Deserialize)]
+pub struct JobPlanQuery {
+    pub job_id: String,
+}
+
 #[utoipa::path(
     post,
-    path = "/v2/last_messages",
-    request_body = GetLastMessagesRequest,
+    path = "/v2/set_job_planning",
+    request_body = Value,
     responses(
-        (status = 200, description = "Successfully retrieved last messages", body = Vec<V2ChatMessage>),
-        (status = 400, description = "Bad request", body = APIError),
+        (status = 200, description = "Successfully turned the planning of the job on or off", body = Value),
+        (status = 404, description = "Job not found", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn get_last_messages_handler(
+pub async fn set_job_planning_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
-    payload: GetLastMessagesRequest,
+    payload: APISetJobPlanning,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
-    let node_commands_sender = node_commands_sender.clone();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     node_commands_sender
-        .send(NodeCommand::V2ApiGetLastMessagesFromInbox {
+        .send(NodeCommand::V2ApiSetJobPlanning {
             bearer,
-            inbox_name: payload.inbox_name,
-            limit: payload.limit,
-            offset_key: payload.offset_key,
+            payload,
             res: res_sender,
         })
         .await
@@ -236,10 +1694,7 @@ pub async fn get_last_messages_handler(
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => {
-            let response = create_success_response(response);
-            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
-        }
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
         Err(error) => Ok(warp::reply::with_status(
             warp::reply::json(&error),
             StatusCode::from_u16(error.code).unwrap(),
@@ -249,23 +1704,27 @@ pub async fn get_last_messages_handler(
 
 #[utoipa::path(
     get,
-    path = "/v2/all_inboxes",
+    path = "/v2/job_plan",
+    params(
+        ("job_id" = String, Query, description = "Job to get the latest plan of")
+    ),
     responses(
-        (status = 200, description = "Successfully retrieved all smart inboxes", body = Vec<V2SmartInbox>),
-        (status = 400, description = "Bad request", body = APIError),
+        (status = 200, description = "Steps of the plan with their status and output", body = Value),
+        (status = 404, description = "The job has no plan", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn get_all_smart_inboxes_handler(
+pub async fn job_plan_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
+    query: JobPlanQuery,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
-    let node_commands_sender = node_commands_sender.clone();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     node_commands_sender
-        .send(NodeCommand::V2ApiGetAllSmartInboxes {
+        .send(NodeCommand::V2ApiGetJobPlan {
             bearer,
+            job_id: query.job_id,
             res: res_sender,
         })
         .await
@@ -273,10 +1732,44 @@ pub async fn get_all_smart_inboxes_handler(
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => {
-            let response = create_success_response(response);
-            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
-        }
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/set_job_retrieval_config",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Retrieval config now persisted in the job", body = Value),
+        (status = 400, description = "Invalid retrieval config", body = APIError),
+        (status = 404, description = "Job not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_job_retrieval_config_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APISetJobRetrievalConfig,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiSetJobRetrievalConfig {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
         Err(error) => Ok(warp::reply::with_status(
             warp::reply::json(&error),
             StatusCode::from_u16(error.code).unwrap(),
@@ -286,23 +1779,27 @@ pub async fn get_all_smart_inboxes_handler(
 
 #[utoipa::path(
     get,
-    path = "/v2/available_llm_providers",
+    path = "/v2/job_retrieval_config",
+    params(
+        ("job_id" = String, Query, description = "Job to get the retrieval config of")
+    ),
     responses(
-        (status = 200, description = "Successfully retrieved available LLM providers", body = Vec<SerializedLLMProvider>),
-        (status = 400, description = "Bad request", body = APIError),
+        (status = 200, description = "Retrieval config of the job. Unset fields use the node's defaults", body = Value),
+        (status = 404, description = "Job not found", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn get_available_llm_providers_handler(
+pub async fn job_retrieval_config_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
+    query: JobPlanQuery,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
-    let node_commands_sender = node_commands_sender.clone();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     node_commands_sender
-        .send(NodeCommand::V2ApiAvailableLLMProviders {
+        .send(NodeCommand::V2ApiGetJobRetrievalConfig {
             bearer,
+            job_id: query.job_id,
             res: res_sender,
         })
         .await
@@ -310,10 +1807,7 @@ pub async fn get_available_llm_providers_handler(
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => {
-            let response = create_success_response(response);
-            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
-        }
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
         Err(error) => Ok(warp::reply::with_status(
             warp::reply::json(&error),
             StatusCode::from_u16(error.code).unwrap(),
@@ -321,28 +1815,38 @@ pub async fn get_available_llm_providers_handler(
     }
 }
 
+#[derive(Deserialize)]
+pub struct JobMessageCitationsQuery {
+    pub job_id: String,
+    /// `node_message_hash` of the reply
+    pub message_hash: String,
+}
+
 #[utoipa::path(
-    post,
-    path = "/v2/update_smart_inbox_name",
-    request_body = UpdateSmartInboxNameRequest,
+    get,
+    path = "/v2/job_message_citations",
+    params(
+        ("job_id" = String, Query, description = "Job of the reply"),
+        ("message_hash" = String, Query, description = "Node message hash of the reply")
+    ),
     responses(
-        (status = 200, description = "Successfully updated smart inbox name", body = Value),
-        (status = 400, description = "Bad request", body = APIError),
+        (status = 200, description = "Source content the reply was based on, with its pages and character offsets", body = Value),
+        (status = 404, description = "Job not found", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn update_smart_inbox_name_handler(
+pub async fn job_message_citations_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
-    payload: UpdateSmartInboxNameRequest,
+    query: JobMessageCitationsQuery,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     node_commands_sender
-        .send(NodeCommand::V2ApiUpdateSmartInboxName {
+        .send(NodeCommand::V2ApiGetJobMessageCitations {
             bearer,
-            inbox_name: payload.inbox_name,
-            custom_name: payload.custom_name,
+            job_id: query.job_id,
+            message_hash: query.message_hash,
             res: res_sender,
         })
         .await
@@ -350,10 +1854,7 @@ pub async fn update_smart_inbox_name_handler(
     let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => {
-            let response = create_success_response(response);
-            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
-        }
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
         Err(error) => Ok(warp::reply::with_status(
             warp::reply::json(&error),
             StatusCode::from_u16(error.code).unwrap(),
@@ -363,22 +1864,26 @@ pub async fn update_smart_inbox_name_handler(
 
 #[utoipa::path(
     post,
-    path = "/v2/create_files_inbox",
+    path = "/v2/mark_as_read_up_to",
+    request_body = MarkAsReadUpToRequest,
     responses(
-        (status = 200, description = "Successfully created files inbox", body = String),
+        (status = 200, description = "Successfully marked the inbox messages as read", body = Value),
         (status = 400, description = "Bad request", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn create_files_inbox_handler(
+pub async fn mark_as_read_up_to_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
+    payload: MarkAsReadUpToRequest,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     node_commands_sender
-        .send(NodeCommand::V2ApiCreateFilesInbox {
+        .send(NodeCommand::V2ApiMarkAsReadUpTo {
             bearer,
+            inbox_name: payload.inbox_name,
+            up_to_time: payload.up_to_time,
             res: res_sender,
         })
         .await
@@ -387,7 +1892,7 @@ pub async fn create_files_inbox_handler(
 
     match result {
         Ok(response) => {
-            let response = create_success_response(response);
+            let response = create_success_response(json!({ "result": response }));
             Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
         }
         Err(error) => Ok(warp::reply::with_status(
@@ -399,133 +1904,36 @@ pub async fn create_files_inbox_handler(
 
 #[utoipa::path(
     post,
-    path = "/v2/add_file_to_inbox",
-    request_body = AddFileToInboxRequest,
+    path = "/v2/add_inbox_permission",
+    request_body = InboxPermissionRequest,
     responses(
-        (status = 200, description = "Successfully added file to inbox", body = String),
+        (status = 200, description = "Successfully added the inbox permission", body = Value),
         (status = 400, description = "Bad request", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn add_file_to_inbox_handler(
+pub async fn add_inbox_permission_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
-    mut form: FormData,
+    payload: InboxPermissionRequest,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
-    let mut file_inbox_name = String::new();
-    let mut filename = String::new();
-    let mut file_data = Vec::new();
-
-    while let Some(part) = form.next().await {
-        let mut part = part.map_err(|e| {
-            eprintln!("Error collecting form data: {:?}", e);
-            warp::reject::custom(APIError::new(
-                StatusCode::BAD_REQUEST,
-                "Bad Request",
-                format!("Failed to collect form data: {:?}", e).as_str(),
-            ))
-        })?;
-        match part.name() {
-            "file_inbox_name" => {
-                let content = part.data().await.ok_or_else(|| {
-                    warp::reject::custom(APIError::new(
-                        StatusCode::BAD_REQUEST,
-                        "Bad Request",
-                        "Missing file_inbox_name",
-                    ))
-                })?;
-                let mut content = content.map_err(|e| {
-                    warp::reject::custom(APIError::new(
-                        StatusCode::BAD_REQUEST,
-                        "Bad Request",
-                        format!("Failed to read file_inbox_name: {:?}", e).as_str(),
-                    ))
-                })?;
-                file_inbox_name =
-                    String::from_utf8(content.copy_to_bytes(content.remaining()).to_vec()).map_err(|_| {
-                        warp::reject::custom(APIError::new(
-                            StatusCode::BAD_REQUEST,
-                            "Bad Request",
-                            "Invalid UTF-8 in file_inbox_name",
-                        ))
-                    })?;
-            }
-            "filename" => {
-                let content = part.data().await.ok_or_else(|| {
-                    warp::reject::custom(APIError::new(
-                        StatusCode::BAD_REQUEST,
-                        "Bad Request",
-                        "Missing filename",
-                    ))
-                })?;
-                let mut content = content.map_err(|_| {
-                    warp::reject::custom(APIError::new(
-                        StatusCode::BAD_REQUEST,
-                        "Bad Request",
-                        "Failed to read filename",
-                    ))
-                })?;
-                filename = String::from_utf8(content.copy_to_bytes(content.remaining()).to_vec()).map_err(|_| {
-                    warp::reject::custom(APIError::new(
-                        StatusCode::BAD_REQUEST,
-                        "Bad Request",
-                        "Invalid UTF-8 in filename",
-                    ))
-                })?;
-            }
-            "file_data" => {
-                while let Some(content) = part.data().await {
-                    let mut content = content.map_err(|_| {
-                        warp::reject::custom(APIError::new(
-                            StatusCode::BAD_REQUEST,
-                            "Bad Request",
-                            "Failed to read file data",
-                        ))
-                    })?;
-                    file_data.extend_from_slice(&content.copy_to_bytes(content.remaining()));
-                }
-            }
-            _ => {}
-        }
-    }
-
-    if file_data.is_empty() {
-        return Err(warp::reject::custom(APIError::new(
-            StatusCode::BAD_REQUEST,
-            "Bad Request",
-            "No file data found. Check that the file is being uploaded correctly in the `field_data` field",
-        )));
-    }
-
     let (res_sender, res_receiver) = async_channel::bounded(1);
     node_commands_sender
-        .send(NodeCommand::V2ApiAddFileToInbox {
+        .send(NodeCommand::V2ApiAddInboxPermission {
             bearer,
-            file_inbox_name,
-            filename,
-            file: file_data,
+            inbox_name: payload.inbox_name,
+            profile: payload.profile,
+            permission: payload.permission,
             res: res_sender,
         })
         .await
-        .map_err(|_| {
-            warp::reject::custom(APIError::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Internal Server Error",
-                "Failed to send command",
-            ))
-        })?;
-    let result = res_receiver.recv().await.map_err(|_| {
-        warp::reject::custom(APIError::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Internal Server Error",
-            "Failed to receive response",
-        ))
-    })?;
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
         Ok(response) => {
-            let response = create_success_response(response);
+            let response = create_success_response(json!({ "result": response }));
             Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
         }
         Err(error) => Ok(warp::reply::with_status(
@@ -537,25 +1945,26 @@ pub async fn add_file_to_inbox_handler(
 
 #[utoipa::path(
     post,
-    path = "/v2/change_job_llm_provider",
-    request_body = APIChangeJobAgentRequest,
+    path = "/v2/remove_inbox_permission",
+    request_body = InboxPermissionRequest,
     responses(
-        (status = 200, description = "Successfully changed job LLM provider", body = Value),
+        (status = 200, description = "Successfully removed the inbox permission", body = Value),
         (status = 400, description = "Bad request", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn change_job_llm_provider_handler(
+pub async fn remove_inbox_permission_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
-    payload: APIChangeJobAgentRequest,
+    payload: InboxPermissionRequest,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     node_commands_sender
-        .send(NodeCommand::V2ApiChangeJobLlmProvider {
+        .send(NodeCommand::V2ApiRemoveInboxPermission {
             bearer,
-            payload,
+            inbox_name: payload.inbox_name,
+            profile: payload.profile,
             res: res_sender,
         })
         .await
@@ -580,15 +1989,44 @@ pub async fn change_job_llm_provider_handler(
         get_all_smart_inboxes_handler,
         get_available_llm_providers_handler,
         create_job_handler,
+        create_job_for_path_handler,
+        set_folder_agent_handler,
+        folder_agents_handler,
         job_message_handler,
         get_last_messages_handler,
         update_smart_inbox_name_handler,
+        move_inboxes_to_folder_handler,
+        set_inbox_labels_handler,
+        inbox_folders_handler,
+        archive_inbox_handler,
+        mute_inbox_handler,
+        inbox_digest_handler,
+        set_inbox_digest_handler,
+        remove_inbox_digest_handler,
+        edit_message_handler,
+        delete_message_handler,
+        message_history_handler,
+        send_message_with_attachments_handler,
+        download_attachment_handler,
         create_files_inbox_handler,
         add_file_to_inbox_handler,
-        change_job_llm_provider_handler
+        change_job_llm_provider_handler,
+        update_job_to_finished_handler,
+        job_status_handler,
+        set_job_planning_handler,
+        job_plan_handler,
+        set_job_retrieval_config_handler,
+        job_retrieval_config_handler,
+        job_message_citations_handler,
+        mark_as_read_up_to_handler,
+        add_inbox_permission_handler,
+        remove_inbox_permission_handler
     ),
     components(
-        schemas(SendResponseBody, SendResponseBodyData, APIError)
+        schemas(
+            SendResponseBody, SendResponseBodyData, APIError, JobStatus, JobState, InboxDigestSchedule,
+            InboxDigestSettings, InboxDigestFrequency
+        )
     ),
     tags(
         (name = "jobs", description = "Job API endpoints")
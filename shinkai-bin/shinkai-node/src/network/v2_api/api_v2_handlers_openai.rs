@@ -0,0 +1,221 @@
+use std::convert::Infallible;
+
+use async_channel::Sender;
+use futures::{stream, StreamExt};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use utoipa::OpenApi;
+use warp::reply::Response;
+use warp::sse::Event;
+use warp::{Filter, Reply};
+
+use crate::network::{error_code::ErrorCode, node_api_router::APIError, node_commands::NodeCommand};
+
+use super::api_v2_commands_openai::{OpenAIChatCompletion, OpenAIChatCompletionRequest};
+use super::api_v2_router::with_sender;
+
+/// OpenAI compatible endpoints, so OpenAI clients and libraries can use the node as a backend. They are
+/// served under `/v1` like OpenAI's, with the API_V2_KEY as the API key and agent ids as the models.
+pub fn openai_routes(
+    node_commands_sender: Sender<NodeCommand>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let chat_completions_route = warp::path!("v1" / "chat" / "completions")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::bytes())
+        .and_then(chat_completions_handler);
+
+    let models_route = warp::path!("v1" / "models")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(models_handler);
+
+    chat_completions_route.or(models_route)
+}
+
+/// Errors in the format OpenAI clients expect
+fn openai_error_reply(code: u16, message: &str) -> Response {
+    let error_type = if code < 500 {
+        "invalid_request_error"
+    } else {
+        "server_error"
+    };
+    let body = json!({
+        "error": {
+            "message": message,
+            "type": error_type,
+            "code": code,
+        }
+    });
+    warp::reply::with_status(
+        warp::reply::json(&body),
+        StatusCode::from_u16(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+    )
+    .into_response()
+}
+
+fn chat_completion_chunk(id: &str, model: &str, created: i64, delta: Value, finish_reason: Option<&str>) -> Event {
+    let chunk = json!({
+        "id": id,
+        "object": "chat.completion.chunk",
+        "created": created,
+        "model": model,
+        "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+    });
+    Event::default().data(chunk.to_string())
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/chat/completions",
+    request_body = OpenAIChatCompletionRequest,
+    responses(
+        (status = 200, description = "Chat completion, or a stream of chunks if stream is set", body = Value),
+        (status = 400, description = "Bad request", body = Value),
+        (status = 504, description = "The agent didn't answer in time", body = Value)
+    )
+)]
+pub async fn chat_completions_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    body: bytes::Bytes,
+) -> Result<Response, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let request: OpenAIChatCompletionRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => return Ok(openai_error_reply(400, &format!("Invalid request body: {}", e))),
+    };
+    let stream = request.stream;
+    let model = request.model.clone();
+
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiOpenAIChatCompletion {
+            bearer,
+            request,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let created = chrono::Utc::now().timestamp();
+
+    if !stream {
+        let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+        return Ok(match result {
+            Ok(completion) => {
+                let body = json!({
+                    "id": format!("chatcmpl-{}", completion.job_id),
+                    "object": "chat.completion",
+                    "created": created,
+                    "model": completion.model,
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": completion.content },
+                        "finish_reason": "stop",
+                    }],
+                    "usage": { "prompt_tokens": 0, "completion_tokens": 0, "total_tokens": 0 },
+                });
+                warp::reply::json(&body).into_response()
+            }
+            Err(error) => openai_error_reply(error.code, &error.message),
+        });
+    }
+
+    // The node answers in one go, so the stream opens with the role and sends the whole answer once it's ready.
+    // Errors after the stream started are sent as an event, since the status can't change anymore.
+    let id = format!("chatcmpl-{}", uuid::Uuid::new_v4());
+    let opening = chat_completion_chunk(
+        &id,
+        &model,
+        created,
+        json!({ "role": "assistant", "content": "" }),
+        None,
+    );
+    let answer = async move {
+        let result: Result<OpenAIChatCompletion, APIError> = match res_receiver.recv().await {
+            Ok(result) => result,
+            Err(e) => Err(APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error_code: ErrorCode::InternalError,
+                error: "Internal Server Error".to_string(),
+                message: e.to_string(),
+            }),
+        };
+        let mut events = match result {
+            Ok(completion) => vec![
+                chat_completion_chunk(&id, &model, created, json!({ "content": completion.content }), None),
+                chat_completion_chunk(&id, &model, created, json!({}), Some("stop")),
+            ],
+            Err(error) => {
+                let body = json!({ "error": { "message": error.message, "type": "server_error", "code": error.code } });
+                vec![Event::default().data(body.to_string())]
+            }
+        };
+        events.push(Event::default().data("[DONE]"));
+        stream::iter(events)
+    };
+    let events = stream::once(async move { opening })
+        .chain(stream::once(answer).flatten())
+        .map(Ok::<Event, Infallible>);
+
+    Ok(warp::sse::reply(warp::sse::keep_alive().stream(events)).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/models",
+    responses(
+        (status = 200, description = "Agents available as models", body = Value),
+        (status = 401, description = "Unauthorized", body = Value)
+    )
+)]
+pub async fn models_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<Response, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiAvailableLLMProviders {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    Ok(match result {
+        Ok(llm_providers) => {
+            let models: Vec<Value> = llm_providers
+                .iter()
+                .map(|llm_provider| {
+                    json!({
+                        "id": llm_provider.id,
+                        "object": "model",
+                        "created": 0,
+                        "owned_by": llm_provider.full_identity_name.get_node_name_string(),
+                    })
+                })
+                .collect();
+            warp::reply::json(&json!({ "object": "list", "data": models })).into_response()
+        }
+        Err(error) => openai_error_reply(error.code, &error.message),
+    })
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        chat_completions_handler,
+        models_handler,
+    ),
+    components(
+        schemas(APIError)
+    ),
+    tags(
+        (name = "openai", description = "OpenAI compatible API endpoints")
+    )
+)]
+pub struct OpenAIApiDoc;
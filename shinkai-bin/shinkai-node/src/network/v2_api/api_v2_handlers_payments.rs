@@ -0,0 +1,414 @@
+use async_channel::Sender;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use shinkai_message_primitives::schemas::payment_invoice::InvoiceStatus;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
+    APICompleteWalletTransaction, APICreateInvoice, APIMarkInvoicePaid, APIPayInvoice,
+};
+use utoipa::OpenApi;
+use warp::Filter;
+
+use crate::network::{node_api_router::APIError, node_commands::NodeCommand};
+use crate::schemas::wallet::{WalletConfig, WalletTransactionStatus};
+
+use super::api_v2_router::with_sender;
+
+pub fn payments_routes(
+    node_commands_sender: Sender<NodeCommand>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let create_invoice_route = warp::path("create_invoice")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(create_invoice_handler);
+
+    let list_invoices_route = warp::path("invoices")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<ListInvoicesRequest>())
+        .and_then(list_invoices_handler);
+
+    let mark_invoice_paid_route = warp::path("mark_invoice_paid")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(mark_invoice_paid_handler);
+
+    let set_wallet_route = warp::path("set_wallet")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_wallet_handler);
+
+    let get_wallet_route = warp::path("wallet")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_wallet_handler);
+
+    let pay_invoice_route = warp::path("pay_invoice")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(pay_invoice_handler);
+
+    let list_wallet_transactions_route = warp::path("wallet_transactions")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<ListWalletTransactionsRequest>())
+        .and_then(list_wallet_transactions_handler);
+
+    let complete_wallet_transaction_route = warp::path("complete_wallet_transaction")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(complete_wallet_transaction_handler);
+
+    create_invoice_route
+        .or(list_invoices_route)
+        .or(mark_invoice_paid_route)
+        .or(set_wallet_route)
+        .or(get_wallet_route)
+        .or(pay_invoice_route)
+        .or(list_wallet_transactions_route)
+        .or(complete_wallet_transaction_route)
+}
+
+#[derive(Deserialize)]
+pub struct ListInvoicesRequest {
+    pub status: Option<InvoiceStatus>,
+}
+
+#[derive(Deserialize)]
+pub struct ListWalletTransactionsRequest {
+    pub status: Option<WalletTransactionStatus>,
+}
+
+/// Issues an invoice to a node or profile for a subscription or the use of a tool. The invoice is sent to the node of
+/// the payer.
+#[utoipa::path(
+    post,
+    path = "/v2/create_invoice",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The invoice created", body = Value),
+        (status = 400, description = "Invalid amount, asset or payer", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn create_invoice_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APICreateInvoice,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiCreateInvoice {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Invoices issued by the node and invoices other nodes sent it, the most recent first
+#[utoipa::path(
+    get,
+    path = "/v2/invoices",
+    params(
+        ("status" = Option<String>, Query, description = "Only the invoices with the status: pending, paid or expired")
+    ),
+    responses(
+        (status = 200, description = "The invoices", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn list_invoices_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    query: ListInvoicesRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiListInvoices {
+            bearer,
+            status: query.status,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Marks an invoice issued by the node as paid. The payer is sent a receipt signed by the node as proof of payment.
+#[utoipa::path(
+    post,
+    path = "/v2/mark_invoice_paid",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The paid invoice, with its receipt", body = Value),
+        (status = 400, description = "The invoice wasn't issued by the node", body = APIError),
+        (status = 404, description = "Invoice not found", body = APIError),
+        (status = 409, description = "The invoice is already paid or expired", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn mark_invoice_paid_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIMarkInvoicePaid,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiMarkInvoicePaid {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Sets up the wallet of the node, with its signer, the assets it pays in and the limits of the automatic payments.
+/// A local key is read from the environment variable the signer names.
+#[utoipa::path(
+    post,
+    path = "/v2/set_wallet",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The wallet, with its address", body = Value),
+        (status = 400, description = "Invalid signer, network or limits", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn set_wallet_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: WalletConfig,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetWallet {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// The wallet of the node, with its address
+#[utoipa::path(
+    get,
+    path = "/v2/wallet",
+    responses(
+        (status = 200, description = "The wallet, with its address", body = Value),
+        (status = 400, description = "No wallet is set up", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn get_wallet_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetWallet {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Pays an invoice the node was sent with its wallet. Payments asked for this way aren't subject to the automatic
+/// payment limits.
+#[utoipa::path(
+    post,
+    path = "/v2/pay_invoice",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The wallet transaction", body = Value),
+        (status = 400, description = "No wallet, or it can't pay the invoice", body = APIError),
+        (status = 404, description = "Invoice not found", body = APIError),
+        (status = 409, description = "The invoice is already paid or expired", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn pay_invoice_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIPayInvoice,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiPayInvoice {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Transactions made by the wallet, the most recent first. An external signer polls the ones awaiting a signature.
+#[utoipa::path(
+    get,
+    path = "/v2/wallet_transactions",
+    params(
+        ("status" = Option<String>, Query, description = "Only the transactions with the status: awaiting_signature, submitted, confirmed or failed")
+    ),
+    responses(
+        (status = 200, description = "The wallet transactions", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn list_wallet_transactions_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    query: ListWalletTransactionsRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiListWalletTransactions {
+            bearer,
+            status: query.status,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Called by the external signer once it sent a transaction awaiting its signature, with its hash, or refused to
+#[utoipa::path(
+    post,
+    path = "/v2/complete_wallet_transaction",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The updated wallet transaction", body = Value),
+        (status = 404, description = "Transaction not found", body = APIError),
+        (status = 409, description = "The transaction isn't awaiting a signature", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn complete_wallet_transaction_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APICompleteWalletTransaction,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiCompleteWalletTransaction {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        create_invoice_handler,
+        list_invoices_handler,
+        mark_invoice_paid_handler,
+        set_wallet_handler,
+        get_wallet_handler,
+        pay_invoice_handler,
+        list_wallet_transactions_handler,
+        complete_wallet_transaction_handler
+    ),
+    components(schemas(APIError)),
+    tags(
+        (name = "payments", description = "Invoices, payment receipts and wallet API endpoints")
+    )
+)]
+pub struct PaymentsApiDoc;
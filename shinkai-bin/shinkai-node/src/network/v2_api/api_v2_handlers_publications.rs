@@ -0,0 +1,157 @@
+use async_channel::Sender;
+use reqwest::StatusCode;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{APIPublishFolder, APIUnpublishFolder};
+use utoipa::OpenApi;
+use warp::Filter;
+
+use crate::network::{node_api_router::APIError, node_commands::NodeCommand};
+
+use super::api_v2_router::with_sender;
+
+pub fn publications_routes(
+    node_commands_sender: Sender<NodeCommand>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let publish_folder_route = warp::path("publish_folder")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(publish_folder_handler);
+
+    let unpublish_folder_route = warp::path("unpublish_folder")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(unpublish_folder_handler);
+
+    let list_publications_route = warp::path("publications")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(list_publications_handler);
+
+    publish_folder_route
+        .or(unpublish_folder_route)
+        .or(list_publications_route)
+}
+
+/// Publishes a shared folder of the main profile on the publishing server (NODE_PUBLISHING_PORT), either to
+/// anyone with the link or to readers with the token returned
+#[utoipa::path(
+    post,
+    path = "/v2/publish_folder",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The publication created, with its token if it needs one", body = Value),
+        (status = 400, description = "The folder isn't shared", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn publish_folder_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIPublishFolder,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiPublishFolder {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/unpublish_folder",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Folder unpublished", body = String),
+        (status = 401, description = "Unauthorized", body = APIError),
+        (status = 404, description = "Publication not found", body = APIError)
+    )
+)]
+pub async fn unpublish_folder_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIUnpublishFolder,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiUnpublishFolder {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/publications",
+    responses(
+        (status = 200, description = "The folders published", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn list_publications_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiListPublications {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        publish_folder_handler,
+        unpublish_folder_handler,
+        list_publications_handler
+    ),
+    components(schemas(APIError)),
+    tags(
+        (name = "publications", description = "Read-only HTTP publishing of shared folders API endpoints")
+    )
+)]
+pub struct PublicationsApiDoc;
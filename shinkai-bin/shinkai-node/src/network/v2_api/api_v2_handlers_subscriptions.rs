@@ -3,8 +3,8 @@ use futures::StreamExt;
 use reqwest::StatusCode;
 use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
     APIAvailableSharedItems, APICreateShareableFolder, APIGetLastNotifications, APIGetMySubscribers,
-    APIGetNotificationsBeforeTimestamp, APISubscribeToSharedFolder, APIUnshareFolder, APIUnsubscribeToSharedFolder,
-    APIUpdateShareableFolder,
+    APIGetNotificationsBeforeTimestamp, APIPushSharedFolderChanges, APISetSharedFolderWriters,
+    APISubscribeToSharedFolder, APIUnshareFolder, APIUnsubscribeToSharedFolder, APIUpdateShareableFolder,
 };
 use utoipa::OpenApi;
 use warp::Filter;
@@ -13,6 +13,7 @@ use crate::network::{
     node_api_router::{APIError, SendResponseBody, SendResponseBodyData},
     node_commands::NodeCommand,
 };
+use crate::schemas::notification::{NotificationKind, NotificationPreferences};
 
 use super::api_v2_router::{create_success_response, with_sender};
 
@@ -68,6 +69,20 @@ pub fn subscriptions_routes(
         .and(warp::body::json())
         .and_then(unsubscribe_handler);
 
+    let set_shared_folder_writers_route = warp::path("set_shared_folder_writers")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_shared_folder_writers_handler);
+
+    let push_shared_folder_changes_route = warp::path("push_shared_folder_changes")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(push_shared_folder_changes_handler);
+
     let my_subscriptions_route = warp::path("my_subscriptions")
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
@@ -102,6 +117,31 @@ pub fn subscriptions_routes(
         .and(warp::body::json())
         .and_then(get_notifications_before_timestamp_handler);
 
+    let get_unread_notifications_count_route = warp::path("unread_notifications_count")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_unread_notifications_count_handler);
+
+    let mark_notifications_read_route = warp::path("mark_notifications_read")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(mark_notifications_read_handler);
+
+    let get_notification_preferences_route = warp::path("notification_preferences")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_notification_preferences_handler);
+
+    let set_notification_preferences_route = warp::path("set_notification_preferences")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_notification_preferences_handler);
+
     available_shared_items_route
         .or(available_shared_items_open_route)
         .or(create_shareable_folder_route)
@@ -109,11 +149,17 @@ pub fn subscriptions_routes(
         .or(unshare_folder_route)
         .or(subscribe_to_shared_folder_route)
         .or(unsubscribe_route)
+        .or(set_shared_folder_writers_route)
+        .or(push_shared_folder_changes_route)
         .or(my_subscriptions_route)
         .or(get_my_subscribers_route)
         .or(get_http_free_subscription_links_route)
         .or(get_last_notifications_route)
         .or(get_notifications_before_timestamp_route)
+        .or(get_unread_notifications_count_route)
+        .or(mark_notifications_read_route)
+        .or(get_notification_preferences_route)
+        .or(set_notification_preferences_route)
 }
 
 #[utoipa::path(
@@ -389,6 +435,84 @@ pub async fn unsubscribe_handler(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/v2/set_shared_folder_writers",
+    request_body = APISetSharedFolderWriters,
+    responses(
+        (status = 200, description = "Successfully set the shared folder writers", body = String),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_shared_folder_writers_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APISetSharedFolderWriters,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiSetSharedFolderWriters {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/push_shared_folder_changes",
+    request_body = APIPushSharedFolderChanges,
+    responses(
+        (status = 200, description = "Successfully sent the changes, returns the push id", body = String),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn push_shared_folder_changes_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIPushSharedFolderChanges,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiPushSharedFolderChanges {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/v2/my_subscriptions",
@@ -581,6 +705,157 @@ pub async fn get_notifications_before_timestamp_handler(
     }
 }
 
+/// Notifications written since they were last marked as read
+#[utoipa::path(
+    get,
+    path = "/v2/unread_notifications_count",
+    responses(
+        (status = 200, description = "Successfully counted unread notifications", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_unread_notifications_count_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiGetUnreadNotificationsCount {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Marks every notification as read
+#[utoipa::path(
+    post,
+    path = "/v2/mark_notifications_read",
+    responses(
+        (status = 200, description = "Successfully marked notifications as read", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn mark_notifications_read_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiMarkNotificationsRead {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Kinds of notifications that are disabled or silenced
+#[utoipa::path(
+    get,
+    path = "/v2/notification_preferences",
+    responses(
+        (status = 200, description = "Successfully retrieved notification preferences", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_notification_preferences_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiGetNotificationPreferences {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Replaces the notification preferences
+#[utoipa::path(
+    post,
+    path = "/v2/set_notification_preferences",
+    request_body = NotificationPreferences,
+    responses(
+        (status = 200, description = "Successfully set notification preferences", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_notification_preferences_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: NotificationPreferences,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiSetNotificationPreferences {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
@@ -591,14 +866,20 @@ pub async fn get_notifications_before_timestamp_handler(
         unshare_folder_handler,
         subscribe_to_shared_folder_handler,
         unsubscribe_handler,
+        set_shared_folder_writers_handler,
+        push_shared_folder_changes_handler,
         my_subscriptions_handler,
         get_my_subscribers_handler,
         get_http_free_subscription_links_handler,
         get_last_notifications_handler,
-        get_notifications_before_timestamp_handler
+        get_notifications_before_timestamp_handler,
+        get_unread_notifications_count_handler,
+        mark_notifications_read_handler,
+        get_notification_preferences_handler,
+        set_notification_preferences_handler
     ),
     components(
-        schemas(SendResponseBody, SendResponseBodyData, APIError)
+        schemas(SendResponseBody, SendResponseBodyData, APIError, NotificationKind, NotificationPreferences)
     ),
     tags(
         (name = "subscriptions", description = "Subscription API endpoints")
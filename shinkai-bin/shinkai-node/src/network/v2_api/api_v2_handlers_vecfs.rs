@@ -2,17 +2,22 @@ use async_channel::Sender;
 use chrono::{DateTime, Utc};
 use reqwest::StatusCode;
 use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
-    APIConvertFilesAndSaveToFolder, APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsDeleteFolder,
-    APIVecFsDeleteItem, APIVecFsMoveFolder, APIVecFsMoveItem, APIVecFsRetrievePathSimplifiedJson, APIVecFsSearchItems,
+    APICancelOperation, APIConvertFilesAndSaveToFolder, APIGetOperationStatus, APIVecFSDiffItemVersion, APIVecFSExportFolderAsVRPack, APIVecFSExportMarkdownBundle, APIVecFSGetFolderStats, APIVecFSGetItemVersions, APIVecFSImportMarkdownBundle, APIVecFSRestoreItemVersion, APIVecFSSetFolderEmbeddingModel, APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsCreateLink, APIVecFsDeleteFolder,
+    APIVecFsDeleteItem, APIVecFsMoveFolder, APIVecFsMoveItem, APIVecFsRetrievePathSimplifiedJson, APIVecFsRetrieveVectorSearchSimplifiedJson,
+    APIVecFsSearchItems,
 };
 
+use crate::managers::operation_registry::{OperationKind, OperationState, OperationStatus};
 use crate::network::{node_api_router::APIError, node_commands::NodeCommand};
+use crate::vector_fs::vector_fs_stats::FolderStats;
+use crate::vector_fs::vector_fs_types::{FSItemMetadataChange, FSItemVersion};
 use warp::Filter;
 use warp::multipart::FormData;
 use futures::StreamExt;
 use bytes::Buf;
 use utoipa::OpenApi;
 
+use super::api_v2_idempotency::{run_idempotent, with_idempotency_key};
 use super::api_v2_router::{create_success_response, with_sender};
 
 pub fn vecfs_routes(
@@ -44,6 +49,7 @@ pub fn vecfs_routes(
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
         .and(warp::header::<String>("authorization"))
+        .and(with_idempotency_key())
         .and(warp::body::json())
         .and_then(create_folder_handler);
 
@@ -51,6 +57,7 @@ pub fn vecfs_routes(
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
         .and(warp::header::<String>("authorization"))
+        .and(with_idempotency_key())
         .and(warp::body::json())
         .and_then(move_item_handler);
 
@@ -58,6 +65,7 @@ pub fn vecfs_routes(
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
         .and(warp::header::<String>("authorization"))
+        .and(with_idempotency_key())
         .and(warp::body::json())
         .and_then(copy_item_handler);
 
@@ -65,6 +73,7 @@ pub fn vecfs_routes(
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
         .and(warp::header::<String>("authorization"))
+        .and(with_idempotency_key())
         .and(warp::body::json())
         .and_then(move_folder_handler);
 
@@ -72,6 +81,7 @@ pub fn vecfs_routes(
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
         .and(warp::header::<String>("authorization"))
+        .and(with_idempotency_key())
         .and(warp::body::json())
         .and_then(copy_folder_handler);
 
@@ -79,6 +89,7 @@ pub fn vecfs_routes(
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
         .and(warp::header::<String>("authorization"))
+        .and(with_idempotency_key())
         .and(warp::body::json())
         .and_then(delete_folder_handler);
 
@@ -86,6 +97,7 @@ pub fn vecfs_routes(
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
         .and(warp::header::<String>("authorization"))
+        .and(with_idempotency_key())
         .and(warp::body::json())
         .and_then(delete_item_handler);
 
@@ -96,13 +108,98 @@ pub fn vecfs_routes(
         .and(warp::body::json())
         .and_then(search_items_handler);
 
+    let vector_search_route = warp::path("vector_search")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(vector_search_handler);
+
     let upload_file_to_folder_route = warp::path("upload_file_to_folder")
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
         .and(warp::header::<String>("authorization"))
+        .and(with_idempotency_key())
         .and(warp::multipart::form())
         .and_then(upload_file_to_folder_handler);
 
+    let folder_stats_route = warp::path("folder_stats")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<APIVecFSGetFolderStats>())
+        .and_then(folder_stats_handler);
+
+    let export_folder_as_vrpack_route = warp::path("export_folder_as_vrpack")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<APIVecFSExportFolderAsVRPack>())
+        .and_then(export_folder_as_vrpack_handler);
+
+    let export_markdown_bundle_route = warp::path("export_markdown_bundle")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(export_markdown_bundle_handler);
+
+    let import_markdown_bundle_route = warp::path("import_markdown_bundle")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(import_markdown_bundle_handler);
+
+    let item_versions_route = warp::path("item_versions")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<APIVecFSGetItemVersions>())
+        .and_then(item_versions_handler);
+
+    let item_version_diff_route = warp::path("item_version_diff")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<APIVecFSDiffItemVersion>())
+        .and_then(item_version_diff_handler);
+
+    let restore_item_version_route = warp::path("restore_item_version")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(restore_item_version_handler);
+
+    let set_folder_embedding_model_route = warp::path("set_folder_embedding_model")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_folder_embedding_model_handler);
+
+    let create_link_route = warp::path("create_link")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(create_link_handler);
+
+    let operation_status_route = warp::path("operation_status")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<APIGetOperationStatus>())
+        .and_then(operation_status_handler);
+
+    let cancel_operation_route = warp::path("cancel_operation")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(cancel_operation_handler);
+
     move_item_route
         .or(copy_item_route)
         .or(move_folder_route)
@@ -110,11 +207,23 @@ pub fn vecfs_routes(
         .or(delete_folder_route)
         .or(delete_item_route)
         .or(search_items_route)
+        .or(vector_search_route)
         .or(retrieve_path_simplified_route)
         .or(retrieve_vector_resource_route)
         .or(convert_files_and_save_route)
         .or(create_folder_route)
         .or(upload_file_to_folder_route)
+        .or(folder_stats_route)
+        .or(export_folder_as_vrpack_route)
+        .or(export_markdown_bundle_route)
+        .or(import_markdown_bundle_route)
+        .or(item_versions_route)
+        .or(item_version_diff_route)
+        .or(restore_item_version_route)
+        .or(set_folder_embedding_model_route)
+        .or(create_link_route)
+        .or(operation_status_route)
+        .or(cancel_operation_route)
 }
 
 #[utoipa::path(
@@ -237,6 +346,9 @@ pub async fn convert_files_and_save_handler(
 #[utoipa::path(
     post,
     path = "/v2/create_folder",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")
+    ),
     request_body = APIVecFsCreateFolder,
     responses(
         (status = 200, description = "Successfully created folder", body = String),
@@ -247,19 +359,31 @@ pub async fn convert_files_and_save_handler(
 pub async fn create_folder_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
+    idempotency_key: Option<String>,
     payload: APIVecFsCreateFolder,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
-    let (res_sender, res_receiver) = async_channel::bounded(1);
-    node_commands_sender
-        .send(NodeCommand::V2ApiVecFSCreateFolder {
-            bearer,
-            payload,
-            res: res_sender,
-        })
-        .await
-        .map_err(|_| warp::reject::reject())?;
-    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+    let request = serde_json::to_vec(&payload).unwrap_or_default();
+    let result = run_idempotent(
+        &node_commands_sender,
+        &bearer,
+        idempotency_key,
+        "create_folder",
+        &request,
+        async {
+            let (res_sender, res_receiver) = async_channel::bounded(1);
+            node_commands_sender
+                .send(NodeCommand::V2ApiVecFSCreateFolder {
+                    bearer: bearer.clone(),
+                    payload,
+                    res: res_sender,
+                })
+                .await
+                .map_err(|_| warp::reject::reject())?;
+            res_receiver.recv().await.map_err(|_| warp::reject::reject())
+        },
+    )
+    .await?;
 
     match result {
         Ok(response) => {
@@ -276,6 +400,9 @@ pub async fn create_folder_handler(
 #[utoipa::path(
     post,
     path = "/v2/move_item",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")
+    ),
     request_body = APIVecFsMoveItem,
     responses(
         (status = 200, description = "Successfully moved item", body = String),
@@ -286,19 +413,31 @@ pub async fn create_folder_handler(
 pub async fn move_item_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
+    idempotency_key: Option<String>,
     payload: APIVecFsMoveItem,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
-    let (res_sender, res_receiver) = async_channel::bounded(1);
-    node_commands_sender
-        .send(NodeCommand::V2ApiMoveItem {
-            bearer,
-            payload,
-            res: res_sender,
-        })
-        .await
-        .map_err(|_| warp::reject::reject())?;
-    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+    let request = serde_json::to_vec(&payload).unwrap_or_default();
+    let result = run_idempotent(
+        &node_commands_sender,
+        &bearer,
+        idempotency_key,
+        "move_item",
+        &request,
+        async {
+            let (res_sender, res_receiver) = async_channel::bounded(1);
+            node_commands_sender
+                .send(NodeCommand::V2ApiMoveItem {
+                    bearer: bearer.clone(),
+                    payload,
+                    res: res_sender,
+                })
+                .await
+                .map_err(|_| warp::reject::reject())?;
+            res_receiver.recv().await.map_err(|_| warp::reject::reject())
+        },
+    )
+    .await?;
 
     match result {
         Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
@@ -312,6 +451,9 @@ pub async fn move_item_handler(
 #[utoipa::path(
     post,
     path = "/v2/copy_item",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")
+    ),
     request_body = APIVecFsCopyItem,
     responses(
         (status = 200, description = "Successfully copied item", body = String),
@@ -322,19 +464,31 @@ pub async fn move_item_handler(
 pub async fn copy_item_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
+    idempotency_key: Option<String>,
     payload: APIVecFsCopyItem,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
-    let (res_sender, res_receiver) = async_channel::bounded(1);
-    node_commands_sender
-        .send(NodeCommand::V2ApiCopyItem {
-            bearer,
-            payload,
-            res: res_sender,
-        })
-        .await
-        .map_err(|_| warp::reject::reject())?;
-    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+    let request = serde_json::to_vec(&payload).unwrap_or_default();
+    let result = run_idempotent(
+        &node_commands_sender,
+        &bearer,
+        idempotency_key,
+        "copy_item",
+        &request,
+        async {
+            let (res_sender, res_receiver) = async_channel::bounded(1);
+            node_commands_sender
+                .send(NodeCommand::V2ApiCopyItem {
+                    bearer: bearer.clone(),
+                    payload,
+                    res: res_sender,
+                })
+                .await
+                .map_err(|_| warp::reject::reject())?;
+            res_receiver.recv().await.map_err(|_| warp::reject::reject())
+        },
+    )
+    .await?;
 
     match result {
         Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
@@ -348,6 +502,9 @@ pub async fn copy_item_handler(
 #[utoipa::path(
     post,
     path = "/v2/move_folder",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")
+    ),
     request_body = APIVecFsMoveFolder,
     responses(
         (status = 200, description = "Successfully moved folder", body = String),
@@ -358,19 +515,31 @@ pub async fn copy_item_handler(
 pub async fn move_folder_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
+    idempotency_key: Option<String>,
     payload: APIVecFsMoveFolder,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
-    let (res_sender, res_receiver) = async_channel::bounded(1);
-    node_commands_sender
-        .send(NodeCommand::V2ApiMoveFolder {
-            bearer,
-            payload,
-            res: res_sender,
-        })
-        .await
-        .map_err(|_| warp::reject::reject())?;
-    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+    let request = serde_json::to_vec(&payload).unwrap_or_default();
+    let result = run_idempotent(
+        &node_commands_sender,
+        &bearer,
+        idempotency_key,
+        "move_folder",
+        &request,
+        async {
+            let (res_sender, res_receiver) = async_channel::bounded(1);
+            node_commands_sender
+                .send(NodeCommand::V2ApiMoveFolder {
+                    bearer: bearer.clone(),
+                    payload,
+                    res: res_sender,
+                })
+                .await
+                .map_err(|_| warp::reject::reject())?;
+            res_receiver.recv().await.map_err(|_| warp::reject::reject())
+        },
+    )
+    .await?;
 
     match result {
         Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
@@ -384,6 +553,9 @@ pub async fn move_folder_handler(
 #[utoipa::path(
     post,
     path = "/v2/copy_folder",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")
+    ),
     request_body = APIVecFsCopyFolder,
     responses(
         (status = 200, description = "Successfully copied folder", body = String),
@@ -394,19 +566,31 @@ pub async fn move_folder_handler(
 pub async fn copy_folder_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
+    idempotency_key: Option<String>,
     payload: APIVecFsCopyFolder,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
-    let (res_sender, res_receiver) = async_channel::bounded(1);
-    node_commands_sender
-        .send(NodeCommand::V2ApiCopyFolder {
-            bearer,
-            payload,
-            res: res_sender,
-        })
-        .await
-        .map_err(|_| warp::reject::reject())?;
-    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+    let request = serde_json::to_vec(&payload).unwrap_or_default();
+    let result = run_idempotent(
+        &node_commands_sender,
+        &bearer,
+        idempotency_key,
+        "copy_folder",
+        &request,
+        async {
+            let (res_sender, res_receiver) = async_channel::bounded(1);
+            node_commands_sender
+                .send(NodeCommand::V2ApiCopyFolder {
+                    bearer: bearer.clone(),
+                    payload,
+                    res: res_sender,
+                })
+                .await
+                .map_err(|_| warp::reject::reject())?;
+            res_receiver.recv().await.map_err(|_| warp::reject::reject())
+        },
+    )
+    .await?;
 
     match result {
         Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
@@ -420,6 +604,9 @@ pub async fn copy_folder_handler(
 #[utoipa::path(
     post,
     path = "/v2/delete_folder",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")
+    ),
     request_body = APIVecFsDeleteFolder,
     responses(
         (status = 200, description = "Successfully deleted folder", body = String),
@@ -430,19 +617,31 @@ pub async fn copy_folder_handler(
 pub async fn delete_folder_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
+    idempotency_key: Option<String>,
     payload: APIVecFsDeleteFolder,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
-    let (res_sender, res_receiver) = async_channel::bounded(1);
-    node_commands_sender
-        .send(NodeCommand::V2ApiDeleteFolder {
-            bearer,
-            payload,
-            res: res_sender,
-        })
-        .await
-        .map_err(|_| warp::reject::reject())?;
-    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+    let request = serde_json::to_vec(&payload).unwrap_or_default();
+    let result = run_idempotent(
+        &node_commands_sender,
+        &bearer,
+        idempotency_key,
+        "delete_folder",
+        &request,
+        async {
+            let (res_sender, res_receiver) = async_channel::bounded(1);
+            node_commands_sender
+                .send(NodeCommand::V2ApiDeleteFolder {
+                    bearer: bearer.clone(),
+                    payload,
+                    res: res_sender,
+                })
+                .await
+                .map_err(|_| warp::reject::reject())?;
+            res_receiver.recv().await.map_err(|_| warp::reject::reject())
+        },
+    )
+    .await?;
 
     match result {
         Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
@@ -456,6 +655,9 @@ pub async fn delete_folder_handler(
 #[utoipa::path(
     post,
     path = "/v2/delete_item",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")
+    ),
     request_body = APIVecFsDeleteItem,
     responses(
         (status = 200, description = "Successfully deleted item", body = String),
@@ -466,12 +668,60 @@ pub async fn delete_folder_handler(
 pub async fn delete_item_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
+    idempotency_key: Option<String>,
     payload: APIVecFsDeleteItem,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let request = serde_json::to_vec(&payload).unwrap_or_default();
+    let result = run_idempotent(
+        &node_commands_sender,
+        &bearer,
+        idempotency_key,
+        "delete_item",
+        &request,
+        async {
+            let (res_sender, res_receiver) = async_channel::bounded(1);
+            node_commands_sender
+                .send(NodeCommand::V2ApiDeleteItem {
+                    bearer: bearer.clone(),
+                    payload,
+                    res: res_sender,
+                })
+                .await
+                .map_err(|_| warp::reject::reject())?;
+            res_receiver.recv().await.map_err(|_| warp::reject::reject())
+        },
+    )
+    .await?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/search_items",
+    request_body = APIVecFsSearchItems,
+    responses(
+        (status = 200, description = "Successfully searched items", body = Vec<String>),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn search_items_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIVecFsSearchItems,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     node_commands_sender
-        .send(NodeCommand::V2ApiDeleteItem {
+        .send(NodeCommand::V2ApiSearchItems {
             bearer,
             payload,
             res: res_sender,
@@ -491,23 +741,23 @@ pub async fn delete_item_handler(
 
 #[utoipa::path(
     post,
-    path = "/v2/search_items",
-    request_body = APIVecFsSearchItems,
+    path = "/v2/vector_search",
+    request_body = APIVecFsRetrieveVectorSearchSimplifiedJson,
     responses(
-        (status = 200, description = "Successfully searched items", body = Vec<String>),
+        (status = 200, description = "Content, path and score of the closest chunks", body = Value),
         (status = 400, description = "Bad request", body = APIError),
         (status = 500, description = "Internal server error", body = APIError)
     )
 )]
-pub async fn search_items_handler(
+pub async fn vector_search_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
-    payload: APIVecFsSearchItems,
+    payload: APIVecFsRetrieveVectorSearchSimplifiedJson,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     node_commands_sender
-        .send(NodeCommand::V2ApiSearchItems {
+        .send(NodeCommand::V2ApiVecFSVectorSearch {
             bearer,
             payload,
             res: res_sender,
@@ -525,10 +775,12 @@ pub async fn search_items_handler(
     }
 }
 
-
 #[utoipa::path(
     post,
     path = "/v2/upload_file_to_folder",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")
+    ),
     request_body = AddFileToInboxRequest,
     responses(
         (status = 200, description = "Successfully uploaded file to folder", body = String),
@@ -539,6 +791,7 @@ pub async fn search_items_handler(
 pub async fn upload_file_to_folder_handler(
     node_commands_sender: Sender<NodeCommand>,
     authorization: String,
+    idempotency_key: Option<String>,
     mut form: FormData,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
@@ -546,6 +799,7 @@ pub async fn upload_file_to_folder_handler(
     let mut file_data = Vec::new();
     let mut path = String::new();
     let mut file_datetime: Option<DateTime<Utc>> = None;
+    let mut allow_duplicates = false;
 
     while let Some(part) = form.next().await {
         let mut part = part.map_err(|e| {
@@ -646,6 +900,24 @@ pub async fn upload_file_to_folder_handler(
                         .with_timezone(&Utc),
                 );
             }
+            // Set to "true" to save the file even if it looks like a duplicate of an item of the folder
+            "allow_duplicates" => {
+                let content = part.data().await.ok_or_else(|| {
+                    warp::reject::custom(APIError::new(
+                        StatusCode::BAD_REQUEST,
+                        "Bad Request",
+                        "Missing allow_duplicates",
+                    ))
+                })?;
+                let mut content = content.map_err(|e| {
+                    warp::reject::custom(APIError::new(
+                        StatusCode::BAD_REQUEST,
+                        "Bad Request",
+                        format!("Failed to read allow_duplicates: {:?}", e).as_str(),
+                    ))
+                })?;
+                allow_duplicates = content.copy_to_bytes(content.remaining()).as_ref() == b"true";
+            }
             _ => {}
         }
     }
@@ -658,37 +930,481 @@ pub async fn upload_file_to_folder_handler(
         )));
     }
 
+    let mut request = Vec::new();
+    let allow_duplicates_field = [allow_duplicates as u8];
+    for field in [
+        path.as_bytes(),
+        filename.as_bytes(),
+        file_data.as_slice(),
+        allow_duplicates_field.as_slice(),
+    ] {
+        request.extend_from_slice(blake3::hash(field).as_bytes());
+    }
+    let result = run_idempotent(
+        &node_commands_sender,
+        &bearer,
+        idempotency_key,
+        "upload_file_to_folder",
+        &request,
+        async {
+            let (res_sender, res_receiver) = async_channel::bounded(1);
+            node_commands_sender
+                .send(NodeCommand::V2ApiUploadFileToFolder {
+                    bearer: bearer.clone(),
+                    filename,
+                    file: file_data,
+                    path,
+                    file_datetime,
+                    allow_duplicates,
+                    res: res_sender,
+                })
+                .await
+                .map_err(|_| {
+                    warp::reject::custom(APIError::new(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Internal Server Error",
+                        "Failed to send command",
+                    ))
+                })?;
+            res_receiver.recv().await.map_err(|_| {
+                warp::reject::custom(APIError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal Server Error",
+                    "Failed to receive response",
+                ))
+            })
+        },
+    )
+    .await?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/folder_stats",
+    params(
+        ("path" = String, Query, description = "Folder to get the stats of, `/` for the whole VecFS")
+    ),
+    responses(
+        (status = 200, description = "Totals of everything held within the folder", body = FolderStats),
+        (status = 404, description = "Folder not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn folder_stats_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    query: APIVecFSGetFolderStats,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
     let (res_sender, res_receiver) = async_channel::bounded(1);
     node_commands_sender
-        .send(NodeCommand::V2ApiUploadFileToFolder {
+        .send(NodeCommand::V2ApiVecFSGetFolderStats {
             bearer,
-            filename,
-            file: file_data,
-            path,
-            file_datetime,
+            payload: query,
             res: res_sender,
         })
         .await
-        .map_err(|_| {
-            warp::reject::custom(APIError::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Internal Server Error",
-                "Failed to send command",
-            ))
-        })?;
-    let result = res_receiver.recv().await.map_err(|_| {
-        warp::reject::custom(APIError::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Internal Server Error",
-            "Failed to receive response",
-        ))
-    })?;
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
 
     match result {
-        Ok(response) => {
-            let response = create_success_response(response);
-            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Size of the chunks the VRPack is streamed in
+const VRPACK_CHUNK_SIZE: usize = 64 * 1024;
+
+#[utoipa::path(
+    get,
+    path = "/v2/export_folder_as_vrpack",
+    params(
+        ("path" = String, Query, description = "Folder to export, `/` for the whole VecFS")
+    ),
+    responses(
+        (status = 200, description = "The folder packed as a VRPack", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 404, description = "Folder not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn export_folder_as_vrpack_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    query: APIVecFSExportFolderAsVRPack,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiVecFSExportFolderAsVRPack {
+            bearer,
+            payload: query,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok((file_name, vrpack)) => {
+            let chunks: Vec<Result<bytes::Bytes, std::io::Error>> = vrpack
+                .chunks(VRPACK_CHUNK_SIZE)
+                .map(|chunk| Ok(bytes::Bytes::copy_from_slice(chunk)))
+                .collect();
+            Ok(Box::new(
+                warp::http::Response::builder()
+                    .header("Content-Type", "application/octet-stream")
+                    .header("Content-Disposition", format!("attachment; filename=\"{}\"", file_name))
+                    .body(warp::hyper::Body::wrap_stream(futures::stream::iter(chunks))),
+            ))
         }
+        Err(error) => Ok(Box::new(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        ))),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/export_markdown_bundle",
+    request_body = APIVecFSExportMarkdownBundle,
+    responses(
+        (status = 200, description = "Items of the folder written as markdown documents with front matter", body = Value),
+        (status = 404, description = "Folder not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn export_markdown_bundle_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIVecFSExportMarkdownBundle,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiVecFSExportMarkdownBundle {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/import_markdown_bundle",
+    request_body = APIVecFSImportMarkdownBundle,
+    responses(
+        (status = 200, description = "Paths of the items the markdown documents were imported as", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn import_markdown_bundle_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIVecFSImportMarkdownBundle,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiVecFSImportMarkdownBundle {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/item_versions",
+    params(
+        ("path" = String, Query, description = "Item to get the previous versions of")
+    ),
+    responses(
+        (status = 200, description = "Previous versions of the item, oldest first", body = Vec<FSItemVersion>),
+        (status = 404, description = "Item not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn item_versions_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    query: APIVecFSGetItemVersions,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiVecFSGetItemVersions {
+            bearer,
+            payload: query,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/item_version_diff",
+    params(
+        ("path" = String, Query, description = "Item the version belongs to"),
+        ("version_id" = u64, Query, description = "Version to compare with the current item")
+    ),
+    responses(
+        (status = 200, description = "Metadata keys whose values changed since the version", body = Vec<FSItemMetadataChange>),
+        (status = 404, description = "Item or version not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn item_version_diff_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    query: APIVecFSDiffItemVersion,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiVecFSDiffItemVersion {
+            bearer,
+            payload: query,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/restore_item_version",
+    request_body = APIVecFSRestoreItemVersion,
+    responses(
+        (status = 200, description = "Item restored, the replaced item is kept as its latest version", body = Value),
+        (status = 404, description = "Item or version not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn restore_item_version_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIVecFSRestoreItemVersion,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiVecFSRestoreItemVersion {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/set_folder_embedding_model",
+    request_body = APIVecFSSetFolderEmbeddingModel,
+    responses(
+        (status = 200, description = "Embedding model of the folder set", body = Value),
+        (status = 400, description = "Unknown embedding model, or items in the folder use another one", body = APIError),
+        (status = 404, description = "Folder not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_folder_embedding_model_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIVecFSSetFolderEmbeddingModel,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiVecFSSetFolderEmbeddingModel {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/create_link",
+    request_body = APIVecFsCreateLink,
+    responses(
+        (status = 200, description = "Link created, it shares the Vector Resource of its target", body = Value),
+        (status = 404, description = "Folder or target item not found", body = APIError),
+        (status = 409, description = "An entry already exists at the path of the link", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn create_link_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIVecFsCreateLink,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiVecFSCreateLink {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/operation_status",
+    params(
+        ("operation_id" = Option<String>, Query, description = "Operation to get the status of, every running operation if it's not set")
+    ),
+    responses(
+        (status = 200, description = "Progress of the operations", body = Vec<OperationStatus>),
+        (status = 404, description = "Operation not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn operation_status_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    query: APIGetOperationStatus,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiGetOperationStatus {
+            bearer,
+            payload: query,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/cancel_operation",
+    request_body = APICancelOperation,
+    responses(
+        (status = 200, description = "Cancellation requested, the operation stops at its next step", body = OperationStatus),
+        (status = 404, description = "Operation not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn cancel_operation_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APICancelOperation,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiCancelOperation {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
         Err(error) => Ok(warp::reply::with_status(
             warp::reply::json(&error),
             StatusCode::from_u16(error.code).unwrap(),
@@ -710,10 +1426,22 @@ pub async fn upload_file_to_folder_handler(
         delete_folder_handler,
         delete_item_handler,
         search_items_handler,
+        vector_search_handler,
         upload_file_to_folder_handler,
+        folder_stats_handler,
+        export_folder_as_vrpack_handler,
+        export_markdown_bundle_handler,
+        import_markdown_bundle_handler,
+        item_versions_handler,
+        item_version_diff_handler,
+        restore_item_version_handler,
+        set_folder_embedding_model_handler,
+        create_link_handler,
+        operation_status_handler,
+        cancel_operation_handler,
     ),
     components(
-        schemas(APIError)
+        schemas(APIError, FolderStats, FSItemVersion, FSItemMetadataChange, OperationStatus, OperationKind, OperationState)
     ),
     tags(
         (name = "vecfs", description = "VecFS API endpoints")
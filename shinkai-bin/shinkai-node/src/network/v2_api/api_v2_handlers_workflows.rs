@@ -2,13 +2,16 @@ use std::collections::HashMap;
 
 use async_channel::Sender;
 use reqwest::StatusCode;
-
+use serde::Deserialize;
 use serde_json::Value;
-use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{APISetWorkflow, APIWorkflowKeyname};
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
+    APIInstallToolkitFromURL, APISetWorkflow, APIWorkflowKeyname,
+};
 use utoipa::OpenApi;
 use warp::Filter;
 
-use crate::network::{node_api_router::APIError, node_commands::NodeCommand};
+use crate::network::{error_code::ErrorCode, node_api_router::APIError, node_commands::NodeCommand};
+use crate::tools::tool_pricing::ToolUsageAllowance;
 
 use super::api_v2_router::{create_success_response, with_sender};
 
@@ -77,6 +80,98 @@ pub fn workflows_routes(
         .and(warp::query::<HashMap<String, String>>())
         .and_then(search_shinkai_tool_handler);
 
+    let add_wasm_tool_route = warp::path("add_wasm_tool")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(add_wasm_tool_handler);
+
+    let get_toolkit_capabilities_route = warp::path("get_toolkit_capabilities")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(get_toolkit_capabilities_handler);
+
+    let grant_toolkit_capabilities_route = warp::path("grant_toolkit_capabilities")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(grant_toolkit_capabilities_handler);
+
+    let revoke_toolkit_capabilities_route = warp::path("revoke_toolkit_capabilities")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(revoke_toolkit_capabilities_handler);
+
+    let get_toolkit_execution_limits_route = warp::path("get_toolkit_execution_limits")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(get_toolkit_execution_limits_handler);
+
+    let set_toolkit_execution_limits_route = warp::path("set_toolkit_execution_limits")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<HashMap<String, String>>())
+        .and(warp::body::json())
+        .and_then(set_toolkit_execution_limits_handler);
+
+    let get_toolkit_pricing_route = warp::path("get_toolkit_pricing")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(get_toolkit_pricing_handler);
+
+    let set_toolkit_pricing_route = warp::path("set_toolkit_pricing")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<HashMap<String, String>>())
+        .and(warp::body::json())
+        .and_then(set_toolkit_pricing_handler);
+
+    let get_tool_usage_route = warp::path("get_tool_usage")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(get_tool_usage_handler);
+
+    let set_tool_usage_allowance_route = warp::path("set_tool_usage_allowance")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_tool_usage_allowance_handler);
+
+    let list_toolkits_route = warp::path("list_toolkits")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(list_toolkits_handler);
+
+    let install_toolkit_from_url_route = warp::path("install_toolkit_from_url")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(install_toolkit_from_url_handler);
+
+    let remove_toolkit_route = warp::path("remove_toolkit")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(remove_toolkit_handler);
+
     search_workflows_route
         .or(set_workflow_route)
         .or(remove_workflow_route)
@@ -86,6 +181,19 @@ pub fn workflows_routes(
         .or(set_shinkai_tool_route)
         .or(get_shinkai_tool_route)
         .or(search_shinkai_tool_route)
+        .or(add_wasm_tool_route)
+        .or(get_toolkit_capabilities_route)
+        .or(grant_toolkit_capabilities_route)
+        .or(revoke_toolkit_capabilities_route)
+        .or(get_toolkit_execution_limits_route)
+        .or(set_toolkit_execution_limits_route)
+        .or(get_toolkit_pricing_route)
+        .or(set_toolkit_pricing_route)
+        .or(get_tool_usage_route)
+        .or(set_tool_usage_allowance_route)
+        .or(list_toolkits_route)
+        .or(install_toolkit_from_url_route)
+        .or(remove_toolkit_route)
 }
 
 #[utoipa::path(
@@ -111,6 +219,7 @@ pub async fn search_workflows_handler(
         .ok_or_else(|| {
             warp::reject::custom(APIError {
                 code: 400,
+                error_code: ErrorCode::InvalidInput,
                 error: "Invalid Query".to_string(),
                 message: "The request query string is invalid.".to_string(),
             })
@@ -162,6 +271,7 @@ pub async fn search_shinkai_tool_handler(
         .ok_or_else(|| {
             warp::reject::custom(APIError {
                 code: 400,
+                error_code: ErrorCode::InvalidInput,
                 error: "Invalid Query".to_string(),
                 message: "The request query string is invalid.".to_string(),
             })
@@ -406,6 +516,7 @@ pub async fn set_shinkai_tool_handler(
         .ok_or_else(|| {
             warp::reject::custom(APIError {
                 code: 400,
+                error_code: ErrorCode::InvalidInput,
                 error: "Invalid Query".to_string(),
                 message: "The request query string is invalid.".to_string(),
             })
@@ -455,6 +566,7 @@ pub async fn get_shinkai_tool_handler(
         .ok_or_else(|| {
             warp::reject::custom(APIError {
                 code: 400,
+                error_code: ErrorCode::InvalidInput,
                 error: "Invalid Query".to_string(),
                 message: "The request query string is invalid.".to_string(),
             })
@@ -483,6 +595,561 @@ pub async fn get_shinkai_tool_handler(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/v2/add_wasm_tool",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully added WASM tool", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn add_wasm_tool_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: Value,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiAddWasmTool {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/get_toolkit_capabilities",
+    params(
+        ("toolkit_name" = String, Query, description = "Name of the toolkit")
+    ),
+    responses(
+        (status = 200, description = "Capabilities requested by and granted to the toolkit", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_toolkit_capabilities_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    query_params: HashMap<String, String>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let toolkit_name = query_params
+        .get("toolkit_name")
+        .ok_or_else(|| {
+            warp::reject::custom(APIError {
+                code: 400,
+                error_code: ErrorCode::InvalidInput,
+                error: "Invalid Query".to_string(),
+                message: "The request query string is invalid.".to_string(),
+            })
+        })?
+        .to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetToolkitCapabilities {
+            bearer,
+            toolkit_name,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/grant_toolkit_capabilities",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully granted the capabilities", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn grant_toolkit_capabilities_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: Value,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGrantToolkitCapabilities {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/revoke_toolkit_capabilities",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully revoked the capabilities", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn revoke_toolkit_capabilities_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: Value,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiRevokeToolkitCapabilities {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/get_toolkit_execution_limits",
+    params(
+        ("toolkit_name" = String, Query, description = "Name of the toolkit")
+    ),
+    responses(
+        (status = 200, description = "Execution limits of the toolkit", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_toolkit_execution_limits_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    query_params: HashMap<String, String>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let toolkit_name = query_params
+        .get("toolkit_name")
+        .ok_or_else(|| {
+            warp::reject::custom(APIError {
+                code: 400,
+                error_code: ErrorCode::InvalidInput,
+                error: "Invalid Query".to_string(),
+                message: "The request query string is invalid.".to_string(),
+            })
+        })?
+        .to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetToolkitExecutionLimits {
+            bearer,
+            toolkit_name,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/set_toolkit_execution_limits",
+    params(
+        ("toolkit_name" = String, Query, description = "Name of the toolkit")
+    ),
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully set the execution limits", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_toolkit_execution_limits_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    query_params: HashMap<String, String>,
+    payload: Value,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let toolkit_name = query_params
+        .get("toolkit_name")
+        .ok_or_else(|| {
+            warp::reject::custom(APIError {
+                code: 400,
+                error_code: ErrorCode::InvalidInput,
+                error: "Invalid Query".to_string(),
+                message: "The request query string is invalid.".to_string(),
+            })
+        })?
+        .to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetToolkitExecutionLimits {
+            bearer,
+            toolkit_name,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/get_toolkit_pricing",
+    params(
+        ("toolkit_name" = String, Query, description = "Name of the toolkit")
+    ),
+    responses(
+        (status = 200, description = "Price of the invocations of the toolkit, null if it's free", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_toolkit_pricing_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    query_params: HashMap<String, String>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let toolkit_name = query_params
+        .get("toolkit_name")
+        .ok_or_else(|| {
+            warp::reject::custom(APIError {
+                code: 400,
+                error_code: ErrorCode::InvalidInput,
+                error: "Invalid Query".to_string(),
+                message: "The request query string is invalid.".to_string(),
+            })
+        })?
+        .to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetToolkitPricing {
+            bearer,
+            toolkit_name,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/set_toolkit_pricing",
+    params(
+        ("toolkit_name" = String, Query, description = "Name of the toolkit")
+    ),
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully set the pricing, null makes the toolkit free", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_toolkit_pricing_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    query_params: HashMap<String, String>,
+    payload: Value,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let toolkit_name = query_params
+        .get("toolkit_name")
+        .ok_or_else(|| {
+            warp::reject::custom(APIError {
+                code: 400,
+                error_code: ErrorCode::InvalidInput,
+                error: "Invalid Query".to_string(),
+                message: "The request query string is invalid.".to_string(),
+            })
+        })?
+        .to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetToolkitPricing {
+            bearer,
+            toolkit_name,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/get_tool_usage",
+    params(
+        ("consumer" = String, Query, description = "Profile the tools ran for"),
+        ("asset" = String, Query, description = "Asset the usage is priced in")
+    ),
+    responses(
+        (status = 200, description = "What the profile owes for the priced tools it used, and its allowance", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_tool_usage_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    query_params: HashMap<String, String>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (Some(consumer), Some(asset)) = (query_params.get("consumer"), query_params.get("asset")) else {
+        return Err(warp::reject::custom(APIError {
+            code: 400,
+            error_code: ErrorCode::InvalidInput,
+            error: "Invalid Query".to_string(),
+            message: "The request query string is invalid.".to_string(),
+        }));
+    };
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetToolUsage {
+            bearer,
+            consumer: consumer.to_string(),
+            asset: asset.to_string(),
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/set_tool_usage_allowance",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Successfully set the allowance", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_tool_usage_allowance_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: ToolUsageAllowance,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetToolUsageAllowance {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/list_toolkits",
+    responses(
+        (status = 200, description = "Toolkits installed from a package, with where they came from", body = Value),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn list_toolkits_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiListToolkits {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/install_toolkit_from_url",
+    request_body = APIInstallToolkitFromURL,
+    responses(
+        (status = 200, description = "Provenance of the installed toolkit", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 403, description = "Package verification failed or permissions not accepted", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn install_toolkit_from_url_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIInstallToolkitFromURL,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiInstallToolkitFromURL {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RemoveToolkitRequest {
+    pub toolkit_name: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/remove_toolkit",
+    request_body = RemoveToolkitRequest,
+    responses(
+        (status = 200, description = "Successfully removed the toolkit", body = Value),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn remove_toolkit_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: RemoveToolkitRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiRemoveToolkit {
+            bearer,
+            toolkit_name: payload.toolkit_name,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => {
+            let response = create_success_response(response);
+            Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK))
+        }
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
@@ -495,6 +1162,19 @@ pub async fn get_shinkai_tool_handler(
         set_shinkai_tool_handler,
         get_shinkai_tool_handler,
         search_shinkai_tool_handler,
+        add_wasm_tool_handler,
+        get_toolkit_capabilities_handler,
+        grant_toolkit_capabilities_handler,
+        revoke_toolkit_capabilities_handler,
+        get_toolkit_execution_limits_handler,
+        set_toolkit_execution_limits_handler,
+        get_toolkit_pricing_handler,
+        set_toolkit_pricing_handler,
+        get_tool_usage_handler,
+        set_tool_usage_allowance_handler,
+        list_toolkits_handler,
+        install_toolkit_from_url_handler,
+        remove_toolkit_handler,
     ),
     components(
         schemas(APIError)
@@ -0,0 +1,346 @@
+use async_channel::Sender;
+use reqwest::StatusCode;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
+    APICreateWorkspace, APISetWorkspaceMember, APIUpdateWorkspace, APIWorkspaceInvitation,
+};
+use utoipa::OpenApi;
+use warp::Filter;
+
+use crate::network::{node_api_router::APIError, node_commands::NodeCommand};
+
+use super::api_v2_router::with_sender;
+
+pub fn workspaces_routes(
+    node_commands_sender: Sender<NodeCommand>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let create_workspace_route = warp::path("create_workspace")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(create_workspace_handler);
+
+    let update_workspace_route = warp::path("update_workspace")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(update_workspace_handler);
+
+    let set_workspace_member_route = warp::path("set_workspace_member")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_workspace_member_handler);
+
+    let list_workspaces_route = warp::path("workspaces")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(list_workspaces_handler);
+
+    let list_workspace_invitations_route = warp::path("workspace_invitations")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(list_workspace_invitations_handler);
+
+    let accept_workspace_invitation_route = warp::path("accept_workspace_invitation")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(accept_workspace_invitation_handler);
+
+    let decline_workspace_invitation_route = warp::path("decline_workspace_invitation")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(decline_workspace_invitation_handler);
+
+    create_workspace_route
+        .or(update_workspace_route)
+        .or(set_workspace_member_route)
+        .or(list_workspaces_route)
+        .or(list_workspace_invitations_route)
+        .or(accept_workspace_invitation_route)
+        .or(decline_workspace_invitation_route)
+}
+
+/// Creates a workspace owned by the main profile. Its folders are shared with the members and its inboxes are
+/// replicated between their nodes.
+#[utoipa::path(
+    post,
+    path = "/v2/create_workspace",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The workspace created", body = Value),
+        (status = 400, description = "Invalid name, member, folder or inbox", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn create_workspace_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APICreateWorkspace,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiCreateWorkspace {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Changes the name, folders or inboxes of a workspace owned by this node
+#[utoipa::path(
+    post,
+    path = "/v2/update_workspace",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The workspace updated", body = Value),
+        (status = 400, description = "Invalid name, folder or inbox", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError),
+        (status = 403, description = "The workspace isn't owned by this node", body = APIError),
+        (status = 404, description = "Workspace not found", body = APIError)
+    )
+)]
+pub async fn update_workspace_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIUpdateWorkspace,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiUpdateWorkspace {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Adds a member to a workspace owned by this node or changes its role. The member is removed if no role is set.
+#[utoipa::path(
+    post,
+    path = "/v2/set_workspace_member",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The workspace updated", body = Value),
+        (status = 400, description = "Invalid member", body = APIError),
+        (status = 401, description = "Unauthorized", body = APIError),
+        (status = 403, description = "The workspace isn't owned by this node", body = APIError),
+        (status = 404, description = "Workspace or member not found", body = APIError)
+    )
+)]
+pub async fn set_workspace_member_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APISetWorkspaceMember,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetWorkspaceMember {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Workspaces owned by this node and the ones it's a member of
+#[utoipa::path(
+    get,
+    path = "/v2/workspaces",
+    responses(
+        (status = 200, description = "The workspaces", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn list_workspaces_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiListWorkspaces {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Workspaces other nodes added this node to, not accepted yet
+#[utoipa::path(
+    get,
+    path = "/v2/workspace_invitations",
+    responses(
+        (status = 200, description = "The workspaces waiting to be accepted", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError)
+    )
+)]
+pub async fn list_workspace_invitations_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiListWorkspaceInvitations {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Accepts a workspace this node was invited to. It's saved and its folders are subscribed to from the profile
+/// of this node that's a member.
+#[utoipa::path(
+    post,
+    path = "/v2/accept_workspace_invitation",
+    request_body = Value,
+    responses(
+        (status = 200, description = "The workspace accepted", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError),
+        (status = 403, description = "The member isn't a profile of this node", body = APIError),
+        (status = 404, description = "Invitation not found", body = APIError)
+    )
+)]
+pub async fn accept_workspace_invitation_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIWorkspaceInvitation,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiAcceptWorkspaceInvitation {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+/// Forgets a workspace this node was invited to
+#[utoipa::path(
+    post,
+    path = "/v2/decline_workspace_invitation",
+    request_body = Value,
+    responses(
+        (status = 200, description = "Invitation declined", body = Value),
+        (status = 401, description = "Unauthorized", body = APIError),
+        (status = 404, description = "Invitation not found", body = APIError)
+    )
+)]
+pub async fn decline_workspace_invitation_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIWorkspaceInvitation,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiDeclineWorkspaceInvitation {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        create_workspace_handler,
+        update_workspace_handler,
+        set_workspace_member_handler,
+        list_workspaces_handler,
+        list_workspace_invitations_handler,
+        accept_workspace_invitation_handler,
+        decline_workspace_invitation_handler
+    ),
+    components(schemas(APIError)),
+    tags(
+        (name = "workspaces", description = "Team workspaces with replicated inboxes and folders API endpoints")
+    )
+)]
+pub struct WorkspacesApiDoc;
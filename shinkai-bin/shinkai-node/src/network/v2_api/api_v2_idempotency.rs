@@ -0,0 +1,171 @@
+use std::future::Future;
+
+use async_channel::Sender;
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::{json, Value};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use warp::Filter;
+
+use crate::network::{error_code::ErrorCode, node_api_router::APIError, node_commands::NodeCommand};
+
+/// Optional `Idempotency-Key` header of the mutating endpoints. Retrying a request with the same key returns the
+/// response of the first one instead of running it again, e.g. creating a second job.
+pub fn with_idempotency_key() -> impl Filter<Extract = (Option<String>,), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("idempotency-key")
+}
+
+/// Hash of the request the key is used for, so the key can't be reused for a different one
+pub fn idempotency_request_hash(endpoint: &str, request: &[u8]) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(endpoint.as_bytes());
+    hasher.update(request);
+    hasher.finalize().to_hex().to_string()
+}
+
+async fn send_command<T>(
+    node_commands_sender: &Sender<NodeCommand>,
+    command: impl FnOnce(Sender<Result<T, APIError>>) -> NodeCommand,
+) -> Result<Result<T, APIError>, warp::Rejection> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(command(res_sender))
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    res_receiver.recv().await.map_err(|_| warp::reject::reject())
+}
+
+/// Runs the request unless it already ran with the same idempotency key, in which case its stored response is
+/// returned. Only successful responses are stored, failed or rejected requests can be retried with the same key.
+pub async fn run_idempotent<T, F>(
+    node_commands_sender: &Sender<NodeCommand>,
+    bearer: &str,
+    idempotency_key: Option<String>,
+    endpoint: &str,
+    request: &[u8],
+    run: F,
+) -> Result<Result<Value, APIError>, warp::Rejection>
+where
+    T: Serialize,
+    F: Future<Output = Result<Result<T, APIError>, warp::Rejection>>,
+{
+    let Some(idempotency_key) = idempotency_key else {
+        return Ok(run.await?.map(|response| json!(response)));
+    };
+    if idempotency_key.is_empty() || idempotency_key.len() > 255 {
+        return Ok(Err(APIError {
+            code: StatusCode::BAD_REQUEST.as_u16(),
+            error_code: ErrorCode::InvalidInput,
+            error: "Bad Request".to_string(),
+            message: "The Idempotency-Key must have between 1 and 255 characters".to_string(),
+        }));
+    }
+
+    // Keys are per endpoint, so clients can use the same one for the steps of an operation
+    let idempotency_key = format!("{}:{}", endpoint, idempotency_key);
+    let request_hash = idempotency_request_hash(endpoint, request);
+    let started = send_command(node_commands_sender, |res| NodeCommand::V2ApiStartIdempotentRequest {
+        bearer: bearer.to_string(),
+        idempotency_key: idempotency_key.clone(),
+        request_hash,
+        res,
+    })
+    .await?;
+    match started {
+        Ok(Some(response)) => return Ok(Ok(response)),
+        Ok(None) => {}
+        Err(error) => return Ok(Err(error)),
+    }
+
+    let reservation = IdempotencyReservation {
+        node_commands_sender: node_commands_sender.clone(),
+        bearer: bearer.to_string(),
+        idempotency_key: Some(idempotency_key),
+    };
+    let result = run.await.map(|result| result.map(|response| json!(response)));
+    let response = match &result {
+        Ok(Ok(response)) => Some(response.clone()),
+        _ => None,
+    };
+    reservation.finish(response).await;
+    result
+}
+
+/// Key reserved for a running request. Finishing it stores the response, or releases the key when there is none.
+/// A reservation dropped before finishing (e.g. the client went away) is released in the background.
+struct IdempotencyReservation {
+    node_commands_sender: Sender<NodeCommand>,
+    bearer: String,
+    idempotency_key: Option<String>,
+}
+
+impl IdempotencyReservation {
+    async fn finish(mut self, response: Option<Value>) {
+        if let Some(idempotency_key) = self.idempotency_key.take() {
+            Self::send_finish(&self.node_commands_sender, &self.bearer, idempotency_key, response).await;
+        }
+    }
+
+    async fn send_finish(
+        node_commands_sender: &Sender<NodeCommand>,
+        bearer: &str,
+        idempotency_key: String,
+        response: Option<Value>,
+    ) {
+        let result = send_command(node_commands_sender, |res| NodeCommand::V2ApiFinishIdempotentRequest {
+            bearer: bearer.to_string(),
+            idempotency_key,
+            response,
+            res,
+        })
+        .await;
+        let error = match result {
+            Ok(Ok(())) => return,
+            Ok(Err(error)) => error.message,
+            Err(_) => "the node didn't answer".to_string(),
+        };
+        shinkai_log(
+            ShinkaiLogOption::Api,
+            ShinkaiLogLevel::Error,
+            &format!("Failed to finish idempotent request: {}", error),
+        );
+    }
+}
+
+impl Drop for IdempotencyReservation {
+    fn drop(&mut self) {
+        let Some(idempotency_key) = self.idempotency_key.take() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let node_commands_sender = self.node_commands_sender.clone();
+        let bearer = self.bearer.clone();
+        runtime.spawn(async move {
+            Self::send_finish(&node_commands_sender, &bearer, idempotency_key, None).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idempotency_request_hash() {
+        let hash = idempotency_request_hash("create_folder", br#"{"path":"/","folder_name":"docs"}"#);
+        assert_eq!(
+            hash,
+            idempotency_request_hash("create_folder", br#"{"path":"/","folder_name":"docs"}"#)
+        );
+        assert_ne!(
+            hash,
+            idempotency_request_hash("create_folder", br#"{"path":"/","folder_name":"notes"}"#)
+        );
+        assert_ne!(
+            hash,
+            idempotency_request_hash("delete_folder", br#"{"path":"/","folder_name":"docs"}"#)
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use reqwest::StatusCode;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::Config;
+use warp::http::Uri;
+use warp::hyper::Response;
+use warp::path::{FullPath, Tail};
+use warp::{Filter, Rejection, Reply};
+
+use crate::network::error_code::ErrorCode;
+
+use super::api_v2_handlers_batch::BatchApiDoc;
+use super::api_v2_handlers_contacts::ContactsApiDoc;
+use super::api_v2_handlers_events::EventsApiDoc;
+use super::api_v2_handlers_general::GeneralApiDoc;
+use super::api_v2_handlers_jobs::JobsApiDoc;
+use super::api_v2_handlers_openai::OpenAIApiDoc;
+use super::api_v2_handlers_payments::PaymentsApiDoc;
+use super::api_v2_handlers_subscriptions::SubscriptionsApiDoc;
+use super::api_v2_handlers_vecfs::VecFsApiDoc;
+use super::api_v2_handlers_workflows::WorkflowsApiDoc;
+use super::api_v2_handlers_publications::PublicationsApiDoc;
+use super::api_v2_handlers_workspaces::WorkspacesApiDoc;
+
+/// Adds the API_V2_KEY bearer auth that every endpoint expects
+struct BearerSecurity;
+
+impl Modify for BearerSecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Shinkai Node API",
+        description = "HTTP API of the Shinkai Node. Requests are authenticated with the API_V2_KEY as a bearer token."
+    ),
+    components(schemas(ErrorCode)),
+    modifiers(&BearerSecurity),
+    security(("bearer" = []))
+)]
+struct NodeApiDoc;
+
+/// OpenAPI document of the whole HTTP API, made of the documents of each group of handlers
+pub fn openapi_document() -> utoipa::openapi::OpenApi {
+    let mut document = NodeApiDoc::openapi();
+    document.merge(GeneralApiDoc::openapi());
+    document.merge(JobsApiDoc::openapi());
+    document.merge(VecFsApiDoc::openapi());
+    document.merge(SubscriptionsApiDoc::openapi());
+    document.merge(WorkflowsApiDoc::openapi());
+    document.merge(EventsApiDoc::openapi());
+    document.merge(BatchApiDoc::openapi());
+    document.merge(OpenAIApiDoc::openapi());
+    document.merge(PaymentsApiDoc::openapi());
+    document.merge(WorkspacesApiDoc::openapi());
+    document.merge(ContactsApiDoc::openapi());
+    document.merge(PublicationsApiDoc::openapi());
+    document
+}
+
+/// `GET /v2/openapi.json` with the generated document and a Swagger UI for it at `/v2/swagger-ui/`.
+/// They don't require the API key, so SDK generators can fetch the document directly.
+pub fn openapi_routes() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let document = Arc::new(openapi_document());
+    let openapi_json_route = warp::path("openapi.json")
+        .and(warp::path::end())
+        .and(warp::get())
+        .map(move || warp::reply::json(document.as_ref()));
+
+    let config = Arc::new(Config::from("/v2/openapi.json"));
+    let swagger_ui_route = warp::path("swagger-ui")
+        .and(warp::get())
+        .and(warp::path::full())
+        .and(warp::path::tail())
+        .and(warp::any().map(move || config.clone()))
+        .and_then(swagger_ui_handler);
+
+    openapi_json_route.or(swagger_ui_route)
+}
+
+async fn swagger_ui_handler(
+    full_path: FullPath,
+    tail: Tail,
+    config: Arc<Config<'static>>,
+) -> Result<Box<dyn Reply + 'static>, Rejection> {
+    // The UI loads its files relative to the folder
+    if full_path.as_str() == "/v2/swagger-ui" {
+        return Ok(Box::new(warp::redirect::found(Uri::from_static("/v2/swagger-ui/"))));
+    }
+
+    match utoipa_swagger_ui::serve(tail.as_str(), config) {
+        Ok(Some(file)) => Ok(Box::new(
+            Response::builder()
+                .header("Content-Type", file.content_type)
+                .body(file.bytes.to_vec()),
+        )),
+        Ok(None) => Ok(Box::new(StatusCode::NOT_FOUND)),
+        Err(error) => Ok(Box::new(
+            Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(error.to_string()),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_document_has_all_handlers() {
+        let document = openapi_document();
+        assert!(document.paths.paths.contains_key("/v2/create_job"));
+        assert!(document.paths.paths.contains_key("/v2/retrieve_path_simplified"));
+        assert!(document.paths.paths.contains_key("/v1/chat/completions"));
+
+        let components = document.components.unwrap();
+        assert!(components.security_schemes.contains_key("bearer"));
+        assert!(components.schemas.contains_key("APIError"));
+        assert!(components.schemas.contains_key("ErrorCode"));
+    }
+}
@@ -1,8 +1,16 @@
 use crate::network::node_commands::NodeCommand;
+use crate::network::request_id::request_scoped_sender;
 
+use super::api_v2_handlers_batch::batch_routes;
+use super::api_v2_handlers_contacts::contacts_routes;
+use super::api_v2_handlers_events::events_routes;
 use super::api_v2_handlers_jobs::job_routes;
+use super::api_v2_handlers_payments::payments_routes;
+use super::api_v2_handlers_publications::publications_routes;
 use super::api_v2_handlers_vecfs::vecfs_routes;
 use super::api_v2_handlers_workflows::workflows_routes;
+use super::api_v2_handlers_workspaces::workspaces_routes;
+use super::api_v2_openapi::openapi_routes;
 use super::{api_v2_handlers_general::general_routes, api_v2_handlers_subscriptions::subscriptions_routes};
 use async_channel::Sender;
 use serde::Serialize;
@@ -19,18 +27,36 @@ pub fn v2_routes(
     let job_routes = job_routes(node_commands_sender.clone(), node_name.clone());
     let subscriptions_routes = subscriptions_routes(node_commands_sender.clone());
     let workflows_routes = workflows_routes(node_commands_sender.clone());
+    let events_routes = events_routes(node_commands_sender.clone());
+    let batch_routes = batch_routes(node_commands_sender.clone());
+    let payments_routes = payments_routes(node_commands_sender.clone());
+    let workspaces_routes = workspaces_routes(node_commands_sender.clone());
+    let publications_routes = publications_routes(node_commands_sender.clone());
+    let contacts_routes = contacts_routes(node_commands_sender.clone());
 
-    general_routes
+    let routes = general_routes
         .or(vecfs_routes)
         .or(job_routes)
         .or(subscriptions_routes)
         .or(workflows_routes)
+        .or(events_routes)
+        .or(batch_routes)
+        .or(payments_routes)
+        .or(workspaces_routes)
+        .or(publications_routes)
+        .or(contacts_routes)
+        .or(openapi_routes());
+
+    #[cfg(feature = "graphql")]
+    let routes = routes.or(super::api_v2_graphql::graphql_routes(node_commands_sender.clone()));
+
+    routes
 }
 
 pub fn with_sender(
     sender: Sender<NodeCommand>,
 ) -> impl Filter<Extract = (Sender<NodeCommand>,), Error = std::convert::Infallible> + Clone {
-    warp::any().map(move || sender.clone())
+    warp::any().map(move || request_scoped_sender(&sender))
 }
 
 pub fn with_node_name(node_name: String) -> impl Filter<Extract = (String,), Error = std::convert::Infallible> + Clone {
@@ -4,8 +4,24 @@ pub mod api_v2_commands_jobs;
 pub mod api_v2_commands_vecfs;
 pub mod api_v2_commands_subscriptions;
 pub mod api_v2_commands_workflows;
+pub mod api_v2_commands_openai;
+pub mod api_v2_commands_payments;
+pub mod api_v2_commands_workspaces;
+pub mod api_v2_commands_publications;
+pub mod api_v2_commands_contacts;
 pub mod api_v2_handlers_general;
 pub mod api_v2_handlers_vecfs;
 pub mod api_v2_handlers_jobs;
 pub mod api_v2_handlers_subscriptions;
-pub mod api_v2_handlers_workflows;
\ No newline at end of file
+pub mod api_v2_handlers_workflows;
+pub mod api_v2_handlers_openai;
+pub mod api_v2_handlers_events;
+pub mod api_v2_handlers_batch;
+pub mod api_v2_handlers_payments;
+pub mod api_v2_handlers_workspaces;
+pub mod api_v2_handlers_publications;
+pub mod api_v2_handlers_contacts;
+pub mod api_v2_idempotency;
+pub mod api_v2_openapi;
+#[cfg(feature = "graphql")]
+pub mod api_v2_graphql;
\ No newline at end of file
@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+use shinkai_vector_resources::source::{NotarizedSourceReference, SourceReference, VRSourceReference};
+use shinkai_vector_resources::vector_resource::VRPath;
+
+use crate::vector_fs::vector_fs_types::{FSFolder, FSItem, FSRoot};
+
+/// A VectorFS folder or the source file of an item, as listed to WebDAV clients
+#[derive(Debug, Clone, PartialEq)]
+pub struct DavResource {
+    /// Path of the folder or item in the VectorFS
+    pub path: VRPath,
+    /// For items, their name followed by the extension of their source file
+    pub name: String,
+    pub is_collection: bool,
+    pub etag: String,
+    pub created_datetime: DateTime<Utc>,
+    pub last_modified_datetime: DateTime<Utc>,
+}
+
+impl DavResource {
+    pub fn from_root(root: &FSRoot) -> Self {
+        Self {
+            path: root.path.clone(),
+            name: String::new(),
+            is_collection: true,
+            etag: root.merkle_root.clone(),
+            created_datetime: root.created_datetime,
+            last_modified_datetime: root.last_written_datetime,
+        }
+    }
+
+    pub fn from_folder(folder: &FSFolder) -> Self {
+        Self {
+            path: folder.path.clone(),
+            name: folder.name.clone(),
+            is_collection: true,
+            etag: folder.merkle_hash.clone(),
+            created_datetime: folder.created_datetime,
+            last_modified_datetime: folder.last_written_datetime,
+        }
+    }
+
+    pub fn from_item(item: &FSItem) -> Self {
+        Self {
+            path: item.path.clone(),
+            name: Self::item_file_name(item),
+            is_collection: false,
+            etag: item.merkle_hash.clone(),
+            created_datetime: item.created_datetime,
+            last_modified_datetime: item.last_written_datetime,
+        }
+    }
+
+    /// Items are saved without the extension of the file they were ingested from, which clients need to open them
+    pub fn item_file_name(item: &FSItem) -> String {
+        match &item.vr_header.resource_source {
+            VRSourceReference::Standard(SourceReference::FileRef(file_ref)) => {
+                format!("{}.{}", item.name, file_ref.file_type)
+            }
+            VRSourceReference::Notarized(NotarizedSourceReference::TLSNotarized(tls_ref)) => {
+                format!("{}.{}", item.name, tls_ref.file_type)
+            }
+            _ => item.name.clone(),
+        }
+    }
+
+    /// Only items whose source file was kept have a file to serve
+    pub fn item_has_source_file(item: &FSItem) -> bool {
+        item.source_file_map_last_saved_datetime.is_some()
+    }
+
+    /// URL path of the resource, percent-encoded, with collections ending in `/`
+    pub fn href(&self) -> String {
+        let mut segments: Vec<String> = self.path.parent_path().path_ids.clone();
+        if !self.path.is_root() {
+            segments.push(self.name.clone());
+        }
+        let mut href = String::new();
+        for segment in segments {
+            href.push('/');
+            href.push_str(&urlencoding::encode(&segment));
+        }
+        if self.is_collection || href.is_empty() {
+            href.push('/');
+        }
+        href
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        if self.is_collection {
+            return "httpd/unix-directory";
+        }
+        let extension = self
+            .name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_lowercase());
+        match extension.as_deref() {
+            Some("pdf") => "application/pdf",
+            Some("txt") => "text/plain",
+            Some("md") => "text/markdown",
+            Some("html") | Some("htm") => "text/html",
+            Some("csv") => "text/csv",
+            Some("json") => "application/json",
+            Some("xml") => "application/xml",
+            Some("docx") => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            Some("xlsx") => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            Some("pptx") => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+            _ => "application/octet-stream",
+        }
+    }
+
+    fn propstat_xml(&self) -> String {
+        let resource_type = if self.is_collection {
+            "<D:resourcetype><D:collection/></D:resourcetype>"
+        } else {
+            "<D:resourcetype/>"
+        };
+        format!(
+            "<D:response><D:href>{}</D:href><D:propstat><D:prop>\
+             <D:displayname>{}</D:displayname>{}<D:getcontenttype>{}</D:getcontenttype>\
+             <D:creationdate>{}</D:creationdate><D:getlastmodified>{}</D:getlastmodified>\
+             <D:getetag>\"{}\"</D:getetag>\
+             </D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>",
+            escape_xml(&self.href()),
+            escape_xml(&self.name),
+            resource_type,
+            self.content_type(),
+            self.created_datetime.to_rfc3339(),
+            http_date(&self.last_modified_datetime),
+            escape_xml(&self.etag),
+        )
+    }
+}
+
+/// Body of a PROPFIND response listing the given resources. Sizes aren't listed since only the encoded size of
+/// the source files is known without reading them.
+pub fn multistatus_xml(resources: &[DavResource]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?><D:multistatus xmlns:D=\"DAV:\">");
+    for resource in resources {
+        xml.push_str(&resource.propstat_xml());
+    }
+    xml.push_str("</D:multistatus>");
+    xml
+}
+
+/// Datetime in the format of the Last-Modified header
+pub fn http_date(datetime: &DateTime<Utc>) -> String {
+    datetime.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multistatus_xml() {
+        let datetime = DateTime::parse_from_rfc3339("2024-05-01T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let folder = DavResource {
+            path: VRPath::from_string("/Research/Papers & Notes").unwrap(),
+            name: "Papers & Notes".to_string(),
+            is_collection: true,
+            etag: "abc".to_string(),
+            created_datetime: datetime,
+            last_modified_datetime: datetime,
+        };
+        let file = DavResource {
+            path: VRPath::from_string("/Research/Papers & Notes/summary").unwrap(),
+            name: "summary.pdf".to_string(),
+            is_collection: false,
+            etag: "def".to_string(),
+            created_datetime: datetime,
+            last_modified_datetime: datetime,
+        };
+        assert_eq!(folder.href(), "/Research/Papers%20%26%20Notes/");
+        assert_eq!(file.href(), "/Research/Papers%20%26%20Notes/summary.pdf");
+        assert_eq!(file.content_type(), "application/pdf");
+
+        let xml = multistatus_xml(&[folder, file]);
+        assert!(xml.contains("<D:displayname>Papers &amp; Notes</D:displayname><D:resourcetype><D:collection/>"));
+        assert!(xml.contains("<D:getlastmodified>Wed, 01 May 2024 10:00:00 GMT</D:getlastmodified>"));
+        assert_eq!(xml.matches("<D:response>").count(), 2);
+    }
+}
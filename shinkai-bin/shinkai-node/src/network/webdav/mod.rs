@@ -0,0 +1,425 @@
+pub mod dav_resource;
+
+use std::net::SocketAddr;
+
+use async_channel::Sender;
+use bytes::Bytes;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
+    APIVecFsCreateFolder, APIVecFsDeleteFolder, APIVecFsDeleteItem, APIVecFsRetrievePathSimplifiedJson,
+    APIVecFsRetrieveSourceFileMap,
+};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use shinkai_vector_resources::source::{SourceFile, SourceFileType};
+use shinkai_vector_resources::vector_resource::VRPath;
+use warp::http::{Method, Response, StatusCode};
+use warp::path::FullPath;
+use warp::Filter;
+
+use crate::vector_fs::vector_fs_types::{FSEntry, FSFolder, FSItem, FSRoot};
+
+use super::{error_code::ErrorCode, node_api_router::APIError, node_commands::NodeCommand};
+use dav_resource::{http_date, multistatus_xml, DavResource};
+
+const READ_METHODS: &str = "OPTIONS, PROPFIND, GET, HEAD";
+const WRITE_METHODS: &str = "PUT, DELETE, MKCOL";
+/// Largest file that can be put, the same as the uploads of the HTTP API
+const MAX_UPLOAD_SIZE: u64 = 1024 * 1024 * 200; // 200MB
+
+type DavResponse = Response<Vec<u8>>;
+
+/// Serves the source files of the VectorFS items over WebDAV, so they can be browsed from file managers. Clients
+/// log in with HTTP Basic auth, using API_V2_KEY as password and any user name. When `writable`, files put into a
+/// folder are ingested into it (replacing the item of the same name) and folders can be created and deleted.
+pub async fn run_webdav_server(
+    node_commands_sender: Sender<NodeCommand>,
+    address: SocketAddr,
+    writable: bool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    shinkai_log(
+        ShinkaiLogOption::Api,
+        ShinkaiLogLevel::Info,
+        &format!("Starting Node WebDAV server at: {} (writable: {})", &address, writable),
+    );
+
+    let server = WebDavServer {
+        node_commands_sender,
+        writable,
+    };
+    // Only PUT requests carry a file, whose size is checked before it's read
+    let put_server = server.clone();
+    let put = warp::put()
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::body::content_length_limit(MAX_UPLOAD_SIZE))
+        .and(warp::body::bytes())
+        .and_then(move |path, authorization, body| {
+            let server = put_server.clone();
+            async move { Ok::<_, warp::Rejection>(server.handle(Method::PUT, path, authorization, None, body).await) }
+        });
+    let other_methods = warp::method()
+        .and_then(reject_put)
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::header::optional::<String>("depth"))
+        .and_then(move |method, path, authorization, depth| {
+            let server = server.clone();
+            let body = Bytes::new();
+            async move { Ok::<_, warp::Rejection>(server.handle(method, path, authorization, depth, body).await) }
+        });
+    let routes = put.or(other_methods).unify();
+
+    let (_, serving) = warp::serve(routes).try_bind_ephemeral(address)?;
+    serving.await;
+    Ok(())
+}
+
+/// Leaves PUT requests to the route that limits their size
+async fn reject_put(method: Method) -> Result<Method, warp::Rejection> {
+    match method {
+        Method::PUT => Err(warp::reject::not_found()),
+        method => Ok(method),
+    }
+}
+
+/// A folder or item a URL path points to
+enum DavEntry {
+    Root(FSRoot),
+    Folder(FSFolder),
+    Item(FSItem),
+}
+
+#[derive(Clone)]
+struct WebDavServer {
+    node_commands_sender: Sender<NodeCommand>,
+    writable: bool,
+}
+
+impl WebDavServer {
+    async fn handle(
+        &self,
+        method: Method,
+        path: FullPath,
+        authorization: Option<String>,
+        depth: Option<String>,
+        body: Bytes,
+    ) -> DavResponse {
+        if method == Method::OPTIONS {
+            return self.options();
+        }
+        let Some(bearer) = authorization.as_deref().and_then(bearer_from_authorization) else {
+            return unauthorized();
+        };
+        let Some(segments) = path_segments(path.as_str()) else {
+            return status_response(StatusCode::BAD_REQUEST);
+        };
+
+        let result = match method.as_str() {
+            "PROPFIND" => self.propfind(&bearer, &segments, depth.as_deref() != Some("0")).await,
+            "GET" => self.get(&bearer, &segments, true).await,
+            "HEAD" => self.get(&bearer, &segments, false).await,
+            "PUT" | "DELETE" | "MKCOL" if !self.writable => Ok(self.method_not_allowed()),
+            "PUT" => self.put(&bearer, &segments, body).await,
+            "DELETE" => self.delete(&bearer, &segments).await,
+            "MKCOL" => self.mkcol(&bearer, &segments).await,
+            _ => Ok(self.method_not_allowed()),
+        };
+        result.unwrap_or_else(error_response)
+    }
+
+    fn allowed_methods(&self) -> String {
+        match self.writable {
+            true => format!("{}, {}", READ_METHODS, WRITE_METHODS),
+            false => READ_METHODS.to_string(),
+        }
+    }
+
+    fn options(&self) -> DavResponse {
+        Response::builder()
+            .status(StatusCode::OK)
+            .header("DAV", "1")
+            .header("MS-Author-Via", "DAV")
+            .header("Allow", self.allowed_methods())
+            .body(Vec::new())
+            .unwrap_or_default()
+    }
+
+    fn method_not_allowed(&self) -> DavResponse {
+        Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .header("Allow", self.allowed_methods())
+            .body(Vec::new())
+            .unwrap_or_default()
+    }
+
+    async fn propfind(&self, bearer: &str, segments: &[String], with_children: bool) -> Result<DavResponse, APIError> {
+        let mut resources = Vec::new();
+        match self.resolve(bearer, segments).await? {
+            None => return Ok(status_response(StatusCode::NOT_FOUND)),
+            Some(DavEntry::Root(root)) => {
+                resources.push(DavResource::from_root(&root));
+                if with_children {
+                    resources.extend(root.child_folders.iter().map(DavResource::from_folder));
+                }
+            }
+            Some(DavEntry::Folder(folder)) => {
+                resources.push(DavResource::from_folder(&folder));
+                if with_children {
+                    resources.extend(folder.child_folders.iter().map(DavResource::from_folder));
+                    resources.extend(
+                        folder
+                            .child_items
+                            .iter()
+                            .filter(|item| DavResource::item_has_source_file(item))
+                            .map(DavResource::from_item),
+                    );
+                }
+            }
+            Some(DavEntry::Item(item)) => resources.push(DavResource::from_item(&item)),
+        }
+
+        Ok(Response::builder()
+            .status(StatusCode::MULTI_STATUS)
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(multistatus_xml(&resources).into_bytes())
+            .unwrap_or_default())
+    }
+
+    async fn get(&self, bearer: &str, segments: &[String], with_body: bool) -> Result<DavResponse, APIError> {
+        let item = match self.resolve(bearer, segments).await? {
+            Some(DavEntry::Item(item)) => item,
+            Some(_) => return Ok(self.method_not_allowed()),
+            None => return Ok(status_response(StatusCode::NOT_FOUND)),
+        };
+
+        let payload = APIVecFsRetrieveSourceFileMap {
+            path: item.path.format_to_string(),
+        };
+        let source_file_map = self
+            .run_command(bearer, |bearer, res| NodeCommand::V2ApiVecFSRetrieveSourceFileMap {
+                bearer,
+                payload,
+                res,
+            })
+            .await?;
+        // Items ingested from a single file keep it at the root of their map
+        let source_file = source_file_map
+            .get_source_file(VRPath::root())
+            .or_else(|| source_file_map.map.values().next());
+        let content = match source_file {
+            Some(SourceFile::Standard(file)) => file.file_content.clone(),
+            Some(SourceFile::TLSNotarized(file)) => file.file_content.clone(),
+            None => return Ok(status_response(StatusCode::NOT_FOUND)),
+        };
+
+        let resource = DavResource::from_item(&item);
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", resource.content_type())
+            .header("Content-Length", content.len())
+            .header("ETag", format!("\"{}\"", resource.etag))
+            .header("Last-Modified", http_date(&resource.last_modified_datetime))
+            .body(if with_body { content } else { Vec::new() })
+            .unwrap_or_default())
+    }
+
+    async fn put(&self, bearer: &str, segments: &[String], body: Bytes) -> Result<DavResponse, APIError> {
+        let Some((file_name, parent_segments)) = segments.split_last() else {
+            return Ok(self.method_not_allowed());
+        };
+        // Finder writes metadata files next to the ones copied, which aren't worth ingesting
+        if file_name.starts_with("._") || file_name == ".DS_Store" {
+            return Ok(status_response(StatusCode::CREATED));
+        }
+        if SourceFileType::detect_file_type(file_name).is_err() {
+            return Ok(status_response(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+        }
+
+        let filename = file_name.clone();
+        let path = vecfs_path(parent_segments);
+        self.run_command(bearer, |bearer, res| NodeCommand::V2ApiUploadFileToFolder {
+            bearer,
+            filename,
+            file: body.to_vec(),
+            path,
+            file_datetime: None,
+            res,
+        })
+        .await?;
+        Ok(status_response(StatusCode::CREATED))
+    }
+
+    async fn delete(&self, bearer: &str, segments: &[String]) -> Result<DavResponse, APIError> {
+        match self.resolve(bearer, segments).await? {
+            None => return Ok(status_response(StatusCode::NOT_FOUND)),
+            Some(DavEntry::Root(_)) => return Ok(status_response(StatusCode::FORBIDDEN)),
+            Some(DavEntry::Folder(folder)) => {
+                let payload = APIVecFsDeleteFolder {
+                    path: folder.path.format_to_string(),
+                };
+                self.run_command(bearer, |bearer, res| NodeCommand::V2ApiDeleteFolder {
+                    bearer,
+                    payload,
+                    res,
+                })
+                .await?;
+            }
+            Some(DavEntry::Item(item)) => {
+                let payload = APIVecFsDeleteItem {
+                    path: item.path.format_to_string(),
+                };
+                self.run_command(bearer, |bearer, res| NodeCommand::V2ApiDeleteItem {
+                    bearer,
+                    payload,
+                    res,
+                })
+                .await?;
+            }
+        }
+        Ok(status_response(StatusCode::NO_CONTENT))
+    }
+
+    async fn mkcol(&self, bearer: &str, segments: &[String]) -> Result<DavResponse, APIError> {
+        let Some((folder_name, parent_segments)) = segments.split_last() else {
+            return Ok(self.method_not_allowed());
+        };
+        if self.resolve(bearer, segments).await?.is_some() {
+            return Ok(self.method_not_allowed());
+        }
+
+        let payload = APIVecFsCreateFolder {
+            path: vecfs_path(parent_segments),
+            folder_name: folder_name.clone(),
+        };
+        self.run_command(bearer, |bearer, res| NodeCommand::V2ApiVecFSCreateFolder {
+            bearer,
+            payload,
+            res,
+        })
+        .await?;
+        Ok(status_response(StatusCode::CREATED))
+    }
+
+    /// Finds what a URL path points to. Items are found in the listing of their folder, since their names in URLs
+    /// end with the extension of their source file.
+    async fn resolve(&self, bearer: &str, segments: &[String]) -> Result<Option<DavEntry>, APIError> {
+        let Some((name, parent_segments)) = segments.split_last() else {
+            return match self.retrieve_entry(bearer, "/").await? {
+                FSEntry::Root(root) => Ok(Some(DavEntry::Root(root))),
+                _ => Ok(None),
+            };
+        };
+
+        let (child_folders, child_items) = match self.retrieve_entry(bearer, &vecfs_path(parent_segments)).await {
+            Ok(FSEntry::Root(root)) => (root.child_folders, Vec::new()),
+            Ok(FSEntry::Folder(folder)) => (folder.child_folders, folder.child_items),
+            Ok(FSEntry::Item(_)) => return Ok(None),
+            Err(e) if e.error_code.status() == StatusCode::NOT_FOUND => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        if let Some(folder) = child_folders.into_iter().find(|folder| &folder.name == name) {
+            return Ok(Some(DavEntry::Folder(folder)));
+        }
+        Ok(child_items
+            .into_iter()
+            .find(|item| DavResource::item_has_source_file(item) && &DavResource::item_file_name(item) == name)
+            .map(DavEntry::Item))
+    }
+
+    async fn retrieve_entry(&self, bearer: &str, path: &str) -> Result<FSEntry, APIError> {
+        let payload = APIVecFsRetrievePathSimplifiedJson { path: path.to_string() };
+        let json = self
+            .run_command(bearer, |bearer, res| {
+                NodeCommand::V2ApiVecFSRetrievePathSimplifiedJson { bearer, payload, res }
+            })
+            .await?;
+        FSEntry::from_json(&json.to_string())
+            .map_err(|e| APIError::from_code(ErrorCode::VecfsError, &format!("Failed to parse VectorFS entry: {}", e)))
+    }
+
+    /// Sends a node command with the client's API key and waits for its result
+    async fn run_command<T>(
+        &self,
+        bearer: &str,
+        command: impl FnOnce(String, Sender<Result<T, APIError>>) -> NodeCommand,
+    ) -> Result<T, APIError> {
+        let (res_sender, res_receiver) = async_channel::bounded(1);
+        self.node_commands_sender
+            .send(command(bearer.to_string(), res_sender))
+            .await
+            .map_err(|e| APIError::from_code(ErrorCode::InternalError, &e.to_string()))?;
+        res_receiver
+            .recv()
+            .await
+            .map_err(|e| APIError::from_code(ErrorCode::InternalError, &e.to_string()))?
+    }
+}
+
+/// API key sent as the password of Basic auth, or as a Bearer token by clients that support it
+fn bearer_from_authorization(authorization: &str) -> Option<String> {
+    if let Some(token) = authorization.strip_prefix("Bearer ") {
+        return Some(token.to_string());
+    }
+    let credentials = base64::decode(authorization.strip_prefix("Basic ")?).ok()?;
+    let credentials = String::from_utf8(credentials).ok()?;
+    let (_, password) = credentials.split_once(':')?;
+    Some(password.to_string())
+}
+
+/// Decoded segments of a URL path, None if a segment isn't valid UTF-8 or tries to leave its folder.
+/// Encoded separators are rejected too, as `%2F..%2F` would otherwise become a path of its own once joined.
+pub(crate) fn path_segments(path: &str) -> Option<Vec<String>> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let segment = urlencoding::decode(segment).ok()?.into_owned();
+            if segment == "." || segment == ".." || segment.contains('/') || segment.contains('\\') {
+                return None;
+            }
+            Some(segment)
+        })
+        .collect()
+}
+
+fn vecfs_path(segments: &[String]) -> String {
+    format!("/{}", segments.join("/"))
+}
+
+fn status_response(status: StatusCode) -> DavResponse {
+    Response::builder().status(status).body(Vec::new()).unwrap_or_default()
+}
+
+fn unauthorized() -> DavResponse {
+    Response::builder()
+        .status(StatusCode::UNAUTHORIZED)
+        .header("WWW-Authenticate", "Basic realm=\"Shinkai VectorFS\"")
+        .body(Vec::new())
+        .unwrap_or_default()
+}
+
+fn error_response(error: APIError) -> DavResponse {
+    let status = error.error_code.status();
+    if status == StatusCode::UNAUTHORIZED {
+        return unauthorized();
+    }
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "text/plain; charset=utf-8")
+        .body(error.message.into_bytes())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_segments() {
+        assert_eq!(
+            path_segments("/My%20Files/report.md"),
+            Some(vec!["My Files".to_string(), "report.md".to_string()])
+        );
+        assert_eq!(path_segments("/My Files/../secret"), None);
+        assert_eq!(path_segments("/My Files/..%2F..%2Fsecret"), None);
+        assert_eq!(path_segments("/My Files/%2E%2E%5Csecret"), None);
+    }
+}
@@ -0,0 +1,743 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::Utc;
+use ed25519_dalek::SigningKey;
+use serde::Serialize;
+use shinkai_message_primitives::schemas::inbox_name::InboxName;
+use shinkai_message_primitives::schemas::inbox_permission::InboxPermission;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::schemas::shinkai_subscription_req::{FolderSubscription, SubscriptionPayment};
+use shinkai_message_primitives::schemas::workspace::{
+    Workspace, WorkspaceInboxMessages, WorkspaceMember, WorkspaceRole,
+};
+use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
+    APICreateWorkspace, APISetWorkspaceMember, APIUpdateWorkspace, MessageSchemaType,
+};
+use shinkai_message_primitives::shinkai_utils::encryption::clone_static_secret_key;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use shinkai_message_primitives::shinkai_utils::shinkai_message_builder::ShinkaiMessageBuilder;
+use shinkai_message_primitives::shinkai_utils::signatures::clone_signature_secret_key;
+use tokio::sync::Mutex;
+use x25519_dalek::StaticSecret as EncryptionStaticKey;
+
+use crate::db::db_errors::ShinkaiDBError;
+use crate::db::ShinkaiDB;
+use crate::managers::IdentityManager;
+use crate::network::node::ProxyConnectionInfo;
+use crate::network::subscription_manager::external_subscriber_manager::ExternalSubscriberManager;
+use crate::network::subscription_manager::my_subscription_manager::MySubscriptionsManager;
+use crate::network::ws_manager::WSUpdateHandler;
+use crate::network::Node;
+use crate::schemas::identity::Identity;
+
+/// Messages of an inbox looked at on each sync. Messages written faster than this between two syncs aren't replicated.
+const SYNC_MESSAGES_PER_INBOX: usize = 50;
+
+#[derive(Debug)]
+pub enum WorkspaceError {
+    InvalidInput(String),
+    NotFound(String),
+    /// The role of the identity doesn't allow the change
+    NotAllowed(String),
+    DatabaseError(ShinkaiDBError),
+    SubscriptionError(String),
+    NetworkError(String),
+}
+
+impl fmt::Display for WorkspaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorkspaceError::InvalidInput(msg) => write!(f, "Invalid workspace: {}", msg),
+            WorkspaceError::NotFound(workspace_id) => write!(f, "Workspace {} not found", workspace_id),
+            WorkspaceError::NotAllowed(msg) => write!(f, "{}", msg),
+            WorkspaceError::DatabaseError(err) => write!(f, "Database error: {}", err),
+            WorkspaceError::SubscriptionError(msg) => write!(f, "Failed to share the folders: {}", msg),
+            WorkspaceError::NetworkError(msg) => write!(f, "Failed to reach the member: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for WorkspaceError {}
+
+impl From<ShinkaiDBError> for WorkspaceError {
+    fn from(err: ShinkaiDBError) -> Self {
+        WorkspaceError::DatabaseError(err)
+    }
+}
+
+/// Workspaces replicate inboxes and VectorFS folders between the nodes of a team.
+///
+/// The node of the owner keeps the workspace and sends every new version to the members. The folders are shared
+/// from the owner profile through the subscription machinery, and only members can subscribe to them. The messages
+/// written to a workspace inbox are sent by the node they were written on to the other members, if its role allows
+/// writing.
+pub struct WorkspaceManager;
+
+impl WorkspaceManager {
+    pub async fn create_workspace(
+        db: &ShinkaiDB,
+        ext_subscription_manager: Arc<Mutex<ExternalSubscriberManager>>,
+        owner: &ShinkaiName,
+        request: APICreateWorkspace,
+    ) -> Result<Workspace, WorkspaceError> {
+        if request.name.trim().is_empty() {
+            return Err(WorkspaceError::InvalidInput("The name is required".to_string()));
+        }
+        if owner.get_profile_name_string().is_none() {
+            return Err(WorkspaceError::InvalidInput(format!("{} isn't a profile", owner)));
+        }
+
+        let mut workspace = Workspace {
+            workspace_id: uuid::Uuid::new_v4().to_string(),
+            name: request.name.trim().to_string(),
+            owner: owner.to_string(),
+            members: Vec::new(),
+            folders: Self::validate_paths(request.folders, "folder")?,
+            inboxes: Self::validate_inboxes(request.inboxes)?,
+            version: 1,
+            updated_at: Utc::now(),
+        };
+        for member in request.members {
+            Self::apply_member(
+                &mut workspace,
+                APISetWorkspaceMember {
+                    workspace_id: workspace.workspace_id.clone(),
+                    identity: member.identity,
+                    role: Some(member.role),
+                },
+            )?;
+        }
+
+        Self::share_folders(&workspace, &workspace.folders, ext_subscription_manager).await?;
+        db.set_workspace(&workspace)?;
+        Ok(workspace)
+    }
+
+    /// Changes the name, folders or inboxes of a workspace this node owns
+    pub async fn update_workspace(
+        db: &ShinkaiDB,
+        ext_subscription_manager: Arc<Mutex<ExternalSubscriberManager>>,
+        node_name: &ShinkaiName,
+        request: APIUpdateWorkspace,
+    ) -> Result<Workspace, WorkspaceError> {
+        let mut workspace = Self::get_managed_workspace(db, node_name, &request.workspace_id)?;
+
+        if let Some(name) = request.name {
+            if name.trim().is_empty() {
+                return Err(WorkspaceError::InvalidInput("The name is required".to_string()));
+            }
+            workspace.name = name.trim().to_string();
+        }
+        if let Some(folders) = request.folders {
+            let folders = Self::validate_paths(folders, "folder")?;
+            let new_folders: Vec<String> = folders
+                .iter()
+                .filter(|folder| !workspace.folders.contains(folder))
+                .cloned()
+                .collect();
+            Self::share_folders(&workspace, &new_folders, ext_subscription_manager).await?;
+            workspace.folders = folders;
+        }
+        if let Some(inboxes) = request.inboxes {
+            workspace.inboxes = Self::validate_inboxes(inboxes)?;
+        }
+
+        workspace.version += 1;
+        workspace.updated_at = Utc::now();
+        db.set_workspace(&workspace)?;
+        Ok(workspace)
+    }
+
+    /// Adds, changes the role of or removes a member of a workspace this node owns.
+    /// Returns the workspace and the member removed, if any, who still has to be told.
+    pub async fn set_member(
+        db: &ShinkaiDB,
+        ext_subscription_manager: Arc<Mutex<ExternalSubscriberManager>>,
+        node_name: &ShinkaiName,
+        request: APISetWorkspaceMember,
+    ) -> Result<(Workspace, Option<String>), WorkspaceError> {
+        let mut workspace = Self::get_managed_workspace(db, node_name, &request.workspace_id)?;
+        let removed = Self::apply_member(&mut workspace, request)?;
+
+        // A removed member stops getting the changes of the folders
+        if let Some(identity) = &removed {
+            let owner =
+                ShinkaiName::new(workspace.owner.clone()).map_err(|e| WorkspaceError::InvalidInput(e.to_string()))?;
+            let requester =
+                ShinkaiName::new(identity.clone()).map_err(|e| WorkspaceError::InvalidInput(e.to_string()))?;
+            let mut ext_subscription_manager = ext_subscription_manager.lock().await;
+            for folder in &workspace.folders {
+                let _ = ext_subscription_manager
+                    .unsubscribe_from_shared_folder(requester.clone(), owner.clone(), folder.clone())
+                    .await;
+            }
+        }
+
+        workspace.version += 1;
+        workspace.updated_at = Utc::now();
+        db.set_workspace(&workspace)?;
+        Ok((workspace, removed))
+    }
+
+    fn apply_member(
+        workspace: &mut Workspace,
+        request: APISetWorkspaceMember,
+    ) -> Result<Option<String>, WorkspaceError> {
+        let identity = ShinkaiName::new(request.identity.clone())
+            .map_err(|e| WorkspaceError::InvalidInput(format!("Invalid member {}: {}", request.identity, e)))?;
+        if identity.get_profile_name_string().is_none() {
+            return Err(WorkspaceError::InvalidInput(format!(
+                "Member {} must be a profile, e.g. @@bob.shinkai/main",
+                request.identity
+            )));
+        }
+        if workspace.role_of(&identity) == Some(WorkspaceRole::Owner) {
+            return Err(WorkspaceError::NotAllowed(
+                "The role of the owner can't be changed".to_string(),
+            ));
+        }
+
+        let node = identity.extract_node();
+        let existing = workspace.members.iter().position(|member| {
+            ShinkaiName::new(member.identity.clone()).map_or(false, |name| name.extract_node() == node)
+        });
+        match (request.role, existing) {
+            (Some(WorkspaceRole::Owner), _) => {
+                Err(WorkspaceError::NotAllowed("A workspace has a single owner".to_string()))
+            }
+            (Some(role), Some(index)) => {
+                workspace.members[index].role = role;
+                workspace.members[index].identity = identity.to_string();
+                Ok(None)
+            }
+            (Some(role), None) => {
+                workspace.members.push(WorkspaceMember {
+                    identity: identity.to_string(),
+                    role,
+                });
+                Ok(None)
+            }
+            (None, Some(index)) => Ok(Some(workspace.members.remove(index).identity)),
+            (None, None) => Err(WorkspaceError::NotFound(format!(
+                "{} member {}",
+                workspace.workspace_id, request.identity
+            ))),
+        }
+    }
+
+    fn get_managed_workspace(
+        db: &ShinkaiDB,
+        node_name: &ShinkaiName,
+        workspace_id: &str,
+    ) -> Result<Workspace, WorkspaceError> {
+        let workspace = Self::get_workspace(db, workspace_id)?;
+        if !workspace.role_of(node_name).map_or(false, |role| role.can_manage()) {
+            return Err(WorkspaceError::NotAllowed(format!(
+                "Only the owner {} can change workspace {}",
+                workspace.owner, workspace_id
+            )));
+        }
+        Ok(workspace)
+    }
+
+    pub fn get_workspace(db: &ShinkaiDB, workspace_id: &str) -> Result<Workspace, WorkspaceError> {
+        db.get_workspace(workspace_id).map_err(|e| match e {
+            ShinkaiDBError::DataNotFound => WorkspaceError::NotFound(workspace_id.to_string()),
+            e => WorkspaceError::DatabaseError(e),
+        })
+    }
+
+    fn validate_paths(paths: Vec<String>, kind: &str) -> Result<Vec<String>, WorkspaceError> {
+        let mut unique = HashSet::new();
+        let mut result = Vec::new();
+        for path in paths {
+            let path = path.trim().trim_end_matches('/').to_string();
+            if !path.starts_with('/') || path.len() < 2 {
+                return Err(WorkspaceError::InvalidInput(format!("Invalid {} path {}", kind, path)));
+            }
+            if unique.insert(path.clone()) {
+                result.push(path);
+            }
+        }
+        Ok(result)
+    }
+
+    fn validate_inboxes(inboxes: Vec<String>) -> Result<Vec<String>, WorkspaceError> {
+        let mut unique = HashSet::new();
+        let mut result = Vec::new();
+        for inbox in inboxes {
+            InboxName::new(inbox.clone())
+                .map_err(|e| WorkspaceError::InvalidInput(format!("Invalid inbox {}: {}", inbox, e)))?;
+            if unique.insert(inbox.clone()) {
+                result.push(inbox);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Shares the folders from the profile of the owner so the members can subscribe to them
+    async fn share_folders(
+        workspace: &Workspace,
+        folders: &[String],
+        ext_subscription_manager: Arc<Mutex<ExternalSubscriberManager>>,
+    ) -> Result<(), WorkspaceError> {
+        let owner =
+            ShinkaiName::new(workspace.owner.clone()).map_err(|e| WorkspaceError::InvalidInput(e.to_string()))?;
+        let mut ext_subscription_manager = ext_subscription_manager.lock().await;
+        for folder in folders {
+            let requirement = FolderSubscription {
+                minimum_token_delegation: None,
+                minimum_time_delegated_hours: None,
+                monthly_payment: None,
+                is_free: true,
+                has_web_alternative: Some(false),
+                folder_description: format!("Folder of workspace {}", workspace.name),
+            };
+            ext_subscription_manager
+                .create_shareable_folder(folder.clone(), owner.clone(), requirement, None)
+                .await
+                .map_err(|e| WorkspaceError::SubscriptionError(format!("{}: {}", folder, e)))?;
+        }
+        Ok(())
+    }
+
+    /// Errors if the folder of the profile belongs to a workspace the requester isn't a member of
+    pub fn check_folder_subscriber(
+        db: &ShinkaiDB,
+        owner: &ShinkaiName,
+        path: &str,
+        requester: &ShinkaiName,
+    ) -> Result<(), WorkspaceError> {
+        if let Some(workspace) = db.get_workspace_for_folder(&owner.to_string(), path)? {
+            if workspace.role_of(requester).is_none() {
+                return Err(WorkspaceError::NotAllowed(format!(
+                    "{} is only shared with the members of workspace {}",
+                    path, workspace.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the requester is an owner or editor of the workspace the folder of the profile belongs to
+    pub fn is_folder_writer(
+        db: &ShinkaiDB,
+        owner: &ShinkaiName,
+        path: &str,
+        requester: &ShinkaiName,
+    ) -> Result<bool, WorkspaceError> {
+        Ok(db
+            .get_workspace_for_folder(&owner.to_string(), path)?
+            .and_then(|workspace| workspace.role_of(requester))
+            .map_or(false, |role| role.can_write()))
+    }
+
+    /// Saves a workspace sent by the node of its owner, and subscribes to the folders added to it.
+    /// A workspace this node doesn't have yet is kept as an invitation until a local profile accepts it, and the
+    /// workspace or invitation is removed if this node isn't a member anymore.
+    pub async fn receive_update(
+        db: &ShinkaiDB,
+        my_subscription_manager: Arc<Mutex<MySubscriptionsManager>>,
+        node_name: &ShinkaiName,
+        sender: &ShinkaiName,
+        workspace: Workspace,
+    ) -> Result<(), WorkspaceError> {
+        let previous = match db.get_workspace(&workspace.workspace_id) {
+            Ok(previous) => Some(previous),
+            Err(ShinkaiDBError::DataNotFound) => None,
+            Err(e) => return Err(e.into()),
+        };
+        let invitation = match (&previous, db.get_workspace_invitation(&workspace.workspace_id)) {
+            (Some(_), _) | (None, Err(ShinkaiDBError::DataNotFound)) => None,
+            (None, Ok(invitation)) => Some(invitation),
+            (None, Err(e)) => return Err(e.into()),
+        };
+        // Only the node of the owner the workspace is known with can update it, a new workspace has to come from the
+        // node of its owner
+        let known = previous.as_ref().or(invitation.as_ref());
+        let known_owner = known.unwrap_or(&workspace);
+        if known_owner.role_of(sender) != Some(WorkspaceRole::Owner) {
+            return Err(WorkspaceError::NotAllowed(format!(
+                "{} sent workspace {} owned by {}",
+                sender, workspace.workspace_id, known_owner.owner
+            )));
+        }
+        if let Some(known) = known {
+            if known.owner != workspace.owner {
+                return Err(WorkspaceError::NotAllowed(format!(
+                    "The owner of workspace {} can't change",
+                    workspace.workspace_id
+                )));
+            }
+            if known.version >= workspace.version {
+                return Ok(());
+            }
+        }
+
+        let Some(my_identity) = Self::my_member_identity(&workspace, node_name)? else {
+            db.remove_workspace(&workspace.workspace_id)?;
+            db.remove_workspace_invitation(&workspace.workspace_id)?;
+            return Ok(());
+        };
+        // Any node can name this one as a member, so nothing is saved nor subscribed to until it's accepted
+        let Some(previous) = previous else {
+            db.set_workspace_invitation(&workspace)?;
+            return Ok(());
+        };
+        db.set_workspace(&workspace)?;
+
+        let new_folders: Vec<String> = workspace
+            .folders
+            .iter()
+            .filter(|folder| !previous.folders.contains(folder))
+            .cloned()
+            .collect();
+        Self::subscribe_to_folders(&workspace, &new_folders, &my_identity, my_subscription_manager).await;
+        Ok(())
+    }
+
+    /// Saves a workspace this node was invited to and subscribes to its folders, once the profile of this node that's
+    /// a member of it accepts
+    pub async fn accept_invitation(
+        db: &ShinkaiDB,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        my_subscription_manager: Arc<Mutex<MySubscriptionsManager>>,
+        node_name: &ShinkaiName,
+        workspace_id: &str,
+    ) -> Result<Workspace, WorkspaceError> {
+        let workspace = Self::get_invitation(db, workspace_id)?;
+        let my_identity = Self::my_member_identity(&workspace, node_name)?.ok_or_else(|| {
+            WorkspaceError::NotAllowed(format!("This node isn't a member of workspace {}", workspace_id))
+        })?;
+        match identity_manager
+            .lock()
+            .await
+            .search_local_identity(&my_identity.to_string())
+            .await
+        {
+            Some(Identity::Standard(_)) => {}
+            _ => {
+                return Err(WorkspaceError::NotAllowed(format!(
+                    "{} isn't a profile of this node",
+                    my_identity
+                )))
+            }
+        }
+
+        db.set_workspace(&workspace)?;
+        db.remove_workspace_invitation(workspace_id)?;
+        Self::subscribe_to_folders(&workspace, &workspace.folders, &my_identity, my_subscription_manager).await;
+        Ok(workspace)
+    }
+
+    /// Forgets a workspace this node was invited to. The owner can send it again with its next update.
+    pub fn decline_invitation(db: &ShinkaiDB, workspace_id: &str) -> Result<(), WorkspaceError> {
+        Self::get_invitation(db, workspace_id)?;
+        db.remove_workspace_invitation(workspace_id)?;
+        Ok(())
+    }
+
+    fn get_invitation(db: &ShinkaiDB, workspace_id: &str) -> Result<Workspace, WorkspaceError> {
+        db.get_workspace_invitation(workspace_id).map_err(|e| match e {
+            ShinkaiDBError::DataNotFound => WorkspaceError::NotFound(format!("{} invitation", workspace_id)),
+            e => WorkspaceError::DatabaseError(e),
+        })
+    }
+
+    /// Profile of this node that's a member of the workspace, if any
+    fn my_member_identity(
+        workspace: &Workspace,
+        node_name: &ShinkaiName,
+    ) -> Result<Option<ShinkaiName>, WorkspaceError> {
+        let my_member = workspace.members.iter().find(|member| {
+            ShinkaiName::new(member.identity.clone())
+                .map_or(false, |name| name.extract_node() == node_name.extract_node())
+        });
+        my_member
+            .map(|member| {
+                ShinkaiName::new(member.identity.clone()).map_err(|e| WorkspaceError::InvalidInput(e.to_string()))
+            })
+            .transpose()
+    }
+
+    /// Subscribes the profile to folders of the workspace. Folders that fail are logged and skipped.
+    async fn subscribe_to_folders(
+        workspace: &Workspace,
+        folders: &[String],
+        my_identity: &ShinkaiName,
+        my_subscription_manager: Arc<Mutex<MySubscriptionsManager>>,
+    ) {
+        let Ok(owner) = ShinkaiName::new(workspace.owner.clone()) else {
+            return;
+        };
+        let mut my_subscription_manager = my_subscription_manager.lock().await;
+        for folder in folders {
+            if let Err(e) = my_subscription_manager
+                .subscribe_to_shared_folder(
+                    owner.extract_node(),
+                    owner.get_profile_name_string().unwrap_or_default(),
+                    my_identity.get_profile_name_string().unwrap_or_default(),
+                    folder.clone(),
+                    SubscriptionPayment::Free,
+                    None,
+                    None,
+                )
+                .await
+            {
+                shinkai_log(
+                    ShinkaiLogOption::MySubscriptions,
+                    ShinkaiLogLevel::Error,
+                    &format!(
+                        "Failed to subscribe to folder {} of workspace {}: {:?}",
+                        folder, workspace.workspace_id, e
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Saves the messages of a workspace inbox written on the node of a member allowed to write
+    pub async fn receive_inbox_messages(
+        db: &ShinkaiDB,
+        node_name: &ShinkaiName,
+        sender: &ShinkaiName,
+        payload: WorkspaceInboxMessages,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) -> Result<usize, WorkspaceError> {
+        // The sender is authorized against the roles of the workspace this node has, which only the owner updates
+        let workspace = Self::get_workspace(db, &payload.workspace_id)?;
+        if !workspace.role_of(sender).map_or(false, |role| role.can_write()) {
+            return Err(WorkspaceError::NotAllowed(format!(
+                "{} can't write to workspace {}",
+                sender, workspace.workspace_id
+            )));
+        }
+        if !workspace.inboxes.contains(&payload.inbox_name) {
+            return Err(WorkspaceError::NotAllowed(format!(
+                "Inbox {} isn't part of workspace {}",
+                payload.inbox_name, workspace.workspace_id
+            )));
+        }
+
+        let mut saved = 0;
+        for message in payload.messages {
+            let inbox_name = InboxName::from_message(&message)
+                .map_err(|e| WorkspaceError::InvalidInput(e.to_string()))?
+                .to_string();
+            let author = ShinkaiName::from_shinkai_message_only_using_sender_node_name(&message)
+                .map_err(|e| WorkspaceError::InvalidInput(e.to_string()))?;
+            // Members only replicate what was written on their node
+            if inbox_name != payload.inbox_name || author.extract_node() != sender.extract_node() {
+                continue;
+            }
+            if db
+                .fetch_message_and_hash(&message.calculate_message_hash_for_pagination())
+                .is_ok()
+            {
+                continue;
+            }
+            db.unsafe_insert_inbox_message(&message, None, ws_manager.clone())
+                .await?;
+            saved += 1;
+        }
+
+        // The profile that's a member sees the inbox
+        if let Some(my_identity) = workspace.participants().into_iter().find_map(|identity| {
+            ShinkaiName::new(identity)
+                .ok()
+                .filter(|name| name.extract_node() == node_name.extract_node())
+        }) {
+            let permission = match workspace.role_of(&my_identity) {
+                Some(role) if role.can_write() => InboxPermission::Write,
+                _ => InboxPermission::Read,
+            };
+            if let Err(e) = db.add_permission_with_profile(&payload.inbox_name, my_identity, permission) {
+                shinkai_log(
+                    ShinkaiLogOption::Network,
+                    ShinkaiLogLevel::Error,
+                    &format!("Failed to give access to inbox {}: {}", payload.inbox_name, e),
+                );
+            }
+        }
+        Ok(saved)
+    }
+
+    /// Sends the messages written on this node to the workspace inboxes since the last sync to the other members
+    #[allow(clippy::too_many_arguments)]
+    pub async fn sync_inbox_messages(
+        node_name: &ShinkaiName,
+        encryption_secret_key: &EncryptionStaticKey,
+        signing_key: &SigningKey,
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) -> Result<usize, WorkspaceError> {
+        let mut sent = 0;
+        for workspace in db.get_all_workspaces()? {
+            if !workspace.role_of(node_name).map_or(false, |role| role.can_write()) {
+                continue;
+            }
+            for inbox_name in &workspace.inboxes {
+                let cursor = db.get_workspace_sync_cursor(&workspace.workspace_id, inbox_name)?;
+                let mut messages: Vec<ShinkaiMessage> = db
+                    .get_last_messages_from_inbox(inbox_name.clone(), SYNC_MESSAGES_PER_INBOX, None)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .flatten()
+                    .filter(|message| {
+                        ShinkaiName::from_shinkai_message_only_using_sender_node_name(message)
+                            .map_or(false, |author| author.extract_node() == node_name.extract_node())
+                    })
+                    .filter(|message| {
+                        cursor
+                            .as_ref()
+                            .map_or(true, |cursor| &message.external_metadata.scheduled_time > cursor)
+                    })
+                    .collect();
+                if messages.is_empty() {
+                    continue;
+                }
+                messages.sort_by(|a, b| {
+                    a.external_metadata
+                        .scheduled_time
+                        .cmp(&b.external_metadata.scheduled_time)
+                });
+                let last_time = messages.last().unwrap().external_metadata.scheduled_time.clone();
+                let count = messages.len();
+
+                let payload = WorkspaceInboxMessages {
+                    workspace_id: workspace.workspace_id.clone(),
+                    inbox_name: inbox_name.clone(),
+                    messages,
+                };
+                Self::send_to_members(
+                    &workspace,
+                    &payload,
+                    MessageSchemaType::WorkspaceInboxMessages,
+                    &[],
+                    node_name,
+                    encryption_secret_key,
+                    signing_key,
+                    db.clone(),
+                    identity_manager.clone(),
+                    proxy_connection_info.clone(),
+                    ws_manager.clone(),
+                )
+                .await;
+                db.set_workspace_sync_cursor(&workspace.workspace_id, inbox_name, &last_time)?;
+                sent += count;
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Sends the payload to the nodes of the participants and the extra recipients, except this node.
+    /// Members that can't be reached are logged and skipped.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_to_members(
+        workspace: &Workspace,
+        payload: impl Serialize,
+        schema_type: MessageSchemaType,
+        extra_recipients: &[String],
+        node_name: &ShinkaiName,
+        encryption_secret_key: &EncryptionStaticKey,
+        signing_key: &SigningKey,
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) {
+        let mut nodes = HashSet::new();
+        let recipients = workspace
+            .participants()
+            .into_iter()
+            .chain(extra_recipients.iter().cloned());
+        for recipient in recipients {
+            let Ok(recipient_node) = ShinkaiName::new(recipient.clone()).map(|name| name.extract_node()) else {
+                continue;
+            };
+            if recipient_node == node_name.extract_node() || !nodes.insert(recipient_node.get_node_name_string()) {
+                continue;
+            }
+
+            if let Err(e) = Self::send_to_node(
+                &payload,
+                schema_type.clone(),
+                &recipient_node,
+                node_name,
+                encryption_secret_key,
+                signing_key,
+                db.clone(),
+                identity_manager.clone(),
+                proxy_connection_info.clone(),
+                ws_manager.clone(),
+            )
+            .await
+            {
+                shinkai_log(
+                    ShinkaiLogOption::Network,
+                    ShinkaiLogLevel::Error,
+                    &format!(
+                        "Failed to send {} of workspace {} to {}: {}",
+                        schema_type.to_str(),
+                        workspace.workspace_id,
+                        recipient_node,
+                        e
+                    ),
+                );
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn send_to_node(
+        payload: impl Serialize,
+        schema_type: MessageSchemaType,
+        recipient_node: &ShinkaiName,
+        node_name: &ShinkaiName,
+        encryption_secret_key: &EncryptionStaticKey,
+        signing_key: &SigningKey,
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) -> Result<(), WorkspaceError> {
+        let recipient_identity = identity_manager
+            .lock()
+            .await
+            .external_profile_to_global_identity(&recipient_node.get_node_name_string())
+            .await
+            .map_err(WorkspaceError::NetworkError)?;
+        let address = recipient_identity
+            .addr
+            .ok_or_else(|| WorkspaceError::NetworkError(format!("{} has no address", recipient_node)))?;
+
+        let message = ShinkaiMessageBuilder::p2p_workspace_message(
+            payload,
+            schema_type,
+            clone_static_secret_key(encryption_secret_key),
+            clone_signature_secret_key(signing_key),
+            recipient_identity.node_encryption_public_key,
+            node_name.get_node_name_string(),
+            recipient_node.get_node_name_string(),
+        )
+        .map_err(|e| WorkspaceError::NetworkError(e.to_string()))?;
+
+        Node::send(
+            message,
+            Arc::new(clone_static_secret_key(encryption_secret_key)),
+            (address, recipient_node.get_node_name_string()),
+            proxy_connection_info,
+            db,
+            identity_manager,
+            ws_manager,
+            false,
+            None,
+        );
+        Ok(())
+    }
+}
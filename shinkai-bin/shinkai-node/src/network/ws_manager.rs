@@ -20,7 +20,7 @@ use std::fmt;
 use std::sync::Weak;
 use std::time::Duration;
 use std::{collections::HashMap, sync::Arc};
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
 use tokio::time::sleep;
 use warp::ws::Message;
 use warp::ws::WebSocket;
@@ -30,6 +30,7 @@ use crate::db::ShinkaiDB;
 use crate::schemas::identity::Identity;
 
 use super::node_api_router::APIError;
+use super::node_events::{NodeEvent, NodeEventType};
 use super::node_shareable_logic::validate_message_main_logic;
 use super::Node;
 use crate::managers::identity_manager::IdentityManagerTrait;
@@ -39,6 +40,7 @@ pub enum MessageType {
     ShinkaiMessage,
     Stream,
     Sheet,
+    Notification,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -159,7 +161,15 @@ impl WebSocketManager {
 
         // Spawn the message sender task
         let message_queue_clone = Arc::clone(&manager.lock().await.message_queue);
-        tokio::spawn(Self::start_message_sender(manager_clone, message_queue_clone));
+        tokio::spawn(Self::start_message_sender(manager_clone, message_queue_clone.clone()));
+
+        // Forward the notifications of the profiles to their subscribers
+        if let Some(db) = manager.lock().await.shinkai_db.upgrade() {
+            tokio::spawn(Self::start_notification_forwarder(
+                db.events.subscribe(),
+                message_queue_clone,
+            ));
+        }
 
         manager
     }
@@ -192,6 +202,26 @@ impl WebSocketManager {
         }
     }
 
+    pub async fn start_notification_forwarder(mut events: broadcast::Receiver<NodeEvent>, message_queue: MessageQueue) {
+        loop {
+            match events.recv().await {
+                Ok(event) if event.event_type == NodeEventType::Notification => {
+                    let profile = event.data["profile"].as_str().unwrap_or_default().to_string();
+                    let mut queue = message_queue.lock().await;
+                    queue.push_back((
+                        WSTopic::Notifications,
+                        profile,
+                        event.data.to_string(),
+                        WSMessageType::None,
+                        false,
+                    ));
+                }
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+
     pub async fn user_validation(
         &self,
         shinkai_name: ShinkaiName,
@@ -265,6 +295,8 @@ impl WebSocketManager {
             }
             WSTopic::Sheet => true,
             WSTopic::SheetList => true,
+            // Profiles only get their own notifications
+            WSTopic::Notifications => shinkai_name.get_profile_name_string() == subtopic,
         }
     }
 
@@ -466,6 +498,7 @@ impl WebSocketManager {
         // Determine the message type
         let message_type = match metadata {
             WSMessageType::Sheet(_) => MessageType::Sheet,
+            _ if topic == WSTopic::Notifications => MessageType::Notification,
             _ => {
                 if is_stream {
                     MessageType::Stream
@@ -493,12 +526,13 @@ impl WebSocketManager {
 
         // Send the update to all active connections that are subscribed to the topic
         for (id, connection) in self.connections.iter() {
-            let is_subscribed_to_smart_inboxes = self
-                .subscriptions
-                .get(id)
-                .unwrap()
-                .get(&format!("{}:::{}", WSTopic::SmartInboxes, ""))
-                .is_some();
+            let is_subscribed_to_smart_inboxes = topic != WSTopic::Notifications
+                && self
+                    .subscriptions
+                    .get(id)
+                    .unwrap()
+                    .get(&format!("{}:::{}", WSTopic::SmartInboxes, ""))
+                    .is_some();
             let is_subscribed_to_topic = self.subscriptions.get(id).unwrap().get(&topic_subtopic).is_some();
 
             let is_subscribed_to_sheets = self
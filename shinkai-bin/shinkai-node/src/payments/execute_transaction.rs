@@ -19,7 +19,7 @@ use std::convert::TryInto;
 // }
 
 lazy_static! {
-    static ref ERC20_ABI: Abi = serde_json::from_str(
+    pub(crate) static ref ERC20_ABI: Abi = serde_json::from_str(
         r#"
         [
             {
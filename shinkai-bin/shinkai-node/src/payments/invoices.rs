@@ -0,0 +1,265 @@
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use shinkai_message_primitives::schemas::payment_invoice::{Invoice, InvoiceStatus, PaymentReceipt};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{APICreateInvoice, MessageSchemaType};
+use shinkai_message_primitives::shinkai_utils::encryption::clone_static_secret_key;
+use shinkai_message_primitives::shinkai_utils::shinkai_message_builder::ShinkaiMessageBuilder;
+use shinkai_message_primitives::shinkai_utils::signatures::clone_signature_secret_key;
+use tokio::sync::Mutex;
+use x25519_dalek::StaticSecret as EncryptionStaticKey;
+
+use crate::db::db_errors::ShinkaiDBError;
+use crate::db::ShinkaiDB;
+use crate::managers::IdentityManager;
+use crate::network::node::ProxyConnectionInfo;
+use crate::network::ws_manager::WSUpdateHandler;
+use crate::network::Node;
+
+#[derive(Debug)]
+pub enum InvoiceError {
+    InvalidInput(String),
+    NotFound(String),
+    /// The invoice can't change anymore, it's paid or expired
+    Closed(String),
+    InvalidReceipt(String),
+    DatabaseError(ShinkaiDBError),
+    NetworkError(String),
+}
+
+impl fmt::Display for InvoiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvoiceError::InvalidInput(msg) => write!(f, "Invalid invoice: {}", msg),
+            InvoiceError::NotFound(invoice_id) => write!(f, "Invoice {} not found", invoice_id),
+            InvoiceError::Closed(msg) => write!(f, "{}", msg),
+            InvoiceError::InvalidReceipt(msg) => write!(f, "Invalid payment receipt: {}", msg),
+            InvoiceError::DatabaseError(err) => write!(f, "Database error: {}", err),
+            InvoiceError::NetworkError(msg) => write!(f, "Failed to reach the other party: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for InvoiceError {}
+
+impl From<ShinkaiDBError> for InvoiceError {
+    fn from(err: ShinkaiDBError) -> Self {
+        InvoiceError::DatabaseError(err)
+    }
+}
+
+/// Invoices issued by the node for its subscriptions and tools, and the ones other nodes sent it
+pub struct InvoiceManager;
+
+impl InvoiceManager {
+    pub fn create_invoice(
+        db: &ShinkaiDB,
+        node_name: &ShinkaiName,
+        request: APICreateInvoice,
+    ) -> Result<Invoice, InvoiceError> {
+        if request.amount <= Decimal::ZERO {
+            return Err(InvoiceError::InvalidInput("The amount must be positive".to_string()));
+        }
+        if request.asset.trim().is_empty() {
+            return Err(InvoiceError::InvalidInput("The asset is required".to_string()));
+        }
+        let payer = ShinkaiName::new(request.payer.clone())
+            .map_err(|e| InvoiceError::InvalidInput(format!("Invalid payer {}: {}", request.payer, e)))?;
+
+        let created_at = Utc::now();
+        let invoice = Invoice {
+            invoice_id: uuid::Uuid::new_v4().to_string(),
+            issuer: node_name.get_node_name_string(),
+            payer: payer.to_string(),
+            amount: request.amount,
+            asset: request.asset.trim().to_string(),
+            memo: request.memo,
+            reference: request.reference,
+            pay_to: request.pay_to,
+            created_at,
+            expires_at: request
+                .expires_in_secs
+                .map(|expires_in_secs| created_at + Duration::seconds(expires_in_secs as i64)),
+            status: InvoiceStatus::Pending,
+            receipt: None,
+        };
+        db.set_invoice(&invoice)?;
+        Ok(invoice)
+    }
+
+    /// Invoices with the status, all of them if not set. Pending invoices past their expiry are marked as expired.
+    pub fn list_invoices(db: &ShinkaiDB, status: Option<InvoiceStatus>) -> Result<Vec<Invoice>, InvoiceError> {
+        let mut invoices = db.get_all_invoices()?;
+        for invoice in invoices.iter_mut() {
+            if invoice.status == InvoiceStatus::Pending && invoice.is_expired() {
+                invoice.status = InvoiceStatus::Expired;
+                db.set_invoice(invoice)?;
+            }
+        }
+        Ok(invoices
+            .into_iter()
+            .filter(|invoice| status.map_or(true, |status| invoice.status == status))
+            .collect())
+    }
+
+    /// Marks an invoice issued by this node as paid, with a receipt signed by the node
+    pub fn mark_paid(
+        db: &ShinkaiDB,
+        node_name: &ShinkaiName,
+        invoice_id: &str,
+        transaction_id: Option<String>,
+        signing_key: &SigningKey,
+    ) -> Result<Invoice, InvoiceError> {
+        let mut invoice = Self::get_invoice(db, invoice_id)?;
+        if invoice.issuer != node_name.get_node_name_string() {
+            return Err(InvoiceError::InvalidInput(
+                "Only the node that issued the invoice can mark it as paid".to_string(),
+            ));
+        }
+        if invoice.status == InvoiceStatus::Paid || invoice.is_expired() {
+            return Err(InvoiceError::Closed(format!(
+                "Invoice {} is already paid or expired",
+                invoice_id
+            )));
+        }
+
+        invoice.receipt = Some(PaymentReceipt::new_signed(&invoice, transaction_id, signing_key));
+        invoice.status = InvoiceStatus::Paid;
+        db.set_invoice(&invoice)?;
+        Ok(invoice)
+    }
+
+    /// Saves an invoice sent by the node that issued it, as long as it's addressed to this node or one of its profiles
+    pub fn receive_invoice(
+        db: &ShinkaiDB,
+        my_node_name: &ShinkaiName,
+        sender: &ShinkaiName,
+        mut invoice: Invoice,
+    ) -> Result<Invoice, InvoiceError> {
+        if invoice.issuer != sender.get_node_name_string() {
+            return Err(InvoiceError::InvalidInput(format!(
+                "{} sent an invoice issued by {}",
+                sender, invoice.issuer
+            )));
+        }
+        // Else any node could have the invoices it makes up for someone else paid automatically by this node
+        let payer = ShinkaiName::new(invoice.payer.clone())
+            .map_err(|e| InvoiceError::InvalidInput(format!("Invalid payer {}: {}", invoice.payer, e)))?;
+        if payer.get_node_name_string() != my_node_name.get_node_name_string() {
+            return Err(InvoiceError::InvalidInput(format!(
+                "{} sent an invoice to be paid by {}",
+                sender, invoice.payer
+            )));
+        }
+        if let Ok(existing) = db.get_invoice(&invoice.invoice_id) {
+            if existing.issuer != invoice.issuer || existing.status != InvoiceStatus::Pending {
+                return Err(InvoiceError::Closed(format!(
+                    "Invoice {} is already known",
+                    invoice.invoice_id
+                )));
+            }
+        }
+
+        invoice.status = InvoiceStatus::Pending;
+        invoice.receipt = None;
+        db.set_invoice(&invoice)?;
+        Ok(invoice)
+    }
+
+    /// Saves the receipt of an invoice this node was sent, once checked it's signed by the node that got paid
+    pub fn receive_receipt(
+        db: &ShinkaiDB,
+        sender: &ShinkaiName,
+        receipt: PaymentReceipt,
+        sender_verifying_key: &VerifyingKey,
+    ) -> Result<Invoice, InvoiceError> {
+        let mut invoice = Self::get_invoice(db, &receipt.invoice_id)?;
+        if invoice.issuer != sender.get_node_name_string() || receipt.payee != invoice.issuer {
+            return Err(InvoiceError::InvalidReceipt(format!(
+                "{} sent a receipt for an invoice issued by {}",
+                sender, invoice.issuer
+            )));
+        }
+        if receipt.payer != invoice.payer || receipt.amount != invoice.amount || receipt.asset != invoice.asset {
+            return Err(InvoiceError::InvalidReceipt(
+                "The receipt doesn't match the invoice".to_string(),
+            ));
+        }
+        if !receipt.verify(sender_verifying_key) {
+            return Err(InvoiceError::InvalidReceipt("Invalid signature".to_string()));
+        }
+
+        invoice.status = InvoiceStatus::Paid;
+        invoice.receipt = Some(receipt);
+        db.set_invoice(&invoice)?;
+        Ok(invoice)
+    }
+
+    pub fn get_invoice(db: &ShinkaiDB, invoice_id: &str) -> Result<Invoice, InvoiceError> {
+        db.get_invoice(invoice_id).map_err(|e| match e {
+            ShinkaiDBError::DataNotFound => InvoiceError::NotFound(invoice_id.to_string()),
+            e => InvoiceError::DatabaseError(e),
+        })
+    }
+
+    /// Sends the invoice or receipt to the node of the other party. Nothing is sent if it's this node.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_to_counterparty(
+        payload: impl Serialize,
+        schema_type: MessageSchemaType,
+        counterparty: &str,
+        node_name: &ShinkaiName,
+        encryption_secret_key: &EncryptionStaticKey,
+        signing_key: &SigningKey,
+        db: Arc<ShinkaiDB>,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        proxy_connection_info: Arc<Mutex<Option<ProxyConnectionInfo>>>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+    ) -> Result<(), InvoiceError> {
+        let counterparty_node = ShinkaiName::new(counterparty.to_string())
+            .map_err(|e| InvoiceError::InvalidInput(format!("Invalid node {}: {}", counterparty, e)))?
+            .extract_node();
+        if counterparty_node == node_name.extract_node() {
+            return Ok(());
+        }
+
+        let counterparty_identity = identity_manager
+            .lock()
+            .await
+            .external_profile_to_global_identity(&counterparty_node.get_node_name_string())
+            .await
+            .map_err(InvoiceError::NetworkError)?;
+        let address = counterparty_identity
+            .addr
+            .ok_or_else(|| InvoiceError::NetworkError(format!("{} has no address", counterparty_node)))?;
+
+        let message = ShinkaiMessageBuilder::p2p_payment_message(
+            payload,
+            schema_type,
+            clone_static_secret_key(encryption_secret_key),
+            clone_signature_secret_key(signing_key),
+            counterparty_identity.node_encryption_public_key,
+            node_name.get_node_name_string(),
+            counterparty_node.get_node_name_string(),
+        )
+        .map_err(|e| InvoiceError::NetworkError(e.to_string()))?;
+
+        Node::send(
+            message,
+            Arc::new(clone_static_secret_key(encryption_secret_key)),
+            (address, counterparty_node.get_node_name_string()),
+            proxy_connection_info,
+            db,
+            identity_manager,
+            ws_manager,
+            false,
+            None,
+        );
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
 pub mod payment_methods;
 pub mod payment_manager;
-pub mod execute_transaction;
\ No newline at end of file
+pub mod execute_transaction;
+pub mod invoices;
+pub mod wallet;
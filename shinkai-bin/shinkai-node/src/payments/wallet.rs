@@ -0,0 +1,395 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use ethers::abi::Token;
+use ethers::prelude::*;
+use lazy_static::lazy_static;
+use rust_decimal::Decimal;
+use shinkai_message_primitives::schemas::payment_invoice::{Invoice, InvoiceReference, InvoiceStatus};
+use tokio::sync::Mutex;
+
+use crate::db::db_errors::ShinkaiDBError;
+use crate::db::ShinkaiDB;
+use crate::schemas::wallet::{
+    WalletAsset, WalletConfig, WalletInfo, WalletNetwork, WalletSigner, WalletTransaction, WalletTransactionStatus,
+};
+
+use super::execute_transaction::ERC20_ABI;
+
+lazy_static! {
+    /// Held from checking a payment until its transaction is saved, so two payments can't both pass the limits or
+    /// both pay the same invoice
+    static ref WALLET_PAYMENT_LOCK: Mutex<()> = Mutex::new(());
+}
+
+#[derive(Debug)]
+pub enum WalletError {
+    NotConfigured,
+    InvalidInput(String),
+    NotFound(String),
+    /// The payment isn't allowed, e.g. it's over the limits or the invoice was already paid
+    NotAllowed(String),
+    TransactionError(String),
+    DatabaseError(ShinkaiDBError),
+}
+
+impl fmt::Display for WalletError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WalletError::NotConfigured => write!(f, "No wallet is set up"),
+            WalletError::InvalidInput(msg) => write!(f, "{}", msg),
+            WalletError::NotFound(msg) => write!(f, "{} not found", msg),
+            WalletError::NotAllowed(msg) => write!(f, "{}", msg),
+            WalletError::TransactionError(msg) => write!(f, "Transaction error: {}", msg),
+            WalletError::DatabaseError(err) => write!(f, "Database error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for WalletError {}
+
+impl From<ShinkaiDBError> for WalletError {
+    fn from(err: ShinkaiDBError) -> Self {
+        WalletError::DatabaseError(err)
+    }
+}
+
+pub enum TransferOutcome {
+    /// Hash of the transaction, confirmed on chain
+    Confirmed(String),
+    /// Queued for the external signer
+    AwaitingSignature,
+}
+
+/// Something that can send assets from the address of the node
+#[async_trait]
+pub trait PaymentWallet: Send + Sync {
+    fn address(&self) -> String;
+    async fn transfer(&self, to: &str, base_units: U256, asset: &WalletAsset) -> Result<TransferOutcome, WalletError>;
+}
+
+/// Signs and sends the transactions itself, with a key held by the node
+pub struct LocalKeyWallet {
+    wallet: LocalWallet,
+    provider: Provider<Http>,
+}
+
+impl LocalKeyWallet {
+    pub fn new(private_key: &str, network: &WalletNetwork) -> Result<Self, WalletError> {
+        let wallet = private_key
+            .trim()
+            .parse::<LocalWallet>()
+            .map_err(|e| WalletError::InvalidInput(format!("Invalid private key: {}", e)))?
+            .with_chain_id(network.chain_id);
+        let provider = Provider::<Http>::try_from(network.rpc_url.as_str())
+            .map_err(|e| WalletError::InvalidInput(format!("Invalid RPC URL {}: {}", network.rpc_url, e)))?;
+        Ok(LocalKeyWallet { wallet, provider })
+    }
+}
+
+#[async_trait]
+impl PaymentWallet for LocalKeyWallet {
+    fn address(&self) -> String {
+        format!("{:?}", self.wallet.address())
+    }
+
+    async fn transfer(&self, to: &str, base_units: U256, asset: &WalletAsset) -> Result<TransferOutcome, WalletError> {
+        let to: Address = to
+            .parse()
+            .map_err(|_| WalletError::InvalidInput(format!("Invalid address {}", to)))?;
+        let tx = match &asset.contract_address {
+            None => TransactionRequest::new().to(to).value(base_units),
+            Some(contract_address) => {
+                let contract_address: Address = contract_address
+                    .parse()
+                    .map_err(|_| WalletError::InvalidInput(format!("Invalid contract address {}", contract_address)))?;
+                let data = ERC20_ABI
+                    .function("transfer")
+                    .and_then(|function| function.encode_input(&[Token::Address(to), Token::Uint(base_units)]))
+                    .map_err(|e| WalletError::TransactionError(e.to_string()))?;
+                TransactionRequest::new().to(contract_address).data(data)
+            }
+        };
+
+        let client = SignerMiddleware::new(self.provider.clone(), self.wallet.clone());
+        let pending_tx = client
+            .send_transaction(tx, None)
+            .await
+            .map_err(|e| WalletError::TransactionError(e.to_string()))?;
+        let tx_hash = format!("{:?}", *pending_tx);
+        pending_tx
+            .confirmations(1)
+            .await
+            .map_err(|e| WalletError::TransactionError(e.to_string()))?;
+        Ok(TransferOutcome::Confirmed(tx_hash))
+    }
+}
+
+/// Leaves the transactions to a wallet app connected to the node, which reports their hash once sent
+pub struct ExternalSignerWallet {
+    address: String,
+}
+
+#[async_trait]
+impl PaymentWallet for ExternalSignerWallet {
+    fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    async fn transfer(
+        &self,
+        _to: &str,
+        _base_units: U256,
+        _asset: &WalletAsset,
+    ) -> Result<TransferOutcome, WalletError> {
+        Ok(TransferOutcome::AwaitingSignature)
+    }
+}
+
+/// Amount in the smallest unit of an asset with `decimals` decimals
+pub fn to_base_units(amount: Decimal, decimals: u32) -> Result<U256, WalletError> {
+    let scaled = 10u64
+        .checked_pow(decimals)
+        .and_then(|unit| amount.checked_mul(Decimal::from(unit)))
+        .ok_or_else(|| WalletError::InvalidInput(format!("{} is too large for {} decimals", amount, decimals)))?;
+    if scaled.is_sign_negative() || !scaled.fract().is_zero() {
+        return Err(WalletError::InvalidInput(format!(
+            "{} can't be paid with {} decimals",
+            amount, decimals
+        )));
+    }
+    U256::from_dec_str(&scaled.trunc().normalize().to_string()).map_err(|e| WalletError::InvalidInput(e.to_string()))
+}
+
+/// Wallet of the node, paying the invoices other nodes sent it
+pub struct WalletManager;
+
+impl WalletManager {
+    fn build_wallet(config: &WalletConfig) -> Result<Box<dyn PaymentWallet>, WalletError> {
+        match &config.signer {
+            WalletSigner::LocalKey { private_key_env } => {
+                let private_key = std::env::var(private_key_env)
+                    .map_err(|_| WalletError::InvalidInput(format!("{} is not set", private_key_env)))?;
+                Ok(Box::new(LocalKeyWallet::new(&private_key, &config.network)?))
+            }
+            WalletSigner::External { address } => {
+                address
+                    .parse::<Address>()
+                    .map_err(|_| WalletError::InvalidInput(format!("Invalid address {}", address)))?;
+                Ok(Box::new(ExternalSignerWallet {
+                    address: address.clone(),
+                }))
+            }
+        }
+    }
+
+    fn get_config(db: &ShinkaiDB) -> Result<WalletConfig, WalletError> {
+        db.get_wallet_config()?.ok_or(WalletError::NotConfigured)
+    }
+
+    /// Sets up the wallet, replacing the previous one. The transaction history is kept.
+    pub fn set_wallet(db: &ShinkaiDB, config: WalletConfig) -> Result<WalletInfo, WalletError> {
+        for limit in config.auto_pay.limits.iter() {
+            if config.asset(&limit.asset).is_none() {
+                return Err(WalletError::InvalidInput(format!(
+                    "The automatic payment limit is for {}, which isn't an asset of the wallet",
+                    limit.asset
+                )));
+            }
+        }
+        let wallet = Self::build_wallet(&config)?;
+        db.set_wallet_config(&config)?;
+        Ok(WalletInfo {
+            address: wallet.address(),
+            config,
+        })
+    }
+
+    pub fn get_wallet(db: &ShinkaiDB) -> Result<WalletInfo, WalletError> {
+        let config = Self::get_config(db)?;
+        let wallet = Self::build_wallet(&config)?;
+        Ok(WalletInfo {
+            address: wallet.address(),
+            config,
+        })
+    }
+
+    pub fn list_transactions(
+        db: &ShinkaiDB,
+        status: Option<WalletTransactionStatus>,
+    ) -> Result<Vec<WalletTransaction>, WalletError> {
+        Ok(db
+            .get_all_wallet_transactions()?
+            .into_iter()
+            .filter(|transaction| status.map_or(true, |status| transaction.status == status))
+            .collect())
+    }
+
+    /// Pays an invoice the node was sent, as asked through the API
+    pub async fn pay_invoice(db: &ShinkaiDB, invoice_id: &str) -> Result<WalletTransaction, WalletError> {
+        let invoice = match db.get_invoice(invoice_id) {
+            Ok(invoice) => invoice,
+            Err(ShinkaiDBError::DataNotFound) => return Err(WalletError::NotFound(format!("Invoice {}", invoice_id))),
+            Err(e) => return Err(e.into()),
+        };
+        let _payment_lock = WALLET_PAYMENT_LOCK.lock().await;
+        Self::pay(db, &invoice, false).await
+    }
+
+    /// Pays the invoice of a subscription of the node if it's within the automatic payment limits. Returns `None` if
+    /// the invoice isn't one to pay automatically.
+    pub async fn auto_pay_invoice(db: &ShinkaiDB, invoice: &Invoice) -> Result<Option<WalletTransaction>, WalletError> {
+        let subscription_id = match &invoice.reference {
+            Some(InvoiceReference::Subscription { subscription_id }) => subscription_id,
+            _ => return Ok(None),
+        };
+        let config = match db.get_wallet_config()? {
+            Some(config) if config.auto_pay.enabled => config,
+            _ => return Ok(None),
+        };
+
+        // Only the node streaming a subscription of this node can bill it
+        let subscription = db
+            .get_my_subscription(subscription_id)
+            .map_err(|_| WalletError::NotAllowed(format!("Subscription {} isn't one of the node", subscription_id)))?;
+        if subscription.streaming_node.get_node_name_string() != invoice.issuer {
+            return Err(WalletError::NotAllowed(format!(
+                "{} doesn't stream subscription {}",
+                invoice.issuer, subscription_id
+            )));
+        }
+
+        let _payment_lock = WALLET_PAYMENT_LOCK.lock().await;
+        let history = db.get_all_wallet_transactions()?;
+        config
+            .auto_pay
+            .check(invoice, &history, Utc::now())
+            .map_err(WalletError::NotAllowed)?;
+        Self::pay(db, invoice, true).await.map(Some)
+    }
+
+    /// Callers hold `WALLET_PAYMENT_LOCK`
+    async fn pay(db: &ShinkaiDB, invoice: &Invoice, automatic: bool) -> Result<WalletTransaction, WalletError> {
+        if invoice.status != InvoiceStatus::Pending || invoice.is_expired() {
+            return Err(WalletError::NotAllowed(format!(
+                "Invoice {} is already paid or expired",
+                invoice.invoice_id
+            )));
+        }
+        let already_paid = db.get_all_wallet_transactions()?.into_iter().any(|transaction| {
+            transaction.invoice_id.as_deref() == Some(invoice.invoice_id.as_str())
+                && transaction.status != WalletTransactionStatus::Failed
+        });
+        if already_paid {
+            return Err(WalletError::NotAllowed(format!(
+                "Invoice {} was already paid by the wallet",
+                invoice.invoice_id
+            )));
+        }
+        let to = invoice.pay_to.clone().ok_or_else(|| {
+            WalletError::InvalidInput(format!("Invoice {} has no address to pay to", invoice.invoice_id))
+        })?;
+
+        let config = Self::get_config(db)?;
+        let asset = config
+            .asset(&invoice.asset)
+            .ok_or_else(|| WalletError::InvalidInput(format!("The wallet has no {} asset", invoice.asset)))?;
+        let base_units = to_base_units(invoice.amount, asset.decimals)?;
+        let wallet = Self::build_wallet(&config)?;
+
+        let now = Utc::now();
+        let mut transaction = WalletTransaction {
+            transaction_id: uuid::Uuid::new_v4().to_string(),
+            invoice_id: Some(invoice.invoice_id.clone()),
+            to: to.clone(),
+            amount: invoice.amount,
+            asset: invoice.asset.clone(),
+            base_units: base_units.to_string(),
+            contract_address: asset.contract_address.clone(),
+            chain_id: config.network.chain_id,
+            automatic,
+            status: WalletTransactionStatus::AwaitingSignature,
+            tx_hash: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        // Saved before sending so it counts towards the limits while it's in flight
+        db.set_wallet_transaction(&transaction)?;
+
+        let result = wallet.transfer(&to, base_units, asset).await;
+        transaction.updated_at = Utc::now();
+        match result {
+            Ok(TransferOutcome::Confirmed(tx_hash)) => {
+                transaction.status = WalletTransactionStatus::Confirmed;
+                transaction.tx_hash = Some(tx_hash);
+            }
+            Ok(TransferOutcome::AwaitingSignature) => {}
+            Err(e) => {
+                transaction.status = WalletTransactionStatus::Failed;
+                transaction.error = Some(e.to_string());
+                db.set_wallet_transaction(&transaction)?;
+                return Err(e);
+            }
+        }
+        db.set_wallet_transaction(&transaction)?;
+        Ok(transaction)
+    }
+
+    /// Records what the external signer did with a transaction awaiting its signature
+    pub fn complete_transaction(
+        db: &ShinkaiDB,
+        transaction_id: &str,
+        tx_hash: Option<String>,
+        error: Option<String>,
+    ) -> Result<WalletTransaction, WalletError> {
+        let mut transaction = match db.get_wallet_transaction(transaction_id) {
+            Ok(transaction) => transaction,
+            Err(ShinkaiDBError::DataNotFound) => {
+                return Err(WalletError::NotFound(format!("Transaction {}", transaction_id)))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if transaction.status != WalletTransactionStatus::AwaitingSignature {
+            return Err(WalletError::NotAllowed(format!(
+                "Transaction {} isn't awaiting a signature",
+                transaction_id
+            )));
+        }
+
+        match (tx_hash, error) {
+            (Some(tx_hash), _) => {
+                transaction.status = WalletTransactionStatus::Submitted;
+                transaction.tx_hash = Some(tx_hash);
+            }
+            (None, error) => {
+                transaction.status = WalletTransactionStatus::Failed;
+                transaction.error = Some(error.unwrap_or_else(|| "Refused by the signer".to_string()));
+            }
+        }
+        transaction.updated_at = Utc::now();
+        db.set_wallet_transaction(&transaction)?;
+        Ok(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_base_units() {
+        assert_eq!(
+            to_base_units(Decimal::new(1050, 2), 6).unwrap(),
+            U256::from(10_500_000u64)
+        );
+        assert_eq!(
+            to_base_units(Decimal::new(1, 0), 18).unwrap(),
+            U256::from(1_000_000_000_000_000_000u64)
+        );
+        assert!(to_base_units(Decimal::new(1, 7), 6).is_err());
+        assert!(to_base_units(Decimal::new(-1, 0), 6).is_err());
+    }
+}
@@ -1,3 +1,4 @@
 pub mod shinkai_plan;
 pub mod kai_files;
-pub mod kai_manager;
\ No newline at end of file
+pub mod kai_manager;
+pub mod task_plan;
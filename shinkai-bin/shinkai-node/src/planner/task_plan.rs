@@ -0,0 +1,276 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlanStepStatus {
+    Pending,
+    Running,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskPlanStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Step of a plan, run once the steps it depends on are done
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanStep {
+    pub step_id: String,
+    pub description: String,
+    /// Tool or workflow the step is expected to use
+    #[serde(default)]
+    pub tool: Option<String>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    pub status: PlanStepStatus,
+    #[serde(default)]
+    pub output: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Step as proposed by the LLM, before it's part of a plan
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProposedPlanStep {
+    pub id: String,
+    pub description: String,
+    #[serde(default)]
+    pub tool: Option<String>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProposedPlan {
+    pub steps: Vec<ProposedPlanStep>,
+}
+
+impl ProposedPlan {
+    /// Parses the JSON object of the LLM response, ignoring any text around it
+    pub fn from_llm_response(response: &str) -> Result<Self, String> {
+        let start = response.find('{').ok_or("The response has no JSON plan")?;
+        let end = response.rfind('}').ok_or("The response has no JSON plan")?;
+        if end < start {
+            return Err("The response has no JSON plan".to_string());
+        }
+        serde_json::from_str(&response[start..=end]).map_err(|e| format!("Invalid plan: {}", e))
+    }
+}
+
+/// Goal of a job decomposed into a DAG of steps
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskPlan {
+    pub plan_id: String,
+    pub job_id: String,
+    pub goal: String,
+    pub steps: Vec<PlanStep>,
+    pub status: TaskPlanStatus,
+    /// Times the remaining steps were planned again after a step failed
+    pub replans: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TaskPlan {
+    pub fn new(job_id: String, goal: String, proposed: ProposedPlan) -> Result<Self, String> {
+        let now = Utc::now();
+        let mut plan = TaskPlan {
+            plan_id: uuid::Uuid::new_v4().to_string(),
+            job_id,
+            goal,
+            steps: Vec::new(),
+            status: TaskPlanStatus::Running,
+            replans: 0,
+            created_at: now,
+            updated_at: now,
+        };
+        plan.replace_pending_steps(proposed)?;
+        Ok(plan)
+    }
+
+    /// Replaces the steps that aren't done with the proposed ones. The proposed steps can depend on the done ones.
+    pub fn replace_pending_steps(&mut self, proposed: ProposedPlan) -> Result<(), String> {
+        let mut steps: Vec<PlanStep> = self
+            .steps
+            .iter()
+            .filter(|step| step.status == PlanStepStatus::Done)
+            .cloned()
+            .collect();
+        for mut step in proposed.steps {
+            step.depends_on.sort();
+            step.depends_on.dedup();
+            steps.push(PlanStep {
+                step_id: step.id,
+                description: step.description,
+                tool: step.tool.filter(|tool| !tool.trim().is_empty()),
+                depends_on: step.depends_on,
+                status: PlanStepStatus::Pending,
+                output: None,
+                error: None,
+            });
+        }
+        Self::validate_steps(&steps)?;
+
+        self.steps = steps;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Checks the steps have unique ids, depend on existing steps and don't form a cycle
+    pub fn validate_steps(steps: &[PlanStep]) -> Result<(), String> {
+        if steps.is_empty() {
+            return Err("The plan has no steps".to_string());
+        }
+        let mut ids = HashSet::new();
+        for step in steps {
+            if !ids.insert(step.step_id.as_str()) {
+                return Err(format!("Step {} is duplicated", step.step_id));
+            }
+        }
+        for step in steps {
+            if let Some(missing) = step.depends_on.iter().find(|id| !ids.contains(id.as_str())) {
+                return Err(format!("Step {} depends on unknown step {}", step.step_id, missing));
+            }
+        }
+
+        // Kahn's algorithm: every step is eventually reachable only if there's no cycle
+        let mut remaining: HashMap<&str, usize> = steps
+            .iter()
+            .map(|step| (step.step_id.as_str(), step.depends_on.len()))
+            .collect();
+        let mut queue: Vec<&str> = remaining
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut visited = 0;
+        while let Some(id) = queue.pop() {
+            visited += 1;
+            for step in steps.iter().filter(|step| step.depends_on.iter().any(|dep| dep == id)) {
+                let count = remaining.get_mut(step.step_id.as_str()).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    queue.push(step.step_id.as_str());
+                }
+            }
+        }
+        if visited != steps.len() {
+            return Err("The steps of the plan form a cycle".to_string());
+        }
+        Ok(())
+    }
+
+    /// Pending steps whose dependencies are all done
+    pub fn ready_steps(&self) -> Vec<String> {
+        let done: HashSet<&str> = self
+            .steps
+            .iter()
+            .filter(|step| step.status == PlanStepStatus::Done)
+            .map(|step| step.step_id.as_str())
+            .collect();
+        self.steps
+            .iter()
+            .filter(|step| {
+                step.status == PlanStepStatus::Pending && step.depends_on.iter().all(|dep| done.contains(dep.as_str()))
+            })
+            .map(|step| step.step_id.clone())
+            .collect()
+    }
+
+    pub fn step(&self, step_id: &str) -> Option<&PlanStep> {
+        self.steps.iter().find(|step| step.step_id == step_id)
+    }
+
+    pub fn update_step(
+        &mut self,
+        step_id: &str,
+        status: PlanStepStatus,
+        output: Option<String>,
+        error: Option<String>,
+    ) {
+        if let Some(step) = self.steps.iter_mut().find(|step| step.step_id == step_id) {
+            step.status = status;
+            step.output = output;
+            step.error = error;
+        }
+        self.updated_at = Utc::now();
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.steps.iter().all(|step| step.status == PlanStepStatus::Done)
+    }
+
+    /// One line per step with its status, as shown to the user while the plan runs
+    pub fn progress_text(&self) -> String {
+        let done = self
+            .steps
+            .iter()
+            .filter(|step| step.status == PlanStepStatus::Done)
+            .count();
+        let mut text = format!("Plan progress: {}/{} steps done\n", done, self.steps.len());
+        for step in &self.steps {
+            let mark = match step.status {
+                PlanStepStatus::Pending => "[ ]",
+                PlanStepStatus::Running => "[~]",
+                PlanStepStatus::Done => "[x]",
+                PlanStepStatus::Failed => "[!]",
+            };
+            text.push_str(&format!("{} {}. {}\n", mark, step.step_id, step.description));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proposed(response: &str) -> ProposedPlan {
+        ProposedPlan::from_llm_response(response).unwrap()
+    }
+
+    #[test]
+    fn test_task_plan_runs_steps_in_dependency_order() {
+        let response = r#"Here is the plan:
+        {"steps": [
+            {"id": "1", "description": "Search the web", "tool": "shinkai__web_search"},
+            {"id": "2", "description": "Read the results", "depends_on": ["1"]},
+            {"id": "3", "description": "Summarize", "depends_on": ["1", "2"]}
+        ]}"#;
+        let mut plan = TaskPlan::new("job1".to_string(), "Summarize the news".to_string(), proposed(response)).unwrap();
+        assert_eq!(plan.ready_steps(), vec!["1".to_string()]);
+
+        plan.update_step("1", PlanStepStatus::Done, Some("results".to_string()), None);
+        assert_eq!(plan.ready_steps(), vec!["2".to_string()]);
+
+        // The steps left are planned again, keeping the done one
+        plan.update_step("2", PlanStepStatus::Failed, None, Some("timeout".to_string()));
+        let replanned = proposed(r#"{"steps": [{"id": "4", "description": "Summarize", "depends_on": ["1"]}]}"#);
+        plan.replace_pending_steps(replanned).unwrap();
+        assert_eq!(plan.steps.len(), 2);
+        assert_eq!(plan.ready_steps(), vec!["4".to_string()]);
+
+        plan.update_step("4", PlanStepStatus::Done, Some("summary".to_string()), None);
+        assert!(plan.is_complete());
+    }
+
+    #[test]
+    fn test_task_plan_rejects_invalid_dags() {
+        let cycle = r#"{"steps": [
+            {"id": "1", "description": "a", "depends_on": ["2"]},
+            {"id": "2", "description": "b", "depends_on": ["1"]}
+        ]}"#;
+        assert!(TaskPlan::new("job1".to_string(), "goal".to_string(), proposed(cycle)).is_err());
+
+        let unknown = r#"{"steps": [{"id": "1", "description": "a", "depends_on": ["7"]}]}"#;
+        assert!(TaskPlan::new("job1".to_string(), "goal".to_string(), proposed(unknown)).is_err());
+
+        assert!(ProposedPlan::from_llm_response("no plan here").is_err());
+    }
+}
@@ -1,24 +1,26 @@
 use super::network::Node;
+use super::utils::data_dir::DataDir;
 use super::utils::environment::{fetch_static_server_env, NodeEnvironment};
 use super::utils::static_server::start_static_server;
+use crate::managers::node_metrics::NODE_STARTED_AT;
 use crate::network::node_api_router;
 use crate::network::node_commands::NodeCommand;
+use crate::network::panic_isolation::catch_panic;
+use crate::network::request_id::init_request_id_logging;
 use crate::utils::args::parse_args;
 use crate::utils::cli::cli_handle_create_message;
-use crate::utils::environment::{fetch_llm_provider_env, fetch_node_environment};
+use crate::utils::environment::{fetch_llm_provider_env, fetch_node_environment, is_ephemeral_node, load_config_file};
 use crate::utils::keys::generate_or_load_keys;
 use crate::utils::qr_code_setup::generate_qr_codes;
 use async_channel::{bounded, Receiver, Sender};
-use ed25519_dalek::VerifyingKey;
 use shinkai_message_primitives::shinkai_utils::encryption::{
     encryption_public_key_to_string, encryption_secret_key_to_string,
 };
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::{
-    init_default_tracing, shinkai_log, ShinkaiLogLevel, ShinkaiLogOption,
+    init_default_tracing, set_log_file, shinkai_log, ShinkaiLogLevel, ShinkaiLogOption,
 };
 use shinkai_message_primitives::shinkai_utils::signatures::{
-    clone_signature_secret_key, hash_signature_public_key, signature_public_key_to_string,
-    signature_secret_key_to_string,
+    clone_signature_secret_key, signature_public_key_to_string, signature_secret_key_to_string,
 };
 use shinkai_vector_resources::embedding_generator::RemoteEmbeddingGenerator;
 use shinkai_vector_resources::file_parser::unstructured_api::UnstructuredAPI;
@@ -29,9 +31,39 @@ use std::path::Path;
 use std::sync::{Arc, Weak};
 use std::{env, fs};
 
-use tokio::sync::Mutex;
+use lazy_static::lazy_static;
+use tokio::sync::{watch, Mutex};
 use tokio::task::JoinHandle;
 
+/// Restarts after crashes in a row before the node gives up, when AUTO_RESTART_ON_CRASH is set
+const MAX_CRASH_RESTARTS: u32 = 5;
+/// A node that ran this long before crashing isn't crashing in a loop
+const CRASH_RESTARTS_RESET_SECS: u64 = 600;
+/// Number of crashes in a row, passed on to the restarted process
+const CRASH_RESTARTS_ENV: &str = "SHINKAI_NODE_CRASH_RESTARTS";
+
+/// Exit code of a node that couldn't start because of its configuration, EX_CONFIG of sysexits.h
+pub const EXIT_CODE_CONFIG_ERROR: i32 = 78;
+/// Exit code of a node that failed while starting or running, EX_SOFTWARE of sysexits.h
+pub const EXIT_CODE_RUNTIME_ERROR: i32 = 70;
+
+/// What the API asked the node to do with itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeLifecycleRequest {
+    Stop,
+    Restart,
+}
+
+lazy_static! {
+    /// Stop and restart requests, handled by run_node_tasks
+    static ref NODE_LIFECYCLE: watch::Sender<Option<NodeLifecycleRequest>> = watch::channel(None).0;
+}
+
+/// Asks run_node_tasks to stop the node, or to start it again in the same process
+pub fn request_node_lifecycle(request: NodeLifecycleRequest) {
+    NODE_LIFECYCLE.send_replace(Some(request));
+}
+
 #[derive(Debug)]
 pub struct NodeRunnerError {
     pub source: Box<dyn StdError + Send + Sync>,
@@ -55,38 +87,89 @@ impl From<Box<dyn StdError + Send + Sync>> for NodeRunnerError {
     }
 }
 
+/// The environment, config file or flags of the node are invalid
+#[derive(Debug)]
+pub struct NodeConfigError(pub String);
+
+impl fmt::Display for NodeConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid node configuration: {}", self.0)
+    }
+}
+
+impl StdError for NodeConfigError {}
+
+/// Exit code for an error returned by initialize_node or run_node_tasks
+pub fn exit_code(error: &(dyn StdError + Send + Sync + 'static)) -> i32 {
+    if error.is::<NodeConfigError>() {
+        EXIT_CODE_CONFIG_ERROR
+    } else {
+        EXIT_CODE_RUNTIME_ERROR
+    }
+}
+
 pub async fn initialize_node() -> Result<
     (Sender<NodeCommand>, JoinHandle<()>, JoinHandle<()>, Weak<Mutex<Node>>),
     Box<dyn std::error::Error + Send + Sync>,
 > {
+    // Fetch args, the config file sets env vars read from here on
+    let args = parse_args();
+    if let Some(config_path) = &args.config {
+        load_config_file(config_path).map_err(NodeConfigError)?;
+    }
+    if let Some(data_dir) = &args.data_dir {
+        env::set_var("NODE_STORAGE_PATH", data_dir);
+    }
+    if args.ephemeral {
+        env::set_var("NODE_EPHEMERAL", "true");
+    }
+
     // Check if TELEMETRY_ENDPOINT is defined
     if let Ok(_telemetry_endpoint) = std::env::var("TELEMETRY_ENDPOINT") {
         // If TELEMETRY_ENDPOINT is defined, initialize telemetry tracing
         #[cfg(feature = "telemetry")]
         {
-            init_telemetry_tracing(&_telemetry_endpoint);
+            use crate::utils::open_telemetry::{init_telemetry_tracing, TelemetryConfig};
+            init_telemetry_tracing(&TelemetryConfig::from_env(&_telemetry_endpoint));
+        }
+        #[cfg(not(feature = "telemetry"))]
+        {
+            init_default_tracing();
+            eprintln!("TELEMETRY_ENDPOINT is set but the node was built without the telemetry feature");
         }
     } else {
         // If TELEMETRY_ENDPOINT is not defined, initialize default tracing
         init_default_tracing();
     }
+    init_request_id_logging();
+    lazy_static::initialize(&NODE_STARTED_AT);
 
-    let main_db: &str = "main_db";
-    let vector_fs_db: &str = "vector_fs_db";
-    let secrets_file: &str = ".secret";
-
-    // Fetch Env vars/args
-    let args = parse_args();
-    let node_env = fetch_node_environment();
-
-    let node_storage_path = node_env.node_storage_path.clone();
+    // Fetch Env vars, invalid values make fetch_node_environment panic
+    let node_env = catch_panic("Reading the node environment", async { fetch_node_environment() })
+        .await
+        .map_err(|e| NodeConfigError(e.message))?;
+
+    // Follows the data directory if it was moved, and moves it if that was requested through the API
+    let data_dir = DataDir::new(node_env.node_storage_path.clone().unwrap_or_default())
+        .resolve()
+        .apply_pending_relocation();
+    env::set_var("NODE_STORAGE_PATH", &data_dir.root);
+
+    // Ephemeral nodes keep their databases in memory and write nothing to the data directory
+    let ephemeral = is_ephemeral_node();
+    let log_file_path = data_dir.log_file_path();
+    if !ephemeral {
+        if let Err(e) = set_log_file(Path::new(&log_file_path)) {
+            eprintln!("Failed to open the log file {}: {}", log_file_path, e);
+        }
+    }
 
-    let secrets_file_path = get_secrets_file_path(secrets_file, node_storage_path.clone());
+    let secrets_file_path = data_dir.secrets_file_path();
     let node_keys = generate_or_load_keys(&secrets_file_path);
 
     // Storage db filesystem
-    let main_db_path = get_main_db_path(main_db, &node_keys.identity_public_key, node_storage_path.clone());
-    let vector_fs_db_path = get_vector_fs_db_path(vector_fs_db, &node_keys.identity_public_key, node_storage_path);
+    let main_db_path = data_dir.main_db_path(&node_keys.identity_public_key);
+    let vector_fs_db_path = data_dir.vector_fs_db_path(&node_keys.identity_public_key);
 
     // Acquire the Node's keys. TODO: Should check with on
     // and then it's with onchain data for matching with the keys provided
@@ -159,7 +242,7 @@ pub async fn initialize_node() -> Result<
         "GLOBAL_IDENTITY_NAME={}\nIDENTITY_SECRET_KEY={}\nENCRYPTION_SECRET_KEY={}",
         global_identity_name, identity_secret_key_string, encryption_secret_key_string
     );
-    if !node_env.no_secrets_file {
+    if !node_env.no_secrets_file && !ephemeral {
         std::fs::create_dir_all(Path::new(&secrets_file_path.clone()).parent().unwrap())
             .expect("Failed to create .secret dir");
         std::fs::write(secrets_file_path.clone(), secret_content).expect("Unable to write to .secret file");
@@ -167,26 +250,30 @@ pub async fn initialize_node() -> Result<
 
     // Now that all core init data acquired, start running the node itself
     let (node_commands_sender, node_commands_receiver): (Sender<NodeCommand>, Receiver<NodeCommand>) = bounded(100);
-    let node = Node::new(
-        global_identity_name.clone().to_string(),
-        node_env.listen_address,
-        clone_signature_secret_key(&node_keys.identity_secret_key),
-        node_keys.encryption_secret_key.clone(),
-        node_env.ping_interval,
-        node_commands_receiver,
-        main_db_path.clone(),
-        secrets_file_path.clone(),
-        node_env.proxy_identity.clone(),
-        node_env.first_device_needs_registration_code,
-        initial_llm_providers,
-        vector_fs_db_path.clone(),
-        Some(embedding_generator),
-        Some(unstructured_api),
-        node_env.ws_address,
-        node_env.default_embedding_model.clone(),
-        node_env.supported_embedding_models.clone(),
+    // A panic while opening the databases or loading the identities is returned as an error of the initialization
+    let node = catch_panic(
+        "Node initialization",
+        Node::new(
+            global_identity_name.clone().to_string(),
+            node_env.listen_address,
+            clone_signature_secret_key(&node_keys.identity_secret_key),
+            node_keys.encryption_secret_key.clone(),
+            node_env.ping_interval,
+            node_commands_receiver,
+            main_db_path.clone(),
+            secrets_file_path.clone(),
+            node_env.proxy_identity.clone(),
+            node_env.first_device_needs_registration_code,
+            initial_llm_providers,
+            vector_fs_db_path.clone(),
+            Some(embedding_generator),
+            Some(unstructured_api),
+            node_env.ws_address,
+            node_env.default_embedding_model.clone(),
+            node_env.supported_embedding_models.clone(),
+        ),
     )
-    .await;
+    .await?;
 
     // Put the Node in an Arc<Mutex<Node>> for use in a task
     let start_node = Arc::clone(&node);
@@ -230,6 +317,54 @@ pub async fn initialize_node() -> Result<
         .await;
     }
 
+    // Setup gRPC API Server task
+    #[cfg(feature = "grpc")]
+    if let (Some(grpc_address), Ok(_)) = (node_env.grpc_address, env::var("API_V2_KEY")) {
+        let grpc_commands_sender = node_commands_sender.clone();
+        tokio::spawn(async move {
+            if let Err(e) = crate::network::grpc_api::run_grpc_api(grpc_commands_sender, grpc_address).await {
+                shinkai_log(
+                    ShinkaiLogOption::Node,
+                    ShinkaiLogLevel::Error,
+                    &format!("gRPC API server failed to start: {}", e),
+                );
+            }
+        });
+    }
+
+    // Setup WebDAV Server task
+    if let (Some(webdav_address), Ok(_)) = (node_env.webdav_address, env::var("API_V2_KEY")) {
+        let webdav_commands_sender = node_commands_sender.clone();
+        let webdav_writable = node_env.webdav_writable;
+        tokio::spawn(async move {
+            if let Err(e) =
+                crate::network::webdav::run_webdav_server(webdav_commands_sender, webdav_address, webdav_writable).await
+            {
+                shinkai_log(
+                    ShinkaiLogOption::Node,
+                    ShinkaiLogLevel::Error,
+                    &format!("WebDAV server failed to start: {}", e),
+                );
+            }
+        });
+    }
+
+    // Setup publishing server task
+    if let Some(publishing_address) = node_env.publishing_address {
+        let publishing_commands_sender = node_commands_sender.clone();
+        tokio::spawn(async move {
+            if let Err(e) =
+                crate::network::publishing::run_publishing_server(publishing_commands_sender, publishing_address).await
+            {
+                shinkai_log(
+                    ShinkaiLogOption::Node,
+                    ShinkaiLogLevel::Error,
+                    &format!("Publishing server failed to start: {}", e),
+                );
+            }
+        });
+    }
+
     // Setup API Server task
     let api_listen_address = node_env.clone().api_listen_address;
     let api_server = tokio::spawn(async move {
@@ -274,72 +409,144 @@ pub async fn run_node_tasks(
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let api_server_abort = api_server.abort_handle();
     let node_task_abort = node_task.abort_handle();
+    let mut lifecycle = NODE_LIFECYCLE.subscribe();
 
-    match tokio::try_join!(api_server, node_task) {
-        Ok(_) => {
-            shinkai_log(ShinkaiLogOption::Node, ShinkaiLogLevel::Info, "All tasks completed");
-            Ok(())
-        }
-        Err(e) => {
+    // Containers and service managers stop the node with a signal, which stops it like the API does
+    tokio::spawn(async {
+        wait_for_shutdown_signal().await;
+        request_node_lifecycle(NodeLifecycleRequest::Stop);
+    });
+
+    tokio::select! {
+        result = async { tokio::try_join!(api_server, node_task) } => match result {
+            Ok(_) => {
+                shinkai_log(ShinkaiLogOption::Node, ShinkaiLogLevel::Info, "All tasks completed");
+                Ok(())
+            }
+            Err(e) => {
+                api_server_abort.abort();
+                node_task_abort.abort();
+
+                if env::var("AUTO_RESTART_ON_CRASH").unwrap_or_default() == "true" {
+                    restart_after_crash(&e.to_string()).await?;
+                }
+                Err(Box::new(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))
+            }
+        },
+        Ok(()) = lifecycle.changed() => {
+            let request = *lifecycle.borrow();
             api_server_abort.abort();
             node_task_abort.abort();
 
-            Err(Box::new(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))
+            shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Info,
+                &format!("{:?} requested", request),
+            );
+            if request == Some(NodeLifecycleRequest::Restart) {
+                restart_process()?;
+            }
+            Ok(())
         }
     }
 }
 
-/// Machine filesystem path to the main ShinkaiDB database, pub key based.
-fn get_main_db_path(main_db: &str, identity_public_key: &VerifyingKey, node_storage_path: Option<String>) -> String {
-    if let Some(path) = node_storage_path {
-        Path::new(&path)
-            .join(main_db)
-            .join(hash_signature_public_key(identity_public_key))
-            .to_str()
-            .expect("Invalid NODE_STORAGE_PATH")
-            .to_string()
-    } else {
-        Path::new(main_db)
-            .join(hash_signature_public_key(identity_public_key))
-            .into_os_string()
-            .into_string()
-            .unwrap()
+/// Waits for SIGTERM, e.g. from `docker stop`, or SIGINT
+#[cfg(unix)]
+async fn wait_for_shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let (Ok(mut sigterm), Ok(mut sigint)) = (signal(SignalKind::terminate()), signal(SignalKind::interrupt())) else {
+        shinkai_log(
+            ShinkaiLogOption::Node,
+            ShinkaiLogLevel::Error,
+            "Failed to listen for shutdown signals",
+        );
+        return std::future::pending().await;
+    };
+    tokio::select! {
+        _ = sigterm.recv() => {}
+        _ = sigint.recv() => {}
     }
 }
 
-/// Machine filesystem path to the main VectorFS database, pub key based.
-fn get_vector_fs_db_path(
-    vector_fs_db: &str,
-    identity_public_key: &VerifyingKey,
-    node_storage_path: Option<String>,
-) -> String {
-    if let Some(path) = node_storage_path {
-        Path::new(&path)
-            .join(vector_fs_db)
-            .join(hash_signature_public_key(identity_public_key))
-            .to_str()
-            .expect("Invalid NODE_STORAGE_PATH")
-            .to_string()
-    } else {
-        Path::new(vector_fs_db)
-            .join(hash_signature_public_key(identity_public_key))
-            .into_os_string()
-            .into_string()
-            .unwrap()
+/// Waits for Ctrl-C, the only shutdown signal outside unix
+#[cfg(not(unix))]
+async fn wait_for_shutdown_signal() {
+    if tokio::signal::ctrl_c().await.is_err() {
+        std::future::pending::<()>().await;
     }
 }
 
-/// Machine filesystem path for .secret.
-fn get_secrets_file_path(secrets_file: &str, node_storage_path: Option<String>) -> String {
-    if let Some(path) = node_storage_path {
-        Path::new(&path)
-            .join(secrets_file)
-            .to_str()
-            .expect("Invalid NODE_STORAGE_PATH")
-            .to_string()
+/// Runs the node again after its API server or node task crashed, waiting longer after every crash. It gives up
+/// after MAX_CRASH_RESTARTS crashes in a row, a node that ran for CRASH_RESTARTS_RESET_SECS starting the count over.
+async fn restart_after_crash(error: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let restarts: u32 = env::var(CRASH_RESTARTS_ENV)
+        .ok()
+        .and_then(|restarts| restarts.parse().ok())
+        .unwrap_or(0);
+    let (restarts, delay_secs) = match crash_restart_delay(restarts, NODE_STARTED_AT.elapsed().as_secs()) {
+        Some(restart) => restart,
+        None => {
+            shinkai_log(
+                ShinkaiLogOption::Node,
+                ShinkaiLogLevel::Error,
+                &format!(
+                    "The node crashed {} times in a row, not restarting it: {}",
+                    restarts + 1,
+                    error
+                ),
+            );
+            return Ok(());
+        }
+    };
+
+    shinkai_log(
+        ShinkaiLogOption::Node,
+        ShinkaiLogLevel::Error,
+        &format!("The node crashed, restarting it in {} seconds: {}", delay_secs, error),
+    );
+    tokio::time::sleep(std::time::Duration::from_secs(delay_secs)).await;
+    env::set_var(CRASH_RESTARTS_ENV, (restarts + 1).to_string());
+    restart_process()
+}
+
+/// The restarts in a row and the seconds to wait before the next run of a node that crashed after running for
+/// `uptime_secs`, or None when it crashed too many times in a row to restart it
+fn crash_restart_delay(restarts: u32, uptime_secs: u64) -> Option<(u32, u64)> {
+    let restarts = if uptime_secs >= CRASH_RESTARTS_RESET_SECS {
+        0
     } else {
-        Path::new(secrets_file).to_str().unwrap().to_string()
+        restarts
+    };
+    if restarts >= MAX_CRASH_RESTARTS {
+        return None;
     }
+    Some((restarts, 2u64.pow(restarts)))
+}
+
+/// Replaces the process with a new run of the node. It keeps the PID, so the desktop app running the node
+/// keeps tracking it.
+#[cfg(unix)]
+fn restart_process() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use std::os::unix::process::CommandExt;
+
+    let error = std::process::Command::new(env::current_exe()?)
+        .args(env::args_os().skip(1))
+        .exec();
+    Err(Box::new(error))
+}
+
+/// A second process couldn't open the databases while this one holds them, so the node only stops and the
+/// desktop app starts it again
+#[cfg(not(unix))]
+fn restart_process() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    shinkai_log(
+        ShinkaiLogOption::Node,
+        ShinkaiLogLevel::Info,
+        "Restarting in place is only supported on unix, the node was stopped",
+    );
+    Ok(())
 }
 
 /// Parses the secrets file ( `.secret`) from the machine's filesystem
@@ -389,6 +596,9 @@ pub fn print_node_info(
     println!("Node API address: {}", node_env.api_listen_address);
     println!("Node TCP address: {}", node_env.listen_address);
     println!("Node WS address: {:?}", node_env.ws_address);
+    println!("Node gRPC address: {:?}", node_env.grpc_address);
+    println!("Node WebDAV address: {:?}", node_env.webdav_address);
+    println!("Node publishing address: {:?}", node_env.publishing_address);
     println!("Node Shinkai identity: {}", node_env.global_identity_name);
     println!("Node Main Profile: main (assumption)"); // Assuming "main" as the main profile
     println!("Node encryption pk: {}", encryption_pk);
@@ -397,3 +607,23 @@ pub fn print_node_info(
     println!("Vector FS DB path: {}", vector_fs_db_path);
     println!("---------------------------------------------------------------");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_restart_delay_backs_off() {
+        assert_eq!(crash_restart_delay(0, 5), Some((0, 1)));
+        assert_eq!(crash_restart_delay(1, 5), Some((1, 2)));
+        assert_eq!(crash_restart_delay(4, 5), Some((4, 16)));
+        // A node that ran long enough before crashing starts the count over
+        assert_eq!(crash_restart_delay(4, CRASH_RESTARTS_RESET_SECS), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_crash_restart_delay_gives_up() {
+        assert_eq!(crash_restart_delay(MAX_CRASH_RESTARTS, 5), None);
+        assert_eq!(crash_restart_delay(MAX_CRASH_RESTARTS + 3, 0), None);
+    }
+}
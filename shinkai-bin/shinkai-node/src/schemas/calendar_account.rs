@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// OAuth token of a Google account. The client it was issued to is configured with
+/// GOOGLE_OAUTH_CLIENT_ID and GOOGLE_OAUTH_CLIENT_SECRET, which are needed to refresh it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OAuthToken {
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl OAuthToken {
+    /// Whether the token expires in less than a minute
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .map(|expires_at| expires_at <= Utc::now() + chrono::Duration::seconds(60))
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum CalendarProvider {
+    /// Any CalDAV server. The password is read from the `password_env` environment variable.
+    CalDav {
+        calendar_url: String,
+        username: String,
+        password_env: String,
+    },
+    Google {
+        #[serde(default = "CalendarProvider::default_google_calendar_id")]
+        calendar_id: String,
+        oauth: OAuthToken,
+    },
+}
+
+impl CalendarProvider {
+    fn default_google_calendar_id() -> String {
+        "primary".to_string()
+    }
+}
+
+/// Calendar of a profile, used by the calendar tools
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalendarAccountConfig {
+    /// Full name of the profile that owns the calendar. Set by the node.
+    #[serde(default)]
+    pub profile: String,
+    #[serde(flatten)]
+    pub provider: CalendarProvider,
+}
+
+impl CalendarAccountConfig {
+    /// Copy without the OAuth tokens, to return it through the API
+    pub fn redacted(&self) -> Self {
+        let mut account = self.clone();
+        if let CalendarProvider::Google { oauth, .. } = &mut account.provider {
+            oauth.access_token = "********".to_string();
+            oauth.refresh_token = oauth.refresh_token.as_ref().map(|_| "********".to_string());
+        }
+        account
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use shinkai_vector_resources::resource_errors::VRError;
+use shinkai_vector_resources::source::SourceFileType;
+use shinkai_vector_resources::vector_resource::VRPath;
+
+use super::calendar_account::OAuthToken;
+
+const REDACTED: &str = "********";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum CloudProvider {
+    /// Refreshing the token needs GOOGLE_OAUTH_CLIENT_ID and GOOGLE_OAUTH_CLIENT_SECRET
+    GoogleDrive { folder_id: String, oauth: OAuthToken },
+    /// Refreshing the token needs DROPBOX_OAUTH_CLIENT_ID and DROPBOX_OAUTH_CLIENT_SECRET. An empty folder path
+    /// is the root of the Dropbox.
+    Dropbox {
+        #[serde(default)]
+        folder_path: String,
+        oauth: OAuthToken,
+    },
+}
+
+impl CloudProvider {
+    pub fn oauth(&self) -> &OAuthToken {
+        match self {
+            CloudProvider::GoogleDrive { oauth, .. } | CloudProvider::Dropbox { oauth, .. } => oauth,
+        }
+    }
+
+    pub fn oauth_mut(&mut self) -> &mut OAuthToken {
+        match self {
+            CloudProvider::GoogleDrive { oauth, .. } | CloudProvider::Dropbox { oauth, .. } => oauth,
+        }
+    }
+
+    /// Whether both sync the same remote folder, whatever their tokens
+    pub fn same_remote_folder(&self, other: &CloudProvider) -> bool {
+        match (self, other) {
+            (CloudProvider::GoogleDrive { folder_id, .. }, CloudProvider::GoogleDrive { folder_id: other, .. }) => {
+                folder_id == other
+            }
+            (CloudProvider::Dropbox { folder_path, .. }, CloudProvider::Dropbox { folder_path: other, .. }) => {
+                folder_path.eq_ignore_ascii_case(other)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Remote folder of a cloud storage whose files are periodically synced into a VectorFS folder of a profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CloudConnector {
+    /// Set by the node
+    #[serde(default)]
+    pub id: String,
+    /// Full name of the profile that owns the VectorFS folder. Set by the node.
+    #[serde(default)]
+    pub profile: String,
+    #[serde(flatten)]
+    pub provider: CloudProvider,
+    pub vector_fs_path: String,
+    #[serde(default = "CloudConnector::default_sync_cron")]
+    pub sync_cron: String,
+}
+
+impl CloudConnector {
+    fn default_sync_cron() -> String {
+        "*/30 * * * *".to_string()
+    }
+
+    /// Copy without the OAuth tokens, to return it through the API
+    pub fn redacted(&self) -> Self {
+        let mut connector = self.clone();
+        let oauth = connector.provider.oauth_mut();
+        oauth.access_token = REDACTED.to_string();
+        oauth.refresh_token = oauth.refresh_token.as_ref().map(|_| REDACTED.to_string());
+        connector
+    }
+
+    /// Takes the OAuth tokens of the saved connector when an update sends back the redacted ones
+    pub fn keep_tokens_of(&mut self, saved: &CloudConnector) {
+        let saved_oauth = saved.provider.oauth();
+        let oauth = self.provider.oauth_mut();
+        if oauth.access_token == REDACTED {
+            oauth.access_token = saved_oauth.access_token.clone();
+            oauth.expires_at = saved_oauth.expires_at;
+        }
+        if oauth.refresh_token.as_deref() == Some(REDACTED) {
+            oauth.refresh_token = saved_oauth.refresh_token.clone();
+        }
+    }
+
+    /// VectorFS folder and file name the remote file at the path (relative to the synced folder) is synced to
+    pub fn vector_fs_location(&self, relative_path: &str) -> Result<(VRPath, String), VRError> {
+        let mut folder_path = VRPath::from_string(&self.vector_fs_path)?;
+        let mut segments: Vec<&str> = relative_path.split('/').filter(|segment| !segment.is_empty()).collect();
+        let file_name = segments.pop().unwrap_or_default().to_string();
+        for segment in segments {
+            folder_path.push(segment.to_string());
+        }
+        Ok((folder_path, file_name))
+    }
+
+    /// Path of the item the remote file at the path (relative to the synced folder) is ingested as
+    pub fn vector_fs_item_path(&self, relative_path: &str) -> Result<VRPath, VRError> {
+        let (folder_path, file_name) = self.vector_fs_location(relative_path)?;
+        Ok(folder_path.push_cloned(SourceFileType::clean_string_of_extension(&file_name)))
+    }
+}
+
+/// Where the last sync of a connector left off
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CloudSyncState {
+    /// Delta cursor of the provider: the Google Drive changes page token or the Dropbox list_folder cursor.
+    /// None until the first full listing finished.
+    pub cursor: Option<String>,
+    /// Path relative to the synced folder of the ingested files, by their key at the provider
+    pub files: HashMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cloud_connector_tokens() {
+        let saved: CloudConnector = serde_json::from_value(serde_json::json!({
+            "provider": "dropbox",
+            "folder_path": "/Research",
+            "oauth": { "access_token": "access", "refresh_token": "refresh" },
+            "vector_fs_path": "/Dropbox/Research",
+        }))
+        .unwrap();
+        assert_eq!(saved.sync_cron, "*/30 * * * *");
+
+        let mut updated = saved.redacted();
+        assert_eq!(updated.provider.oauth().access_token, REDACTED);
+        assert_eq!(updated.provider.oauth().refresh_token.as_deref(), Some(REDACTED));
+
+        updated.sync_cron = "0 * * * *".to_string();
+        updated.keep_tokens_of(&saved);
+        assert_eq!(updated.provider, saved.provider);
+
+        assert_eq!(
+            saved
+                .vector_fs_item_path("papers/attention.pdf")
+                .unwrap()
+                .format_to_string(),
+            "/Dropbox/Research/papers/attention"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Sizes of a column family, as estimated by RocksDB
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, ToSchema)]
+pub struct ColumnFamilySize {
+    pub name: String,
+    pub estimated_keys: u64,
+    /// Size of the live data, without the deleted and overwritten values compaction would drop
+    pub live_data_bytes: u64,
+    pub sst_files_bytes: u64,
+    /// Large values are stored in blob files, outside of the SST files
+    pub blob_files_bytes: u64,
+    /// Bytes compaction has yet to rewrite to get the column family back into shape
+    pub pending_compaction_bytes: u64,
+}
+
+impl ColumnFamilySize {
+    /// Reads the sizes through a getter of RocksDB integer properties, missing properties count as 0
+    pub fn read<E>(name: &str, property: impl Fn(&str) -> Result<Option<u64>, E>) -> Result<Self, E> {
+        let value = |property_name: &str| property(property_name).map(|value| value.unwrap_or(0));
+        Ok(ColumnFamilySize {
+            name: name.to_string(),
+            estimated_keys: value("rocksdb.estimate-num-keys")?,
+            live_data_bytes: value("rocksdb.estimate-live-data-size")?,
+            sst_files_bytes: value("rocksdb.total-sst-files-size")?,
+            blob_files_bytes: value("rocksdb.total-blob-file-size")?,
+            pending_compaction_bytes: value("rocksdb.estimate-pending-compaction-bytes")?,
+        })
+    }
+}
+
+/// When the databases are compacted on their own
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, ToSchema)]
+pub struct DbCompactionSchedule {
+    /// None if the databases are only compacted on demand
+    pub interval_hours: Option<u64>,
+    #[schema(value_type = Option<String>)]
+    pub last_compaction: Option<DateTime<Utc>>,
+}
+
+impl DbCompactionSchedule {
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        match (self.interval_hours, self.last_compaction) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(hours), Some(last_compaction)) => now - last_compaction >= chrono::Duration::hours(hours as i64),
+        }
+    }
+}
+
+/// Sizes of the column families of the main database and of the VectorFS database
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, ToSchema)]
+pub struct DbMaintenanceReport {
+    pub main_db: Vec<ColumnFamilySize>,
+    pub vector_fs_db: Vec<ColumnFamilySize>,
+    /// Whether the databases were compacted before measuring them
+    pub compacted: bool,
+    pub schedule: DbCompactionSchedule,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compaction_schedule_is_due() {
+        let now = Utc::now();
+        let mut schedule = DbCompactionSchedule::default();
+        assert!(!schedule.is_due(now));
+
+        schedule.interval_hours = Some(24);
+        assert!(schedule.is_due(now));
+        schedule.last_compaction = Some(now - chrono::Duration::hours(2));
+        assert!(!schedule.is_due(now));
+        schedule.last_compaction = Some(now - chrono::Duration::hours(25));
+        assert!(schedule.is_due(now));
+    }
+}
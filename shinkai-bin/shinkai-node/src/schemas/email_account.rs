@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+/// Email account of a profile, used to ingest mail into the VectorFS and to send mail from workflows.
+/// The password is read from the `password_env` environment variable so it isn't stored in the DB.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmailAccountConfig {
+    /// Full name of the profile that owns the account. Set by the node.
+    #[serde(default)]
+    pub profile: String,
+    pub username: String,
+    pub password_env: String,
+    pub imap_host: String,
+    #[serde(default = "EmailAccountConfig::default_imap_port")]
+    pub imap_port: u16,
+    pub smtp_host: String,
+    #[serde(default = "EmailAccountConfig::default_smtp_port")]
+    pub smtp_port: u16,
+    pub from_address: String,
+    /// IMAP folders that get ingested, e.g. INBOX
+    #[serde(default)]
+    pub ingest_folders: Vec<String>,
+    /// Cron expression of when the folders get ingested
+    #[serde(default = "EmailAccountConfig::default_ingest_cron")]
+    pub ingest_cron: String,
+    /// VectorFS folder emails are saved to, with a subfolder per IMAP folder
+    #[serde(default = "EmailAccountConfig::default_vector_fs_path")]
+    pub vector_fs_path: String,
+    /// Addresses (`bob@example.com`) or domains (`@example.com`) emails can be sent to.
+    /// Sending is disabled while it's empty.
+    #[serde(default)]
+    pub sending_allowlist: Vec<String>,
+}
+
+impl EmailAccountConfig {
+    fn default_imap_port() -> u16 {
+        993
+    }
+
+    fn default_smtp_port() -> u16 {
+        587
+    }
+
+    fn default_ingest_cron() -> String {
+        "0 * * * *".to_string()
+    }
+
+    fn default_vector_fs_path() -> String {
+        "/Email".to_string()
+    }
+
+    pub fn password(&self) -> Result<String, String> {
+        std::env::var(&self.password_env).map_err(|_| format!("{} is not set", self.password_env))
+    }
+
+    pub fn allows_recipient(&self, address: &str) -> bool {
+        let address = address.trim().to_lowercase();
+        self.sending_allowlist.iter().any(|allowed| {
+            let allowed = allowed.trim().to_lowercase();
+            if allowed.starts_with('@') {
+                address.ends_with(&allowed)
+            } else {
+                address == allowed
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows_recipient() {
+        let mut account: EmailAccountConfig = serde_json::from_value(serde_json::json!({
+            "username": "alice",
+            "password_env": "ALICE_EMAIL_PASSWORD",
+            "imap_host": "imap.example.com",
+            "smtp_host": "smtp.example.com",
+            "from_address": "alice@example.com",
+        }))
+        .unwrap();
+        assert_eq!(account.imap_port, 993);
+        assert!(!account.allows_recipient("bob@example.com"));
+
+        account.sending_allowlist = vec!["@example.com".to_string(), "carol@other.com".to_string()];
+        assert!(account.allows_recipient("Bob@Example.com"));
+        assert!(account.allows_recipient("carol@other.com"));
+        assert!(!account.allows_recipient("dave@other.com"));
+        assert!(!account.allows_recipient("mallory@evil-example.com"));
+    }
+}
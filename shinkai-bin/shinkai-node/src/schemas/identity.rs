@@ -88,6 +88,17 @@ pub struct RegistrationCode {
     pub permission_type: IdentityPermissions,
 }
 
+/// A device of a profile, as shown to the user managing them
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeviceInfo {
+    pub full_identity_name: String,
+    /// Name chosen by the user, the identity name can't change since it's in the device messages
+    pub display_name: Option<String>,
+    pub permission_type: IdentityPermissions,
+    /// Last time the node received a valid message from the device
+    pub last_seen: Option<String>,
+}
+
 #[derive(Clone)]
 pub enum Identity {
     // IdentityType::Global or IdentityType::Profile
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
+use utoipa::ToSchema;
+
+/// Filters applied to the messages other nodes send to a profile. Filters that aren't set are off.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct InboundMessagePolicy {
+    /// Messages each sender can send to the profile per minute
+    #[serde(default)]
+    pub max_messages_per_minute: Option<u32>,
+    /// Size of the content of a message, in bytes
+    #[serde(default)]
+    pub max_message_bytes: Option<usize>,
+    /// Messages containing any of these words, ignoring case, are dropped
+    #[serde(default)]
+    pub blocked_keywords: Vec<String>,
+    /// Contacts of the profile skip the rate limit and the filters, unless they are untrusted
+    #[serde(default = "InboundMessagePolicy::default_exempt_contacts")]
+    pub exempt_contacts: bool,
+}
+
+impl Default for InboundMessagePolicy {
+    fn default() -> Self {
+        Self {
+            max_messages_per_minute: None,
+            max_message_bytes: None,
+            blocked_keywords: Vec::new(),
+            exempt_contacts: Self::default_exempt_contacts(),
+        }
+    }
+}
+
+impl InboundMessagePolicy {
+    fn default_exempt_contacts() -> bool {
+        true
+    }
+
+    /// Why the message is dropped by the size and keyword filters, if it is. Messages whose content is still
+    /// encrypted are measured whole and can't be checked for keywords.
+    pub fn filter(&self, message: &ShinkaiMessage) -> Option<String> {
+        let content = message.get_message_content().ok();
+        if let Some(max_message_bytes) = self.max_message_bytes {
+            let size = match &content {
+                Some(content) => content.len(),
+                None => message.encode_message().map_or(0, |bytes| bytes.len()),
+            };
+            if size > max_message_bytes {
+                return Some(format!(
+                    "{} bytes is over the limit of {} bytes",
+                    size, max_message_bytes
+                ));
+            }
+        }
+
+        let content = content?.to_lowercase();
+        self.blocked_keywords
+            .iter()
+            .find(|keyword| content.contains(&keyword.to_lowercase()))
+            .map(|keyword| format!("it contains the blocked keyword \"{}\"", keyword))
+    }
+}
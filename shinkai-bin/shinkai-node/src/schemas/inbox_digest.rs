@@ -0,0 +1,52 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InboxDigestFrequency {
+    Daily,
+    Weekly,
+}
+
+impl InboxDigestFrequency {
+    pub fn period(&self) -> Duration {
+        match self {
+            InboxDigestFrequency::Daily => Duration::days(1),
+            InboxDigestFrequency::Weekly => Duration::weeks(1),
+        }
+    }
+}
+
+/// How a profile wants its digests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct InboxDigestSettings {
+    pub frequency: InboxDigestFrequency,
+    /// Agent that writes the digests
+    pub llm_provider_id: String,
+    /// Addresses the digests are also emailed to, from the email account of the profile. They must be in the
+    /// sending allowlist of the account.
+    #[serde(default)]
+    pub email_to: Vec<String>,
+}
+
+/// Digest schedule of a profile. Each digest summarizes the unread messages and the answered jobs since the
+/// previous one, and is posted to a job inbox of its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct InboxDigestSchedule {
+    pub settings: InboxDigestSettings,
+    /// Inbox the digests are posted to, created with the first digest
+    pub inbox_name: Option<String>,
+    /// When the last digest was made, or the schedule set if there's none yet
+    pub last_digest_at: String,
+}
+
+impl InboxDigestSchedule {
+    /// Whether a frequency period passed since the last digest
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        match DateTime::parse_from_rfc3339(&self.last_digest_at) {
+            Ok(last_digest_at) => now >= last_digest_at.with_timezone(&Utc) + self.settings.frequency.period(),
+            Err(_) => true,
+        }
+    }
+}
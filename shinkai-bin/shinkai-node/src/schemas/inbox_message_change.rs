@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// Edit or deletion of a message of an inbox. It's what the node of the sender of the message sends to the other
+/// participants of the inbox so they apply the change too.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InboxMessageChange {
+    pub inbox_name: String,
+    pub message_hash: String,
+    /// New content of the message, or None if the message is deleted
+    pub new_content: Option<String>,
+    pub changed_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MessageEdit {
+    pub content: String,
+    pub edited_at: String,
+}
+
+/// Edits of a message, oldest first, and when it was deleted. Deleting a message drops its edits and only the
+/// tombstone is kept.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct MessageChangeHistory {
+    pub edits: Vec<MessageEdit>,
+    pub deleted_at: Option<String>,
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// Encrypted chunk of an attachment, sent by the node of the sender of the message to the nodes of the other
+/// participants of its inbox
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AttachmentChunk {
+    pub inbox_name: String,
+    pub attachment_id: String,
+    pub index: u32,
+    pub chunk_count: u32,
+    /// Base64 encoded nonce and ciphertext of the chunk
+    pub data: String,
+}
+
+/// Attachment of a message received from another node. Only the chunks sent by that node for the inbox of the
+/// message are stored.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AttachmentAnnouncement {
+    pub inbox_name: String,
+    pub sender_node: String,
+    pub chunk_count: u32,
+}
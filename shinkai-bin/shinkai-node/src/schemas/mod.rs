@@ -1,3 +1,17 @@
+pub mod calendar_account;
+pub mod cloud_connector;
+pub mod db_maintenance;
+pub mod email_account;
+pub mod inbound_message_policy;
+pub mod inbox_digest;
+pub mod inbox_message_change;
 pub mod inbox_permission;
+pub mod message_attachment;
+pub mod network_peer;
+pub mod notification;
+pub mod outbound_proxy;
 pub mod identity;
-pub mod smart_inbox;
\ No newline at end of file
+pub mod profile_limits;
+pub mod smart_inbox;
+pub mod watched_folder;
+pub mod wallet;
@@ -0,0 +1,35 @@
+use std::net::SocketAddr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::schemas::shinkai_network::{PeerProtocol, NETWORK_PROTOCOL_VERSION_1};
+
+/// A node this node knows about, with the protocol it announced
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkPeer {
+    pub address: SocketAddr,
+    pub profile_name: String,
+    pub last_seen: DateTime<Utc>,
+    /// Version 1 for peers which never announced their protocol
+    pub protocol_version: u32,
+    pub protocol: Option<PeerProtocol>,
+}
+
+impl NetworkPeer {
+    pub fn new(
+        address: SocketAddr,
+        profile_name: String,
+        last_seen: DateTime<Utc>,
+        protocol: Option<PeerProtocol>,
+    ) -> Self {
+        NetworkPeer {
+            address,
+            profile_name,
+            last_seen,
+            protocol_version: protocol
+                .as_ref()
+                .map_or(NETWORK_PROTOCOL_VERSION_1, |protocol| protocol.protocol_version),
+            protocol,
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// A subscription was requested, confirmed or synced
+    #[default]
+    Subscription,
+    /// A job finished processing a message
+    JobDone,
+    /// A subscriber paid for a shared folder
+    PaymentReceived,
+    /// Something failed in the background, e.g. a job
+    Error,
+    /// The VectorFS integrity check found corrupted or missing data
+    Integrity,
+}
+
+/// How a profile wants to be notified. Kinds that aren't listed are stored and pushed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct NotificationPreferences {
+    /// Kinds of notifications that are dropped
+    #[serde(default)]
+    pub disabled_kinds: Vec<NotificationKind>,
+    /// Kinds of notifications that are stored but not pushed to the event stream and WebSockets, so the
+    /// desktop app doesn't show them in the tray
+    #[serde(default)]
+    pub silent_kinds: Vec<NotificationKind>,
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Resources a profile can use, so one profile can't starve the others when a node is shared by a team.
+/// Limits that aren't set are unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ProfileLimits {
+    /// Name of the profile, e.g. `main`
+    pub profile: String,
+    /// Job creations and job messages the profile can send per minute
+    #[serde(default)]
+    pub max_requests_per_minute: Option<u32>,
+    /// Jobs of the profile that can be processed at the same time, the others wait in the queue
+    #[serde(default)]
+    pub max_concurrent_jobs: Option<usize>,
+    /// Size of the Vector Resources and source files the profile can store in its VectorFS
+    #[serde(default)]
+    pub vector_fs_quota_bytes: Option<u64>,
+    /// LLM tokens (prompts and responses, estimated) the profile can use per calendar month (UTC)
+    #[serde(default)]
+    pub monthly_llm_token_budget: Option<u64>,
+}
+
+/// Limits of a profile and how much of them it's using
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ProfileUsage {
+    pub limits: ProfileLimits,
+    /// Month the token usage is for, e.g. `2024-09`
+    pub month: String,
+    pub llm_tokens_used: u64,
+    pub vector_fs_bytes_used: u64,
+}
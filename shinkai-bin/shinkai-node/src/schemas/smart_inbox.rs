@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use shinkai_message_primitives::{schemas::{llm_providers::serialized_llm_provider::{LLMProviderInterface, SerializedLLMProvider}, shinkai_name::ShinkaiName}, shinkai_message::{shinkai_message::ShinkaiMessage, shinkai_message_schemas::V2ChatMessage}};
+use std::collections::BTreeMap;
+use shinkai_message_primitives::{schemas::{contact::Contact, llm_providers::serialized_llm_provider::{LLMProviderInterface, SerializedLLMProvider}, shinkai_name::ShinkaiName}, shinkai_message::{shinkai_message::ShinkaiMessage, shinkai_message_schemas::V2ChatMessage}};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct LLMProviderSubset {
@@ -28,6 +29,16 @@ pub struct SmartInbox {
     pub is_finished: bool,
     pub job_scope: Option<Value>,
     pub agent: Option<LLMProviderSubset>,
+    pub folder: Option<String>,
+    pub labels: Vec<String>,
+    pub is_archived: bool,
+    pub is_muted: bool,
+    /// Unread messages, up to 100. Always 0 for muted inboxes.
+    pub unread_count: usize,
+    /// Conversation started by an identity that isn't allowed by the contact book yet
+    pub is_message_request: bool,
+    /// Contact of the other participant of a regular inbox
+    pub contact: Option<Contact>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -39,4 +50,28 @@ pub struct V2SmartInbox {
     pub is_finished: bool,
     pub job_scope: Option<Value>,
     pub agent: Option<LLMProviderSubset>,
+    pub folder: Option<String>,
+    pub labels: Vec<String>,
+    pub is_archived: bool,
+    pub is_muted: bool,
+    /// Unread messages, up to 100. Always 0 for muted inboxes.
+    pub unread_count: usize,
+    pub is_message_request: bool,
+    pub contact: Option<Contact>,
+}
+
+/// Where the user filed an inbox: the folder it's in (none if unfiled) and the labels it's tagged with
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct InboxOrganization {
+    pub folder: Option<String>,
+    pub labels: Vec<String>,
+}
+
+/// Folders and labels in use by the inboxes of a profile, with how many inboxes each one has
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct InboxFolders {
+    pub folders: BTreeMap<String, usize>,
+    pub labels: BTreeMap<String, usize>,
+    /// Inboxes that aren't in any folder
+    pub unfiled: usize,
 }
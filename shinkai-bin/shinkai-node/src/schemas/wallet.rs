@@ -0,0 +1,232 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::schemas::payment_invoice::Invoice;
+
+/// What signs the transactions of the wallet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WalletSigner {
+    /// Key held by the node. It's read from the `private_key_env` environment variable so it isn't stored in the DB.
+    LocalKey { private_key_env: String },
+    /// Wallet app connected to the node, walletconnect style. The node queues the transactions it wants to send, the
+    /// app signs and sends them, then reports their hash back.
+    External { address: String },
+}
+
+/// EVM network the wallet pays on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalletNetwork {
+    pub name: String,
+    pub chain_id: u64,
+    pub rpc_url: String,
+}
+
+/// Asset the wallet can pay invoices in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalletAsset {
+    /// Symbol the invoices use, e.g. `USDC`
+    pub symbol: String,
+    /// ERC20 contract of the token. The native coin of the network if not set.
+    #[serde(default)]
+    pub contract_address: Option<String>,
+    pub decimals: u32,
+}
+
+/// How much the node pays on its own in an asset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoPayLimit {
+    pub asset: String,
+    pub max_per_invoice: Decimal,
+    /// Most that is paid automatically over `period_secs`
+    pub max_per_period: Decimal,
+    #[serde(default = "AutoPayLimit::default_period_secs")]
+    pub period_secs: u64,
+}
+
+impl AutoPayLimit {
+    fn default_period_secs() -> u64 {
+        30 * 24 * 60 * 60
+    }
+}
+
+/// Invoices of subscriptions are paid as they arrive, within the limits of their asset. Assets without a limit are
+/// never paid automatically.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AutoPaySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub limits: Vec<AutoPayLimit>,
+}
+
+impl AutoPaySettings {
+    /// Checks the invoice can be paid without asking, given the transactions the wallet already made
+    pub fn check(&self, invoice: &Invoice, history: &[WalletTransaction], now: DateTime<Utc>) -> Result<(), String> {
+        if !self.enabled {
+            return Err("Automatic payments are disabled".to_string());
+        }
+        let limit = self
+            .limits
+            .iter()
+            .find(|limit| limit.asset.eq_ignore_ascii_case(&invoice.asset))
+            .ok_or_else(|| format!("No automatic payment limit for {}", invoice.asset))?;
+        if invoice.amount > limit.max_per_invoice {
+            return Err(format!(
+                "{} {} is over the limit of {} per invoice",
+                invoice.amount, invoice.asset, limit.max_per_invoice
+            ));
+        }
+
+        let period_start = now - Duration::seconds(limit.period_secs as i64);
+        let spent: Decimal = history
+            .iter()
+            .filter(|transaction| {
+                transaction.automatic
+                    && transaction.status != WalletTransactionStatus::Failed
+                    && transaction.asset.eq_ignore_ascii_case(&invoice.asset)
+                    && transaction.created_at > period_start
+            })
+            .map(|transaction| transaction.amount)
+            .sum();
+        if spent + invoice.amount > limit.max_per_period {
+            return Err(format!(
+                "Paying {} {} would go over the limit of {} per period, {} was already paid",
+                invoice.amount, invoice.asset, limit.max_per_period, spent
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalletConfig {
+    pub signer: WalletSigner,
+    pub network: WalletNetwork,
+    #[serde(default)]
+    pub assets: Vec<WalletAsset>,
+    #[serde(default)]
+    pub auto_pay: AutoPaySettings,
+}
+
+impl WalletConfig {
+    pub fn asset(&self, symbol: &str) -> Option<&WalletAsset> {
+        self.assets
+            .iter()
+            .find(|asset| asset.symbol.eq_ignore_ascii_case(symbol))
+    }
+}
+
+/// The wallet as returned by the API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalletInfo {
+    pub address: String,
+    #[serde(flatten)]
+    pub config: WalletConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletTransactionStatus {
+    /// Waiting for the external signer to sign and send it
+    AwaitingSignature,
+    /// Sent by the external signer, not checked on chain by the node
+    Submitted,
+    Confirmed,
+    Failed,
+}
+
+/// Payment made by the wallet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalletTransaction {
+    pub transaction_id: String,
+    pub invoice_id: Option<String>,
+    pub to: String,
+    pub amount: Decimal,
+    pub asset: String,
+    /// Amount in the smallest unit of the asset, what the transaction transfers
+    pub base_units: String,
+    pub contract_address: Option<String>,
+    pub chain_id: u64,
+    /// Whether it was paid within the automatic payment limits rather than asked for through the API
+    pub automatic: bool,
+    pub status: WalletTransactionStatus,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shinkai_message_primitives::schemas::payment_invoice::{InvoiceReference, InvoiceStatus};
+
+    fn invoice(amount: Decimal) -> Invoice {
+        Invoice {
+            invoice_id: "invoice1".to_string(),
+            issuer: "@@alice.sepolia-shinkai".to_string(),
+            payer: "@@bob.sepolia-shinkai".to_string(),
+            amount,
+            asset: "USDC".to_string(),
+            memo: None,
+            reference: Some(InvoiceReference::Subscription {
+                subscription_id: "subscription1".to_string(),
+            }),
+            pay_to: Some("0x0000000000000000000000000000000000000001".to_string()),
+            created_at: Utc::now(),
+            expires_at: None,
+            status: InvoiceStatus::Pending,
+            receipt: None,
+        }
+    }
+
+    #[test]
+    fn test_auto_pay_limits() {
+        let settings = AutoPaySettings {
+            enabled: true,
+            limits: vec![AutoPayLimit {
+                asset: "usdc".to_string(),
+                max_per_invoice: Decimal::new(10, 0),
+                max_per_period: Decimal::new(15, 0),
+                period_secs: 3600,
+            }],
+        };
+        let now = Utc::now();
+        assert!(settings.check(&invoice(Decimal::new(8, 0)), &[], now).is_ok());
+        assert!(settings.check(&invoice(Decimal::new(11, 0)), &[], now).is_err());
+
+        let paid = WalletTransaction {
+            transaction_id: "transaction1".to_string(),
+            invoice_id: Some("invoice0".to_string()),
+            to: "0x0000000000000000000000000000000000000001".to_string(),
+            amount: Decimal::new(8, 0),
+            asset: "USDC".to_string(),
+            base_units: "8000000".to_string(),
+            contract_address: None,
+            chain_id: 1,
+            automatic: true,
+            status: WalletTransactionStatus::Confirmed,
+            tx_hash: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        assert!(settings
+            .check(&invoice(Decimal::new(8, 0)), &[paid.clone()], now)
+            .is_err());
+
+        // Payments older than the period and failed ones don't count
+        let old = WalletTransaction {
+            created_at: now - Duration::seconds(7200),
+            ..paid.clone()
+        };
+        let failed = WalletTransaction {
+            status: WalletTransactionStatus::Failed,
+            ..paid
+        };
+        assert!(settings
+            .check(&invoice(Decimal::new(8, 0)), &[old, failed], now)
+            .is_ok());
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use shinkai_vector_resources::resource_errors::VRError;
+use shinkai_vector_resources::source::SourceFileType;
+use shinkai_vector_resources::vector_resource::VRPath;
+
+/// Suffixes of the files editors and browsers write before moving them to their final name
+const TEMPORARY_FILE_SUFFIXES: [&str; 5] = [".tmp", ".part", ".crdownload", ".swp", ".download"];
+
+/// Local directory of the node's machine whose files are kept in sync with a VectorFS folder of a profile.
+/// New and changed files get ingested into the folder and deleted ones get removed from it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchedFolder {
+    /// Set by the node
+    #[serde(default)]
+    pub id: String,
+    /// Full name of the profile that owns the VectorFS folder. Set by the node.
+    #[serde(default)]
+    pub profile: String,
+    /// Absolute path of the local directory
+    pub local_path: String,
+    pub vector_fs_path: String,
+    /// Whether the files of subdirectories are synced too, into subfolders of the same names
+    #[serde(default = "WatchedFolder::default_recursive")]
+    pub recursive: bool,
+}
+
+impl WatchedFolder {
+    fn default_recursive() -> bool {
+        true
+    }
+
+    /// Whether the file at the path (relative to the local directory, `/` separated) gets synced. Hidden files,
+    /// temporary files and files of types that can't be ingested are skipped.
+    pub fn should_sync(&self, relative_path: &str) -> bool {
+        let segments: Vec<&str> = relative_path.split('/').collect();
+        if !self.recursive && segments.len() > 1 {
+            return false;
+        }
+        if segments
+            .iter()
+            .any(|segment| segment.is_empty() || segment.starts_with('.') || segment.starts_with('~'))
+        {
+            return false;
+        }
+        let file_name = segments.last().copied().unwrap_or_default();
+        let lowercase_name = file_name.to_lowercase();
+        if TEMPORARY_FILE_SUFFIXES
+            .iter()
+            .any(|suffix| lowercase_name.ends_with(suffix))
+        {
+            return false;
+        }
+        SourceFileType::detect_file_type(file_name).is_ok()
+    }
+
+    /// VectorFS folder and file name the file at the path (relative to the local directory) is synced to
+    pub fn vector_fs_location(&self, relative_path: &str) -> Result<(VRPath, String), VRError> {
+        let mut folder_path = VRPath::from_string(&self.vector_fs_path)?;
+        let mut segments: Vec<&str> = relative_path.split('/').collect();
+        let file_name = segments.pop().unwrap_or_default().to_string();
+        for segment in segments {
+            folder_path.push(segment.to_string());
+        }
+        Ok((folder_path, file_name))
+    }
+
+    /// Path of the item the file at the path (relative to the local directory) is ingested as
+    pub fn vector_fs_item_path(&self, relative_path: &str) -> Result<VRPath, VRError> {
+        let (folder_path, file_name) = self.vector_fs_location(relative_path)?;
+        Ok(folder_path.push_cloned(SourceFileType::clean_string_of_extension(&file_name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watched_folder_paths() {
+        let mut folder: WatchedFolder = serde_json::from_value(serde_json::json!({
+            "local_path": "/home/alice/Documents/Research",
+            "vector_fs_path": "/Research",
+        }))
+        .unwrap();
+        assert!(folder.recursive);
+
+        assert!(folder.should_sync("papers/attention.pdf"));
+        assert!(!folder.should_sync(".git/notes.md"));
+        assert!(!folder.should_sync("~$draft.docx"));
+        assert!(!folder.should_sync("download.pdf.part"));
+        assert!(!folder.should_sync("photo.unknown_type"));
+
+        let (folder_path, file_name) = folder.vector_fs_location("papers/attention.pdf").unwrap();
+        assert_eq!(folder_path.format_to_string(), "/Research/papers");
+        assert_eq!(file_name, "attention.pdf");
+        assert_eq!(
+            folder
+                .vector_fs_item_path("papers/attention.pdf")
+                .unwrap()
+                .format_to_string(),
+            "/Research/papers/attention"
+        );
+
+        folder.recursive = false;
+        assert!(!folder.should_sync("papers/attention.pdf"));
+        assert!(folder.should_sync("summary.md"));
+    }
+}
@@ -1,3 +1,5 @@
+use super::error::ToolError;
+
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ToolArgument {
     pub name: String,
@@ -17,3 +19,81 @@ impl ToolArgument {
         }
     }
 }
+
+impl ToolArgument {
+    /// Returns the JSON schema type of the argument. Types that aren't JSON schema types
+    /// (e.g. the internal ones used by the static Rust tools) are exposed as strings.
+    pub fn json_schema_type(&self) -> &str {
+        match self.arg_type.as_str() {
+            "string" | "number" | "integer" | "boolean" | "object" | "array" => self.arg_type.as_str(),
+            _ => "string",
+        }
+    }
+
+    /// Validates a set of function call arguments against the expected arguments.
+    /// Strings are accepted for non-string arguments if they parse into the expected type,
+    /// as workflows pass every argument as a string.
+    pub fn validate_args(
+        expected: &[ToolArgument],
+        args: serde_json::Value,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, ToolError> {
+        let mut args = match args {
+            serde_json::Value::Object(map) => map,
+            serde_json::Value::Null => serde_json::Map::new(),
+            _ => {
+                return Err(ToolError::InvalidFunctionArguments(
+                    "Expected the arguments to be a JSON object".to_string(),
+                ))
+            }
+        };
+
+        for arg in expected {
+            let value = match args.get(&arg.name) {
+                Some(serde_json::Value::Null) | None => {
+                    if arg.is_required {
+                        return Err(ToolError::InvalidFunctionArguments(format!(
+                            "Missing required argument: {}",
+                            arg.name
+                        )));
+                    }
+                    continue;
+                }
+                Some(value) => value.clone(),
+            };
+
+            let value = arg.coerce_value(value).ok_or_else(|| {
+                ToolError::InvalidFunctionArguments(format!(
+                    "Argument {} is not of type {}",
+                    arg.name,
+                    arg.json_schema_type()
+                ))
+            })?;
+            args.insert(arg.name.clone(), value);
+        }
+
+        Ok(args)
+    }
+
+    fn coerce_value(&self, value: serde_json::Value) -> Option<serde_json::Value> {
+        let matches_type = |value: &serde_json::Value| match self.json_schema_type() {
+            "string" => value.is_string(),
+            "number" => value.is_number(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "boolean" => value.is_boolean(),
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            _ => false,
+        };
+
+        if matches_type(&value) {
+            return Some(value);
+        }
+
+        match value {
+            serde_json::Value::String(s) => serde_json::from_str::<serde_json::Value>(&s)
+                .ok()
+                .filter(|parsed| matches_type(parsed)),
+            _ => None,
+        }
+    }
+}
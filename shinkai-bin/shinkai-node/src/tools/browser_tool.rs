@@ -0,0 +1,273 @@
+use std::env;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::Page;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::network::outbound_proxy::OUTBOUND_PROXY;
+use crate::schemas::outbound_proxy::DestinationClass;
+use crate::tools::argument::ToolArgument;
+use crate::tools::error::ToolError;
+use crate::tools::js_toolkit_headers::ToolCapability;
+use crate::tools::native_tool::NativeTool;
+use crate::tools::rust_tools::RustTool;
+use crate::tools::tool_execution_limits::ToolExecutionLimits;
+
+const DEFAULT_WAIT_FOR_TIMEOUT_MS: u64 = 10_000;
+const WAIT_FOR_POLL_INTERVAL_MS: u64 = 200;
+
+/// A step run on the page after it loads
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BrowserAction {
+    Navigate { url: String },
+    Click { selector: String },
+    Fill { selector: String, value: String },
+    Press { selector: String, key: String },
+    WaitFor { selector: String, timeout_ms: Option<u64> },
+    Sleep { ms: u64 },
+}
+
+/// Built-in tool that drives a headless Chrome, for pages that only render with JS.
+///
+/// Chrome is looked up in the usual locations unless CHROME_PATH is set.
+/// Set BROWSER_TOOL_NO_SANDBOX=true when the node runs as root (e.g. in Docker).
+pub struct BrowserTool;
+
+impl BrowserTool {
+    pub const NAME: &'static str = "browser_automation";
+
+    fn browser_config() -> Result<BrowserConfig, ToolError> {
+        let mut builder = BrowserConfig::builder();
+        if let Ok(chrome_path) = env::var("CHROME_PATH") {
+            builder = builder.chrome_executable(chrome_path);
+        }
+        if env::var("BROWSER_TOOL_NO_SANDBOX")
+            .map(|v| v == "true")
+            .unwrap_or(false)
+        {
+            builder = builder.no_sandbox();
+        }
+        if let Some(proxy_url) = OUTBOUND_PROXY.proxy_url(DestinationClass::WebTools) {
+            // Chromium resolves host names through SOCKS5 proxies on its own and only knows the socks5 scheme
+            builder = builder.arg(format!(
+                "--proxy-server={}",
+                proxy_url.replacen("socks5h://", "socks5://", 1)
+            ));
+        }
+        builder.build().map_err(ToolError::ExecutionError)
+    }
+
+    async fn run_actions(page: &Page, actions: &[BrowserAction]) -> Result<(), ToolError> {
+        for action in actions {
+            match action {
+                BrowserAction::Navigate { url } => {
+                    page.goto(url.as_str()).await.map_err(Self::browser_error)?;
+                    page.wait_for_navigation().await.map_err(Self::browser_error)?;
+                }
+                BrowserAction::Click { selector } => {
+                    page.find_element(selector.as_str())
+                        .await
+                        .map_err(Self::browser_error)?
+                        .click()
+                        .await
+                        .map_err(Self::browser_error)?;
+                }
+                BrowserAction::Fill { selector, value } => {
+                    page.find_element(selector.as_str())
+                        .await
+                        .map_err(Self::browser_error)?
+                        .click()
+                        .await
+                        .map_err(Self::browser_error)?
+                        .type_str(value)
+                        .await
+                        .map_err(Self::browser_error)?;
+                }
+                BrowserAction::Press { selector, key } => {
+                    page.find_element(selector.as_str())
+                        .await
+                        .map_err(Self::browser_error)?
+                        .press_key(key)
+                        .await
+                        .map_err(Self::browser_error)?;
+                }
+                BrowserAction::WaitFor { selector, timeout_ms } => {
+                    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_WAIT_FOR_TIMEOUT_MS));
+                    let wait = async {
+                        while page.find_element(selector.as_str()).await.is_err() {
+                            tokio::time::sleep(Duration::from_millis(WAIT_FOR_POLL_INTERVAL_MS)).await;
+                        }
+                    };
+                    tokio::time::timeout(timeout, wait).await.map_err(|_| {
+                        ToolError::ExecutionError(format!("Element {} didn't show up in time", selector))
+                    })?;
+                }
+                BrowserAction::Sleep { ms } => tokio::time::sleep(Duration::from_millis(*ms)).await,
+            }
+        }
+        Ok(())
+    }
+
+    async fn extract(page: &Page, extract_selector: Option<&str>, include_html: bool) -> Result<Value, ToolError> {
+        let content = match extract_selector {
+            Some(selector) => {
+                let mut texts = Vec::new();
+                for element in page.find_elements(selector).await.map_err(Self::browser_error)? {
+                    if let Some(text) = element.inner_text().await.map_err(Self::browser_error)? {
+                        texts.push(text);
+                    }
+                }
+                texts.join("\n")
+            }
+            None => page
+                .evaluate("document.body ? document.body.innerText : ''")
+                .await
+                .map_err(Self::browser_error)?
+                .into_value::<String>()
+                .map_err(|e| ToolError::SerializationError(e.to_string()))?,
+        };
+
+        let mut result = json!({
+            "url": page.url().await.map_err(Self::browser_error)?,
+            "title": page.get_title().await.map_err(Self::browser_error)?,
+            "content": content,
+        });
+        if include_html {
+            result["html"] = json!(page.content().await.map_err(Self::browser_error)?);
+        }
+        Ok(result)
+    }
+
+    async fn browse(
+        url: &str,
+        actions: &[BrowserAction],
+        extract_selector: Option<&str>,
+        include_html: bool,
+    ) -> Result<Value, ToolError> {
+        let (mut browser, mut handler) = Browser::launch(Self::browser_config()?)
+            .await
+            .map_err(Self::browser_error)?;
+        let handler_task = tokio::spawn(async move {
+            while let Some(event) = handler.next().await {
+                if event.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let result = async {
+            let page = browser.new_page(url).await.map_err(Self::browser_error)?;
+            page.wait_for_navigation().await.map_err(Self::browser_error)?;
+            Self::run_actions(&page, actions).await?;
+            Self::extract(&page, extract_selector, include_html).await
+        }
+        .await;
+
+        let _ = browser.close().await;
+        let _ = browser.wait().await;
+        handler_task.abort();
+        result
+    }
+
+    fn browser_error(e: chromiumoxide::error::CdpError) -> ToolError {
+        ToolError::ExecutionError(format!("Browser error: {}", e))
+    }
+}
+
+#[async_trait]
+impl NativeTool for BrowserTool {
+    fn definition(&self) -> RustTool {
+        RustTool::new(
+            Self::NAME.to_string(),
+            "Opens a web page in a headless browser, optionally runs actions on it (navigate, click, fill, press, wait_for, sleep) and returns its rendered text. Use it for pages that need JavaScript to show their content.".to_string(),
+            vec![
+                ToolArgument::new("url".to_string(), "string".to_string(), "URL of the page to open".to_string(), true),
+                ToolArgument::new(
+                    "actions".to_string(),
+                    "array".to_string(),
+                    "Steps to run after the page loads, e.g. [{\"type\": \"fill\", \"selector\": \"#q\", \"value\": \"shinkai\"}, {\"type\": \"press\", \"selector\": \"#q\", \"key\": \"Enter\"}, {\"type\": \"wait_for\", \"selector\": \".results\"}]".to_string(),
+                    false,
+                ),
+                ToolArgument::new(
+                    "extract_selector".to_string(),
+                    "string".to_string(),
+                    "CSS selector of the elements to get the text from. Defaults to the whole page".to_string(),
+                    false,
+                ),
+                ToolArgument::new(
+                    "include_html".to_string(),
+                    "boolean".to_string(),
+                    "Whether to also return the rendered HTML".to_string(),
+                    false,
+                ),
+            ],
+            None,
+        )
+    }
+
+    /// Pages can be opened on any host
+    fn capabilities(&self) -> Vec<ToolCapability> {
+        vec![ToolCapability::Network("*".to_string())]
+    }
+
+    async fn run(&self, args: serde_json::Map<String, Value>) -> Result<Value, ToolError> {
+        let url = args.get("url").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let actions: Vec<BrowserAction> = match args.get("actions") {
+            Some(actions) => serde_json::from_value(actions.clone())
+                .map_err(|e| ToolError::InvalidFunctionArguments(format!("Invalid browser actions: {}", e)))?,
+            None => vec![],
+        };
+        let extract_selector = args.get("extract_selector").and_then(|v| v.as_str());
+        let include_html = args.get("include_html").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let limits = ToolExecutionLimits::default();
+        let result = tokio::time::timeout(
+            limits.timeout(),
+            Self::browse(&url, &actions, extract_selector, include_html),
+        )
+        .await
+        .map_err(|_| limits.timeout_error(Self::NAME))??;
+
+        limits.check_output(Self::NAME, &result)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_browser_actions() {
+        let actions: Vec<BrowserAction> = serde_json::from_value(json!([
+            {"type": "fill", "selector": "#q", "value": "shinkai"},
+            {"type": "press", "selector": "#q", "key": "Enter"},
+            {"type": "wait_for", "selector": ".results"},
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            actions,
+            vec![
+                BrowserAction::Fill {
+                    selector: "#q".to_string(),
+                    value: "shinkai".to_string()
+                },
+                BrowserAction::Press {
+                    selector: "#q".to_string(),
+                    key: "Enter".to_string()
+                },
+                BrowserAction::WaitFor {
+                    selector: ".results".to_string(),
+                    timeout_ms: None
+                },
+            ]
+        );
+        assert!(serde_json::from_value::<Vec<BrowserAction>>(json!([{"type": "hover"}])).is_err());
+    }
+}
@@ -0,0 +1,721 @@
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::db::ShinkaiDB;
+use crate::schemas::calendar_account::{CalendarAccountConfig, CalendarProvider, OAuthToken};
+use crate::tools::argument::ToolArgument;
+use crate::tools::error::ToolError;
+use crate::tools::native_tool::{NativeTool, NativeToolContext};
+use crate::tools::rust_tools::RustTool;
+
+const CALENDAR_TIMEOUT_SECS: u64 = 30;
+const GOOGLE_CALENDAR_API: &str = "https://www.googleapis.com/calendar/v3";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    pub id: String,
+    pub summary: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub attendees: Vec<String>,
+}
+
+/// Talks to the calendar of a profile. Refreshed Google tokens are saved back to the DB.
+pub struct CalendarClient {
+    account: CalendarAccountConfig,
+    db: Arc<ShinkaiDB>,
+    client: reqwest::Client,
+}
+
+impl CalendarClient {
+    pub fn new(account: CalendarAccountConfig, db: Arc<ShinkaiDB>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(CALENDAR_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+        CalendarClient { account, db, client }
+    }
+
+    /// Client for the calendar of the profile in the context
+    pub fn from_context(context: &NativeToolContext) -> Result<Self, ToolError> {
+        let (Some(profile), Some(db)) = (&context.profile, &context.db) else {
+            return Err(ToolError::ExecutionError(
+                "Calendar tools need to know which profile they run for".to_string(),
+            ));
+        };
+        let account = db
+            .get_calendar_account(&profile.full_name)
+            .map_err(|_| ToolError::ExecutionError(format!("{} doesn't have a calendar set up", profile.full_name)))?;
+        Ok(Self::new(account, db.clone()))
+    }
+
+    /// Events overlapping the range, sorted by start
+    pub async fn list_events(
+        &mut self,
+        time_min: DateTime<Utc>,
+        time_max: DateTime<Utc>,
+    ) -> Result<Vec<CalendarEvent>, ToolError> {
+        let mut events = match self.account.provider.clone() {
+            CalendarProvider::CalDav {
+                calendar_url,
+                username,
+                password_env,
+            } => {
+                let body = format!(
+                    r#"<?xml version="1.0" encoding="utf-8"?>
+<C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop><C:calendar-data/></D:prop>
+  <C:filter>
+    <C:comp-filter name="VCALENDAR">
+      <C:comp-filter name="VEVENT">
+        <C:time-range start="{}" end="{}"/>
+      </C:comp-filter>
+    </C:comp-filter>
+  </C:filter>
+</C:calendar-query>"#,
+                    format_ics_datetime(&time_min),
+                    format_ics_datetime(&time_max)
+                );
+                let method =
+                    reqwest::Method::from_bytes(b"REPORT").map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+                let response = self
+                    .client
+                    .request(method, &calendar_url)
+                    .basic_auth(username, Some(Self::caldav_password(&password_env)?))
+                    .header("Depth", "1")
+                    .header("Content-Type", "application/xml; charset=utf-8")
+                    .body(body)
+                    .send()
+                    .await?;
+                if !response.status().is_success() {
+                    return Err(Self::status_error("CalDAV", response.status()));
+                }
+                let text = response.text().await?;
+                extract_calendar_data(&text)
+                    .iter()
+                    .flat_map(|ics| parse_ics_events(ics))
+                    .filter(|event| event.start < time_max && event.end > time_min)
+                    .collect::<Vec<_>>()
+            }
+            CalendarProvider::Google { calendar_id, .. } => {
+                let access_token = self.google_access_token().await?;
+                let response = self
+                    .client
+                    .get(format!(
+                        "{}/calendars/{}/events",
+                        GOOGLE_CALENDAR_API,
+                        urlencoding::encode(&calendar_id)
+                    ))
+                    .bearer_auth(access_token)
+                    .query(&[
+                        ("timeMin", time_min.to_rfc3339()),
+                        ("timeMax", time_max.to_rfc3339()),
+                        ("singleEvents", "true".to_string()),
+                        ("orderBy", "startTime".to_string()),
+                        ("maxResults", "250".to_string()),
+                    ])
+                    .send()
+                    .await?;
+                if !response.status().is_success() {
+                    return Err(Self::status_error("Google Calendar", response.status()));
+                }
+                let body: Value = response.json().await?;
+                parse_google_events(&body)
+            }
+        };
+
+        events.sort_by_key(|event| event.start);
+        Ok(events)
+    }
+
+    pub async fn create_event(&mut self, event: &CalendarEvent) -> Result<CalendarEvent, ToolError> {
+        match self.account.provider.clone() {
+            CalendarProvider::CalDav {
+                calendar_url,
+                username,
+                password_env,
+            } => {
+                let response = self
+                    .client
+                    .put(format!("{}/{}.ics", calendar_url.trim_end_matches('/'), event.id))
+                    .basic_auth(username, Some(Self::caldav_password(&password_env)?))
+                    .header("Content-Type", "text/calendar; charset=utf-8")
+                    .header("If-None-Match", "*")
+                    .body(to_ics(event))
+                    .send()
+                    .await?;
+                if !response.status().is_success() {
+                    return Err(Self::status_error("CalDAV", response.status()));
+                }
+                Ok(event.clone())
+            }
+            CalendarProvider::Google { calendar_id, .. } => {
+                let access_token = self.google_access_token().await?;
+                let attendees: Vec<Value> = event.attendees.iter().map(|email| json!({ "email": email })).collect();
+                let response = self
+                    .client
+                    .post(format!(
+                        "{}/calendars/{}/events",
+                        GOOGLE_CALENDAR_API,
+                        urlencoding::encode(&calendar_id)
+                    ))
+                    .bearer_auth(access_token)
+                    .json(&json!({
+                        "summary": event.summary,
+                        "description": event.description,
+                        "location": event.location,
+                        "start": { "dateTime": event.start.to_rfc3339() },
+                        "end": { "dateTime": event.end.to_rfc3339() },
+                        "attendees": attendees,
+                    }))
+                    .send()
+                    .await?;
+                if !response.status().is_success() {
+                    return Err(Self::status_error("Google Calendar", response.status()));
+                }
+                let body: Value = response.json().await?;
+                parse_google_event(&body)
+                    .ok_or_else(|| ToolError::ParseError("the Google Calendar response".to_string()))
+            }
+        }
+    }
+
+    fn caldav_password(password_env: &str) -> Result<String, ToolError> {
+        env::var(password_env).map_err(|_| ToolError::ExecutionError(format!("{} is not set", password_env)))
+    }
+
+    fn status_error(provider: &str, status: reqwest::StatusCode) -> ToolError {
+        ToolError::ExecutionError(format!("{} request failed with status {}", provider, status))
+    }
+
+    /// Access token of the Google account, refreshing it first if it expired
+    async fn google_access_token(&mut self) -> Result<String, ToolError> {
+        let CalendarProvider::Google { oauth, .. } = &self.account.provider else {
+            return Err(ToolError::ExecutionError("Not a Google calendar".to_string()));
+        };
+        if !oauth.is_expired() {
+            return Ok(oauth.access_token.clone());
+        }
+        let Some(refresh_token) = oauth.refresh_token.clone() else {
+            return Err(ToolError::ExecutionError(
+                "The Google token expired and there is no refresh token".to_string(),
+            ));
+        };
+
+        let client_id = env::var("GOOGLE_OAUTH_CLIENT_ID")
+            .map_err(|_| ToolError::ExecutionError("GOOGLE_OAUTH_CLIENT_ID is not set".to_string()))?;
+        let client_secret = env::var("GOOGLE_OAUTH_CLIENT_SECRET")
+            .map_err(|_| ToolError::ExecutionError("GOOGLE_OAUTH_CLIENT_SECRET is not set".to_string()))?;
+        let response = self
+            .client
+            .post(GOOGLE_TOKEN_URL)
+            .form(&[
+                ("client_id", client_id.as_str()),
+                ("client_secret", client_secret.as_str()),
+                ("refresh_token", refresh_token.as_str()),
+                ("grant_type", "refresh_token"),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(Self::status_error("Google OAuth", response.status()));
+        }
+        let body: Value = response.json().await?;
+        let access_token = body
+            .get("access_token")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::ParseError("the Google token response".to_string()))?
+            .to_string();
+        let expires_in = body.get("expires_in").and_then(|v| v.as_i64()).unwrap_or(3600);
+
+        if let CalendarProvider::Google { oauth, .. } = &mut self.account.provider {
+            *oauth = OAuthToken {
+                access_token: access_token.clone(),
+                // Google only returns a new refresh token when it rotates it
+                refresh_token: body
+                    .get("refresh_token")
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.to_string())
+                    .or(Some(refresh_token)),
+                expires_at: Some(Utc::now() + chrono::Duration::seconds(expires_in)),
+            };
+        }
+        self.db
+            .set_calendar_account(&self.account)
+            .map_err(|e| ToolError::DatabaseError(e.to_string()))?;
+        Ok(access_token)
+    }
+}
+
+/// Free ranges of at least `duration` between `time_min` and `time_max` that don't overlap any event
+pub fn find_free_slots(
+    events: &[CalendarEvent],
+    time_min: DateTime<Utc>,
+    time_max: DateTime<Utc>,
+    duration: chrono::Duration,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut busy: Vec<(DateTime<Utc>, DateTime<Utc>)> = events.iter().map(|event| (event.start, event.end)).collect();
+    busy.sort();
+
+    let mut slots = Vec::new();
+    let mut cursor = time_min;
+    for (start, end) in busy {
+        if start > cursor && start - cursor >= duration {
+            slots.push((cursor, start.min(time_max)));
+        }
+        cursor = cursor.max(end);
+        if cursor >= time_max {
+            break;
+        }
+    }
+    if time_max > cursor && time_max - cursor >= duration {
+        slots.push((cursor, time_max));
+    }
+    slots.retain(|(start, end)| *end - *start >= duration);
+    slots
+}
+
+fn format_ics_datetime(datetime: &DateTime<Utc>) -> String {
+    datetime.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Parses DTSTART/DTEND values. Times with a TZID or without a zone are taken as UTC.
+fn parse_ics_datetime(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S") {
+        return Some(datetime.and_utc());
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|datetime| datetime.and_utc())
+}
+
+/// Contents of the calendar-data elements of a CalDAV multistatus response
+fn extract_calendar_data(xml: &str) -> Vec<String> {
+    let re = Regex::new(r"(?s)<(?:[\w-]+:)?calendar-data[^>]*>(.*?)</(?:[\w-]+:)?calendar-data>").unwrap();
+    re.captures_iter(xml)
+        .map(|captures| {
+            captures[1]
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&#13;", "")
+                .replace("&amp;", "&")
+        })
+        .collect()
+}
+
+/// Parses the VEVENTs of an iCalendar document
+pub fn parse_ics_events(ics: &str) -> Vec<CalendarEvent> {
+    // Unfold continuation lines first
+    let unfolded = ics.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+
+    let mut events = Vec::new();
+    let mut current: Option<IcsEvent> = None;
+    for line in unfolded.lines() {
+        match line.trim_end() {
+            "BEGIN:VEVENT" => current = Some(IcsEvent::default()),
+            "END:VEVENT" => {
+                if let Some(event) = current.take().and_then(IcsEvent::into_event) {
+                    events.push(event);
+                }
+            }
+            line => {
+                let Some(event) = current.as_mut() else {
+                    continue;
+                };
+                let Some((name, value)) = line.split_once(':') else {
+                    continue;
+                };
+                let name = name.split(';').next().unwrap_or_default();
+                let unescaped = value.replace("\\n", "\n").replace("\\,", ",").replace("\\;", ";");
+                match name {
+                    "UID" => event.uid = Some(value.to_string()),
+                    "SUMMARY" => event.summary = Some(unescaped),
+                    "DTSTART" => event.start = parse_ics_datetime(value),
+                    "DTEND" => event.end = parse_ics_datetime(value),
+                    "LOCATION" => event.location = Some(unescaped),
+                    "DESCRIPTION" => event.description = Some(unescaped),
+                    "ATTENDEE" => event.attendees.push(value.trim_start_matches("mailto:").to_string()),
+                    _ => {}
+                }
+            }
+        }
+    }
+    events
+}
+
+/// VEVENT properties read so far
+#[derive(Default)]
+struct IcsEvent {
+    uid: Option<String>,
+    summary: Option<String>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    location: Option<String>,
+    description: Option<String>,
+    attendees: Vec<String>,
+}
+
+impl IcsEvent {
+    fn into_event(self) -> Option<CalendarEvent> {
+        let start = self.start?;
+        Some(CalendarEvent {
+            id: self.uid?,
+            summary: self.summary.unwrap_or_default(),
+            start,
+            // Events without an end don't take any time
+            end: self.end.unwrap_or(start),
+            location: self.location,
+            description: self.description,
+            attendees: self.attendees,
+        })
+    }
+}
+
+fn to_ics(event: &CalendarEvent) -> String {
+    let escape = |text: &str| {
+        text.replace('\\', "\\\\")
+            .replace(',', "\\,")
+            .replace(';', "\\;")
+            .replace('\n', "\\n")
+    };
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Shinkai//Shinkai Node//EN".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", event.id),
+        format!("DTSTAMP:{}", format_ics_datetime(&Utc::now())),
+        format!("DTSTART:{}", format_ics_datetime(&event.start)),
+        format!("DTEND:{}", format_ics_datetime(&event.end)),
+        format!("SUMMARY:{}", escape(&event.summary)),
+    ];
+    if let Some(location) = &event.location {
+        lines.push(format!("LOCATION:{}", escape(location)));
+    }
+    if let Some(description) = &event.description {
+        lines.push(format!("DESCRIPTION:{}", escape(description)));
+    }
+    for attendee in &event.attendees {
+        lines.push(format!("ATTENDEE;RSVP=TRUE:mailto:{}", attendee));
+    }
+    lines.push("END:VEVENT".to_string());
+    lines.push("END:VCALENDAR".to_string());
+    lines.join("\r\n") + "\r\n"
+}
+
+fn parse_google_event(item: &Value) -> Option<CalendarEvent> {
+    let parse_time = |time: &Value| {
+        time.get("dateTime")
+            .and_then(|v| v.as_str())
+            .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+            .map(|v| v.with_timezone(&Utc))
+            .or_else(|| {
+                time.get("date")
+                    .and_then(|v| v.as_str())
+                    .and_then(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").ok())
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    .map(|datetime| datetime.and_utc())
+            })
+    };
+    let text = |key: &str| item.get(key).and_then(|v| v.as_str()).map(|v| v.to_string());
+
+    Some(CalendarEvent {
+        id: text("id")?,
+        summary: text("summary").unwrap_or_default(),
+        start: parse_time(item.get("start")?)?,
+        end: parse_time(item.get("end")?)?,
+        location: text("location"),
+        description: text("description"),
+        attendees: item
+            .get("attendees")
+            .and_then(|v| v.as_array())
+            .map(|attendees| {
+                attendees
+                    .iter()
+                    .filter_map(|attendee| attendee.get("email").and_then(|v| v.as_str()))
+                    .map(|email| email.to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+fn parse_google_events(body: &Value) -> Vec<CalendarEvent> {
+    body.get("items")
+        .and_then(|items| items.as_array())
+        .map(|items| {
+            items
+                .iter()
+                // Cancelled occurrences of recurring events are still listed
+                .filter(|item| item.get("status").and_then(|v| v.as_str()) != Some("cancelled"))
+                .filter_map(parse_google_event)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn datetime_arg(args: &serde_json::Map<String, Value>, name: &str) -> Result<DateTime<Utc>, ToolError> {
+    let value = args.get(name).and_then(|v| v.as_str()).unwrap_or_default();
+    DateTime::parse_from_rfc3339(value)
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .map_err(|_| ToolError::InvalidFunctionArguments(format!("{} must be an RFC 3339 date time", name)))
+}
+
+fn time_range_args() -> Vec<ToolArgument> {
+    vec![
+        ToolArgument::new(
+            "time_min".to_string(),
+            "string".to_string(),
+            "Start of the range, as an RFC 3339 date time, e.g. 2024-10-14T09:00:00Z".to_string(),
+            true,
+        ),
+        ToolArgument::new(
+            "time_max".to_string(),
+            "string".to_string(),
+            "End of the range, as an RFC 3339 date time".to_string(),
+            true,
+        ),
+    ]
+}
+
+fn profile_required_error(name: &str) -> ToolError {
+    ToolError::ExecutionError(format!("{} needs to know which profile's calendar to use", name))
+}
+
+/// Built-in tool that lists the events of the calling profile's calendar
+pub struct ListCalendarEventsTool;
+
+impl ListCalendarEventsTool {
+    pub const NAME: &'static str = "calendar_list_events";
+}
+
+#[async_trait]
+impl NativeTool for ListCalendarEventsTool {
+    fn definition(&self) -> RustTool {
+        RustTool::new(
+            Self::NAME.to_string(),
+            "Lists the events of the user's calendar between two date times.".to_string(),
+            time_range_args(),
+            None,
+        )
+    }
+
+    async fn run(&self, _args: serde_json::Map<String, Value>) -> Result<Value, ToolError> {
+        Err(profile_required_error(Self::NAME))
+    }
+
+    async fn run_with_context(
+        &self,
+        args: serde_json::Map<String, Value>,
+        context: NativeToolContext,
+    ) -> Result<Value, ToolError> {
+        let time_min = datetime_arg(&args, "time_min")?;
+        let time_max = datetime_arg(&args, "time_max")?;
+        let events = CalendarClient::from_context(&context)?
+            .list_events(time_min, time_max)
+            .await?;
+        Ok(json!({ "events": events }))
+    }
+}
+
+/// Built-in tool that finds free slots in the calling profile's calendar
+pub struct FindCalendarSlotTool;
+
+impl FindCalendarSlotTool {
+    pub const NAME: &'static str = "calendar_find_free_slots";
+}
+
+#[async_trait]
+impl NativeTool for FindCalendarSlotTool {
+    fn definition(&self) -> RustTool {
+        let mut args = time_range_args();
+        args.push(ToolArgument::new(
+            "duration_minutes".to_string(),
+            "integer".to_string(),
+            "How long the slot must be".to_string(),
+            true,
+        ));
+        RustTool::new(
+            Self::NAME.to_string(),
+            "Finds the free slots of at least the given duration in the user's calendar between two date times."
+                .to_string(),
+            args,
+            None,
+        )
+    }
+
+    async fn run(&self, _args: serde_json::Map<String, Value>) -> Result<Value, ToolError> {
+        Err(profile_required_error(Self::NAME))
+    }
+
+    async fn run_with_context(
+        &self,
+        args: serde_json::Map<String, Value>,
+        context: NativeToolContext,
+    ) -> Result<Value, ToolError> {
+        let time_min = datetime_arg(&args, "time_min")?;
+        let time_max = datetime_arg(&args, "time_max")?;
+        let duration_minutes = args
+            .get("duration_minutes")
+            .and_then(|v| v.as_i64())
+            .filter(|minutes| *minutes > 0)
+            .ok_or_else(|| ToolError::InvalidFunctionArguments("duration_minutes must be positive".to_string()))?;
+
+        let events = CalendarClient::from_context(&context)?
+            .list_events(time_min, time_max)
+            .await?;
+        let slots: Vec<Value> =
+            find_free_slots(&events, time_min, time_max, chrono::Duration::minutes(duration_minutes))
+                .into_iter()
+                .map(|(start, end)| json!({ "start": start.to_rfc3339(), "end": end.to_rfc3339() }))
+                .collect();
+        Ok(json!({ "slots": slots }))
+    }
+}
+
+/// Built-in tool that creates an event in the calling profile's calendar
+pub struct CreateCalendarEventTool;
+
+impl CreateCalendarEventTool {
+    pub const NAME: &'static str = "calendar_create_event";
+}
+
+#[async_trait]
+impl NativeTool for CreateCalendarEventTool {
+    fn definition(&self) -> RustTool {
+        RustTool::new(
+            Self::NAME.to_string(),
+            "Creates an event in the user's calendar and invites the attendees.".to_string(),
+            vec![
+                ToolArgument::new(
+                    "summary".to_string(),
+                    "string".to_string(),
+                    "Title of the event".to_string(),
+                    true,
+                ),
+                ToolArgument::new(
+                    "start".to_string(),
+                    "string".to_string(),
+                    "Start of the event, as an RFC 3339 date time".to_string(),
+                    true,
+                ),
+                ToolArgument::new(
+                    "end".to_string(),
+                    "string".to_string(),
+                    "End of the event, as an RFC 3339 date time".to_string(),
+                    true,
+                ),
+                ToolArgument::new(
+                    "attendees".to_string(),
+                    "string".to_string(),
+                    "Comma separated emails of the people to invite".to_string(),
+                    false,
+                ),
+                ToolArgument::new(
+                    "description".to_string(),
+                    "string".to_string(),
+                    "Details of the event".to_string(),
+                    false,
+                ),
+                ToolArgument::new(
+                    "location".to_string(),
+                    "string".to_string(),
+                    "Where it takes place".to_string(),
+                    false,
+                ),
+            ],
+            None,
+        )
+    }
+
+    async fn run(&self, _args: serde_json::Map<String, Value>) -> Result<Value, ToolError> {
+        Err(profile_required_error(Self::NAME))
+    }
+
+    async fn run_with_context(
+        &self,
+        args: serde_json::Map<String, Value>,
+        context: NativeToolContext,
+    ) -> Result<Value, ToolError> {
+        let start = datetime_arg(&args, "start")?;
+        let end = datetime_arg(&args, "end")?;
+        if end <= start {
+            return Err(ToolError::InvalidFunctionArguments(
+                "The event must end after it starts".to_string(),
+            ));
+        }
+        let text = |key: &str| {
+            args.get(key)
+                .and_then(|v| v.as_str())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+
+        let event = CalendarEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            summary: text("summary").unwrap_or_default(),
+            start,
+            end,
+            location: text("location"),
+            description: text("description"),
+            attendees: text("attendees")
+                .map(|attendees| {
+                    attendees
+                        .split(',')
+                        .map(|email| email.trim().to_string())
+                        .filter(|email| !email.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+        let event = CalendarClient::from_context(&context)?.create_event(&event).await?;
+        Ok(json!({ "event": event }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        NaiveDate::from_ymd_opt(2024, 10, 14)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    #[test]
+    fn test_parse_ics_and_find_free_slots() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:standup\r\nSUMMARY:Standup\\, daily\r\nDTSTART:20241014T093000Z\r\nDTEND:20241014T100000Z\r\nATTENDEE;CN=Bob:mailto:bob@example.com\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nUID:lunch\r\nSUMMARY:Lunch with a very long\r\n  title\r\nDTSTART;TZID=UTC:20241014T120000\r\nDTEND;TZID=UTC:20241014T130000\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let events = parse_ics_events(ics);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].summary, "Standup, daily");
+        assert_eq!(events[0].attendees, vec!["bob@example.com".to_string()]);
+        assert_eq!(events[1].summary, "Lunch with a very long title");
+        assert_eq!(events[1].start, at(12, 0));
+
+        let slots = find_free_slots(&events, at(9, 0), at(14, 0), chrono::Duration::minutes(60));
+        assert_eq!(slots, vec![(at(10, 0), at(12, 0)), (at(13, 0), at(14, 0))]);
+
+        let slots = find_free_slots(&events, at(9, 0), at(14, 0), chrono::Duration::minutes(30));
+        assert_eq!(slots[0], (at(9, 0), at(9, 30)));
+    }
+}
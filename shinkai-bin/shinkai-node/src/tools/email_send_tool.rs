@@ -0,0 +1,142 @@
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde_json::{json, Value};
+
+use crate::schemas::email_account::EmailAccountConfig;
+use crate::tools::argument::ToolArgument;
+use crate::tools::error::ToolError;
+use crate::tools::native_tool::{NativeTool, NativeToolContext};
+use crate::tools::rust_tools::RustTool;
+
+/// Built-in tool that sends an email from the email account of the calling profile.
+/// Recipients must be in the account's sending allowlist.
+pub struct SendEmailTool;
+
+impl SendEmailTool {
+    pub const NAME: &'static str = "send_email";
+
+    fn recipients(args: &serde_json::Map<String, Value>) -> Vec<String> {
+        match args.get("to") {
+            Some(Value::String(to)) => to
+                .split(',')
+                .map(|address| address.trim().to_string())
+                .filter(|address| !address.is_empty())
+                .collect(),
+            Some(Value::Array(to)) => to
+                .iter()
+                .filter_map(|address| address.as_str())
+                .map(|address| address.trim().to_string())
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    async fn send(
+        account: &EmailAccountConfig,
+        recipients: &[String],
+        subject: &str,
+        body: &str,
+    ) -> Result<(), ToolError> {
+        let parse_mailbox = |address: &str| {
+            address
+                .parse::<Mailbox>()
+                .map_err(|e| ToolError::InvalidFunctionArguments(format!("Invalid address {}: {}", address, e)))
+        };
+
+        let mut builder = Message::builder()
+            .from(parse_mailbox(&account.from_address)?)
+            .subject(subject);
+        for recipient in recipients {
+            builder = builder.to(parse_mailbox(recipient)?);
+        }
+        let message = builder
+            .body(body.to_string())
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+        let credentials = Credentials::new(
+            account.username.clone(),
+            account.password().map_err(ToolError::ExecutionError)?,
+        );
+        // Port 465 uses implicit TLS, anything else upgrades with STARTTLS
+        let transport_builder = if account.smtp_port == 465 {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(&account.smtp_host)
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&account.smtp_host)
+        }
+        .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        let transport = transport_builder
+            .port(account.smtp_port)
+            .credentials(credentials)
+            .build();
+
+        transport
+            .send(message)
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to send email: {}", e)))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl NativeTool for SendEmailTool {
+    fn definition(&self) -> RustTool {
+        RustTool::new(
+            Self::NAME.to_string(),
+            "Sends a plain text email from the user's email account. Only addresses allowed in the account settings can receive it.".to_string(),
+            vec![
+                ToolArgument::new(
+                    "to".to_string(),
+                    "string".to_string(),
+                    "Comma separated addresses to send the email to".to_string(),
+                    true,
+                ),
+                ToolArgument::new("subject".to_string(), "string".to_string(), "Subject of the email".to_string(), true),
+                ToolArgument::new("body".to_string(), "string".to_string(), "Text of the email".to_string(), true),
+            ],
+            None,
+        )
+    }
+
+    async fn run(&self, _args: serde_json::Map<String, Value>) -> Result<Value, ToolError> {
+        Err(ToolError::ExecutionError(format!(
+            "{} needs to know which profile is sending the email",
+            Self::NAME
+        )))
+    }
+
+    async fn run_with_context(
+        &self,
+        args: serde_json::Map<String, Value>,
+        context: NativeToolContext,
+    ) -> Result<Value, ToolError> {
+        let (Some(profile), Some(db)) = (context.profile, context.db) else {
+            return self.run(args).await;
+        };
+        let account = db.get_email_account(&profile.full_name).map_err(|_| {
+            ToolError::ExecutionError(format!("{} doesn't have an email account set up", profile.full_name))
+        })?;
+
+        let recipients = Self::recipients(&args);
+        if recipients.is_empty() {
+            return Err(ToolError::InvalidFunctionArguments("No recipients given".to_string()));
+        }
+        if let Some(denied) = recipients.iter().find(|address| !account.allows_recipient(address)) {
+            return Err(ToolError::ExecutionError(format!(
+                "{} is not in the sending allowlist of {}",
+                denied, profile.full_name
+            )));
+        }
+
+        let subject = args.get("subject").and_then(|v| v.as_str()).unwrap_or_default();
+        let body = args.get("body").and_then(|v| v.as_str()).unwrap_or_default();
+        Self::send(&account, &recipients, subject, body).await?;
+
+        Ok(json!({
+            "sent": true,
+            "from": account.from_address,
+            "to": recipients,
+        }))
+    }
+}
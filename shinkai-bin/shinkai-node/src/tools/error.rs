@@ -32,6 +32,11 @@ pub enum ToolError {
     MissingEmbedding,
     EmbeddingGenerationError(String),
     MissingConfigError(String),
+    InvalidFunctionArguments(String),
+    CapabilityNotGranted(String),
+    ExecutionTimeout(String),
+    OutputTooLarge(String),
+    AllowanceExhausted(String),
 }
 
 impl fmt::Display for ToolError {
@@ -66,6 +71,11 @@ impl fmt::Display for ToolError {
             ToolError::MissingEmbedding => write!(f, "Missing embedding."),
             ToolError::EmbeddingGenerationError(ref e) => write!(f, "Embedding generation error: {}", e),
             ToolError::MissingConfigError(ref e) => write!(f, "Missing config error: {}", e),
+            ToolError::InvalidFunctionArguments(ref e) => write!(f, "Invalid function arguments: {}", e),
+            ToolError::CapabilityNotGranted(ref e) => write!(f, "Capability not granted: {}", e),
+            ToolError::ExecutionTimeout(ref e) => write!(f, "Tool timed out: {}", e),
+            ToolError::OutputTooLarge(ref e) => write!(f, "Tool output too large: {}", e),
+            ToolError::AllowanceExhausted(ref e) => write!(f, "Tool usage allowance exhausted: {}", e),
         }
     }
 }
@@ -0,0 +1,221 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use deno_core::{serde_v8, v8, JsRuntime, PollEventLoopOptions, RuntimeOptions};
+use serde_json::Value as JsonValue;
+
+use crate::tools::error::ToolError;
+use crate::tools::js_toolkit_executor::JSRuntimeLimits;
+
+/// Runs JS toolkits in-process with deno_core instead of the tools runner.
+///
+/// Tools follow the same convention as the tools runner: the code defines `globalThis.tool.Tool`,
+/// a class built with the configurations whose `run(parameters)` resolves to `{ data }`.
+pub struct DenoToolRuntime;
+
+impl DenoToolRuntime {
+    /// Runs the tool in a dedicated thread, as a JsRuntime can't move between threads.
+    pub fn run(
+        code: String,
+        configurations: JsonValue,
+        parameters: JsonValue,
+        limits: JSRuntimeLimits,
+    ) -> Result<JsonValue, ToolError> {
+        thread::Builder::new()
+            .name("deno-tool".to_string())
+            .stack_size(8 * 1024 * 1024) // 8 MB
+            .spawn(move || {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+                rt.block_on(Self::run_in_runtime(code, configurations, parameters, limits))
+            })
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?
+            .join()
+            .map_err(|_| ToolError::ExecutionError("Deno runtime panicked".to_string()))?
+    }
+
+    async fn run_in_runtime(
+        code: String,
+        configurations: JsonValue,
+        parameters: JsonValue,
+        limits: JSRuntimeLimits,
+    ) -> Result<JsonValue, ToolError> {
+        let create_params = v8::CreateParams::default().heap_limits(0, limits.max_heap_bytes);
+        let mut runtime = JsRuntime::new(RuntimeOptions {
+            create_params: Some(create_params),
+            ..Default::default()
+        });
+
+        // Terminate instead of crashing the whole node when the tool runs out of memory
+        let isolate_handle = runtime.v8_isolate().thread_safe_handle();
+        let out_of_memory = Arc::new(AtomicBool::new(false));
+        {
+            let out_of_memory = out_of_memory.clone();
+            let isolate_handle = isolate_handle.clone();
+            runtime.add_near_heap_limit_callback(move |current_limit, _initial_limit| {
+                out_of_memory.store(true, Ordering::SeqCst);
+                isolate_handle.terminate_execution();
+                // Give V8 some room to unwind the execution
+                current_limit * 2
+            });
+        }
+
+        // Watchdog that stops the isolate once the timeout is reached
+        let finished = Arc::new(AtomicBool::new(false));
+        let timed_out = Arc::new(AtomicBool::new(false));
+        {
+            let finished = finished.clone();
+            let timed_out = timed_out.clone();
+            let timeout = limits.timeout;
+            thread::spawn(move || {
+                let step = Duration::from_millis(50);
+                let mut elapsed = Duration::ZERO;
+                while elapsed < timeout {
+                    if finished.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    thread::sleep(step);
+                    elapsed += step;
+                }
+                if !finished.load(Ordering::SeqCst) {
+                    timed_out.store(true, Ordering::SeqCst);
+                    isolate_handle.terminate_execution();
+                }
+            });
+        }
+
+        let result = Self::execute(&mut runtime, code, configurations, parameters).await;
+        finished.store(true, Ordering::SeqCst);
+
+        if timed_out.load(Ordering::SeqCst) {
+            return Err(ToolError::ExecutionTimeout(format!(
+                "JS tool didn't finish within {} seconds",
+                limits.timeout.as_secs()
+            )));
+        }
+        if out_of_memory.load(Ordering::SeqCst) {
+            return Err(ToolError::ExecutionError(format!(
+                "JS tool exceeded the memory limit of {} bytes",
+                limits.max_heap_bytes
+            )));
+        }
+        result
+    }
+
+    async fn execute(
+        runtime: &mut JsRuntime,
+        code: String,
+        configurations: JsonValue,
+        parameters: JsonValue,
+    ) -> Result<JsonValue, ToolError> {
+        runtime
+            .execute_script("<tool>", code)
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+        let run_script = format!(
+            "(async () => {{ const tool = new globalThis.tool.Tool({}); return await tool.run({}); }})()",
+            configurations, parameters
+        );
+        let promise = runtime
+            .execute_script("<run>", run_script)
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        let resolved = runtime.resolve(promise);
+        let value = runtime
+            .with_event_loop_promise(resolved, PollEventLoopOptions::default())
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+        let scope = &mut runtime.handle_scope();
+        let local = v8::Local::new(scope, value);
+        let result: JsonValue =
+            serde_v8::from_v8(scope, local).map_err(|e| ToolError::SerializationError(e.to_string()))?;
+
+        Ok(result.get("data").cloned().unwrap_or(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const GREETING_TOOL: &str = r#"
+        globalThis.tool = {
+            Tool: class {
+                constructor(config) {
+                    this.config = config;
+                }
+                async run(params) {
+                    return { data: { greeting: `${this.config.prefix} ${params.name}` } };
+                }
+            }
+        };
+    "#;
+
+    fn limits(timeout: Duration) -> JSRuntimeLimits {
+        JSRuntimeLimits {
+            max_heap_bytes: 64 * 1024 * 1024,
+            timeout,
+        }
+    }
+
+    #[test]
+    fn test_run_tool() {
+        let result = DenoToolRuntime::run(
+            GREETING_TOOL.to_string(),
+            json!({"prefix": "Hello"}),
+            json!({"name": "Alice"}),
+            limits(Duration::from_secs(10)),
+        )
+        .unwrap();
+        assert_eq!(result, json!({"greeting": "Hello Alice"}));
+    }
+
+    #[test]
+    fn test_run_tool_that_throws() {
+        let code = r#"
+            globalThis.tool = {
+                Tool: class {
+                    async run(params) {
+                        throw new Error("tool failed");
+                    }
+                }
+            };
+        "#;
+        let result = DenoToolRuntime::run(code.to_string(), json!({}), json!({}), limits(Duration::from_secs(10)));
+        assert!(matches!(result, Err(ToolError::ExecutionError(e)) if e.contains("tool failed")));
+
+        // Code that doesn't define a tool fails the same way
+        let result = DenoToolRuntime::run(
+            "const x = 1;".to_string(),
+            json!({}),
+            json!({}),
+            limits(Duration::from_secs(10)),
+        );
+        assert!(matches!(result, Err(ToolError::ExecutionError(_))));
+    }
+
+    #[test]
+    fn test_run_tool_past_timeout() {
+        let code = r#"
+            globalThis.tool = {
+                Tool: class {
+                    async run(params) {
+                        while (true) {}
+                    }
+                }
+            };
+        "#;
+        let result = DenoToolRuntime::run(
+            code.to_string(),
+            json!({}),
+            json!({}),
+            limits(Duration::from_millis(200)),
+        );
+        assert!(matches!(result, Err(ToolError::ExecutionTimeout(_))));
+    }
+}
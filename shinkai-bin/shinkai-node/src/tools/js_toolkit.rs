@@ -59,6 +59,7 @@ impl JSToolkit {
             activated: false,
             embedding: None,
             result,
+            capabilities: vec![],
         }
     }
 
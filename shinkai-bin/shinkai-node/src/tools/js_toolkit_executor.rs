@@ -1,3 +1,6 @@
+use std::env;
+use std::time::Duration;
+
 use crate::tools::error::ToolError;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -10,6 +13,68 @@ pub struct ToolExecutionResult {
     pub result: JsonValue,
 }
 
+/// Which runtime JS toolkits are executed with. Set with JS_TOOLS_RUNTIME (`runner` or `deno`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JSRuntimeKind {
+    /// The tools runner from shinkai_tools_runner
+    ToolsRunner,
+    /// In-process deno_core runtime (requires the `deno-runtime` feature)
+    Deno,
+}
+
+impl JSRuntimeKind {
+    pub fn from_env() -> Self {
+        match env::var("JS_TOOLS_RUNTIME").unwrap_or_default().to_lowercase().as_str() {
+            "deno" => JSRuntimeKind::Deno,
+            _ => JSRuntimeKind::ToolsRunner,
+        }
+    }
+}
+
+/// Resource limits applied to JS tools running in the embedded runtime
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JSRuntimeLimits {
+    pub max_heap_bytes: usize,
+    pub timeout: Duration,
+}
+
+impl JSRuntimeLimits {
+    /// Reads JS_TOOLS_MAX_HEAP_MB (default 256) and JS_TOOLS_TIMEOUT_SECS (default 60)
+    pub fn from_env() -> Self {
+        let read_env = |key: &str, default: u64| -> u64 {
+            env::var(key)
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(default)
+        };
+
+        JSRuntimeLimits {
+            max_heap_bytes: read_env("JS_TOOLS_MAX_HEAP_MB", 256) as usize * 1024 * 1024,
+            timeout: Duration::from_secs(read_env("JS_TOOLS_TIMEOUT_SECS", 60)),
+        }
+    }
+}
+
+/// Runs the tool code with the embedded Deno runtime and returns the tool's data
+pub fn run_with_deno(
+    code: String,
+    configurations: JsonValue,
+    parameters: JsonValue,
+    limits: JSRuntimeLimits,
+) -> Result<JsonValue, ToolError> {
+    #[cfg(feature = "deno-runtime")]
+    {
+        super::js_deno_runtime::DenoToolRuntime::run(code, configurations, parameters, limits)
+    }
+    #[cfg(not(feature = "deno-runtime"))]
+    {
+        let _ = (code, configurations, parameters, limits);
+        Err(ToolError::ToolNotRunnable(
+            "JS_TOOLS_RUNTIME is set to deno but the node was built without the deno-runtime feature".to_string(),
+        ))
+    }
+}
+
 pub struct JSToolkitExecutor;
 
 impl JSToolkitExecutor {
@@ -25,6 +90,17 @@ impl JSToolkitExecutor {
         tool_arguments: JsonValue,
         fn_args: JsonValue,
     ) -> Result<ToolExecutionResult, ToolError> {
+        if JSRuntimeKind::from_env() == JSRuntimeKind::Deno {
+            let limits = JSRuntimeLimits::from_env();
+            let result = tokio::task::spawn_blocking(move || run_with_deno(code, tool_arguments, fn_args, limits))
+                .await
+                .map_err(|e| ToolError::ExecutionError(e.to_string()))??;
+            return Ok(ToolExecutionResult {
+                tool: tool_name,
+                result,
+            });
+        }
+
         let mut tool = Tool::new();
         tool.load_from_code(&code, &tool_arguments.to_string()).await.map_err(ToolError::from)?;
         let result = tool.run(&fn_args.to_string(), None).await.map_err(ToolError::from)?;
@@ -34,3 +110,44 @@ impl JSToolkitExecutor {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::tool_execution_limits::ToolExecutionLimits;
+    use serde_json::json;
+
+    #[cfg(not(feature = "deno-runtime"))]
+    #[test]
+    fn test_run_with_deno_without_feature() {
+        let result = run_with_deno(
+            "globalThis.tool = {};".to_string(),
+            json!({}),
+            json!({}),
+            ToolExecutionLimits::default().js_runtime_limits(),
+        );
+        assert!(matches!(result, Err(ToolError::ToolNotRunnable(_))));
+    }
+
+    #[cfg(feature = "deno-runtime")]
+    #[test]
+    fn test_run_with_deno() {
+        let code = r#"
+            globalThis.tool = {
+                Tool: class {
+                    async run(params) {
+                        return { data: params.a + params.b };
+                    }
+                }
+            };
+        "#;
+        let result = run_with_deno(
+            code.to_string(),
+            json!({}),
+            json!({"a": 1, "b": 2}),
+            ToolExecutionLimits::default().js_runtime_limits(),
+        )
+        .unwrap();
+        assert_eq!(result, json!(3));
+    }
+}
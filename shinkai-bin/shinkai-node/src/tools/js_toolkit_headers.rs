@@ -71,4 +71,118 @@ pub struct BasicConfig {
     pub description: String,
     pub required: bool,
     pub key_value: Option<String>,
-}
\ No newline at end of file
+}
+/// Something a toolkit needs access to in order to run. Toolkits declare the capabilities they need
+/// and the user has to grant them before any of the toolkit's tools can be executed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum ToolCapability {
+    /// HTTP access to a host (`*` for any host)
+    Network(String),
+    /// Reading the files uploaded to the job's file inbox
+    FileInboxRead,
+    /// Reading a VectorFS path and everything below it
+    VectorFSRead(String),
+    /// Access to a secret / config value by key name
+    Secret(String),
+}
+
+impl ToolCapability {
+    /// Checks if this (granted) capability covers the requested one
+    pub fn covers(&self, requested: &ToolCapability) -> bool {
+        match (self, requested) {
+            (ToolCapability::Network(granted), ToolCapability::Network(host)) => {
+                granted == "*" || granted.eq_ignore_ascii_case(host)
+            }
+            (ToolCapability::FileInboxRead, ToolCapability::FileInboxRead) => true,
+            (ToolCapability::VectorFSRead(granted), ToolCapability::VectorFSRead(path)) => {
+                let granted = granted.trim_end_matches('/');
+                let path = path.trim_end_matches('/');
+                granted.is_empty() || path == granted || path.starts_with(&format!("{}/", granted))
+            }
+            (ToolCapability::Secret(granted), ToolCapability::Secret(key)) => granted == key,
+            _ => false,
+        }
+    }
+
+    /// Network capability for the host of the URL, if it has one
+    pub fn network_for_url(url: &str) -> Option<ToolCapability> {
+        reqwest::Url::parse(url)
+            .ok()?
+            .host_str()
+            .map(|host| ToolCapability::Network(host.to_string()))
+    }
+
+    /// Returns the requested capabilities that aren't covered by the granted ones
+    pub fn missing(requested: &[ToolCapability], granted: &[ToolCapability]) -> Vec<ToolCapability> {
+        requested
+            .iter()
+            .filter(|requested| !granted.iter().any(|granted| granted.covers(requested)))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Capabilities the user granted to a toolkit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolkitCapabilityGrants {
+    pub toolkit_name: String,
+    pub granted: Vec<ToolCapability>,
+}
+
+impl ToolkitCapabilityGrants {
+    pub fn grant(&mut self, capabilities: Vec<ToolCapability>) {
+        for capability in capabilities {
+            if !self.granted.contains(&capability) {
+                self.granted.push(capability);
+            }
+        }
+    }
+
+    /// Revokes the given capabilities, or all of them if none is given
+    pub fn revoke(&mut self, capabilities: &[ToolCapability]) {
+        if capabilities.is_empty() {
+            self.granted.clear();
+        } else {
+            self.granted.retain(|granted| !capabilities.contains(granted));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_capabilities() {
+        let requested = vec![
+            ToolCapability::Network("api.example.com".to_string()),
+            ToolCapability::VectorFSRead("/My Files/reports/2024".to_string()),
+            ToolCapability::Secret("API_KEY".to_string()),
+        ];
+        let granted = vec![
+            ToolCapability::Network("*".to_string()),
+            ToolCapability::VectorFSRead("/My Files/reports".to_string()),
+        ];
+
+        assert_eq!(
+            ToolCapability::missing(&requested, &granted),
+            vec![ToolCapability::Secret("API_KEY".to_string())]
+        );
+        assert!(ToolCapability::missing(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_grant_and_revoke() {
+        let mut grants = ToolkitCapabilityGrants::default();
+        grants.grant(vec![ToolCapability::FileInboxRead, ToolCapability::FileInboxRead]);
+        grants.grant(vec![ToolCapability::Secret("API_KEY".to_string())]);
+        assert_eq!(grants.granted.len(), 2);
+
+        grants.revoke(&[ToolCapability::FileInboxRead]);
+        assert_eq!(grants.granted, vec![ToolCapability::Secret("API_KEY".to_string())]);
+
+        grants.revoke(&[]);
+        assert!(grants.granted.is_empty());
+    }
+}
@@ -1,8 +1,10 @@
 use std::thread;
 
-use super::js_toolkit_headers::ToolConfig;
+use super::js_toolkit_executor::{run_with_deno, JSRuntimeKind};
+use super::js_toolkit_headers::{ToolCapability, ToolConfig};
 use crate::tools::argument::ToolArgument;
 use crate::tools::error::ToolError;
+use crate::tools::tool_execution_limits::ToolExecutionLimits;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value as JsonValue;
 use shinkai_tools_runner::tools::run_result::RunResult;
@@ -23,14 +25,18 @@ pub struct JSTool {
     pub activated: bool,
     pub embedding: Option<Embedding>,
     pub result: JSToolResult,
+    /// Capabilities the tool needs, which have to be granted to its toolkit before it can run
+    #[serde(default)]
+    pub capabilities: Vec<ToolCapability>,
 }
 
 impl JSTool {
-    pub fn run(&self, input_json: JsonValue, extra_config: Option<String>) -> Result<RunResult, ToolError> {
-        eprintln!("Running JSTool named: {}", self.name);
-        eprintln!("Running JSTool with input: {}", input_json);
-        eprintln!("Running JSTool with extra_config: {:?}", extra_config);
-
+    pub fn run(
+        &self,
+        input_json: JsonValue,
+        extra_config: Option<String>,
+        limits: &ToolExecutionLimits,
+    ) -> Result<RunResult, ToolError> {
         let code = self.js_code.clone();
         let input = serde_json::to_string(&input_json).map_err(|e| ToolError::SerializationError(e.to_string()))?;
 
@@ -58,24 +64,57 @@ impl JSTool {
         // Use extra_config directly without serializing again
         let config = extra_config.unwrap_or_else(|| "{}".to_string());
 
+        if JSRuntimeKind::from_env() == JSRuntimeKind::Deno {
+            let config: JsonValue =
+                serde_json::from_str(&config).map_err(|e| ToolError::SerializationError(e.to_string()))?;
+            let data = run_with_deno(code, config, input_json, limits.js_runtime_limits())?;
+            limits.check_output(&self.name, &data)?;
+            return Ok(RunResult { data });
+        }
+
+        // The tools runner has no memory limit of its own, so refuse to run rather than ignore the cap
+        limits.check_memory_cap_unsupported(&self.name, "tools runner")?;
+
         // Create a new thread with its own Tokio runtime
+        let timeout = limits.timeout();
+        let timeout_error = limits.timeout_error(&self.name);
         let js_tool_thread = thread::Builder::new().stack_size(8 * 1024 * 1024); // 8 MB
-        js_tool_thread
+        let result = js_tool_thread
             .spawn(move || {
                 let rt = Runtime::new().expect("Failed to create Tokio runtime");
                 rt.block_on(async {
-                    let mut tool = Tool::new();
-                    tool.load_from_code(&code, &config)
-                        .await
-                        .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
-                    tool.run(&input, None)
-                        .await
-                        .map_err(|e| ToolError::ExecutionError(e.to_string()))
+                    // Dropping the future on timeout stops the tool instead of leaving the workflow hanging
+                    let run = async {
+                        let mut tool = Tool::new();
+                        tool.load_from_code(&code, &config)
+                            .await
+                            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+                        tool.run(&input, None)
+                            .await
+                            .map_err(|e| ToolError::ExecutionError(e.to_string()))
+                    };
+                    tokio::time::timeout(timeout, run).await.map_err(|_| timeout_error)?
                 })
             })
             .unwrap()
             .join()
-            .expect("Thread panicked")
+            .expect("Thread panicked")?;
+
+        limits.check_output(&self.name, &result.data)?;
+        Ok(result)
+    }
+
+    /// Capabilities the tool declares plus a secret for every config entry, as the whole config is
+    /// handed to the tool when it runs
+    pub fn requested_capabilities(&self) -> Vec<ToolCapability> {
+        let mut capabilities = self.capabilities.clone();
+        for config in &self.config {
+            let secret = ToolCapability::Secret(config.name());
+            if !capabilities.contains(&secret) {
+                capabilities.push(secret);
+            }
+        }
+        capabilities
     }
 
     /// Check if all required config fields are set
@@ -174,6 +213,7 @@ mod tests {
             activated: false,
             embedding: None,
             result: JSToolResult::new("object".to_string(), json!({}), vec![]),
+            capabilities: vec![],
         };
         assert!(tool_without_config.check_required_config_fields());
 
@@ -201,4 +241,47 @@ mod tests {
         };
         assert!(tool_with_config.check_required_config_fields());
     }
+
+    #[test]
+    fn test_config_entries_are_requested_as_secrets() {
+        let tool = JSTool {
+            toolkit_name: "test_toolkit".to_string(),
+            name: "test_tool".to_string(),
+            author: "author".to_string(),
+            js_code: "".to_string(),
+            config: vec![ToolConfig::BasicConfig(BasicConfig {
+                key_name: "apiKey".to_string(),
+                description: "API Key".to_string(),
+                required: false,
+                key_value: None,
+            })],
+            description: "A test tool".to_string(),
+            keywords: vec![],
+            input_args: vec![],
+            activated: false,
+            embedding: None,
+            result: JSToolResult::new("object".to_string(), json!({}), vec![]),
+            capabilities: vec![
+                ToolCapability::Network("api.example.com".to_string()),
+                ToolCapability::Secret("apiKey".to_string()),
+            ],
+        };
+
+        assert_eq!(
+            tool.requested_capabilities(),
+            vec![
+                ToolCapability::Network("api.example.com".to_string()),
+                ToolCapability::Secret("apiKey".to_string()),
+            ]
+        );
+
+        let undeclared = JSTool {
+            capabilities: vec![],
+            ..tool
+        };
+        assert_eq!(
+            undeclared.requested_capabilities(),
+            vec![ToolCapability::Secret("apiKey".to_string())]
+        );
+    }
 }
\ No newline at end of file
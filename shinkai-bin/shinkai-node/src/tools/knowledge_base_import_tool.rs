@@ -0,0 +1,721 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_recursion::async_recursion;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_vector_resources::embedding_generator::RemoteEmbeddingGenerator;
+use shinkai_vector_resources::file_parser::file_parser::FileParser;
+use shinkai_vector_resources::source::{DistributionInfo, SourceFileType};
+use shinkai_vector_resources::vector_resource::VRPath;
+
+use crate::db::ShinkaiDB;
+use crate::llm_provider::parsing_helper::ParsingHelper;
+use crate::tools::argument::ToolArgument;
+use crate::tools::error::ToolError;
+use crate::tools::js_toolkit_headers::ToolCapability;
+use crate::tools::native_tool::{NativeTool, NativeToolContext};
+use crate::tools::rust_tools::RustTool;
+use crate::vector_fs::vector_fs::VectorFS;
+
+const IMPORT_TIMEOUT_SECS: u64 = 60;
+const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+/// Nested Notion blocks deeper than this are left out
+const MAX_NOTION_DEPTH: usize = 8;
+
+/// A page of the knowledge base, converted to markdown
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnowledgeBasePage {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    /// Changes whenever the page is edited. Pages whose value didn't change since the last import are skipped.
+    pub last_edited: String,
+    /// Notion database properties, or the Confluence version
+    pub properties: Vec<(String, String)>,
+    /// Fetched separately for Notion pages, and only if they changed
+    pub markdown: Option<String>,
+}
+
+impl KnowledgeBasePage {
+    /// The markdown of the page with its metadata as front matter. Values are JSON strings, which YAML reads too.
+    pub fn to_markdown_document(&self, source: &str, markdown: &str) -> String {
+        let mut front_matter = vec![
+            ("title".to_string(), self.title.clone()),
+            ("source".to_string(), source.to_string()),
+            ("source_id".to_string(), self.id.clone()),
+            ("url".to_string(), self.url.clone()),
+            ("last_edited".to_string(), self.last_edited.clone()),
+        ];
+        front_matter.extend(self.properties.iter().cloned());
+
+        let mut document = String::from("---\n");
+        for (key, value) in front_matter {
+            document.push_str(&format!("{}: {}\n", key, json!(value)));
+        }
+        document.push_str(&format!("---\n\n# {}\n\n{}", self.title, markdown.trim()));
+        document
+    }
+
+    /// Name of the markdown file, unique among the names already used by the import
+    pub fn file_name(&self, used_names: &HashSet<String>) -> String {
+        let title: String = self
+            .title
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == ' ' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(80)
+            .collect();
+        let title = match title.trim() {
+            "" => "Untitled",
+            title => title,
+        };
+        let file_name = format!("{}.md", title);
+        match used_names.contains(&file_name) {
+            true => format!("{} ({}).md", title, self.id.chars().take(8).collect::<String>()),
+            false => file_name,
+        }
+    }
+}
+
+/// What an import of a Notion page or database or of a Confluence space or page ingested, to only update what
+/// changed the next time
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KnowledgeBaseImportState {
+    /// File name and last edit of the imported pages, by their id at the source
+    pub pages: HashMap<String, (String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfluenceConfig {
+    /// Site URL including the `/wiki` context path of Confluence Cloud
+    pub base_url: String,
+    pub email: String,
+    pub api_token: String,
+}
+
+/// Built-in tool that imports pages from Notion or Confluence into a VectorFS folder. Configured with
+/// NOTION_API_TOKEN, and CONFLUENCE_BASE_URL, CONFLUENCE_EMAIL and CONFLUENCE_API_TOKEN.
+pub struct KnowledgeBaseImportTool {
+    notion_token: Option<String>,
+    confluence: Option<ConfluenceConfig>,
+    client: reqwest::Client,
+}
+
+impl KnowledgeBaseImportTool {
+    pub const NAME: &'static str = "knowledge_base_import";
+
+    pub fn new(notion_token: Option<String>, confluence: Option<ConfluenceConfig>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(IMPORT_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+        KnowledgeBaseImportTool {
+            notion_token,
+            confluence,
+            client,
+        }
+    }
+
+    /// Returns None if neither Notion nor Confluence is configured
+    pub fn from_env() -> Option<Self> {
+        let notion_token = env::var("NOTION_API_TOKEN").ok();
+        let confluence = match (
+            env::var("CONFLUENCE_BASE_URL"),
+            env::var("CONFLUENCE_EMAIL"),
+            env::var("CONFLUENCE_API_TOKEN"),
+        ) {
+            (Ok(base_url), Ok(email), Ok(api_token)) => Some(ConfluenceConfig {
+                base_url: base_url.trim_end_matches('/').to_string(),
+                email,
+                api_token,
+            }),
+            _ => None,
+        };
+        if notion_token.is_none() && confluence.is_none() {
+            return None;
+        }
+        Some(Self::new(notion_token, confluence))
+    }
+
+    /// Imports the pages into the folder, skipping the ones that didn't change since the last import of the same
+    /// source into it and removing the ones that no longer exist
+    #[allow(clippy::too_many_arguments)]
+    async fn import(
+        &self,
+        db: &ShinkaiDB,
+        vector_fs: &Arc<VectorFS>,
+        generator: &RemoteEmbeddingGenerator,
+        profile: &ShinkaiName,
+        source: &str,
+        id: &str,
+        folder_path: VRPath,
+    ) -> Result<Value, ToolError> {
+        let mut pages = match source {
+            "notion" => self.notion_pages(id).await?,
+            "confluence" => self.confluence_pages(id).await?,
+            _ => {
+                return Err(ToolError::InvalidFunctionArguments(format!(
+                    "Unknown source {}, expected notion or confluence",
+                    source
+                )))
+            }
+        };
+        let state_key = format!(
+            "{}:{}:{}:{}",
+            profile.full_name,
+            source,
+            id,
+            folder_path.format_to_string()
+        );
+        let mut state = db
+            .get_knowledge_base_import_state(&state_key)
+            .map_err(|e| ToolError::DatabaseError(e.to_string()))?;
+
+        let root_writer = vector_fs
+            .new_writer(profile.clone(), VRPath::root(), profile.clone())
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        vector_fs
+            .create_new_folder_auto(&root_writer, folder_path.clone())
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        let writer = vector_fs
+            .new_writer(profile.clone(), folder_path.clone(), profile.clone())
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+        let (generator, ingestion) = vector_fs
+            .folder_embedding_settings(
+                profile,
+                &folder_path,
+                generator,
+                &ParsingHelper::ingestion_settings(db, profile),
+            )
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        let (mut imported, mut unchanged) = (0, 0);
+        let mut used_names: HashSet<String> = state.pages.values().map(|(file_name, _)| file_name.clone()).collect();
+        for page in pages.iter_mut() {
+            let previous = state.pages.get(&page.id).cloned();
+            if let Some((file_name, last_edited)) = &previous {
+                // Items deleted from the VectorFS since are imported again
+                let item_path = Self::item_path(&folder_path, file_name);
+                if *last_edited == page.last_edited
+                    && vector_fs.validate_path_points_to_item(item_path, profile).await.is_ok()
+                {
+                    unchanged += 1;
+                    continue;
+                }
+            }
+            let markdown = match page.markdown.take() {
+                Some(markdown) => markdown,
+                None => self.notion_markdown(&page.id, 0).await?,
+            };
+            let file_name = match &previous {
+                Some((file_name, _)) => file_name.clone(),
+                None => page.file_name(&used_names),
+            };
+            used_names.insert(file_name.clone());
+
+            let modified = DateTime::parse_from_rfc3339(&page.last_edited)
+                .ok()
+                .map(|datetime| datetime.with_timezone(&Utc));
+            let document = page.to_markdown_document(source, &markdown);
+            let distribution_info = DistributionInfo::new_auto(&file_name, modified);
+            let processed = ParsingHelper::process_files_into_vrkai(
+                vec![(file_name.clone(), document.into_bytes(), distribution_info)],
+                &*generator,
+                None,
+                FileParser::Local,
+                &ingestion,
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+            // Saving over the item of the previous import keeps that one as a version
+            for (_, vrkai) in processed {
+                vector_fs
+                    .save_vrkai_in_folder(&writer, vrkai)
+                    .await
+                    .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+            }
+
+            state
+                .pages
+                .insert(page.id.clone(), (file_name, page.last_edited.clone()));
+            db.set_knowledge_base_import_state(&state_key, &state)
+                .map_err(|e| ToolError::DatabaseError(e.to_string()))?;
+            imported += 1;
+        }
+
+        let listed: HashSet<&String> = pages.iter().map(|page| &page.id).collect();
+        let removed: Vec<String> = state.pages.keys().filter(|id| !listed.contains(id)).cloned().collect();
+        for page_id in &removed {
+            if let Some((file_name, _)) = state.pages.remove(page_id) {
+                let item_writer = vector_fs
+                    .new_writer(
+                        profile.clone(),
+                        Self::item_path(&folder_path, &file_name),
+                        profile.clone(),
+                    )
+                    .await
+                    .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+                // The item may already have been deleted from the VectorFS
+                let _ = vector_fs.delete_item(&item_writer).await;
+            }
+        }
+        db.set_knowledge_base_import_state(&state_key, &state)
+            .map_err(|e| ToolError::DatabaseError(e.to_string()))?;
+
+        Ok(json!({
+            "vector_fs_path": folder_path.format_to_string(),
+            "imported": imported,
+            "unchanged": unchanged,
+            "removed": removed.len(),
+        }))
+    }
+
+    fn item_path(folder_path: &VRPath, file_name: &str) -> VRPath {
+        folder_path.push_cloned(SourceFileType::clean_string_of_extension(file_name))
+    }
+
+    fn notion_request(&self, request: reqwest::RequestBuilder) -> Result<reqwest::RequestBuilder, ToolError> {
+        let token = self
+            .notion_token
+            .as_ref()
+            .ok_or_else(|| ToolError::MissingConfigError("NOTION_API_TOKEN is not set".to_string()))?;
+        Ok(request.bearer_auth(token).header("Notion-Version", NOTION_VERSION))
+    }
+
+    async fn send_json(request: reqwest::RequestBuilder, service: &str) -> Result<Value, ToolError> {
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "{} request failed with status {}",
+                service,
+                response.status()
+            )));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// The pages of the database with the id, or the page with the id if it isn't a database
+    async fn notion_pages(&self, id: &str) -> Result<Vec<KnowledgeBasePage>, ToolError> {
+        let database = self
+            .notion_request(self.client.get(format!("{}/databases/{}", NOTION_API, id)))?
+            .send()
+            .await?;
+        if !database.status().is_success() {
+            let page = Self::send_json(
+                self.notion_request(self.client.get(format!("{}/pages/{}", NOTION_API, id)))?,
+                "Notion",
+            )
+            .await?;
+            return Ok(parse_notion_page(&page).into_iter().collect());
+        }
+
+        let mut pages = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut payload = json!({ "page_size": 100 });
+            if let Some(cursor) = &cursor {
+                payload["start_cursor"] = json!(cursor);
+            }
+            let body = Self::send_json(
+                self.notion_request(self.client.post(format!("{}/databases/{}/query", NOTION_API, id)))?
+                    .json(&payload),
+                "Notion",
+            )
+            .await?;
+            pages.extend(
+                body.get("results")
+                    .and_then(|results| results.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(parse_notion_page),
+            );
+            cursor = body.get("next_cursor").and_then(|v| v.as_str()).map(|v| v.to_string());
+            if cursor.is_none() {
+                return Ok(pages);
+            }
+        }
+    }
+
+    /// Markdown of the blocks below the block (or page) with the id
+    #[async_recursion]
+    async fn notion_markdown(&self, block_id: &str, depth: usize) -> Result<String, ToolError> {
+        let mut markdown = String::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut params = vec![("page_size", "100".to_string())];
+            if let Some(cursor) = &cursor {
+                params.push(("start_cursor", cursor.clone()));
+            }
+            let body = Self::send_json(
+                self.notion_request(
+                    self.client
+                        .get(format!("{}/blocks/{}/children", NOTION_API, block_id))
+                        .query(&params),
+                )?,
+                "Notion",
+            )
+            .await?;
+
+            let mut numbered_index = 0;
+            for block in body
+                .get("results")
+                .and_then(|results| results.as_array())
+                .into_iter()
+                .flatten()
+            {
+                let block_type = block.get("type").and_then(|v| v.as_str()).unwrap_or_default();
+                numbered_index = match block_type {
+                    "numbered_list_item" => numbered_index + 1,
+                    _ => 0,
+                };
+                let indent = "  ".repeat(depth);
+                if let Some(line) = notion_block_to_markdown(block, numbered_index) {
+                    markdown.push_str(&format!("{}{}\n", indent, line));
+                }
+                // Child pages are imported on their own when they're part of the import
+                let has_children = block.get("has_children").and_then(|v| v.as_bool()).unwrap_or(false);
+                if has_children && block_type != "child_page" && depth < MAX_NOTION_DEPTH {
+                    let id = block.get("id").and_then(|v| v.as_str()).unwrap_or_default();
+                    markdown.push_str(&self.notion_markdown(id, depth + 1).await?);
+                }
+            }
+
+            cursor = body.get("next_cursor").and_then(|v| v.as_str()).map(|v| v.to_string());
+            if cursor.is_none() {
+                return Ok(markdown);
+            }
+        }
+    }
+
+    /// The pages of the space with the key, or the page with the (numeric) id and its descendants
+    async fn confluence_pages(&self, id: &str) -> Result<Vec<KnowledgeBasePage>, ToolError> {
+        let confluence = self.confluence.as_ref().ok_or_else(|| {
+            ToolError::MissingConfigError(
+                "CONFLUENCE_BASE_URL, CONFLUENCE_EMAIL and CONFLUENCE_API_TOKEN must be set".to_string(),
+            )
+        })?;
+        let request = |url: String, params: Vec<(&str, String)>| {
+            self.client
+                .get(url)
+                .query(&params)
+                .basic_auth(&confluence.email, Some(&confluence.api_token))
+        };
+        let expand = ("expand", "version,body.storage".to_string());
+
+        let mut pages = Vec::new();
+        let url = match id.chars().all(|c| c.is_ascii_digit()) {
+            true => {
+                let page = Self::send_json(
+                    request(
+                        format!("{}/rest/api/content/{}", confluence.base_url, id),
+                        vec![expand.clone()],
+                    ),
+                    "Confluence",
+                )
+                .await?;
+                pages.extend(parse_confluence_page(&page, &confluence.base_url));
+                format!("{}/rest/api/content/{}/descendant/page", confluence.base_url, id)
+            }
+            false => format!("{}/rest/api/content", confluence.base_url),
+        };
+
+        let mut start = 0;
+        loop {
+            let mut params = vec![
+                expand.clone(),
+                ("limit", "50".to_string()),
+                ("start", start.to_string()),
+            ];
+            if !id.chars().all(|c| c.is_ascii_digit()) {
+                params.push(("spaceKey", id.to_string()));
+                params.push(("type", "page".to_string()));
+            }
+            let body = Self::send_json(request(url.clone(), params), "Confluence").await?;
+            let results = body
+                .get("results")
+                .and_then(|results| results.as_array())
+                .cloned()
+                .unwrap_or_default();
+            pages.extend(
+                results
+                    .iter()
+                    .filter_map(|page| parse_confluence_page(page, &confluence.base_url)),
+            );
+            if body.pointer("/_links/next").is_none() || results.is_empty() {
+                return Ok(pages);
+            }
+            start += results.len();
+        }
+    }
+}
+
+#[async_trait]
+impl NativeTool for KnowledgeBaseImportTool {
+    fn definition(&self) -> RustTool {
+        RustTool::new(
+            Self::NAME.to_string(),
+            "Imports the pages of a Notion page or database, or of a Confluence space or page, as markdown files into a folder of the user's VectorFS. Running it again only updates what changed.".to_string(),
+            vec![
+                ToolArgument::new(
+                    "source".to_string(),
+                    "string".to_string(),
+                    "Where to import from: notion or confluence".to_string(),
+                    true,
+                ),
+                ToolArgument::new(
+                    "id".to_string(),
+                    "string".to_string(),
+                    "Id of the Notion page or database, or the Confluence space key or page id".to_string(),
+                    true,
+                ),
+                ToolArgument::new(
+                    "vector_fs_path".to_string(),
+                    "string".to_string(),
+                    "VectorFS folder the pages are imported into, e.g. /Knowledge Base/Engineering".to_string(),
+                    true,
+                ),
+            ],
+            None,
+        )
+    }
+
+    fn capabilities(&self) -> Vec<ToolCapability> {
+        let mut capabilities = Vec::new();
+        if self.notion_token.is_some() {
+            capabilities.extend(ToolCapability::network_for_url(NOTION_API));
+        }
+        if let Some(confluence) = &self.confluence {
+            capabilities.extend(ToolCapability::network_for_url(&confluence.base_url));
+        }
+        capabilities
+    }
+
+    async fn run(&self, _args: serde_json::Map<String, Value>) -> Result<Value, ToolError> {
+        Err(ToolError::ExecutionError(format!(
+            "{} needs to know which profile's VectorFS to import into",
+            Self::NAME
+        )))
+    }
+
+    async fn run_with_context(
+        &self,
+        args: serde_json::Map<String, Value>,
+        context: NativeToolContext,
+    ) -> Result<Value, ToolError> {
+        let (Some(profile), Some(db), Some(vector_fs), Some(generator)) =
+            (&context.profile, &context.db, &context.vector_fs, &context.generator)
+        else {
+            return self.run(args).await;
+        };
+        let arg = |name: &str| {
+            args.get(name)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        let id = arg("id");
+        if id.is_empty() {
+            return Err(ToolError::InvalidFunctionArguments("The id can't be empty".to_string()));
+        }
+        let folder_path = VRPath::from_string(&arg("vector_fs_path"))?;
+
+        self.import(
+            db,
+            vector_fs,
+            generator,
+            profile,
+            &arg("source").to_lowercase(),
+            &id,
+            folder_path,
+        )
+        .await
+    }
+}
+
+/// Plain text of a Notion rich text array, keeping links
+fn notion_rich_text(rich_text: Option<&Value>) -> String {
+    rich_text
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .map(|text| {
+            let plain = text.get("plain_text").and_then(|v| v.as_str()).unwrap_or_default();
+            match text.get("href").and_then(|v| v.as_str()) {
+                Some(href) => format!("[{}]({})", plain, href),
+                None => plain.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Markdown line of a block, without its children. `numbered_index` is its position in a numbered list.
+pub fn notion_block_to_markdown(block: &Value, numbered_index: usize) -> Option<String> {
+    let block_type = block.get("type")?.as_str()?;
+    let content = block.get(block_type)?;
+    let text = notion_rich_text(content.get("rich_text"));
+    let line = match block_type {
+        "paragraph" => text,
+        "heading_1" => format!("# {}", text),
+        "heading_2" => format!("## {}", text),
+        "heading_3" => format!("### {}", text),
+        "bulleted_list_item" | "toggle" => format!("- {}", text),
+        "numbered_list_item" => format!("{}. {}", numbered_index, text),
+        "to_do" => {
+            let checked = content.get("checked").and_then(|v| v.as_bool()).unwrap_or(false);
+            format!("- [{}] {}", if checked { "x" } else { " " }, text)
+        }
+        "quote" | "callout" => format!("> {}", text),
+        "code" => {
+            let language = content.get("language").and_then(|v| v.as_str()).unwrap_or_default();
+            format!("```{}\n{}\n```", language, text)
+        }
+        "divider" => "---".to_string(),
+        "child_page" => format!("- {}", content.get("title")?.as_str()?),
+        _ => return None,
+    };
+    Some(line)
+}
+
+/// Title, url, last edit and properties of a Notion page
+fn parse_notion_page(page: &Value) -> Option<KnowledgeBasePage> {
+    let mut title = String::new();
+    let mut properties = Vec::new();
+    for (name, property) in page.get("properties").and_then(|v| v.as_object()).into_iter().flatten() {
+        let property_type = property.get("type").and_then(|v| v.as_str()).unwrap_or_default();
+        let value = match property_type {
+            "title" => {
+                title = notion_rich_text(property.get("title"));
+                continue;
+            }
+            "rich_text" => notion_rich_text(property.get("rich_text")),
+            "select" | "status" => property
+                .pointer(&format!("/{}/name", property_type))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            "multi_select" => property
+                .get("multi_select")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|option| option.get("name").and_then(|v| v.as_str()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            "date" => property
+                .pointer("/date/start")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            "number" | "checkbox" | "url" | "email" | "phone_number" => match property.get(property_type) {
+                Some(Value::String(value)) => value.clone(),
+                Some(Value::Null) | None => String::new(),
+                Some(value) => value.to_string(),
+            },
+            _ => continue,
+        };
+        if !value.is_empty() {
+            properties.push((name.clone(), value));
+        }
+    }
+    properties.sort();
+
+    Some(KnowledgeBasePage {
+        id: page.get("id")?.as_str()?.to_string(),
+        title,
+        url: page.get("url").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+        last_edited: page.get("last_edited_time")?.as_str()?.to_string(),
+        properties,
+        markdown: None,
+    })
+}
+
+/// A Confluence page with its storage format body converted to markdown
+fn parse_confluence_page(page: &Value, base_url: &str) -> Option<KnowledgeBasePage> {
+    let version = page.pointer("/version/number")?.to_string();
+    let body = page
+        .pointer("/body/storage/value")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+
+    Some(KnowledgeBasePage {
+        id: page.get("id")?.as_str()?.to_string(),
+        title: page.get("title")?.as_str()?.to_string(),
+        url: page
+            .pointer("/_links/webui")
+            .and_then(|v| v.as_str())
+            .map(|webui| format!("{}{}", base_url, webui))
+            .unwrap_or_default(),
+        last_edited: page
+            .pointer("/version/when")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        properties: vec![("version".to_string(), version)],
+        markdown: Some(html2md::parse_html(body)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notion_page_to_markdown() {
+        let page = parse_notion_page(&json!({
+            "id": "a1b2c3d4-0000",
+            "url": "https://www.notion.so/Roadmap-a1b2c3d4",
+            "last_edited_time": "2024-05-01T10:00:00.000Z",
+            "properties": {
+                "Name": { "type": "title", "title": [{ "plain_text": "Q3: Roadmap" }] },
+                "Status": { "type": "status", "status": { "name": "In progress" } },
+                "Tags": { "type": "multi_select", "multi_select": [{ "name": "infra" }, { "name": "ai" }] },
+            },
+        }))
+        .unwrap();
+        assert_eq!(page.title, "Q3: Roadmap");
+        assert_eq!(
+            page.properties,
+            vec![
+                ("Status".to_string(), "In progress".to_string()),
+                ("Tags".to_string(), "infra, ai".to_string())
+            ]
+        );
+        assert_eq!(page.file_name(&HashSet::new()), "Q3_ Roadmap.md");
+        assert_eq!(
+            page.file_name(&HashSet::from(["Q3_ Roadmap.md".to_string()])),
+            "Q3_ Roadmap (a1b2c3d4).md"
+        );
+
+        let block = json!({
+            "type": "to_do",
+            "to_do": { "checked": true, "rich_text": [{ "plain_text": "Ship it", "href": "https://shinkai.com" }] },
+        });
+        assert_eq!(
+            notion_block_to_markdown(&block, 0).unwrap(),
+            "- [x] [Ship it](https://shinkai.com)"
+        );
+
+        let document = page.to_markdown_document("notion", "- [x] Ship it");
+        assert!(document.starts_with("---\ntitle: \"Q3: Roadmap\"\nsource: \"notion\"\n"));
+        assert!(document.contains("Status: \"In progress\"\n---\n\n# Q3: Roadmap\n\n- [x] Ship it"));
+    }
+}
@@ -1,11 +1,30 @@
 pub mod argument;
+#[cfg(feature = "browser-tool")]
+pub mod browser_tool;
+pub mod calendar_tool;
+#[cfg(feature = "email")]
+pub mod email_send_tool;
 pub mod error;
 pub mod js_toolkit;
+#[cfg(feature = "deno-runtime")]
+pub mod js_deno_runtime;
 pub mod js_toolkit_executor;
 pub mod js_toolkit_headers;
 pub mod js_tools;
+pub mod knowledge_base_import_tool;
+pub mod native_tool;
+pub mod tool_execution_limits;
+pub mod tool_pricing;
 pub mod tool_router;
 pub mod rust_tools;
 pub mod shinkai_tool;
+#[cfg(feature = "sql-tool")]
+pub mod sql_tool;
+pub mod table_query_tool;
+pub mod toolkit_package;
+pub mod web_search_tool;
 pub mod workflow_tool;
+pub mod wasm_tools;
+#[cfg(feature = "wasm-tools")]
+pub mod wasm_runtime;
 pub mod tool_router_dep;
\ No newline at end of file
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use serde_json::Value;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_vector_resources::embedding_generator::RemoteEmbeddingGenerator;
+
+use crate::db::ShinkaiDB;
+use crate::llm_provider::execution::chains::dsl_chain::generic_functions::RustToolFunctions;
+use crate::tools::argument::ToolArgument;
+use crate::tools::error::ToolError;
+use crate::tools::js_toolkit_headers::ToolCapability;
+use crate::tools::rust_tools::RustTool;
+use crate::vector_fs::vector_fs::VectorFS;
+
+lazy_static! {
+    /// Native Rust tools registered by the embedder of the node (see `NativeToolRegistry`)
+    pub static ref NATIVE_TOOL_REGISTRY: NativeToolRegistry = NativeToolRegistry::new();
+}
+
+/// Who a native tool is running for
+#[derive(Clone, Default)]
+pub struct NativeToolContext {
+    pub profile: Option<ShinkaiName>,
+    pub db: Option<Arc<ShinkaiDB>>,
+    pub vector_fs: Option<Arc<VectorFS>>,
+    pub generator: Option<RemoteEmbeddingGenerator>,
+}
+
+/// A tool implemented in Rust by whoever embeds the node.
+///
+/// The definition is exposed to LLMs and workflows like any other Rust tool, and the arguments
+/// are validated against `definition().input_args` before `run` is called.
+#[async_trait]
+pub trait NativeTool: Send + Sync {
+    /// Name, description and arguments of the tool
+    fn definition(&self) -> RustTool;
+
+    /// Capabilities that have to be granted to the tool before it can run. Tools that only work on
+    /// their arguments don't need any.
+    fn capabilities(&self) -> Vec<ToolCapability> {
+        vec![]
+    }
+
+    /// Runs the tool with the already validated arguments
+    async fn run(&self, args: serde_json::Map<String, Value>) -> Result<Value, ToolError>;
+
+    /// Runs the tool for the profile in the context. Only tools that depend on who calls them need to implement it.
+    async fn run_with_context(
+        &self,
+        args: serde_json::Map<String, Value>,
+        context: NativeToolContext,
+    ) -> Result<Value, ToolError> {
+        let _ = context;
+        self.run(args).await
+    }
+}
+
+/// Keeps the native tools available to the tool router.
+///
+/// Tools registered before `Node::start` are added to the tool router during its initialization.
+/// Tools registered afterwards should go through `ToolRouter::register_native_tool` so they also get indexed.
+pub struct NativeToolRegistry {
+    tools: RwLock<HashMap<String, Arc<dyn NativeTool>>>,
+}
+
+impl NativeToolRegistry {
+    pub fn new() -> Self {
+        Self {
+            tools: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Registers a native tool. Fails if the name is already used by a built-in or a registered tool.
+    pub fn register(&self, tool: Arc<dyn NativeTool>) -> Result<RustTool, ToolError> {
+        let definition = tool.definition();
+        // Names go through the same cleaning as the static Rust tools
+        let definition = RustTool::new(
+            definition.name,
+            definition.description,
+            definition.input_args,
+            definition.tool_embedding,
+        );
+
+        if definition.name.is_empty() {
+            return Err(ToolError::ParseError("Native tool name can't be empty".to_string()));
+        }
+        if RustToolFunctions::get_tool_function(&definition.name).is_some() {
+            return Err(ToolError::ToolAlreadyInstalled(definition.name));
+        }
+
+        let mut tools = self.tools.write().unwrap();
+        if tools.contains_key(&definition.name) {
+            return Err(ToolError::ToolAlreadyInstalled(definition.name));
+        }
+        tools.insert(definition.name.clone(), tool);
+
+        Ok(definition)
+    }
+
+    /// Removes a native tool. Returns true if the tool was registered.
+    pub fn unregister(&self, name: &str) -> bool {
+        self.tools.write().unwrap().remove(name).is_some()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.tools.read().unwrap().contains_key(name)
+    }
+
+    /// Capabilities the tool needs, empty for tools that aren't native ones
+    pub fn capabilities(&self, name: &str) -> Vec<ToolCapability> {
+        self.tools
+            .read()
+            .unwrap()
+            .get(name)
+            .map(|tool| tool.capabilities())
+            .unwrap_or_default()
+    }
+
+    /// Definitions of every registered tool
+    pub fn definitions(&self) -> Vec<RustTool> {
+        self.tools
+            .read()
+            .unwrap()
+            .values()
+            .map(|tool| {
+                let definition = tool.definition();
+                RustTool::new(
+                    definition.name,
+                    definition.description,
+                    definition.input_args,
+                    definition.tool_embedding,
+                )
+            })
+            .collect()
+    }
+
+    /// Validates the arguments against the tool definition and runs it
+    pub async fn call(&self, name: &str, args: Value, context: NativeToolContext) -> Result<Value, ToolError> {
+        let tool = self
+            .tools
+            .read()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ToolError::ToolNotFound(name.to_string()))?;
+
+        let args = ToolArgument::validate_args(&tool.definition().input_args, args)?;
+        tool.run_with_context(args, context).await
+    }
+}
+
+/// Registers the native tools that ship with the node.
+/// Tools behind a disabled feature or that aren't configured are skipped.
+pub fn register_built_in_native_tools() {
+    #[cfg(feature = "browser-tool")]
+    let _ = NATIVE_TOOL_REGISTRY.register(Arc::new(super::browser_tool::BrowserTool));
+
+    if let Some(web_search_tool) = super::web_search_tool::WebSearchTool::from_env() {
+        let _ = NATIVE_TOOL_REGISTRY.register(Arc::new(web_search_tool));
+    }
+
+    if let Some(import_tool) = super::knowledge_base_import_tool::KnowledgeBaseImportTool::from_env() {
+        let _ = NATIVE_TOOL_REGISTRY.register(Arc::new(import_tool));
+    }
+
+    #[cfg(feature = "sql-tool")]
+    if let Some(sql_tool) = super::sql_tool::SqlQueryTool::from_env() {
+        let _ = NATIVE_TOOL_REGISTRY.register(Arc::new(sql_tool));
+    }
+
+    #[cfg(feature = "email")]
+    let _ = NATIVE_TOOL_REGISTRY.register(Arc::new(super::email_send_tool::SendEmailTool));
+
+    let _ = NATIVE_TOOL_REGISTRY.register(Arc::new(super::table_query_tool::TableQueryTool));
+
+    let _ = NATIVE_TOOL_REGISTRY.register(Arc::new(super::calendar_tool::ListCalendarEventsTool));
+    let _ = NATIVE_TOOL_REGISTRY.register(Arc::new(super::calendar_tool::FindCalendarSlotTool));
+    let _ = NATIVE_TOOL_REGISTRY.register(Arc::new(super::calendar_tool::CreateCalendarEventTool));
+}
+
+impl Default for NativeToolRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct AddTool;
+
+    #[async_trait]
+    impl NativeTool for AddTool {
+        fn definition(&self) -> RustTool {
+            RustTool::new(
+                "add_numbers".to_string(),
+                "Adds two numbers.".to_string(),
+                vec![
+                    ToolArgument::new("a".to_string(), "number".to_string(), "First number".to_string(), true),
+                    ToolArgument::new("b".to_string(), "number".to_string(), "Second number".to_string(), true),
+                ],
+                None,
+            )
+        }
+
+        async fn run(&self, args: serde_json::Map<String, Value>) -> Result<Value, ToolError> {
+            let a = args["a"].as_f64().unwrap_or_default();
+            let b = args["b"].as_f64().unwrap_or_default();
+            Ok(json!(a + b))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_and_call_native_tool() {
+        let registry = NativeToolRegistry::new();
+        registry.register(Arc::new(AddTool)).unwrap();
+        assert!(registry.contains("add_numbers"));
+        assert!(matches!(
+            registry.register(Arc::new(AddTool)),
+            Err(ToolError::ToolAlreadyInstalled(_))
+        ));
+
+        let result = registry
+            .call("add_numbers", json!({"a": 1, "b": "2.5"}), NativeToolContext::default())
+            .await
+            .unwrap();
+        assert_eq!(result, json!(3.5));
+
+        let missing = registry
+            .call("add_numbers", json!({"a": 1}), NativeToolContext::default())
+            .await;
+        assert!(matches!(missing, Err(ToolError::InvalidFunctionArguments(_))));
+
+        let wrong_type = registry
+            .call("add_numbers", json!({"a": 1, "b": "two"}), NativeToolContext::default())
+            .await;
+        assert!(matches!(wrong_type, Err(ToolError::InvalidFunctionArguments(_))));
+    }
+}
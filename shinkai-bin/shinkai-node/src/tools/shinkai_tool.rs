@@ -3,11 +3,18 @@ use std::env;
 use crate::tools::argument::ToolArgument;
 use crate::tools::error::ToolError;
 use crate::tools::js_tools::JSTool;
+use crate::tools::native_tool::NATIVE_TOOL_REGISTRY;
 use crate::tools::rust_tools::RustTool;
+use crate::tools::tool_execution_limits::ToolExecutionLimits;
+use crate::tools::wasm_tools::WasmTool;
 use serde_json::{self};
 use shinkai_vector_resources::embeddings::Embedding;
 
-use super::{js_toolkit_headers::ToolConfig, workflow_tool::WorkflowTool};
+use super::{
+    js_toolkit_headers::{ToolCapability, ToolConfig},
+    workflow_tool::WorkflowTool,
+};
+use crate::db::ShinkaiDB;
 
 pub type IsEnabled = bool;
 
@@ -17,6 +24,7 @@ pub enum ShinkaiTool {
     Rust(RustTool, IsEnabled),
     JS(JSTool, IsEnabled),
     Workflow(WorkflowTool, IsEnabled),
+    Wasm(WasmTool, IsEnabled),
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -30,6 +38,8 @@ pub struct ShinkaiToolHeader {
     pub version: String,
     pub enabled: bool,
     pub config: Option<Vec<ToolConfig>>,
+    #[serde(default)]
+    pub capabilities: Vec<ToolCapability>,
 }
 
 impl ShinkaiTool {
@@ -45,6 +55,7 @@ impl ShinkaiTool {
             version: self.version(),
             enabled: self.is_enabled(),
             config: self.get_js_tool_config().cloned(),
+            capabilities: self.requested_capabilities(),
         }
     }
 
@@ -59,6 +70,7 @@ impl ShinkaiTool {
                     match self {
                         ShinkaiTool::Rust(r, _) => r.toolkit_type_name(),
                         ShinkaiTool::JS(j, _) => j.toolkit_name.to_string(),
+                        ShinkaiTool::Wasm(w, _) => w.toolkit_name.to_string(),
                         _ => unreachable!(), // This case is already handled above
                     },
                 );
@@ -79,6 +91,7 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(r, _) => r.name.clone(),
             ShinkaiTool::JS(j, _) => j.name.clone(),
             ShinkaiTool::Workflow(w, _) => w.get_name(),
+            ShinkaiTool::Wasm(w, _) => w.name.clone(),
         }
     }
     /// Tool description
@@ -87,6 +100,7 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(r, _) => r.description.clone(),
             ShinkaiTool::JS(j, _) => j.description.clone(),
             ShinkaiTool::Workflow(w, _) => w.get_description(),
+            ShinkaiTool::Wasm(w, _) => w.description.clone(),
         }
     }
 
@@ -96,6 +110,7 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(r, _) => r.name.clone(),
             ShinkaiTool::JS(j, _) => j.name.clone(),
             ShinkaiTool::Workflow(w, _) => w.get_name(),
+            ShinkaiTool::Wasm(w, _) => w.name.clone(),
         }
     }
 
@@ -105,6 +120,7 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(r, _) => r.toolkit_type_name().clone(),
             ShinkaiTool::JS(j, _) => j.toolkit_name.clone(),
             ShinkaiTool::Workflow(w, _) => w.get_name(),
+            ShinkaiTool::Wasm(w, _) => w.toolkit_name.clone(),
         }
     }
 
@@ -114,6 +130,7 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(r, _) => r.input_args.clone(),
             ShinkaiTool::JS(j, _) => j.input_args.clone(),
             ShinkaiTool::Workflow(w, _) => w.get_input_args(),
+            ShinkaiTool::Wasm(w, _) => w.input_args.clone(),
         }
     }
 
@@ -123,6 +140,7 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(_, _) => "Rust",
             ShinkaiTool::JS(_, _) => "JS",
             ShinkaiTool::Workflow(_, _) => "Workflow",
+            ShinkaiTool::Wasm(_, _) => "Wasm",
         }
     }
 
@@ -142,6 +160,17 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(r, _) => r.tool_embedding = Some(embedding),
             ShinkaiTool::JS(j, _) => j.embedding = Some(embedding),
             ShinkaiTool::Workflow(w, _) => w.embedding = Some(embedding),
+            ShinkaiTool::Wasm(w, _) => w.embedding = Some(embedding),
+        }
+    }
+
+    /// Returns true if the arguments of the tool are exposed with their JSON schema type. Only native and Wasm
+    /// tools validate their arguments against those types, every other tool keeps receiving strings.
+    fn has_typed_args(&self) -> bool {
+        match self {
+            ShinkaiTool::Rust(r, _) => NATIVE_TOOL_REGISTRY.contains(&r.name),
+            ShinkaiTool::Wasm(_, _) => true,
+            ShinkaiTool::JS(_, _) | ShinkaiTool::Workflow(_, _) => false,
         }
     }
 
@@ -149,12 +178,13 @@ impl ShinkaiTool {
     pub fn json_function_call_format(&self) -> Result<serde_json::Value, ToolError> {
         let mut properties = serde_json::Map::new();
         let mut required_args = vec![];
+        let typed_args = self.has_typed_args();
 
         for arg in self.input_args() {
             properties.insert(
                 arg.name.clone(),
                 serde_json::json!({
-                    "type": "string",
+                    "type": if typed_args { arg.json_schema_type() } else { "string" },
                     "description": arg.description.clone(),
                 }),
             );
@@ -195,6 +225,7 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(r, _) => r.tool_embedding.clone(),
             ShinkaiTool::JS(j, _) => j.embedding.clone(),
             ShinkaiTool::Workflow(w, _) => w.embedding.clone(),
+            ShinkaiTool::Wasm(w, _) => w.embedding.clone(),
         }
     }
 
@@ -212,6 +243,7 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(_r, _) => "@@official.shinkai".to_string(),
             ShinkaiTool::JS(j, _) => j.author.clone(),
             ShinkaiTool::Workflow(w, _) => w.workflow.author.clone(),
+            ShinkaiTool::Wasm(w, _) => w.author.clone(),
         }
     }
 
@@ -221,6 +253,7 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(_r, _) => "v0.1".to_string(),
             ShinkaiTool::JS(_j, _) => "v0.1".to_string(),
             ShinkaiTool::Workflow(w, _) => w.workflow.version.clone(),
+            ShinkaiTool::Wasm(_w, _) => "v0.1".to_string(),
         }
     }
 
@@ -230,6 +263,7 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(_, enabled) => *enabled,
             ShinkaiTool::JS(_, enabled) => *enabled,
             ShinkaiTool::Workflow(_, enabled) => *enabled,
+            ShinkaiTool::Wasm(_, enabled) => *enabled,
         }
     }
 
@@ -239,6 +273,7 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(_, enabled) => *enabled = true,
             ShinkaiTool::JS(_, enabled) => *enabled = true,
             ShinkaiTool::Workflow(_, enabled) => *enabled = true,
+            ShinkaiTool::Wasm(_, enabled) => *enabled = true,
         }
     }
 
@@ -248,6 +283,7 @@ impl ShinkaiTool {
             ShinkaiTool::Rust(_, enabled) => *enabled = false,
             ShinkaiTool::JS(_, enabled) => *enabled = false,
             ShinkaiTool::Workflow(_, enabled) => *enabled = false,
+            ShinkaiTool::Wasm(_, enabled) => *enabled = false,
         }
     }
 
@@ -265,10 +301,49 @@ impl ShinkaiTool {
         match self {
             ShinkaiTool::Rust(_, _) => true,
             ShinkaiTool::Workflow(_, _) => true,
+            ShinkaiTool::Wasm(_, _) => true,
             ShinkaiTool::JS(js_tool, _) => js_tool.check_required_config_fields(),
         }
     }
 
+    /// Capabilities the tool needs granted to its toolkit before it can run
+    pub fn requested_capabilities(&self) -> Vec<ToolCapability> {
+        match self {
+            ShinkaiTool::Rust(rust_tool, _) => NATIVE_TOOL_REGISTRY.capabilities(&rust_tool.name),
+            ShinkaiTool::Workflow(workflow_tool, _) => workflow_tool.requested_capabilities(),
+            ShinkaiTool::JS(js_tool, _) => js_tool.requested_capabilities(),
+            ShinkaiTool::Wasm(wasm_tool, _) => wasm_tool.permissions.to_capabilities(),
+        }
+    }
+
+    /// Checks that the user granted every capability the tool needs to its toolkit
+    pub fn check_capabilities(&self, db: &ShinkaiDB) -> Result<(), ToolError> {
+        let requested = self.requested_capabilities();
+        if requested.is_empty() {
+            return Ok(());
+        }
+
+        let grants = db
+            .get_toolkit_capability_grants(&self.toolkit_type_name())
+            .map_err(|e| ToolError::DatabaseError(e.to_string()))?;
+        let missing = ToolCapability::missing(&requested, &grants.granted);
+        if !missing.is_empty() {
+            return Err(ToolError::CapabilityNotGranted(format!(
+                "{} requires {:?} to be granted to toolkit {}",
+                self.name(),
+                missing,
+                self.toolkit_type_name()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Limits that apply to each invocation of the tool, as configured for its toolkit
+    pub fn execution_limits(&self, db: &ShinkaiDB) -> Result<ToolExecutionLimits, ToolError> {
+        db.get_toolkit_execution_limits(&self.toolkit_type_name())
+            .map_err(|e| ToolError::DatabaseError(e.to_string()))
+    }
+
     /// Convert to json
     pub fn to_json(&self) -> Result<String, ToolError> {
         serde_json::to_string(self).map_err(|_| ToolError::FailedJSONParsing)
@@ -292,3 +367,60 @@ impl From<JSTool> for ShinkaiTool {
         ShinkaiTool::JS(tool, true)
     }
 }
+
+impl From<WasmTool> for ShinkaiTool {
+    fn from(tool: WasmTool) -> Self {
+        ShinkaiTool::Wasm(tool, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::native_tool::NativeTool;
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    fn count_tool(name: &str) -> RustTool {
+        RustTool::new(
+            name.to_string(),
+            "Counts up to a number.".to_string(),
+            vec![ToolArgument::new(
+                "up_to".to_string(),
+                "integer".to_string(),
+                "Number to count up to".to_string(),
+                true,
+            )],
+            None,
+        )
+    }
+
+    struct CountTool;
+
+    #[async_trait]
+    impl NativeTool for CountTool {
+        fn definition(&self) -> RustTool {
+            count_tool("count_up_to_native")
+        }
+
+        async fn run(&self, args: serde_json::Map<String, Value>) -> Result<Value, ToolError> {
+            Ok(args["up_to"].clone())
+        }
+    }
+
+    fn arg_type(tool: &ShinkaiTool) -> Value {
+        tool.json_function_call_format().unwrap()["function"]["parameters"]["properties"]["up_to"]["type"].clone()
+    }
+
+    #[test]
+    fn test_only_native_tools_expose_typed_args() {
+        NATIVE_TOOL_REGISTRY.register(Arc::new(CountTool)).unwrap();
+        let native_tool = ShinkaiTool::Rust(count_tool("count_up_to_native"), true);
+        assert_eq!(arg_type(&native_tool), json!("integer"));
+
+        // Tools that aren't native keep exposing their arguments as strings
+        let static_tool = ShinkaiTool::Rust(count_tool("count_up_to_static"), true);
+        assert_eq!(arg_type(&static_tool), json!("string"));
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::env;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::any::{install_default_drivers, AnyArguments, AnyPoolOptions, AnyRow};
+use sqlx::query::Query;
+use sqlx::{Any, Column, Row};
+
+use crate::tools::argument::ToolArgument;
+use crate::tools::error::ToolError;
+use crate::tools::js_toolkit_headers::ToolCapability;
+use crate::tools::native_tool::NativeTool;
+use crate::tools::rust_tools::RustTool;
+
+const DEFAULT_MAX_ROWS: usize = 200;
+
+/// Keywords that can't show up anywhere in a query, as they would change data or the connection
+const FORBIDDEN_KEYWORDS: [&str; 17] = [
+    "insert", "update", "delete", "merge", "upsert", "drop", "alter", "create", "truncate", "grant", "revoke",
+    "attach", "detach", "pragma", "vacuum", "copy", "call",
+];
+
+/// A database the SQL tool can query. The connection URL is read from the `url_env` environment variable
+/// so credentials don't end up in the tool configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SqlDatabaseConfig {
+    pub url_env: String,
+    /// Tables queries can read from. Empty means any table.
+    #[serde(default)]
+    pub allowed_tables: Vec<String>,
+    /// If set, only these queries (ignoring case and whitespace) can run
+    #[serde(default)]
+    pub approved_queries: Option<Vec<String>>,
+    #[serde(default)]
+    pub max_rows: Option<usize>,
+}
+
+impl SqlDatabaseConfig {
+    /// Checks the query is a single read-only statement allowed by the policy of the database
+    pub fn check_query(&self, query: &str) -> Result<(), ToolError> {
+        let query = query.trim().trim_end_matches(';').trim();
+        if query.contains(';') {
+            return Err(Self::rejected("only a single statement can run"));
+        }
+
+        let tokens = Self::tokenize(query);
+        match tokens.first().map(String::as_str) {
+            Some("select") | Some("with") => {}
+            _ => return Err(Self::rejected("only SELECT queries can run")),
+        }
+        if let Some(keyword) = tokens.iter().find(|t| FORBIDDEN_KEYWORDS.contains(&t.as_str())) {
+            return Err(Self::rejected(&format!("`{}` isn't allowed", keyword)));
+        }
+
+        if !self.allowed_tables.is_empty() {
+            for table in Self::referenced_tables(&tokens) {
+                let allowed = self.allowed_tables.iter().any(|allowed| {
+                    let allowed = allowed.to_lowercase();
+                    allowed == table || table.rsplit('.').next() == Some(allowed.as_str())
+                });
+                if !allowed {
+                    return Err(Self::rejected(&format!("table {} isn't in the allowlist", table)));
+                }
+            }
+        }
+
+        if let Some(approved) = &self.approved_queries {
+            let normalized = Self::normalize(query);
+            if !approved
+                .iter()
+                .any(|q| Self::normalize(q.trim().trim_end_matches(';')) == normalized)
+            {
+                return Err(Self::rejected("the query hasn't been approved"));
+            }
+        }
+        Ok(())
+    }
+
+    fn rejected(reason: &str) -> ToolError {
+        ToolError::InvalidFunctionArguments(format!("Query rejected: {}", reason))
+    }
+
+    fn normalize(query: &str) -> String {
+        query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+    }
+
+    /// Splits the query into lowercased words and `(`, `)` and `,`, leaving out string literals
+    fn tokenize(query: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut current = String::new();
+        let mut in_string = false;
+        for c in query.chars() {
+            if c == '\'' {
+                in_string = !in_string;
+                continue;
+            }
+            if in_string || c == '"' || c == '`' {
+                continue;
+            }
+            if c.is_alphanumeric() || c == '_' || c == '.' {
+                current.push(c.to_ascii_lowercase());
+                continue;
+            }
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            if c == '(' || c == ')' || c == ',' {
+                tokens.push(c.to_string());
+            }
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+        tokens
+    }
+
+    /// Tables listed after FROM (including comma separated ones) and JOIN, leaving out CTEs and subqueries
+    fn referenced_tables(tokens: &[String]) -> Vec<String> {
+        const CLAUSE_KEYWORDS: [&str; 20] = [
+            "where",
+            "group",
+            "order",
+            "limit",
+            "offset",
+            "having",
+            "union",
+            "except",
+            "intersect",
+            "window",
+            "join",
+            "inner",
+            "left",
+            "right",
+            "full",
+            "cross",
+            "natural",
+            "on",
+            "using",
+            ")",
+        ];
+
+        // `name AS (` right after WITH or a comma defines a CTE
+        let cte_names: Vec<&String> = tokens
+            .windows(4)
+            .filter(|w| (w[0] == "with" || w[0] == "," || w[0] == "recursive") && w[2] == "as" && w[3] == "(")
+            .map(|w| &w[1])
+            .collect();
+
+        let mut tables = Vec::new();
+        for (i, token) in tokens.iter().enumerate() {
+            if token != "from" && token != "join" {
+                continue;
+            }
+            let mut j = i + 1;
+            while let Some(name) = tokens.get(j) {
+                if name == "(" {
+                    break;
+                }
+                if !cte_names.contains(&name) {
+                    tables.push(name.clone());
+                }
+                // Skip the alias, then continue with the next comma separated table, if any
+                j += 1;
+                while let Some(next) = tokens.get(j) {
+                    if next == "," || CLAUSE_KEYWORDS.contains(&next.as_str()) {
+                        break;
+                    }
+                    j += 1;
+                }
+                if token != "from" || tokens.get(j).map(String::as_str) != Some(",") {
+                    break;
+                }
+                j += 1;
+            }
+        }
+        tables
+    }
+}
+
+/// Built-in tool that runs read-only queries on the databases configured in SQL_TOOL_DATABASES, a JSON object
+/// of database name to `SqlDatabaseConfig`, e.g. `{"analytics": {"url_env": "ANALYTICS_DATABASE_URL"}}`.
+pub struct SqlQueryTool {
+    databases: HashMap<String, SqlDatabaseConfig>,
+}
+
+impl SqlQueryTool {
+    pub const NAME: &'static str = "sql_query";
+
+    /// Returns None if no database is configured
+    pub fn from_env() -> Option<Self> {
+        let databases: HashMap<String, SqlDatabaseConfig> =
+            serde_json::from_str(&env::var("SQL_TOOL_DATABASES").ok()?).ok()?;
+        if databases.is_empty() {
+            return None;
+        }
+        install_default_drivers();
+        Some(SqlQueryTool { databases })
+    }
+
+    fn bind_param<'q>(
+        query: Query<'q, Any, AnyArguments<'q>>,
+        param: &Value,
+    ) -> Result<Query<'q, Any, AnyArguments<'q>>, ToolError> {
+        Ok(match param {
+            Value::Null => query.bind(None::<String>),
+            Value::Bool(b) => query.bind(*b),
+            Value::Number(n) if n.is_i64() => query.bind(n.as_i64().unwrap_or_default()),
+            Value::Number(n) => query.bind(n.as_f64().unwrap_or_default()),
+            Value::String(s) => query.bind(s.clone()),
+            _ => {
+                return Err(ToolError::InvalidFunctionArguments(
+                    "Query params can only be strings, numbers, booleans or null".to_string(),
+                ))
+            }
+        })
+    }
+
+    fn row_to_values(row: &AnyRow) -> Vec<Value> {
+        (0..row.columns().len())
+            .map(|i| {
+                if let Ok(value) = row.try_get::<Option<i64>, _>(i) {
+                    json!(value)
+                } else if let Ok(value) = row.try_get::<Option<f64>, _>(i) {
+                    json!(value)
+                } else if let Ok(value) = row.try_get::<Option<bool>, _>(i) {
+                    json!(value)
+                } else if let Ok(value) = row.try_get::<Option<String>, _>(i) {
+                    json!(value)
+                } else {
+                    Value::Null
+                }
+            })
+            .collect()
+    }
+
+    async fn execute(config: &SqlDatabaseConfig, sql: &str, params: &[Value]) -> Result<Value, ToolError> {
+        let mut url = env::var(&config.url_env).map_err(|_| ToolError::MissingConfigError(config.url_env.clone()))?;
+        // Besides checking the query, the connection itself is made read-only
+        if url.starts_with("sqlite") && !url.contains("mode=ro") {
+            url = format!("{}{}mode=ro", url, if url.contains('?') { "&" } else { "?" });
+        }
+        let pool = AnyPoolOptions::new()
+            .max_connections(1)
+            .connect(&url)
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to connect to the database: {}", e)))?;
+
+        if url.starts_with("mysql") {
+            sqlx::query("SET SESSION TRANSACTION READ ONLY")
+                .execute(&pool)
+                .await
+                .map_err(Self::sql_error)?;
+        }
+        let mut tx = pool.begin().await.map_err(Self::sql_error)?;
+        if url.starts_with("postgres") {
+            sqlx::query("SET TRANSACTION READ ONLY")
+                .execute(&mut *tx)
+                .await
+                .map_err(Self::sql_error)?;
+        }
+
+        let mut query = sqlx::query(sql);
+        for param in params {
+            query = Self::bind_param(query, param)?;
+        }
+        let rows = query.fetch_all(&mut *tx).await.map_err(Self::sql_error)?;
+        let _ = tx.rollback().await;
+        pool.close().await;
+
+        let max_rows = config.max_rows.unwrap_or(DEFAULT_MAX_ROWS);
+        let columns: Vec<String> = rows
+            .first()
+            .map(|row| row.columns().iter().map(|c| c.name().to_string()).collect())
+            .unwrap_or_default();
+        let truncated = rows.len() > max_rows;
+        let values: Vec<Vec<Value>> = rows.iter().take(max_rows).map(Self::row_to_values).collect();
+
+        Ok(json!({
+            "columns": columns,
+            "rows": values,
+            "row_count": values.len(),
+            "truncated": truncated,
+        }))
+    }
+
+    fn sql_error(e: sqlx::Error) -> ToolError {
+        ToolError::ExecutionError(format!("Query failed: {}", e))
+    }
+}
+
+#[async_trait]
+impl NativeTool for SqlQueryTool {
+    fn definition(&self) -> RustTool {
+        let mut databases: Vec<&String> = self.databases.keys().collect();
+        databases.sort();
+        RustTool::new(
+            Self::NAME.to_string(),
+            format!(
+                "Runs a read-only SQL SELECT query and returns the columns and rows. Use placeholders for values ($1 for Postgres, ? for MySQL and SQLite) and pass them in params. Available databases: {}",
+                databases.iter().map(|d| d.as_str()).collect::<Vec<_>>().join(", ")
+            ),
+            vec![
+                ToolArgument::new("database".to_string(), "string".to_string(), "Name of the database".to_string(), true),
+                ToolArgument::new("query".to_string(), "string".to_string(), "The SELECT query".to_string(), true),
+                ToolArgument::new(
+                    "params".to_string(),
+                    "array".to_string(),
+                    "Values for the query placeholders, in order".to_string(),
+                    false,
+                ),
+            ],
+            None,
+        )
+    }
+
+    /// The connection URL of every database is a secret of its own
+    fn capabilities(&self) -> Vec<ToolCapability> {
+        let mut url_envs: Vec<&String> = self.databases.values().map(|config| &config.url_env).collect();
+        url_envs.sort();
+        url_envs.dedup();
+        url_envs
+            .into_iter()
+            .map(|url_env| ToolCapability::Secret(url_env.clone()))
+            .collect()
+    }
+
+    async fn run(&self, args: serde_json::Map<String, Value>) -> Result<Value, ToolError> {
+        let database = args.get("database").and_then(|v| v.as_str()).unwrap_or_default();
+        let sql = args.get("query").and_then(|v| v.as_str()).unwrap_or_default();
+        let params = args
+            .get("params")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        let config = self
+            .databases
+            .get(database)
+            .ok_or_else(|| ToolError::InvalidFunctionArguments(format!("Unknown database: {}", database)))?;
+        config.check_query(sql)?;
+
+        Self::execute(config, sql, &params).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(allowed_tables: Vec<&str>, approved_queries: Option<Vec<&str>>) -> SqlDatabaseConfig {
+        SqlDatabaseConfig {
+            url_env: "TEST_DATABASE_URL".to_string(),
+            allowed_tables: allowed_tables.into_iter().map(String::from).collect(),
+            approved_queries: approved_queries.map(|q| q.into_iter().map(String::from).collect()),
+            max_rows: None,
+        }
+    }
+
+    #[test]
+    fn test_check_query() {
+        let open = config(vec![], None);
+        assert!(open.check_query("SELECT * FROM orders WHERE id = $1;").is_ok());
+        assert!(open.check_query("select 'drop table' as text").is_ok());
+        assert!(open.check_query("DELETE FROM orders").is_err());
+        assert!(open.check_query("SELECT 1; DROP TABLE orders").is_err());
+        assert!(open
+            .check_query("WITH x AS (DELETE FROM orders RETURNING *) SELECT * FROM x")
+            .is_err());
+
+        let allowlisted = config(vec!["orders"], None);
+        assert!(allowlisted
+            .check_query("WITH recent AS (SELECT * FROM public.orders) SELECT * FROM recent")
+            .is_ok());
+        assert!(allowlisted
+            .check_query("SELECT * FROM orders JOIN users ON users.id = orders.user_id")
+            .is_err());
+        assert!(allowlisted.check_query("SELECT * FROM orders o, users u").is_err());
+        assert!(allowlisted.check_query("SELECT secret AS users FROM users").is_err());
+
+        let approved = config(vec![], Some(vec!["SELECT count(*) FROM orders"]));
+        assert!(approved.check_query("select   COUNT(*)\nfrom orders;").is_ok());
+        assert!(approved.check_query("SELECT * FROM orders").is_err());
+    }
+}
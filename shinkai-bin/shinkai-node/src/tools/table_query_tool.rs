@@ -0,0 +1,174 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use shinkai_vector_resources::file_parser::file_parser_tables::ParsedTable;
+
+use crate::tools::argument::ToolArgument;
+use crate::tools::error::ToolError;
+use crate::tools::native_tool::NativeTool;
+use crate::tools::rust_tools::RustTool;
+
+/// Rows returned when no limit is given
+const DEFAULT_ROW_LIMIT: usize = 50;
+
+/// Built-in tool that answers questions about a table retrieved from the job scope by filtering, selecting
+/// and aggregating its columns, instead of leaving the LLM to do it over the markdown
+pub struct TableQueryTool;
+
+impl TableQueryTool {
+    pub const NAME: &'static str = "table_query";
+
+    /// Runs the query over the table. Cells are compared ignoring case, and aggregates skip non numeric cells.
+    pub fn query(table: &ParsedTable, args: &serde_json::Map<String, Value>) -> Result<Value, ToolError> {
+        let string_arg = |name: &str| args.get(name).and_then(|v| v.as_str()).filter(|v| !v.trim().is_empty());
+        let column = |name: &str| {
+            table
+                .column_index(name)
+                .ok_or_else(|| ToolError::InvalidFunctionArguments(format!("The table has no column {}", name)))
+        };
+
+        let mut rows: Vec<&Vec<String>> = table.rows.iter().collect();
+        if let Some(filter_column) = string_arg("filter_column") {
+            let index = column(filter_column)?;
+            let filter_value = string_arg("filter_value").unwrap_or_default().to_lowercase();
+            rows.retain(|row| row[index].to_lowercase().contains(&filter_value));
+        }
+
+        if let Some(aggregate) = string_arg("aggregate") {
+            let aggregate = aggregate.to_lowercase();
+            if aggregate == "count" {
+                return Ok(json!({ "aggregate": aggregate, "value": rows.len(), "rows_matched": rows.len() }));
+            }
+
+            let aggregate_column = string_arg("aggregate_column").ok_or_else(|| {
+                ToolError::InvalidFunctionArguments(format!("aggregate_column is required to {}", aggregate))
+            })?;
+            let index = column(aggregate_column)?;
+            let numbers: Vec<f64> = rows
+                .iter()
+                .filter_map(|row| row[index].replace(',', "").trim().parse::<f64>().ok())
+                .collect();
+
+            let value = match aggregate.as_str() {
+                "sum" => Some(numbers.iter().sum::<f64>()),
+                "avg" => (!numbers.is_empty()).then(|| numbers.iter().sum::<f64>() / numbers.len() as f64),
+                "min" => numbers.iter().cloned().reduce(f64::min),
+                "max" => numbers.iter().cloned().reduce(f64::max),
+                _ => {
+                    return Err(ToolError::InvalidFunctionArguments(format!(
+                        "Unknown aggregate {}, expected count, sum, avg, min or max",
+                        aggregate
+                    )))
+                }
+            };
+            return Ok(json!({
+                "aggregate": aggregate,
+                "column": table.headers[index],
+                "value": value,
+                "rows_matched": rows.len(),
+            }));
+        }
+
+        let indexes: Vec<usize> = match string_arg("columns") {
+            Some(columns) => columns.split(',').map(column).collect::<Result<_, _>>()?,
+            None => (0..table.headers.len()).collect(),
+        };
+        let limit = args
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|limit| limit as usize)
+            .unwrap_or(DEFAULT_ROW_LIMIT);
+
+        Ok(json!({
+            "headers": indexes.iter().map(|i| &table.headers[*i]).collect::<Vec<_>>(),
+            "rows": rows
+                .iter()
+                .take(limit)
+                .map(|row| indexes.iter().map(|i| &row[*i]).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            "rows_matched": rows.len(),
+        }))
+    }
+}
+
+#[async_trait]
+impl NativeTool for TableQueryTool {
+    fn definition(&self) -> RustTool {
+        let optional_string = |name: &str, description: &str| {
+            ToolArgument::new(name.to_string(), "string".to_string(), description.to_string(), false)
+        };
+        RustTool::new(
+            Self::NAME.to_string(),
+            "Queries a table from the documents (in markdown) by filtering its rows, selecting columns or \
+             computing an aggregate (count, sum, avg, min, max) over a column."
+                .to_string(),
+            vec![
+                ToolArgument::new(
+                    "table".to_string(),
+                    "string".to_string(),
+                    "The table in markdown, as it appears in the documents".to_string(),
+                    true,
+                ),
+                optional_string("columns", "Comma separated columns to return. All by default"),
+                optional_string("filter_column", "Column to filter the rows by"),
+                optional_string("filter_value", "Rows whose filter_column contains this value are kept"),
+                optional_string("aggregate", "count, sum, avg, min or max"),
+                optional_string("aggregate_column", "Column to compute the aggregate over"),
+                ToolArgument::new(
+                    "limit".to_string(),
+                    "integer".to_string(),
+                    "Maximum rows to return. 50 by default".to_string(),
+                    false,
+                ),
+            ],
+            None,
+        )
+    }
+
+    async fn run(&self, args: serde_json::Map<String, Value>) -> Result<Value, ToolError> {
+        let markdown = args.get("table").and_then(|v| v.as_str()).unwrap_or_default();
+        let table = ParsedTable::from_markdown(markdown).ok_or_else(|| {
+            ToolError::InvalidFunctionArguments("table must be a markdown table with headers".to_string())
+        })?;
+        Self::query(&table, &args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(value: Value) -> serde_json::Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_table_query_filters_and_aggregates() {
+        let table = ParsedTable::from_markdown(
+            "| Region | Product | Sales |\n|---|---|---|\n| North | Apples | 1,200 |\n| South | Apples | 800 |\n\
+             | North | Pears | 300 |",
+        )
+        .unwrap();
+
+        let result = TableQueryTool::query(
+            &table,
+            &args(json!({ "filter_column": "region", "filter_value": "north", "columns": "Product" })),
+        )
+        .unwrap();
+        assert_eq!(result["rows"], json!([["Apples"], ["Pears"]]));
+
+        let result = TableQueryTool::query(
+            &table,
+            &args(json!({
+                "filter_column": "Product",
+                "filter_value": "apples",
+                "aggregate": "sum",
+                "aggregate_column": "Sales",
+            })),
+        )
+        .unwrap();
+        assert_eq!(result["value"], json!(2000.0));
+        assert_eq!(result["rows_matched"], json!(2));
+
+        assert!(TableQueryTool::query(&table, &args(json!({ "columns": "Price" }))).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use std::env;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::tools::error::ToolError;
+use crate::tools::js_toolkit_executor::JSRuntimeLimits;
+
+/// Limits applied to every invocation of a JS or WASM tool. They are configured per toolkit and
+/// default to JS_TOOLS_TIMEOUT_SECS, JS_TOOLS_MAX_HEAP_MB (if set) and TOOLS_MAX_OUTPUT_KB (default 1024).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ToolExecutionLimits {
+    /// Wall-clock time a single invocation can take
+    pub timeout_secs: u64,
+    /// Max size of the JSON the tool returns
+    pub max_output_bytes: usize,
+    /// Max memory (JS heap or WASM linear memory) the tool can use. `None` leaves it to the runtime default
+    /// (256 MB for the embedded JS runtime and WASM, no cap for the tools runner).
+    pub max_memory_mb: Option<u64>,
+}
+
+impl Default for ToolExecutionLimits {
+    fn default() -> Self {
+        let js_limits = JSRuntimeLimits::from_env();
+        let max_memory_mb = env::var("JS_TOOLS_MAX_HEAP_MB")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok());
+        let max_output_kb = env::var("TOOLS_MAX_OUTPUT_KB")
+            .ok()
+            .and_then(|value| value.parse::<usize>().ok())
+            .unwrap_or(1024);
+
+        ToolExecutionLimits {
+            timeout_secs: js_limits.timeout.as_secs(),
+            max_output_bytes: max_output_kb * 1024,
+            max_memory_mb,
+        }
+    }
+}
+
+impl ToolExecutionLimits {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+
+    pub fn max_memory_bytes(&self) -> usize {
+        match self.max_memory_mb {
+            Some(max_memory_mb) => max_memory_mb as usize * 1024 * 1024,
+            None => JSRuntimeLimits::from_env().max_heap_bytes,
+        }
+    }
+
+    /// Fails if a memory cap is configured, for runtimes that have no way to apply it
+    pub fn check_memory_cap_unsupported(&self, tool_name: &str, runtime: &str) -> Result<(), ToolError> {
+        match self.max_memory_mb {
+            Some(max_memory_mb) => Err(ToolError::ToolNotRunnable(format!(
+                "{} has a {} MB memory cap, which the {} can't enforce",
+                tool_name, max_memory_mb, runtime
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    pub fn js_runtime_limits(&self) -> JSRuntimeLimits {
+        JSRuntimeLimits {
+            max_heap_bytes: self.max_memory_bytes(),
+            timeout: self.timeout(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ToolError> {
+        if self.timeout_secs == 0 || self.max_output_bytes == 0 || self.max_memory_mb == Some(0) {
+            return Err(ToolError::InvalidFunctionArguments(
+                "Tool execution limits must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn timeout_error(&self, tool_name: &str) -> ToolError {
+        ToolError::ExecutionTimeout(format!(
+            "{} didn't finish within {} seconds",
+            tool_name, self.timeout_secs
+        ))
+    }
+
+    /// Fails if the serialized output is bigger than allowed
+    pub fn check_output(&self, tool_name: &str, output: &JsonValue) -> Result<(), ToolError> {
+        let size = serde_json::to_vec(output)
+            .map_err(|e| ToolError::SerializationError(e.to_string()))?
+            .len();
+        self.check_output_size(tool_name, size)
+    }
+
+    pub fn check_output_size(&self, tool_name: &str, size: usize) -> Result<(), ToolError> {
+        if size > self.max_output_bytes {
+            return Err(ToolError::OutputTooLarge(format!(
+                "{} returned {} bytes but the limit is {} bytes",
+                tool_name, size, self.max_output_bytes
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_check_output() {
+        let limits = ToolExecutionLimits {
+            timeout_secs: 10,
+            max_output_bytes: 16,
+            max_memory_mb: Some(64),
+        };
+
+        assert!(limits.check_output("echo", &json!({"a": 1})).is_ok());
+        assert!(matches!(
+            limits.check_output("echo", &json!({"text": "way more than sixteen bytes"})),
+            Err(ToolError::OutputTooLarge(_))
+        ));
+        assert!(ToolExecutionLimits {
+            timeout_secs: 0,
+            ..limits
+        }
+        .validate()
+        .is_err());
+
+        assert!(limits.check_memory_cap_unsupported("echo", "tools runner").is_err());
+        assert!(ToolExecutionLimits {
+            max_memory_mb: None,
+            ..limits
+        }
+        .check_memory_cap_unsupported("echo", "tools runner")
+        .is_ok());
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::schemas::payment_invoice::{Invoice, InvoiceReference, InvoiceStatus};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::APICreateInvoice;
+
+use crate::db::ShinkaiDB;
+use crate::payments::invoices::InvoiceManager;
+use crate::tools::error::ToolError;
+use crate::tools::shinkai_tool::ShinkaiTool;
+
+lazy_static! {
+    /// Price of the invocations that passed `authorize` but aren't recorded yet, per consumer and asset. The allowance
+    /// is checked and the invocation reserved under this lock, so concurrent calls can't spend the same allowance.
+    static ref TOOL_USAGE_RESERVATIONS: Mutex<HashMap<(String, String), Decimal>> = Mutex::new(HashMap::new());
+}
+
+/// Price the author of a toolkit charges for each invocation of its tools
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolPricing {
+    pub price_per_invocation: Decimal,
+    pub asset: String,
+    /// Address the usage invoices are paid to
+    #[serde(default)]
+    pub pay_to: Option<String>,
+}
+
+impl ToolPricing {
+    pub fn validate(&self) -> Result<(), ToolError> {
+        if self.price_per_invocation <= Decimal::ZERO || self.asset.trim().is_empty() {
+            return Err(ToolError::InvalidFunctionArguments(
+                "A toolkit price needs a positive price per invocation and an asset".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Paid invocation of a tool, billed once it's part of an invoice
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolUsageRecord {
+    pub usage_id: String,
+    /// Profile the tool ran for
+    pub consumer: String,
+    pub toolkit_name: String,
+    pub tool_name: String,
+    pub price: Decimal,
+    pub asset: String,
+    pub pay_to: Option<String>,
+    pub invoked_at: DateTime<Utc>,
+    pub invoice_id: Option<String>,
+}
+
+/// How much a consumer can owe in an asset, counting what isn't billed yet and the unpaid usage invoices
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolUsageAllowance {
+    pub consumer: String,
+    pub asset: String,
+    pub allowance: Decimal,
+}
+
+/// What a consumer owes in an asset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolUsageSummary {
+    pub consumer: String,
+    pub asset: String,
+    pub unbilled_invocations: usize,
+    pub unbilled: Decimal,
+    pub unpaid_invoices: Decimal,
+    pub allowance: Decimal,
+}
+
+impl ToolUsageSummary {
+    /// Fails if one more invocation at `price` would take the consumer over its allowance
+    pub fn check(&self, price: Decimal) -> Result<(), ToolError> {
+        if self.unbilled + self.unpaid_invoices + price > self.allowance {
+            return Err(ToolError::AllowanceExhausted(format!(
+                "{} owes {} {} of its {} allowance",
+                self.consumer,
+                self.unbilled + self.unpaid_invoices,
+                self.asset,
+                self.allowance
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Allowance reserved for one invocation of a priced tool. Dropping it without recording the usage releases the
+/// reservation, so calls that fail aren't counted against the allowance.
+#[derive(Debug)]
+pub struct ToolUsageReservation {
+    pub consumer: String,
+    pub pricing: ToolPricing,
+}
+
+impl ToolUsageReservation {
+    fn key(&self) -> (String, String) {
+        (self.consumer.clone(), self.pricing.asset.to_uppercase())
+    }
+}
+
+impl Drop for ToolUsageReservation {
+    fn drop(&mut self) {
+        let key = self.key();
+        let mut reservations = TOOL_USAGE_RESERVATIONS.lock().unwrap();
+        if let Some(reserved) = reservations.get_mut(&key) {
+            *reserved -= self.pricing.price_per_invocation;
+            if *reserved <= Decimal::ZERO {
+                reservations.remove(&key);
+            }
+        }
+    }
+}
+
+/// Meters the invocations of priced toolkits and bills them to the profiles that used them
+pub struct ToolUsageMeter;
+
+impl ToolUsageMeter {
+    /// Allowance of the consumers none was set for, from TOOL_USAGE_DEFAULT_ALLOWANCE (0 if not set)
+    pub fn default_allowance() -> Decimal {
+        env::var("TOOL_USAGE_DEFAULT_ALLOWANCE")
+            .ok()
+            .and_then(|value| value.parse::<Decimal>().ok())
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    pub fn summary(db: &ShinkaiDB, consumer: &str, asset: &str) -> Result<ToolUsageSummary, ToolError> {
+        let unbilled: Vec<ToolUsageRecord> = db
+            .get_all_tool_usage_records()
+            .map_err(|e| ToolError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .filter(|record| {
+                record.invoice_id.is_none() && record.consumer == consumer && record.asset.eq_ignore_ascii_case(asset)
+            })
+            .collect();
+        let unpaid_invoices = db
+            .get_all_invoices()
+            .map_err(|e| ToolError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .filter(|invoice| {
+                invoice.status == InvoiceStatus::Pending
+                    && invoice.payer == consumer
+                    && invoice.asset.eq_ignore_ascii_case(asset)
+                    && matches!(invoice.reference, Some(InvoiceReference::ToolUsage { .. }))
+            })
+            .map(|invoice| invoice.amount)
+            .sum();
+        let allowance = db
+            .get_tool_usage_allowance(consumer, asset)
+            .map_err(|e| ToolError::DatabaseError(e.to_string()))?
+            .map_or_else(Self::default_allowance, |allowance| allowance.allowance);
+
+        Ok(ToolUsageSummary {
+            consumer: consumer.to_string(),
+            asset: asset.to_string(),
+            unbilled_invocations: unbilled.len(),
+            unbilled: unbilled.iter().map(|record| record.price).sum(),
+            unpaid_invoices,
+            allowance,
+        })
+    }
+
+    /// Checks the consumer can afford one more invocation of the tool, counting the invocations still running, and
+    /// reserves it. Returns `None` if the toolkit is free.
+    pub fn authorize(
+        db: &ShinkaiDB,
+        consumer: &str,
+        tool: &ShinkaiTool,
+    ) -> Result<Option<ToolUsageReservation>, ToolError> {
+        let pricing = match db
+            .get_toolkit_pricing(&tool.toolkit_type_name())
+            .map_err(|e| ToolError::DatabaseError(e.to_string()))?
+        {
+            Some(pricing) => pricing,
+            None => return Ok(None),
+        };
+
+        let key = (consumer.to_string(), pricing.asset.to_uppercase());
+        let mut reservations = TOOL_USAGE_RESERVATIONS.lock().unwrap();
+        let reserved = reservations.get(&key).copied().unwrap_or(Decimal::ZERO);
+        Self::summary(db, consumer, &pricing.asset)?.check(reserved + pricing.price_per_invocation)?;
+        *reservations.entry(key).or_insert(Decimal::ZERO) += pricing.price_per_invocation;
+
+        Ok(Some(ToolUsageReservation {
+            consumer: consumer.to_string(),
+            pricing,
+        }))
+    }
+
+    /// Records the usage of a successful invocation, which takes the place of its reservation
+    pub fn record(db: &ShinkaiDB, tool: &ShinkaiTool, reservation: ToolUsageReservation) -> Result<(), ToolError> {
+        let pricing = &reservation.pricing;
+        let record = ToolUsageRecord {
+            usage_id: uuid::Uuid::new_v4().to_string(),
+            consumer: reservation.consumer.clone(),
+            toolkit_name: tool.toolkit_type_name(),
+            tool_name: tool.name(),
+            price: pricing.price_per_invocation,
+            asset: pricing.asset.clone(),
+            pay_to: pricing.pay_to.clone(),
+            invoked_at: Utc::now(),
+            invoice_id: None,
+        };
+        db.set_tool_usage_record(&record)
+            .map_err(|e| ToolError::DatabaseError(e.to_string()))
+    }
+
+    /// Turns the usage not billed yet into one invoice per consumer, toolkit and asset
+    pub fn bill(db: &ShinkaiDB, node_name: &ShinkaiName) -> Result<Vec<Invoice>, ToolError> {
+        let mut groups: HashMap<(String, String, String, Option<String>), Vec<ToolUsageRecord>> = HashMap::new();
+        for record in db
+            .get_all_tool_usage_records()
+            .map_err(|e| ToolError::DatabaseError(e.to_string()))?
+        {
+            if record.invoice_id.is_none() {
+                let key = (
+                    record.consumer.clone(),
+                    record.toolkit_name.clone(),
+                    record.asset.to_uppercase(),
+                    record.pay_to.clone(),
+                );
+                groups.entry(key).or_default().push(record);
+            }
+        }
+
+        let mut invoices = Vec::new();
+        for ((consumer, toolkit_name, asset, pay_to), records) in groups {
+            let request = APICreateInvoice {
+                payer: consumer,
+                amount: records.iter().map(|record| record.price).sum(),
+                asset,
+                memo: Some(format!("{} invocations of toolkit {}", records.len(), toolkit_name)),
+                reference: Some(InvoiceReference::ToolUsage { tool_key: toolkit_name }),
+                pay_to,
+                expires_in_secs: None,
+            };
+            let invoice = InvoiceManager::create_invoice(db, node_name, request)
+                .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+            for mut record in records {
+                record.invoice_id = Some(invoice.invoice_id.clone());
+                db.set_tool_usage_record(&record)
+                    .map_err(|e| ToolError::DatabaseError(e.to_string()))?;
+            }
+            invoices.push(invoice);
+        }
+        Ok(invoices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_usage_allowance() {
+        let summary = ToolUsageSummary {
+            consumer: "@@node1.shinkai/main".to_string(),
+            asset: "USDC".to_string(),
+            unbilled_invocations: 3,
+            unbilled: Decimal::new(3, 1),
+            unpaid_invoices: Decimal::new(5, 1),
+            allowance: Decimal::new(1, 0),
+        };
+        assert!(summary.check(Decimal::new(2, 1)).is_ok());
+        assert!(matches!(
+            summary.check(Decimal::new(3, 1)),
+            Err(ToolError::AllowanceExhausted(_))
+        ));
+
+        let pricing = ToolPricing {
+            price_per_invocation: Decimal::ZERO,
+            asset: "USDC".to_string(),
+            pay_to: None,
+        };
+        assert!(pricing.validate().is_err());
+    }
+}
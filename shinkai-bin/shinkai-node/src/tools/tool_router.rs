@@ -12,6 +12,7 @@ use crate::llm_provider::execution::chains::inference_chain_trait::InferenceChai
 use crate::llm_provider::providers::shared::openai::{FunctionCall, FunctionCallResponse};
 use crate::tools::error::ToolError;
 use crate::tools::shinkai_tool::ShinkaiTool;
+use crate::tools::tool_pricing::ToolUsageMeter;
 use crate::tools::workflow_tool::WorkflowTool;
 use crate::workflows::sm_executor::AsyncFunction;
 use serde_json::Value;
@@ -22,9 +23,11 @@ use shinkai_vector_resources::model_type::{EmbeddingModelType, OllamaTextEmbeddi
 use tokio::sync::Mutex;
 
 use super::js_toolkit::JSToolkit;
+use super::native_tool::{register_built_in_native_tools, NativeTool, NativeToolContext, NATIVE_TOOL_REGISTRY};
 use super::rust_tools::RustTool;
 use super::shinkai_tool::ShinkaiToolHeader;
 use super::tool_router_dep::workflows_data;
+use super::wasm_tools::WasmToolHostContext;
 use crate::llm_provider::execution::chains::inference_chain_trait::InferenceChain;
 
 #[derive(Clone)]
@@ -57,9 +60,36 @@ impl ToolRouter {
             let _ = self.add_js_tools().await;
         }
 
+        // Native tools are registered in memory on every start so we always re-index them
+        let _ = self.add_native_tools().await;
+
+        Ok(())
+    }
+
+    async fn add_native_tools(&self) -> Result<(), ToolError> {
+        register_built_in_native_tools();
+
+        let lance_db = self.lance_db.lock().await;
+        for rust_tool in NATIVE_TOOL_REGISTRY.definitions() {
+            let shinkai_tool = ShinkaiTool::Rust(rust_tool, true);
+            lance_db.set_tool(&shinkai_tool).await?;
+        }
         Ok(())
     }
 
+    /// Registers a native Rust tool and adds it to the tool router so it's available right away
+    pub async fn register_native_tool(&self, tool: Arc<dyn NativeTool>) -> Result<ShinkaiTool, ToolError> {
+        let rust_tool = NATIVE_TOOL_REGISTRY.register(tool)?;
+        let shinkai_tool = ShinkaiTool::Rust(rust_tool, true);
+
+        let lance_db = self.lance_db.lock().await;
+        if let Err(e) = lance_db.set_tool(&shinkai_tool).await {
+            NATIVE_TOOL_REGISTRY.unregister(&shinkai_tool.name());
+            return Err(e);
+        }
+        Ok(shinkai_tool)
+    }
+
     async fn add_static_workflows(&self, generator: Box<dyn EmbeddingGenerator>) -> Result<(), ToolError> {
         let lance_db = self.lance_db.lock().await;
         let model_type = generator.model_type();
@@ -197,8 +227,40 @@ impl ToolRouter {
         let function_name = function_call.name.clone();
         let function_args = function_call.arguments.clone();
 
+        shinkai_tool
+            .check_capabilities(&context.db())
+            .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
+        let consumer = context.user_profile().to_string();
+        let reservation = ToolUsageMeter::authorize(&context.db(), &consumer, shinkai_tool)
+            .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
+
         match shinkai_tool {
             ShinkaiTool::Rust(_, _) => {
+                if NATIVE_TOOL_REGISTRY.contains(&function_name) {
+                    let native_context = NativeToolContext {
+                        profile: Some(context.user_profile().clone()),
+                        db: Some(context.db()),
+                        vector_fs: Some(context.vector_fs()),
+                        generator: Some(context.generator().clone()),
+                    };
+                    let result = NATIVE_TOOL_REGISTRY
+                        .call(&function_name, function_args, native_context)
+                        .await
+                        .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
+                    if let Some(reservation) = reservation {
+                        ToolUsageMeter::record(&context.db(), shinkai_tool, reservation)
+                            .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
+                    }
+                    let result_str = match result {
+                        Value::String(s) => s,
+                        _ => serde_json::to_string(&result)
+                            .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?,
+                    };
+                    return Ok(FunctionCallResponse {
+                        response: result_str,
+                        function_call,
+                    });
+                }
                 if let Some(rust_function) = RustToolFunctions::get_tool_function(&function_name) {
                     let args: Vec<Box<dyn Any + Send>> = RustTool::convert_args_from_fn_call(function_args)?;
                     let result = rust_function(context, args)
@@ -209,6 +271,10 @@ impl ToolRouter {
                             LLMProviderError::InvalidFunctionResult(format!("Invalid result: {:?}", result))
                         })?
                         .clone();
+                    if let Some(reservation) = reservation {
+                        ToolUsageMeter::record(&context.db(), shinkai_tool, reservation)
+                            .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
+                    }
                     return Ok(FunctionCallResponse {
                         response: result_str,
                         function_call,
@@ -217,9 +283,39 @@ impl ToolRouter {
             }
             ShinkaiTool::JS(js_tool, _) => {
                 let function_config = shinkai_tool.get_config_from_env();
+                let limits = shinkai_tool
+                    .execution_limits(&context.db())
+                    .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
                 let result = js_tool
-                    .run(function_args, function_config)
+                    .run(function_args, function_config, &limits)
+                    .map_err(tool_execution_error)?;
+                if let Some(reservation) = reservation {
+                    ToolUsageMeter::record(&context.db(), shinkai_tool, reservation)
+                        .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
+                }
+                let result_str = serde_json::to_string(&result)
+                    .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
+                return Ok(FunctionCallResponse {
+                    response: result_str,
+                    function_call,
+                });
+            }
+            ShinkaiTool::Wasm(wasm_tool, _) => {
+                let host_context = WasmToolHostContext {
+                    vector_fs: Some(context.vector_fs()),
+                    profile: Some(context.user_profile().clone()),
+                };
+                let limits = shinkai_tool
+                    .execution_limits(&context.db())
                     .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
+                let result = wasm_tool
+                    .run(function_args, host_context, &limits)
+                    .await
+                    .map_err(tool_execution_error)?;
+                if let Some(reservation) = reservation {
+                    ToolUsageMeter::record(&context.db(), shinkai_tool, reservation)
+                        .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
+                }
                 let result_str = serde_json::to_string(&result)
                     .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
                 return Ok(FunctionCallResponse {
@@ -250,6 +346,10 @@ impl ToolRouter {
                 dsl_inference.add_tools_from_router(tools).await?;
 
                 let inference_result = dsl_inference.run_chain().await?;
+                if let Some(reservation) = reservation {
+                    ToolUsageMeter::record(&context.db(), shinkai_tool, reservation)
+                        .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
+                }
 
                 return Ok(FunctionCallResponse {
                     response: inference_result.response,
@@ -262,6 +362,15 @@ impl ToolRouter {
     }
 }
 
+/// Keeps timeouts and oversized outputs as their own errors so they are reported as such in the job inbox
+fn tool_execution_error(err: ToolError) -> LLMProviderError {
+    match err {
+        ToolError::ExecutionTimeout(e) => LLMProviderError::ToolTimedOut(e),
+        ToolError::OutputTooLarge(e) => LLMProviderError::ToolOutputTooLarge(e),
+        e => LLMProviderError::FunctionExecutionError(e.to_string()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -0,0 +1,271 @@
+use std::env;
+
+use ed25519_dalek::{Signature, Verifier};
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::shinkai_utils::signatures::string_to_signature_public_key;
+use shinkai_tools_runner::tools::tool_definition::ToolDefinition;
+
+use crate::tools::error::ToolError;
+use crate::tools::js_toolkit::JSToolkit;
+use crate::tools::shinkai_tool::ShinkaiTool;
+use crate::tools::wasm_tools::{WasmTool, WasmToolPermissions};
+
+/// Max size of a downloaded toolkit package
+pub const MAX_TOOLKIT_PACKAGE_SIZE: usize = 50 * 1024 * 1024;
+
+/// A toolkit package as published by its author.
+///
+/// `manifest` is kept as the raw JSON string that was signed so the signature can be verified
+/// without depending on how the manifest gets re-serialized.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedToolkitPackage {
+    pub manifest: String,
+    /// Hex encoded ed25519 public key of the publisher
+    pub public_key: String,
+    /// Hex encoded ed25519 signature of `manifest`
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "content")]
+pub enum ToolkitPackageContent {
+    JS(ToolDefinition),
+    Wasm(Vec<WasmTool>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolkitManifest {
+    pub name: String,
+    pub version: String,
+    pub author: String,
+    /// Everything the toolkit's tools will be allowed to do once installed
+    #[serde(default)]
+    pub permissions: WasmToolPermissions,
+    pub content: ToolkitPackageContent,
+}
+
+/// Where an installed toolkit came from, stored in the DB at install time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolkitProvenance {
+    pub toolkit_name: String,
+    pub version: String,
+    pub author: String,
+    pub source_url: String,
+    pub publisher_public_key: String,
+    /// blake3 hash of the downloaded package
+    pub package_hash: String,
+    pub permissions: WasmToolPermissions,
+    pub tool_router_keys: Vec<String>,
+    pub installed_at: String,
+}
+
+impl SignedToolkitPackage {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ToolError> {
+        if bytes.len() > MAX_TOOLKIT_PACKAGE_SIZE {
+            return Err(ToolError::ParseError(format!(
+                "Toolkit package is bigger than {} bytes",
+                MAX_TOOLKIT_PACKAGE_SIZE
+            )));
+        }
+        let package: Self = serde_json::from_slice(bytes)?;
+        Ok(package)
+    }
+
+    /// Publisher keys toolkits can be installed from, read from TOOLKIT_TRUSTED_PUBLISHER_KEYS (comma separated
+    /// hex encoded ed25519 public keys)
+    pub fn trusted_publisher_keys() -> Vec<String> {
+        env::var("TOOLKIT_TRUSTED_PUBLISHER_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|key| key.trim().to_lowercase())
+            .filter(|key| !key.is_empty())
+            .collect()
+    }
+
+    /// Verifies that the manifest was signed by one of the trusted publishers and, if provided, by the expected
+    /// publisher. The key inside the package is only trusted if it's one of them.
+    pub fn verify(
+        &self,
+        trusted_keys: &[String],
+        expected_public_key: Option<&str>,
+    ) -> Result<ToolkitManifest, ToolError> {
+        let public_key = self.public_key.trim().to_lowercase();
+        if let Some(expected) = expected_public_key {
+            if expected.trim().to_lowercase() != public_key {
+                return Err(ToolError::InvalidProfile(
+                    "Toolkit package was not signed by the expected publisher".to_string(),
+                ));
+            }
+        }
+        if !trusted_keys.iter().any(|key| key.trim().to_lowercase() == public_key) {
+            return Err(ToolError::InvalidProfile(
+                "Toolkit publisher is not in the list of trusted publishers".to_string(),
+            ));
+        }
+
+        let verifying_key =
+            string_to_signature_public_key(&public_key).map_err(|e| ToolError::ParseError(e.to_string()))?;
+        let signature_bytes =
+            hex::decode(self.signature.trim()).map_err(|e| ToolError::ParseError(format!("signature: {}", e)))?;
+        let signature =
+            Signature::from_slice(&signature_bytes).map_err(|e| ToolError::ParseError(format!("signature: {}", e)))?;
+        verifying_key
+            .verify(self.manifest.as_bytes(), &signature)
+            .map_err(|_| ToolError::InvalidProfile("Invalid toolkit package signature".to_string()))?;
+
+        let manifest: ToolkitManifest = serde_json::from_str(&self.manifest)?;
+        Ok(manifest)
+    }
+}
+
+impl ToolkitManifest {
+    /// Checks that the permissions the toolkit declares are covered by what the user accepted,
+    /// and that no WASM tool asks for more than the toolkit declares.
+    pub fn check_permissions(&self, accepted: &WasmToolPermissions) -> Result<(), ToolError> {
+        Self::check_subset(&self.permissions, accepted).map_err(|e| {
+            ToolError::InvalidProfile(format!("Toolkit requires permissions that weren't granted: {}", e))
+        })?;
+
+        if let ToolkitPackageContent::Wasm(tools) = &self.content {
+            for tool in tools {
+                Self::check_subset(&tool.permissions, &self.permissions).map_err(|e| {
+                    ToolError::InvalidProfile(format!(
+                        "Tool {} requires permissions the toolkit doesn't declare: {}",
+                        tool.name, e
+                    ))
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_subset(requested: &WasmToolPermissions, granted: &WasmToolPermissions) -> Result<(), String> {
+        for host in &requested.network_allowed_hosts {
+            let covered = if host == "*" {
+                granted.network_allowed_hosts.iter().any(|h| h == "*")
+            } else {
+                granted.allows_host(host)
+            };
+            if !covered {
+                return Err(format!("network access to {}", host));
+            }
+        }
+        for path in &requested.vector_fs_read {
+            if !granted.allows_vector_fs_path(path) {
+                return Err(format!("read access to {}", path));
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts the manifest into the tools that get added to the tool router
+    pub fn into_tools(self) -> Result<Vec<ShinkaiTool>, ToolError> {
+        match self.content {
+            ToolkitPackageContent::JS(definition) => {
+                if definition.code.is_none() {
+                    return Err(ToolError::ParseError(
+                        "Tool definition is missing the code field".to_string(),
+                    ));
+                }
+                let toolkit = JSToolkit::new(&self.name, vec![definition]);
+                Ok(toolkit
+                    .tools
+                    .into_iter()
+                    .map(|mut tool| {
+                        tool.author = self.author.clone();
+                        tool.capabilities = self.permissions.to_capabilities();
+                        ShinkaiTool::JS(tool, true)
+                    })
+                    .collect())
+            }
+            ToolkitPackageContent::Wasm(tools) => tools
+                .into_iter()
+                .map(|mut tool| {
+                    tool.toolkit_name = self.name.clone();
+                    tool.author = self.author.clone();
+                    tool.validate()?;
+                    Ok(ShinkaiTool::Wasm(tool, true))
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::Signer;
+    use shinkai_message_primitives::shinkai_utils::signatures::{
+        signature_public_key_to_string, unsafe_deterministic_signature_keypair,
+    };
+
+    fn wasm_manifest(permissions: WasmToolPermissions, tool_permissions: WasmToolPermissions) -> ToolkitManifest {
+        ToolkitManifest {
+            name: "weather".to_string(),
+            version: "1.0.0".to_string(),
+            author: "@@alice.shinkai".to_string(),
+            permissions,
+            content: ToolkitPackageContent::Wasm(vec![WasmTool {
+                toolkit_name: "weather".to_string(),
+                name: "get_weather".to_string(),
+                author: "@@alice.shinkai".to_string(),
+                description: "Gets the weather".to_string(),
+                input_args: vec![],
+                wasm_code: String::new(),
+                permissions: tool_permissions,
+                embedding: None,
+            }]),
+        }
+    }
+
+    #[test]
+    fn test_verify_signed_package() {
+        let (signing_key, verifying_key) = unsafe_deterministic_signature_keypair(0);
+        let manifest = serde_json::to_string(&wasm_manifest(Default::default(), Default::default())).unwrap();
+        let signature = signing_key.sign(manifest.as_bytes());
+        let public_key = signature_public_key_to_string(verifying_key);
+
+        let package = SignedToolkitPackage {
+            manifest: manifest.clone(),
+            public_key: public_key.clone(),
+            signature: hex::encode(signature.to_bytes()),
+        };
+        let trusted_keys = vec![public_key.clone()];
+        assert!(package.verify(&trusted_keys, Some(&public_key)).is_ok());
+        assert!(package.verify(&trusted_keys, None).is_ok());
+
+        // A valid signature isn't enough when the publisher isn't trusted
+        assert!(package.verify(&[], None).is_err());
+        let (_, other_key) = unsafe_deterministic_signature_keypair(1);
+        let other_public_key = signature_public_key_to_string(other_key);
+        assert!(package.verify(&[other_public_key.clone()], None).is_err());
+        assert!(package.verify(&trusted_keys, Some(&other_public_key)).is_err());
+
+        let tampered = SignedToolkitPackage {
+            manifest: manifest.replace("get_weather", "steal_files"),
+            ..package
+        };
+        assert!(tampered.verify(&trusted_keys, None).is_err());
+    }
+
+    #[test]
+    fn test_check_permissions() {
+        let declared = WasmToolPermissions {
+            network_allowed_hosts: vec!["api.weather.com".to_string()],
+            vector_fs_read: vec![],
+        };
+        let manifest = wasm_manifest(declared.clone(), declared.clone());
+
+        assert!(manifest.check_permissions(&declared).is_ok());
+        assert!(manifest.check_permissions(&WasmToolPermissions::default()).is_err());
+
+        let sneaky = wasm_manifest(
+            declared.clone(),
+            WasmToolPermissions {
+                network_allowed_hosts: vec!["*".to_string()],
+                vector_fs_read: vec![],
+            },
+        );
+        assert!(sneaky.check_permissions(&declared).is_err());
+    }
+}
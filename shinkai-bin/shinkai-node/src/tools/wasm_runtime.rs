@@ -0,0 +1,320 @@
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value as JsonValue};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use shinkai_vector_resources::vector_resource::VRPath;
+use tokio::runtime::Handle;
+use wasmtime::{
+    Caller, Config, Engine, Extern, Instance, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, Trap,
+    TypedFunc,
+};
+
+use crate::tools::error::ToolError;
+use crate::tools::tool_execution_limits::ToolExecutionLimits;
+use crate::tools::wasm_tools::{WasmToolHostContext, WasmToolPermissions};
+
+const HOST_MODULE: &str = "shinkai";
+const ALLOC_EXPORT: &str = "shinkai_alloc";
+const RUN_EXPORT: &str = "shinkai_run";
+const HTTP_TIMEOUT_SECS: u64 = 30;
+const MAX_HTTP_REDIRECTS: usize = 10;
+
+/// State available to the host functions while a WASM tool runs
+struct WasmHostState {
+    tool_name: String,
+    permissions: WasmToolPermissions,
+    host_context: WasmToolHostContext,
+    runtime_handle: Option<Handle>,
+    http_client: reqwest::blocking::Client,
+    store_limits: StoreLimits,
+}
+
+/// Compiles the module and checks it exports the functions the runtime calls
+pub fn validate_module(wasm_bytes: &[u8]) -> Result<(), ToolError> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, wasm_bytes).map_err(|e| ToolError::ParseError(e.to_string()))?;
+
+    for export in ["memory", ALLOC_EXPORT, RUN_EXPORT] {
+        if module.get_export(export).is_none() {
+            return Err(ToolError::ParseError(format!(
+                "WASM module is missing the `{}` export",
+                export
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Runs the tool to completion. This blocks the current thread, so it must not be called from an async context.
+pub fn run_module(
+    tool_name: &str,
+    wasm_bytes: &[u8],
+    input: &[u8],
+    permissions: WasmToolPermissions,
+    host_context: WasmToolHostContext,
+    runtime_handle: Option<Handle>,
+    limits: &ToolExecutionLimits,
+) -> Result<JsonValue, ToolError> {
+    // Epoch interruption lets the watchdog below stop a tool that runs past its timeout
+    let mut config = Config::new();
+    config.epoch_interruption(true);
+    let engine = Engine::new(&config).map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+    let module = Module::new(&engine, wasm_bytes).map_err(|e| ToolError::ParseError(e.to_string()))?;
+
+    // Every redirect hop is checked against the granted hosts, otherwise an allowed host could bounce
+    // the request to any other host
+    let redirect_permissions = permissions.clone();
+    let redirect_policy = reqwest::redirect::Policy::custom(move |attempt| {
+        let host = attempt.url().host_str().unwrap_or_default().to_string();
+        if attempt.previous().len() >= MAX_HTTP_REDIRECTS {
+            attempt.error("Too many redirects")
+        } else if !redirect_permissions.allows_host(&host) {
+            attempt.error(format!("Redirect to {} is not allowed for this tool", host))
+        } else {
+            attempt.follow()
+        }
+    });
+    let http_client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
+        .redirect(redirect_policy)
+        .build()
+        .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+    let state = WasmHostState {
+        tool_name: tool_name.to_string(),
+        permissions,
+        host_context,
+        runtime_handle,
+        http_client,
+        store_limits: StoreLimitsBuilder::new()
+            .memory_size(limits.max_memory_bytes())
+            .trap_on_grow_failure(true)
+            .build(),
+    };
+    let mut store = Store::new(&engine, state);
+    store.limiter(|state| &mut state.store_limits);
+    store.set_epoch_deadline(1);
+    let linker = build_linker(&engine)?;
+
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or_else(|| ToolError::ExecutionError("WASM module doesn't export its memory".to_string()))?;
+    let alloc = typed_export::<i32, i32>(&instance, &mut store, ALLOC_EXPORT)?;
+    let run = typed_export::<(i32, i32), i64>(&instance, &mut store, RUN_EXPORT)?;
+
+    let input_ptr = alloc
+        .call(&mut store, input.len() as i32)
+        .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+    memory
+        .write(&mut store, input_ptr as usize, input)
+        .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+    // The watchdog stops waiting as soon as `_finished` is dropped
+    let (_finished, finished_receiver) = mpsc::channel::<()>();
+    {
+        let engine = engine.clone();
+        let timeout = limits.timeout();
+        thread::spawn(move || {
+            if let Err(mpsc::RecvTimeoutError::Timeout) = finished_receiver.recv_timeout(timeout) {
+                engine.increment_epoch();
+            }
+        });
+    }
+
+    let packed = run
+        .call(&mut store, (input_ptr, input.len() as i32))
+        .map_err(|e| match e.downcast_ref::<Trap>() {
+            Some(Trap::Interrupt) => limits.timeout_error(tool_name),
+            _ => ToolError::ExecutionError(e.to_string()),
+        })?;
+    let (output_ptr, output_len) = unpack_ptr_len(packed);
+    limits.check_output_size(tool_name, output_len)?;
+    let output = memory
+        .data(&store)
+        .get(output_ptr..output_ptr + output_len)
+        .ok_or_else(|| ToolError::ExecutionError("WASM tool returned an out of bounds result".to_string()))?;
+
+    serde_json::from_slice(output).map_err(|e| ToolError::ParseError(format!("WASM tool result: {}", e)))
+}
+
+fn typed_export<Params, Results>(
+    instance: &Instance,
+    store: &mut Store<WasmHostState>,
+    name: &str,
+) -> Result<TypedFunc<Params, Results>, ToolError>
+where
+    Params: wasmtime::WasmParams,
+    Results: wasmtime::WasmResults,
+{
+    instance
+        .get_typed_func::<Params, Results>(store, name)
+        .map_err(|e| ToolError::ExecutionError(format!("Invalid `{}` export: {}", name, e)))
+}
+
+fn build_linker(engine: &Engine) -> Result<Linker<WasmHostState>, ToolError> {
+    let mut linker = Linker::new(engine);
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "log",
+            |mut caller: Caller<'_, WasmHostState>, ptr: i32, len: i32| -> wasmtime::Result<()> {
+                let message = read_guest_string(&mut caller, ptr, len)?;
+                shinkai_log(
+                    ShinkaiLogOption::JobExecution,
+                    ShinkaiLogLevel::Debug,
+                    &format!("WASM tool {}: {}", caller.data().tool_name, message),
+                );
+                Ok(())
+            },
+        )
+        .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "http_request",
+            |mut caller: Caller<'_, WasmHostState>, ptr: i32, len: i32| -> wasmtime::Result<i64> {
+                let request = read_guest_string(&mut caller, ptr, len)?;
+                let response = match http_request(caller.data(), &request) {
+                    Ok(value) => json!({ "ok": value }),
+                    Err(e) => json!({ "error": e }),
+                };
+                write_guest_json(&mut caller, &response)
+            },
+        )
+        .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "vector_fs_read",
+            |mut caller: Caller<'_, WasmHostState>, ptr: i32, len: i32| -> wasmtime::Result<i64> {
+                let path = read_guest_string(&mut caller, ptr, len)?;
+                let response = match vector_fs_read(caller.data(), &path) {
+                    Ok(value) => json!({ "ok": value }),
+                    Err(e) => json!({ "error": e }),
+                };
+                write_guest_json(&mut caller, &response)
+            },
+        )
+        .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+    Ok(linker)
+}
+
+/// Sends an HTTP request described as `{"method": "GET", "url": "...", "headers": {...}, "body": "..."}`
+fn http_request(state: &WasmHostState, request: &str) -> Result<JsonValue, String> {
+    let request: JsonValue = serde_json::from_str(request).map_err(|e| e.to_string())?;
+    let url = request
+        .get("url")
+        .and_then(|url| url.as_str())
+        .ok_or_else(|| "Missing url".to_string())?;
+    let url = reqwest::Url::parse(url).map_err(|e| e.to_string())?;
+    let host = url.host_str().unwrap_or_default();
+    if !state.permissions.allows_host(host) {
+        return Err(format!("Network access to {} is not allowed for this tool", host));
+    }
+
+    let method = request.get("method").and_then(|m| m.as_str()).unwrap_or("GET");
+    let method = reqwest::Method::from_str(&method.to_uppercase()).map_err(|e| e.to_string())?;
+    let mut builder = state.http_client.request(method, url);
+    if let Some(headers) = request.get("headers").and_then(|h| h.as_object()) {
+        for (key, value) in headers {
+            if let Some(value) = value.as_str() {
+                builder = builder.header(key.as_str(), value);
+            }
+        }
+    }
+    if let Some(body) = request.get("body").and_then(|b| b.as_str()) {
+        builder = builder.body(body.to_string());
+    }
+
+    let response = builder.send().map_err(|e| e.to_string())?;
+    let status = response.status().as_u16();
+    let body = response.text().map_err(|e| e.to_string())?;
+    Ok(json!({ "status": status, "body": body }))
+}
+
+/// Returns the text content of the VectorFS item at the path, as seen by the profile running the job
+fn vector_fs_read(state: &WasmHostState, path: &str) -> Result<JsonValue, String> {
+    if !state.permissions.allows_vector_fs_path(path) {
+        return Err(format!("Reading {} is not allowed for this tool", path));
+    }
+    let (Some(vector_fs), Some(profile), Some(handle)) = (
+        state.host_context.vector_fs.clone(),
+        state.host_context.profile.clone(),
+        state.runtime_handle.clone(),
+    ) else {
+        return Err("VectorFS is not available in this context".to_string());
+    };
+
+    let vr_path = VRPath::from_string(path).map_err(|e| e.to_string())?;
+    let resource = handle.block_on(async {
+        let reader = vector_fs
+            .new_reader(profile.clone(), vr_path, profile)
+            .await
+            .map_err(|e| e.to_string())?;
+        vector_fs
+            .retrieve_vector_resource(&reader)
+            .await
+            .map_err(|e| e.to_string())
+    })?;
+
+    let content = resource
+        .as_trait_object()
+        .get_all_nodes_flattened()
+        .iter()
+        .filter_map(|node| node.get_text_content().ok().map(|text| text.to_string()))
+        .collect::<Vec<String>>()
+        .join("\n");
+    Ok(json!({ "name": resource.as_trait_object().name(), "content": content }))
+}
+
+fn guest_memory(caller: &mut Caller<'_, WasmHostState>) -> wasmtime::Result<Memory> {
+    match caller.get_export("memory") {
+        Some(Extern::Memory(memory)) => Ok(memory),
+        _ => Err(wasmtime::Error::msg("WASM module doesn't export its memory")),
+    }
+}
+
+fn read_guest_string(caller: &mut Caller<'_, WasmHostState>, ptr: i32, len: i32) -> wasmtime::Result<String> {
+    let memory = guest_memory(caller)?;
+    // Guest pointers are u32 offsets, so a negative i32 is a large offset rather than an error
+    let start = ptr as u32 as usize;
+    let end = start
+        .checked_add(len as u32 as usize)
+        .ok_or_else(|| wasmtime::Error::msg("Out of bounds memory access"))?;
+    if end > memory.data_size(&caller) {
+        return Err(wasmtime::Error::msg("Out of bounds memory access"));
+    }
+    let bytes = &memory.data(&caller)[start..end];
+    Ok(String::from_utf8_lossy(bytes).to_string())
+}
+
+/// Copies the JSON into memory allocated by the guest and returns its packed pointer and length
+fn write_guest_json(caller: &mut Caller<'_, WasmHostState>, value: &JsonValue) -> wasmtime::Result<i64> {
+    let bytes = serde_json::to_vec(value)?;
+    let alloc = match caller.get_export(ALLOC_EXPORT) {
+        Some(Extern::Func(func)) => func.typed::<i32, i32>(&caller)?,
+        _ => return Err(wasmtime::Error::msg("WASM module doesn't export an allocator")),
+    };
+    let ptr = alloc.call(&mut *caller, bytes.len() as i32)?;
+    let memory = guest_memory(caller)?;
+    memory.write(&mut *caller, ptr as usize, &bytes)?;
+    Ok(pack_ptr_len(ptr as usize, bytes.len()))
+}
+
+fn pack_ptr_len(ptr: usize, len: usize) -> i64 {
+    (((ptr as u64) << 32) | (len as u64 & 0xFFFF_FFFF)) as i64
+}
+
+fn unpack_ptr_len(packed: i64) -> (usize, usize) {
+    let packed = packed as u64;
+    ((packed >> 32) as usize, (packed & 0xFFFF_FFFF) as usize)
+}
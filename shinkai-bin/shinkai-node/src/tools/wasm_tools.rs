@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use serde_json::Value as JsonValue;
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_vector_resources::embeddings::Embedding;
+
+use crate::tools::argument::ToolArgument;
+use crate::tools::error::ToolError;
+use crate::tools::js_toolkit_headers::ToolCapability;
+use crate::tools::tool_execution_limits::ToolExecutionLimits;
+use crate::vector_fs::vector_fs::VectorFS;
+
+/// Capabilities granted to a WASM tool. Everything that isn't explicitly granted is denied.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WasmToolPermissions {
+    /// Hosts the tool can send HTTP requests to. `*` grants access to any host.
+    #[serde(default)]
+    pub network_allowed_hosts: Vec<String>,
+    /// VectorFS paths (and everything below them) the tool can read from, e.g. `/My Files/reports`
+    #[serde(default)]
+    pub vector_fs_read: Vec<String>,
+}
+
+impl WasmToolPermissions {
+    pub fn allows_host(&self, host: &str) -> bool {
+        self.network_allowed_hosts
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(host))
+    }
+
+    pub fn allows_vector_fs_path(&self, path: &str) -> bool {
+        let path = path.trim_end_matches('/');
+        self.vector_fs_read.iter().any(|allowed| {
+            let allowed = allowed.trim_end_matches('/');
+            allowed.is_empty() || path == allowed || path.starts_with(&format!("{}/", allowed))
+        })
+    }
+
+    /// The capabilities a tool with these permissions needs granted before it can run
+    pub fn to_capabilities(&self) -> Vec<ToolCapability> {
+        self.network_allowed_hosts
+            .iter()
+            .map(|host| ToolCapability::Network(host.clone()))
+            .chain(
+                self.vector_fs_read
+                    .iter()
+                    .map(|path| ToolCapability::VectorFSRead(path.clone())),
+            )
+            .collect()
+    }
+}
+
+/// A tool compiled to WASM and uploaded by the user. It runs in-process in a wasmtime sandbox
+/// and can only reach the outside world through the host functions its permissions allow.
+///
+/// The module must export `memory`, `shinkai_alloc(len: i32) -> i32` and
+/// `shinkai_run(ptr: i32, len: i32) -> i64`, where the input is the JSON encoded arguments and the
+/// output is a JSON string whose pointer and length are packed in the returned i64 (`ptr << 32 | len`).
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WasmTool {
+    pub toolkit_name: String,
+    pub name: String,
+    pub author: String,
+    pub description: String,
+    pub input_args: Vec<ToolArgument>,
+    /// Base64 encoded WASM module
+    pub wasm_code: String,
+    #[serde(default)]
+    pub permissions: WasmToolPermissions,
+    pub embedding: Option<Embedding>,
+}
+
+/// What the host functions of a running WASM tool have access to
+#[derive(Clone)]
+pub struct WasmToolHostContext {
+    pub vector_fs: Option<Arc<VectorFS>>,
+    pub profile: Option<ShinkaiName>,
+}
+
+impl WasmTool {
+    pub fn wasm_bytes(&self) -> Result<Vec<u8>, ToolError> {
+        base64::decode(&self.wasm_code).map_err(|e| ToolError::ParseError(format!("Invalid WASM module: {}", e)))
+    }
+
+    /// Checks that the module compiles and exports what the runtime expects
+    pub fn validate(&self) -> Result<(), ToolError> {
+        let wasm_bytes = self.wasm_bytes()?;
+        #[cfg(feature = "wasm-tools")]
+        {
+            super::wasm_runtime::validate_module(&wasm_bytes)
+        }
+        #[cfg(not(feature = "wasm-tools"))]
+        {
+            let _ = wasm_bytes;
+            Err(Self::runtime_not_available(&self.name))
+        }
+    }
+
+    pub async fn run(
+        &self,
+        input_json: JsonValue,
+        host_context: WasmToolHostContext,
+        limits: &ToolExecutionLimits,
+    ) -> Result<JsonValue, ToolError> {
+        let input_json = ToolArgument::validate_args(&self.input_args, input_json)?;
+        let wasm_bytes = self.wasm_bytes()?;
+        let input = serde_json::to_vec(&input_json).map_err(|e| ToolError::SerializationError(e.to_string()))?;
+
+        #[cfg(feature = "wasm-tools")]
+        {
+            let tool_name = self.name.clone();
+            let permissions = self.permissions.clone();
+            let runtime_handle = tokio::runtime::Handle::try_current().ok();
+            let limits = *limits;
+
+            // Host functions block (HTTP, VectorFS), so the module runs on the blocking thread pool. The runtime
+            // interrupts the module once it runs past its timeout, the timeout here only covers the host functions.
+            let task = tokio::task::spawn_blocking(move || {
+                super::wasm_runtime::run_module(
+                    &tool_name,
+                    &wasm_bytes,
+                    &input,
+                    permissions,
+                    host_context,
+                    runtime_handle,
+                    &limits,
+                )
+            });
+            let output = tokio::time::timeout(limits.timeout(), task)
+                .await
+                .map_err(|_| limits.timeout_error(&self.name))?
+                .map_err(|_| ToolError::ExecutionError(format!("WASM tool {} panicked", self.name)))??;
+
+            limits.check_output(&self.name, &output)?;
+            Ok(output)
+        }
+        #[cfg(not(feature = "wasm-tools"))]
+        {
+            let _ = (wasm_bytes, input, host_context, limits);
+            Err(Self::runtime_not_available(&self.name))
+        }
+    }
+
+    #[cfg(not(feature = "wasm-tools"))]
+    fn runtime_not_available(name: &str) -> ToolError {
+        ToolError::ToolNotRunnable(format!(
+            "{} is a WASM tool but the node was built without the wasm-tools feature",
+            name
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wasm_tool_permissions() {
+        let permissions = WasmToolPermissions {
+            network_allowed_hosts: vec!["api.example.com".to_string()],
+            vector_fs_read: vec!["/My Files/reports/".to_string()],
+        };
+
+        assert!(permissions.allows_host("api.example.com"));
+        assert!(!permissions.allows_host("example.com"));
+        assert!(permissions.allows_vector_fs_path("/My Files/reports"));
+        assert!(permissions.allows_vector_fs_path("/My Files/reports/2024/q1.pdf"));
+        assert!(!permissions.allows_vector_fs_path("/My Files/reports-private"));
+        assert!(!WasmToolPermissions::default().allows_vector_fs_path("/My Files"));
+    }
+}
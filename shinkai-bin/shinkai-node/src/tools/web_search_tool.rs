@@ -0,0 +1,248 @@
+use std::env;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::network::outbound_proxy::OUTBOUND_PROXY;
+use crate::schemas::outbound_proxy::DestinationClass;
+use crate::tools::argument::ToolArgument;
+use crate::tools::error::ToolError;
+use crate::tools::js_toolkit_headers::ToolCapability;
+use crate::tools::native_tool::NativeTool;
+use crate::tools::rust_tools::RustTool;
+
+const DEFAULT_NUM_RESULTS: usize = 5;
+const MAX_NUM_RESULTS: usize = 20;
+const SEARCH_TIMEOUT_SECS: u64 = 30;
+
+/// A search result in the same shape for every provider. `index` is what chains cite, e.g. [1].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebSearchResult {
+    pub index: usize,
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+/// Search backends. Picked with WEB_SEARCH_PROVIDER (`searxng`, `brave` or `serper`) and configured with
+/// SEARXNG_URL, BRAVE_SEARCH_API_KEY or SERPER_API_KEY.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebSearchProvider {
+    SearxNG { base_url: String },
+    Brave { api_key: String },
+    Serper { api_key: String },
+}
+
+impl WebSearchProvider {
+    /// Returns None if no provider is configured
+    pub fn from_env() -> Option<Self> {
+        let provider = env::var("WEB_SEARCH_PROVIDER").ok()?.to_lowercase();
+        match provider.as_str() {
+            "searxng" => env::var("SEARXNG_URL").ok().map(|base_url| WebSearchProvider::SearxNG {
+                base_url: base_url.trim_end_matches('/').to_string(),
+            }),
+            "brave" => env::var("BRAVE_SEARCH_API_KEY")
+                .ok()
+                .map(|api_key| WebSearchProvider::Brave { api_key }),
+            "serper" => env::var("SERPER_API_KEY")
+                .ok()
+                .map(|api_key| WebSearchProvider::Serper { api_key }),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebSearchProvider::SearxNG { .. } => "searxng",
+            WebSearchProvider::Brave { .. } => "brave",
+            WebSearchProvider::Serper { .. } => "serper",
+        }
+    }
+
+    pub async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+        num_results: usize,
+    ) -> Result<Vec<WebSearchResult>, ToolError> {
+        let request = match self {
+            WebSearchProvider::SearxNG { base_url } => client
+                .get(format!("{}/search", base_url))
+                .query(&[("q", query), ("format", "json")]),
+            WebSearchProvider::Brave { api_key } => client
+                .get("https://api.search.brave.com/res/v1/web/search")
+                .header("X-Subscription-Token", api_key)
+                .header("Accept", "application/json")
+                .query(&[("q", query.to_string()), ("count", num_results.to_string())]),
+            WebSearchProvider::Serper { api_key } => client
+                .post("https://google.serper.dev/search")
+                .header("X-API-KEY", api_key)
+                .json(&json!({ "q": query, "num": num_results })),
+        };
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "{} search failed with status {}",
+                self.name(),
+                response.status()
+            )));
+        }
+        let body: Value = response.json().await?;
+
+        let mut results = self.parse_results(&body);
+        results.truncate(num_results);
+        Ok(results)
+    }
+
+    /// Maps the provider response into normalized results
+    pub fn parse_results(&self, body: &Value) -> Vec<WebSearchResult> {
+        let (items, url_key, snippet_key) = match self {
+            WebSearchProvider::SearxNG { .. } => (body.get("results"), "url", "content"),
+            WebSearchProvider::Brave { .. } => (body.pointer("/web/results"), "url", "description"),
+            WebSearchProvider::Serper { .. } => (body.get("organic"), "link", "snippet"),
+        };
+        let field = |item: &Value, key: &str| item.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+        items
+            .and_then(|items| items.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter(|item| !field(item, url_key).is_empty())
+                    .enumerate()
+                    .map(|(i, item)| WebSearchResult {
+                        index: i + 1,
+                        title: field(item, "title"),
+                        url: field(item, url_key),
+                        snippet: field(item, snippet_key),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Built-in tool that searches the web with the configured provider
+pub struct WebSearchTool {
+    provider: WebSearchProvider,
+    client: reqwest::Client,
+}
+
+impl WebSearchTool {
+    pub const NAME: &'static str = "web_search";
+
+    pub fn new(provider: WebSearchProvider) -> Self {
+        let client = OUTBOUND_PROXY
+            .client_builder(DestinationClass::WebTools, reqwest::Client::builder())
+            .timeout(Duration::from_secs(SEARCH_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+        WebSearchTool { provider, client }
+    }
+
+    /// Returns None if no provider is configured
+    pub fn from_env() -> Option<Self> {
+        WebSearchProvider::from_env().map(Self::new)
+    }
+}
+
+#[async_trait]
+impl NativeTool for WebSearchTool {
+    fn definition(&self) -> RustTool {
+        RustTool::new(
+            Self::NAME.to_string(),
+            "Searches the web and returns a numbered list of results with their title, url and snippet. Cite results by their index, e.g. [1].".to_string(),
+            vec![
+                ToolArgument::new("query".to_string(), "string".to_string(), "What to search for".to_string(), true),
+                ToolArgument::new(
+                    "num_results".to_string(),
+                    "integer".to_string(),
+                    format!("How many results to return (default {}, max {})", DEFAULT_NUM_RESULTS, MAX_NUM_RESULTS),
+                    false,
+                ),
+            ],
+            None,
+        )
+    }
+
+    fn capabilities(&self) -> Vec<ToolCapability> {
+        let url = match &self.provider {
+            WebSearchProvider::SearxNG { base_url } => base_url.as_str(),
+            WebSearchProvider::Brave { .. } => "https://api.search.brave.com",
+            WebSearchProvider::Serper { .. } => "https://google.serper.dev",
+        };
+        ToolCapability::network_for_url(url).into_iter().collect()
+    }
+
+    async fn run(&self, args: serde_json::Map<String, Value>) -> Result<Value, ToolError> {
+        let query = args.get("query").and_then(|v| v.as_str()).unwrap_or_default();
+        if query.trim().is_empty() {
+            return Err(ToolError::InvalidFunctionArguments(
+                "The search query can't be empty".to_string(),
+            ));
+        }
+        let num_results = args
+            .get("num_results")
+            .and_then(|v| v.as_u64())
+            .map(|n| (n as usize).clamp(1, MAX_NUM_RESULTS))
+            .unwrap_or(DEFAULT_NUM_RESULTS);
+
+        let results = self.provider.search(&self.client, query, num_results).await?;
+        Ok(json!({
+            "query": query,
+            "provider": self.provider.name(),
+            "results": results,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_provider_results() {
+        let searxng = WebSearchProvider::SearxNG {
+            base_url: "http://localhost:8080".to_string(),
+        };
+        let results = searxng.parse_results(&json!({
+            "results": [
+                {"title": "Shinkai", "url": "https://shinkai.com", "content": "AI agents"},
+                {"title": "No url", "content": "skipped"},
+            ]
+        }));
+        assert_eq!(
+            results,
+            vec![WebSearchResult {
+                index: 1,
+                title: "Shinkai".to_string(),
+                url: "https://shinkai.com".to_string(),
+                snippet: "AI agents".to_string(),
+            }]
+        );
+
+        let brave = WebSearchProvider::Brave {
+            api_key: "key".to_string(),
+        };
+        let results = brave.parse_results(&json!({
+            "web": {"results": [{"title": "Rust", "url": "https://rust-lang.org", "description": "A language"}]}
+        }));
+        assert_eq!(results[0].snippet, "A language");
+
+        let serper = WebSearchProvider::Serper {
+            api_key: "key".to_string(),
+        };
+        let results = serper.parse_results(&json!({
+            "organic": [
+                {"title": "A", "link": "https://a.com", "snippet": "a"},
+                {"title": "B", "link": "https://b.com", "snippet": "b"},
+            ]
+        }));
+        assert_eq!(results[1].index, 2);
+        assert_eq!(results[1].url, "https://b.com");
+        assert!(serper.parse_results(&json!({})).is_empty());
+    }
+}
@@ -5,6 +5,7 @@ use shinkai_vector_resources::embeddings::Embedding;
 
 use super::{
     argument::ToolArgument,
+    js_toolkit_headers::ToolCapability,
     tool_router_dep::workflow_static_texts::{
         AGILITY_STORY_SYSTEM, AI_SYSTEM, ANALYZE_ANSWERS_SYSTEM, ANALYZE_CLAIMS_SYSTEM, ANALYZE_DEBATE_SYSTEM,
         ANALYZE_INCIDENT_SYSTEM, ANALYZE_LOGS_SYSTEM, ANALYZE_MALWARE_SYSTEM, ANALYZE_PAPER_SYSTEM,
@@ -54,6 +55,9 @@ pub struct WorkflowTool {
 }
 
 impl WorkflowTool {
+    /// Workflow functions that read the files uploaded to the job's file inbox
+    const FILE_INBOX_FUNCTIONS: [&'static str; 2] = ["count_files_from_input", "retrieve_file_from_input"];
+
     pub fn new(workflow: Workflow) -> Self {
         WorkflowTool {
             workflow,
@@ -61,6 +65,20 @@ impl WorkflowTool {
         }
     }
 
+    /// Capabilities the workflow needs for the functions it calls itself. Tools it calls are checked on their own.
+    pub fn requested_capabilities(&self) -> Vec<ToolCapability> {
+        let reads_file_inbox = self
+            .workflow
+            .extract_function_names()
+            .iter()
+            .any(|name| Self::FILE_INBOX_FUNCTIONS.contains(&name.as_str()));
+        if reads_file_inbox {
+            vec![ToolCapability::FileInboxRead]
+        } else {
+            vec![]
+        }
+    }
+
     pub fn get_db_key(&self) -> String {
         format!("{}:::{}", self.workflow.name, self.workflow.version)
     }
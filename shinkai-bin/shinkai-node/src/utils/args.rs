@@ -10,6 +10,12 @@ pub struct Args {
     pub receiver_subidentity: Option<String>,
     pub inbox: Option<String>,
     pub body_content: Option<String>,
+    /// File of `KEY=VALUE` lines with the same variables as the environment
+    pub config: Option<String>,
+    /// Folder of the databases, secrets and logs, overriding NODE_STORAGE_PATH
+    pub data_dir: Option<String>,
+    /// Keeps the databases in memory, same as NODE_EPHEMERAL=true
+    pub ephemeral: bool,
 }
 
 pub fn parse_args() -> Args {
@@ -59,6 +65,9 @@ pub fn parse_args() -> Args {
                 .long("body_content")
                 .takes_value(true),
         )
+        .arg(clap::Arg::new("config").long("config").takes_value(true))
+        .arg(clap::Arg::new("data_dir").long("data-dir").takes_value(true))
+        .arg(clap::Arg::new("ephemeral").long("ephemeral").takes_value(false))
         .get_matches();
 
     Args {
@@ -71,5 +80,8 @@ pub fn parse_args() -> Args {
         receiver_subidentity: matches.value_of("receiver_subidentity").map(String::from),
         inbox: matches.value_of("inbox").map(String::from),
         body_content: matches.value_of("body_content").map(String::from),
+        config: matches.value_of("config").map(String::from),
+        data_dir: matches.value_of("data_dir").map(String::from),
+        ephemeral: matches.is_present("ephemeral"),
     }
 }
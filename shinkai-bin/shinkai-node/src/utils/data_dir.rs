@@ -0,0 +1,285 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ed25519_dalek::VerifyingKey;
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use shinkai_message_primitives::shinkai_utils::signatures::hash_signature_public_key;
+
+const MAIN_DB_DIR: &str = "main_db";
+const VECTOR_FS_DB_DIR: &str = "vector_fs_db";
+const SECRETS_FILE: &str = ".secret";
+const LOGS_DIR: &str = "logs";
+/// Left in a data directory whose data was moved, holding the path of the new data directory
+const RELOCATED_TO_FILE: &str = "relocated_to";
+/// Holds where the data directory is moved to on the next start
+const PENDING_RELOCATION_FILE: &str = "pending_relocation.json";
+/// Relocated data directories are followed at most this many times, in case they point at each other
+const MAX_RELOCATION_HOPS: usize = 8;
+
+/// A move of the data directory, done on the next start while the databases are closed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingRelocation {
+    pub target: PathBuf,
+    /// Deletes the data from the old data directory once the copy is verified
+    #[serde(default)]
+    pub remove_old_copy: bool,
+}
+
+/// Layout of the folder holding the databases, secrets and logs of the node (NODE_STORAGE_PATH)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataDir {
+    pub root: PathBuf,
+}
+
+impl DataDir {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        DataDir { root: root.into() }
+    }
+
+    /// The data directory the data was moved to, if it was relocated, else this one
+    pub fn resolve(self) -> Self {
+        let mut data_dir = self;
+        for _ in 0..MAX_RELOCATION_HOPS {
+            match fs::read_to_string(data_dir.root.join(RELOCATED_TO_FILE)) {
+                Ok(target) if !target.trim().is_empty() => data_dir = DataDir::new(target.trim()),
+                _ => break,
+            }
+        }
+        data_dir
+    }
+
+    /// Machine filesystem path to the main database, pub key based
+    pub fn main_db_path(&self, identity_public_key: &VerifyingKey) -> String {
+        Self::path_string(
+            self.root
+                .join(MAIN_DB_DIR)
+                .join(hash_signature_public_key(identity_public_key)),
+        )
+    }
+
+    /// Machine filesystem path to the main VectorFS database, pub key based
+    pub fn vector_fs_db_path(&self, identity_public_key: &VerifyingKey) -> String {
+        Self::path_string(
+            self.root
+                .join(VECTOR_FS_DB_DIR)
+                .join(hash_signature_public_key(identity_public_key)),
+        )
+    }
+
+    pub fn secrets_file_path(&self) -> String {
+        Self::path_string(self.root.join(SECRETS_FILE))
+    }
+
+    /// Machine filesystem path of the node log file, which can be downloaded through the API
+    pub fn log_file_path(&self) -> String {
+        Self::path_string(self.root.join(LOGS_DIR).join("shinkai_node.log"))
+    }
+
+    fn path_string(path: PathBuf) -> String {
+        path.to_str().expect("Invalid NODE_STORAGE_PATH").to_string()
+    }
+
+    /// Total size of the data of the node, in bytes
+    pub fn data_size(&self) -> u64 {
+        self.data_entries().iter().map(|entry| Self::entry_size(entry)).sum()
+    }
+
+    /// Schedules moving the data to the target directory on the next start. The target must be empty or not exist
+    /// yet, outside of this data directory, with enough free space for the data.
+    pub fn request_relocation(&self, target: &Path, remove_old_copy: bool) -> Result<(), String> {
+        if !target.is_absolute() {
+            return Err("The new data directory must be an absolute path".to_string());
+        }
+        let root = self.root.canonicalize().map_err(|e| e.to_string())?;
+        let existing_ancestor = target
+            .ancestors()
+            .find(|ancestor| ancestor.exists())
+            .ok_or("The new data directory is on no existing disk")?
+            .canonicalize()
+            .map_err(|e| e.to_string())?;
+        if existing_ancestor.starts_with(&root) || (target.exists() && root.starts_with(&existing_ancestor)) {
+            return Err("The new data directory can't contain or be within the current one".to_string());
+        }
+        if target.exists() && fs::read_dir(target).map_err(|e| e.to_string())?.next().is_some() {
+            return Err("The new data directory must be empty".to_string());
+        }
+        let available = fs2::available_space(&existing_ancestor).map_err(|e| e.to_string())?;
+        let needed = self.data_size();
+        if available < needed {
+            return Err(format!(
+                "Not enough space for the data: {} bytes needed, {} bytes available",
+                needed, available
+            ));
+        }
+
+        let relocation = PendingRelocation {
+            target: target.to_path_buf(),
+            remove_old_copy,
+        };
+        let json = serde_json::to_string(&relocation).map_err(|e| e.to_string())?;
+        fs::write(self.root.join(PENDING_RELOCATION_FILE), json).map_err(|e| e.to_string())
+    }
+
+    pub fn pending_relocation(&self) -> Option<PendingRelocation> {
+        let json = fs::read_to_string(self.root.join(PENDING_RELOCATION_FILE)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Moves the data to where a relocation was requested, if one was, and returns the data directory to use from
+    /// now on. Runs before the databases are opened. A failed relocation leaves the data where it was.
+    pub fn apply_pending_relocation(self) -> Self {
+        let Some(relocation) = self.pending_relocation() else {
+            return self;
+        };
+        let _ = fs::remove_file(self.root.join(PENDING_RELOCATION_FILE));
+
+        match self.relocate(&relocation) {
+            Ok(data_dir) => {
+                shinkai_log(
+                    ShinkaiLogOption::Node,
+                    ShinkaiLogLevel::Info,
+                    &format!("Moved the data directory to {}", data_dir.root.display()),
+                );
+                data_dir
+            }
+            Err(e) => {
+                shinkai_log(
+                    ShinkaiLogOption::Node,
+                    ShinkaiLogLevel::Error,
+                    &format!(
+                        "Failed to move the data directory to {}, keeping it at {}: {}",
+                        relocation.target.display(),
+                        self.root.display(),
+                        e
+                    ),
+                );
+                self
+            }
+        }
+    }
+
+    /// Copies the data, verifies the copy byte for byte and points this data directory to the new one
+    fn relocate(&self, relocation: &PendingRelocation) -> Result<DataDir, io::Error> {
+        let target = DataDir::new(&relocation.target);
+        fs::create_dir_all(&target.root)?;
+
+        let copied = self.data_entries().iter().try_for_each(|entry| {
+            let relative = entry.strip_prefix(&self.root).unwrap_or(entry);
+            let target_entry = target.root.join(relative);
+            Self::copy_entry(entry, &target_entry)?;
+            Self::verify_copy(entry, &target_entry)
+        });
+        if let Err(e) = copied {
+            for entry in self.data_entries() {
+                let relative = entry.strip_prefix(&self.root).unwrap_or(&entry);
+                let _ = Self::remove_entry(&target.root.join(relative));
+            }
+            return Err(e);
+        }
+
+        fs::write(
+            self.root.join(RELOCATED_TO_FILE),
+            target.root.to_string_lossy().as_bytes(),
+        )?;
+        if relocation.remove_old_copy {
+            for entry in self.data_entries() {
+                Self::remove_entry(&entry)?;
+            }
+        }
+        Ok(target)
+    }
+
+    fn data_entries(&self) -> Vec<PathBuf> {
+        [MAIN_DB_DIR, VECTOR_FS_DB_DIR, SECRETS_FILE, LOGS_DIR]
+            .iter()
+            .map(|name| self.root.join(name))
+            .filter(|entry| entry.exists())
+            .collect()
+    }
+
+    fn copy_entry(source: &Path, target: &Path) -> io::Result<()> {
+        if source.is_dir() {
+            fs::create_dir_all(target)?;
+            for child in fs::read_dir(source)? {
+                let child = child?;
+                Self::copy_entry(&child.path(), &target.join(child.file_name()))?;
+            }
+            Ok(())
+        } else {
+            fs::copy(source, target).map(|_| ())
+        }
+    }
+
+    fn verify_copy(source: &Path, target: &Path) -> io::Result<()> {
+        if source.is_dir() {
+            for child in fs::read_dir(source)? {
+                let child = child?;
+                Self::verify_copy(&child.path(), &target.join(child.file_name()))?;
+            }
+            return Ok(());
+        }
+        if blake3::hash(&fs::read(source)?) != blake3::hash(&fs::read(target)?) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} differs from its copy", source.display()),
+            ));
+        }
+        Ok(())
+    }
+
+    fn remove_entry(entry: &Path) -> io::Result<()> {
+        match entry.is_dir() {
+            true => fs::remove_dir_all(entry),
+            false => fs::remove_file(entry),
+        }
+    }
+
+    fn entry_size(entry: &Path) -> u64 {
+        if entry.is_dir() {
+            fs::read_dir(entry)
+                .map(|children| children.flatten().map(|child| Self::entry_size(&child.path())).sum())
+                .unwrap_or(0)
+        } else {
+            fs::metadata(entry).map(|metadata| metadata.len()).unwrap_or(0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relocate_data_dir() {
+        let base = std::env::temp_dir().join(format!("shinkai_data_dir_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        let data_dir = DataDir::new(base.join("old"));
+        fs::create_dir_all(data_dir.root.join(MAIN_DB_DIR).join("key")).unwrap();
+        fs::write(
+            data_dir.root.join(MAIN_DB_DIR).join("key").join("CURRENT"),
+            "MANIFEST-1",
+        )
+        .unwrap();
+        fs::write(data_dir.root.join(SECRETS_FILE), "IDENTITY_SECRET_KEY=abc").unwrap();
+
+        let target = base.join("new");
+        assert!(data_dir
+            .request_relocation(&data_dir.root.join("inside"), false)
+            .is_err());
+        data_dir.request_relocation(&target, true).unwrap();
+
+        let relocated = data_dir.clone().apply_pending_relocation();
+        assert_eq!(relocated.root, target);
+        assert_eq!(
+            fs::read_to_string(target.join(MAIN_DB_DIR).join("key").join("CURRENT")).unwrap(),
+            "MANIFEST-1"
+        );
+        assert!(!data_dir.root.join(SECRETS_FILE).exists());
+        assert_eq!(data_dir.clone().resolve(), relocated);
+        assert_eq!(data_dir.pending_relocation(), None);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -1,4 +1,5 @@
 use std::env;
+use std::fs;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 
@@ -6,6 +7,7 @@ use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider:
     LLMProviderInterface, SerializedLLMProvider,
 };
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_vector_resources::embedding_generator::RemoteEmbeddingGenerator;
 use shinkai_vector_resources::model_type::{EmbeddingModelType, OllamaTextEmbeddingsInference};
 
 #[derive(Debug, Clone)]
@@ -14,6 +16,12 @@ pub struct NodeEnvironment {
     pub listen_address: SocketAddr,
     pub api_listen_address: SocketAddr,
     pub ws_address: Option<SocketAddr>,
+    pub grpc_address: Option<SocketAddr>,
+    pub webdav_address: Option<SocketAddr>,
+    /// Whether files written over WebDAV are saved (and ingested) into the VectorFS
+    pub webdav_writable: bool,
+    /// Address of the server of the published folders, which needs no API key
+    pub publishing_address: Option<SocketAddr>,
     pub ping_interval: u64,
     pub starting_num_qr_profiles: u32,
     pub starting_num_qr_devices: u32,
@@ -119,6 +127,13 @@ pub fn fetch_node_environment() -> NodeEnvironment {
         .expect("Failed to parse port number");
 
     let ws_port: Option<u16> = env::var("NODE_WS_PORT").ok().and_then(|p| p.parse().ok());
+    let grpc_port: Option<u16> = env::var("NODE_GRPC_PORT").ok().and_then(|p| p.parse().ok());
+    let webdav_port: Option<u16> = env::var("NODE_WEBDAV_PORT").ok().and_then(|p| p.parse().ok());
+    let publishing_port: Option<u16> = env::var("NODE_PUBLISHING_PORT").ok().and_then(|p| p.parse().ok());
+    let webdav_writable: bool = env::var("WEBDAV_WRITABLE")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .expect("Failed to parse WEBDAV_WRITABLE");
 
     // TODO: remove this and just assume one device per profile
     let starting_num_qr_profiles: u32 = env::var("STARTING_NUM_QR_PROFILES")
@@ -157,7 +172,8 @@ pub fn fetch_node_environment() -> NodeEnvironment {
         .parse()
         .expect("Failed to parse AUTO_DETECT_LOCAL_LLMS");
 
-    // External server env vars
+    // External server env vars. EMBEDDINGS_SERVER_URL=deterministic generates embeddings from the hash of the
+    // text instead of calling a server, for tests
     let unstructured_server_url: Option<String> = env::var("UNSTRUCTURED_SERVER_URL").ok();
     let unstructured_server_api_key: Option<String> = env::var("UNSTRUCTURED_SERVER_API_KEY").ok();
     let embeddings_server_url: Option<String> = env::var("EMBEDDINGS_SERVER_URL").ok();
@@ -169,6 +185,13 @@ pub fn fetch_node_environment() -> NodeEnvironment {
     // WebSocket address
     let ws_address = ws_port.map(|port| SocketAddr::new(ip, port));
 
+    // gRPC API address, served next to the HTTP API
+    let grpc_address = grpc_port.map(|port| SocketAddr::new(api_ip, port));
+
+    // WebDAV address, also served next to the HTTP API
+    let webdav_address = webdav_port.map(|port| SocketAddr::new(api_ip, port));
+    let publishing_address = publishing_port.map(|port| SocketAddr::new(api_ip, port));
+
     // Check if NODE_API_IP:NODE_API_PORT is the same as NODE_IP:NODE_PORT
     if ip == api_ip && port == api_port {
         panic!("NODE_API_IP:NODE_API_PORT cannot be the same as NODE_IP:NODE_PORT");
@@ -199,6 +222,10 @@ pub fn fetch_node_environment() -> NodeEnvironment {
         listen_address,
         api_listen_address,
         ws_address,
+        grpc_address,
+        webdav_address,
+        webdav_writable,
+        publishing_address,
         ping_interval,
         starting_num_qr_profiles,
         starting_num_qr_devices,
@@ -216,6 +243,72 @@ pub fn fetch_node_environment() -> NodeEnvironment {
     }
 }
 
+/// Generator of the image embeddings that find the images saved in the VectorFS by text, if an image embedding model
+/// is set in IMAGE_EMBEDDING_MODEL (ie. `clip/openai/clip-vit-base-patch32`). It's served by
+/// IMAGE_EMBEDDINGS_SERVER_URL, or by the embeddings server if not set.
+pub fn image_embedding_generator() -> Option<RemoteEmbeddingGenerator> {
+    let model = EmbeddingModelType::from_string(&env::var("IMAGE_EMBEDDING_MODEL").ok()?)
+        .ok()
+        .filter(|model| model.supports_images())?;
+    let api_url = env::var("IMAGE_EMBEDDINGS_SERVER_URL")
+        .or_else(|_| env::var("EMBEDDINGS_SERVER_URL"))
+        .ok()?;
+    let api_key = env::var("IMAGE_EMBEDDINGS_SERVER_API_KEY")
+        .or_else(|_| env::var("EMBEDDINGS_SERVER_API_KEY"))
+        .ok();
+    Some(RemoteEmbeddingGenerator::new(model, &api_url, api_key))
+}
+
+/// Generator of the embeddings of the documents in other languages than English, if a multilingual embedding model
+/// is set in MULTILINGUAL_EMBEDDING_MODEL (ie. `jina/jina-embeddings-v2-base-es:latest`). It's served by the
+/// embeddings server, next to the default model.
+pub fn multilingual_embedding_generator() -> Option<RemoteEmbeddingGenerator> {
+    let model = EmbeddingModelType::from_string(&env::var("MULTILINGUAL_EMBEDDING_MODEL").ok()?).ok()?;
+    let api_url = env::var("EMBEDDINGS_SERVER_URL").ok()?;
+    let api_key = env::var("EMBEDDINGS_SERVER_API_KEY").ok();
+    Some(RemoteEmbeddingGenerator::new(model, &api_url, api_key))
+}
+
+/// Whether the databases of the node are kept in memory (NODE_EPHEMERAL or --ephemeral), for tests and demos.
+/// Nothing is persisted, the data is gone when the node stops.
+pub fn is_ephemeral_node() -> bool {
+    env::var("NODE_EPHEMERAL").is_ok_and(|value| value == "true")
+}
+
+/// Sets the variables of a config file of `KEY=VALUE` lines, like the env files of Docker. Variables already set
+/// in the environment win over the ones of the file.
+pub fn load_config_file(path: &str) -> Result<(), String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Failed to read the config file {}: {}", path, e))?;
+    for (key, value) in parse_config_file(&contents)? {
+        if env::var_os(&key).is_none() {
+            env::set_var(key, value);
+        }
+    }
+    Ok(())
+}
+
+/// Blank lines and lines starting with `#` are skipped, and values can be quoted
+fn parse_config_file(contents: &str) -> Result<Vec<(String, String)>, String> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(format!("Line {} of the config file isn't KEY=VALUE", index + 1))?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+            Ok((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
 pub fn fetch_static_server_env() -> Option<StaticServerEnvironment> {
     let port = env::var("STATIC_SERVER_PORT").ok().and_then(|p| p.parse::<u16>().ok());
     let ip = env::var("STATIC_SERVER_IP")
@@ -230,3 +323,21 @@ pub fn fetch_static_server_env() -> Option<StaticServerEnvironment> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_file() {
+        let contents = "# Node\nNODE_API_PORT=9550\n\nexport NODE_STORAGE_PATH=\"/data/node\"\n";
+        assert_eq!(
+            parse_config_file(contents).unwrap(),
+            vec![
+                ("NODE_API_PORT".to_string(), "9550".to_string()),
+                ("NODE_STORAGE_PATH".to_string(), "/data/node".to_string()),
+            ]
+        );
+        assert!(parse_config_file("NODE_API_PORT").is_err());
+    }
+}
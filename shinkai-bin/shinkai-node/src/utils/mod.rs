@@ -1,5 +1,6 @@
 pub mod args;
 pub mod cli;
+pub mod data_dir;
 pub mod environment;
 pub mod keys;
 pub mod logging_helpers;
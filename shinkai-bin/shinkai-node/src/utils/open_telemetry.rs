@@ -1,9 +1,11 @@
-use opentelemetry::{trace::Tracer as _, KeyValue};
+use opentelemetry::metrics::MetricsError;
+use opentelemetry::{global, trace::TraceError, KeyValue};
 
 use opentelemetry_otlp::WithExportConfig;
 
 use opentelemetry_sdk::runtime;
 
+use opentelemetry_sdk::metrics::MeterProvider;
 use opentelemetry_sdk::trace::BatchConfig;
 use opentelemetry_sdk::trace::Config;
 use opentelemetry_sdk::trace::Sampler;
@@ -19,7 +21,61 @@ use std::time::Duration;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::Registry;
 
+/// Where and how the node exports its traces and metrics over OTLP/HTTP, e.g. to Jaeger, Tempo or Grafana.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryConfig {
+    /// Base URL of the collector, `/v1/traces` and `/v1/metrics` are added to it
+    pub endpoint: String,
+    /// Value of the `Authorization` header, for collectors that require one
+    pub auth_header: Option<String>,
+    /// Share of the traces that are exported, between 0 and 1. Spans of sampled remote parents are always exported.
+    pub sampling_ratio: f64,
+    pub service_name: String,
+    pub environment: String,
+    pub metrics_interval: Duration,
+}
+
+impl TelemetryConfig {
+    /// Reads the config from TELEMETRY_* environment variables
+    pub fn from_env(endpoint: &str) -> Self {
+        let sampling_ratio = std::env::var("TELEMETRY_SAMPLING_RATIO")
+            .ok()
+            .and_then(|ratio| ratio.parse::<f64>().ok())
+            .map(|ratio| ratio.clamp(0.0, 1.0))
+            .unwrap_or(1.0);
+        let metrics_interval_secs = std::env::var("TELEMETRY_METRICS_INTERVAL_SECS")
+            .ok()
+            .and_then(|secs| secs.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(60);
+
+        TelemetryConfig {
+            endpoint: endpoint.to_string(),
+            auth_header: std::env::var("TELEMETRY_AUTH_HEADER").ok(),
+            sampling_ratio,
+            service_name: std::env::var("TELEMETRY_SERVICE_NAME")
+                .unwrap_or_else(|_| env!("CARGO_PKG_NAME").to_string()),
+            environment: std::env::var("TELEMETRY_ENVIRONMENT").unwrap_or_else(|_| "develop".to_string()),
+            metrics_interval: Duration::from_secs(metrics_interval_secs),
+        }
+    }
+
+    fn headers(&self) -> HashMap<String, String> {
+        let mut headers = HashMap::new();
+        if let Some(auth_header) = &self.auth_header {
+            headers.insert("Authorization".to_string(), auth_header.clone());
+        }
+        headers.insert("stream-name".to_string(), "default".to_string());
+        headers
+    }
+
+    fn sampler(&self) -> Sampler {
+        Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(self.sampling_ratio)))
+    }
+}
+
 struct OpenTelemetryLogger {
+    #[allow(dead_code)]
     tracer: Tracer,
 }
 
@@ -35,57 +91,96 @@ impl ShinkaiTelemetry for OpenTelemetryLogger {
     }
 }
 
-pub fn init_telemetry_tracing(telemetry_endpoint: &str) {
-    let tracer = init_tracer(telemetry_endpoint);
+pub fn init_telemetry_tracing(config: &TelemetryConfig) {
+    let tracer = match init_tracer(config) {
+        Ok(tracer) => tracer,
+        Err(e) => {
+            eprintln!("Failed to install OpenTelemetry tracer: {}", e);
+            panic!("Failed to install OpenTelemetry tracer");
+        }
+    };
     let telemetry = tracing_opentelemetry::layer().with_tracer(tracer.clone());
     let subscriber = Registry::default().with(telemetry);
-    
+
     tracing::subscriber::set_global_default(subscriber).expect("Failed to set global default subscriber");
 
+    // Metrics are optional, the node keeps exporting traces without them
+    match init_meter_provider(config) {
+        Ok(meter_provider) => global::set_meter_provider(meter_provider),
+        Err(e) => eprintln!("Failed to install OpenTelemetry metrics exporter: {}", e),
+    }
+
     // Set the OpenTelemetryLogger as the ShinkaiTelemetry implementation
     let logger = Arc::new(OpenTelemetryLogger { tracer });
     shinkai_message_primitives::shinkai_utils::shinkai_logging::set_telemetry(logger);
 }
 
-fn resource() -> Resource {
+/// Records the duration and outcome of a processed job message
+pub fn record_job_execution(duration: Duration, success: bool) {
+    let meter = global::meter("shinkai_node");
+    let histogram = meter
+        .f64_histogram("shinkai.job.duration")
+        .with_description("Time to process a job message")
+        .with_unit(opentelemetry::metrics::Unit::new("s"))
+        .init();
+    histogram.record(duration.as_secs_f64(), &[KeyValue::new("success", success)]);
+}
+
+fn resource(config: &TelemetryConfig) -> Resource {
     Resource::new(vec![
-        KeyValue::new(SERVICE_NAME, env!("CARGO_PKG_NAME")),
+        KeyValue::new(SERVICE_NAME, config.service_name.clone()),
         KeyValue::new(SERVICE_VERSION, env!("CARGO_PKG_VERSION")),
-        KeyValue::new(DEPLOYMENT_ENVIRONMENT, "develop"),
+        KeyValue::new(DEPLOYMENT_ENVIRONMENT, config.environment.clone()),
     ])
 }
 
-fn init_tracer(telemetry_endpoint: &str) -> Tracer {
-    let mut headers = HashMap::new();
-    let auth_header = std::env::var("TELEMETRY_AUTH_HEADER").unwrap_or_else(|_| panic!("TELEMETRY_AUTH_HEADER not set"));
-    headers.insert("Authorization".to_string(), auth_header);
-    headers.insert("stream-name".to_string(), "default".to_string());
-
-    let tracer = opentelemetry_otlp::new_pipeline()
+fn init_tracer(config: &TelemetryConfig) -> Result<Tracer, TraceError> {
+    opentelemetry_otlp::new_pipeline()
         .tracing()
         .with_trace_config(
             Config::default()
-                .with_sampler(Sampler::AlwaysOn)
-                .with_resource(resource()),
+                .with_sampler(config.sampler())
+                .with_resource(resource(config)),
         )
         .with_batch_config(BatchConfig::default())
         .with_exporter(
             opentelemetry_otlp::new_exporter()
                 .http()
-                .with_endpoint(telemetry_endpoint)
-                .with_headers(headers)
-                .with_timeout(Duration::from_secs(3))
-                // .with_tls_config(None), // Disable TLS
+                .with_endpoint(&config.endpoint)
+                .with_headers(config.headers())
+                .with_timeout(Duration::from_secs(3)),
         )
-        .install_batch(runtime::Tokio);
-
-        match tracer {
-            Ok(t) => {
-                t
-            }
-            Err(e) => {
-                eprintln!("Failed to install OpenTelemetry tracer: {}", e);
-                panic!("Failed to install OpenTelemetry tracer");
-            }
-        }
+        .install_batch(runtime::Tokio)
+}
+
+fn init_meter_provider(config: &TelemetryConfig) -> Result<MeterProvider, MetricsError> {
+    opentelemetry_otlp::new_pipeline()
+        .metrics(runtime::Tokio)
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(&config.endpoint)
+                .with_headers(config.headers())
+                .with_timeout(Duration::from_secs(3)),
+        )
+        .with_resource(resource(config))
+        .with_period(config.metrics_interval)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_telemetry_config_sampler() {
+        let mut config = TelemetryConfig::from_env("http://localhost:4318");
+        assert_eq!(config.endpoint, "http://localhost:4318");
+
+        config.sampling_ratio = 0.25;
+        assert!(matches!(
+            config.sampler(),
+            Sampler::ParentBased(root) if matches!(*root, Sampler::TraceIdRatioBased(ratio) if ratio == 0.25)
+        ));
+    }
 }
@@ -42,6 +42,8 @@ pub async fn generate_qr_codes(
             .send(NodeCommand::LocalCreateRegistrationCode {
                 permissions: IdentityPermissions::Admin,
                 code_type: RegistrationCodeType::Device("main".to_string()),
+                ttl_secs: None,
+                max_uses: None,
                 res: res1_registration_sender,
             })
             .await?;
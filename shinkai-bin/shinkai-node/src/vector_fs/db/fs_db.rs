@@ -1,10 +1,13 @@
 use super::super::vector_fs_error::VectorFSError;
 use crate::db::db_profile_bound::ProfileBoundWriteBatch;
 use crate::db::ShinkaiDB;
+use crate::schemas::db_maintenance::ColumnFamilySize;
+use crate::utils::environment::is_ephemeral_node;
 use rand::Rng;
 use rand::{distributions::Alphanumeric, thread_rng};
 use rocksdb::{
-    AsColumnFamilyRef, ColumnFamily, ColumnFamilyDescriptor, DBCompressionType, IteratorMode, Options, SingleThreaded,
+    AsColumnFamilyRef, ColumnFamily, ColumnFamilyDescriptor, DBCompressionType, Env, IteratorMode, Options,
+    SingleThreaded,
 };
 use rocksdb::{Error, OptimisticTransactionDB};
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
@@ -48,7 +51,19 @@ pub struct VectorFSDB {
 
 impl VectorFSDB {
     pub fn new(db_path: &str) -> Result<Self, Error> {
+        Self::open(db_path, is_ephemeral_node())
+    }
+
+    /// Opens a database kept in memory, gone once dropped. Nothing is written at the path, which only names it.
+    pub fn new_in_memory(db_path: &str) -> Result<Self, Error> {
+        Self::open(db_path, true)
+    }
+
+    fn open(db_path: &str, in_memory: bool) -> Result<Self, Error> {
         let mut db_opts = Options::default();
+        if in_memory {
+            db_opts.set_env(&Env::mem_env()?);
+        }
         db_opts.create_if_missing(true);
         db_opts.create_missing_column_families(true);
         // if we want to enable compression
@@ -60,7 +75,7 @@ impl VectorFSDB {
         db_opts.set_keep_log_file_num(10);
         db_opts.set_blob_compression_type(DBCompressionType::Lz4);
 
-        let cf_names = if Path::new(db_path).exists() {
+        let cf_names = if !in_memory && Path::new(db_path).exists() {
             // If the database file exists, get the list of column families from the database
             OptimisticTransactionDB::<SingleThreaded>::list_cf(&db_opts, db_path)?
         } else {
@@ -121,6 +136,34 @@ impl VectorFSDB {
         Ok(Self { db, path: db_path })
     }
 
+    /// Sizes of every column family, as estimated by RocksDB
+    pub fn column_family_sizes(&self) -> Result<Vec<ColumnFamilySize>, VectorFSError> {
+        let cf_names = OptimisticTransactionDB::<SingleThreaded>::list_cf(&Options::default(), &self.path)?;
+
+        let mut sizes = Vec::new();
+        for cf_name in cf_names {
+            let Some(cf) = self.db.cf_handle(&cf_name) else {
+                continue;
+            };
+            sizes.push(ColumnFamilySize::read(&cf_name, |property| {
+                self.db.property_int_value_cf(cf, property)
+            })?);
+        }
+        Ok(sizes)
+    }
+
+    /// Compacts every column family, which drops the tombstones of deleted keys and the overwritten values.
+    /// Blocks until done, so it's run off the async runtime.
+    pub fn compact(&self) -> Result<(), VectorFSError> {
+        let cf_names = OptimisticTransactionDB::<SingleThreaded>::list_cf(&Options::default(), &self.path)?;
+        for cf_name in cf_names {
+            if let Some(cf) = self.db.cf_handle(&cf_name) {
+                self.db.compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+            }
+        }
+        Ok(())
+    }
+
     /// Fetches the ColumnFamily handle.
     pub fn get_cf_handle(&self, topic: FSTopic) -> Result<&ColumnFamily, VectorFSError> {
         let handle = self
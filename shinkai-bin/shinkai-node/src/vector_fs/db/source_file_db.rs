@@ -31,6 +31,20 @@ impl VectorFSDB {
         Ok((bytes, cf))
     }
 
+    /// Deletes the `SourceFileMap` from the SourceFiles topic.
+    /// Note: this is only to be used internally, as this simply removes the source file map from the FSDB,
+    /// and does absolutely nothing else related to the VectorFS.
+    pub fn wb_delete_source_file_map(
+        &self,
+        db_key: &str,
+        batch: &mut ProfileBoundWriteBatch,
+    ) -> Result<(), VectorFSError> {
+        // Delete from the "SourceFiles" column family
+        batch.pb_delete_cf(FSTopic::SourceFiles.as_str(), db_key);
+
+        Ok(())
+    }
+
     /// Fetches the SourceFileMap from the DB using a VRHeader
     pub fn get_source_file_map_by_header(
         &self,
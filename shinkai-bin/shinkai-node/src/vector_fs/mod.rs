@@ -1,9 +1,13 @@
 pub mod db;
 pub mod vector_fs;
+pub mod vector_fs_duplicates;
 pub mod vector_fs_error;
+pub mod vector_fs_integrity;
 pub mod vector_fs_internals;
+pub mod vector_fs_markdown_bundle;
 pub mod vector_fs_permissions;
 pub mod vector_fs_reader;
 pub mod vector_fs_search;
+pub mod vector_fs_stats;
 pub mod vector_fs_types;
 pub mod vector_fs_writer;
@@ -1,11 +1,16 @@
+use crate::llm_provider::parsing_helper::IngestionSettings;
 use crate::welcome_files::shinkai_faq::SHINKAI_FAQ_VRKAI;
 use crate::welcome_files::shinkai_whitepaper::SHINKAI_WHITEPAPER_VRKAI;
 
 use super::vector_fs_internals::VectorFSInternals;
 
 use super::vector_fs_reader::VFSReader;
+use super::vector_fs_types::FSItem;
 use super::vector_fs_writer::VFSWriter;
-use super::{db::fs_db::VectorFSDB, vector_fs_error::VectorFSError};
+use super::{
+    db::fs_db::{FSTopic, TransactionOperation, VectorFSDB},
+    vector_fs_error::VectorFSError,
+};
 use chrono::{DateTime, Utc};
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
 use shinkai_vector_resources::embedding_generator::{EmbeddingGenerator, RemoteEmbeddingGenerator};
@@ -26,12 +31,15 @@ pub struct VectorFS {
     /// Processing content into Vector Resources should always be done outside of the VectorFS
     /// to prevent locking for long periods of time. (If VR with unsupported model is tried to be added to FS, should error, and regeneration happens externally)
     pub embedding_generator: RemoteEmbeddingGenerator,
+    /// Bytes each profile (by profile name) can store, profiles without a quota are unlimited
+    pub profile_quotas: RwLock<HashMap<String, u64>>,
 }
 
 impl VectorFS {
     /// Initializes the VectorFS struct. If no existing VectorFS exists in the VectorFSDB, then initializes from scratch.
     /// Otherwise reads from the FSDB. Requires supplying list of profiles setup in the node.
     /// Auto-initializes new profiles, setting their default embedding model to be based on the supplied embedding_generator.
+    /// The supported embedding models are also added to the ones of the existing profiles.
     pub async fn new(
         embedding_generator: RemoteEmbeddingGenerator,
         supported_embedding_models: Vec<EmbeddingModelType>,
@@ -45,7 +53,12 @@ impl VectorFS {
         let mut internals_map = HashMap::new();
         for profile in &profile_list {
             match fs_db.get_profile_fs_internals(profile) {
-                Ok(internals) => {
+                Ok(mut internals) => {
+                    for model in &supported_embedding_models {
+                        if !internals.supported_embedding_models.contains(model) {
+                            internals.supported_embedding_models.push(model.clone());
+                        }
+                    }
                     internals_map.insert(profile.clone(), internals);
                 }
                 _ => continue,
@@ -61,6 +74,7 @@ impl VectorFS {
             db: fs_db,
             embedding_generator,
             node_name: node_name.clone(),
+            profile_quotas: RwLock::new(HashMap::new()),
         };
 
         // Initialize any new profiles which don't already exist in the VectorFS
@@ -86,6 +100,7 @@ impl VectorFS {
             db,
             embedding_generator: RemoteEmbeddingGenerator::new_default(),
             node_name: ShinkaiName::from_node_name("@@node1.shinkai".to_string()).unwrap(),
+            profile_quotas: RwLock::new(HashMap::new()),
         })
     }
 
@@ -276,6 +291,57 @@ impl VectorFS {
         Ok(generator)
     }
 
+    /// Get a prepared Embedding Generator for the embedding model of the folder at the path (or the folder holding
+    /// the item at the path), which is the profile's default model unless the folder declares its own.
+    pub async fn embedding_generator_for_path(
+        &self,
+        profile: &ShinkaiName,
+        path: &VRPath,
+    ) -> Result<RemoteEmbeddingGenerator, VectorFSError> {
+        let internals = self.get_profile_fs_internals_cloned(profile).await?;
+        Ok(RemoteEmbeddingGenerator::new(
+            internals.folder_embedding_model(path),
+            &self.embedding_generator.api_url,
+            self.embedding_generator.api_key.clone(),
+        ))
+    }
+
+    /// Get a prepared Embedding Generator for the embedding model declared by the folder at the path (or by the
+    /// closest parent folder declaring one). None if no folder declares one, so files saved in the folder are embedded
+    /// with the node's generator.
+    pub async fn folder_embedding_generator_override(
+        &self,
+        profile: &ShinkaiName,
+        path: &VRPath,
+    ) -> Result<Option<RemoteEmbeddingGenerator>, VectorFSError> {
+        let internals = self.get_profile_fs_internals_cloned(profile).await?;
+        Ok(internals.folder_embedding_model_override(path).map(|model| {
+            RemoteEmbeddingGenerator::new(
+                model,
+                &self.embedding_generator.api_url,
+                self.embedding_generator.api_key.clone(),
+            )
+        }))
+    }
+
+    /// Get the Embedding Generator and ingestion settings to process the files saved in the folder at the path with.
+    /// Files are embedded with the embedding model of the folder, if it declares its own, else with the supplied ones.
+    pub async fn folder_embedding_settings(
+        &self,
+        profile: &ShinkaiName,
+        path: &VRPath,
+        generator: &dyn EmbeddingGenerator,
+        ingestion: &IngestionSettings,
+    ) -> Result<(Box<dyn EmbeddingGenerator>, IngestionSettings), VectorFSError> {
+        let folder_generator = self.folder_embedding_generator_override(profile, path).await?;
+        let ingestion = ingestion.for_folder(&folder_generator);
+        let generator: Box<dyn EmbeddingGenerator> = match folder_generator {
+            Some(folder_generator) => Box::new(folder_generator),
+            None => generator.box_clone(),
+        };
+        Ok((generator, ingestion))
+    }
+
     /// Validates the permission for a node action for a given requester ShinkaiName. Internal method.
     /// In case of error, includes requester_name automatically together with your error message
     pub fn _validate_node_action_permission(
@@ -342,6 +408,67 @@ impl VectorFS {
         Ok(())
     }
 
+    /// Sets the bytes the profile can store, or removes its quota
+    pub async fn set_profile_quota(&self, profile_name: &str, quota_bytes: Option<u64>) {
+        let mut profile_quotas = self.profile_quotas.write().await;
+        match quota_bytes {
+            Some(quota_bytes) => profile_quotas.insert(profile_name.to_lowercase(), quota_bytes),
+            None => profile_quotas.remove(&profile_name.to_lowercase()),
+        };
+    }
+
+    /// Deletes every item, source file and access log of a profile, together with its fs internals
+    pub async fn remove_profile(
+        &self,
+        requester_name: &ShinkaiName,
+        profile: &ShinkaiName,
+    ) -> Result<(), VectorFSError> {
+        self._validate_node_action_permission(requester_name, &format!("Failed removing profile {}.", profile))?;
+        let key_prefix = VectorFSDB::generate_profile_bound_key("", profile)?;
+
+        let topics = [
+            FSTopic::VectorResources,
+            FSTopic::FileSystem,
+            FSTopic::SourceFiles,
+            FSTopic::ReadAccessLogs,
+            FSTopic::WriteAccessLogs,
+            FSTopic::TempFilesInbox,
+        ];
+        let mut operations = Vec::new();
+        for topic in topics {
+            for item in self.db.iterator_cf(topic.as_str())? {
+                let (key, _) = item?;
+                if key.starts_with(key_prefix.as_bytes()) {
+                    let key = String::from_utf8_lossy(&key).to_string();
+                    operations.push(TransactionOperation::Delete(topic.as_str().to_string(), key));
+                }
+            }
+        }
+        self.db.commit_operations(operations)?;
+
+        self.internals_map.write().await.remove(profile);
+        self.set_profile_quota(&VectorFSDB::get_profile_name_string(profile)?, None)
+            .await;
+        Ok(())
+    }
+
+    /// Size of the Vector Resources and source files stored by the profile, including previous versions of items
+    pub async fn profile_storage_bytes(&self, profile: &ShinkaiName) -> Result<u64, VectorFSError> {
+        let internals = self.get_profile_fs_internals_cloned(profile).await?;
+        let mut total = 0;
+        for ret_node in internals.fs_core_resource.retrieve_vrheader_nodes_exhaustive(None) {
+            if FSItem::process_link_target_from_node(&ret_node.node).is_some() {
+                continue;
+            }
+            let (vr_size, sfm_size) = FSItem::process_sizes_from_node(&ret_node.node)?;
+            total += (vr_size + sfm_size) as u64;
+            for version in FSItem::process_versions_from_node(&ret_node.node)? {
+                total += (version.vr_size + version.source_file_map_size) as u64;
+            }
+        }
+        Ok(total)
+    }
+
     /// Updates the last read path and time for a given profile.
     pub async fn update_last_read_path(
         &self,
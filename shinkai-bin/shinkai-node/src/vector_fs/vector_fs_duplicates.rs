@@ -0,0 +1,124 @@
+use super::vector_fs_types::FSItem;
+use super::{vector_fs::VectorFS, vector_fs_error::VectorFSError, vector_fs_reader::VFSReader};
+use serde::{Deserialize, Serialize};
+use shinkai_vector_resources::embeddings::Embedding;
+use shinkai_vector_resources::vector_resource::{
+    BaseVectorResource, NodeContent, SourceFileType, VRKai, VRPath, VectorResourceCore,
+};
+use utoipa::ToSchema;
+
+/// Similarity of the embedding centroids of two documents above which one is a possible duplicate of the other
+const SIMILAR_CONTENT_THRESHOLD: f32 = 0.97;
+
+/// An item of a folder that a document about to be saved there may duplicate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PossibleDuplicate {
+    /// Name of the file of the document
+    pub name: String,
+    /// Path of the item the document may duplicate
+    pub duplicate_of: String,
+    /// Whether the source file of the item has the exact same content
+    pub same_content: bool,
+    /// Cosine similarity of the centroids of the embeddings of the document and the item
+    pub similarity: f32,
+}
+
+impl VectorFS {
+    /// Finds the item of the folder at the reader's path that the VRKai most likely duplicates: an item whose source
+    /// file has the same content, else the item whose embeddings are the most similar on average, if above
+    /// SIMILAR_CONTENT_THRESHOLD. The item the VRKai would overwrite is left out, as overwriting keeps it as a
+    /// version. Links, and items embedded with another model, are not compared.
+    pub async fn find_possible_duplicate(
+        &self,
+        reader: &VFSReader,
+        name: &str,
+        vrkai: &VRKai,
+    ) -> Result<Option<PossibleDuplicate>, VectorFSError> {
+        // Items can't be saved in the root
+        if reader.path == VRPath::root() {
+            return Err(VectorFSError::PathDoesNotPointAtFolder(reader.path.clone()));
+        }
+        let internals = self.get_profile_fs_internals_cloned(&reader.profile).await?;
+        let ret_node = internals
+            .fs_core_resource
+            .retrieve_node_at_path(reader.path.clone(), None)?;
+        let NodeContent::Resource(folder_resource) = &ret_node.node.content else {
+            return Err(VectorFSError::PathDoesNotPointAtFolder(reader.path.clone()));
+        };
+        let overwritten_name = SourceFileType::clean_string_of_extension(vrkai.resource.as_trait_object().name());
+        let folder_resource = folder_resource.as_trait_object();
+        let item_nodes: Vec<_> = folder_resource
+            .get_root_nodes_ref()
+            .into_iter()
+            .filter(|node| {
+                matches!(node.content, NodeContent::VRHeader(_))
+                    && node.id != overwritten_name
+                    && FSItem::process_link_target_from_node(node).is_none()
+            })
+            .collect();
+
+        if let Some(content_hash) = vrkai.sfm.as_ref().map(FSItem::source_file_content_hash) {
+            let same_content = item_nodes
+                .iter()
+                .find(|node| FSItem::process_source_file_content_hash_from_node(node).as_ref() == Some(&content_hash));
+            if let Some(node) = same_content {
+                return Ok(Some(PossibleDuplicate {
+                    name: name.to_string(),
+                    duplicate_of: reader.path.push_cloned(node.id.clone()).format_to_string(),
+                    same_content: true,
+                    similarity: 1.0,
+                }));
+            }
+        }
+
+        let Some(centroid) = Self::embeddings_centroid(&vrkai.resource) else {
+            return Ok(None);
+        };
+        let embedding_model = vrkai.resource.as_trait_object().embedding_model_used();
+        let mut most_similar: Option<PossibleDuplicate> = None;
+        let mut highest_similarity = SIMILAR_CONTENT_THRESHOLD;
+        for node in item_nodes {
+            let header = node.get_vr_header_content()?;
+            if header.resource_embedding_model_used != embedding_model {
+                continue;
+            }
+            let resource = self.db.get_resource(&header.reference_string(), &reader.profile)?;
+            let Some(item_centroid) = Self::embeddings_centroid(&resource) else {
+                continue;
+            };
+            let similarity = centroid.cosine_similarity(&item_centroid);
+            if similarity >= highest_similarity {
+                highest_similarity = similarity;
+                most_similar = Some(PossibleDuplicate {
+                    name: name.to_string(),
+                    duplicate_of: reader.path.push_cloned(node.id.clone()).format_to_string(),
+                    same_content: false,
+                    similarity,
+                });
+            }
+        }
+        Ok(most_similar)
+    }
+
+    /// Mean of all the embeddings of the Vector Resource, at any depth. None if it has none.
+    pub fn embeddings_centroid(resource: &BaseVectorResource) -> Option<Embedding> {
+        let embeddings = resource.as_trait_object().get_all_embeddings_flattened();
+        let dimensions = embeddings.first()?.vector.len();
+        if dimensions == 0 {
+            return None;
+        }
+        let mut sums = vec![0.0; dimensions];
+        let mut count = 0;
+        for embedding in embeddings
+            .iter()
+            .filter(|embedding| embedding.vector.len() == dimensions)
+        {
+            for (sum, value) in sums.iter_mut().zip(&embedding.vector) {
+                *sum += value;
+            }
+            count += 1;
+        }
+        let centroid = sums.into_iter().map(|sum| sum / count as f32).collect();
+        Some(Embedding::new("centroid", centroid))
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{tools::error::ToolError};
+use crate::{network::error_code::ErrorCode, tools::error::ToolError};
 use core::fmt;
 use shinkai_message_primitives::{
     schemas::{
@@ -53,7 +53,10 @@ pub enum VectorFSError {
     DateTimeParseError(String),
     FailedGettingFSPathOfRetrievedNode(String),
     CannotMoveFolderIntoItself(VRPath),
-    LockAcquisitionFailed
+    LockAcquisitionFailed,
+    ProfileQuotaExceeded(ShinkaiName, u64),
+    OperationCancelled(String),
+    ItemVersionNotFound(VRPath, u64),
 }
 
 impl fmt::Display for VectorFSError {
@@ -159,6 +162,46 @@ impl fmt::Display for VectorFSError {
             VectorFSError::FailedGettingFSPathOfRetrievedNode(s) => write!(f, "While performing 2-tier 'deep' vector search, unable to get VectorFS path of the VR the retrieved node was from: {}", s),
             VectorFSError::CannotMoveFolderIntoItself(e) => write!(f, "Cannot move folder into itself at a deeper level: {}", e),
             VectorFSError::LockAcquisitionFailed => write!(f, "Failed to acquire lock"),
+            VectorFSError::ProfileQuotaExceeded(profile, quota_bytes) => {
+                write!(f, "{} would exceed its VectorFS quota of {} bytes", profile, quota_bytes)
+            }
+            VectorFSError::OperationCancelled(e) => write!(f, "Operation cancelled: {}", e),
+            VectorFSError::ItemVersionNotFound(p, version_id) => {
+                write!(f, "Item at {} has no version {}", p, version_id)
+            }
+        }
+    }
+}
+
+impl VectorFSError {
+    /// Stable code of the error for API clients
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            VectorFSError::NoEntryAtPath(_)
+            | VectorFSError::NoPermissionEntryAtPath(_)
+            | VectorFSError::PathDoesNotPointAtItem(_)
+            | VectorFSError::PathDoesNotPointAtFolder(_)
+            | VectorFSError::NoSourceFileAvailable(_) => ErrorCode::VecfsPathNotFound,
+            VectorFSError::ItemVersionNotFound(_, _) => ErrorCode::NotFound,
+            VectorFSError::EntryAlreadyExistsAtPath(_)
+            | VectorFSError::CannotOverwriteFolder(_)
+            | VectorFSError::CannotOverwriteFSEntry(_) => ErrorCode::VecfsPathAlreadyExists,
+            VectorFSError::InvalidNodeActionPermission(_, _)
+            | VectorFSError::InvalidProfileActionPermission(_, _)
+            | VectorFSError::InvalidReaderPermission(_, _, _)
+            | VectorFSError::InvalidWriterPermission(_, _, _)
+            | VectorFSError::InvalidReadPermission(_, _)
+            | VectorFSError::InvalidWritePermission(_, _) => ErrorCode::PermissionDenied,
+            VectorFSError::ProfileNameNonExistent(_) => ErrorCode::ProfileNotFound,
+            VectorFSError::ProfileQuotaExceeded(_, _) => ErrorCode::ProfileLimitExceeded,
+            VectorFSError::OperationCancelled(_) => ErrorCode::OperationCancelled,
+            VectorFSError::CannotMoveFolderIntoItself(_)
+            | VectorFSError::InvalidFSEntryType(_)
+            | VectorFSError::InvalidMetadata(_)
+            | VectorFSError::EmbeddingModelTypeMismatch(_, _)
+            | VectorFSError::ShinkaiNameError(_)
+            | VectorFSError::ShinkaiNameLacksProfile => ErrorCode::InvalidInput,
+            _ => ErrorCode::VecfsError,
         }
     }
 }
@@ -0,0 +1,171 @@
+use super::vector_fs_types::FSItem;
+use super::{vector_fs::VectorFS, vector_fs_error::VectorFSError};
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_vector_resources::vector_resource::{BaseVectorResource, Node, NodeContent, VRPath, VectorResourceCore};
+use std::fmt;
+use utoipa::ToSchema;
+
+/// Something wrong with the data stored for an item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityProblem {
+    /// The Vector Resource of the item is not in the database
+    MissingResource,
+    /// The Vector Resource of the item can't be read back
+    UnreadableResource,
+    /// The content of a chunk doesn't match its merkle hash
+    CorruptedChunk { chunk_id: String },
+    /// The Vector Resource holds fewer chunks than when it was saved
+    MissingChunks { expected: usize, found: usize },
+    /// The merkle root of the Vector Resource doesn't match its chunks or the one of the item
+    MerkleRootMismatch,
+    /// The item was saved with source files that are not in the database anymore, or can't be read back
+    MissingSourceFiles,
+    /// The content of the source files doesn't match the hash taken when they were saved
+    CorruptedSourceFiles,
+}
+
+impl fmt::Display for IntegrityProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityProblem::MissingResource => write!(f, "missing vector resource"),
+            IntegrityProblem::UnreadableResource => write!(f, "unreadable vector resource"),
+            IntegrityProblem::CorruptedChunk { chunk_id } => write!(f, "corrupted chunk {}", chunk_id),
+            IntegrityProblem::MissingChunks { expected, found } => {
+                write!(f, "{} of {} chunks missing", expected.saturating_sub(*found), expected)
+            }
+            IntegrityProblem::MerkleRootMismatch => write!(f, "merkle root mismatch"),
+            IntegrityProblem::MissingSourceFiles => write!(f, "missing source files"),
+            IntegrityProblem::CorruptedSourceFiles => write!(f, "corrupted source files"),
+        }
+    }
+}
+
+/// The problems found with an item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ItemIntegrityReport {
+    pub path: String,
+    pub problems: Vec<IntegrityProblem>,
+}
+
+impl VectorFS {
+    /// Re-validates the merkle hashes of the Vector Resources and the content hashes of the source files of every
+    /// item of the profile. Only the items with problems are returned. Links are checked through their target.
+    pub async fn verify_profile_integrity(
+        &self,
+        profile: &ShinkaiName,
+    ) -> Result<Vec<ItemIntegrityReport>, VectorFSError> {
+        let internals = self.get_profile_fs_internals_cloned(profile).await?;
+        let core_resource = BaseVectorResource::Map(internals.fs_core_resource);
+        let mut reports = Vec::new();
+        self.verify_folder_integrity(&core_resource, VRPath::root(), profile, &mut reports);
+        Ok(reports)
+    }
+
+    fn verify_folder_integrity(
+        &self,
+        folder_resource: &BaseVectorResource,
+        path: VRPath,
+        profile: &ShinkaiName,
+        reports: &mut Vec<ItemIntegrityReport>,
+    ) {
+        for node in folder_resource.as_trait_object().get_root_nodes_ref() {
+            let node_path = path.push_cloned(node.id.clone());
+            match &node.content {
+                NodeContent::Resource(child_resource) => {
+                    self.verify_folder_integrity(child_resource, node_path, profile, reports)
+                }
+                NodeContent::VRHeader(_) if FSItem::process_link_target_from_node(node).is_some() => continue,
+                NodeContent::VRHeader(_) => {
+                    let problems = self.verify_item_integrity(node, profile);
+                    if !problems.is_empty() {
+                        reports.push(ItemIntegrityReport {
+                            path: node_path.format_to_string(),
+                            problems,
+                        });
+                    }
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    /// Checks the data stored for the FSItem node against the hashes and counts kept in the node
+    pub fn verify_item_integrity(&self, node: &Node, profile: &ShinkaiName) -> Vec<IntegrityProblem> {
+        let mut problems = Vec::new();
+        let Ok(header) = node.get_vr_header_content() else {
+            return vec![IntegrityProblem::UnreadableResource];
+        };
+
+        match self.db.get_resource(&header.reference_string(), profile) {
+            Ok(resource) => {
+                problems.extend(Self::verify_resource_integrity(&resource));
+                let stored_root = resource.as_trait_object().get_merkle_root().ok();
+                if header.resource_merkle_root.is_some()
+                    && stored_root.is_some()
+                    && header.resource_merkle_root != stored_root
+                    && !problems.contains(&IntegrityProblem::MerkleRootMismatch)
+                {
+                    problems.push(IntegrityProblem::MerkleRootMismatch);
+                }
+                if let Some((expected, _)) = FSItem::process_chunk_counts_from_node(node) {
+                    let (found, _) = FSItem::count_chunks_and_tokens(&resource);
+                    if found < expected {
+                        problems.push(IntegrityProblem::MissingChunks { expected, found });
+                    }
+                }
+            }
+            Err(VectorFSError::FailedFetchingValue) => problems.push(IntegrityProblem::MissingResource),
+            Err(_) => problems.push(IntegrityProblem::UnreadableResource),
+        }
+
+        let has_source_files =
+            FSItem::process_datetimes_from_node(node).is_ok_and(|(_, sfm_saved)| sfm_saved.is_some());
+        if has_source_files {
+            match self.db.get_source_file_map(&header.reference_string(), profile) {
+                Ok(source_file_map) => {
+                    let content_hash = FSItem::process_source_file_content_hash_from_node(node);
+                    if content_hash.is_some_and(|hash| hash != FSItem::source_file_content_hash(&source_file_map)) {
+                        problems.push(IntegrityProblem::CorruptedSourceFiles);
+                    }
+                }
+                Err(_) => problems.push(IntegrityProblem::MissingSourceFiles),
+            }
+        }
+        problems
+    }
+
+    /// Recomputes the merkle hashes of the chunks of the Vector Resource and of its nested resources, and the merkle
+    /// roots above them. Resources that aren't merkelized are not checked.
+    pub fn verify_resource_integrity(resource: &BaseVectorResource) -> Vec<IntegrityProblem> {
+        let mut problems = Vec::new();
+        let resource = resource.as_trait_object();
+        let Ok(merkle_root) = resource.get_merkle_root() else {
+            return problems;
+        };
+
+        let mut node_hashes = Vec::new();
+        for node in resource.get_root_nodes_ref() {
+            if let NodeContent::Resource(child_resource) = &node.content {
+                problems.extend(Self::verify_resource_integrity(child_resource));
+            } else if let (Some(stored_hash), Ok(hash)) = (&node.merkle_hash, node._generate_merkle_hash()) {
+                if *stored_hash != hash {
+                    problems.push(IntegrityProblem::CorruptedChunk {
+                        chunk_id: node.id.clone(),
+                    });
+                }
+            }
+            match node.get_merkle_hash() {
+                Ok(hash) => node_hashes.push(hash),
+                Err(_) => return problems,
+            }
+        }
+
+        let root_hash = blake3::hash(node_hashes.join("").as_bytes()).to_hex().to_string();
+        if root_hash != merkle_root && !problems.contains(&IntegrityProblem::MerkleRootMismatch) {
+            problems.push(IntegrityProblem::MerkleRootMismatch);
+        }
+        problems
+    }
+}
@@ -1,6 +1,6 @@
 use super::{
     vector_fs_permissions::PermissionsIndex,
-    vector_fs_types::{LastReadIndex, SubscriptionsIndex},
+    vector_fs_types::{FSFolder, LastReadIndex, SubscriptionsIndex},
 };
 use serde_json;
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
@@ -8,7 +8,7 @@ use shinkai_vector_resources::{
     embeddings::Embedding,
     model_type::{EmbeddingModelType, OllamaTextEmbeddingsInference},
     source::DistributionInfo,
-    vector_resource::{MapVectorResource, VRSourceReference, VectorResourceCore},
+    vector_resource::{MapVectorResource, VRPath, VRSourceReference, VectorResourceCore},
 };
 use std::collections::HashMap;
 
@@ -62,6 +62,28 @@ impl VectorFSInternals {
         self.fs_core_resource.embedding_model_used()
     }
 
+    /// Returns the embedding model declared by the folder at the path, or else by the closest parent folder which
+    /// declares one. For an item path, it's the model of the folder holding it. None if no folder declares one.
+    pub fn folder_embedding_model_override(&self, path: &VRPath) -> Option<EmbeddingModelType> {
+        let mut path = path.clone();
+        while !path.is_empty() {
+            if let Ok(ret_node) = self.fs_core_resource.retrieve_node_at_path(path.clone(), None) {
+                if let Some(model) = FSFolder::process_embedding_model_from_node(&ret_node.node) {
+                    return Some(model);
+                }
+            }
+            path = path.parent_path();
+        }
+        None
+    }
+
+    /// Returns the embedding model the FSItems saved at the path are embedded with: the one declared by its
+    /// folder (or a parent folder), else the profile's default.
+    pub fn folder_embedding_model(&self, path: &VRPath) -> EmbeddingModelType {
+        self.folder_embedding_model_override(path)
+            .unwrap_or_else(|| self.default_embedding_model())
+    }
+
     /// A hard-coded DB key for the profile-wide VectorFSInternals.
     pub fn profile_fs_internals_shinkai_db_key() -> String {
         "profile_vector_fs_internals".to_string()
@@ -0,0 +1,285 @@
+use super::vector_fs_types::{FSEntry, FSFolder, FSItem};
+use super::{vector_fs::VectorFS, vector_fs_error::VectorFSError, vector_fs_reader::VFSReader};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use shinkai_vector_resources::source::VRSourceReference;
+use shinkai_vector_resources::vector_resource::{BaseVectorResource, NodeContent, VectorResource, VectorResourceCore};
+use std::path::{Path, PathBuf};
+
+/// A Vector Resource as a plain markdown document: its text chunks in order, with its metadata as front matter.
+/// Front matter values are written as JSON, which YAML reads too, so bundles of these documents can be edited in
+/// tools like Obsidian and imported back.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MarkdownBundleDocument {
+    pub name: String,
+    pub description: Option<String>,
+    pub keywords: Vec<String>,
+    /// Where the resource came from. Only informative, imported documents have the markdown file as their source.
+    pub source: Option<String>,
+    /// Only informative, imported documents get a new id
+    pub resource_id: Option<String>,
+    pub last_written_datetime: Option<DateTime<Utc>>,
+    pub body: String,
+}
+
+impl MarkdownBundleDocument {
+    pub fn from_resource(resource: &BaseVectorResource) -> Self {
+        let resource = resource.as_trait_object();
+        let source = match resource.source() {
+            VRSourceReference::None => None,
+            source => Some(source.format_source_string()),
+        };
+        let mut body = String::new();
+        Self::push_nodes_markdown(*resource, 2, &mut body);
+
+        MarkdownBundleDocument {
+            name: resource.name().to_string(),
+            description: resource.description().map(|description| description.to_string()),
+            keywords: resource.keywords().keyword_list.clone(),
+            source,
+            resource_id: Some(resource.resource_id().to_string()),
+            last_written_datetime: Some(resource.last_written_datetime()),
+            body: body.trim_end().to_string(),
+        }
+    }
+
+    /// Appends the text of the nodes in order. Nested resources become sections headed by their name.
+    fn push_nodes_markdown(resource: &dyn VectorResource, heading_level: usize, body: &mut String) {
+        for node in resource.get_root_nodes_ref() {
+            match &node.content {
+                NodeContent::Text(text) => {
+                    body.push_str(text.trim());
+                    body.push_str("\n\n");
+                }
+                NodeContent::Resource(child) => {
+                    let child = child.as_trait_object();
+                    body.push_str(&format!("{} {}\n\n", "#".repeat(heading_level.min(6)), child.name()));
+                    Self::push_nodes_markdown(*child, heading_level + 1, body);
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut front_matter = vec![("name", Value::from(self.name.clone()))];
+        if let Some(description) = &self.description {
+            front_matter.push(("description", Value::from(description.clone())));
+        }
+        if !self.keywords.is_empty() {
+            front_matter.push(("keywords", Value::from(self.keywords.clone())));
+        }
+        if let Some(source) = &self.source {
+            front_matter.push(("source", Value::from(source.clone())));
+        }
+        if let Some(resource_id) = &self.resource_id {
+            front_matter.push(("resource_id", Value::from(resource_id.clone())));
+        }
+        if let Some(datetime) = &self.last_written_datetime {
+            front_matter.push(("last_written_datetime", Value::from(datetime.to_rfc3339())));
+        }
+
+        let mut document = String::from("---\n");
+        for (key, value) in front_matter {
+            document.push_str(&format!("{}: {}\n", key, value));
+        }
+        document.push_str(&format!("---\n\n{}\n", self.body));
+        document
+    }
+
+    /// Reads a markdown document, named after its file unless its front matter names it. The front matter may also
+    /// be hand-written YAML with plain scalars and block lists, and Obsidian `tags` are read as keywords.
+    pub fn parse(markdown: &str, file_name: &str) -> Self {
+        let mut document = MarkdownBundleDocument {
+            name: file_name.strip_suffix(".md").unwrap_or(file_name).to_string(),
+            ..Default::default()
+        };
+
+        let markdown = markdown.trim_start_matches('\u{feff}');
+        let Some((front_matter, body)) = markdown.strip_prefix("---\n").and_then(|rest| {
+            rest.split_once("\n---\n")
+                .or_else(|| rest.strip_suffix("\n---").map(|fm| (fm, "")))
+        }) else {
+            document.body = markdown.trim().to_string();
+            return document;
+        };
+        document.body = body.trim().to_string();
+
+        let mut entries: Vec<(String, Value)> = Vec::new();
+        for line in front_matter.lines() {
+            if let Some(item) = line.trim_start().strip_prefix("- ") {
+                if let Some((_, Value::Array(items))) = entries.last_mut() {
+                    items.push(Self::parse_value(item));
+                }
+                continue;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = match value.trim() {
+                "" => Value::Array(Vec::new()),
+                value => Self::parse_value(value),
+            };
+            entries.push((key.trim().to_string(), value));
+        }
+
+        for (key, value) in entries {
+            match (key.as_str(), value) {
+                ("name" | "title", Value::String(name)) if !name.is_empty() => document.name = name,
+                ("description", Value::String(description)) => document.description = Some(description),
+                ("keywords" | "tags", Value::Array(items)) => document.keywords.extend(
+                    items
+                        .into_iter()
+                        .filter_map(|item| item.as_str().map(|keyword| keyword.to_string())),
+                ),
+                ("keywords" | "tags", Value::String(keyword)) => document.keywords.push(keyword),
+                ("source", Value::String(source)) => document.source = Some(source),
+                ("resource_id", Value::String(resource_id)) => document.resource_id = Some(resource_id),
+                ("last_written_datetime", Value::String(datetime)) => {
+                    document.last_written_datetime = DateTime::parse_from_rfc3339(&datetime)
+                        .ok()
+                        .map(|datetime| datetime.with_timezone(&Utc))
+                }
+                _ => continue,
+            }
+        }
+        document
+    }
+
+    /// JSON values as written on export, anything else as a plain string
+    fn parse_value(value: &str) -> Value {
+        let value = value.trim();
+        serde_json::from_str(value).unwrap_or_else(|_| Value::from(value.trim_matches(|c| c == '\'' || c == '"')))
+    }
+
+    /// Sets the name, description and keywords of the document on the resource its body was processed into
+    pub fn apply_to(&self, resource: &mut BaseVectorResource) {
+        let mut resource = resource.as_trait_object_mut();
+        resource.set_name(self.name.clone());
+        if self.description.is_some() {
+            resource.set_description(self.description.clone());
+        }
+        if !self.keywords.is_empty() {
+            resource.keywords_mut().set_keywords(self.keywords.clone());
+        }
+    }
+
+    /// File name of the document of an item, without the characters file systems don't allow
+    pub fn file_name(name: &str) -> String {
+        let name: String = name
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+                c => c,
+            })
+            .collect();
+        format!("{}.md", name)
+    }
+
+    /// Reads the markdown documents of a bundle directory, with the path of their subdirectory as folder names.
+    /// Hidden files and directories, like the `.obsidian` settings, are skipped.
+    pub fn read_bundle(dir: &Path) -> Result<Vec<(Vec<String>, MarkdownBundleDocument)>, VectorFSError> {
+        let mut documents = Vec::new();
+        Self::read_bundle_dir(dir, &mut Vec::new(), &mut documents)?;
+        Ok(documents)
+    }
+
+    fn read_bundle_dir(
+        dir: &Path,
+        folders: &mut Vec<String>,
+        documents: &mut Vec<(Vec<String>, MarkdownBundleDocument)>,
+    ) -> Result<(), VectorFSError> {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)?.flatten().map(|entry| entry.path()).collect();
+        entries.sort();
+        for path in entries {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            if file_name.starts_with('.') {
+                continue;
+            }
+            if path.is_dir() {
+                folders.push(file_name);
+                Self::read_bundle_dir(&path, folders, documents)?;
+                folders.pop();
+            } else if file_name.to_lowercase().ends_with(".md") {
+                let markdown = std::fs::read_to_string(&path)?;
+                documents.push((folders.clone(), Self::parse(&markdown, &file_name)));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl VectorFS {
+    /// Writes the items in the folder (or root) at the reader's path as markdown documents into the directory,
+    /// with a subdirectory per folder. Links are skipped. Returns the number of documents written.
+    pub async fn export_markdown_bundle(&self, reader: &VFSReader, dir: &Path) -> Result<u64, VectorFSError> {
+        let mut items = Vec::new();
+        match self.retrieve_fs_entry(reader).await? {
+            FSEntry::Root(root) => {
+                for folder in &root.child_folders {
+                    Self::collect_bundle_items(folder, dir.join(&folder.name), &mut items);
+                }
+            }
+            FSEntry::Folder(folder) => Self::collect_bundle_items(&folder, dir.to_path_buf(), &mut items),
+            FSEntry::Item(_) => return Err(VectorFSError::PathDoesNotPointAtFolder(reader.path.clone())),
+        }
+
+        tokio::fs::create_dir_all(dir).await?;
+        let mut count = 0;
+        for (item_dir, item) in items {
+            let resource = self.db.get_resource_by_fs_item(&item, &reader.profile)?;
+            let document = MarkdownBundleDocument::from_resource(&resource);
+            tokio::fs::create_dir_all(&item_dir).await?;
+            tokio::fs::write(
+                item_dir.join(MarkdownBundleDocument::file_name(&item.name)),
+                document.to_markdown(),
+            )
+            .await?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn collect_bundle_items(folder: &FSFolder, dir: PathBuf, items: &mut Vec<(PathBuf, FSItem)>) {
+        for item in folder.child_items.iter().filter(|item| !item.is_link()) {
+            items.push((dir.clone(), item.clone()));
+        }
+        for child in &folder.child_folders {
+            Self::collect_bundle_items(child, dir.join(&child.name), items);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_bundle_document_round_trip() {
+        let document = MarkdownBundleDocument {
+            name: "Meeting: notes".to_string(),
+            description: Some("Weekly sync".to_string()),
+            keywords: vec!["roadmap".to_string(), "q3".to_string()],
+            source: Some("notes.pdf".to_string()),
+            resource_id: Some("abc".to_string()),
+            last_written_datetime: DateTime::parse_from_rfc3339("2024-05-01T10:00:00+00:00")
+                .ok()
+                .map(|datetime| datetime.with_timezone(&Utc)),
+            body: "First chunk\n\nSecond chunk".to_string(),
+        };
+        assert_eq!(
+            MarkdownBundleDocument::parse(&document.to_markdown(), "other.md"),
+            document
+        );
+        assert_eq!(MarkdownBundleDocument::file_name(&document.name), "Meeting_ notes.md");
+
+        let edited = MarkdownBundleDocument::parse(
+            "---\ndescription: Edited by hand\ntags:\n  - a\n  - b\n---\nBody",
+            "Notes.md",
+        );
+        assert_eq!(edited.name, "Notes");
+        assert_eq!(edited.description.as_deref(), Some("Edited by hand"));
+        assert_eq!(edited.keywords, vec!["a", "b"]);
+        assert_eq!(edited.body, "Body");
+    }
+}
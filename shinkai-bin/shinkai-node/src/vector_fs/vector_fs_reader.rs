@@ -1,12 +1,13 @@
 use super::vector_fs::VectorFS;
 use super::vector_fs_error::VectorFSError;
-use super::vector_fs_types::{FSEntry, FSFolder, FSItem, FSRoot};
+use super::vector_fs_types::{FSEntry, FSFolder, FSItem, FSItemMetadataChange, FSItemVersion, FSRoot};
 use super::vector_fs_writer::VFSWriter;
 use crate::db::db_profile_bound::ProfileBoundWriteBatch;
 use async_recursion::async_recursion;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_vector_resources::embeddings::Embedding;
 use shinkai_vector_resources::resource_errors::VRError;
 use shinkai_vector_resources::shinkai_time::ShinkaiTime;
 use shinkai_vector_resources::source::SourceFileMap;
@@ -170,6 +171,35 @@ impl VectorFS {
         self.db.get_source_file_map_by_fs_item(&fs_item, &reader.profile)
     }
 
+    /// Retrieves the previous versions of the FSItem at the path specified in reader, oldest first.
+    pub async fn retrieve_item_versions(&self, reader: &VFSReader) -> Result<Vec<FSItemVersion>, VectorFSError> {
+        self.validate_path_points_to_item(reader.path.clone(), &reader.profile)
+            .await?;
+        let ret_node = self
+            ._retrieve_core_resource_node_at_path(reader.path.clone(), &reader.profile)
+            .await?;
+        FSItem::process_versions_from_node(&ret_node.node)
+    }
+
+    /// Compares the metadata of a previous version of the FSItem at the path specified in reader with the
+    /// metadata it has now.
+    pub async fn diff_item_version_metadata(
+        &self,
+        reader: &VFSReader,
+        version_id: u64,
+    ) -> Result<Vec<FSItemMetadataChange>, VectorFSError> {
+        self.validate_path_points_to_item(reader.path.clone(), &reader.profile)
+            .await?;
+        let ret_node = self
+            ._retrieve_core_resource_node_at_path(reader.path.clone(), &reader.profile)
+            .await?;
+        let version = FSItem::process_versions_from_node(&ret_node.node)?
+            .into_iter()
+            .find(|version| version.version_id == version_id)
+            .ok_or(VectorFSError::ItemVersionNotFound(reader.path.clone(), version_id))?;
+        version.diff_metadata(&ret_node.node)
+    }
+
     /// Attempts to retrieve a VRKai from the path specified in reader (errors if entry at path is not an item).
     pub async fn retrieve_vrkai(&self, reader: &VFSReader) -> Result<VRKai, VectorFSError> {
         let fs_item = self.retrieve_fs_entry(reader).await?.as_item()?;
@@ -351,6 +381,28 @@ impl VectorFS {
         }
     }
 
+    /// Centroid of the embeddings held in the folder at any depth, i.e. of its items' and subfolders' embeddings.
+    /// Read from the core resource in memory, so it's cheap enough to compute per query. None if the folder is empty.
+    pub async fn folder_embeddings_centroid(
+        &self,
+        path: VRPath,
+        profile: &ShinkaiName,
+    ) -> Result<Option<Embedding>, VectorFSError> {
+        let internals_map = self.internals_map.read().await;
+        let internals = internals_map
+            .get(profile)
+            .ok_or_else(|| VectorFSError::ProfileNameNonExistent(profile.to_string()))?;
+        let ret_node = internals
+            .fs_core_resource
+            .retrieve_node_at_path(path.clone(), None)
+            .map_err(|_| VectorFSError::NoEntryAtPath(path.clone()))?;
+
+        match &ret_node.node.content {
+            NodeContent::Resource(resource) => Ok(Self::embeddings_centroid(resource)),
+            _ => Err(VectorFSError::PathDoesNotPointAtFolder(path)),
+        }
+    }
+
     /// Validates that the path points to a FSItem
     pub async fn validate_path_points_to_item(&self, path: VRPath, profile: &ShinkaiName) -> Result<(), VectorFSError> {
         let ret_node = self._retrieve_core_resource_node_at_path(path.clone(), profile).await?;
@@ -2,7 +2,8 @@ use super::vector_fs_types::FSItem;
 use super::{vector_fs::VectorFS, vector_fs_error::VectorFSError, vector_fs_reader::VFSReader};
 use crate::vector_fs::vector_fs_permissions::PermissionsIndex;
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
-use shinkai_vector_resources::embedding_generator::EmbeddingGenerator;
+use shinkai_vector_resources::embedding_generator::{EmbeddingGenerator, RemoteEmbeddingGenerator};
+use shinkai_vector_resources::model_type::EmbeddingModelType;
 use shinkai_vector_resources::source::SourceFileMap;
 use shinkai_vector_resources::vector_resource::{
     deep_search_scores_average_out, BaseVectorResource, LimitTraversalMode, Node, NodeContent, ScoringMode, VRHeader,
@@ -12,7 +13,11 @@ use shinkai_vector_resources::{
     embeddings::Embedding,
     vector_resource::{RetrievedNode, TraversalMethod, TraversalOption, VRPath, VectorResourceSearch},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use tracing::instrument;
+
+/// Constant of the reciprocal rank fusion merging the rankings of searches whose scores can't be compared
+const RANK_FUSION_K: f32 = 60.0;
 
 /// A retrieved node from within a Vector Resource inside of the VectorFS.
 /// Includes the path of the FSItem in the VectorFS and the retrieved node
@@ -70,13 +75,15 @@ impl VectorFS {
     }
 
     /// Generates an Embedding for the input query to be used in a Vector Search in the VecFS.
-    /// This automatically uses the correct default embedding model for the given profile in reader.
+    /// This automatically uses the embedding model of the folder at the reader's path, which is the profile's
+    /// default model unless the folder (or a parent folder) declares its own.
     pub async fn generate_query_embedding_using_reader(
         &self,
         input_query: String,
         reader: &VFSReader,
     ) -> Result<Embedding, VectorFSError> {
-        self.generate_query_embedding(input_query, &reader.profile).await
+        let generator = self.embedding_generator_for_path(&reader.profile, &reader.path).await?;
+        Ok(generator.generate_embedding_default(&input_query).await?)
     }
 
     /// Performs a "deep" vector search into the VectorFS starting at the reader's path,
@@ -105,6 +112,7 @@ impl VectorFS {
     /// vector search into each Vector Resource (inside the FSItem) to find and return the highest scored nodes.
     /// Allows specifying custom deep_traversal_options which are used when searching into the VRs themselves.
     /// average_out_deep_search_scores: If true, averages out the VR top level search score across the VectorFS, with the scores of the nodes inside the VR.
+    #[instrument(skip_all, fields(path = %reader.path, num_of_resources_to_search_into, num_of_results))]
     pub async fn deep_vector_search_customized(
         &self,
         reader: &VFSReader,
@@ -114,14 +122,10 @@ impl VectorFS {
         deep_traversal_options: Vec<TraversalOption>,
         average_out_deep_search_scores: bool,
     ) -> Result<Vec<FSRetrievedNode>, VectorFSError> {
-        let query = self
-            .generate_query_embedding_using_reader(query_text.clone(), reader)
-            .await?;
-
         let mut ret_nodes = Vec::new();
         let mut fs_path_hashmap = HashMap::new();
         let items_with_scores = self
-            .vector_search_fs_item_with_score(reader, query.clone(), num_of_resources_to_search_into)
+            .vector_search_fs_item_with_score_multi_model(reader, &query_text, num_of_resources_to_search_into)
             .await?;
 
         for (item, score) in items_with_scores {
@@ -129,7 +133,9 @@ impl VectorFS {
                 if let Ok(resource) = self.retrieve_vector_resource(&new_reader).await {
                     fs_path_hashmap.insert(resource.as_trait_object().reference_string(), item.path);
 
-                    let generator = self._get_embedding_generator(&reader.profile).await?;
+                    let generator = self
+                        .embedding_generator_for_path(&reader.profile, &new_reader.path)
+                        .await?;
                     let mut results = resource
                         .as_trait_object()
                         .dynamic_vector_search_customized(
@@ -188,6 +194,7 @@ impl VectorFS {
 
     /// Performs a vector search into the VectorFS starting at the reader's path,
     /// returning the retrieved (FSItem, score) pairs extracted from the VRHeader-holding nodes
+    #[instrument(skip_all, fields(path = %reader.path, num_of_results))]
     pub async fn vector_search_fs_item_with_score(
         &self,
         reader: &VFSReader,
@@ -200,6 +207,8 @@ impl VectorFS {
         let internals = self.get_profile_fs_internals_cloned(&reader.profile).await?;
 
         let mut fs_items_with_scores = vec![];
+        // Links share the Vector Resource of their target, which is only returned once
+        let mut seen_resources = HashSet::new();
         for ret_node in ret_nodes {
             if let NodeContent::VRHeader(_) = ret_node.node.content {
                 let item = FSItem::from_vr_header_node(
@@ -207,12 +216,180 @@ impl VectorFS {
                     ret_node.retrieval_path,
                     &internals.last_read_index,
                 )?;
+                if !seen_resources.insert(item.resource_db_key()) {
+                    continue;
+                }
                 fs_items_with_scores.push((item, ret_node.score));
             }
         }
         Ok(fs_items_with_scores)
     }
 
+    /// Performs a vector search into the VectorFS starting at the reader's path, returning the (FSItem, score) pairs.
+    /// FSItems embedded with another model than the one of the reader's folder (ie. documents in other languages
+    /// embedded with the multilingual model) are also scored, by embedding the query text with each of their models.
+    /// Ignores FSItems which the requester_name does not have permission to read.
+    pub async fn vector_search_fs_item_with_score_multi_model(
+        &self,
+        reader: &VFSReader,
+        query_text: &str,
+        num_of_results: u64,
+    ) -> Result<Vec<(FSItem, f32)>, VectorFSError> {
+        let generator = self.embedding_generator_for_path(&reader.profile, &reader.path).await?;
+        let query_model = generator.model_type();
+        let query = generator.generate_embedding_default(query_text).await?;
+        // The FSItems of other models are scored below, as their embeddings can't be compared with the query
+        let mut items_with_scores: Vec<(FSItem, f32)> = self
+            .vector_search_fs_item_with_score(reader, query, num_of_results)
+            .await?
+            .into_iter()
+            .filter(|(item, _)| item.vr_header.resource_embedding_model_used == query_model)
+            .collect();
+
+        let internals = self.get_profile_fs_internals_cloned(&reader.profile).await?;
+        let mut seen_resources: HashSet<String> = items_with_scores
+            .iter()
+            .map(|(item, _)| item.resource_db_key())
+            .collect();
+        let mut model_queries: HashMap<EmbeddingModelType, Embedding> = HashMap::new();
+        let ret_nodes = internals
+            .fs_core_resource
+            .retrieve_vrheader_nodes_exhaustive(Some(reader.path.clone()));
+        for ret_node in ret_nodes {
+            let (model, resource_embedding) = match &ret_node.node.content {
+                NodeContent::VRHeader(VRHeader {
+                    resource_embedding_model_used,
+                    resource_embedding: Some(resource_embedding),
+                    ..
+                }) if *resource_embedding_model_used != query_model => {
+                    (resource_embedding_model_used.clone(), resource_embedding.clone())
+                }
+                _ => continue,
+            };
+            if internals
+                .permissions_index
+                .validate_read_access(&reader.requester_name, &ret_node.retrieval_path)
+                .is_err()
+            {
+                continue;
+            }
+            let item = FSItem::from_vr_header_node(ret_node.node, ret_node.retrieval_path, &internals.last_read_index)?;
+            if !seen_resources.insert(item.resource_db_key()) {
+                continue;
+            }
+
+            let model_query = match model_queries.get(&model) {
+                Some(model_query) => model_query.clone(),
+                None => {
+                    let model_generator =
+                        RemoteEmbeddingGenerator::new(model.clone(), &generator.api_url, generator.api_key.clone());
+                    let model_query = model_generator.generate_embedding_default(query_text).await?;
+                    model_queries.insert(model, model_query.clone());
+                    model_query
+                }
+            };
+            items_with_scores.push((item, model_query.cosine_similarity(&resource_embedding)));
+        }
+        if model_queries.is_empty() {
+            return Ok(items_with_scores);
+        }
+
+        // The scores of the different models are normalized before being ranked together
+        for (item, score) in items_with_scores.iter_mut() {
+            *score *= item
+                .vr_header
+                .resource_embedding_model_used
+                .embedding_normalization_factor();
+        }
+        items_with_scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        items_with_scores.truncate(num_of_results as usize);
+        Ok(items_with_scores)
+    }
+
+    /// Performs a vector search over the images saved underneath the reader's path, returning the (FSItem, score)
+    /// pairs of the images most similar to the query. The query must be generated by the image embedding model
+    /// (ie. a text embedding of the same CLIP model), images embedded by other models are skipped.
+    /// Ignores images which the requester_name does not have permission to read.
+    pub async fn vector_search_images_with_score(
+        &self,
+        reader: &VFSReader,
+        query: Embedding,
+        image_model: &EmbeddingModelType,
+        num_of_results: u64,
+    ) -> Result<Vec<(FSItem, f32)>, VectorFSError> {
+        let internals = self.get_profile_fs_internals_cloned(&reader.profile).await?;
+        let ret_nodes = internals
+            .fs_core_resource
+            .retrieve_vrheader_nodes_exhaustive(Some(reader.path.clone()));
+
+        let mut images_with_scores = vec![];
+        let mut seen_resources = HashSet::new();
+        for ret_node in ret_nodes {
+            let image_embedding = match FSItem::process_image_embedding_from_node(&ret_node.node, image_model) {
+                Some(image_embedding) => image_embedding,
+                None => continue,
+            };
+            if internals
+                .permissions_index
+                .validate_read_access(&reader.requester_name, &ret_node.retrieval_path)
+                .is_err()
+            {
+                continue;
+            }
+            let item = FSItem::from_vr_header_node(ret_node.node, ret_node.retrieval_path, &internals.last_read_index)?;
+            if !seen_resources.insert(item.resource_db_key()) {
+                continue;
+            }
+            images_with_scores.push((item, query.cosine_similarity(&image_embedding)));
+        }
+
+        images_with_scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        images_with_scores.truncate(num_of_results as usize);
+        Ok(images_with_scores)
+    }
+
+    /// Finds the FSItems underneath the reader's path for a text query. With an image embedding generator, images
+    /// are also found by their image embeddings, and both rankings are merged by reciprocal rank fusion, as the
+    /// scores of different embedding models can't be compared.
+    pub async fn search_fs_items_with_images(
+        &self,
+        reader: &VFSReader,
+        query_text: String,
+        image_generator: Option<&RemoteEmbeddingGenerator>,
+        num_of_results: u64,
+    ) -> Result<Vec<FSItem>, VectorFSError> {
+        let query = self
+            .generate_query_embedding_using_reader(query_text.clone(), reader)
+            .await?;
+        let items = self.vector_search_fs_item(reader, query, num_of_results).await?;
+        let image_generator = match image_generator {
+            Some(image_generator) => image_generator,
+            None => return Ok(items),
+        };
+
+        let image_query = image_generator.generate_embedding_default(&query_text).await?;
+        let images = self
+            .vector_search_images_with_score(reader, image_query, &image_generator.model_type(), num_of_results)
+            .await?;
+
+        let mut fused: HashMap<String, (FSItem, f32)> = HashMap::new();
+        let rankings = [items, images.into_iter().map(|(item, _)| item).collect()];
+        for ranking in rankings {
+            for (rank, item) in ranking.into_iter().enumerate() {
+                let rank_score = 1.0 / (RANK_FUSION_K + rank as f32 + 1.0);
+                fused.entry(item.path.to_string()).or_insert_with(|| (item, 0.0)).1 += rank_score;
+            }
+        }
+
+        let mut fused: Vec<(FSItem, f32)> = fused.into_values().collect();
+        fused.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(fused
+            .into_iter()
+            .take(num_of_results as usize)
+            .map(|(item, _)| item)
+            .collect())
+    }
+
     /// Performs a vector search into the VectorFS starting at the reader's path,
     /// returning the retrieved VRKai of the most similar FSItems.
     /// Ignores FSItem results which the requester_name does not have permission to read.
@@ -318,11 +495,16 @@ impl VectorFS {
     ) -> Result<Vec<RetrievedNode>, VectorFSError> {
         let mut traversal_options = traversal_options.clone();
         let internals = self.get_profile_fs_internals_cloned(&reader.profile).await?;
-        let stringified_permissions_map = internals
+        let mut stringified_permissions_map = internals
             .permissions_index
             .export_permissions_hashmap_with_reader(reader)
             .await;
 
+        // The query is embedded with the model of the reader's folder, so folders which declare another model
+        // aren't traversed, as their items' embeddings can't be compared with the query
+        let query_model = internals.folder_embedding_model(&reader.path);
+        stringified_permissions_map.retain(|path, _| internals.folder_embedding_model(path) == query_model);
+
         // Search without unique scoring (ie. hierarchical) because "folders" have no content/real embedding.
         // Also remove any set traversal limit, so we can enforce folder permission traversal limiting.
         traversal_options.retain(|option| match option {
@@ -0,0 +1,165 @@
+use super::vector_fs_types::{FSFolder, FSItem};
+use super::{vector_fs::VectorFS, vector_fs_error::VectorFSError, vector_fs_reader::VFSReader};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_vector_resources::shinkai_time::ShinkaiTime;
+use shinkai_vector_resources::vector_resource::{BaseVectorResource, Node, NodeContent, VRPath, VectorResourceCore};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use utoipa::ToSchema;
+
+lazy_static! {
+    /// Stats of folders and items by merkle hash and last written datetime, which change whenever anything under
+    /// them changes. Unchanged subfolders are thus not walked again when the stats of a folder above them are
+    /// recomputed.
+    static ref CACHED_STATS: Mutex<HashMap<String, FolderStats>> = Mutex::new(HashMap::new());
+}
+
+/// The cache is cleared once it holds this many entries, outdated hashes are never looked up again
+const MAX_CACHED_STATS: usize = 10_000;
+
+/// Totals of everything held within a folder, at any depth
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, ToSchema)]
+pub struct FolderStats {
+    pub folder_count: u64,
+    pub item_count: u64,
+    /// Text chunks embedded in the Vector Resources of the items
+    pub chunk_count: u64,
+    pub embedded_tokens: u64,
+    pub vector_resource_bytes: u64,
+    pub source_file_bytes: u64,
+    /// Latest write to the folder itself or to anything within it
+    #[schema(value_type = Option<String>)]
+    pub last_modified_datetime: Option<DateTime<Utc>>,
+}
+
+impl FolderStats {
+    fn add(&mut self, other: &FolderStats) {
+        self.folder_count += other.folder_count;
+        self.item_count += other.item_count;
+        self.chunk_count += other.chunk_count;
+        self.embedded_tokens += other.embedded_tokens;
+        self.vector_resource_bytes += other.vector_resource_bytes;
+        self.source_file_bytes += other.source_file_bytes;
+        self.update_last_modified(other.last_modified_datetime);
+    }
+
+    fn update_last_modified(&mut self, datetime: Option<DateTime<Utc>>) {
+        self.last_modified_datetime = self.last_modified_datetime.max(datetime);
+    }
+}
+
+impl VectorFS {
+    /// Computes the stats of the folder (or root) at the reader's path. Items saved before chunk counts were
+    /// kept in their metadata have their Vector Resource read once, afterwards their stats come from the cache.
+    pub async fn get_folder_stats(&self, reader: &VFSReader) -> Result<FolderStats, VectorFSError> {
+        let internals = self.get_profile_fs_internals_cloned(&reader.profile).await?;
+        if reader.path == VRPath::root() {
+            let core_resource = BaseVectorResource::Map(internals.fs_core_resource);
+            let cache_key = core_resource.as_trait_object().get_merkle_root().ok();
+            return self.resource_folder_stats(&core_resource, cache_key, &reader.profile);
+        }
+
+        let ret_node = internals
+            .fs_core_resource
+            .retrieve_node_at_path(reader.path.clone(), None)?;
+        let NodeContent::Resource(folder_resource) = &ret_node.node.content else {
+            return Err(VectorFSError::PathDoesNotPointAtFolder(reader.path.clone()));
+        };
+        let mut stats =
+            self.resource_folder_stats(folder_resource, Self::cache_key(&ret_node.node), &reader.profile)?;
+        stats.update_last_modified(Self::folder_last_modified(&ret_node.node));
+        Ok(stats)
+    }
+
+    /// Stats of the children of a folder's Vector Resource, summing up the cached stats of unchanged subfolders
+    fn resource_folder_stats(
+        &self,
+        folder_resource: &BaseVectorResource,
+        cache_key: Option<String>,
+        profile: &ShinkaiName,
+    ) -> Result<FolderStats, VectorFSError> {
+        if let Some(stats) = Self::cached_stats(&cache_key) {
+            return Ok(stats);
+        }
+
+        let mut stats = FolderStats::default();
+        for node in folder_resource.as_trait_object().get_root_nodes_ref() {
+            match &node.content {
+                NodeContent::Resource(child_resource) => {
+                    let mut child_stats = self.resource_folder_stats(child_resource, Self::cache_key(node), profile)?;
+                    child_stats.update_last_modified(Self::folder_last_modified(node));
+                    stats.folder_count += 1;
+                    stats.add(&child_stats);
+                }
+                // Links hold no data of their own
+                NodeContent::VRHeader(_) if FSItem::process_link_target_from_node(node).is_some() => continue,
+                NodeContent::VRHeader(_) => stats.add(&self.item_stats(node, profile)?),
+                _ => continue,
+            }
+        }
+
+        Self::cache_stats(cache_key, &stats);
+        Ok(stats)
+    }
+
+    fn item_stats(&self, node: &Node, profile: &ShinkaiName) -> Result<FolderStats, VectorFSError> {
+        let cache_key = Self::cache_key(node);
+        if let Some(stats) = Self::cached_stats(&cache_key) {
+            return Ok(stats);
+        }
+
+        let (vr_size, sfm_size) = FSItem::process_sizes_from_node(node)?;
+        let (chunk_count, embedded_tokens) = match FSItem::process_chunk_counts_from_node(node) {
+            Some(counts) => counts,
+            None => {
+                let vr_header = node.get_vr_header_content()?;
+                let resource = self.db.get_resource(&vr_header.reference_string(), profile)?;
+                FSItem::count_chunks_and_tokens(&resource)
+            }
+        };
+        let stats = FolderStats {
+            folder_count: 0,
+            item_count: 1,
+            chunk_count: chunk_count as u64,
+            embedded_tokens: embedded_tokens as u64,
+            vector_resource_bytes: vr_size as u64,
+            source_file_bytes: sfm_size as u64,
+            last_modified_datetime: Some(node.last_written_datetime),
+        };
+
+        Self::cache_stats(cache_key, &stats);
+        Ok(stats)
+    }
+
+    fn folder_last_modified(node: &Node) -> Option<DateTime<Utc>> {
+        node.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(&FSFolder::last_modified_key()))
+            .and_then(|datetime| ShinkaiTime::from_rfc3339_string(datetime).ok())
+    }
+
+    /// Copies of a folder or item share its merkle hash, but not its last written datetime
+    fn cache_key(node: &Node) -> Option<String> {
+        let merkle_hash = node.merkle_hash.as_ref()?;
+        Some(format!("{}:{}", merkle_hash, node.last_written_datetime.to_rfc3339()))
+    }
+
+    fn cached_stats(cache_key: &Option<String>) -> Option<FolderStats> {
+        let cache_key = cache_key.as_ref()?;
+        CACHED_STATS.lock().unwrap().get(cache_key).cloned()
+    }
+
+    fn cache_stats(cache_key: Option<String>, stats: &FolderStats) {
+        let Some(cache_key) = cache_key else {
+            return;
+        };
+        let mut cache = CACHED_STATS.lock().unwrap();
+        if cache.len() >= MAX_CACHED_STATS {
+            cache.clear();
+        }
+        cache.insert(cache_key, stats.clone());
+    }
+}
@@ -1,16 +1,22 @@
 use super::vector_fs_error::VectorFSError;
+use crate::managers::model_capabilities_manager::ModelCapabilitiesManager;
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 use shinkai_message_primitives::{
     schemas::shinkai_name::ShinkaiName, shinkai_utils::job_scope::VectorFSItemScopeEntry,
 };
 use shinkai_vector_resources::{
+    embeddings::Embedding,
+    model_type::EmbeddingModelType,
     resource_errors::VRError,
     shinkai_time::ShinkaiTime,
-    source::DistributionInfo,
-    vector_resource::{BaseVectorResource, MapVectorResource, Node, NodeContent, VRHeader, VRKeywords, VRPath},
+    source::{DistributionInfo, SourceFile, SourceFileMap},
+    vector_resource::{
+        BaseVectorResource, MapVectorResource, Node, NodeContent, VRHeader, VRKeywords, VRPath, VectorResourceSearch,
+    },
 };
 use std::collections::HashMap;
+use utoipa::ToSchema;
 
 /// Enum that holds the types of external-facing entries used in the VectorFS
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -208,6 +214,10 @@ pub struct FSFolder {
     pub last_written_datetime: DateTime<Utc>,
     /// Merkle hash comprised of all of the FSEntries within this folder
     pub merkle_hash: String,
+    /// Embedding model declared by the folder, which its FSItems are embedded with instead of the profile's default.
+    /// None if the folder doesn't declare one (it may still inherit the one of a parent folder).
+    #[serde(default)]
+    pub embedding_model: Option<EmbeddingModelType>,
     // pub read_permission:
     // pub write_permission:
 }
@@ -235,6 +245,7 @@ impl FSFolder {
             last_modified_datetime,
             last_written_datetime,
             merkle_hash,
+            embedding_model: None,
         }
     }
 
@@ -267,11 +278,18 @@ impl FSFolder {
     ) -> Result<Self, VectorFSError> {
         // Process datetimes from node
         let last_modified_datetime = Self::process_datetimes_from_node(&node)?;
+        let embedding_model = Self::process_embedding_model_from_node(&node);
 
         match node.content {
             NodeContent::Resource(base_vector_resource) => {
                 // Call from_vector_resource with the parsed datetimes
-                Self::from_vector_resource(base_vector_resource, node_fs_path, lr_index, last_modified_datetime)
+                Self::from_vector_resource(
+                    base_vector_resource,
+                    node_fs_path,
+                    lr_index,
+                    last_modified_datetime,
+                    embedding_model,
+                )
             }
             _ => Err(VRError::InvalidNodeType(node.id))?,
         }
@@ -284,6 +302,7 @@ impl FSFolder {
         resource_fs_path: VRPath,
         lr_index: &LastReadIndex,
         last_modified_datetime: DateTime<Utc>,
+        embedding_model: Option<EmbeddingModelType>,
     ) -> Result<Self, VectorFSError> {
         let mut child_folders = Vec::new();
         let mut child_items = Vec::new();
@@ -301,6 +320,7 @@ impl FSFolder {
                         new_path,
                         lr_index,
                         lm_datetime,
+                        Self::process_embedding_model_from_node(node),
                     )?);
                 }
                 // If it's a VRHeader, then create a FSEntry and push it to child_items
@@ -318,7 +338,7 @@ impl FSFolder {
         let created_datetime = resource.as_trait_object().created_datetime();
         let last_written_datetime = resource.as_trait_object().last_written_datetime();
         let merkle_hash = resource.as_trait_object().get_merkle_root()?;
-        Ok(Self::new(
+        let mut folder = Self::new(
             resource_fs_path,
             child_folders,
             child_items,
@@ -327,7 +347,9 @@ impl FSFolder {
             last_read_datetime,
             last_modified_datetime,
             merkle_hash,
-        ))
+        );
+        folder.embedding_model = embedding_model;
+        Ok(folder)
     }
 
     /// Process last_modified datetime in a Node from the VectorFS core resource.
@@ -352,6 +374,17 @@ impl FSFolder {
         String::from("last_modified")
     }
 
+    /// Metadata key where the embedding model declared by the folder will be found in an FSFolder Node.
+    pub fn embedding_model_metadata_key() -> String {
+        String::from("embedding_model")
+    }
+
+    /// Reads the embedding model declared in metadata in an FSFolder Node. None if the folder doesn't declare one.
+    pub fn process_embedding_model_from_node(node: &Node) -> Option<EmbeddingModelType> {
+        let model = node.metadata.as_ref()?.get(&Self::embedding_model_metadata_key())?;
+        EmbeddingModelType::from_string(model).ok()
+    }
+
     /// Converts to a JSON string
     pub fn to_json(&self) -> Result<String, VectorFSError> {
         Ok(serde_json::to_string(self)?)
@@ -443,6 +476,13 @@ pub struct FSItem {
     pub source_file_map_size: usize,
     /// Merkle hash, which is in fact the merkle root of the Vector Resource stored in the FSItem
     pub merkle_hash: String,
+    /// Path of the FSItem this one links to. Links hold no Vector Resource of their own, they share the one of
+    /// their target (and mirror its VRHeader and metadata).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_target: Option<VRPath>,
+    /// Summary of the document, generated by an LLM when it was ingested if summarization was on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
 }
 
 impl FSItem {
@@ -474,6 +514,8 @@ impl FSItem {
             vr_size,
             source_file_map_size,
             merkle_hash,
+            link_target: None,
+            summary: None,
         }
     }
 
@@ -523,7 +565,7 @@ impl FSItem {
                 let (vr_size, sfm_size) = Self::process_sizes_from_node(&node)?;
                 let merkle_hash = node.get_merkle_hash()?;
 
-                Ok(FSItem::new(
+                let mut item = FSItem::new(
                     node_fs_path,
                     header.clone(),
                     header.resource_created_datetime,
@@ -535,13 +577,25 @@ impl FSItem {
                     vr_size,
                     sfm_size,
                     merkle_hash,
-                ))
+                );
+                item.summary = Self::process_summary_from_node(&node);
+                // Links are named independently of the VRHeader they mirror
+                if let Some(link_target) = Self::process_link_target_from_node(&node) {
+                    item.name = node.id.clone();
+                    item.link_target = Some(link_target);
+                }
+                Ok(item)
             }
 
             _ => Err(VRError::InvalidNodeType(node.id))?,
         }
     }
 
+    /// Whether the FSItem is a link to another FSItem
+    pub fn is_link(&self) -> bool {
+        self.link_target.is_some()
+    }
+
     /// Converts the FSItem into a job scope VectorFSItemScopeEntry
     pub fn as_scope_entry(&self) -> VectorFSItemScopeEntry {
         VectorFSItemScopeEntry {
@@ -640,6 +694,30 @@ impl FSItem {
         Ok((vr_size, sfm_size))
     }
 
+    /// Reads the chunk count and embedded tokens stored in metadata in an FSItem Node from the VectorFS core
+    /// resource. None for items saved before they were stored.
+    pub fn process_chunk_counts_from_node(node: &Node) -> Option<(usize, usize)> {
+        let metadata = node.metadata.as_ref()?;
+        let chunk_count = metadata.get(&Self::vr_chunk_count_metadata_key())?.parse().ok()?;
+        let embedded_tokens = metadata.get(&Self::vr_embedded_tokens_metadata_key())?.parse().ok()?;
+        Some((chunk_count, embedded_tokens))
+    }
+
+    /// Counts the text nodes of the Vector Resource and their tokens, as estimated for llama3
+    pub fn count_chunks_and_tokens(resource: &BaseVectorResource) -> (usize, usize) {
+        resource
+            .as_trait_object()
+            .retrieve_nodes_exhaustive_unordered(None)
+            .iter()
+            .filter_map(|ret_node| match &ret_node.node.content {
+                NodeContent::Text(text) => Some(ModelCapabilitiesManager::count_tokens_from_message_llama3(text)),
+                _ => None,
+            })
+            .fold((0, 0), |(chunks, tokens), chunk_tokens| {
+                (chunks + 1, tokens + chunk_tokens)
+            })
+    }
+
     /// Returns the metadata key for the Vector Resource last saved datetime.
     pub fn vr_last_saved_metadata_key() -> String {
         String::from("vr_last_saved")
@@ -650,6 +728,49 @@ impl FSItem {
         String::from("vr_size")
     }
 
+    /// Metadata key where the number of text chunks of the Vector Resource will be found in a Node.
+    pub fn vr_chunk_count_metadata_key() -> String {
+        String::from("vr_chunk_count")
+    }
+
+    /// Metadata key where the estimated tokens of the Vector Resource's text chunks will be found in a Node.
+    pub fn vr_embedded_tokens_metadata_key() -> String {
+        String::from("vr_embedded_tokens")
+    }
+
+    /// Metadata key where the summary of the Vector Resource will be found in a Node, and in the metadata of a VRKai
+    /// that holds one.
+    pub fn vr_summary_metadata_key() -> String {
+        String::from("vr_summary")
+    }
+
+    /// Reads the summary stored in metadata in an FSItem Node. None if the item wasn't summarized.
+    pub fn process_summary_from_node(node: &Node) -> Option<String> {
+        node.metadata.as_ref()?.get(&Self::vr_summary_metadata_key()).cloned()
+    }
+
+    /// Metadata key where the image embedding of an FSItem holding an image will be found in a Node, and in the
+    /// metadata of a VRKai that holds one. It's generated by the image embedding model, not the model of the VR.
+    pub fn vr_image_embedding_metadata_key() -> String {
+        String::from("vr_image_embedding")
+    }
+
+    /// Metadata key where the model the image embedding was generated with will be found in a Node.
+    pub fn vr_image_embedding_model_metadata_key() -> String {
+        String::from("vr_image_embedding_model")
+    }
+
+    /// Reads the image embedding stored in metadata in an FSItem Node. None if the item isn't an image embedded
+    /// by the given model.
+    pub fn process_image_embedding_from_node(node: &Node, model: &EmbeddingModelType) -> Option<Embedding> {
+        let metadata = node.metadata.as_ref()?;
+        if metadata.get(&Self::vr_image_embedding_model_metadata_key()) != Some(&model.to_string()) {
+            return None;
+        }
+        let vector = serde_json::from_str(metadata.get(&Self::vr_image_embedding_metadata_key())?).ok()?;
+        Some(Embedding::new("", vector))
+    }
+
     /// Metadata key where Source File Map's last saved datetime will be found in a Node.
     pub fn source_file_map_last_saved_metadata_key() -> String {
         String::from("sfm_last_saved")
@@ -659,6 +780,147 @@ impl FSItem {
     pub fn source_file_map_size_metadata_key() -> String {
         String::from("sfm_size")
     }
+
+    /// Metadata key where the hash of the content of the SourceFileMap's files will be found in a Node.
+    pub fn source_file_content_hash_metadata_key() -> String {
+        String::from("sfm_content_hash")
+    }
+
+    /// Blake3 hash of the content of the files in the SourceFileMap, in the order of their paths. The same files
+    /// have the same hash whatever they are named.
+    pub fn source_file_content_hash(source_file_map: &SourceFileMap) -> String {
+        let mut files: Vec<(&VRPath, &SourceFile)> = source_file_map.map.iter().collect();
+        files.sort_by_key(|(path, _)| path.format_to_string());
+        let mut hasher = blake3::Hasher::new();
+        for (_, file) in files {
+            let content = match file {
+                SourceFile::Standard(file) => &file.file_content,
+                SourceFile::TLSNotarized(file) => &file.file_content,
+            };
+            hasher.update(content);
+        }
+        hasher.finalize().to_hex().to_string()
+    }
+
+    /// Reads the hash of the content of the SourceFileMap stored in metadata in an FSItem Node. None for items
+    /// without a SourceFileMap, or saved before the hash was stored.
+    pub fn process_source_file_content_hash_from_node(node: &Node) -> Option<String> {
+        node.metadata
+            .as_ref()?
+            .get(&Self::source_file_content_hash_metadata_key())
+            .cloned()
+    }
+
+    /// Metadata key where the previous versions of the FSItem will be found in a Node, as JSON.
+    pub fn versions_metadata_key() -> String {
+        String::from("vr_versions")
+    }
+
+    /// Metadata key where the path of the FSItem a link points to will be found in a Node.
+    pub fn link_target_metadata_key() -> String {
+        String::from("link_target")
+    }
+
+    /// Reads the path of the FSItem a link points to from metadata in an FSItem Node. None if the node isn't a link.
+    pub fn process_link_target_from_node(node: &Node) -> Option<VRPath> {
+        let link_target = node.metadata.as_ref()?.get(&Self::link_target_metadata_key())?;
+        VRPath::from_string(link_target).ok()
+    }
+
+    /// Reads the previous versions stored in metadata in an FSItem Node from the VectorFS core resource,
+    /// oldest first.
+    pub fn process_versions_from_node(node: &Node) -> Result<Vec<FSItemVersion>, VectorFSError> {
+        match node
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(&Self::versions_metadata_key()))
+        {
+            Some(json) => {
+                serde_json::from_str(json).map_err(|_| VectorFSError::InvalidMetadata(Self::versions_metadata_key()))
+            }
+            None => Ok(vec![]),
+        }
+    }
+}
+
+/// A previous version of an FSItem, kept when the item was overwritten. Its Vector Resource (and SourceFileMap
+/// if it had one) stay saved in the FSDB under `resource_db_key`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct FSItemVersion {
+    /// Increases with every overwrite of the item
+    pub version_id: u64,
+    pub resource_db_key: String,
+    pub merkle_hash: String,
+    pub vr_size: usize,
+    pub source_file_map_size: usize,
+    pub has_source_file_map: bool,
+    /// Datetime the version's Vector Resource was saved into the item
+    #[schema(value_type = String)]
+    pub vr_saved_datetime: DateTime<Utc>,
+    /// Datetime the version was overwritten
+    #[schema(value_type = String)]
+    pub replaced_datetime: DateTime<Utc>,
+    /// Metadata of the FSItem node at the time, without its versions
+    pub metadata: HashMap<String, String>,
+}
+
+impl FSItemVersion {
+    /// Captures the FSItem node being overwritten as a version
+    pub fn from_item_node(
+        node: &Node,
+        version_id: u64,
+        replaced_datetime: DateTime<Utc>,
+    ) -> Result<Self, VectorFSError> {
+        let vr_header = node.get_vr_header_content()?;
+        let (vr_saved_datetime, source_file_map_last_saved) = FSItem::process_datetimes_from_node(node)?;
+        let (vr_size, source_file_map_size) = FSItem::process_sizes_from_node(node)?;
+        let mut metadata = node.metadata.clone().unwrap_or_default();
+        metadata.remove(&FSItem::versions_metadata_key());
+
+        Ok(Self {
+            version_id,
+            resource_db_key: vr_header.reference_string(),
+            merkle_hash: node.get_merkle_hash()?,
+            vr_size,
+            source_file_map_size,
+            has_source_file_map: source_file_map_last_saved.is_some(),
+            vr_saved_datetime,
+            replaced_datetime,
+            metadata,
+        })
+    }
+
+    /// Metadata keys (and the merkle hash) whose values differ between this version and the current FSItem node
+    pub fn diff_metadata(&self, current_node: &Node) -> Result<Vec<FSItemMetadataChange>, VectorFSError> {
+        let current = Self::from_item_node(current_node, 0, ShinkaiTime::generate_time_now())?;
+        let fields = |version: &FSItemVersion| {
+            let mut fields = version.metadata.clone();
+            fields.insert("merkle_hash".to_string(), version.merkle_hash.clone());
+            fields
+        };
+        let (version_fields, current_fields) = (fields(self), fields(&current));
+
+        let mut keys: Vec<&String> = version_fields.keys().chain(current_fields.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        Ok(keys
+            .into_iter()
+            .filter(|key| version_fields.get(*key) != current_fields.get(*key))
+            .map(|key| FSItemMetadataChange {
+                key: key.clone(),
+                version_value: version_fields.get(key).cloned(),
+                current_value: current_fields.get(key).cloned(),
+            })
+            .collect())
+    }
+}
+
+/// A metadata key whose value changed since a previous version of an FSItem. None if the key wasn't set.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct FSItemMetadataChange {
+    pub key: String,
+    pub version_value: Option<String>,
+    pub current_value: Option<String>,
 }
 
 /// TODO: Implement SubscriptionsIndex later on when it's relevant. For now struct exists
@@ -1,11 +1,13 @@
 use super::vector_fs_permissions::PathPermission;
-use super::vector_fs_types::{FSEntry, FSFolder, FSItem};
+use super::vector_fs_types::{FSEntry, FSFolder, FSItem, FSItemVersion};
 use super::{vector_fs::VectorFS, vector_fs_error::VectorFSError, vector_fs_reader::VFSReader};
 use crate::db::db_profile_bound::ProfileBoundWriteBatch;
+use crate::managers::operation_registry::OperationHandle;
 use crate::vector_fs::vector_fs_permissions::{ReadPermission, WritePermission};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_vector_resources::model_type::EmbeddingModelType;
 use shinkai_vector_resources::resource_errors::VRError;
 use shinkai_vector_resources::shinkai_time::ShinkaiTime;
 use shinkai_vector_resources::source::SourceFileMap;
@@ -17,6 +19,12 @@ use shinkai_vector_resources::{
 };
 use std::collections::HashMap;
 
+/// VRKais written per write batch by `save_vrkais_in_folder`, which bounds the memory a batch takes
+const BULK_SAVE_BATCH_SIZE: usize = 50;
+
+/// Previous versions kept per item when it gets overwritten, unless VECTOR_FS_VERSION_RETENTION is set
+const DEFAULT_VERSION_RETENTION: usize = 5;
+
 /// A struct that represents having rights to write to the VectorFS under a profile/at a specific path.
 /// If a VFSWriter struct is constructed, that means the `requester_name` has passed
 /// permissions validation and is thus allowed to write to `path`.
@@ -88,12 +96,53 @@ impl VectorFS {
     pub async fn copy_folder(&self, writer: &VFSWriter, destination_path: VRPath) -> Result<FSFolder, VectorFSError> {
         let write_batch = writer.new_write_batch()?;
         let (write_batch, new_folder) = self
-            .internal_wb_copy_folder(writer, destination_path, write_batch, false)
+            .internal_wb_copy_folder(writer, destination_path, write_batch, false, None)
             .await?;
         self.db.write_pb(write_batch)?;
         Ok(new_folder)
     }
 
+    /// Copies the FSFolder like `copy_folder`, reporting each copied folder and item as a step of the operation.
+    /// Nothing is written if the operation is cancelled before the copy finishes.
+    pub async fn copy_folder_with_progress(
+        &self,
+        writer: &VFSWriter,
+        destination_path: VRPath,
+        operation: &OperationHandle,
+    ) -> Result<FSFolder, VectorFSError> {
+        let reader = writer.new_reader_copied_data(writer.path.clone(), self).await?;
+        if let FSEntry::Folder(folder) = self.retrieve_fs_entry(&reader).await? {
+            operation.set_total_steps(Self::count_folder_entries(&folder));
+        }
+
+        let write_batch = writer.new_write_batch()?;
+        match self
+            .internal_wb_copy_folder(writer, destination_path, write_batch, false, Some(operation))
+            .await
+        {
+            Ok((write_batch, new_folder)) => {
+                self.db.write_pb(write_batch)?;
+                Ok(new_folder)
+            }
+            Err(e) => {
+                // The copied entries were already added to the fs internals in memory
+                self.revert_internals_to_last_db_save(&writer.profile, &writer.profile)
+                    .await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Number of folders and items held within the folder, at any depth
+    fn count_folder_entries(folder: &FSFolder) -> u64 {
+        let child_folder_entries: u64 = folder
+            .child_folders
+            .iter()
+            .map(|child_folder| 1 + Self::count_folder_entries(child_folder))
+            .sum();
+        folder.child_items.len() as u64 + child_folder_entries
+    }
+
     /// Internal method to copy the FSFolder from the writer's path into being held underneath the destination_path.
     #[async_recursion::async_recursion]
     async fn internal_wb_copy_folder(
@@ -102,6 +151,7 @@ impl VectorFS {
         destination_path: VRPath,
         mut write_batch: ProfileBoundWriteBatch,
         is_recursive_call: bool,
+        operation: Option<&OperationHandle>,
     ) -> Result<(ProfileBoundWriteBatch, FSFolder), VectorFSError> {
         let current_datetime = ShinkaiTime::generate_time_now();
         let destination_writer = writer.new_writer_copied_data(destination_path.clone(), self).await?;
@@ -182,6 +232,11 @@ impl VectorFS {
 
         // Now we copy each of the folder's original child folders/items (nodes) and add them to their destination path
         for (node, _) in nodes_embeddings {
+            if let Some(operation) = operation {
+                if operation.is_cancelled() {
+                    return Err(VectorFSError::OperationCancelled(operation.operation_id().to_string()));
+                }
+            }
             let origin_writer = writer
                 .new_writer_copied_data(writer.path.push_cloned(node.id.clone()), self)
                 .await?;
@@ -189,7 +244,7 @@ impl VectorFS {
             match node.content {
                 NodeContent::Resource(_) => {
                     let (batch, _) = self
-                        .internal_wb_copy_folder(&origin_writer, dest_path, write_batch, true)
+                        .internal_wb_copy_folder(&origin_writer, dest_path, write_batch, true, operation)
                         .await?;
                     write_batch = batch;
                 }
@@ -199,6 +254,9 @@ impl VectorFS {
                 }
                 _ => continue,
             }
+            if let Some(operation) = operation {
+                operation.advance();
+            }
         }
 
         // Only commit updating the fs internals once at the top level, efficiency improvement
@@ -260,6 +318,15 @@ impl VectorFS {
                 path: writer.path.push_cloned(item.name.clone()),
                 profile: writer.profile.clone(),
             };
+            // Links are already gone if their target was deleted before them
+            if item.is_link()
+                && self
+                    .validate_path_points_to_item(item_writer.path.clone(), &writer.profile)
+                    .await
+                    .is_err()
+            {
+                continue;
+            }
             write_batch = self.wb_delete_item(&item_writer, write_batch).await?;
         }
 
@@ -314,6 +381,7 @@ impl VectorFS {
             .await?;
         let (item_node, _) = self._remove_node_from_core_resource(writer).await?;
         let ref_string = item_node.get_vr_header_content()?.reference_string();
+        let versions = FSItem::process_versions_from_node(&item_node)?;
 
         let internals = self.get_profile_fs_internals_cloned(&writer.profile).await?;
         internals
@@ -322,7 +390,18 @@ impl VectorFS {
             .await;
         self._update_fs_internals(writer.profile.clone(), internals.clone())
             .await?;
-        self.db.wb_delete_resource(&ref_string, &mut write_batch)?;
+
+        // Deleting a link leaves its target as is, while deleting an item removes the links to it
+        if FSItem::process_link_target_from_node(&item_node).is_none() {
+            self.db.wb_delete_resource(&ref_string, &mut write_batch)?;
+            for version in versions {
+                self.db.wb_delete_resource(&version.resource_db_key, &mut write_batch)?;
+                self.db
+                    .wb_delete_source_file_map(&version.resource_db_key, &mut write_batch)?;
+            }
+            self._sync_links(&writer.profile, None).await?;
+        }
+        let internals = self.get_profile_fs_internals_cloned(&writer.profile).await?;
         self.db.wb_save_profile_fs_internals(&internals, &mut write_batch)?;
         Ok(write_batch)
     }
@@ -363,7 +442,25 @@ impl VectorFS {
 
         // Get the existing item
         let (item_ret_node, _) = self._get_node_from_core_resource(writer).await?;
-        let item_metadata = item_ret_node.node.metadata;
+        // Copies of links are links to the same target
+        if let Some(link_target) = FSItem::process_link_target_from_node(&item_ret_node.node) {
+            let new_item = self
+                ._add_link_to_core_resource(
+                    &destination_writer,
+                    item_ret_node.node.id.clone(),
+                    link_target,
+                    current_datetime,
+                )
+                .await?;
+            let internals = self.get_profile_fs_internals_cloned(&writer.profile).await?;
+            self.db.wb_save_profile_fs_internals(&internals, &mut write_batch)?;
+            return Ok((write_batch, new_item));
+        }
+        // Versions stay with the original item, their resources aren't copied
+        let item_metadata = item_ret_node.node.metadata.map(|mut metadata| {
+            metadata.remove(&FSItem::versions_metadata_key());
+            metadata
+        });
         let mut source_file_map = None;
         let source_file_map_is_saved = item_metadata
             .as_ref()
@@ -448,9 +545,18 @@ impl VectorFS {
         }
 
         // If the item was moved successfully in memory, then commit to the DB
-        let move_result = self
+        let mut move_result = self
             ._internal_move_item(writer, &destination_writer, current_datetime, destination_path)
             .await;
+        // Links to the item follow it
+        if let Ok(new_item) = &move_result {
+            if let Err(e) = self
+                ._sync_links(&writer.profile, Some((&writer.path, &new_item.path)))
+                .await
+            {
+                move_result = Err(e);
+            }
+        }
         if let Ok(new_item) = move_result {
             let internals = self.get_profile_fs_internals_cloned(&writer.profile).await?;
             let mut write_batch = writer.new_write_batch()?;
@@ -480,7 +586,14 @@ impl VectorFS {
         let item_metadata = item_node.metadata;
         // And save the item into the new destination w/permissions
         let new_item = self
-            ._add_vr_header_to_core_resource(destination_writer, header, item_metadata, current_datetime, false)
+            ._add_item_node_to_core_resource(
+                destination_writer,
+                item_node.id,
+                header,
+                item_metadata,
+                current_datetime,
+                false,
+            )
             .await?;
 
         // Determine and set permissions based on the parent of the destination path
@@ -547,9 +660,18 @@ impl VectorFS {
         }
 
         // If the folder was moved successfully in memory, then commit to the DB
-        let move_result = self
+        let mut move_result = self
             .internal_move_folder(writer, &destination_writer, current_datetime, destination_path)
             .await;
+        // Links to items within the folder follow them
+        if let Ok(new_folder) = &move_result {
+            if let Err(e) = self
+                ._sync_links(&writer.profile, Some((&writer.path, &new_folder.path)))
+                .await
+            {
+                move_result = Err(e);
+            }
+        }
         if let Ok(new_folder) = move_result {
             let internals = self.get_profile_fs_internals_cloned(&writer.profile).await?;
             let mut write_batch = writer.new_write_batch()?;
@@ -930,10 +1052,24 @@ impl VectorFS {
 
     /// Saves a VRKai into an FSItem, underneath the FSFolder at the writer's path.
     /// If a FSItem with the same name (as the VR) already exists underneath the current path, then updates(overwrites) it.
+    /// The summary and image embedding in the VRKai's metadata, if any, become the ones of the FSItem.
     /// Does not support saving into VecFS root.
     pub async fn save_vrkai_in_folder(&self, writer: &VFSWriter, vrkai: VRKai) -> Result<FSItem, VectorFSError> {
-        self.save_vector_resource_in_folder(writer, vrkai.resource, vrkai.sfm)
-            .await
+        let mut write_batch = writer.new_write_batch()?;
+        let item = self
+            .internal_wb_save_vector_resource_in_folder(
+                writer,
+                vrkai.resource,
+                vrkai.sfm,
+                &vrkai.metadata,
+                &mut write_batch,
+            )
+            .await?;
+        let internals = self.get_profile_fs_internals_cloned(&writer.profile).await?;
+        self.db.wb_save_profile_fs_internals(&internals, &mut write_batch)?;
+        self.db.write_pb(write_batch)?;
+
+        Ok(item)
     }
 
     /// Saves a Vector Resource and optional SourceFile into an FSItem at the exact path specified in writer (ie. `.../{parent_folder}/resource_name`)
@@ -959,17 +1095,83 @@ impl VectorFS {
         writer: &VFSWriter,
         resource: BaseVectorResource,
         source_file_map: Option<SourceFileMap>,
+    ) -> Result<FSItem, VectorFSError> {
+        let mut write_batch = writer.new_write_batch()?;
+        let item = self
+            .internal_wb_save_vector_resource_in_folder(
+                writer,
+                resource,
+                source_file_map,
+                &HashMap::new(),
+                &mut write_batch,
+            )
+            .await?;
+        let internals = self.get_profile_fs_internals_cloned(&writer.profile).await?;
+        self.db.wb_save_profile_fs_internals(&internals, &mut write_batch)?;
+        self.db.write_pb(write_batch)?;
+
+        Ok(item)
+    }
+
+    /// Saves many VRKais into FSItems underneath the FSFolder at the writer's path, like `save_vrkai_in_folder`.
+    /// Items and the profile fs internals are written once per BULK_SAVE_BATCH_SIZE VRKais instead of once per VRKai,
+    /// which is much faster for imports of many files. A VRKai that fails doesn't stop the others from being saved,
+    /// the results being in the order of the VRKais.
+    pub async fn save_vrkais_in_folder(
+        &self,
+        writer: &VFSWriter,
+        vrkais: Vec<VRKai>,
+    ) -> Result<Vec<Result<FSItem, VectorFSError>>, VectorFSError> {
+        let mut results = Vec::with_capacity(vrkais.len());
+        let mut vrkais = vrkais.into_iter().peekable();
+        while vrkais.peek().is_some() {
+            let mut write_batch = writer.new_write_batch()?;
+            for vrkai in vrkais.by_ref().take(BULK_SAVE_BATCH_SIZE) {
+                results.push(
+                    self.internal_wb_save_vector_resource_in_folder(
+                        writer,
+                        vrkai.resource,
+                        vrkai.sfm,
+                        &vrkai.metadata,
+                        &mut write_batch,
+                    )
+                    .await,
+                );
+            }
+            let internals = self.get_profile_fs_internals_cloned(&writer.profile).await?;
+            self.db.wb_save_profile_fs_internals(&internals, &mut write_batch)?;
+            self.db.write_pb(write_batch)?;
+        }
+        Ok(results)
+    }
+
+    /// Adds saving the Vector Resource and optional SourceFile to the write batch, updating the fs internals of the
+    /// profile in memory. Saving the fs internals is left to the caller, so it's done once per write batch.
+    /// The summary and image embedding are taken from the VRKai metadata given. Without them, the ones of the item
+    /// being overwritten are kept if its content is unchanged.
+    async fn internal_wb_save_vector_resource_in_folder(
+        &self,
+        writer: &VFSWriter,
+        resource: BaseVectorResource,
+        source_file_map: Option<SourceFileMap>,
+        vrkai_metadata: &HashMap<String, String>,
+        write_batch: &mut ProfileBoundWriteBatch,
     ) -> Result<FSItem, VectorFSError> {
         let mut resource = resource;
 
-        let vr_header = resource.as_trait_object().generate_resource_header();
-        let source_db_key = vr_header.reference_string();
+        let mut source_file_map = source_file_map;
+
+        let mut vr_header = resource.as_trait_object().generate_resource_header();
+        let mut source_db_key = vr_header.reference_string();
         let resource_name = SourceFileType::clean_string_of_extension(resource.as_trait_object().name());
         resource.as_trait_object_mut().set_name(resource_name.clone());
         let node_path = writer.path.push_cloned(resource_name.to_string());
         let mut node_metadata = None;
         let mut node_at_path_already_exists = false;
+        let mut existing_item_size = 0;
+        let mut existing_item_node = None;
         let mut new_item = None;
+        let version_retention = Self::version_retention();
 
         {
             // Ensure path of writer points at a folder before proceeding
@@ -994,20 +1196,33 @@ impl VectorFS {
                 {
                     node_metadata.clone_from(&ret_node.node.metadata);
                     node_at_path_already_exists = true;
-                    if let Ok(vr_header) = ret_node.node.get_vr_header_content() {
-                        existing_vr_ref = Some(vr_header.reference_string());
+                    if let Ok((vr_size, sfm_size)) = FSItem::process_sizes_from_node(&ret_node.node) {
+                        existing_item_size = (vr_size + sfm_size) as u64;
+                    }
+                    // A link being overwritten is replaced by the item, its target is left as is
+                    if FSItem::process_link_target_from_node(&ret_node.node).is_none() {
+                        if let Ok(vr_header) = ret_node.node.get_vr_header_content() {
+                            existing_vr_ref = Some(vr_header.reference_string());
+                        }
+                        existing_item_node = Some(ret_node.node);
+                    } else {
+                        node_metadata = None;
+                        existing_item_size = 0;
                     }
                 }
             }
 
             // Check if an existing VR is saved in the FSDB with the same reference string. If so, re-generate id of the current resource.
-            if existing_vr_ref != Some(resource.as_trait_object().reference_string()) {
-                if let Ok(r) = self
-                    .db
-                    .get_resource(&resource.as_trait_object().reference_string(), &writer.profile)
-                {
-                    resource.as_trait_object_mut().generate_and_update_resource_id();
-                }
+            // When versions are kept, the overwritten VR also keeps its key so it stays readable as a version.
+            let resource_ref = resource.as_trait_object().reference_string();
+            let keeps_version = existing_vr_ref == Some(resource_ref.clone()) && version_retention > 0;
+            if keeps_version
+                || (existing_vr_ref != Some(resource_ref.clone())
+                    && self.db.get_resource(&resource_ref, &writer.profile).is_ok())
+            {
+                resource.as_trait_object_mut().generate_and_update_resource_id();
+                vr_header.resource_id = resource.as_trait_object().resource_id().to_string();
+                source_db_key = vr_header.reference_string();
             }
 
             // Now all validation checks/setup have passed, move forward with saving header/resource/source file
@@ -1015,6 +1230,31 @@ impl VectorFS {
             // Update the metadata keys of the FSItem node
             let mut node_metadata = node_metadata.unwrap_or_else(HashMap::new);
             node_metadata.insert(FSItem::vr_last_saved_metadata_key(), current_datetime.to_rfc3339());
+            // The overwritten item becomes the latest version, its SourceFileMap carries over if no new one is given
+            if let Some(existing_node) = &existing_item_node {
+                let mut versions = FSItem::process_versions_from_node(existing_node)?;
+                let version_id = versions.last().map_or(1, |version| version.version_id + 1);
+                let version = FSItemVersion::from_item_node(existing_node, version_id, current_datetime)?;
+                if source_file_map.is_none() && version.has_source_file_map {
+                    source_file_map = Some(self.db.get_source_file_map(&version.resource_db_key, &writer.profile)?);
+                }
+                versions.push(version);
+
+                let dropped_count = versions.len().saturating_sub(version_retention);
+                for dropped in versions.drain(..dropped_count) {
+                    if dropped.resource_db_key != source_db_key {
+                        self.db.wb_delete_resource(&dropped.resource_db_key, write_batch)?;
+                        self.db
+                            .wb_delete_source_file_map(&dropped.resource_db_key, write_batch)?;
+                    }
+                }
+                if versions.is_empty() {
+                    node_metadata.remove(&FSItem::versions_metadata_key());
+                } else {
+                    node_metadata.insert(FSItem::versions_metadata_key(), serde_json::to_string(&versions)?);
+                }
+            }
+            let mut sfm_size = 0;
             if let Some(sfm) = &source_file_map {
                 // Last Saved SFM
                 node_metadata.insert(
@@ -1022,12 +1262,48 @@ impl VectorFS {
                     current_datetime.to_rfc3339(),
                 );
                 // SFM Size
-                let sfm_size = sfm.encoded_size()?;
+                sfm_size = sfm.encoded_size()?;
                 node_metadata.insert(FSItem::source_file_map_size_metadata_key(), sfm_size.to_string());
+                node_metadata.insert(
+                    FSItem::source_file_content_hash_metadata_key(),
+                    FSItem::source_file_content_hash(sfm),
+                );
             }
             // Update vr_size key in metadata
             let vr_size = resource.as_trait_object().encoded_size()?;
             node_metadata.insert(FSItem::vr_size_metadata_key(), vr_size.to_string());
+            // Kept for folder stats, which would otherwise read every Vector Resource
+            let (chunk_count, embedded_tokens) = FSItem::count_chunks_and_tokens(&resource);
+            node_metadata.insert(FSItem::vr_chunk_count_metadata_key(), chunk_count.to_string());
+            node_metadata.insert(FSItem::vr_embedded_tokens_metadata_key(), embedded_tokens.to_string());
+            // A summary or image embedding describes the content it was generated from
+            let same_content = existing_item_node
+                .as_ref()
+                .and_then(|node| node.get_vr_header_content().ok())
+                .is_some_and(|header| {
+                    header.resource_merkle_root.is_some()
+                        && header.resource_merkle_root == vr_header.resource_merkle_root
+                });
+            for key in [
+                FSItem::vr_summary_metadata_key(),
+                FSItem::vr_image_embedding_metadata_key(),
+                FSItem::vr_image_embedding_model_metadata_key(),
+            ] {
+                match vrkai_metadata.get(&key) {
+                    Some(value) => {
+                        node_metadata.insert(key, value.clone());
+                    }
+                    None if !same_content => {
+                        node_metadata.remove(&key);
+                    }
+                    None => {}
+                }
+            }
+
+            // Overwriting an item frees its size, unless it's kept as a version
+            let freed_bytes = if version_retention > 0 { 0 } else { existing_item_size };
+            self.validate_profile_quota(&writer.profile, (vr_size + sfm_size) as u64, freed_bytes)
+                .await?;
 
             // Now after updating the metadata, finally save the VRHeader Node into the core vector resource
             {
@@ -1042,6 +1318,10 @@ impl VectorFS {
                     .await?,
                 );
             }
+            // Links to the overwritten item now mirror the new one
+            if existing_item_node.is_some() {
+                self._sync_links(&writer.profile, None).await?;
+            }
         }
 
         // Now that we've inserted the the new item into the fs internals core VR proceed forward
@@ -1076,16 +1356,11 @@ impl VectorFS {
                 self._update_fs_internals(writer.profile.clone(), internals).await?;
             }
 
-            // Finally saving the resource, the source file (if it was provided), and the FSInternals into the FSDB
-            let mut write_batch = writer.new_write_batch()?;
+            // Finally adding the resource and the source file (if it was provided) to the write batch
             if let Some(sfm) = source_file_map {
-                self.db
-                    .wb_save_source_file_map(&sfm, &source_db_key, &mut write_batch)?;
+                self.db.wb_save_source_file_map(&sfm, &source_db_key, write_batch)?;
             }
-            self.db.wb_save_resource(&resource, &mut write_batch)?;
-            let internals = self.get_profile_fs_internals_cloned(&writer.profile).await?;
-            self.db.wb_save_profile_fs_internals(&internals, &mut write_batch)?;
-            self.db.write_pb(write_batch)?;
+            self.db.wb_save_resource(&resource, write_batch)?;
 
             Ok(item)
         } else {
@@ -1093,6 +1368,230 @@ impl VectorFS {
         }
     }
 
+    /// Overwrites the FSItem at the writer's path with one of its previous versions. The item being replaced is
+    /// kept as a version itself, so a restore can be undone.
+    pub async fn restore_item_version(&self, writer: &VFSWriter, version_id: u64) -> Result<FSItem, VectorFSError> {
+        self.validate_path_points_to_item(writer.path.clone(), &writer.profile)
+            .await?;
+        let (item_ret_node, _) = self._get_node_from_core_resource(writer).await?;
+        let version = FSItem::process_versions_from_node(&item_ret_node.node)?
+            .into_iter()
+            .find(|version| version.version_id == version_id)
+            .ok_or(VectorFSError::ItemVersionNotFound(writer.path.clone(), version_id))?;
+
+        let resource = self.db.get_resource(&version.resource_db_key, &writer.profile)?;
+        let source_file_map = match version.has_source_file_map {
+            true => Some(self.db.get_source_file_map(&version.resource_db_key, &writer.profile)?),
+            false => None,
+        };
+        let folder_writer = writer.new_writer_copied_data(writer.path.parent_path(), self).await?;
+        self.save_vector_resource_in_folder(&folder_writer, resource, source_file_map)
+            .await
+    }
+
+    /// Creates a link named `link_name` in the folder at the writer's path, to the FSItem at `target_path`. The link
+    /// shares the Vector Resource of its target instead of copying it, follows the target when it moves and is
+    /// removed when the target is deleted. Linking to a link links to its target.
+    pub async fn create_link(
+        &self,
+        writer: &VFSWriter,
+        link_name: &str,
+        target_path: VRPath,
+    ) -> Result<FSItem, VectorFSError> {
+        self.validate_path_points_to_folder(writer.path.clone(), &writer.profile)
+            .await?;
+        let link_name = VRPath::clean_string(link_name);
+        let link_path = writer.path.push_cloned(link_name.clone());
+        if self
+            .validate_path_points_to_entry(link_path.clone(), &writer.profile)
+            .await
+            .is_ok()
+        {
+            return Err(VectorFSError::EntryAlreadyExistsAtPath(link_path));
+        }
+        // The requester must be able to read what it links to
+        let target_reader = writer.new_reader_copied_data(target_path, self).await?;
+        self.validate_path_points_to_item(target_reader.path.clone(), &writer.profile)
+            .await?;
+
+        let current_datetime = ShinkaiTime::generate_time_now();
+        let link_result = self
+            ._add_link_to_core_resource(writer, link_name, target_reader.path, current_datetime)
+            .await;
+        match link_result {
+            Ok(link) => {
+                let internals = self.get_profile_fs_internals_cloned(&writer.profile).await?;
+                let mut write_batch = writer.new_write_batch()?;
+                self.db.wb_save_profile_fs_internals(&internals, &mut write_batch)?;
+                self.db.write_pb(write_batch)?;
+                Ok(link)
+            }
+            Err(e) => {
+                self.revert_internals_to_last_db_save(&writer.profile, &writer.profile)
+                    .await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Internal method which adds a link to the FSItem at target_path into the folder at the writer's path, with
+    /// the permissions of the folder. Applies only in memory.
+    async fn _add_link_to_core_resource(
+        &self,
+        writer: &VFSWriter,
+        link_name: String,
+        target_path: VRPath,
+        current_datetime: DateTime<Utc>,
+    ) -> Result<FSItem, VectorFSError> {
+        let mut target_path = target_path;
+        let mut target = self
+            ._retrieve_core_resource_node_at_path(target_path.clone(), &writer.profile)
+            .await?;
+        if let Some(link_target) = FSItem::process_link_target_from_node(&target.node) {
+            target_path = link_target;
+            target = self
+                ._retrieve_core_resource_node_at_path(target_path.clone(), &writer.profile)
+                .await?;
+        }
+        let vr_header = target.node.get_vr_header_content()?.clone();
+        let metadata = Self::link_metadata(&target.node, &target_path);
+        let link = self
+            ._add_item_node_to_core_resource(writer, link_name, vr_header, Some(metadata), current_datetime, true)
+            .await?;
+
+        let internals = self.get_profile_fs_internals_cloned(&writer.profile).await?;
+        let (read_permission, write_permission) =
+            match internals.permissions_index.get_path_permission(&writer.path).await {
+                Ok(permission) if writer.path != VRPath::root() => {
+                    (permission.read_permission, permission.write_permission)
+                }
+                _ => (ReadPermission::Private, WritePermission::Private),
+            };
+        internals
+            .permissions_index
+            .insert_path_permission(link.path.clone(), read_permission, write_permission)
+            .await?;
+        self._update_fs_internals(writer.profile.clone(), internals).await?;
+        Ok(link)
+    }
+
+    /// Refreshes the links of the profile from their targets, removing the links whose target no longer exists.
+    /// `moved` holds the origin and destination paths of an item or folder that just moved, which links to it (or
+    /// within it) follow. Applies only in memory.
+    async fn _sync_links(&self, profile: &ShinkaiName, moved: Option<(&VRPath, &VRPath)>) -> Result<(), VectorFSError> {
+        let current_datetime = ShinkaiTime::generate_time_now();
+        let internals = self.get_profile_fs_internals_cloned(profile).await?;
+        for link in internals.fs_core_resource.retrieve_vrheader_nodes_exhaustive(None) {
+            let Some(mut target_path) = FSItem::process_link_target_from_node(&link.node) else {
+                continue;
+            };
+            if let Some((origin, destination)) = moved {
+                if origin == &target_path || origin.is_descendant_path(&target_path) {
+                    let mut moved_target_path = destination.clone();
+                    for path_id in &target_path.path_ids[origin.path_ids.len()..] {
+                        moved_target_path.push(path_id.clone());
+                    }
+                    target_path = moved_target_path;
+                }
+            }
+
+            let link_writer = VFSWriter {
+                requester_name: profile.clone(),
+                path: link.retrieval_path.clone(),
+                profile: profile.clone(),
+            };
+            let target = self
+                ._retrieve_core_resource_node_at_path(target_path.clone(), profile)
+                .await
+                .ok()
+                .filter(|target| {
+                    matches!(target.node.content, NodeContent::VRHeader(_))
+                        && FSItem::process_link_target_from_node(&target.node).is_none()
+                });
+            match target {
+                Some(target) => {
+                    let metadata = Self::link_metadata(&target.node, &target_path);
+                    if link.node.content == target.node.content && link.node.metadata.as_ref() == Some(&metadata) {
+                        continue;
+                    }
+                    self._remove_node_from_core_resource(&link_writer).await?;
+                    let folder_writer = VFSWriter {
+                        path: link_writer.path.parent_path(),
+                        ..link_writer.clone()
+                    };
+                    self._add_item_node_to_core_resource(
+                        &folder_writer,
+                        link_writer.path.last_path_id()?,
+                        target.node.get_vr_header_content()?.clone(),
+                        Some(metadata),
+                        current_datetime,
+                        false,
+                    )
+                    .await?;
+                }
+                None => {
+                    self._remove_node_from_core_resource(&link_writer).await?;
+                    let internals = self.get_profile_fs_internals_cloned(profile).await?;
+                    internals
+                        .permissions_index
+                        .remove_path_permission(link_writer.path.clone())
+                        .await;
+                    self._update_fs_internals(profile.clone(), internals).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Metadata of a link node, which mirrors the one of its target apart from the versions
+    fn link_metadata(target_node: &Node, target_path: &VRPath) -> HashMap<String, String> {
+        let mut metadata = target_node.metadata.clone().unwrap_or_default();
+        metadata.remove(&FSItem::versions_metadata_key());
+        metadata.insert(FSItem::link_target_metadata_key(), target_path.format_to_string());
+        metadata
+    }
+
+    /// Returns a writer at the target of the link at the writer's path, or the writer itself if it isn't at a link.
+    /// Writing through a link requires write permission on its target.
+    async fn resolve_link_writer(&self, writer: &VFSWriter) -> Result<VFSWriter, VectorFSError> {
+        let link_target = self
+            ._retrieve_core_resource_node_at_path(writer.path.clone(), &writer.profile)
+            .await
+            .ok()
+            .and_then(|ret_node| FSItem::process_link_target_from_node(&ret_node.node));
+        match link_target {
+            Some(link_target) => writer.new_writer_copied_data(link_target, self).await,
+            None => Ok(writer.clone()),
+        }
+    }
+
+    /// Number of previous versions kept per item, from VECTOR_FS_VERSION_RETENTION. 0 turns versioning off.
+    fn version_retention() -> usize {
+        std::env::var("VECTOR_FS_VERSION_RETENTION")
+            .ok()
+            .and_then(|retention| retention.parse().ok())
+            .unwrap_or(DEFAULT_VERSION_RETENTION)
+    }
+
+    /// Errors if saving `new_bytes` (replacing `freed_bytes`) would put the profile over its quota
+    async fn validate_profile_quota(
+        &self,
+        profile: &ShinkaiName,
+        new_bytes: u64,
+        freed_bytes: u64,
+    ) -> Result<(), VectorFSError> {
+        let profile_name = profile.get_profile_name_string().unwrap_or_default().to_lowercase();
+        let Some(quota_bytes) = self.profile_quotas.read().await.get(&profile_name).copied() else {
+            return Ok(());
+        };
+
+        let used_bytes = self.profile_storage_bytes(profile).await?;
+        if used_bytes.saturating_sub(freed_bytes) + new_bytes > quota_bytes {
+            return Err(VectorFSError::ProfileQuotaExceeded(profile.clone(), quota_bytes));
+        }
+        Ok(())
+    }
+
     /// Updates the SourceFileMap of the FSItem at the writer's path.
     /// If no FSItem with the same name already exists underneath the current path, then errors.
     pub async fn update_source_file_map(
@@ -1100,6 +1599,7 @@ impl VectorFS {
         writer: &VFSWriter,
         source_file_map: SourceFileMap,
     ) -> Result<FSItem, VectorFSError> {
+        let writer = &self.resolve_link_writer(writer).await?;
         let mut source_db_key = String::new();
         let mut node_metadata = None;
         let mut vr_header = None;
@@ -1142,6 +1642,10 @@ impl VectorFS {
             );
             let sfm_size = source_file_map.encoded_size()?;
             node_metadata.insert(FSItem::source_file_map_size_metadata_key(), sfm_size.to_string());
+            node_metadata.insert(
+                FSItem::source_file_content_hash_metadata_key(),
+                FSItem::source_file_content_hash(&source_file_map),
+            );
 
             // Now after updating the metadata, finally save the VRHeader Node into the core vector resource
             let vr_header = vr_header.ok_or(VectorFSError::InvalidFSEntryType(writer.path.to_string()))?;
@@ -1157,6 +1661,7 @@ impl VectorFS {
                     .await?,
                 );
             }
+            self._sync_links(&writer.profile, None).await?;
         }
 
         // Finally saving the the source file map and the FSInternals into the FSDB
@@ -1180,6 +1685,7 @@ impl VectorFS {
         writer: &VFSWriter,
         description: String,
     ) -> Result<FSItem, VectorFSError> {
+        let writer = &self.resolve_link_writer(writer).await?;
         // Fetch the VR and SFM from the DB
         let reader = writer.new_reader_copied_data(writer.path.clone(), self).await?;
         let mut vector_resource = self.retrieve_vector_resource(&reader).await?;
@@ -1189,6 +1695,61 @@ impl VectorFS {
         Self::save_vector_resource(self, writer, vector_resource, None).await
     }
 
+    /// Sets the embedding model the FSItems in the folder at the writer's path (and in its subfolders which don't
+    /// declare their own) are embedded with, instead of the profile's default. None removes the folder's model, so
+    /// it inherits the one of its parent folders again. Errors if any FSItem already in the folder was embedded with
+    /// a different model, as it could no longer be found by searches.
+    pub async fn set_folder_embedding_model(
+        &self,
+        writer: &VFSWriter,
+        embedding_model: Option<EmbeddingModelType>,
+    ) -> Result<FSFolder, VectorFSError> {
+        self.validate_path_points_to_folder(writer.path.clone(), &writer.profile)
+            .await?;
+        let mut internals = self.get_profile_fs_internals_cloned(&writer.profile).await?;
+
+        let mut mutator = |node: &mut Node, _embedding: &mut Embedding| -> Result<(), VRError> {
+            let metadata = node.metadata.get_or_insert_with(HashMap::new);
+            match &embedding_model {
+                Some(model) => metadata.insert(FSFolder::embedding_model_metadata_key(), model.to_string()),
+                None => metadata.remove(&FSFolder::embedding_model_metadata_key()),
+            };
+            Ok(())
+        };
+        internals
+            .fs_core_resource
+            .mutate_node_at_path(writer.path.clone(), &mut mutator, true)?;
+
+        // The items already in the folder must match the model they'd now be searched with
+        for ret_node in internals
+            .fs_core_resource
+            .retrieve_vrheader_nodes_exhaustive(Some(writer.path.clone()))
+        {
+            let vr_header = ret_node.node.get_vr_header_content()?;
+            let folder_model = internals.folder_embedding_model(&ret_node.retrieval_path);
+            if vr_header.resource_embedding_model_used != folder_model {
+                return Err(VectorFSError::EmbeddingModelTypeMismatch(
+                    vr_header.resource_embedding_model_used.clone(),
+                    folder_model,
+                ));
+            }
+        }
+
+        let folder_node = internals
+            .fs_core_resource
+            .retrieve_node_at_path(writer.path.clone(), None)?;
+        let folder =
+            FSFolder::from_vector_resource_node(folder_node.node, writer.path.clone(), &internals.last_read_index)?;
+        self._update_fs_internals(writer.profile.clone(), internals.clone())
+            .await?;
+
+        let mut write_batch = writer.new_write_batch()?;
+        self.db.wb_save_profile_fs_internals(&internals, &mut write_batch)?;
+        self.db.write_pb(write_batch)?;
+
+        Ok(folder)
+    }
+
     /// Internal method used to add a VRHeader into the core resource of a profile's VectorFS internals in memory.
     async fn _add_vr_header_to_core_resource(
         &self,
@@ -1198,7 +1759,30 @@ impl VectorFS {
         current_datetime: DateTime<Utc>,
         adding_new_item_to_fs: bool,
     ) -> Result<FSItem, VectorFSError> {
-        let new_node_path = writer.path.push_cloned(vr_header.resource_name.clone());
+        let node_id = vr_header.resource_name.clone();
+        self._add_item_node_to_core_resource(
+            writer,
+            node_id,
+            vr_header,
+            metadata,
+            current_datetime,
+            adding_new_item_to_fs,
+        )
+        .await
+    }
+
+    /// Internal method used to add a VRHeader-holding node with the given id into the core resource of a profile's
+    /// VectorFS internals in memory. The id differs from the resource name only for links.
+    async fn _add_item_node_to_core_resource(
+        &self,
+        writer: &VFSWriter,
+        node_id: String,
+        vr_header: VRHeader,
+        metadata: Option<HashMap<String, String>>,
+        current_datetime: DateTime<Utc>,
+        adding_new_item_to_fs: bool,
+    ) -> Result<FSItem, VectorFSError> {
+        let new_node_path = writer.path.push_cloned(node_id.clone());
 
         // Mutator method for inserting the VR header and updating the last_modified metadata of parent folder
         let mut mutator = |node: &mut Node, _embedding: &mut Embedding| -> Result<(), VRError> {
@@ -1210,15 +1794,14 @@ impl VectorFS {
                     .map(|m| m.insert(FSFolder::last_modified_key(), current_datetime.to_rfc3339()));
             }
             // Setup the new node & insert it
-            let node_id = vr_header.resource_name.clone();
             let resource = node.get_vector_resource_content_mut()?;
-            let new_vr_header_node = Node::new_vr_header(node_id, &vr_header, metadata.clone(), &vec![]);
+            let new_vr_header_node = Node::new_vr_header(node_id.clone(), &vr_header, metadata.clone(), &vec![]);
             let new_node_embedding = vr_header
                 .resource_embedding
                 .clone()
                 .ok_or(VRError::NoEmbeddingProvided)?;
             resource.as_trait_object_mut().insert_node_dt_specified(
-                vr_header.resource_name.clone(),
+                node_id.clone(),
                 new_vr_header_node,
                 new_node_embedding,
                 Some(current_datetime),
@@ -1248,11 +1831,17 @@ impl VectorFS {
                 .get_mut(&writer.profile)
                 .ok_or_else(|| VectorFSError::ProfileNameNonExistent(writer.profile.to_string()))?;
 
-            if vr_header.resource_embedding_model_used == internals.default_embedding_model()
-                || internals
-                    .supported_embedding_models
-                    .contains(&vr_header.resource_embedding_model_used)
-            {
+            // Folders which declare an embedding model only hold items embedded with it
+            let model_matches = match internals.folder_embedding_model_override(&writer.path) {
+                Some(folder_model) => vr_header.resource_embedding_model_used == folder_model,
+                None => {
+                    vr_header.resource_embedding_model_used == internals.default_embedding_model()
+                        || internals
+                            .supported_embedding_models
+                            .contains(&vr_header.resource_embedding_model_used)
+                }
+            };
+            if model_matches {
                 internals
                     .fs_core_resource
                     .mutate_node_at_path(writer.path.clone(), &mut mutator, true)?;
@@ -1277,7 +1866,7 @@ impl VectorFS {
                 // using the default embedding model and add it to the VRHeader in the FSItem. At the same time implement dynamic vector searching in VecFS to support this.
                 Err(VectorFSError::EmbeddingModelTypeMismatch(
                     vr_header.resource_embedding_model_used,
-                    internals.default_embedding_model(),
+                    internals.folder_embedding_model(&writer.path),
                 ))
             }
         } else {
@@ -548,3 +548,145 @@ fn test_remove_profile() {
     assert!(permission_in_db.is_none());
     assert!(identity_type_in_db.is_none());
 }
+
+#[test]
+fn test_list_rename_and_revoke_devices() {
+    init_default_tracing();
+    setup();
+    let node_profile_name = "@@node1.shinkai";
+    let (_, identity_pk) = unsafe_deterministic_signature_keypair(0);
+    let (_, encryption_pk) = unsafe_deterministic_encryption_keypair(0);
+    let db_path = format!("db_tests/{}", hash_string(node_profile_name));
+    let shinkai_db = ShinkaiDB::new(&db_path).unwrap();
+
+    let (_, profile_identity_pk) = unsafe_deterministic_signature_keypair(1);
+    let (_, profile_encryption_pk) = unsafe_deterministic_encryption_keypair(1);
+    let (_, device_identity_pk) = unsafe_deterministic_signature_keypair(2);
+    let (_, device_encryption_pk) = unsafe_deterministic_encryption_keypair(2);
+
+    task::block_on(create_local_node_profile(
+        &shinkai_db,
+        node_profile_name.to_string(),
+        encryption_pk,
+        identity_pk,
+    ));
+
+    let register_device = |device_name: &str| {
+        let registration_code = shinkai_db
+            .generate_registration_new_code(
+                IdentityPermissions::Standard,
+                RegistrationCodeType::Device("main".to_string()),
+            )
+            .unwrap();
+        shinkai_db.use_registration_code(
+            &registration_code,
+            node_profile_name,
+            device_name,
+            &signature_public_key_to_string(profile_identity_pk),
+            &encryption_public_key_to_string(profile_encryption_pk),
+            Some(&signature_public_key_to_string(device_identity_pk)),
+            Some(&encryption_public_key_to_string(device_encryption_pk)),
+        )
+    };
+    register_device("phone").unwrap();
+
+    let profile_name =
+        ShinkaiName::from_node_and_profile_names(node_profile_name.to_string(), "main".to_string()).unwrap();
+    let device_name = ShinkaiName::new(format!("{}/main/device/phone", node_profile_name)).unwrap();
+
+    shinkai_db.set_device_display_name(&device_name, "Work phone").unwrap();
+    shinkai_db.update_device_last_seen(&device_name).unwrap();
+    let devices = shinkai_db.get_profile_devices(&profile_name).unwrap();
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].full_identity_name, device_name.to_string());
+    assert_eq!(devices[0].display_name, Some("Work phone".to_string()));
+    assert!(devices[0].last_seen.is_some());
+
+    // A revoked device is gone and its keys can't be registered again
+    shinkai_db.revoke_device(&device_name).unwrap();
+    assert!(shinkai_db.get_profile_devices(&profile_name).unwrap().is_empty());
+    assert!(matches!(
+        shinkai_db.get_device(device_name.clone()),
+        Err(ShinkaiDBError::DeviceNameNonExistent(_))
+    ));
+    assert!(matches!(
+        register_device("phone"),
+        Err(ShinkaiDBError::DeviceKeyRevoked(_))
+    ));
+}
+
+#[test]
+fn test_registration_code_expiry_usage_limit_and_revocation() {
+    init_default_tracing();
+    setup();
+    let node_profile_name = "@@node1.shinkai";
+    let (_, identity_pk) = unsafe_deterministic_signature_keypair(0);
+    let (_, encryption_pk) = unsafe_deterministic_encryption_keypair(0);
+    let db_path = format!("db_tests/{}", hash_string(node_profile_name));
+    let shinkai_db = ShinkaiDB::new(&db_path).unwrap();
+
+    task::block_on(create_local_node_profile(
+        &shinkai_db,
+        node_profile_name.to_string(),
+        encryption_pk,
+        identity_pk,
+    ));
+
+    let register_profile = |registration_code: &str, profile_name: &str, key_index: u32| {
+        let (_, profile_identity_pk) = unsafe_deterministic_signature_keypair(key_index);
+        let (_, profile_encryption_pk) = unsafe_deterministic_encryption_keypair(key_index);
+        shinkai_db.use_registration_code(
+            registration_code,
+            node_profile_name,
+            profile_name,
+            &signature_public_key_to_string(profile_identity_pk),
+            &encryption_public_key_to_string(profile_encryption_pk),
+            None,
+            None,
+        )
+    };
+
+    let limited_code = shinkai_db
+        .generate_registration_new_code_with_limits(
+            IdentityPermissions::Standard,
+            RegistrationCodeType::Profile,
+            Some(3600),
+            Some(2),
+        )
+        .unwrap();
+    let expired_code = shinkai_db
+        .generate_registration_new_code_with_limits(
+            IdentityPermissions::Standard,
+            RegistrationCodeType::Profile,
+            Some(0),
+            None,
+        )
+        .unwrap();
+    let revoked_code = shinkai_db
+        .generate_registration_new_code(IdentityPermissions::Standard, RegistrationCodeType::Profile)
+        .unwrap();
+    shinkai_db.revoke_registration_code(&revoked_code).unwrap();
+
+    let outstanding_codes = shinkai_db.get_outstanding_registration_codes().unwrap();
+    assert_eq!(outstanding_codes.len(), 1);
+    assert_eq!(outstanding_codes[0].0, limited_code);
+
+    // The limited code can be used twice
+    register_profile(&limited_code, "profile_1", 1).unwrap();
+    assert_eq!(shinkai_db.get_registration_code_info(&limited_code).unwrap().uses, 1);
+    register_profile(&limited_code, "profile_2", 2).unwrap();
+    assert!(matches!(
+        register_profile(&limited_code, "profile_3", 3),
+        Err(ShinkaiDBError::CodeAlreadyUsed)
+    ));
+    assert!(shinkai_db.get_outstanding_registration_codes().unwrap().is_empty());
+
+    assert!(matches!(
+        register_profile(&expired_code, "profile_3", 3),
+        Err(ShinkaiDBError::CodeExpired)
+    ));
+    assert!(matches!(
+        register_profile(&revoked_code, "profile_3", 3),
+        Err(ShinkaiDBError::CodeRevoked)
+    ));
+}
@@ -1,3 +1,4 @@
+use shinkai_message_primitives::schemas::contact::{Contact, ContactTrustLevel};
 use shinkai_message_primitives::schemas::inbox_name::{InboxName, InboxNameError};
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
 use shinkai_message_primitives::shinkai_message::shinkai_message::{
@@ -7,18 +8,28 @@ use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{Ident
 use shinkai_message_primitives::shinkai_utils::encryption::{
     unsafe_deterministic_encryption_keypair, EncryptionMethod,
 };
+use shinkai_message_primitives::shinkai_utils::job_scope::JobScope;
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::init_default_tracing;
 use shinkai_message_primitives::shinkai_utils::shinkai_message_builder::ShinkaiMessageBuilder;
 use shinkai_message_primitives::shinkai_utils::signatures::{
     clone_signature_secret_key, unsafe_deterministic_signature_keypair,
 };
+use shinkai_node::cron_tasks::inbox_digester::InboxDigester;
 use shinkai_node::db::db_errors::ShinkaiDBError;
 use shinkai_node::db::ShinkaiDB;
+use shinkai_node::network::inbound_message_filter::{InboundMessageFilter, InboundMessageVerdict};
+use shinkai_node::network::inbox_message_changes::{InboxMessageChangeError, InboxMessageChanges};
+use shinkai_node::network::message_attachments::{MessageAttachmentError, MessageAttachments};
 use shinkai_node::schemas::identity::{StandardIdentity, StandardIdentityType};
+use shinkai_node::schemas::inbound_message_policy::InboundMessagePolicy;
+use shinkai_node::schemas::inbox_digest::{InboxDigestFrequency, InboxDigestSchedule, InboxDigestSettings};
+use shinkai_node::schemas::inbox_message_change::InboxMessageChange;
 use shinkai_node::schemas::inbox_permission::InboxPermission;
+use shinkai_node::schemas::message_attachment::AttachmentChunk;
 use shinkai_vector_resources::utils::hash_string;
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
 
 use ed25519_dalek::SigningKey;
 use x25519_dalek::{PublicKey as EncryptionPublicKey, StaticSecret as EncryptionStaticKey};
@@ -220,6 +231,317 @@ async fn test_insert_two_messages_and_check_order_and_parent() {
     );
 }
 
+#[tokio::test]
+async fn test_edit_and_delete_inbox_messages() {
+    init_default_tracing();
+    setup();
+
+    let node_identity_name = "@@node.shinkai";
+    let subidentity_name = "main";
+    let (node_identity_sk, _) = unsafe_deterministic_signature_keypair(0);
+    let (node_encryption_sk, node_encryption_pk) = unsafe_deterministic_encryption_keypair(0);
+
+    let node_db_path = format!("db_tests/{}", hash_string(node_identity_name));
+    let shinkai_db = ShinkaiDB::new(&node_db_path).unwrap();
+
+    let message1 = generate_message_with_text(
+        "First Message".to_string(),
+        node_encryption_sk.clone(),
+        clone_signature_secret_key(&node_identity_sk),
+        node_encryption_pk,
+        subidentity_name.to_string(),
+        node_identity_name.to_string(),
+        "2023-07-02T20:53:34.812Z".to_string(),
+    );
+    let message2 = generate_message_with_text(
+        "Second Message".to_string(),
+        node_encryption_sk.clone(),
+        clone_signature_secret_key(&node_identity_sk),
+        node_encryption_pk,
+        subidentity_name.to_string(),
+        node_identity_name.to_string(),
+        "2023-07-02T20:54:34.923Z".to_string(),
+    );
+    let hash1 = message1.calculate_message_hash_for_pagination();
+    let hash2 = message2.calculate_message_hash_for_pagination();
+    shinkai_db.unsafe_insert_inbox_message(&message1, None, None).await.unwrap();
+    shinkai_db
+        .unsafe_insert_inbox_message(&message2, Some(hash1.clone()), None)
+        .await
+        .unwrap();
+    let inbox_name = InboxName::from_message(&message1).unwrap().to_string();
+
+    // Only the sender can change its messages
+    let someone_else = ShinkaiName::new("@@other_node.shinkai".to_string()).unwrap();
+    assert!(matches!(
+        InboxMessageChanges::change_own_message(&shinkai_db, &someone_else, &inbox_name, &hash1, None),
+        Err(InboxMessageChangeError::NotAllowed(_))
+    ));
+    let sender = ShinkaiName::new(node_identity_name.to_string()).unwrap();
+    assert!(matches!(
+        InboxMessageChanges::change_own_message(&shinkai_db, &sender, &inbox_name, &hash1, Some(" ".to_string())),
+        Err(InboxMessageChangeError::InvalidInput(_))
+    ));
+
+    InboxMessageChanges::change_own_message(
+        &shinkai_db,
+        &sender,
+        &inbox_name,
+        &hash1,
+        Some("First Message, edited".to_string()),
+    )
+    .unwrap();
+    InboxMessageChanges::change_own_message(
+        &shinkai_db,
+        &sender,
+        &inbox_name,
+        &hash1,
+        Some("First Message, edited twice".to_string()),
+    )
+    .unwrap();
+    InboxMessageChanges::change_own_message(&shinkai_db, &sender, &inbox_name, &hash2, None).unwrap();
+
+    let history = shinkai_db.get_inbox_message_changes(&inbox_name, &hash1).unwrap();
+    assert_eq!(history.edits.len(), 2);
+    assert_eq!(history.edits[0].content, "First Message, edited");
+    assert_eq!(history.deleted_at, None);
+
+    // Deleted messages keep a tombstone and can't be edited anymore
+    let history = shinkai_db.get_inbox_message_changes(&inbox_name, &hash2).unwrap();
+    assert!(history.edits.is_empty());
+    assert!(history.deleted_at.is_some());
+    assert!(matches!(
+        InboxMessageChanges::change_own_message(&shinkai_db, &sender, &inbox_name, &hash2, Some("Back".to_string())),
+        Err(InboxMessageChangeError::InvalidInput(_))
+    ));
+
+    // Changes sent by other nodes go through the same checks
+    let remote_change = |inbox_name: &str, message_hash: &str, new_content: &str| InboxMessageChange {
+        inbox_name: inbox_name.to_string(),
+        message_hash: message_hash.to_string(),
+        new_content: Some(new_content.to_string()),
+        changed_at: "2023-07-02T21:00:00.000Z".to_string(),
+    };
+    assert!(matches!(
+        InboxMessageChanges::receive_change(&shinkai_db, &sender, remote_change(&inbox_name, &hash2, "Back")),
+        Err(InboxMessageChangeError::InvalidInput(_))
+    ));
+    let other_inbox_name = "inbox::@@node.shinkai/main::@@other_node.shinkai/main::false";
+    assert!(matches!(
+        InboxMessageChanges::receive_change(&shinkai_db, &sender, remote_change(other_inbox_name, &hash1, "Moved")),
+        Err(InboxMessageChangeError::NotFound(_))
+    ));
+    assert!(shinkai_db
+        .get_inbox_message_changes(other_inbox_name, &hash1)
+        .unwrap()
+        .edits
+        .is_empty());
+
+    // The branch keeps the original messages, so their hashes don't change, with the changes in their node api data
+    let messages = shinkai_db
+        .get_last_messages_from_inbox(inbox_name.clone(), 2, None)
+        .unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0][0].get_message_content().unwrap(), "First Message");
+    assert_eq!(messages[0][0].calculate_message_hash_for_pagination(), hash1);
+    let node_api_data = |message: &ShinkaiMessage| match &message.body {
+        MessageBody::Unencrypted(body) => body.internal_metadata.node_api_data.clone().unwrap(),
+        _ => panic!("Expected an unencrypted message"),
+    };
+    let first = node_api_data(&messages[0][0]);
+    assert_eq!(first.edited_content, Some("First Message, edited twice".to_string()));
+    assert!(first.edited_at.is_some());
+    assert_eq!(first.deleted_at, None);
+    let second = node_api_data(&messages[1][0]);
+    assert_eq!(second.edited_content, None);
+    assert!(second.deleted_at.is_some());
+
+    let paginated_messages = shinkai_db
+        .get_last_messages_from_inbox(inbox_name.clone(), 2, Some(hash2.clone()))
+        .unwrap();
+    assert_eq!(paginated_messages.len(), 1);
+    assert_eq!(paginated_messages[0][0].calculate_message_hash_for_pagination(), hash1);
+}
+
+#[tokio::test]
+async fn test_read_message_attachments() {
+    init_default_tracing();
+    setup();
+
+    let node_identity_name = "@@node.shinkai";
+    let subidentity_name = "main";
+    let (node_identity_sk, _) = unsafe_deterministic_signature_keypair(0);
+    let (node_encryption_sk, node_encryption_pk) = unsafe_deterministic_encryption_keypair(0);
+
+    let node_db_path = format!("db_tests/{}", hash_string(node_identity_name));
+    let shinkai_db = ShinkaiDB::new(&node_db_path).unwrap();
+
+    let large_file: Vec<u8> = (0..600 * 1024).map(|i| (i % 251) as u8).collect();
+    let (mut payload, attachments) = MessageAttachments::encrypt_files(
+        &shinkai_db,
+        "Here are the files".to_string(),
+        vec![
+            ("notes.txt".to_string(), b"Some notes".to_vec()),
+            ("data.bin".to_string(), large_file.clone()),
+        ],
+    )
+    .unwrap();
+    assert_eq!(attachments[0].0.chunk_count, 1);
+    assert_eq!(attachments[1].0.chunk_count, 3);
+
+    // An attachment whose chunks weren't received yet
+    let mut pending = payload.attachments[0].clone();
+    pending.attachment_id = "pending".to_string();
+    payload.attachments.push(pending);
+
+    let message = generate_message_with_text(
+        serde_json::to_string(&payload).unwrap(),
+        node_encryption_sk.clone(),
+        clone_signature_secret_key(&node_identity_sk),
+        node_encryption_pk,
+        subidentity_name.to_string(),
+        node_identity_name.to_string(),
+        "2023-07-02T20:53:34.812Z".to_string(),
+    );
+    let hash = message.calculate_message_hash_for_pagination();
+    shinkai_db.unsafe_insert_inbox_message(&message, None, None).await.unwrap();
+    let inbox_name = InboxName::from_message(&message).unwrap().to_string();
+
+    let (file_name, file) =
+        MessageAttachments::read_attachment(&shinkai_db, &inbox_name, &hash, &attachments[0].0.attachment_id).unwrap();
+    assert_eq!(file_name, "notes.txt");
+    assert_eq!(file, b"Some notes".to_vec());
+    let (file_name, file) =
+        MessageAttachments::read_attachment(&shinkai_db, &inbox_name, &hash, &attachments[1].0.attachment_id).unwrap();
+    assert_eq!(file_name, "data.bin");
+    assert_eq!(file, large_file);
+
+    assert!(matches!(
+        MessageAttachments::read_attachment(&shinkai_db, &inbox_name, &hash, "pending"),
+        Err(MessageAttachmentError::NotReceived(_))
+    ));
+    assert!(matches!(
+        MessageAttachments::read_attachment(&shinkai_db, &inbox_name, &hash, "missing"),
+        Err(MessageAttachmentError::NotFound(_))
+    ));
+    assert!(matches!(
+        MessageAttachments::read_attachment(
+            &shinkai_db,
+            "inbox::@@other.shinkai::@@node.shinkai::false",
+            &hash,
+            "pending"
+        ),
+        Err(MessageAttachmentError::NotFound(_))
+    ));
+
+    // Attachments of deleted messages can't be read anymore
+    let sender = ShinkaiName::new(node_identity_name.to_string()).unwrap();
+    InboxMessageChanges::change_own_message(&shinkai_db, &sender, &inbox_name, &hash, None).unwrap();
+    assert!(matches!(
+        MessageAttachments::read_attachment(&shinkai_db, &inbox_name, &hash, &attachments[0].0.attachment_id),
+        Err(MessageAttachmentError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_receive_attachment_chunks() {
+    init_default_tracing();
+    setup();
+
+    let node_name = ShinkaiName::new("@@node.shinkai".to_string()).unwrap();
+    let sender = ShinkaiName::new("@@alice.shinkai".to_string()).unwrap();
+    let (sender_identity_sk, _) = unsafe_deterministic_signature_keypair(1);
+    let (sender_encryption_sk, _) = unsafe_deterministic_encryption_keypair(1);
+    let (_, node_encryption_pk) = unsafe_deterministic_encryption_keypair(0);
+
+    let sender_db = ShinkaiDB::new(&format!("db_tests/{}", hash_string("@@alice.shinkai"))).unwrap();
+    let shinkai_db = ShinkaiDB::new(&format!("db_tests/{}", hash_string("@@node.shinkai"))).unwrap();
+
+    let file: Vec<u8> = (0..300 * 1024).map(|i| (i % 251) as u8).collect();
+    let (payload, attachments) = MessageAttachments::encrypt_files(
+        &sender_db,
+        "Here is the file".to_string(),
+        vec![("data.bin".to_string(), file.clone())],
+    )
+    .unwrap();
+    let (attachment, chunks) = &attachments[0];
+    assert_eq!(attachment.chunk_count, 2);
+
+    let inbox_name = InboxName::get_regular_inbox_name_from_params(
+        "@@alice.shinkai".to_string(),
+        "main".to_string(),
+        "@@node.shinkai".to_string(),
+        "main".to_string(),
+        false,
+    )
+    .unwrap()
+    .get_value();
+    let message = ShinkaiMessageBuilder::new(sender_encryption_sk, sender_identity_sk, node_encryption_pk)
+        .message_raw_content(serde_json::to_string(&payload).unwrap())
+        .body_encryption(EncryptionMethod::None)
+        .message_schema_type(MessageSchemaType::MessageWithAttachments)
+        .internal_metadata_with_inbox(
+            "main".to_string(),
+            "main".to_string(),
+            inbox_name.clone(),
+            EncryptionMethod::None,
+            None,
+        )
+        .external_metadata_with_schedule(
+            "@@node.shinkai".to_string(),
+            "@@alice.shinkai".to_string(),
+            "2023-07-02T20:53:34.812Z".to_string(),
+        )
+        .build()
+        .unwrap();
+    let hash = message.calculate_message_hash_for_pagination();
+
+    let chunk = |index: usize, data: &[u8]| AttachmentChunk {
+        inbox_name: inbox_name.clone(),
+        attachment_id: attachment.attachment_id.clone(),
+        index: index as u32,
+        chunk_count: attachment.chunk_count,
+        data: base64::encode(data),
+    };
+
+    // Chunks are only accepted for attachments of a saved message, from the node that sent it
+    assert!(matches!(
+        MessageAttachments::receive_chunk(&shinkai_db, &node_name, &sender, chunk(0, &chunks[0])),
+        Err(MessageAttachmentError::NotAllowed(_))
+    ));
+    shinkai_db.unsafe_insert_inbox_message(&message, None, None).await.unwrap();
+    MessageAttachments::receive_announcement(&shinkai_db, &node_name, &message).unwrap();
+    assert!(matches!(
+        MessageAttachments::receive_chunk(&shinkai_db, &node_name, &node_name, chunk(0, &chunks[0])),
+        Err(MessageAttachmentError::NotAllowed(_))
+    ));
+
+    // Chunks larger than an encrypted chunk or out of the announced range are refused
+    assert!(matches!(
+        MessageAttachments::receive_chunk(&shinkai_db, &node_name, &sender, chunk(0, &[0u8; 300 * 1024])),
+        Err(MessageAttachmentError::InvalidInput(_))
+    ));
+    let mut extra_chunk = chunk(2, &chunks[0]);
+    extra_chunk.chunk_count = 3;
+    assert!(matches!(
+        MessageAttachments::receive_chunk(&shinkai_db, &node_name, &sender, extra_chunk),
+        Err(MessageAttachmentError::InvalidInput(_))
+    ));
+
+    // The first chunk received for an index is kept
+    MessageAttachments::receive_chunk(&shinkai_db, &node_name, &sender, chunk(0, &chunks[0])).unwrap();
+    assert!(matches!(
+        MessageAttachments::receive_chunk(&shinkai_db, &node_name, &sender, chunk(0, &chunks[1])),
+        Err(MessageAttachmentError::InvalidInput(_))
+    ));
+    MessageAttachments::receive_chunk(&shinkai_db, &node_name, &sender, chunk(1, &chunks[1])).unwrap();
+
+    let (file_name, received) =
+        MessageAttachments::read_attachment(&shinkai_db, &inbox_name, &hash, &attachment.attachment_id).unwrap();
+    assert_eq!(file_name, "data.bin");
+    assert_eq!(received, file);
+}
+
 #[tokio::test]
 async fn test_insert_messages_with_simple_tree_structure() {
     init_default_tracing();
@@ -1059,7 +1381,7 @@ async fn db_inbox() {
 
     // Test get_smart_inboxes_for_profile
     let smart_inboxes = shinkai_db
-        .get_all_smart_inboxes_for_profile(node1_profile_identity.clone())
+        .get_all_smart_inboxes_for_profile(node1_profile_identity.clone(), false)
         .unwrap();
     assert_eq!(smart_inboxes.len(), 1);
 
@@ -1104,7 +1426,7 @@ async fn db_inbox() {
 
     // Get smart_inboxes again
     let updated_smart_inboxes = shinkai_db
-        .get_all_smart_inboxes_for_profile(node1_profile_identity)
+        .get_all_smart_inboxes_for_profile(node1_profile_identity.clone(), false)
         .unwrap();
 
     // Check if the name of the updated inbox has been changed
@@ -1114,6 +1436,314 @@ async fn db_inbox() {
             assert_eq!(smart_inbox.custom_name, new_name);
         }
     }
+
+    // Renaming an inbox removes it from the ones waiting for a generated title
+    let profile_name = ShinkaiName::new(format!("{}/{}", node1_identity_name, node1_subidentity_name)).unwrap();
+    shinkai_db
+        .add_inbox_pending_title(inbox_to_update, &profile_name)
+        .unwrap();
+    assert_eq!(
+        shinkai_db.get_inboxes_pending_title().unwrap(),
+        vec![(inbox_to_update.to_string(), profile_name)]
+    );
+    shinkai_db.update_smart_inbox_name(inbox_to_update, "Renamed").unwrap();
+    assert!(shinkai_db.get_inboxes_pending_title().unwrap().is_empty());
+
+    // File the inbox in a folder and tag it
+    shinkai_db
+        .set_inbox_folder(inbox_to_update, Some(" Work ".to_string()))
+        .unwrap();
+    shinkai_db
+        .set_inbox_labels(
+            inbox_to_update,
+            vec![
+                "urgent".to_string(),
+                "urgent".to_string(),
+                " ".to_string(),
+                "clients".to_string(),
+            ],
+        )
+        .unwrap();
+    let smart_inbox = shinkai_db
+        .get_all_smart_inboxes_for_profile(node1_profile_identity.clone(), false)
+        .unwrap()
+        .into_iter()
+        .find(|smart_inbox| smart_inbox.inbox_id == inbox_to_update)
+        .unwrap();
+    assert_eq!(smart_inbox.folder.as_deref(), Some("Work"));
+    assert_eq!(smart_inbox.labels, vec!["urgent".to_string(), "clients".to_string()]);
+
+    let inbox_folders = shinkai_db
+        .get_inbox_folders_for_profile(node1_profile_identity.clone())
+        .unwrap();
+    assert_eq!(inbox_folders.folders.get("Work"), Some(&1));
+    assert_eq!(inbox_folders.labels.get("clients"), Some(&1));
+    assert_eq!(inbox_folders.unfiled, 0);
+
+    // Moving it out of the folder keeps its labels
+    shinkai_db.set_inbox_folder(inbox_to_update, None).unwrap();
+    let inbox_folders = shinkai_db
+        .get_inbox_folders_for_profile(node1_profile_identity)
+        .unwrap();
+    assert!(inbox_folders.folders.is_empty());
+    assert_eq!(inbox_folders.labels.len(), 2);
+    assert_eq!(inbox_folders.unfiled, 1);
+
+    // Muted inboxes don't count their unread messages
+    let unread_count = |shinkai_db: &ShinkaiDB| {
+        shinkai_db
+            .get_all_smart_inboxes_for_profile(node1_profile_identity.clone(), false)
+            .unwrap()[0]
+            .unread_count
+    };
+    assert!(unread_count(&shinkai_db) > 0);
+    shinkai_db.set_inbox_muted(inbox_to_update, true).unwrap();
+    assert_eq!(unread_count(&shinkai_db), 0);
+
+    // Archived inboxes are only listed when requested
+    shinkai_db.set_inbox_archived(inbox_to_update, true).unwrap();
+    assert!(shinkai_db
+        .get_all_smart_inboxes_for_profile(node1_profile_identity.clone(), false)
+        .unwrap()
+        .is_empty());
+    let smart_inboxes = shinkai_db
+        .get_all_smart_inboxes_for_profile(node1_profile_identity.clone(), true)
+        .unwrap();
+    assert_eq!(smart_inboxes.len(), 1);
+    assert!(smart_inboxes[0].is_archived && smart_inboxes[0].is_muted);
+
+    shinkai_db.set_inbox_archived(inbox_to_update, false).unwrap();
+    assert_eq!(
+        shinkai_db
+            .get_all_smart_inboxes_for_profile(node1_profile_identity, false)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_message_requests_and_contacts() {
+    init_default_tracing();
+    setup();
+
+    let node_identity_name = "@@node1.shinkai";
+    let profile_name = "main";
+    let (node_identity_sk, node_identity_pk) = unsafe_deterministic_signature_keypair(0);
+    let (node_encryption_sk, node_encryption_pk) = unsafe_deterministic_encryption_keypair(0);
+    let (_, profile_identity_pk) = unsafe_deterministic_signature_keypair(100);
+    let (_, profile_encryption_pk) = unsafe_deterministic_encryption_keypair(100);
+
+    let node_db_path = format!("db_tests/{}", hash_string(node_identity_name));
+    let shinkai_db = ShinkaiDB::new(&node_db_path).unwrap();
+
+    let profile =
+        ShinkaiName::from_node_and_profile_names(node_identity_name.to_string(), profile_name.to_string()).unwrap();
+    let profile_identity = StandardIdentity::new(
+        profile.clone(),
+        None,
+        node_encryption_pk,
+        node_identity_pk,
+        Some(profile_encryption_pk),
+        Some(profile_identity_pk),
+        StandardIdentityType::Profile,
+        IdentityPermissions::Standard,
+    );
+    shinkai_db.insert_profile(profile_identity.clone()).unwrap();
+
+    let message = |content: &str, timestamp: &str| {
+        generate_message_with_text(
+            content.to_string(),
+            node_encryption_sk.clone(),
+            clone_signature_secret_key(&node_identity_sk),
+            profile_encryption_pk,
+            profile_name.to_string(),
+            node_identity_name.to_string(),
+            timestamp.to_string(),
+        )
+    };
+    let first_message = message("Hello", "2023-07-02T20:53:34.812Z");
+
+    // Without allowlist mode anyone can start a conversation
+    assert!(!shinkai_db.flag_message_request(&first_message).unwrap());
+
+    // In allowlist mode, new conversations from unknown and untrusted identities are message requests
+    shinkai_db.set_contacts_allowlist_mode(&profile, true).unwrap();
+    let sender = ShinkaiName::new(node_identity_name.to_string()).unwrap();
+    let mut contact = Contact {
+        identity: sender.full_name.clone(),
+        nickname: Some("Node one".to_string()),
+        notes: None,
+        trust_level: ContactTrustLevel::Untrusted,
+        pinned: false,
+        added_at: "2023-07-02T20:50:00.000Z".to_string(),
+    };
+    shinkai_db.set_contact(&profile, contact.clone()).unwrap();
+    assert!(!shinkai_db.get_contact_book(&profile).unwrap().allows(&sender));
+    assert!(shinkai_db.flag_message_request(&first_message).unwrap());
+    shinkai_db
+        .unsafe_insert_inbox_message(&first_message, None, None)
+        .await
+        .unwrap();
+    let inbox_name = InboxName::from_message(&first_message).unwrap().to_string();
+    assert!(shinkai_db.is_inbox_message_request(&inbox_name).unwrap());
+
+    // Replies to an existing conversation don't change it
+    let second_message = message("Are you there?", "2023-07-02T20:54:34.812Z");
+    assert!(!shinkai_db.flag_message_request(&second_message).unwrap());
+
+    // Inboxes come with the contact of the other participant
+    contact.trust_level = ContactTrustLevel::Trusted;
+    contact.added_at = "2024-01-01T00:00:00.000Z".to_string();
+    shinkai_db.set_contact(&profile, contact).unwrap();
+    let smart_inboxes = shinkai_db
+        .get_all_smart_inboxes_for_profile(profile_identity.clone(), false)
+        .unwrap();
+    assert_eq!(smart_inboxes.len(), 1);
+    assert!(smart_inboxes[0].is_message_request);
+    let inbox_contact = smart_inboxes[0].contact.clone().unwrap();
+    assert_eq!(inbox_contact.nickname.as_deref(), Some("Node one"));
+    assert_eq!(inbox_contact.trust_level, ContactTrustLevel::Trusted);
+    assert_eq!(inbox_contact.added_at, "2023-07-02T20:50:00.000Z");
+
+    shinkai_db.set_inbox_message_request(&inbox_name, false).unwrap();
+    let smart_inboxes = shinkai_db
+        .get_all_smart_inboxes_for_profile(profile_identity, false)
+        .unwrap();
+    assert!(!smart_inboxes[0].is_message_request);
+
+    shinkai_db.remove_contact(&profile, &sender.full_name).unwrap();
+    assert!(matches!(
+        shinkai_db.remove_contact(&profile, &sender.full_name),
+        Err(ShinkaiDBError::DataNotFound)
+    ));
+    assert!(shinkai_db.get_contact_book(&profile).unwrap().contacts.is_empty());
+
+    shinkai_db.set_contacts_allowlist_mode(&profile, true).unwrap();
+    shinkai_db.remove_contact_book(&profile).unwrap();
+    assert!(!shinkai_db.get_contact_book(&profile).unwrap().allowlist_mode);
+}
+
+#[test]
+fn test_inbound_message_policy() {
+    init_default_tracing();
+    setup();
+
+    let node_identity_name = "@@node1.shinkai";
+    let profile_name = "main";
+    let (node_identity_sk, node_identity_pk) = unsafe_deterministic_signature_keypair(0);
+    let (node_encryption_sk, node_encryption_pk) = unsafe_deterministic_encryption_keypair(0);
+    let (_, profile_identity_pk) = unsafe_deterministic_signature_keypair(100);
+    let (_, profile_encryption_pk) = unsafe_deterministic_encryption_keypair(100);
+
+    let node_db_path = format!("db_tests/{}", hash_string(node_identity_name));
+    let shinkai_db = ShinkaiDB::new(&node_db_path).unwrap();
+
+    let profile =
+        ShinkaiName::from_node_and_profile_names(node_identity_name.to_string(), profile_name.to_string()).unwrap();
+    let profile_identity = StandardIdentity::new(
+        profile.clone(),
+        None,
+        node_encryption_pk,
+        node_identity_pk,
+        Some(profile_encryption_pk),
+        Some(profile_identity_pk),
+        StandardIdentityType::Profile,
+        IdentityPermissions::Standard,
+    );
+    shinkai_db.insert_profile(profile_identity).unwrap();
+
+    let message = |content: &str| {
+        generate_message_with_text(
+            content.to_string(),
+            node_encryption_sk.clone(),
+            clone_signature_secret_key(&node_identity_sk),
+            profile_encryption_pk,
+            profile_name.to_string(),
+            node_identity_name.to_string(),
+            "2023-07-02T20:53:34.812Z".to_string(),
+        )
+    };
+    let filter = InboundMessageFilter::new(5, Duration::from_secs(60));
+
+    // Profiles without a policy don't filter anything
+    assert_eq!(
+        shinkai_db.get_inbound_message_policy(&profile).unwrap(),
+        InboundMessagePolicy::default()
+    );
+    assert_eq!(
+        filter.check(&shinkai_db, &message("Hello")).unwrap(),
+        InboundMessageVerdict::Accept
+    );
+
+    let policy = InboundMessagePolicy {
+        max_messages_per_minute: Some(2),
+        max_message_bytes: Some(20),
+        blocked_keywords: vec!["free money".to_string()],
+        exempt_contacts: true,
+    };
+    shinkai_db.set_inbound_message_policy(&profile, &policy).unwrap();
+    assert!(matches!(
+        filter.check(&shinkai_db, &message("Get FREE Money")).unwrap(),
+        InboundMessageVerdict::Drop(_)
+    ));
+    assert!(matches!(
+        filter.check(&shinkai_db, &message("This message is too long")).unwrap(),
+        InboundMessageVerdict::Drop(_)
+    ));
+
+    // Filtered messages don't count for the rate limit
+    assert_eq!(
+        filter.check(&shinkai_db, &message("Hi")).unwrap(),
+        InboundMessageVerdict::Accept
+    );
+    assert_eq!(
+        filter.check(&shinkai_db, &message("Hi again")).unwrap(),
+        InboundMessageVerdict::Accept
+    );
+    assert!(matches!(
+        filter.check(&shinkai_db, &message("Hi, third")).unwrap(),
+        InboundMessageVerdict::Drop(_)
+    ));
+
+    // Contacts skip the policy, unless they are untrusted
+    let sender = ShinkaiName::new(node_identity_name.to_string()).unwrap();
+    let mut contact = Contact {
+        identity: sender.full_name.clone(),
+        nickname: None,
+        notes: None,
+        trust_level: ContactTrustLevel::Known,
+        pinned: false,
+        added_at: "2023-07-02T20:50:00.000Z".to_string(),
+    };
+    shinkai_db.set_contact(&profile, contact.clone()).unwrap();
+    assert_eq!(
+        filter.check(&shinkai_db, &message("Get free money")).unwrap(),
+        InboundMessageVerdict::Accept
+    );
+    contact.trust_level = ContactTrustLevel::Untrusted;
+    shinkai_db.set_contact(&profile, contact).unwrap();
+    assert!(matches!(
+        filter.check(&shinkai_db, &message("Get free money")).unwrap(),
+        InboundMessageVerdict::Drop(_)
+    ));
+
+    // First contacts wait for approval in allowlist mode
+    shinkai_db
+        .set_inbound_message_policy(&profile, &InboundMessagePolicy::default())
+        .unwrap();
+    shinkai_db.set_contacts_allowlist_mode(&profile, true).unwrap();
+    assert_eq!(
+        filter.check(&shinkai_db, &message("Hello")).unwrap(),
+        InboundMessageVerdict::Request
+    );
+
+    shinkai_db.set_inbound_message_policy(&profile, &policy).unwrap();
+    shinkai_db.remove_inbound_message_policy(&profile).unwrap();
+    assert_eq!(
+        shinkai_db.get_inbound_message_policy(&profile).unwrap(),
+        InboundMessagePolicy::default()
+    );
 }
 
 #[test]
@@ -1229,3 +1859,126 @@ fn test_permission_errors() {
         ShinkaiDBError::ProfileNotFound("Profile not found for: nonexistent_identity".to_string())
     );
 }
+
+#[tokio::test]
+async fn test_inbox_digest_activity() {
+    init_default_tracing();
+    setup();
+
+    let node_identity_name = "@@node1.shinkai";
+    let profile_name = "main";
+    let (node_identity_sk, node_identity_pk) = unsafe_deterministic_signature_keypair(0);
+    let (node_encryption_sk, node_encryption_pk) = unsafe_deterministic_encryption_keypair(0);
+    let (_, profile_identity_pk) = unsafe_deterministic_signature_keypair(100);
+    let (_, profile_encryption_pk) = unsafe_deterministic_encryption_keypair(100);
+
+    let node_db_path = format!("db_tests/{}", hash_string(node_identity_name));
+    let shinkai_db = ShinkaiDB::new(&node_db_path).unwrap();
+
+    let profile =
+        ShinkaiName::from_node_and_profile_names(node_identity_name.to_string(), profile_name.to_string()).unwrap();
+    let profile_identity = StandardIdentity::new(
+        profile.clone(),
+        None,
+        node_encryption_pk,
+        node_identity_pk,
+        Some(profile_encryption_pk),
+        Some(profile_identity_pk),
+        StandardIdentityType::Profile,
+        IdentityPermissions::Standard,
+    );
+    shinkai_db.insert_profile(profile_identity.clone()).unwrap();
+
+    let since = "2023-07-03T00:00:00.000Z";
+    for (content, timestamp) in [
+        ("Old news", "2023-07-02T20:53:34.812Z"),
+        ("Lunch tomorrow?", "2023-07-03T09:00:00.000Z"),
+    ] {
+        let message = generate_message_with_text(
+            content.to_string(),
+            node_encryption_sk.clone(),
+            clone_signature_secret_key(&node_identity_sk),
+            profile_encryption_pk,
+            profile_name.to_string(),
+            node_identity_name.to_string(),
+            timestamp.to_string(),
+        );
+        shinkai_db
+            .unsafe_insert_inbox_message(&message, None, None)
+            .await
+            .unwrap();
+    }
+
+    // A job answered by its agent
+    let job_id = "job_digest_test".to_string();
+    shinkai_db
+        .create_new_job(job_id.clone(), "agent1".to_string(), JobScope::new_default(), false)
+        .unwrap();
+    let job_inbox = InboxName::get_job_inbox_name_from_params(job_id.clone())
+        .unwrap()
+        .to_string();
+    shinkai_db
+        .add_permission_with_profile(&job_inbox, profile.clone(), InboxPermission::Admin)
+        .unwrap();
+    let answer = ShinkaiMessageBuilder::job_message_from_llm_provider(
+        job_id.clone(),
+        "The report is ready".to_string(),
+        "".to_string(),
+        clone_signature_secret_key(&node_identity_sk),
+        node_identity_name.to_string(),
+        node_identity_name.to_string(),
+    )
+    .unwrap();
+    shinkai_db
+        .add_message_to_job_inbox(&job_id, &answer, None, None)
+        .await
+        .unwrap();
+
+    // Only the unread messages received since the last digest are summarized
+    let activity = InboxDigester::collect_activity(&shinkai_db, profile_identity.clone(), None, since).unwrap();
+    assert_eq!(activity.unread_messages.len(), 1);
+    assert_eq!(activity.unread_messages[0].1, node_identity_name);
+    assert_eq!(activity.unread_messages[0].2, "Lunch tomorrow?");
+    assert_eq!(activity.completed_jobs.len(), 1);
+    assert_eq!(activity.completed_jobs[0].1, "The report is ready");
+
+    // The inbox of the digests and muted inboxes are left out
+    let activity =
+        InboxDigester::collect_activity(&shinkai_db, profile_identity.clone(), Some(&job_inbox), since).unwrap();
+    assert!(activity.completed_jobs.is_empty());
+    let regular_inbox = shinkai_db
+        .get_inboxes_for_profile(profile_identity.clone())
+        .unwrap()
+        .into_iter()
+        .find(|inbox_name| *inbox_name != job_inbox)
+        .unwrap();
+    shinkai_db.set_inbox_muted(&regular_inbox, true).unwrap();
+    let activity = InboxDigester::collect_activity(&shinkai_db, profile_identity, Some(&job_inbox), since).unwrap();
+    assert!(activity.is_empty());
+
+    // Schedules are due once a period passed since the last digest
+    let mut schedule = InboxDigestSchedule {
+        settings: InboxDigestSettings {
+            frequency: InboxDigestFrequency::Daily,
+            llm_provider_id: "agent1".to_string(),
+            email_to: vec![],
+        },
+        inbox_name: None,
+        last_digest_at: since.to_string(),
+    };
+    let at = |datetime: &str| {
+        chrono::DateTime::parse_from_rfc3339(datetime)
+            .unwrap()
+            .with_timezone(&chrono::Utc)
+    };
+    assert!(!schedule.is_due(at("2023-07-03T23:59:59Z")));
+    assert!(schedule.is_due(at("2023-07-04T00:00:00Z")));
+    schedule.settings.frequency = InboxDigestFrequency::Weekly;
+    assert!(!schedule.is_due(at("2023-07-04T00:00:00Z")));
+    assert!(schedule.is_due(at("2023-07-10T00:00:00Z")));
+
+    shinkai_db.set_inbox_digest_schedule(&profile, &schedule).unwrap();
+    assert_eq!(shinkai_db.get_inbox_digest_schedule(&profile).unwrap(), Some(schedule));
+    shinkai_db.remove_inbox_digest_schedule(&profile).unwrap();
+    assert_eq!(shinkai_db.get_inbox_digest_schedule(&profile).unwrap(), None);
+}
@@ -12,12 +12,15 @@ fn setup() {
 mod tests {
     use shinkai_message_primitives::{
         schemas::{
-            llm_providers::serialized_llm_provider::{LLMProviderInterface, OpenAI, SerializedLLMProvider},
+            llm_providers::serialized_llm_provider::{LLMProviderInterface, Ollama, OpenAI, SerializedLLMProvider},
             shinkai_name::ShinkaiName,
         },
         shinkai_utils::shinkai_logging::init_default_tracing,
     };
     use shinkai_node::llm_provider::{execution::prompts::prompts::JobPromptGenerator, llm_provider::LLMProvider};
+    use shinkai_node::managers::{
+        model_capabilities_manager::ModelCapabilitiesManager, model_capabilities_prober::ProbedModelCapabilities,
+    };
     use shinkai_vector_resources::utils::hash_string;
 
     use super::*;
@@ -232,6 +235,94 @@ mod tests {
         assert_eq!(vec!["toolkit2"], toolkits);
     }
 
+    #[test]
+    fn test_remove_agent_forgets_probed_capabilities() {
+        init_default_tracing();
+        setup();
+        let db_path = format!("db_tests/{}", hash_string("agent_test"));
+        let db = ShinkaiDB::new(&db_path).unwrap();
+        let model = LLMProviderInterface::Ollama(Ollama {
+            model_type: "unknown-model:7b".to_string(),
+        });
+        let identity = ShinkaiName::new("@@alice.shinkai/profileName/agent/myOllamaAgent".to_string()).unwrap();
+        let profile = identity.extract_profile().unwrap();
+
+        let test_agent = SerializedLLMProvider {
+            id: "test_agent".to_string(),
+            full_identity_name: identity.clone(),
+            perform_locally: false,
+            external_url: Some("http://localhost:11434".to_string()),
+            api_key: None,
+            model: model.clone(),
+            toolkit_permissions: vec![],
+            storage_bucket_permissions: vec![],
+            allowed_message_senders: vec![],
+        };
+        let other_agent = SerializedLLMProvider {
+            id: "other_agent".to_string(),
+            ..test_agent.clone()
+        };
+        let remote_agent = SerializedLLMProvider {
+            id: "remote_agent".to_string(),
+            external_url: Some("http://remote-host:11434".to_string()),
+            ..test_agent.clone()
+        };
+        db.add_llm_provider(test_agent.clone(), &profile).unwrap();
+        db.add_llm_provider(other_agent.clone(), &profile).unwrap();
+        db.add_llm_provider(remote_agent.clone(), &profile).unwrap();
+
+        // Without a probe the static table is used
+        let static_max_input_tokens = ModelCapabilitiesManager::get_max_input_tokens(&model);
+        assert_eq!(
+            ModelCapabilitiesManager::get_max_input_tokens_with_probe(&db, &test_agent),
+            static_max_input_tokens
+        );
+
+        let probed = ProbedModelCapabilities {
+            vision: false,
+            context_length: Some(32_000),
+            json_mode: true,
+            tool_calling: false,
+            probed_at: "2024-01-01T00:00:00Z".to_string(),
+        };
+        db.set_probed_model_capabilities(&model, test_agent.external_url.as_deref(), &probed)
+            .unwrap();
+        assert_eq!(
+            ModelCapabilitiesManager::get_max_input_tokens_with_probe(&db, &test_agent),
+            32_000 - ModelCapabilitiesManager::get_max_output_tokens(&model) / 2
+        );
+
+        // The same model served by another host isn't covered by the probe
+        assert_eq!(
+            ModelCapabilitiesManager::get_max_input_tokens_with_probe(&db, &remote_agent),
+            static_max_input_tokens
+        );
+
+        // The probe is kept while another provider still uses the model on the same host
+        db.remove_llm_provider(&test_agent.id, &profile).unwrap();
+        assert_eq!(
+            db.get_probed_model_capabilities(&model, test_agent.external_url.as_deref())
+                .unwrap(),
+            probed
+        );
+
+        db.remove_llm_provider(&other_agent.id, &profile).unwrap();
+        assert!(matches!(
+            db.get_probed_model_capabilities(&model, test_agent.external_url.as_deref()),
+            Err(ShinkaiDBError::DataNotFound)
+        ));
+        assert_eq!(
+            ModelCapabilitiesManager::get_max_input_tokens_with_probe(&db, &test_agent),
+            static_max_input_tokens
+        );
+
+        // Removing an unknown provider fails without touching anything
+        assert!(matches!(
+            db.remove_llm_provider(&test_agent.id, &profile),
+            Err(ShinkaiDBError::DataNotFound)
+        ));
+    }
+
     #[tokio::test]
     async fn test_agent_call_external_api_openai() {
         init_default_tracing();
@@ -8,7 +8,8 @@ use shinkai_message_primitives::shinkai_utils::shinkai_message_builder::ShinkaiM
 use shinkai_message_primitives::shinkai_utils::signatures::{
     clone_signature_secret_key, unsafe_deterministic_signature_keypair,
 };
-use shinkai_node::db::ShinkaiDB;
+use shinkai_node::db::{ShinkaiDB, Topic};
+use shinkai_node::vector_fs::db::fs_db::{FSTopic, VectorFSDB};
 use shinkai_vector_resources::utils::hash_string;
 use std::fs;
 use std::path::Path;
@@ -234,3 +235,60 @@ fn test_schedule_and_get_due_scheduled_messages() {
         .unwrap();
     assert_eq!(due_messages.len(), 0);
 }
+
+#[test]
+fn test_in_memory_dbs_persist_nothing() {
+    setup();
+    let db_path = format!("db_tests/{}", hash_string("in_memory"));
+    let vector_fs_db_path = format!("db_tests/vector_fs{}", hash_string("in_memory"));
+
+    let shinkai_db = ShinkaiDB::new_in_memory(&db_path).unwrap();
+    let cf = shinkai_db.db.cf_handle(Topic::NodeAndUsers.as_str()).unwrap();
+    shinkai_db.db.put_cf(cf, b"in_memory_key", b"value").unwrap();
+    assert_eq!(
+        shinkai_db.db.get_cf(cf, b"in_memory_key").unwrap(),
+        Some(b"value".to_vec())
+    );
+
+    let vector_fs_db = VectorFSDB::new_in_memory(&vector_fs_db_path).unwrap();
+    let cf = vector_fs_db.db.cf_handle(FSTopic::FileSystem.as_str()).unwrap();
+    vector_fs_db.db.put_cf(cf, b"in_memory_key", b"value").unwrap();
+    assert_eq!(
+        vector_fs_db.db.get_cf(cf, b"in_memory_key").unwrap(),
+        Some(b"value".to_vec())
+    );
+
+    assert!(!Path::new(&db_path).exists());
+    assert!(!Path::new(&vector_fs_db_path).exists());
+
+    // Reopening starts from an empty database
+    drop(shinkai_db);
+    let shinkai_db = ShinkaiDB::new_in_memory(&db_path).unwrap();
+    let cf = shinkai_db.db.cf_handle(Topic::NodeAndUsers.as_str()).unwrap();
+    assert_eq!(shinkai_db.db.get_cf(cf, b"in_memory_key").unwrap(), None);
+}
+
+#[test]
+fn test_shared_folder_writers_are_kept_per_profile() {
+    setup();
+    let shinkai_db = ShinkaiDB::new_in_memory(&format!("db_tests/{}", hash_string("shared_folder_writers"))).unwrap();
+
+    let writers = vec!["@@bob.shinkai".to_string()];
+    shinkai_db
+        .set_shared_folder_writers("main", "/shared", &writers)
+        .unwrap();
+    assert_eq!(
+        shinkai_db.get_shared_folder_writers("main", "/shared").unwrap(),
+        writers
+    );
+    assert!(shinkai_db
+        .get_shared_folder_writers("other", "/shared")
+        .unwrap()
+        .is_empty());
+
+    shinkai_db.set_shared_folder_writers("main", "/shared", &[]).unwrap();
+    assert!(shinkai_db
+        .get_shared_folder_writers("main", "/shared")
+        .unwrap()
+        .is_empty());
+}
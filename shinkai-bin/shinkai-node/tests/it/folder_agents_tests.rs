@@ -0,0 +1,166 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::{
+    LLMProviderInterface, OpenAI, SerializedLLMProvider,
+};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
+    APISetFolderAgent, FolderAgentBinding, IdentityPermissions,
+};
+use shinkai_message_primitives::shinkai_utils::encryption::unsafe_deterministic_encryption_keypair;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::init_default_tracing;
+use shinkai_message_primitives::shinkai_utils::signatures::unsafe_deterministic_signature_keypair;
+use shinkai_node::db::ShinkaiDB;
+use shinkai_node::managers::IdentityManager;
+use shinkai_node::network::error_code::ErrorCode;
+use shinkai_node::network::Node;
+use shinkai_node::schemas::identity::{StandardIdentity, StandardIdentityType};
+use shinkai_node::vector_fs::vector_fs::VectorFS;
+use shinkai_vector_resources::embedding_generator::RemoteEmbeddingGenerator;
+use shinkai_vector_resources::model_type::{EmbeddingModelType, OllamaTextEmbeddingsInference};
+use shinkai_vector_resources::utils::hash_string;
+use shinkai_vector_resources::vector_resource::VRPath;
+use tokio::sync::Mutex;
+
+const API_KEY: &str = "folder_agents_tests_key";
+
+fn setup() {
+    let _ = fs::remove_dir_all(Path::new("db_tests/"));
+    env::set_var("API_V2_KEY", API_KEY);
+}
+
+fn main_profile() -> ShinkaiName {
+    ShinkaiName::new("@@localhost.shinkai/main".to_string()).unwrap()
+}
+
+/// A node with a main profile, an agent and a `/reports` folder
+async fn setup_node(name: &str) -> (Arc<ShinkaiDB>, Arc<VectorFS>, Arc<Mutex<IdentityManager>>) {
+    let db = Arc::new(ShinkaiDB::new(&format!("db_tests/{}", hash_string(name))).unwrap());
+    let (_, identity_pk) = unsafe_deterministic_signature_keypair(0);
+    let (_, encryption_pk) = unsafe_deterministic_encryption_keypair(0);
+    db.insert_profile(StandardIdentity::new(
+        main_profile(),
+        None,
+        encryption_pk,
+        identity_pk,
+        Some(encryption_pk),
+        Some(identity_pk),
+        StandardIdentityType::Profile,
+        IdentityPermissions::Admin,
+    ))
+    .unwrap();
+    let agent = SerializedLLMProvider {
+        id: "summarizer".to_string(),
+        full_identity_name: ShinkaiName::new("@@localhost.shinkai/main/agent/summarizer".to_string()).unwrap(),
+        perform_locally: false,
+        external_url: Some("https://api.openai.com".to_string()),
+        api_key: None,
+        model: LLMProviderInterface::OpenAI(OpenAI {
+            model_type: "gpt-4o-mini".to_string(),
+        }),
+        toolkit_permissions: vec![],
+        storage_bucket_permissions: vec![],
+        allowed_message_senders: vec![],
+    };
+    db.add_llm_provider(agent, &main_profile()).unwrap();
+    let identity_manager = IdentityManager::new(Arc::downgrade(&db), main_profile().extract_node())
+        .await
+        .unwrap();
+
+    let vector_fs = VectorFS::new(
+        RemoteEmbeddingGenerator::new_default(),
+        vec![EmbeddingModelType::OllamaTextEmbeddingsInference(
+            OllamaTextEmbeddingsInference::SnowflakeArcticEmbed_M,
+        )],
+        vec![main_profile()],
+        &format!("db_tests/{}", hash_string(&format!("{}_vector_fs", name))),
+        main_profile().extract_node(),
+    )
+    .await
+    .unwrap();
+    let writer = vector_fs
+        .new_writer(main_profile(), VRPath::root(), main_profile())
+        .await
+        .unwrap();
+    vector_fs
+        .create_new_folder(&writer, "reports".to_string())
+        .await
+        .unwrap();
+
+    (db, Arc::new(vector_fs), Arc::new(Mutex::new(identity_manager)))
+}
+
+async fn set_folder_agent(
+    db: &Arc<ShinkaiDB>,
+    vector_fs: &Arc<VectorFS>,
+    identity_manager: &Arc<Mutex<IdentityManager>>,
+    path: &str,
+    agent: Option<&str>,
+) -> Result<String, ErrorCode> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    let payload = APISetFolderAgent {
+        path: path.to_string(),
+        agent: agent.map(String::from),
+    };
+    Node::v2_api_set_folder_agent(
+        db.clone(),
+        identity_manager.clone(),
+        vector_fs.clone(),
+        API_KEY.to_string(),
+        payload,
+        res_sender,
+    )
+    .await
+    .unwrap();
+    res_receiver.recv().await.unwrap().map_err(|error| error.error_code)
+}
+
+async fn list_folder_agents(db: &Arc<ShinkaiDB>) -> Vec<FolderAgentBinding> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    Node::v2_api_list_folder_agents(db.clone(), API_KEY.to_string(), res_sender)
+        .await
+        .unwrap();
+    res_receiver.recv().await.unwrap().unwrap()
+}
+
+#[tokio::test]
+async fn test_set_and_remove_folder_agent() {
+    init_default_tracing();
+    setup();
+    let (db, vector_fs, identity_manager) = setup_node("set_and_remove_folder_agent").await;
+
+    set_folder_agent(&db, &vector_fs, &identity_manager, "/reports", Some("summarizer"))
+        .await
+        .unwrap();
+    assert_eq!(
+        list_folder_agents(&db).await,
+        vec![FolderAgentBinding {
+            path: "/reports".to_string(),
+            agent: "summarizer".to_string(),
+        }]
+    );
+    assert_eq!(db.get_folder_agent("/reports").unwrap(), Some("summarizer".to_string()));
+
+    set_folder_agent(&db, &vector_fs, &identity_manager, "/reports", None)
+        .await
+        .unwrap();
+    assert!(list_folder_agents(&db).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_set_folder_agent_rejects_unknown_agent_and_folder() {
+    init_default_tracing();
+    setup();
+    let (db, vector_fs, identity_manager) = setup_node("set_folder_agent_rejects").await;
+
+    let result = set_folder_agent(&db, &vector_fs, &identity_manager, "/reports", Some("unknown")).await;
+    assert_eq!(result, Err(ErrorCode::LlmProviderNotFound));
+
+    let result = set_folder_agent(&db, &vector_fs, &identity_manager, "/missing", Some("summarizer")).await;
+    assert_eq!(result, Err(ErrorCode::VecfsPathNotFound));
+
+    assert!(list_folder_agents(&db).await.is_empty());
+}
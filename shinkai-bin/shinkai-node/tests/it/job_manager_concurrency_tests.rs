@@ -221,6 +221,7 @@ async fn test_process_job_queue_concurrency() {
                 workflow_name: None,
                 sheet_job_data: None,
                 callback: None,
+                retrieval: None,
             },
             ShinkaiName::new("@@node1.shinkai/main".to_string()).unwrap(),
         );
@@ -371,6 +372,7 @@ async fn test_sequential_process_for_same_job_id() {
                 workflow_name: None,
                 sheet_job_data: None,
                 callback: None,
+                retrieval: None,
             },
             ShinkaiName::new("@@node1.shinkai/main".to_string()).unwrap(),
         );
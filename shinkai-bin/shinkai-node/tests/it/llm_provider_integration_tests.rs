@@ -464,6 +464,7 @@ fn node_llm_provider_registration() {
                     workflow_name: None,
                     sheet_job_data: None,
                     callback: None,
+                    retrieval: None,
                 };
                 let body = serde_json::to_string(&job_message)
                     .map_err(|_| "Failed to serialize job message to JSON")
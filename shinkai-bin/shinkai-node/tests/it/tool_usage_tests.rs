@@ -0,0 +1,63 @@
+use std::fs;
+use std::path::Path;
+
+use rust_decimal::Decimal;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::init_default_tracing;
+use shinkai_node::db::ShinkaiDB;
+use shinkai_node::tools::error::ToolError;
+use shinkai_node::tools::rust_tools::RustTool;
+use shinkai_node::tools::shinkai_tool::ShinkaiTool;
+use shinkai_node::tools::tool_pricing::{ToolPricing, ToolUsageAllowance, ToolUsageMeter};
+use shinkai_vector_resources::utils::hash_string;
+
+fn setup() {
+    let _ = fs::remove_dir_all(Path::new("db_tests/"));
+}
+
+#[test]
+fn test_authorized_invocations_reserve_the_allowance() {
+    init_default_tracing();
+    setup();
+    let db = ShinkaiDB::new(&format!("db_tests/{}", hash_string("tool_usage_reservations"))).unwrap();
+    let consumer = "@@node1.shinkai/main";
+    let tool = ShinkaiTool::Rust(
+        RustTool::new("priced_tool".to_string(), "A priced tool".to_string(), vec![], None),
+        true,
+    );
+    db.set_toolkit_pricing(
+        &tool.toolkit_type_name(),
+        &ToolPricing {
+            price_per_invocation: Decimal::new(1, 0),
+            asset: "USDC".to_string(),
+            pay_to: None,
+        },
+    )
+    .unwrap();
+    db.set_tool_usage_allowance(&ToolUsageAllowance {
+        consumer: consumer.to_string(),
+        asset: "USDC".to_string(),
+        allowance: Decimal::new(1, 0),
+    })
+    .unwrap();
+
+    // A running invocation holds the allowance, so a concurrent one can't spend it too
+    let reservation = ToolUsageMeter::authorize(&db, consumer, &tool).unwrap().unwrap();
+    assert!(matches!(
+        ToolUsageMeter::authorize(&db, consumer, &tool),
+        Err(ToolError::AllowanceExhausted(_))
+    ));
+
+    // A failed invocation releases it
+    drop(reservation);
+    let reservation = ToolUsageMeter::authorize(&db, consumer, &tool).unwrap().unwrap();
+
+    // A recorded one keeps it spent
+    ToolUsageMeter::record(&db, &tool, reservation).unwrap();
+    let summary = ToolUsageMeter::summary(&db, consumer, "USDC").unwrap();
+    assert_eq!(summary.unbilled_invocations, 1);
+    assert_eq!(summary.unbilled, Decimal::new(1, 0));
+    assert!(matches!(
+        ToolUsageMeter::authorize(&db, consumer, &tool),
+        Err(ToolError::AllowanceExhausted(_))
+    ));
+}
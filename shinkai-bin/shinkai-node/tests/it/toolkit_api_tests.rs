@@ -0,0 +1,115 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::APIInstallToolkitFromURL;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::init_default_tracing;
+use shinkai_node::db::ShinkaiDB;
+use shinkai_node::lance_db::shinkai_lance_db::LanceShinkaiDb;
+use shinkai_node::network::error_code::ErrorCode;
+use shinkai_node::network::Node;
+use shinkai_node::tools::toolkit_package::ToolkitProvenance;
+use shinkai_vector_resources::embedding_generator::{EmbeddingGenerator, RemoteEmbeddingGenerator};
+use shinkai_vector_resources::utils::hash_string;
+use tokio::sync::Mutex;
+
+const API_KEY: &str = "toolkit_api_tests_key";
+
+fn setup() {
+    let _ = fs::remove_dir_all(Path::new("db_tests/"));
+    let _ = fs::remove_dir_all(Path::new("lance_db_tests/"));
+    env::set_var("API_V2_KEY", API_KEY);
+}
+
+async fn setup_dbs(name: &str) -> (Arc<ShinkaiDB>, Arc<Mutex<LanceShinkaiDb>>) {
+    let db = ShinkaiDB::new(&format!("db_tests/{}", hash_string(name))).unwrap();
+    let generator = RemoteEmbeddingGenerator::new_default();
+    let lance_db = LanceShinkaiDb::new(
+        &format!("lance_db_tests/{}", hash_string(name)),
+        generator.model_type(),
+        generator.clone(),
+    )
+    .await
+    .unwrap();
+    (Arc::new(db), Arc::new(Mutex::new(lance_db)))
+}
+
+fn weather_provenance() -> ToolkitProvenance {
+    ToolkitProvenance {
+        toolkit_name: "weather".to_string(),
+        version: "1.0.0".to_string(),
+        author: "@@alice.shinkai".to_string(),
+        source_url: "https://example.com/weather.json".to_string(),
+        publisher_public_key: "00".repeat(32),
+        package_hash: "11".repeat(32),
+        permissions: Default::default(),
+        tool_router_keys: vec!["local:::weather:::get_weather".to_string()],
+        installed_at: "2024-01-01T00:00:00Z".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_list_and_remove_toolkits() {
+    init_default_tracing();
+    setup();
+    let (db, lance_db) = setup_dbs("toolkit_api_list_and_remove").await;
+    db.set_toolkit_provenance(&weather_provenance()).unwrap();
+
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    Node::v2_api_list_toolkits(db.clone(), API_KEY.to_string(), res_sender)
+        .await
+        .unwrap();
+    let toolkits = res_receiver.recv().await.unwrap().unwrap();
+    assert_eq!(toolkits, serde_json::json!([weather_provenance()]));
+
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    Node::v2_api_remove_toolkit(
+        db.clone(),
+        lance_db.clone(),
+        API_KEY.to_string(),
+        "weather".to_string(),
+        res_sender,
+    )
+    .await
+    .unwrap();
+    assert!(res_receiver.recv().await.unwrap().is_ok());
+    assert!(db.get_toolkit_provenance("weather").is_err());
+
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    Node::v2_api_list_toolkits(db.clone(), API_KEY.to_string(), res_sender)
+        .await
+        .unwrap();
+    let toolkits = res_receiver.recv().await.unwrap().unwrap();
+    assert_eq!(toolkits, serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_toolkit_endpoints_reject_bad_requests() {
+    init_default_tracing();
+    setup();
+    let (db, lance_db) = setup_dbs("toolkit_api_bad_requests").await;
+
+    // A wrong bearer token is rejected before anything is read
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    Node::v2_api_list_toolkits(db.clone(), "wrong_key".to_string(), res_sender)
+        .await
+        .unwrap();
+    let error = res_receiver.recv().await.unwrap().unwrap_err();
+    assert_eq!(error.error_code, ErrorCode::Unauthorized);
+
+    // Only HTTP(S) packages can be installed
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    let payload = APIInstallToolkitFromURL {
+        url: "ftp://example.com/weather.json".to_string(),
+        expected_public_key: None,
+        accepted_network_hosts: vec![],
+        accepted_vector_fs_read: vec![],
+    };
+    Node::v2_api_install_toolkit_from_url(db.clone(), lance_db.clone(), API_KEY.to_string(), payload, res_sender)
+        .await
+        .unwrap();
+    let error = res_receiver.recv().await.unwrap().unwrap_err();
+    assert_eq!(error.error_code, ErrorCode::BadRequest);
+    assert!(db.get_all_toolkit_provenances().unwrap().is_empty());
+}
@@ -36,6 +36,8 @@ pub async fn api_registration_device_node_profile_main(
             .send(NodeCommand::LocalCreateRegistrationCode {
                 permissions: IdentityPermissions::Admin,
                 code_type: RegistrationCodeType::Device("main".to_string()),
+                ttl_secs: None,
+                max_uses: None,
                 res: res_registration_sender,
             })
             .await
@@ -244,6 +246,8 @@ pub async fn api_try_re_register_profile_node(
         .send(NodeCommand::LocalCreateRegistrationCode {
             permissions: IdentityPermissions::Admin,
             code_type: RegistrationCodeType::Profile,
+            ttl_secs: None,
+            max_uses: None,
             res: res1_registration_sender,
         })
         .await
@@ -278,6 +282,7 @@ pub async fn api_try_re_register_profile_node(
         Err(e) => match e {
             APIError {
                 code: 400,
+                error_code: _,
                 error: _,
                 message,
             } if message == "Failed to add device subidentity: Profile name already exists" => (),
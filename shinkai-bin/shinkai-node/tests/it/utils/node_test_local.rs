@@ -26,6 +26,8 @@ pub async fn local_registration_profile_node(
             .send(NodeCommand::LocalCreateRegistrationCode {
                 permissions: IdentityPermissions::Admin,
                 code_type: RegistrationCodeType::Profile,
+                ttl_secs: None,
+                max_uses: None,
                 res: res_registration_sender,
             })
             .await
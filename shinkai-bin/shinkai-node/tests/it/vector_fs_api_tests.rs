@@ -290,6 +290,7 @@ fn vector_fs_api_tests() {
                     path: "/test_folder".to_string(),
                     file_inbox: hash_of_aes_encryption_key_hex(symmetrical_sk),
                     file_datetime: Some(Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap()),
+                    allow_duplicates: false,
                 };
 
                 let msg = generate_message_with_payload(
@@ -396,6 +397,7 @@ fn vector_fs_api_tests() {
                     path: "/test_folder".to_string(),
                     file_inbox: hash_of_aes_encryption_key_hex(symmetrical_sk),
                     file_datetime: Some(Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap()),
+                    allow_duplicates: false,
                 };
 
                 let msg = generate_message_with_payload(
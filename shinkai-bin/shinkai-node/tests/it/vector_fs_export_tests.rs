@@ -0,0 +1,135 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
+    APIVecFSExportFolderAsVRPack, IdentityPermissions,
+};
+use shinkai_message_primitives::shinkai_utils::encryption::unsafe_deterministic_encryption_keypair;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::init_default_tracing;
+use shinkai_message_primitives::shinkai_utils::signatures::unsafe_deterministic_signature_keypair;
+use shinkai_node::db::ShinkaiDB;
+use shinkai_node::managers::IdentityManager;
+use shinkai_node::network::error_code::ErrorCode;
+use shinkai_node::network::Node;
+use shinkai_node::schemas::identity::{StandardIdentity, StandardIdentityType};
+use shinkai_node::vector_fs::vector_fs::VectorFS;
+use shinkai_vector_resources::embedding_generator::RemoteEmbeddingGenerator;
+use shinkai_vector_resources::model_type::{EmbeddingModelType, OllamaTextEmbeddingsInference};
+use shinkai_vector_resources::utils::hash_string;
+use shinkai_vector_resources::vector_resource::{VRPack, VRPath};
+use tokio::sync::Mutex;
+
+const API_KEY: &str = "vector_fs_export_tests_key";
+
+fn setup() {
+    let _ = fs::remove_dir_all(Path::new("db_tests/"));
+    env::set_var("API_V2_KEY", API_KEY);
+}
+
+fn main_profile() -> ShinkaiName {
+    ShinkaiName::new("@@localhost.shinkai/main".to_string()).unwrap()
+}
+
+/// A node with a main profile and an empty VectorFS
+async fn setup_node(name: &str) -> (Arc<ShinkaiDB>, Arc<VectorFS>, Arc<Mutex<IdentityManager>>) {
+    let db = Arc::new(ShinkaiDB::new(&format!("db_tests/{}", hash_string(name))).unwrap());
+    let (_, identity_pk) = unsafe_deterministic_signature_keypair(0);
+    let (_, encryption_pk) = unsafe_deterministic_encryption_keypair(0);
+    db.insert_profile(StandardIdentity::new(
+        main_profile(),
+        None,
+        encryption_pk,
+        identity_pk,
+        Some(encryption_pk),
+        Some(identity_pk),
+        StandardIdentityType::Profile,
+        IdentityPermissions::Admin,
+    ))
+    .unwrap();
+    let identity_manager = IdentityManager::new(Arc::downgrade(&db), main_profile().extract_node())
+        .await
+        .unwrap();
+
+    let vector_fs = VectorFS::new(
+        RemoteEmbeddingGenerator::new_default(),
+        vec![EmbeddingModelType::OllamaTextEmbeddingsInference(
+            OllamaTextEmbeddingsInference::SnowflakeArcticEmbed_M,
+        )],
+        vec![main_profile()],
+        &format!("db_tests/{}", hash_string(&format!("{}_vector_fs", name))),
+        main_profile().extract_node(),
+    )
+    .await
+    .unwrap();
+
+    (db, Arc::new(vector_fs), Arc::new(Mutex::new(identity_manager)))
+}
+
+#[tokio::test]
+async fn test_export_folder_as_vrpack() {
+    init_default_tracing();
+    setup();
+    let (db, vector_fs, identity_manager) = setup_node("export_folder_as_vrpack").await;
+    let writer = vector_fs
+        .new_writer(main_profile(), VRPath::root(), main_profile())
+        .await
+        .unwrap();
+    vector_fs
+        .create_new_folder(&writer, "reports".to_string())
+        .await
+        .unwrap();
+
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    let payload = APIVecFSExportFolderAsVRPack {
+        path: "/reports".to_string(),
+    };
+    Node::v2_export_folder_as_vrpack(
+        db.clone(),
+        vector_fs.clone(),
+        identity_manager.clone(),
+        payload,
+        API_KEY.to_string(),
+        res_sender,
+    )
+    .await
+    .unwrap();
+    let (file_name, bytes) = res_receiver.recv().await.unwrap().unwrap();
+
+    assert_eq!(file_name, "reports.vrpack");
+    let vrpack = VRPack::from_bytes(&bytes).unwrap();
+    assert_eq!(vrpack.name, "reports");
+    assert_eq!(vrpack.metadata_get("exported_from_path"), Some(&"/reports".to_string()));
+    assert_eq!(
+        vrpack.metadata_get("exported_from_node"),
+        Some(&"@@localhost.shinkai".to_string())
+    );
+    assert!(vrpack.metadata_get("exported_at").is_some());
+}
+
+#[tokio::test]
+async fn test_export_missing_folder_as_vrpack() {
+    init_default_tracing();
+    setup();
+    let (db, vector_fs, identity_manager) = setup_node("export_missing_folder_as_vrpack").await;
+
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    let payload = APIVecFSExportFolderAsVRPack {
+        path: "/missing".to_string(),
+    };
+    Node::v2_export_folder_as_vrpack(
+        db.clone(),
+        vector_fs.clone(),
+        identity_manager.clone(),
+        payload,
+        API_KEY.to_string(),
+        res_sender,
+    )
+    .await
+    .unwrap();
+    let error = res_receiver.recv().await.unwrap().unwrap_err();
+
+    assert_eq!(error.error_code, ErrorCode::VecfsPathNotFound);
+}
@@ -19,20 +19,25 @@ use shinkai_message_primitives::shinkai_utils::file_encryption::{
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::init_default_tracing;
 use shinkai_message_primitives::shinkai_utils::shinkai_message_builder::ShinkaiMessageBuilder;
 use shinkai_message_primitives::shinkai_utils::signatures::clone_signature_secret_key;
+use shinkai_node::db::db_profile_bound::ProfileBoundWriteBatch;
 use shinkai_node::llm_provider::execution::user_message_parser::ParsedUserMessage;
+use shinkai_node::llm_provider::parsing_helper::IngestionSettings;
 use shinkai_node::network::node_commands::NodeCommand;
 use shinkai_node::vector_fs::vector_fs::VectorFS;
+use shinkai_node::vector_fs::vector_fs_integrity::IntegrityProblem;
 use shinkai_node::vector_fs::vector_fs_permissions::{ReadPermission, WritePermission};
+use shinkai_node::vector_fs::vector_fs_types::FSItem;
 use shinkai_vector_resources::data_tags::DataTag;
 use shinkai_vector_resources::embedding_generator::{EmbeddingGenerator, RemoteEmbeddingGenerator};
 use shinkai_vector_resources::file_parser::file_parser::{FileParser, ShinkaiFileParser};
+use shinkai_vector_resources::file_parser::file_parser_types::TextGroup;
 use shinkai_vector_resources::file_parser::unstructured_api::UnstructuredAPI;
 use shinkai_vector_resources::model_type::{EmbeddingModelType, OllamaTextEmbeddingsInference};
 use shinkai_vector_resources::resource_errors::VRError;
 use shinkai_vector_resources::source::{DistributionInfo, SourceFile, SourceFileMap, SourceFileType};
 use shinkai_vector_resources::vector_resource::{simplified_fs_types::*, VRPack};
 use shinkai_vector_resources::vector_resource::{
-    BaseVectorResource, DocumentVectorResource, VRKai, VRPath, VRSourceReference, VectorResourceCore,
+    BaseVectorResource, DocumentVectorResource, NodeContent, VRKai, VRPath, VRSourceReference, VectorResourceCore,
     VectorResourceSearch,
 };
 use std::collections::HashMap;
@@ -1269,6 +1274,610 @@ async fn test_folder_empty_check_reuse() {
     );
 }
 
+#[tokio::test]
+async fn test_save_vrkais_in_folder() {
+    setup();
+    let generator = RemoteEmbeddingGenerator::new_default();
+    let vector_fs = setup_default_vector_fs().await;
+
+    let folder_path = VRPath::root().push_cloned("imports".to_string());
+    let writer = vector_fs
+        .new_writer(default_test_profile(), VRPath::root(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.create_new_folder(&writer, "imports").await.unwrap();
+    let writer = vector_fs
+        .new_writer(default_test_profile(), folder_path.clone(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.create_new_folder(&writer, "existing_folder").await.unwrap();
+
+    // The second resource is named like a folder, so it fails without stopping the others
+    let (doc_resource, source_file_map) = get_shinkai_intro_doc_async(&generator, &vec![]).await.unwrap();
+    let vrkais: Vec<VRKai> = ["first_doc", "existing_folder", "second_doc"]
+        .iter()
+        .map(|name| {
+            let mut resource = BaseVectorResource::Document(doc_resource.clone());
+            resource.as_trait_object_mut().set_name(name.to_string());
+            resource.as_trait_object_mut().generate_and_update_resource_id();
+            VRKai::new(resource, Some(source_file_map.clone()))
+        })
+        .collect();
+    let results = vector_fs.save_vrkais_in_folder(&writer, vrkais).await.unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+    for name in ["first_doc", "second_doc"] {
+        let reader = vector_fs
+            .new_reader(
+                default_test_profile(),
+                folder_path.push_cloned(name.to_string()),
+                default_test_profile(),
+            )
+            .await
+            .unwrap();
+        let resource = vector_fs.retrieve_vector_resource(&reader).await.unwrap();
+        assert_eq!(resource.as_trait_object().name(), name);
+    }
+}
+
+#[tokio::test]
+async fn test_get_folder_stats() {
+    setup();
+    let generator = RemoteEmbeddingGenerator::new_default();
+    let vector_fs = setup_default_vector_fs().await;
+
+    let writer = vector_fs
+        .new_writer(default_test_profile(), VRPath::root(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.create_new_folder(&writer, "projects").await.unwrap();
+    let folder_path = VRPath::root().push_cloned("projects".to_string());
+    let writer = vector_fs
+        .new_writer(default_test_profile(), folder_path.clone(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.create_new_folder(&writer, "empty").await.unwrap();
+
+    let (doc_resource, source_file_map) = get_shinkai_intro_doc_async(&generator, &vec![]).await.unwrap();
+    let resource = BaseVectorResource::Document(doc_resource);
+    let (chunk_count, embedded_tokens) = FSItem::count_chunks_and_tokens(&resource);
+    vector_fs
+        .save_vector_resource_in_folder(&writer, resource, Some(source_file_map))
+        .await
+        .unwrap();
+
+    let reader = vector_fs
+        .new_reader(default_test_profile(), folder_path, default_test_profile())
+        .await
+        .unwrap();
+    let stats = vector_fs.get_folder_stats(&reader).await.unwrap();
+    assert_eq!(stats.folder_count, 1);
+    assert_eq!(stats.item_count, 1);
+    assert!(chunk_count > 0);
+    assert_eq!(stats.chunk_count, chunk_count as u64);
+    assert_eq!(stats.embedded_tokens, embedded_tokens as u64);
+    assert!(stats.vector_resource_bytes > 0);
+    assert!(stats.last_modified_datetime.is_some());
+
+    // The root counts the folder holding everything
+    let reader = vector_fs
+        .new_reader(default_test_profile(), VRPath::root(), default_test_profile())
+        .await
+        .unwrap();
+    let root_stats = vector_fs.get_folder_stats(&reader).await.unwrap();
+    assert_eq!(root_stats.folder_count, 2);
+    assert_eq!(root_stats.chunk_count, stats.chunk_count);
+}
+
+#[tokio::test]
+async fn test_item_summary() {
+    setup();
+    let generator = RemoteEmbeddingGenerator::new_default();
+    let vector_fs = setup_default_vector_fs().await;
+
+    let writer = vector_fs
+        .new_writer(default_test_profile(), VRPath::root(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.create_new_folder(&writer, "papers").await.unwrap();
+    let folder_path = VRPath::root().push_cloned("papers".to_string());
+    let writer = vector_fs
+        .new_writer(default_test_profile(), folder_path.clone(), default_test_profile())
+        .await
+        .unwrap();
+
+    let (doc_resource, source_file_map) = get_shinkai_intro_doc_async(&generator, &vec![]).await.unwrap();
+    let resource = BaseVectorResource::Document(doc_resource);
+    let mut vrkai = VRKai::new(resource.clone(), Some(source_file_map));
+    vrkai.metadata.insert(
+        FSItem::vr_summary_metadata_key(),
+        "An introduction to Shinkai".to_string(),
+    );
+    let item = vector_fs.save_vrkai_in_folder(&writer, vrkai).await.unwrap();
+    assert_eq!(item.summary.as_deref(), Some("An introduction to Shinkai"));
+
+    // Saving the same content again keeps the summary, and folder listings show it
+    vector_fs
+        .save_vector_resource_in_folder(&writer, resource, None)
+        .await
+        .unwrap();
+    let reader = vector_fs
+        .new_reader(default_test_profile(), folder_path, default_test_profile())
+        .await
+        .unwrap();
+    let listing = vector_fs.retrieve_fs_path_simplified_json_value(&reader).await.unwrap();
+    assert_eq!(
+        listing["child_items"][0]["summary"],
+        serde_json::json!("An introduction to Shinkai")
+    );
+}
+
+#[tokio::test]
+async fn test_find_possible_duplicate() {
+    setup();
+    let generator = RemoteEmbeddingGenerator::new_default();
+    let vector_fs = setup_default_vector_fs().await;
+
+    let writer = vector_fs
+        .new_writer(default_test_profile(), VRPath::root(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.create_new_folder(&writer, "uploads").await.unwrap();
+    let folder_path = VRPath::root().push_cloned("uploads".to_string());
+    let writer = vector_fs
+        .new_writer(default_test_profile(), folder_path.clone(), default_test_profile())
+        .await
+        .unwrap();
+
+    let (doc_resource, source_file_map) = get_shinkai_intro_doc_async(&generator, &vec![]).await.unwrap();
+    let resource = BaseVectorResource::Document(doc_resource);
+    let item = vector_fs
+        .save_vrkai_in_folder(&writer, VRKai::new(resource.clone(), Some(source_file_map.clone())))
+        .await
+        .unwrap();
+
+    let reader = vector_fs
+        .new_reader(default_test_profile(), folder_path, default_test_profile())
+        .await
+        .unwrap();
+    // Saving it under its own name overwrites the item, which isn't a duplicate
+    let same_name = VRKai::new(resource.clone(), Some(source_file_map.clone()));
+    assert_eq!(
+        vector_fs
+            .find_possible_duplicate(&reader, "intro.pdf", &same_name)
+            .await
+            .unwrap(),
+        None
+    );
+
+    let mut renamed = resource.clone();
+    renamed.as_trait_object_mut().set_name("intro copy".to_string());
+    let duplicate = vector_fs
+        .find_possible_duplicate(
+            &reader,
+            "intro copy.pdf",
+            &VRKai::new(renamed.clone(), Some(source_file_map)),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(duplicate.same_content);
+    assert_eq!(duplicate.duplicate_of, item.path.format_to_string());
+
+    // Without its source file, the identical embeddings give it away
+    let duplicate = vector_fs
+        .find_possible_duplicate(&reader, "intro copy.pdf", &VRKai::new(renamed, None))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!duplicate.same_content);
+    assert!(duplicate.similarity > 0.99);
+}
+
+#[tokio::test]
+async fn test_verify_integrity() {
+    setup();
+    let generator = RemoteEmbeddingGenerator::new_default();
+    let vector_fs = setup_default_vector_fs().await;
+
+    let writer = vector_fs
+        .new_writer(default_test_profile(), VRPath::root(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.create_new_folder(&writer, "docs").await.unwrap();
+    let writer = vector_fs
+        .new_writer(
+            default_test_profile(),
+            VRPath::root().push_cloned("docs".to_string()),
+            default_test_profile(),
+        )
+        .await
+        .unwrap();
+
+    let (doc_resource, source_file_map) = get_shinkai_intro_doc_async(&generator, &vec![]).await.unwrap();
+    let resource = BaseVectorResource::Document(doc_resource);
+    let item = vector_fs
+        .save_vector_resource_in_folder(&writer, resource.clone(), Some(source_file_map))
+        .await
+        .unwrap();
+    assert!(VectorFS::verify_resource_integrity(&resource).is_empty());
+    assert!(vector_fs
+        .verify_profile_integrity(&default_test_profile())
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Changing the content of a chunk without updating its merkle hash
+    let mut tampered = resource.clone();
+    let (mut node, embedding) = tampered.as_trait_object().get_root_nodes_and_embeddings()[0].clone();
+    node.content = NodeContent::Text("tampered".to_string());
+    tampered
+        .as_trait_object_mut()
+        .replace_node_at_path(
+            VRPath::root().push_cloned(node.id.clone()),
+            node.clone(),
+            embedding,
+            false,
+        )
+        .unwrap();
+    assert_eq!(
+        VectorFS::verify_resource_integrity(&tampered),
+        vec![IntegrityProblem::CorruptedChunk { chunk_id: node.id }]
+    );
+
+    let mut write_batch = ProfileBoundWriteBatch::new_vfs_batch(&default_test_profile()).unwrap();
+    vector_fs
+        .db
+        .wb_delete_resource(&item.resource_db_key(), &mut write_batch)
+        .unwrap();
+    vector_fs.db.write_pb(write_batch).unwrap();
+    let reports = vector_fs
+        .verify_profile_integrity(&default_test_profile())
+        .await
+        .unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].path, item.path.format_to_string());
+    assert_eq!(reports[0].problems, vec![IntegrityProblem::MissingResource]);
+}
+
+#[tokio::test]
+async fn test_item_versions() {
+    setup();
+    let generator = RemoteEmbeddingGenerator::new_default();
+    let vector_fs = setup_default_vector_fs().await;
+
+    let writer = vector_fs
+        .new_writer(default_test_profile(), VRPath::root(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.create_new_folder(&writer, "docs").await.unwrap();
+    let folder_path = VRPath::root().push_cloned("docs".to_string());
+    let writer = vector_fs
+        .new_writer(default_test_profile(), folder_path.clone(), default_test_profile())
+        .await
+        .unwrap();
+
+    let (doc_resource, source_file_map) = get_shinkai_intro_doc_async(&generator, &vec![]).await.unwrap();
+    let resource = BaseVectorResource::Document(doc_resource);
+    let first_item = vector_fs
+        .save_vector_resource_in_folder(&writer, resource.clone(), Some(source_file_map))
+        .await
+        .unwrap();
+    // Saving the same resource again keeps the first save as a version, along with its source file
+    let second_item = vector_fs
+        .save_vector_resource_in_folder(&writer, resource, None)
+        .await
+        .unwrap();
+    assert_eq!(first_item.path, second_item.path);
+    assert_ne!(first_item.resource_db_key(), second_item.resource_db_key());
+    assert!(second_item.is_source_file_map_saved());
+
+    let reader = vector_fs
+        .new_reader(default_test_profile(), second_item.path.clone(), default_test_profile())
+        .await
+        .unwrap();
+    let versions = vector_fs.retrieve_item_versions(&reader).await.unwrap();
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0].version_id, 1);
+    assert_eq!(versions[0].resource_db_key, first_item.resource_db_key());
+    assert!(vector_fs
+        .db
+        .get_resource(&versions[0].resource_db_key, &default_test_profile())
+        .is_ok());
+
+    let changes = vector_fs.diff_item_version_metadata(&reader, 1).await.unwrap();
+    assert!(changes
+        .iter()
+        .any(|change| change.key == FSItem::vr_last_saved_metadata_key()));
+    assert!(vector_fs.diff_item_version_metadata(&reader, 2).await.is_err());
+
+    // Restoring keeps the replaced item as a version too
+    let item_writer = vector_fs
+        .new_writer(default_test_profile(), second_item.path.clone(), default_test_profile())
+        .await
+        .unwrap();
+    let restored_item = vector_fs.restore_item_version(&item_writer, 1).await.unwrap();
+    assert_eq!(restored_item.merkle_hash, first_item.merkle_hash);
+    let versions = vector_fs.retrieve_item_versions(&reader).await.unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[1].resource_db_key, second_item.resource_db_key());
+
+    // Deleting the item deletes its versions
+    vector_fs.delete_item(&item_writer).await.unwrap();
+    assert!(vector_fs
+        .db
+        .get_resource(&versions[0].resource_db_key, &default_test_profile())
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_item_links() {
+    setup();
+    let generator = RemoteEmbeddingGenerator::new_default();
+    let vector_fs = setup_default_vector_fs().await;
+
+    let writer = vector_fs
+        .new_writer(default_test_profile(), VRPath::root(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.create_new_folder(&writer, "docs").await.unwrap();
+    vector_fs.create_new_folder(&writer, "views").await.unwrap();
+    vector_fs.create_new_folder(&writer, "archive").await.unwrap();
+    let docs_path = VRPath::root().push_cloned("docs".to_string());
+    let views_path = VRPath::root().push_cloned("views".to_string());
+    let archive_path = VRPath::root().push_cloned("archive".to_string());
+
+    let docs_writer = vector_fs
+        .new_writer(default_test_profile(), docs_path, default_test_profile())
+        .await
+        .unwrap();
+    let (doc_resource, source_file_map) = get_shinkai_intro_doc_async(&generator, &vec![]).await.unwrap();
+    let item = vector_fs
+        .save_vector_resource_in_folder(
+            &docs_writer,
+            BaseVectorResource::Document(doc_resource),
+            Some(source_file_map),
+        )
+        .await
+        .unwrap();
+
+    let views_writer = vector_fs
+        .new_writer(default_test_profile(), views_path.clone(), default_test_profile())
+        .await
+        .unwrap();
+    let link = vector_fs
+        .create_link(&views_writer, "intro_link", item.path.clone())
+        .await
+        .unwrap();
+    assert_eq!(link.name, "intro_link");
+    assert_eq!(link.link_target, Some(item.path.clone()));
+    assert_eq!(link.resource_db_key(), item.resource_db_key());
+
+    // The link reads the resource of its target, which search returns once
+    let reader = vector_fs
+        .new_reader(default_test_profile(), link.path.clone(), default_test_profile())
+        .await
+        .unwrap();
+    let resource = vector_fs.retrieve_vector_resource(&reader).await.unwrap();
+    assert_eq!(resource.as_trait_object().reference_string(), item.resource_db_key());
+    let root_reader = vector_fs
+        .new_reader(default_test_profile(), VRPath::root(), default_test_profile())
+        .await
+        .unwrap();
+    let query_embedding = vector_fs
+        .generate_query_embedding_using_reader("Shinkai".to_string(), &root_reader)
+        .await
+        .unwrap();
+    let items = vector_fs
+        .vector_search_fs_item(&root_reader, query_embedding, 100)
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+
+    // The link follows its target when it moves
+    let item_writer = vector_fs
+        .new_writer(default_test_profile(), item.path.clone(), default_test_profile())
+        .await
+        .unwrap();
+    let moved_item = vector_fs.move_item(&item_writer, archive_path).await.unwrap();
+    let link = vector_fs.retrieve_fs_entry(&reader).await.unwrap().as_item().unwrap();
+    assert_eq!(link.link_target, Some(moved_item.path.clone()));
+
+    // Deleting the link leaves the target, deleting the target removes the link
+    let link_writer = vector_fs
+        .new_writer(default_test_profile(), link.path.clone(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.delete_item(&link_writer).await.unwrap();
+    let moved_reader = vector_fs
+        .new_reader(default_test_profile(), moved_item.path.clone(), default_test_profile())
+        .await
+        .unwrap();
+    assert!(vector_fs.retrieve_vector_resource(&moved_reader).await.is_ok());
+
+    vector_fs
+        .create_link(&views_writer, "intro_link", moved_item.path.clone())
+        .await
+        .unwrap();
+    let moved_writer = vector_fs
+        .new_writer(default_test_profile(), moved_item.path.clone(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.delete_item(&moved_writer).await.unwrap();
+    assert!(vector_fs
+        .validate_path_points_to_entry(link.path.clone(), &default_test_profile())
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_folder_embedding_model() {
+    setup();
+    let generator = RemoteEmbeddingGenerator::new_default();
+    let vector_fs = setup_default_vector_fs().await;
+    let es_model =
+        EmbeddingModelType::OllamaTextEmbeddingsInference(OllamaTextEmbeddingsInference::JinaEmbeddingsV2BaseEs);
+
+    let writer = vector_fs
+        .new_writer(default_test_profile(), VRPath::root(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.create_new_folder(&writer, "docs").await.unwrap();
+    vector_fs.create_new_folder(&writer, "docs-es").await.unwrap();
+    let docs_path = VRPath::root().push_cloned("docs".to_string());
+    let es_path = VRPath::root().push_cloned("docs-es".to_string());
+    let docs_writer = vector_fs
+        .new_writer(default_test_profile(), docs_path.clone(), default_test_profile())
+        .await
+        .unwrap();
+    let es_writer = vector_fs
+        .new_writer(default_test_profile(), es_path.clone(), default_test_profile())
+        .await
+        .unwrap();
+
+    let (doc_resource, _) = get_shinkai_intro_doc_async(&generator, &vec![]).await.unwrap();
+    let resource = BaseVectorResource::Document(doc_resource);
+    vector_fs
+        .save_vector_resource_in_folder(&docs_writer, resource.clone(), None)
+        .await
+        .unwrap();
+
+    // A folder holding items of another model can't declare a new one
+    assert!(vector_fs
+        .set_folder_embedding_model(&docs_writer, Some(es_model.clone()))
+        .await
+        .is_err());
+
+    let folder = vector_fs
+        .set_folder_embedding_model(&es_writer, Some(es_model.clone()))
+        .await
+        .unwrap();
+    assert_eq!(folder.embedding_model, Some(es_model.clone()));
+    assert!(vector_fs
+        .save_vector_resource_in_folder(&es_writer, resource.clone(), None)
+        .await
+        .is_err());
+
+    // Subfolders inherit the model, and the rest of the VectorFS keeps the default one
+    vector_fs.create_new_folder(&es_writer, "contratos").await.unwrap();
+    let contratos_generator = vector_fs
+        .folder_embedding_generator_override(&default_test_profile(), &es_path.push_cloned("contratos".to_string()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(contratos_generator.model_type(), es_model);
+    assert!(vector_fs
+        .folder_embedding_generator_override(&default_test_profile(), &docs_path)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Files saved in the folder are processed with its model rather than the multilingual one
+    let ingestion = IngestionSettings {
+        multilingual_embedding_generator: Some(generator.clone()),
+        ..Default::default()
+    };
+    let (contratos_generator, contratos_ingestion) = vector_fs
+        .folder_embedding_settings(
+            &default_test_profile(),
+            &es_path.push_cloned("contratos".to_string()),
+            &generator,
+            &ingestion,
+        )
+        .await
+        .unwrap();
+    assert_eq!(contratos_generator.model_type(), es_model);
+    assert!(contratos_ingestion.multilingual_embedding_generator.is_none());
+    let (docs_generator, docs_ingestion) = vector_fs
+        .folder_embedding_settings(&default_test_profile(), &docs_path, &generator, &ingestion)
+        .await
+        .unwrap();
+    assert_eq!(docs_generator.model_type(), generator.model_type());
+    assert!(docs_ingestion.multilingual_embedding_generator.is_some());
+
+    // Removing the model makes the folder use the default one again
+    vector_fs.set_folder_embedding_model(&es_writer, None).await.unwrap();
+    vector_fs
+        .save_vector_resource_in_folder(&es_writer, resource, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_multilingual_items_search() {
+    setup();
+    let generator = RemoteEmbeddingGenerator::new_default();
+    let es_generator = RemoteEmbeddingGenerator::new(
+        EmbeddingModelType::OllamaTextEmbeddingsInference(OllamaTextEmbeddingsInference::JinaEmbeddingsV2BaseEs),
+        &generator.api_url,
+        generator.api_key.clone(),
+    );
+    let vector_fs = VectorFS::new(
+        generator.clone(),
+        vec![generator.model_type(), es_generator.model_type()],
+        vec![default_test_profile()],
+        "db_tests/vector_fs",
+        node_name(),
+    )
+    .await
+    .unwrap();
+
+    let writer = vector_fs
+        .new_writer(default_test_profile(), VRPath::root(), default_test_profile())
+        .await
+        .unwrap();
+    vector_fs.create_new_folder(&writer, "docs").await.unwrap();
+    let docs_path = VRPath::root().push_cloned("docs".to_string());
+    let docs_writer = vector_fs
+        .new_writer(default_test_profile(), docs_path.clone(), default_test_profile())
+        .await
+        .unwrap();
+
+    let (doc_resource, _) = get_shinkai_intro_doc_async(&generator, &vec![]).await.unwrap();
+    vector_fs
+        .save_vector_resource_in_folder(&docs_writer, BaseVectorResource::Document(doc_resource), None)
+        .await
+        .unwrap();
+
+    // A Spanish document embedded with the multilingual model is saved next to the English one
+    let spanish = "El contrato de arrendamiento establece que el inquilino debe pagar la renta durante los primeros \
+                   cinco días de cada mes, y que el propietario se encarga de las reparaciones del departamento.";
+    let mut text_groups = vec![TextGroup::new(spanish.to_string(), HashMap::new(), vec![], None)];
+    let language = ShinkaiFileParser::tag_text_groups_language(&mut text_groups);
+    assert_eq!(language.as_deref(), Some("spa"));
+    let es_resource = ShinkaiFileParser::process_groups_into_resource(
+        text_groups,
+        &es_generator,
+        "contrato".to_string(),
+        None,
+        VRSourceReference::None,
+        &vec![],
+        500,
+        DistributionInfo::new_empty(),
+    )
+    .await
+    .unwrap();
+    vector_fs
+        .save_vector_resource_in_folder(&docs_writer, es_resource, None)
+        .await
+        .unwrap();
+
+    // Both are scored, each with the query embedded by its own model
+    let reader = vector_fs
+        .new_reader(default_test_profile(), docs_path, default_test_profile())
+        .await
+        .unwrap();
+    let results = vector_fs
+        .vector_search_fs_item_with_score_multi_model(&reader, "¿Cuándo hay que pagar la renta?", 2)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0.name, "contrato");
+}
+
 #[tokio::test]
 async fn test_remove_code_blocks_with_parsed_user_message() {
     // Example strings containing code blocks
@@ -1548,6 +2157,7 @@ fn vector_search_multiple_embedding_models_test() {
                     path: "/test_folder".to_string(),
                     file_inbox: hash_of_aes_encryption_key_hex(symmetrical_sk),
                     file_datetime: Some(Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap()),
+                    allow_duplicates: false,
                 };
 
                 let msg = generate_message_with_payload(
@@ -1652,6 +2262,7 @@ fn vector_search_multiple_embedding_models_test() {
                     path: "/test_folder".to_string(),
                     file_inbox: hash_of_aes_encryption_key_hex(symmetrical_sk),
                     file_datetime: Some(Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap()),
+                    allow_duplicates: false,
                 };
 
                 let msg = generate_message_with_payload(
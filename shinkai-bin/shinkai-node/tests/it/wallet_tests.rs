@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use shinkai_message_primitives::schemas::payment_invoice::{Invoice, InvoiceStatus};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::init_default_tracing;
+use shinkai_node::db::ShinkaiDB;
+use shinkai_node::payments::invoices::InvoiceManager;
+use shinkai_node::payments::wallet::WalletManager;
+use shinkai_node::schemas::wallet::{AutoPaySettings, WalletAsset, WalletConfig, WalletNetwork, WalletSigner};
+use shinkai_vector_resources::utils::hash_string;
+
+fn setup() {
+    let _ = fs::remove_dir_all(Path::new("db_tests/"));
+}
+
+fn invoice() -> Invoice {
+    Invoice {
+        invoice_id: "invoice1".to_string(),
+        issuer: "@@alice.sepolia-shinkai".to_string(),
+        payer: "@@bob.sepolia-shinkai".to_string(),
+        amount: Decimal::new(5, 0),
+        asset: "USDC".to_string(),
+        memo: None,
+        reference: None,
+        pay_to: Some("0x0000000000000000000000000000000000000001".to_string()),
+        created_at: Utc::now(),
+        expires_at: None,
+        status: InvoiceStatus::Pending,
+        receipt: None,
+    }
+}
+
+#[tokio::test]
+async fn test_concurrent_payments_pay_an_invoice_once() {
+    init_default_tracing();
+    setup();
+    let db = ShinkaiDB::new(&format!("db_tests/{}", hash_string("concurrent_wallet_payments"))).unwrap();
+    let config = WalletConfig {
+        signer: WalletSigner::External {
+            address: "0x0000000000000000000000000000000000000002".to_string(),
+        },
+        network: WalletNetwork {
+            name: "sepolia".to_string(),
+            chain_id: 11155111,
+            rpc_url: "https://sepolia.example.com".to_string(),
+        },
+        assets: vec![WalletAsset {
+            symbol: "USDC".to_string(),
+            contract_address: Some("0x0000000000000000000000000000000000000003".to_string()),
+            decimals: 6,
+        }],
+        auto_pay: AutoPaySettings::default(),
+    };
+    WalletManager::set_wallet(&db, config).unwrap();
+    db.set_invoice(&invoice()).unwrap();
+
+    let (first, second) = tokio::join!(
+        WalletManager::pay_invoice(&db, "invoice1"),
+        WalletManager::pay_invoice(&db, "invoice1")
+    );
+    assert!(first.is_ok() != second.is_ok());
+    assert_eq!(WalletManager::list_transactions(&db, None).unwrap().len(), 1);
+}
+
+#[test]
+fn test_invoices_for_other_nodes_are_rejected() {
+    init_default_tracing();
+    setup();
+    let db = ShinkaiDB::new(&format!("db_tests/{}", hash_string("receive_invoices"))).unwrap();
+    let alice = ShinkaiName::new("@@alice.sepolia-shinkai".to_string()).unwrap();
+    let bob = ShinkaiName::new("@@bob.sepolia-shinkai".to_string()).unwrap();
+    let carol = ShinkaiName::new("@@carol.sepolia-shinkai".to_string()).unwrap();
+
+    assert!(InvoiceManager::receive_invoice(&db, &carol, &alice, invoice()).is_err());
+    assert!(InvoiceManager::get_invoice(&db, "invoice1").is_err());
+
+    let received = InvoiceManager::receive_invoice(&db, &bob, &alice, invoice()).unwrap();
+    assert_eq!(received.payer, "@@bob.sepolia-shinkai");
+    assert!(InvoiceManager::get_invoice(&db, "invoice1").is_ok());
+}
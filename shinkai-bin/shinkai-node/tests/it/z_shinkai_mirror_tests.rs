@@ -529,6 +529,8 @@ pub async fn api_registration_device_node_profile_main(
             .send(NodeCommand::LocalCreateRegistrationCode {
                 permissions: IdentityPermissions::Admin,
                 code_type: RegistrationCodeType::Device("main".to_string()),
+                ttl_secs: None,
+                max_uses: None,
                 res: res_registration_sender,
             })
             .await
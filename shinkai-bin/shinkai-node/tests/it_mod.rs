@@ -13,6 +13,7 @@ mod it {
     mod db_restore_tests;
     mod db_tests;
     mod encrypted_files_tests;
+    mod folder_agents_tests;
     mod get_onchain_identity_tests;
     mod job_branchs_retries_tests;
     mod job_concurrency_in_seq_tests;
@@ -32,10 +33,14 @@ mod it {
     mod planner_tests;
     // mod toolkit_tests;
     mod new_toolkit_tests;
+    mod toolkit_api_tests;
+    mod tool_usage_tests;
     mod subscription_http_upload_tests;
     mod utils;
     mod vector_fs_api_tests;
+    mod vector_fs_export_tests;
     mod vector_fs_tests;
+    mod wallet_tests;
     mod websocket_tests;
 
     mod change_nodes_name_tests;
@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use serde_json::Value;
+use shinkai_message_primitives::schemas::inbox_name::InboxName;
+use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
+
+use crate::{http_requests::PostRequestError, shinkai::shinkai_manager_for_subs::ShinkaiManagerForSubs};
+
+/// How often the job inbox is checked for the answer of the agent
+const POLL_INTERVAL_MILLIS: u64 = 500;
+/// Jobs that take longer than this are left running, their answer shows up with the next one
+const ANSWER_TIMEOUT_SECS: u64 = 600;
+
+/// A job with an agent, messaged from the terminal
+pub struct ChatSession {
+    manager: ShinkaiManagerForSubs,
+    agent_id: String,
+    job_id: String,
+    job_inbox: String,
+    /// Messages of the job already printed or sent by us
+    seen_messages: HashSet<String>,
+}
+
+impl ChatSession {
+    pub async fn start(manager: ShinkaiManagerForSubs, agent_id: String) -> Result<Self, PostRequestError> {
+        let job_id = manager.create_job(&agent_id).await?;
+        let job_inbox = InboxName::get_job_inbox_name_from_params(job_id.clone())
+            .map_err(|e| PostRequestError::InvalidResponse(e.to_string()))?
+            .to_string();
+
+        Ok(Self {
+            manager,
+            agent_id,
+            job_id,
+            job_inbox,
+            seen_messages: HashSet::new(),
+        })
+    }
+
+    pub fn job_id(&self) -> &str {
+        &self.job_id
+    }
+
+    /// Sends a message with the given files and waits for the answers of the agent
+    pub async fn send(&mut self, content: &str, file_paths: &[String]) -> Result<Vec<String>, PostRequestError> {
+        // Messages from before this one were already answered
+        for message in self.manager.get_last_messages_from_inbox(&self.job_inbox, 100).await? {
+            self.seen_messages
+                .insert(message.calculate_message_hash_for_pagination());
+        }
+
+        let files_inbox = if file_paths.is_empty() {
+            String::new()
+        } else {
+            self.manager.upload_files_to_new_inbox(file_paths).await?
+        };
+        self.manager
+            .send_job_message(&self.job_id, content, &files_inbox, &self.agent_id)
+            .await?;
+
+        let mut waited = Duration::ZERO;
+        while waited < Duration::from_secs(ANSWER_TIMEOUT_SECS) {
+            tokio::time::sleep(Duration::from_millis(POLL_INTERVAL_MILLIS)).await;
+            waited += Duration::from_millis(POLL_INTERVAL_MILLIS);
+
+            let answers = self.new_answers().await?;
+            if !answers.is_empty() {
+                return Ok(answers);
+            }
+        }
+        Err(PostRequestError::RequestFailed(format!(
+            "No answer after {} seconds",
+            ANSWER_TIMEOUT_SECS
+        )))
+    }
+
+    /// Runs a chat reading messages from stdin. `/attach <path>` adds a file to the next message and
+    /// `/exit` ends the chat.
+    pub async fn run_repl(&mut self) -> Result<(), PostRequestError> {
+        println!("Chatting with {} in job {}", self.agent_id, self.job_id);
+        println!("Type /attach <path> to add a file to the next message and /exit to quit");
+
+        let mut attached_files = Vec::new();
+        let stdin = io::stdin();
+        loop {
+            print!("> ");
+            io::stdout().flush().ok();
+
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+                return Ok(());
+            }
+            let line = line.trim();
+
+            if line == "/exit" {
+                return Ok(());
+            } else if let Some(path) = line.strip_prefix("/attach ") {
+                attached_files.push(path.trim().to_string());
+                println!("Attached {} file(s) to the next message", attached_files.len());
+            } else if !line.is_empty() {
+                match self.send(line, &attached_files).await {
+                    Ok(answers) => answers.iter().for_each(|answer| println!("{}\n", answer)),
+                    Err(e) => eprintln!("Error: {}", String::from(e)),
+                }
+                attached_files.clear();
+            }
+        }
+    }
+
+    async fn new_answers(&mut self) -> Result<Vec<String>, PostRequestError> {
+        let messages = self.manager.get_last_messages_from_inbox(&self.job_inbox, 100).await?;
+        let mut answers = Vec::new();
+        for message in messages {
+            if !self
+                .seen_messages
+                .insert(message.calculate_message_hash_for_pagination())
+            {
+                continue;
+            }
+            if message.get_sender_subidentity() != Some(self.manager.sender_subidentity.clone()) {
+                answers.push(Self::message_text(&message));
+            }
+        }
+        Ok(answers)
+    }
+
+    /// Text of an answer of the agent, which is sent as a job message
+    fn message_text(message: &ShinkaiMessage) -> String {
+        Self::answer_text(message.get_message_content().unwrap_or_default())
+    }
+
+    /// The `content` of a job message, or the whole content if it isn't a job message
+    fn answer_text(content: String) -> String {
+        match serde_json::from_str::<Value>(&content) {
+            Ok(value) => value
+                .get("content")
+                .and_then(|content| content.as_str())
+                .map(String::from)
+                .unwrap_or(content),
+            Err(_) => content,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_text_of_job_message() {
+        let content = r#"{"job_id":"job_1","content":"The weather is sunny","files_inbox":""}"#;
+        assert_eq!(
+            ChatSession::answer_text(content.to_string()),
+            "The weather is sunny".to_string()
+        );
+    }
+
+    #[test]
+    fn test_answer_text_of_other_content() {
+        assert_eq!(ChatSession::answer_text("not json".to_string()), "not json".to_string());
+        let content = r#"{"job_id":"job_1"}"#;
+        assert_eq!(ChatSession::answer_text(content.to_string()), content.to_string());
+    }
+}
@@ -1,3 +1,4 @@
 pub mod subscription_manager;
 pub mod http_requests;
-pub mod shinkai;
\ No newline at end of file
+pub mod shinkai;
+pub mod chat_session;
\ No newline at end of file
@@ -5,7 +5,8 @@ use shinkai_message_primitives::{
     shinkai_message::shinkai_message_schemas::FileDestinationCredentials,
 };
 use shinkai_subscription_management_cli::{
-    shinkai::shinkai_manager_for_subs::ShinkaiManagerForSubs, subscription_manager::SubscriptionManager,
+    chat_session::ChatSession, shinkai::shinkai_manager_for_subs::ShinkaiManagerForSubs,
+    subscription_manager::SubscriptionManager,
 };
 use std::{env, path::Path, process};
 
@@ -230,6 +231,33 @@ async fn main() {
                         .index(3),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("chat")
+                .about("Chats with an agent of the profile, interactively or with a single message")
+                .arg(
+                    Arg::with_name("agent_id")
+                        .help("The ID of the agent to chat with")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("message")
+                        .short('m')
+                        .long("message")
+                        .value_name("MESSAGE")
+                        .help("Sends a single message, prints the answer and exits")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("attach")
+                        .short('a')
+                        .long("attach")
+                        .value_name("FILE")
+                        .help("File to attach to the message, can be repeated")
+                        .takes_value(true)
+                        .multiple_occurrences(true),
+                ),
+        )
         .get_matches();
 
     let encrypted_file_path = matches
@@ -249,6 +277,37 @@ async fn main() {
     )
     .expect("Failed to initialize ShinkaiManagerForSync");
 
+    if let Some(matches) = matches.subcommand_matches("chat") {
+        let agent_id = matches.value_of("agent_id").unwrap();
+        let attached_files: Vec<String> = matches
+            .values_of("attach")
+            .map(|files| files.map(String::from).collect())
+            .unwrap_or_default();
+
+        let mut chat_session = match ChatSession::start(subscription_manager_subs, agent_id.to_string()).await {
+            Ok(chat_session) => chat_session,
+            Err(e) => {
+                eprintln!("Error creating the job: {}", String::from(e));
+                process::exit(1);
+            }
+        };
+        match matches.value_of("message") {
+            Some(message) => match chat_session.send(message, &attached_files).await {
+                Ok(answers) => answers.iter().for_each(|answer| println!("{}", answer)),
+                Err(e) => {
+                    eprintln!("Error in job {}: {}", chat_session.job_id(), String::from(e));
+                    process::exit(1);
+                }
+            },
+            None => {
+                if let Err(e) = chat_session.run_repl().await {
+                    eprintln!("Error: {}", String::from(e));
+                }
+            }
+        }
+        return;
+    }
+
     let subscription_manager = SubscriptionManager::new(subscription_manager_subs).await;
 
     if matches.subcommand_matches("check_node_health").is_some() {
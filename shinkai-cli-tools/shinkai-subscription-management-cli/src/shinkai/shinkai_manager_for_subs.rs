@@ -1,15 +1,24 @@
 use std::fs;
 use std::path::Path;
 
-use crate::http_requests::{request_post, PostRequestError};
+use crate::http_requests::{request_post, request_post_multipart, PostRequestError};
+use aes_gcm::aead::{generic_array::GenericArray, Aead};
+use aes_gcm::{Aes256Gcm, KeyInit};
 use ed25519_dalek::SigningKey;
+use rand::RngCore;
 use serde_json::Value;
 use shinkai_message_primitives::schemas::shinkai_subscription_req::{FolderSubscription, SubscriptionPayment};
+use shinkai_message_primitives::shinkai_message::shinkai_message::ShinkaiMessage;
 use shinkai_message_primitives::shinkai_message::shinkai_message_schemas::{
     APICreateShareableFolder, FileDestinationCredentials,
 };
 use shinkai_message_primitives::shinkai_utils::{
     encryption::{string_to_encryption_public_key, string_to_encryption_static_key},
+    file_encryption::{
+        aes_encryption_key_to_string, aes_nonce_to_hex_string, hash_of_aes_encryption_key_hex,
+        random_aes_encryption_key,
+    },
+    job_scope::JobScope,
     shinkai_message_builder::{ShinkaiMessageBuilder, ShinkaiNameString},
     signatures::string_to_signature_secret_key,
 };
@@ -100,7 +109,8 @@ impl ShinkaiManagerForSubs {
                     let health_status: NodeHealthStatus = serde_json::from_value(health_data.clone())
                         .expect("Failed to parse health data into NodeHealthStatusPayload");
 
-                    if health_status.status == "ok" {
+                    // Older nodes answer "ok", newer ones "healthy" or "degraded"
+                    if matches!(health_status.status.as_str(), "ok" | "healthy" | "degraded") {
                         Ok(health_status)
                     } else {
                         Err("Shinkai node health check failed")
@@ -399,4 +409,139 @@ impl ShinkaiManagerForSubs {
             Err(_e) => Err("Failed to create folder"),
         }
     }
+
+    /// Creates a job for an agent of the profile and returns its ID
+    pub async fn create_job(&self, agent_id: &str) -> Result<String, PostRequestError> {
+        let shinkai_message = ShinkaiMessageBuilder::job_creation(
+            JobScope::new_default(),
+            false,
+            self.my_encryption_secret_key.clone(),
+            self.my_signature_secret_key.clone(),
+            self.receiver_public_key,
+            self.sender.clone(),
+            self.sender_subidentity.clone(),
+            self.node_receiver.clone(),
+            format!("{}/agent/{}", self.node_receiver_subidentity, agent_id),
+        )
+        .map_err(|e| PostRequestError::SerializationError(e.to_string()))?;
+
+        let payload = serde_json::to_string(&shinkai_message).expect("Failed to serialize shinkai_message");
+        let resp = request_post(self.node_address.clone(), payload, "/v1/create_job").await?;
+        resp.data
+            .as_str()
+            .map(String::from)
+            .ok_or(PostRequestError::InvalidResponse(format!(
+                "Unexpected job creation response {}",
+                resp.data
+            )))
+    }
+
+    /// Sends a message to a job, with the files of `files_inbox` if it's not empty
+    pub async fn send_job_message(
+        &self,
+        job_id: &str,
+        content: &str,
+        files_inbox: &str,
+        agent_id: &str,
+    ) -> Result<(), PostRequestError> {
+        let shinkai_message = ShinkaiMessageBuilder::job_message(
+            job_id.to_string(),
+            content.to_string(),
+            files_inbox.to_string(),
+            "".to_string(),
+            None,
+            None,
+            self.my_encryption_secret_key.clone(),
+            self.my_signature_secret_key.clone(),
+            self.receiver_public_key,
+            self.sender.clone(),
+            self.sender_subidentity.clone(),
+            self.node_receiver.clone(),
+            format!("{}/agent/{}", self.node_receiver_subidentity, agent_id),
+        )
+        .map_err(|e| PostRequestError::SerializationError(e.to_string()))?;
+
+        let payload = serde_json::to_string(&shinkai_message).expect("Failed to serialize shinkai_message");
+        request_post(self.node_address.clone(), payload, "/v1/job_message").await?;
+        Ok(())
+    }
+
+    /// Last messages of an inbox, from the oldest
+    pub async fn get_last_messages_from_inbox(
+        &self,
+        inbox: &str,
+        count: usize,
+    ) -> Result<Vec<ShinkaiMessage>, PostRequestError> {
+        let shinkai_message = ShinkaiMessageBuilder::get_last_messages_from_inbox(
+            self.my_encryption_secret_key.clone(),
+            self.my_signature_secret_key.clone(),
+            self.receiver_public_key,
+            inbox.to_string(),
+            count,
+            None,
+            self.sender_subidentity.clone(),
+            self.sender.clone(),
+            self.node_receiver.clone(),
+        )
+        .map_err(|e| PostRequestError::SerializationError(e.to_string()))?;
+
+        let payload = serde_json::to_string(&shinkai_message).expect("Failed to serialize shinkai_message");
+        let resp = request_post(self.node_address.clone(), payload, "/v1/last_messages_from_inbox").await?;
+        serde_json::from_value(resp.data).map_err(|e| PostRequestError::SerializationError(e.to_string()))
+    }
+
+    /// Uploads files to a new files inbox, encrypted with a key only this upload uses, and returns the name of
+    /// the inbox to attach them to a job message
+    pub async fn upload_files_to_new_inbox(&self, file_paths: &[String]) -> Result<String, PostRequestError> {
+        let symmetric_key = random_aes_encryption_key();
+        let files_inbox = hash_of_aes_encryption_key_hex(symmetric_key);
+
+        let shinkai_message = ShinkaiMessageBuilder::create_files_inbox_with_sym_key(
+            self.my_encryption_secret_key.clone(),
+            self.my_signature_secret_key.clone(),
+            self.receiver_public_key,
+            files_inbox.clone(),
+            aes_encryption_key_to_string(symmetric_key),
+            self.sender_subidentity.clone(),
+            self.sender.clone(),
+            self.node_receiver.clone(),
+        )
+        .map_err(|e| PostRequestError::SerializationError(e.to_string()))?;
+        let payload = serde_json::to_string(&shinkai_message).expect("Failed to serialize shinkai_message");
+        request_post(
+            self.node_address.clone(),
+            payload,
+            "/v1/create_files_inbox_with_symmetric_key",
+        )
+        .await?;
+
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(&symmetric_key));
+        for file_path in file_paths {
+            let file_data = fs::read(file_path)
+                .map_err(|e| PostRequestError::RequestFailed(format!("Failed to read {}: {}", file_path, e)))?;
+            let filename = Path::new(file_path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| file_path.clone());
+
+            let mut nonce = [0u8; 12];
+            rand::thread_rng().fill_bytes(&mut nonce);
+            let encrypted_file = cipher
+                .encrypt(GenericArray::from_slice(&nonce), file_data.as_ref())
+                .map_err(|_| PostRequestError::RequestFailed(format!("Failed to encrypt {}", file_path)))?;
+
+            let form = reqwest::multipart::Form::new().part(
+                "file",
+                reqwest::multipart::Part::bytes(encrypted_file).file_name(filename),
+            );
+            let path = format!(
+                "/v1/add_file_to_inbox_with_symmetric_key/{}/{}",
+                files_inbox,
+                aes_nonce_to_hex_string(&nonce)
+            );
+            request_post_multipart(self.node_address.clone(), &path, form).await?;
+        }
+
+        Ok(files_inbox)
+    }
 }
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::shinkai_registry::{OnchainIdentity, ShinkaiRegistry, ShinkaiRegistryError};
+
+/// Where the identities of other nodes are looked up
+#[async_trait]
+pub trait IdentityRegistryBackend: Send + Sync {
+    async fn get_identity_record(&self, identity: String) -> Result<OnchainIdentity, ShinkaiRegistryError>;
+}
+
+#[async_trait]
+impl IdentityRegistryBackend for ShinkaiRegistry {
+    async fn get_identity_record(&self, identity: String) -> Result<OnchainIdentity, ShinkaiRegistryError> {
+        ShinkaiRegistry::get_identity_record(self, identity).await
+    }
+}
+
+/// Identity as listed in a static file or served by an HTTPS directory. Registries outside of the chain have no
+/// tokens, so they are left at zero.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DirectoryIdentity {
+    pub shinkai_identity: String,
+    pub encryption_key: String,
+    pub signature_key: String,
+    #[serde(default)]
+    pub routing: bool,
+    pub address_or_proxy_nodes: Vec<String>,
+}
+
+impl From<DirectoryIdentity> for OnchainIdentity {
+    fn from(identity: DirectoryIdentity) -> Self {
+        OnchainIdentity {
+            shinkai_identity: identity.shinkai_identity.trim_start_matches("@@").to_string(),
+            bound_nft: U256::zero(),
+            staked_tokens: U256::zero(),
+            encryption_key: identity.encryption_key,
+            signature_key: identity.signature_key,
+            routing: identity.routing,
+            address_or_proxy_nodes: identity.address_or_proxy_nodes,
+            delegated_tokens: U256::zero(),
+            last_updated: Utc::now(),
+        }
+    }
+}
+
+/// Identities read once from a JSON file holding a list of `DirectoryIdentity`
+pub struct StaticFileRegistry {
+    identities: HashMap<String, OnchainIdentity>,
+}
+
+impl StaticFileRegistry {
+    pub fn new(path: &str) -> Result<Self, ShinkaiRegistryError> {
+        let content = fs::read_to_string(path).map_err(ShinkaiRegistryError::IoError)?;
+        Self::from_json(&content)
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, ShinkaiRegistryError> {
+        let identities: Vec<DirectoryIdentity> = serde_json::from_str(content)?;
+        let identities = identities
+            .into_iter()
+            .map(OnchainIdentity::from)
+            .map(|identity| (identity.shinkai_identity.clone(), identity))
+            .collect();
+        Ok(StaticFileRegistry { identities })
+    }
+}
+
+#[async_trait]
+impl IdentityRegistryBackend for StaticFileRegistry {
+    async fn get_identity_record(&self, identity: String) -> Result<OnchainIdentity, ShinkaiRegistryError> {
+        self.identities
+            .get(identity.trim_start_matches("@@"))
+            .cloned()
+            .ok_or_else(|| ShinkaiRegistryError::CustomError(format!("Identity {} not found in the file", identity)))
+    }
+}
+
+/// Identities served by an HTTPS directory, which answers `GET {base_url}/{identity}` with a `DirectoryIdentity`
+pub struct HttpsDirectoryRegistry {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl HttpsDirectoryRegistry {
+    pub fn new(base_url: &str) -> Self {
+        Self::with_client(base_url, reqwest::Client::new())
+    }
+
+    pub fn with_client(base_url: &str, client: reqwest::Client) -> Self {
+        HttpsDirectoryRegistry {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl IdentityRegistryBackend for HttpsDirectoryRegistry {
+    async fn get_identity_record(&self, identity: String) -> Result<OnchainIdentity, ShinkaiRegistryError> {
+        let url = format!("{}/{}", self.base_url, identity.trim_start_matches("@@"));
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| ShinkaiRegistryError::CustomError(format!("Failed to reach the directory: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(ShinkaiRegistryError::CustomError(format!(
+                "The directory answered {} for {}",
+                response.status(),
+                identity
+            )));
+        }
+        let directory_identity: DirectoryIdentity = response
+            .json()
+            .await
+            .map_err(|e| ShinkaiRegistryError::CustomError(format!("Invalid directory identity: {}", e)))?;
+        Ok(directory_identity.into())
+    }
+}
+
+/// Which registry backend the identities are looked up in
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdentityRegistryConfig {
+    Onchain {
+        rpc_url: String,
+        contract_address: String,
+        abi_path: Option<String>,
+    },
+    StaticFile {
+        path: String,
+    },
+    HttpsDirectory {
+        base_url: String,
+    },
+}
+
+impl IdentityRegistryConfig {
+    pub async fn build(self) -> Result<Arc<dyn IdentityRegistryBackend>, ShinkaiRegistryError> {
+        match self {
+            IdentityRegistryConfig::Onchain {
+                rpc_url,
+                contract_address,
+                abi_path,
+            } => Ok(Arc::new(
+                ShinkaiRegistry::new(&rpc_url, &contract_address, abi_path).await?,
+            )),
+            IdentityRegistryConfig::StaticFile { path } => Ok(Arc::new(StaticFileRegistry::new(&path)?)),
+            IdentityRegistryConfig::HttpsDirectory { base_url } => Ok(Arc::new(HttpsDirectoryRegistry::new(&base_url))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_static_file_registry() {
+        let registry = StaticFileRegistry::from_json(
+            r#"[{
+                "shinkai_identity": "@@node1.private",
+                "encryption_key": "encryption_key",
+                "signature_key": "signature_key",
+                "address_or_proxy_nodes": ["10.0.0.2:9552"]
+            }]"#,
+        )
+        .unwrap();
+
+        let record = registry
+            .get_identity_record("@@node1.private".to_string())
+            .await
+            .unwrap();
+        assert_eq!(record.shinkai_identity, "node1.private");
+        assert_eq!(record.address_or_proxy_nodes, vec!["10.0.0.2:9552".to_string()]);
+        assert!(!record.routing);
+        assert!(registry.get_identity_record("node2.private".to_string()).await.is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use std::convert::TryFrom;
+
+use async_trait::async_trait;
+use ethers::providers::{Http, Middleware, Provider};
+use serde_json::Value;
+
+use crate::shinkai_registry::{OnchainIdentity, ShinkaiRegistryError};
+
+/// ENS text record holding the Shinkai identity of the name
+pub const ENS_IDENTITY_RECORD: &str = "com.shinkai.identity";
+/// ENS text record holding the signature key the Shinkai identity must have in the registry
+pub const ENS_SIGNATURE_KEY_RECORD: &str = "com.shinkai.signature_key";
+/// Type of the DID document service pointing to the Shinkai identity
+pub const DID_SHINKAI_SERVICE_TYPE: &str = "ShinkaiNode";
+
+/// What an ENS name or a DID points to. The identity itself is then looked up in the registry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedIdentityName {
+    pub name: String,
+    /// Shinkai identity of the node, e.g. `@@alice.sepolia-shinkai`
+    pub shinkai_identity: String,
+    /// Signature key the name vouches for, checked against the registry record if set
+    pub signature_key: Option<String>,
+}
+
+impl ResolvedIdentityName {
+    /// Checks that the registry record of the identity has the key the name vouches for
+    pub fn verify(&self, record: &OnchainIdentity) -> Result<(), ShinkaiRegistryError> {
+        match &self.signature_key {
+            Some(signature_key) if !signature_key.eq_ignore_ascii_case(&record.signature_key) => {
+                Err(ShinkaiRegistryError::CustomError(format!(
+                    "{} vouches for a signature key {} doesn't have in the registry",
+                    self.name, self.shinkai_identity
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Maps names from outside of Shinkai to Shinkai identities
+#[async_trait]
+pub trait IdentityNameResolver: Send + Sync {
+    fn can_resolve(&self, name: &str) -> bool;
+    async fn resolve(&self, name: &str) -> Result<ResolvedIdentityName, ShinkaiRegistryError>;
+}
+
+/// Resolves `.eth` names with their `com.shinkai.identity` and `com.shinkai.signature_key` text records
+pub struct EnsResolver {
+    provider: Provider<Http>,
+}
+
+impl EnsResolver {
+    pub fn new(rpc_url: &str) -> Result<Self, ShinkaiRegistryError> {
+        let provider =
+            Provider::<Http>::try_from(rpc_url).map_err(|err| ShinkaiRegistryError::CustomError(err.to_string()))?;
+        Ok(EnsResolver { provider })
+    }
+}
+
+#[async_trait]
+impl IdentityNameResolver for EnsResolver {
+    fn can_resolve(&self, name: &str) -> bool {
+        name.ends_with(".eth")
+    }
+
+    async fn resolve(&self, name: &str) -> Result<ResolvedIdentityName, ShinkaiRegistryError> {
+        let shinkai_identity = self
+            .provider
+            .resolve_field(name, ENS_IDENTITY_RECORD)
+            .await
+            .map_err(|e| ShinkaiRegistryError::CustomError(format!("Failed to resolve {}: {}", name, e)))?;
+        if shinkai_identity.is_empty() {
+            return Err(ShinkaiRegistryError::CustomError(format!(
+                "{} has no {} record",
+                name, ENS_IDENTITY_RECORD
+            )));
+        }
+        let signature_key = self
+            .provider
+            .resolve_field(name, ENS_SIGNATURE_KEY_RECORD)
+            .await
+            .ok()
+            .filter(|signature_key| !signature_key.is_empty());
+
+        Ok(ResolvedIdentityName {
+            name: name.to_string(),
+            shinkai_identity,
+            signature_key,
+        })
+    }
+}
+
+/// Resolves `did:web` DIDs with the `ShinkaiNode` service of their document. The signature key is the `publicKeyHex`
+/// of the first verification method having one.
+pub struct DidWebResolver {
+    client: reqwest::Client,
+}
+
+impl DidWebResolver {
+    pub fn new() -> Self {
+        Self::with_client(reqwest::Client::new())
+    }
+
+    pub fn with_client(client: reqwest::Client) -> Self {
+        DidWebResolver { client }
+    }
+
+    /// Where the DID document is served, as per the did:web method spec
+    pub fn document_url(did: &str) -> Result<String, ShinkaiRegistryError> {
+        let method_specific_id = did
+            .strip_prefix("did:web:")
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| ShinkaiRegistryError::CustomError(format!("{} isn't a did:web DID", did)))?;
+        let mut parts = method_specific_id
+            .split(':')
+            .map(|part| part.replace("%3A", ":").replace("%3a", ":"));
+        let domain = parts.next().unwrap_or_default();
+        let path: Vec<String> = parts.collect();
+
+        Ok(match path.is_empty() {
+            true => format!("https://{}/.well-known/did.json", domain),
+            false => format!("https://{}/{}/did.json", domain, path.join("/")),
+        })
+    }
+
+    pub fn from_document(did: &str, document: &Value) -> Result<ResolvedIdentityName, ShinkaiRegistryError> {
+        let shinkai_identity = document["service"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|service| service["type"] == DID_SHINKAI_SERVICE_TYPE)
+            .and_then(|service| service["serviceEndpoint"].as_str())
+            .ok_or_else(|| {
+                ShinkaiRegistryError::CustomError(format!("{} has no {} service", did, DID_SHINKAI_SERVICE_TYPE))
+            })?;
+        let signature_key = document["verificationMethod"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find_map(|method| method["publicKeyHex"].as_str());
+
+        Ok(ResolvedIdentityName {
+            name: did.to_string(),
+            shinkai_identity: shinkai_identity.to_string(),
+            signature_key: signature_key.map(|signature_key| signature_key.to_string()),
+        })
+    }
+}
+
+impl Default for DidWebResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl IdentityNameResolver for DidWebResolver {
+    fn can_resolve(&self, name: &str) -> bool {
+        name.starts_with("did:web:")
+    }
+
+    async fn resolve(&self, name: &str) -> Result<ResolvedIdentityName, ShinkaiRegistryError> {
+        let url = Self::document_url(name)?;
+        let response = self.client.get(&url).send().await.map_err(|e| {
+            ShinkaiRegistryError::CustomError(format!("Failed to fetch the document of {}: {}", name, e))
+        })?;
+        if !response.status().is_success() {
+            return Err(ShinkaiRegistryError::CustomError(format!(
+                "{} answered {} for the document of {}",
+                url,
+                response.status(),
+                name
+            )));
+        }
+        let document: Value = response
+            .json()
+            .await
+            .map_err(|e| ShinkaiRegistryError::CustomError(format!("Invalid DID document of {}: {}", name, e)))?;
+        Self::from_document(name, &document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_did_web_resolution() {
+        assert_eq!(
+            DidWebResolver::document_url("did:web:example.com").unwrap(),
+            "https://example.com/.well-known/did.json"
+        );
+        assert_eq!(
+            DidWebResolver::document_url("did:web:example.com%3A8443:users:alice").unwrap(),
+            "https://example.com:8443/users/alice/did.json"
+        );
+        assert!(DidWebResolver::document_url("did:key:z6Mk").is_err());
+
+        let document = serde_json::json!({
+            "id": "did:web:example.com",
+            "verificationMethod": [{
+                "id": "did:web:example.com#key-1",
+                "type": "Ed25519VerificationKey2018",
+                "publicKeyHex": "abcd"
+            }],
+            "service": [{
+                "id": "did:web:example.com#shinkai",
+                "type": "ShinkaiNode",
+                "serviceEndpoint": "@@alice.sepolia-shinkai"
+            }]
+        });
+        let resolved = DidWebResolver::from_document("did:web:example.com", &document).unwrap();
+        assert_eq!(resolved.shinkai_identity, "@@alice.sepolia-shinkai");
+        assert_eq!(resolved.signature_key, Some("abcd".to_string()));
+    }
+}
@@ -1,5 +1,9 @@
 // Declare the modules in the library
+pub mod identity_registry;
+pub mod identity_resolvers;
 pub mod shinkai_registry;
 
 // Re-export commonly used items for easier access
+pub use crate::identity_registry::*;
+pub use crate::identity_resolvers::*;
 pub use crate::shinkai_registry::*;
\ No newline at end of file
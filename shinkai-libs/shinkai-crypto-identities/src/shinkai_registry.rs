@@ -5,6 +5,7 @@ use ed25519_dalek::VerifyingKey;
 use ethers::abi::Abi;
 use ethers::prelude::*;
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 use shinkai_message_primitives::shinkai_utils::encryption::string_to_encryption_public_key;
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::shinkai_log;
 use shinkai_message_primitives::shinkai_utils::shinkai_logging::ShinkaiLogLevel;
@@ -89,7 +90,7 @@ impl From<std::io::Error> for ShinkaiRegistryError {
 
 impl std::error::Error for ShinkaiRegistryError {}
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct OnchainIdentity {
     pub shinkai_identity: String,
     pub bound_nft: U256, // id of the nft
@@ -106,7 +106,8 @@ impl ShinkaiManagerForSync {
                     let health_status: NodeHealthStatus = serde_json::from_value(health_data.clone())
                         .expect("Failed to parse health data into NodeHealthStatusPayload");
 
-                    if health_status.status == "ok" {
+                    // Older nodes answer "ok", newer ones "healthy" or "degraded"
+                    if matches!(health_status.status.as_str(), "ok" | "healthy" | "degraded") {
                         Ok(health_status)
                     } else {
                         Err("Shinkai node health check failed")
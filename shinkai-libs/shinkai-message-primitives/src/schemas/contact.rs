@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use super::shinkai_name::ShinkaiName;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContactTrustLevel {
+    /// Kept in the contact book, but treated as unknown by the allowlist mode
+    Untrusted,
+    #[default]
+    Known,
+    Trusted,
+}
+
+/// An identity in the contact book of a profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contact {
+    /// Node (`@@bob.sepolia-shinkai`) or profile (`@@bob.sepolia-shinkai/main`). A node covers all its profiles.
+    pub identity: String,
+    #[serde(default)]
+    pub nickname: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub trust_level: ContactTrustLevel,
+    #[serde(default)]
+    pub pinned: bool,
+    /// Set by the node when the contact is added
+    #[serde(default)]
+    pub added_at: String,
+}
+
+/// Contacts of a profile. In allowlist mode, conversations started by identities that aren't contacts go to the
+/// message requests of the profile instead of its inboxes.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ContactBook {
+    #[serde(default)]
+    pub allowlist_mode: bool,
+    /// Pinned contacts first, then by nickname or identity
+    #[serde(default)]
+    pub contacts: Vec<Contact>,
+}
+
+impl ContactBook {
+    /// Contact of the profile of the identity, or else the one of its node
+    pub fn find(&self, identity: &ShinkaiName) -> Option<&Contact> {
+        let profile = identity.extract_profile().ok().map(|profile| profile.full_name);
+        let node = identity.extract_node().full_name;
+
+        let find_by_name = |name: &str| self.contacts.iter().find(|contact| contact.identity == name);
+        profile
+            .and_then(|profile| find_by_name(&profile))
+            .or_else(|| find_by_name(&node))
+    }
+
+    /// Whether the identity can start conversations with the profile
+    pub fn allows(&self, identity: &ShinkaiName) -> bool {
+        !self.allowlist_mode
+            || self
+                .find(identity)
+                .is_some_and(|contact| contact.trust_level != ContactTrustLevel::Untrusted)
+    }
+
+    /// Adds the contact, or replaces the one with the same identity keeping when it was added
+    pub fn upsert(&mut self, mut contact: Contact) {
+        if let Some(existing) = self
+            .contacts
+            .iter_mut()
+            .find(|existing| existing.identity == contact.identity)
+        {
+            contact.added_at.clone_from(&existing.added_at);
+            *existing = contact;
+        } else {
+            self.contacts.push(contact);
+        }
+        self.contacts.sort_by(|a, b| {
+            let name = |contact: &Contact| contact.nickname.as_deref().unwrap_or(&contact.identity).to_lowercase();
+            b.pinned.cmp(&a.pinned).then_with(|| name(a).cmp(&name(b)))
+        });
+    }
+
+    /// Removes the contact of the identity. Returns whether there was one.
+    pub fn remove(&mut self, identity: &str) -> bool {
+        let len = self.contacts.len();
+        self.contacts.retain(|contact| contact.identity != identity);
+        self.contacts.len() != len
+    }
+}
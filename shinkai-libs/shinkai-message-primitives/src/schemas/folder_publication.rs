@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PublicationAccess {
+    /// Anyone with the link can read the folder
+    Public,
+    /// Readers need the token of the publication
+    Token,
+}
+
+/// A shared folder served read-only over HTTP to people who don't run a node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FolderPublication {
+    pub publication_id: String,
+    pub path: String,
+    /// Profile the folder is read as
+    pub profile: String,
+    pub access: PublicationAccess,
+    pub token: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FolderPublication {
+    pub fn allows(&self, token: Option<&str>) -> bool {
+        match self.access {
+            PublicationAccess::Public => true,
+            PublicationAccess::Token => self.token.is_some() && self.token.as_deref() == token,
+        }
+    }
+}
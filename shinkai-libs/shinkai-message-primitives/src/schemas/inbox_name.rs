@@ -481,6 +481,9 @@ mod tests {
                         parent_hash: "".into(),
                         node_message_hash: "node_message_hash".into(),
                         node_timestamp: "20230714T19363326163".into(),
+                        edited_content: None,
+                        edited_at: None,
+                        deleted_at: None,
                     }),
                 },
             }),
@@ -522,6 +525,9 @@ mod tests {
                         parent_hash: "parent_hash".into(),
                         node_message_hash: "node_message_hash".into(),
                         node_timestamp: "20230714T19363326163".into(),
+                        edited_content: None,
+                        edited_at: None,
+                        deleted_at: None,
                     }),
                 },
             }),
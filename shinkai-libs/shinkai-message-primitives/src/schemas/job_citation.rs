@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use shinkai_vector_resources::vector_resource::RetrievedNode;
+
+/// A piece of source content an answer of a job was based on, anchored to where it is in the source document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobCitation {
+    pub resource_id: String,
+    pub resource_name: String,
+    /// Original file of the resource (ie. `report.pdf`), if it has one
+    pub source: Option<String>,
+    /// Path of the node inside the resource
+    pub retrieval_path: String,
+    /// Pages of the source document the content is in. Empty if the parser didn't provide them.
+    pub page_numbers: Vec<u32>,
+    /// Start and end characters of the content inside its page
+    pub char_offsets: Option<(usize, usize)>,
+    pub score: f32,
+}
+
+impl JobCitation {
+    pub fn from_retrieved_node(node: &RetrievedNode) -> Self {
+        let source = match node.resource_header.resource_source.format_source_string() {
+            source if source == "None" => None,
+            source => Some(source),
+        };
+
+        JobCitation {
+            resource_id: node.resource_header.resource_id.clone(),
+            resource_name: node.resource_header.resource_name.clone(),
+            source,
+            retrieval_path: node.retrieval_path.format_to_string(),
+            page_numbers: node.page_numbers(),
+            char_offsets: node.char_offsets(),
+            score: node.score,
+        }
+    }
+
+    /// Citations of the retrieved nodes, dropping the ones pointing at the same content
+    pub fn from_retrieved_nodes(nodes: &[RetrievedNode]) -> Vec<Self> {
+        let mut citations: Vec<JobCitation> = Vec::new();
+        for citation in nodes.iter().map(Self::from_retrieved_node) {
+            let is_duplicate = citations.iter().any(|existing| {
+                existing.resource_id == citation.resource_id && existing.retrieval_path == citation.retrieval_path
+            });
+            if !is_duplicate {
+                citations.push(citation);
+            }
+        }
+        citations
+    }
+}
@@ -7,4 +7,11 @@ pub mod shinkai_subscription;
 pub mod shinkai_subscription_req;
 pub mod shinkai_network;
 pub mod shinkai_proxy_builder_info;
-pub mod sheet;
\ No newline at end of file
+pub mod sheet;
+pub mod payment_invoice;
+pub mod workspace;
+pub mod contact;
+pub mod shared_folder_sync;
+pub mod folder_publication;
+pub mod retrieval_config;
+pub mod job_citation;
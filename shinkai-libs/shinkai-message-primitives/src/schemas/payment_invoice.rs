@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// What an invoice is paying for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum InvoiceReference {
+    Subscription { subscription_id: String },
+    ToolUsage { tool_key: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvoiceStatus {
+    Pending,
+    Paid,
+    Expired,
+}
+
+/// Request for a payment, issued by the node getting paid and sent to the node of the payer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Invoice {
+    pub invoice_id: String,
+    /// Node getting paid
+    pub issuer: String,
+    /// Node or profile paying, e.g. `@@bob.sepolia-shinkai/main`
+    pub payer: String,
+    pub amount: Decimal,
+    /// Asset the amount is in, e.g. `USDC` or `KAI`
+    pub asset: String,
+    pub memo: Option<String>,
+    pub reference: Option<InvoiceReference>,
+    /// Address the amount is sent to when the invoice is paid on chain
+    #[serde(default)]
+    pub pay_to: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub status: InvoiceStatus,
+    pub receipt: Option<PaymentReceipt>,
+}
+
+impl Invoice {
+    /// Pending invoices past their expiry can't be paid anymore
+    pub fn is_expired(&self) -> bool {
+        self.status == InvoiceStatus::Expired
+            || (self.status == InvoiceStatus::Pending
+                && self.expires_at.map_or(false, |expires_at| expires_at <= Utc::now()))
+    }
+}
+
+/// Proof that an invoice was paid, signed by the identity key of the node that got paid
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaymentReceipt {
+    pub invoice_id: String,
+    pub payee: String,
+    pub payer: String,
+    pub amount: Decimal,
+    pub asset: String,
+    /// Id of the transaction the invoice was paid with, if it was paid on chain
+    pub transaction_id: Option<String>,
+    pub paid_at: DateTime<Utc>,
+    pub signature: String,
+}
+
+impl PaymentReceipt {
+    pub fn new_signed(invoice: &Invoice, transaction_id: Option<String>, signing_key: &SigningKey) -> Self {
+        let mut receipt = PaymentReceipt {
+            invoice_id: invoice.invoice_id.clone(),
+            payee: invoice.issuer.clone(),
+            payer: invoice.payer.clone(),
+            amount: invoice.amount,
+            asset: invoice.asset.clone(),
+            transaction_id,
+            paid_at: Utc::now(),
+            signature: String::new(),
+        };
+        receipt.signature = hex::encode(signing_key.sign(receipt.signed_content().as_bytes()).to_bytes());
+        receipt
+    }
+
+    fn signed_content(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}:{}:{}",
+            self.invoice_id,
+            self.payee,
+            self.payer,
+            self.amount,
+            self.asset,
+            self.transaction_id.as_deref().unwrap_or_default(),
+            self.paid_at.to_rfc3339()
+        )
+    }
+
+    /// Whether the receipt was signed by the key of the payee
+    pub fn verify(&self, verifying_key: &VerifyingKey) -> bool {
+        let signature = hex::decode(&self.signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok());
+        match signature {
+            Some(signature) => verifying_key
+                .verify(self.signed_content().as_bytes(), &signature)
+                .is_ok(),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shinkai_utils::signatures::unsafe_deterministic_signature_keypair;
+
+    #[test]
+    fn test_payment_receipt_signature() {
+        let (signing_key, verifying_key) = unsafe_deterministic_signature_keypair(0);
+        let (_, other_verifying_key) = unsafe_deterministic_signature_keypair(1);
+        let invoice = Invoice {
+            invoice_id: "invoice1".to_string(),
+            issuer: "@@alice.sepolia-shinkai".to_string(),
+            payer: "@@bob.sepolia-shinkai/main".to_string(),
+            amount: Decimal::new(1050, 2),
+            asset: "USDC".to_string(),
+            memo: None,
+            reference: Some(InvoiceReference::ToolUsage {
+                tool_key: "local:::shinkai-tool-echo".to_string(),
+            }),
+            pay_to: None,
+            created_at: Utc::now(),
+            expires_at: Some(Utc::now()),
+            status: InvoiceStatus::Pending,
+            receipt: None,
+        };
+        assert!(invoice.is_expired());
+
+        let mut receipt = PaymentReceipt::new_signed(&invoice, Some("0xabc".to_string()), &signing_key);
+        assert!(receipt.verify(&verifying_key));
+        assert!(!receipt.verify(&other_verifying_key));
+
+        receipt.amount = Decimal::new(1, 2);
+        assert!(!receipt.verify(&verifying_key));
+    }
+}
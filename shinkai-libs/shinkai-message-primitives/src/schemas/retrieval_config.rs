@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use shinkai_vector_resources::vector_resource::{TraversalMethod, TraversalOption};
+
+/// How the vector searches of a job walk through the Vector Resources of its scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetrievalTraversal {
+    /// Only goes deeper into the highest scored Vector Resources at each level
+    Efficient,
+    /// Scores all the content at every level of depth
+    Exhaustive,
+}
+
+impl RetrievalTraversal {
+    pub fn traversal_method(&self) -> TraversalMethod {
+        match self {
+            RetrievalTraversal::Efficient => TraversalMethod::Efficient,
+            RetrievalTraversal::Exhaustive => TraversalMethod::Exhaustive,
+        }
+    }
+}
+
+/// Retrieval parameters of a job. Unset fields use the node's defaults, so a job only persists what it changes,
+/// and a message only overrides what it sets.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetrievalConfig {
+    /// Number of top results (proximity groups) taken from each search
+    pub top_k: Option<u64>,
+    /// Results scored below it (0.0 - 1.0) are dropped
+    pub min_score: Option<f32>,
+    pub traversal: Option<RetrievalTraversal>,
+    /// Levels of nested Vector Resources searched into. The root level is 0.
+    pub max_depth: Option<u64>,
+    /// Tokens of retrieved content added to the prompt at most
+    pub max_context_tokens: Option<usize>,
+    /// If the documents found are in another language than the query, the query is translated into their language
+    /// and searched again
+    pub translate_query: Option<bool>,
+}
+
+impl RetrievalConfig {
+    /// The fields set in `overrides` replace the ones of the config
+    pub fn merged_with(&self, overrides: &RetrievalConfig) -> RetrievalConfig {
+        RetrievalConfig {
+            top_k: overrides.top_k.or(self.top_k),
+            min_score: overrides.min_score.or(self.min_score),
+            traversal: overrides.traversal.or(self.traversal),
+            max_depth: overrides.max_depth.or(self.max_depth),
+            max_context_tokens: overrides.max_context_tokens.or(self.max_context_tokens),
+            translate_query: overrides.translate_query.or(self.translate_query),
+        }
+    }
+
+    pub fn traversal_method(&self, default: TraversalMethod) -> TraversalMethod {
+        self.traversal
+            .map(|traversal| traversal.traversal_method())
+            .unwrap_or(default)
+    }
+
+    /// Traversal options limiting the depth of the search, if set
+    pub fn traversal_options(&self) -> Vec<TraversalOption> {
+        self.max_depth.map(TraversalOption::UntilDepth).into_iter().collect()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.top_k == Some(0) {
+            return Err("top_k must be greater than 0".to_string());
+        }
+        if let Some(min_score) = self.min_score {
+            if !(0.0..=1.0).contains(&min_score) {
+                return Err("min_score must be between 0.0 and 1.0".to_string());
+            }
+        }
+        if self.max_context_tokens == Some(0) {
+            return Err("max_context_tokens must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merged_with_prefers_overrides() {
+        let job_config = RetrievalConfig {
+            top_k: Some(10),
+            min_score: Some(0.5),
+            traversal: Some(RetrievalTraversal::Efficient),
+            translate_query: Some(true),
+            ..Default::default()
+        };
+        let message_config = RetrievalConfig {
+            top_k: Some(3),
+            max_context_tokens: Some(2000),
+            ..Default::default()
+        };
+
+        let merged = job_config.merged_with(&message_config);
+
+        assert_eq!(merged.top_k, Some(3));
+        assert_eq!(merged.min_score, Some(0.5));
+        assert_eq!(merged.traversal, Some(RetrievalTraversal::Efficient));
+        assert_eq!(merged.max_context_tokens, Some(2000));
+        assert_eq!(merged.translate_query, Some(true));
+        assert!(RetrievalConfig {
+            min_score: Some(1.5),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
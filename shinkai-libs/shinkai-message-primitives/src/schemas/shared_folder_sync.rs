@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Change to an item of a shared folder, pushed back to the node sharing it by a subscriber
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedFolderPushItem {
+    /// Path of the item in the node sharing the folder
+    pub path: String,
+    /// Last modification of the item the change was made on, as synced from the node sharing the folder.
+    /// Not set if the subscriber created the item.
+    pub base_last_modified: Option<DateTime<Utc>>,
+    /// New version of the item as a base64 VRKai. The item is deleted if not set.
+    pub vrkai: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedFolderPush {
+    pub push_id: String,
+    pub shared_folder: String,
+    pub items: Vec<SharedFolderPushItem>,
+}
+
+/// Item changed in the node sharing the folder since the subscriber synced it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedFolderConflict {
+    pub path: String,
+    /// Not set if the item was deleted
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+/// Sent back to the subscriber once the node sharing the folder applied a push
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedFolderPushResult {
+    pub push_id: String,
+    pub shared_folder: String,
+    pub accepted: Vec<String>,
+    pub conflicts: Vec<SharedFolderConflict>,
+    /// Set if the whole push was rejected, e.g. the subscriber can't write to the folder
+    pub error: Option<String>,
+}
+
+impl SharedFolderConflict {
+    /// An item conflicts if it changed since the version the subscriber based its change on,
+    /// or if the subscriber created an item that already exists
+    pub fn check(
+        path: &str,
+        base_last_modified: Option<DateTime<Utc>>,
+        current_last_modified: Option<DateTime<Utc>>,
+    ) -> Option<Self> {
+        if base_last_modified == current_last_modified {
+            return None;
+        }
+        Some(SharedFolderConflict {
+            path: path.to_string(),
+            last_modified: current_last_modified,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_shared_folder_conflicts() {
+        let synced = Utc::now();
+
+        // Unchanged since the sync, or created by the subscriber and still missing
+        assert!(SharedFolderConflict::check("/team/doc", Some(synced), Some(synced)).is_none());
+        assert!(SharedFolderConflict::check("/team/new", None, None).is_none());
+
+        // Changed or deleted since the sync, or created in both nodes
+        let changed = SharedFolderConflict::check("/team/doc", Some(synced), Some(synced + Duration::seconds(5)));
+        assert_eq!(changed.unwrap().last_modified, Some(synced + Duration::seconds(5)));
+        assert!(SharedFolderConflict::check("/team/doc", Some(synced), None).is_some());
+        assert!(SharedFolderConflict::check("/team/new", None, Some(synced)).is_some());
+    }
+}
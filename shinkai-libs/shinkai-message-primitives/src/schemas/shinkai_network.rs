@@ -1,8 +1,498 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Largest frame accepted from the network, big enough for the VRKais of shared folders
+pub const MAX_NETWORK_FRAME_LENGTH: usize = 512 * 1024 * 1024;
+/// Largest identity accepted in the header of a frame
+pub const MAX_NETWORK_IDENTITY_LENGTH: usize = 1024;
+/// Time allowed by default to receive the rest of a frame once its first bytes arrived
+pub const DEFAULT_NETWORK_READ_TIMEOUT_SECS: u64 = 300;
+
+/// Frames from version 2 on start with this marker, then the version byte, then the header of version 1 frames. A
+/// total length of 0 is never valid in a version 1 frame, so both can be told apart.
+pub const NETWORK_FRAME_VERSION_MARKER: [u8; 4] = [0; 4];
+/// Frames without a version marker, understood by every node
+pub const NETWORK_FRAME_VERSION_1: u8 = 1;
+pub const NETWORK_FRAME_VERSION_2: u8 = 2;
+/// Latest frame version this node reads. Peers are sent frames of version 1 unless they announced a later one.
+pub const LATEST_NETWORK_FRAME_VERSION: u8 = NETWORK_FRAME_VERSION_2;
+
+/// Version of the protocol spoken between nodes. Nodes which never sent a `ProtocolHello` speak version 1.
+pub const NETWORK_PROTOCOL_VERSION_1: u32 = 1;
+pub const NETWORK_PROTOCOL_VERSION: u32 = 2;
+/// Hellos sent longer ago than this, or this far ahead of the clock of the node, are not accepted
+pub const PROTOCOL_HELLO_MAX_AGE_SECS: i64 = 300;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum NetworkMessageType {
     ShinkaiMessage,
     VRKaiPathPair,
     ProxyMessage,
-}
\ No newline at end of file
+    /// Announces the protocol version and capabilities of a node. Nodes which don't know this type drop the
+    /// connection, so it's sent on a connection of its own.
+    ProtocolHello,
+}
+
+impl NetworkMessageType {
+    /// Byte identifying the type of message in the header of a frame
+    pub fn header_byte(&self) -> u8 {
+        match self {
+            NetworkMessageType::ShinkaiMessage => 0x01,
+            NetworkMessageType::VRKaiPathPair => 0x02,
+            NetworkMessageType::ProxyMessage => 0x03,
+            NetworkMessageType::ProtocolHello => 0x04,
+        }
+    }
+
+    pub fn from_header_byte(byte: u8) -> Result<Self, NetworkFrameError> {
+        match byte {
+            0x01 => Ok(NetworkMessageType::ShinkaiMessage),
+            0x02 => Ok(NetworkMessageType::VRKaiPathPair),
+            0x03 => Ok(NetworkMessageType::ProxyMessage),
+            0x04 => Ok(NetworkMessageType::ProtocolHello),
+            _ => Err(NetworkFrameError::UnknownMessageType(byte)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkFrameError {
+    FrameTooLarge(usize),
+    IdentityTooLong(usize),
+    /// The identity and the header don't fit in the length of the frame
+    InconsistentLengths {
+        total_length: usize,
+        identity_length: usize,
+    },
+    UnknownMessageType(u8),
+    UnsupportedVersion(u8),
+    InvalidIdentity,
+    Truncated,
+}
+
+impl fmt::Display for NetworkFrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkFrameError::FrameTooLarge(length) => {
+                write!(
+                    f,
+                    "Frame of {} bytes is over the limit of {}",
+                    length, MAX_NETWORK_FRAME_LENGTH
+                )
+            }
+            NetworkFrameError::IdentityTooLong(length) => write!(
+                f,
+                "Identity of {} bytes is over the limit of {}",
+                length, MAX_NETWORK_IDENTITY_LENGTH
+            ),
+            NetworkFrameError::InconsistentLengths {
+                total_length,
+                identity_length,
+            } => write!(
+                f,
+                "Identity of {} bytes doesn't fit in a frame of {} bytes",
+                identity_length, total_length
+            ),
+            NetworkFrameError::UnknownMessageType(byte) => write!(f, "Unknown message type: {}", byte),
+            NetworkFrameError::UnsupportedVersion(version) => write!(f, "Unsupported frame version: {}", version),
+            NetworkFrameError::InvalidIdentity => write!(f, "Identity is not valid UTF-8"),
+            NetworkFrameError::Truncated => write!(f, "Frame is shorter than its header says"),
+        }
+    }
+}
+
+impl std::error::Error for NetworkFrameError {}
+
+/// Limits applied to the frames read from a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkFrameLimits {
+    pub max_frame_length: usize,
+    /// Time allowed to receive a whole frame once its first bytes arrived
+    pub read_timeout: Duration,
+}
+
+impl Default for NetworkFrameLimits {
+    fn default() -> Self {
+        NetworkFrameLimits {
+            max_frame_length: MAX_NETWORK_FRAME_LENGTH,
+            read_timeout: Duration::from_secs(DEFAULT_NETWORK_READ_TIMEOUT_SECS),
+        }
+    }
+}
+
+impl NetworkFrameLimits {
+    /// Reads NETWORK_MAX_FRAME_BYTES and NETWORK_READ_TIMEOUT_SECS. The frame length can be lowered below
+    /// `MAX_NETWORK_FRAME_LENGTH`, not raised over it.
+    pub fn new_from_env() -> Self {
+        let read_env = |key: &str| std::env::var(key).ok().and_then(|value| value.parse::<u64>().ok());
+        let defaults = Self::default();
+        NetworkFrameLimits {
+            max_frame_length: read_env("NETWORK_MAX_FRAME_BYTES")
+                .map(|bytes| (bytes as usize).min(MAX_NETWORK_FRAME_LENGTH))
+                .unwrap_or(defaults.max_frame_length),
+            read_timeout: read_env("NETWORK_READ_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.read_timeout),
+        }
+    }
+}
+
+/// Checks the version read after a `NETWORK_FRAME_VERSION_MARKER`
+pub fn check_network_frame_version(version: u8) -> Result<u8, NetworkFrameError> {
+    match version {
+        NETWORK_FRAME_VERSION_2..=LATEST_NETWORK_FRAME_VERSION => Ok(version),
+        _ => Err(NetworkFrameError::UnsupportedVersion(version)),
+    }
+}
+
+/// Builds the frame sending a payload to an identity, in the given frame version
+pub fn encode_network_frame(version: u8, identity: &str, message_type: &NetworkMessageType, payload: &[u8]) -> Vec<u8> {
+    let identity_bytes = identity.as_bytes();
+    // 4 bytes for the identity length and 1 for the message type
+    let total_length = (payload.len() + 1 + identity_bytes.len() + 4) as u32;
+
+    let mut frame = Vec::with_capacity(5 + 4 + total_length as usize);
+    if version > NETWORK_FRAME_VERSION_1 {
+        frame.extend_from_slice(&NETWORK_FRAME_VERSION_MARKER);
+        frame.push(version);
+    }
+    frame.extend_from_slice(&total_length.to_be_bytes());
+    frame.extend_from_slice(&(identity_bytes.len() as u32).to_be_bytes());
+    frame.extend_from_slice(identity_bytes);
+    frame.push(message_type.header_byte());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Features a node supports, as bit flags. Unknown flags announced by newer nodes are kept.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NetworkCapabilities(pub u64);
+
+impl NetworkCapabilities {
+    /// Reads frames of the versions up to `max_frame_version`
+    pub const VERSIONED_FRAMES: u64 = 1 << 0;
+
+    /// Capabilities of this node
+    pub fn supported() -> Self {
+        NetworkCapabilities(Self::VERSIONED_FRAMES)
+    }
+
+    pub fn contains(&self, flag: u64) -> bool {
+        self.0 & flag == flag
+    }
+}
+
+/// Payload of a `ProtocolHello` frame, signed by the identity key of the node sending it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ProtocolHello {
+    pub node_name: String,
+    pub protocol_version: u32,
+    pub max_frame_version: u8,
+    pub capabilities: NetworkCapabilities,
+    /// Hellos answering a hello aren't answered, so that two nodes don't keep greeting each other
+    pub is_reply: bool,
+    pub timestamp: DateTime<Utc>,
+    /// Random, so that two hellos sent at the same time are still told apart
+    pub nonce: String,
+    pub signature: String,
+}
+
+impl ProtocolHello {
+    /// The hello of this node
+    pub fn new_signed(node_name: String, is_reply: bool, signing_key: &SigningKey) -> Self {
+        let mut hello = ProtocolHello {
+            node_name,
+            protocol_version: NETWORK_PROTOCOL_VERSION,
+            max_frame_version: LATEST_NETWORK_FRAME_VERSION,
+            capabilities: NetworkCapabilities::supported(),
+            is_reply,
+            timestamp: Utc::now(),
+            nonce: {
+                let mut nonce = [0u8; 16];
+                rand::thread_rng().fill_bytes(&mut nonce);
+                hex::encode(nonce)
+            },
+            signature: String::new(),
+        };
+        hello.signature = hex::encode(signing_key.sign(hello.signed_content().as_bytes()).to_bytes());
+        hello
+    }
+
+    fn signed_content(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}:{}:{}",
+            self.node_name,
+            self.protocol_version,
+            self.max_frame_version,
+            self.capabilities.0,
+            self.is_reply,
+            self.timestamp.to_rfc3339_opts(SecondsFormat::Nanos, true),
+            self.nonce
+        )
+    }
+
+    /// Whether the hello was sent recently enough to be accepted, so that old hellos can't be replayed
+    pub fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        (now - self.timestamp).num_seconds().abs() <= PROTOCOL_HELLO_MAX_AGE_SECS
+    }
+
+    /// Whether the hello was signed by the key of the node it names
+    pub fn verify(&self, verifying_key: &VerifyingKey) -> bool {
+        let signature = hex::decode(&self.signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok());
+        match signature {
+            Some(signature) => verifying_key
+                .verify(self.signed_content().as_bytes(), &signature)
+                .is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// What a peer announced in its last `ProtocolHello`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PeerProtocol {
+    pub protocol_version: u32,
+    pub max_frame_version: u8,
+    pub capabilities: NetworkCapabilities,
+    pub announced_at: DateTime<Utc>,
+}
+
+impl PeerProtocol {
+    pub fn from_hello(hello: &ProtocolHello) -> Self {
+        PeerProtocol {
+            protocol_version: hello.protocol_version,
+            max_frame_version: hello.max_frame_version,
+            capabilities: hello.capabilities,
+            announced_at: hello.timestamp,
+        }
+    }
+
+    /// Latest frame version both this node and the peer read
+    pub fn frame_version(&self) -> u8 {
+        match self.capabilities.contains(NetworkCapabilities::VERSIONED_FRAMES) {
+            true => self
+                .max_frame_version
+                .clamp(NETWORK_FRAME_VERSION_1, LATEST_NETWORK_FRAME_VERSION),
+            false => NETWORK_FRAME_VERSION_1,
+        }
+    }
+}
+
+/// Lengths announced by the header of a frame, which is laid out as: total length (u32 BE, the length of what
+/// follows the identity length) | identity length (u32 BE) | identity | message type byte | payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkFrameLengths {
+    pub identity_length: usize,
+    pub payload_length: usize,
+}
+
+impl NetworkFrameLengths {
+    /// Validates the two length prefixes of a frame, before anything is allocated for the rest of it
+    pub fn parse(total_length_bytes: [u8; 4], identity_length_bytes: [u8; 4]) -> Result<Self, NetworkFrameError> {
+        Self::parse_with_limit(total_length_bytes, identity_length_bytes, MAX_NETWORK_FRAME_LENGTH)
+    }
+
+    pub fn parse_with_limit(
+        total_length_bytes: [u8; 4],
+        identity_length_bytes: [u8; 4],
+        max_frame_length: usize,
+    ) -> Result<Self, NetworkFrameError> {
+        let total_length = u32::from_be_bytes(total_length_bytes) as usize;
+        let identity_length = u32::from_be_bytes(identity_length_bytes) as usize;
+        if total_length > max_frame_length {
+            return Err(NetworkFrameError::FrameTooLarge(total_length));
+        }
+        if identity_length > MAX_NETWORK_IDENTITY_LENGTH {
+            return Err(NetworkFrameError::IdentityTooLong(identity_length));
+        }
+        // 4 bytes for the identity length and 1 for the message type
+        let inconsistent_lengths = NetworkFrameError::InconsistentLengths {
+            total_length,
+            identity_length,
+        };
+        let payload_length = total_length
+            .checked_sub(4 + 1 + identity_length)
+            .ok_or(inconsistent_lengths)?;
+        Ok(NetworkFrameLengths {
+            identity_length,
+            payload_length,
+        })
+    }
+}
+
+/// A frame received from the network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkFrame<'a> {
+    pub version: u8,
+    pub identity: String,
+    pub message_type: NetworkMessageType,
+    pub payload: &'a [u8],
+}
+
+impl<'a> NetworkFrame<'a> {
+    /// Parses a whole frame held in memory. Bytes after the frame are ignored.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, NetworkFrameError> {
+        let prefix = |start: usize| -> Result<[u8; 4], NetworkFrameError> {
+            bytes
+                .get(start..start + 4)
+                .and_then(|slice| slice.try_into().ok())
+                .ok_or(NetworkFrameError::Truncated)
+        };
+        let (version, start) = match prefix(0)? {
+            NETWORK_FRAME_VERSION_MARKER => {
+                let version = *bytes.get(4).ok_or(NetworkFrameError::Truncated)?;
+                (check_network_frame_version(version)?, 5)
+            }
+            _ => (NETWORK_FRAME_VERSION_1, 0),
+        };
+        let lengths = NetworkFrameLengths::parse(prefix(start)?, prefix(start + 4)?)?;
+
+        let identity_start = start + 8;
+        let identity_end = identity_start + lengths.identity_length;
+        let identity_bytes = bytes
+            .get(identity_start..identity_end)
+            .ok_or(NetworkFrameError::Truncated)?;
+        let identity = String::from_utf8(identity_bytes.to_vec()).map_err(|_| NetworkFrameError::InvalidIdentity)?;
+        let header_byte = *bytes.get(identity_end).ok_or(NetworkFrameError::Truncated)?;
+        let message_type = NetworkMessageType::from_header_byte(header_byte)?;
+        let payload = bytes
+            .get(identity_end + 1..identity_end + 1 + lengths.payload_length)
+            .ok_or(NetworkFrameError::Truncated)?;
+
+        Ok(NetworkFrame {
+            version,
+            identity,
+            message_type,
+            payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shinkai_utils::signatures::unsafe_deterministic_signature_keypair;
+
+    fn frame_with_header_byte(identity: &str, header_byte: u8, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&((payload.len() + 1 + identity.len() + 4) as u32).to_be_bytes());
+        bytes.extend_from_slice(&(identity.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(identity.as_bytes());
+        bytes.push(header_byte);
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_network_frame() {
+        let bytes = encode_network_frame(
+            NETWORK_FRAME_VERSION_1,
+            "@@node1.shinkai",
+            &NetworkMessageType::ShinkaiMessage,
+            b"{}",
+        );
+        assert_eq!(bytes, frame_with_header_byte("@@node1.shinkai", 0x01, b"{}"));
+        let parsed = NetworkFrame::parse(&bytes).unwrap();
+        assert_eq!(parsed.identity, "@@node1.shinkai");
+        assert_eq!(parsed.message_type, NetworkMessageType::ShinkaiMessage);
+        assert_eq!(parsed.payload, b"{}");
+
+        // Every truncation of a valid frame is an error, never a panic
+        for end in 0..bytes.len() {
+            assert!(NetworkFrame::parse(&bytes[..end]).is_err());
+        }
+        assert_eq!(
+            NetworkFrame::parse(&frame_with_header_byte("@@node1.shinkai", 0x09, b"{}")),
+            Err(NetworkFrameError::UnknownMessageType(0x09))
+        );
+    }
+
+    #[test]
+    fn test_parse_versioned_network_frame() {
+        let bytes = encode_network_frame(
+            NETWORK_FRAME_VERSION_2,
+            "@@node1.shinkai",
+            &NetworkMessageType::VRKaiPathPair,
+            b"vrkai",
+        );
+        assert_eq!(bytes[..5], [0, 0, 0, 0, NETWORK_FRAME_VERSION_2]);
+        let parsed = NetworkFrame::parse(&bytes).unwrap();
+        assert_eq!(parsed.version, NETWORK_FRAME_VERSION_2);
+        assert_eq!(parsed.identity, "@@node1.shinkai");
+        assert_eq!(parsed.message_type, NetworkMessageType::VRKaiPathPair);
+        assert_eq!(parsed.payload, b"vrkai");
+
+        let mut future_version = bytes.clone();
+        future_version[4] = LATEST_NETWORK_FRAME_VERSION + 1;
+        assert_eq!(
+            NetworkFrame::parse(&future_version),
+            Err(NetworkFrameError::UnsupportedVersion(LATEST_NETWORK_FRAME_VERSION + 1))
+        );
+        // 5 bytes of payload, 1 for the message type, 15 for the identity and 4 for its length
+        assert_eq!(
+            NetworkFrameLengths::parse_with_limit(25u32.to_be_bytes(), 15u32.to_be_bytes(), 10),
+            Err(NetworkFrameError::FrameTooLarge(25))
+        );
+    }
+
+    #[test]
+    fn test_protocol_hello() {
+        let (signing_key, verifying_key) = unsafe_deterministic_signature_keypair(0);
+        let hello = ProtocolHello::new_signed("@@node1.shinkai".to_string(), false, &signing_key);
+        assert!(hello.verify(&verifying_key));
+        let received: ProtocolHello = serde_json::from_str(&serde_json::to_string(&hello).unwrap()).unwrap();
+        assert!(received.verify(&verifying_key));
+        assert_eq!(
+            PeerProtocol::from_hello(&hello).frame_version(),
+            LATEST_NETWORK_FRAME_VERSION
+        );
+
+        // A node can't announce more than it signed
+        let mut tampered = hello.clone();
+        tampered.max_frame_version = 9;
+        assert!(!tampered.verify(&verifying_key));
+        let (_, other_key) = unsafe_deterministic_signature_keypair(1);
+        assert!(!hello.verify(&other_key));
+
+        // Nor replay an old hello, or move its time forward
+        assert!(hello.is_fresh(Utc::now()));
+        assert!(!hello.is_fresh(hello.timestamp + chrono::Duration::seconds(PROTOCOL_HELLO_MAX_AGE_SECS + 1)));
+        assert!(!hello.is_fresh(hello.timestamp - chrono::Duration::seconds(PROTOCOL_HELLO_MAX_AGE_SECS + 1)));
+        let mut delayed = hello.clone();
+        delayed.timestamp = Utc::now() + chrono::Duration::seconds(60);
+        assert!(!delayed.verify(&verifying_key));
+        let mut other_nonce = hello.clone();
+        other_nonce.nonce = "00".repeat(16);
+        assert!(!other_nonce.verify(&verifying_key));
+
+        let mut without_versioned_frames = PeerProtocol::from_hello(&hello);
+        without_versioned_frames.capabilities = NetworkCapabilities::default();
+        assert_eq!(without_versioned_frames.frame_version(), NETWORK_FRAME_VERSION_1);
+    }
+
+    #[test]
+    fn test_parse_network_frame_lengths() {
+        // Used to underflow when computing the length of the payload
+        assert_eq!(
+            NetworkFrameLengths::parse(2u32.to_be_bytes(), 10u32.to_be_bytes()),
+            Err(NetworkFrameError::InconsistentLengths {
+                total_length: 2,
+                identity_length: 10
+            })
+        );
+        assert_eq!(
+            NetworkFrameLengths::parse(u32::MAX.to_be_bytes(), 10u32.to_be_bytes()),
+            Err(NetworkFrameError::FrameTooLarge(u32::MAX as usize))
+        );
+        assert_eq!(
+            NetworkFrameLengths::parse(100u32.to_be_bytes(), u32::MAX.to_be_bytes()),
+            Err(NetworkFrameError::IdentityTooLong(u32::MAX as usize))
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::shinkai_message::shinkai_message::ShinkaiMessage;
+
+use super::shinkai_name::ShinkaiName;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceRole {
+    /// Manages the members, folders and inboxes of the workspace. Only the node that created it.
+    Owner,
+    /// Writes to the inboxes of the workspace
+    Editor,
+    /// Only reads the inboxes and folders
+    Viewer,
+}
+
+impl WorkspaceRole {
+    pub fn can_write(&self) -> bool {
+        matches!(self, WorkspaceRole::Owner | WorkspaceRole::Editor)
+    }
+
+    pub fn can_manage(&self) -> bool {
+        matches!(self, WorkspaceRole::Owner)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceMember {
+    /// Profile of the member, e.g. `@@bob.sepolia-shinkai/main`
+    pub identity: String,
+    pub role: WorkspaceRole,
+}
+
+/// Inboxes and VectorFS folders replicated between the nodes of a team.
+/// The node of the owner is the source of truth: it sends every new version to the members.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workspace {
+    pub workspace_id: String,
+    pub name: String,
+    /// Profile that created the workspace. The folders are shared from it.
+    pub owner: String,
+    pub members: Vec<WorkspaceMember>,
+    /// VectorFS paths of the folders, in the profile of the owner
+    pub folders: Vec<String>,
+    pub inboxes: Vec<String>,
+    /// Increased on every change, so members ignore updates older than the one they have
+    pub version: u64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Workspace {
+    /// Role of the node of the identity, if it's a member. The owner is always a member.
+    pub fn role_of(&self, identity: &ShinkaiName) -> Option<WorkspaceRole> {
+        let node = identity.extract_node();
+        if ShinkaiName::new(self.owner.clone()).map_or(false, |owner| owner.extract_node() == node) {
+            return Some(WorkspaceRole::Owner);
+        }
+        self.members
+            .iter()
+            .find(|member| ShinkaiName::new(member.identity.clone()).map_or(false, |name| name.extract_node() == node))
+            .map(|member| member.role)
+    }
+
+    /// Profiles the updates and messages are sent to, the owner included
+    pub fn participants(&self) -> Vec<String> {
+        let mut participants = vec![self.owner.clone()];
+        participants.extend(self.members.iter().map(|member| member.identity.clone()));
+        participants
+    }
+}
+
+/// Messages of a workspace inbox written on the node sending them, replicated to the other members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceInboxMessages {
+    pub workspace_id: String,
+    pub inbox_name: String,
+    pub messages: Vec<ShinkaiMessage>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_roles_by_node() {
+        let workspace = Workspace {
+            workspace_id: "ws1".to_string(),
+            name: "Team".to_string(),
+            owner: "@@alice.shinkai/main".to_string(),
+            members: vec![
+                WorkspaceMember {
+                    identity: "@@bob.shinkai/main".to_string(),
+                    role: WorkspaceRole::Editor,
+                },
+                WorkspaceMember {
+                    identity: "@@carol.shinkai/main".to_string(),
+                    role: WorkspaceRole::Viewer,
+                },
+            ],
+            folders: vec!["/team".to_string()],
+            inboxes: vec![],
+            version: 1,
+            updated_at: Utc::now(),
+        };
+
+        let role = |name: &str| workspace.role_of(&ShinkaiName::new(name.to_string()).unwrap());
+        assert_eq!(role("@@alice.shinkai"), Some(WorkspaceRole::Owner));
+        assert_eq!(role("@@bob.shinkai/other"), Some(WorkspaceRole::Editor));
+        assert!(role("@@bob.shinkai").unwrap().can_write());
+        assert!(!role("@@carol.shinkai").unwrap().can_write());
+        assert_eq!(role("@@mallory.shinkai"), None);
+        assert_eq!(workspace.participants().len(), 3);
+    }
+}
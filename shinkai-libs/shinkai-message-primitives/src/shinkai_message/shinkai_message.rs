@@ -43,6 +43,14 @@ pub struct NodeApiData {
     pub parent_hash: String,
     pub node_message_hash: String,
     pub node_timestamp: String,
+    /// Content of the message after its last edit, set by the node when returning it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edited_content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edited_at: Option<String>,
+    /// Set by the node when returning a deleted message, whose content is kept for the hashes of the branch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
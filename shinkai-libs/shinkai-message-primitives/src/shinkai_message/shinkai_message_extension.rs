@@ -143,20 +143,17 @@ impl ShinkaiMessage {
         serde_json::to_vec(&self).map_err(|err| ShinkaiMessageError::SerializationError(err.to_string()))
     }
 
+    /// Decodes a message received from the network. Any bytes either decode or give an error, they never panic.
     pub fn decode_message_result(encoded: Vec<u8>) -> Result<Self, ShinkaiMessageError> {
-        // Try to deserialize as JSON first
-        if let Ok(str_data) = std::str::from_utf8(&encoded) {
-            if str_data.starts_with('{') && str_data.ends_with('}') {
-                if let Ok(message) = serde_json::from_str::<ShinkaiMessage>(str_data) {
-                    return Ok(message);
-                }
-            }
+        let str_data = std::str::from_utf8(&encoded)
+            .map_err(|e| ShinkaiMessageError::DeserializationError(format!("Failed to decode message: {}", e)))?;
+        if !(str_data.starts_with('{') && str_data.ends_with('}')) {
+            return Err(ShinkaiMessageError::DeserializationError(
+                "Failed to decode message: not a JSON object".to_string(),
+            ));
         }
-
-        // If JSON deserialization failed, return an error
-        Err(ShinkaiMessageError::DecryptionError(
-            "Failed to decode message".to_string(),
-        ))
+        serde_json::from_str::<ShinkaiMessage>(str_data)
+            .map_err(|e| ShinkaiMessageError::DeserializationError(format!("Failed to decode message: {}", e)))
     }
 
     pub fn to_string(&self) -> Result<String, ShinkaiMessageError> {
@@ -1,8 +1,15 @@
+use crate::schemas::payment_invoice::InvoiceReference;
 use crate::schemas::sheet::{APIColumnDefinition, ColumnUuid, RowUuid, UuidString};
 use crate::schemas::shinkai_subscription_req::{FolderSubscription, SubscriptionPayment};
+use crate::schemas::folder_publication::PublicationAccess;
+use crate::schemas::retrieval_config::RetrievalConfig;
+use crate::schemas::workspace::{WorkspaceMember, WorkspaceRole};
+use crate::schemas::contact::ContactTrustLevel;
 use crate::schemas::{inbox_name::InboxName, llm_providers::serialized_llm_provider::SerializedLLMProvider};
 use crate::shinkai_utils::job_scope::JobScope;
+use crate::shinkai_utils::shinkai_logging::{ShinkaiLogLevel, ShinkaiLogOption};
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 use std::fmt;
@@ -63,6 +70,7 @@ pub enum MessageSchemaType {
     GetProcessingPreference,
     APIRemoveToolkit,
     APIAddToolkit,
+    APIInstallToolkitFromURL,
     APIListToolkits,
     GetNotificationsBeforeTimestamp,
     GetLastNotifications,
@@ -87,6 +95,15 @@ pub enum MessageSchemaType {
     ListAllShinkaiTools,
     GetShinkaiTool,
     SearchShinkaiTool,
+    PaymentInvoice,
+    PaymentReceipt,
+    WorkspaceUpdate,
+    WorkspaceInboxMessages,
+    SharedFolderPush,
+    SharedFolderPushResult,
+    InboxMessageChange,
+    MessageWithAttachments,
+    AttachmentChunk,
 }
 
 impl MessageSchemaType {
@@ -144,6 +161,7 @@ impl MessageSchemaType {
             "GetProcessingPreference" => Some(Self::GetProcessingPreference),
             "APIRemoveToolkit" => Some(Self::APIRemoveToolkit),
             "APIAddToolkit" => Some(Self::APIAddToolkit),
+            "APIInstallToolkitFromURL" => Some(Self::APIInstallToolkitFromURL),
             "APIListToolkits" => Some(Self::APIListToolkits),
             "GetNotificationsBeforeTimestamp" => Some(Self::GetNotificationsBeforeTimestamp),
             "GetLastNotifications" => Some(Self::GetLastNotifications),
@@ -168,6 +186,15 @@ impl MessageSchemaType {
             "ListAllShinkaiTools" => Some(Self::ListAllShinkaiTools),
             "GetShinkaiTool" => Some(Self::GetShinkaiTool),
             "SearchShinkaiTool" => Some(Self::SearchShinkaiTool),
+            "PaymentInvoice" => Some(Self::PaymentInvoice),
+            "PaymentReceipt" => Some(Self::PaymentReceipt),
+            "WorkspaceUpdate" => Some(Self::WorkspaceUpdate),
+            "WorkspaceInboxMessages" => Some(Self::WorkspaceInboxMessages),
+            "SharedFolderPush" => Some(Self::SharedFolderPush),
+            "SharedFolderPushResult" => Some(Self::SharedFolderPushResult),
+            "InboxMessageChange" => Some(Self::InboxMessageChange),
+            "MessageWithAttachments" => Some(Self::MessageWithAttachments),
+            "AttachmentChunk" => Some(Self::AttachmentChunk),
             _ => None,
         }
     }
@@ -225,6 +252,7 @@ impl MessageSchemaType {
             Self::GetProcessingPreference => "GetProcessingPreference",
             Self::APIRemoveToolkit => "APIRemoveToolkit",
             Self::APIAddToolkit => "APIAddToolkit",
+            Self::APIInstallToolkitFromURL => "APIInstallToolkitFromURL",
             Self::APIListToolkits => "APIListToolkits",
             Self::GetNotificationsBeforeTimestamp => "GetNotificationsBeforeTimestamp",
             Self::GetLastNotifications => "GetLastNotifications",
@@ -249,6 +277,15 @@ impl MessageSchemaType {
             Self::ListAllShinkaiTools => "ListAllShinkaiTools",
             Self::GetShinkaiTool => "GetShinkaiTool",
             Self::SearchShinkaiTool => "SearchShinkaiTool",
+            Self::PaymentInvoice => "PaymentInvoice",
+            Self::PaymentReceipt => "PaymentReceipt",
+            Self::WorkspaceUpdate => "WorkspaceUpdate",
+            Self::WorkspaceInboxMessages => "WorkspaceInboxMessages",
+            Self::SharedFolderPush => "SharedFolderPush",
+            Self::SharedFolderPushResult => "SharedFolderPushResult",
+            Self::InboxMessageChange => "InboxMessageChange",
+            Self::MessageWithAttachments => "MessageWithAttachments",
+            Self::AttachmentChunk => "AttachmentChunk",
             Self::Empty => "",
         }
     }
@@ -268,6 +305,9 @@ pub struct SymmetricKeyExchange {
 pub struct JobCreationInfo {
     pub scope: JobScope,
     pub is_hidden: Option<bool>,
+    /// Retrieval parameters persisted in the job
+    #[serde(default)]
+    pub retrieval: Option<RetrievalConfig>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -288,6 +328,9 @@ pub struct JobMessage {
     pub workflow_name: Option<String>,
     pub sheet_job_data: Option<String>,
     pub callback: Option<Box<CallbackAction>>,
+    /// Overrides the retrieval parameters of the job for this message only
+    #[serde(default)]
+    pub retrieval: Option<RetrievalConfig>,
 }
 
 fn deserialize_workflow_name<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
@@ -312,6 +355,30 @@ pub struct V2ChatMessage {
     pub receiver_subidentity: String,
     pub node_api_data: NodeApiData,
     pub inbox: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<MessageAttachment>,
+}
+
+/// File attached to a message. Its content is encrypted in chunks with the key of the message and transferred to
+/// the nodes of the recipients apart from it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MessageAttachment {
+    pub attachment_id: String,
+    pub file_name: String,
+    pub size: u64,
+    pub chunk_count: u32,
+    /// Blake3 hash of the content, hex encoded
+    pub content_hash: String,
+}
+
+/// Content of the messages with the MessageWithAttachments schema
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MessageWithAttachments {
+    pub content: String,
+    pub attachments: Vec<MessageAttachment>,
+    /// Hex encoded AES key the attachments of this message are encrypted with. It's only readable by the
+    /// participants, like the rest of the message.
+    pub key: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -433,6 +500,9 @@ pub struct APIConvertFilesAndSaveToFolder {
     pub path: String,
     pub file_inbox: String,
     pub file_datetime: Option<DateTime<Utc>>,
+    /// Saves the files even if they look like duplicates of items of the folder
+    #[serde(default)]
+    pub allow_duplicates: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -467,6 +537,19 @@ pub struct APIVecFsCreateFolder {
     pub folder_name: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIVecFsCreateLink {
+    /// Folder the link is created in
+    pub path: String,
+    pub link_name: String,
+    pub target_path: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIVecFsRetrieveSourceFileMap {
+    pub path: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct APIVecFsDeleteFolder {
     pub path: String,
@@ -483,6 +566,55 @@ pub struct APIVecFsMoveFolder {
     pub destination_path: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIVecFSGetFolderStats {
+    pub path: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIVecFSExportFolderAsVRPack {
+    pub path: String,
+}
+
+/// `local_path` is a directory on the machine the node runs on
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIVecFSExportMarkdownBundle {
+    pub path: String,
+    pub local_path: String,
+}
+
+/// `local_path` is a directory on the machine the node runs on
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIVecFSImportMarkdownBundle {
+    pub local_path: String,
+    pub path: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIVecFSGetItemVersions {
+    pub path: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIVecFSDiffItemVersion {
+    pub path: String,
+    pub version_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIVecFSRestoreItemVersion {
+    pub path: String,
+    pub version_id: u64,
+}
+
+/// Sets the embedding model of a folder, ie. `clip/openai/clip-vit-base-patch32`. None removes it, so the folder
+/// uses the one of its parent folders (or the profile's default) again.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIVecFSSetFolderEmbeddingModel {
+    pub path: String,
+    pub embedding_model: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct APIVecFsCopyFolder {
     pub origin_path: String,
@@ -555,6 +687,135 @@ pub struct APIAddOllamaModels {
     pub models: Vec<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIGetRecentLogs {
+    /// Only entries of this level or more severe
+    pub level: Option<ShinkaiLogLevel>,
+    pub module: Option<ShinkaiLogOption>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIRunDbMaintenance {
+    /// Compacts the main and VectorFS databases before reporting their sizes
+    #[serde(default)]
+    pub compact: bool,
+    /// Compacts the databases every this many hours. 0 stops the periodic compaction, None keeps the current
+    /// schedule.
+    pub compaction_interval_hours: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIRelocateStorage {
+    /// Absolute path of the new data directory, which must be empty or not exist yet
+    pub new_data_dir: String,
+    /// Deletes the data from the current data directory once the copy is verified
+    #[serde(default)]
+    pub remove_old_copy: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerBanAction {
+    /// Refuses the connections of the peer until the ban is cleared
+    Ban,
+    /// Never bans nor deprioritizes the peer
+    Allow,
+    /// Removes the ban or allow and forgets the offenses of the peer
+    Clear,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APISetPeerBan {
+    /// IP or node identity of the peer, e.g. `@@bob.sepolia-shinkai`
+    pub peer: String,
+    pub action: PeerBanAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APICreateInvoice {
+    /// Node or profile to pay the invoice, e.g. `@@bob.sepolia-shinkai/main`
+    pub payer: String,
+    pub amount: Decimal,
+    pub asset: String,
+    pub memo: Option<String>,
+    pub reference: Option<InvoiceReference>,
+    /// Address the amount is sent to when the invoice is paid on chain
+    #[serde(default)]
+    pub pay_to: Option<String>,
+    /// The invoice can't be paid this long after it's created. It never expires if not set.
+    pub expires_in_secs: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIMarkInvoicePaid {
+    pub invoice_id: String,
+    pub transaction_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIPayInvoice {
+    pub invoice_id: String,
+}
+
+/// Sent by the external signer of the wallet once it signed and sent a transaction, or refused to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APICompleteWalletTransaction {
+    pub transaction_id: String,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIRenameDevice {
+    /// Full identity name of the device, e.g. `@@node.shinkai/main/device/phone`
+    pub device_name: String,
+    pub display_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIRevokeDevice {
+    pub device_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIRevokeRegistrationCode {
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIExportProfileData {
+    pub profile: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIDeleteProfile {
+    pub profile: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIRemoveWatchedFolder {
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIRemoveCloudConnector {
+    pub id: String,
+}
+
+/// Everything the first run of a node needs, so it can be set up in a single call
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIInitializeNodeInteractive {
+    pub profile_encryption_pk: String,
+    pub profile_identity_pk: String,
+    /// Default embedding model of the node, e.g. `snowflake-arctic-embed:xs`. Keeps the one of the
+    /// environment if not set.
+    pub embedding_model: Option<String>,
+    /// Added to the main profile with the LLM providers of the environment
+    #[serde(default)]
+    pub llm_providers: Vec<SerializedLLMProvider>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct APIGetMySubscribers {
     pub path: String,
@@ -572,18 +833,185 @@ pub struct APIGetNotificationsBeforeTimestamp {
     pub count: usize,
 }
 
+/// Content of the message to list the smart inboxes of a profile. A plain profile name is also accepted, which
+/// leaves the archived inboxes out.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIGetAllSmartInboxesForProfile {
+    pub profile: String,
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct APIChangeJobAgentRequest {
     pub job_id: String,
     pub new_agent_id: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIGetJobStatus {
+    pub job_id: String,
+    /// Waits up to this long for a queued or running job to change state before answering
+    pub wait_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APISetJobPlanning {
+    pub job_id: String,
+    /// The messages of the job are decomposed into a plan of steps before being answered
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APISetJobRetrievalConfig {
+    pub job_id: String,
+    /// Replaces the retrieval parameters of the job
+    pub config: RetrievalConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APICreateWorkspace {
+    pub name: String,
+    #[serde(default)]
+    pub members: Vec<WorkspaceMember>,
+    /// VectorFS paths of the folders to share with the members
+    #[serde(default)]
+    pub folders: Vec<String>,
+    #[serde(default)]
+    pub inboxes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIUpdateWorkspace {
+    pub workspace_id: String,
+    pub name: Option<String>,
+    /// Replaces the folders of the workspace if set
+    pub folders: Option<Vec<String>>,
+    /// Replaces the inboxes of the workspace if set
+    pub inboxes: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APISetWorkspaceMember {
+    pub workspace_id: String,
+    pub identity: String,
+    /// The member is removed if not set
+    pub role: Option<WorkspaceRole>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIWorkspaceInvitation {
+    pub workspace_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APISetContact {
+    /// Node or profile of the contact. Replaces the contact with the same identity.
+    pub identity: String,
+    #[serde(default)]
+    pub nickname: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub trust_level: ContactTrustLevel,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIRemoveContact {
+    pub identity: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APISetContactsAllowlistMode {
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIAcceptMessageRequest {
+    pub inbox_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APISetSharedFolderWriters {
+    pub path: String,
+    /// Nodes or profiles allowed to push changes to the folder. Two-way sync is off if empty.
+    pub writers: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIPushSharedFolderChanges {
+    pub streamer_node_name: String,
+    pub streamer_profile_name: String,
+    pub shared_folder: String,
+    /// Paths of the items changed, as in the node sharing the folder. Items missing locally are deleted.
+    pub paths: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIPublishFolder {
+    pub path: String,
+    pub access: PublicationAccess,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIUnpublishFolder {
+    pub publication_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APICreateJobForPath {
+    /// VectorFS folder the job chats with
+    pub path: String,
+    /// Agent of the job. Defaults to the agent bound to the folder if not set.
+    pub agent: Option<String>,
+    pub is_hidden: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APISetFolderAgent {
+    pub path: String,
+    /// The binding is removed if not set
+    pub agent: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FolderAgentBinding {
+    pub path: String,
+    pub agent: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APIGetOperationStatus {
+    /// Every operation that didn't stop yet if it's not set
+    pub operation_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct APICancelOperation {
+    pub operation_id: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TopicSubscription {
     pub topic: WSTopic,
     pub subtopic: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct APIInstallToolkitFromURL {
+    pub url: String,
+    /// Hex encoded ed25519 public key the package must be signed with
+    pub expected_public_key: Option<String>,
+    /// Hosts the user agreed to let the toolkit reach
+    #[serde(default)]
+    pub accepted_network_hosts: Vec<String>,
+    /// VectorFS paths the user agreed to let the toolkit read
+    #[serde(default)]
+    pub accepted_vector_fs_read: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct APISetWorkflow {
     pub workflow_raw: String,
@@ -656,6 +1084,8 @@ pub enum WSTopic {
     SmartInboxes,
     Sheet,
     SheetList,
+    /// Notifications of a profile, the subtopic is the name of the profile
+    Notifications,
 }
 
 impl fmt::Display for WSTopic {
@@ -665,6 +1095,7 @@ impl fmt::Display for WSTopic {
             WSTopic::SmartInboxes => write!(f, "smart_inboxes"),
             WSTopic::Sheet => write!(f, "sheet"),
             WSTopic::SheetList => write!(f, "sheet_list"),
+            WSTopic::Notifications => write!(f, "notifications"),
         }
     }
 }
@@ -673,6 +1104,12 @@ impl fmt::Display for WSTopic {
 pub struct RegistrationCodeRequest {
     pub permissions: IdentityPermissions,
     pub code_type: RegistrationCodeType,
+    /// Seconds until the code expires, it never does if not set
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+    /// Times the code can be used, unlimited if not set
+    #[serde(default)]
+    pub max_uses: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -1,6 +1,6 @@
 use blake3::Hasher;
 use aes_gcm::Aes256Gcm;
-use aes_gcm::aead::{generic_array::GenericArray};
+use aes_gcm::aead::{generic_array::GenericArray, Aead, Payload};
 use aes_gcm::KeyInit;
 
 use rand::RngCore;
@@ -51,4 +51,103 @@ pub fn hex_string_to_aes_nonce(hex_string: &str) -> Result<[u8; 12], hex::FromHe
     let mut nonce = [0u8; 12];
     nonce.copy_from_slice(&bytes);
     Ok(nonce)
-}
\ No newline at end of file
+}
+
+/// Size of the plain chunks attachments are encrypted and transferred in
+pub const ATTACHMENT_CHUNK_SIZE: usize = 256 * 1024;
+/// Largest encrypted chunk, a full plain chunk with its 12 bytes nonce and 16 bytes tag
+pub const ENCRYPTED_ATTACHMENT_CHUNK_SIZE: usize = ATTACHMENT_CHUNK_SIZE + 12 + 16;
+
+/// Encrypts the file in chunks of ATTACHMENT_CHUNK_SIZE bytes. Each chunk is its random nonce followed by the
+/// ciphertext, and is authenticated with its position and the number of chunks so they can't be reordered or
+/// dropped. Empty files are a single empty chunk.
+pub fn encrypt_file_chunks(key: &[u8; 32], data: &[u8]) -> Result<Vec<Vec<u8>>, aes_gcm::Error> {
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(key));
+    let plain_chunks: Vec<&[u8]> = if data.is_empty() {
+        vec![data]
+    } else {
+        data.chunks(ATTACHMENT_CHUNK_SIZE).collect()
+    };
+    let chunk_count = plain_chunks.len() as u32;
+
+    plain_chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, plain_chunk)| {
+            let mut nonce = [0u8; 12];
+            rand::thread_rng().fill_bytes(&mut nonce);
+            let aad = chunk_aad(index as u32, chunk_count);
+            let ciphertext = cipher.encrypt(
+                GenericArray::from_slice(&nonce),
+                Payload {
+                    msg: plain_chunk,
+                    aad: &aad,
+                },
+            )?;
+            Ok([nonce.to_vec(), ciphertext].concat())
+        })
+        .collect()
+}
+
+/// Decrypts the chunks made by encrypt_file_chunks, in order, back into the file
+pub fn decrypt_file_chunks(key: &[u8; 32], chunks: &[Vec<u8>]) -> Result<Vec<u8>, aes_gcm::Error> {
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(key));
+    let chunk_count = chunks.len() as u32;
+
+    let mut data = Vec::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        if chunk.len() < 12 {
+            return Err(aes_gcm::Error);
+        }
+        let (nonce, ciphertext) = chunk.split_at(12);
+        let aad = chunk_aad(index as u32, chunk_count);
+        let plain_chunk = cipher.decrypt(
+            GenericArray::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: &aad,
+            },
+        )?;
+        data.extend(plain_chunk);
+    }
+    Ok(data)
+}
+
+fn chunk_aad(index: u32, chunk_count: u32) -> [u8; 8] {
+    let mut aad = [0u8; 8];
+    aad[..4].copy_from_slice(&index.to_le_bytes());
+    aad[4..].copy_from_slice(&chunk_count.to_le_bytes());
+    aad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_chunks_roundtrip() {
+        let key = unsafe_deterministic_aes_encryption_key(0);
+        let data: Vec<u8> = (0..ATTACHMENT_CHUNK_SIZE * 2 + 10).map(|i| (i % 251) as u8).collect();
+
+        let chunks = encrypt_file_chunks(&key, &data).unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(decrypt_file_chunks(&key, &chunks).unwrap(), data);
+
+        let empty_chunks = encrypt_file_chunks(&key, &[]).unwrap();
+        assert_eq!(empty_chunks.len(), 1);
+        assert!(decrypt_file_chunks(&key, &empty_chunks).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_file_chunks_reject_tampering() {
+        let key = unsafe_deterministic_aes_encryption_key(0);
+        let data = vec![7u8; ATTACHMENT_CHUNK_SIZE + 1];
+        let chunks = encrypt_file_chunks(&key, &data).unwrap();
+
+        // Chunks can't be dropped, reordered or decrypted with another key
+        assert!(decrypt_file_chunks(&key, &chunks[..1]).is_err());
+        let reordered = vec![chunks[1].clone(), chunks[0].clone()];
+        assert!(decrypt_file_chunks(&key, &reordered).is_err());
+        assert!(decrypt_file_chunks(&unsafe_deterministic_aes_encryption_key(1), &chunks).is_err());
+    }
+}
@@ -1,5 +1,11 @@
 use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, Once};
 
 // Conditional compilation: Only include tracing imports for non-WASM targets
@@ -9,16 +15,55 @@ use tracing::{debug, error, info, span, Level};
 static INIT: Once = Once::new();
 static TELEMETRY: Mutex<Option<Arc<dyn ShinkaiTelemetry + Send + Sync>>> = Mutex::new(None);
 
+static REQUEST_ID_PROVIDER: Mutex<Option<fn() -> Option<String>>> = Mutex::new(None);
+/// Levels set at runtime, they take precedence over the LOG_* environment variables
+static LOG_LEVELS: Mutex<Option<HashMap<ShinkaiLogOption, Option<ShinkaiLogLevel>>>> = Mutex::new(None);
+
+/// Number of log entries kept in memory, for the log viewer of the apps
+pub const RECENT_LOGS_CAPACITY: usize = 2000;
+/// Size at which the log file is rotated, the previous file is kept as `<file>.1`
+const MAX_LOG_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+static RECENT_LOGS: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+static LOG_FILE: Mutex<Option<LogFile>> = Mutex::new(None);
+
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: ShinkaiLogLevel,
+    pub module: ShinkaiLogOption,
+    pub request_id: Option<String>,
+    pub message: String,
+}
+
 pub fn set_telemetry(telemetry: Arc<dyn ShinkaiTelemetry + Send + Sync>) {
     let mut telemetry_option = TELEMETRY.lock().unwrap();
     *telemetry_option = Some(telemetry);
 }
 
+/// Sets the function returning the correlation ID of the request being handled, if any.
+/// It's added to every log line so the logs of a request can be followed across the node.
+pub fn set_request_id_provider(provider: fn() -> Option<String>) {
+    let mut provider_option = REQUEST_ID_PROVIDER.lock().unwrap();
+    *provider_option = Some(provider);
+}
+
+fn current_request_id() -> Option<String> {
+    let provider = *REQUEST_ID_PROVIDER.lock().unwrap();
+    provider.and_then(|provider| provider())
+}
+
 pub trait ShinkaiTelemetry {
     fn log(&self, option: ShinkaiLogOption, level: ShinkaiLogLevel, message: &str);
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ShinkaiLogOption {
     Blockchain,
     Database,
@@ -40,7 +85,8 @@ pub enum ShinkaiLogOption {
     Tests,
 }
 
-#[derive(PartialEq)]
+/// Ordered from the least to the most verbose
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ShinkaiLogLevel {
     Error,
     Info,
@@ -60,28 +106,156 @@ impl ShinkaiLogLevel {
     }
 }
 
+pub const ALL_LOG_OPTIONS: [ShinkaiLogOption; 18] = [
+    ShinkaiLogOption::Blockchain,
+    ShinkaiLogOption::Database,
+    ShinkaiLogOption::Identity,
+    ShinkaiLogOption::IdentityNetwork,
+    ShinkaiLogOption::ExtSubscriptions,
+    ShinkaiLogOption::MySubscriptions,
+    ShinkaiLogOption::SubscriptionHTTPUploader,
+    ShinkaiLogOption::SubscriptionHTTPDownloader,
+    ShinkaiLogOption::CryptoIdentity,
+    ShinkaiLogOption::JobExecution,
+    ShinkaiLogOption::CronExecution,
+    ShinkaiLogOption::Api,
+    ShinkaiLogOption::WsAPI,
+    ShinkaiLogOption::DetailedAPI,
+    ShinkaiLogOption::Node,
+    ShinkaiLogOption::InternalAPI,
+    ShinkaiLogOption::Network,
+    ShinkaiLogOption::Tests,
+];
+
+/// Level of a log option, `None` when it's off
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct LogLevelSetting {
+    pub option: ShinkaiLogOption,
+    pub level: Option<ShinkaiLogLevel>,
+}
+
+/// Sets the most verbose level logged for the option (`None` turns it off), overriding its LOG_* variable
+pub fn set_log_level(option: ShinkaiLogOption, level: Option<ShinkaiLogLevel>) {
+    let mut levels = LOG_LEVELS.lock().unwrap();
+    levels.get_or_insert_with(HashMap::new).insert(option, level);
+}
+
+/// Goes back to the levels of the LOG_* environment variables
+pub fn reset_log_levels() {
+    let mut levels = LOG_LEVELS.lock().unwrap();
+    *levels = None;
+}
+
+/// Current level of every log option
+pub fn log_levels() -> Vec<LogLevelSetting> {
+    ALL_LOG_OPTIONS
+        .iter()
+        .map(|option| LogLevelSetting {
+            option: *option,
+            level: log_level(option),
+        })
+        .collect()
+}
+
+fn log_level(option: &ShinkaiLogOption) -> Option<ShinkaiLogLevel> {
+    let runtime_level = LOG_LEVELS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|levels| levels.get(option).copied());
+    if let Some(level) = runtime_level {
+        return level;
+    }
+    // Options turned on by environment log every level, the tracing filter (RUST_LOG) does the rest
+    if active_log_options().contains(option) {
+        Some(ShinkaiLogLevel::Debug)
+    } else {
+        None
+    }
+}
+
+/// Also writes the logs to the file, appending to it if it exists
+pub fn set_log_file(path: &Path) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let size = file.metadata()?.len();
+    let mut log_file = LOG_FILE.lock().unwrap();
+    *log_file = Some(LogFile {
+        path: path.to_path_buf(),
+        file,
+        size,
+    });
+    Ok(())
+}
+
+/// Path of the file the logs are written to, if any
+pub fn log_file_path() -> Option<PathBuf> {
+    LOG_FILE.lock().unwrap().as_ref().map(|log_file| log_file.path.clone())
+}
+
+/// The most recent `limit` log entries, oldest first. `max_level` keeps the entries of that level or more severe.
+pub fn recent_logs(
+    max_level: Option<ShinkaiLogLevel>,
+    module: Option<ShinkaiLogOption>,
+    limit: usize,
+) -> Vec<LogEntry> {
+    let recent_logs = RECENT_LOGS.lock().unwrap();
+    let mut entries: Vec<LogEntry> = recent_logs
+        .iter()
+        .rev()
+        .filter(|entry| max_level.map_or(true, |max_level| entry.level <= max_level))
+        .filter(|entry| module.map_or(true, |module| entry.module == module))
+        .take(limit)
+        .cloned()
+        .collect();
+    entries.reverse();
+    entries
+}
+
+fn capture_log(entry: LogEntry, line: &str) {
+    {
+        let mut recent_logs = RECENT_LOGS.lock().unwrap();
+        if recent_logs.len() == RECENT_LOGS_CAPACITY {
+            recent_logs.pop_front();
+        }
+        recent_logs.push_back(entry);
+    }
+
+    let mut log_file = LOG_FILE.lock().unwrap();
+    let Some(current) = log_file.as_mut() else {
+        return;
+    };
+    if current.size + line.len() as u64 + 1 > MAX_LOG_FILE_BYTES {
+        let mut rotated_path = current.path.clone().into_os_string();
+        rotated_path.push(".1");
+        let _ = std::fs::rename(&current.path, rotated_path);
+        match OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&current.path)
+        {
+            Ok(file) => {
+                current.file = file;
+                current.size = 0;
+            }
+            Err(_) => {
+                // Stop writing to the file rather than failing every log call
+                *log_file = None;
+                return;
+            }
+        }
+    }
+    if writeln!(current.file, "{}", line).is_ok() {
+        current.size += line.len() as u64 + 1;
+    }
+}
+
 fn active_log_options() -> Vec<ShinkaiLogOption> {
     if std::env::var("LOG_ALL").is_ok() {
-        return vec![
-            ShinkaiLogOption::Blockchain,
-            ShinkaiLogOption::Database,
-            ShinkaiLogOption::Identity,
-            ShinkaiLogOption::IdentityNetwork,
-            ShinkaiLogOption::ExtSubscriptions,
-            ShinkaiLogOption::MySubscriptions,
-            ShinkaiLogOption::SubscriptionHTTPUploader,
-            ShinkaiLogOption::SubscriptionHTTPDownloader,
-            ShinkaiLogOption::CryptoIdentity,
-            ShinkaiLogOption::JobExecution,
-            ShinkaiLogOption::CronExecution,
-            ShinkaiLogOption::Api,
-            ShinkaiLogOption::WsAPI,
-            ShinkaiLogOption::DetailedAPI,
-            ShinkaiLogOption::Node,
-            ShinkaiLogOption::InternalAPI,
-            ShinkaiLogOption::Network,
-            ShinkaiLogOption::Tests,
-        ];
+        return ALL_LOG_OPTIONS.to_vec();
     }
 
     let mut active_options = Vec::new();
@@ -143,9 +317,10 @@ fn active_log_options() -> Vec<ShinkaiLogOption> {
 }
 
 pub fn shinkai_log(option: ShinkaiLogOption, level: ShinkaiLogLevel, message: &str) {
-    let active_options = active_log_options();
-    if active_options.contains(&option) {
+    if log_level(&option).map_or(false, |max_level| level <= max_level) {
         let is_simple_log = std::env::var("LOG_SIMPLE").is_ok();
+        // LOG_FORMAT=json writes a JSON object per line, for log collectors like ELK
+        let is_json_log = std::env::var("LOG_FORMAT").map_or(false, |format| format.eq_ignore_ascii_case("json"));
         let time = Local::now().format("%Y-%m-%d %H:%M:%S");
 
         let option_str = format!("{:?}", option);
@@ -155,16 +330,45 @@ pub fn shinkai_log(option: ShinkaiLogOption, level: ShinkaiLogLevel, message: &s
             ShinkaiLogLevel::Debug => "DEBUG",
         };
 
-        let message_with_header = if is_simple_log {
-            message.to_string()
+        let request_id = current_request_id();
+        let full_line = if is_json_log {
+            json!({
+                "timestamp": Local::now().to_rfc3339(),
+                "level": level_str,
+                "module": option_str,
+                "request_id": request_id,
+                "pid": std::process::id(),
+                "message": message,
+            })
+            .to_string()
         } else {
             let hostname = "localhost";
             let app_name = "shinkai";
             let proc_id = std::process::id().to_string();
-            let msg_id = "-";
+            let msg_id = request_id.as_deref().unwrap_or("-");
             let header = format!("{} {} {} {} {}", time, hostname, app_name, proc_id, msg_id);
             format!("{} - {} - {} - {}", header, level_str, option_str, message)
         };
+        let message_with_header = if is_simple_log && !is_json_log {
+            match &request_id {
+                Some(request_id) => format!("[{}] {}", request_id, message),
+                None => message.to_string(),
+            }
+        } else {
+            full_line.clone()
+        };
+
+        // The captured logs always have the full header, even with LOG_SIMPLE
+        capture_log(
+            LogEntry {
+                timestamp: Local::now().to_rfc3339(),
+                level,
+                module: option,
+                request_id,
+                message: message.to_string(),
+            },
+            &full_line,
+        );
 
         // Conditional compilation: Only include tracing-related code for non-WASM targets
         #[cfg(not(target_arch = "wasm32"))]
@@ -181,6 +385,8 @@ pub fn shinkai_log(option: ShinkaiLogOption, level: ShinkaiLogLevel, message: &s
                     Some(telemetry) => {
                         telemetry.log(option, level, &message_with_header);
                     }
+                    // Printed as is, the tracing formatter would prefix the JSON
+                    None if is_json_log => println!("{}", message_with_header),
                     None => match level {
                         ShinkaiLogLevel::Error => error!("{}", message_with_header),
                         ShinkaiLogLevel::Info => info!("{}", message_with_header),
@@ -225,3 +431,41 @@ pub fn init_default_tracing() {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_log_levels() {
+        set_log_level(ShinkaiLogOption::JobExecution, Some(ShinkaiLogLevel::Info));
+        set_log_level(ShinkaiLogOption::Network, None);
+
+        let levels = log_levels();
+        assert_eq!(levels.len(), ALL_LOG_OPTIONS.len());
+        assert!(levels.contains(&LogLevelSetting {
+            option: ShinkaiLogOption::JobExecution,
+            level: Some(ShinkaiLogLevel::Info),
+        }));
+        assert_eq!(log_level(&ShinkaiLogOption::Network), None);
+        assert!(ShinkaiLogLevel::Error < ShinkaiLogLevel::Info);
+    }
+
+    #[test]
+    fn test_recent_logs() {
+        set_log_level(ShinkaiLogOption::Tests, Some(ShinkaiLogLevel::Debug));
+        shinkai_log(ShinkaiLogOption::Tests, ShinkaiLogLevel::Info, "first");
+        shinkai_log(ShinkaiLogOption::Tests, ShinkaiLogLevel::Debug, "second");
+        shinkai_log(ShinkaiLogOption::Tests, ShinkaiLogLevel::Error, "third");
+
+        let entries = recent_logs(None, Some(ShinkaiLogOption::Tests), 2);
+        let messages: Vec<&str> = entries.iter().map(|entry| entry.message.as_str()).collect();
+        assert_eq!(messages, vec!["second", "third"]);
+
+        let entries = recent_logs(Some(ShinkaiLogLevel::Info), Some(ShinkaiLogOption::Tests), 10);
+        let messages: Vec<&str> = entries.iter().map(|entry| entry.message.as_str()).collect();
+        assert_eq!(messages, vec!["first", "third"]);
+
+        set_log_level(ShinkaiLogOption::Tests, None);
+    }
+}
@@ -105,6 +105,7 @@ impl ShinkaiMessageBuilder {
         let job_creation = JobCreationInfo {
             scope,
             is_hidden: Some(is_hidden),
+            retrieval: None,
         };
         let body = serde_json::to_string(&job_creation).map_err(|_| "Failed to serialize job creation to JSON")?;
 
@@ -150,6 +151,7 @@ impl ShinkaiMessageBuilder {
             workflow_name,
             sheet_job_data: None,
             callback: None,
+            retrieval: None,
         };
         let body = serde_json::to_string(&job_message).map_err(|_| "Failed to serialize job message to JSON")?;
 
@@ -191,6 +193,7 @@ impl ShinkaiMessageBuilder {
             workflow_name: None, // the agent wont be sending you a workflow
             sheet_job_data: None,
             callback: None,
+            retrieval: None,
         };
         let body = serde_json::to_string(&job_message).map_err(|_| "Failed to serialize job message to JSON")?;
 
@@ -248,7 +251,12 @@ impl ShinkaiMessageBuilder {
         sender: ShinkaiNameString,
         receiver: ShinkaiNameString,
     ) -> Result<ShinkaiMessage, &'static str> {
-        let registration_code_request = RegistrationCodeRequest { permissions, code_type };
+        let registration_code_request = RegistrationCodeRequest {
+            permissions,
+            code_type,
+            ttl_secs: None,
+            max_uses: None,
+        };
 
         ShinkaiMessageBuilder::create_custom_shinkai_message_to_node(
             my_subidentity_encryption_sk,
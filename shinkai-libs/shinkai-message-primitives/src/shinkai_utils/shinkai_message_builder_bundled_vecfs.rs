@@ -280,6 +280,7 @@ impl ShinkaiMessageBuilder {
             path: destination_path.to_string(),
             file_inbox: file_inbox.to_string(),
             file_datetime: file_datetime_option,
+            allow_duplicates: false,
         };
 
         Self::create_vecfs_message(
@@ -756,4 +757,124 @@ impl ShinkaiMessageBuilder {
             node_receiver_subidentity,
         )
     }
+
+    /// Invoice or payment receipt sent to the node of the other party
+    #[allow(clippy::too_many_arguments)]
+    pub fn p2p_payment_message(
+        payload: impl Serialize,
+        schema_type: MessageSchemaType,
+        my_encryption_secret_key: EncryptionStaticKey,
+        my_signature_secret_key: SigningKey,
+        receiver_public_key: EncryptionPublicKey,
+        sender: ShinkaiNameString,
+        node_receiver: ShinkaiNameString,
+    ) -> Result<ShinkaiMessage, &'static str> {
+        Self::create_vecfs_message(
+            payload,
+            schema_type,
+            my_encryption_secret_key,
+            my_signature_secret_key,
+            receiver_public_key,
+            sender,
+            "".to_string(),
+            node_receiver,
+            "".to_string(),
+        )
+    }
+
+    /// Workspace update or replicated inbox messages sent to the node of a member
+    #[allow(clippy::too_many_arguments)]
+    pub fn p2p_workspace_message(
+        payload: impl Serialize,
+        schema_type: MessageSchemaType,
+        my_encryption_secret_key: EncryptionStaticKey,
+        my_signature_secret_key: SigningKey,
+        receiver_public_key: EncryptionPublicKey,
+        sender: ShinkaiNameString,
+        node_receiver: ShinkaiNameString,
+    ) -> Result<ShinkaiMessage, &'static str> {
+        Self::create_vecfs_message(
+            payload,
+            schema_type,
+            my_encryption_secret_key,
+            my_signature_secret_key,
+            receiver_public_key,
+            sender,
+            "".to_string(),
+            node_receiver,
+            "".to_string(),
+        )
+    }
+
+    /// Changes pushed to a shared folder by a subscriber, or the result sent back by the node sharing it
+    #[allow(clippy::too_many_arguments)]
+    pub fn p2p_shared_folder_sync_message(
+        payload: impl Serialize,
+        schema_type: MessageSchemaType,
+        my_encryption_secret_key: EncryptionStaticKey,
+        my_signature_secret_key: SigningKey,
+        receiver_public_key: EncryptionPublicKey,
+        sender: ShinkaiNameString,
+        sender_subidentity: ShinkaiNameString,
+        node_receiver: ShinkaiNameString,
+        node_receiver_subidentity: ShinkaiNameString,
+        proxy_info: Option<ShinkaiProxyBuilderInfo>,
+    ) -> Result<ShinkaiMessage, &'static str> {
+        Self::create_vecfs_message_with_proxy(
+            payload,
+            schema_type,
+            my_encryption_secret_key,
+            my_signature_secret_key,
+            receiver_public_key,
+            sender,
+            sender_subidentity,
+            node_receiver,
+            node_receiver_subidentity,
+            proxy_info,
+        )
+    }
+
+    /// Edit or deletion of a message sent to the nodes of the other participants of its inbox
+    pub fn p2p_inbox_message_change(
+        payload: impl Serialize,
+        my_encryption_secret_key: EncryptionStaticKey,
+        my_signature_secret_key: SigningKey,
+        receiver_public_key: EncryptionPublicKey,
+        sender: ShinkaiNameString,
+        node_receiver: ShinkaiNameString,
+    ) -> Result<ShinkaiMessage, &'static str> {
+        Self::create_vecfs_message(
+            payload,
+            MessageSchemaType::InboxMessageChange,
+            my_encryption_secret_key,
+            my_signature_secret_key,
+            receiver_public_key,
+            sender,
+            "".to_string(),
+            node_receiver,
+            "".to_string(),
+        )
+    }
+
+    /// Encrypted chunk of an attachment sent to the nodes of the other participants of the inbox of its message
+    pub fn p2p_attachment_chunk(
+        payload: impl Serialize,
+        my_encryption_secret_key: EncryptionStaticKey,
+        my_signature_secret_key: SigningKey,
+        receiver_public_key: EncryptionPublicKey,
+        sender: ShinkaiNameString,
+        node_receiver: ShinkaiNameString,
+    ) -> Result<ShinkaiMessage, &'static str> {
+        Self::create_vecfs_message(
+            payload,
+            MessageSchemaType::AttachmentChunk,
+            my_encryption_secret_key,
+            my_signature_secret_key,
+            receiver_public_key,
+            sender,
+            "".to_string(),
+            node_receiver,
+            "".to_string(),
+        )
+    }
 }
@@ -404,7 +404,12 @@ impl PyShinkaiMessageBuilder {
             .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid permissions"))?;
         let code_type: RegistrationCodeType = serde_json::from_str(&code_type)
             .map_err(|_| PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid code type"))?;
-        let registration_code_request = RegistrationCodeRequest { permissions, code_type };
+        let registration_code_request = RegistrationCodeRequest {
+            permissions,
+            code_type,
+            ttl_secs: None,
+            max_uses: None,
+        };
         let data = serde_json::to_string(&registration_code_request)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string().clone()))?;
 
@@ -1171,6 +1176,7 @@ impl PyShinkaiMessageBuilder {
             let job_creation = JobCreationInfo {
                 scope: scope.inner.clone(),
                 is_hidden: Some(is_hidden),
+                retrieval: None,
             };
 
             let body = match serde_json::to_string(&job_creation) {
@@ -1248,6 +1254,7 @@ impl PyShinkaiMessageBuilder {
                 workflow_name,
                 sheet_job_data: None,
                 callback: None,
+                retrieval: None,
             };
 
             let body = match serde_json::to_string(&job_message) {
@@ -1652,6 +1659,7 @@ impl PyShinkaiMessageBuilder {
                 path: destination_path,
                 file_inbox,
                 file_datetime: file_datetime_option,
+                allow_duplicates: false,
             };
 
             let body = match serde_json::to_string(&payload) {
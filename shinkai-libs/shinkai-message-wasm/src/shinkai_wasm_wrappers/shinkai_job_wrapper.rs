@@ -49,6 +49,7 @@ impl JobCreationWrapper {
         let job_creation = JobCreationInfo {
             scope,
             is_hidden: Some(is_hidden),
+            retrieval: None,
         };
         Ok(JobCreationWrapper { inner: job_creation })
     }
@@ -88,6 +89,7 @@ impl JobCreationWrapper {
             inner: JobCreationInfo {
                 scope: job_scope,
                 is_hidden: Some(false),
+                retrieval: None,
             },
         })
     }
@@ -137,6 +139,7 @@ impl JobMessageWrapper {
             workflow_name,
             sheet_job_data: None,
             callback: None,
+            retrieval: None,
         };
         Ok(JobMessageWrapper { inner: job_message })
     }
@@ -182,6 +185,7 @@ impl JobMessageWrapper {
             workflow_name,
             sheet_job_data: None,
             callback: None,
+            retrieval: None,
         };
         JobMessageWrapper { inner: job_message }
     }
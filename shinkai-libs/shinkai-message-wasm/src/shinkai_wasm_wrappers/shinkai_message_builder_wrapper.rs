@@ -391,7 +391,12 @@ impl ShinkaiMessageBuilderWrapper {
             IdentityPermissions::from_str(&permissions).ok_or_else(|| JsValue::from_str("Invalid permissions"))?;
         let code_type = RegistrationCodeType::deserialize(serde_json::Value::String(code_type))
             .map_err(|_| JsValue::from_str("Invalid code type"))?;
-        let registration_code_request = RegistrationCodeRequest { permissions, code_type };
+        let registration_code_request = RegistrationCodeRequest {
+            permissions,
+            code_type,
+            ttl_secs: None,
+            max_uses: None,
+        };
         let data = match registration_code_request.to_json_str() {
             Ok(data) => data,
             Err(e) => return Err(JsValue::from_str(&e.to_string())),
@@ -828,6 +833,7 @@ impl ShinkaiMessageBuilderWrapper {
         let job_creation = JobCreationInfo {
             scope,
             is_hidden: Some(is_hidden),
+            retrieval: None,
         };
         let body = serde_json::to_string(&job_creation).map_err(|e| JsValue::from_str(&e.to_string()))?;
 
@@ -875,6 +881,7 @@ impl ShinkaiMessageBuilderWrapper {
             workflow_name,
             sheet_job_data: None,
             callback: None,
+            retrieval: None,
         };
 
         let body = serde_json::to_string(&job_message).map_err(|e| JsValue::from_str(&e.to_string()))?;
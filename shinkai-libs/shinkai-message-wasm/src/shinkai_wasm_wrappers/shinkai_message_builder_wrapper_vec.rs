@@ -200,6 +200,7 @@ impl ShinkaiMessageBuilderWrapper {
             path: destination_path,
             file_inbox,
             file_datetime: file_datetime_option,
+            allow_duplicates: false,
         };
         let body = serde_json::to_string(&create_items_info).map_err(|e| JsValue::from_str(&e.to_string()))?;
         let schema = MessageSchemaType::ConvertFilesAndSaveToFolder.to_str().to_string();
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use shinkai_message_primitives::shinkai_utils::shinkai_logging::{shinkai_log, ShinkaiLogLevel, ShinkaiLogOption};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Limits of the bytes relayed for each client identity, in both directions. Limits that aren't set are unlimited.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BandwidthLimits {
+    /// Bytes a client can relay per quota period
+    pub quota_bytes: Option<u64>,
+    pub quota_period: Duration,
+    /// Bytes a client can relay per second on average. A message is refused once the client is over its allowance,
+    /// so a single message bigger than the allowance still goes through.
+    pub bytes_per_second: Option<u64>,
+}
+
+impl Default for BandwidthLimits {
+    fn default() -> Self {
+        BandwidthLimits {
+            quota_bytes: None,
+            quota_period: Duration::from_secs(24 * 60 * 60),
+            bytes_per_second: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandwidthDirection {
+    FromClient,
+    ToClient,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandwidthRejection {
+    QuotaExceeded,
+    RateLimited,
+}
+
+impl fmt::Display for BandwidthRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BandwidthRejection::QuotaExceeded => write!(f, "Relay quota exceeded"),
+            BandwidthRejection::RateLimited => write!(f, "Relay rate limit exceeded"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClientBandwidthStats {
+    pub identity: String,
+    pub bytes_from_client: u64,
+    pub bytes_to_client: u64,
+    /// Bytes relayed in both directions since the start of the current quota period
+    pub period_bytes: u64,
+    pub period_started_at: DateTime<Utc>,
+    /// Messages not relayed because the client was over its quota or rate limit
+    pub rejected_messages: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RelayStats {
+    pub total_bytes_relayed: u64,
+    pub quota_bytes: Option<u64>,
+    pub quota_period_secs: u64,
+    pub bytes_per_second: Option<u64>,
+    pub clients: Vec<ClientBandwidthStats>,
+}
+
+#[derive(Debug)]
+struct ClientUsage {
+    bytes_from_client: u64,
+    bytes_to_client: u64,
+    period_bytes: u64,
+    period_started: Instant,
+    period_started_at: DateTime<Utc>,
+    /// Bytes the client can still relay right away, negative once it sent more than its allowance
+    allowance: f64,
+    allowance_updated: Instant,
+    rejected_messages: u64,
+}
+
+impl ClientUsage {
+    fn new(limits: &BandwidthLimits, now: Instant) -> Self {
+        ClientUsage {
+            bytes_from_client: 0,
+            bytes_to_client: 0,
+            period_bytes: 0,
+            period_started: now,
+            period_started_at: Utc::now(),
+            allowance: limits.bytes_per_second.unwrap_or(0) as f64,
+            allowance_updated: now,
+            rejected_messages: 0,
+        }
+    }
+
+    /// Starts a new quota period if the current one is over, and refills the allowance for the time elapsed
+    fn refresh(&mut self, limits: &BandwidthLimits, now: Instant) {
+        if now.saturating_duration_since(self.period_started) >= limits.quota_period {
+            self.period_bytes = 0;
+            self.period_started = now;
+            self.period_started_at = Utc::now();
+        }
+        if let Some(bytes_per_second) = limits.bytes_per_second {
+            let elapsed = now.saturating_duration_since(self.allowance_updated);
+            let refill = elapsed.as_secs_f64() * bytes_per_second as f64;
+            self.allowance = (self.allowance + refill).min(bytes_per_second as f64);
+        }
+        self.allowance_updated = now;
+    }
+}
+
+/// Bytes relayed for each client identity, and the limits they are held to
+#[derive(Debug)]
+pub struct BandwidthAccounting {
+    limits: BandwidthLimits,
+    usage: Mutex<HashMap<String, ClientUsage>>,
+}
+
+impl BandwidthAccounting {
+    pub fn new(limits: BandwidthLimits) -> Self {
+        BandwidthAccounting {
+            limits,
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn limits(&self) -> BandwidthLimits {
+        self.limits
+    }
+
+    /// Whether a message of the client can be relayed, counting it as rejected if not
+    pub fn check(&self, identity: &str) -> Result<(), BandwidthRejection> {
+        self.check_at(identity, Instant::now())
+    }
+
+    fn check_at(&self, identity: &str, now: Instant) -> Result<(), BandwidthRejection> {
+        let mut usage = self.usage.lock().unwrap();
+        let client = usage
+            .entry(identity.to_string())
+            .or_insert_with(|| ClientUsage::new(&self.limits, now));
+        client.refresh(&self.limits, now);
+
+        let rejection = if self
+            .limits
+            .quota_bytes
+            .map_or(false, |quota_bytes| client.period_bytes >= quota_bytes)
+        {
+            Some(BandwidthRejection::QuotaExceeded)
+        } else if self.limits.bytes_per_second.is_some() && client.allowance < 0.0 {
+            Some(BandwidthRejection::RateLimited)
+        } else {
+            None
+        };
+        match rejection {
+            Some(rejection) => {
+                client.rejected_messages += 1;
+                Err(rejection)
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn record(&self, identity: &str, direction: BandwidthDirection, bytes: usize) {
+        self.record_at(identity, direction, bytes, Instant::now())
+    }
+
+    fn record_at(&self, identity: &str, direction: BandwidthDirection, bytes: usize, now: Instant) {
+        let mut usage = self.usage.lock().unwrap();
+        let client = usage
+            .entry(identity.to_string())
+            .or_insert_with(|| ClientUsage::new(&self.limits, now));
+        client.refresh(&self.limits, now);
+
+        match direction {
+            BandwidthDirection::FromClient => client.bytes_from_client += bytes as u64,
+            BandwidthDirection::ToClient => client.bytes_to_client += bytes as u64,
+        }
+        client.period_bytes += bytes as u64;
+        if self.limits.bytes_per_second.is_some() {
+            client.allowance -= bytes as f64;
+        }
+    }
+
+    pub fn stats(&self) -> RelayStats {
+        let usage = self.usage.lock().unwrap();
+        let mut clients: Vec<ClientBandwidthStats> = usage
+            .iter()
+            .map(|(identity, client)| ClientBandwidthStats {
+                identity: identity.clone(),
+                bytes_from_client: client.bytes_from_client,
+                bytes_to_client: client.bytes_to_client,
+                period_bytes: client.period_bytes,
+                period_started_at: client.period_started_at,
+                rejected_messages: client.rejected_messages,
+            })
+            .collect();
+        clients.sort_by(|a, b| a.identity.cmp(&b.identity));
+
+        RelayStats {
+            total_bytes_relayed: clients
+                .iter()
+                .map(|client| client.bytes_from_client + client.bytes_to_client)
+                .sum(),
+            quota_bytes: self.limits.quota_bytes,
+            quota_period_secs: self.limits.quota_period.as_secs(),
+            bytes_per_second: self.limits.bytes_per_second,
+            clients,
+        }
+    }
+}
+
+/// Answers `GET /stats` with the relay stats as JSON. It listens apart from the relay so operators can keep it private.
+pub async fn serve_stats(listener: TcpListener, bandwidth: Arc<BandwidthAccounting>) {
+    loop {
+        let mut socket = match listener.accept().await {
+            Ok((socket, _)) => socket,
+            Err(e) => {
+                shinkai_log(
+                    ShinkaiLogOption::Network,
+                    ShinkaiLogLevel::Error,
+                    &format!("Failed to accept stats connection: {}", e),
+                );
+                continue;
+            }
+        };
+        let bandwidth = bandwidth.clone();
+        tokio::spawn(async move {
+            let mut request = [0u8; 1024];
+            let read = socket.read(&mut request).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&request[..read]);
+            let (status, body) = match request.starts_with("GET /stats ") {
+                true => ("200 OK", serde_json::to_string(&bandwidth.stats()).unwrap_or_default()),
+                false => ("404 Not Found", r#"{"error":"Not found"}"#.to_string()),
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bandwidth_quota_and_rate_limit() {
+        let bandwidth = BandwidthAccounting::new(BandwidthLimits {
+            quota_bytes: Some(1_000),
+            bytes_per_second: Some(400),
+            ..Default::default()
+        });
+
+        let start = Instant::now();
+        assert!(bandwidth.check_at("node1.shinkai", start).is_ok());
+        bandwidth.record_at("node1.shinkai", BandwidthDirection::FromClient, 500, start);
+        assert_eq!(
+            bandwidth.check_at("node1.shinkai", start),
+            Err(BandwidthRejection::RateLimited)
+        );
+        assert!(bandwidth.check_at("node2.shinkai", start).is_ok());
+
+        // Refills the allowance, the quota stays used
+        let later = start + Duration::from_millis(300);
+        assert!(bandwidth.check_at("node1.shinkai", later).is_ok());
+        bandwidth.record_at("node1.shinkai", BandwidthDirection::ToClient, 500, later);
+        let later = later + Duration::from_millis(300);
+        assert_eq!(
+            bandwidth.check_at("node1.shinkai", later),
+            Err(BandwidthRejection::QuotaExceeded)
+        );
+
+        let stats = bandwidth.stats();
+        assert_eq!(stats.total_bytes_relayed, 1_000);
+        assert_eq!(stats.clients[0].bytes_to_client, 500);
+        assert_eq!(stats.clients[0].rejected_messages, 2);
+    }
+}
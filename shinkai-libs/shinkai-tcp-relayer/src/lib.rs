@@ -1,6 +1,8 @@
+pub mod bandwidth;
 pub mod tcp_server;
 pub mod server_error;
 pub mod network_message;
+pub use bandwidth::*;
 pub use tcp_server::*;
 pub use server_error::*;
 pub use network_message::*;
\ No newline at end of file
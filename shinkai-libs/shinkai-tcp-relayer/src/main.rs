@@ -3,8 +3,9 @@ use dotenv::dotenv;
 use shinkai_message_primitives::shinkai_utils::{
     encryption::string_to_encryption_static_key, signatures::string_to_signature_secret_key,
 };
-use shinkai_tcp_relayer::{NetworkMessageError, TCPProxy};
+use shinkai_tcp_relayer::{serve_stats, BandwidthLimits, NetworkMessageError, TCPProxy};
 use std::env;
+use std::time::Duration;
 use tokio::net::TcpListener;
 
 #[tokio::main]
@@ -85,6 +86,40 @@ async fn main() -> Result<(), NetworkMessageError> {
                 .default_value("true")
                 .env("OPEN_TO_ALL"),
         )
+        .arg(
+            Arg::with_name("quota_bytes")
+                .long("quota-bytes")
+                .value_name("QUOTA_BYTES")
+                .help("Bytes each client identity can relay per quota period, unlimited if not set")
+                .takes_value(true)
+                .env("QUOTA_BYTES"),
+        )
+        .arg(
+            Arg::with_name("quota_period_secs")
+                .long("quota-period-secs")
+                .value_name("QUOTA_PERIOD_SECS")
+                .help("Length of the quota period in seconds")
+                .takes_value(true)
+                .default_value("86400")
+                .env("QUOTA_PERIOD_SECS"),
+        )
+        .arg(
+            Arg::with_name("rate_limit_bytes_per_sec")
+                .long("rate-limit-bytes-per-sec")
+                .value_name("RATE_LIMIT_BYTES_PER_SEC")
+                .help("Bytes per second each client identity can relay on average, unlimited if not set")
+                .takes_value(true)
+                .env("RATE_LIMIT_BYTES_PER_SEC"),
+        )
+        .arg(
+            Arg::with_name("stats_address")
+                .long("stats-address")
+                .value_name("STATS_ADDRESS")
+                .help("Sets the address serving the relay stats at GET /stats")
+                .takes_value(true)
+                .default_value("127.0.0.1:8081")
+                .env("STATS_ADDRESS"),
+        )
         .get_matches();
 
     let address = matches.value_of("address").unwrap().to_string();
@@ -95,6 +130,17 @@ async fn main() -> Result<(), NetworkMessageError> {
     let node_name = matches.value_of("node_name").unwrap().to_string();
     let open_to_all = matches.value_of("open_to_all").map(|v| v == "true").unwrap_or(true);
     let max_connections = matches.value_of("max_connections").map(|v| v.parse().unwrap_or(20));
+    let bandwidth_limits = BandwidthLimits {
+        quota_bytes: matches.value_of("quota_bytes").and_then(|v| v.parse().ok()),
+        quota_period: Duration::from_secs(
+            matches
+                .value_of("quota_period_secs")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(86400),
+        ),
+        bytes_per_second: matches.value_of("rate_limit_bytes_per_sec").and_then(|v| v.parse().ok()),
+    };
+    let stats_address = matches.value_of("stats_address").unwrap().to_string();
 
 
     let identity_secret_key =
@@ -113,7 +159,14 @@ async fn main() -> Result<(), NetworkMessageError> {
         contract_address,
         max_connections,
     )
-    .await?;
+    .await?
+    .with_bandwidth_limits(bandwidth_limits);
+
+    let stats_listener = TcpListener::bind(&stats_address).await.map_err(|e| {
+        NetworkMessageError::CustomError(format!("Failed to bind the stats address {}: {}", stats_address, e))
+    })?;
+    println!("Stats available at http://{}/stats", stats_address);
+    tokio::spawn(serve_stats(stats_listener, proxy.bandwidth.clone()));
 
     loop {
         let (socket, _) = listener.accept().await.unwrap();
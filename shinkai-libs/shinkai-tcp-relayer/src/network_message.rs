@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use shinkai_message_primitives::schemas::shinkai_network::NetworkMessageType;
+use shinkai_message_primitives::schemas::shinkai_network::{
+    check_network_frame_version, NetworkFrameLengths, NetworkFrameLimits, NetworkMessageType,
+    NETWORK_FRAME_VERSION_MARKER,
+};
 use tokio::io::{AsyncReadExt, ReadHalf};
 use tokio::net::TcpStream;
 use tokio::sync::Mutex;
@@ -20,44 +23,57 @@ impl NetworkMessage {
         _log_with_identity: Option<String>,
     ) -> Result<Self, NetworkMessageError> {
         let mut reader = reader.lock().await;
+        let frame_limits = NetworkFrameLimits::new_from_env();
 
-        async fn read_exact(reader: &mut ReadHalf<TcpStream>, buf: &mut [u8]) -> Result<(), NetworkMessageError> {
-            let mut total_read = 0;
-            while total_read < buf.len() {
-                match reader.read(&mut buf[total_read..]).await {
-                    Ok(0) => return Err(NetworkMessageError::ConnectionClosed),
-                    Ok(n) => total_read += n,
-                    Err(e) => return Err(NetworkMessageError::IoError(e)),
-                }
-            }
-            Ok(())
-        }
+        // Clients keep their connection open between messages, so only the rest of a frame is timed
+        let mut first_bytes = [0u8; 4];
+        read_exact(&mut reader, &mut first_bytes).await?;
+        tokio::time::timeout(
+            frame_limits.read_timeout,
+            Self::read_rest_of_frame(&mut reader, first_bytes, frame_limits.max_frame_length),
+        )
+        .await
+        .map_err(|_| NetworkMessageError::Timeout)?
+    }
 
-        let mut length_bytes = [0u8; 4];
-        read_exact(&mut reader, &mut length_bytes).await?;
-        let total_length = u32::from_be_bytes(length_bytes) as usize;
+    /// Reads a frame whose first 4 bytes were read, which are either the total length of a version 1 frame or the
+    /// marker of a versioned frame
+    async fn read_rest_of_frame(
+        reader: &mut ReadHalf<TcpStream>,
+        first_bytes: [u8; 4],
+        max_frame_length: usize,
+    ) -> Result<Self, NetworkMessageError> {
+        let length_bytes = match first_bytes {
+            NETWORK_FRAME_VERSION_MARKER => {
+                let mut version_byte = [0u8; 1];
+                read_exact(reader, &mut version_byte).await?;
+                check_network_frame_version(version_byte[0])
+                    .map_err(|e| NetworkMessageError::InvalidData(e.to_string()))?;
+                let mut length_bytes = [0u8; 4];
+                read_exact(reader, &mut length_bytes).await?;
+                length_bytes
+            }
+            length_bytes => length_bytes,
+        };
 
         let mut identity_length_bytes = [0u8; 4];
-        read_exact(&mut reader, &mut identity_length_bytes).await?;
-        let identity_length = u32::from_be_bytes(identity_length_bytes) as usize;
+        read_exact(reader, &mut identity_length_bytes).await?;
+
+        let lengths = NetworkFrameLengths::parse_with_limit(length_bytes, identity_length_bytes, max_frame_length)
+            .map_err(|e| NetworkMessageError::InvalidData(e.to_string()))?;
 
-        let mut identity_bytes = vec![0u8; identity_length];
-        read_exact(&mut reader, &mut identity_bytes).await?;
+        let mut identity_bytes = vec![0u8; lengths.identity_length];
+        read_exact(reader, &mut identity_bytes).await?;
         let identity = String::from_utf8(identity_bytes)?;
 
         let mut header_byte = [0u8; 1];
-        read_exact(&mut reader, &mut header_byte).await?;
-        let message_type = match header_byte[0] {
-            0x01 => NetworkMessageType::ShinkaiMessage,
-            0x02 => NetworkMessageType::VRKaiPathPair,
-            0x03 => NetworkMessageType::ProxyMessage,
-            _ => return Err(NetworkMessageError::UnknownMessageType(header_byte[0])),
-        };
+        read_exact(reader, &mut header_byte).await?;
+        let message_type = NetworkMessageType::from_header_byte(header_byte[0])
+            .map_err(|_| NetworkMessageError::UnknownMessageType(header_byte[0]))?;
 
-        let msg_length = total_length - 1 - 4 - identity_length;
-        let mut buffer = vec![0u8; msg_length];
+        let mut buffer = vec![0u8; lengths.payload_length];
 
-        read_exact(&mut reader, &mut buffer).await?;
+        read_exact(reader, &mut buffer).await?;
 
         Ok(NetworkMessage {
             identity,
@@ -65,4 +81,16 @@ impl NetworkMessage {
             payload: buffer,
         })
     }
-}
\ No newline at end of file
+}
+
+async fn read_exact(reader: &mut ReadHalf<TcpStream>, buf: &mut [u8]) -> Result<(), NetworkMessageError> {
+    let mut total_read = 0;
+    while total_read < buf.len() {
+        match reader.read(&mut buf[total_read..]).await {
+            Ok(0) => return Err(NetworkMessageError::ConnectionClosed),
+            Ok(n) => total_read += n,
+            Err(e) => return Err(NetworkMessageError::IoError(e)),
+        }
+    }
+    Ok(())
+}
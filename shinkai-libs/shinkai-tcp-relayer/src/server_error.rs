@@ -18,6 +18,7 @@ pub enum NetworkMessageError {
     Timeout,
     EncryptionError(String),
     RecipientLoopError(String),
+    BandwidthExceeded(String),
 }
 
 impl fmt::Display for NetworkMessageError {
@@ -36,6 +37,7 @@ impl fmt::Display for NetworkMessageError {
             NetworkMessageError::Timeout => write!(f, "Operation timed out"),
             NetworkMessageError::EncryptionError(msg) => write!(f, "{}", msg),
             NetworkMessageError::RecipientLoopError(msg) => write!(f, "Trying to relay a message using the relayer public ip/dns {}", msg),
+            NetworkMessageError::BandwidthExceeded(msg) => write!(f, "{}", msg),
         }
     }
 }
@@ -56,6 +58,7 @@ impl std::error::Error for NetworkMessageError {
             NetworkMessageError::Timeout => None,
             NetworkMessageError::EncryptionError(_) => None,
             NetworkMessageError::RecipientLoopError(_) => None,
+            NetworkMessageError::BandwidthExceeded(_) => None,
         }
     }
 }
@@ -6,7 +6,9 @@ use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use shinkai_crypto_identities::ShinkaiRegistry;
 use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
-use shinkai_message_primitives::schemas::shinkai_network::NetworkMessageType;
+use shinkai_message_primitives::schemas::shinkai_network::{
+    encode_network_frame, NetworkMessageType, NETWORK_FRAME_VERSION_1,
+};
 use shinkai_message_primitives::shinkai_message::shinkai_message::{MessageBody, ShinkaiMessage};
 use shinkai_message_primitives::shinkai_utils::encryption::{
     encryption_public_key_to_string, string_to_encryption_public_key, string_to_encryption_static_key,
@@ -24,6 +26,7 @@ use tokio::sync::{Mutex, Semaphore};
 use uuid::Uuid;
 use x25519_dalek::{PublicKey as EncryptionPublicKey, StaticSecret as EncryptionStaticKey};
 
+use crate::bandwidth::{BandwidthAccounting, BandwidthDirection, BandwidthLimits};
 use crate::{NetworkMessage, NetworkMessageError};
 
 pub type TCPProxyClients =
@@ -49,6 +52,7 @@ pub struct TCPProxy {
     pub encryption_public_key: EncryptionPublicKey,
     pub connection_semaphore: Arc<Semaphore>,
     pub max_connections: usize,
+    pub bandwidth: Arc<BandwidthAccounting>,
 }
 
 impl TCPProxy {
@@ -146,9 +150,16 @@ impl TCPProxy {
             encryption_public_key,
             connection_semaphore: Arc::new(Semaphore::new(max_connections)),
             max_connections,
+            bandwidth: Arc::new(BandwidthAccounting::new(BandwidthLimits::default())),
         })
     }
 
+    /// Holds each client identity to the quota and rate limit, unlimited by default
+    pub fn with_bandwidth_limits(mut self, limits: BandwidthLimits) -> Self {
+        self.bandwidth = Arc::new(BandwidthAccounting::new(limits));
+        self
+    }
+
     /// Handle a new client connection which could be:
     /// - a Node that needs punch hole
     /// - a Node answering to a request that needs to get redirected to a Node using a punch hole
@@ -202,6 +213,7 @@ impl TCPProxy {
                 let identity_secret_key = self.identity_secret_key.clone();
                 let encryption_secret_key = self.encryption_secret_key.clone();
                 let connection_semaphore = self.connection_semaphore.clone();
+                let bandwidth = self.bandwidth.clone();
 
                 tokio::spawn(async move {
                     // The permit will be dropped when the task completes, releasing the semaphore
@@ -225,6 +237,7 @@ impl TCPProxy {
                         identity_secret_key,
                         encryption_secret_key,
                         session_id,
+                        &bandwidth,
                     )
                     .await;
 
@@ -237,8 +250,11 @@ impl TCPProxy {
                     );
                 });
             }
-            NetworkMessageType::VRKaiPathPair => {
-                eprintln!("[{}] VRKaiPathPair message not supported yet", session_id);
+            NetworkMessageType::VRKaiPathPair | NetworkMessageType::ProtocolHello => {
+                eprintln!(
+                    "[{}] {:?} message not supported yet",
+                    session_id, network_msg.message_type
+                );
                 drop(permit);
                 println!(
                     "[{}] Semaphore permit dropped. Available permits: {} out of {}",
@@ -263,6 +279,7 @@ impl TCPProxy {
         identity_secret_key: SigningKey,
         encryption_secret_key: EncryptionStaticKey,
         session_id: Uuid,
+        bandwidth: &BandwidthAccounting,
     ) {
         let shinkai_message: Result<ShinkaiMessage, _> = serde_json::from_slice(&network_msg.payload);
         match shinkai_message {
@@ -278,7 +295,8 @@ impl TCPProxy {
                     node_name,
                     identity_secret_key,
                     encryption_secret_key,
-                    session_id
+                    session_id,
+                    bandwidth,
                 )
                 .await;
                 match response {
@@ -336,6 +354,7 @@ impl TCPProxy {
         let node_name = self.node_name.clone();
         let identity_sk = self.identity_secret_key.clone();
         let encryption_sk = self.encryption_secret_key.clone();
+        let bandwidth = self.bandwidth.clone();
 
         tokio::spawn(async move {
             loop {
@@ -343,7 +362,13 @@ impl TCPProxy {
                     msg = NetworkMessage::read_from_socket(reader.clone(), Some(identity.clone())) => {
                         match msg {
                             Ok(msg) => {
-                                if let Err(e) = Self::handle_incoming_message(Ok(msg), &clients_clone, &pk_to_clients_clone, reader.clone(), writer.clone(), &registry_clone, &identity, node_name.clone(), identity_sk.clone(), encryption_sk.clone(), session_id).await {
+                                if let Err(rejection) = bandwidth.check(&identity) {
+                                    eprintln!("[{}] Not relaying message of {}: {}", session_id, identity, rejection);
+                                    let _ = send_message_with_length(writer.clone(), rejection.to_string()).await;
+                                    continue;
+                                }
+                                bandwidth.record(&identity, BandwidthDirection::FromClient, msg.identity.len() + msg.payload.len());
+                                if let Err(e) = Self::handle_incoming_message(Ok(msg), &clients_clone, &pk_to_clients_clone, reader.clone(), writer.clone(), &registry_clone, &identity, node_name.clone(), identity_sk.clone(), encryption_sk.clone(), session_id, &bandwidth).await {
                                     eprintln!("[{}] Error handling incoming message: {}", session_id, e);
                                     break;
                                 }
@@ -395,6 +420,7 @@ impl TCPProxy {
         identity_secret_key: SigningKey,
         encryption_secret_key: EncryptionStaticKey,
         session_id: Uuid,
+        bandwidth: &BandwidthAccounting,
     ) -> Result<(), NetworkMessageError> {
         match msg {
             Ok(msg) => match msg.message_type {
@@ -415,6 +441,7 @@ impl TCPProxy {
                                 identity_secret_key,
                                 encryption_secret_key,
                                 session_id,
+                                bandwidth,
                             )
                             .await
                         }
@@ -438,12 +465,13 @@ impl TCPProxy {
                         identity_secret_key,
                         encryption_secret_key,
                         session_id,
+                        bandwidth,
                     )
                     .await;
                     Ok(())
                 }
-                NetworkMessageType::VRKaiPathPair => {
-                    eprintln!("[{}] VRKaiPathPair not supported yet", session_id);
+                NetworkMessageType::VRKaiPathPair | NetworkMessageType::ProtocolHello => {
+                    eprintln!("[{}] {:?} not supported yet", session_id, msg.message_type);
                     Ok(())
                 }
             },
@@ -467,6 +495,7 @@ impl TCPProxy {
         identity_secret_key: SigningKey,
         encryption_secret_key: EncryptionStaticKey,
         session_id: Uuid,
+        bandwidth: &BandwidthAccounting,
     ) -> Result<(), NetworkMessageError> {
         /*
          For Proxy Message we have multiple cases
@@ -530,6 +559,7 @@ impl TCPProxy {
                 tcp_node_name,
                 msg_sender,
                 session_id,
+                bandwidth,
             )
             .await?;
             return Ok(());
@@ -572,11 +602,14 @@ impl TCPProxy {
                 }
             };
 
-            if Self::send_shinkai_message_to_proxied_identity(connection.1, parsed_message)
-                .await
-                .is_err()
+            if let Err(e) =
+                Self::send_shinkai_message_to_proxied_identity(connection.1, parsed_message, bandwidth, &msg_recipient)
+                    .await
             {
-                eprintln!("[{}] Failed to send message to client {}", session_id, msg_recipient);
+                eprintln!(
+                    "[{}] Failed to send message to client {}: {}",
+                    session_id, msg_recipient, e
+                );
             }
 
             return Ok(());
@@ -645,6 +678,7 @@ impl TCPProxy {
         tcp_node_name: ShinkaiName,
         msg_sender: String,
         session_id: Uuid,
+        bandwidth: &BandwidthAccounting,
     ) -> Result<(), NetworkMessageError> {
         // Fetch the public keys from the registry
         let registry_identity = registry.get_identity_record(msg_sender.clone()).await.unwrap();
@@ -721,11 +755,14 @@ impl TCPProxy {
         };
 
         // Send message to the client using connection
-        if Self::send_shinkai_message_to_proxied_identity(connection.1, updated_message)
-            .await
-            .is_err()
+        if let Err(e) =
+            Self::send_shinkai_message_to_proxied_identity(connection.1, updated_message, bandwidth, &client_identity)
+                .await
         {
-            eprintln!("[{}] Failed to send message to client {}", session_id, client_identity);
+            eprintln!(
+                "[{}] Failed to send message to client {}: {}",
+                session_id, client_identity, e
+            );
         }
 
         Ok(())
@@ -759,17 +796,12 @@ impl TCPProxy {
                                 } else {
                                     msg_recipient.clone()
                                 };
-                                let identity_bytes = modified_msg_recipient.as_bytes();
-                                let identity_length = (identity_bytes.len() as u32).to_be_bytes();
-                                let total_length =
-                                    (payload.len() as u32 + 1 + identity_bytes.len() as u32 + 4).to_be_bytes();
-
-                                let mut data_to_send = Vec::new();
-                                data_to_send.extend_from_slice(&total_length);
-                                data_to_send.extend_from_slice(&identity_length);
-                                data_to_send.extend(identity_bytes);
-                                data_to_send.push(0x01); // Message type identifier for ShinkaiMessage
-                                data_to_send.extend_from_slice(&payload);
+                                let data_to_send = encode_network_frame(
+                                    NETWORK_FRAME_VERSION_1,
+                                    &modified_msg_recipient,
+                                    &NetworkMessageType::ShinkaiMessage,
+                                    &payload,
+                                );
 
                                 stream.write_all(&data_to_send).await?;
                                 stream.flush().await?;
@@ -1120,25 +1152,24 @@ impl TCPProxy {
         Ok(msg)
     }
 
+    /// Sends the message to a client connected to the relay, unless it's over its quota or rate limit
     async fn send_shinkai_message_to_proxied_identity(
         writer: Arc<Mutex<WriteHalf<TcpStream>>>,
         message: ShinkaiMessage,
+        bandwidth: &BandwidthAccounting,
+        client_identity: &str,
     ) -> Result<(), NetworkMessageError> {
+        bandwidth
+            .check(client_identity)
+            .map_err(|rejection| NetworkMessageError::BandwidthExceeded(rejection.to_string()))?;
         let encoded_msg = message.encode_message().unwrap();
         let identity = &message.external_metadata.recipient;
-        let identity_bytes = identity.as_bytes();
-        let identity_length = (identity_bytes.len() as u32).to_be_bytes();
-
-        // Prepare the message with a length prefix and identity length
-        let total_length = (encoded_msg.len() as u32 + 1 + identity_bytes.len() as u32 + 4).to_be_bytes(); // Convert the total length to bytes, adding 1 for the header and 4 for the identity length
-
-        let mut data_to_send = Vec::new();
-        let header_data_to_send = vec![0x01]; // Message type identifier for ShinkaiMessage
-        data_to_send.extend_from_slice(&total_length);
-        data_to_send.extend_from_slice(&identity_length);
-        data_to_send.extend(identity_bytes);
-        data_to_send.extend(header_data_to_send);
-        data_to_send.extend_from_slice(&encoded_msg);
+        let data_to_send = encode_network_frame(
+            NETWORK_FRAME_VERSION_1,
+            identity,
+            &NetworkMessageType::ShinkaiMessage,
+            &encoded_msg,
+        );
 
         let mut writer_lock = writer.lock().await;
         writer_lock
@@ -1146,6 +1177,7 @@ impl TCPProxy {
             .await
             .map_err(|_| NetworkMessageError::SendError)?;
         writer_lock.flush().await.map_err(|_| NetworkMessageError::SendError)?;
+        bandwidth.record(client_identity, BandwidthDirection::ToClient, data_to_send.len());
 
         println!("Message sent to client");
         Ok(())
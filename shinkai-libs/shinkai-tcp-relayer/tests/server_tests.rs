@@ -480,11 +480,7 @@ async fn send_network_message(socket: &mut tokio::net::TcpStream, msg: &NetworkM
     let total_length = (encoded_msg.len() as u32 + 1 + identity_bytes.len() as u32 + 4).to_be_bytes();
 
     let mut data_to_send = Vec::new();
-    let header_data_to_send = vec![match msg.message_type {
-        NetworkMessageType::ShinkaiMessage => 0x01,
-        NetworkMessageType::VRKaiPathPair => 0x02,
-        NetworkMessageType::ProxyMessage => 0x03,
-    }];
+    let header_data_to_send = vec![msg.message_type.header_byte()];
     data_to_send.extend_from_slice(&total_length);
     data_to_send.extend_from_slice(&identity_length);
     data_to_send.extend(identity_bytes);
@@ -0,0 +1,4 @@
+pub mod mock_embeddings;
+pub mod mock_llm_provider;
+pub mod shinkai_testing_framework;
+pub mod vecfs_test_utils;
@@ -0,0 +1,72 @@
+use mockito::{Mock, Server, ServerGuard};
+use serde_json::{json, Value};
+use shinkai_vector_resources::embedding_generator::{DeterministicEmbeddingGenerator, RemoteEmbeddingGenerator};
+use shinkai_vector_resources::model_type::EmbeddingModelType;
+
+/// Embeddings server speaking the Ollama embeddings API, answering with the embeddings of
+/// `DeterministicEmbeddingGenerator`. Nodes take a `RemoteEmbeddingGenerator`, which `remote_generator` points at
+/// this server. Setting EMBEDDINGS_SERVER_URL to `DETERMINISTIC_EMBEDDINGS_URL` gets the same embeddings without a
+/// server, this one is for tests that check the requests made.
+pub struct MockEmbeddingServer {
+    pub server: ServerGuard,
+    pub model_type: EmbeddingModelType,
+    /// Mocks are removed from the server once dropped
+    _embeddings_mock: Mock,
+}
+
+impl MockEmbeddingServer {
+    pub async fn start(model_type: EmbeddingModelType) -> Self {
+        let mut server = Server::new_async().await;
+        let generator = DeterministicEmbeddingGenerator::new(model_type.clone());
+        let embeddings_mock = server
+            .mock("POST", "/api/embeddings")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_request(move |request| {
+                let prompt = request
+                    .body()
+                    .ok()
+                    .and_then(|body| serde_json::from_slice::<Value>(body).ok())
+                    .and_then(|body| body["prompt"].as_str().map(String::from))
+                    .unwrap_or_default();
+                json!({ "embedding": generator.vector_for(&prompt) })
+                    .to_string()
+                    .into_bytes()
+            })
+            .create_async()
+            .await;
+        MockEmbeddingServer {
+            server,
+            model_type,
+            _embeddings_mock: embeddings_mock,
+        }
+    }
+
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    pub fn remote_generator(&self) -> RemoteEmbeddingGenerator {
+        RemoteEmbeddingGenerator::new(self.model_type.clone(), &self.url(), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shinkai_vector_resources::embedding_generator::EmbeddingGenerator;
+
+    #[tokio::test]
+    async fn test_mock_embedding_server() {
+        let generator = DeterministicEmbeddingGenerator::default();
+        let first = generator.generate_embedding("hello", "1").await.unwrap();
+
+        let server = MockEmbeddingServer::start(generator.model_type()).await;
+        let remote = server
+            .remote_generator()
+            .generate_embedding("hello", "1")
+            .await
+            .unwrap();
+        assert_eq!(remote.vector, first.vector);
+    }
+}
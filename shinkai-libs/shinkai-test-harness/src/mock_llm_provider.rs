@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use mockito::{Mock, Server, ServerGuard};
+use serde_json::{json, Value};
+use shinkai_message_primitives::schemas::llm_providers::serialized_llm_provider::{
+    LLMProviderInterface, OpenAI, SerializedLLMProvider,
+};
+use shinkai_message_primitives::schemas::shinkai_name::ShinkaiName;
+
+/// API key the mock LLM provider expects
+pub const MOCK_LLM_API_KEY: &str = "mockapikey";
+
+/// LLM provider server speaking the OpenAI chat completions API. It answers with the scripted answers in order,
+/// then keeps repeating the last one, and records the requests it got so tests can check the prompts.
+pub struct MockLLMProvider {
+    pub server: ServerGuard,
+    answers: Arc<Mutex<VecDeque<String>>>,
+    requests: Arc<Mutex<Vec<Value>>>,
+    /// Mocks are removed from the server once dropped
+    _completions_mock: Mock,
+}
+
+impl MockLLMProvider {
+    /// Starts the server, answering `default_answer` until other answers are scripted
+    pub async fn start(default_answer: &str) -> Self {
+        let mut server = Server::new_async().await;
+        let answers = Arc::new(Mutex::new(VecDeque::from([default_answer.to_string()])));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let (answers_ref, requests_ref) = (answers.clone(), requests.clone());
+        let completions_mock = server
+            .mock("POST", "/v1/chat/completions")
+            .match_header("authorization", format!("Bearer {}", MOCK_LLM_API_KEY).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_request(move |request| {
+                if let Some(body) = request.body().ok().and_then(|body| serde_json::from_slice(body).ok()) {
+                    requests_ref.lock().unwrap().push(body);
+                }
+                let mut answers = answers_ref.lock().unwrap();
+                let answer = match answers.len() > 1 {
+                    true => answers.pop_front().unwrap_or_default(),
+                    false => answers.front().cloned().unwrap_or_default(),
+                };
+                Self::completion(&answer).to_string().into_bytes()
+            })
+            .create_async()
+            .await;
+
+        MockLLMProvider {
+            server,
+            answers,
+            requests,
+            _completions_mock: completions_mock,
+        }
+    }
+
+    /// Replaces the answers to give, in order. The last one is repeated once the others are given.
+    pub fn script_answers(&self, answers: Vec<&str>) {
+        let mut scripted = self.answers.lock().unwrap();
+        scripted.clear();
+        scripted.extend(answers.into_iter().map(String::from));
+    }
+
+    /// Bodies of the chat completion requests received so far
+    pub fn requests(&self) -> Vec<Value> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// The LLM provider to register in a node so that its jobs are answered by this server
+    pub fn serialized_llm_provider(&self, id: &str, full_identity_name: ShinkaiName) -> SerializedLLMProvider {
+        SerializedLLMProvider {
+            id: id.to_string(),
+            full_identity_name,
+            perform_locally: false,
+            external_url: Some(self.url()),
+            api_key: Some(MOCK_LLM_API_KEY.to_string()),
+            model: LLMProviderInterface::OpenAI(OpenAI {
+                model_type: "gpt-4o-mini".to_string(),
+            }),
+            toolkit_permissions: vec![],
+            storage_bucket_permissions: vec![],
+            allowed_message_senders: vec![],
+        }
+    }
+
+    fn completion(answer: &str) -> Value {
+        json!({
+            "id": "chatcmpl-mock",
+            "object": "chat.completion",
+            "created": 1677652288,
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": answer
+                },
+                "finish_reason": "stop"
+            }],
+            "usage": {
+                "prompt_tokens": 0,
+                "completion_tokens": 0,
+                "total_tokens": 0
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_llm_provider_answers_in_order() {
+        let provider = MockLLMProvider::start("default").await;
+        provider.script_answers(vec!["first", "second"]);
+
+        let client = reqwest::Client::new();
+        let mut contents = Vec::new();
+        for _ in 0..3 {
+            let response: Value = client
+                .post(format!("{}/v1/chat/completions", provider.url()))
+                .bearer_auth(MOCK_LLM_API_KEY)
+                .json(&json!({ "model": "gpt-4o-mini", "messages": [] }))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            contents.push(
+                response["choices"][0]["message"]["content"]
+                    .as_str()
+                    .unwrap()
+                    .to_string(),
+            );
+        }
+        assert_eq!(contents, vec!["first", "second", "second"]);
+        assert_eq!(provider.requests().len(), 3);
+    }
+}
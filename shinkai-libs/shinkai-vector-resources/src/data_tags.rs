@@ -33,6 +33,36 @@ impl DataTag {
         }
     }
 
+    /// Built-in tags for the people, organizations and dates mentioned in a text. They're a lightweight take on
+    /// named entity recognition: people are found by their honorific or middle initial, organizations by their
+    /// legal suffix or institution kind, and dates in the common numeric and written formats.
+    pub fn entity_tags() -> Vec<DataTag> {
+        const MONTHS: &str = "(?:Jan(?:uary)?|Feb(?:ruary)?|Mar(?:ch)?|Apr(?:il)?|May|June?|July?|Aug(?:ust)?|Sep(?:t(?:ember)?)?|Oct(?:ober)?|Nov(?:ember)?|Dec(?:ember)?)";
+        let person = r"\b(?:(?:Mr|Mrs|Ms|Mx|Dr|Prof|Sir|Dame)\.? [A-Z][a-z]+(?: [A-Z][a-z]+)*|[A-Z][a-z]+ [A-Z]\. [A-Z][a-z]+)\b";
+        let organization = r"\b(?:(?:[A-Z][\w&'-]*,? )+(?:Inc|Corp|Corporation|LLC|Ltd|GmbH|PLC|Company|Group|Foundation|Institute|University|Association|Bank|Labs|Agency)\b|(?:University|Institute|Bank|Ministry|Department|Bureau) of(?: the)?(?: [A-Z][a-z]+)+)";
+        let date = format!(
+            r"\b(?:(?:19|20)\d\d-(?:0[1-9]|1[012])-(?:0[1-9]|[12]\d|3[01])|\d{{1,2}}/\d{{1,2}}/(?:19|20)?\d\d|{m}\.? \d{{1,2}}(?:st|nd|rd|th)?,? (?:19|20)\d\d|\d{{1,2}}(?:st|nd|rd|th)? {m}\.?,? (?:19|20)\d\d)\b",
+            m = MONTHS
+        );
+
+        [
+            (
+                "person",
+                "A person, named with their honorific or middle initial",
+                person,
+            ),
+            (
+                "organization",
+                "A company, institution or other organization",
+                organization,
+            ),
+            ("date", "A calendar date, written or numeric", date.as_str()),
+        ]
+        .into_iter()
+        .filter_map(|(name, description, regex_str)| DataTag::new(name, description, regex_str).ok())
+        .collect()
+    }
+
     /// Validates a list of tags and returns those that pass validation
     pub fn validate_tag_list(input_string: &str, tag_list: &[DataTag]) -> Vec<DataTag> {
         tag_list
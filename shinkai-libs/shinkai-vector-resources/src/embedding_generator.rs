@@ -6,10 +6,9 @@ use async_trait::async_trait;
 use lazy_static::lazy_static;
 #[cfg(feature = "desktop-only")]
 use reqwest::blocking::Client;
-#[cfg(feature = "desktop-only")]
-use reqwest::Client as AsyncClient;
 use serde::{Deserialize, Serialize};
 use reqwest::ClientBuilder;
+use std::sync::RwLock;
 use std::time::Duration;
 
 lazy_static! {
@@ -17,6 +16,45 @@ lazy_static! {
     pub static ref DEFAULT_EMBEDDINGS_LOCAL_URL: &'static str = "http://localhost:11434/";
 }
 
+/// Embeddings server url (EMBEDDINGS_SERVER_URL) that makes a RemoteEmbeddingGenerator generate deterministic
+/// embeddings itself instead of calling a server, for tests
+pub const DETERMINISTIC_EMBEDDINGS_URL: &str = "deterministic";
+
+lazy_static! {
+    static ref EMBEDDINGS_PROXY: RwLock<Option<String>> = RwLock::new(None);
+}
+
+/// Sends the requests of the remote embedding generators through a proxy, e.g. `socks5h://127.0.0.1:9050`, or
+/// directly when None
+pub fn set_embeddings_proxy(proxy_url: Option<String>) {
+    *EMBEDDINGS_PROXY.write().unwrap() = proxy_url;
+}
+
+#[cfg(feature = "desktop-only")]
+fn embeddings_proxy() -> Option<reqwest::Proxy> {
+    EMBEDDINGS_PROXY
+        .read()
+        .unwrap()
+        .as_deref()
+        .and_then(|proxy_url| reqwest::Proxy::all(proxy_url).ok())
+}
+
+#[cfg(feature = "desktop-only")]
+fn embeddings_client_builder() -> ClientBuilder {
+    match embeddings_proxy() {
+        Some(proxy) => ClientBuilder::new().proxy(proxy),
+        None => ClientBuilder::new(),
+    }
+}
+
+#[cfg(feature = "desktop-only")]
+fn embeddings_blocking_client_builder() -> reqwest::blocking::ClientBuilder {
+    match embeddings_proxy() {
+        Some(proxy) => Client::builder().proxy(proxy),
+        None => Client::builder(),
+    }
+}
+
 /// A trait for types that can generate embeddings from text.
 #[async_trait]
 pub trait EmbeddingGenerator: Sync + Send {
@@ -69,6 +107,111 @@ pub trait EmbeddingGenerator: Sync + Send {
         let ids: Vec<String> = vec!["".to_string(); input_strings.len()];
         self.generate_embeddings(input_strings, &ids).await
     }
+
+    /// Generates an embedding from the given image (the bytes of the image file), in the same space as the text
+    /// embeddings, and assigns the provided id. Only models that support images can embed them.
+    async fn generate_image_embedding(&self, _image: &[u8], _id: &str) -> Result<Embedding, VRError> {
+        Err(VRError::FailedEmbeddingGeneration(format!(
+            "{} does not support embedding images",
+            self.model_type()
+        )))
+    }
+}
+
+/// Generates pseudo-embeddings from the hash of the text, without any network. The same text always gets the same
+/// embedding, different texts get unrelated ones. Meant for tests, where searching a text finds itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeterministicEmbeddingGenerator {
+    pub model_type: EmbeddingModelType,
+}
+
+impl DeterministicEmbeddingGenerator {
+    /// Dimensions of the embeddings of models whose dimensions aren't known
+    const DEFAULT_DIMENSIONS: usize = 384;
+
+    pub fn new(model_type: EmbeddingModelType) -> Self {
+        DeterministicEmbeddingGenerator { model_type }
+    }
+
+    /// Unit vector of the dimensions of the model, derived from the blake3 hash of the text
+    pub fn vector_for(&self, text: &str) -> Vec<f32> {
+        self.vector_for_bytes(text.as_bytes())
+    }
+
+    /// Unit vector of the dimensions of the model, derived from the blake3 hash of the bytes
+    pub fn vector_for_bytes(&self, input: &[u8]) -> Vec<f32> {
+        let dimensions = self.model_type.vector_dimensions().unwrap_or(Self::DEFAULT_DIMENSIONS);
+        let mut bytes = vec![0u8; dimensions];
+        blake3::Hasher::new().update(input).finalize_xof().fill(&mut bytes);
+        let vector: Vec<f32> = bytes.iter().map(|byte| *byte as f32 / 127.5 - 1.0).collect();
+        let norm = vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+        if norm == 0.0 {
+            return vector;
+        }
+        vector.into_iter().map(|value| value / norm).collect()
+    }
+}
+
+impl Default for DeterministicEmbeddingGenerator {
+    /// Generates embeddings as the default embedding model
+    fn default() -> Self {
+        DeterministicEmbeddingGenerator::new(EmbeddingModelType::OllamaTextEmbeddingsInference(
+            OllamaTextEmbeddingsInference::SnowflakeArcticEmbed_M,
+        ))
+    }
+}
+
+#[async_trait]
+impl EmbeddingGenerator for DeterministicEmbeddingGenerator {
+    fn model_type(&self) -> EmbeddingModelType {
+        self.model_type.clone()
+    }
+
+    fn set_model_type(&mut self, model_type: EmbeddingModelType) {
+        self.model_type = model_type;
+    }
+
+    fn box_clone(&self) -> Box<dyn EmbeddingGenerator> {
+        Box::new(self.clone())
+    }
+
+    fn generate_embedding_blocking(&self, input_string: &str, id: &str) -> Result<Embedding, VRError> {
+        Ok(Embedding::new(id, self.vector_for(input_string)))
+    }
+
+    fn generate_embeddings_blocking(
+        &self,
+        input_strings: &Vec<String>,
+        ids: &Vec<String>,
+    ) -> Result<Vec<Embedding>, VRError> {
+        input_strings
+            .iter()
+            .zip(ids)
+            .map(|(input_string, id)| self.generate_embedding_blocking(input_string, id))
+            .collect()
+    }
+
+    async fn generate_embedding(&self, input_string: &str, id: &str) -> Result<Embedding, VRError> {
+        self.generate_embedding_blocking(input_string, id)
+    }
+
+    async fn generate_embeddings(
+        &self,
+        input_strings: &Vec<String>,
+        ids: &Vec<String>,
+    ) -> Result<Vec<Embedding>, VRError> {
+        self.generate_embeddings_blocking(input_strings, ids)
+    }
+
+    async fn generate_image_embedding(&self, image: &[u8], id: &str) -> Result<Embedding, VRError> {
+        if !self.model_type.supports_images() {
+            return Err(VRError::FailedEmbeddingGeneration(format!(
+                "{} does not support embedding images",
+                self.model_type
+            )));
+        }
+        Ok(Embedding::new(id, self.vector_for_bytes(image)))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -96,13 +239,17 @@ impl EmbeddingGenerator for RemoteEmbeddingGenerator {
         input_strings: &Vec<String>,
         ids: &Vec<String>,
     ) -> Result<Vec<Embedding>, VRError> {
+        if self.api_url == DETERMINISTIC_EMBEDDINGS_URL {
+            return DeterministicEmbeddingGenerator::new(self.model_type.clone())
+                .generate_embeddings_blocking(input_strings, ids);
+        }
         let input_strings: Vec<String> = input_strings
             .iter()
             .map(|s| s.chars().take(self.model_type.max_input_token_count()).collect())
             .collect();
 
         match self.model_type {
-            EmbeddingModelType::TextEmbeddingsInference(_) => {
+            EmbeddingModelType::TextEmbeddingsInference(_) | EmbeddingModelType::ClipEmbeddingsInference(_) => {
                 self.generate_embedding_tei_blocking(input_strings.clone(), ids.clone())
             }
             EmbeddingModelType::OllamaTextEmbeddingsInference(_) => {
@@ -153,13 +300,18 @@ impl EmbeddingGenerator for RemoteEmbeddingGenerator {
         input_strings: &Vec<String>,
         ids: &Vec<String>,
     ) -> Result<Vec<Embedding>, VRError> {
+        if self.api_url == DETERMINISTIC_EMBEDDINGS_URL {
+            return DeterministicEmbeddingGenerator::new(self.model_type.clone())
+                .generate_embeddings(input_strings, ids)
+                .await;
+        }
         let input_strings: Vec<String> = input_strings
             .iter()
             .map(|s| s.chars().take(self.model_type.max_input_token_count()).collect())
             .collect();
 
         match self.model_type.clone() {
-            EmbeddingModelType::TextEmbeddingsInference(_) => {
+            EmbeddingModelType::TextEmbeddingsInference(_) | EmbeddingModelType::ClipEmbeddingsInference(_) => {
                 self.generate_embedding_tei(input_strings.clone(), ids.clone()).await
             }
             EmbeddingModelType::OllamaTextEmbeddingsInference(model) => {
@@ -203,6 +355,56 @@ impl EmbeddingGenerator for RemoteEmbeddingGenerator {
         }
     }
 
+    #[cfg(feature = "desktop-only")]
+    /// Generate an Embedding for an image by using the `embed_image` endpoint of the external API, which image
+    /// embedding models (CLIP) serve next to the `embed` one used for text.
+    async fn generate_image_embedding(&self, image: &[u8], id: &str) -> Result<Embedding, VRError> {
+        if self.api_url == DETERMINISTIC_EMBEDDINGS_URL {
+            return DeterministicEmbeddingGenerator::new(self.model_type.clone())
+                .generate_image_embedding(image, id)
+                .await;
+        }
+        if !self.model_type.supports_images() {
+            return Err(VRError::FailedEmbeddingGeneration(format!(
+                "{} does not support embedding images",
+                self.model_type
+            )));
+        }
+
+        let request_body = EmbeddingArrayRequestBody {
+            inputs: vec![base64::encode(image)],
+        };
+        let client = embeddings_client_builder().timeout(Duration::from_secs(60)).build()?;
+        let mut request = client
+            .post(self.clip_image_endpoint_url())
+            .header("Content-Type", "application/json")
+            .json(&request_body);
+        if let Some(api_key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|err| VRError::RequestFailed(format!("HTTP request failed: {}", err)))?;
+        if !response.status().is_success() {
+            return Err(VRError::RequestFailed(format!(
+                "HTTP request failed with status: {}",
+                response.status()
+            )));
+        }
+        let embeddings = response
+            .json::<Vec<Vec<f32>>>()
+            .await
+            .map_err(|err| VRError::RequestFailed(format!("Failed to deserialize response JSON: {}", err)))?;
+        match embeddings.into_iter().next() {
+            Some(vector) => Ok(Embedding::new(id, vector)),
+            None => Err(VRError::FailedEmbeddingGeneration(
+                "No results returned from the embedding generation".to_string(),
+            )),
+        }
+    }
+
     /// Returns the EmbeddingModelType
     fn model_type(&self) -> EmbeddingModelType {
         self.model_type.clone()
@@ -256,6 +458,16 @@ impl RemoteEmbeddingGenerator {
         }
     }
 
+    /// String of the endpoint url for generating image embeddings via
+    /// an image embedding model (CLIP) server
+    fn clip_image_endpoint_url(&self) -> String {
+        if self.api_url.ends_with('/') {
+            format!("{}embed_image", self.api_url)
+        } else {
+            format!("{}/embed_image", self.api_url)
+        }
+    }
+
     /// String of the main endpoint url for generating embeddings via
     /// Ollama Text Embedding Interface server
     fn ollama_endpoint_url(&self) -> String {
@@ -289,7 +501,7 @@ impl RemoteEmbeddingGenerator {
 
             // Create the HTTP client with a custom timeout
             let timeout = Duration::from_secs(60);
-            let client = ClientBuilder::new().timeout(timeout).build()?;
+            let client = embeddings_client_builder().timeout(timeout).build()?;
 
             // Build the request
             let mut request = client
@@ -376,7 +588,7 @@ impl RemoteEmbeddingGenerator {
         };
 
         // Create the HTTP client
-        let client = Client::new();
+        let client = embeddings_blocking_client_builder().build().unwrap_or_default();
 
         // Build the request
         let mut request = client
@@ -436,7 +648,7 @@ impl RemoteEmbeddingGenerator {
 
             // Create the HTTP client with a custom timeout
             let timeout = Duration::from_secs(60);
-            let client = ClientBuilder::new().timeout(timeout).build()?;
+            let client = embeddings_client_builder().timeout(timeout).build()?;
 
             // Build the request
             let mut request = client
@@ -542,7 +754,7 @@ impl RemoteEmbeddingGenerator {
 
         // Create the HTTP client with a custom timeout
         let timeout = Duration::from_secs(60); // Set the desired timeout duration
-        let client = Client::builder()
+        let client = embeddings_blocking_client_builder()
             .timeout(timeout)
             .build()
             .map_err(|err| VRError::RequestFailed(format!("Failed to create HTTP client: {}", err)))?;
@@ -631,7 +843,7 @@ impl RemoteEmbeddingGenerator {
         };
 
         // Create the HTTP client
-        let client = AsyncClient::new();
+        let client = embeddings_client_builder().build().unwrap_or_default();
 
         // Build the request
         let mut request = client
@@ -683,7 +895,7 @@ impl RemoteEmbeddingGenerator {
         };
 
         // Create the HTTP client
-        let client = Client::new();
+        let client = embeddings_blocking_client_builder().build().unwrap_or_default();
 
         // Build the request
         let mut request = client
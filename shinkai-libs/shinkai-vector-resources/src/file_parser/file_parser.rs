@@ -103,6 +103,7 @@ impl ShinkaiFileParser {
 
     #[cfg(feature = "desktop-only")]
     /// Processes the input file into a list of `TextGroup` with no embedding generated yet.
+    /// Every text group is tagged with the language it's in.
     pub async fn process_file_into_text_groups(
         file_buffer: Vec<u8>,
         file_name: String,
@@ -110,20 +111,23 @@ impl ShinkaiFileParser {
         source: VRSourceReference,
         file_parser: FileParser,
     ) -> Result<Vec<TextGroup>, VRError> {
-        match file_parser {
+        let mut text_groups = match file_parser {
             FileParser::Local => {
-                LocalFileParser::process_file_into_grouped_text(file_buffer, file_name, max_node_text_size, source)
+                LocalFileParser::process_file_into_grouped_text(file_buffer, file_name, max_node_text_size, source)?
             }
             FileParser::Unstructured(unstructured_api) => {
                 unstructured_api
                     .process_file_into_grouped_text(file_buffer, file_name, max_node_text_size)
-                    .await
+                    .await?
             }
-        }
+        };
+        Self::tag_text_groups_language(&mut text_groups);
+        Ok(text_groups)
     }
 
     #[cfg(feature = "desktop-only")]
     /// Processes the input file into a list of `TextGroup` with no embedding generated yet.
+    /// Every text group is tagged with the language it's in.
     pub fn process_file_into_text_groups_blocking(
         file_buffer: Vec<u8>,
         file_name: String,
@@ -131,14 +135,16 @@ impl ShinkaiFileParser {
         source: VRSourceReference,
         file_parser: FileParser,
     ) -> Result<Vec<TextGroup>, VRError> {
-        match file_parser {
+        let mut text_groups = match file_parser {
             FileParser::Local => {
-                LocalFileParser::process_file_into_grouped_text(file_buffer, file_name, max_node_text_size, source)
+                LocalFileParser::process_file_into_grouped_text(file_buffer, file_name, max_node_text_size, source)?
             }
             FileParser::Unstructured(unstructured_api) => {
-                unstructured_api.process_file_into_grouped_text_blocking(file_buffer, file_name, max_node_text_size)
+                unstructured_api.process_file_into_grouped_text_blocking(file_buffer, file_name, max_node_text_size)?
             }
-        }
+        };
+        Self::tag_text_groups_language(&mut text_groups);
+        Ok(text_groups)
     }
 
     #[cfg(feature = "desktop-only")]
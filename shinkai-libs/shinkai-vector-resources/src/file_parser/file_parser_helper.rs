@@ -5,6 +5,7 @@ use reqwest::Url;
 use std::collections::HashMap;
 
 use super::file_parser::ShinkaiFileParser;
+use super::file_parser_tables::ParsedTable;
 use super::file_parser_types::TextGroup;
 use crate::vector_resource::SourceFileType;
 
@@ -18,6 +19,21 @@ impl ShinkaiFileParser {
         "pg_nums".to_string()
     }
 
+    /// Key of table metadata: the `ParsedTable` held by the node, as JSON
+    pub fn table_metadata_key() -> String {
+        "table".to_string()
+    }
+
+    /// Key of character offsets metadata: `[start, end]` of the text inside its page
+    pub fn char_offsets_metadata_key() -> String {
+        "char_offsets".to_string()
+    }
+
+    /// Key of language metadata: the ISO 639-3 code of the language the text is in (ie. `spa`)
+    pub fn language_metadata_key() -> String {
+        "language".to_string()
+    }
+
     /// Key of datetime metadata
     pub fn datetime_metadata_key() -> String {
         "datetime".to_string()
@@ -387,29 +403,43 @@ impl ShinkaiFileParser {
     ) {
         if !text.is_empty() {
             let created_text_groups = ShinkaiFileParser::parse_and_split_into_text_groups(text, max_node_text_size);
+            Self::push_text_groups_by_depth(text_groups, depth, created_text_groups);
+        }
+    }
 
-            if depth > 0 {
-                let mut parent_group = text_groups.last_mut();
-                for _ in 1..depth {
-                    if let Some(last_group) = parent_group {
-                        parent_group = last_group.sub_groups.last_mut();
-                    }
-                }
+    /// Pushes a table at the given depth, kept whole in its own text groups
+    pub fn push_table_by_depth(
+        text_groups: &mut Vec<TextGroup>,
+        depth: usize,
+        table: ParsedTable,
+        max_node_text_size: u64,
+    ) {
+        Self::push_text_groups_by_depth(text_groups, depth, table.into_text_groups(max_node_text_size));
+    }
 
+    /// Internal method used to push already created text groups at the given depth
+    fn push_text_groups_by_depth(text_groups: &mut Vec<TextGroup>, depth: usize, created_text_groups: Vec<TextGroup>) {
+        if depth > 0 {
+            let mut parent_group = text_groups.last_mut();
+            for _ in 1..depth {
                 if let Some(last_group) = parent_group {
-                    for text_group in created_text_groups {
-                        last_group.push_sub_group(text_group);
-                    }
-                } else {
-                    for text_group in created_text_groups {
-                        text_groups.push(text_group);
-                    }
+                    parent_group = last_group.sub_groups.last_mut();
+                }
+            }
+
+            if let Some(last_group) = parent_group {
+                for text_group in created_text_groups {
+                    last_group.push_sub_group(text_group);
                 }
             } else {
                 for text_group in created_text_groups {
                     text_groups.push(text_group);
                 }
             }
+        } else {
+            for text_group in created_text_groups {
+                text_groups.push(text_group);
+            }
         }
     }
 }
@@ -0,0 +1,119 @@
+use whatlang::Lang;
+
+use super::file_parser::ShinkaiFileParser;
+use super::file_parser_types::TextGroup;
+
+/// Text shorter than this is too little to detect its language reliably, so it takes the language of its document
+const MIN_LANGUAGE_DETECTION_CHARS: usize = 60;
+/// How much of a document's text is used to detect the language of the whole document
+const DOCUMENT_LANGUAGE_SAMPLE_CHARS: usize = 10000;
+
+impl ShinkaiFileParser {
+    /// ISO 639-3 code of the English language, which the default embedding models are trained on
+    pub const ENGLISH_LANGUAGE_CODE: &'static str = "eng";
+
+    /// Detects the language of the text, returning its ISO 639-3 code (ie. `eng`, `spa`).
+    /// Returns None if the text is too short or the detection isn't reliable.
+    pub fn detect_language(text: &str) -> Option<String> {
+        if text.trim().chars().count() < MIN_LANGUAGE_DETECTION_CHARS {
+            return None;
+        }
+        whatlang::detect(text)
+            .filter(|info| info.is_reliable())
+            .map(|info| info.lang().code().to_string())
+    }
+
+    /// Whether the ISO 639-3 language code is English
+    pub fn is_english_language(language: &str) -> bool {
+        language == Self::ENGLISH_LANGUAGE_CODE
+    }
+
+    /// English name of the language of the ISO 639-3 code (ie. `Spanish` for `spa`), used in prompts
+    pub fn language_name(language: &str) -> Option<String> {
+        Lang::from_code(language).map(|lang| lang.eng_name().to_string())
+    }
+
+    /// Detects the language of the document made of the text groups, and tags every text group (and sub group)
+    /// with its own language in its metadata. Groups too short to be detected on their own take the language of
+    /// the document. Returns the language of the document, if it could be detected.
+    pub fn tag_text_groups_language(text_groups: &mut [TextGroup]) -> Option<String> {
+        let document_language = Self::text_groups_language(text_groups);
+        Self::tag_text_groups_language_recursive(text_groups, document_language.as_deref());
+        document_language
+    }
+
+    /// Detects the language of the document made of the text groups, from the beginning of its text
+    pub fn text_groups_language(text_groups: &[TextGroup]) -> Option<String> {
+        let mut sample = String::new();
+        Self::collect_text_sample(text_groups, &mut sample);
+        Self::detect_language(&sample)
+    }
+
+    /// The language that appears the most in the list of ISO 639-3 codes, if there are any
+    pub fn most_common_language<'a>(languages: impl Iterator<Item = &'a str>) -> Option<String> {
+        let mut counts: Vec<(&str, usize)> = vec![];
+        for language in languages {
+            match counts.iter_mut().find(|(counted, _)| *counted == language) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((language, 1)),
+            }
+        }
+        counts
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(language, _)| language.to_string())
+    }
+
+    fn collect_text_sample(text_groups: &[TextGroup], sample: &mut String) {
+        for group in text_groups {
+            if sample.len() >= DOCUMENT_LANGUAGE_SAMPLE_CHARS {
+                return;
+            }
+            sample.push_str(&group.text);
+            sample.push('\n');
+            Self::collect_text_sample(&group.sub_groups, sample);
+        }
+    }
+
+    fn tag_text_groups_language_recursive(text_groups: &mut [TextGroup], document_language: Option<&str>) {
+        for group in text_groups {
+            let language = Self::detect_language(&group.text).or_else(|| document_language.map(str::to_string));
+            if let Some(language) = language {
+                group
+                    .metadata
+                    .insert(ShinkaiFileParser::language_metadata_key(), language);
+            }
+            Self::tag_text_groups_language_recursive(&mut group.sub_groups, document_language);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_tag_text_groups_language() {
+        let spanish = "El contrato de arrendamiento establece que el inquilino debe pagar la renta durante los \
+                       primeros cinco días de cada mes, y que el propietario se encarga de las reparaciones.";
+        let english = "The lease agreement states that the tenant must pay the rent during the first five days \
+                       of every month, and that the landlord takes care of the repairs.";
+        let mut text_groups = vec![
+            TextGroup::new(spanish.to_string(), HashMap::new(), vec![], None),
+            TextGroup::new(spanish.to_string(), HashMap::new(), vec![], None),
+            TextGroup::new("Cláusula 3".to_string(), HashMap::new(), vec![], None),
+            TextGroup::new(english.to_string(), HashMap::new(), vec![], None),
+        ];
+
+        let document_language = ShinkaiFileParser::tag_text_groups_language(&mut text_groups);
+
+        let key = ShinkaiFileParser::language_metadata_key();
+        assert_eq!(document_language.as_deref(), Some("spa"));
+        assert_eq!(text_groups[0].metadata.get(&key).map(|l| l.as_str()), Some("spa"));
+        // Too short to be detected, so it takes the document's language
+        assert_eq!(text_groups[2].metadata.get(&key).map(|l| l.as_str()), Some("spa"));
+        assert_eq!(text_groups[3].metadata.get(&key).map(|l| l.as_str()), Some("eng"));
+        assert_eq!(ShinkaiFileParser::language_name("spa").as_deref(), Some("Spanish"));
+    }
+}
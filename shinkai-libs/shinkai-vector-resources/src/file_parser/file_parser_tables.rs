@@ -0,0 +1,250 @@
+use csv::ReaderBuilder;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use super::file_parser::ShinkaiFileParser;
+use super::file_parser_types::TextGroup;
+use crate::resource_errors::VRError;
+
+/// Tables are kept in a single node while their markdown is up to this many times the max node text size.
+/// Bigger tables are split by rows, with every part repeating the headers.
+const MAX_TABLE_NODE_SIZE_MULTIPLIER: usize = 4;
+/// Minimum lines for text to be detected as a table
+const MIN_TABLE_LINES: usize = 3;
+
+/// A table found while parsing a file (a CSV, a spreadsheet sheet, a table in a PDF or an HTML page).
+/// Tables are stored whole in a node instead of being chunked as prose: the node text is the table as markdown,
+/// and the node metadata holds the table itself so it can be queried by column.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParsedTable {
+    /// Name of the sheet or caption of the table, if any
+    pub name: Option<String>,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl ParsedTable {
+    pub fn new(name: Option<String>, headers: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+        ParsedTable { name, headers, rows }
+    }
+
+    /// Creates a table taking the first row as the headers. Returns None if it isn't a table
+    /// (less than 2 columns or no data rows).
+    pub fn from_rows(name: Option<String>, mut rows: Vec<Vec<String>>) -> Option<Self> {
+        rows.retain(|row| row.iter().any(|cell| !cell.trim().is_empty()));
+        if rows.len() < 2 {
+            return None;
+        }
+
+        let headers: Vec<String> = rows.remove(0).into_iter().map(|cell| cell.trim().to_string()).collect();
+        if headers.len() < 2 {
+            return None;
+        }
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                let mut row: Vec<String> = row.into_iter().map(|cell| cell.trim().to_string()).collect();
+                row.resize(headers.len(), String::new());
+                row
+            })
+            .collect();
+
+        Some(Self::new(name, headers, rows))
+    }
+
+    /// Parses a CSV file into a table. The first record is used as the headers.
+    pub fn from_csv(buffer: &[u8]) -> Result<Self, VRError> {
+        let mut reader = ReaderBuilder::new()
+            .flexible(true)
+            .has_headers(false)
+            .from_reader(Cursor::new(buffer));
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|_| VRError::FailedCSVParsing)?;
+            rows.push(record.iter().map(String::from).collect());
+        }
+
+        Self::from_rows(None, rows).ok_or(VRError::FailedCSVParsing)
+    }
+
+    /// Parses the first `<table>` of an HTML fragment, as provided by Unstructured in `text_as_html`
+    pub fn from_html(html: &str, name: Option<String>) -> Option<Self> {
+        let fragment = Html::parse_fragment(html);
+        let table_selector = Selector::parse("table").ok()?;
+        let row_selector = Selector::parse("tr").ok()?;
+        let cell_selector = Selector::parse("th, td").ok()?;
+
+        let table = fragment.select(&table_selector).next()?;
+        let rows = table
+            .select(&row_selector)
+            .map(|row| {
+                row.select(&cell_selector)
+                    .map(|cell| cell.text().collect::<Vec<_>>().join(" "))
+                    .collect()
+            })
+            .collect();
+
+        Self::from_rows(name, rows)
+    }
+
+    /// Parses a markdown table, as written by `to_markdown`
+    pub fn from_markdown(markdown: &str) -> Option<Self> {
+        let mut name = None;
+        let mut rows = Vec::new();
+        for line in markdown.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if !line.starts_with('|') {
+                if rows.is_empty() {
+                    name = Some(line.trim_start_matches('#').trim().to_string());
+                }
+                continue;
+            }
+
+            // Escaped pipes are part of the cells
+            let cells: Vec<String> = line
+                .replace("\\|", "\u{0}")
+                .trim_matches('|')
+                .split('|')
+                .map(|cell| cell.trim().replace('\u{0}', "|"))
+                .collect();
+            // Skip the separator line between the headers and the rows
+            if cells
+                .iter()
+                .all(|cell| !cell.is_empty() && cell.chars().all(|c| c == '-' || c == ':'))
+            {
+                continue;
+            }
+            rows.push(cells);
+        }
+
+        Self::from_rows(name, rows)
+    }
+
+    /// Detects text laid out as a table: at least 3 lines with the same number (2 or more) of cells
+    /// separated by tabs or by 2 or more spaces. PDF parsers output tables like that.
+    pub fn detect_in_text(text: &str) -> Option<Self> {
+        let lines: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).collect();
+        if lines.len() < MIN_TABLE_LINES {
+            return None;
+        }
+
+        let rows: Vec<Vec<String>> = lines
+            .iter()
+            .map(|line| {
+                line.split(|c| c == '\t')
+                    .flat_map(|cell| cell.split("  "))
+                    .map(str::trim)
+                    .filter(|cell| !cell.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .collect();
+
+        let columns = rows[0].len();
+        if columns < 2 || rows.iter().any(|row| row.len() != columns) {
+            return None;
+        }
+
+        Self::from_rows(None, rows)
+    }
+
+    /// Index of a column, ignoring case
+    pub fn column_index(&self, column: &str) -> Option<usize> {
+        let column = column.trim().to_lowercase();
+        self.headers.iter().position(|header| header.to_lowercase() == column)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let escape = |cell: &String| cell.replace('|', "\\|").replace('\n', " ");
+        let format_row = |row: &Vec<String>| format!("| {} |", row.iter().map(escape).collect::<Vec<_>>().join(" | "));
+
+        let mut lines = Vec::with_capacity(self.rows.len() + 3);
+        if let Some(name) = &self.name {
+            lines.push(name.clone());
+        }
+        lines.push(format_row(&self.headers));
+        lines.push(format!("|{}|", vec!["---"; self.headers.len()].join("|")));
+        lines.extend(self.rows.iter().map(format_row));
+        lines.join("\n")
+    }
+
+    /// Splits the table by rows into tables whose markdown fits in `max_text_size`, every part repeating the
+    /// headers. Rows are never split, so a single row bigger than the limit gets a part of its own.
+    pub fn split_by_rows(self, max_text_size: usize) -> Vec<ParsedTable> {
+        if self.to_markdown().len() <= max_text_size {
+            return vec![self];
+        }
+
+        let header_size = ParsedTable::new(self.name.clone(), self.headers.clone(), vec![])
+            .to_markdown()
+            .len();
+        let mut parts = Vec::new();
+        let mut current_rows: Vec<Vec<String>> = Vec::new();
+        let mut current_size = header_size;
+        for row in self.rows {
+            // Each row takes its cells plus the `| ` and ` | ` separators
+            let row_size = row.iter().map(|cell| cell.len() + 3).sum::<usize>() + 2;
+            if !current_rows.is_empty() && current_size + row_size > max_text_size {
+                parts.push(ParsedTable::new(
+                    self.name.clone(),
+                    self.headers.clone(),
+                    std::mem::take(&mut current_rows),
+                ));
+                current_size = header_size;
+            }
+            current_size += row_size;
+            current_rows.push(row);
+        }
+        if !current_rows.is_empty() {
+            parts.push(ParsedTable::new(self.name.clone(), self.headers.clone(), current_rows));
+        }
+
+        parts
+    }
+
+    /// Creates the text groups holding the table. Tables up to 4 times `max_node_text_size` are kept whole.
+    pub fn into_text_groups(self, max_node_text_size: u64) -> Vec<TextGroup> {
+        let max_table_size = max_node_text_size as usize * MAX_TABLE_NODE_SIZE_MULTIPLIER;
+        self.split_by_rows(max_table_size)
+            .into_iter()
+            .map(|table| {
+                let mut metadata = HashMap::new();
+                metadata.insert(
+                    ShinkaiFileParser::table_metadata_key(),
+                    serde_json::to_string(&table).unwrap_or_default(),
+                );
+                TextGroup::new(table.to_markdown(), metadata, vec![], None)
+            })
+            .collect()
+    }
+
+    /// The table held in the metadata of a node or text group, if it's a table
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Option<Self> {
+        let table = metadata.get(&ShinkaiFileParser::table_metadata_key())?;
+        serde_json::from_str(table).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_round_trips_and_splits_by_rows() {
+        let table = ParsedTable::from_csv(b"Name,Price\nApple,1.5\nPear,2\nKiwi | Gold,0.75\n").unwrap();
+        assert_eq!(table.headers, vec!["Name", "Price"]);
+        assert_eq!(table.rows.len(), 3);
+        assert_eq!(ParsedTable::from_markdown(&table.to_markdown()), Some(table.clone()));
+
+        let parts = table.clone().split_by_rows(40);
+        assert!(parts.len() > 1);
+        assert!(parts.iter().all(|part| part.headers == table.headers));
+        assert_eq!(parts.iter().map(|part| part.rows.len()).sum::<usize>(), 3);
+
+        let detected = ParsedTable::detect_in_text("Name    Price\nApple   1.5\nPear\t2").unwrap();
+        assert_eq!(detected.rows[1], vec!["Pear", "2"]);
+        assert!(ParsedTable::detect_in_text("Just a sentence.\nAnother one.\nAnd a third one.").is_none());
+    }
+}
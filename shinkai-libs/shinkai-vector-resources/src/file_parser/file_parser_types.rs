@@ -42,6 +42,11 @@ impl TextGroup {
     /// Extracts most prevalent keywords from all sub-groups and appends them to
     /// the end of the groups actual text.
     pub fn format_text_for_embedding(&self, max_node_text_size: u64) -> String {
+        // Tables can be bigger than a node, so only their beginning (headers and first rows) is embedded
+        if self.metadata.contains_key(&ShinkaiFileParser::table_metadata_key()) {
+            return self.text.chars().take(max_node_text_size as usize).collect();
+        }
+
         let mut keyword_string = String::new();
         let base_string = &self.text;
         let pre_keyword_length = base_string.len();
@@ -110,6 +115,18 @@ impl TextGroup {
         }
     }
 
+    /// Anchors this TextGroup to the range of characters it was parsed from in a single page
+    pub fn set_page_anchor(&mut self, page_number: u32, start: usize, end: usize) {
+        self.metadata.insert(
+            ShinkaiFileParser::page_numbers_metadata_key(),
+            format!("[{}]", page_number),
+        );
+        self.metadata.insert(
+            ShinkaiFileParser::char_offsets_metadata_key(),
+            format!("[{}, {}]", start, end),
+        );
+    }
+
     /// Pushes a sub-group into this TextGroup
     pub fn push_sub_group(&mut self, sub_group: TextGroup) {
         self.sub_groups.push(sub_group);
@@ -1,6 +1,6 @@
 use super::LocalFileParser;
 use crate::{
-    file_parser::{file_parser::ShinkaiFileParser, file_parser_types::TextGroup},
+    file_parser::{file_parser::ShinkaiFileParser, file_parser_tables::ParsedTable, file_parser_types::TextGroup},
     resource_errors::VRError,
 };
 use csv::ReaderBuilder;
@@ -8,7 +8,12 @@ use std::{collections::HashMap, io::Cursor};
 
 impl LocalFileParser {
     /// Attempts to process the provided csv file into a list of TextGroups.
+    /// CSVs with a header and 2 or more columns are kept whole as tables, the rest are processed line by line.
     pub fn process_csv_file(file_buffer: Vec<u8>, max_node_text_size: u64) -> Result<Vec<TextGroup>, VRError> {
+        if let Ok(table) = ParsedTable::from_csv(&file_buffer) {
+            return Ok(table.into_text_groups(max_node_text_size));
+        }
+
         let csv_lines = Self::parse_csv_auto(&file_buffer).map_err(|_| VRError::FailedCSVParsing)?;
 
         let mut text_groups = Vec::new();
@@ -7,7 +7,7 @@ use docx_rust::{
 };
 
 use crate::{
-    file_parser::{file_parser::ShinkaiFileParser, file_parser_types::TextGroup},
+    file_parser::{file_parser::ShinkaiFileParser, file_parser_tables::ParsedTable, file_parser_types::TextGroup},
     resource_errors::VRError,
 };
 
@@ -132,23 +132,25 @@ impl LocalFileParser {
                 }
             }
             BodyContent::Table(table) => {
-                let mut row_text = Vec::new();
+                let mut rows = Vec::new();
                 table.rows.iter().for_each(|row| {
-                    let mut cell_text = Vec::new();
+                    let mut cells = Vec::new();
 
                     row.cells.iter().for_each(|cell| match cell {
                         TableRowContent::TableCell(cell) => {
+                            let mut cell_text = Vec::new();
                             cell.content.iter().for_each(|content| match content {
                                 TableCellContent::Paragraph(paragraph) => {
                                     let text = paragraph.text();
                                     cell_text.push(text);
                                 }
                             });
+                            cells.push(cell_text.join(" "));
                         }
                         _ => {}
                     });
 
-                    row_text.push(cell_text.join("; "));
+                    rows.push(cells);
                 });
 
                 ShinkaiFileParser::push_text_group_by_depth(
@@ -159,13 +161,26 @@ impl LocalFileParser {
                 );
                 current_text = "".to_string();
 
-                let table_text = row_text.join("\n");
-                ShinkaiFileParser::push_text_group_by_depth(
-                    &mut text_groups,
-                    heading_depth,
-                    table_text,
-                    max_node_text_size,
-                );
+                let row_text: Vec<String> = rows.iter().map(|cells| cells.join("; ")).collect();
+                match ParsedTable::from_rows(None, rows) {
+                    Some(table) => {
+                        ShinkaiFileParser::push_table_by_depth(
+                            &mut text_groups,
+                            heading_depth,
+                            table,
+                            max_node_text_size,
+                        );
+                    }
+                    None => {
+                        let table_text = row_text.join("\n");
+                        ShinkaiFileParser::push_text_group_by_depth(
+                            &mut text_groups,
+                            heading_depth,
+                            table_text,
+                            max_node_text_size,
+                        );
+                    }
+                }
             }
             _ => {}
         });
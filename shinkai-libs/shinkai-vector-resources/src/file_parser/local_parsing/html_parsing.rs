@@ -2,7 +2,7 @@ use regex::Regex;
 use scraper::{ElementRef, Html, Selector};
 
 use crate::{
-    file_parser::{file_parser::ShinkaiFileParser, file_parser_types::TextGroup},
+    file_parser::{file_parser::ShinkaiFileParser, file_parser_tables::ParsedTable, file_parser_types::TextGroup},
     resource_errors::VRError,
 };
 
@@ -277,15 +277,21 @@ impl LocalFileParser {
                                             node_text.push_str(&format!("{}* {}\n", indentation, inner_text.trim()));
                                         }
                                     }
-                                    // Push table data to a text group
-                                    "table" => {
-                                        ShinkaiFileParser::push_text_group_by_depth(
+                                    // Push table data to a text group, kept whole if it's a proper table
+                                    "table" => match ParsedTable::from_html(&element.html(), None) {
+                                        Some(table) => ShinkaiFileParser::push_table_by_depth(
+                                            text_groups,
+                                            heading_parents.len(),
+                                            table,
+                                            max_node_text_size,
+                                        ),
+                                        None => ShinkaiFileParser::push_text_group_by_depth(
                                             text_groups,
                                             heading_parents.len(),
                                             inner_text.trim().to_owned(),
                                             max_node_text_size,
-                                        );
-                                    }
+                                        ),
+                                    },
                                     "caption" => {
                                         node_text.push_str(&format!("{}\n", inner_text.trim()));
                                     }
@@ -1,6 +1,6 @@
 #[cfg(any(feature = "dynamic-pdf-parser", feature = "static-pdf-parser"))]
 use crate::{
-    file_parser::{file_parser::ShinkaiFileParser, file_parser_types::TextGroup},
+    file_parser::{file_parser::ShinkaiFileParser, file_parser_tables::ParsedTable, file_parser_types::TextGroup},
     resource_errors::VRError,
 };
 
@@ -19,8 +19,52 @@ impl LocalFileParser {
         let mut text_groups = Vec::new();
 
         for page in parsed_pages.into_iter() {
+            // Character offset of the current text inside the page
+            let mut page_offset = 0;
+
             for pdf_text in page.content.into_iter() {
-                ShinkaiFileParser::push_text_group_by_depth(&mut text_groups, 0, pdf_text.text, max_node_text_size);
+                let text_length = pdf_text.text.chars().count();
+                if text_length == 0 {
+                    continue;
+                }
+
+                // Tables are kept whole, anchored to the whole text they were detected in
+                if let Some(table) = ParsedTable::detect_in_text(&pdf_text.text) {
+                    for mut text_group in table.into_text_groups(max_node_text_size) {
+                        text_group.set_page_anchor(page.page_number as u32, page_offset, page_offset + text_length);
+                        text_groups.push(text_group);
+                    }
+                    page_offset += text_length + 1;
+                    continue;
+                }
+
+                // Anchors every chunk to where it was found in the page, so answers can point at it
+                let mut search_from = 0;
+                for mut text_group in
+                    ShinkaiFileParser::parse_and_split_into_text_groups(pdf_text.text.clone(), max_node_text_size)
+                {
+                    let (start, end) = match pdf_text.text[search_from..].find(text_group.text.as_str()) {
+                        Some(index) => {
+                            let byte_start = search_from + index;
+                            search_from = byte_start + text_group.text.len();
+                            (
+                                pdf_text.text[..byte_start].chars().count(),
+                                pdf_text.text[..search_from].chars().count(),
+                            )
+                        }
+                        // The chunk was rewritten while parsing (ie. metadata extracted), so it's anchored
+                        // right after the previous one
+                        None => {
+                            let start = pdf_text.text[..search_from].chars().count();
+                            (start, (start + text_group.text.chars().count()).min(text_length))
+                        }
+                    };
+                    text_group.set_page_anchor(page.page_number as u32, page_offset + start, page_offset + end);
+                    text_groups.push(text_group);
+                }
+
+                // Texts of a page are separated by a new line
+                page_offset += text_length + 1;
             }
         }
 
@@ -1,6 +1,8 @@
 pub mod file_parser;
 pub mod file_parser_grouping;
 pub mod file_parser_helper;
+pub mod file_parser_language;
+pub mod file_parser_tables;
 pub mod file_parser_types;
 pub mod local_parsing;
 pub mod unstructured_api;
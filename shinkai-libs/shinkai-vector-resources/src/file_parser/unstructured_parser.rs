@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use super::file_parser::ShinkaiFileParser;
+use super::file_parser_tables::ParsedTable;
 use super::file_parser_types::TextGroup;
 use super::unstructured_types::{ElementType, UnstructuredElement};
 
@@ -85,6 +86,31 @@ impl UnstructuredParser {
                 continue;
             }
 
+            // Tables are kept whole in their own groups instead of being mixed into the surrounding text
+            if let Some(table) = Self::element_table(element) {
+                ShinkaiFileParser::push_group_to_appropriate_parent(
+                    current_group,
+                    &mut current_title_group,
+                    &mut groups,
+                );
+                current_group = TextGroup::new_empty();
+
+                for mut table_group in table.into_text_groups(max_node_text_size as u64) {
+                    if let Some(page_number) = element.metadata.page_number {
+                        table_group.metadata.insert(
+                            ShinkaiFileParser::page_numbers_metadata_key(),
+                            format!("[{}]", page_number),
+                        );
+                    }
+                    ShinkaiFileParser::push_group_to_appropriate_parent(
+                        table_group,
+                        &mut current_title_group,
+                        &mut groups,
+                    );
+                }
+                continue;
+            }
+
             if element.element_type != ElementType::Title {
                 // If adding the current element text would exceed the max_node_text_size,
                 // push the current group to title group or groups and start a new group
@@ -166,6 +192,21 @@ impl UnstructuredParser {
         groups
     }
 
+    /// The table of a Table element, from its HTML if Unstructured provided it. Sheets of spreadsheets are named
+    /// after their page name.
+    fn element_table(element: &UnstructuredElement) -> Option<ParsedTable> {
+        if !matches!(element.element_type, ElementType::Table | ElementType::TableChunk) {
+            return None;
+        }
+
+        element
+            .metadata
+            .text_as_html
+            .as_deref()
+            .and_then(|html| ParsedTable::from_html(html, element.metadata.page_name.clone()))
+            .or_else(|| ParsedTable::detect_in_text(&element.text))
+    }
+
     /// Skip over any elements that Unstructured failed to clean out.
     fn should_element_be_skipped(element: &UnstructuredElement) -> bool {
         // Remove Uncategorized text (usually filler like headers/footers) && elements with no content at all.
@@ -11,6 +11,7 @@ pub enum EmbeddingModelType {
     TextEmbeddingsInference(TextEmbeddingsInference),
     OpenAI(OpenAIModelType),
     OllamaTextEmbeddingsInference(OllamaTextEmbeddingsInference),
+    ClipEmbeddingsInference(ClipEmbeddingsInference),
 }
 
 impl EmbeddingModelType {
@@ -25,6 +26,9 @@ impl EmbeddingModelType {
         if let Ok(model) = OllamaTextEmbeddingsInference::from_string(s) {
             return Ok(EmbeddingModelType::OllamaTextEmbeddingsInference(model));
         }
+        if let Ok(model) = ClipEmbeddingsInference::from_string(s) {
+            return Ok(EmbeddingModelType::ClipEmbeddingsInference(model));
+        }
         Err(VRError::InvalidModelArchitecture)
     }
 
@@ -32,6 +36,7 @@ impl EmbeddingModelType {
 
     /// Returns the maximum allowed token count for an input string to be embedded, based on the embedding model
     pub fn max_input_token_count(&self) -> usize {
+        static CONTEXT_77: usize = 250;
         static CONTEXT_512: usize = 400;
         static CONTEXT_1024: usize = 9000;
         static CONTEXT_8200: usize = 7800;
@@ -62,9 +67,15 @@ impl EmbeddingModelType {
                 OllamaTextEmbeddingsInference::JinaEmbeddingsV2BaseEs => CONTEXT_1024, // it's really 8200, but we're using 1024 for now
                 OllamaTextEmbeddingsInference::Other(_) => CONTEXT_512,
             },
+            EmbeddingModelType::ClipEmbeddingsInference(_) => CONTEXT_77,
         }
     }
 
+    /// Whether the model embeds images too, into the same space as text, so images can be found by text queries
+    pub fn supports_images(&self) -> bool {
+        matches!(self, EmbeddingModelType::ClipEmbeddingsInference(_))
+    }
+
     // Returns the normalization factor for the embedding model to calibrate vector search with different embedding model types
     // The reference model is snowflake-arctic-embed:xs
     pub fn embedding_normalization_factor(&self) -> f32 {
@@ -77,6 +88,7 @@ impl EmbeddingModelType {
                 OllamaTextEmbeddingsInference::JinaEmbeddingsV2BaseEs => 1.5,
                 OllamaTextEmbeddingsInference::Other(_) => 1.0,
             },
+            EmbeddingModelType::ClipEmbeddingsInference(_) => 1.0,
         }
     }
 
@@ -89,6 +101,7 @@ impl EmbeddingModelType {
                 Err(VRError::UnimplementedModelDimensions(format!("OpenAI: {}", model)))
             },
             EmbeddingModelType::OllamaTextEmbeddingsInference(model) => model.vector_dimensions(),
+            EmbeddingModelType::ClipEmbeddingsInference(model) => model.vector_dimensions(),
         }
     }
 }
@@ -99,6 +112,7 @@ impl fmt::Display for EmbeddingModelType {
             EmbeddingModelType::TextEmbeddingsInference(model) => write!(f, "{}", model),
             EmbeddingModelType::OpenAI(model) => write!(f, "{}", model),
             EmbeddingModelType::OllamaTextEmbeddingsInference(model) => write!(f, "{}", model),
+            EmbeddingModelType::ClipEmbeddingsInference(model) => write!(f, "{}", model),
         }
     }
 }
@@ -278,6 +292,52 @@ impl fmt::Display for OllamaTextEmbeddingsInference {
     }
 }
 
+/// CLIP-style models, which embed images and text into the same space
+/// (https://github.com/openai/CLIP)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum ClipEmbeddingsInference {
+    ClipVitBasePatch32,
+    ClipVitLargePatch14,
+    Other(String),
+}
+
+impl ClipEmbeddingsInference {
+    const CLIP_VIT_BASE_PATCH32: &'static str = "openai/clip-vit-base-patch32";
+    const CLIP_VIT_LARGE_PATCH14: &'static str = "openai/clip-vit-large-patch14";
+
+    /// Parses a string in the format of "clip/<model>" into a ClipEmbeddingsInference
+    fn from_string(s: &str) -> Result<Self, VRError> {
+        let stripped = s.strip_prefix("clip/").ok_or(VRError::InvalidModelArchitecture)?;
+        match stripped {
+            Self::CLIP_VIT_BASE_PATCH32 => Ok(ClipEmbeddingsInference::ClipVitBasePatch32),
+            Self::CLIP_VIT_LARGE_PATCH14 => Ok(ClipEmbeddingsInference::ClipVitLargePatch14),
+            _ => Ok(ClipEmbeddingsInference::Other(stripped.to_string())),
+        }
+    }
+
+    /// Returns the vector dimensions for the embedding model
+    pub fn vector_dimensions(&self) -> Result<usize, VRError> {
+        match self {
+            ClipEmbeddingsInference::ClipVitBasePatch32 => Ok(512),
+            ClipEmbeddingsInference::ClipVitLargePatch14 => Ok(768),
+            ClipEmbeddingsInference::Other(model_name) => {
+                Err(VRError::UnimplementedModelDimensions(model_name.clone()))
+            }
+        }
+    }
+}
+
+impl fmt::Display for ClipEmbeddingsInference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let model_str = match self {
+            ClipEmbeddingsInference::ClipVitBasePatch32 => Self::CLIP_VIT_BASE_PATCH32,
+            ClipEmbeddingsInference::ClipVitLargePatch14 => Self::CLIP_VIT_LARGE_PATCH14,
+            ClipEmbeddingsInference::Other(name) => name,
+        };
+        write!(f, "clip/{}", model_str)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -319,4 +379,15 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_parse_clip_as_embedding_model_type() {
+        let parsed_model = EmbeddingModelType::from_string("clip/openai/clip-vit-base-patch32").unwrap();
+        assert_eq!(
+            parsed_model,
+            EmbeddingModelType::ClipEmbeddingsInference(ClipEmbeddingsInference::ClipVitBasePatch32)
+        );
+        assert!(parsed_model.supports_images());
+        assert_eq!(parsed_model.to_string(), "clip/openai/clip-vit-base-patch32");
+    }
 }
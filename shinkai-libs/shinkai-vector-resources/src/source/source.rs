@@ -413,7 +413,7 @@ impl SourceFileType {
             if let Ok(shinkai_type) = ShinkaiFileType::from_str(ext) {
                 return Ok(SourceFileType::Shinkai(shinkai_type));
             }
-            // Video/audio/image support will come in the future by first converting to text.
+            // Video/audio support will come in the future by first converting to text.
             if let Ok(_video_type) = VideoFileType::from_str(ext) {
                 // return Ok(SourceFileType::Video(video_type));
                 return Err(VRError::FileTypeNotSupported(file_name.to_string()));
@@ -422,9 +422,9 @@ impl SourceFileType {
                 // return Ok(SourceFileType::Audio(audio_type));
                 return Err(VRError::FileTypeNotSupported(file_name.to_string()));
             }
-            if let Ok(_img_type) = ImageFileType::from_str(ext) {
-                // return Ok(SourceFileType::Image(img_type));
-                return Err(VRError::FileTypeNotSupported(file_name.to_string()));
+            // Images are stored as is, and found by their image embeddings
+            if let Ok(img_type) = ImageFileType::from_str(ext) {
+                return Ok(SourceFileType::Image(img_type));
             }
         }
 
@@ -507,7 +507,7 @@ impl FromStr for ImageFileType {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "png" => Ok(ImageFileType::Png),
-            "jpeg" => Ok(ImageFileType::Jpeg),
+            "jpeg" | "jpg" => Ok(ImageFileType::Jpeg),
             "gif" => Ok(ImageFileType::Gif),
             "bmp" => Ok(ImageFileType::Bmp),
             "tiff" => Ok(ImageFileType::Tiff),
@@ -156,6 +156,9 @@ pub struct SimplifiedFSItem {
     pub source_file_map_size: usize,
     /// Merkle hash, which is in fact the merkle root of the Vector Resource stored in the SimplifiedFSItem
     pub merkle_hash: String,
+    /// Summary of the document, if it was summarized when ingested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
 }
 
 impl SimplifiedFSItem {
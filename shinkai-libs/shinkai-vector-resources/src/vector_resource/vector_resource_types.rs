@@ -206,6 +206,34 @@ impl RetrievedNode {
         Some(formatted_string)
     }
 
+    /// Pages of the source document the node was parsed from, if the parser provided them
+    pub fn page_numbers(&self) -> Vec<u32> {
+        self.metadata_numbers(&ShinkaiFileParser::page_numbers_metadata_key())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|page| page as u32)
+            .collect()
+    }
+
+    /// Start and end characters of the node's text inside its page, if the parser provided them
+    pub fn char_offsets(&self) -> Option<(usize, usize)> {
+        let offsets = self.metadata_numbers(&ShinkaiFileParser::char_offsets_metadata_key())?;
+        match offsets.as_slice() {
+            [start, end] => Some((*start as usize, *end as usize)),
+            _ => None,
+        }
+    }
+
+    /// Parses a `[1, 2, 3]` formatted metadata value of the node
+    fn metadata_numbers(&self, key: &str) -> Option<Vec<u64>> {
+        let value = self.node.metadata.as_ref()?.get(key)?;
+        value
+            .trim_matches(|c| c == '[' || c == ']')
+            .split(',')
+            .map(|n| n.trim().parse::<u64>().ok())
+            .collect()
+    }
+
     /// Parses node position in the content using metadata/retrieved node data.
     pub fn format_position_string(&self) -> String {
         if let Some(metadata) = &self.node.metadata {
@@ -108,27 +108,15 @@ impl VRPack {
 
     /// Parses a VRPack from an array of bytes, assuming the bytes are a Base64 encoded string.
     pub fn from_bytes(base64_bytes: &[u8]) -> Result<Self, VRError> {
-        // If it is Version V1
-        if let Ok(base64_str) = String::from_utf8(base64_bytes.to_vec())
-            .map_err(|e| VRError::VRPackParsingError(format!("UTF-8 conversion error: {}", e)))
-        {
-            return Self::from_base64(&base64_str);
-        }
-
-        return Err(VRError::UnsupportedVRPackVersion("".to_string()));
+        let base64_str = std::str::from_utf8(base64_bytes)
+            .map_err(|e| VRError::VRPackParsingError(format!("UTF-8 conversion error: {}", e)))?;
+        Self::from_base64(base64_str)
     }
 
     /// Parses a VRPack from a Base64 encoded string without compression.
     pub fn from_base64(base64_encoded: &str) -> Result<Self, VRError> {
-        // If it is Version V1
-        let v1 = Self::from_base64_v1(base64_encoded);
-        if let Ok(vrkai) = v1 {
-            return Ok(vrkai);
-        } else if let Err(e) = v1 {
-            eprintln!("VRPack Error: {}", e);
-        }
-
-        return Err(VRError::UnsupportedVRPackVersion("".to_string()));
+        // V1 is the only version so far, its parsing error says what is wrong with the input
+        Self::from_base64_v1(base64_encoded)
     }
 
     /// Parses a VRPack from a Base64 encoded string using V1 logic without compression.
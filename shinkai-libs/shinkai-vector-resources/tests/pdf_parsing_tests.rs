@@ -34,4 +34,9 @@ async fn local_pdf_parsing_test() {
     let results = resource.as_trait_object().vector_search(query_embedding, 3);
 
     assert!(results[0].score > 0.7);
+
+    // Nodes are anchored to the page and characters they were parsed from
+    assert_eq!(results[0].page_numbers().len(), 1);
+    let (start, end) = results[0].char_offsets().unwrap();
+    assert!(start < end);
 }
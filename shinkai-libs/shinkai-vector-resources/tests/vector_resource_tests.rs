@@ -1,5 +1,7 @@
 use shinkai_vector_resources::data_tags::DataTag;
-use shinkai_vector_resources::embedding_generator::{EmbeddingGenerator, RemoteEmbeddingGenerator};
+use shinkai_vector_resources::embedding_generator::{
+    DeterministicEmbeddingGenerator, EmbeddingGenerator, RemoteEmbeddingGenerator, DETERMINISTIC_EMBEDDINGS_URL,
+};
 use shinkai_vector_resources::file_parser::file_parser::{FileParser, ShinkaiFileParser};
 use shinkai_vector_resources::file_parser::unstructured_api::UnstructuredAPI;
 use shinkai_vector_resources::source::{DistributionInfo, VRSourceReference};
@@ -12,7 +14,7 @@ use shinkai_vector_resources::vector_resource::{
     FilterMode, NodeContent, ResultsMode, ScoringMode, TraversalMethod, TraversalOption, VectorResourceCore,
     VectorResourceSearch,
 };
-use shinkai_vector_resources::vector_resource::{RetrievedNode, VRPath};
+use shinkai_vector_resources::vector_resource::{Node, RetrievedNode, VRPath};
 use std::collections::HashMap;
 
 pub fn default_vector_resource_doc() -> DocumentVectorResource {
@@ -157,6 +159,38 @@ async fn test_remote_embedding_generation_async_batched() {
     assert_ne!(embeddings[0], embeddings[9]);
 }
 
+#[tokio::test]
+async fn test_deterministic_embedding_generation() {
+    let generator = DeterministicEmbeddingGenerator::default();
+    let remote_generator = RemoteEmbeddingGenerator::new(generator.model_type(), DETERMINISTIC_EMBEDDINGS_URL, None);
+
+    let dog_embedding = generator.generate_embedding_default_blocking("dog").unwrap();
+    assert_eq!(dog_embedding.vector.len(), 384);
+    assert_eq!(
+        dog_embedding,
+        generator.generate_embedding_default("dog").await.unwrap()
+    );
+    assert_eq!(
+        dog_embedding,
+        remote_generator.generate_embedding_default("dog").await.unwrap()
+    );
+    assert_ne!(
+        dog_embedding,
+        generator.generate_embedding_default_blocking("cat").unwrap()
+    );
+
+    let mut doc = DocumentVectorResource::new_empty("Facts", None, VRSourceReference::None, true);
+    doc.set_embedding_model_used(generator.model_type());
+    let facts = vec!["Dogs bark.", "Camels have humps.", "Seals swim in the ocean."];
+    for fact in &facts {
+        let embedding = generator.generate_embedding_default_blocking(fact).unwrap();
+        doc.append_text_node(fact, None, embedding, &vec![]).unwrap();
+    }
+    let query_embedding = generator.generate_embedding_default_blocking(facts[1]).unwrap();
+    let res = doc.vector_search(query_embedding, 1);
+    assert_eq!(facts[1], res[0].node.get_text_content().unwrap().to_string());
+}
+
 #[test]
 fn test_manual_resource_vector_search() {
     let generator = RemoteEmbeddingGenerator::new_default();
@@ -1396,3 +1430,67 @@ async fn metadata_parsing_with_unstructured_test() {
         "0x43b9a4bc24246855e3d5f4459a7a3d79e50505e6"
     );
 }
+
+#[test]
+fn test_entity_data_tags() {
+    let entity_tags = DataTag::entity_tags();
+    let tag_names = |text: &str| -> Vec<String> {
+        DataTag::validate_tag_list(text, &entity_tags)
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect()
+    };
+
+    assert_eq!(
+        tag_names("Dr. Jane Goodall joined Acme Corp on March 3rd, 2021."),
+        vec!["person", "organization", "date"]
+    );
+    assert_eq!(tag_names("Signed by John F. Kennedy"), vec!["person"]);
+    assert_eq!(
+        tag_names("Funded by the University of California"),
+        vec!["organization"]
+    );
+    assert_eq!(tag_names("Due 2024-05-01, or 12 Sept 2024 at the latest"), vec!["date"]);
+    assert!(tag_names("the model may improve results by 4x").is_empty());
+}
+
+fn retrieved_node_with_metadata(metadata: HashMap<String, String>) -> RetrievedNode {
+    let doc = DocumentVectorResource::new_empty("Report", None, VRSourceReference::new_uri_ref("report.pdf"), true);
+    let node = Node::new_text(
+        "1".to_string(),
+        "Quarterly results".to_string(),
+        Some(metadata),
+        &vec![],
+    );
+    RetrievedNode::new(
+        node,
+        0.9,
+        doc.generate_resource_header(),
+        VRPath::new().push_cloned("1".to_string()),
+    )
+}
+
+#[test]
+fn test_retrieved_node_page_anchors() {
+    let node = retrieved_node_with_metadata(HashMap::from([
+        (ShinkaiFileParser::page_numbers_metadata_key(), "[2, 3]".to_string()),
+        (ShinkaiFileParser::char_offsets_metadata_key(), "[10, 250]".to_string()),
+    ]));
+
+    assert_eq!(node.page_numbers(), vec![2, 3]);
+    assert_eq!(node.char_offsets(), Some((10, 250)));
+}
+
+#[test]
+fn test_retrieved_node_without_valid_page_anchors() {
+    let node = retrieved_node_with_metadata(HashMap::new());
+    assert!(node.page_numbers().is_empty());
+    assert_eq!(node.char_offsets(), None);
+
+    let node = retrieved_node_with_metadata(HashMap::from([
+        (ShinkaiFileParser::page_numbers_metadata_key(), "[two]".to_string()),
+        (ShinkaiFileParser::char_offsets_metadata_key(), "[10]".to_string()),
+    ]));
+    assert!(node.page_numbers().is_empty());
+    assert_eq!(node.char_offsets(), None);
+}